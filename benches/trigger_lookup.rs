@@ -0,0 +1,115 @@
+//! Micro-benchmark of the trigger lookups made while dispatching a match.
+//!
+//! Compares resolving the triggers of a match fanning out to five triggers by cloning the
+//! configurations, as the services used to hand them out, with the shared `Arc`s returned by
+//! `TriggerService::get`, and with a per-dispatch `TriggerResolutionCache`.
+//!
+//! Run with `cargo bench --bench trigger_lookup`.
+
+use std::{
+	alloc::{GlobalAlloc, Layout, System},
+	collections::HashMap,
+	hint::black_box,
+	sync::atomic::{AtomicUsize, Ordering},
+	time::{Duration, Instant},
+};
+
+use openzeppelin_monitor::{
+	models::Trigger,
+	repositories::{TriggerRepository, TriggerService},
+	services::trigger::TriggerResolutionCache,
+	utils::tests::builders::trigger::TriggerBuilder,
+};
+
+/// Allocator counting the allocations made by the benchmarked code
+struct CountingAllocator;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+	unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+		ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+		System.alloc(layout)
+	}
+
+	unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+		System.dealloc(ptr, layout)
+	}
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+const DISPATCHES: usize = 10_000;
+const TRIGGERS_PER_MATCH: usize = 5;
+
+/// Trigger with a large template and many headers
+fn heavy_trigger(name: &str) -> Trigger {
+	let headers = (0..32)
+		.map(|i| (format!("X-Header-{}", i), "value".repeat(8)))
+		.collect::<HashMap<_, _>>();
+	TriggerBuilder::new()
+		.name(name)
+		.webhook("https://webhook.example.com")
+		.webhook_headers(headers)
+		.message("Large transfer", &"${events.0.args.value} ".repeat(200))
+		.build()
+}
+
+/// Runs the dispatches, returning the elapsed time and the allocations made per dispatch
+fn measure(name: &str, dispatch: impl Fn()) {
+	let allocations = ALLOCATIONS.load(Ordering::Relaxed);
+	let start = Instant::now();
+	for _ in 0..DISPATCHES {
+		dispatch();
+	}
+	let elapsed: Duration = start.elapsed();
+	let allocations = ALLOCATIONS.load(Ordering::Relaxed) - allocations;
+	println!(
+		"{:<24} {:>10.0} ns/dispatch {:>8.1} allocations/dispatch",
+		name,
+		elapsed.as_nanos() as f64 / DISPATCHES as f64,
+		allocations as f64 / DISPATCHES as f64
+	);
+}
+
+fn main() {
+	let slugs = (0..TRIGGERS_PER_MATCH)
+		.map(|i| format!("trigger_{}", i))
+		.collect::<Vec<_>>();
+	let triggers = slugs
+		.iter()
+		.map(|slug| (slug.clone(), heavy_trigger(slug)))
+		.collect::<HashMap<_, _>>();
+	let service = TriggerService::new_with_repository(TriggerRepository {
+		triggers: triggers.clone(),
+	})
+	.unwrap();
+
+	measure("cloned triggers", || {
+		for slug in &slugs {
+			black_box(triggers.get(slug).cloned());
+		}
+	});
+	measure("shared triggers", || {
+		for slug in &slugs {
+			black_box(service.get(slug));
+		}
+	});
+	// The triggers are looked up for de-duplication, budgets and delivery
+	measure("shared triggers (x3)", || {
+		for _ in 0..3 {
+			for slug in &slugs {
+				black_box(service.get(slug));
+			}
+		}
+	});
+	measure("resolution cache (x3)", || {
+		let cache = TriggerResolutionCache::new(|slug: &str| service.get(slug));
+		for _ in 0..3 {
+			for slug in &slugs {
+				black_box(cache.get(slug));
+			}
+		}
+	});
+}
@@ -0,0 +1,78 @@
+//! Build script embedding the build information of the monitor.
+//!
+//! Sets the following environment variables for the crate, read by `utils::build_info`:
+//!
+//! - `MONITOR_GIT_SHA`: short SHA of the commit being built, `unknown` outside of a git checkout.
+//!   An existing `MONITOR_GIT_SHA` variable is used as is, for builds from a source archive.
+//! - `MONITOR_BUILD_TIMESTAMP`: unix time of the build in seconds, taken from
+//!   `SOURCE_DATE_EPOCH` when set for reproducible builds.
+//! - `MONITOR_FEATURES`: comma-separated list of the enabled cargo features.
+
+use std::{
+	env,
+	path::Path,
+	process::Command,
+	time::{SystemTime, UNIX_EPOCH},
+};
+
+fn git_sha() -> Option<String> {
+	let output = Command::new("git")
+		.args(["rev-parse", "--short=12", "HEAD"])
+		.output()
+		.ok()?;
+	if !output.status.success() {
+		return None;
+	}
+	let sha = String::from_utf8(output.stdout).ok()?.trim().to_string();
+	(!sha.is_empty()).then_some(sha)
+}
+
+fn build_timestamp() -> u64 {
+	env::var("SOURCE_DATE_EPOCH")
+		.ok()
+		.and_then(|v| v.parse().ok())
+		.unwrap_or_else(|| {
+			SystemTime::now()
+				.duration_since(UNIX_EPOCH)
+				.map(|d| d.as_secs())
+				.unwrap_or_default()
+		})
+}
+
+fn features() -> String {
+	let mut features: Vec<String> = env::vars()
+		.filter_map(|(key, _)| {
+			key.strip_prefix("CARGO_FEATURE_")
+				.map(|name| name.to_lowercase().replace('_', "-"))
+		})
+		.collect();
+	features.sort();
+	features.join(",")
+}
+
+fn main() {
+	let sha = env::var("MONITOR_GIT_SHA")
+		.ok()
+		.filter(|v| !v.is_empty())
+		.or_else(git_sha)
+		.unwrap_or_else(|| "unknown".to_string());
+
+	println!("cargo:rustc-env=MONITOR_GIT_SHA={}", sha);
+	println!(
+		"cargo:rustc-env=MONITOR_BUILD_TIMESTAMP={}",
+		build_timestamp()
+	);
+	println!("cargo:rustc-env=MONITOR_FEATURES={}", features());
+
+	// Declaring any rerun condition disables the default of rerunning on every change in the
+	// package, so the sources are listed to keep the timestamp current.
+	println!("cargo:rerun-if-changed=build.rs");
+	println!("cargo:rerun-if-changed=src");
+	for git_path in [".git/HEAD", ".git/refs", ".git/packed-refs"] {
+		if Path::new(git_path).exists() {
+			println!("cargo:rerun-if-changed={}", git_path);
+		}
+	}
+	println!("cargo:rerun-if-env-changed=MONITOR_GIT_SHA");
+	println!("cargo:rerun-if-env-changed=SOURCE_DATE_EPOCH");
+}
@@ -0,0 +1,324 @@
+//! Gated application of reloaded configurations.
+//!
+//! Every reloaded configuration is compared with the running one before it is applied. The
+//! resulting diff is logged and kept for inspection. With approval required, the candidate is
+//! staged and only applied once approved, or automatically after an optional delay. A staged
+//! configuration is never published to the subscribers of the applied one.
+//!
+//! The running monitor subscribes to applied configurations, and restarts its block watchers,
+//! filters and trigger handlers with each of them.
+
+use serde::Serialize;
+use std::{
+	sync::{Arc, Mutex},
+	time::Duration,
+};
+use tokio::sync::watch;
+
+use crate::models::{ConfigDiff, ConfigSnapshot};
+
+/// Options controlling how reloaded configurations are applied
+#[derive(Debug, Clone, Default)]
+pub struct ConfigApplyOptions {
+	/// Stage reloaded configurations until they are approved
+	pub require_approval: bool,
+	/// Apply a staged configuration automatically after this delay
+	pub auto_apply_after: Option<Duration>,
+}
+
+/// Status of the latest reloaded configuration
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ConfigApplyStatus {
+	/// The configuration is waiting for approval
+	Staged,
+	/// The configuration has been applied
+	Applied,
+	/// The configuration did not differ from the running one
+	Unchanged,
+	/// A newer configuration replaced this one before it was applied
+	Superseded,
+}
+
+/// Diff of the latest reloaded configuration with its status
+#[derive(Debug, Clone, Serialize)]
+pub struct ConfigDiffReport {
+	/// Sequence number of the reload
+	pub generation: u64,
+	/// Status of the reloaded configuration
+	pub status: ConfigApplyStatus,
+	/// Time of the reload in milliseconds since the Unix epoch
+	pub proposed_at: i64,
+	/// Time at which a staged configuration is applied automatically
+	pub auto_apply_at: Option<i64>,
+	/// Changes from the configuration running at reload time
+	pub diff: ConfigDiff,
+}
+
+/// Configuration waiting for approval
+struct StagedConfig {
+	generation: u64,
+	snapshot: ConfigSnapshot,
+}
+
+#[derive(Default)]
+struct ApplierState {
+	generation: u64,
+	staged: Option<StagedConfig>,
+	latest: Option<ConfigDiffReport>,
+}
+
+/// Compares reloaded configurations with the running one and applies them
+pub struct ConfigApplier {
+	options: ConfigApplyOptions,
+	current: watch::Sender<Arc<ConfigSnapshot>>,
+	state: Mutex<ApplierState>,
+}
+
+impl ConfigApplier {
+	/// Creates an applier for the configuration currently running
+	pub fn new(current: ConfigSnapshot, options: ConfigApplyOptions) -> Arc<Self> {
+		let (current, _) = watch::channel(Arc::new(current));
+		Arc::new(Self {
+			options,
+			current,
+			state: Mutex::new(ApplierState::default()),
+		})
+	}
+
+	/// Returns the options of the applier
+	pub fn options(&self) -> &ConfigApplyOptions {
+		&self.options
+	}
+
+	/// Returns the applied configuration
+	pub fn current(&self) -> Arc<ConfigSnapshot> {
+		self.current.borrow().clone()
+	}
+
+	/// Subscribes to applied configurations
+	pub fn subscribe(&self) -> watch::Receiver<Arc<ConfigSnapshot>> {
+		self.current.subscribe()
+	}
+
+	/// Returns the diff of the latest reloaded configuration
+	pub fn latest_diff(&self) -> Option<ConfigDiffReport> {
+		self.lock_state().latest.clone()
+	}
+
+	/// Compares a reloaded configuration with the running one and applies or stages it
+	///
+	/// # Returns
+	/// * `ConfigDiffReport` - The diff of the configuration and what happened to it
+	pub fn propose(self: &Arc<Self>, candidate: ConfigSnapshot) -> ConfigDiffReport {
+		let diff = ConfigDiff::between(&self.current(), &candidate);
+		let proposed_at = chrono::Utc::now().timestamp_millis();
+		tracing::info!("Reloaded configuration: {}", diff);
+
+		let mut state = self.lock_state();
+		state.generation += 1;
+		let generation = state.generation;
+		if let Some(report) = state.latest.as_mut() {
+			if report.status == ConfigApplyStatus::Staged {
+				report.status = ConfigApplyStatus::Superseded;
+			}
+		}
+		state.staged = None;
+
+		let mut report = ConfigDiffReport {
+			generation,
+			status: ConfigApplyStatus::Applied,
+			proposed_at,
+			auto_apply_at: None,
+			diff,
+		};
+
+		if report.diff.is_empty() {
+			report.status = ConfigApplyStatus::Unchanged;
+		} else if self.options.require_approval {
+			report.status = ConfigApplyStatus::Staged;
+			report.auto_apply_at = self
+				.options
+				.auto_apply_after
+				.map(|delay| proposed_at + delay.as_millis() as i64);
+			state.staged = Some(StagedConfig {
+				generation,
+				snapshot: candidate,
+			});
+
+			if let Some(delay) = self.options.auto_apply_after {
+				let applier = Arc::clone(self);
+				tokio::spawn(async move {
+					tokio::time::sleep(delay).await;
+					if applier.apply_generation(Some(generation)).is_some() {
+						tracing::info!(
+							"Applied staged configuration {} after {}s without approval",
+							generation,
+							delay.as_secs()
+						);
+					}
+				});
+			}
+			tracing::info!(
+				"Configuration {} staged, waiting for approval before applying",
+				generation
+			);
+		} else {
+			self.current.send_replace(Arc::new(candidate));
+		}
+
+		state.latest = Some(report.clone());
+		report
+	}
+
+	/// Applies the staged configuration
+	///
+	/// # Returns
+	/// * `Option<ConfigDiffReport>` - The report of the applied configuration, or None if no
+	///   configuration was staged
+	pub fn apply_staged(&self) -> Option<ConfigDiffReport> {
+		self.apply_generation(None)
+	}
+
+	/// Applies the staged configuration if it matches the given generation
+	fn apply_generation(&self, generation: Option<u64>) -> Option<ConfigDiffReport> {
+		let mut state = self.lock_state();
+		let staged = state
+			.staged
+			.take_if(|staged| generation.is_none_or(|g| g == staged.generation))?;
+
+		self.current.send_replace(Arc::new(staged.snapshot));
+		let report = state.latest.as_mut()?;
+		report.status = ConfigApplyStatus::Applied;
+		Some(report.clone())
+	}
+
+	fn lock_state(&self) -> std::sync::MutexGuard<'_, ApplierState> {
+		self.state.lock().unwrap_or_else(|e| e.into_inner())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::utils::tests::builders::evm::monitor::MonitorBuilder;
+	use std::collections::HashMap;
+
+	fn snapshot(monitors: &[&str]) -> ConfigSnapshot {
+		ConfigSnapshot {
+			monitors: monitors
+				.iter()
+				.map(|name| (name.to_string(), MonitorBuilder::new().name(name).build()))
+				.collect::<HashMap<_, _>>(),
+			..Default::default()
+		}
+	}
+
+	#[tokio::test]
+	async fn test_applies_immediately_without_approval() {
+		let applier = ConfigApplier::new(snapshot(&["a"]), ConfigApplyOptions::default());
+		let mut applied = applier.subscribe();
+
+		let report = applier.propose(snapshot(&["a", "b"]));
+
+		assert_eq!(report.status, ConfigApplyStatus::Applied);
+		assert_eq!(report.diff.monitors.added, vec!["b"]);
+		assert!(applied.has_changed().unwrap());
+		assert_eq!(applied.borrow_and_update().monitors.len(), 2);
+		assert!(applier.apply_staged().is_none());
+	}
+
+	#[tokio::test]
+	async fn test_unchanged_configuration() {
+		let applier = ConfigApplier::new(snapshot(&["a"]), ConfigApplyOptions::default());
+		let applied = applier.subscribe();
+
+		let report = applier.propose(snapshot(&["a"]));
+
+		assert_eq!(report.status, ConfigApplyStatus::Unchanged);
+		assert!(!applied.has_changed().unwrap());
+	}
+
+	#[tokio::test]
+	async fn test_staged_configuration_applied_on_approval() {
+		let applier = ConfigApplier::new(
+			snapshot(&["a"]),
+			ConfigApplyOptions {
+				require_approval: true,
+				auto_apply_after: None,
+			},
+		);
+		let applied = applier.subscribe();
+
+		let report = applier.propose(snapshot(&["b"]));
+		assert_eq!(report.status, ConfigApplyStatus::Staged);
+		assert_eq!(report.auto_apply_at, None);
+
+		// Staged configurations are not visible to processing
+		assert!(!applied.has_changed().unwrap());
+		assert!(applier.current().monitors.contains_key("a"));
+		assert_eq!(
+			applier.latest_diff().unwrap().status,
+			ConfigApplyStatus::Staged
+		);
+
+		let report = applier.apply_staged().unwrap();
+		assert_eq!(report.status, ConfigApplyStatus::Applied);
+		assert_eq!(report.diff.monitors.removed, vec!["a"]);
+		assert!(applied.has_changed().unwrap());
+		assert!(applier.current().monitors.contains_key("b"));
+		assert!(applier.apply_staged().is_none());
+	}
+
+	#[tokio::test]
+	async fn test_staged_configuration_auto_applied_after_timeout() {
+		let applier = ConfigApplier::new(
+			snapshot(&["a"]),
+			ConfigApplyOptions {
+				require_approval: true,
+				auto_apply_after: Some(Duration::from_millis(50)),
+			},
+		);
+		let mut applied = applier.subscribe();
+
+		let report = applier.propose(snapshot(&["b"]));
+		assert_eq!(report.status, ConfigApplyStatus::Staged);
+		assert_eq!(report.auto_apply_at, Some(report.proposed_at + 50));
+		assert!(!applied.has_changed().unwrap());
+
+		tokio::time::timeout(Duration::from_secs(1), applied.changed())
+			.await
+			.expect("staged configuration should be applied after the timeout")
+			.unwrap();
+		assert!(applier.current().monitors.contains_key("b"));
+		assert_eq!(
+			applier.latest_diff().unwrap().status,
+			ConfigApplyStatus::Applied
+		);
+	}
+
+	#[tokio::test]
+	async fn test_newer_reload_supersedes_staged_configuration() {
+		let applier = ConfigApplier::new(
+			snapshot(&["a"]),
+			ConfigApplyOptions {
+				require_approval: true,
+				auto_apply_after: Some(Duration::from_millis(50)),
+			},
+		);
+
+		applier.propose(snapshot(&["b"]));
+		tokio::time::sleep(Duration::from_millis(30)).await;
+		let report = applier.propose(snapshot(&["c"]));
+		assert_eq!(report.generation, 2);
+		assert_eq!(report.diff.monitors.added, vec!["c"]);
+
+		// The timer of the superseded configuration does not apply the newer one early
+		tokio::time::sleep(Duration::from_millis(30)).await;
+		assert!(applier.current().monitors.contains_key("a"));
+
+		let report = applier.apply_staged().unwrap();
+		assert_eq!(report.generation, 2);
+		assert!(applier.current().monitors.contains_key("c"));
+	}
+}
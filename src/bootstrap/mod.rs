@@ -14,27 +14,72 @@
 //!   blockchain
 //! - `create_trigger_handler`: Creates a trigger handler function that processes trigger events
 //!   from the block processing pipeline
+//! - `create_queued_trigger_handler`: Creates a trigger handler function that queues matches for
+//!   dispatch through a bounded queue created by `create_dispatch_queue`, or through the
+//!   notification outbox
+//! - `create_dispatch_fn`: Creates the function delivering a match to its triggers
+//! - `send_due_budget_digests`: Sends the digests of notification budgets that reset
+//!
+//! # Preflight
+//! - `preflight`: Summarizes the effective configuration and optionally probes its endpoints
+//!
+//! # Configuration reloads
+//! - `ConfigApplier`: Reports what a reloaded configuration changes and gates its application
+//! - `load_config_snapshot`: Loads the monitors, networks and triggers from the config
+//!   directories
+//!
+//! # Configuration services
+//! - `create_config_services`: Creates the monitor, network and trigger services of the config
+//!   directories, with the monitor settings read from the environment
+//! - `initialize_applied_services`: Creates the services of an applied configuration, which the
+//!   monitor restarts its block processing with
+
+mod config_apply;
+mod preflight;
+
+pub use config_apply::{ConfigApplier, ConfigApplyOptions, ConfigApplyStatus, ConfigDiffReport};
+
+pub use preflight::{
+	preflight, EndpointReport, MonitorReport, NetworkReport, PreflightOptions, PreflightReport,
+	ProbeResult, TriggerReport, DEFAULT_PROBE_TIMEOUT,
+};
 
 use futures::future::BoxFuture;
-use std::{collections::HashMap, error::Error, sync::Arc};
+use std::{
+	collections::{BTreeMap, HashMap},
+	error::Error,
+	sync::Arc,
+	time::Duration,
+};
 use tokio::sync::{watch, Mutex};
 
 use crate::{
 	models::{
-		BlockChainType, BlockType, ContractSpec, Monitor, MonitorMatch, Network, ProcessedBlock,
-		ScriptLanguage, TriggerConditions,
+		lint_configuration, BlockChainType, BlockType, BudgetOverflow, ConfigSnapshot,
+		ContractSpec, LintConfig, MessageOverride, Monitor, MonitorMatch, Network, ProcessedBlock,
+		ScriptLanguage, TriggerConditions, TriggerOverride, TriggerReference, TriggerType,
 	},
 	repositories::{
-		MonitorRepositoryTrait, MonitorService, NetworkRepositoryTrait, NetworkService,
-		TriggerRepositoryTrait, TriggerService,
+		monitor_tags_from_env, ConsistencyMode, MonitorRepository, MonitorRepositoryTrait,
+		MonitorService, NetworkRepository, NetworkRepositoryTrait, NetworkService, RepositoryError,
+		TriggerRepository, TriggerRepositoryTrait, TriggerService,
 	},
 	services::{
 		blockchain::{BlockChainClient, BlockFilterFactory, ClientPoolTrait},
-		filter::{evm_helpers, handle_match, stellar_helpers, FilterService},
-		notification::NotificationService,
+		filter::{
+			evm_helpers, handle_match_with_variables, match_variables, stellar_helpers,
+			BaselineTracker, DecodeFailureReport, DecodeFailures, FilterService,
+			NetworkMetricsTracker, PriceOracles, SenderActivityTracker,
+		},
+		notification::{match_uuid, ChannelCapabilities, NotificationService},
 		trigger::{
-			ScriptError, ScriptExecutorFactory, TriggerError, TriggerExecutionService,
-			TriggerExecutionServiceTrait,
+			apply_rollup, dedup_matches, deliver_digest_parts, rollup_matches, AddressLabels,
+			BudgetDecision, BudgetDigest, ConditionCache, DedupedMatch, DigestMessage, DispatchFn,
+			DispatchQueue, DispatchQueueConfig, Enricher, MatchHistory, NetworkDivergence,
+			NetworkViews, NotificationBudgets, NotificationOutbox, ScriptError,
+			ScriptExecutorFactory, SharedDedup, TriggerCircuitBreakers, TriggerError,
+			TriggerExecutionService, TriggerExecutionServiceTrait, TriggerMutes,
+			TriggerResolutionCache, DEFAULT_DIGEST_PART_DELAY,
 		},
 	},
 	utils::normalize_string,
@@ -43,7 +88,7 @@ use crate::{
 /// Type alias for handling ServiceResult
 pub type Result<T> = std::result::Result<T, Box<dyn Error>>;
 
-type ServiceResult<M, N, T> = Result<(
+pub type ServiceResult<M, N, T> = Result<(
 	Arc<FilterService>,
 	Arc<TriggerExecutionService<T>>,
 	Vec<Monitor>,
@@ -64,12 +109,20 @@ type ServiceResult<M, N, T> = Result<(
 /// - `Arc<Mutex<M>>`: Data access for monitor configs
 /// - `Arc<Mutex<N>>`: Data access for network configs
 /// - `Arc<Mutex<T>>`: Data access for trigger configs
+///
+/// Deliveries to failing triggers are suppressed when `circuit_breakers` is set, and deliveries
+/// to muted triggers are skipped when `mutes` is set. The `match_history` is only kept when an
+/// active monitor has a `history` configuration, or a trigger of one includes earlier matches
+/// with a `context`.
 /// # Errors
 /// Returns an error if any service initialization fails
 pub async fn initialize_services<M, N, T>(
 	monitor_service: Option<MonitorService<M, N, T>>,
 	network_service: Option<NetworkService<N>>,
 	trigger_service: Option<TriggerService<T>>,
+	circuit_breakers: Option<Arc<TriggerCircuitBreakers>>,
+	mutes: Option<Arc<TriggerMutes>>,
+	match_history: Option<Arc<MatchHistory>>,
 ) -> ServiceResult<M, N, T>
 where
 	M: MonitorRepositoryTrait<N, T> + Send + Sync + 'static,
@@ -105,18 +158,33 @@ where
 		}
 	};
 
-	let notification_service = NotificationService::new();
-
-	let filter_service = Arc::new(FilterService::new());
-	let trigger_execution_service = Arc::new(TriggerExecutionService::new(
-		trigger_service.clone(),
-		notification_service,
-	));
+	let price_oracles = Arc::new(PriceOracles::default());
+	let filter_service = Arc::new(FilterService::new().with_price_oracles(price_oracles.clone()));
 
 	let monitors = monitor_service.get_all();
-	let active_monitors = filter_active_monitors(monitors);
 	let networks = network_service.get_all();
 
+	let trigger_execution_service = create_trigger_execution_service(
+		&trigger_service,
+		&monitors,
+		price_oracles,
+		circuit_breakers,
+		mutes,
+		match_history,
+	);
+
+	// Report configuration that loads but is probably wrong, failing on denied lints
+	let lint_config = LintConfig::load(None)?;
+	let lint_warnings = lint_configuration(
+		&monitors,
+		&networks,
+		&trigger_service.get_all(),
+		&lint_config,
+	);
+	lint_config.enforce(&lint_warnings)?;
+
+	let active_monitors = filter_active_monitors(monitors);
+
 	Ok((
 		filter_service,
 		trigger_execution_service,
@@ -128,12 +196,114 @@ where
 	))
 }
 
+/// Initializes the services of an applied configuration, see [`initialize_services`].
+///
+/// The filter service of the running configuration is kept, so that the prices and decoding
+/// failures it holds carry over, and the services are only returned once all of them are created.
+///
+/// # Returns
+/// Returns a tuple containing:
+/// - TriggerExecutionService: Manages trigger execution
+/// - `Vec<Monitor>`: List of active monitors
+/// - `HashMap<String, Network>`: Available networks indexed by slug
+/// - `ConfigServices`: The monitor, network and trigger services of the configuration
+///
+/// # Errors
+/// Returns an error if a service fails to be created or a denied lint is found
+pub fn initialize_applied_services(
+	snapshot: &ConfigSnapshot,
+	filter_service: &FilterService,
+	circuit_breakers: Option<Arc<TriggerCircuitBreakers>>,
+	mutes: Option<Arc<TriggerMutes>>,
+	match_history: Option<Arc<MatchHistory>>,
+) -> Result<(
+	Arc<TriggerExecutionService<TriggerRepository>>,
+	Vec<Monitor>,
+	HashMap<String, Network>,
+	ConfigServices,
+)> {
+	let monitor_service = MonitorService::new_with_repository(
+		MonitorRepository::new_with_monitors(snapshot.monitors.clone()),
+	)?;
+	let network_service = NetworkService::new_with_repository(NetworkRepository {
+		networks: snapshot.networks.clone(),
+	})?;
+	let trigger_service = TriggerService::new_with_repository(TriggerRepository {
+		triggers: snapshot.triggers.clone(),
+	})?;
+
+	let lint_config = LintConfig::load(None)?;
+	let lint_warnings = lint_configuration(
+		&snapshot.monitors,
+		&snapshot.networks,
+		&snapshot.triggers,
+		&lint_config,
+	);
+	lint_config.enforce(&lint_warnings)?;
+
+	let trigger_execution_service = create_trigger_execution_service(
+		&trigger_service,
+		&snapshot.monitors,
+		filter_service.price_oracles().clone(),
+		circuit_breakers,
+		mutes,
+		match_history,
+	);
+
+	Ok((
+		trigger_execution_service,
+		filter_active_monitors(snapshot.monitors.clone()),
+		snapshot.networks.clone(),
+		(monitor_service, network_service, trigger_service),
+	))
+}
+
+/// Creates the trigger execution service of a configuration.
+///
+/// # Arguments
+/// * `trigger_service` - Service holding the triggers of the configuration
+/// * `monitors` - Monitors of the configuration, whose addresses label notifications
+/// * `price_oracles` - Price oracles shared with the filter service
+/// * `circuit_breakers` - Breakers suppressing deliveries to failing triggers, if enabled
+/// * `mutes` - Mutes skipping deliveries to muted triggers, if enabled
+/// * `match_history` - History of matches, only kept when the configuration uses it
+///
+/// # Returns
+/// Returns the trigger execution service
+pub fn create_trigger_execution_service<T: TriggerRepositoryTrait + Send + Sync + 'static>(
+	trigger_service: &TriggerService<T>,
+	monitors: &HashMap<String, Monitor>,
+	price_oracles: Arc<PriceOracles>,
+	circuit_breakers: Option<Arc<TriggerCircuitBreakers>>,
+	mutes: Option<Arc<TriggerMutes>>,
+	match_history: Option<Arc<MatchHistory>>,
+) -> Arc<TriggerExecutionService<T>> {
+	let mut trigger_execution_service =
+		TriggerExecutionService::new(trigger_service.clone(), NotificationService::new())
+			.with_address_labels(AddressLabels::from_monitors(monitors.values()))
+			.with_price_oracles(price_oracles);
+	if let Some(circuit_breakers) = circuit_breakers {
+		trigger_execution_service =
+			trigger_execution_service.with_circuit_breakers(circuit_breakers);
+	}
+	if let Some(mutes) = mutes {
+		trigger_execution_service = trigger_execution_service.with_mutes(mutes);
+	}
+	if let Some(match_history) = match_history
+		.filter(|_| uses_match_history(monitors.values().filter(|m| !m.paused), trigger_service))
+	{
+		trigger_execution_service = trigger_execution_service.with_match_history(match_history);
+	}
+	Arc::new(trigger_execution_service)
+}
+
 /// Creates a block handler function that processes new blocks from the blockchain.
 ///
 /// # Arguments
 /// * `shutdown_tx` - Watch channel for shutdown signals
 /// * `filter_service` - Service for filtering blockchain data
-/// * `active_monitors` - List of active monitors
+/// * `active_monitors` - List of active monitors, shared with the monitor service (see
+///   [`MonitorService::get_active_shared`]) so that blocks do not copy them
 /// * `client_pools` - Client pools for accessing blockchain clients
 ///
 /// # Returns
@@ -141,10 +311,11 @@ where
 pub fn create_block_handler<P: ClientPoolTrait + 'static>(
 	shutdown_tx: watch::Sender<bool>,
 	filter_service: Arc<FilterService>,
-	active_monitors: Vec<Monitor>,
+	active_monitors: Vec<Arc<Monitor>>,
 	client_pools: Arc<P>,
 	contract_specs: Vec<(String, ContractSpec)>,
 ) -> Arc<impl Fn(BlockType, Network) -> BoxFuture<'static, ProcessedBlock> + Send + Sync> {
+	let active_monitors = Arc::new(active_monitors);
 	Arc::new(
 		move |block: BlockType, network: Network| -> BoxFuture<'static, ProcessedBlock> {
 			let filter_service = filter_service.clone();
@@ -238,7 +409,7 @@ pub async fn process_block<T>(
 	client: &T,
 	network: &Network,
 	block: &BlockType,
-	applicable_monitors: &[Monitor],
+	applicable_monitors: &[Arc<Monitor>],
 	contract_specs: Option<&[(String, ContractSpec)]>,
 	filter_service: &FilterService,
 	shutdown_rx: &mut watch::Receiver<bool>,
@@ -377,12 +548,560 @@ pub async fn get_contract_specs<P: ClientPoolTrait + 'static>(
 	all_specs
 }
 
+/// Runs the trigger conditions of a block's matches and de-duplicates them across monitors
+/// sharing a `dedup_group`, for triggers that opt into `group_dedup`
+///
+/// With `shared_dedup`, deliveries are also de-duplicated across monitor instances. Matches of
+/// monitors sharing a `rollup_group` are then combined for the triggers that roll them up.
+async fn prepare_block_matches<S: TriggerExecutionServiceTrait>(
+	block: &ProcessedBlock,
+	trigger_service: &S,
+	trigger_scripts: &HashMap<String, (ScriptLanguage, String)>,
+	condition_cache: &ConditionCache,
+	shared_dedup: Option<&SharedDedup>,
+) -> Vec<DedupedMatch> {
+	if block.processing_results.is_empty() {
+		return vec![];
+	}
+	let filtered_matches = run_trigger_filters(
+		&block.processing_results,
+		&block.network_slug,
+		trigger_scripts,
+		condition_cache,
+	)
+	.await;
+	let triggers = TriggerResolutionCache::new(|slug: &str| trigger_service.get_trigger(slug));
+	let is_group_dedup_trigger = |slug: &str| triggers.get(slug).is_some_and(|t| t.group_dedup);
+	let deduped = dedup_matches(&filtered_matches, |slug| {
+		triggers
+			.get(slug)
+			.filter(|t| t.group_dedup)
+			.map(|t| t.dedup_strategy)
+	});
+	let deduped = match shared_dedup {
+		Some(shared_dedup) => shared_dedup.claim(deduped, is_group_dedup_trigger).await,
+		None => deduped,
+	};
+	rollup_matches(deduped, |reference| {
+		reference.rollup() || triggers.get(reference.name()).is_some_and(|t| t.rollup)
+	})
+}
+
+/// Formats a time in milliseconds since the Unix epoch for notifications
+fn format_budget_time(timestamp_ms: i64) -> String {
+	chrono::DateTime::from_timestamp_millis(timestamp_ms)
+		.map_or_else(|| timestamp_ms.to_string(), |time| time.to_rfc3339())
+}
+
+/// Returns the names of the monitor's triggers budget notices are delivered to
+///
+/// Script triggers are left out.
+fn budget_notice_triggers<S: TriggerExecutionServiceTrait>(
+	monitor_match: &MonitorMatch,
+	trigger_service: &S,
+) -> Vec<String> {
+	monitor_match
+		.monitor()
+		.triggers
+		.iter()
+		.map(|reference| reference.name().to_string())
+		.filter(|name| {
+			trigger_service
+				.get_trigger(name)
+				.is_some_and(|trigger| trigger.trigger_type != TriggerType::Script)
+		})
+		.collect()
+}
+
+/// Sends an informational message about a monitor's notification budget
+///
+/// The message is delivered with the monitor's notification triggers, script triggers are left
+/// out. The match only carries the monitor and its network.
+async fn send_budget_notice<S: TriggerExecutionServiceTrait>(
+	monitor_match: MonitorMatch,
+	title: String,
+	body: String,
+	trigger_service: &S,
+	trigger_scripts: &HashMap<String, (ScriptLanguage, String)>,
+) {
+	let triggers = budget_notice_triggers(&monitor_match, trigger_service);
+	if let Err(e) = deliver_budget_notice(
+		monitor_match,
+		title,
+		body,
+		&triggers,
+		trigger_service,
+		trigger_scripts,
+	)
+	.await
+	{
+		TriggerError::execution_error(e.to_string(), Some(e.into()), None);
+	}
+}
+
+/// Delivers an informational message about a monitor's notification budget to the given
+/// triggers of the monitor
+async fn deliver_budget_notice<S: TriggerExecutionServiceTrait>(
+	mut monitor_match: MonitorMatch,
+	title: String,
+	body: String,
+	triggers: &[String],
+	trigger_service: &S,
+	trigger_scripts: &HashMap<String, (ScriptLanguage, String)>,
+) -> std::result::Result<(), TriggerError> {
+	let message = MessageOverride {
+		title: Some(title),
+		body: Some(body),
+	};
+	let monitor = monitor_match.monitor_mut();
+	monitor.triggers = monitor
+		.triggers
+		.iter()
+		.filter(|reference| triggers.iter().any(|name| name == reference.name()))
+		.map(|reference| {
+			TriggerReference::WithOverrides(TriggerOverride {
+				name: reference.name().to_string(),
+				message: Some(message.clone()),
+				variables: reference.variables().cloned().unwrap_or_default(),
+				rollup: reference.rollup(),
+			})
+		})
+		.collect();
+	let trigger_slugs = monitor
+		.triggers
+		.iter()
+		.map(|reference| reference.name().to_string())
+		.collect::<Vec<_>>();
+	if trigger_slugs.is_empty() {
+		return Ok(());
+	}
+
+	let variables = HashMap::from([("monitor.name".to_string(), monitor.name.clone())]);
+	trigger_service
+		.execute(&trigger_slugs, variables, &monitor_match, trigger_scripts)
+		.await
+}
+
+/// Sends the digest of the matches a monitor held back over its budget
+///
+/// The digest is sent as a single message when it fits in every channel of the monitor's
+/// triggers. Otherwise, each trigger receives the digest split across as many messages as its
+/// channel needs, and a failed message is retried for that trigger only.
+async fn send_budget_digest<S: TriggerExecutionServiceTrait>(
+	digest: BudgetDigest,
+	trigger_service: &S,
+	trigger_scripts: &HashMap<String, (ScriptLanguage, String)>,
+) {
+	let monitor = digest.last_match.monitor();
+	let monitor_name = monitor.name.clone();
+	let part_delay = monitor
+		.notification_budget
+		.as_ref()
+		.and_then(|budget| budget.digest_part_delay_ms)
+		.map_or(DEFAULT_DIGEST_PART_DELAY, Duration::from_millis);
+	let message = DigestMessage {
+		title: format!("Notification digest for {}", monitor_name),
+		summary: format!(
+			"{} match(es) of monitor '{}' were held back over its notification budget between {} \
+			 and {}.",
+			digest.held_back,
+			monitor_name,
+			format_budget_time(digest.period_start),
+			format_budget_time(digest.period_end)
+		),
+		entries_label: "Transactions".to_string(),
+		unlisted: digest.held_back - digest.transaction_hashes.len() as u64,
+		entries: digest.transaction_hashes,
+	};
+
+	let mut groups: BTreeMap<Option<usize>, Vec<String>> = BTreeMap::new();
+	for name in budget_notice_triggers(&digest.last_match, trigger_service) {
+		let max_payload_bytes = trigger_service.get_trigger(&name).and_then(|trigger| {
+			ChannelCapabilities::for_trigger_type(&trigger.trigger_type).max_payload_bytes
+		});
+		groups.entry(max_payload_bytes).or_default().push(name);
+	}
+	let paginated = groups
+		.into_iter()
+		.map(|(max_payload_bytes, triggers)| (message.paginate(max_payload_bytes), triggers))
+		.collect::<Vec<_>>();
+
+	if paginated.iter().all(|(parts, _)| parts.len() == 1) {
+		let whole = message.paginate(None).remove(0);
+		send_budget_notice(
+			digest.last_match,
+			whole.title,
+			whole.body,
+			trigger_service,
+			trigger_scripts,
+		)
+		.await;
+		return;
+	}
+
+	for (parts, triggers) in paginated {
+		for trigger in triggers {
+			let failed = deliver_digest_parts(&parts, part_delay, |part| {
+				deliver_budget_notice(
+					digest.last_match.clone(),
+					part.title.clone(),
+					part.body.clone(),
+					std::slice::from_ref(&trigger),
+					trigger_service,
+					trigger_scripts,
+				)
+			})
+			.await;
+			if !failed.is_empty() {
+				TriggerError::execution_error(
+					format!(
+						"Failed to send {} of {} parts of the digest of monitor '{}' to trigger '{}'",
+						failed.len(),
+						parts.len(),
+						monitor_name,
+						trigger
+					),
+					None,
+					None,
+				);
+			}
+		}
+	}
+}
+
+/// Sends the digests of the notification budgets whose period has ended.
+///
+/// Digests are also sent when the next match of a monitor arrives after the reset, calling this
+/// periodically delivers them for monitors without further matches.
+///
+/// # Arguments
+/// * `budgets` - Notification budgets of the monitors
+/// * `trigger_service` - Service for executing triggers
+/// * `trigger_scripts` - Scripts used by the triggers
+pub async fn send_due_budget_digests<S: TriggerExecutionServiceTrait>(
+	budgets: &NotificationBudgets,
+	trigger_service: &S,
+	trigger_scripts: &HashMap<String, (ScriptLanguage, String)>,
+) {
+	for digest in budgets.take_due_digests() {
+		send_budget_digest(digest, trigger_service, trigger_scripts).await;
+	}
+}
+
+/// Checks a match against its monitor's notification budget
+///
+/// Sends the notices that became due and returns the match narrowed to the triggers it is
+/// delivered to, or None if it is not delivered.
+async fn apply_notification_budget<S: TriggerExecutionServiceTrait>(
+	mut monitor_match: MonitorMatch,
+	budgets: &NotificationBudgets,
+	trigger_service: &S,
+	trigger_scripts: &HashMap<String, (ScriptLanguage, String)>,
+) -> Option<MonitorMatch> {
+	let Some(budget) = monitor_match.monitor().notification_budget.clone() else {
+		return Some(monitor_match);
+	};
+	let check = budgets.check(&monitor_match);
+
+	if let Some(digest) = check.digest {
+		send_budget_digest(digest, trigger_service, trigger_scripts).await;
+	}
+	if check.exhausted {
+		let monitor_name = monitor_match.monitor().name.clone();
+		let handling = match budget.overflow {
+			BudgetOverflow::Digest => "summarized in a digest sent when the budget resets",
+			BudgetOverflow::Drop => "dropped",
+			BudgetOverflow::DatabaseOnly => "only delivered to the triggers recording them",
+		};
+		tracing::info!(
+			"Monitor '{}' exhausted its notification budget of {} per day",
+			monitor_name,
+			budget.max_per_day
+		);
+		send_budget_notice(
+			monitor_match.clone(),
+			format!("Notification budget exhausted for {}", monitor_name),
+			format!(
+				"Monitor '{}' reached its notification budget of {} per day. Further matches are \
+				 {} until {}.",
+				monitor_name,
+				budget.max_per_day,
+				handling,
+				format_budget_time(check.resets_at)
+			),
+			trigger_service,
+			trigger_scripts,
+		)
+		.await;
+	}
+
+	match check.decision {
+		BudgetDecision::Deliver => Some(monitor_match),
+		BudgetDecision::Overflow(BudgetOverflow::DatabaseOnly) => {
+			let monitor = monitor_match.monitor_mut();
+			monitor
+				.triggers
+				.retain(|reference| budget.record_triggers.iter().any(|t| t == reference.name()));
+			(!monitor.triggers.is_empty()).then_some(monitor_match)
+		}
+		BudgetDecision::Overflow(_) => None,
+	}
+}
+
+/// Reports the matches a view of a network group did not see
+///
+/// Divergences are logged and counted, and delivered to the `divergence_triggers` of the
+/// monitor with the match as seen by the first view. The variables of the match are extended
+/// with `divergence.network`, `divergence.group`, `divergence.block_number` and
+/// `divergence.observed_block`.
+async fn report_network_divergences<S: TriggerExecutionServiceTrait>(
+	divergences: Vec<NetworkDivergence>,
+	trigger_service: &S,
+	trigger_scripts: &HashMap<String, (ScriptLanguage, String)>,
+) {
+	for divergence in divergences {
+		let NetworkDivergence {
+			group,
+			network_slug,
+			observed_block,
+			block_number,
+			mut monitor_match,
+		} = divergence;
+		let monitor_name = monitor_match.monitor().name.clone();
+		let description = format!(
+			"Transaction {} matched by monitor '{}' on network '{}' at block {} was not seen on \
+			 network '{}' by block {}.",
+			monitor_match.transaction_hash(),
+			monitor_name,
+			monitor_match.network_slug(),
+			block_number,
+			network_slug,
+			observed_block
+		);
+		tracing::error!(
+			monitor = %monitor_name,
+			network = %network_slug,
+			group = %group,
+			"Network view divergence: {}",
+			description
+		);
+
+		let monitor = monitor_match.monitor_mut();
+		let divergence_triggers = monitor
+			.network_views
+			.as_ref()
+			.map(|views| views.divergence_triggers.clone())
+			.unwrap_or_default();
+		if divergence_triggers.is_empty() {
+			continue;
+		}
+		let message = MessageOverride {
+			title: Some(format!("Network view divergence for {}", monitor_name)),
+			body: Some(description),
+		};
+		monitor.triggers = divergence_triggers
+			.iter()
+			.map(|name| {
+				TriggerReference::WithOverrides(TriggerOverride {
+					name: name.clone(),
+					message: Some(message.clone()),
+					variables: HashMap::new(),
+					rollup: false,
+				})
+			})
+			.collect();
+
+		let mut variables = match_variables(&monitor_match, &[], trigger_service.price_oracles());
+		variables.extend([
+			("divergence.network".to_string(), network_slug),
+			("divergence.group".to_string(), group),
+			(
+				"divergence.block_number".to_string(),
+				block_number.to_string(),
+			),
+			(
+				"divergence.observed_block".to_string(),
+				observed_block.to_string(),
+			),
+		]);
+		if let Err(e) = trigger_service
+			.execute(
+				&divergence_triggers,
+				variables,
+				&monitor_match,
+				trigger_scripts,
+			)
+			.await
+		{
+			TriggerError::execution_error(e.to_string(), Some(e.into()), None);
+		}
+	}
+}
+
+/// Reports the conditions that reached the decoding failure threshold of their monitor
+///
+/// Alerts are delivered to the triggers of `alert_on_decode_failure`, or to the monitor's
+/// triggers when it has none, with the last failing transaction as the match. The variables of
+/// the match are extended with `decode_failure.condition`, `decode_failure.selector`,
+/// `decode_failure.error` and `decode_failure.count`.
+async fn report_decode_failures<S: TriggerExecutionServiceTrait>(
+	reports: Vec<DecodeFailureReport>,
+	trigger_service: &S,
+	trigger_scripts: &HashMap<String, (ScriptLanguage, String)>,
+) {
+	for report in reports {
+		let DecodeFailureReport {
+			condition,
+			selector,
+			error,
+			count,
+			mut monitor_match,
+		} = report;
+		let monitor_name = monitor_match.monitor().name.clone();
+		let description = format!(
+			"Condition '{}' of monitor '{}' failed to decode {} times on network '{}', last in \
+			 transaction {} ({}): {}. Check the ABI of the monitored contracts.",
+			condition,
+			monitor_name,
+			count,
+			monitor_match.network_slug(),
+			monitor_match.transaction_hash(),
+			selector,
+			error
+		);
+		tracing::error!(
+			monitor = %monitor_name,
+			condition = %condition,
+			"Decoding failure threshold reached: {}",
+			description
+		);
+
+		let monitor = monitor_match.monitor_mut();
+		let alert_triggers = match &monitor.alert_on_decode_failure {
+			Some(alert) if !alert.triggers.is_empty() => alert.triggers.clone(),
+			_ => monitor
+				.triggers
+				.iter()
+				.map(|reference| reference.name().to_string())
+				.collect(),
+		};
+		if alert_triggers.is_empty() {
+			continue;
+		}
+		let message = MessageOverride {
+			title: Some(format!("ABI decoding failures for {}", monitor_name)),
+			body: Some(description),
+		};
+		monitor.triggers = alert_triggers
+			.iter()
+			.map(|name| {
+				TriggerReference::WithOverrides(TriggerOverride {
+					name: name.clone(),
+					message: Some(message.clone()),
+					variables: HashMap::new(),
+					rollup: false,
+				})
+			})
+			.collect();
+
+		let mut variables = match_variables(&monitor_match, &[], trigger_service.price_oracles());
+		variables.extend([
+			("decode_failure.condition".to_string(), condition),
+			("decode_failure.selector".to_string(), selector),
+			("decode_failure.error".to_string(), error),
+			("decode_failure.count".to_string(), count.to_string()),
+		]);
+		if let Err(e) = trigger_service
+			.execute(&alert_triggers, variables, &monitor_match, trigger_scripts)
+			.await
+		{
+			TriggerError::execution_error(e.to_string(), Some(e.into()), None);
+		}
+	}
+}
+
+/// Delivers a match to its triggers, logging delivery failures
+///
+/// Rollups of related matches are summarized into a single notification first, and matches
+/// of monitors with a notification budget are checked against it. Monitors with
+/// a `history` configuration then get the prior occurrences of the match, matches of monitors
+/// with a trigger having a `context` are recorded as recent matches, and the monitor's
+/// enrichments add their variables to the match. Variables produced by the match
+/// take precedence over enriched ones with the same name.
+async fn dispatch_match<S: TriggerExecutionServiceTrait>(
+	deduped: DedupedMatch,
+	trigger_service: &S,
+	trigger_scripts: &HashMap<String, (ScriptLanguage, String)>,
+	budgets: &NotificationBudgets,
+	enricher: &Enricher,
+) {
+	let DedupedMatch {
+		mut monitor_match,
+		also_matched,
+		rolled_up,
+	} = deduped;
+	let rollup_variables =
+		(!rolled_up.is_empty()).then(|| apply_rollup(&mut monitor_match, &rolled_up));
+	let Some(monitor_match) =
+		apply_notification_budget(monitor_match, budgets, trigger_service, trigger_scripts).await
+	else {
+		return;
+	};
+	let mut variables = match_variables(
+		&monitor_match,
+		&also_matched,
+		trigger_service.price_oracles(),
+	);
+	variables.extend(rollup_variables.unwrap_or_default());
+	if let Some(history) = trigger_service.match_history() {
+		if !history.apply(&monitor_match, &mut variables).await {
+			tracing::debug!(
+				"Skipping repeat match of monitor '{}', which only delivers first occurrences",
+				monitor_match.monitor().name
+			);
+			return;
+		}
+		// Kept for the triggers including earlier matches, which leave out this transaction
+		let includes_prior_matches = monitor_match.monitor().trigger_names().iter().any(|name| {
+			trigger_service
+				.get_trigger(name)
+				.is_some_and(|trigger| trigger.context.is_some())
+		});
+		if includes_prior_matches {
+			history.record_recent(&monitor_match, &variables);
+		}
+	}
+	let mut enriched = match enricher
+		.enrich(&monitor_match, &variables, trigger_scripts)
+		.await
+	{
+		Ok(enriched) => enriched,
+		Err(e) => {
+			TriggerError::execution_error(e.to_string(), Some(e.into()), None);
+			return;
+		}
+	};
+	enriched.extend(variables);
+	if let Err(e) =
+		handle_match_with_variables(monitor_match, enriched, trigger_service, trigger_scripts).await
+	{
+		TriggerError::execution_error(e.to_string(), Some(e.into()), None);
+	}
+}
+
 /// Creates a trigger handler function that processes trigger events from the block processing
 /// pipeline.
 ///
+/// Matches of a block are de-duplicated across the views of a network group, and across
+/// monitors sharing a `dedup_group` for triggers that opt into `group_dedup`, before being
+/// dispatched. Trigger condition results are cached per match for the lifetime of the handler.
+///
 /// # Arguments
 /// * `shutdown_tx` - Watch channel for shutdown signals
 /// * `trigger_service` - Service for executing triggers
+/// * `active_monitors_trigger_scripts` - Scripts used by the triggers
+/// * `network_views` - Views of the network groups the matches are compared across
+/// * `decode_failures` - Decoding failures recorded by the filters, whose alerts are delivered
 ///
 /// # Returns
 /// Returns a function that handles trigger execution for matching monitors
@@ -390,23 +1109,187 @@ pub fn create_trigger_handler<S: TriggerExecutionServiceTrait + Send + Sync + 's
 	shutdown_tx: watch::Sender<bool>,
 	trigger_service: Arc<S>,
 	active_monitors_trigger_scripts: HashMap<String, (ScriptLanguage, String)>,
+	network_views: Arc<NetworkViews>,
+	decode_failures: Arc<DecodeFailures>,
 ) -> Arc<impl Fn(&ProcessedBlock) -> tokio::task::JoinHandle<()> + Send + Sync> {
+	let condition_cache = Arc::new(ConditionCache::default());
+	let budgets = Arc::new(NotificationBudgets::new());
+	let enricher = Arc::new(Enricher::default());
+	let sender_activity = SenderActivityTracker::new();
+	let baselines = BaselineTracker::new();
+	let network_metrics = NetworkMetricsTracker::new();
 	Arc::new(move |block: &ProcessedBlock| {
 		let mut shutdown_rx = shutdown_tx.subscribe();
 		let trigger_service = trigger_service.clone();
 		let trigger_scripts = active_monitors_trigger_scripts.clone();
-		let block = block.clone();
+		let condition_cache = condition_cache.clone();
+		let budgets = budgets.clone();
+		let enricher = enricher.clone();
+		// Blocks reach the handler in order, as the nonce tracking of senders, the activity
+		// baselines and the persistence of network metrics conditions require
+		let block = sender_activity.resolve(block.clone());
+		let block = baselines.resolve(block);
+		let block = network_metrics.resolve(block);
+		let (block, divergences) = network_views.resolve(block);
+		let decode_failure_reports = decode_failures.take_alerts(&block.network_slug);
 
 		tokio::spawn(async move {
 			tokio::select! {
 				_ = async {
-					if block.processing_results.is_empty() {
-						return;
+					report_network_divergences(divergences, &*trigger_service, &trigger_scripts).await;
+					report_decode_failures(decode_failure_reports, &*trigger_service, &trigger_scripts).await;
+					let deduped_matches = prepare_block_matches(&block, &*trigger_service, &trigger_scripts, &condition_cache, None).await;
+					for deduped in deduped_matches {
+						dispatch_match(deduped, &*trigger_service, &trigger_scripts, &budgets, &enricher).await;
 					}
-					let filtered_matches = run_trigger_filters(&block.processing_results, &block.network_slug, &trigger_scripts).await;
-					for monitor_match in &filtered_matches {
-						if let Err(e) = handle_match(monitor_match.clone(), &*trigger_service, &trigger_scripts).await {
-							TriggerError::execution_error(e.to_string(), Some(e.into()), None);
+				} => {}
+				_ = shutdown_rx.changed() => {
+					tracing::info!("Shutting down trigger handling task");
+				}
+			}
+		})
+	})
+}
+
+/// Creates the function delivering a match to its triggers.
+///
+/// # Arguments
+/// * `trigger_service` - Service for executing triggers
+/// * `active_monitors_trigger_scripts` - Scripts used by the triggers
+/// * `budgets` - Notification budgets the matches are checked against
+///
+/// # Returns
+/// Returns the function used by the dispatch queue and the outbox dispatcher
+pub fn create_dispatch_fn<S: TriggerExecutionServiceTrait + Send + Sync + 'static>(
+	trigger_service: Arc<S>,
+	active_monitors_trigger_scripts: HashMap<String, (ScriptLanguage, String)>,
+	budgets: Arc<NotificationBudgets>,
+) -> DispatchFn {
+	let trigger_scripts = Arc::new(active_monitors_trigger_scripts);
+	let enricher = Arc::new(Enricher::default());
+	Arc::new(move |deduped: DedupedMatch| {
+		let trigger_service = trigger_service.clone();
+		let trigger_scripts = trigger_scripts.clone();
+		let budgets = budgets.clone();
+		let enricher = enricher.clone();
+		Box::pin(async move {
+			dispatch_match(
+				deduped,
+				&*trigger_service,
+				&trigger_scripts,
+				&budgets,
+				&enricher,
+			)
+			.await;
+		}) as BoxFuture<'static, ()>
+	})
+}
+
+/// Creates the queue through which matches are dispatched to their triggers.
+///
+/// # Arguments
+/// * `config` - Capacity, overflow policy and concurrency of the queue
+/// * `trigger_service` - Service for executing triggers
+/// * `active_monitors_trigger_scripts` - Scripts used by the triggers
+/// * `budgets` - Notification budgets the matches are checked against
+///
+/// # Returns
+/// Returns the started queue, to be closed on shutdown to drain pending matches
+pub fn create_dispatch_queue<S: TriggerExecutionServiceTrait + Send + Sync + 'static>(
+	config: DispatchQueueConfig,
+	trigger_service: Arc<S>,
+	active_monitors_trigger_scripts: HashMap<String, (ScriptLanguage, String)>,
+	budgets: Arc<NotificationBudgets>,
+) -> Arc<DispatchQueue> {
+	DispatchQueue::start(
+		config,
+		create_dispatch_fn(trigger_service, active_monitors_trigger_scripts, budgets),
+	)
+}
+
+/// Creates a trigger handler function that queues matches for dispatch.
+///
+/// Behaves like [`create_trigger_handler`], except that matches are added to `dispatch_queue`
+/// instead of being dispatched by the handler. The returned task completes once the block's
+/// matches are queued, so with [`crate::services::trigger::OverflowPolicy::Block`] a full queue holds back block
+/// processing.
+///
+/// With `outbox`, matches are written to the outbox instead of the queue, before the block is
+/// recorded as processed, and delivered by an
+/// [`crate::services::trigger::OutboxDispatcher`].
+///
+/// # Arguments
+/// * `shutdown_tx` - Watch channel for shutdown signals
+/// * `trigger_service` - Service for executing triggers
+/// * `active_monitors_trigger_scripts` - Scripts used by trigger conditions
+/// * `dispatch_queue` - Queue the matches are added to
+/// * `sender_activity` - Nonce history the sender activity matches are checked against
+/// * `baselines` - Activity baselines the baseline matches are checked against
+/// * `network_views` - Views of the network groups the matches are compared across
+/// * `shared_dedup` - State store through which group dedup is shared with other instances
+/// * `outbox` - Outbox the matches are written to instead of the queue
+/// * `decode_failures` - Decoding failures recorded by the filters, whose alerts are delivered
+///
+/// # Returns
+/// Returns a function that queues the matches of processed blocks
+pub fn create_queued_trigger_handler<S: TriggerExecutionServiceTrait + Send + Sync + 'static>(
+	shutdown_tx: watch::Sender<bool>,
+	trigger_service: Arc<S>,
+	active_monitors_trigger_scripts: HashMap<String, (ScriptLanguage, String)>,
+	dispatch_queue: Arc<DispatchQueue>,
+	sender_activity: Arc<SenderActivityTracker>,
+	baselines: Arc<BaselineTracker>,
+	network_views: Arc<NetworkViews>,
+	shared_dedup: Option<SharedDedup>,
+	outbox: Option<Arc<NotificationOutbox>>,
+	decode_failures: Arc<DecodeFailures>,
+) -> Arc<impl Fn(&ProcessedBlock) -> tokio::task::JoinHandle<()> + Send + Sync> {
+	let condition_cache = Arc::new(ConditionCache::default());
+	let network_metrics = NetworkMetricsTracker::new();
+	Arc::new(move |block: &ProcessedBlock| {
+		let mut shutdown_rx = shutdown_tx.subscribe();
+		let trigger_service = trigger_service.clone();
+		let trigger_scripts = active_monitors_trigger_scripts.clone();
+		let condition_cache = condition_cache.clone();
+		let dispatch_queue = dispatch_queue.clone();
+		let shared_dedup = shared_dedup.clone();
+		let outbox = outbox.clone();
+		let persisted_baselines = baselines.clone();
+		// Blocks reach the handler in order, as the nonce tracking of senders, the activity
+		// baselines and the persistence of network metrics conditions require
+		let block = sender_activity.resolve(block.clone());
+		let block = baselines.resolve(block);
+		let block = network_metrics.resolve(block);
+		let (block, divergences) = network_views.resolve(block);
+		let decode_failure_reports = decode_failures.take_alerts(&block.network_slug);
+
+		tokio::spawn(async move {
+			tokio::select! {
+				_ = async {
+					persisted_baselines.persist().await;
+					report_network_divergences(divergences, &*trigger_service, &trigger_scripts).await;
+					report_decode_failures(decode_failure_reports, &*trigger_service, &trigger_scripts).await;
+					let deduped_matches = prepare_block_matches(&block, &*trigger_service, &trigger_scripts, &condition_cache, shared_dedup.as_ref()).await;
+					match outbox {
+						Some(outbox) => {
+							if let Err(e) = outbox.add(deduped_matches.clone()).await {
+								// Queue the matches, the block is recorded as processed anyway
+								tracing::error!(
+									network = %block.network_slug,
+									block_number = block.block_number,
+									"Failed to write the matches of block {} to the notification outbox, queueing them: {}",
+									block.block_number,
+									e
+								);
+								for deduped in deduped_matches {
+									dispatch_queue.enqueue(deduped).await;
+								}
+							}
+						}
+						None => {
+							for deduped in deduped_matches {
+								dispatch_queue.enqueue(deduped).await;
+							}
 						}
 					}
 				} => {}
@@ -418,6 +1301,67 @@ pub fn create_trigger_handler<S: TriggerExecutionServiceTrait + Send + Sync + 's
 	})
 }
 
+/// Services of the default config directories
+pub type ConfigServices = (
+	MonitorService<
+		MonitorRepository<NetworkRepository, TriggerRepository>,
+		NetworkRepository,
+		TriggerRepository,
+	>,
+	NetworkService<NetworkRepository>,
+	TriggerService<TriggerRepository>,
+);
+
+/// Creates the monitor, network and trigger services of the default config directories.
+///
+/// Only the monitors carrying one of the tags set by `MONITOR_TAGS` are loaded, all of them
+/// when it is unset, and unused triggers and networks fail the load with
+/// `STRICT_CONFIG_CONSISTENCY=true`. The services are passed to [`initialize_services`].
+///
+/// # Errors
+/// Returns an error if a directory fails to load or a monitor fails validation
+pub async fn create_config_services() -> std::result::Result<ConfigServices, RepositoryError> {
+	let network_service = NetworkService::new_with_repository(NetworkRepository::new(None).await?)?;
+	let trigger_service = TriggerService::new_with_repository(TriggerRepository::new(None).await?)?;
+	let monitors = MonitorRepository::new_with_tag_filter(
+		None,
+		Some(network_service.clone()),
+		Some(trigger_service.clone()),
+		monitor_tags_from_env(),
+		ConsistencyMode::from_env(),
+	)
+	.await?;
+	Ok((
+		MonitorService::new_with_repository(monitors)?,
+		network_service,
+		trigger_service,
+	))
+}
+
+/// Loads the monitors, networks and triggers from the default config directories.
+///
+/// Used to build the candidate configuration of a reload, which goes through the same
+/// validation as the configuration loaded at startup, including the settings read from the
+/// environment by [`create_config_services`].
+pub async fn load_config_snapshot() -> std::result::Result<ConfigSnapshot, RepositoryError> {
+	let networks = NetworkRepository::new(None).await?;
+	let triggers = TriggerRepository::new(None).await?;
+	let monitors = MonitorRepository::<NetworkRepository, TriggerRepository>::new_with_tag_filter(
+		None,
+		Some(NetworkService::new_with_repository(networks.clone())?),
+		Some(TriggerService::new_with_repository(triggers.clone())?),
+		monitor_tags_from_env(),
+		ConsistencyMode::from_env(),
+	)
+	.await?;
+
+	Ok(ConfigSnapshot {
+		monitors: monitors.monitors,
+		networks: networks.networks,
+		triggers: triggers.triggers,
+	})
+}
+
 /// Checks if a network has any active monitors.
 ///
 /// # Arguments
@@ -432,6 +1376,22 @@ pub fn has_active_monitors(monitors: &[Monitor], network_slug: &String) -> bool
 		.any(|m| m.networks.contains(network_slug) && !m.paused)
 }
 
+/// Returns whether a monitor has a `history` configuration, or a trigger of one includes earlier
+/// matches with a `context`.
+fn uses_match_history<'a, T: TriggerRepositoryTrait>(
+	monitors: impl IntoIterator<Item = &'a Monitor>,
+	trigger_service: &TriggerService<T>,
+) -> bool {
+	monitors.into_iter().any(|monitor| {
+		monitor.history.is_some()
+			|| monitor.trigger_names().iter().any(|name| {
+				trigger_service
+					.get(name)
+					.is_some_and(|trigger| trigger.context.is_some())
+			})
+	})
+}
+
 /// Filters out paused monitors from the provided collection.
 ///
 /// # Arguments
@@ -454,7 +1414,7 @@ fn filter_active_monitors(monitors: HashMap<String, Monitor>) -> Vec<Monitor> {
 ///
 /// # Returns
 /// Returns a vector of monitors that are configured for the specified network
-fn filter_network_monitors(monitors: &[Monitor], network_slug: &String) -> Vec<Monitor> {
+fn filter_network_monitors(monitors: &[Arc<Monitor>], network_slug: &String) -> Vec<Arc<Monitor>> {
 	monitors
 		.iter()
 		.filter(|m| m.networks.contains(network_slug))
@@ -462,22 +1422,24 @@ fn filter_network_monitors(monitors: &[Monitor], network_slug: &String) -> Vec<M
 		.collect()
 }
 
-async fn execute_trigger_condition(
+async fn run_trigger_condition(
 	trigger_condition: &TriggerConditions,
 	monitor_match: &MonitorMatch,
 	script_content: &(ScriptLanguage, String),
-) -> bool {
+) -> std::result::Result<bool, anyhow::Error> {
 	let executor = ScriptExecutorFactory::create(&script_content.0, &script_content.1);
 
-	let result = executor
+	executor
 		.execute(
 			monitor_match.clone(),
 			&trigger_condition.timeout_ms,
 			trigger_condition.arguments.as_deref(),
 			false,
 		)
-		.await;
+		.await
+}
 
+fn condition_result(result: std::result::Result<bool, anyhow::Error>) -> bool {
 	match result {
 		Ok(true) => true,
 		Err(e) => {
@@ -488,40 +1450,41 @@ async fn execute_trigger_condition(
 	}
 }
 
+/// Removes the matches filtered out by their monitor's trigger conditions.
+///
+/// Condition results are cached per match, so a match dispatched again does not execute its
+/// condition scripts a second time.
 async fn run_trigger_filters(
 	matches: &[MonitorMatch],
 	_network: &str,
 	trigger_scripts: &HashMap<String, (ScriptLanguage, String)>,
+	condition_cache: &ConditionCache,
 ) -> Vec<MonitorMatch> {
 	let mut filtered_matches = vec![];
 
 	for monitor_match in matches {
 		let mut is_filtered = false;
-		let trigger_conditions = match monitor_match {
-			MonitorMatch::EVM(evm_match) => &evm_match.monitor.trigger_conditions,
-			MonitorMatch::Stellar(stellar_match) => &stellar_match.monitor.trigger_conditions,
-			MonitorMatch::Midnight(midnight_match) => &midnight_match.monitor.trigger_conditions,
-		};
+		let match_id = match_uuid(monitor_match);
+		let trigger_conditions = &monitor_match.monitor().trigger_conditions;
 
 		for trigger_condition in trigger_conditions {
-			let monitor_name = match monitor_match {
-				MonitorMatch::EVM(evm_match) => evm_match.monitor.name.clone(),
-				MonitorMatch::Stellar(stellar_match) => stellar_match.monitor.name.clone(),
-				MonitorMatch::Midnight(midnight_match) => midnight_match.monitor.name.clone(),
-			};
-
-			let script_content = trigger_scripts
-				.get(&format!(
-					"{}|{}",
-					normalize_string(&monitor_name),
-					trigger_condition.script_path
-				))
-				.ok_or_else(|| {
-					ScriptError::execution_error("Script content not found".to_string(), None, None)
-				});
+			let monitor_name = monitor_match.monitor().name.clone();
+
+			let script_key = format!(
+				"{}|{}",
+				normalize_string(&monitor_name),
+				trigger_condition.script_path
+			);
+			let script_content = trigger_scripts.get(&script_key).ok_or_else(|| {
+				ScriptError::execution_error("Script content not found".to_string(), None, None)
+			});
 			if let Ok(script_content) = script_content {
-				if execute_trigger_condition(trigger_condition, monitor_match, script_content).await
-				{
+				let result = condition_cache
+					.get_or_evaluate(match_id, &script_key, || {
+						run_trigger_condition(trigger_condition, monitor_match, script_content)
+					})
+					.await;
+				if condition_result(result) {
 					is_filtered = true;
 					break;
 				}
@@ -640,8 +1603,16 @@ mod tests {
 					functions: vec![],
 					events: vec![],
 					transactions: vec![],
+					sender_activity: None,
+					baseline: None,
+					network_metrics: None,
 				},
 				matched_on_args: None,
+				sender_activity: None,
+				baseline: None,
+				network_metrics: None,
+				timing: None,
+				origin: Default::default(),
 			})),
 			BlockChainType::Stellar => MonitorMatch::Stellar(Box::new(StellarMonitorMatch {
 				monitor: create_test_monitor("test", vec![], false, script_path),
@@ -652,8 +1623,13 @@ mod tests {
 					functions: vec![],
 					events: vec![],
 					transactions: vec![],
+					sender_activity: None,
+					baseline: None,
+					network_metrics: None,
 				},
 				matched_on_args: None,
+				timing: None,
+				origin: Default::default(),
 			})),
 			BlockChainType::Midnight => unimplemented!(),
 		}
@@ -674,8 +1650,16 @@ mod tests {
 					functions: vec![],
 					events: vec![],
 					transactions: vec![],
+					sender_activity: None,
+					baseline: None,
+					network_metrics: None,
 				},
 				matched_on_args: None,
+				sender_activity: None,
+				baseline: None,
+				network_metrics: None,
+				timing: None,
+				origin: Default::default(),
 			})),
 			BlockChainType::Stellar => MonitorMatch::Stellar(Box::new(StellarMonitorMatch {
 				monitor,
@@ -686,8 +1670,13 @@ mod tests {
 					functions: vec![],
 					events: vec![],
 					transactions: vec![],
+					sender_activity: None,
+					baseline: None,
+					network_metrics: None,
 				},
 				matched_on_args: None,
+				timing: None,
+				origin: Default::default(),
 			})),
 			BlockChainType::Midnight => unimplemented!(),
 		}
@@ -759,14 +1748,24 @@ mod tests {
 	#[test]
 	fn test_filter_network_monitors() {
 		let monitors = vec![
-			create_test_monitor("1", vec!["ethereum_mainnet"], false, None),
-			create_test_monitor("2", vec!["stellar_mainnet"], true, None),
-			create_test_monitor(
+			Arc::new(create_test_monitor(
+				"1",
+				vec!["ethereum_mainnet"],
+				false,
+				None,
+			)),
+			Arc::new(create_test_monitor(
+				"2",
+				vec!["stellar_mainnet"],
+				true,
+				None,
+			)),
+			Arc::new(create_test_monitor(
 				"3",
 				vec!["ethereum_mainnet", "stellar_mainnet"],
 				false,
 				None,
-			),
+			)),
 		];
 
 		let eth_monitors = filter_network_monitors(&monitors, &"ethereum_mainnet".to_string());
@@ -809,7 +1808,13 @@ print(False)
 		);
 
 		// Test the filter function
-		let filtered = run_trigger_filters(&matches, "ethereum_mainnet", &trigger_scripts).await;
+		let filtered = run_trigger_filters(
+			&matches,
+			"ethereum_mainnet",
+			&trigger_scripts,
+			&ConditionCache::default(),
+		)
+		.await;
 		assert!(filtered.is_empty());
 	}
 
@@ -839,7 +1844,13 @@ print(result)
 		);
 		let matches = vec![match_item.clone()];
 
-		let filtered = run_trigger_filters(&matches, "ethereum_mainnet", &trigger_scripts).await;
+		let filtered = run_trigger_filters(
+			&matches,
+			"ethereum_mainnet",
+			&trigger_scripts,
+			&ConditionCache::default(),
+		)
+		.await;
 		assert_eq!(filtered.len(), 1);
 		assert!(matches_equal(&filtered[0], &match_item));
 	}
@@ -870,7 +1881,13 @@ print(result)
 		);
 		let matches = vec![match_item.clone()];
 
-		let filtered = run_trigger_filters(&matches, "ethereum_mainnet", &trigger_scripts).await;
+		let filtered = run_trigger_filters(
+			&matches,
+			"ethereum_mainnet",
+			&trigger_scripts,
+			&ConditionCache::default(),
+		)
+		.await;
 		assert_eq!(filtered.len(), 1);
 	}
 
@@ -890,8 +1907,9 @@ print(result)
 		);
 		let script_content = (ScriptLanguage::Python, script_content.to_string());
 
-		let result =
-			execute_trigger_condition(&trigger_condition, &match_item, &script_content).await;
+		let result = condition_result(
+			run_trigger_condition(&trigger_condition, &match_item, &script_content).await,
+		);
 		assert!(!result); // Should be false when script returns false
 	}
 
@@ -911,8 +1929,9 @@ print(result)
 		);
 		let script_content = (ScriptLanguage::Python, script_content.to_string());
 
-		let result =
-			execute_trigger_condition(&trigger_condition, &match_item, &script_content).await;
+		let result = condition_result(
+			run_trigger_condition(&trigger_condition, &match_item, &script_content).await,
+		);
 		assert!(!result); // Should be false when script errors
 	}
 
@@ -930,8 +1949,9 @@ print(result)
 		);
 		let script_content = (ScriptLanguage::Python, "invalid script content".to_string());
 
-		let result =
-			execute_trigger_condition(&trigger_condition, &match_item, &script_content).await;
+		let result = condition_result(
+			run_trigger_condition(&trigger_condition, &match_item, &script_content).await,
+		);
 		assert!(!result); // Should be false for invalid script
 	}
 
@@ -981,7 +2001,13 @@ print(True)
 
 		// Run the filter with our test data
 		let matches = vec![match_item.clone()];
-		let filtered = run_trigger_filters(&matches, "ethereum_mainnet", &trigger_scripts).await;
+		let filtered = run_trigger_filters(
+			&matches,
+			"ethereum_mainnet",
+			&trigger_scripts,
+			&ConditionCache::default(),
+		)
+		.await;
 
 		assert_eq!(filtered.len(), 0);
 	}
@@ -1009,7 +2035,13 @@ print(True)
 		);
 
 		let matches = vec![match_item.clone()];
-		let filtered = run_trigger_filters(&matches, "ethereum_mainnet", &trigger_scripts).await;
+		let filtered = run_trigger_filters(
+			&matches,
+			"ethereum_mainnet",
+			&trigger_scripts,
+			&ConditionCache::default(),
+		)
+		.await;
 		assert_eq!(filtered.len(), 0);
 	}
 
@@ -1035,7 +2067,13 @@ print(True)
 		);
 
 		let matches = vec![match_item.clone()];
-		let filtered = run_trigger_filters(&matches, "ethereum_mainnet", &trigger_scripts).await;
+		let filtered = run_trigger_filters(
+			&matches,
+			"ethereum_mainnet",
+			&trigger_scripts,
+			&ConditionCache::default(),
+		)
+		.await;
 		assert_eq!(filtered.len(), 1);
 	}
 
@@ -1061,7 +2099,13 @@ print(True)
 		);
 
 		let matches = vec![match_item.clone()];
-		let filtered = run_trigger_filters(&matches, "ethereum_mainnet", &trigger_scripts).await;
+		let filtered = run_trigger_filters(
+			&matches,
+			"ethereum_mainnet",
+			&trigger_scripts,
+			&ConditionCache::default(),
+		)
+		.await;
 		assert_eq!(filtered.len(), 0);
 	}
 
@@ -1092,7 +2136,13 @@ print(True)
 		);
 
 		let matches = vec![match_item.clone()];
-		let filtered = run_trigger_filters(&matches, "ethereum_mainnet", &trigger_scripts).await;
+		let filtered = run_trigger_filters(
+			&matches,
+			"ethereum_mainnet",
+			&trigger_scripts,
+			&ConditionCache::default(),
+		)
+		.await;
 		assert_eq!(filtered.len(), 0);
 	}
 
@@ -1123,7 +2173,13 @@ print(True)
 		);
 
 		let matches = vec![match_item.clone()];
-		let filtered = run_trigger_filters(&matches, "ethereum_mainnet", &trigger_scripts).await;
+		let filtered = run_trigger_filters(
+			&matches,
+			"ethereum_mainnet",
+			&trigger_scripts,
+			&ConditionCache::default(),
+		)
+		.await;
 		assert_eq!(filtered.len(), 1);
 	}
 
@@ -1148,7 +2204,13 @@ print(False)
 			),
 		);
 
-		let filtered = run_trigger_filters(&matches, "stellar_mainnet", &trigger_scripts).await;
+		let filtered = run_trigger_filters(
+			&matches,
+			"stellar_mainnet",
+			&trigger_scripts,
+			&ConditionCache::default(),
+		)
+		.await;
 		assert!(filtered.is_empty());
 	}
 
@@ -1178,7 +2240,13 @@ print(result)
 		);
 		let matches = vec![match_item.clone()];
 
-		let filtered = run_trigger_filters(&matches, "stellar_mainnet", &trigger_scripts).await;
+		let filtered = run_trigger_filters(
+			&matches,
+			"stellar_mainnet",
+			&trigger_scripts,
+			&ConditionCache::default(),
+		)
+		.await;
 		assert_eq!(filtered.len(), 1);
 		assert!(matches_equal(&filtered[0], &match_item));
 	}
@@ -1205,7 +2273,13 @@ print(result)
 		);
 
 		let matches = vec![match_item.clone()];
-		let filtered = run_trigger_filters(&matches, "stellar_mainnet", &trigger_scripts).await;
+		let filtered = run_trigger_filters(
+			&matches,
+			"stellar_mainnet",
+			&trigger_scripts,
+			&ConditionCache::default(),
+		)
+		.await;
 		assert_eq!(filtered.len(), 0); // Match should be filtered out because condition2 returns true
 	}
 }
@@ -26,39 +26,71 @@ pub mod utils;
 
 use crate::{
 	bootstrap::{
-		create_block_handler, create_trigger_handler, get_contract_specs, has_active_monitors,
-		initialize_services, Result,
+		create_block_handler, create_config_services, create_dispatch_fn, create_dispatch_queue,
+		create_queued_trigger_handler, get_contract_specs, has_active_monitors,
+		initialize_applied_services, initialize_services, load_config_snapshot, preflight,
+		send_due_budget_digests, ConfigApplier, ConfigApplyOptions, PreflightOptions, Result,
+		ServiceResult,
+	},
+	models::{
+		config_schemas, lint_configuration, write_config_schemas, BlockChainType, BlockType,
+		ConfigSnapshot, ContractSpec, LintConfig, MatchOrigin, Monitor, Network, ProcessedBlock,
+		ScriptLanguage,
 	},
-	models::{BlockChainType, Network, ScriptLanguage},
 	repositories::{
 		MonitorRepository, MonitorService, NetworkRepository, NetworkService, TriggerRepository,
 	},
 	services::{
-		blockchain::{ClientPool, ClientPoolTrait},
-		blockwatcher::{BlockTracker, BlockTrackerTrait, BlockWatcherService, FileBlockStorage},
-		filter::FilterService,
-		trigger::{TriggerExecutionService, TriggerExecutionServiceTrait},
+		blockchain::{ClientPool, ClientPoolTrait, FixtureClientPool},
+		blockwatcher::{
+			retry_failed_blocks, run_backfill, BackfillJob, BlockTracker, BlockTrackerTrait,
+			BlockWatcherService, FileBlockStorage, FileLeaseStore, LeaderElector,
+		},
+		filter::{
+			BaselineTracker, FilterService, SenderActivityTracker, DEFAULT_BASELINE_STATE_FILE,
+			DEFAULT_SENDER_ACTIVITY_STATE_FILE,
+		},
+		notification::NotificationPreview,
+		state::{
+			create_state_store, FileStateStore, StateStore, StateStoreBackend, StateStoreConfig,
+		},
+		trigger::{
+			CircuitBreakerConfig, DispatchQueue, DispatchQueueConfig, MatchHistory, MuteRequest,
+			NetworkViews, NotificationBudgets, NotificationOutbox, OutboxDispatcher,
+			OutboxDispatcherConfig, OverflowPolicy, SharedDedup, TriggerCircuitBreakers,
+			TriggerExecutionService, TriggerExecutionServiceTrait, TriggerMutes,
+			DEFAULT_BUDGET_STATE_FILE, DEFAULT_CIRCUIT_BREAKER_STATE_FILE,
+			DEFAULT_MATCH_HISTORY_CACHE_CAPACITY, DEFAULT_MATCH_HISTORY_STATE_FILE,
+			DEFAULT_MATCH_HISTORY_TIMEOUT, DEFAULT_OUTBOX_FILE, DEFAULT_OUTBOX_RETENTION,
+			DEFAULT_SHARED_DEDUP_TTL, DEFAULT_TRIGGER_MUTES_STATE_FILE,
+		},
 	},
 	utils::{
+		build_info,
 		constants::DOCUMENTATION_URL,
 		logging::setup_logging,
-		metrics::server::create_metrics_server,
+		metrics::server::{create_metrics_server, AdminToken},
 		monitor::{
+			comparison::{compare_monitors, ComparisonGate, MonitorComparisonConfig},
+			defender::{import_defender_export, parse_defender_export, parse_network_mapping},
 			execution::{execute_monitor, MonitorExecutionConfig},
+			preview::{load_match, preview_notifications, sample_match, sample_monitor},
 			MonitorExecutionError,
 		},
 		parse_string_to_bytes_size,
 	},
 };
 
-use clap::Parser;
+use clap::{Parser, Subcommand};
 use dotenvy::dotenv_override;
+use futures::future::BoxFuture;
 use std::collections::HashMap;
 use std::env::{set_var, var};
+use std::path::PathBuf;
 use std::sync::Arc;
 use tokio::sync::{watch, Mutex};
 use tokio_cron_scheduler::JobScheduler;
-use tracing::{error, info, instrument};
+use tracing::{error, info, instrument, warn};
 
 type MonitorServiceType = MonitorService<
 	MonitorRepository<NetworkRepository, TriggerRepository>,
@@ -136,6 +168,182 @@ struct Cli {
 	/// Validate configuration files without starting the service
 	#[arg(long)]
 	check: bool,
+
+	/// Retry the blocks that could not be fetched, for all networks or the one given by --network
+	#[arg(long)]
+	retry_failed_blocks: bool,
+
+	/// Print the preflight report, probing all endpoints, and exit
+	#[arg(long)]
+	preflight_only: bool,
+
+	#[command(subcommand)]
+	command: Option<Command>,
+}
+
+#[derive(Subcommand)]
+enum Command {
+	/// Print or write the JSON Schemas of the monitor, network and trigger configuration files
+	Schema {
+		/// Only emit the schema of this configuration file
+		#[arg(
+			long,
+			value_name = "KIND",
+			value_parser = ["monitor", "network", "trigger"],
+			conflicts_with = "output"
+		)]
+		kind: Option<String>,
+
+		/// Directory to write the schema files to instead of printing them
+		#[arg(long, value_name = "DIR")]
+		output: Option<PathBuf>,
+	},
+	/// Evaluate two versions of a monitor over a range of blocks and report the matches that
+	/// differ, without sending notifications
+	Compare {
+		/// Path to the current version of the monitor
+		#[arg(long, value_name = "MONITOR_PATH")]
+		old: PathBuf,
+
+		/// Path to the new version of the monitor
+		#[arg(long, value_name = "MONITOR_PATH")]
+		new: PathBuf,
+
+		/// Network to evaluate the monitors on
+		#[arg(long, value_name = "NETWORK_SLUG")]
+		network: String,
+
+		/// First block to evaluate
+		#[arg(long, value_name = "BLOCK_NUMBER")]
+		from_block: u64,
+
+		/// Last block to evaluate, included
+		#[arg(long, value_name = "BLOCK_NUMBER")]
+		to_block: u64,
+
+		/// Format of the report
+		#[arg(long, value_name = "FORMAT", value_parser = ["json", "csv"], default_value = "json")]
+		format: String,
+
+		/// File to write the report to instead of printing it
+		#[arg(long, value_name = "FILE")]
+		output: Option<PathBuf>,
+
+		/// Exit with an error if the report has matches only in the old monitor, only in the new
+		/// one, or any difference
+		#[arg(
+			long,
+			value_name = "GATE",
+			value_parser = ["only-in-old", "only-in-new", "any-change"]
+		)]
+		fail_on: Option<String>,
+
+		/// Replay the blocks from fixtures recorded with `FIXTURE_RECORD_DIR` instead of fetching
+		/// them from the network
+		#[arg(long, value_name = "DIR")]
+		fixtures: Option<PathBuf>,
+	},
+	/// Process a range of historical blocks with the active monitors and dispatch their matches
+	/// to the triggers accepting backfilled matches
+	Backfill {
+		/// Network to process the blocks of
+		#[arg(long, value_name = "NETWORK_SLUG")]
+		network: String,
+
+		/// First block to process
+		#[arg(long, value_name = "BLOCK_NUMBER")]
+		from_block: u64,
+
+		/// Last block to process, included
+		#[arg(long, value_name = "BLOCK_NUMBER")]
+		to_block: u64,
+
+		/// Identifier of the job, carried by its matches (default
+		/// `<network>-<from_block>-<to_block>`)
+		#[arg(long, value_name = "JOB_ID")]
+		job_id: Option<String>,
+
+		/// Mark the matches as live, so that every trigger is sent them, to intentionally
+		/// re-alert on the range
+		#[arg(long, value_name = "ORIGIN", value_parser = ["live"])]
+		origin_override: Option<String>,
+
+		/// Replay the blocks from fixtures recorded with `FIXTURE_RECORD_DIR` instead of fetching
+		/// them from the network, marking the matches as replayed
+		#[arg(long, value_name = "DIR")]
+		fixtures: Option<PathBuf>,
+	},
+	/// Render the payloads the triggers of a monitor would send for a match, without sending
+	/// them
+	Preview {
+		/// Monitor whose triggers are previewed
+		#[arg(long, value_name = "MONITOR_NAME", required_unless_present = "trigger")]
+		monitor: Option<String>,
+
+		/// Only preview this trigger, or preview it for a sample monitor when no monitor is given
+		#[arg(long, value_name = "TRIGGER_SLUG")]
+		trigger: Option<String>,
+
+		/// Recorded match to preview, instead of a synthetic sample
+		#[arg(long = "match", value_name = "FILE")]
+		match_path: Option<PathBuf>,
+
+		/// Directory to write one file per trigger to instead of printing the previews
+		#[arg(long, value_name = "DIR")]
+		out_dir: Option<PathBuf>,
+	},
+	/// Silence the deliveries to a trigger for a bounded window, printing the created mute
+	Mute {
+		/// Trigger to mute
+		#[arg(long, value_name = "TRIGGER_SLUG")]
+		trigger: String,
+
+		/// Only mute the deliveries of the matches of this monitor
+		#[arg(long, value_name = "MONITOR_NAME")]
+		monitor: Option<String>,
+
+		/// Time the trigger stays muted, in seconds
+		#[arg(long, value_name = "SECONDS")]
+		duration_secs: u64,
+
+		/// Reason of the mute, e.g. the planned maintenance
+		#[arg(long, value_name = "REASON", default_value = "")]
+		reason: String,
+	},
+	/// Remove a mute before it expires
+	Unmute {
+		/// Identifier of the mute, as printed by `mute`
+		#[arg(value_name = "MUTE_ID")]
+		id: String,
+	},
+	/// Generate paused monitors and trigger stubs from the export of another monitoring setup
+	Import {
+		#[command(subcommand)]
+		source: ImportSource,
+	},
+}
+
+/// Monitoring setups configurations can be imported from
+#[derive(Subcommand)]
+enum ImportSource {
+	/// Import the sentinels and notification channels of an OpenZeppelin Defender export
+	Defender {
+		/// Path to the Sentinel export
+		#[arg(long, value_name = "FILE")]
+		input: PathBuf,
+
+		/// Network slug of a Defender network, e.g. `mainnet=ethereum_mainnet` (repeatable)
+		#[arg(
+			long = "network",
+			value_name = "DEFENDER=SLUG",
+			value_parser = parse_network_mapping
+		)]
+		networks: Vec<(String, String)>,
+
+		/// Configuration directory to write the monitors, triggers and report to
+		#[arg(long, value_name = "DIR", default_value = "config")]
+		output: PathBuf,
+	},
 }
 
 impl Cli {
@@ -194,6 +402,23 @@ impl Cli {
 async fn main() -> Result<()> {
 	let cli = Cli::parse();
 
+	// Schemas are emitted before any configuration is loaded, keeping stdout free of logs
+	if let Some(Command::Schema { kind, output }) = &cli.command {
+		return emit_config_schemas(kind.as_deref(), output.as_deref());
+	}
+
+	// Imports only translate files, without loading the configuration
+	if let Some(Command::Import {
+		source: ImportSource::Defender {
+			input,
+			networks,
+			output,
+		},
+	}) = &cli.command
+	{
+		return import_defender(input, networks, output);
+	}
+
 	// Apply CLI options to environment
 	cli.apply_to_env();
 
@@ -202,12 +427,47 @@ async fn main() -> Result<()> {
 		error!("Failed to setup logging: {}", e);
 	});
 
+	let mutes = create_trigger_mutes().await;
+
+	// Mutes are written to the state store, where running instances pick them up
+	match &cli.command {
+		Some(Command::Mute {
+			trigger,
+			monitor,
+			duration_secs,
+			reason,
+		}) => {
+			let mute = mutes
+				.mute(MuteRequest {
+					trigger: trigger.clone(),
+					monitor: monitor.clone(),
+					duration_secs: *duration_secs,
+					reason: reason.clone(),
+				})
+				.await?;
+			println!("{}", serde_json::to_string_pretty(&mute)?);
+			return Ok(());
+		}
+		Some(Command::Unmute { id }) => {
+			return match mutes.unmute(id).await? {
+				Some(mute) => {
+					println!("{}", serde_json::to_string_pretty(&mute)?);
+					Ok(())
+				}
+				None => Err(anyhow::anyhow!("No active mute has the identifier '{}'", id).into()),
+			};
+		}
+		_ => {}
+	}
+
 	// If --check flag is provided, only validate configuration and exit
 	if cli.check {
 		validate_configuration().await;
 		return Ok(());
 	}
 
+	let circuit_breakers = create_trigger_circuit_breakers().await;
+	let match_history = create_match_history().await;
 	let (
 		filter_service,
 		trigger_execution_service,
@@ -216,20 +476,85 @@ async fn main() -> Result<()> {
 		monitor_service,
 		network_service,
 		trigger_service,
-	) = initialize_services::<
-		MonitorRepository<NetworkRepository, TriggerRepository>,
-		NetworkRepository,
-		TriggerRepository,
-	>(None, None, None)
+	) = initialize_config_services(
+		circuit_breakers.clone(),
+		Some(mutes.clone()),
+		Some(match_history.clone()),
+	)
 	.await
 	.map_err(|e| anyhow::anyhow!("Failed to initialize services: {}. Please refer to the documentation quickstart ({}) on how to configure the service.", e, DOCUMENTATION_URL))?;
 
+	// Restore the backoff of triggers that were failing before a restart
+	if let Some(circuit_breakers) = &circuit_breakers {
+		let triggers = trigger_service.lock().await.get_all();
+		let suppressed = circuit_breakers
+			.restore(triggers.keys().map(String::as_str))
+			.await;
+		if suppressed > 0 {
+			info!(
+				"Restored circuit breakers, {} trigger(s) still suppressed",
+				suppressed
+			);
+		}
+	}
+
 	// Pre-load all trigger scripts into memory at startup to reduce file I/O operations.
 	// This prevents repeated file descriptor usage during script execution and improves performance
 	// by keeping scripts readily available in memory.
 	let active_monitors_trigger_scripts = trigger_execution_service
 		.load_scripts(&active_monitors)
 		.await?;
+
+	if let Some(Command::Preview {
+		monitor,
+		trigger,
+		match_path,
+		out_dir,
+	}) = &cli.command
+	{
+		let monitor = match monitor {
+			Some(name) => monitor_service
+				.lock()
+				.await
+				.get(name)
+				.ok_or_else(|| anyhow::anyhow!("Monitor '{}' not found", name))?,
+			None => sample_monitor(trigger.iter().cloned().collect()),
+		};
+		let monitor_match = match match_path {
+			Some(path) => load_match(path)?,
+			None => sample_match(&monitor),
+		};
+		let previews = preview_notifications(
+			&monitor_match,
+			&trigger.iter().cloned().collect::<Vec<_>>(),
+			&trigger_execution_service,
+		)
+		.await?;
+		return write_previews(&previews, out_dir.as_deref());
+	}
+
+	// Summarize the effective configuration, probing endpoints when requested
+	let preflight_report = preflight(
+		&monitor_service.lock().await.get_all(),
+		&network_service.lock().await.get_all(),
+		&trigger_service.lock().await.get_all(),
+		&PreflightOptions {
+			probe: cli.preflight_only
+				|| var("PREFLIGHT_PROBES")
+					.map(|v| v == "true")
+					.unwrap_or(false),
+			..Default::default()
+		},
+	)
+	.await;
+	if cli.preflight_only {
+		println!("{}", preflight_report);
+		if !preflight_report.is_healthy() {
+			return Err(anyhow::anyhow!("Preflight probes failed").into());
+		}
+		return Ok(());
+	}
+	info!("{}", preflight_report);
 	// Read CLI arguments to determine if we should test monitor execution
 	let monitor_path = cli.monitor_path.clone();
 	let network_slug = cli.network.clone();
@@ -237,6 +562,98 @@ async fn main() -> Result<()> {
 
 	let client_pool = Arc::new(ClientPool::new());
 
+	if let Some(Command::Compare {
+		old,
+		new,
+		network,
+		from_block,
+		to_block,
+		format,
+		output,
+		fail_on,
+		fixtures,
+	}) = &cli.command
+	{
+		let load_monitor = |path: &PathBuf| {
+			let monitor_service = monitor_service.clone();
+			let path = path.clone();
+			async move {
+				monitor_service
+					.lock()
+					.await
+					.load_from_path(Some(&path), None, None)
+					.await
+					.map_err(|e| {
+						anyhow::anyhow!("Failed to load monitor {}: {}", path.display(), e)
+					})
+			}
+		};
+		let old_monitor = load_monitor(old).await?;
+		let new_monitor = load_monitor(new).await?;
+		let network = network_service
+			.lock()
+			.await
+			.get(network)
+			.map(|network| network.as_ref().clone())
+			.ok_or_else(|| anyhow::anyhow!("Network '{}' not found", network))?;
+
+		let report = match fixtures {
+			Some(fixtures) => {
+				compare_monitors(MonitorComparisonConfig {
+					old_monitor,
+					new_monitor,
+					network,
+					from_block: *from_block,
+					to_block: *to_block,
+					filter_service,
+					client_pool: Arc::new(FixtureClientPool::new(fixtures)),
+				})
+				.await?
+			}
+			None => {
+				compare_monitors(MonitorComparisonConfig {
+					old_monitor,
+					new_monitor,
+					network,
+					from_block: *from_block,
+					to_block: *to_block,
+					filter_service,
+					client_pool,
+				})
+				.await?
+			}
+		};
+		info!(result = %report.summary(), "Monitor comparison completed");
+
+		let content = match format.as_str() {
+			"csv" => report.to_csv(),
+			_ => report
+				.to_json()
+				.map_err(|e| anyhow::anyhow!("Failed to serialize comparison report: {}", e))?,
+		};
+		match output {
+			Some(path) => std::fs::write(path, content).map_err(|e| {
+				anyhow::anyhow!("Failed to write report to {}: {}", path.display(), e)
+			})?,
+			None => println!("{}", content),
+		}
+
+		if let Some(fail_on) = fail_on {
+			let gate = fail_on
+				.parse::<ComparisonGate>()
+				.map_err(|e| anyhow::anyhow!(e))?;
+			if report.fails(gate) {
+				return Err(anyhow::anyhow!(
+					"Monitor comparison failed the {} check: {}",
+					fail_on,
+					report.summary()
+				)
+				.into());
+			}
+		}
+		return Ok(());
+	}
+
 	let should_test_monitor_execution = monitor_path.is_some();
 	// If monitor path is provided, test monitor execution else start the service
 	if should_test_monitor_execution {
@@ -258,6 +675,18 @@ async fn main() -> Result<()> {
 		.await;
 	}
 
+	// Report what a reload changes, staging it for approval when required
+	let config_applier = ConfigApplier::new(
+		ConfigSnapshot {
+			monitors: monitor_service.lock().await.get_all(),
+			networks: network_service.lock().await.get_all(),
+			triggers: trigger_service.lock().await.get_all(),
+		},
+		create_config_apply_options(),
+	);
+	watch_config_reloads(config_applier.clone());
+	let mut applied = config_applier.subscribe();
+
 	// Check if metrics should be enabled from either CLI flag or env var
 	let metrics_enabled =
 		cli.metrics || var("METRICS_ENABLED").map(|v| v == "true").unwrap_or(false);
@@ -285,6 +714,12 @@ async fn main() -> Result<()> {
 			monitor_service.clone(),
 			network_service.clone(),
 			trigger_service.clone(),
+			config_applier.clone(),
+			client_pool.clone(),
+			filter_service.price_oracles().clone(),
+			filter_service.decode_failures().clone(),
+			mutes.clone(),
+			AdminToken::from_env(),
 		) {
 			Ok(server) => Some(server),
 			Err(e) => {
@@ -297,64 +732,576 @@ async fn main() -> Result<()> {
 		None
 	};
 
-	let networks_with_monitors: Vec<Network> = networks
-		.values()
-		.filter(|network| has_active_monitors(&active_monitors.clone(), &network.slug))
-		.cloned()
-		.collect();
+	let mut config = PipelineConfig {
+		trigger_execution_service,
+		trigger_scripts: active_monitors_trigger_scripts,
+		active_monitors,
+		shared_monitors: monitor_service
+			.lock()
+			.await
+			.get_active_shared()
+			.into_values()
+			.collect(),
+		networks,
+	};
+	let network_monitors = config.network_monitors();
 
-	if networks_with_monitors.is_empty() {
+	if network_monitors.is_empty() {
 		info!("No networks with active monitors found. Exiting...");
 		return Ok(());
 	}
 
-	// Create a vector of networks with their associated monitors
-	let network_monitors = networks_with_monitors
-		.iter()
-		.map(|network| {
-			(
-				network.clone(),
-				active_monitors
-					.iter()
-					.filter(|m| m.networks.contains(&network.slug))
-					.cloned()
-					.collect::<Vec<_>>(),
-			)
-		})
-		.collect::<Vec<_>>();
+	let context = PipelineContext {
+		client_pool,
+		filter_service,
+		notification_budgets: create_notification_budgets(),
+		sender_activity: create_sender_activity_tracker(),
+		shared_dedup: create_shared_dedup().await,
+		outbox: create_notification_outbox().await,
+	};
+
+	// Fetch all contract specs for all active monitors before creating the handlers
+	let mut pipeline = create_pipeline(
+		&context,
+		&config,
+		get_contract_specs(&context.client_pool, &network_monitors).await,
+		create_baseline_tracker(&config.active_monitors).await,
+	);
+
+	// Backfill jobs process their range and exit once the matches are dispatched
+	if let Some(Command::Backfill {
+		network,
+		from_block,
+		to_block,
+		job_id,
+		origin_override,
+		fixtures,
+	}) = &cli.command
+	{
+		let network = config
+			.networks
+			.get(network)
+			.ok_or_else(|| anyhow::anyhow!("Network '{}' has no active monitors", network))?;
+		let origin_override = match (origin_override.as_deref(), fixtures) {
+			(Some("live"), _) => Some(MatchOrigin::Live),
+			(_, Some(_)) => Some(MatchOrigin::Replay),
+			_ => None,
+		};
+		let job = BackfillJob {
+			job_id: job_id
+				.clone()
+				.unwrap_or_else(|| format!("{}-{}-{}", network.slug, from_block, to_block)),
+			from_block: *from_block,
+			to_block: *to_block,
+			origin_override,
+		};
+		let result = match fixtures {
+			Some(fixtures) => {
+				run_backfill_on_network(
+					&FixtureClientPool::new(fixtures),
+					network,
+					&job,
+					&*pipeline.block_handler,
+					&*pipeline.trigger_handler,
+				)
+				.await
+			}
+			None => {
+				run_backfill_on_network(
+					&*context.client_pool,
+					network,
+					&job,
+					&*pipeline.block_handler,
+					&*pipeline.trigger_handler,
+				)
+				.await
+			}
+		};
+		pipeline.close().await;
+		let processed = result?;
+		info!(
+			"Backfill job {} processed {} blocks on network {}",
+			job.job_id, processed, network.slug
+		);
+		return Ok(());
+	}
+
+	let file_block_storage = Arc::new(FileBlockStorage::default());
+
+	// If --retry-failed-blocks is provided, retry the recorded failed blocks and exit
+	if cli.retry_failed_blocks {
+		let client_pool = &context.client_pool;
+		let block_handler = &*pipeline.block_handler;
+		let trigger_handler = &*pipeline.trigger_handler;
+		for network in config
+			.networks
+			.values()
+			.filter(|n| cli.network.as_ref().is_none_or(|slug| &n.slug == slug))
+		{
+			let result = match network.network_type {
+				BlockChainType::EVM => match client_pool.get_evm_client(network).await {
+					Ok(client) => {
+						retry_failed_blocks(
+							network,
+							&*client,
+							&*file_block_storage,
+							block_handler,
+							trigger_handler,
+							None,
+						)
+						.await
+					}
+					Err(e) => {
+						error!(
+							"Failed to get EVM client for network {}: {}",
+							network.slug, e
+						);
+						continue;
+					}
+				},
+				BlockChainType::Stellar => match client_pool.get_stellar_client(network).await {
+					Ok(client) => {
+						retry_failed_blocks(
+							network,
+							&*client,
+							&*file_block_storage,
+							block_handler,
+							trigger_handler,
+							None,
+						)
+						.await
+					}
+					Err(e) => {
+						error!(
+							"Failed to get Stellar client for network {}: {}",
+							network.slug, e
+						);
+						continue;
+					}
+				},
+				BlockChainType::Midnight => match client_pool.get_midnight_client(network).await {
+					Ok(client) => {
+						retry_failed_blocks(
+							network,
+							&*client,
+							&*file_block_storage,
+							block_handler,
+							trigger_handler,
+							None,
+						)
+						.await
+					}
+					Err(e) => {
+						error!(
+							"Failed to get Midnight client for network {}: {}",
+							network.slug, e
+						);
+						continue;
+					}
+				},
+			};
+			match result {
+				Ok(recovered) => info!(
+					"Recovered {} failed blocks for network {}",
+					recovered, network.slug
+				),
+				Err(e) => error!(
+					"Failed to retry failed blocks for network {}: {}",
+					network.slug, e
+				),
+			}
+		}
+		pipeline.close().await;
+		return Ok(());
+	}
+
+	// Only the replica holding the leader lease processes blocks when leader election is enabled
+	let (shutdown_tx, _) = watch::channel(false);
+	let leader_elector = if var("LEADER_ELECTION_ENABLED")
+		.map(|v| v == "true")
+		.unwrap_or(false)
+	{
+		let leader_elector = create_leader_elector();
+		info!(
+			holder = leader_elector.holder_id(),
+			"Leader election enabled, waiting for the leader lease"
+		);
+		notify_leadership_changes(&leader_elector);
+		leader_elector.clone().spawn(shutdown_tx.subscribe());
+		Some(leader_elector)
+	} else {
+		None
+	};
+
+	let mut block_watcher = start_block_watchers(
+		&pipeline,
+		&network_monitors,
+		file_block_storage.clone(),
+		leader_elector.clone(),
+		&context.client_pool,
+	)
+	.await?;
+
+	info!("Service started. Press Ctrl+C to shutdown");
+
+	let ctrl_c = tokio::signal::ctrl_c();
+	tokio::pin!(ctrl_c);
+	let metrics_future = async move {
+		match metrics_server {
+			Some(metrics_future) => metrics_future.await,
+			None => std::future::pending().await,
+		}
+	};
+	tokio::pin!(metrics_future);
+
+	loop {
+		tokio::select! {
+			result = &mut ctrl_c => {
+				if let Err(e) = result {
+					error!("Error waiting for Ctrl+C: {}", e);
+				}
+				info!("Shutdown signal received, stopping services...");
+				break;
+			}
+			result = &mut metrics_future => {
+				if let Err(e) = result {
+					error!("Metrics server error: {}", e);
+				}
+				info!("Metrics server stopped, shutting down services...");
+				break;
+			}
+			Ok(()) = applied.changed() => {
+				let snapshot = applied.borrow_and_update().clone();
+				let (
+					trigger_execution_service,
+					active_monitors,
+					networks,
+					(applied_monitor_service, applied_network_service, applied_trigger_service),
+				) = match initialize_applied_services(
+					&snapshot,
+					&context.filter_service,
+					circuit_breakers.clone(),
+					Some(mutes.clone()),
+					Some(match_history.clone()),
+				) {
+					Ok(services) => services,
+					Err(e) => {
+						error!(
+							"Failed to initialize the services of the applied configuration, \
+							 keeping the running one: {}",
+							e
+						);
+						continue;
+					}
+				};
+				let trigger_scripts = match trigger_execution_service
+					.load_scripts(&active_monitors)
+					.await
+				{
+					Ok(trigger_scripts) => trigger_scripts,
+					Err(e) => {
+						error!(
+							"Failed to load the trigger scripts of the applied configuration, \
+							 keeping the running one: {}",
+							e
+						);
+						continue;
+					}
+				};
+
+				info!("Applying configuration, restarting block processing");
+				pipeline.stop(&block_watcher, &config.networks).await;
+
+				config = PipelineConfig {
+					trigger_execution_service,
+					trigger_scripts,
+					active_monitors,
+					shared_monitors: applied_monitor_service
+						.get_active_shared()
+						.into_values()
+						.collect(),
+					networks,
+				};
+				*monitor_service.lock().await = applied_monitor_service;
+				*network_service.lock().await = applied_network_service;
+				*trigger_service.lock().await = applied_trigger_service;
+
+				let network_monitors = config.network_monitors();
+				if network_monitors.is_empty() {
+					warn!(
+						"No networks with active monitors in the applied configuration, blocks are \
+						 not processed until another configuration is applied"
+					);
+				}
+				pipeline = create_pipeline(
+					&context,
+					&config,
+					get_contract_specs(&context.client_pool, &network_monitors).await,
+					create_baseline_tracker(&config.active_monitors).await,
+				);
+				block_watcher = start_block_watchers(
+					&pipeline,
+					&network_monitors,
+					file_block_storage.clone(),
+					leader_elector.clone(),
+					&context.client_pool,
+				)
+				.await?;
+				info!(
+					"Block processing restarted with {} active monitors",
+					config.active_monitors.len()
+				);
+			}
+		}
+	}
+
+	// Common shutdown logic
+	pipeline.stop(&block_watcher, &config.networks).await;
+	let _ = shutdown_tx.send(true);
+
+	tokio::time::sleep(tokio::time::Duration::from_secs(1)).await;
+
+	info!("Shutdown complete");
+	Ok(())
+}
+
+/// Runs a backfill job on a network, fetching its blocks from the given client pool.
+///
+/// Returns the number of blocks processed, see [`run_backfill`].
+async fn run_backfill_on_network<
+	P: ClientPoolTrait,
+	H: Fn(BlockType, Network) -> BoxFuture<'static, ProcessedBlock> + Send + Sync + 'static,
+	T: Fn(&ProcessedBlock) -> tokio::task::JoinHandle<()> + Send + Sync + 'static,
+>(
+	client_pool: &P,
+	network: &Network,
+	job: &BackfillJob,
+	block_handler: &H,
+	trigger_handler: &T,
+) -> Result<usize> {
+	let processed = match network.network_type {
+		BlockChainType::EVM => {
+			let client = client_pool.get_evm_client(network).await.map_err(|e| {
+				anyhow::anyhow!(
+					"Failed to get EVM client for network {}: {}",
+					network.slug,
+					e
+				)
+			})?;
+			run_backfill(network, &*client, job, block_handler, trigger_handler).await?
+		}
+		BlockChainType::Stellar => {
+			let client = client_pool.get_stellar_client(network).await.map_err(|e| {
+				anyhow::anyhow!(
+					"Failed to get Stellar client for network {}: {}",
+					network.slug,
+					e
+				)
+			})?;
+			run_backfill(network, &*client, job, block_handler, trigger_handler).await?
+		}
+		BlockChainType::Midnight => {
+			let client = client_pool
+				.get_midnight_client(network)
+				.await
+				.map_err(|e| {
+					anyhow::anyhow!(
+						"Failed to get Midnight client for network {}: {}",
+						network.slug,
+						e
+					)
+				})?;
+			run_backfill(network, &*client, job, block_handler, trigger_handler).await?
+		}
+	};
+	Ok(processed)
+}
+
+/// Services of the configuration that blocks are processed with
+struct PipelineConfig {
+	trigger_execution_service: Arc<TriggerExecutionService<TriggerRepository>>,
+	trigger_scripts: HashMap<String, (ScriptLanguage, String)>,
+	active_monitors: Vec<Monitor>,
+	shared_monitors: Vec<Arc<Monitor>>,
+	networks: HashMap<String, Network>,
+}
+
+impl PipelineConfig {
+	/// Returns the networks with active monitors, along with their active monitors.
+	fn network_monitors(&self) -> Vec<(Network, Vec<Monitor>)> {
+		self.networks
+			.values()
+			.filter(|network| has_active_monitors(&self.active_monitors, &network.slug))
+			.map(|network| {
+				(
+					network.clone(),
+					self.active_monitors
+						.iter()
+						.filter(|m| m.networks.contains(&network.slug))
+						.cloned()
+						.collect(),
+				)
+			})
+			.collect()
+	}
+}
+
+/// State that block processing keeps across the applied configurations
+struct PipelineContext {
+	client_pool: Arc<ClientPool>,
+	filter_service: Arc<FilterService>,
+	notification_budgets: Arc<NotificationBudgets>,
+	sender_activity: Arc<SenderActivityTracker>,
+	shared_dedup: Option<SharedDedup>,
+	outbox: Option<(Arc<NotificationOutbox>, OutboxDispatcherConfig)>,
+}
+
+/// Block processing of a configuration, replaced when another configuration is applied
+struct Pipeline<H, T> {
+	shutdown_tx: watch::Sender<bool>,
+	block_handler: Arc<H>,
+	trigger_handler: Arc<T>,
+	dispatch_queue: Arc<DispatchQueue>,
+	outbox_dispatcher: Option<OutboxDispatcher>,
+}
+
+impl<H, T> Pipeline<H, T>
+where
+	H: Fn(BlockType, Network) -> BoxFuture<'static, ProcessedBlock> + Send + Sync + 'static,
+	T: Fn(&ProcessedBlock) -> tokio::task::JoinHandle<()> + Send + Sync + 'static,
+{
+	/// Delivers the matches still waiting for dispatch.
+	async fn close(&self) {
+		self.dispatch_queue.close().await;
+		if let Some(outbox_dispatcher) = &self.outbox_dispatcher {
+			outbox_dispatcher
+				.close(self.dispatch_queue.config().drain_timeout)
+				.await;
+		}
+	}
+
+	/// Stops the network watchers of the pipeline, then delivers the matches still waiting for
+	/// dispatch.
+	async fn stop(
+		&self,
+		block_watcher: &BlockWatcherService<FileBlockStorage, H, T, JobScheduler>,
+		networks: &HashMap<String, Network>,
+	) {
+		let _ = self.shutdown_tx.send(true);
+
+		// Future for all network shutdown operations
+		let shutdown_futures = networks
+			.values()
+			.map(|network| block_watcher.stop_network_watcher(&network.slug));
+
+		for result in futures::future::join_all(shutdown_futures).await {
+			if let Err(e) = result {
+				error!("Error during shutdown: {}", e);
+			}
+		}
 
-	// Fetch all contract specs for all active monitors
-	let contract_specs = get_contract_specs(&client_pool, &network_monitors).await;
+		self.close().await;
+	}
+}
 
+/// Creates the block processing of a configuration.
+///
+/// The budget digests are sent with the triggers of the configuration until the pipeline is
+/// stopped.
+fn create_pipeline(
+	context: &PipelineContext,
+	config: &PipelineConfig,
+	contract_specs: Vec<(String, ContractSpec)>,
+	baselines: Arc<BaselineTracker>,
+) -> Pipeline<
+	impl Fn(BlockType, Network) -> BoxFuture<'static, ProcessedBlock> + Send + Sync,
+	impl Fn(&ProcessedBlock) -> tokio::task::JoinHandle<()> + Send + Sync,
+> {
 	let (shutdown_tx, _) = watch::channel(false);
 	let block_handler = create_block_handler(
 		shutdown_tx.clone(),
-		filter_service,
-		active_monitors,
-		client_pool.clone(),
+		context.filter_service.clone(),
+		config.shared_monitors.clone(),
+		context.client_pool.clone(),
 		contract_specs,
 	);
-	let trigger_handler = create_trigger_handler(
+	let dispatch_queue = create_dispatch_queue(
+		create_dispatch_queue_config(),
+		config.trigger_execution_service.clone(),
+		config.trigger_scripts.clone(),
+		context.notification_budgets.clone(),
+	);
+	let outbox_dispatcher = context.outbox.as_ref().map(|(outbox, outbox_config)| {
+		OutboxDispatcher::start(
+			outbox.clone(),
+			outbox_config.clone(),
+			create_dispatch_fn(
+				config.trigger_execution_service.clone(),
+				config.trigger_scripts.clone(),
+				context.notification_budgets.clone(),
+			),
+		)
+	});
+	spawn_budget_digests(
+		context.notification_budgets.clone(),
+		config.trigger_execution_service.clone(),
+		config.trigger_scripts.clone(),
+		shutdown_tx.subscribe(),
+	);
+	let trigger_handler = create_queued_trigger_handler(
 		shutdown_tx.clone(),
-		trigger_execution_service,
-		active_monitors_trigger_scripts,
+		config.trigger_execution_service.clone(),
+		config.trigger_scripts.clone(),
+		dispatch_queue.clone(),
+		context.sender_activity.clone(),
+		baselines,
+		Arc::new(NetworkViews::new(config.networks.values())),
+		context.shared_dedup.clone(),
+		context.outbox.as_ref().map(|(outbox, _)| outbox.clone()),
+		context.filter_service.decode_failures().clone(),
 	);
 
-	let file_block_storage = Arc::new(FileBlockStorage::default());
-	let block_watcher = BlockWatcherService::<FileBlockStorage, _, _, JobScheduler>::new(
-		file_block_storage.clone(),
+	Pipeline {
+		shutdown_tx,
 		block_handler,
 		trigger_handler,
+		dispatch_queue,
+		outbox_dispatcher,
+	}
+}
+
+/// Creates the block watcher of a pipeline and starts it on the networks with active monitors.
+///
+/// Networks whose client cannot be created or whose watcher fails to start are logged and
+/// skipped.
+async fn start_block_watchers<H, T>(
+	pipeline: &Pipeline<H, T>,
+	network_monitors: &[(Network, Vec<Monitor>)],
+	block_storage: Arc<FileBlockStorage>,
+	leader_elector: Option<Arc<LeaderElector>>,
+	client_pool: &ClientPool,
+) -> Result<BlockWatcherService<FileBlockStorage, H, T, JobScheduler>>
+where
+	H: Fn(BlockType, Network) -> BoxFuture<'static, ProcessedBlock> + Send + Sync + 'static,
+	T: Fn(&ProcessedBlock) -> tokio::task::JoinHandle<()> + Send + Sync + 'static,
+{
+	let mut block_watcher = BlockWatcherService::<FileBlockStorage, _, _, JobScheduler>::new(
+		block_storage,
+		pipeline.block_handler.clone(),
+		pipeline.trigger_handler.clone(),
 		Arc::new(BlockTracker::new(1000)),
 	)
 	.await?;
+	if let Some(leader_elector) = leader_elector {
+		block_watcher = block_watcher.with_leader_elector(leader_elector);
+	}
 
-	for network in networks_with_monitors {
+	for (network, _) in network_monitors {
 		match network.network_type {
 			BlockChainType::EVM => {
-				if let Ok(client) = client_pool.get_evm_client(&network).await {
+				if let Ok(client) = client_pool.get_evm_client(network).await {
 					let _ = block_watcher
-						.start_network_watcher(&network, (*client).clone())
+						.start_network_watcher(network, (*client).clone())
 						.await
 						.inspect_err(|e| {
 							error!("Failed to start EVM network watcher: {}", e);
@@ -364,9 +1311,9 @@ async fn main() -> Result<()> {
 				}
 			}
 			BlockChainType::Stellar => {
-				if let Ok(client) = client_pool.get_stellar_client(&network).await {
+				if let Ok(client) = client_pool.get_stellar_client(network).await {
 					let _ = block_watcher
-						.start_network_watcher(&network, (*client).clone())
+						.start_network_watcher(network, (*client).clone())
 						.await
 						.inspect_err(|e| {
 							error!("Failed to start Stellar network watcher: {}", e);
@@ -376,9 +1323,9 @@ async fn main() -> Result<()> {
 				}
 			}
 			BlockChainType::Midnight => {
-				if let Ok(client) = client_pool.get_midnight_client(&network).await {
+				if let Ok(client) = client_pool.get_midnight_client(network).await {
 					let _ = block_watcher
-						.start_network_watcher(&network, (*client).clone())
+						.start_network_watcher(network, (*client).clone())
 						.await
 						.inspect_err(|e| {
 							error!("Failed to start Midnight network watcher: {}", e);
@@ -392,49 +1339,386 @@ async fn main() -> Result<()> {
 			}
 		}
 	}
+	Ok(block_watcher)
+}
 
-	info!("Service started. Press Ctrl+C to shutdown");
+/// Creates the reload apply options from the `CONFIG_*` environment variables.
+///
+/// Reloads are accepted immediately unless `CONFIG_REQUIRE_APPROVAL=true`. Staged reloads are
+/// accepted automatically after `CONFIG_AUTO_APPLY_SECS` when it is set.
+fn create_config_apply_options() -> ConfigApplyOptions {
+	ConfigApplyOptions {
+		require_approval: var("CONFIG_REQUIRE_APPROVAL")
+			.map(|v| v == "true")
+			.unwrap_or(false),
+		auto_apply_after: var("CONFIG_AUTO_APPLY_SECS")
+			.ok()
+			.and_then(|v| v.parse::<u64>().ok())
+			.map(std::time::Duration::from_secs),
+	}
+}
 
-	let ctrl_c = tokio::signal::ctrl_c();
+/// Reloads the configuration files on `SIGHUP` and hands them to the applier.
+fn watch_config_reloads(config_applier: Arc<ConfigApplier>) {
+	#[cfg(unix)]
+	tokio::spawn(async move {
+		use tokio::signal::unix::{signal, SignalKind};
 
-	if let Some(metrics_future) = metrics_server {
-		tokio::select! {
-				result = ctrl_c => {
-					if let Err(e) = result {
-			  error!("Error waiting for Ctrl+C: {}", e);
+		let mut hangup = match signal(SignalKind::hangup()) {
+			Ok(hangup) => hangup,
+			Err(e) => {
+				error!("Failed to listen for SIGHUP, reloads are disabled: {}", e);
+				return;
 			}
-			info!("Shutdown signal received, stopping services...");
-		  }
-		  result = metrics_future => {
-			if let Err(e) = result {
-			  error!("Metrics server error: {}", e);
+		};
+		while hangup.recv().await.is_some() {
+			info!("SIGHUP received, reloading configuration");
+			match load_config_snapshot().await {
+				Ok(candidate) => {
+					config_applier.propose(candidate);
+				}
+				Err(e) => error!("Failed to reload configuration: {}", e),
 			}
-			info!("Metrics server stopped, shutting down services...");
-		  }
 		}
-	} else {
-		let _ = ctrl_c.await;
-		info!("Shutdown signal received, stopping services...");
+	});
+	#[cfg(not(unix))]
+	let _ = config_applier;
+}
+
+/// Creates the notification budgets, restoring their counters from the state file.
+///
+/// Counters that cannot be restored are logged and start from zero.
+fn create_notification_budgets() -> Arc<NotificationBudgets> {
+	let budgets =
+		NotificationBudgets::with_state_file(DEFAULT_BUDGET_STATE_FILE).unwrap_or_else(|e| {
+			error!(
+				"Failed to restore notification budgets from {}, starting from zero: {}",
+				DEFAULT_BUDGET_STATE_FILE, e
+			);
+			NotificationBudgets::new()
+		});
+	Arc::new(budgets)
+}
+
+/// Creates the nonce tracker of monitored senders, restoring its history from the state file.
+///
+/// A history that cannot be restored is logged and tracking starts over.
+fn create_sender_activity_tracker() -> Arc<SenderActivityTracker> {
+	let tracker = SenderActivityTracker::with_state_file(DEFAULT_SENDER_ACTIVITY_STATE_FILE)
+		.unwrap_or_else(|e| {
+			error!(
+				"Failed to restore sender activity from {}, starting over: {}",
+				DEFAULT_SENDER_ACTIVITY_STATE_FILE, e
+			);
+			SenderActivityTracker::new()
+		});
+	Arc::new(tracker)
+}
+
+/// Creates the group dedup shared with other instances from the `STATE_STORE*` environment
+/// variables.
+///
+/// Sharing is disabled unless `STATE_STORE` selects a backend. A store that cannot be created is
+/// logged and dedup stays local to this instance.
+async fn create_shared_dedup() -> Option<SharedDedup> {
+	let config = create_state_store_config()?;
+	let backend = config.backend;
+	let ttl = var("STATE_STORE_DEDUP_TTL_SECS")
+		.ok()
+		.and_then(|v| v.parse::<u64>().ok())
+		.filter(|v| *v > 0)
+		.map_or(DEFAULT_SHARED_DEDUP_TTL, std::time::Duration::from_secs);
+
+	match create_state_store(&config).await {
+		Ok(store) => {
+			info!("Sharing group dedup through the {} state store", backend);
+			Some(SharedDedup::new(store, ttl))
+		}
+		Err(e) => {
+			error!(
+				"Failed to create the {} state store, group dedup stays local: {}",
+				backend, e
+			);
+			None
+		}
 	}
+}
 
-	// Common shutdown logic
-	let _ = shutdown_tx.send(true);
+/// Creates the state store configuration from the `STATE_STORE*` environment variables.
+///
+/// Returns None unless `STATE_STORE` selects a valid backend.
+fn create_state_store_config() -> Option<StateStoreConfig> {
+	let backend = var("STATE_STORE").ok()?;
+	let backend = match backend.parse::<StateStoreBackend>() {
+		Ok(backend) => backend,
+		Err(e) => {
+			error!("Invalid STATE_STORE: {}", e);
+			return None;
+		}
+	};
+	let mut config = StateStoreConfig {
+		backend,
+		redis_url: var("STATE_STORE_REDIS_URL").ok(),
+		..Default::default()
+	};
+	if let Ok(path) = var("STATE_STORE_PATH") {
+		config.path = path.into();
+	}
+	if let Ok(prefix) = var("STATE_STORE_KEY_PREFIX") {
+		config.key_prefix = prefix;
+	}
+	Some(config)
+}
+
+/// Creates the trigger circuit breakers from the `TRIGGER_CIRCUIT_BREAKER*` environment
+/// variables.
+///
+/// Circuit breakers are disabled unless `TRIGGER_CIRCUIT_BREAKER=true`. Their state is persisted
+/// to the state store selected by `STATE_STORE`, or to a file when no store is selected or it
+/// cannot be created. Unset or invalid values fall back to the defaults: 5 consecutive failures,
+/// a backoff of 30 seconds doubling up to 30 minutes, and state discarded after 24 hours.
+async fn create_trigger_circuit_breakers() -> Option<Arc<TriggerCircuitBreakers>> {
+	if var("TRIGGER_CIRCUIT_BREAKER").ok()? != "true" {
+		return None;
+	}
+	let secs = |name: &str| {
+		var(name)
+			.ok()
+			.and_then(|v| v.parse::<u64>().ok())
+			.filter(|v| *v > 0)
+			.map(std::time::Duration::from_secs)
+	};
+	let mut config = CircuitBreakerConfig::default();
+	if let Some(failures) = var("TRIGGER_CIRCUIT_BREAKER_FAILURES")
+		.ok()
+		.and_then(|v| v.parse::<u32>().ok())
+		.filter(|v| *v > 0)
+	{
+		config.failure_threshold = failures;
+	}
+	if let Some(backoff) = secs("TRIGGER_CIRCUIT_BREAKER_BACKOFF_SECS") {
+		config.backoff = backoff;
+	}
+	if let Some(max_backoff) = secs("TRIGGER_CIRCUIT_BREAKER_MAX_BACKOFF_SECS") {
+		config.max_backoff = max_backoff;
+	}
+	if let Some(stale_after) = secs("TRIGGER_CIRCUIT_BREAKER_STALE_AFTER_SECS") {
+		config.stale_after = stale_after;
+	}
+
+	let store =
+		create_persistent_state_store("circuit breakers", DEFAULT_CIRCUIT_BREAKER_STATE_FILE).await;
+	Some(Arc::new(TriggerCircuitBreakers::new(store, config)))
+}
 
-	// Future for all network shutdown operations
-	let shutdown_futures = networks
-		.values()
-		.map(|network| block_watcher.stop_network_watcher(&network.slug));
+/// Creates the trigger mutes.
+///
+/// Mutes are persisted to the state store selected by `STATE_STORE`, or to a file when no store
+/// is selected or it cannot be created, so that the `mute` and `unmute` commands reach running
+/// instances sharing the store.
+async fn create_trigger_mutes() -> Arc<TriggerMutes> {
+	let store =
+		create_persistent_state_store("trigger mutes", DEFAULT_TRIGGER_MUTES_STATE_FILE).await;
+	Arc::new(TriggerMutes::new(store))
+}
 
-	for result in futures::future::join_all(shutdown_futures).await {
-		if let Err(e) = result {
-			error!("Error during shutdown: {}", e);
+/// Creates the tracker of the activity baselines, restoring the baselines of the active
+/// monitors with a `baseline` condition.
+///
+/// Baselines are persisted to the state store selected by `STATE_STORE`, or to a file when no
+/// store is selected or it cannot be created.
+async fn create_baseline_tracker(active_monitors: &[Monitor]) -> Arc<BaselineTracker> {
+	let store = create_persistent_state_store("baselines", DEFAULT_BASELINE_STATE_FILE).await;
+	let tracker = BaselineTracker::with_store(store);
+	let restored = tracker.restore(active_monitors).await;
+	if restored > 0 {
+		info!("Restored {} activity baseline(s)", restored);
+	}
+	Arc::new(tracker)
+}
+
+/// Creates the history of the dispatched matches, kept by the trigger execution service when an
+/// active monitor uses it (see [`initialize_services`]).
+///
+/// Occurrences are persisted to the state store selected by `STATE_STORE`, or to a file when no
+/// store is selected or it cannot be created. `MATCH_HISTORY_TIMEOUT_MS` bounds each lookup,
+/// defaulting to 100 milliseconds.
+async fn create_match_history() -> Arc<MatchHistory> {
+	let timeout = var("MATCH_HISTORY_TIMEOUT_MS")
+		.ok()
+		.and_then(|v| v.parse::<u64>().ok())
+		.filter(|v| *v > 0)
+		.map_or(
+			DEFAULT_MATCH_HISTORY_TIMEOUT,
+			std::time::Duration::from_millis,
+		);
+	let store =
+		create_persistent_state_store("match history", DEFAULT_MATCH_HISTORY_STATE_FILE).await;
+	Arc::new(MatchHistory::new(
+		store,
+		timeout,
+		DEFAULT_MATCH_HISTORY_CACHE_CAPACITY,
+	))
+}
+
+/// Creates the state store selected by `STATE_STORE`, falling back to a file store at
+/// `fallback_path` when no store is selected or it cannot be created.
+async fn create_persistent_state_store(purpose: &str, fallback_path: &str) -> Arc<dyn StateStore> {
+	match create_state_store_config() {
+		Some(store_config) => match create_state_store(&store_config).await {
+			Ok(store) => store,
+			Err(e) => {
+				error!(
+					"Failed to create the {} state store, persisting {} to {}: {}",
+					store_config.backend, purpose, fallback_path, e
+				);
+				Arc::new(FileStateStore::new(fallback_path))
+			}
+		},
+		None => Arc::new(FileStateStore::new(fallback_path)),
+	}
+}
+
+/// Sends the digests of reset notification budgets every minute until shutdown.
+fn spawn_budget_digests(
+	budgets: Arc<NotificationBudgets>,
+	trigger_execution_service: Arc<TriggerExecutionService<TriggerRepository>>,
+	trigger_scripts: HashMap<String, (ScriptLanguage, String)>,
+	mut shutdown_rx: watch::Receiver<bool>,
+) {
+	tokio::spawn(async move {
+		let mut interval = tokio::time::interval(std::time::Duration::from_secs(60));
+		loop {
+			tokio::select! {
+				_ = interval.tick() => {
+					send_due_budget_digests(&budgets, &*trigger_execution_service, &trigger_scripts).await;
+				}
+				_ = shutdown_rx.changed() => break,
+			}
+		}
+	});
+}
+
+/// Creates the dispatch queue configuration from the `DISPATCH_QUEUE_*` environment variables.
+///
+/// Unset or invalid values fall back to the defaults: a capacity of 1000 matches, the `block`
+/// overflow policy and a drain timeout of 30 seconds.
+fn create_dispatch_queue_config() -> DispatchQueueConfig {
+	let mut config = DispatchQueueConfig::default();
+	if let Some(capacity) = var("DISPATCH_QUEUE_CAPACITY")
+		.ok()
+		.and_then(|v| v.parse::<usize>().ok())
+		.filter(|v| *v > 0)
+	{
+		config.capacity = capacity;
+	}
+	if let Ok(overflow) = var("DISPATCH_QUEUE_OVERFLOW") {
+		match overflow.parse::<OverflowPolicy>() {
+			Ok(policy) => config.overflow = policy,
+			Err(e) => error!("Invalid DISPATCH_QUEUE_OVERFLOW: {}", e),
 		}
 	}
+	if let Some(secs) = var("DISPATCH_QUEUE_DRAIN_TIMEOUT_SECS")
+		.ok()
+		.and_then(|v| v.parse::<u64>().ok())
+	{
+		config.drain_timeout = std::time::Duration::from_secs(secs);
+	}
+	config
+}
 
-	tokio::time::sleep(tokio::time::Duration::from_secs(1)).await;
+/// Creates the notification outbox from the `NOTIFICATION_OUTBOX*` environment variables.
+///
+/// The outbox is disabled unless `NOTIFICATION_OUTBOX=true`. The dispatcher id defaults to the
+/// host name, so that a restarted instance recovers the entries it had claimed, and the lease to
+/// 60 seconds.
+async fn create_notification_outbox() -> Option<(Arc<NotificationOutbox>, OutboxDispatcherConfig)> {
+	if var("NOTIFICATION_OUTBOX").ok()? != "true" {
+		return None;
+	}
+	let retention = var("NOTIFICATION_OUTBOX_RETENTION_SECS")
+		.ok()
+		.and_then(|v| v.parse::<u64>().ok())
+		.filter(|v| *v > 0)
+		.map_or(DEFAULT_OUTBOX_RETENTION, std::time::Duration::from_secs);
+	let outbox = Arc::new(
+		NotificationOutbox::new(
+			var("NOTIFICATION_OUTBOX_PATH").unwrap_or_else(|_| DEFAULT_OUTBOX_FILE.to_string()),
+			retention,
+		)
+		.with_monitor_version(build_info::reported_monitor_version().map(str::to_string)),
+	);
 
-	info!("Shutdown complete");
-	Ok(())
+	let mut config = OutboxDispatcherConfig {
+		owner: var("NOTIFICATION_OUTBOX_OWNER")
+			.or_else(|_| var("HOSTNAME"))
+			.unwrap_or_else(|_| "monitor".to_string()),
+		..Default::default()
+	};
+	if let Some(secs) = var("NOTIFICATION_OUTBOX_LEASE_SECS")
+		.ok()
+		.and_then(|v| v.parse::<u64>().ok())
+		.filter(|v| *v > 0)
+	{
+		config.lease = std::time::Duration::from_secs(secs);
+	}
+
+	match outbox.recover(&config.owner).await {
+		Ok(pending) => info!(
+			"Notification outbox enabled, {} matches waiting for dispatch",
+			pending
+		),
+		Err(e) => error!("Failed to recover the notification outbox: {}", e),
+	}
+	Some((outbox, config))
+}
+
+/// Creates the leader elector from the `LEADER_ELECTION_*` environment variables.
+///
+/// The replica id defaults to the host name with a startup timestamp, and the lease TTL to 30
+/// seconds. The lease is stored next to the block storage files.
+fn create_leader_elector() -> Arc<LeaderElector> {
+	let holder_id = var("LEADER_ELECTION_HOLDER_ID").unwrap_or_else(|_| {
+		format!(
+			"{}-{}",
+			var("HOSTNAME").unwrap_or_else(|_| "monitor".to_string()),
+			chrono::Utc::now().timestamp_micros()
+		)
+	});
+	let ttl_secs = var("LEADER_ELECTION_LEASE_TTL_SECS")
+		.ok()
+		.and_then(|v| v.parse::<u64>().ok())
+		.filter(|v| *v > 0)
+		.unwrap_or(30);
+
+	Arc::new(LeaderElector::new(
+		holder_id,
+		Arc::new(FileLeaseStore::default()),
+		std::time::Duration::from_secs(ttl_secs),
+	))
+}
+
+/// Posts leadership changes to `LEADER_ELECTION_NOTIFY_URL` when it is set.
+fn notify_leadership_changes(leader_elector: &LeaderElector) {
+	let Ok(url) = var("LEADER_ELECTION_NOTIFY_URL") else {
+		return;
+	};
+	let holder_id = leader_elector.holder_id().to_string();
+	let mut status = leader_elector.subscribe();
+
+	tokio::spawn(async move {
+		let client = reqwest::Client::new();
+		while status.changed().await.is_ok() {
+			let is_leader = *status.borrow_and_update();
+			let payload = serde_json::json!({
+				"holder": holder_id,
+				"is_leader": is_leader,
+			});
+			if let Err(e) = client.post(&url).json(&payload).send().await {
+				error!("Failed to send leadership change notification: {}", e);
+			}
+		}
+	});
 }
 
 /// Tests the execution of a blockchain monitor configuration file.
@@ -696,20 +1980,135 @@ async fn test_monitor_execution(config: MonitorExecutionTestConfig) -> Result<()
 }
 
 /// Validates configuration files and their structure
+/// Prints the configuration schemas, or writes them into the output directory
+///
+/// Printing all schemas emits a single JSON object keyed by configuration file kind.
+fn emit_config_schemas(kind: Option<&str>, output: Option<&std::path::Path>) -> Result<()> {
+	if let Some(dir) = output {
+		let paths = write_config_schemas(dir)
+			.map_err(|e| anyhow::anyhow!("Failed to write configuration schemas: {}", e))?;
+		for path in paths {
+			println!("{}", path.display());
+		}
+		return Ok(());
+	}
+
+	let schemas = config_schemas()
+		.into_iter()
+		.filter(|(schema_kind, _)| kind.is_none_or(|kind| kind == schema_kind.as_str()));
+	let value = match kind {
+		Some(_) => schemas.map(|(_, schema)| schema).next().unwrap_or_default(),
+		None => serde_json::Value::Object(
+			schemas
+				.map(|(schema_kind, schema)| (schema_kind.to_string(), schema))
+				.collect(),
+		),
+	};
+	let content = serde_json::to_string_pretty(&value)
+		.map_err(|e| anyhow::anyhow!("Failed to serialize configuration schemas: {}", e))?;
+	println!("{}", content);
+	Ok(())
+}
+
+/// Translates a Defender export into the configuration directory and prints the report
+fn import_defender(
+	input: &std::path::Path,
+	networks: &[(String, String)],
+	output: &std::path::Path,
+) -> Result<()> {
+	let content = std::fs::read_to_string(input)
+		.map_err(|e| anyhow::anyhow!("Failed to read {}: {}", input.display(), e))?;
+	let export = parse_defender_export(&content)
+		.map_err(|e| anyhow::anyhow!("Failed to import {}: {}", input.display(), e))?;
+	let import = import_defender_export(&export, &networks.iter().cloned().collect());
+	let paths = import
+		.write(output)
+		.map_err(|e| anyhow::anyhow!("Failed to write the imported configuration: {}", e))?;
+	for path in paths {
+		println!("{}", path.display());
+	}
+	println!(
+		"Imported {} paused monitor(s) and {} trigger stub(s) from {} sentinel(s)",
+		import.monitors.len(),
+		import.triggers.len(),
+		export.sentinels.len()
+	);
+	for entry in &import.report {
+		println!("  {}", entry);
+	}
+	Ok(())
+}
+
+/// Prints the notification previews separated by their headers, or writes each one into its
+/// own file of the output directory
+fn write_previews(
+	previews: &[NotificationPreview],
+	out_dir: Option<&std::path::Path>,
+) -> Result<()> {
+	let Some(dir) = out_dir else {
+		for preview in previews {
+			println!("{}", preview);
+		}
+		return Ok(());
+	};
+	std::fs::create_dir_all(dir)
+		.map_err(|e| anyhow::anyhow!("Failed to create {}: {}", dir.display(), e))?;
+	for preview in previews {
+		let path = dir.join(preview.file_name());
+		std::fs::write(&path, preview.to_string())
+			.map_err(|e| anyhow::anyhow!("Failed to write {}: {}", path.display(), e))?;
+		println!("{}", path.display());
+	}
+	Ok(())
+}
+
+/// Initializes the services of the config directories, with the monitor settings of the
+/// environment
+async fn initialize_config_services(
+	circuit_breakers: Option<Arc<TriggerCircuitBreakers>>,
+	mutes: Option<Arc<TriggerMutes>>,
+	match_history: Option<Arc<MatchHistory>>,
+) -> ServiceResult<
+	MonitorRepository<NetworkRepository, TriggerRepository>,
+	NetworkRepository,
+	TriggerRepository,
+> {
+	let (monitor_service, network_service, trigger_service) = create_config_services().await?;
+	initialize_services(
+		Some(monitor_service),
+		Some(network_service),
+		Some(trigger_service),
+		circuit_breakers,
+		mutes,
+		match_history,
+	)
+	.await
+}
+
 async fn validate_configuration() {
 	info!("Validating configuration files...");
 
 	// Initialize services in validation mode to check configurations
-	match initialize_services::<
-		MonitorRepository<NetworkRepository, TriggerRepository>,
-		NetworkRepository,
-		TriggerRepository,
-	>(None, None, None)
-	.await
-	{
-		Ok((_, _, active_monitors, networks, _, _, _)) => {
+	match initialize_config_services(None, None, None).await {
+		Ok((_, _, active_monitors, networks, monitor_service, _, trigger_service)) => {
 			info!("✓ Core services initialized successfully");
 
+			// Lint findings are logged individually during initialization
+			let lint_warnings = lint_configuration(
+				&monitor_service.lock().await.get_all(),
+				&networks,
+				&trigger_service.lock().await.get_all(),
+				&LintConfig::load(None).unwrap_or_default(),
+			);
+			if lint_warnings.is_empty() {
+				info!("✓ No configuration lint warnings");
+			} else {
+				info!(
+					"⚠ Found {} configuration lint warning(s), add their codes to `deny` in config/lint.json to treat them as errors",
+					lint_warnings.len()
+				);
+			}
+
 			// Check if we have any monitors configured
 			if active_monitors.is_empty() {
 				error!("No active monitors found. Please refer to the documentation quickstart ({}) for configuration setup.", DOCUMENTATION_URL);
@@ -752,7 +2151,7 @@ mod tests {
 				MonitorRepository<NetworkRepository, TriggerRepository>,
 				NetworkRepository,
 				TriggerRepository,
-			>(None, None, None)
+			>(None, None, None, None, None, None)
 			.await
 			.unwrap();
 
@@ -791,7 +2190,7 @@ mod tests {
 				MonitorRepository<NetworkRepository, TriggerRepository>,
 				NetworkRepository,
 				TriggerRepository,
-			>(None, None, None)
+			>(None, None, None, None, None, None)
 			.await
 			.unwrap();
 
@@ -46,8 +46,22 @@ pub struct BaseBlock<TX> {
 	/// Base fee per unit of gas (if past London)
 	#[serde(rename = "baseFeePerGas", skip_serializing_if = "Option::is_none")]
 	pub base_fee_per_gas: Option<U256>,
+	/// Blob gas used by the block (if past Cancun)
+	#[serde(
+		rename = "blobGasUsed",
+		default,
+		skip_serializing_if = "Option::is_none"
+	)]
+	pub blob_gas_used: Option<U256>,
+	/// Excess blob gas carried into the block (if past Cancun)
+	#[serde(
+		rename = "excessBlobGas",
+		default,
+		skip_serializing_if = "Option::is_none"
+	)]
+	pub excess_blob_gas: Option<U256>,
 	/// Extra data
-	#[serde(rename = "extraData")]
+	#[serde(rename = "extraData", default)]
 	pub extra_data: Bytes,
 	/// Logs bloom
 	#[serde(rename = "logsBloom")]
@@ -67,6 +81,7 @@ pub struct BaseBlock<TX> {
 	#[serde(default)]
 	pub uncles: Vec<B256>,
 	/// Transactions
+	#[serde(default)]
 	pub transactions: Vec<TX>,
 	/// Size in bytes
 	pub size: Option<U256>,
@@ -117,6 +132,8 @@ impl From<AlloyBlock<AlloyTransaction>> for Block {
 				.inner
 				.base_fee_per_gas
 				.map(|fee| U256::from(fee)),
+			blob_gas_used: block.header.inner.blob_gas_used.map(U256::from),
+			excess_blob_gas: block.header.inner.excess_blob_gas.map(U256::from),
 			extra_data: block.header.inner.extra_data,
 			logs_bloom: Some(block.header.inner.logs_bloom.into()),
 			timestamp: U256::from(block.header.inner.timestamp),
@@ -174,6 +191,8 @@ mod tests {
 			mix_hash: None,
 			nonce: None,
 			base_fee_per_gas: None,
+			blob_gas_used: None,
+			excess_blob_gas: None,
 		}
 	}
 
@@ -10,9 +10,10 @@ mod transaction;
 
 pub use block::Block as EVMBlock;
 pub use monitor::{
-	ContractSpec as EVMContractSpec, MatchArguments as EVMMatchArguments,
-	MatchParamEntry as EVMMatchParamEntry, MatchParamsMap as EVMMatchParamsMap,
-	MonitorConfig as EVMMonitorConfig, MonitorMatch as EVMMonitorMatch,
+	BaselineActivity as EVMBaselineActivity, ContractSpec as EVMContractSpec,
+	MatchArguments as EVMMatchArguments, MatchParamEntry as EVMMatchParamEntry,
+	MatchParamsMap as EVMMatchParamsMap, MonitorConfig as EVMMonitorConfig,
+	MonitorMatch as EVMMonitorMatch, SenderActivity as EVMSenderActivity,
 };
 pub use receipt::{
 	BaseLog as EVMReceiptLog, BaseReceipt as EVMBaseReceipt,
@@ -1,6 +1,8 @@
 use crate::models::{
-	EVMReceiptLog, EVMTransaction, EVMTransactionReceipt, MatchConditions, Monitor,
+	AlertTiming, EVMReceiptLog, EVMTransaction, EVMTransactionReceipt, MatchConditions,
+	MatchOrigin, Monitor, NetworkMetrics, SenderActivityKind,
 };
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 /// Result of a successful monitor match on an EVM chain
@@ -26,6 +28,73 @@ pub struct MonitorMatch {
 
 	/// Decoded arguments from the matched conditions
 	pub matched_on_args: Option<MatchArguments>,
+
+	/// Nonce activity of the sender account, for matches of a `sender_activity` condition
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub sender_activity: Option<SenderActivity>,
+
+	/// Aggregates of the monitor's activity, for matches of a `baseline` condition
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub baseline: Option<BaselineActivity>,
+
+	/// Metrics of the blocks the condition held for, for matches of a `network_metrics`
+	/// condition
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub network_metrics: Option<NetworkMetrics>,
+
+	/// Times at which the match went through the monitoring pipeline
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub timing: Option<AlertTiming>,
+
+	/// Pipeline the match was produced by
+	#[serde(default, skip_serializing_if = "MatchOrigin::is_live")]
+	pub origin: MatchOrigin,
+}
+
+/// Nonce activity of a monitored sender account
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+pub struct SenderActivity {
+	/// Kind of activity, None until the transaction is checked against the nonce history of
+	/// its sender
+	pub kind: Option<SenderActivityKind>,
+
+	/// Nonce of the transaction, or the missing nonce for stuck activity
+	pub nonce: u64,
+
+	/// Nonce the sender was expected to use next
+	pub expected_nonce: Option<u64>,
+
+	/// Hash of the transaction previously seen with the same nonce
+	pub replaced_hash: Option<String>,
+
+	/// Seconds the missing nonce has been left unmined
+	pub stuck_duration: Option<u64>,
+}
+
+impl SenderActivity {
+	/// Creates the activity of a transaction not yet checked against the nonce history
+	pub fn observed(nonce: u64) -> Self {
+		Self {
+			kind: None,
+			nonce,
+			expected_nonce: None,
+			replaced_hash: None,
+			stuck_duration: None,
+		}
+	}
+}
+
+/// Activity of a monitor compared with its baseline when a match fired
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+pub struct BaselineActivity {
+	/// Aggregated activity of the short window
+	pub short_window_value: f64,
+
+	/// Average activity of the long window, scaled to the length of the short window
+	pub long_window_average: f64,
+
+	/// Ratio of the short window value to the long window average, floored
+	pub ratio: f64,
 }
 
 /// Collection of decoded parameters from matched conditions
@@ -39,6 +108,10 @@ pub struct MatchParamsMap {
 
 	/// Raw function/event signature as bytes
 	pub hex_signature: Option<String>,
+
+	/// Index of the log in the block, for events
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub log_index: Option<u64>,
 }
 
 /// Single decoded parameter from a function or event
@@ -72,8 +145,8 @@ pub struct MatchArguments {
 /// This structure represents the parsed specification of an EVM smart contract,
 /// following the Ethereum Contract ABI format. It contains information about all
 /// callable functions in the contract.
-#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Default)]
-pub struct ContractSpec(alloy::json_abi::JsonAbi);
+#[derive(Debug, Clone, Deserialize, JsonSchema, Serialize, PartialEq, Default)]
+pub struct ContractSpec(#[schemars(with = "Vec<serde_json::Value>")] alloy::json_abi::JsonAbi);
 
 /// Convert a ContractSpec to an EVMContractSpec
 impl From<crate::models::ContractSpec> for ContractSpec {
@@ -129,7 +202,7 @@ impl std::ops::Deref for ContractSpec {
 ///
 /// This configuration is used to for additional fields in the monitor configuration
 /// that are specific to EVM.
-#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Default)]
+#[derive(Debug, Clone, Deserialize, JsonSchema, Serialize, PartialEq, Default)]
 pub struct MonitorConfig {}
 
 #[cfg(test)]
@@ -187,6 +260,7 @@ mod tests {
 				},
 			]),
 			hex_signature: Some("0xa9059cbb".to_string()),
+			log_index: None,
 		};
 
 		let monitor_match = MonitorMatch {
@@ -202,11 +276,19 @@ mod tests {
 				}],
 				events: vec![],
 				transactions: vec![],
+				sender_activity: None,
+				baseline: None,
+				network_metrics: None,
 			},
 			matched_on_args: Some(MatchArguments {
 				functions: Some(vec![match_params]),
 				events: None,
 			}),
+			sender_activity: None,
+			baseline: None,
+			network_metrics: None,
+			timing: None,
+			origin: Default::default(),
 		};
 
 		assert_eq!(monitor_match.monitor.name, "TestMonitor");
@@ -263,6 +345,7 @@ mod tests {
 					},
 				]),
 				hex_signature: Some("0xa9059cbb".to_string()),
+				log_index: None,
 			}]),
 			events: Some(vec![MatchParamsMap {
 				signature: "Transfer(address,address,uint256)".to_string(),
@@ -290,6 +373,7 @@ mod tests {
 					"0xddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef"
 						.to_string(),
 				),
+				log_index: None,
 			}]),
 		};
 
@@ -35,7 +35,7 @@ pub struct BaseReceipt {
 	#[serde(default)]
 	pub to: Option<Address>,
 	/// Cumulative gas used within the block after this was executed.
-	#[serde(rename = "cumulativeGasUsed")]
+	#[serde(rename = "cumulativeGasUsed", default)]
 	pub cumulative_gas_used: U256,
 	/// Gas used by this transaction alone.
 	///
@@ -46,13 +46,15 @@ pub struct BaseReceipt {
 	#[serde(rename = "contractAddress")]
 	pub contract_address: Option<Address>,
 	/// Logs generated within this transaction.
+	#[serde(default)]
 	pub logs: Vec<BaseLog>,
 	/// Status: either 1 (success) or 0 (failure).
 	pub status: Option<U64>,
 	/// State root.
 	pub root: Option<B256>,
 	/// Logs bloom
-	#[serde(rename = "logsBloom")]
+	/// Note: empty bloom if the client did not return this value (some L2 nodes omit it)
+	#[serde(rename = "logsBloom", default)]
 	pub logs_bloom: B2048,
 	/// Transaction type, Some(1) for AccessList transaction, None for Legacy
 	#[serde(rename = "type", default, skip_serializing_if = "Option::is_none")]
@@ -69,8 +71,10 @@ pub struct BaseLog {
 	/// H160
 	pub address: Address,
 	/// Topics
+	#[serde(default)]
 	pub topics: Vec<B256>,
 	/// Data
+	#[serde(default)]
 	pub data: Bytes,
 	/// Block Hash
 	#[serde(rename = "blockHash")]
@@ -66,6 +66,7 @@ pub struct BaseTransaction {
 	/// Gas amount
 	pub gas: U256,
 	/// Input data
+	#[serde(default)]
 	pub input: Bytes,
 	/// ECDSA recovery id
 	#[serde(default, skip_serializing_if = "Option::is_none")]
@@ -4,9 +4,12 @@
 //! on the Midnight blockchain. It includes types for representing monitor matches,
 //! parameters, and configuration.
 
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-use crate::models::{MatchConditions, MidnightTransaction, Monitor, SecretValue};
+use crate::models::{
+	AlertTiming, MatchConditions, MatchOrigin, MidnightTransaction, Monitor, SecretValue,
+};
 
 /// Result of a successful monitor match on an Midnight chain
 ///
@@ -29,6 +32,14 @@ pub struct MonitorMatch {
 
 	/// Decoded arguments from the matched conditions
 	pub matched_on_args: Option<MatchArguments>,
+
+	/// Times at which the match went through the monitoring pipeline
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub timing: Option<AlertTiming>,
+
+	/// Pipeline the match was produced by
+	#[serde(default, skip_serializing_if = "MatchOrigin::is_live")]
+	pub origin: MatchOrigin,
 }
 
 /// Collection of decoded parameters from matched conditions
@@ -84,7 +95,7 @@ pub struct MatchArguments {
 /// This configuration is used for additional fields in the monitor configuration
 /// that are specific to Midnight. It includes viewing keys for decrypting
 /// transaction data.
-#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Default)]
+#[derive(Debug, Clone, Deserialize, JsonSchema, Serialize, PartialEq, Default)]
 pub struct MonitorConfig {
 	/// List of hex encoded viewing keys for decrypting transaction data
 	#[serde(default)]
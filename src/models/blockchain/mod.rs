@@ -4,14 +4,18 @@
 //! blockchain platforms (EVM, Stellar, Midnight, etc). Each submodule implements the
 //! platform-specific logic for blocks, transactions, and event monitoring.
 
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, sync::Arc};
+
+use crate::models::{MatchConditions, Monitor};
 
 pub mod evm;
 pub mod midnight;
 pub mod stellar;
 
 /// Supported blockchain platform types
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq, Eq, Hash)]
 #[serde(deny_unknown_fields)]
 pub enum BlockChainType {
 	/// Ethereum Virtual Machine based chains
@@ -28,8 +32,9 @@ pub enum BlockType {
 	/// EVM block and transaction data
 	///
 	/// # Note
-	/// Box is used here to equalize the enum variants
-	EVM(Box<evm::EVMBlock>),
+	/// Arc is used here to equalize the enum variants and to share the block with the tasks
+	/// evaluating monitors without copying its transactions
+	EVM(Arc<evm::EVMBlock>),
 	/// Stellar ledger and transaction data
 	///
 	/// # Note
@@ -50,6 +55,19 @@ impl BlockType {
 			BlockType::Midnight(b) => b.number(),
 		}
 	}
+
+	/// Returns the timestamp of the block in milliseconds since the Unix epoch
+	///
+	/// Returns `None` when the chain data does not carry it, as for Midnight blocks.
+	pub fn timestamp_ms(&self) -> Option<i64> {
+		match self {
+			BlockType::EVM(b) => i64::try_from(b.timestamp)
+				.ok()
+				.and_then(|seconds| seconds.checked_mul(1000)),
+			BlockType::Stellar(b) => b.close_time().and_then(|seconds| seconds.checked_mul(1000)),
+			BlockType::Midnight(_) => None,
+		}
+	}
 }
 
 /// Transaction data from different blockchain platforms
@@ -65,7 +83,7 @@ pub enum TransactionType {
 }
 
 /// Contract spec from different blockchain platforms
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq)]
 #[serde(untagged)]
 pub enum ContractSpec {
 	/// EVM contract spec
@@ -96,8 +114,261 @@ pub enum MonitorMatch {
 	Midnight(Box<midnight::MidnightMonitorMatch>),
 }
 
+/// Accessors for the parts of a match shared by every chain
+///
+/// Code that does not depend on chain-specific data should use these instead of matching on
+/// the variants, so that adding a chain only requires extending them.
+impl MonitorMatch {
+	/// Returns the monitor that produced the match
+	pub fn monitor(&self) -> &Monitor {
+		match self {
+			MonitorMatch::EVM(m) => &m.monitor,
+			MonitorMatch::Stellar(m) => &m.monitor,
+			MonitorMatch::Midnight(m) => &m.monitor,
+		}
+	}
+
+	/// Returns a mutable reference to the monitor that produced the match
+	pub fn monitor_mut(&mut self) -> &mut Monitor {
+		match self {
+			MonitorMatch::EVM(m) => &mut m.monitor,
+			MonitorMatch::Stellar(m) => &mut m.monitor,
+			MonitorMatch::Midnight(m) => &mut m.monitor,
+		}
+	}
+
+	/// Returns the slug of the network the match was found on
+	pub fn network_slug(&self) -> &str {
+		match self {
+			MonitorMatch::EVM(m) => &m.network_slug,
+			MonitorMatch::Stellar(m) => &m.network_slug,
+			MonitorMatch::Midnight(m) => &m.network_slug,
+		}
+	}
+
+	/// Returns the hash of the matched transaction
+	pub fn transaction_hash(&self) -> String {
+		match self {
+			MonitorMatch::EVM(m) => m.transaction.hash().to_string(),
+			MonitorMatch::Stellar(m) => m.transaction.hash().to_string(),
+			MonitorMatch::Midnight(m) => m.transaction.hash().to_string(),
+		}
+	}
+
+	/// Returns the locations of the matched events in the transaction
+	///
+	/// These are the log indexes on EVM, and `<operation index>.<event index>` on Stellar.
+	/// Events without a known location are left out.
+	pub fn event_locations(&self) -> Vec<String> {
+		match self {
+			MonitorMatch::EVM(m) => m
+				.matched_on_args
+				.iter()
+				.flat_map(|args| args.events.iter().flatten())
+				.filter_map(|event| event.log_index)
+				.map(|log_index| log_index.to_string())
+				.collect(),
+			MonitorMatch::Stellar(m) => m
+				.matched_on_args
+				.iter()
+				.flat_map(|args| args.events.iter().flatten())
+				.filter_map(|event| {
+					Some(format!("{}.{}", event.operation_index?, event.event_index?))
+				})
+				.collect(),
+			MonitorMatch::Midnight(_) => vec![],
+		}
+	}
+
+	/// Returns a key identifying the matched transaction and the events matched in it
+	///
+	/// The key is the transaction hash, followed by the locations of the matched events when
+	/// known, so that matches of different events of a transaction have different keys.
+	pub fn match_key(&self) -> String {
+		let locations = self.event_locations();
+		if locations.is_empty() {
+			self.transaction_hash()
+		} else {
+			format!("{}:{}", self.transaction_hash(), locations.join(","))
+		}
+	}
+
+	/// Returns the number of the block (or ledger) containing the matched transaction
+	///
+	/// Returns `None` when the chain data does not carry it.
+	pub fn block_reference(&self) -> Option<u64> {
+		match self {
+			MonitorMatch::EVM(m) => m.transaction.block_number.map(|n| n.to::<u64>()),
+			MonitorMatch::Stellar(m) => m.ledger.number(),
+			MonitorMatch::Midnight(_) => None,
+		}
+	}
+
+	/// Returns the metrics of the blocks a `network_metrics` condition held for
+	pub fn network_metrics(&self) -> Option<&NetworkMetrics> {
+		match self {
+			MonitorMatch::EVM(m) => m.network_metrics.as_ref(),
+			MonitorMatch::Stellar(m) => m.network_metrics.as_ref(),
+			MonitorMatch::Midnight(_) => None,
+		}
+	}
+
+	/// Returns the conditions the match was found on
+	pub fn matched_on(&self) -> &MatchConditions {
+		match self {
+			MonitorMatch::EVM(m) => &m.matched_on,
+			MonitorMatch::Stellar(m) => &m.matched_on,
+			MonitorMatch::Midnight(m) => &m.matched_on,
+		}
+	}
+
+	/// Returns the decoded arguments of the matched functions and events as JSON
+	pub fn matched_args_json(&self) -> Option<serde_json::Value> {
+		let value = match self {
+			MonitorMatch::EVM(m) => serde_json::to_value(m.matched_on_args.as_ref()?),
+			MonitorMatch::Stellar(m) => serde_json::to_value(m.matched_on_args.as_ref()?),
+			MonitorMatch::Midnight(m) => serde_json::to_value(m.matched_on_args.as_ref()?),
+		};
+		value.ok()
+	}
+
+	/// Returns the times at which the match went through the monitoring pipeline
+	pub fn timing(&self) -> Option<&AlertTiming> {
+		match self {
+			MonitorMatch::EVM(m) => m.timing.as_ref(),
+			MonitorMatch::Stellar(m) => m.timing.as_ref(),
+			MonitorMatch::Midnight(m) => m.timing.as_ref(),
+		}
+	}
+
+	/// Sets the times at which the match went through the monitoring pipeline
+	pub fn set_timing(&mut self, timing: AlertTiming) {
+		match self {
+			MonitorMatch::EVM(m) => m.timing = Some(timing),
+			MonitorMatch::Stellar(m) => m.timing = Some(timing),
+			MonitorMatch::Midnight(m) => m.timing = Some(timing),
+		}
+	}
+
+	/// Returns the pipeline the match was produced by
+	pub fn origin(&self) -> &MatchOrigin {
+		match self {
+			MonitorMatch::EVM(m) => &m.origin,
+			MonitorMatch::Stellar(m) => &m.origin,
+			MonitorMatch::Midnight(m) => &m.origin,
+		}
+	}
+
+	/// Sets the pipeline the match was produced by
+	pub fn set_origin(&mut self, origin: MatchOrigin) {
+		match self {
+			MonitorMatch::EVM(m) => m.origin = origin,
+			MonitorMatch::Stellar(m) => m.origin = origin,
+			MonitorMatch::Midnight(m) => m.origin = origin,
+		}
+	}
+}
+
+/// Metrics of the blocks a `network_metrics` condition held for
+///
+/// Matches of the condition have no transaction, their transaction hash is the hash of the
+/// block they fired on.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NetworkMetrics {
+	/// Metrics of the blocks the condition held for in a row, oldest first, ending with the
+	/// block the match fired on
+	pub series: Vec<NetworkMetricsSample>,
+}
+
+impl NetworkMetrics {
+	/// Returns the metrics of the block the match fired on
+	pub fn latest(&self) -> Option<&NetworkMetricsSample> {
+		self.series.last()
+	}
+}
+
+/// Metrics of a block
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NetworkMetricsSample {
+	/// Number of the block
+	pub block_number: u64,
+
+	/// Timestamp of the block in seconds since the Unix epoch, if the chain provides one
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub timestamp: Option<i64>,
+
+	/// Values of the metrics, keyed by name
+	pub values: BTreeMap<String, String>,
+}
+
+/// Times at which the data of a match went through the monitoring pipeline
+///
+/// Times are in milliseconds since the Unix epoch. The block timestamp is set by the chain while
+/// the other times are read from the local clock, so durations measured from the block
+/// timestamp include the clock skew between the two.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AlertTiming {
+	/// Timestamp of the block containing the match, if the chain provides one
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub block_timestamp_ms: Option<i64>,
+
+	/// Time the block was fetched from the network
+	pub fetched_at_ms: i64,
+
+	/// Time the block's matches were produced
+	pub matched_at_ms: i64,
+}
+
+/// Pipeline a match was produced by
+///
+/// Matches found while following the chain are `Live`. Matches of historical blocks processed
+/// by a backfill job carry the job's identifier, and matches of recorded chain data are
+/// `Replay`, so that triggers and downstream consumers can tell them apart from live data.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum MatchOrigin {
+	/// Found while following the chain
+	#[default]
+	Live,
+	/// Found by a backfill job over historical blocks
+	Backfill {
+		/// Identifier of the backfill job
+		job_id: String,
+	},
+	/// Found in recorded chain data
+	Replay,
+}
+
+impl MatchOrigin {
+	/// Returns the kind of the origin, as configured in `accept_origins`
+	pub fn kind(&self) -> OriginKind {
+		match self {
+			MatchOrigin::Live => OriginKind::Live,
+			MatchOrigin::Backfill { .. } => OriginKind::Backfill,
+			MatchOrigin::Replay => OriginKind::Replay,
+		}
+	}
+
+	/// Returns whether the match was found while following the chain
+	pub fn is_live(&self) -> bool {
+		*self == MatchOrigin::Live
+	}
+}
+
+/// Kind of [`MatchOrigin`], as configured in the `accept_origins` of a trigger
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum OriginKind {
+	/// Matches found while following the chain
+	Live,
+	/// Matches found by backfill jobs
+	Backfill,
+	/// Matches found in recorded chain data
+	Replay,
+}
+
 /// Chain-specific configuration
-#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Default)]
+#[derive(Debug, Clone, Deserialize, JsonSchema, Serialize, PartialEq, Default)]
 pub struct ChainConfiguration {
 	/// Midnight-specific configuration
 	#[serde(skip_serializing_if = "Option::is_none")]
@@ -121,3 +392,267 @@ pub struct ProcessedBlock {
 	pub network_slug: String,
 	pub processing_results: Vec<MonitorMatch>,
 }
+
+impl ProcessedBlock {
+	/// Sets the pipeline times of all the block's matches
+	pub fn set_timing(&mut self, timing: AlertTiming) {
+		for monitor_match in &mut self.processing_results {
+			monitor_match.set_timing(timing);
+		}
+	}
+
+	/// Sets the pipeline all the block's matches were produced by
+	pub fn set_origin(&mut self, origin: &MatchOrigin) {
+		for monitor_match in &mut self.processing_results {
+			monitor_match.set_origin(origin.clone());
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{
+		models::{FunctionCondition, StellarBlock, StellarTransaction},
+		utils::tests::builders::{
+			evm::{
+				monitor::MonitorBuilder, monitor_match::MonitorMatchBuilder,
+				transaction::TransactionBuilder,
+			},
+			midnight::transaction::TransactionBuilder as MidnightTransactionBuilder,
+		},
+	};
+	use alloy::primitives::{B256, U64};
+	use serde_json::json;
+
+	fn create_monitor(name: &str) -> Monitor {
+		MonitorBuilder::new()
+			.name(name)
+			.function("transfer(address,uint256)", None)
+			.build()
+	}
+
+	fn create_matched_on() -> MatchConditions {
+		MatchConditions {
+			functions: vec![FunctionCondition {
+				signature: "transfer(address,uint256)".to_string(),
+				expression: None,
+			}],
+			events: vec![],
+			transactions: vec![],
+			sender_activity: None,
+			baseline: None,
+			network_metrics: None,
+		}
+	}
+
+	#[test]
+	fn test_evm_match_accessors() {
+		let mut transaction = TransactionBuilder::new()
+			.hash(B256::with_last_byte(1))
+			.build();
+		transaction.0.block_number = Some(U64::from(42));
+		let mut monitor_match = MonitorMatchBuilder::new()
+			.monitor(create_monitor("evm_monitor"))
+			.transaction(transaction)
+			.matched_on(create_matched_on())
+			.matched_on_args(evm::EVMMatchArguments {
+				functions: Some(vec![evm::EVMMatchParamsMap {
+					signature: "transfer(address,uint256)".to_string(),
+					args: None,
+					hex_signature: Some("a9059cbb".to_string()),
+					log_index: None,
+				}]),
+				events: None,
+			})
+			.build();
+
+		assert_eq!(monitor_match.monitor().name, "evm_monitor");
+		assert_eq!(monitor_match.network_slug(), "ethereum_mainnet");
+		assert_eq!(
+			monitor_match.transaction_hash(),
+			format!("0x{}01", "00".repeat(31))
+		);
+		assert_eq!(monitor_match.block_reference(), Some(42));
+		assert_eq!(monitor_match.matched_on().functions.len(), 1);
+		assert_eq!(
+			monitor_match.matched_args_json(),
+			Some(json!({
+				"functions": [{
+					"signature": "transfer(address,uint256)",
+					"args": null,
+					"hex_signature": "a9059cbb"
+				}],
+				"events": null
+			}))
+		);
+
+		monitor_match.monitor_mut().name = "renamed".to_string();
+		assert_eq!(monitor_match.monitor().name, "renamed");
+	}
+
+	#[test]
+	fn test_stellar_match_accessors() {
+		let transaction = StellarTransaction(stellar::StellarTransactionInfo {
+			transaction_hash: "stellar_hash".to_string(),
+			ledger: 123,
+			..Default::default()
+		});
+		let ledger = StellarBlock(stellar::StellarLedgerInfo {
+			sequence: 123,
+			..Default::default()
+		});
+		let monitor_match = MonitorMatch::Stellar(Box::new(stellar::StellarMonitorMatch {
+			monitor: create_monitor("stellar_monitor"),
+			transaction,
+			ledger,
+			network_slug: "stellar_mainnet".to_string(),
+			matched_on: create_matched_on(),
+			matched_on_args: None,
+			network_metrics: None,
+			timing: None,
+			origin: Default::default(),
+		}));
+
+		assert_eq!(monitor_match.monitor().name, "stellar_monitor");
+		assert_eq!(monitor_match.network_slug(), "stellar_mainnet");
+		assert_eq!(monitor_match.transaction_hash(), "stellar_hash");
+		assert_eq!(monitor_match.block_reference(), Some(123));
+		assert_eq!(monitor_match.matched_on().functions.len(), 1);
+		assert_eq!(monitor_match.matched_args_json(), None);
+		assert_eq!(monitor_match.match_key(), "stellar_hash");
+	}
+
+	#[test]
+	fn test_stellar_match_key_includes_event_locations() {
+		let event = |event_index| stellar::StellarMatchParamsMap {
+			signature: "transfer(Address,Address,I128)".to_string(),
+			args: None,
+			operation_index: Some(0),
+			event_index: Some(event_index),
+		};
+		let monitor_match = MonitorMatch::Stellar(Box::new(stellar::StellarMonitorMatch {
+			monitor: create_monitor("stellar_monitor"),
+			transaction: StellarTransaction(stellar::StellarTransactionInfo {
+				transaction_hash: "stellar_hash".to_string(),
+				..Default::default()
+			}),
+			ledger: StellarBlock(stellar::StellarLedgerInfo::default()),
+			network_slug: "stellar_mainnet".to_string(),
+			matched_on: create_matched_on(),
+			matched_on_args: Some(stellar::StellarMatchArguments {
+				functions: None,
+				events: Some(vec![event(1), event(2)]),
+			}),
+			network_metrics: None,
+			timing: None,
+			origin: Default::default(),
+		}));
+
+		assert_eq!(monitor_match.event_locations(), vec!["0.1", "0.2"]);
+		assert_eq!(monitor_match.match_key(), "stellar_hash:0.1,0.2");
+	}
+
+	#[test]
+	fn test_midnight_match_accessors() {
+		let monitor_match = MonitorMatch::Midnight(Box::new(midnight::MidnightMonitorMatch {
+			monitor: create_monitor("midnight_monitor"),
+			transaction: MidnightTransactionBuilder::new()
+				.hash("midnight_hash".to_string())
+				.build(),
+			network_slug: "midnight_testnet".to_string(),
+			matched_on: MatchConditions::default(),
+			matched_on_args: Some(midnight::MidnightMatchArguments {
+				functions: None,
+				events: None,
+			}),
+			timing: None,
+			origin: Default::default(),
+		}));
+
+		assert_eq!(monitor_match.monitor().name, "midnight_monitor");
+		assert_eq!(monitor_match.network_slug(), "midnight_testnet");
+		assert_eq!(monitor_match.transaction_hash(), "midnight_hash");
+		assert_eq!(monitor_match.block_reference(), None);
+		assert!(monitor_match.matched_on().functions.is_empty());
+		assert_eq!(
+			monitor_match.matched_args_json(),
+			Some(json!({ "functions": null, "events": null }))
+		);
+	}
+
+	#[test]
+	fn test_stellar_block_timestamp_ms() {
+		let ledger = |ledger_close_time: &str| {
+			BlockType::Stellar(Box::new(StellarBlock(stellar::StellarLedgerInfo {
+				ledger_close_time: ledger_close_time.to_string(),
+				..Default::default()
+			})))
+		};
+
+		assert_eq!(ledger("1734715051").timestamp_ms(), Some(1_734_715_051_000));
+		assert_eq!(
+			ledger("2024-03-20T10:00:00Z").timestamp_ms(),
+			Some(1_710_928_800_000)
+		);
+		assert_eq!(ledger("").timestamp_ms(), None);
+	}
+
+	#[test]
+	fn test_processed_block_timing_is_set_on_all_matches() {
+		let stellar_match = |hash: &str| {
+			MonitorMatch::Stellar(Box::new(stellar::StellarMonitorMatch {
+				monitor: create_monitor("stellar_monitor"),
+				transaction: StellarTransaction(stellar::StellarTransactionInfo {
+					transaction_hash: hash.to_string(),
+					..Default::default()
+				}),
+				ledger: StellarBlock::default(),
+				network_slug: "stellar_mainnet".to_string(),
+				matched_on: create_matched_on(),
+				matched_on_args: None,
+				network_metrics: None,
+				timing: None,
+				origin: Default::default(),
+			}))
+		};
+		let mut block = ProcessedBlock {
+			block_number: 123,
+			network_slug: "stellar_mainnet".to_string(),
+			processing_results: vec![stellar_match("a"), stellar_match("b")],
+		};
+		let timing = AlertTiming {
+			block_timestamp_ms: Some(1_000),
+			fetched_at_ms: 4_000,
+			matched_at_ms: 4_500,
+		};
+
+		block.set_timing(timing);
+
+		for monitor_match in &block.processing_results {
+			assert_eq!(monitor_match.timing(), Some(&timing));
+		}
+		// The timing survives the round trip through the outbox
+		let serialized = serde_json::to_string(&block.processing_results[0]).unwrap();
+		let restored: MonitorMatch = serde_json::from_str(&serialized).unwrap();
+		assert_eq!(restored.timing(), Some(&timing));
+	}
+
+	#[test]
+	fn test_match_origin_serialization() {
+		let backfill = MatchOrigin::Backfill {
+			job_id: "backfill-42".to_string(),
+		};
+		assert_eq!(
+			serde_json::to_value(&backfill).unwrap(),
+			serde_json::json!({ "type": "backfill", "job_id": "backfill-42" })
+		);
+		assert_eq!(
+			serde_json::to_value(MatchOrigin::Live).unwrap(),
+			serde_json::json!({ "type": "live" })
+		);
+		assert_eq!(backfill.kind(), OriginKind::Backfill);
+		assert!(MatchOrigin::Live.is_live());
+		assert!(!MatchOrigin::Replay.is_live());
+	}
+}
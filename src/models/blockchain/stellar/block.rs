@@ -56,6 +56,19 @@ impl Block {
 	pub fn number(&self) -> Option<u64> {
 		Some(self.0.sequence as u64)
 	}
+
+	/// Get the close time of the ledger in seconds since the Unix epoch
+	///
+	/// The close time is reported either as a number of seconds or as an RFC 3339 date.
+	pub fn close_time(&self) -> Option<i64> {
+		let close_time = self.0.ledger_close_time.trim();
+		match close_time.parse::<i64>() {
+			Ok(seconds) => Some(seconds),
+			Err(_) => chrono::DateTime::parse_from_rfc3339(close_time)
+				.ok()
+				.map(|time| time.timestamp()),
+		}
+	}
 }
 
 impl From<LedgerInfo> for Block {
@@ -57,3 +57,37 @@ pub struct Event {
 	#[serde(rename = "valueJson", skip_serializing_if = "Option::is_none")]
 	pub value_json: Option<serde_json::Value>,
 }
+
+impl Event {
+	/// Returns the index of the operation in its transaction and of the event in the operation
+	///
+	/// Both are encoded in the event id, `<TOID>-<event index>`, the low 12 bits of the TOID
+	/// holding the operation index. Returns `None` if the id has another format.
+	pub fn location(&self) -> Option<(u32, u32)> {
+		let (toid, event_index) = self.id.split_once('-')?;
+		let toid = toid.parse::<u64>().ok()?;
+		Some(((toid & 0xFFF) as u32, event_index.parse().ok()?))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_location() {
+		let event = |id: &str| Event {
+			id: id.to_string(),
+			..Default::default()
+		};
+
+		// Ledger 4, transaction 1, operation 2, event 3
+		let toid = (4u64 << 32) | (1 << 12) | 2;
+		assert_eq!(
+			event(&format!("{:019}-{:010}", toid, 3)).location(),
+			Some((2, 3))
+		);
+		assert_eq!(event("0017179873280-0000000000").location(), Some((0, 0)));
+		assert_eq!(event("invalid").location(), None);
+	}
+}
@@ -1,11 +1,15 @@
 //! Monitor implementation for Stellar blockchain.
 
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use stellar_xdr::curr::ScSpecEntry;
 
 use crate::{
-	models::{MatchConditions, Monitor, StellarBlock, StellarTransaction},
+	models::{
+		AlertTiming, MatchConditions, MatchOrigin, Monitor, NetworkMetrics, StellarBlock,
+		StellarTransaction,
+	},
 	services::filter::stellar_helpers::{
 		get_contract_spec_events, get_contract_spec_functions,
 		get_contract_spec_with_event_parameters, get_contract_spec_with_function_input_parameters,
@@ -32,6 +36,19 @@ pub struct MonitorMatch {
 
 	/// Decoded arguments from the matched conditions
 	pub matched_on_args: Option<MatchArguments>,
+
+	/// Metrics of the blocks the condition held for, for matches of a `network_metrics`
+	/// condition
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub network_metrics: Option<NetworkMetrics>,
+
+	/// Times at which the match went through the monitoring pipeline
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub timing: Option<AlertTiming>,
+
+	/// Pipeline the match was produced by
+	#[serde(default, skip_serializing_if = "MatchOrigin::is_live")]
+	pub origin: MatchOrigin,
 }
 
 /// Collection of decoded parameters from matched conditions
@@ -42,6 +59,14 @@ pub struct MatchParamsMap {
 
 	/// Decoded argument values
 	pub args: Option<Vec<MatchParamEntry>>,
+
+	/// Index of the operation in the transaction
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub operation_index: Option<u32>,
+
+	/// Index of the event in the operation, for events
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub event_index: Option<u32>,
 }
 
 /// Single decoded parameter from a function or event
@@ -109,8 +134,8 @@ pub struct DecodedParamEntry {
 /// from ScSpecEntry. It contains the raw contract interface data as provided by the Stellar
 /// blockchain, including all function definitions, types, and other contract metadata in their
 /// original format.
-#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Default)]
-pub struct ContractSpec(Vec<ScSpecEntry>);
+#[derive(Debug, Clone, Deserialize, JsonSchema, Serialize, PartialEq, Default)]
+pub struct ContractSpec(#[schemars(with = "Vec<serde_json::Value>")] Vec<ScSpecEntry>);
 
 impl From<Vec<ScSpecEntry>> for ContractSpec {
 	fn from(spec: Vec<ScSpecEntry>) -> Self {
@@ -292,7 +317,7 @@ pub struct ContractEvent {
 ///
 /// This configuration is used to for additional fields in the monitor configuration
 /// that are specific to Stellar.
-#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Default)]
+#[derive(Debug, Clone, Deserialize, JsonSchema, Serialize, PartialEq, Default)]
 pub struct MonitorConfig {}
 
 #[cfg(test)]
@@ -538,6 +563,8 @@ mod tests {
 					indexed: false,
 				},
 			]),
+			operation_index: None,
+			event_index: None,
 		};
 
 		let monitor_match = MonitorMatch {
@@ -552,11 +579,16 @@ mod tests {
 				}],
 				events: vec![],
 				transactions: vec![],
+				sender_activity: None,
+				baseline: None,
+				network_metrics: None,
 			},
 			matched_on_args: Some(MatchArguments {
 				functions: Some(vec![match_params]),
 				events: None,
 			}),
+			timing: None,
+			origin: Default::default(),
 		};
 
 		assert_eq!(monitor_match.monitor.name, "TestMonitor");
@@ -645,6 +677,8 @@ mod tests {
 						indexed: false,
 					},
 				]),
+				operation_index: None,
+				event_index: None,
 			}]),
 			events: Some(vec![MatchParamsMap {
 				signature: "Transfer(address,address,uint256)".to_string(),
@@ -670,6 +704,8 @@ mod tests {
 						indexed: false,
 					},
 				]),
+				operation_index: None,
+				event_index: None,
 			}]),
 		};
 
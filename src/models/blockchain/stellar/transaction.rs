@@ -28,7 +28,7 @@ pub struct TransactionInfo {
 	pub application_order: i32,
 
 	/// Whether this is a fee bump transaction
-	#[serde(rename = "feeBump")]
+	#[serde(rename = "feeBump", default)]
 	pub fee_bump: bool,
 
 	// XDR and JSON fields
@@ -0,0 +1,111 @@
+//! Content hashes of configuration entries.
+//!
+//! The content hash identifies an exact monitor, network or trigger definition. It is the
+//! SHA-256 of the canonical JSON form of the entry: the entry is serialized after loading, with
+//! its defaults applied, and written with sorted keys and no whitespace. The hash is therefore
+//! independent of key order and formatting of the configuration file, and changes with any
+//! field.
+
+use serde::Serialize;
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+
+use crate::models::{Monitor, Network, Trigger};
+
+/// Configuration entry identified by the hash of its content
+pub trait ContentHash: Serialize {
+	/// Returns the hex encoded SHA-256 of the canonical JSON form of the entry
+	fn content_hash(&self) -> String {
+		content_hash(self)
+	}
+}
+
+impl ContentHash for Monitor {}
+
+impl ContentHash for Network {}
+
+impl ContentHash for Trigger {}
+
+/// Returns the hex encoded SHA-256 of the canonical JSON form of a value
+pub fn content_hash<T: Serialize + ?Sized>(value: &T) -> String {
+	let value = serde_json::to_value(value).unwrap_or(Value::Null);
+	let mut canonical = String::new();
+	write_canonical(&value, &mut canonical);
+	hex::encode(Sha256::digest(canonical.as_bytes()))
+}
+
+/// Writes a JSON value with its object keys sorted and no whitespace
+fn write_canonical(value: &Value, out: &mut String) {
+	match value {
+		Value::Object(map) => {
+			let mut entries: Vec<_> = map.iter().collect();
+			entries.sort_by(|a, b| a.0.cmp(b.0));
+			out.push('{');
+			for (i, (key, value)) in entries.into_iter().enumerate() {
+				if i > 0 {
+					out.push(',');
+				}
+				out.push_str(&Value::String(key.clone()).to_string());
+				out.push(':');
+				write_canonical(value, out);
+			}
+			out.push('}');
+		}
+		Value::Array(items) => {
+			out.push('[');
+			for (i, item) in items.iter().enumerate() {
+				if i > 0 {
+					out.push(',');
+				}
+				write_canonical(item, out);
+			}
+			out.push(']');
+		}
+		_ => out.push_str(&value.to_string()),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::utils::tests::builders::evm::monitor::MonitorBuilder;
+	use serde_json::json;
+
+	#[test]
+	fn test_content_hash_ignores_key_order_and_whitespace() {
+		let a: Value = serde_json::from_str(r#"{"name": "a", "networks": ["x", "y"]}"#).unwrap();
+		let b: Value =
+			serde_json::from_str("{\n  \"networks\":[\"x\",\"y\"],\n  \"name\":\"a\"\n}").unwrap();
+		assert_eq!(content_hash(&a), content_hash(&b));
+		assert_eq!(content_hash(&a).len(), 64);
+	}
+
+	#[test]
+	fn test_content_hash_changes_with_any_field() {
+		let monitor = MonitorBuilder::new().name("hashed").build();
+		let hash = monitor.content_hash();
+		assert_eq!(hash, monitor.clone().content_hash());
+
+		let mut paused = monitor.clone();
+		paused.paused = true;
+		assert_ne!(paused.content_hash(), hash);
+
+		let mut moved = monitor.clone();
+		moved.networks = vec!["sepolia".to_string()];
+		assert_ne!(moved.content_hash(), hash);
+
+		// Reordered list items are a different definition
+		assert_ne!(
+			content_hash(&json!({"networks": ["x", "y"]})),
+			content_hash(&json!({"networks": ["y", "x"]}))
+		);
+	}
+
+	#[test]
+	fn test_monitor_content_hash_applies_defaults() {
+		let monitor = MonitorBuilder::new().name("defaults").build();
+		let serialized = serde_json::to_value(&monitor).unwrap();
+		let reloaded: Monitor = serde_json::from_value(serialized).unwrap();
+		assert_eq!(reloaded.content_hash(), monitor.content_hash());
+	}
+}
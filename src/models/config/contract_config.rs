@@ -0,0 +1,415 @@
+//! Contract registry configuration loading and validation.
+//!
+//! This module implements the ConfigLoader trait for Contract configurations, allowing
+//! contract specs shared between monitors to be loaded from JSON files, and resolves the
+//! references monitors make to them.
+
+use async_trait::async_trait;
+use std::{collections::HashMap, path::Path};
+
+use crate::{
+	models::{config::error::ConfigError, AddressWithSpec, ConfigLoader, Contract, Monitor},
+	utils::normalize_string,
+};
+
+impl Monitor {
+	/// Returns a copy of the monitor with its contract references resolved
+	///
+	/// Addresses referencing a registry contract get the contract's spec, label and annotations,
+	/// the address's own label and annotations taking precedence. An address left empty is
+	/// replaced by the contract's default addresses on the monitor's networks.
+	///
+	/// # Arguments
+	/// * `contracts` - Contracts of the registry, keyed by name
+	///
+	/// # Returns
+	/// * `Result<Monitor, ConfigError>` - The resolved monitor, or an error naming the monitor
+	///   and the contract that could not be resolved
+	pub fn resolve_contracts(
+		&self,
+		contracts: &HashMap<String, Contract>,
+	) -> Result<Self, ConfigError> {
+		let mut addresses = Vec::with_capacity(self.addresses.len());
+
+		for address in &self.addresses {
+			let Some(contract_name) = &address.contract else {
+				addresses.push(address.clone());
+				continue;
+			};
+			let metadata = HashMap::from([
+				("monitor".to_string(), self.name.clone()),
+				("contract".to_string(), contract_name.clone()),
+			]);
+
+			let Some(contract) = contracts.get(contract_name) else {
+				return Err(ConfigError::validation_error(
+					format!(
+						"Monitor '{}' references unknown contract '{}'",
+						self.name, contract_name
+					),
+					None,
+					Some(metadata),
+				));
+			};
+			if address.contract_spec.is_some() {
+				return Err(ConfigError::validation_error(
+					format!(
+						"Monitor '{}' sets both a contract_spec and the contract '{}'",
+						self.name, contract_name
+					),
+					None,
+					Some(metadata),
+				));
+			}
+
+			let label = address.label.clone().or_else(|| contract.label.clone());
+			let mut annotations = contract.annotations.clone();
+			annotations.extend(address.annotations.clone());
+
+			if !address.address.is_empty() {
+				addresses.push(AddressWithSpec {
+					contract_spec: Some(contract.contract_spec.clone()),
+					label,
+					annotations,
+					..address.clone()
+				});
+				continue;
+			}
+
+			let defaults = self
+				.networks
+				.iter()
+				.filter_map(|network| contract.addresses.get(network))
+				.flatten()
+				.collect::<Vec<_>>();
+			if defaults.is_empty() {
+				return Err(ConfigError::validation_error(
+					format!(
+						"Monitor '{}' omits the address of contract '{}', which has no default \
+						 address on the monitor's networks",
+						self.name, contract_name
+					),
+					None,
+					Some(metadata),
+				));
+			}
+			addresses.extend(defaults.into_iter().map(|default| AddressWithSpec {
+				address: default.clone(),
+				contract_spec: Some(contract.contract_spec.clone()),
+				contract: Some(contract_name.clone()),
+				label: label.clone(),
+				annotations: annotations.clone(),
+			}));
+		}
+
+		Ok(Monitor {
+			addresses,
+			..self.clone()
+		})
+	}
+}
+
+#[async_trait]
+impl ConfigLoader for Contract {
+	/// Contracts hold no secrets
+	async fn resolve_secrets(&self) -> Result<Self, ConfigError> {
+		Ok(self.clone())
+	}
+
+	/// Load all contract configurations from a directory
+	///
+	/// Reads and parses all JSON files in the specified directory (or default
+	/// config directory) as contract configurations, keyed by contract name.
+	/// The registry is optional, so a missing directory yields no contracts.
+	async fn load_all<T>(path: Option<&Path>) -> Result<T, ConfigError>
+	where
+		T: FromIterator<(String, Self)>,
+	{
+		let contract_dir = path.unwrap_or(Path::new("config/contracts"));
+		let mut pairs = Vec::new();
+
+		if !contract_dir.exists() {
+			return Ok(T::from_iter(pairs));
+		}
+
+		for entry in std::fs::read_dir(contract_dir).map_err(|e| {
+			ConfigError::file_error(
+				format!("failed to read contracts directory: {}", e),
+				Some(Box::new(e)),
+				Some(HashMap::from([(
+					"path".to_string(),
+					contract_dir.display().to_string(),
+				)])),
+			)
+		})? {
+			let entry = entry.map_err(|e| {
+				ConfigError::file_error(
+					format!("failed to read directory entry: {}", e),
+					Some(Box::new(e)),
+					Some(HashMap::from([(
+						"path".to_string(),
+						contract_dir.display().to_string(),
+					)])),
+				)
+			})?;
+			let path = entry.path();
+
+			if !Self::is_json_file(&path) {
+				continue;
+			}
+
+			let contract = Self::load_from_path(&path).await?;
+
+			let existing_contracts: Vec<&Contract> =
+				pairs.iter().map(|(_, contract)| contract).collect();
+			Self::validate_uniqueness(&existing_contracts, &contract, &path.display().to_string())?;
+
+			pairs.push((contract.name.clone(), contract));
+		}
+
+		Ok(T::from_iter(pairs))
+	}
+
+	/// Load a contract configuration from a specific file
+	async fn load_from_path(path: &Path) -> Result<Self, ConfigError> {
+		let file = std::fs::File::open(path).map_err(|e| {
+			ConfigError::file_error(
+				format!("failed to open contract config file: {}", e),
+				Some(Box::new(e)),
+				Some(HashMap::from([(
+					"path".to_string(),
+					path.display().to_string(),
+				)])),
+			)
+		})?;
+		let config: Contract = serde_json::from_reader(file).map_err(|e| {
+			ConfigError::parse_error(
+				format!("failed to parse contract config: {}", e),
+				Some(Box::new(e)),
+				Some(HashMap::from([(
+					"path".to_string(),
+					path.display().to_string(),
+				)])),
+			)
+		})?;
+
+		config.validate()?;
+
+		Ok(config)
+	}
+
+	/// Validate the contract configuration
+	///
+	/// Ensures that the contract has a name and that its default addresses are not empty.
+	fn validate(&self) -> Result<(), ConfigError> {
+		if self.name.trim().is_empty() {
+			return Err(ConfigError::validation_error(
+				"Contract name is required",
+				None,
+				None,
+			));
+		}
+
+		for (network, addresses) in &self.addresses {
+			if addresses.iter().any(|address| address.trim().is_empty()) {
+				return Err(ConfigError::validation_error(
+					format!(
+						"Contract '{}' has an empty default address on network '{}'",
+						self.name, network
+					),
+					None,
+					None,
+				));
+			}
+		}
+
+		Ok(())
+	}
+
+	/// Contracts have no protocol to check
+	fn validate_protocol(&self) {}
+
+	fn validate_uniqueness(
+		instances: &[&Self],
+		current_instance: &Self,
+		file_path: &str,
+	) -> Result<(), ConfigError> {
+		if instances.iter().any(|existing_contract| {
+			normalize_string(&existing_contract.name) == normalize_string(&current_instance.name)
+		}) {
+			return Err(ConfigError::validation_error(
+				format!("Duplicate contract name found: '{}'", current_instance.name),
+				None,
+				Some(HashMap::from([
+					("contract_name".to_string(), current_instance.name.clone()),
+					("path".to_string(), file_path.to_string()),
+				])),
+			));
+		}
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{
+		models::{ContractSpec, EVMContractSpec},
+		utils::tests::evm::monitor::MonitorBuilder,
+	};
+	use std::fs;
+	use tempfile::TempDir;
+
+	fn create_contract(name: &str, addresses: HashMap<String, Vec<String>>) -> Contract {
+		Contract {
+			name: name.to_string(),
+			contract_spec: ContractSpec::EVM(EVMContractSpec::from(serde_json::json!([]))),
+			addresses,
+			label: None,
+			annotations: HashMap::new(),
+		}
+	}
+
+	fn create_reference(address: &str, contract: &str) -> AddressWithSpec {
+		AddressWithSpec {
+			address: address.to_string(),
+			contract_spec: None,
+			contract: Some(contract.to_string()),
+			label: None,
+			annotations: HashMap::new(),
+		}
+	}
+
+	#[test]
+	fn test_resolve_contracts_fills_spec_and_default_addresses() {
+		let contracts = HashMap::from([(
+			"erc20".to_string(),
+			create_contract(
+				"erc20",
+				HashMap::from([
+					("ethereum_mainnet".to_string(), vec!["0x01".to_string()]),
+					("base_mainnet".to_string(), vec!["0x02".to_string()]),
+				]),
+			),
+		)]);
+		let mut monitor = MonitorBuilder::new()
+			.networks(vec!["ethereum_mainnet".to_string()])
+			.build();
+		monitor.addresses = vec![
+			create_reference("0xaa", "erc20"),
+			create_reference("", "erc20"),
+		];
+
+		let resolved = monitor.resolve_contracts(&contracts).unwrap();
+
+		assert_eq!(resolved.addresses.len(), 2);
+		assert_eq!(resolved.addresses[0].address, "0xaa");
+		assert_eq!(resolved.addresses[1].address, "0x01");
+		assert!(resolved
+			.addresses
+			.iter()
+			.all(|a| a.contract_spec == Some(contracts["erc20"].contract_spec.clone())));
+	}
+
+	#[test]
+	fn test_resolve_contracts_applies_labels() {
+		let contracts = HashMap::from([(
+			"timelock".to_string(),
+			Contract {
+				label: Some("Treasury Timelock".to_string()),
+				annotations: HashMap::from([
+					("severity".to_string(), "critical".to_string()),
+					("team".to_string(), "treasury".to_string()),
+				]),
+				..create_contract(
+					"timelock",
+					HashMap::from([("ethereum_mainnet".to_string(), vec!["0x01".to_string()])]),
+				)
+			},
+		)]);
+		let mut monitor = MonitorBuilder::new()
+			.networks(vec!["ethereum_mainnet".to_string()])
+			.build();
+		monitor.addresses = vec![
+			AddressWithSpec {
+				label: Some("Ops Timelock".to_string()),
+				annotations: HashMap::from([("team".to_string(), "ops".to_string())]),
+				..create_reference("0xaa", "timelock")
+			},
+			create_reference("", "timelock"),
+		];
+
+		let resolved = monitor.resolve_contracts(&contracts).unwrap();
+
+		// The address's own label and annotations take precedence over the contract's
+		assert_eq!(resolved.addresses[0].label.as_deref(), Some("Ops Timelock"));
+		assert_eq!(resolved.addresses[0].annotations["team"], "ops");
+		assert_eq!(resolved.addresses[0].annotations["severity"], "critical");
+		assert_eq!(
+			resolved.addresses[1].label.as_deref(),
+			Some("Treasury Timelock")
+		);
+		assert_eq!(
+			resolved.addresses[1].annotations,
+			contracts["timelock"].annotations
+		);
+	}
+
+	#[test]
+	fn test_resolve_contracts_errors() {
+		let contracts = HashMap::from([(
+			"erc20".to_string(),
+			create_contract("erc20", HashMap::new()),
+		)]);
+		let mut monitor = MonitorBuilder::new().name("Transfers").build();
+
+		monitor.addresses = vec![create_reference("0xaa", "erc721")];
+		let err = monitor.resolve_contracts(&contracts).unwrap_err();
+		assert!(err
+			.to_string()
+			.contains("Monitor 'Transfers' references unknown contract 'erc721'"));
+
+		monitor.addresses = vec![create_reference("", "erc20")];
+		assert!(monitor.resolve_contracts(&contracts).is_err());
+
+		monitor.addresses = vec![AddressWithSpec {
+			contract_spec: Some(contracts["erc20"].contract_spec.clone()),
+			..create_reference("0xaa", "erc20")
+		}];
+		assert!(monitor.resolve_contracts(&contracts).is_err());
+	}
+
+	#[tokio::test]
+	async fn test_load_all_contracts() {
+		let temp_dir = TempDir::new().unwrap();
+		fs::write(
+			temp_dir.path().join("erc20.json"),
+			r#"{"name": "erc20", "contract_spec": [], "addresses": {"ethereum_mainnet": ["0x01"]}}"#,
+		)
+		.unwrap();
+
+		let contracts: HashMap<String, Contract> =
+			Contract::load_all(Some(temp_dir.path())).await.unwrap();
+		assert_eq!(
+			contracts["erc20"].addresses["ethereum_mainnet"],
+			vec!["0x01"]
+		);
+
+		fs::write(
+			temp_dir.path().join("duplicate.json"),
+			r#"{"name": "ERC20", "contract_spec": []}"#,
+		)
+		.unwrap();
+		assert!(
+			Contract::load_all::<HashMap<String, Contract>>(Some(temp_dir.path()))
+				.await
+				.is_err()
+		);
+
+		let missing: HashMap<String, Contract> =
+			Contract::load_all(Some(&temp_dir.path().join("missing")))
+				.await
+				.unwrap();
+		assert!(missing.is_empty());
+	}
+}
@@ -0,0 +1,1030 @@
+//! Configuration linting.
+//!
+//! Lints flag configuration that loads fine but is probably not what the user intended, such as
+//! a monitor without triggers or a network polled faster than it produces blocks. Lints are
+//! reported as warnings unless their code is listed in the `deny` setting of the lint
+//! configuration file, in which case loading fails. Some lints only run when enabled in the lint
+//! configuration, such as `require_owner`.
+
+use serde::{Deserialize, Serialize};
+use std::{collections::HashMap, fmt, path::Path};
+
+use crate::{
+	models::{
+		config::{error::ConfigError, plaintext_secrets},
+		ContractSpec, Monitor, Network, Trigger,
+	},
+	services::{
+		filter::{evm_helpers::are_same_signature, expression_variable_names},
+		notification::ChannelCapabilities,
+	},
+	utils::get_cron_interval_ms,
+};
+
+/// Default path of the lint configuration file
+const DEFAULT_LINT_CONFIG_PATH: &str = "config/lint.json";
+
+/// Number of blocks a network may produce between two polls before its polling is too slow
+const MAX_BLOCKS_PER_POLL_INTERVAL: u64 = 10;
+
+/// Identifies a lint
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LintCode {
+	/// Monitor defines no function, event or transaction conditions
+	EmptyMatchConditions,
+	/// Monitor watches no addresses
+	MissingAddresses,
+	/// Monitor lists the same address more than once
+	DuplicateAddress,
+	/// Monitor lists the same function or event condition more than once
+	DuplicateCondition,
+	/// Condition signature is not part of any ABI attached to the monitor
+	UnknownSignature,
+	/// Condition expression references an argument the signature does not declare
+	UnknownExpressionArgument,
+	/// Monitor has no triggers to notify
+	MonitorWithoutTriggers,
+	/// Trigger is not referenced by any monitor
+	UnusedTrigger,
+	/// Trigger sets an option its notification channel does not support
+	UnsupportedChannelOption,
+	/// Network is polled more often than it produces blocks
+	PollingFasterThanBlockTime,
+	/// Network is polled so rarely that many blocks accumulate between polls
+	PollingSlowerThanBlockTime,
+	/// Monitor has no owner while owners are required
+	MissingOwner,
+	/// Trigger or network holds a credential as a plain value
+	PlaintextSecret,
+}
+
+impl LintCode {
+	/// All lints, in reporting order
+	pub const ALL: [LintCode; 13] = [
+		LintCode::EmptyMatchConditions,
+		LintCode::MissingAddresses,
+		LintCode::DuplicateAddress,
+		LintCode::DuplicateCondition,
+		LintCode::UnknownSignature,
+		LintCode::UnknownExpressionArgument,
+		LintCode::MonitorWithoutTriggers,
+		LintCode::UnusedTrigger,
+		LintCode::UnsupportedChannelOption,
+		LintCode::PollingFasterThanBlockTime,
+		LintCode::PollingSlowerThanBlockTime,
+		LintCode::MissingOwner,
+		LintCode::PlaintextSecret,
+	];
+
+	/// Returns the code used to refer to the lint in configuration and logs
+	pub fn as_str(&self) -> &'static str {
+		match self {
+			LintCode::EmptyMatchConditions => "empty-match-conditions",
+			LintCode::MissingAddresses => "missing-addresses",
+			LintCode::DuplicateAddress => "duplicate-address",
+			LintCode::DuplicateCondition => "duplicate-condition",
+			LintCode::UnknownSignature => "unknown-signature",
+			LintCode::UnknownExpressionArgument => "unknown-expression-argument",
+			LintCode::MonitorWithoutTriggers => "monitor-without-triggers",
+			LintCode::UnusedTrigger => "unused-trigger",
+			LintCode::UnsupportedChannelOption => "unsupported-channel-option",
+			LintCode::PollingFasterThanBlockTime => "polling-faster-than-block-time",
+			LintCode::PollingSlowerThanBlockTime => "polling-slower-than-block-time",
+			LintCode::MissingOwner => "missing-owner",
+			LintCode::PlaintextSecret => "plaintext-secret",
+		}
+	}
+
+	/// Looks up a lint by its code
+	pub fn from_code(code: &str) -> Option<Self> {
+		Self::ALL.into_iter().find(|lint| lint.as_str() == code)
+	}
+}
+
+impl fmt::Display for LintCode {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str(self.as_str())
+	}
+}
+
+/// Kind of configuration entity a lint was raised on
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LintEntity {
+	Monitor,
+	Network,
+	Trigger,
+}
+
+impl fmt::Display for LintEntity {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			LintEntity::Monitor => f.write_str("monitor"),
+			LintEntity::Network => f.write_str("network"),
+			LintEntity::Trigger => f.write_str("trigger"),
+		}
+	}
+}
+
+/// A single lint finding
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LintWarning {
+	/// Kind of entity the finding is about
+	pub entity: LintEntity,
+	/// Name (or slug) of the entity
+	pub name: String,
+	/// Path of the offending field within the entity
+	pub field: String,
+	/// Lint that produced the finding
+	pub code: LintCode,
+	/// Description of the problem
+	pub message: String,
+	/// Suggested fix
+	pub suggestion: String,
+}
+
+impl fmt::Display for LintWarning {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(
+			f,
+			"[{}] {} '{}' ({}): {}. Suggestion: {}",
+			self.code, self.entity, self.name, self.field, self.message, self.suggestion
+		)
+	}
+}
+
+/// Lint settings loaded from `config/lint.json`
+#[derive(Debug, Clone, Default, Deserialize, Serialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct LintConfig {
+	/// Lint codes promoted from warnings to errors
+	#[serde(default)]
+	pub deny: Vec<String>,
+
+	/// Whether monitors without an `owner` are reported
+	#[serde(default)]
+	pub require_owner: bool,
+}
+
+impl LintConfig {
+	/// Loads the lint configuration, defaulting to no denied lints if the file does not exist
+	///
+	/// # Arguments
+	/// * `path` - Path of the lint configuration file, defaults to `config/lint.json`
+	///
+	/// # Returns
+	/// * `Result<Self, ConfigError>` - The validated lint configuration
+	pub fn load(path: Option<&Path>) -> Result<Self, ConfigError> {
+		let path = path.unwrap_or(Path::new(DEFAULT_LINT_CONFIG_PATH));
+		if !path.exists() {
+			return Ok(Self::default());
+		}
+
+		let metadata = Some(HashMap::from([(
+			"path".to_string(),
+			path.display().to_string(),
+		)]));
+		let content = std::fs::read_to_string(path).map_err(|e| {
+			ConfigError::file_error(
+				format!("failed to read lint config: {}", e),
+				Some(Box::new(e)),
+				metadata.clone(),
+			)
+		})?;
+		let config: Self = serde_json::from_str(&content).map_err(|e| {
+			ConfigError::parse_error(
+				format!("failed to parse lint config: {}", e),
+				Some(Box::new(e)),
+				metadata,
+			)
+		})?;
+		config.validate()?;
+
+		Ok(config)
+	}
+
+	/// Validates that every denied code refers to a known lint
+	pub fn validate(&self) -> Result<(), ConfigError> {
+		for code in &self.deny {
+			if LintCode::from_code(code).is_none() {
+				return Err(ConfigError::validation_error(
+					format!("Unknown lint code in deny list: {}", code),
+					None,
+					None,
+				));
+			}
+		}
+		Ok(())
+	}
+
+	/// Returns whether the given lint has been promoted to an error
+	pub fn is_denied(&self, code: LintCode) -> bool {
+		self.deny.iter().any(|denied| denied == code.as_str())
+	}
+
+	/// Logs the findings and fails if any of them is denied
+	///
+	/// # Arguments
+	/// * `warnings` - Findings produced by [`lint_configuration`]
+	///
+	/// # Returns
+	/// * `Result<(), ConfigError>` - Error listing the denied findings, if any
+	pub fn enforce(&self, warnings: &[LintWarning]) -> Result<(), ConfigError> {
+		let mut denied = Vec::new();
+		for warning in warnings {
+			if self.is_denied(warning.code) {
+				tracing::error!(lint = %warning.code, "{}", warning);
+				denied.push(warning.to_string());
+			} else {
+				tracing::warn!(lint = %warning.code, "{}", warning);
+			}
+		}
+
+		if denied.is_empty() {
+			return Ok(());
+		}
+
+		Err(ConfigError::validation_error(
+			format!(
+				"{} configuration lint(s) denied: {}",
+				denied.len(),
+				denied.join("; ")
+			),
+			None,
+			None,
+		))
+	}
+}
+
+/// Runs all lints over the loaded configuration
+///
+/// # Arguments
+/// * `monitors` - All loaded monitors, including paused ones
+/// * `networks` - All loaded networks
+/// * `triggers` - All loaded triggers
+/// * `config` - Lint settings enabling optional lints
+///
+/// # Returns
+/// * `Vec<LintWarning>` - Findings ordered by entity name
+pub fn lint_configuration(
+	monitors: &HashMap<String, Monitor>,
+	networks: &HashMap<String, Network>,
+	triggers: &HashMap<String, Trigger>,
+	config: &LintConfig,
+) -> Vec<LintWarning> {
+	let mut warnings = Vec::new();
+
+	let mut monitor_names: Vec<&String> = monitors.keys().collect();
+	monitor_names.sort();
+	for name in monitor_names {
+		let monitor = &monitors[name];
+		lint_monitor(monitor, &mut warnings);
+		if config.require_owner && monitor.owner.is_none() {
+			warnings.push(LintWarning {
+				entity: LintEntity::Monitor,
+				name: monitor.name.clone(),
+				field: "owner".to_string(),
+				code: LintCode::MissingOwner,
+				message: "no owner is set, so nobody is known to respond to its alerts".to_string(),
+				suggestion: "add an owner with the team name and a runbook_url".to_string(),
+			});
+		}
+	}
+
+	let mut trigger_names: Vec<&String> = triggers.keys().collect();
+	trigger_names.sort();
+	for name in trigger_names {
+		let trigger = &triggers[name];
+		let referenced = monitors
+			.values()
+			.any(|monitor| monitor.trigger_reference(name).is_some());
+		if !referenced {
+			warnings.push(LintWarning {
+				entity: LintEntity::Trigger,
+				name: trigger.name.clone(),
+				field: "name".to_string(),
+				code: LintCode::UnusedTrigger,
+				message: "trigger is not referenced by any monitor".to_string(),
+				suggestion: "reference it from a monitor's triggers or remove it".to_string(),
+			});
+		}
+		lint_plaintext_secrets(LintEntity::Trigger, &trigger.name, &mut warnings);
+		let channel = trigger.trigger_type.as_str();
+		let capabilities = ChannelCapabilities::for_trigger_type(&trigger.trigger_type);
+		for option in capabilities.unsupported_options(&trigger.config) {
+			warnings.push(LintWarning {
+				entity: LintEntity::Trigger,
+				name: trigger.name.clone(),
+				field: option.field.to_string(),
+				code: LintCode::UnsupportedChannelOption,
+				message: format!(
+					"{} notifications do not support {}, so the option has no effect",
+					channel, option.feature
+				),
+				suggestion: format!(
+					"remove {} or use a channel that supports {}",
+					option.field, option.feature
+				),
+			});
+		}
+	}
+
+	let mut network_slugs: Vec<&String> = networks.keys().collect();
+	network_slugs.sort();
+	for slug in network_slugs {
+		let network = &networks[slug];
+		lint_plaintext_secrets(LintEntity::Network, &network.slug, &mut warnings);
+		if let Some(interval_ms) = get_cron_interval_ms(&network.cron_schedule) {
+			if interval_ms >= 0 && (interval_ms as u64) < network.block_time_ms {
+				warnings.push(LintWarning {
+					entity: LintEntity::Network,
+					name: network.slug.clone(),
+					field: "cron_schedule".to_string(),
+					code: LintCode::PollingFasterThanBlockTime,
+					message: format!(
+						"polling interval of {}ms is shorter than the block time of {}ms",
+						interval_ms, network.block_time_ms
+					),
+					suggestion: "poll at most once per block_time_ms to avoid empty polls"
+						.to_string(),
+				});
+			} else if interval_ms as u64
+				> network
+					.block_time_ms
+					.saturating_mul(MAX_BLOCKS_PER_POLL_INTERVAL)
+			{
+				warnings.push(LintWarning {
+					entity: LintEntity::Network,
+					name: network.slug.clone(),
+					field: "cron_schedule".to_string(),
+					code: LintCode::PollingSlowerThanBlockTime,
+					message: format!(
+						"polling interval of {}ms is more than {} times the block time of {}ms",
+						interval_ms, MAX_BLOCKS_PER_POLL_INTERVAL, network.block_time_ms
+					),
+					suggestion: format!(
+						"poll at least once per {} blocks to keep alerts timely",
+						MAX_BLOCKS_PER_POLL_INTERVAL
+					),
+				});
+			}
+		}
+	}
+
+	warnings
+}
+
+/// Reports the fields of a trigger or network that held plain secrets when it was loaded
+///
+/// Secrets are resolved to plain values on load, so the fields are the ones recorded by the
+/// secrets policy before resolution.
+fn lint_plaintext_secrets(entity: LintEntity, name: &str, warnings: &mut Vec<LintWarning>) {
+	for field in plaintext_secrets(entity, name) {
+		warnings.push(LintWarning {
+			entity,
+			name: name.to_string(),
+			field,
+			code: LintCode::PlaintextSecret,
+			message: "credential is stored in plain text in the configuration".to_string(),
+			suggestion: "use an environment or hashicorpcloudvault secret".to_string(),
+		});
+	}
+}
+
+/// Returns the ABIs attached to a monitor's addresses
+fn monitor_abis(monitor: &Monitor) -> Vec<&alloy::json_abi::JsonAbi> {
+	monitor
+		.addresses
+		.iter()
+		.filter_map(|address| match &address.contract_spec {
+			Some(ContractSpec::EVM(spec)) => Some(&**spec),
+			_ => None,
+		})
+		.collect()
+}
+
+/// Returns the argument names of the ABI items matching a signature
+///
+/// `None` means no ABI item matches the signature, while an empty result means the matching
+/// item has unnamed inputs and its arguments cannot be checked.
+fn abi_argument_names(
+	abis: &[&alloy::json_abi::JsonAbi],
+	signature: &str,
+	is_event: bool,
+) -> Option<Vec<String>> {
+	abis.iter().find_map(|abi| {
+		if is_event {
+			abi.events()
+				.find(|event| are_same_signature(&event.signature(), signature))
+				.map(|event| event.inputs.iter().map(|p| p.name.clone()).collect())
+		} else {
+			abi.functions()
+				.find(|function| are_same_signature(&function.signature(), signature))
+				.map(|function| function.inputs.iter().map(|p| p.name.clone()).collect())
+		}
+	})
+}
+
+/// Runs the monitor-level lints
+fn lint_monitor(monitor: &Monitor, warnings: &mut Vec<LintWarning>) {
+	let warn = |field: String, code: LintCode, message: String, suggestion: &str| LintWarning {
+		entity: LintEntity::Monitor,
+		name: monitor.name.clone(),
+		field,
+		code,
+		message,
+		suggestion: suggestion.to_string(),
+	};
+	let conditions = &monitor.match_conditions;
+	// Monitors of the network's metrics watch blocks, not addresses
+	let watches_addresses = conditions.network_metrics.is_none();
+
+	if watches_addresses
+		&& conditions.functions.is_empty()
+		&& conditions.events.is_empty()
+		&& conditions.transactions.is_empty()
+	{
+		warnings.push(warn(
+			"match_conditions".to_string(),
+			LintCode::EmptyMatchConditions,
+			"no conditions are defined, so every transaction touching the monitored addresses \
+			 matches"
+				.to_string(),
+			"add function, event or transaction conditions",
+		));
+	}
+
+	if watches_addresses && monitor.addresses.is_empty() {
+		warnings.push(warn(
+			"addresses".to_string(),
+			LintCode::MissingAddresses,
+			"no addresses are monitored, so the monitor never matches".to_string(),
+			"add the contract addresses to watch",
+		));
+	}
+
+	let mut seen_addresses: Vec<String> = Vec::new();
+	for (index, address) in monitor.addresses.iter().enumerate() {
+		let normalized = address.address.trim().to_lowercase();
+		if seen_addresses.contains(&normalized) {
+			warnings.push(warn(
+				format!("addresses[{}].address", index),
+				LintCode::DuplicateAddress,
+				format!("address {} is listed more than once", address.address),
+				"remove the duplicate entry",
+			));
+		} else {
+			seen_addresses.push(normalized);
+		}
+	}
+
+	let abis = monitor_abis(monitor);
+	let signature_conditions = conditions
+		.functions
+		.iter()
+		.enumerate()
+		.map(|(index, f)| ("functions", index, &f.signature, &f.expression, false))
+		.chain(
+			conditions
+				.events
+				.iter()
+				.enumerate()
+				.map(|(index, e)| ("events", index, &e.signature, &e.expression, true)),
+		);
+
+	let mut seen_conditions: Vec<(&str, String, &Option<String>)> = Vec::new();
+	for (kind, index, signature, expression, is_event) in signature_conditions {
+		let field = format!("match_conditions.{}[{}]", kind, index);
+
+		let key = (kind, signature.replace(char::is_whitespace, ""), expression);
+		if seen_conditions.contains(&key) {
+			warnings.push(warn(
+				format!("{}.signature", field),
+				LintCode::DuplicateCondition,
+				format!("condition {} is listed more than once", signature),
+				"remove the duplicate condition",
+			));
+		} else {
+			seen_conditions.push(key);
+		}
+
+		if abis.is_empty() {
+			continue;
+		}
+
+		let Some(argument_names) = abi_argument_names(&abis, signature, is_event) else {
+			warnings.push(warn(
+				format!("{}.signature", field),
+				LintCode::UnknownSignature,
+				format!("{} is not declared in the monitor's ABI", signature),
+				"check the signature against the contract ABI",
+			));
+			continue;
+		};
+
+		if argument_names.iter().any(|name| name.is_empty()) {
+			continue;
+		}
+
+		let Some(variables) = expression.as_deref().and_then(expression_variable_names) else {
+			continue;
+		};
+		for variable in variables {
+			if !argument_names.iter().any(|name| name == variable) {
+				warnings.push(warn(
+					format!("{}.expression", field),
+					LintCode::UnknownExpressionArgument,
+					format!("{} has no argument named '{}'", signature, variable),
+					&format!("use one of: {}", argument_names.join(", ")),
+				));
+			}
+		}
+	}
+
+	if monitor.triggers.is_empty() {
+		warnings.push(warn(
+			"triggers".to_string(),
+			LintCode::MonitorWithoutTriggers,
+			"no triggers are configured, so matches are not notified".to_string(),
+			"add at least one trigger",
+		));
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{
+		models::{
+			config::{network_plaintext_secrets, SecretsPolicy},
+			MatchConditions, MessageFormat, MonitorOwner, NetworkMetricsCondition,
+		},
+		utils::tests::builders::{
+			evm::monitor::MonitorBuilder, network::NetworkBuilder, trigger::TriggerBuilder,
+		},
+	};
+	use std::io::Write;
+	use tempfile::NamedTempFile;
+
+	fn erc20_abi() -> ContractSpec {
+		ContractSpec::EVM(crate::models::EVMContractSpec::from(serde_json::json!([
+			{
+				"type": "event",
+				"name": "Transfer",
+				"anonymous": false,
+				"inputs": [
+					{ "name": "from", "type": "address", "indexed": true },
+					{ "name": "to", "type": "address", "indexed": true },
+					{ "name": "value", "type": "uint256", "indexed": false }
+				]
+			},
+			{
+				"type": "function",
+				"name": "transfer",
+				"stateMutability": "nonpayable",
+				"inputs": [
+					{ "name": "to", "type": "address" },
+					{ "name": "amount", "type": "uint256" }
+				],
+				"outputs": [{ "name": "", "type": "bool" }]
+			}
+		])))
+	}
+
+	fn clean_monitor() -> Monitor {
+		MonitorBuilder::new()
+			.name("clean")
+			.address_with_spec(
+				"0xdAC17F958D2ee523a2206206994597C13D831ec7",
+				Some(erc20_abi()),
+			)
+			.event(
+				"Transfer(address,address,uint256)",
+				Some("value > 100".to_string()),
+			)
+			.function("transfer(address,uint256)", Some("amount > 5".to_string()))
+			.triggers(vec!["notify".to_string()])
+			.build()
+	}
+
+	fn clean_network() -> Network {
+		NetworkBuilder::new()
+			.slug("ethereum_mainnet")
+			.block_time_ms(12000)
+			.cron_schedule("0 */1 * * * *")
+			.build()
+	}
+
+	fn lint(monitors: Vec<Monitor>, networks: Vec<Network>) -> Vec<LintWarning> {
+		let triggers = HashMap::from([(
+			"notify".to_string(),
+			TriggerBuilder::new().name("notify").build(),
+		)]);
+		lint_monitors_and_triggers(monitors, networks, triggers)
+	}
+
+	fn lint_monitors_and_triggers(
+		monitors: Vec<Monitor>,
+		networks: Vec<Network>,
+		triggers: HashMap<String, Trigger>,
+	) -> Vec<LintWarning> {
+		lint_with_config(monitors, networks, triggers, &LintConfig::default())
+	}
+
+	fn lint_with_config(
+		monitors: Vec<Monitor>,
+		networks: Vec<Network>,
+		triggers: HashMap<String, Trigger>,
+		config: &LintConfig,
+	) -> Vec<LintWarning> {
+		let monitors = monitors
+			.into_iter()
+			.map(|m| (m.name.clone(), m))
+			.collect::<HashMap<_, _>>();
+		let networks = networks
+			.into_iter()
+			.map(|n| (n.slug.clone(), n))
+			.collect::<HashMap<_, _>>();
+		lint_configuration(&monitors, &networks, &triggers, config)
+	}
+
+	fn codes(warnings: &[LintWarning]) -> Vec<LintCode> {
+		warnings.iter().map(|w| w.code).collect()
+	}
+
+	#[test]
+	fn test_clean_config_has_no_warnings() {
+		let warnings = lint(vec![clean_monitor()], vec![clean_network()]);
+		assert!(warnings.is_empty(), "unexpected warnings: {:?}", warnings);
+	}
+
+	#[test]
+	fn test_empty_match_conditions() {
+		let monitor = MonitorBuilder::new()
+			.name("empty")
+			.triggers(vec!["notify".to_string()])
+			.build();
+		let warnings = lint(vec![monitor], vec![]);
+		assert_eq!(codes(&warnings), vec![LintCode::EmptyMatchConditions]);
+		assert_eq!(warnings[0].field, "match_conditions");
+		assert_eq!(warnings[0].name, "empty");
+	}
+
+	#[test]
+	fn test_missing_addresses() {
+		let mut monitor = clean_monitor();
+		monitor.addresses.clear();
+		let warnings = lint(vec![monitor], vec![]);
+		assert_eq!(codes(&warnings), vec![LintCode::MissingAddresses]);
+	}
+
+	#[test]
+	fn test_network_metrics_monitor_without_addresses() {
+		let mut monitor = clean_monitor();
+		monitor.addresses.clear();
+		monitor.match_conditions = MatchConditions {
+			network_metrics: Some(NetworkMetricsCondition {
+				expression: "base_fee > 150000000000".to_string(),
+				consecutive_blocks: 20,
+				cooldown_blocks: 0,
+				blob_base_fee_update_fraction: None,
+			}),
+			..Default::default()
+		};
+		assert!(lint(vec![monitor], vec![]).is_empty());
+	}
+
+	#[test]
+	fn test_duplicate_address() {
+		let mut monitor = clean_monitor();
+		let mut duplicate = monitor.addresses[0].clone();
+		duplicate.address = duplicate.address.to_lowercase();
+		monitor.addresses.push(duplicate);
+		let warnings = lint(vec![monitor], vec![]);
+		assert_eq!(codes(&warnings), vec![LintCode::DuplicateAddress]);
+		assert_eq!(warnings[0].field, "addresses[1].address");
+	}
+
+	#[test]
+	fn test_duplicate_condition() {
+		let mut monitor = clean_monitor();
+		let duplicate = monitor.match_conditions.events[0].clone();
+		monitor.match_conditions.events.push(duplicate);
+		let warnings = lint(vec![monitor], vec![]);
+		assert_eq!(codes(&warnings), vec![LintCode::DuplicateCondition]);
+		assert_eq!(warnings[0].field, "match_conditions.events[1].signature");
+	}
+
+	#[test]
+	fn test_unknown_signature() {
+		let mut monitor = clean_monitor();
+		monitor.match_conditions.events[0].signature = "Approval(address,address,uint256)".into();
+		let warnings = lint(vec![monitor], vec![]);
+		assert_eq!(codes(&warnings), vec![LintCode::UnknownSignature]);
+	}
+
+	#[test]
+	fn test_unknown_expression_argument() {
+		let mut monitor = clean_monitor();
+		monitor.match_conditions.functions[0].expression = Some("value > 5".to_string());
+		let warnings = lint(vec![monitor], vec![]);
+		assert_eq!(codes(&warnings), vec![LintCode::UnknownExpressionArgument]);
+		assert_eq!(
+			warnings[0].field,
+			"match_conditions.functions[0].expression"
+		);
+		assert_eq!(warnings[0].suggestion, "use one of: to, amount");
+	}
+
+	#[test]
+	fn test_expression_arguments_not_checked_without_abi() {
+		let monitor = MonitorBuilder::new()
+			.name("no_abi")
+			.event(
+				"Transfer(address,address,uint256)",
+				Some("unknown > 1".to_string()),
+			)
+			.triggers(vec!["notify".to_string()])
+			.build();
+		assert!(lint(vec![monitor], vec![]).is_empty());
+	}
+
+	#[test]
+	fn test_monitor_without_triggers() {
+		let mut monitor = clean_monitor();
+		monitor.triggers.clear();
+		let warnings = lint(vec![monitor], vec![]);
+		assert_eq!(
+			codes(&warnings),
+			vec![LintCode::MonitorWithoutTriggers, LintCode::UnusedTrigger]
+		);
+	}
+
+	#[test]
+	fn test_unused_trigger() {
+		let triggers = HashMap::from([
+			(
+				"notify".to_string(),
+				TriggerBuilder::new().name("notify").build(),
+			),
+			(
+				"orphan".to_string(),
+				TriggerBuilder::new().name("orphan").build(),
+			),
+		]);
+		let warnings = lint_monitors_and_triggers(vec![clean_monitor()], vec![], triggers);
+		assert_eq!(codes(&warnings), vec![LintCode::UnusedTrigger]);
+		assert_eq!(warnings[0].entity, LintEntity::Trigger);
+		assert_eq!(warnings[0].name, "orphan");
+	}
+
+	fn lint_trigger(trigger: Trigger) -> Vec<LintWarning> {
+		let mut monitor = clean_monitor();
+		monitor.triggers = vec![trigger.name.clone().into()];
+		let triggers = HashMap::from([(trigger.name.clone(), trigger)]);
+		lint_monitors_and_triggers(vec![monitor], vec![], triggers)
+	}
+
+	#[test]
+	fn test_thread_key_on_webhook() {
+		let trigger = TriggerBuilder::new()
+			.name("webhook")
+			.webhook("https://example.com/webhook")
+			.thread_key("${monitor.name}")
+			.build();
+		let warnings = lint_trigger(trigger);
+		assert_eq!(codes(&warnings), vec![LintCode::UnsupportedChannelOption]);
+		assert_eq!(warnings[0].entity, LintEntity::Trigger);
+		assert_eq!(warnings[0].name, "webhook");
+		assert_eq!(warnings[0].field, "message.thread_key");
+		assert!(warnings[0].message.contains("webhook notifications"));
+		assert!(warnings[0].message.contains("threading"));
+	}
+
+	#[test]
+	fn test_attachments_on_slack() {
+		let trigger = TriggerBuilder::new()
+			.name("slack")
+			.slack("https://hooks.slack.com/services/A/B/C")
+			.attachments(vec!["report.csv".to_string()])
+			.build();
+		let warnings = lint_trigger(trigger);
+		assert_eq!(codes(&warnings), vec![LintCode::UnsupportedChannelOption]);
+		assert_eq!(warnings[0].field, "message.attachments");
+		assert!(warnings[0].message.contains("slack notifications"));
+	}
+
+	#[test]
+	fn test_html_format_on_telegram() {
+		let trigger = TriggerBuilder::new()
+			.name("telegram")
+			.telegram("1234567890:ABCdefGHIjklMNOpqrSTUvwxYZ", "123", false)
+			.message_format(MessageFormat::Html)
+			.build();
+		let warnings = lint_trigger(trigger);
+		assert_eq!(codes(&warnings), vec![LintCode::UnsupportedChannelOption]);
+		assert_eq!(warnings[0].field, "message.format");
+		assert!(warnings[0].message.contains("telegram notifications"));
+		assert!(warnings[0].message.contains("HTML messages"));
+	}
+
+	#[test]
+	fn test_message_options_supported_by_email() {
+		let trigger = TriggerBuilder::new()
+			.name("email")
+			.email(
+				"smtp.example.com",
+				"user",
+				"pass",
+				"alerts@example.com",
+				vec!["ops@example.com"],
+			)
+			.message_format(MessageFormat::Html)
+			.thread_key("${monitor.name}")
+			.attachments(vec!["report.csv".to_string()])
+			.build();
+		let warnings = lint_trigger(trigger);
+		assert!(warnings.is_empty(), "unexpected warnings: {:?}", warnings);
+	}
+
+	#[test]
+	fn test_enforce_fails_on_denied_unsupported_channel_option() {
+		let trigger = TriggerBuilder::new()
+			.name("discord")
+			.discord("https://discord.com/api/webhooks/1/token")
+			.thread_key("${monitor.name}")
+			.build();
+		let warnings = lint_trigger(trigger);
+		let config = LintConfig {
+			deny: vec!["unsupported-channel-option".to_string()],
+			..Default::default()
+		};
+		let err = config.enforce(&warnings).unwrap_err();
+		assert!(matches!(err, ConfigError::ValidationError(_)));
+		assert!(err.to_string().contains("discord"));
+		assert!(err.to_string().contains("message.thread_key"));
+	}
+
+	#[test]
+	fn test_polling_faster_than_block_time() {
+		let network = NetworkBuilder::new()
+			.slug("fast")
+			.block_time_ms(12000)
+			.cron_schedule("*/5 * * * * *")
+			.build();
+		let warnings = lint(vec![clean_monitor()], vec![network]);
+		assert_eq!(codes(&warnings), vec![LintCode::PollingFasterThanBlockTime]);
+		assert_eq!(warnings[0].field, "cron_schedule");
+	}
+
+	#[test]
+	fn test_polling_slower_than_block_time() {
+		let network = NetworkBuilder::new()
+			.slug("slow")
+			.block_time_ms(12000)
+			.cron_schedule("0 */5 * * * *")
+			.build();
+		let warnings = lint(vec![clean_monitor()], vec![network]);
+		assert_eq!(codes(&warnings), vec![LintCode::PollingSlowerThanBlockTime]);
+		assert_eq!(warnings[0].field, "cron_schedule");
+
+		// Ten blocks per poll is still timely
+		let network = NetworkBuilder::new()
+			.slug("slow")
+			.block_time_ms(12000)
+			.cron_schedule("0 */2 * * * *")
+			.build();
+		assert!(lint(vec![clean_monitor()], vec![network]).is_empty());
+	}
+
+	#[test]
+	fn test_plaintext_secret_warns_when_allowed() {
+		let network = NetworkBuilder::new()
+			.slug("plaintext_network")
+			.block_time_ms(12000)
+			.cron_schedule("*/15 * * * * *")
+			.rpc_url("https://rpc.example.com")
+			.build();
+		SecretsPolicy::default()
+			.enforce(
+				LintEntity::Network,
+				"plaintext_network",
+				network_plaintext_secrets(&network),
+			)
+			.unwrap();
+
+		let warnings = lint(vec![clean_monitor()], vec![network]);
+		assert_eq!(codes(&warnings), vec![LintCode::PlaintextSecret]);
+		assert_eq!(warnings[0].entity, LintEntity::Network);
+		assert_eq!(warnings[0].name, "plaintext_network");
+		assert_eq!(warnings[0].field, "rpc_urls[0].url");
+		assert!(!warnings[0].to_string().contains("rpc.example.com"));
+	}
+
+	#[test]
+	fn test_missing_owner_only_when_required() {
+		let triggers = HashMap::from([(
+			"notify".to_string(),
+			TriggerBuilder::new().name("notify").build(),
+		)]);
+		let owned = MonitorBuilder::new()
+			.name("owned")
+			.address_with_spec(
+				"0xdAC17F958D2ee523a2206206994597C13D831ec7",
+				Some(erc20_abi()),
+			)
+			.event("Transfer(address,address,uint256)", None)
+			.triggers(vec!["notify".to_string()])
+			.owner(MonitorOwner {
+				team: "treasury".to_string(),
+				..Default::default()
+			})
+			.build();
+		let monitors = vec![clean_monitor(), owned];
+
+		// Owners are optional by default
+		let warnings = lint_with_config(
+			monitors.clone(),
+			vec![],
+			triggers.clone(),
+			&LintConfig::default(),
+		);
+		assert!(warnings.is_empty(), "unexpected warnings: {:?}", warnings);
+
+		let config = LintConfig {
+			require_owner: true,
+			..Default::default()
+		};
+		let warnings = lint_with_config(monitors, vec![], triggers, &config);
+		assert_eq!(codes(&warnings), vec![LintCode::MissingOwner]);
+		assert_eq!(warnings[0].name, "clean");
+		assert_eq!(warnings[0].field, "owner");
+		assert!(config.enforce(&warnings).is_ok());
+	}
+
+	#[test]
+	fn test_enforce_warns_without_deny() {
+		let mut monitor = clean_monitor();
+		monitor.triggers.clear();
+		let warnings = lint(vec![monitor], vec![]);
+		assert!(LintConfig::default().enforce(&warnings).is_ok());
+	}
+
+	#[test]
+	fn test_enforce_fails_on_denied_lint() {
+		let mut monitor = clean_monitor();
+		monitor.triggers.clear();
+		let warnings = lint(vec![monitor], vec![]);
+		let config = LintConfig {
+			deny: vec!["unused-trigger".to_string()],
+			..Default::default()
+		};
+		let err = config.enforce(&warnings).unwrap_err();
+		assert!(matches!(err, ConfigError::ValidationError(_)));
+		assert!(err.to_string().contains("unused-trigger"));
+		assert!(!err.to_string().contains("monitor-without-triggers"));
+	}
+
+	#[test]
+	fn test_load_lint_config() {
+		let mut file = NamedTempFile::new().unwrap();
+		write!(file, r#"{{"deny": ["duplicate-address"]}}"#).unwrap();
+		let config = LintConfig::load(Some(file.path())).unwrap();
+		assert!(config.is_denied(LintCode::DuplicateAddress));
+		assert!(!config.is_denied(LintCode::UnusedTrigger));
+		assert!(!config.require_owner);
+	}
+
+	#[test]
+	fn test_load_lint_config_require_owner() {
+		let mut file = NamedTempFile::new().unwrap();
+		write!(
+			file,
+			r#"{{"require_owner": true, "deny": ["missing-owner"]}}"#
+		)
+		.unwrap();
+		let config = LintConfig::load(Some(file.path())).unwrap();
+		assert!(config.require_owner);
+		assert!(config.is_denied(LintCode::MissingOwner));
+	}
+
+	#[test]
+	fn test_load_lint_config_missing_file() {
+		let config = LintConfig::load(Some(Path::new("does/not/exist.json"))).unwrap();
+		assert!(config.deny.is_empty());
+	}
+
+	#[test]
+	fn test_load_lint_config_unknown_code() {
+		let mut file = NamedTempFile::new().unwrap();
+		write!(file, r#"{{"deny": ["not-a-lint"]}}"#).unwrap();
+		let result = LintConfig::load(Some(file.path()));
+		assert!(matches!(result, Err(ConfigError::ValidationError(_))));
+	}
+
+	#[test]
+	fn test_lint_codes_round_trip() {
+		for code in LintCode::ALL {
+			assert_eq!(LintCode::from_code(code.as_str()), Some(code));
+		}
+	}
+}
@@ -1,19 +1,36 @@
 //! Configuration loading and validation.
 //!
 //! This module provides traits and implementations for loading and validating
-//! configuration files for networks, monitors, and triggers.
+//! configuration files for contracts, networks, monitors, triggers, and trigger groups.
 
 #![allow(clippy::result_large_err)]
 
 use async_trait::async_trait;
 use std::path::Path;
 
+mod content_hash;
+mod contract_config;
+mod diff;
 mod error;
+mod lint;
 mod monitor_config;
 mod network_config;
+mod presets;
+mod schema;
+mod secrets_policy;
 mod trigger_config;
+mod trigger_group_config;
 
+pub use content_hash::{content_hash, ContentHash};
+pub use diff::{redact_secrets, ConfigDiff, ConfigSnapshot, EntryDiff, FieldChange, ModifiedEntry};
 pub use error::ConfigError;
+pub use lint::{lint_configuration, LintCode, LintConfig, LintEntity, LintWarning};
+pub use presets::{find_preset, Preset, PresetEvent, PRESETS};
+pub use schema::{config_schemas, write_config_schemas, ConfigSchemaKind};
+pub use secrets_policy::{
+	network_plaintext_secrets, plaintext_secrets, record_plaintext_secrets,
+	trigger_plaintext_secrets, SecretsPolicy, SECRETS_ALLOW_PLAIN_ENV,
+};
 
 /// Common interface for loading configuration files
 #[async_trait]
@@ -4,13 +4,38 @@
 //! allowing monitors to be loaded from JSON files.
 
 use crate::{
-	models::{config::error::ConfigError, ConfigLoader, Monitor, SecretValue},
-	services::trigger::validate_script_config,
+	models::{
+		config::error::ConfigError, BaselineAggregate, ConfigLoader, Enrichment, Monitor,
+		NotificationBudget, SecretValue,
+	},
+	services::{
+		filter::{expression_variable_names, network_metric},
+		trigger::validate_script_config,
+	},
 	utils::normalize_string,
 };
 use async_trait::async_trait;
+use chrono::FixedOffset;
 use futures::TryStreamExt;
-use std::{collections::HashMap, fs, path::Path};
+use std::{
+	collections::{HashMap, HashSet},
+	fs,
+	path::Path,
+	str::FromStr,
+};
+
+impl NotificationBudget {
+	/// Returns the UTC offset of the day boundary at which the budget resets
+	///
+	/// Accepts "UTC", "Z" or an offset in the form "+HH:MM" / "-HH:MM".
+	/// Returns None if the configured offset cannot be parsed.
+	pub fn offset(&self) -> Option<FixedOffset> {
+		match self.utc_offset.as_deref() {
+			None | Some("UTC") | Some("Z") => FixedOffset::east_opt(0),
+			Some(offset) => FixedOffset::from_str(offset).ok(),
+		}
+	}
+}
 
 #[async_trait]
 impl ConfigLoader for Monitor {
@@ -172,6 +197,215 @@ impl ConfigLoader for Monitor {
 			));
 		}
 
+		// Validate dedup group
+		if self
+			.dedup_group
+			.as_ref()
+			.is_some_and(|group| group.trim().is_empty())
+		{
+			return Err(ConfigError::validation_error(
+				"dedup_group must not be empty when specified",
+				None,
+				None,
+			));
+		}
+
+		// Validate rollup group
+		if self
+			.rollup_group
+			.as_ref()
+			.is_some_and(|group| group.trim().is_empty())
+		{
+			return Err(ConfigError::validation_error(
+				"rollup_group must not be empty when specified",
+				None,
+				None,
+			));
+		}
+
+		// Validate owner
+		if let Some(owner) = &self.owner {
+			if owner.team.trim().is_empty() {
+				return Err(ConfigError::validation_error(
+					"owner.team must not be empty when an owner is specified",
+					None,
+					None,
+				));
+			}
+			if let Some(runbook_url) = &owner.runbook_url {
+				let is_http_url = url::Url::parse(runbook_url)
+					.is_ok_and(|url| matches!(url.scheme(), "http" | "https") && url.has_host());
+				if !is_http_url {
+					return Err(ConfigError::validation_error(
+						format!(
+							"Invalid owner.runbook_url '{}' (expected an http or https URL)",
+							runbook_url
+						),
+						None,
+						None,
+					));
+				}
+			}
+		}
+
+		// Validate notification budget
+		if let Some(budget) = &self.notification_budget {
+			if budget.max_per_day == 0 {
+				return Err(ConfigError::validation_error(
+					"notification_budget.max_per_day must be greater than 0",
+					None,
+					None,
+				));
+			}
+			if budget.offset().is_none() {
+				return Err(ConfigError::validation_error(
+					format!(
+						"Invalid notification_budget utc_offset '{}' (expected UTC or +HH:MM)",
+						budget.utc_offset.as_deref().unwrap_or_default()
+					),
+					None,
+					None,
+				));
+			}
+			let trigger_names = self.trigger_names();
+			if let Some(unknown) = budget
+				.record_triggers
+				.iter()
+				.find(|name| !trigger_names.contains(name))
+			{
+				return Err(ConfigError::validation_error(
+					format!(
+						"notification_budget.record_triggers references '{}', which is not a \
+						 trigger of the monitor",
+						unknown
+					),
+					None,
+					None,
+				));
+			}
+		}
+
+		// Validate network views
+		if self
+			.network_views
+			.as_ref()
+			.is_some_and(|views| views.divergence_blocks == Some(0))
+		{
+			return Err(ConfigError::validation_error(
+				"network_views.divergence_blocks must be greater than 0",
+				None,
+				None,
+			));
+		}
+
+		// Validate decoding failure alert
+		if self
+			.alert_on_decode_failure
+			.as_ref()
+			.is_some_and(|alert| alert.threshold == 0)
+		{
+			return Err(ConfigError::validation_error(
+				"alert_on_decode_failure.threshold must be greater than 0",
+				None,
+				None,
+			));
+		}
+
+		// Validate sender activity condition
+		if self
+			.match_conditions
+			.sender_activity
+			.as_ref()
+			.is_some_and(|condition| condition.stuck_after_secs == 0)
+		{
+			return Err(ConfigError::validation_error(
+				"sender_activity.stuck_after_secs must be greater than 0",
+				None,
+				None,
+			));
+		}
+
+		// Validate baseline condition
+		if let Some(baseline) = &self.match_conditions.baseline {
+			if baseline.short_window_blocks == 0 {
+				return Err(ConfigError::validation_error(
+					"baseline.short_window_blocks must be greater than 0",
+					None,
+					None,
+				));
+			}
+			if baseline.long_window_blocks <= baseline.short_window_blocks {
+				return Err(ConfigError::validation_error(
+					"baseline.long_window_blocks must be greater than baseline.short_window_blocks",
+					None,
+					None,
+				));
+			}
+			if !(baseline.factor.is_finite() && baseline.factor > 0.0) {
+				return Err(ConfigError::validation_error(
+					"baseline.factor must be a positive number",
+					None,
+					None,
+				));
+			}
+			if !(baseline.floor.is_finite() && baseline.floor > 0.0) {
+				return Err(ConfigError::validation_error(
+					"baseline.floor must be a positive number",
+					None,
+					None,
+				));
+			}
+			if baseline.aggregate == BaselineAggregate::Sum && baseline.amount.is_none() {
+				return Err(ConfigError::validation_error(
+					"baseline.amount is required by the sum aggregate",
+					None,
+					None,
+				));
+			}
+		}
+
+		// Validate network metrics condition
+		if let Some(condition) = &self.match_conditions.network_metrics {
+			let conditions = &self.match_conditions;
+			if !self.addresses.is_empty()
+				|| !conditions.functions.is_empty()
+				|| !conditions.events.is_empty()
+				|| !conditions.transactions.is_empty()
+				|| conditions.sender_activity.is_some()
+				|| conditions.baseline.is_some()
+			{
+				return Err(ConfigError::validation_error(
+					"network_metrics monitors must not have addresses or other match conditions",
+					None,
+					None,
+				));
+			}
+			if condition.consecutive_blocks == 0 {
+				return Err(ConfigError::validation_error(
+					"network_metrics.consecutive_blocks must be greater than 0",
+					None,
+					None,
+				));
+			}
+			let Some(names) = expression_variable_names(&condition.expression) else {
+				return Err(ConfigError::validation_error(
+					format!(
+						"Invalid network_metrics.expression '{}'",
+						condition.expression
+					),
+					None,
+					None,
+				));
+			};
+			if let Some(name) = names.iter().find(|name| network_metric(name).is_none()) {
+				return Err(ConfigError::validation_error(
+					format!("Unknown metric '{}' in network_metrics.expression", name),
+					None,
+					None,
+				));
+			}
+		}
+
 		// Validate function signatures
 		for func in &self.match_conditions.functions {
 			if !func.signature.contains('(') || !func.signature.contains(')') {
@@ -203,6 +437,80 @@ impl ConfigLoader for Monitor {
 			)?;
 		}
 
+		// Validate enrichments
+		let mut enrichment_names = HashSet::new();
+		for enrichment in &self.enrichments {
+			if enrichment.name().trim().is_empty() {
+				return Err(ConfigError::validation_error(
+					"Enrichment name is required",
+					None,
+					None,
+				));
+			}
+			if !enrichment_names.insert(enrichment.name()) {
+				return Err(ConfigError::validation_error(
+					format!("Duplicate enrichment name: {}", enrichment.name()),
+					None,
+					None,
+				));
+			}
+			match enrichment {
+				Enrichment::Http(http) => {
+					if !http.url.starts_with("http://") && !http.url.starts_with("https://") {
+						return Err(ConfigError::validation_error(
+							format!(
+								"Enrichment '{}' URL must start with http:// or https://",
+								http.name
+							),
+							None,
+							None,
+						));
+					}
+					if http.variables.is_empty() {
+						return Err(ConfigError::validation_error(
+							format!(
+								"Enrichment '{}' must extract at least one variable",
+								http.name
+							),
+							None,
+							None,
+						));
+					}
+					if let Some((variable, pointer)) = http
+						.variables
+						.iter()
+						.find(|(_, pointer)| !pointer.is_empty() && !pointer.starts_with('/'))
+					{
+						return Err(ConfigError::validation_error(
+							format!(
+								"Enrichment '{}' variable '{}' has an invalid JSON pointer '{}'",
+								http.name, variable, pointer
+							),
+							None,
+							None,
+						));
+					}
+					if http.timeout_ms == 0 {
+						return Err(ConfigError::validation_error(
+							format!(
+								"Enrichment '{}' timeout_ms must be greater than 0",
+								http.name
+							),
+							None,
+							None,
+						));
+					}
+				}
+				Enrichment::Script(script) => {
+					validate_script_config(
+						&script.script_path,
+						&script.language,
+						&script.timeout_ms,
+					)?;
+				}
+			}
+		}
+
 		// Log a warning if the monitor uses an insecure protocol
 		self.validate_protocol();
 
@@ -213,6 +521,19 @@ impl ConfigLoader for Monitor {
 	///
 	/// Returns if safe, or logs a warning message if unsafe.
 	fn validate_protocol(&self) {
+		for enrichment in &self.enrichments {
+			if let Enrichment::Http(http) = enrichment {
+				if http.url.starts_with("http://") {
+					tracing::warn!(
+						"Monitor '{}' enrichment '{}' URL uses an insecure protocol: {}",
+						self.name,
+						http.name,
+						http.url
+					);
+				}
+			}
+		}
+
 		// Check script file permissions on Unix systems
 		#[cfg(unix)]
 		for condition in &self.trigger_conditions {
@@ -261,7 +582,11 @@ impl ConfigLoader for Monitor {
 mod tests {
 	use super::*;
 	use crate::{
-		models::core::{ScriptLanguage, TransactionStatus},
+		models::core::{
+			BaselineCondition, DecodeFailureAlert, HttpEnrichment, MonitorOwner,
+			NetworkMetricsCondition, NetworkViewsConfig, ScriptLanguage, SenderActivityCondition,
+			SenderActivityKind, TransactionStatus, TriggerReference,
+		},
 		utils::tests::builders::evm::monitor::MonitorBuilder,
 	};
 	use std::collections::HashMap;
@@ -310,6 +635,63 @@ mod tests {
 		assert_eq!(monitor.name, "TestMonitor");
 	}
 
+	#[tokio::test]
+	async fn test_load_monitor_with_trigger_overrides() {
+		let temp_dir = TempDir::new().unwrap();
+		let file_path = temp_dir.path().join("override_monitor.json");
+
+		let config = r#"{
+			"name": "TestMonitor",
+			"networks": ["ethereum_mainnet"],
+			"paused": false,
+			"addresses": [
+				{
+					"address": "0x0000000000000000000000000000000000000000",
+					"contract_spec": null
+				}
+			],
+			"match_conditions": {
+				"functions": [],
+				"events": [],
+				"transactions": []
+			},
+			"trigger_conditions": [],
+			"triggers": [
+				"plain_trigger",
+				{
+					"name": "slack_security",
+					"message": { "body": "Treasury moved ${value}" },
+					"variables": { "team": "security" }
+				}
+			]
+		}"#;
+
+		fs::write(&file_path, config).unwrap();
+
+		let monitor = Monitor::load_from_path(&file_path).await.unwrap();
+		assert_eq!(
+			monitor.trigger_names(),
+			vec!["plain_trigger", "slack_security"]
+		);
+		assert_eq!(
+			monitor.triggers[0],
+			TriggerReference::Name("plain_trigger".to_string())
+		);
+
+		let reference = monitor.trigger_reference("slack_security").unwrap();
+		let message = reference.message().unwrap();
+		assert_eq!(message.title, None);
+		assert_eq!(message.body.as_deref(), Some("Treasury moved ${value}"));
+		assert_eq!(
+			reference
+				.variables()
+				.unwrap()
+				.get("team")
+				.map(String::as_str),
+			Some("security")
+		);
+	}
+
 	#[tokio::test]
 	async fn test_load_invalid_monitor() {
 		let temp_dir = TempDir::new().unwrap();
@@ -424,6 +806,417 @@ mod tests {
 		assert!(invalid_monitor.validate().is_err());
 	}
 
+	#[test]
+	fn test_validate_monitor_dedup_group() {
+		let valid_monitor = MonitorBuilder::new()
+			.name("TestMonitor")
+			.networks(vec!["ethereum_mainnet".to_string()])
+			.dedup_group("treasury")
+			.build();
+		assert!(valid_monitor.validate().is_ok());
+
+		let invalid_monitor = MonitorBuilder::new()
+			.name("TestMonitor")
+			.networks(vec!["ethereum_mainnet".to_string()])
+			.dedup_group(" ")
+			.build();
+		assert!(invalid_monitor.validate().is_err());
+	}
+
+	#[test]
+	fn test_validate_monitor_rollup_group() {
+		let valid_monitor = MonitorBuilder::new()
+			.name("TestMonitor")
+			.networks(vec!["ethereum_mainnet".to_string()])
+			.rollup_group("protocol_health")
+			.build();
+		assert!(valid_monitor.validate().is_ok());
+
+		let invalid_monitor = MonitorBuilder::new()
+			.name("TestMonitor")
+			.networks(vec!["ethereum_mainnet".to_string()])
+			.rollup_group("")
+			.build();
+		assert!(invalid_monitor.validate().is_err());
+	}
+
+	#[test]
+	fn test_validate_monitor_notification_budget() {
+		let budget = NotificationBudget {
+			max_per_day: 50,
+			overflow: Default::default(),
+			utc_offset: Some("+02:00".to_string()),
+			digest_part_delay_ms: None,
+			record_triggers: vec![],
+		};
+		let valid_monitor = MonitorBuilder::new()
+			.notification_budget(budget.clone())
+			.build();
+		assert!(valid_monitor.validate().is_ok());
+
+		let zero_budget = MonitorBuilder::new()
+			.notification_budget(NotificationBudget {
+				max_per_day: 0,
+				..budget.clone()
+			})
+			.build();
+		assert!(zero_budget.validate().is_err());
+
+		let invalid_offset = MonitorBuilder::new()
+			.notification_budget(NotificationBudget {
+				utc_offset: Some("Europe/Berlin".to_string()),
+				..budget.clone()
+			})
+			.build();
+		assert!(invalid_offset.validate().is_err());
+
+		let unknown_record_trigger = MonitorBuilder::new()
+			.triggers(vec!["webhook".to_string()])
+			.notification_budget(NotificationBudget {
+				record_triggers: vec!["archive".to_string()],
+				..budget.clone()
+			})
+			.build();
+		assert!(unknown_record_trigger.validate().is_err());
+
+		let record_trigger = MonitorBuilder::new()
+			.triggers(vec!["webhook".to_string()])
+			.notification_budget(NotificationBudget {
+				record_triggers: vec!["webhook".to_string()],
+				..budget
+			})
+			.build();
+		assert!(record_trigger.validate().is_ok());
+	}
+
+	#[test]
+	fn test_validate_monitor_network_views() {
+		let valid_monitor = MonitorBuilder::new()
+			.network_views(NetworkViewsConfig {
+				dedup: false,
+				divergence_blocks: Some(5),
+				divergence_triggers: vec![],
+			})
+			.build();
+		assert!(valid_monitor.validate().is_ok());
+
+		let zero_blocks = MonitorBuilder::new()
+			.network_views(NetworkViewsConfig {
+				divergence_blocks: Some(0),
+				..Default::default()
+			})
+			.build();
+		assert!(zero_blocks.validate().is_err());
+
+		let views: NetworkViewsConfig = serde_json::from_str("{}").unwrap();
+		assert!(views.dedup);
+	}
+
+	#[test]
+	fn test_validate_monitor_alert_on_decode_failure() {
+		let alert: DecodeFailureAlert = serde_json::from_str("{}").unwrap();
+		assert_eq!(alert.threshold, 10);
+		assert!(alert.triggers.is_empty());
+
+		let valid_monitor = MonitorBuilder::new().alert_on_decode_failure(alert).build();
+		assert!(valid_monitor.validate().is_ok());
+
+		let zero_threshold = MonitorBuilder::new()
+			.alert_on_decode_failure(DecodeFailureAlert {
+				threshold: 0,
+				triggers: vec![],
+			})
+			.build();
+		assert!(zero_threshold.validate().is_err());
+	}
+
+	#[tokio::test]
+	async fn test_load_monitor_with_and_without_owner() {
+		let temp_dir = TempDir::new().unwrap();
+		let config = |owner: &str| {
+			format!(
+				r#"{{
+					"name": "TestMonitor",
+					"networks": ["ethereum_mainnet"],
+					"paused": false,
+					"addresses": [],
+					"match_conditions": {{"functions": [], "events": [], "transactions": []}},
+					"trigger_conditions": [],
+					"triggers": []
+					{}
+				}}"#,
+				owner
+			)
+		};
+		let with_owner = temp_dir.path().join("with_owner.json");
+		fs::write(
+			&with_owner,
+			config(
+				r##", "owner": {"team": "treasury", "contact": "#treasury-oncall", "runbook_url": "https://runbooks.example.com/withdrawals"}"##,
+			),
+		)
+		.unwrap();
+		let without_owner = temp_dir.path().join("without_owner.json");
+		fs::write(&without_owner, config("")).unwrap();
+
+		let monitor = Monitor::load_from_path(&with_owner).await.unwrap();
+		assert_eq!(
+			monitor.owner,
+			Some(MonitorOwner {
+				team: "treasury".to_string(),
+				contact: Some("#treasury-oncall".to_string()),
+				runbook_url: Some("https://runbooks.example.com/withdrawals".to_string()),
+			})
+		);
+		assert_eq!(monitor.owner_team(), "treasury");
+
+		let monitor = Monitor::load_from_path(&without_owner).await.unwrap();
+		assert_eq!(monitor.owner, None);
+		assert_eq!(monitor.owner_team(), "");
+	}
+
+	#[test]
+	fn test_validate_monitor_owner() {
+		let owner = MonitorOwner {
+			team: "treasury".to_string(),
+			contact: None,
+			runbook_url: Some("https://runbooks.example.com/withdrawals".to_string()),
+		};
+		let valid_monitor = MonitorBuilder::new().owner(owner.clone()).build();
+		assert!(valid_monitor.validate().is_ok());
+
+		let empty_team = MonitorBuilder::new()
+			.owner(MonitorOwner {
+				team: " ".to_string(),
+				..owner.clone()
+			})
+			.build();
+		assert!(empty_team.validate().is_err());
+
+		for runbook_url in [
+			"runbooks/withdrawals",
+			"ftp://runbooks.example.com",
+			"https://",
+		] {
+			let invalid_url = MonitorBuilder::new()
+				.owner(MonitorOwner {
+					runbook_url: Some(runbook_url.to_string()),
+					..owner.clone()
+				})
+				.build();
+			let err = invalid_url.validate().unwrap_err();
+			assert!(
+				err.to_string().contains("owner.runbook_url"),
+				"{}",
+				runbook_url
+			);
+		}
+	}
+
+	#[test]
+	fn test_validate_monitor_sender_activity() {
+		let condition = SenderActivityCondition {
+			kinds: vec![SenderActivityKind::Stuck],
+			stuck_after_secs: 600,
+			expression: None,
+		};
+		let valid_monitor = MonitorBuilder::new()
+			.sender_activity(condition.clone())
+			.build();
+		assert!(valid_monitor.validate().is_ok());
+
+		let invalid_monitor = MonitorBuilder::new()
+			.sender_activity(SenderActivityCondition {
+				stuck_after_secs: 0,
+				..condition
+			})
+			.build();
+		assert!(invalid_monitor.validate().is_err());
+	}
+
+	#[test]
+	fn test_validate_monitor_baseline() {
+		let condition = BaselineCondition {
+			aggregate: BaselineAggregate::Count,
+			amount: None,
+			short_window_blocks: 100,
+			long_window_blocks: 50_400,
+			factor: 5.0,
+			warmup_blocks: None,
+			floor: 1.0,
+		};
+		let with_baseline = |condition: BaselineCondition| {
+			MonitorBuilder::new().baseline(condition).build().validate()
+		};
+		assert!(with_baseline(condition.clone()).is_ok());
+		assert!(with_baseline(BaselineCondition {
+			short_window_blocks: 0,
+			..condition.clone()
+		})
+		.is_err());
+		assert!(with_baseline(BaselineCondition {
+			long_window_blocks: 100,
+			..condition.clone()
+		})
+		.is_err());
+		assert!(with_baseline(BaselineCondition {
+			factor: 0.0,
+			..condition.clone()
+		})
+		.is_err());
+		assert!(with_baseline(BaselineCondition {
+			aggregate: BaselineAggregate::Sum,
+			..condition.clone()
+		})
+		.is_err());
+		assert!(with_baseline(BaselineCondition {
+			aggregate: BaselineAggregate::Sum,
+			amount: Some("value".to_string()),
+			..condition
+		})
+		.is_ok());
+	}
+
+	#[test]
+	fn test_validate_monitor_network_metrics() {
+		let condition = NetworkMetricsCondition {
+			expression: "base_fee > 150000000000 && gas_used_ratio > 0.9".to_string(),
+			consecutive_blocks: 20,
+			cooldown_blocks: 100,
+			blob_base_fee_update_fraction: None,
+		};
+		let with_condition = |condition: NetworkMetricsCondition| {
+			MonitorBuilder::new()
+				.addresses(vec![])
+				.network_metrics(condition)
+				.build()
+				.validate()
+		};
+		assert!(with_condition(condition.clone()).is_ok());
+		assert!(with_condition(NetworkMetricsCondition {
+			consecutive_blocks: 0,
+			..condition.clone()
+		})
+		.is_err());
+		assert!(with_condition(NetworkMetricsCondition {
+			expression: "base_fee >".to_string(),
+			..condition.clone()
+		})
+		.is_err());
+		assert!(with_condition(NetworkMetricsCondition {
+			expression: "priority_fee > 1".to_string(),
+			..condition.clone()
+		})
+		.is_err());
+
+		// Network metrics monitors watch no address
+		let with_address = MonitorBuilder::new().network_metrics(condition).build();
+		assert!(with_address.validate().is_err());
+	}
+
+	#[test]
+	#[traced_test]
+	fn test_validate_monitor_http_enrichments() {
+		let enrichment = HttpEnrichment {
+			name: "customer".to_string(),
+			url: "https://crm.example.com/addresses/${transaction.from}".to_string(),
+			variables: HashMap::from([("customer_id".to_string(), "/id".to_string())]),
+			timeout_ms: 1_000,
+			cache_ttl_secs: Some(300),
+			required: false,
+		};
+		let with_enrichment = |enrichment: HttpEnrichment| {
+			MonitorBuilder::new()
+				.enrichment(Enrichment::Http(enrichment))
+				.build()
+		};
+		assert!(with_enrichment(enrichment.clone()).validate().is_ok());
+
+		let duplicate = MonitorBuilder::new()
+			.enrichment(Enrichment::Http(enrichment.clone()))
+			.enrichment(Enrichment::Http(enrichment.clone()))
+			.build();
+		assert!(duplicate.validate().is_err());
+
+		let invalid_url = with_enrichment(HttpEnrichment {
+			url: "crm.example.com".to_string(),
+			..enrichment.clone()
+		});
+		assert!(invalid_url.validate().is_err());
+
+		let invalid_pointer = with_enrichment(HttpEnrichment {
+			variables: HashMap::from([("customer_id".to_string(), "id".to_string())]),
+			..enrichment.clone()
+		});
+		assert!(invalid_pointer.validate().is_err());
+
+		let no_variables = with_enrichment(HttpEnrichment {
+			variables: HashMap::new(),
+			..enrichment.clone()
+		});
+		assert!(no_variables.validate().is_err());
+
+		let insecure = with_enrichment(HttpEnrichment {
+			url: "http://crm.example.com".to_string(),
+			..enrichment
+		});
+		assert!(insecure.validate().is_ok());
+		assert!(logs_contain(
+			"enrichment 'customer' URL uses an insecure protocol"
+		));
+	}
+
+	#[test]
+	fn test_deserialize_monitor_enrichments() {
+		let monitor: Monitor = serde_json::from_value(serde_json::json!({
+			"name": "TestMonitor",
+			"networks": ["ethereum_mainnet"],
+			"paused": false,
+			"addresses": [],
+			"match_conditions": {"functions": [], "events": [], "transactions": []},
+			"trigger_conditions": [],
+			"triggers": [],
+			"enrichments": [
+				{
+					"type": "http",
+					"name": "price",
+					"url": "https://prices.example.com/usdc",
+					"variables": {"price": "/usd"}
+				},
+				{
+					"type": "script",
+					"name": "risk",
+					"script_path": "config/filters/risk.py",
+					"language": "Python",
+					"timeout_ms": 1000,
+					"required": true
+				}
+			]
+		}))
+		.unwrap();
+
+		let Enrichment::Http(http) = &monitor.enrichments[0] else {
+			panic!("expected an HTTP enrichment");
+		};
+		assert_eq!(
+			http.timeout_ms,
+			crate::models::DEFAULT_ENRICHMENT_TIMEOUT_MS
+		);
+		assert_eq!(http.cache_ttl_secs, None);
+		assert!(!monitor.enrichments[0].required());
+		assert_eq!(monitor.enrichments[1].name(), "risk");
+		assert!(monitor.enrichments[1].required());
+
+		let unknown_field = serde_json::from_value::<Enrichment>(serde_json::json!({
+			"type": "http",
+			"name": "price",
+			"url": "https://prices.example.com/usdc",
+			"variables": {"price": "/usd"},
+			"retries": 3
+		}));
+		assert!(unknown_field.is_err());
+	}
+
 	#[test]
 	fn test_validate_monitor_with_trigger_conditions() {
 		// Create a temporary directory and script file
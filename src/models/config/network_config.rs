@@ -3,14 +3,95 @@
 //! This module implements the ConfigLoader trait for Network configurations,
 //! allowing network definitions to be loaded from JSON files.
 
+use alloy::primitives::Address;
 use async_trait::async_trait;
+use chrono::{DateTime, FixedOffset, TimeDelta, TimeZone, Utc};
+use chrono_tz::Tz;
+use rust_decimal::Decimal;
 use std::{collections::HashMap, path::Path, str::FromStr};
 
 use crate::{
-	models::{config::error::ConfigError, BlockChainType, ConfigLoader, Network, SecretValue},
-	utils::{get_cron_interval_ms, normalize_string},
+	models::{
+		config::{error::ConfigError, network_plaintext_secrets, LintEntity, SecretsPolicy},
+		BlockChainType, ConfigLoader, MaintenanceWindow, Network, PriceOracleConfig, PriceSource,
+		SecretValue, NATIVE_TOKEN,
+	},
+	utils::{get_cron_interval_ms, normalize_cron_schedule, normalize_string},
 };
 
+/// Longest duration a single maintenance window may last (7 days)
+const MAX_MAINTENANCE_WINDOW_DURATION_MS: u64 = 7 * 24 * 60 * 60 * 1000;
+
+/// Most decimals a priced token may have, keeping USD conversions within 512 bits
+const MAX_PRICED_TOKEN_DECIMALS: u8 = 36;
+
+impl MaintenanceWindow {
+	/// Returns the UTC offset the window's cron expression is evaluated in
+	///
+	/// Accepts "UTC", "Z" or an offset in the form "+HH:MM" / "-HH:MM".
+	/// Returns None if the configured offset cannot be parsed.
+	pub fn offset(&self) -> Option<FixedOffset> {
+		match self.utc_offset.as_deref() {
+			None | Some("UTC") | Some("Z") => FixedOffset::east_opt(0),
+			Some(offset) => FixedOffset::from_str(offset).ok(),
+		}
+	}
+
+	/// Returns the IANA time zone the window's cron expression is evaluated in, if any
+	///
+	/// Returns None if no time zone is configured or it is not a known IANA name.
+	pub fn time_zone(&self) -> Option<Tz> {
+		self.timezone
+			.as_deref()
+			.and_then(|name| Tz::from_str(name).ok())
+	}
+
+	/// Checks whether the window is active at the given point in time
+	///
+	/// A window is active if one of its cron start times falls within
+	/// `(now - duration_ms, now]`. Start times are computed in `timezone` if set, so a window
+	/// keeps its local start time across daylight saving time changes, and in `utc_offset`
+	/// otherwise.
+	///
+	/// # Arguments
+	/// * `now` - The point in time to evaluate the window at
+	///
+	/// # Returns
+	/// * `bool` - True if the window is active, false otherwise (including invalid configuration)
+	pub fn is_active_at(&self, now: DateTime<Utc>) -> bool {
+		let (Ok(schedule), Some(duration)) = (
+			cron::Schedule::from_str(&self.cron_schedule),
+			i64::try_from(self.duration_ms)
+				.ok()
+				.and_then(TimeDelta::try_milliseconds),
+		) else {
+			return false;
+		};
+
+		match (&self.timezone, self.time_zone(), self.offset()) {
+			(Some(_), Some(time_zone), _) => {
+				window_started(&schedule, now.with_timezone(&time_zone), duration)
+			}
+			(None, _, Some(offset)) => {
+				window_started(&schedule, now.with_timezone(&offset), duration)
+			}
+			_ => false,
+		}
+	}
+}
+
+/// Checks whether a cron start time falls within `(now - duration, now]`
+fn window_started<Z: TimeZone>(
+	schedule: &cron::Schedule,
+	now: DateTime<Z>,
+	duration: TimeDelta,
+) -> bool {
+	schedule
+		.after(&(now.clone() - duration))
+		.next()
+		.is_some_and(|start| start <= now)
+}
+
 impl Network {
 	/// Calculates the recommended minimum number of past blocks to maintain for this network.
 	///
@@ -32,6 +113,32 @@ impl Network {
 		let blocks_per_cron = cron_interval_ms / self.block_time_ms;
 		blocks_per_cron + self.confirmation_blocks + 1
 	}
+
+	/// Normalizes the cron schedules of the network and its maintenance windows
+	///
+	/// Equivalent spellings of a schedule, differing in whitespace or in a trailing `*` year
+	/// field, are stored in the same form, so that they compare equal across reloads.
+	pub fn normalize_schedules(&mut self) {
+		self.cron_schedule = normalize_cron_schedule(&self.cron_schedule);
+		for window in self.maintenance_windows.iter_mut().flatten() {
+			window.cron_schedule = normalize_cron_schedule(&window.cron_schedule);
+		}
+	}
+
+	/// Returns the maintenance window active at the given point in time, if any
+	///
+	/// # Arguments
+	/// * `now` - The point in time to evaluate the windows at
+	///
+	/// # Returns
+	/// * `Option<&MaintenanceWindow>` - The first active window, or None
+	pub fn active_maintenance_window(&self, now: DateTime<Utc>) -> Option<&MaintenanceWindow> {
+		self.maintenance_windows
+			.as_deref()
+			.unwrap_or_default()
+			.iter()
+			.find(|window| window.is_active_at(now))
+	}
 }
 
 #[async_trait]
@@ -118,6 +225,9 @@ impl ConfigLoader for Network {
 			pairs.push((name, network));
 		}
 
+		let networks: Vec<&Network> = pairs.iter().map(|(_, network)| network).collect();
+		Self::validate_groups(&networks)?;
+
 		Ok(T::from_iter(pairs))
 	}
 
@@ -146,10 +256,18 @@ impl ConfigLoader for Network {
 			)
 		})?;
 
+		// Check plain secrets against the policy while they can be told apart
+		SecretsPolicy::load(None)?.enforce(
+			LintEntity::Network,
+			&config.slug,
+			network_plaintext_secrets(&config),
+		)?;
+
 		// Resolve secrets before validating
 		config = config.resolve_secrets().await?;
 
 		// Validate the config after loading
+		config.normalize_schedules();
 		config.validate()?;
 
 		Ok(config)
@@ -162,6 +280,7 @@ impl ConfigLoader for Network {
 	/// - At least one RPC URL is specified
 	/// - Required chain-specific parameters are present
 	/// - Block time and confirmation values are reasonable
+	/// - Maintenance windows have a valid schedule, duration and offset
 	fn validate(&self) -> Result<(), ConfigError> {
 		// Validate network name
 		if self.name.is_empty() {
@@ -249,17 +368,42 @@ impl ConfigLoader for Network {
 		}
 
 		// Validate cron_schedule
-		if self.cron_schedule.is_empty() {
+		let schedule_metadata = || {
+			Some(HashMap::from([
+				("network".to_string(), self.slug.clone()),
+				("field".to_string(), "cron_schedule".to_string()),
+			]))
+		};
+		if self.cron_schedule.trim().is_empty() {
 			return Err(ConfigError::validation_error(
-				"Cron schedule must be provided",
-				None,
+				format!("Network '{}' cron_schedule must be provided", self.slug),
 				None,
+				schedule_metadata(),
 			));
 		}
 
 		// Add cron schedule format validation
 		if let Err(e) = cron::Schedule::from_str(&self.cron_schedule) {
-			return Err(ConfigError::validation_error(e.to_string(), None, None));
+			return Err(ConfigError::validation_error(
+				format!(
+					"Network '{}' cron_schedule '{}' is invalid: {}",
+					self.slug, self.cron_schedule, e
+				),
+				None,
+				schedule_metadata(),
+			));
+		}
+
+		// A schedule without two upcoming occurrences never polls the network again
+		if get_cron_interval_ms(&self.cron_schedule).is_none_or(|interval_ms| interval_ms <= 0) {
+			return Err(ConfigError::validation_error(
+				format!(
+					"Network '{}' cron_schedule '{}' does not fire at a regular interval",
+					self.slug, self.cron_schedule
+				),
+				None,
+				schedule_metadata(),
+			));
 		}
 
 		// Validate max_past_blocks
@@ -285,12 +429,225 @@ impl ConfigLoader for Network {
 			}
 		}
 
+		// Validate maintenance windows
+		for window in self.maintenance_windows.as_deref().unwrap_or_default() {
+			if let Err(e) = cron::Schedule::from_str(&window.cron_schedule) {
+				return Err(ConfigError::validation_error(
+					format!(
+						"Invalid maintenance window cron schedule '{}': {}",
+						window.cron_schedule, e
+					),
+					None,
+					None,
+				));
+			}
+
+			if window.duration_ms == 0 || window.duration_ms > MAX_MAINTENANCE_WINDOW_DURATION_MS {
+				return Err(ConfigError::validation_error(
+					format!(
+						"Maintenance window duration_ms must be between 1 and {}",
+						MAX_MAINTENANCE_WINDOW_DURATION_MS
+					),
+					None,
+					None,
+				));
+			}
+
+			if window.offset().is_none() {
+				return Err(ConfigError::validation_error(
+					format!(
+						"Invalid maintenance window utc_offset '{}' (expected UTC or +HH:MM)",
+						window.utc_offset.as_deref().unwrap_or_default()
+					),
+					None,
+					None,
+				));
+			}
+
+			if let Some(timezone) = &window.timezone {
+				if window.utc_offset.is_some() {
+					return Err(ConfigError::validation_error(
+						"Maintenance window timezone and utc_offset cannot both be set",
+						None,
+						None,
+					));
+				}
+				if window.time_zone().is_none() {
+					return Err(ConfigError::validation_error(
+						format!(
+							"Invalid maintenance window timezone '{}' (expected an IANA time \
+							 zone such as Europe/Berlin)",
+							timezone
+						),
+						None,
+						None,
+					));
+				}
+			}
+		}
+
+		// Validate block fetch limits
+		if let Some(block_fetch) = &self.block_fetch {
+			if block_fetch.timeout_ms == 0 {
+				return Err(ConfigError::validation_error(
+					"block_fetch.timeout_ms must be greater than 0",
+					None,
+					None,
+				));
+			}
+			if block_fetch.max_failed_blocks == 0 {
+				return Err(ConfigError::validation_error(
+					"block_fetch.max_failed_blocks must be greater than 0",
+					None,
+					None,
+				));
+			}
+			if block_fetch.retry_interval_ms == 0 {
+				return Err(ConfigError::validation_error(
+					"block_fetch.retry_interval_ms must be greater than 0",
+					None,
+					None,
+				));
+			}
+		}
+
+		// Validate monitor concurrency
+		if self.monitor_concurrency == Some(0) {
+			return Err(ConfigError::validation_error(
+				"monitor_concurrency must be greater than 0",
+				None,
+				None,
+			));
+		}
+
+		// Validate network group
+		if self
+			.group
+			.as_ref()
+			.is_some_and(|group| group.trim().is_empty())
+		{
+			return Err(ConfigError::validation_error(
+				"group must not be empty when specified",
+				None,
+				None,
+			));
+		}
+
+		// Validate price oracle
+		if let Some(price_oracle) = &self.price_oracle {
+			self.validate_price_oracle(price_oracle)?;
+		}
+
 		// Log a warning if the network uses an insecure protocol
 		self.validate_protocol();
 
 		Ok(())
 	}
 
+	/// Validate the price oracle of the network
+	///
+	/// Every token must be `native` or a contract address, and provide what its source needs
+	/// to price it.
+	fn validate_price_oracle(&self, price_oracle: &PriceOracleConfig) -> Result<(), ConfigError> {
+		if self.network_type != BlockChainType::EVM {
+			return Err(ConfigError::validation_error(
+				"price_oracle is only supported on EVM networks",
+				None,
+				None,
+			));
+		}
+		if price_oracle.tokens.is_empty() {
+			return Err(ConfigError::validation_error(
+				"price_oracle.tokens must not be empty",
+				None,
+				None,
+			));
+		}
+		if price_oracle.cache_ttl_secs == 0 {
+			return Err(ConfigError::validation_error(
+				"price_oracle.cache_ttl_secs must be greater than 0",
+				None,
+				None,
+			));
+		}
+		if price_oracle.max_age_secs == 0 {
+			return Err(ConfigError::validation_error(
+				"price_oracle.max_age_secs must be greater than 0",
+				None,
+				None,
+			));
+		}
+		if let PriceSource::Http { url } = &price_oracle.source {
+			if !url.starts_with("http://") && !url.starts_with("https://") {
+				return Err(ConfigError::validation_error(
+					format!("Invalid price_oracle url '{}'", url),
+					None,
+					None,
+				));
+			}
+		}
+
+		for (token, priced) in &price_oracle.tokens {
+			if token != NATIVE_TOKEN && Address::from_str(token).is_err() {
+				return Err(ConfigError::validation_error(
+					format!(
+						"Invalid price_oracle token '{}' (expected '{}' or a contract address)",
+						token, NATIVE_TOKEN
+					),
+					None,
+					None,
+				));
+			}
+			if priced.decimals > MAX_PRICED_TOKEN_DECIMALS {
+				return Err(ConfigError::validation_error(
+					format!(
+						"price_oracle token '{}' decimals must be at most {}",
+						token, MAX_PRICED_TOKEN_DECIMALS
+					),
+					None,
+					None,
+				));
+			}
+			let missing = match &price_oracle.source {
+				PriceSource::Static => match priced.price.as_deref().map(Decimal::from_str) {
+					Some(Ok(price)) if !price.is_sign_negative() => None,
+					Some(_) => {
+						return Err(ConfigError::validation_error(
+							format!("Invalid price_oracle price for token '{}'", token),
+							None,
+							None,
+						));
+					}
+					None => Some("price"),
+				},
+				PriceSource::Http { .. } => match priced.id.as_deref() {
+					Some(id) if !id.trim().is_empty() => None,
+					_ => Some("id"),
+				},
+				PriceSource::Chainlink => match priced.feed.as_deref().map(Address::from_str) {
+					Some(Ok(_)) => None,
+					Some(Err(_)) => {
+						return Err(ConfigError::validation_error(
+							format!("Invalid price_oracle feed address for token '{}'", token),
+							None,
+							None,
+						));
+					}
+					None => Some("feed"),
+				},
+			};
+			if let Some(field) = missing {
+				return Err(ConfigError::validation_error(
+					format!("price_oracle token '{}' is missing its {}", token, field),
+					None,
+					None,
+				));
+			}
+		}
+
+		Ok(())
+	}
+
 	/// Validate the safety of the protocol used in the network
 	///
 	/// Returns if safe, or logs a warning message if unsafe.
@@ -314,6 +671,49 @@ impl ConfigLoader for Network {
 		}
 	}
 
+	/// Validates the network groups across networks
+	///
+	/// A group may not share its name with a network slug, as monitors reference both the same
+	/// way, and its members must be of the same blockchain type.
+	fn validate_groups(networks: &[&Self]) -> Result<(), ConfigError> {
+		let mut group_types: HashMap<&str, (&str, &BlockChainType)> = HashMap::new();
+		for network in networks {
+			let Some(group) = network.group.as_deref() else {
+				continue;
+			};
+			if networks
+				.iter()
+				.any(|other| normalize_string(&other.slug) == normalize_string(group))
+			{
+				return Err(ConfigError::validation_error(
+					format!(
+						"Network '{}' uses the slug of a network as its group '{}'",
+						network.slug, group
+					),
+					None,
+					Some(HashMap::from([("group".to_string(), group.to_string())])),
+				));
+			}
+			match group_types.get(group) {
+				Some((slug, network_type)) if *network_type != &network.network_type => {
+					return Err(ConfigError::validation_error(
+						format!(
+							"Networks '{}' and '{}' of group '{}' have different network types",
+							slug, network.slug, group
+						),
+						None,
+						Some(HashMap::from([("group".to_string(), group.to_string())])),
+					));
+				}
+				Some(_) => {}
+				None => {
+					group_types.insert(group, (&network.slug, &network.network_type));
+				}
+			}
+		}
+		Ok(())
+	}
+
 	fn validate_uniqueness(
 		instances: &[&Self],
 		current_instance: &Self,
@@ -350,7 +750,10 @@ impl ConfigLoader for Network {
 #[cfg(test)]
 mod tests {
 	use super::*;
-	use crate::{models::SecretString, utils::tests::builders::network::NetworkBuilder};
+	use crate::{
+		models::{BlockFetchConfig, PricedToken, SecretString},
+		utils::tests::builders::network::NetworkBuilder,
+	};
 	use std::fs;
 	use tempfile::TempDir;
 	use tracing_test::traced_test;
@@ -502,6 +905,397 @@ mod tests {
 		));
 	}
 
+	#[test]
+	fn test_validate_malformed_cron_schedule_names_network_and_field() {
+		let network = NetworkBuilder::new()
+			.slug("ethereum_mainnet")
+			.cron_schedule("*/5 * * *")
+			.build();
+		let err = network.validate().unwrap_err();
+		assert!(err.to_string().contains("ethereum_mainnet"));
+		assert!(err.to_string().contains("cron_schedule"));
+		let ConfigError::ValidationError(context) = err else {
+			panic!("expected a validation error");
+		};
+		let metadata = context.metadata.unwrap();
+		assert_eq!(metadata["network"], "ethereum_mainnet");
+		assert_eq!(metadata["field"], "cron_schedule");
+	}
+
+	#[test]
+	fn test_validate_cron_schedule_that_never_fires() {
+		let network = NetworkBuilder::new()
+			.slug("ethereum_mainnet")
+			.cron_schedule("0 0 0 1 1 * 2020")
+			.build();
+		let err = network.validate().unwrap_err();
+		assert!(err.to_string().contains("does not fire"));
+	}
+
+	#[test]
+	fn test_normalize_schedules() {
+		let mut network = NetworkBuilder::new()
+			.cron_schedule("  0 */5  *\t* * * *  ")
+			.build();
+		network.maintenance_windows = Some(vec![MaintenanceWindow {
+			cron_schedule: "0 0 3 * * Sun *".to_string(),
+			duration_ms: 3_600_000,
+			utc_offset: None,
+			timezone: None,
+		}]);
+		network.normalize_schedules();
+		assert_eq!(network.cron_schedule, "0 */5 * * * *");
+		let windows = network.maintenance_windows.clone().unwrap();
+		assert_eq!(windows[0].cron_schedule, "0 0 3 * * Sun");
+
+		// Normalizing a normalized network leaves it unchanged
+		let normalized = network.clone();
+		network.normalize_schedules();
+		assert_eq!(network, normalized);
+		assert!(network.validate().is_ok());
+	}
+
+	fn utc(value: &str) -> DateTime<Utc> {
+		DateTime::parse_from_rfc3339(value)
+			.unwrap()
+			.with_timezone(&Utc)
+	}
+
+	#[test]
+	fn test_maintenance_window_is_active_at() {
+		// Every Sunday 02:00-03:00 UTC (2024-01-07 is a Sunday)
+		let network = NetworkBuilder::new()
+			.maintenance_window("0 0 2 * * Sun", 3_600_000, None)
+			.build();
+
+		assert!(network
+			.active_maintenance_window(utc("2024-01-07T01:59:59Z"))
+			.is_none());
+		assert!(network
+			.active_maintenance_window(utc("2024-01-07T02:00:00Z"))
+			.is_some());
+		assert!(network
+			.active_maintenance_window(utc("2024-01-07T02:59:59Z"))
+			.is_some());
+		assert!(network
+			.active_maintenance_window(utc("2024-01-07T03:00:00Z"))
+			.is_none());
+		assert!(network
+			.active_maintenance_window(utc("2024-01-08T02:30:00Z"))
+			.is_none());
+	}
+
+	#[test]
+	fn test_maintenance_window_with_utc_offset() {
+		// 04:00 at +02:00 is 02:00 UTC
+		let network = NetworkBuilder::new()
+			.maintenance_window("0 0 4 * * Sun", 3_600_000, Some("+02:00"))
+			.build();
+
+		assert!(network
+			.active_maintenance_window(utc("2024-01-07T01:30:00Z"))
+			.is_none());
+		assert!(network
+			.active_maintenance_window(utc("2024-01-07T02:30:00Z"))
+			.is_some());
+	}
+
+	#[test]
+	fn test_maintenance_window_with_timezone() {
+		// 03:00 in Berlin is 02:00 UTC in winter and 01:00 UTC in summer
+		let network = NetworkBuilder::new()
+			.maintenance_window_in_timezone("0 0 3 * * Sun", 3_600_000, "Europe/Berlin")
+			.build();
+		assert!(network.validate().is_ok());
+
+		// 2024-01-07 is a Sunday in winter (UTC+1)
+		assert!(network
+			.active_maintenance_window(utc("2024-01-07T01:59:59Z"))
+			.is_none());
+		assert!(network
+			.active_maintenance_window(utc("2024-01-07T02:00:00Z"))
+			.is_some());
+		assert!(network
+			.active_maintenance_window(utc("2024-01-07T03:00:00Z"))
+			.is_none());
+
+		// 2024-07-07 is a Sunday in summer (UTC+2)
+		assert!(network
+			.active_maintenance_window(utc("2024-07-07T00:59:59Z"))
+			.is_none());
+		assert!(network
+			.active_maintenance_window(utc("2024-07-07T01:00:00Z"))
+			.is_some());
+		assert!(network
+			.active_maintenance_window(utc("2024-07-07T02:00:00Z"))
+			.is_none());
+	}
+
+	#[test]
+	fn test_validate_maintenance_window_timezone() {
+		let network = NetworkBuilder::new()
+			.maintenance_window_in_timezone("0 0 3 * * Sun", 3_600_000, "Mars/Olympus_Mons")
+			.build();
+		assert!(matches!(
+			network.validate(),
+			Err(ConfigError::ValidationError(_))
+		));
+
+		let mut network = NetworkBuilder::new()
+			.maintenance_window_in_timezone("0 0 3 * * Sun", 3_600_000, "Europe/Berlin")
+			.build();
+		network.maintenance_windows.as_mut().unwrap()[0].utc_offset = Some("+01:00".to_string());
+		let err = network.validate().unwrap_err();
+		assert!(err.to_string().contains("cannot both be set"));
+	}
+
+	#[test]
+	fn test_no_maintenance_windows() {
+		let network = NetworkBuilder::new().build();
+		assert!(network
+			.active_maintenance_window(utc("2024-01-07T02:30:00Z"))
+			.is_none());
+	}
+
+	#[test]
+	fn test_validate_maintenance_window_valid() {
+		let mut network = create_valid_network();
+		network.maintenance_windows = Some(vec![MaintenanceWindow {
+			cron_schedule: "0 0 2 * * Sun".to_string(),
+			duration_ms: 3_600_000,
+			utc_offset: Some("UTC".to_string()),
+			timezone: None,
+		}]);
+		assert!(network.validate().is_ok());
+	}
+
+	#[test]
+	fn test_validate_maintenance_window_invalid_cron() {
+		let network = NetworkBuilder::new()
+			.maintenance_window("not a cron", 3_600_000, None)
+			.build();
+		assert!(matches!(
+			network.validate(),
+			Err(ConfigError::ValidationError(_))
+		));
+	}
+
+	#[test]
+	fn test_validate_maintenance_window_invalid_duration() {
+		let network = NetworkBuilder::new()
+			.maintenance_window("0 0 2 * * Sun", 0, None)
+			.build();
+		assert!(matches!(
+			network.validate(),
+			Err(ConfigError::ValidationError(_))
+		));
+
+		let network = NetworkBuilder::new()
+			.maintenance_window(
+				"0 0 2 * * Sun",
+				MAX_MAINTENANCE_WINDOW_DURATION_MS + 1,
+				None,
+			)
+			.build();
+		assert!(matches!(
+			network.validate(),
+			Err(ConfigError::ValidationError(_))
+		));
+	}
+
+	#[test]
+	fn test_validate_block_fetch() {
+		let network = NetworkBuilder::new()
+			.block_fetch(BlockFetchConfig::default())
+			.build();
+		assert!(network.validate().is_ok());
+
+		let network = NetworkBuilder::new()
+			.block_fetch(BlockFetchConfig {
+				timeout_ms: 0,
+				..Default::default()
+			})
+			.build();
+		assert!(matches!(
+			network.validate(),
+			Err(ConfigError::ValidationError(_))
+		));
+
+		let network = NetworkBuilder::new()
+			.block_fetch(BlockFetchConfig {
+				max_failed_blocks: 0,
+				..Default::default()
+			})
+			.build();
+		assert!(matches!(
+			network.validate(),
+			Err(ConfigError::ValidationError(_))
+		));
+	}
+
+	#[test]
+	fn test_validate_monitor_concurrency() {
+		let network = NetworkBuilder::new().monitor_concurrency(4).build();
+		assert!(network.validate().is_ok());
+		assert_eq!(network.monitor_concurrency(), 4);
+
+		let network = NetworkBuilder::new().monitor_concurrency(0).build();
+		assert!(matches!(
+			network.validate(),
+			Err(ConfigError::ValidationError(_))
+		));
+
+		let network = NetworkBuilder::new().build();
+		assert_eq!(
+			network.monitor_concurrency(),
+			crate::models::DEFAULT_MONITOR_CONCURRENCY
+		);
+	}
+
+	#[test]
+	fn test_validate_price_oracle() {
+		let usdc = "0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48";
+		let oracle = |source: PriceSource, token: PricedToken| PriceOracleConfig {
+			source,
+			tokens: HashMap::from([(usdc.to_string(), token)]),
+			cache_ttl_secs: 60,
+			max_age_secs: 3600,
+		};
+		let token = PricedToken {
+			decimals: 6,
+			price: Some("1.00".to_string()),
+			id: Some("usd-coin".to_string()),
+			feed: Some("0x8fffffd4afb6115b954bd326cbe7b4ba576818f6".to_string()),
+		};
+
+		for source in [
+			PriceSource::Static,
+			PriceSource::Http {
+				url: "https://api.coingecko.com/api/v3/simple/price".to_string(),
+			},
+			PriceSource::Chainlink,
+		] {
+			let network = NetworkBuilder::new()
+				.price_oracle(oracle(source, token.clone()))
+				.build();
+			assert!(network.validate().is_ok());
+		}
+
+		let invalid = [
+			oracle(
+				PriceSource::Static,
+				PricedToken {
+					price: Some("one".to_string()),
+					..token.clone()
+				},
+			),
+			oracle(
+				PriceSource::Http {
+					url: "api.coingecko.com".to_string(),
+				},
+				token.clone(),
+			),
+			oracle(
+				PriceSource::Chainlink,
+				PricedToken {
+					feed: None,
+					..token.clone()
+				},
+			),
+			oracle(
+				PriceSource::Static,
+				PricedToken {
+					decimals: 40,
+					..token.clone()
+				},
+			),
+			PriceOracleConfig {
+				tokens: HashMap::from([("usdc".to_string(), token.clone())]),
+				..oracle(PriceSource::Static, token.clone())
+			},
+			PriceOracleConfig {
+				max_age_secs: 0,
+				..oracle(PriceSource::Static, token.clone())
+			},
+		];
+		for price_oracle in invalid {
+			let network = NetworkBuilder::new().price_oracle(price_oracle).build();
+			assert!(matches!(
+				network.validate(),
+				Err(ConfigError::ValidationError(_))
+			));
+		}
+
+		let network = create_valid_midnight_network();
+		let network = Network {
+			price_oracle: Some(oracle(PriceSource::Static, token)),
+			..network
+		};
+		assert!(matches!(
+			network.validate(),
+			Err(ConfigError::ValidationError(_))
+		));
+	}
+
+	#[test]
+	fn test_validate_groups() {
+		let internal = NetworkBuilder::new()
+			.slug("ethereum_mainnet_internal")
+			.group("ethereum_mainnet")
+			.build();
+		let public = NetworkBuilder::new()
+			.slug("ethereum_mainnet_public")
+			.group("ethereum_mainnet")
+			.build();
+		assert!(internal.validate().is_ok());
+		assert!(Network::validate_groups(&[&internal, &public]).is_ok());
+
+		let stellar = NetworkBuilder::new()
+			.slug("stellar_mainnet")
+			.network_type(BlockChainType::Stellar)
+			.group("ethereum_mainnet")
+			.build();
+		assert!(matches!(
+			Network::validate_groups(&[&internal, &stellar]),
+			Err(ConfigError::ValidationError(_))
+		));
+
+		let named_like_slug = NetworkBuilder::new()
+			.slug("ethereum_mainnet_backup")
+			.group("ethereum_mainnet_internal")
+			.build();
+		assert!(matches!(
+			Network::validate_groups(&[&internal, &named_like_slug]),
+			Err(ConfigError::ValidationError(_))
+		));
+
+		let empty = NetworkBuilder::new().group(" ").build();
+		assert!(matches!(
+			empty.validate(),
+			Err(ConfigError::ValidationError(_))
+		));
+	}
+
+	#[test]
+	fn test_block_fetch_defaults() {
+		let block_fetch: BlockFetchConfig =
+			serde_json::from_str(r#"{"timeout_ms": 5000}"#).unwrap();
+		assert_eq!(block_fetch.timeout_ms, 5000);
+		assert_eq!(block_fetch.max_retries, 3);
+		assert_eq!(block_fetch.max_failed_blocks, 1_000);
+	}
+
+	#[test]
+	fn test_validate_maintenance_window_invalid_offset() {
+		let network = NetworkBuilder::new()
+			.maintenance_window("0 0 2 * * Sun", 3_600_000, Some("Europe/Berlin"))
+			.build();
+		assert!(matches!(
+			network.validate(),
+			Err(ConfigError::ValidationError(_))
+		));
+	}
+
 	#[tokio::test]
 	async fn test_invalid_load_from_path() {
 		let path = Path::new("config/networks/invalid.json");
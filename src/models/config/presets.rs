@@ -0,0 +1,422 @@
+//! Built-in condition presets.
+//!
+//! Presets are named packs of event conditions for well-known OpenZeppelin contract events.
+//! Monitors reference them by name in `presets`, and they are expanded into ordinary event
+//! conditions when monitors are loaded. The ABI of the preset events is added to the monitor's
+//! EVM contract specs so that the events can be decoded without a full contract ABI.
+
+use alloy::{json_abi::Event, primitives::Address};
+use std::{collections::HashMap, str::FromStr};
+
+use crate::{
+	models::{
+		config::error::ConfigError, ContractSpec, EVMContractSpec, EventCondition, Monitor,
+		PresetReference,
+	},
+	utils::normalize_string,
+};
+
+/// Event of a preset
+#[derive(Debug)]
+pub struct PresetEvent {
+	/// Human-readable ABI declaration of the event
+	pub declaration: &'static str,
+	/// Expression applied unless the monitor overrides it
+	pub default_expression: Option<&'static str>,
+}
+
+/// Named pack of event conditions
+#[derive(Debug)]
+pub struct Preset {
+	/// Name monitors reference the preset by
+	pub name: &'static str,
+	/// Short description of what the preset watches
+	pub description: &'static str,
+	/// Events of the preset
+	pub events: &'static [PresetEvent],
+}
+
+const OWNERSHIP_TRANSFERRED: PresetEvent = PresetEvent {
+	declaration: "event OwnershipTransferred(address indexed previousOwner, address indexed \
+	              newOwner)",
+	// Skips the initial assignment of ownership when the contract is deployed
+	default_expression: Some("previousOwner != '0x0000000000000000000000000000000000000000'"),
+};
+
+const UPGRADED: PresetEvent = PresetEvent {
+	declaration: "event Upgraded(address indexed implementation)",
+	default_expression: None,
+};
+
+/// Presets shipped with the monitor
+pub const PRESETS: &[Preset] = &[
+	Preset {
+		name: "ownable",
+		description: "Ownership transfers and renouncements of Ownable contracts",
+		events: &[OWNERSHIP_TRANSFERRED],
+	},
+	Preset {
+		name: "ownable-2step",
+		description: "Started and completed ownership transfers of Ownable2Step contracts",
+		events: &[
+			PresetEvent {
+				declaration: "event OwnershipTransferStarted(address indexed previousOwner, \
+				              address indexed newOwner)",
+				default_expression: None,
+			},
+			OWNERSHIP_TRANSFERRED,
+		],
+	},
+	Preset {
+		name: "openzeppelin-access-control",
+		description: "Role grants, revocations and admin role changes of AccessControl contracts",
+		events: &[
+			PresetEvent {
+				declaration: "event RoleGranted(bytes32 indexed role, address indexed account, \
+				              address indexed sender)",
+				default_expression: None,
+			},
+			PresetEvent {
+				declaration: "event RoleRevoked(bytes32 indexed role, address indexed account, \
+				              address indexed sender)",
+				default_expression: None,
+			},
+			PresetEvent {
+				declaration: "event RoleAdminChanged(bytes32 indexed role, bytes32 indexed \
+				              previousAdminRole, bytes32 indexed newAdminRole)",
+				default_expression: None,
+			},
+		],
+	},
+	Preset {
+		name: "pausable",
+		description: "Pauses and unpauses of Pausable contracts",
+		events: &[
+			PresetEvent {
+				declaration: "event Paused(address account)",
+				default_expression: None,
+			},
+			PresetEvent {
+				declaration: "event Unpaused(address account)",
+				default_expression: None,
+			},
+		],
+	},
+	Preset {
+		name: "uups-upgrades",
+		description: "Implementation upgrades of UUPS proxies",
+		events: &[UPGRADED],
+	},
+	Preset {
+		name: "erc1967-proxy",
+		description: "Implementation, admin and beacon changes of ERC-1967 proxies",
+		events: &[
+			UPGRADED,
+			PresetEvent {
+				declaration: "event AdminChanged(address previousAdmin, address newAdmin)",
+				default_expression: None,
+			},
+			PresetEvent {
+				declaration: "event BeaconUpgraded(address indexed beacon)",
+				default_expression: None,
+			},
+		],
+	},
+];
+
+impl PresetEvent {
+	/// Returns the ABI of the event
+	pub fn abi(&self) -> Event {
+		Event::parse(self.declaration).expect("preset event declarations are valid")
+	}
+
+	/// Returns the signature of the event, e.g. `Paused(address)`
+	pub fn signature(&self) -> String {
+		self.abi().signature()
+	}
+}
+
+/// Returns the preset with the given name
+pub fn find_preset(name: &str) -> Option<&'static Preset> {
+	let name = normalize_string(name);
+	PRESETS
+		.iter()
+		.find(|preset| normalize_string(preset.name) == name)
+}
+
+/// Returns the names of the presets close to the given, misspelled, name
+fn suggest_presets(name: &str) -> Vec<&'static str> {
+	let name = normalize_string(name);
+	PRESETS
+		.iter()
+		.filter(|preset| {
+			let candidate = normalize_string(preset.name);
+			candidate.contains(&name)
+				|| name.contains(&candidate)
+				|| edit_distance(&candidate, &name) <= 2.max(candidate.len() / 4)
+		})
+		.map(|preset| preset.name)
+		.collect()
+}
+
+/// Levenshtein distance between two strings
+fn edit_distance(a: &str, b: &str) -> usize {
+	let b = b.chars().collect::<Vec<_>>();
+	let mut previous = (0..=b.len()).collect::<Vec<_>>();
+	for (i, a_char) in a.chars().enumerate() {
+		let mut current = vec![i + 1; b.len() + 1];
+		for (j, b_char) in b.iter().enumerate() {
+			let substitution = previous[j] + usize::from(a_char != *b_char);
+			current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+		}
+		previous = current;
+	}
+	previous[b.len()]
+}
+
+impl Monitor {
+	/// Returns a copy of the monitor with its presets expanded into event conditions
+	///
+	/// Event conditions the monitor already declares take precedence over the preset's. The
+	/// ABI of the preset events is added to the EVM contract specs of the monitor's addresses.
+	///
+	/// # Returns
+	/// * `Result<Monitor, ConfigError>` - The expanded monitor, or an error naming the monitor
+	///   and the preset that could not be expanded
+	pub fn expand_presets(&self) -> Result<Self, ConfigError> {
+		if self.presets.is_empty() {
+			return Ok(self.clone());
+		}
+
+		let mut monitor = self.clone();
+		let mut abi_events = Vec::new();
+
+		for reference in &self.presets {
+			let preset = self.resolve_preset(reference)?;
+			let overrides = reference.expressions();
+
+			for event in preset.events {
+				let abi = event.abi();
+				let signature = abi.signature();
+				abi_events.push(abi);
+
+				if monitor
+					.match_conditions
+					.events
+					.iter()
+					.any(|condition| condition.signature == signature)
+				{
+					continue;
+				}
+				let expression = match overrides.and_then(|o| o.get(&signature)) {
+					Some(expression) => expression.clone(),
+					None => event.default_expression.map(str::to_string),
+				};
+				monitor.match_conditions.events.push(EventCondition {
+					signature,
+					expression,
+					match_any_emitter: false,
+				});
+			}
+		}
+
+		for address in &mut monitor.addresses {
+			let spec = match &address.contract_spec {
+				Some(ContractSpec::EVM(spec)) => (**spec).clone(),
+				None if Address::from_str(&address.address).is_ok() => Default::default(),
+				_ => continue,
+			};
+			address.contract_spec = Some(ContractSpec::EVM(EVMContractSpec::from(with_events(
+				spec,
+				&abi_events,
+			))));
+		}
+
+		Ok(monitor)
+	}
+
+	/// Looks up a referenced preset and checks that its overrides name events of the preset
+	fn resolve_preset(&self, reference: &PresetReference) -> Result<&'static Preset, ConfigError> {
+		let metadata = HashMap::from([
+			("monitor".to_string(), self.name.clone()),
+			("preset".to_string(), reference.name().to_string()),
+		]);
+
+		let Some(preset) = find_preset(reference.name()) else {
+			let suggestions = suggest_presets(reference.name());
+			let hint = if suggestions.is_empty() {
+				String::new()
+			} else {
+				format!(", did you mean '{}'?", suggestions.join("' or '"))
+			};
+			return Err(ConfigError::validation_error(
+				format!(
+					"Monitor '{}' references unknown preset '{}'{} Available presets: {}",
+					self.name,
+					reference.name(),
+					if hint.is_empty() { "." } else { &hint },
+					PRESETS
+						.iter()
+						.map(|preset| preset.name)
+						.collect::<Vec<_>>()
+						.join(", ")
+				),
+				None,
+				Some(metadata),
+			));
+		};
+
+		let signatures = preset
+			.events
+			.iter()
+			.map(PresetEvent::signature)
+			.collect::<Vec<_>>();
+		if let Some(unknown) = reference
+			.expressions()
+			.into_iter()
+			.flat_map(|overrides| overrides.keys())
+			.find(|signature| !signatures.contains(signature))
+		{
+			return Err(ConfigError::validation_error(
+				format!(
+					"Monitor '{}' overrides event '{}', which is not part of preset '{}' ({})",
+					self.name,
+					unknown,
+					preset.name,
+					signatures.join(", ")
+				),
+				None,
+				Some(metadata),
+			));
+		}
+
+		Ok(preset)
+	}
+
+	/// Returns the name of the preset an event condition was expanded from, if any
+	pub fn preset_for_event(&self, signature: &str) -> Option<&str> {
+		self.presets.iter().map(PresetReference::name).find(|name| {
+			find_preset(name).is_some_and(|preset| {
+				preset
+					.events
+					.iter()
+					.any(|event| event.signature() == signature)
+			})
+		})
+	}
+}
+
+/// Adds the events missing from a contract ABI
+fn with_events(mut abi: alloy::json_abi::JsonAbi, events: &[Event]) -> alloy::json_abi::JsonAbi {
+	for event in events {
+		let declared = abi.events.entry(event.name.clone()).or_default();
+		if !declared
+			.iter()
+			.any(|existing| existing.signature() == event.signature())
+		{
+			declared.push(event.clone());
+		}
+	}
+	abi
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{models::PresetOverride, utils::tests::evm::monitor::MonitorBuilder};
+
+	#[test]
+	fn test_preset_declarations_are_valid() {
+		for preset in PRESETS {
+			assert!(!preset.events.is_empty(), "{} has no events", preset.name);
+			for event in preset.events {
+				assert!(
+					Event::parse(event.declaration).is_ok(),
+					"{}",
+					event.declaration
+				);
+			}
+		}
+		assert_eq!(UPGRADED.signature(), "Upgraded(address)");
+		assert_eq!(
+			find_preset("Pausable").map(|preset| preset.name),
+			Some("pausable")
+		);
+	}
+
+	#[test]
+	fn test_expand_presets_with_overrides() {
+		let role = "0x9f2df0fed2c77648de5860a4cc508cd0818c85b8b8a1ab4ceeef8d981c8956a6";
+		let monitor = MonitorBuilder::new()
+			.address("0x0000000000000000000000000000000000001234")
+			.event("Paused(address)", Some("account == '0x01'".to_string()))
+			.presets(vec![
+				PresetReference::from("pausable"),
+				PresetReference::WithOverrides(PresetOverride {
+					name: "openzeppelin-access-control".to_string(),
+					expressions: HashMap::from([(
+						"RoleGranted(bytes32,address,address)".to_string(),
+						Some(format!("role == '{}'", role)),
+					)]),
+				}),
+			])
+			.build();
+
+		let expanded = monitor.expand_presets().unwrap();
+
+		let events = expanded
+			.match_conditions
+			.events
+			.iter()
+			.map(|c| (c.signature.as_str(), c.expression.as_deref()))
+			.collect::<Vec<_>>();
+		assert_eq!(
+			events,
+			vec![
+				("Paused(address)", Some("account == '0x01'")),
+				("Unpaused(address)", None),
+				(
+					"RoleGranted(bytes32,address,address)",
+					Some(format!("role == '{}'", role).as_str())
+				),
+				("RoleRevoked(bytes32,address,address)", None),
+				("RoleAdminChanged(bytes32,bytes32,bytes32)", None),
+			]
+		);
+		assert_eq!(expanded.presets, monitor.presets);
+		assert_eq!(
+			expanded.preset_for_event("RoleRevoked(bytes32,address,address)"),
+			Some("openzeppelin-access-control")
+		);
+
+		let Some(ContractSpec::EVM(spec)) = &expanded.addresses[0].contract_spec else {
+			panic!("expected an EVM contract spec");
+		};
+		for name in ["Paused", "Unpaused", "RoleGranted", "RoleRevoked"] {
+			assert!(spec.events.contains_key(name), "{} missing from ABI", name);
+		}
+	}
+
+	#[test]
+	fn test_expand_presets_errors() {
+		let err = MonitorBuilder::new()
+			.name("Admin")
+			.presets(vec![PresetReference::from("pausible")])
+			.build()
+			.expand_presets()
+			.unwrap_err();
+		assert!(err.to_string().contains(
+			"Monitor 'Admin' references unknown preset 'pausible', did you mean 'pausable'?"
+		));
+
+		let err = MonitorBuilder::new()
+			.presets(vec![PresetReference::WithOverrides(PresetOverride {
+				name: "pausable".to_string(),
+				expressions: HashMap::from([("Upgraded(address)".to_string(), None)]),
+			})])
+			.build()
+			.expand_presets()
+			.unwrap_err();
+		assert!(err.to_string().contains("not part of preset 'pausable'"));
+	}
+}
@@ -0,0 +1,179 @@
+//! JSON Schemas of the configuration files.
+//!
+//! The schemas are generated from the configuration models, so they follow the same serde
+//! attributes the loaders use. Each schema is stamped with the crate version in its `$id` and
+//! `version` keywords, allowing consumers to pin the schema of a given release.
+
+use schemars::{generate::SchemaSettings, JsonSchema};
+use serde_json::Value;
+use std::{
+	collections::HashMap,
+	fmt,
+	path::{Path, PathBuf},
+};
+
+use crate::models::{config::error::ConfigError, Monitor, Network, Trigger};
+
+/// Base URL of the schema identifiers, followed by the crate version and the schema file name
+const SCHEMA_ID_BASE: &str = "https://github.com/OpenZeppelin/openzeppelin-monitor/schemas";
+
+/// Configuration file described by a schema
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ConfigSchemaKind {
+	/// Monitor file, holding a single monitor
+	Monitor,
+	/// Network file, holding a single network
+	Network,
+	/// Trigger file, mapping trigger identifiers to triggers
+	Trigger,
+}
+
+impl ConfigSchemaKind {
+	/// All configuration files with a schema
+	pub const ALL: [ConfigSchemaKind; 3] = [
+		ConfigSchemaKind::Monitor,
+		ConfigSchemaKind::Network,
+		ConfigSchemaKind::Trigger,
+	];
+
+	/// Returns the name of the configuration file kind
+	pub fn as_str(&self) -> &'static str {
+		match self {
+			ConfigSchemaKind::Monitor => "monitor",
+			ConfigSchemaKind::Network => "network",
+			ConfigSchemaKind::Trigger => "trigger",
+		}
+	}
+
+	/// Returns the file name the schema is written to
+	pub fn file_name(&self) -> String {
+		format!("{}.schema.json", self.as_str())
+	}
+
+	/// Returns the `$id` of the schema for the current crate version
+	pub fn id(&self) -> String {
+		format!(
+			"{}/v{}/{}",
+			SCHEMA_ID_BASE,
+			env!("CARGO_PKG_VERSION"),
+			self.file_name()
+		)
+	}
+
+	/// Generates the draft 2020-12 schema of the configuration file
+	pub fn schema(&self) -> Value {
+		match self {
+			ConfigSchemaKind::Monitor => self.generate::<Monitor>("Monitor"),
+			ConfigSchemaKind::Network => self.generate::<Network>("Network"),
+			ConfigSchemaKind::Trigger => self.generate::<HashMap<String, Trigger>>("Triggers"),
+		}
+	}
+
+	fn generate<T: JsonSchema>(&self, title: &str) -> Value {
+		let schema = SchemaSettings::draft2020_12()
+			.into_generator()
+			.into_root_schema_for::<T>();
+		let mut schema = schema.to_value();
+		if let Value::Object(object) = &mut schema {
+			object.insert("$id".to_string(), Value::String(self.id()));
+			object.insert("title".to_string(), Value::String(title.to_string()));
+			object.insert(
+				"version".to_string(),
+				Value::String(env!("CARGO_PKG_VERSION").to_string()),
+			);
+		}
+		schema
+	}
+}
+
+impl fmt::Display for ConfigSchemaKind {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "{}", self.as_str())
+	}
+}
+
+/// Generates the schemas of the monitor, network and trigger configuration files
+pub fn config_schemas() -> Vec<(ConfigSchemaKind, Value)> {
+	ConfigSchemaKind::ALL
+		.iter()
+		.map(|kind| (*kind, kind.schema()))
+		.collect()
+}
+
+/// Writes the configuration schemas into the given directory, creating it if needed
+///
+/// Returns the paths of the written files.
+pub fn write_config_schemas(dir: &Path) -> Result<Vec<PathBuf>, ConfigError> {
+	std::fs::create_dir_all(dir).map_err(|e| {
+		ConfigError::file_error(
+			format!("Failed to create schema directory {}", dir.display()),
+			Some(e.into()),
+			None,
+		)
+	})?;
+
+	config_schemas()
+		.into_iter()
+		.map(|(kind, schema)| {
+			let path = dir.join(kind.file_name());
+			let content = serde_json::to_string_pretty(&schema).map_err(|e| {
+				ConfigError::parse_error(
+					format!("Failed to serialize {} schema", kind),
+					Some(e.into()),
+					None,
+				)
+			})?;
+			std::fs::write(&path, content + "\n").map_err(|e| {
+				ConfigError::file_error(
+					format!("Failed to write schema file {}", path.display()),
+					Some(e.into()),
+					None,
+				)
+			})?;
+			Ok(path)
+		})
+		.collect()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_schemas_are_stamped_with_crate_version() {
+		for (kind, schema) in config_schemas() {
+			assert_eq!(
+				schema["$schema"],
+				"https://json-schema.org/draft/2020-12/schema"
+			);
+			assert_eq!(schema["$id"], kind.id());
+			assert!(kind
+				.id()
+				.contains(&format!("/v{}/", env!("CARGO_PKG_VERSION"))));
+			assert_eq!(schema["version"], env!("CARGO_PKG_VERSION"));
+		}
+	}
+
+	#[test]
+	fn test_trigger_schema_describes_trigger_map() {
+		let schema = ConfigSchemaKind::Trigger.schema();
+		assert_eq!(schema["type"], "object");
+		assert_eq!(schema["additionalProperties"]["$ref"], "#/$defs/Trigger");
+	}
+
+	#[test]
+	fn test_write_config_schemas() {
+		let dir = tempfile::tempdir().unwrap();
+		let paths = write_config_schemas(&dir.path().join("schemas")).unwrap();
+		assert_eq!(paths.len(), 3);
+		for (path, kind) in paths.iter().zip(ConfigSchemaKind::ALL) {
+			assert_eq!(
+				path.file_name().unwrap().to_str().unwrap(),
+				kind.file_name()
+			);
+			let written: Value =
+				serde_json::from_str(&std::fs::read_to_string(path).unwrap()).unwrap();
+			assert_eq!(written, kind.schema());
+		}
+	}
+}
@@ -0,0 +1,284 @@
+//! Secrets policy.
+//!
+//! Credentials such as webhook URLs, SMTP passwords and RPC URLs can be given as plain values,
+//! environment variables or Hashicorp Cloud Vault secrets. The secrets policy, loaded from
+//! `config/secrets_policy.json`, decides whether plain values are accepted. When they are not,
+//! loading a trigger or network holding one fails, naming the entity and the field but never
+//! the value. Plain values that are accepted are reported by the `plaintext-secret` lint.
+//!
+//! The `SECRETS_ALLOW_PLAIN` environment variable overrides `allow_plain`, so that a fleet can
+//! be locked down without changing every configuration.
+
+use lazy_static::lazy_static;
+use serde::{Deserialize, Serialize};
+use std::{
+	collections::HashMap,
+	path::Path,
+	sync::{Mutex, PoisonError},
+};
+
+use crate::models::{
+	config::{error::ConfigError, LintEntity},
+	Network, SecretValue, Trigger, TriggerTypeConfig,
+};
+
+/// Default path of the secrets policy file
+const DEFAULT_SECRETS_POLICY_PATH: &str = "config/secrets_policy.json";
+
+/// Environment variable overriding `allow_plain`
+pub const SECRETS_ALLOW_PLAIN_ENV: &str = "SECRETS_ALLOW_PLAIN";
+
+lazy_static! {
+	/// Fields holding plain secrets, by entity, recorded when the entity is loaded
+	///
+	/// Secrets are resolved to plain values on load, so the lint layer reads the fields from
+	/// here rather than from the loaded configuration.
+	static ref PLAINTEXT_SECRETS: Mutex<HashMap<(String, String), Vec<String>>> =
+		Mutex::new(HashMap::new());
+}
+
+/// Secrets policy loaded from `config/secrets_policy.json`
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct SecretsPolicy {
+	/// Whether secrets may be given as plain values
+	#[serde(default = "default_allow_plain")]
+	pub allow_plain: bool,
+
+	/// Fields accepting plain values even when `allow_plain` is false, as
+	/// `<trigger name or network slug>.<field path>`, e.g. `local_anvil.rpc_urls[0].url`
+	#[serde(default)]
+	pub allow_plain_fields: Vec<String>,
+}
+
+fn default_allow_plain() -> bool {
+	true
+}
+
+impl Default for SecretsPolicy {
+	fn default() -> Self {
+		Self {
+			allow_plain: default_allow_plain(),
+			allow_plain_fields: vec![],
+		}
+	}
+}
+
+impl SecretsPolicy {
+	/// Loads the secrets policy, allowing plain values if the file does not exist
+	///
+	/// `SECRETS_ALLOW_PLAIN`, when set to `true` or `false`, overrides `allow_plain`.
+	///
+	/// # Arguments
+	/// * `path` - Path of the secrets policy file, defaults to `config/secrets_policy.json`
+	///
+	/// # Returns
+	/// * `Result<Self, ConfigError>` - The secrets policy
+	pub fn load(path: Option<&Path>) -> Result<Self, ConfigError> {
+		let path = path.unwrap_or(Path::new(DEFAULT_SECRETS_POLICY_PATH));
+		let mut policy = if path.exists() {
+			let metadata = Some(HashMap::from([(
+				"path".to_string(),
+				path.display().to_string(),
+			)]));
+			let content = std::fs::read_to_string(path).map_err(|e| {
+				ConfigError::file_error(
+					format!("failed to read secrets policy: {}", e),
+					Some(Box::new(e)),
+					metadata.clone(),
+				)
+			})?;
+			serde_json::from_str(&content).map_err(|e| {
+				ConfigError::parse_error(
+					format!("failed to parse secrets policy: {}", e),
+					Some(Box::new(e)),
+					metadata,
+				)
+			})?
+		} else {
+			Self::default()
+		};
+
+		match std::env::var(SECRETS_ALLOW_PLAIN_ENV).as_deref() {
+			Ok("true") => policy.allow_plain = true,
+			Ok("false") => policy.allow_plain = false,
+			Ok(other) => {
+				return Err(ConfigError::validation_error(
+					format!(
+						"{} must be true or false, got '{}'",
+						SECRETS_ALLOW_PLAIN_ENV, other
+					),
+					None,
+					None,
+				))
+			}
+			Err(_) => {}
+		}
+
+		Ok(policy)
+	}
+
+	/// Returns whether the given field of the given entity may hold a plain value
+	pub fn allows(&self, name: &str, field: &str) -> bool {
+		self.allow_plain
+			|| self
+				.allow_plain_fields
+				.iter()
+				.any(|allowed| allowed == &format!("{}.{}", name, field))
+	}
+
+	/// Checks the plain secrets of an entity against the policy and records them for linting
+	///
+	/// # Arguments
+	/// * `entity` - Kind of the entity
+	/// * `name` - Trigger name or network slug
+	/// * `fields` - Paths of the fields holding plain secrets, before secrets are resolved
+	///
+	/// # Returns
+	/// * `Result<(), ConfigError>` - Error naming the entity and the first forbidden field
+	pub fn enforce(
+		&self,
+		entity: LintEntity,
+		name: &str,
+		fields: Vec<String>,
+	) -> Result<(), ConfigError> {
+		if let Some(field) = fields.iter().find(|field| !self.allows(name, field)) {
+			return Err(ConfigError::validation_error(
+				format!(
+					"{} '{}' has a plain secret in {}, which the secrets policy forbids; use an \
+					 environment or hashicorpcloudvault secret instead",
+					entity, name, field
+				),
+				None,
+				Some(HashMap::from([
+					("entity".to_string(), entity.to_string()),
+					("name".to_string(), name.to_string()),
+					("field".to_string(), field.clone()),
+				])),
+			));
+		}
+		record_plaintext_secrets(entity, name, fields);
+		Ok(())
+	}
+}
+
+/// Records the fields of an entity holding plain secrets, replacing the previous record
+pub fn record_plaintext_secrets(entity: LintEntity, name: &str, fields: Vec<String>) {
+	let mut secrets = PLAINTEXT_SECRETS
+		.lock()
+		.unwrap_or_else(PoisonError::into_inner);
+	let key = (entity.to_string(), name.to_string());
+	if fields.is_empty() {
+		secrets.remove(&key);
+	} else {
+		secrets.insert(key, fields);
+	}
+}
+
+/// Returns the fields of an entity that held plain secrets when it was last loaded
+pub fn plaintext_secrets(entity: LintEntity, name: &str) -> Vec<String> {
+	PLAINTEXT_SECRETS
+		.lock()
+		.unwrap_or_else(PoisonError::into_inner)
+		.get(&(entity.to_string(), name.to_string()))
+		.cloned()
+		.unwrap_or_default()
+}
+
+/// Returns the paths of the fields of a trigger holding plain secrets
+pub fn trigger_plaintext_secrets(trigger: &Trigger) -> Vec<String> {
+	let secrets: Vec<(&str, Option<&SecretValue>)> = match &trigger.config {
+		TriggerTypeConfig::Slack { slack_url, .. } => vec![("slack_url", Some(slack_url))],
+		TriggerTypeConfig::Email {
+			username, password, ..
+		} => vec![("username", Some(username)), ("password", Some(password))],
+		TriggerTypeConfig::Webhook { url, secret, .. } => {
+			vec![("url", Some(url)), ("secret", secret.as_ref())]
+		}
+		TriggerTypeConfig::Telegram { token, .. } => vec![("token", Some(token))],
+		TriggerTypeConfig::Discord { discord_url, .. } => {
+			vec![("discord_url", Some(discord_url))]
+		}
+		TriggerTypeConfig::Script { .. } => vec![],
+	};
+	secrets
+		.into_iter()
+		.filter(|(_, secret)| matches!(secret, Some(SecretValue::Plain(_))))
+		.map(|(field, _)| format!("config.{}", field))
+		.collect()
+}
+
+/// Returns the paths of the fields of a network holding plain secrets
+pub fn network_plaintext_secrets(network: &Network) -> Vec<String> {
+	network
+		.rpc_urls
+		.iter()
+		.enumerate()
+		.filter(|(_, rpc_url)| matches!(rpc_url.url, SecretValue::Plain(_)))
+		.map(|(index, _)| format!("rpc_urls[{}].url", index))
+		.collect()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::utils::tests::builders::{network::NetworkBuilder, trigger::TriggerBuilder};
+
+	#[test]
+	fn test_plaintext_secret_fields() {
+		let trigger = TriggerBuilder::new()
+			.name("plain_webhook")
+			.webhook("https://example.com/hook")
+			.build();
+		assert_eq!(trigger_plaintext_secrets(&trigger), vec!["config.url"]);
+
+		let network = NetworkBuilder::new()
+			.slug("plain_network")
+			.rpc_url("https://rpc.example.com")
+			.build();
+		assert_eq!(network_plaintext_secrets(&network), vec!["rpc_urls[0].url"]);
+	}
+
+	#[test]
+	fn test_allowed_policy_records_plain_secrets() {
+		let policy = SecretsPolicy::default();
+		assert!(policy
+			.enforce(
+				LintEntity::Trigger,
+				"allowed_trigger",
+				vec!["config.url".to_string()]
+			)
+			.is_ok());
+		assert_eq!(
+			plaintext_secrets(LintEntity::Trigger, "allowed_trigger"),
+			vec!["config.url"]
+		);
+	}
+
+	#[test]
+	fn test_denied_policy_names_entity_and_field() {
+		let policy = SecretsPolicy {
+			allow_plain: false,
+			allow_plain_fields: vec!["denied_network.rpc_urls[0].url".to_string()],
+		};
+		assert!(policy
+			.enforce(
+				LintEntity::Network,
+				"denied_network",
+				vec!["rpc_urls[0].url".to_string()]
+			)
+			.is_ok());
+
+		let error = policy
+			.enforce(
+				LintEntity::Network,
+				"denied_network",
+				vec!["rpc_urls[0].url".to_string(), "rpc_urls[1].url".to_string()],
+			)
+			.unwrap_err();
+		assert!(error.to_string().contains(
+			"network 'denied_network' has a plain secret in rpc_urls[1].url, which the secrets \
+			 policy forbids"
+		));
+	}
+}
@@ -10,8 +10,8 @@ use std::{collections::HashMap, fs, path::Path};
 
 use crate::{
 	models::{
-		config::error::ConfigError, ConfigLoader, SecretValue, Trigger, TriggerType,
-		TriggerTypeConfig,
+		config::{error::ConfigError, trigger_plaintext_secrets, LintEntity, SecretsPolicy},
+		ConfigLoader, SecretValue, Trigger, TriggerType, TriggerTypeConfig,
 	},
 	services::trigger::validate_script_config,
 	utils::normalize_string,
@@ -146,6 +146,7 @@ impl ConfigLoader for Trigger {
 			)
 		})?;
 
+		let secrets_policy = SecretsPolicy::load(None)?;
 		let mut trigger_pairs = Vec::new();
 		for entry in entries {
 			let entry = entry.map_err(|e| {
@@ -184,6 +185,13 @@ impl ConfigLoader for Trigger {
 
 				// Validate each trigger before adding it
 				for (name, mut trigger) in file_triggers.triggers {
+					// Check plain secrets against the policy while they can be told apart
+					secrets_policy.enforce(
+						LintEntity::Trigger,
+						&trigger.name,
+						trigger_plaintext_secrets(&trigger),
+					)?;
+
 					// Resolve secrets before validating
 					trigger = trigger.resolve_secrets().await?;
 					if let Err(validation_error) = trigger.validate() {
@@ -225,6 +233,13 @@ impl ConfigLoader for Trigger {
 		let mut config: Trigger = serde_json::from_reader(file)
 			.map_err(|e| ConfigError::parse_error(e.to_string(), None, None))?;
 
+		// Check plain secrets against the policy while they can be told apart
+		SecretsPolicy::load(None)?.enforce(
+			LintEntity::Trigger,
+			&config.name,
+			trigger_plaintext_secrets(&config),
+		)?;
+
 		// Resolve secrets before validating
 		config = config.resolve_secrets().await?;
 
@@ -432,7 +447,9 @@ impl ConfigLoader for Trigger {
 				if let TriggerTypeConfig::Webhook {
 					url,
 					method,
+					headers,
 					message,
+					idempotency_header,
 					..
 				} = &self.config
 				{
@@ -457,6 +474,28 @@ impl ConfigLoader for Trigger {
 							}
 						}
 					}
+					// Validate the idempotency header, which a custom header must not replace
+					if let Some(header) = idempotency_header {
+						if reqwest::header::HeaderName::from_bytes(header.as_bytes()).is_err() {
+							return Err(ConfigError::validation_error(
+								format!("Invalid idempotency header name: {}", header),
+								None,
+								None,
+							));
+						}
+						if headers.as_ref().is_some_and(|headers| {
+							headers.keys().any(|name| name.eq_ignore_ascii_case(header))
+						}) {
+							return Err(ConfigError::validation_error(
+								format!(
+									"Idempotency header {} is also set as a custom header",
+									header
+								),
+								None,
+								None,
+							));
+						}
+					}
 					// Validate message
 					if message.title.trim().is_empty() {
 						return Err(ConfigError::validation_error(
@@ -702,7 +741,7 @@ impl ConfigLoader for Trigger {
 mod tests {
 	use super::*;
 	use crate::models::NotificationMessage;
-	use crate::models::{core::Trigger, ScriptLanguage, SecretString};
+	use crate::models::{core::Trigger, MatchOrigin, OriginKind, ScriptLanguage, SecretString};
 	use crate::utils::tests::builders::trigger::TriggerBuilder;
 	use crate::utils::RetryConfig;
 	use std::{fs::File, io::Write, os::unix::fs::PermissionsExt};
@@ -938,6 +977,36 @@ mod tests {
 			.message("Alert", "")
 			.build();
 		assert!(invalid_body.validate().is_err());
+
+		// Idempotency header
+		let idempotent = || {
+			TriggerBuilder::new()
+				.name("test_webhook")
+				.webhook("https://api.example.com/webhook")
+				.webhook_idempotency_header("Idempotency-Key")
+		};
+		assert!(idempotent().build().validate().is_ok());
+		let invalid_header = TriggerBuilder::new()
+			.name("test_webhook")
+			.webhook("https://api.example.com/webhook")
+			.webhook_idempotency_header("Idempotency Key")
+			.build();
+		assert!(invalid_header
+			.validate()
+			.unwrap_err()
+			.to_string()
+			.contains("Invalid idempotency header name"));
+		let overwritten_header = idempotent()
+			.webhook_headers(HashMap::from([(
+				"idempotency-key".to_string(),
+				"fixed".to_string(),
+			)]))
+			.build();
+		assert!(overwritten_header
+			.validate()
+			.unwrap_err()
+			.to_string()
+			.contains("also set as a custom header"));
 	}
 
 	#[test]
@@ -1446,9 +1515,18 @@ mod tests {
 				message: NotificationMessage {
 					title: "Test".to_string(),
 					body: "x".repeat(TELEGRAM_MAX_BODY_LENGTH + 1), // Exceeds max length
+					format: None,
+					thread_key: None,
+					attachments: None,
 				},
 				retry_policy: RetryConfig::default(),
 			},
+			group_dedup: false,
+			dedup_strategy: Default::default(),
+			rollup: false,
+			accept_origins: None,
+			strict_variables: None,
+			context: None,
 		};
 		assert!(max_body_length.validate().is_err());
 	}
@@ -1465,9 +1543,18 @@ mod tests {
 				message: NotificationMessage {
 					title: "Test".to_string(),
 					body: "z".repeat(DISCORD_MAX_BODY_LENGTH + 1), // Exceeds max length
+					format: None,
+					thread_key: None,
+					attachments: None,
 				},
 				retry_policy: RetryConfig::default(),
 			},
+			group_dedup: false,
+			dedup_strategy: Default::default(),
+			rollup: false,
+			accept_origins: None,
+			strict_variables: None,
+			context: None,
 		};
 		assert!(max_body_length.validate().is_err());
 	}
@@ -1523,4 +1610,38 @@ mod tests {
 			assert!(err.message.contains("Duplicate trigger name found"));
 		}
 	}
+
+	#[test]
+	fn test_accept_origins() {
+		let backfill = MatchOrigin::Backfill {
+			job_id: "backfill-42".to_string(),
+		};
+
+		// Paging channels only accept live matches by default
+		let slack = TriggerBuilder::new()
+			.slack("https://hooks.slack.com/services/xxx")
+			.build();
+		assert!(slack.accepts_origin(&MatchOrigin::Live));
+		assert!(!slack.accepts_origin(&backfill));
+		assert!(!slack.accepts_origin(&MatchOrigin::Replay));
+
+		// Webhooks accept every origin by default
+		let webhook = TriggerBuilder::new()
+			.webhook("https://api.example.com/webhook")
+			.build();
+		assert!(webhook.accepts_origin(&backfill));
+		assert!(webhook.accepts_origin(&MatchOrigin::Replay));
+
+		let slack = TriggerBuilder::new()
+			.slack("https://hooks.slack.com/services/xxx")
+			.accept_origins(vec![OriginKind::Live, OriginKind::Backfill])
+			.build();
+		assert!(slack.accepts_origin(&backfill));
+		assert!(!slack.accepts_origin(&MatchOrigin::Replay));
+		let webhook = TriggerBuilder::new()
+			.webhook("https://api.example.com/webhook")
+			.accept_origins(vec![OriginKind::Live])
+			.build();
+		assert!(!webhook.accepts_origin(&backfill));
+	}
 }
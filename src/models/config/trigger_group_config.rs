@@ -0,0 +1,538 @@
+//! Trigger group configuration loading and validation.
+//!
+//! This module implements the ConfigLoader trait for TriggerGroup configurations, allowing
+//! named lists of triggers to be loaded from JSON files, and expands the groups monitors
+//! reference into their member triggers.
+
+use async_trait::async_trait;
+use std::{collections::HashMap, path::Path};
+
+use crate::{
+	models::{
+		config::error::ConfigError, ConfigLoader, Monitor, Trigger, TriggerGroup, TriggerOverride,
+		TriggerReference,
+	},
+	utils::normalize_string,
+};
+
+impl TriggerGroup {
+	/// Expands a group into the names of its member triggers
+	///
+	/// Nested groups are expanded in place, and triggers reached more than once are kept at
+	/// their first position.
+	///
+	/// # Arguments
+	/// * `name` - Name of the group to expand
+	/// * `groups` - Trigger groups, keyed by name
+	/// * `triggers` - Triggers, keyed by name
+	///
+	/// # Returns
+	/// * `Result<Vec<String>, ConfigError>` - The member trigger names, or an error if a group
+	///   is unknown, references an unknown trigger, shares its name with a trigger or is part
+	///   of a cycle
+	pub fn expand(
+		name: &str,
+		groups: &HashMap<String, TriggerGroup>,
+		triggers: &HashMap<String, Trigger>,
+	) -> Result<Vec<String>, ConfigError> {
+		let mut members = Vec::new();
+		Self::collect_members(name, groups, triggers, &mut Vec::new(), &mut members)?;
+		Ok(members)
+	}
+
+	fn collect_members<'a>(
+		name: &'a str,
+		groups: &'a HashMap<String, TriggerGroup>,
+		triggers: &HashMap<String, Trigger>,
+		path: &mut Vec<&'a str>,
+		members: &mut Vec<String>,
+	) -> Result<(), ConfigError> {
+		let metadata = Some(HashMap::from([(
+			"trigger_group".to_string(),
+			name.to_string(),
+		)]));
+		if path.contains(&name) {
+			return Err(ConfigError::validation_error(
+				format!("Trigger group cycle: {} -> {}", path.join(" -> "), name),
+				None,
+				metadata,
+			));
+		}
+		let Some(group) = groups.get(name) else {
+			return Err(ConfigError::validation_error(
+				format!("Unknown trigger group '{}'", name),
+				None,
+				metadata,
+			));
+		};
+		if triggers.contains_key(name) {
+			return Err(ConfigError::validation_error(
+				format!("Trigger group '{}' has the same name as a trigger", name),
+				None,
+				metadata,
+			));
+		}
+
+		path.push(name);
+		for member in &group.triggers {
+			if groups.contains_key(member) {
+				Self::collect_members(member, groups, triggers, path, members)?;
+			} else if triggers.contains_key(member) {
+				if !members.contains(member) {
+					members.push(member.clone());
+				}
+			} else {
+				return Err(ConfigError::validation_error(
+					format!(
+						"Trigger group '{}' references unknown trigger '{}'",
+						name, member
+					),
+					None,
+					metadata,
+				));
+			}
+		}
+		path.pop();
+		Ok(())
+	}
+}
+
+impl Monitor {
+	/// Returns a copy of the monitor with its trigger group references expanded
+	///
+	/// A group reference is replaced by references to the group's member triggers. Overrides
+	/// set on the group reference apply to every member, message overrides only to members
+	/// that have a message. Members of a group with `rollup` set roll up the monitor's matches.
+	/// A trigger referenced several times is kept at its first position.
+	///
+	/// # Arguments
+	/// * `groups` - Trigger groups, keyed by name
+	/// * `triggers` - Triggers, keyed by name
+	///
+	/// # Returns
+	/// * `Result<Monitor, ConfigError>` - The expanded monitor, or an error naming the monitor
+	///   and the group that could not be expanded
+	pub fn expand_trigger_groups(
+		&self,
+		groups: &HashMap<String, TriggerGroup>,
+		triggers: &HashMap<String, Trigger>,
+	) -> Result<Self, ConfigError> {
+		if !self.triggers.iter().any(|t| groups.contains_key(t.name())) {
+			return Ok(self.clone());
+		}
+
+		let mut references: Vec<TriggerReference> = Vec::with_capacity(self.triggers.len());
+		let mut push = |reference: TriggerReference| {
+			if !references.iter().any(|r| r.name() == reference.name()) {
+				references.push(reference);
+			}
+		};
+
+		for reference in &self.triggers {
+			let group_name = reference.name();
+			if !groups.contains_key(group_name) {
+				push(reference.clone());
+				continue;
+			}
+
+			let members = TriggerGroup::expand(group_name, groups, triggers).map_err(|e| {
+				let reason = match e {
+					ConfigError::ValidationError(context) => context.message,
+					e => e.to_string(),
+				};
+				ConfigError::validation_error(
+					format!(
+						"Monitor '{}' references invalid trigger group '{}': {}",
+						self.name, group_name, reason
+					),
+					None,
+					Some(HashMap::from([
+						("monitor".to_string(), self.name.clone()),
+						("trigger_group".to_string(), group_name.to_string()),
+					])),
+				)
+			})?;
+			let rollup = groups[group_name].rollup;
+			for member in members {
+				push(match reference {
+					TriggerReference::Name(_) if !rollup => TriggerReference::Name(member),
+					TriggerReference::Name(_) => TriggerReference::WithOverrides(TriggerOverride {
+						name: member,
+						message: None,
+						variables: HashMap::new(),
+						rollup,
+					}),
+					TriggerReference::WithOverrides(overrides) => {
+						let has_message = triggers
+							.get(&member)
+							.is_some_and(|trigger| trigger.config.message().is_some());
+						TriggerReference::WithOverrides(TriggerOverride {
+							message: overrides.message.clone().filter(|_| has_message),
+							variables: overrides.variables.clone(),
+							rollup: rollup || overrides.rollup,
+							name: member,
+						})
+					}
+				});
+			}
+		}
+
+		Ok(Monitor {
+			triggers: references,
+			..self.clone()
+		})
+	}
+}
+
+#[async_trait]
+impl ConfigLoader for TriggerGroup {
+	/// Trigger groups hold no secrets
+	async fn resolve_secrets(&self) -> Result<Self, ConfigError> {
+		Ok(self.clone())
+	}
+
+	/// Load all trigger group configurations from a directory
+	///
+	/// Reads and parses all JSON files in the specified directory (or default
+	/// config directory) as trigger group configurations, keyed by group name.
+	/// Trigger groups are optional, so a missing directory yields no groups.
+	async fn load_all<T>(path: Option<&Path>) -> Result<T, ConfigError>
+	where
+		T: FromIterator<(String, Self)>,
+	{
+		let group_dir = path.unwrap_or(Path::new("config/trigger_groups"));
+		let mut pairs = Vec::new();
+
+		if !group_dir.exists() {
+			return Ok(T::from_iter(pairs));
+		}
+
+		for entry in std::fs::read_dir(group_dir).map_err(|e| {
+			ConfigError::file_error(
+				format!("failed to read trigger groups directory: {}", e),
+				Some(Box::new(e)),
+				Some(HashMap::from([(
+					"path".to_string(),
+					group_dir.display().to_string(),
+				)])),
+			)
+		})? {
+			let entry = entry.map_err(|e| {
+				ConfigError::file_error(
+					format!("failed to read directory entry: {}", e),
+					Some(Box::new(e)),
+					Some(HashMap::from([(
+						"path".to_string(),
+						group_dir.display().to_string(),
+					)])),
+				)
+			})?;
+			let path = entry.path();
+
+			if !Self::is_json_file(&path) {
+				continue;
+			}
+
+			let group = Self::load_from_path(&path).await?;
+
+			let existing_groups: Vec<&TriggerGroup> =
+				pairs.iter().map(|(_, group)| group).collect();
+			Self::validate_uniqueness(&existing_groups, &group, &path.display().to_string())?;
+
+			pairs.push((group.name.clone(), group));
+		}
+
+		Ok(T::from_iter(pairs))
+	}
+
+	/// Load a trigger group configuration from a specific file
+	async fn load_from_path(path: &Path) -> Result<Self, ConfigError> {
+		let file = std::fs::File::open(path).map_err(|e| {
+			ConfigError::file_error(
+				format!("failed to open trigger group config file: {}", e),
+				Some(Box::new(e)),
+				Some(HashMap::from([(
+					"path".to_string(),
+					path.display().to_string(),
+				)])),
+			)
+		})?;
+		let config: TriggerGroup = serde_json::from_reader(file).map_err(|e| {
+			ConfigError::parse_error(
+				format!("failed to parse trigger group config: {}", e),
+				Some(Box::new(e)),
+				Some(HashMap::from([(
+					"path".to_string(),
+					path.display().to_string(),
+				)])),
+			)
+		})?;
+
+		config.validate()?;
+
+		Ok(config)
+	}
+
+	/// Validate the trigger group configuration
+	///
+	/// Ensures that the group has a name and lists distinct, non-empty members. Members are
+	/// checked against the triggers when monitors are loaded.
+	fn validate(&self) -> Result<(), ConfigError> {
+		if self.name.trim().is_empty() {
+			return Err(ConfigError::validation_error(
+				"Trigger group name is required",
+				None,
+				None,
+			));
+		}
+
+		if self.triggers.is_empty() {
+			return Err(ConfigError::validation_error(
+				format!("Trigger group '{}' has no triggers", self.name),
+				None,
+				None,
+			));
+		}
+
+		for (index, member) in self.triggers.iter().enumerate() {
+			if member.trim().is_empty() {
+				return Err(ConfigError::validation_error(
+					format!("Trigger group '{}' has an empty trigger name", self.name),
+					None,
+					None,
+				));
+			}
+			if self.triggers[..index].contains(member) {
+				return Err(ConfigError::validation_error(
+					format!(
+						"Trigger group '{}' lists trigger '{}' more than once",
+						self.name, member
+					),
+					None,
+					None,
+				));
+			}
+		}
+
+		Ok(())
+	}
+
+	/// Trigger groups have no protocol to check
+	fn validate_protocol(&self) {}
+
+	fn validate_uniqueness(
+		instances: &[&Self],
+		current_instance: &Self,
+		file_path: &str,
+	) -> Result<(), ConfigError> {
+		if instances.iter().any(|existing_group| {
+			normalize_string(&existing_group.name) == normalize_string(&current_instance.name)
+		}) {
+			return Err(ConfigError::validation_error(
+				format!(
+					"Duplicate trigger group name found: '{}'",
+					current_instance.name
+				),
+				None,
+				Some(HashMap::from([
+					(
+						"trigger_group_name".to_string(),
+						current_instance.name.clone(),
+					),
+					("path".to_string(), file_path.to_string()),
+				])),
+			));
+		}
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{
+		models::MessageOverride,
+		utils::tests::{builders::trigger::TriggerBuilder, evm::monitor::MonitorBuilder},
+	};
+	use std::fs;
+	use tempfile::TempDir;
+
+	fn create_group(name: &str, triggers: &[&str]) -> (String, TriggerGroup) {
+		(
+			name.to_string(),
+			TriggerGroup {
+				name: name.to_string(),
+				triggers: triggers.iter().map(|t| t.to_string()).collect(),
+				rollup: false,
+			},
+		)
+	}
+
+	fn create_triggers() -> HashMap<String, Trigger> {
+		["slack_sec", "pagerduty_sec", "webhook_siem"]
+			.into_iter()
+			.map(|name| (name.to_string(), TriggerBuilder::new().name(name).build()))
+			.chain([(
+				"db_archive".to_string(),
+				TriggerBuilder::new()
+					.name("db_archive")
+					.script(
+						"config/filters/archive.sh",
+						crate::models::ScriptLanguage::Bash,
+					)
+					.build(),
+			)])
+			.collect()
+	}
+
+	fn names(monitor: &Monitor) -> Vec<&str> {
+		monitor.triggers.iter().map(|t| t.name()).collect()
+	}
+
+	#[test]
+	fn test_expand_trigger_groups() {
+		let groups = HashMap::from([
+			create_group("security", &["slack_sec", "pagerduty_sec"]),
+			create_group("all", &["security", "db_archive", "webhook_siem"]),
+		]);
+		let triggers = create_triggers();
+		let monitor = MonitorBuilder::new()
+			.triggers(vec!["webhook_siem".to_string(), "all".to_string()])
+			.build();
+
+		let expanded = monitor.expand_trigger_groups(&groups, &triggers).unwrap();
+
+		assert_eq!(
+			names(&expanded),
+			vec!["webhook_siem", "slack_sec", "pagerduty_sec", "db_archive"]
+		);
+	}
+
+	#[test]
+	fn test_expand_trigger_groups_applies_overrides_to_members() {
+		let groups = HashMap::from([create_group("all", &["slack_sec", "db_archive"])]);
+		let triggers = create_triggers();
+		let monitor = MonitorBuilder::new()
+			.trigger_reference(TriggerReference::WithOverrides(TriggerOverride {
+				name: "all".to_string(),
+				message: Some(MessageOverride {
+					title: Some("[HIGH] ${monitor.name}".to_string()),
+					body: None,
+				}),
+				variables: HashMap::from([("severity".to_string(), "high".to_string())]),
+				rollup: false,
+			}))
+			.build();
+
+		let expanded = monitor.expand_trigger_groups(&groups, &triggers).unwrap();
+
+		assert_eq!(names(&expanded), vec!["slack_sec", "db_archive"]);
+		assert!(expanded.triggers[0].message().is_some());
+		// Script triggers have no message to override
+		assert!(expanded.triggers[1].message().is_none());
+		for reference in &expanded.triggers {
+			assert_eq!(reference.variables().unwrap()["severity"], "high");
+		}
+	}
+
+	#[test]
+	fn test_expand_trigger_groups_applies_rollup_to_members() {
+		let (name, mut group) = create_group("paging", &["slack_sec", "pagerduty_sec"]);
+		group.rollup = true;
+		let groups = HashMap::from([(name, group)]);
+		let triggers = create_triggers();
+		let monitor = MonitorBuilder::new()
+			.triggers(vec!["paging".to_string(), "webhook_siem".to_string()])
+			.build();
+
+		let expanded = monitor.expand_trigger_groups(&groups, &triggers).unwrap();
+
+		assert_eq!(
+			names(&expanded),
+			vec!["slack_sec", "pagerduty_sec", "webhook_siem"]
+		);
+		assert!(expanded.triggers[0].rollup());
+		assert!(expanded.triggers[1].rollup());
+		assert!(!expanded.triggers[2].rollup());
+	}
+
+	#[test]
+	fn test_expand_trigger_groups_errors() {
+		let triggers = create_triggers();
+		let monitor = MonitorBuilder::new()
+			.name("Admin")
+			.triggers(vec!["a".to_string()])
+			.build();
+
+		let groups = HashMap::from([
+			create_group("a", &["slack_sec", "b"]),
+			create_group("b", &["a"]),
+		]);
+		let err = monitor
+			.expand_trigger_groups(&groups, &triggers)
+			.unwrap_err();
+		assert!(err.to_string().contains(
+			"Monitor 'Admin' references invalid trigger group 'a': Trigger group cycle: a -> b -> a"
+		));
+
+		let groups = HashMap::from([create_group("a", &["slack_sec", "sms"])]);
+		let err = monitor
+			.expand_trigger_groups(&groups, &triggers)
+			.unwrap_err();
+		assert!(err
+			.to_string()
+			.contains("Trigger group 'a' references unknown trigger 'sms'"));
+
+		let groups = HashMap::from([create_group("slack_sec", &["pagerduty_sec"])]);
+		assert!(TriggerGroup::expand("slack_sec", &groups, &triggers)
+			.unwrap_err()
+			.to_string()
+			.contains("has the same name as a trigger"));
+	}
+
+	#[test]
+	fn test_validate_trigger_group() {
+		assert!(create_group("a", &["slack_sec"]).1.validate().is_ok());
+		assert!(create_group("", &["slack_sec"]).1.validate().is_err());
+		assert!(create_group("a", &[]).1.validate().is_err());
+		assert!(create_group("a", &["slack_sec", " "]).1.validate().is_err());
+		assert!(create_group("a", &["slack_sec", "slack_sec"])
+			.1
+			.validate()
+			.is_err());
+	}
+
+	#[tokio::test]
+	async fn test_load_all_trigger_groups() {
+		let temp_dir = TempDir::new().unwrap();
+		fs::write(
+			temp_dir.path().join("security.json"),
+			r#"{"name": "security", "triggers": ["slack_sec", "pagerduty_sec"]}"#,
+		)
+		.unwrap();
+
+		let groups: HashMap<String, TriggerGroup> =
+			TriggerGroup::load_all(Some(temp_dir.path())).await.unwrap();
+		assert_eq!(
+			groups["security"].triggers,
+			vec!["slack_sec", "pagerduty_sec"]
+		);
+
+		fs::write(
+			temp_dir.path().join("duplicate.json"),
+			r#"{"name": "Security", "triggers": ["slack_sec"]}"#,
+		)
+		.unwrap();
+		assert!(
+			TriggerGroup::load_all::<HashMap<String, TriggerGroup>>(Some(temp_dir.path()))
+				.await
+				.is_err()
+		);
+
+		let missing: HashMap<String, TriggerGroup> =
+			TriggerGroup::load_all(Some(&temp_dir.path().join("missing")))
+				.await
+				.unwrap();
+		assert!(missing.is_empty());
+	}
+}
@@ -0,0 +1,29 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::models::ContractSpec;
+
+/// Contract spec shared between monitors through the contracts registry.
+///
+/// Monitors reference a contract by name from their addresses instead of embedding its spec.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct Contract {
+	/// Unique name monitors use to reference the contract
+	pub name: String,
+
+	/// Contract spec for decoding contract interactions
+	pub contract_spec: ContractSpec,
+
+	/// Default addresses of the contract, keyed by network slug
+	#[serde(default)]
+	pub addresses: HashMap<String, Vec<String>>,
+
+	/// Label of the contract's addresses, unless a monitor labels them itself
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub label: Option<String>,
+
+	/// Annotations of the contract's addresses, completed by those set by monitors
+	#[serde(default, skip_serializing_if = "HashMap::is_empty")]
+	pub annotations: HashMap<String, String>,
+}
@@ -1,17 +1,34 @@
 //! Core domain models for the blockchain monitoring system.
 //!
 //! This module contains the fundamental data structures that represent:
+//! - Contracts: Contract specs shared between monitors
 //! - Monitors: Configuration for watching blockchain activity
 //! - Networks: Blockchain network definitions and connection details
 //! - Triggers: Actions to take when monitored conditions are met
+//! - Trigger groups: Named lists of triggers referenced by monitors as a single trigger
 
+mod contract;
 mod monitor;
 mod network;
 mod trigger;
+mod trigger_group;
 
+pub use contract::Contract;
 pub use monitor::{
-	AddressWithSpec, EventCondition, FunctionCondition, MatchConditions, Monitor, ScriptLanguage,
-	TransactionCondition, TransactionStatus, TriggerConditions, SCRIPT_LANGUAGE_EXTENSIONS,
+	AddressWithSpec, BaselineAggregate, BaselineCondition, BudgetOverflow, DecodeFailureAlert,
+	Enrichment, EventCondition, FunctionCondition, HttpEnrichment, MatchConditions,
+	MatchHistoryConfig, MessageOverride, Monitor, MonitorOwner, NetworkMetricsCondition,
+	NetworkViewsConfig, NotificationBudget, PresetOverride, PresetReference, ScriptEnrichment,
+	ScriptLanguage, SenderActivityCondition, SenderActivityKind, TransactionCondition,
+	TransactionStatus, TriggerConditions, TriggerOverride, TriggerReference,
+	DEFAULT_ENRICHMENT_TIMEOUT_MS, SCRIPT_LANGUAGE_EXTENSIONS,
 };
-pub use network::{Network, RpcUrl};
-pub use trigger::{NotificationMessage, Trigger, TriggerType, TriggerTypeConfig};
+pub use network::{
+	BlockFetchConfig, MaintenanceWindow, Network, PriceOracleConfig, PriceSource, PricedToken,
+	RpcUrl, DEFAULT_MONITOR_CONCURRENCY, NATIVE_TOKEN,
+};
+pub use trigger::{
+	DedupStrategy, MessageFormat, NotificationMessage, Trigger, TriggerContext, TriggerType,
+	TriggerTypeConfig, WebhookFormat,
+};
+pub use trigger_group::TriggerGroup;
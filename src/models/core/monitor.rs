@@ -1,4 +1,6 @@
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
 use crate::models::{blockchain::ContractSpec, ChainConfiguration};
 
@@ -11,7 +13,7 @@ use crate::models::{blockchain::ContractSpec, ChainConfiguration};
 /// - Triggers conditions refers to a custom filter script that being executed apply extra filters
 ///   to the matched transactions before triggering the notifications
 /// - Triggers to execute when conditions are met
-#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Default)]
+#[derive(Debug, Clone, Deserialize, JsonSchema, Serialize, PartialEq, Default)]
 #[serde(deny_unknown_fields)]
 pub struct Monitor {
 	/// Unique name identifying this monitor
@@ -32,27 +34,491 @@ pub struct Monitor {
 	/// Conditions that should be met prior to triggering notifications
 	pub trigger_conditions: Vec<TriggerConditions>,
 
-	/// IDs of triggers to execute when conditions match
-	pub triggers: Vec<String>,
+	/// Triggers to execute when conditions match, optionally with per-monitor overrides
+	pub triggers: Vec<TriggerReference>,
 
 	/// Chain-specific configurations
 	#[serde(default)]
 	pub chain_configurations: Vec<ChainConfiguration>,
+
+	/// Optional group used to de-duplicate notifications across overlapping monitors
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub dedup_group: Option<String>,
+
+	/// Optional group whose matches within a block are combined into a single notification by
+	/// the triggers that roll up matches
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub rollup_group: Option<String>,
+
+	/// Optional daily cap on the matches delivered to the monitor's triggers
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub notification_budget: Option<NotificationBudget>,
+
+	/// Built-in condition presets expanded into event conditions when the monitor is loaded
+	#[serde(default, skip_serializing_if = "Vec::is_empty")]
+	pub presets: Vec<PresetReference>,
+
+	/// Lookups adding variables to the monitor's matches before they are dispatched
+	#[serde(default, skip_serializing_if = "Vec::is_empty")]
+	pub enrichments: Vec<Enrichment>,
+
+	/// Optional team owning the monitor, surfaced in notifications and metrics
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub owner: Option<MonitorOwner>,
+
+	/// Labels of the monitor, an instance only loads the monitors carrying one of the tags set
+	/// by `MONITOR_TAGS`
+	#[serde(default, skip_serializing_if = "Vec::is_empty")]
+	pub tags: Vec<String>,
+
+	/// Handling of the matches seen through several networks of a network group
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub network_views: Option<NetworkViewsConfig>,
+
+	/// Lookup of the prior occurrences of the monitor's matches at dispatch time
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub history: Option<MatchHistoryConfig>,
+
+	/// Alert raised when the arguments of a condition keep failing to decode with the
+	/// monitor's ABIs (EVM only)
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub alert_on_decode_failure: Option<DecodeFailureAlert>,
+}
+
+/// Team owning a monitor, to know whom to contact when it fires
+#[derive(Debug, Clone, Default, Deserialize, JsonSchema, Serialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct MonitorOwner {
+	/// Name of the owning team
+	pub team: String,
+
+	/// How to reach the team, e.g. a Slack channel or an email address
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub contact: Option<String>,
+
+	/// URL of the runbook describing how to respond to the monitor's alerts
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub runbook_url: Option<String>,
+}
+
+/// Default timeout of an HTTP enrichment, in milliseconds
+pub const DEFAULT_ENRICHMENT_TIMEOUT_MS: u32 = 5_000;
+
+fn default_enrichment_timeout_ms() -> u32 {
+	DEFAULT_ENRICHMENT_TIMEOUT_MS
+}
+
+/// Lookup adding variables to a monitor's matches before they are dispatched
+///
+/// The variables are available to the templates of all the monitor's triggers. A failed
+/// enrichment leaves its variables unset, unless it is `required`, in which case the match is
+/// not delivered.
+#[derive(Debug, Clone, Deserialize, JsonSchema, Serialize, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Enrichment {
+	/// HTTP GET request whose JSON response provides the variables
+	Http(HttpEnrichment),
+	/// Script receiving the match and printing the variables as a JSON object
+	Script(ScriptEnrichment),
+}
+
+/// Enrichment fetching its variables from an HTTP endpoint
+#[derive(Debug, Clone, Deserialize, JsonSchema, Serialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct HttpEnrichment {
+	/// Name identifying the enrichment within the monitor
+	pub name: String,
+
+	/// URL template, match variables such as `${transaction.from}` are substituted
+	pub url: String,
+
+	/// JSON pointers into the response, keyed by the name of the variable they provide
+	pub variables: HashMap<String, String>,
+
+	/// Timeout of the request in milliseconds
+	#[serde(default = "default_enrichment_timeout_ms")]
+	pub timeout_ms: u32,
+
+	/// Time during which the variables fetched from a URL are reused, no caching if unset
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub cache_ttl_secs: Option<u64>,
+
+	/// Whether a failure of the enrichment prevents the match from being delivered
+	#[serde(default)]
+	pub required: bool,
+}
+
+/// Enrichment computing its variables with a script
+///
+/// The script receives the match and arguments like a trigger condition script, and prints
+/// the variables as a JSON object on its last line of output.
+#[derive(Debug, Clone, Deserialize, JsonSchema, Serialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct ScriptEnrichment {
+	/// Name identifying the enrichment within the monitor
+	pub name: String,
+
+	/// The path to the script
+	pub script_path: String,
+
+	/// The language of the script
+	pub language: ScriptLanguage,
+
+	/// The arguments of the script
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub arguments: Option<Vec<String>>,
+
+	/// The timeout of the script
+	pub timeout_ms: u32,
+
+	/// Whether a failure of the enrichment prevents the match from being delivered
+	#[serde(default)]
+	pub required: bool,
+}
+
+impl Enrichment {
+	/// Returns the name of the enrichment
+	pub fn name(&self) -> &str {
+		match self {
+			Enrichment::Http(enrichment) => &enrichment.name,
+			Enrichment::Script(enrichment) => &enrichment.name,
+		}
+	}
+
+	/// Returns whether a failure of the enrichment prevents the match from being delivered
+	pub fn required(&self) -> bool {
+		match self {
+			Enrichment::Http(enrichment) => enrichment.required,
+			Enrichment::Script(enrichment) => enrichment.required,
+		}
+	}
+}
+
+/// Reference from a monitor to a built-in condition preset
+///
+/// Either the plain preset name, or an object naming the preset together with expressions
+/// replacing the preset's defaults for this monitor.
+#[derive(Debug, Clone, Deserialize, JsonSchema, Serialize, PartialEq)]
+#[serde(untagged)]
+pub enum PresetReference {
+	/// Plain preset name
+	Name(String),
+	/// Preset name with monitor-specific overrides
+	WithOverrides(PresetOverride),
+}
+
+/// Monitor-specific overrides applied to a preset when it is expanded
+#[derive(Debug, Clone, Deserialize, JsonSchema, Serialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct PresetOverride {
+	/// Name of the referenced preset
+	pub name: String,
+
+	/// Expressions keyed by event signature, null removes the preset's default expression
+	#[serde(default, skip_serializing_if = "HashMap::is_empty")]
+	pub expressions: HashMap<String, Option<String>>,
+}
+
+impl PresetReference {
+	/// Returns the name of the referenced preset
+	pub fn name(&self) -> &str {
+		match self {
+			PresetReference::Name(name) => name,
+			PresetReference::WithOverrides(reference) => &reference.name,
+		}
+	}
+
+	/// Returns the expression overrides, if any
+	pub fn expressions(&self) -> Option<&HashMap<String, Option<String>>> {
+		match self {
+			PresetReference::Name(_) => None,
+			PresetReference::WithOverrides(reference) => Some(&reference.expressions),
+		}
+	}
+}
+
+impl From<&str> for PresetReference {
+	fn from(name: &str) -> Self {
+		PresetReference::Name(name.to_string())
+	}
+}
+
+/// Daily cap on the notifications sent by a monitor
+///
+/// Once `max_per_day` matches have been delivered, further matches are handled according to
+/// `overflow` until the budget resets at the next local midnight of `utc_offset`.
+#[derive(Debug, Clone, Deserialize, JsonSchema, Serialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct NotificationBudget {
+	/// Maximum number of matches delivered to the monitor's triggers per day
+	pub max_per_day: u32,
+
+	/// What happens to matches once the budget is exhausted
+	#[serde(default)]
+	pub overflow: BudgetOverflow,
+
+	/// UTC offset of the day boundary, "UTC" or "+HH:MM" / "-HH:MM" (defaults to UTC)
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub utc_offset: Option<String>,
+
+	/// Delay between the messages of a digest split across several messages, in milliseconds
+	/// (defaults to 1000)
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub digest_part_delay_ms: Option<u64>,
+
+	/// Triggers of the monitor still receiving matches over the budget with `database_only`
+	/// overflow, typically the webhooks and scripts recording them
+	#[serde(default, skip_serializing_if = "Vec::is_empty")]
+	pub record_triggers: Vec<String>,
+}
+
+/// Handling of the matches of a monitor watching several views of the same chain
+///
+/// Networks sharing a `group` observe the same chain, so a transaction is usually matched once
+/// per view. By default, only the first view to match it notifies. Monitors detecting missing
+/// data can instead be notified by every view and report the matches a view did not see.
+#[derive(Debug, Clone, Deserialize, JsonSchema, Serialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct NetworkViewsConfig {
+	/// Whether a match seen through several views is delivered once (defaults to true)
+	#[serde(default = "default_network_views_dedup")]
+	pub dedup: bool,
+
+	/// Number of blocks a view may lag behind the first view matching a transaction before the
+	/// match is reported as missing from it, disabled when unset
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub divergence_blocks: Option<u64>,
+
+	/// Triggers the divergences are reported to
+	#[serde(default, skip_serializing_if = "Vec::is_empty")]
+	pub divergence_triggers: Vec<String>,
+}
+
+/// Alert on the decoding failures of a monitor's conditions
+///
+/// Function calls and events whose arguments fail to decode with the monitor's ABIs never
+/// match. Once a condition failed to decode `threshold` times on a network, an alert is sent to
+/// `triggers`, so that a wrong ABI does not go unnoticed.
+#[derive(Debug, Clone, Deserialize, JsonSchema, Serialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct DecodeFailureAlert {
+	/// Number of decoding failures of a condition on a network raising the alert
+	#[serde(default = "default_decode_failure_threshold")]
+	pub threshold: u64,
+
+	/// Triggers the alert is sent to, the monitor's triggers when empty
+	#[serde(default, skip_serializing_if = "Vec::is_empty")]
+	pub triggers: Vec<String>,
+}
+
+fn default_decode_failure_threshold() -> u64 {
+	10
+}
+
+/// Lookup of the prior occurrences of a monitor's matches
+///
+/// Matches are similar when they matched the same function and event signatures, and, with
+/// `by_counterparty`, were sent by the same account. The number of prior similar matches is
+/// exposed to templates as `prior_count`, with `first_occurrence` and `last_seen_at`.
+#[derive(Debug, Clone, Default, Deserialize, JsonSchema, Serialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct MatchHistoryConfig {
+	/// Whether the sender of the transaction is part of the similarity key
+	#[serde(default)]
+	pub by_counterparty: bool,
+
+	/// Whether only the first occurrence of a similar match is delivered
+	#[serde(default)]
+	pub first_occurrence_only: bool,
+}
+
+impl Default for NetworkViewsConfig {
+	fn default() -> Self {
+		Self {
+			dedup: default_network_views_dedup(),
+			divergence_blocks: None,
+			divergence_triggers: vec![],
+		}
+	}
+}
+
+fn default_network_views_dedup() -> bool {
+	true
+}
+
+/// Handling of matches over a monitor's notification budget
+#[derive(Debug, Clone, Copy, Deserialize, JsonSchema, Serialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum BudgetOverflow {
+	/// Hold matches back and send a summary of them when the budget resets
+	#[default]
+	Digest,
+	/// Drop matches
+	Drop,
+	/// Only deliver matches to the budget's `record_triggers`, and suppress the other triggers
+	DatabaseOnly,
+}
+
+/// Reference from a monitor to a trigger
+///
+/// Either the plain trigger name, or an object naming the trigger together with message
+/// overrides and extra variables that only apply when this monitor dispatches to it.
+#[derive(Debug, Clone, Deserialize, JsonSchema, Serialize, PartialEq)]
+#[serde(untagged)]
+pub enum TriggerReference {
+	/// Plain trigger name
+	Name(String),
+	/// Trigger name with monitor-specific overrides
+	WithOverrides(TriggerOverride),
+}
+
+/// Monitor-specific overrides applied to a trigger at dispatch time
+#[derive(Debug, Clone, Deserialize, JsonSchema, Serialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct TriggerOverride {
+	/// Name of the referenced trigger
+	pub name: String,
+
+	/// Title and body templates replacing the trigger's own
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub message: Option<MessageOverride>,
+
+	/// Static variables made available to the message templates
+	#[serde(default, skip_serializing_if = "HashMap::is_empty")]
+	pub variables: HashMap<String, String>,
+
+	/// Whether the trigger combines the matches of the monitor's `rollup_group`, as when the
+	/// trigger itself or the trigger group it is referenced through sets `rollup`
+	#[serde(default, skip_serializing_if = "std::ops::Not::not")]
+	pub rollup: bool,
+}
+
+/// Replacement notification templates, unset fields keep the trigger's value
+#[derive(Debug, Clone, Deserialize, JsonSchema, Serialize, PartialEq, Default)]
+#[serde(deny_unknown_fields)]
+pub struct MessageOverride {
+	/// Notification title or subject template
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub title: Option<String>,
+
+	/// Message body template
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub body: Option<String>,
+}
+
+impl TriggerReference {
+	/// Returns the name of the referenced trigger
+	pub fn name(&self) -> &str {
+		match self {
+			TriggerReference::Name(name) => name,
+			TriggerReference::WithOverrides(reference) => &reference.name,
+		}
+	}
+
+	/// Returns the message override, if any
+	pub fn message(&self) -> Option<&MessageOverride> {
+		match self {
+			TriggerReference::Name(_) => None,
+			TriggerReference::WithOverrides(reference) => reference.message.as_ref(),
+		}
+	}
+
+	/// Returns the extra static variables, if any
+	pub fn variables(&self) -> Option<&HashMap<String, String>> {
+		match self {
+			TriggerReference::Name(_) => None,
+			TriggerReference::WithOverrides(reference) => Some(&reference.variables),
+		}
+	}
+
+	/// Returns whether the reference makes the trigger roll up the matches of the monitor's
+	/// `rollup_group`
+	pub fn rollup(&self) -> bool {
+		match self {
+			TriggerReference::Name(_) => false,
+			TriggerReference::WithOverrides(reference) => reference.rollup,
+		}
+	}
+}
+
+impl From<String> for TriggerReference {
+	fn from(name: String) -> Self {
+		TriggerReference::Name(name)
+	}
+}
+
+impl From<&str> for TriggerReference {
+	fn from(name: &str) -> Self {
+		TriggerReference::Name(name.to_string())
+	}
+}
+
+impl PartialEq<str> for TriggerReference {
+	fn eq(&self, other: &str) -> bool {
+		self.name() == other
+	}
+}
+
+impl PartialEq<&str> for TriggerReference {
+	fn eq(&self, other: &&str) -> bool {
+		self.name() == *other
+	}
+}
+
+impl PartialEq<String> for TriggerReference {
+	fn eq(&self, other: &String) -> bool {
+		self.name() == other
+	}
+}
+
+impl Monitor {
+	/// Returns the names of the triggers referenced by this monitor
+	pub fn trigger_names(&self) -> Vec<String> {
+		self.triggers.iter().map(|t| t.name().to_string()).collect()
+	}
+
+	/// Returns the reference to the given trigger, if this monitor has one
+	pub fn trigger_reference(&self, trigger_name: &str) -> Option<&TriggerReference> {
+		self.triggers.iter().find(|t| t.name() == trigger_name)
+	}
+
+	/// Returns the team owning this monitor, or an empty string if it has no owner
+	///
+	/// Used as the `team` label of per-monitor metrics.
+	pub fn owner_team(&self) -> &str {
+		self.owner.as_ref().map_or("", |owner| owner.team.as_str())
+	}
 }
 
 /// Contract address with optional ABI for decoding transactions and events
-#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+#[derive(Debug, Clone, Deserialize, JsonSchema, Serialize, PartialEq)]
 #[serde(deny_unknown_fields)]
 pub struct AddressWithSpec {
 	/// Contract address in the network's native format
+	///
+	/// May be left empty when `contract` is set, to watch the contract's default addresses on
+	/// the monitor's networks.
+	#[serde(default)]
 	pub address: String,
 
 	/// Optional contract spec for decoding contract interactions
 	pub contract_spec: Option<ContractSpec>,
+
+	/// Name of a contract in the contracts registry providing the contract spec
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub contract: Option<String>,
+
+	/// Human readable name of the address, rendered into notifications
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub label: Option<String>,
+
+	/// Free-form annotations of the address rendered next to its label, e.g. its criticality
+	#[serde(default, skip_serializing_if = "HashMap::is_empty")]
+	pub annotations: HashMap<String, String>,
 }
 
 /// Collection of conditions that can trigger a monitor
-#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Default)]
+#[derive(Debug, Clone, Deserialize, JsonSchema, Serialize, PartialEq, Default)]
 #[serde(deny_unknown_fields)]
 pub struct MatchConditions {
 	/// Function calls to match
@@ -63,10 +529,23 @@ pub struct MatchConditions {
 
 	/// Transaction states to match
 	pub transactions: Vec<TransactionCondition>,
+
+	/// Nonce activity of the monitored sender accounts to match (EVM only)
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub sender_activity: Option<SenderActivityCondition>,
+
+	/// Activity baseline the other conditions' matches must spike above to fire (EVM only)
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub baseline: Option<BaselineCondition>,
+
+	/// Threshold on the metrics of the network's blocks, for monitors without addresses (EVM
+	/// and Stellar)
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub network_metrics: Option<NetworkMetricsCondition>,
 }
 
 /// Condition for matching contract function calls
-#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+#[derive(Debug, Clone, Deserialize, JsonSchema, Serialize, PartialEq)]
 #[serde(deny_unknown_fields)]
 pub struct FunctionCondition {
 	/// Function signature (e.g., "transfer(address,uint256)")
@@ -77,7 +556,7 @@ pub struct FunctionCondition {
 }
 
 /// Condition for matching contract events
-#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+#[derive(Debug, Clone, Deserialize, JsonSchema, Serialize, PartialEq)]
 #[serde(deny_unknown_fields)]
 pub struct EventCondition {
 	/// Event signature (e.g., "Transfer(address,address,uint256)")
@@ -85,10 +564,15 @@ pub struct EventCondition {
 
 	/// Optional expression to filter event parameters
 	pub expression: Option<String>,
+
+	/// Whether the event also matches when emitted by contracts other than the monitored
+	/// addresses, decoded with their contract specs (EVM only)
+	#[serde(default, skip_serializing_if = "std::ops::Not::not")]
+	pub match_any_emitter: bool,
 }
 
 /// Condition for matching transaction states
-#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+#[derive(Debug, Clone, Deserialize, JsonSchema, Serialize, PartialEq)]
 #[serde(deny_unknown_fields)]
 pub struct TransactionCondition {
 	/// Required transaction status
@@ -98,8 +582,158 @@ pub struct TransactionCondition {
 	pub expression: Option<String>,
 }
 
+/// Condition for matching the nonce activity of the monitored sender accounts
+///
+/// Transactions sent by the monitor's addresses are tracked by nonce across processed blocks
+/// to detect nonce gaps, replaced transactions and nonces left unmined.
+#[derive(Debug, Clone, Deserialize, JsonSchema, Serialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct SenderActivityCondition {
+	/// Kinds of activity to match, all of them if empty
+	#[serde(default, skip_serializing_if = "Vec::is_empty")]
+	pub kinds: Vec<SenderActivityKind>,
+
+	/// Seconds after which a missing nonce is reported as stuck
+	#[serde(default = "default_stuck_after_secs")]
+	pub stuck_after_secs: u64,
+
+	/// Optional expression over `nonce`, `expected_nonce`, `replaced_hash` and
+	/// `stuck_duration`
+	pub expression: Option<String>,
+}
+
+fn default_stuck_after_secs() -> u64 {
+	300
+}
+
+impl SenderActivityCondition {
+	/// Returns true if the condition matches the given kind of activity
+	pub fn matches_kind(&self, kind: SenderActivityKind) -> bool {
+		self.kinds.is_empty() || self.kinds.contains(&kind)
+	}
+}
+
+/// Kinds of sender account activity
+#[derive(Debug, Copy, Clone, Deserialize, JsonSchema, Serialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum SenderActivityKind {
+	/// A transaction was sent with a nonce above the next expected one
+	NonceGap,
+	/// A nonce already seen was used again by a different transaction
+	Replacement,
+	/// A missing nonce stayed unmined past the configured threshold
+	Stuck,
+}
+
+impl std::fmt::Display for SenderActivityKind {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			SenderActivityKind::NonceGap => write!(f, "nonce_gap"),
+			SenderActivityKind::Replacement => write!(f, "replacement"),
+			SenderActivityKind::Stuck => write!(f, "stuck"),
+		}
+	}
+}
+
+/// Condition comparing the recent activity of a monitor with its usual activity
+///
+/// The matches of the monitor's other conditions are aggregated over a short and a long
+/// window of blocks. A match fires when the short window value exceeds `factor` times the
+/// average of the long window, scaled to the length of the short window, and fires again only
+/// once the value went back below the threshold.
+#[derive(Debug, Clone, Deserialize, JsonSchema, Serialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct BaselineCondition {
+	/// How the matches are aggregated
+	#[serde(default)]
+	pub aggregate: BaselineAggregate,
+
+	/// Decoded argument summed by the `sum` aggregate, e.g. `value`
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub amount: Option<String>,
+
+	/// Number of blocks of the window holding the recent activity
+	pub short_window_blocks: u64,
+
+	/// Number of blocks of the window the usual activity is averaged over, leaving out the
+	/// short window at its end
+	pub long_window_blocks: u64,
+
+	/// Multiple of the long window average the short window value must exceed
+	pub factor: f64,
+
+	/// Number of blocks observed before the condition can fire, defaults to the long window
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub warmup_blocks: Option<u64>,
+
+	/// Minimum long window average the short window value is compared with
+	#[serde(default = "default_baseline_floor")]
+	pub floor: f64,
+}
+
+fn default_baseline_floor() -> f64 {
+	1.0
+}
+
+impl BaselineCondition {
+	/// Returns the number of blocks observed before the condition can fire
+	pub fn warmup_blocks(&self) -> u64 {
+		self.warmup_blocks.unwrap_or(self.long_window_blocks)
+	}
+}
+
+/// How the matches of a baseline condition are aggregated
+#[derive(Debug, Copy, Clone, Default, Deserialize, JsonSchema, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum BaselineAggregate {
+	/// Number of matches
+	#[default]
+	Count,
+	/// Sum of the decoded `amount` argument of the matches
+	Sum,
+}
+
+impl std::fmt::Display for BaselineAggregate {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			BaselineAggregate::Count => write!(f, "count"),
+			BaselineAggregate::Sum => write!(f, "sum"),
+		}
+	}
+}
+
+/// Condition on the metrics of the blocks of a network
+///
+/// The expression is evaluated against the metrics of every block, such as its base fee or
+/// transaction count. A match fires once the expression held for `consecutive_blocks` blocks
+/// in a row, and the next match needs as many blocks again, at least `cooldown_blocks` blocks
+/// after the previous one.
+#[derive(Debug, Clone, Deserialize, JsonSchema, Serialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct NetworkMetricsCondition {
+	/// Expression over the metrics of a block, e.g. `base_fee > 150000000000`
+	pub expression: String,
+
+	/// Number of blocks in a row the expression must hold for before a match fires
+	#[serde(default = "default_consecutive_blocks")]
+	pub consecutive_blocks: u64,
+
+	/// Number of blocks after a match during which no other match fires
+	#[serde(default)]
+	pub cooldown_blocks: u64,
+
+	/// Update fraction of the blob base fee the `blob_gas_price` metric is computed with,
+	/// defaulting to the value of the Prague upgrade (EVM only)
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub blob_base_fee_update_fraction: Option<u64>,
+}
+
+fn default_consecutive_blocks() -> u64 {
+	1
+}
+
 /// Possible transaction execution states
-#[derive(Debug, Copy, Clone, Deserialize, Serialize, PartialEq)]
+#[derive(Debug, Copy, Clone, Deserialize, JsonSchema, Serialize, PartialEq)]
 #[serde(deny_unknown_fields)]
 pub enum TransactionStatus {
 	/// Match any transaction status
@@ -111,7 +745,7 @@ pub enum TransactionStatus {
 }
 
 /// Conditions that should be met prior to triggering notifications
-#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+#[derive(Debug, Clone, Deserialize, JsonSchema, Serialize, PartialEq)]
 #[serde(deny_unknown_fields)]
 pub struct TriggerConditions {
 	/// The path to the script
@@ -128,7 +762,7 @@ pub struct TriggerConditions {
 	pub timeout_ms: u32,
 }
 /// The possible languages of the script
-#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Hash, Eq)]
+#[derive(Debug, Clone, Deserialize, JsonSchema, Serialize, PartialEq, Hash, Eq)]
 pub enum ScriptLanguage {
 	JavaScript,
 	Python,
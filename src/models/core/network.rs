@@ -1,11 +1,16 @@
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
 use crate::models::{BlockChainType, SecretValue};
 
+/// Default number of monitors evaluated concurrently for a block
+pub const DEFAULT_MONITOR_CONCURRENCY: usize = 8;
+
 /// Configuration for connecting to and interacting with a blockchain network.
 ///
 /// Defines connection details and operational parameters for a specific blockchain network.
-#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+#[derive(Debug, Clone, Deserialize, JsonSchema, Serialize, PartialEq)]
 #[serde(deny_unknown_fields)]
 pub struct Network {
 	/// Type of blockchain (EVM, Stellar, etc)
@@ -40,10 +45,186 @@ pub struct Network {
 
 	/// Whether to store processed blocks
 	pub store_blocks: Option<bool>,
+
+	/// Scheduled maintenance windows during which block processing is paused
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub maintenance_windows: Option<Vec<MaintenanceWindow>>,
+
+	/// Per-block fetch limits, enables recovery from blocks that cannot be fetched
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub block_fetch: Option<BlockFetchConfig>,
+
+	/// Maximum number of monitors evaluated concurrently for a block, 1 evaluates them one at a
+	/// time
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub monitor_concurrency: Option<usize>,
+
+	/// Group of networks observing the same chain through different RPC views, e.g. an internal
+	/// node and a public provider. Monitors may reference the group instead of its members.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub group: Option<String>,
+
+	/// Source of the USD prices of the network's tokens, used by `usd()` in expressions and
+	/// the `*_usd` template variables (EVM only)
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub price_oracle: Option<PriceOracleConfig>,
+}
+
+impl Network {
+	/// Returns the number of monitors evaluated concurrently for a block
+	pub fn monitor_concurrency(&self) -> usize {
+		self.monitor_concurrency
+			.unwrap_or(DEFAULT_MONITOR_CONCURRENCY)
+			.max(1)
+	}
+}
+
+/// Token used for the network's own currency in price oracle configurations and `usd()`
+pub const NATIVE_TOKEN: &str = "native";
+
+/// USD prices of the tokens of a network
+///
+/// Prices are refreshed at most once per `cache_ttl_secs`, failed refreshes included, and a
+/// price older than `max_age_secs` is considered stale and not used.
+#[derive(Debug, Clone, Deserialize, JsonSchema, Serialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct PriceOracleConfig {
+	/// Where the prices are read from
+	pub source: PriceSource,
+
+	/// Priced tokens by contract address, or `native` for the network's currency
+	pub tokens: HashMap<String, PricedToken>,
+
+	/// Time in seconds the prices are reused before being refreshed
+	#[serde(default = "default_price_cache_ttl_secs")]
+	pub cache_ttl_secs: u64,
+
+	/// Age in seconds after which a price is stale
+	#[serde(default = "default_price_max_age_secs")]
+	pub max_age_secs: u64,
+}
+
+fn default_price_cache_ttl_secs() -> u64 {
+	60
+}
+
+fn default_price_max_age_secs() -> u64 {
+	3_600
+}
+
+/// Source of the prices of a price oracle
+#[derive(Debug, Clone, Deserialize, JsonSchema, Serialize, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case", deny_unknown_fields)]
+pub enum PriceSource {
+	/// Prices set in the configuration of the tokens
+	Static,
+	/// Prices fetched from a Coingecko compatible `simple/price` endpoint, using the `id` of
+	/// the tokens
+	Http {
+		/// URL of the endpoint, e.g. `https://api.coingecko.com/api/v3/simple/price`
+		url: String,
+	},
+	/// Prices read from the Chainlink USD feeds of the tokens through the network's RPC
+	Chainlink,
+}
+
+/// Token priced by a price oracle
+#[derive(Debug, Clone, Deserialize, JsonSchema, Serialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct PricedToken {
+	/// Number of decimals of the token amounts, e.g. 18 for ETH and 6 for USDC
+	pub decimals: u8,
+
+	/// Price in USD, for static sources
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub price: Option<String>,
+
+	/// Identifier of the token at an HTTP source, e.g. `usd-coin` on Coingecko
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub id: Option<String>,
+
+	/// Address of the Chainlink USD feed of the token, for Chainlink sources
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub feed: Option<String>,
+}
+
+/// Limits applied to fetching individual blocks
+///
+/// When a block range cannot be fetched, the blocks are fetched one at a time instead. A block
+/// still failing after its retry budget is recorded as failed and skipped, and failed blocks
+/// are retried in the background.
+#[derive(Debug, Clone, Deserialize, JsonSchema, Serialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct BlockFetchConfig {
+	/// Time allowed for fetching a single block in milliseconds
+	#[serde(default = "default_block_fetch_timeout_ms")]
+	pub timeout_ms: u64,
+
+	/// Number of retries after the first attempt before a block is recorded as failed
+	#[serde(default = "default_block_fetch_max_retries")]
+	pub max_retries: u32,
+
+	/// Maximum number of failed blocks kept for the network
+	#[serde(default = "default_max_failed_blocks")]
+	pub max_failed_blocks: usize,
+
+	/// Interval between background retries of failed blocks in milliseconds
+	#[serde(default = "default_failed_blocks_retry_interval_ms")]
+	pub retry_interval_ms: u64,
+}
+
+fn default_block_fetch_timeout_ms() -> u64 {
+	10_000
+}
+
+fn default_block_fetch_max_retries() -> u32 {
+	3
+}
+
+fn default_max_failed_blocks() -> usize {
+	1_000
+}
+
+fn default_failed_blocks_retry_interval_ms() -> u64 {
+	300_000
+}
+
+impl Default for BlockFetchConfig {
+	fn default() -> Self {
+		Self {
+			timeout_ms: default_block_fetch_timeout_ms(),
+			max_retries: default_block_fetch_max_retries(),
+			max_failed_blocks: default_max_failed_blocks(),
+			retry_interval_ms: default_failed_blocks_retry_interval_ms(),
+		}
+	}
+}
+
+/// Recurring period during which the network's RPC endpoints are expected to be unavailable
+///
+/// While a window is active the block watcher skips fetching for the network without
+/// advancing the last processed block, so the missed range is caught up once it ends.
+#[derive(Debug, Clone, Deserialize, JsonSchema, Serialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct MaintenanceWindow {
+	/// Cron expression describing when each window starts
+	pub cron_schedule: String,
+
+	/// How long each window lasts in milliseconds
+	pub duration_ms: u64,
+
+	/// Fixed UTC offset the cron expression is evaluated in (e.g. "+02:00"), defaults to UTC
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub utc_offset: Option<String>,
+
+	/// IANA time zone the cron expression is evaluated in (e.g. "Europe/Berlin"), following
+	/// its daylight saving time changes. Cannot be combined with `utc_offset`
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub timezone: Option<String>,
 }
 
 /// RPC endpoint configuration with load balancing weight
-#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+#[derive(Debug, Clone, Deserialize, JsonSchema, Serialize, PartialEq)]
 #[serde(deny_unknown_fields)]
 pub struct RpcUrl {
 	/// Type of RPC endpoint (e.g. "rpc")
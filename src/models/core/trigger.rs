@@ -1,12 +1,16 @@
 use crate::{
-	models::{core::ScriptLanguage, SecretValue},
+	models::{
+		core::{MessageOverride, ScriptLanguage},
+		MatchOrigin, OriginKind, SecretValue,
+	},
 	utils::RetryConfig,
 };
 use email_address::EmailAddress;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 /// Configuration for actions to take when monitored conditions are met.
-#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+#[derive(Debug, Clone, Deserialize, JsonSchema, Serialize, PartialEq)]
 #[serde(deny_unknown_fields)]
 pub struct Trigger {
 	/// Unique name identifying this trigger
@@ -17,10 +21,98 @@ pub struct Trigger {
 
 	/// Configuration specific to the trigger type
 	pub config: TriggerTypeConfig,
+
+	/// Whether matches from monitors sharing a `dedup_group` are delivered only once per
+	/// transaction within a block
+	#[serde(default, skip_serializing_if = "std::ops::Not::not")]
+	pub group_dedup: bool,
+
+	/// Which match of a dedup group is delivered when several match the same transaction
+	#[serde(default, skip_serializing_if = "DedupStrategy::is_first")]
+	pub dedup_strategy: DedupStrategy,
+
+	/// Whether matches from monitors sharing a `rollup_group` within a block are delivered as a
+	/// single combined notification
+	#[serde(default, skip_serializing_if = "std::ops::Not::not")]
+	pub rollup: bool,
+
+	/// Origins of the matches the trigger is sent, defaulting to live matches only for chat and
+	/// email channels and to all origins for webhooks and scripts
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub accept_origins: Option<Vec<OriginKind>>,
+
+	/// Whether references to unknown or unset template variables are errors, defaulting to
+	/// `STRICT_TEMPLATE_VARIABLES`
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub strict_variables: Option<bool>,
+
+	/// Earlier matches of the monitor included in the trigger's notifications
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub context: Option<TriggerContext>,
+}
+
+/// Earlier matches of a monitor a trigger includes in its notifications
+///
+/// The most recent `prior_matches` matches of the monitor are looked up in the match history
+/// when the trigger renders a notification, and exposed to its templates as `prior`.
+#[derive(Debug, Clone, Copy, Default, Deserialize, JsonSchema, Serialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct TriggerContext {
+	/// Number of earlier matches included
+	pub prior_matches: u8,
+
+	/// Whether only the earlier matches sent by the same account are included
+	#[serde(default)]
+	pub by_counterparty: bool,
+}
+
+/// Match delivered by a `group_dedup` trigger when monitors of a dedup group overlap
+#[derive(Debug, Clone, Copy, Default, Deserialize, JsonSchema, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum DedupStrategy {
+	/// The first match, in dispatch order
+	#[default]
+	First,
+	/// The match with the highest `severity` variable, ties going to the first match
+	HighestSeverity,
+}
+
+impl DedupStrategy {
+	/// Returns whether this is the default strategy
+	pub fn is_first(&self) -> bool {
+		*self == DedupStrategy::First
+	}
+}
+
+impl Trigger {
+	/// Returns whether matches of the given origin are sent to the trigger
+	pub fn accepts_origin(&self, origin: &MatchOrigin) -> bool {
+		match &self.accept_origins {
+			Some(accepted) => accepted.contains(&origin.kind()),
+			None => origin.is_live() || !self.trigger_type.is_paging(),
+		}
+	}
+
+	/// Returns a copy of the trigger with the given title and body templates applied
+	///
+	/// Unset override fields keep the trigger's own templates. Triggers without a message
+	/// (scripts) are returned unchanged.
+	pub fn with_message_override(&self, message_override: &MessageOverride) -> Trigger {
+		let mut trigger = self.clone();
+		if let Some(message) = trigger.config.message_mut() {
+			if let Some(title) = &message_override.title {
+				message.title = title.clone();
+			}
+			if let Some(body) = &message_override.body {
+				message.body = body.clone();
+			}
+		}
+		trigger
+	}
 }
 
 /// Supported trigger action types
-#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+#[derive(Debug, Clone, Deserialize, JsonSchema, Serialize, PartialEq)]
 #[serde(rename_all = "lowercase")]
 #[serde(deny_unknown_fields)]
 pub enum TriggerType {
@@ -38,18 +130,70 @@ pub enum TriggerType {
 	Script,
 }
 
+impl TriggerType {
+	/// Returns whether the channel pages people, as opposed to feeding other systems
+	///
+	/// Paging channels only receive live matches unless the trigger sets `accept_origins`.
+	pub fn is_paging(&self) -> bool {
+		!matches!(self, TriggerType::Webhook | TriggerType::Script)
+	}
+
+	/// Returns the name of the trigger type, as used in configuration files
+	pub fn as_str(&self) -> &'static str {
+		match self {
+			TriggerType::Slack => "slack",
+			TriggerType::Email => "email",
+			TriggerType::Webhook => "webhook",
+			TriggerType::Telegram => "telegram",
+			TriggerType::Discord => "discord",
+			TriggerType::Script => "script",
+		}
+	}
+}
+
+/// Format of a notification message body
+#[derive(Debug, Clone, Copy, Deserialize, JsonSchema, Serialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum MessageFormat {
+	/// Markdown, converted by channels that do not render it natively
+	#[default]
+	Markdown,
+	/// HTML, sent as is
+	Html,
+}
+
+/// Format of the body of generic webhook requests
+#[derive(Debug, Clone, Copy, Deserialize, JsonSchema, Serialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum WebhookFormat {
+	/// JSON object with the title and body of the message
+	#[default]
+	Json,
+	/// Body of a Slack incoming webhook, for receivers compatible with Slack
+	SlackCompatible,
+}
+
 /// Notification message fields
-#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Default)]
+#[derive(Debug, Clone, Deserialize, JsonSchema, Serialize, PartialEq, Default)]
 #[serde(deny_unknown_fields)]
 pub struct NotificationMessage {
 	/// Notification title or subject
 	pub title: String,
 	/// Message template
 	pub body: String,
+	/// Format of the message body (default `markdown`)
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub format: Option<MessageFormat>,
+	/// Template of the key grouping notifications into a thread
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub thread_key: Option<String>,
+	/// Paths of the files attached to every notification
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub attachments: Option<Vec<String>>,
 }
 
 /// Type-specific configuration for triggers
-#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+#[derive(Debug, Clone, Deserialize, JsonSchema, Serialize, PartialEq)]
 #[serde(deny_unknown_fields)]
 #[serde(untagged)]
 pub enum TriggerTypeConfig {
@@ -76,8 +220,10 @@ pub enum TriggerTypeConfig {
 		/// Notification message
 		message: NotificationMessage,
 		/// Email sender
+		#[schemars(with = "String")]
 		sender: EmailAddress,
 		/// Email recipients
+		#[schemars(with = "Vec<String>")]
 		recipients: Vec<EmailAddress>,
 		/// Retry policy for SMTP requests
 		#[serde(default)]
@@ -98,6 +244,12 @@ pub enum TriggerTypeConfig {
 		/// Retry policy for HTTP requests
 		#[serde(default)]
 		retry_policy: RetryConfig,
+		/// Name of the header carrying the idempotency key (default `Idempotency-Key`)
+		#[serde(default, skip_serializing_if = "Option::is_none")]
+		idempotency_header: Option<String>,
+		/// Format of the request body (default `json`)
+		#[serde(default, skip_serializing_if = "Option::is_none")]
+		format: Option<WebhookFormat>,
 	},
 	/// Telegram notification configuration
 	Telegram {
@@ -138,6 +290,30 @@ pub enum TriggerTypeConfig {
 }
 
 impl TriggerTypeConfig {
+	/// Get the notification message of the trigger type, if applicable.
+	pub fn message(&self) -> Option<&NotificationMessage> {
+		match self {
+			Self::Slack { message, .. }
+			| Self::Email { message, .. }
+			| Self::Webhook { message, .. }
+			| Self::Telegram { message, .. }
+			| Self::Discord { message, .. } => Some(message),
+			Self::Script { .. } => None,
+		}
+	}
+
+	/// Get a mutable reference to the notification message, if applicable.
+	pub fn message_mut(&mut self) -> Option<&mut NotificationMessage> {
+		match self {
+			Self::Slack { message, .. }
+			| Self::Email { message, .. }
+			| Self::Webhook { message, .. }
+			| Self::Telegram { message, .. }
+			| Self::Discord { message, .. } => Some(message),
+			Self::Script { .. } => None,
+		}
+	}
+
 	/// Get the retry policy for the trigger type, if applicable.
 	pub fn get_retry_policy(&self) -> Option<RetryConfig> {
 		match self {
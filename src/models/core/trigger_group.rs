@@ -0,0 +1,20 @@
+use serde::{Deserialize, Serialize};
+
+/// Named list of triggers that monitors reference as a single trigger.
+///
+/// Members are trigger names or names of other groups, expanded in order when monitors are
+/// loaded.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct TriggerGroup {
+	/// Unique name monitors use to reference the group
+	pub name: String,
+
+	/// Names of the member triggers or groups, in dispatch order
+	pub triggers: Vec<String>,
+
+	/// Whether the members referenced through the group combine the matches of the monitor's
+	/// `rollup_group` into a single notification
+	#[serde(default, skip_serializing_if = "std::ops::Not::not")]
+	pub rollup: bool,
+}
@@ -4,7 +4,7 @@
 //!
 //! - `blockchain`: Platform-specific implementations for different blockchains
 //! - `config`: Configuration loading and validation
-//! - `core`: Core domain models (Monitor, Network, Trigger)
+//! - `core`: Core domain models (Contract, Monitor, Network, Trigger)
 //! - `security`: Security models (Secret)
 
 mod blockchain;
@@ -14,14 +14,15 @@ mod security;
 
 // Re-export blockchain types
 pub use blockchain::{
-	BlockChainType, BlockType, ChainConfiguration, ContractSpec, MonitorMatch, ProcessedBlock,
+	AlertTiming, BlockChainType, BlockType, ChainConfiguration, ContractSpec, MatchOrigin,
+	MonitorMatch, NetworkMetrics, NetworkMetricsSample, OriginKind, ProcessedBlock,
 	TransactionType,
 };
 
 pub use blockchain::evm::{
-	EVMBaseReceipt, EVMBaseTransaction, EVMBlock, EVMContractSpec, EVMMatchArguments,
-	EVMMatchParamEntry, EVMMatchParamsMap, EVMMonitorConfig, EVMMonitorMatch, EVMReceiptLog,
-	EVMTransaction, EVMTransactionReceipt,
+	EVMBaseReceipt, EVMBaseTransaction, EVMBaselineActivity, EVMBlock, EVMContractSpec,
+	EVMMatchArguments, EVMMatchParamEntry, EVMMatchParamsMap, EVMMonitorConfig, EVMMonitorMatch,
+	EVMReceiptLog, EVMSenderActivity, EVMTransaction, EVMTransactionReceipt,
 };
 
 pub use blockchain::stellar::{
@@ -43,13 +44,25 @@ pub use blockchain::midnight::{
 
 // Re-export core types
 pub use core::{
-	AddressWithSpec, EventCondition, FunctionCondition, MatchConditions, Monitor, Network,
-	NotificationMessage, RpcUrl, ScriptLanguage, TransactionCondition, TransactionStatus, Trigger,
-	TriggerConditions, TriggerType, TriggerTypeConfig, SCRIPT_LANGUAGE_EXTENSIONS,
+	AddressWithSpec, BaselineAggregate, BaselineCondition, BlockFetchConfig, BudgetOverflow,
+	Contract, DecodeFailureAlert, Enrichment, EventCondition, FunctionCondition, HttpEnrichment,
+	MaintenanceWindow, MatchConditions, MatchHistoryConfig, MessageFormat, MessageOverride,
+	Monitor, MonitorOwner, Network, NetworkMetricsCondition, NetworkViewsConfig,
+	NotificationBudget, NotificationMessage, PresetOverride, PresetReference, PriceOracleConfig,
+	PriceSource, PricedToken, RpcUrl, ScriptEnrichment, ScriptLanguage, SenderActivityCondition,
+	SenderActivityKind, TransactionCondition, TransactionStatus, Trigger, TriggerConditions,
+	TriggerContext, TriggerGroup, TriggerOverride, TriggerReference, TriggerType,
+	TriggerTypeConfig, WebhookFormat, DEFAULT_ENRICHMENT_TIMEOUT_MS, DEFAULT_MONITOR_CONCURRENCY,
+	NATIVE_TOKEN, SCRIPT_LANGUAGE_EXTENSIONS,
 };
 
 // Re-export config types
-pub use config::{ConfigError, ConfigLoader};
+pub use config::{
+	config_schemas, content_hash, find_preset, lint_configuration, redact_secrets,
+	write_config_schemas, ConfigDiff, ConfigError, ConfigLoader, ConfigSchemaKind, ConfigSnapshot,
+	ContentHash, EntryDiff, FieldChange, LintCode, LintConfig, LintEntity, LintWarning,
+	ModifiedEntry, Preset, PresetEvent, PRESETS,
+};
 
 // Re-export security types
 pub use security::{SecretString, SecretValue, SecurityError};
@@ -11,8 +11,9 @@
 //! - Serde support for configuration files
 
 use oz_keystore::HashicorpCloudClient;
+use schemars::{json_schema, JsonSchema, Schema, SchemaGenerator};
 use serde::{Deserialize, Serialize};
-use std::{env, fmt, sync::Arc};
+use std::{borrow::Cow, env, fmt, sync::Arc};
 use tokio::sync::OnceCell;
 use zeroize::{Zeroize, ZeroizeOnDrop};
 
@@ -133,6 +134,46 @@ impl_case_insensitive_enum!(SecretValue, {
 	"hashicorpcloudvault" => HashicorpCloudVault,
 });
 
+/// JSON Schema of a secret reference
+///
+/// The `type` field is matched case-insensitively on deserialization, which the schema
+/// expresses as a pattern since JSON Schema enums are case-sensitive.
+impl JsonSchema for SecretValue {
+	fn schema_name() -> Cow<'static, str> {
+		"SecretValue".into()
+	}
+
+	fn json_schema(_generator: &mut SchemaGenerator) -> Schema {
+		let pattern = ["plain", "environment", "hashicorpcloudvault"]
+			.iter()
+			.map(|variant| {
+				variant
+					.chars()
+					.map(|c| format!("[{}{}]", c.to_ascii_uppercase(), c))
+					.collect::<String>()
+			})
+			.collect::<Vec<_>>()
+			.join("|");
+
+		json_schema!({
+			"description": "Secret sourced from a plain value, an environment variable or Hashicorp Cloud Vault",
+			"type": "object",
+			"properties": {
+				"type": {
+					"description": "Source of the secret: plain, environment or hashicorpcloudvault (case-insensitive)",
+					"type": "string",
+					"pattern": format!("^({})$", pattern),
+				},
+				"value": {
+					"description": "Secret value, environment variable name or vault secret name",
+					"type": "string",
+				},
+			},
+			"required": ["type", "value"],
+		})
+	}
+}
+
 impl PartialEq for SecretValue {
 	fn eq(&self, other: &Self) -> bool {
 		match (self, other) {
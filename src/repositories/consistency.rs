@@ -0,0 +1,321 @@
+//! Consistency of the references between monitors, networks and triggers.
+//!
+//! Monitors referencing networks or triggers that do not exist fail to load. The opposite drift,
+//! triggers and networks no monitor references anymore, loads fine but hides typos and leftover
+//! configuration. The consistency report lists both, and unused entries are logged as warnings
+//! when monitors are loaded, or fail the load with `STRICT_CONFIG_CONSISTENCY=true`.
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap};
+
+use crate::{
+	models::{Monitor, Network, Trigger},
+	repositories::error::RepositoryError,
+};
+
+/// Environment variable failing the load of configurations with unused triggers or networks
+pub const STRICT_CONFIG_CONSISTENCY_ENV: &str = "STRICT_CONFIG_CONSISTENCY";
+
+/// How unused triggers and networks are handled
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ConsistencyMode {
+	/// Unused entries are logged as warnings
+	#[default]
+	Lenient,
+	/// Unused entries fail the load
+	Strict,
+}
+
+impl ConsistencyMode {
+	/// Returns the mode set by `STRICT_CONFIG_CONSISTENCY`, lenient unless it is `true`
+	pub fn from_env() -> Self {
+		match std::env::var(STRICT_CONFIG_CONSISTENCY_ENV) {
+			Ok(value) if value == "true" => Self::Strict,
+			_ => Self::Lenient,
+		}
+	}
+}
+
+/// References of a monitor to networks and triggers that do not exist
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct MonitorConsistency {
+	/// Name of the monitor
+	pub name: String,
+	/// Networks the monitor references that do not exist
+	pub missing_networks: Vec<String>,
+	/// Triggers the monitor references that do not exist
+	pub missing_triggers: Vec<String>,
+}
+
+/// Inconsistencies between monitors, networks and triggers
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct ConfigValidationReport {
+	/// Networks referenced by a monitor that do not exist, sorted
+	pub missing_networks: Vec<String>,
+	/// Triggers referenced by a monitor that do not exist, sorted
+	pub missing_triggers: Vec<String>,
+	/// Triggers no monitor references, sorted
+	pub unused_triggers: Vec<String>,
+	/// Networks no monitor references, sorted
+	pub unused_networks: Vec<String>,
+	/// Monitors with missing references, sorted by name
+	pub monitors: Vec<MonitorConsistency>,
+}
+
+impl ConfigValidationReport {
+	/// Returns true if a monitor references a network or trigger that does not exist
+	pub fn has_missing_references(&self) -> bool {
+		!self.missing_networks.is_empty() || !self.missing_triggers.is_empty()
+	}
+
+	/// Returns true if a trigger or network is not referenced by any monitor
+	pub fn has_unused_entries(&self) -> bool {
+		!self.unused_triggers.is_empty() || !self.unused_networks.is_empty()
+	}
+
+	/// Returns true if the configuration is consistent
+	pub fn is_empty(&self) -> bool {
+		!self.has_missing_references() && !self.has_unused_entries()
+	}
+
+	/// Reports the unused triggers and networks
+	///
+	/// Missing references are reported by the validation of the monitors, so they are left out.
+	///
+	/// # Errors
+	/// Returns an error listing the unused entries in strict mode, they are logged as warnings
+	/// otherwise.
+	pub fn enforce_unused(&self, mode: ConsistencyMode) -> Result<(), RepositoryError> {
+		if !self.has_unused_entries() {
+			return Ok(());
+		}
+		let mut problems = Vec::new();
+		for trigger in &self.unused_triggers {
+			problems.push(format!("Trigger '{}' is not used by any monitor", trigger));
+		}
+		for network in &self.unused_networks {
+			problems.push(format!("Network '{}' is not used by any monitor", network));
+		}
+
+		if mode == ConsistencyMode::Lenient {
+			for problem in &problems {
+				tracing::warn!("{}", problem);
+			}
+			return Ok(());
+		}
+		Err(RepositoryError::validation_error(
+			format!(
+				"Configuration consistency check failed:\n{}",
+				problems.join("\n")
+			),
+			None,
+			Some(HashMap::from([
+				(
+					"unused_triggers".to_string(),
+					self.unused_triggers.join(", "),
+				),
+				(
+					"unused_networks".to_string(),
+					self.unused_networks.join(", "),
+				),
+			])),
+		))
+	}
+}
+
+/// Returns the triggers a monitor references, including those reporting divergences and
+/// decoding failures
+fn referenced_triggers(monitor: &Monitor) -> BTreeSet<&str> {
+	monitor
+		.triggers
+		.iter()
+		.map(|reference| reference.name())
+		.chain(
+			monitor
+				.network_views
+				.iter()
+				.flat_map(|views| &views.divergence_triggers)
+				.map(String::as_str),
+		)
+		.chain(
+			monitor
+				.alert_on_decode_failure
+				.iter()
+				.flat_map(|alert| &alert.triggers)
+				.map(String::as_str),
+		)
+		.collect()
+}
+
+/// Compares the references of monitors with the networks and triggers that exist
+///
+/// # Arguments
+/// * `monitors` - Monitors, with their trigger and network groups expanded
+/// * `triggers` - Triggers keyed by name
+/// * `networks` - Networks keyed by slug
+///
+/// # Returns
+/// * `ConfigValidationReport` - Missing references, and triggers and networks no monitor uses
+pub fn validate_configuration_consistency(
+	monitors: &HashMap<String, Monitor>,
+	triggers: &HashMap<String, Trigger>,
+	networks: &HashMap<String, Network>,
+) -> ConfigValidationReport {
+	let mut report = ConfigValidationReport::default();
+	let mut missing_networks = BTreeSet::new();
+	let mut missing_triggers = BTreeSet::new();
+	let mut used_networks = BTreeSet::new();
+	let mut used_triggers = BTreeSet::new();
+
+	let mut names: Vec<&String> = monitors.keys().collect();
+	names.sort();
+	for name in names {
+		let monitor = &monitors[name];
+		let mut details = MonitorConsistency {
+			name: name.clone(),
+			..Default::default()
+		};
+		for network in &monitor.networks {
+			used_networks.insert(network.as_str());
+			if !networks.contains_key(network) && !details.missing_networks.contains(network) {
+				details.missing_networks.push(network.clone());
+				missing_networks.insert(network.clone());
+			}
+		}
+		for trigger in referenced_triggers(monitor) {
+			used_triggers.insert(trigger);
+			if !triggers.contains_key(trigger) {
+				details.missing_triggers.push(trigger.to_string());
+				missing_triggers.insert(trigger.to_string());
+			}
+		}
+		if !details.missing_networks.is_empty() || !details.missing_triggers.is_empty() {
+			report.monitors.push(details);
+		}
+	}
+
+	report.missing_networks = missing_networks.into_iter().collect();
+	report.missing_triggers = missing_triggers.into_iter().collect();
+	report.unused_triggers = triggers
+		.keys()
+		.filter(|name| !used_triggers.contains(name.as_str()))
+		.cloned()
+		.collect::<BTreeSet<_>>()
+		.into_iter()
+		.collect();
+	report.unused_networks = networks
+		.keys()
+		.filter(|slug| !used_networks.contains(slug.as_str()))
+		.cloned()
+		.collect::<BTreeSet<_>>()
+		.into_iter()
+		.collect();
+	report
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::utils::tests::builders::{
+		evm::monitor::MonitorBuilder, network::NetworkBuilder, trigger::TriggerBuilder,
+	};
+
+	fn monitor(name: &str, networks: &[&str], triggers: &[&str]) -> (String, Monitor) {
+		(
+			name.to_string(),
+			MonitorBuilder::new()
+				.name(name)
+				.networks(networks.iter().map(|n| n.to_string()).collect())
+				.triggers(triggers.iter().map(|t| t.to_string()).collect())
+				.build(),
+		)
+	}
+
+	fn networks(slugs: &[&str]) -> HashMap<String, Network> {
+		slugs
+			.iter()
+			.map(|slug| (slug.to_string(), NetworkBuilder::new().slug(slug).build()))
+			.collect()
+	}
+
+	fn triggers(names: &[&str]) -> HashMap<String, Trigger> {
+		names
+			.iter()
+			.map(|name| (name.to_string(), TriggerBuilder::new().name(name).build()))
+			.collect()
+	}
+
+	#[test]
+	fn test_consistent_configuration() {
+		let report = validate_configuration_consistency(
+			&HashMap::from([monitor("watch", &["ethereum_mainnet"], &["notify"])]),
+			&triggers(&["notify"]),
+			&networks(&["ethereum_mainnet"]),
+		);
+		assert!(report.is_empty());
+		assert!(report.enforce_unused(ConsistencyMode::Strict).is_ok());
+	}
+
+	#[test]
+	fn test_missing_references() {
+		let report = validate_configuration_consistency(
+			&HashMap::from([
+				monitor("b", &["ethereum_mainnet", "sepolia"], &["notify"]),
+				monitor("a", &["ethereum_mainnet"], &["notify", "pager"]),
+			]),
+			&triggers(&["notify"]),
+			&networks(&["ethereum_mainnet"]),
+		);
+		assert_eq!(report.missing_networks, vec!["sepolia"]);
+		assert_eq!(report.missing_triggers, vec!["pager"]);
+		assert!(report.has_missing_references());
+		assert!(!report.has_unused_entries());
+		assert_eq!(
+			report.monitors,
+			vec![
+				MonitorConsistency {
+					name: "a".to_string(),
+					missing_networks: vec![],
+					missing_triggers: vec!["pager".to_string()],
+				},
+				MonitorConsistency {
+					name: "b".to_string(),
+					missing_networks: vec!["sepolia".to_string()],
+					missing_triggers: vec![],
+				},
+			]
+		);
+	}
+
+	#[test]
+	fn test_unused_triggers_and_networks() {
+		let report = validate_configuration_consistency(
+			&HashMap::from([monitor("watch", &["ethereum_mainnet"], &["notify"])]),
+			&triggers(&["notify", "old_slack", "email"]),
+			&networks(&["ethereum_mainnet", "sepolia"]),
+		);
+		assert_eq!(report.unused_triggers, vec!["email", "old_slack"]);
+		assert_eq!(report.unused_networks, vec!["sepolia"]);
+		assert!(!report.has_missing_references());
+
+		let json = serde_json::to_value(&report).unwrap();
+		assert_eq!(json["unused_networks"], serde_json::json!(["sepolia"]));
+	}
+
+	#[test]
+	fn test_enforce_unused_strict_and_lenient() {
+		let report = validate_configuration_consistency(
+			&HashMap::from([monitor("watch", &["ethereum_mainnet"], &["notify"])]),
+			&triggers(&["notify", "old_slack"]),
+			&networks(&["ethereum_mainnet", "sepolia"]),
+		);
+		assert!(report.enforce_unused(ConsistencyMode::Lenient).is_ok());
+
+		let err = report
+			.enforce_unused(ConsistencyMode::Strict)
+			.unwrap_err()
+			.to_string();
+		assert!(err.contains("Trigger 'old_slack' is not used by any monitor"));
+		assert!(err.contains("Network 'sepolia' is not used by any monitor"));
+	}
+}
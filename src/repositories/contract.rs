@@ -0,0 +1,90 @@
+//! Contract registry repository implementation.
+//!
+//! This module provides storage and retrieval of contract specs shared between monitors.
+//! The repository loads contract configurations from JSON files, and monitors reference
+//! them by name from their addresses.
+
+#![allow(clippy::result_large_err)]
+
+use std::{collections::HashMap, path::Path};
+
+use async_trait::async_trait;
+
+use crate::{
+	models::{ConfigLoader, Contract},
+	repositories::error::RepositoryError,
+};
+
+/// Repository for storing and retrieving contract configurations
+#[derive(Clone, Default)]
+pub struct ContractRepository {
+	/// Map of contract names to their configurations
+	pub contracts: HashMap<String, Contract>,
+}
+
+impl ContractRepository {
+	/// Create a new contract repository from the given path
+	///
+	/// Loads all contract configurations from JSON files in the specified directory
+	/// (or default config directory if None is provided).
+	pub async fn new(path: Option<&Path>) -> Result<Self, RepositoryError> {
+		let contracts = Self::load_all(path).await?;
+		Ok(ContractRepository { contracts })
+	}
+}
+
+/// Interface for contract repository implementations
+///
+/// This trait defines the standard operations that any contract repository must support,
+/// allowing for different storage backends while maintaining a consistent interface.
+#[async_trait]
+pub trait ContractRepositoryTrait: Clone {
+	/// Create a new repository instance
+	async fn new(path: Option<&Path>) -> Result<Self, RepositoryError>
+	where
+		Self: Sized;
+
+	/// Load all contract configurations from the given path
+	///
+	/// If no path is provided, uses the default config directory.
+	/// This is a static method that doesn't require an instance.
+	async fn load_all(path: Option<&Path>) -> Result<HashMap<String, Contract>, RepositoryError>;
+
+	/// Get a specific contract by name
+	///
+	/// Returns None if the contract doesn't exist.
+	fn get(&self, contract_name: &str) -> Option<Contract>;
+
+	/// Get all contracts
+	///
+	/// Returns a copy of the contract map to prevent external mutation.
+	fn get_all(&self) -> HashMap<String, Contract>;
+}
+
+#[async_trait]
+impl ContractRepositoryTrait for ContractRepository {
+	async fn new(path: Option<&Path>) -> Result<Self, RepositoryError> {
+		ContractRepository::new(path).await
+	}
+
+	async fn load_all(path: Option<&Path>) -> Result<HashMap<String, Contract>, RepositoryError> {
+		Contract::load_all(path).await.map_err(|e| {
+			RepositoryError::load_error(
+				"Failed to load contracts",
+				Some(Box::new(e)),
+				Some(HashMap::from([(
+					"path".to_string(),
+					path.map_or_else(|| "default".to_string(), |p| p.display().to_string()),
+				)])),
+			)
+		})
+	}
+
+	fn get(&self, contract_name: &str) -> Option<Contract> {
+		self.contracts.get(contract_name).cloned()
+	}
+
+	fn get_all(&self) -> HashMap<String, Contract> {
+		self.contracts.clone()
+	}
+}
@@ -24,6 +24,10 @@ pub enum RepositoryError {
 	#[error("Internal error: {0}")]
 	InternalError(ErrorContext),
 
+	/// Errors related to writes based on a stale configuration
+	#[error("Conflict error: {0}")]
+	ConflictError(ErrorContext),
+
 	/// Other errors that don't fit into the categories above
 	#[error(transparent)]
 	Other(#[from] anyhow::Error),
@@ -56,6 +60,15 @@ impl RepositoryError {
 	) -> Self {
 		Self::InternalError(ErrorContext::new_with_log(msg, source, metadata))
 	}
+
+	// Conflict error
+	pub fn conflict_error(
+		msg: impl Into<String>,
+		source: Option<Box<dyn std::error::Error + Send + Sync + 'static>>,
+		metadata: Option<HashMap<String, String>>,
+	) -> Self {
+		Self::ConflictError(ErrorContext::new_with_log(msg, source, metadata))
+	}
 }
 
 impl TraceableError for RepositoryError {
@@ -64,6 +77,7 @@ impl TraceableError for RepositoryError {
 			Self::ValidationError(ctx) => ctx.trace_id.clone(),
 			Self::LoadError(ctx) => ctx.trace_id.clone(),
 			Self::InternalError(ctx) => ctx.trace_id.clone(),
+			Self::ConflictError(ctx) => ctx.trace_id.clone(),
 			Self::Other(_) => Uuid::new_v4().to_string(),
 		}
 	}
@@ -122,6 +136,22 @@ mod tests {
 		);
 	}
 
+	#[test]
+	fn test_conflict_error_formatting() {
+		let error = RepositoryError::conflict_error("test error", None, None);
+		assert_eq!(error.to_string(), "Conflict error: test error");
+
+		let error = RepositoryError::conflict_error(
+			"test error",
+			None,
+			Some(HashMap::from([("key1".to_string(), "value1".to_string())])),
+		);
+		assert_eq!(
+			error.to_string(),
+			"Conflict error: test error [key1=value1]"
+		);
+	}
+
 	#[test]
 	fn test_from_anyhow_error() {
 		let anyhow_error = anyhow::anyhow!("test anyhow error");
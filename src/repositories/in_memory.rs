@@ -0,0 +1,1614 @@
+//! In-memory repository implementations.
+//!
+//! The file repositories load their configurations once, and the services built on them work
+//! on snapshots. The in-memory repositories keep their configurations behind a lock instead, so
+//! that a process embedding the monitor can add, update and remove networks, triggers and
+//! monitors at runtime from any thread. Clones of a repository share its configurations.
+//!
+//! Writes go through the same validation as loaded configurations, monitors being checked
+//! against the networks and triggers they reference. Each write bumps the version published by
+//! `subscribe`, which the network and trigger services watch to read the repository again after
+//! a write, and which other consumers can use to react to changes. Monitors can
+//! also be updated only if their content hash is still the one the writer read, so that
+//! concurrent writers do not silently overwrite each other.
+//!
+//! Configuration directories can be imported with the loaders of the file repositories, and the
+//! configurations exported back to one JSON file each, so that both representations can be kept
+//! in sync. Importing a configuration that differs from the one stored under its name fails,
+//! listing the conflicting names, unless `force` is set. Secrets loaded from environment
+//! variables or a vault are exported as the references they were resolved from, so that an
+//! export never writes them in plain text.
+
+#![allow(clippy::result_large_err)]
+
+use std::{
+	collections::HashMap,
+	marker::PhantomData,
+	path::{Path, PathBuf},
+	sync::{Arc, RwLock, RwLockReadGuard, RwLockWriteGuard},
+};
+
+use async_trait::async_trait;
+use serde::Serialize;
+use serde_json::Value;
+use tokio::sync::watch;
+
+use crate::{
+	models::{ConfigLoader, ContentHash, Monitor, Network, Trigger},
+	repositories::{
+		error::RepositoryError,
+		monitor::{MonitorRepository, MonitorRepositoryTrait},
+		network::{NetworkRepository, NetworkRepositoryTrait, NetworkService},
+		trigger::{TriggerRepository, TriggerRepositoryTrait, TriggerService},
+	},
+};
+
+/// Secret of a loaded configuration that was resolved from a reference
+#[derive(Clone, Debug, PartialEq)]
+struct SecretReference {
+	/// JSON pointer to the secret in the serialized configuration
+	pointer: String,
+	/// Secret as written in the configuration file
+	reference: Value,
+	/// Secret as resolved when the configuration was loaded
+	resolved: Value,
+}
+
+/// Configurations of one type kept in memory, with the version of their last write
+///
+/// Each configuration is stored in an `Arc`, so that lookups can share it instead of copying.
+#[derive(Clone)]
+struct SharedConfigs<C> {
+	configs: Arc<RwLock<HashMap<String, Arc<C>>>>,
+	/// Secrets of the loaded configurations resolved from references, by name
+	references: Arc<RwLock<HashMap<String, Vec<SecretReference>>>>,
+	version: Arc<watch::Sender<u64>>,
+}
+
+impl<C: Clone> SharedConfigs<C> {
+	fn new(configs: HashMap<String, C>) -> Self {
+		Self {
+			configs: Arc::new(RwLock::new(
+				configs
+					.into_iter()
+					.map(|(name, config)| (name, Arc::new(config)))
+					.collect(),
+			)),
+			references: Arc::new(RwLock::new(HashMap::new())),
+			version: Arc::new(watch::channel(0).0),
+		}
+	}
+
+	/// Remembers the references the secrets of loaded configurations were resolved from
+	fn record_references(&self, references: HashMap<String, Vec<SecretReference>>) {
+		self.references
+			.write()
+			.unwrap_or_else(|e| e.into_inner())
+			.extend(references);
+	}
+
+	fn get(&self, name: &str) -> Option<C> {
+		self.read().get(name).map(|config| C::clone(config))
+	}
+
+	fn get_all(&self) -> HashMap<String, C> {
+		self.read()
+			.iter()
+			.map(|(name, config)| (name.clone(), C::clone(config)))
+			.collect()
+	}
+
+	fn get_shared(&self, name: &str) -> Option<Arc<C>> {
+		self.read().get(name).cloned()
+	}
+
+	fn get_all_shared(&self) -> HashMap<String, Arc<C>> {
+		self.read().clone()
+	}
+
+	/// Inserts a configuration under a name that must not be taken
+	fn insert(&self, kind: &str, name: String, config: C) -> Result<(), RepositoryError> {
+		let mut configs = self.write();
+		if configs.contains_key(&name) {
+			return Err(RepositoryError::validation_error(
+				format!("{} '{}' already exists", kind, name),
+				None,
+				Some(HashMap::from([("name".to_string(), name)])),
+			));
+		}
+		configs.insert(name, Arc::new(config));
+		drop(configs);
+		self.bump();
+		Ok(())
+	}
+
+	/// Replaces the configuration stored under a name
+	fn replace(&self, kind: &str, name: &str, config: C) -> Result<(), RepositoryError> {
+		let mut configs = self.write();
+		let Some(existing) = configs.get_mut(name) else {
+			return Err(not_found(kind, name));
+		};
+		*existing = Arc::new(config);
+		drop(configs);
+		self.bump();
+		Ok(())
+	}
+
+	/// Replaces the configuration stored under a name if its content hash is the expected one
+	fn replace_if_unchanged(
+		&self,
+		kind: &str,
+		name: &str,
+		expected_hash: &str,
+		config: C,
+	) -> Result<(), RepositoryError>
+	where
+		C: ContentHash,
+	{
+		let mut configs = self.write();
+		let Some(existing) = configs.get_mut(name) else {
+			return Err(not_found(kind, name));
+		};
+		let current_hash = existing.content_hash();
+		if current_hash != expected_hash {
+			return Err(RepositoryError::conflict_error(
+				format!("{} '{}' was changed since it was read", kind, name),
+				None,
+				Some(HashMap::from([
+					("name".to_string(), name.to_string()),
+					("expected_hash".to_string(), expected_hash.to_string()),
+					("current_hash".to_string(), current_hash),
+				])),
+			));
+		}
+		*existing = Arc::new(config);
+		drop(configs);
+		self.bump();
+		Ok(())
+	}
+
+	/// Removes and returns the configuration stored under a name
+	fn remove(&self, kind: &str, name: &str) -> Result<C, RepositoryError> {
+		let removed = self
+			.write()
+			.remove(name)
+			.ok_or_else(|| not_found(kind, name))?;
+		self.bump();
+		Ok(Arc::unwrap_or_clone(removed))
+	}
+
+	/// Stores configurations, replacing those stored under the same names
+	///
+	/// Returns the number of configurations added or replaced.
+	fn upsert(&self, configs: Vec<(String, C)>) -> usize
+	where
+		C: PartialEq,
+	{
+		let mut stored = self.write();
+		let mut count = 0;
+		for (name, config) in configs {
+			if stored.get(&name).map(Arc::as_ref) != Some(&config) {
+				stored.insert(name, Arc::new(config));
+				count += 1;
+			}
+		}
+		drop(stored);
+		if count > 0 {
+			self.bump();
+		}
+		count
+	}
+
+	/// Stores loaded configurations, keeping the repository unchanged if one of them conflicts
+	///
+	/// Returns the number of configurations added or replaced.
+	fn import(
+		&self,
+		kind: &str,
+		path: &Path,
+		configs: HashMap<String, C>,
+		force: bool,
+	) -> Result<usize, RepositoryError>
+	where
+		C: PartialEq,
+	{
+		let mut stored = self.write();
+		let changed = configs
+			.into_iter()
+			.filter(|(name, config)| stored.get(name).map(Arc::as_ref) != Some(config))
+			.collect::<Vec<_>>();
+		let mut conflicts = changed
+			.iter()
+			.filter(|(name, _)| stored.contains_key(name))
+			.map(|(name, _)| name.clone())
+			.collect::<Vec<_>>();
+		if !conflicts.is_empty() && !force {
+			conflicts.sort();
+			return Err(RepositoryError::validation_error(
+				format!(
+					"{} {}(s) differ from the stored configurations",
+					conflicts.len(),
+					kind
+				),
+				None,
+				Some(HashMap::from([
+					("path".to_string(), path.display().to_string()),
+					("conflicts".to_string(), conflicts.join(", ")),
+				])),
+			));
+		}
+		let count = changed.len();
+		stored.extend(
+			changed
+				.into_iter()
+				.map(|(name, config)| (name, Arc::new(config))),
+		);
+		drop(stored);
+		if count > 0 {
+			self.bump();
+		}
+		Ok(count)
+	}
+
+	/// Writes each configuration to `<name>.json` in a directory, as rendered by `document`
+	///
+	/// A secret still holding the value it was resolved to when loaded is written as the
+	/// reference it was resolved from. Secrets changed since, or written as plain values, are
+	/// written as stored.
+	fn export(
+		&self,
+		kind: &str,
+		dir: &Path,
+		document: impl Fn(&str, Value) -> Value,
+	) -> Result<Vec<PathBuf>, RepositoryError>
+	where
+		C: Serialize,
+	{
+		let configs = self.get_all_shared();
+		let references = self
+			.references
+			.read()
+			.unwrap_or_else(|e| e.into_inner())
+			.clone();
+		let mut names = configs.keys().collect::<Vec<_>>();
+		names.sort();
+		// Names are checked first, so that no file is written for an invalid export
+		if let Some(name) = names.iter().find(|name| !is_file_stem(name)) {
+			return Err(RepositoryError::validation_error(
+				format!("{} '{}' cannot be exported to a file", kind, name),
+				None,
+				Some(HashMap::from([("name".to_string(), name.to_string())])),
+			));
+		}
+		std::fs::create_dir_all(dir).map_err(|e| write_error(dir, e))?;
+
+		let mut paths = Vec::with_capacity(names.len());
+		for name in names {
+			let path = dir.join(format!("{}.json", name));
+			let content = serde_json::to_value(configs[name].as_ref())
+				.map(|mut value| {
+					for secret in references.get(name.as_str()).into_iter().flatten() {
+						if let Some(target) = value.pointer_mut(&secret.pointer) {
+							if *target == secret.resolved {
+								*target = secret.reference.clone();
+							}
+						}
+					}
+					document(name, value)
+				})
+				.and_then(|document| serde_json::to_string_pretty(&document))
+				.map_err(|e| write_error(&path, e))?;
+			std::fs::write(&path, content + "\n").map_err(|e| write_error(&path, e))?;
+			paths.push(path);
+		}
+		Ok(paths)
+	}
+
+	fn subscribe(&self) -> watch::Receiver<u64> {
+		self.version.subscribe()
+	}
+
+	fn bump(&self) {
+		self.version.send_modify(|version| *version += 1);
+	}
+
+	fn read(&self) -> RwLockReadGuard<'_, HashMap<String, Arc<C>>> {
+		self.configs.read().unwrap_or_else(|e| e.into_inner())
+	}
+
+	fn write(&self) -> RwLockWriteGuard<'_, HashMap<String, Arc<C>>> {
+		self.configs.write().unwrap_or_else(|e| e.into_inner())
+	}
+}
+
+/// Returns the error for a configuration missing from a repository
+fn not_found(kind: &str, name: &str) -> RepositoryError {
+	RepositoryError::validation_error(
+		format!("{} '{}' does not exist", kind, name),
+		None,
+		Some(HashMap::from([("name".to_string(), name.to_string())])),
+	)
+}
+
+/// Returns whether a name can be used as the stem of a file name
+pub(super) fn is_file_stem(name: &str) -> bool {
+	!name.is_empty() && name != "." && name != ".." && !name.contains(['/', '\\', '\0'])
+}
+
+/// Returns the secrets of loaded configurations that were resolved from references
+///
+/// The JSON files of `dir` are read again without resolving their secrets. Trigger files hold
+/// triggers keyed by name (`nested`), the other files a single configuration keyed by file
+/// stem, as the loaders key them. Files that cannot be read were already reported by the
+/// loader and are skipped.
+fn secret_references<C: Serialize>(
+	dir: &Path,
+	nested: bool,
+	configs: &HashMap<String, C>,
+) -> HashMap<String, Vec<SecretReference>> {
+	let documents = std::fs::read_dir(dir)
+		.into_iter()
+		.flatten()
+		.flatten()
+		.map(|entry| entry.path())
+		.filter(|path| <Network as ConfigLoader>::is_json_file(path))
+		.filter_map(|path| {
+			let content = std::fs::read_to_string(&path).ok()?;
+			let document = serde_json::from_str::<Value>(&content).ok()?;
+			let stem = path.file_stem()?.to_str()?.to_string();
+			Some((stem, document))
+		})
+		.flat_map(|(stem, document)| match (nested, document) {
+			(true, Value::Object(triggers)) => triggers.into_iter().collect::<Vec<_>>(),
+			(true, _) => Vec::new(),
+			(false, document) => vec![(stem, document)],
+		});
+
+	let mut references = HashMap::new();
+	for (name, document) in documents {
+		let Some(resolved) = configs
+			.get(&name)
+			.and_then(|config| serde_json::to_value(config).ok())
+		else {
+			continue;
+		};
+		let mut found = Vec::new();
+		collect_secret_references(&document, String::new(), &mut found);
+		let secrets = found
+			.into_iter()
+			.filter_map(|(pointer, reference)| {
+				let resolved = resolved.pointer(&pointer)?;
+				let is_plain = resolved.get("type").and_then(Value::as_str) == Some("Plain");
+				is_plain.then(|| SecretReference {
+					resolved: resolved.clone(),
+					pointer,
+					reference,
+				})
+			})
+			.collect::<Vec<_>>();
+		if !secrets.is_empty() {
+			references.insert(name, secrets);
+		}
+	}
+	references
+}
+
+/// Collects the JSON pointers of the secret references in a configuration file
+fn collect_secret_references(value: &Value, pointer: String, found: &mut Vec<(String, Value)>) {
+	match value {
+		Value::Object(fields) => {
+			let is_reference = fields.len() == 2
+				&& fields.contains_key("value")
+				&& fields
+					.get("type")
+					.and_then(Value::as_str)
+					.is_some_and(|kind| !kind.eq_ignore_ascii_case("plain"));
+			if is_reference {
+				found.push((pointer, value.clone()));
+				return;
+			}
+			for (key, field) in fields {
+				let key = key.replace('~', "~0").replace('/', "~1");
+				collect_secret_references(field, format!("{}/{}", pointer, key), found);
+			}
+		}
+		Value::Array(items) => {
+			for (index, item) in items.iter().enumerate() {
+				collect_secret_references(item, format!("{}/{}", pointer, index), found);
+			}
+		}
+		_ => {}
+	}
+}
+
+/// Returns the error for a file that could not be written
+fn write_error(
+	path: &Path,
+	error: impl std::error::Error + Send + Sync + 'static,
+) -> RepositoryError {
+	RepositoryError::internal_error(
+		format!("Failed to write {}", path.display()),
+		Some(Box::new(error)),
+		Some(HashMap::from([(
+			"path".to_string(),
+			path.display().to_string(),
+		)])),
+	)
+}
+
+/// Validates a configuration as if it was loaded
+fn validate<C: ConfigLoader>(kind: &str, name: &str, config: &C) -> Result<(), RepositoryError> {
+	config.validate().map_err(|e| {
+		RepositoryError::validation_error(
+			format!("{} validation failed: {}", kind, e),
+			Some(Box::new(e)),
+			Some(HashMap::from([("name".to_string(), name.to_string())])),
+		)
+	})
+}
+
+/// Validates a batch of configurations, rejecting the whole batch if one of them is invalid
+///
+/// # Errors
+/// Returns an error describing each invalid configuration, with their names, or positions in
+/// the batch for unnamed ones, listed in the `rejected` metadata.
+fn validate_batch<C: ConfigLoader>(
+	kind: &str,
+	configs: Vec<(String, C)>,
+) -> Result<Vec<(String, C)>, RepositoryError> {
+	let mut rejected = Vec::new();
+	let mut problems = Vec::new();
+	for (index, (name, config)) in configs.iter().enumerate() {
+		let problem = if name.is_empty() {
+			"name must not be empty".to_string()
+		} else if let Err(e) = config.validate() {
+			e.to_string()
+		} else {
+			continue;
+		};
+		let label = if name.is_empty() {
+			format!("#{}", index)
+		} else {
+			name.clone()
+		};
+		problems.push(format!("{} '{}': {}", kind, label, problem));
+		rejected.push(label);
+	}
+	if rejected.is_empty() {
+		return Ok(configs);
+	}
+	Err(RepositoryError::validation_error(
+		format!(
+			"{} of {} {}(s) rejected, none were stored:\n{}",
+			rejected.len(),
+			configs.len(),
+			kind,
+			problems.join("\n")
+		),
+		None,
+		Some(HashMap::from([(
+			"rejected".to_string(),
+			rejected.join(", "),
+		)])),
+	))
+}
+
+/// Repository keeping network configurations in memory
+#[derive(Clone)]
+pub struct InMemoryNetworkRepository {
+	networks: SharedConfigs<Network>,
+}
+
+impl InMemoryNetworkRepository {
+	/// Create a new repository holding the given networks, keyed by slug
+	pub fn new_with_networks(networks: HashMap<String, Network>) -> Self {
+		Self {
+			networks: SharedConfigs::new(networks),
+		}
+	}
+
+	/// Adds a network under its slug, with its cron schedules normalized
+	///
+	/// # Errors
+	/// Returns an error if a network with the same slug exists or the network is invalid.
+	pub fn add(&self, mut network: Network) -> Result<(), RepositoryError> {
+		network.normalize_schedules();
+		validate("Network", &network.slug, &network)?;
+		self.networks
+			.insert("Network", network.slug.clone(), network)
+	}
+
+	/// Replaces the network stored under a slug
+	///
+	/// # Errors
+	/// Returns an error if the network does not exist or the new configuration is invalid.
+	pub fn update(&self, slug: &str, mut network: Network) -> Result<(), RepositoryError> {
+		network.normalize_schedules();
+		validate("Network", slug, &network)?;
+		self.networks.replace("Network", slug, network)
+	}
+
+	/// Adds or replaces networks under their slugs, with their cron schedules normalized
+	///
+	/// # Returns
+	/// * `Result<usize, RepositoryError>` - Number of networks added or replaced
+	///
+	/// # Errors
+	/// Returns an error if a network is invalid, listing the rejected slugs in the `rejected`
+	/// metadata. The repository is left unchanged on error.
+	pub fn upsert_many(&self, networks: Vec<Network>) -> Result<usize, RepositoryError> {
+		let networks = networks
+			.into_iter()
+			.map(|mut network| {
+				network.normalize_schedules();
+				(network.slug.clone(), network)
+			})
+			.collect();
+		Ok(self.networks.upsert(validate_batch("Network", networks)?))
+	}
+
+	/// Removes and returns the network stored under a slug
+	pub fn remove(&self, slug: &str) -> Result<Network, RepositoryError> {
+		self.networks.remove("Network", slug)
+	}
+
+	/// Returns a receiver of the version of the networks, bumped by each write
+	pub fn subscribe(&self) -> watch::Receiver<u64> {
+		self.networks.subscribe()
+	}
+
+	/// Imports the networks of a configuration directory
+	///
+	/// # Arguments
+	/// * `path` - Directory of network files, loaded as by [`NetworkRepository`]
+	/// * `force` - Whether to replace the stored networks differing from the loaded ones
+	///
+	/// # Returns
+	/// * `Result<usize, RepositoryError>` - Number of networks added or replaced
+	///
+	/// # Errors
+	/// Returns an error if the directory fails to load, or if a loaded network differs from
+	/// the stored one and `force` is not set. The conflicting slugs are listed in the
+	/// `conflicts` metadata of the error, and the repository is left unchanged.
+	pub async fn import_from_filesystem(
+		&self,
+		path: &Path,
+		force: bool,
+	) -> Result<usize, RepositoryError> {
+		let networks = <NetworkRepository as NetworkRepositoryTrait>::load_all(Some(path)).await?;
+		let references = secret_references(path, false, &networks);
+		let count = self.networks.import("Network", path, networks, force)?;
+		self.networks.record_references(references);
+		Ok(count)
+	}
+
+	/// Writes each network to `<slug>.json` in a directory
+	///
+	/// Secrets loaded from references are written as those references.
+	///
+	/// # Returns
+	/// * `Result<Vec<PathBuf>, RepositoryError>` - Paths of the written files, sorted by slug
+	pub fn export_to_filesystem(&self, dir: &Path) -> Result<Vec<PathBuf>, RepositoryError> {
+		self.networks.export("Network", dir, |_, network| network)
+	}
+}
+
+#[async_trait]
+impl NetworkRepositoryTrait for InMemoryNetworkRepository {
+	async fn new(path: Option<&Path>) -> Result<Self, RepositoryError> {
+		let networks = <Self as NetworkRepositoryTrait>::load_all(path).await?;
+		let references = secret_references(
+			path.unwrap_or(Path::new("config/networks")),
+			false,
+			&networks,
+		);
+		let repository = Self::new_with_networks(networks);
+		repository.networks.record_references(references);
+		Ok(repository)
+	}
+
+	async fn load_all(path: Option<&Path>) -> Result<HashMap<String, Network>, RepositoryError> {
+		<NetworkRepository as NetworkRepositoryTrait>::load_all(path).await
+	}
+
+	fn get(&self, network_id: &str) -> Option<Network> {
+		self.networks.get(network_id)
+	}
+
+	fn get_all(&self) -> HashMap<String, Network> {
+		self.networks.get_all()
+	}
+
+	fn watch_versions(&self) -> Option<watch::Receiver<u64>> {
+		Some(self.subscribe())
+	}
+}
+
+/// Repository keeping trigger configurations in memory
+#[derive(Clone)]
+pub struct InMemoryTriggerRepository {
+	triggers: SharedConfigs<Trigger>,
+}
+
+impl InMemoryTriggerRepository {
+	/// Create a new repository holding the given triggers, keyed by name
+	pub fn new_with_triggers(triggers: HashMap<String, Trigger>) -> Self {
+		Self {
+			triggers: SharedConfigs::new(triggers),
+		}
+	}
+
+	/// Adds a trigger under its name
+	///
+	/// # Errors
+	/// Returns an error if a trigger with the same name exists or the trigger is invalid.
+	pub fn add(&self, trigger: Trigger) -> Result<(), RepositoryError> {
+		validate("Trigger", &trigger.name, &trigger)?;
+		self.triggers
+			.insert("Trigger", trigger.name.clone(), trigger)
+	}
+
+	/// Replaces the trigger stored under a name
+	///
+	/// # Errors
+	/// Returns an error if the trigger does not exist or the new configuration is invalid.
+	pub fn update(&self, name: &str, trigger: Trigger) -> Result<(), RepositoryError> {
+		validate("Trigger", name, &trigger)?;
+		self.triggers.replace("Trigger", name, trigger)
+	}
+
+	/// Adds or replaces triggers under their names
+	///
+	/// # Returns
+	/// * `Result<usize, RepositoryError>` - Number of triggers added or replaced
+	///
+	/// # Errors
+	/// Returns an error if a trigger is invalid, listing the rejected names in the `rejected`
+	/// metadata. The repository is left unchanged on error.
+	pub fn upsert_many(&self, triggers: Vec<Trigger>) -> Result<usize, RepositoryError> {
+		let triggers = triggers
+			.into_iter()
+			.map(|trigger| (trigger.name.clone(), trigger))
+			.collect();
+		Ok(self.triggers.upsert(validate_batch("Trigger", triggers)?))
+	}
+
+	/// Removes and returns the trigger stored under a name
+	pub fn remove(&self, name: &str) -> Result<Trigger, RepositoryError> {
+		self.triggers.remove("Trigger", name)
+	}
+
+	/// Returns a receiver of the version of the triggers, bumped by each write
+	pub fn subscribe(&self) -> watch::Receiver<u64> {
+		self.triggers.subscribe()
+	}
+
+	/// Imports the triggers of a configuration directory
+	///
+	/// # Arguments
+	/// * `path` - Directory of trigger files, loaded as by [`TriggerRepository`]
+	/// * `force` - Whether to replace the stored triggers differing from the loaded ones
+	///
+	/// # Returns
+	/// * `Result<usize, RepositoryError>` - Number of triggers added or replaced
+	///
+	/// # Errors
+	/// Returns an error if the directory fails to load, or if a loaded trigger differs from
+	/// the stored one and `force` is not set. The conflicting names are listed in the
+	/// `conflicts` metadata of the error, and the repository is left unchanged.
+	pub async fn import_from_filesystem(
+		&self,
+		path: &Path,
+		force: bool,
+	) -> Result<usize, RepositoryError> {
+		let triggers = <TriggerRepository as TriggerRepositoryTrait>::load_all(Some(path)).await?;
+		let references = secret_references(path, true, &triggers);
+		let count = self.triggers.import("Trigger", path, triggers, force)?;
+		self.triggers.record_references(references);
+		Ok(count)
+	}
+
+	/// Writes each trigger to `<name>.json` in a directory, keyed by its name as trigger files
+	/// expect
+	///
+	/// Secrets loaded from references are written as those references.
+	///
+	/// # Returns
+	/// * `Result<Vec<PathBuf>, RepositoryError>` - Paths of the written files, sorted by name
+	pub fn export_to_filesystem(&self, dir: &Path) -> Result<Vec<PathBuf>, RepositoryError> {
+		self.triggers.export("Trigger", dir, |name, trigger| {
+			Value::Object(serde_json::Map::from_iter([(name.to_string(), trigger)]))
+		})
+	}
+}
+
+#[async_trait]
+impl TriggerRepositoryTrait for InMemoryTriggerRepository {
+	async fn new(path: Option<&Path>) -> Result<Self, RepositoryError> {
+		let triggers = <Self as TriggerRepositoryTrait>::load_all(path).await?;
+		let references = secret_references(
+			path.unwrap_or(Path::new("config/triggers")),
+			true,
+			&triggers,
+		);
+		let repository = Self::new_with_triggers(triggers);
+		repository.triggers.record_references(references);
+		Ok(repository)
+	}
+
+	async fn load_all(path: Option<&Path>) -> Result<HashMap<String, Trigger>, RepositoryError> {
+		<TriggerRepository as TriggerRepositoryTrait>::load_all(path).await
+	}
+
+	fn get(&self, trigger_id: &str) -> Option<Trigger> {
+		self.triggers.get(trigger_id)
+	}
+
+	fn get_all(&self) -> HashMap<String, Trigger> {
+		self.triggers.get_all()
+	}
+
+	fn watch_versions(&self) -> Option<watch::Receiver<u64>> {
+		Some(self.subscribe())
+	}
+}
+
+/// Repository keeping monitor configurations in memory
+///
+/// Monitors are stored as given: contract references, presets and trigger groups of monitors
+/// written at runtime are not resolved, and their secrets are resolved when used.
+#[derive(Clone)]
+pub struct InMemoryMonitorRepository<
+	N: NetworkRepositoryTrait + Send + 'static,
+	T: TriggerRepositoryTrait + Send + 'static,
+> {
+	monitors: SharedConfigs<Monitor>,
+	_network_repository: PhantomData<N>,
+	_trigger_repository: PhantomData<T>,
+}
+
+impl<
+		N: NetworkRepositoryTrait + Send + Sync + 'static,
+		T: TriggerRepositoryTrait + Send + Sync + 'static,
+	> InMemoryMonitorRepository<N, T>
+{
+	/// Create a new repository holding the given monitors, keyed by name
+	pub fn new_with_monitors(monitors: HashMap<String, Monitor>) -> Self {
+		Self {
+			monitors: SharedConfigs::new(monitors),
+			_network_repository: PhantomData,
+			_trigger_repository: PhantomData,
+		}
+	}
+
+	/// Adds a monitor under its name
+	///
+	/// # Arguments
+	/// * `monitor` - Monitor to add
+	/// * `network_service` - Networks the monitor may reference
+	/// * `trigger_service` - Triggers the monitor may reference
+	///
+	/// # Errors
+	/// Returns an error if a monitor with the same name exists, the monitor is invalid or it
+	/// references a missing network or trigger.
+	pub fn add(
+		&self,
+		monitor: Monitor,
+		network_service: &NetworkService<N>,
+		trigger_service: &TriggerService<T>,
+	) -> Result<(), RepositoryError> {
+		let name = monitor.name.clone();
+		Self::validate_monitor(&name, &monitor, network_service, trigger_service)?;
+		self.monitors.insert("Monitor", name, monitor)
+	}
+
+	/// Replaces the monitor stored under a name
+	///
+	/// # Arguments
+	/// * `name` - Name of the monitor to replace
+	/// * `monitor` - New configuration of the monitor
+	/// * `network_service` - Networks the monitor may reference
+	/// * `trigger_service` - Triggers the monitor may reference
+	///
+	/// # Errors
+	/// Returns an error if the monitor does not exist, or the new configuration is invalid or
+	/// references a missing network or trigger. The repository is left unchanged on error.
+	pub fn update(
+		&self,
+		name: &str,
+		monitor: Monitor,
+		network_service: &NetworkService<N>,
+		trigger_service: &TriggerService<T>,
+	) -> Result<(), RepositoryError> {
+		Self::validate_monitor(name, &monitor, network_service, trigger_service)?;
+		self.monitors.replace("Monitor", name, monitor)
+	}
+
+	/// Replaces the monitor stored under a name, unless it was changed since it was read
+	///
+	/// Concurrent writers read the monitor with its content hash, and pass that hash back so
+	/// that a writer working on a stale monitor does not overwrite the changes of another.
+	///
+	/// # Arguments
+	/// * `name` - Name of the monitor to replace
+	/// * `expected_hash` - Content hash of the monitor the update is based on
+	/// * `monitor` - New configuration of the monitor
+	/// * `network_service` - Networks the monitor may reference
+	/// * `trigger_service` - Triggers the monitor may reference
+	///
+	/// # Errors
+	/// Returns a conflict error carrying the `current_hash` of the stored monitor if it differs
+	/// from `expected_hash`, so that the caller can read it again and retry. Returns the errors
+	/// of `update` otherwise. The repository is left unchanged on error.
+	pub fn update_if_unchanged(
+		&self,
+		name: &str,
+		expected_hash: &str,
+		monitor: Monitor,
+		network_service: &NetworkService<N>,
+		trigger_service: &TriggerService<T>,
+	) -> Result<(), RepositoryError> {
+		Self::validate_monitor(name, &monitor, network_service, trigger_service)?;
+		self.monitors
+			.replace_if_unchanged("Monitor", name, expected_hash, monitor)
+	}
+
+	/// Returns the content hash of the monitor stored under a name, to pass to
+	/// `update_if_unchanged`
+	pub fn get_content_hash(&self, name: &str) -> Option<String> {
+		self.monitors
+			.get_shared(name)
+			.map(|monitor| monitor.content_hash())
+	}
+
+	/// Removes and returns the monitor stored under a name
+	pub fn remove(&self, name: &str) -> Result<Monitor, RepositoryError> {
+		self.monitors.remove("Monitor", name)
+	}
+
+	/// Pauses or resumes the monitor stored under a name
+	///
+	/// The references of the monitor are left as they are, so they are not validated again.
+	pub fn set_paused(&self, name: &str, paused: bool) -> Result<(), RepositoryError> {
+		let mut monitor = self
+			.monitors
+			.get(name)
+			.ok_or_else(|| not_found("Monitor", name))?;
+		monitor.paused = paused;
+		self.monitors.replace("Monitor", name, monitor)
+	}
+
+	/// Returns the monitors watching a network, keyed by name
+	pub fn get_by_network(&self, network_slug: &str) -> HashMap<String, Monitor> {
+		self.monitors
+			.read()
+			.iter()
+			.filter(|(_, monitor)| monitor.networks.iter().any(|slug| slug == network_slug))
+			.map(|(name, monitor)| (name.clone(), Monitor::clone(monitor)))
+			.collect()
+	}
+
+	/// Returns a receiver of the version of the monitors, bumped by each write
+	pub fn subscribe(&self) -> watch::Receiver<u64> {
+		self.monitors.subscribe()
+	}
+
+	/// Imports the monitors of a configuration directory
+	///
+	/// # Arguments
+	/// * `path` - Directory of monitor files, loaded as by [`MonitorRepository`]
+	/// * `network_service` - Networks the monitors may reference
+	/// * `trigger_service` - Triggers the monitors may reference
+	/// * `force` - Whether to replace the stored monitors differing from the loaded ones
+	///
+	/// # Returns
+	/// * `Result<usize, RepositoryError>` - Number of monitors added or replaced
+	///
+	/// # Errors
+	/// Returns an error if the directory fails to load, a monitor references a missing network
+	/// or trigger, or a loaded monitor differs from the stored one and `force` is not set. The
+	/// conflicting names are listed in the `conflicts` metadata of the error, and the repository
+	/// is left unchanged.
+	pub async fn import_from_filesystem(
+		&self,
+		path: &Path,
+		network_service: &NetworkService<N>,
+		trigger_service: &TriggerService<T>,
+		force: bool,
+	) -> Result<usize, RepositoryError> {
+		let monitors = <MonitorRepository<N, T> as MonitorRepositoryTrait<N, T>>::load_all(
+			Some(path),
+			Some(network_service.clone()),
+			Some(trigger_service.clone()),
+		)
+		.await?;
+		let references = secret_references(path, false, &monitors);
+		let count = self.monitors.import("Monitor", path, monitors, force)?;
+		self.monitors.record_references(references);
+		Ok(count)
+	}
+
+	/// Writes each monitor to `<name>.json` in a directory
+	///
+	/// Monitors are written as stored, with their contract references and presets resolved.
+	/// Secrets loaded from references are written as those references.
+	///
+	/// # Returns
+	/// * `Result<Vec<PathBuf>, RepositoryError>` - Paths of the written files, sorted by name
+	pub fn export_to_filesystem(&self, dir: &Path) -> Result<Vec<PathBuf>, RepositoryError> {
+		self.monitors.export("Monitor", dir, |_, monitor| monitor)
+	}
+
+	/// Validates a monitor and its references to networks and triggers
+	fn validate_monitor(
+		name: &str,
+		monitor: &Monitor,
+		network_service: &NetworkService<N>,
+		trigger_service: &TriggerService<T>,
+	) -> Result<(), RepositoryError> {
+		validate("Monitor", name, monitor)?;
+		MonitorRepository::<N, T>::validate_monitor_references(
+			&HashMap::from([(name.to_string(), monitor.clone())]),
+			&trigger_service.get_all(),
+			&network_service.get_all(),
+		)
+	}
+}
+
+#[async_trait]
+impl<
+		N: NetworkRepositoryTrait + Send + Sync + 'static,
+		T: TriggerRepositoryTrait + Send + Sync + 'static,
+	> MonitorRepositoryTrait<N, T> for InMemoryMonitorRepository<N, T>
+{
+	async fn new(
+		path: Option<&Path>,
+		network_service: Option<NetworkService<N>>,
+		trigger_service: Option<TriggerService<T>>,
+	) -> Result<Self, RepositoryError> {
+		let monitors = <Self as MonitorRepositoryTrait<N, T>>::load_all(
+			path,
+			network_service,
+			trigger_service,
+		)
+		.await?;
+		let references = secret_references(
+			path.unwrap_or(Path::new("config/monitors")),
+			false,
+			&monitors,
+		);
+		let repository = Self::new_with_monitors(monitors);
+		repository.monitors.record_references(references);
+		Ok(repository)
+	}
+
+	async fn load_all(
+		path: Option<&Path>,
+		network_service: Option<NetworkService<N>>,
+		trigger_service: Option<TriggerService<T>>,
+	) -> Result<HashMap<String, Monitor>, RepositoryError> {
+		<MonitorRepository<N, T> as MonitorRepositoryTrait<N, T>>::load_all(
+			path,
+			network_service,
+			trigger_service,
+		)
+		.await
+	}
+
+	async fn load_from_path(
+		&self,
+		path: Option<&Path>,
+		network_service: Option<NetworkService<N>>,
+		trigger_service: Option<TriggerService<T>>,
+	) -> Result<Monitor, RepositoryError> {
+		MonitorRepository::<N, T>::new_with_monitors(HashMap::new())
+			.load_from_path(path, network_service, trigger_service)
+			.await
+	}
+
+	fn get(&self, monitor_id: &str) -> Option<Monitor> {
+		self.monitors.get(monitor_id)
+	}
+
+	fn get_all(&self) -> HashMap<String, Monitor> {
+		self.monitors.get_all()
+	}
+
+	fn get_shared(&self, monitor_id: &str) -> Option<Arc<Monitor>> {
+		self.monitors.get_shared(monitor_id)
+	}
+
+	fn get_all_shared(&self) -> HashMap<String, Arc<Monitor>> {
+		self.monitors.get_all_shared()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{
+		models::{SecretValue, TriggerReference},
+		utils::tests::builders::{
+			evm::monitor::MonitorBuilder, network::NetworkBuilder, trigger::TriggerBuilder,
+		},
+	};
+
+	type Monitors = InMemoryMonitorRepository<InMemoryNetworkRepository, InMemoryTriggerRepository>;
+
+	fn repositories() -> (InMemoryNetworkRepository, InMemoryTriggerRepository) {
+		let networks = InMemoryNetworkRepository::new_with_networks(HashMap::new());
+		networks
+			.add(NetworkBuilder::new().slug("ethereum_mainnet").build())
+			.unwrap();
+		let triggers = InMemoryTriggerRepository::new_with_triggers(HashMap::new());
+		triggers
+			.add(TriggerBuilder::new().name("notify").build())
+			.unwrap();
+		(networks, triggers)
+	}
+
+	fn services(
+		networks: &InMemoryNetworkRepository,
+		triggers: &InMemoryTriggerRepository,
+	) -> (
+		NetworkService<InMemoryNetworkRepository>,
+		TriggerService<InMemoryTriggerRepository>,
+	) {
+		(
+			NetworkService::new_with_repository(networks.clone()).unwrap(),
+			TriggerService::new_with_repository(triggers.clone()).unwrap(),
+		)
+	}
+
+	fn monitor(name: &str) -> Monitor {
+		MonitorBuilder::new()
+			.name(name)
+			.networks(vec!["ethereum_mainnet".to_string()])
+			.triggers(vec!["notify".to_string()])
+			.build()
+	}
+
+	#[test]
+	fn test_writes_are_shared_by_clones() {
+		let (networks, triggers) = repositories();
+		let (network_service, trigger_service) = services(&networks, &triggers);
+		let monitors = Monitors::new_with_monitors(HashMap::new());
+		let clone = monitors.clone();
+		let mut versions = monitors.subscribe();
+
+		monitors
+			.add(monitor("watched"), &network_service, &trigger_service)
+			.unwrap();
+		let mut paused = monitor("watched");
+		paused.paused = true;
+		clone
+			.update("watched", paused, &network_service, &trigger_service)
+			.unwrap();
+		assert!(monitors.get("watched").unwrap().paused);
+		assert!(Arc::ptr_eq(
+			&monitors.get_shared("watched").unwrap(),
+			&clone.get_shared("watched").unwrap()
+		));
+		assert!(versions.has_changed().unwrap());
+		assert_eq!(*versions.borrow_and_update(), 2);
+
+		assert!(monitors.get_active().is_empty());
+		clone.set_paused("watched", false).unwrap();
+		assert_eq!(monitors.get_active().len(), 1);
+		assert_eq!(*versions.borrow_and_update(), 3);
+		assert!(monitors.set_paused("missing", true).is_err());
+
+		assert_eq!(monitors.get_by_network("ethereum_mainnet").len(), 1);
+		assert!(monitors.get_by_network("stellar_mainnet").is_empty());
+		assert_eq!(clone.remove("watched").unwrap().name, "watched");
+		assert!(monitors.get_all().is_empty());
+		assert!(monitors.remove("watched").is_err());
+	}
+
+	#[test]
+	fn test_writes_validate_references() {
+		let (networks, triggers) = repositories();
+		let (network_service, trigger_service) = services(&networks, &triggers);
+		let monitors = Monitors::new_with_monitors(HashMap::new());
+
+		let mut invalid = monitor("invalid");
+		invalid.networks = vec!["missing_network".to_string()];
+		invalid.triggers = vec![TriggerReference::Name("missing_trigger".to_string())];
+		let err = monitors
+			.add(invalid, &network_service, &trigger_service)
+			.unwrap_err()
+			.to_string();
+		assert!(err.contains("references non-existent network 'missing_network'"));
+		assert!(err.contains("references non-existent trigger 'missing_trigger'"));
+
+		monitors
+			.add(monitor("valid"), &network_service, &trigger_service)
+			.unwrap();
+		assert!(monitors
+			.add(monitor("valid"), &network_service, &trigger_service)
+			.unwrap_err()
+			.to_string()
+			.contains("already exists"));
+		let mut invalid = monitor("valid");
+		invalid.triggers = vec![TriggerReference::Name("missing_trigger".to_string())];
+		assert!(monitors
+			.update("valid", invalid, &network_service, &trigger_service)
+			.is_err());
+		assert_eq!(monitors.get("valid").unwrap().triggers.len(), 1);
+		assert!(monitors
+			.update(
+				"missing",
+				monitor("missing"),
+				&network_service,
+				&trigger_service
+			)
+			.is_err());
+
+		// Services read through to the repositories, and see later writes without a reload
+		let mut escalated = monitor("valid");
+		escalated.triggers = vec![TriggerReference::Name("escalate".to_string())];
+		assert!(trigger_service.get("escalate").is_none());
+		triggers
+			.add(TriggerBuilder::new().name("escalate").build())
+			.unwrap();
+		monitors
+			.update("valid", escalated, &network_service, &trigger_service)
+			.unwrap();
+		assert!(trigger_service.get("escalate").is_some());
+
+		networks.remove("ethereum_mainnet").unwrap();
+		assert!(network_service.get("ethereum_mainnet").is_none());
+		assert!(network_service.get_all().is_empty());
+	}
+
+	#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+	async fn test_concurrent_writes_and_reads() {
+		let (networks, triggers) = repositories();
+		let (network_service, trigger_service) = services(&networks, &triggers);
+		let monitors = Monitors::new_with_monitors(HashMap::new());
+
+		let writers = (0..16).map(|i| {
+			let monitors = monitors.clone();
+			let network_service = network_service.clone();
+			let trigger_service = trigger_service.clone();
+			tokio::spawn(async move {
+				let name = format!("monitor_{}", i);
+				monitors
+					.add(monitor(&name), &network_service, &trigger_service)
+					.unwrap();
+				let mut paused = monitor(&name);
+				paused.paused = true;
+				monitors
+					.update(&name, paused, &network_service, &trigger_service)
+					.unwrap();
+				if i % 2 == 0 {
+					monitors.remove(&name).unwrap();
+				}
+			})
+		});
+		let readers = (0..16).map(|_| {
+			let monitors = monitors.clone();
+			tokio::spawn(async move {
+				for _ in 0..100 {
+					let all = monitors.get_all();
+					assert!(all.len() <= 16);
+					assert!(monitors.get_by_network("ethereum_mainnet").len() <= 16);
+					tokio::task::yield_now().await;
+				}
+			})
+		});
+		let tasks = writers.chain(readers).collect::<Vec<_>>();
+		tokio::time::timeout(std::time::Duration::from_secs(10), async {
+			for task in tasks {
+				task.await.unwrap();
+			}
+		})
+		.await
+		.expect("concurrent writes and reads completed");
+
+		let all = monitors.get_all();
+		assert_eq!(all.len(), 8);
+		assert!(all.values().all(|monitor| monitor.paused));
+		assert_eq!(*monitors.subscribe().borrow(), 40);
+	}
+
+	#[tokio::test]
+	async fn test_filesystem_round_trip() {
+		let (networks, triggers) = repositories();
+		let (network_service, trigger_service) = services(&networks, &triggers);
+		let monitors = Monitors::new_with_monitors(HashMap::new());
+		monitors
+			.add(monitor("watched"), &network_service, &trigger_service)
+			.unwrap();
+
+		let export = |dir: &Path,
+		              networks: &InMemoryNetworkRepository,
+		              triggers: &InMemoryTriggerRepository,
+		              monitors: &Monitors| {
+			[
+				networks.export_to_filesystem(&dir.join("networks")),
+				triggers.export_to_filesystem(&dir.join("triggers")),
+				monitors.export_to_filesystem(&dir.join("monitors")),
+			]
+			.into_iter()
+			.flat_map(Result::unwrap)
+			.collect::<Vec<_>>()
+		};
+		let exported = tempfile::tempdir().unwrap();
+		let files = export(exported.path(), &networks, &triggers, &monitors);
+		assert_eq!(
+			files,
+			vec![
+				exported.path().join("networks/ethereum_mainnet.json"),
+				exported.path().join("triggers/notify.json"),
+				exported.path().join("monitors/watched.json"),
+			]
+		);
+
+		// Files to repositories, and back to files
+		let imported_networks = InMemoryNetworkRepository::new_with_networks(HashMap::new());
+		let imported_triggers = InMemoryTriggerRepository::new_with_triggers(HashMap::new());
+		let imported_monitors = Monitors::new_with_monitors(HashMap::new());
+		assert_eq!(
+			imported_networks
+				.import_from_filesystem(&exported.path().join("networks"), false)
+				.await
+				.unwrap(),
+			1
+		);
+		assert_eq!(
+			imported_triggers
+				.import_from_filesystem(&exported.path().join("triggers"), false)
+				.await
+				.unwrap(),
+			1
+		);
+		let (imported_network_service, imported_trigger_service) =
+			services(&imported_networks, &imported_triggers);
+		assert_eq!(
+			imported_monitors
+				.import_from_filesystem(
+					&exported.path().join("monitors"),
+					&imported_network_service,
+					&imported_trigger_service,
+					false
+				)
+				.await
+				.unwrap(),
+			1
+		);
+		assert_eq!(imported_networks.get_all(), networks.get_all());
+		assert_eq!(imported_triggers.get_all(), triggers.get_all());
+		assert_eq!(imported_monitors.get_all(), monitors.get_all());
+
+		let reexported = tempfile::tempdir().unwrap();
+		let reexported_files = export(
+			reexported.path(),
+			&imported_networks,
+			&imported_triggers,
+			&imported_monitors,
+		);
+		for (file, reexported_file) in files.iter().zip(&reexported_files) {
+			assert_eq!(
+				std::fs::read(file).unwrap(),
+				std::fs::read(reexported_file).unwrap()
+			);
+		}
+
+		// Importing the same configurations again changes nothing
+		let version = *imported_networks.subscribe().borrow();
+		assert_eq!(
+			imported_networks
+				.import_from_filesystem(&exported.path().join("networks"), false)
+				.await
+				.unwrap(),
+			0
+		);
+		assert_eq!(*imported_networks.subscribe().borrow(), version);
+	}
+
+	#[tokio::test]
+	async fn test_export_writes_secret_references() {
+		const RPC_URL_VAR: &str = "IN_MEMORY_EXPORT_TEST_RPC_URL";
+		const WEBHOOK_URL_VAR: &str = "IN_MEMORY_EXPORT_TEST_WEBHOOK_URL";
+		const RPC_URL: &str = "https://rpc.example.com/v1/resolved-rpc-key";
+		const WEBHOOK_URL: &str = "https://hooks.example.com/resolved-webhook-key";
+		std::env::set_var(RPC_URL_VAR, RPC_URL);
+		std::env::set_var(WEBHOOK_URL_VAR, WEBHOOK_URL);
+
+		let config = tempfile::tempdir().unwrap();
+		let network = NetworkBuilder::new()
+			.slug("ethereum_mainnet")
+			.clear_rpc_urls()
+			.add_secret_rpc_url(
+				SecretValue::Environment(RPC_URL_VAR.to_string()),
+				"rpc",
+				100,
+			)
+			.build();
+		let trigger = TriggerBuilder::new()
+			.name("hook")
+			.webhook("https://placeholder.example.com")
+			.url(SecretValue::Environment(WEBHOOK_URL_VAR.to_string()))
+			.build();
+		std::fs::create_dir_all(config.path().join("networks")).unwrap();
+		std::fs::create_dir_all(config.path().join("triggers")).unwrap();
+		std::fs::write(
+			config.path().join("networks/ethereum_mainnet.json"),
+			serde_json::to_string(&network).unwrap(),
+		)
+		.unwrap();
+		std::fs::write(
+			config.path().join("triggers/hook.json"),
+			serde_json::json!({ "hook": trigger }).to_string(),
+		)
+		.unwrap();
+
+		let networks = InMemoryNetworkRepository::new_with_networks(HashMap::new());
+		let triggers = InMemoryTriggerRepository::new_with_triggers(HashMap::new());
+		networks
+			.import_from_filesystem(&config.path().join("networks"), false)
+			.await
+			.unwrap();
+		triggers
+			.import_from_filesystem(&config.path().join("triggers"), false)
+			.await
+			.unwrap();
+		// The stored configurations hold the resolved secrets
+		assert_eq!(
+			networks.get("ethereum_mainnet").unwrap().rpc_urls[0]
+				.url
+				.as_ref(),
+			RPC_URL
+		);
+
+		let exported = tempfile::tempdir().unwrap();
+		let files = [
+			networks.export_to_filesystem(&exported.path().join("networks")),
+			triggers.export_to_filesystem(&exported.path().join("triggers")),
+		]
+		.into_iter()
+		.flat_map(Result::unwrap)
+		.collect::<Vec<_>>();
+		for file in &files {
+			let content = std::fs::read_to_string(file).unwrap();
+			assert!(!content.contains("resolved-rpc-key"), "{}", content);
+			assert!(!content.contains("resolved-webhook-key"), "{}", content);
+		}
+		assert!(std::fs::read_to_string(&files[0])
+			.unwrap()
+			.contains(RPC_URL_VAR));
+		assert!(std::fs::read_to_string(&files[1])
+			.unwrap()
+			.contains(WEBHOOK_URL_VAR));
+
+		// The exported files load back to the same configurations
+		let reimported = InMemoryNetworkRepository::new_with_networks(HashMap::new());
+		reimported
+			.import_from_filesystem(&exported.path().join("networks"), false)
+			.await
+			.unwrap();
+		assert_eq!(reimported.get_all(), networks.get_all());
+
+		// A secret replaced after the import is written as stored
+		networks
+			.update(
+				"ethereum_mainnet",
+				NetworkBuilder::new()
+					.slug("ethereum_mainnet")
+					.rpc_url("https://rpc.example.com/v1/replaced")
+					.build(),
+			)
+			.unwrap();
+		let files = networks
+			.export_to_filesystem(&exported.path().join("networks"))
+			.unwrap();
+		let content = std::fs::read_to_string(&files[0]).unwrap();
+		assert!(content.contains("https://rpc.example.com/v1/replaced"));
+		assert!(!content.contains(RPC_URL_VAR));
+	}
+
+	#[tokio::test]
+	async fn test_import_reports_conflicts() {
+		let (networks, triggers) = repositories();
+		let (network_service, trigger_service) = services(&networks, &triggers);
+		let monitors = Monitors::new_with_monitors(HashMap::new());
+		for name in ["watched", "unchanged"] {
+			monitors
+				.add(monitor(name), &network_service, &trigger_service)
+				.unwrap();
+		}
+		let dir = tempfile::tempdir().unwrap();
+		monitors.export_to_filesystem(dir.path()).unwrap();
+
+		let mut paused = monitor("watched");
+		paused.paused = true;
+		monitors
+			.update("watched", paused, &network_service, &trigger_service)
+			.unwrap();
+		let version = *monitors.subscribe().borrow();
+
+		match monitors
+			.import_from_filesystem(dir.path(), &network_service, &trigger_service, false)
+			.await
+		{
+			Err(RepositoryError::ValidationError(context)) => {
+				assert_eq!(context.metadata.unwrap()["conflicts"], "watched");
+			}
+			other => panic!("expected a conflict, got {:?}", other.map(|_| ())),
+		}
+		assert!(monitors.get("watched").unwrap().paused);
+		assert_eq!(*monitors.subscribe().borrow(), version);
+
+		assert_eq!(
+			monitors
+				.import_from_filesystem(dir.path(), &network_service, &trigger_service, true)
+				.await
+				.unwrap(),
+			1
+		);
+		assert!(!monitors.get("watched").unwrap().paused);
+		assert_eq!(*monitors.subscribe().borrow(), version + 1);
+
+		// Names that are not file names are refused before anything is written
+		let escaping = Monitors::new_with_monitors(HashMap::from([
+			("escaping".to_string(), monitor("escaping")),
+			("../escaped".to_string(), monitor("../escaped")),
+		]));
+		let dir = tempfile::tempdir().unwrap();
+		assert!(escaping
+			.export_to_filesystem(&dir.path().join("monitors"))
+			.is_err());
+		assert!(!dir.path().join("monitors").exists());
+	}
+
+	#[test]
+	fn test_network_and_trigger_writes() {
+		let (networks, triggers) = repositories();
+		let mut versions = networks.subscribe();
+
+		assert!(networks
+			.add(NetworkBuilder::new().slug("ethereum_mainnet").build())
+			.is_err());
+		let updated = NetworkBuilder::new()
+			.slug("ethereum_mainnet")
+			.rpc_url("https://eth-fallback.example.com")
+			.build();
+		networks
+			.update("ethereum_mainnet", updated.clone())
+			.unwrap();
+		assert!(versions.has_changed().unwrap());
+		assert_eq!(networks.get("ethereum_mainnet"), Some(updated));
+		assert_eq!(
+			networks.remove("ethereum_mainnet").unwrap().slug,
+			"ethereum_mainnet"
+		);
+
+		networks
+			.add(
+				NetworkBuilder::new()
+					.slug("sepolia")
+					.cron_schedule(" 0  */5 * * * * * ")
+					.build(),
+			)
+			.unwrap();
+		assert_eq!(
+			networks.get("sepolia").unwrap().cron_schedule,
+			"0 */5 * * * *"
+		);
+		let err = networks
+			.update(
+				"sepolia",
+				NetworkBuilder::new()
+					.slug("sepolia")
+					.cron_schedule("*/5 * * *")
+					.build(),
+			)
+			.unwrap_err();
+		assert!(err.to_string().contains("sepolia"));
+		assert!(err.to_string().contains("cron_schedule"));
+
+		assert!(triggers
+			.update("missing", TriggerBuilder::new().name("missing").build())
+			.is_err());
+		assert_eq!(triggers.remove("notify").unwrap().name, "notify");
+		assert!(triggers.get_all().is_empty());
+	}
+
+	#[test]
+	fn test_upsert_many_is_all_or_nothing() {
+		let (networks, triggers) = repositories();
+		let batch = |rpc_url: &str| {
+			["ethereum_mainnet", "sepolia", "holesky"]
+				.into_iter()
+				.map(|slug| NetworkBuilder::new().slug(slug).rpc_url(rpc_url).build())
+				.collect::<Vec<_>>()
+		};
+		assert_eq!(
+			networks
+				.upsert_many(batch("https://rpc.example.com"))
+				.unwrap(),
+			3
+		);
+		// Unchanged networks are not counted
+		let mut modified = batch("https://rpc.example.com");
+		modified[1] = NetworkBuilder::new()
+			.slug("sepolia")
+			.rpc_url("https://sepolia.example.com")
+			.build();
+		assert_eq!(networks.upsert_many(modified.clone()).unwrap(), 1);
+		assert_eq!(networks.get("sepolia"), Some(modified[1].clone()));
+
+		let version = *networks.subscribe().borrow();
+		let mut invalid = batch("https://other.example.com");
+		invalid[2].cron_schedule = "*/5 * * *".to_string();
+		let err = networks.upsert_many(invalid).unwrap_err();
+		assert!(err.to_string().contains("1 of 3 Network(s) rejected"));
+		assert!(err.to_string().contains("Network 'holesky'"));
+		assert_eq!(networks.get("sepolia"), Some(modified[1].clone()));
+		assert_eq!(*networks.subscribe().borrow(), version);
+
+		let err = triggers
+			.upsert_many(vec![
+				TriggerBuilder::new().name("pager").build(),
+				TriggerBuilder::new().name("").build(),
+			])
+			.unwrap_err();
+		assert!(err
+			.to_string()
+			.contains("Trigger '#1': name must not be empty"));
+		assert!(triggers.get("pager").is_none());
+		assert_eq!(
+			triggers
+				.upsert_many(vec![TriggerBuilder::new().name("pager").build()])
+				.unwrap(),
+			1
+		);
+	}
+
+	#[test]
+	fn test_update_if_unchanged_rejects_stale_writes() {
+		let (networks, triggers) = repositories();
+		let (network_service, trigger_service) = services(&networks, &triggers);
+		let monitors = Monitors::new_with_monitors(HashMap::new());
+		monitors
+			.add(monitor("watched"), &network_service, &trigger_service)
+			.unwrap();
+
+		// Both writers read the same monitor
+		let read_hash = monitors.get_content_hash("watched").unwrap();
+		let mut paused = monitor("watched");
+		paused.paused = true;
+		let mut tagged = monitor("watched");
+		tagged.tags = vec!["payments".to_string()];
+
+		monitors
+			.update_if_unchanged(
+				"watched",
+				&read_hash,
+				paused,
+				&network_service,
+				&trigger_service,
+			)
+			.unwrap();
+		let winner_hash = monitors.get_content_hash("watched").unwrap();
+		assert_ne!(winner_hash, read_hash);
+		let version = *monitors.subscribe().borrow();
+
+		match monitors.update_if_unchanged(
+			"watched",
+			&read_hash,
+			tagged,
+			&network_service,
+			&trigger_service,
+		) {
+			Err(RepositoryError::ConflictError(context)) => {
+				assert_eq!(context.metadata.unwrap()["current_hash"], winner_hash);
+			}
+			other => panic!("expected a conflict, got {:?}", other),
+		}
+		assert!(monitors.get("watched").unwrap().paused);
+		assert_eq!(*monitors.subscribe().borrow(), version);
+		assert!(monitors.get_content_hash("missing").is_none());
+	}
+}
@@ -8,18 +8,45 @@
 //! - Validating configuration references between different types
 //! - Accessing configurations through a service layer
 //!
+//! In-memory implementations of the network, trigger and monitor repositories can be written
+//! to at runtime, for embedding the monitor and driving its configuration programmatically.
+//!
 //! Currently supported repositories:
+//! - Contract: Loads contract specs shared between monitors
 //! - Monitor: Loads and validates monitor configurations, ensuring referenced networks and triggers
 //!   exist
 //! - Network: Loads network configurations defining blockchain connection details
 //! - Trigger: Loads trigger configurations defining actions to take when conditions match
+//! - Trigger group: Loads named lists of triggers referenced by monitors as a single trigger
+//!
+//! Loading monitors also reports the triggers and networks no monitor references, see
+//! [`validate_configuration_consistency`]. The configuration held by the services can be
+//! exported with its secrets redacted, see [`export_snapshot`].
 
+mod consistency;
+mod contract;
 mod error;
+mod in_memory;
 mod monitor;
 mod network;
+mod snapshot;
 mod trigger;
+mod trigger_group;
 
+pub use consistency::{
+	validate_configuration_consistency, ConfigValidationReport, ConsistencyMode,
+	MonitorConsistency, STRICT_CONFIG_CONSISTENCY_ENV,
+};
+pub use contract::{ContractRepository, ContractRepositoryTrait};
 pub use error::RepositoryError;
-pub use monitor::{MonitorRepository, MonitorRepositoryTrait, MonitorService};
+pub use in_memory::{
+	InMemoryMonitorRepository, InMemoryNetworkRepository, InMemoryTriggerRepository,
+};
+pub use monitor::{
+	monitor_tags_from_env, MonitorRepository, MonitorRepositoryTrait, MonitorService,
+	MONITOR_TAGS_ENV,
+};
 pub use network::{NetworkRepository, NetworkRepositoryTrait, NetworkService};
+pub use snapshot::{export_snapshot, ConfigExport};
 pub use trigger::{TriggerRepository, TriggerRepositoryTrait, TriggerService};
+pub use trigger_group::{TriggerGroupRepository, TriggerGroupRepositoryTrait};
@@ -2,31 +2,77 @@
 //!
 //! This module provides storage and retrieval of monitor configurations, including
 //! validation of references to networks and triggers. The repository loads monitor
-//! configurations from JSON files and ensures all referenced components exist.
+//! configurations from JSON files, resolves the contracts they reference from the
+//! contracts registry, expands the trigger and network groups they reference and ensures all
+//! referenced components exist. Monitors can also be added, updated and removed at runtime,
+//! which writes them to the monitors directory after the same validation.
 
 #![allow(clippy::result_large_err)]
 
-use std::{collections::HashMap, marker::PhantomData, path::Path};
+use std::{
+	collections::HashMap,
+	marker::PhantomData,
+	path::{Path, PathBuf},
+	sync::Arc,
+};
 
 use async_trait::async_trait;
 
 use crate::{
-	models::{ConfigLoader, Monitor, Network, Trigger, SCRIPT_LANGUAGE_EXTENSIONS},
+	models::{
+		ConfigError, ConfigLoader, Contract, Monitor, Network, Trigger, TriggerGroup,
+		SCRIPT_LANGUAGE_EXTENSIONS,
+	},
 	repositories::{
+		consistency::{validate_configuration_consistency, ConsistencyMode},
+		contract::{ContractRepository, ContractRepositoryTrait},
 		error::RepositoryError,
+		in_memory::is_file_stem,
 		network::{NetworkRepository, NetworkRepositoryTrait, NetworkService},
 		trigger::{TriggerRepository, TriggerRepositoryTrait, TriggerService},
+		trigger_group::{TriggerGroupRepository, TriggerGroupRepositoryTrait},
 	},
+	services::trigger::{unknown_template_variables, TemplateVariablesMode},
 };
 
+/// Default directory of the monitor configurations
+const DEFAULT_MONITORS_PATH: &str = "config/monitors";
+
+/// Environment variable restricting the monitors loaded to those carrying one of its
+/// comma-separated tags
+pub const MONITOR_TAGS_ENV: &str = "MONITOR_TAGS";
+
+/// Returns the tags set by `MONITOR_TAGS`, or None to load all monitors
+///
+/// Read when the services are created at startup and passed to
+/// [`MonitorRepository::new_with_tag_filter`], along with [`ConsistencyMode::from_env`].
+pub fn monitor_tags_from_env() -> Option<Vec<String>> {
+	let tags: Vec<String> = std::env::var(MONITOR_TAGS_ENV)
+		.ok()?
+		.split(',')
+		.map(str::trim)
+		.filter(|tag| !tag.is_empty())
+		.map(String::from)
+		.collect();
+	(!tags.is_empty()).then_some(tags)
+}
+
 /// Repository for storing and retrieving monitor configurations
 #[derive(Clone)]
 pub struct MonitorRepository<
 	N: NetworkRepositoryTrait + Send + 'static,
 	T: TriggerRepositoryTrait + Send + 'static,
 > {
-	/// Map of monitor names to their configurations
-	pub monitors: HashMap<String, Monitor>,
+	/// Map of monitor names to their configurations, shared with the callers of `get_shared`
+	pub monitors: HashMap<String, Arc<Monitor>>,
+	/// Directory the monitors are loaded from and written to, None to keep writes in memory
+	path: Option<PathBuf>,
+	/// Networks monitors are validated against when none are passed, shared with the service
+	/// the repository was created with so that its reloads are seen
+	network_service: Option<NetworkService<N>>,
+	/// Triggers monitors are validated against when none are passed, shared with the service
+	/// the repository was created with so that its reloads are seen
+	trigger_service: Option<TriggerService<T>>,
 	_network_repository: PhantomData<N>,
 	_trigger_repository: PhantomData<T>,
 }
@@ -39,49 +85,775 @@ impl<
 	/// Create a new monitor repository from the given path
 	///
 	/// Loads all monitor configurations from JSON files in the specified directory
-	/// (or default config directory if None is provided).
+	/// (or default config directory if None is provided). Unused triggers and networks are
+	/// logged as warnings.
 	pub async fn new(
 		path: Option<&Path>,
 		network_service: Option<NetworkService<N>>,
 		trigger_service: Option<TriggerService<T>>,
 	) -> Result<Self, RepositoryError> {
-		let monitors = Self::load_all(path, network_service, trigger_service).await?;
+		Self::new_with_tag_filter(
+			path,
+			network_service,
+			trigger_service,
+			None,
+			ConsistencyMode::Lenient,
+		)
+		.await
+	}
+
+	/// Create a new monitor repository holding the monitors carrying one of the given tags
+	///
+	/// Only the monitors kept are validated, so excluded monitors may reference networks and
+	/// triggers that do not exist. Triggers and networks only used by excluded monitors are
+	/// not reported as unused.
+	///
+	/// # Arguments
+	/// * `path` - Directory of the monitor files, the default directory if None
+	/// * `network_service` - Networks the monitors may reference
+	/// * `trigger_service` - Triggers the monitors may reference
+	/// * `tag_filter` - Tags of the monitors to load, all monitors if None
+	/// * `consistency` - How triggers and networks no loaded monitor references are handled,
+	///   only checked when all monitors are loaded
+	pub async fn new_with_tag_filter(
+		path: Option<&Path>,
+		network_service: Option<NetworkService<N>>,
+		trigger_service: Option<TriggerService<T>>,
+		tag_filter: Option<Vec<String>>,
+		consistency: ConsistencyMode,
+	) -> Result<Self, RepositoryError> {
+		let monitors = Self::load_tagged(
+			path,
+			network_service.clone(),
+			trigger_service.clone(),
+			tag_filter.as_deref(),
+			consistency,
+		)
+		.await?;
 		Ok(MonitorRepository {
-			monitors,
+			monitors: share_monitors(monitors),
+			path: Some(
+				path.unwrap_or(Path::new(DEFAULT_MONITORS_PATH))
+					.to_path_buf(),
+			),
+			network_service,
+			trigger_service,
 			_network_repository: PhantomData,
 			_trigger_repository: PhantomData,
 		})
 	}
 
 	/// Create a new monitor repository from a list of monitors
+	///
+	/// Monitors added to this repository are kept in memory only.
 	pub fn new_with_monitors(monitors: HashMap<String, Monitor>) -> Self {
 		MonitorRepository {
-			monitors,
+			monitors: share_monitors(monitors),
+			path: None,
+			network_service: None,
+			trigger_service: None,
 			_network_repository: PhantomData,
 			_trigger_repository: PhantomData,
 		}
 	}
 
-	/// Returns an error if any monitor references a non-existent network or trigger.
-	pub fn validate_monitor_references(
-		monitors: &HashMap<String, Monitor>,
-		triggers: &HashMap<String, Trigger>,
-		networks: &HashMap<String, Network>,
-	) -> Result<(), RepositoryError> {
-		let mut validation_errors = Vec::new();
-		let mut metadata = HashMap::new();
+	/// Loads the monitors of a directory carrying one of the given tags, all of them if None
+	async fn load_tagged(
+		path: Option<&Path>,
+		network_service: Option<NetworkService<N>>,
+		trigger_service: Option<TriggerService<T>>,
+		tag_filter: Option<&[String]>,
+		consistency: ConsistencyMode,
+	) -> Result<HashMap<String, Monitor>, RepositoryError> {
+		let mut monitors = Monitor::load_all(path).await.map_err(|e| {
+			RepositoryError::load_error(
+				"Failed to load monitors",
+				Some(Box::new(e)),
+				Some(HashMap::from([(
+					"path".to_string(),
+					path.map_or_else(|| "default".to_string(), |p| p.display().to_string()),
+				)])),
+			)
+		})?;
+		if let Some(tags) = tag_filter {
+			monitors.retain(|_, monitor| monitor.tags.iter().any(|tag| tags.contains(tag)));
+		}
+		let monitors = Self::resolve_contract_references(monitors, path).await?;
+		let monitors = Self::expand_presets(monitors)?;
+
+		let networks = match network_service {
+			Some(service) => service.get_all(),
+			None => {
+				NetworkRepository::new(None)
+					.await
+					.map_err(|e| {
+						RepositoryError::load_error(
+							"Failed to load networks for monitor validation",
+							Some(Box::new(e)),
+							None,
+						)
+					})?
+					.networks
+			}
+		};
+
+		let triggers = match trigger_service {
+			Some(service) => service.get_all(),
+			None => {
+				TriggerRepository::new(None)
+					.await
+					.map_err(|e| {
+						RepositoryError::load_error(
+							"Failed to load triggers for monitor validation",
+							Some(Box::new(e)),
+							None,
+						)
+					})?
+					.triggers
+			}
+		};
+
+		let monitors = Self::resolve_trigger_group_references(monitors, &triggers, path).await?;
+		let monitors = Self::expand_network_groups(monitors, &networks);
+		Self::validate_monitor_references(&monitors, &triggers, &networks)?;
+		// Other instances run the monitors left out, and use the triggers and networks they need
+		if tag_filter.is_none() {
+			validate_configuration_consistency(&monitors, &triggers, &networks)
+				.enforce_unused(consistency)?;
+		}
+		Ok(monitors)
+	}
+
+	/// Sets the networks and triggers monitors are validated against when none are passed
+	///
+	/// Without them, the networks and triggers of the default directories are loaded for each
+	/// monitor added, updated or loaded from a path.
+	pub fn with_services(
+		mut self,
+		network_service: NetworkService<N>,
+		trigger_service: TriggerService<T>,
+	) -> Self {
+		self.network_service = Some(network_service);
+		self.trigger_service = Some(trigger_service);
+		self
+	}
+
+	/// Returns the networks and triggers to validate monitors against
+	///
+	/// The given services are used first, then the services of the repository, and the default
+	/// directories are only loaded when neither is set.
+	async fn references(
+		&self,
+		network_service: Option<NetworkService<N>>,
+		trigger_service: Option<TriggerService<T>>,
+	) -> Result<(HashMap<String, Network>, HashMap<String, Trigger>), RepositoryError> {
+		let networks = match network_service.or_else(|| self.network_service.clone()) {
+			Some(service) => service.get_all(),
+			None => NetworkRepository::new(None).await?.networks,
+		};
+		let triggers = match trigger_service.or_else(|| self.trigger_service.clone()) {
+			Some(service) => service.get_all(),
+			None => TriggerRepository::new(None).await?.triggers,
+		};
+		Ok((networks, triggers))
+	}
+
+	/// Returns the contracts registry directory for the given monitors directory
+	///
+	/// The registry sits next to the monitors directory (`config/contracts` for
+	/// `config/monitors`). Falls back to the default directory if there is none.
+	fn contracts_path(monitor_dir: Option<&Path>) -> Option<PathBuf> {
+		monitor_dir
+			.and_then(|dir| dir.parent())
+			.map(|dir| dir.join("contracts"))
+			.filter(|dir| dir.exists())
+	}
+
+	/// Returns the trigger groups directory for the given monitors directory
+	///
+	/// The directory sits next to the monitors directory (`config/trigger_groups` for
+	/// `config/monitors`). Falls back to the default directory if there is none.
+	fn trigger_groups_path(monitor_dir: Option<&Path>) -> Option<PathBuf> {
+		monitor_dir
+			.and_then(|dir| dir.parent())
+			.map(|dir| dir.join("trigger_groups"))
+			.filter(|dir| dir.exists())
+	}
+
+	/// Loads the contracts registry and resolves the contract references of the monitors
+	///
+	/// Returns an error naming the monitor and the contract for every reference that
+	/// cannot be resolved.
+	pub async fn resolve_contract_references(
+		monitors: HashMap<String, Monitor>,
+		monitor_dir: Option<&Path>,
+	) -> Result<HashMap<String, Monitor>, RepositoryError> {
+		if monitors
+			.values()
+			.all(|monitor| monitor.addresses.iter().all(|a| a.contract.is_none()))
+		{
+			return Ok(monitors);
+		}
+
+		let contracts_path = Self::contracts_path(monitor_dir);
+		let contracts = ContractRepository::new(contracts_path.as_deref())
+			.await?
+			.get_all();
+		Self::resolve_contracts(monitors, &contracts)
+	}
+
+	/// Resolves the contract references of the monitors against the given contracts
+	pub fn resolve_contracts(
+		monitors: HashMap<String, Monitor>,
+		contracts: &HashMap<String, Contract>,
+	) -> Result<HashMap<String, Monitor>, RepositoryError> {
+		let mut resolved = HashMap::with_capacity(monitors.len());
+		let mut validation_errors = Vec::new();
+		let mut metadata = HashMap::new();
+
+		for (key, monitor) in monitors {
+			match monitor.resolve_contracts(contracts) {
+				Ok(monitor) => {
+					resolved.insert(key, monitor);
+				}
+				Err(ConfigError::ValidationError(context)) => {
+					validation_errors.push(context.message.clone());
+					if let Some(contract) =
+						context.metadata.as_ref().and_then(|m| m.get("contract"))
+					{
+						metadata.insert(
+							format!("monitor_{}_invalid_contract", monitor.name),
+							contract.clone(),
+						);
+					}
+				}
+				Err(e) => validation_errors.push(e.to_string()),
+			}
+		}
+
+		if !validation_errors.is_empty() {
+			validation_errors.sort();
+			return Err(RepositoryError::validation_error(
+				format!(
+					"Configuration validation failed:\n{}",
+					validation_errors.join("\n"),
+				),
+				None,
+				Some(metadata),
+			));
+		}
+
+		Ok(resolved)
+	}
+
+	/// Expands the condition presets referenced by the monitors into event conditions
+	///
+	/// Returns an error naming the monitor and the preset for every preset that cannot be
+	/// expanded.
+	pub fn expand_presets(
+		monitors: HashMap<String, Monitor>,
+	) -> Result<HashMap<String, Monitor>, RepositoryError> {
+		let mut expanded = HashMap::with_capacity(monitors.len());
+		let mut validation_errors = Vec::new();
+		let mut metadata = HashMap::new();
+
+		for (key, monitor) in monitors {
+			match monitor.expand_presets() {
+				Ok(monitor) => {
+					expanded.insert(key, monitor);
+				}
+				Err(ConfigError::ValidationError(context)) => {
+					validation_errors.push(context.message.clone());
+					if let Some(preset) = context.metadata.as_ref().and_then(|m| m.get("preset")) {
+						metadata.insert(
+							format!("monitor_{}_invalid_preset", monitor.name),
+							preset.clone(),
+						);
+					}
+				}
+				Err(e) => validation_errors.push(e.to_string()),
+			}
+		}
+
+		if !validation_errors.is_empty() {
+			validation_errors.sort();
+			return Err(RepositoryError::validation_error(
+				format!(
+					"Configuration validation failed:\n{}",
+					validation_errors.join("\n"),
+				),
+				None,
+				Some(metadata),
+			));
+		}
+
+		Ok(expanded)
+	}
+
+	/// Loads the trigger groups and expands the group references of the monitors
+	///
+	/// Groups are loaded on every call, so a change of membership is picked up on reload
+	/// without editing the monitors.
+	pub async fn resolve_trigger_group_references(
+		monitors: HashMap<String, Monitor>,
+		triggers: &HashMap<String, Trigger>,
+		monitor_dir: Option<&Path>,
+	) -> Result<HashMap<String, Monitor>, RepositoryError> {
+		let trigger_groups_path = Self::trigger_groups_path(monitor_dir);
+		let groups = TriggerGroupRepository::new(trigger_groups_path.as_deref())
+			.await?
+			.get_all();
+		if groups.is_empty() {
+			return Ok(monitors);
+		}
+		Self::expand_trigger_groups(monitors, &groups, triggers)
+	}
+
+	/// Expands the trigger groups referenced by the monitors into their member triggers
+	///
+	/// Every group is checked, including groups no monitor references. Returns an error
+	/// naming the group for every unknown member or cycle, and the monitor for every
+	/// reference that cannot be expanded.
+	pub fn expand_trigger_groups(
+		monitors: HashMap<String, Monitor>,
+		groups: &HashMap<String, TriggerGroup>,
+		triggers: &HashMap<String, Trigger>,
+	) -> Result<HashMap<String, Monitor>, RepositoryError> {
+		let mut expanded = HashMap::with_capacity(monitors.len());
+		let mut validation_errors = Vec::new();
+		let mut metadata = HashMap::new();
+
+		for name in groups.keys() {
+			if let Err(e) = TriggerGroup::expand(name, groups, triggers) {
+				validation_errors.push(match e {
+					ConfigError::ValidationError(context) => context.message,
+					e => e.to_string(),
+				});
+			}
+		}
+
+		for (key, monitor) in monitors {
+			match monitor.expand_trigger_groups(groups, triggers) {
+				Ok(monitor) => {
+					expanded.insert(key, monitor);
+				}
+				Err(ConfigError::ValidationError(context)) => {
+					validation_errors.push(context.message.clone());
+					if let Some(group) = context
+						.metadata
+						.as_ref()
+						.and_then(|m| m.get("trigger_group"))
+					{
+						metadata.insert(
+							format!("monitor_{}_invalid_trigger_group", monitor.name),
+							group.clone(),
+						);
+					}
+				}
+				Err(e) => validation_errors.push(e.to_string()),
+			}
+		}
+
+		if !validation_errors.is_empty() {
+			validation_errors.sort();
+			validation_errors.dedup();
+			return Err(RepositoryError::validation_error(
+				format!(
+					"Configuration validation failed:\n{}",
+					validation_errors.join("\n"),
+				),
+				None,
+				Some(metadata),
+			));
+		}
+
+		Ok(expanded)
+	}
+
+	/// Expands the network groups referenced by the monitors into their member networks
+	///
+	/// A reference naming a network is kept as is. Members are added in slug order, and a
+	/// network referenced both directly and through a group is only listed once. References
+	/// naming neither a network nor a group are left for validation to report.
+	pub fn expand_network_groups(
+		monitors: HashMap<String, Monitor>,
+		networks: &HashMap<String, Network>,
+	) -> HashMap<String, Monitor> {
+		let mut groups: HashMap<&str, Vec<&str>> = HashMap::new();
+		for network in networks.values() {
+			if let Some(group) = network.group.as_deref() {
+				groups.entry(group).or_default().push(&network.slug);
+			}
+		}
+		if groups.is_empty() {
+			return monitors;
+		}
+		for members in groups.values_mut() {
+			members.sort();
+		}
+
+		monitors
+			.into_iter()
+			.map(|(key, mut monitor)| {
+				let mut expanded: Vec<String> = Vec::with_capacity(monitor.networks.len());
+				for reference in &monitor.networks {
+					let members = match groups.get(reference.as_str()) {
+						Some(members) if !networks.contains_key(reference) => members.clone(),
+						_ => vec![reference.as_str()],
+					};
+					for member in members {
+						if !expanded.iter().any(|slug| slug == member) {
+							expanded.push(member.to_string());
+						}
+					}
+				}
+				monitor.networks = expanded;
+				(key, monitor)
+			})
+			.collect()
+	}
+
+	/// Adds a monitor, writing it to `<name>.json` in the monitors directory
+	///
+	/// The monitor goes through the same validation as loaded monitors, and is only added to
+	/// the repository once it is written.
+	///
+	/// # Arguments
+	/// * `monitor` - Monitor to add, keyed by its name
+	/// * `network_service` - Networks the monitor may reference, those of the repository or of
+	///   the default directory if None
+	/// * `trigger_service` - Triggers the monitor may reference, those of the repository or of
+	///   the default directory if None
+	///
+	/// # Errors
+	/// Returns an error if a monitor with the same name exists, the monitor is invalid or it
+	/// cannot be written.
+	pub async fn add(
+		&mut self,
+		monitor: Monitor,
+		network_service: Option<NetworkService<N>>,
+		trigger_service: Option<TriggerService<T>>,
+	) -> Result<(), RepositoryError> {
+		let name = monitor.name.clone();
+		if self.monitors.contains_key(&name) {
+			return Err(RepositoryError::validation_error(
+				format!("Monitor '{}' already exists", name),
+				None,
+				Some(HashMap::from([("monitor_name".to_string(), name)])),
+			));
+		}
+		self.save(name, monitor, network_service, trigger_service)
+			.await
+	}
+
+	/// Replaces a monitor, rewriting its file in the monitors directory
+	///
+	/// # Arguments
+	/// * `name` - Key of the monitor to replace
+	/// * `monitor` - New configuration of the monitor
+	/// * `network_service` - Networks the monitor may reference, those of the repository or of
+	///   the default directory if None
+	/// * `trigger_service` - Triggers the monitor may reference, those of the repository or of
+	///   the default directory if None
+	///
+	/// # Errors
+	/// Returns an error if the monitor does not exist, the new configuration is invalid or it
+	/// cannot be written. The repository is left unchanged on error.
+	pub async fn update(
+		&mut self,
+		name: &str,
+		monitor: Monitor,
+		network_service: Option<NetworkService<N>>,
+		trigger_service: Option<TriggerService<T>>,
+	) -> Result<(), RepositoryError> {
+		if !self.monitors.contains_key(name) {
+			return Err(Self::not_found(name));
+		}
+		self.save(name.to_string(), monitor, network_service, trigger_service)
+			.await
+	}
+
+	/// Removes a monitor, deleting its file from the monitors directory
+	///
+	/// # Returns
+	/// * `Result<Monitor, RepositoryError>` - The removed monitor, or an error if it does not
+	///   exist or its file cannot be deleted, in which case it is kept
+	pub fn remove(&mut self, name: &str) -> Result<Monitor, RepositoryError> {
+		if !self.monitors.contains_key(name) {
+			return Err(Self::not_found(name));
+		}
+		if let Some(file) = self.monitor_file(name)? {
+			if file.exists() {
+				std::fs::remove_file(&file).map_err(|e| {
+					RepositoryError::internal_error(
+						format!("Failed to delete monitor '{}'", name),
+						Some(Box::new(e)),
+						Some(HashMap::from([(
+							"path".to_string(),
+							file.display().to_string(),
+						)])),
+					)
+				})?;
+			}
+		}
+		Ok(Arc::unwrap_or_clone(
+			self.monitors
+				.remove(name)
+				.expect("monitor presence was checked"),
+		))
+	}
+
+	/// Pauses or resumes a monitor, rewriting the `paused` field of its file
+	///
+	/// Only the `paused` field of the file is changed, the rest of the configuration is kept as
+	/// written. The references of paused monitors keep being validated when they are loaded.
+	///
+	/// # Arguments
+	/// * `name` - Key of the monitor
+	/// * `paused` - Whether the monitor is paused
+	///
+	/// # Errors
+	/// Returns an error if the monitor does not exist or its file cannot be rewritten, in which
+	/// case the monitor is left unchanged.
+	pub fn set_paused(&mut self, name: &str, paused: bool) -> Result<(), RepositoryError> {
+		if !self.monitors.contains_key(name) {
+			return Err(Self::not_found(name));
+		}
+		if let Some(file) = self.monitor_file(name)? {
+			if file.exists() {
+				let mut document: serde_json::Value = std::fs::read_to_string(&file)
+					.map_err(|e| Box::new(e) as Box<dyn std::error::Error + Send + Sync>)
+					.and_then(|content| serde_json::from_str(&content).map_err(Box::from))
+					.map_err(|e| {
+						RepositoryError::internal_error(
+							format!("Failed to read monitor '{}'", name),
+							Some(e),
+							Some(HashMap::from([(
+								"path".to_string(),
+								file.display().to_string(),
+							)])),
+						)
+					})?;
+				if let Some(fields) = document.as_object_mut() {
+					fields.insert("paused".to_string(), serde_json::Value::Bool(paused));
+				}
+				Self::write_monitor_file(&file, &document)?;
+			}
+		}
+		if let Some(monitor) = self.monitors.get_mut(name) {
+			Arc::make_mut(monitor).paused = paused;
+		}
+		Ok(())
+	}
+
+	/// Validates a monitor as if it was loaded, writes it and stores it under `name`
+	async fn save(
+		&mut self,
+		name: String,
+		monitor: Monitor,
+		network_service: Option<NetworkService<N>>,
+		trigger_service: Option<TriggerService<T>>,
+	) -> Result<(), RepositoryError> {
+		let metadata = HashMap::from([("monitor_name".to_string(), monitor.name.clone())]);
+		let others: Vec<&Monitor> = self
+			.monitors
+			.iter()
+			.filter(|(key, _)| **key != name)
+			.map(|(_, monitor)| monitor.as_ref())
+			.collect();
+		let file = self.monitor_file(&name)?;
+		let file_name = file
+			.as_ref()
+			.map_or_else(|| name.clone(), |file| file.display().to_string());
+		Monitor::validate_uniqueness(&others, &monitor, &file_name).map_err(|e| {
+			RepositoryError::validation_error(
+				e.to_string(),
+				Some(Box::new(e)),
+				Some(metadata.clone()),
+			)
+		})?;
+		let resolved = monitor.resolve_secrets().await.map_err(|e| {
+			RepositoryError::validation_error(
+				"Failed to resolve the secrets of the monitor",
+				Some(Box::new(e)),
+				Some(metadata.clone()),
+			)
+		})?;
+		resolved.validate().map_err(|e| {
+			RepositoryError::validation_error(
+				format!("Monitor validation failed: {}", e),
+				Some(Box::new(e)),
+				Some(metadata.clone()),
+			)
+		})?;
+
+		let (networks, triggers) = self.references(network_service, trigger_service).await?;
+		let monitor_dir = self.path.as_deref();
+		let monitors = HashMap::from([(name.clone(), resolved)]);
+		let monitors = Self::resolve_contract_references(monitors, monitor_dir).await?;
+		let monitors = Self::expand_presets(monitors)?;
+		let monitors =
+			Self::resolve_trigger_group_references(monitors, &triggers, monitor_dir).await?;
+		let mut monitors = Self::expand_network_groups(monitors, &networks);
+		Self::validate_monitor_references(&monitors, &triggers, &networks)?;
+		let prepared = monitors
+			.remove(&name)
+			.expect("prepared monitor is keyed by its name");
+
+		// The configuration is written as given, with its secret references and unexpanded
+		// groups, so that it loads back to the same monitor
+		if let Some(file) = &file {
+			Self::write_monitor_file(file, &monitor)?;
+		}
+		self.monitors.insert(name, Arc::new(prepared));
+		Ok(())
+	}
+
+	/// Returns the path of the file holding the monitor stored under `name`
+	///
+	/// # Errors
+	/// Returns an error if the name is not a plain file name, such as one containing a path
+	/// separator or `..`, so that no file outside the monitors directory is touched
+	fn monitor_file(&self, name: &str) -> Result<Option<PathBuf>, RepositoryError> {
+		let Some(dir) = &self.path else {
+			return Ok(None);
+		};
+		if !is_file_stem(name) {
+			return Err(RepositoryError::validation_error(
+				format!("Monitor name '{}' cannot be used as a file name", name),
+				None,
+				Some(HashMap::from([(
+					"monitor_name".to_string(),
+					name.to_string(),
+				)])),
+			));
+		}
+		Ok(Some(dir.join(format!("{}.json", name))))
+	}
+
+	/// Writes a monitor configuration, replacing the file atomically
+	fn write_monitor_file(
+		file: &Path,
+		monitor: &impl serde::Serialize,
+	) -> Result<(), RepositoryError> {
+		let metadata = HashMap::from([("path".to_string(), file.display().to_string())]);
+		let write_error = |e: Box<dyn std::error::Error + Send + Sync>| {
+			RepositoryError::internal_error(
+				"Failed to write monitor",
+				Some(e),
+				Some(metadata.clone()),
+			)
+		};
+		let content = serde_json::to_string_pretty(monitor).map_err(|e| write_error(e.into()))?;
+		let temp_file = file.with_extension("json.tmp");
+		std::fs::write(&temp_file, content).map_err(|e| write_error(e.into()))?;
+		std::fs::rename(&temp_file, file).map_err(|e| {
+			let _ = std::fs::remove_file(&temp_file);
+			write_error(e.into())
+		})
+	}
+
+	/// Returns the error for a monitor missing from the repository
+	fn not_found(name: &str) -> RepositoryError {
+		RepositoryError::validation_error(
+			format!("Monitor '{}' does not exist", name),
+			None,
+			Some(HashMap::from([(
+				"monitor_name".to_string(),
+				name.to_string(),
+			)])),
+		)
+	}
+
+	/// Returns an error if any monitor references a non-existent network or trigger.
+	pub fn validate_monitor_references(
+		monitors: &HashMap<String, Monitor>,
+		triggers: &HashMap<String, Trigger>,
+		networks: &HashMap<String, Network>,
+	) -> Result<(), RepositoryError> {
+		let mut validation_errors = Vec::new();
+		let mut metadata = HashMap::new();
+
+		for (monitor_name, monitor) in monitors {
+			// Validate trigger references
+			for reference in &monitor.triggers {
+				let trigger_id = reference.name();
+				let Some(trigger) = triggers.get(trigger_id) else {
+					validation_errors.push(format!(
+						"Monitor '{}' references non-existent trigger '{}'",
+						monitor_name, trigger_id
+					));
+					metadata.insert(
+						format!("monitor_{}_invalid_trigger", monitor_name),
+						trigger_id.to_string(),
+					);
+					continue;
+				};
+
+				// Validate message overrides as if they were part of the trigger
+				if let Some(message_override) = reference.message() {
+					if trigger.config.message().is_none() {
+						validation_errors.push(format!(
+							"Monitor '{}' overrides the message of trigger '{}' which has no \
+							 message",
+							monitor_name, trigger_id
+						));
+					} else if let Err(e) =
+						trigger.with_message_override(message_override).validate()
+					{
+						validation_errors.push(format!(
+							"Monitor '{}' has an invalid message override for trigger '{}': {}",
+							monitor_name, trigger_id, e
+						));
+					}
+				}
+
+				// Strict triggers only reference variables the monitor's matches can set
+				if TemplateVariablesMode::for_trigger(trigger) == TemplateVariablesMode::Strict {
+					for name in unknown_template_variables(monitor, reference, trigger) {
+						validation_errors.push(format!(
+							"Monitor '{}' references unknown template variable '${{{}}}' in \
+							 trigger '{}'",
+							monitor_name, name, trigger_id
+						));
+						metadata.insert(format!("monitor_{}_unknown_variable", monitor_name), name);
+					}
+				}
+			}
+
+			// Validate the triggers network view divergences are reported to
+			for trigger_id in monitor
+				.network_views
+				.iter()
+				.flat_map(|views| &views.divergence_triggers)
+			{
+				if !triggers.contains_key(trigger_id) {
+					validation_errors.push(format!(
+						"Monitor '{}' reports network view divergences to non-existent trigger \
+						 '{}'",
+						monitor_name, trigger_id
+					));
+					metadata.insert(
+						format!("monitor_{}_invalid_trigger", monitor_name),
+						trigger_id.to_string(),
+					);
+				}
+			}
 
-		for (monitor_name, monitor) in monitors {
-			// Validate trigger references
-			for trigger_id in &monitor.triggers {
+			// Validate the triggers decoding failures are reported to
+			for trigger_id in monitor
+				.alert_on_decode_failure
+				.iter()
+				.flat_map(|alert| &alert.triggers)
+			{
 				if !triggers.contains_key(trigger_id) {
 					validation_errors.push(format!(
-						"Monitor '{}' references non-existent trigger '{}'",
+						"Monitor '{}' reports decoding failures to non-existent trigger '{}'",
 						monitor_name, trigger_id
 					));
 					metadata.insert(
 						format!("monitor_{}_invalid_trigger", monitor_name),
-						trigger_id.clone(),
+						trigger_id.to_string(),
 					);
 				}
 			}
@@ -181,7 +953,8 @@ pub trait MonitorRepositoryTrait<
 	/// Load all monitor configurations from the given path
 	///
 	/// If no path is provided, uses the default config directory.
-	/// Also validates references to networks and triggers.
+	/// Also validates references to networks and triggers, and logs the networks and
+	/// triggers no monitor references as warnings.
 	/// This is a static method that doesn't require an instance.
 	async fn load_all(
 		path: Option<&Path>,
@@ -208,6 +981,42 @@ pub trait MonitorRepositoryTrait<
 	///
 	/// Returns a copy of the monitor map to prevent external mutation.
 	fn get_all(&self) -> HashMap<String, Monitor>;
+
+	/// Get the monitors that are not paused
+	///
+	/// Returns a copy of the active monitors, keyed like `get_all`.
+	fn get_active(&self) -> HashMap<String, Monitor> {
+		self.get_all()
+			.into_iter()
+			.filter(|(_, monitor)| !monitor.paused)
+			.collect()
+	}
+
+	/// Get the monitors carrying a tag
+	///
+	/// Returns a copy of the tagged monitors, keyed like `get_all`.
+	fn get_by_tag(&self, tag: &str) -> HashMap<String, Monitor> {
+		self.get_all()
+			.into_iter()
+			.filter(|(_, monitor)| monitor.tags.iter().any(|t| t == tag))
+			.collect()
+	}
+
+	/// Get a specific monitor by ID without copying it
+	///
+	/// Returns None if the monitor doesn't exist. Repositories storing their monitors in `Arc`s
+	/// hand out the stored one, the default implementation wraps a copy.
+	fn get_shared(&self, monitor_id: &str) -> Option<Arc<Monitor>> {
+		self.get(monitor_id).map(Arc::new)
+	}
+
+	/// Get all monitors without copying them
+	///
+	/// Repositories storing their monitors in `Arc`s hand out the stored ones, the default
+	/// implementation wraps copies.
+	fn get_all_shared(&self) -> HashMap<String, Arc<Monitor>> {
+		share_monitors(self.get_all())
+	}
 }
 
 #[async_trait]
@@ -229,56 +1038,20 @@ impl<
 		network_service: Option<NetworkService<N>>,
 		trigger_service: Option<TriggerService<T>>,
 	) -> Result<HashMap<String, Monitor>, RepositoryError> {
-		let monitors = Monitor::load_all(path).await.map_err(|e| {
-			RepositoryError::load_error(
-				"Failed to load monitors",
-				Some(Box::new(e)),
-				Some(HashMap::from([(
-					"path".to_string(),
-					path.map_or_else(|| "default".to_string(), |p| p.display().to_string()),
-				)])),
-			)
-		})?;
-
-		let networks = match network_service {
-			Some(service) => service.get_all(),
-			None => {
-				NetworkRepository::new(None)
-					.await
-					.map_err(|e| {
-						RepositoryError::load_error(
-							"Failed to load networks for monitor validation",
-							Some(Box::new(e)),
-							None,
-						)
-					})?
-					.networks
-			}
-		};
-
-		let triggers = match trigger_service {
-			Some(service) => service.get_all(),
-			None => {
-				TriggerRepository::new(None)
-					.await
-					.map_err(|e| {
-						RepositoryError::load_error(
-							"Failed to load triggers for monitor validation",
-							Some(Box::new(e)),
-							None,
-						)
-					})?
-					.triggers
-			}
-		};
-
-		Self::validate_monitor_references(&monitors, &triggers, &networks)?;
-		Ok(monitors)
+		Self::load_tagged(
+			path,
+			network_service,
+			trigger_service,
+			None,
+			ConsistencyMode::Lenient,
+		)
+		.await
 	}
 
 	/// Load a monitor from a specific path
 	///
-	/// Loads a monitor configuration from a specific path and validates all network and trigger references.
+	/// Loads a monitor configuration from a specific path and validates all network and trigger
+	/// references, against the services of the repository when none are passed.
 	async fn load_from_path(
 		&self,
 		path: Option<&Path>,
@@ -298,16 +1071,18 @@ impl<
 					)
 				})?;
 
-				let networks = match network_service {
-					Some(service) => service.get_all(),
-					None => NetworkRepository::new(None).await?.networks,
-				};
-
-				let triggers = match trigger_service {
-					Some(service) => service.get_all(),
-					None => TriggerRepository::new(None).await?.triggers,
-				};
-				let monitors = HashMap::from([(monitor.name.clone(), monitor)]);
+				let (networks, triggers) =
+					self.references(network_service, trigger_service).await?;
+				let monitors = Self::resolve_contract_references(
+					HashMap::from([(monitor.name.clone(), monitor)]),
+					path.parent(),
+				)
+				.await?;
+				let monitors = Self::expand_presets(monitors)?;
+				let monitors =
+					Self::resolve_trigger_group_references(monitors, &triggers, path.parent())
+						.await?;
+				let monitors = Self::expand_network_groups(monitors, &networks);
 				Self::validate_monitor_references(&monitors, &triggers, &networks)?;
 				match monitors.values().next() {
 					Some(monitor) => Ok(monitor.clone()),
@@ -323,14 +1098,35 @@ impl<
 	}
 
 	fn get(&self, monitor_id: &str) -> Option<Monitor> {
-		self.monitors.get(monitor_id).cloned()
+		self.monitors
+			.get(monitor_id)
+			.map(|monitor| Monitor::clone(monitor))
 	}
 
 	fn get_all(&self) -> HashMap<String, Monitor> {
+		self.monitors
+			.iter()
+			.map(|(name, monitor)| (name.clone(), Monitor::clone(monitor)))
+			.collect()
+	}
+
+	fn get_shared(&self, monitor_id: &str) -> Option<Arc<Monitor>> {
+		self.monitors.get(monitor_id).cloned()
+	}
+
+	fn get_all_shared(&self) -> HashMap<String, Arc<Monitor>> {
 		self.monitors.clone()
 	}
 }
 
+/// Wraps each monitor of a map in an `Arc`
+fn share_monitors(monitors: HashMap<String, Monitor>) -> HashMap<String, Arc<Monitor>> {
+	monitors
+		.into_iter()
+		.map(|(name, monitor)| (name, Arc::new(monitor)))
+		.collect()
+}
+
 /// Service layer for monitor repository operations
 ///
 /// This type provides a higher-level interface for working with monitor configurations,
@@ -409,6 +1205,35 @@ impl<
 		self.repository.get_all()
 	}
 
+	/// Get the monitors that are not paused
+	pub fn get_active(&self) -> HashMap<String, Monitor> {
+		self.repository.get_active()
+	}
+
+	/// Get the monitors carrying a tag
+	pub fn get_by_tag(&self, tag: &str) -> HashMap<String, Monitor> {
+		self.repository.get_by_tag(tag)
+	}
+
+	/// Get a specific monitor by ID, shared with the repository instead of copied
+	pub fn get_shared(&self, monitor_id: &str) -> Option<Arc<Monitor>> {
+		self.repository.get_shared(monitor_id)
+	}
+
+	/// Get all monitors, shared with the repository instead of copied
+	pub fn get_all_shared(&self) -> HashMap<String, Arc<Monitor>> {
+		self.repository.get_all_shared()
+	}
+
+	/// Get the monitors that are not paused, shared with the repository instead of copied
+	pub fn get_active_shared(&self) -> HashMap<String, Arc<Monitor>> {
+		self.repository
+			.get_all_shared()
+			.into_iter()
+			.filter(|(_, monitor)| !monitor.paused)
+			.collect()
+	}
+
 	/// Load a monitor from a specific path
 	///
 	/// Loads a monitor configuration from a specific path and validates all network and trigger references.
@@ -427,7 +1252,14 @@ impl<
 #[cfg(test)]
 mod tests {
 	use super::*;
-	use crate::{models::ScriptLanguage, utils::tests::builders::evm::monitor::MonitorBuilder};
+	use crate::{
+		models::{
+			MessageOverride, NetworkViewsConfig, ScriptLanguage, TriggerOverride, TriggerReference,
+		},
+		utils::tests::builders::{
+			evm::monitor::MonitorBuilder, network::NetworkBuilder, trigger::TriggerBuilder,
+		},
+	};
 	use std::fs;
 	use tempfile::TempDir;
 
@@ -570,6 +1402,82 @@ mod tests {
 		assert!(err.to_string().contains("references non-existent network"));
 	}
 
+	#[test]
+	fn test_expand_network_groups() {
+		let networks = [
+			("ethereum_mainnet_public", Some("ethereum_mainnet")),
+			("ethereum_mainnet_internal", Some("ethereum_mainnet")),
+			("base_mainnet", None),
+		]
+		.into_iter()
+		.map(|(slug, group)| {
+			let mut builder = NetworkBuilder::new().slug(slug);
+			if let Some(group) = group {
+				builder = builder.group(group);
+			}
+			(slug.to_string(), builder.build())
+		})
+		.collect::<HashMap<_, _>>();
+		let monitors = HashMap::from([(
+			"test_monitor".to_string(),
+			MonitorBuilder::new()
+				.name("test_monitor")
+				.networks(vec![
+					"ethereum_mainnet_public".to_string(),
+					"ethereum_mainnet".to_string(),
+					"base_mainnet".to_string(),
+				])
+				.build(),
+		)]);
+
+		let monitors =
+			MonitorRepository::<NetworkRepository, TriggerRepository>::expand_network_groups(
+				monitors, &networks,
+			);
+
+		assert_eq!(
+			monitors["test_monitor"].networks,
+			vec![
+				"ethereum_mainnet_public",
+				"ethereum_mainnet_internal",
+				"base_mainnet"
+			]
+		);
+		assert!(
+			MonitorRepository::<NetworkRepository, TriggerRepository>::validate_monitor_references(
+				&monitors,
+				&HashMap::new(),
+				&networks,
+			)
+			.is_ok()
+		);
+	}
+
+	#[test]
+	fn test_divergence_trigger_validation_error() {
+		let monitors = HashMap::from([(
+			"test_monitor".to_string(),
+			MonitorBuilder::new()
+				.name("test_monitor")
+				.networks(vec![])
+				.network_views(NetworkViewsConfig {
+					divergence_triggers: vec!["non_existent_trigger".to_string()],
+					..Default::default()
+				})
+				.build(),
+		)]);
+
+		let result =
+			MonitorRepository::<NetworkRepository, TriggerRepository>::validate_monitor_references(
+				&monitors,
+				&HashMap::new(),
+				&HashMap::new(),
+			);
+
+		let err = result.unwrap_err().to_string();
+		assert!(err.contains("reports network view divergences to non-existent trigger"));
+	}
+
 	#[test]
 	fn test_trigger_validation_error() {
 		// Create a monitor with a reference to a non-existent trigger
@@ -595,6 +1503,514 @@ mod tests {
 		assert!(err.to_string().contains("references non-existent trigger"));
 	}
 
+	#[test]
+	fn test_validate_monitor_references_message_override() {
+		let triggers = HashMap::from([(
+			"shared".to_string(),
+			TriggerBuilder::new().name("shared").build(),
+		)]);
+		let networks = HashMap::new();
+		let create_monitors = |body: &str| {
+			let monitor = MonitorBuilder::new()
+				.name("treasury_monitor")
+				.networks(vec![])
+				.trigger_reference(TriggerReference::WithOverrides(TriggerOverride {
+					name: "shared".to_string(),
+					message: Some(MessageOverride {
+						title: None,
+						body: Some(body.to_string()),
+					}),
+					variables: HashMap::new(),
+					rollup: false,
+				}))
+				.build();
+			HashMap::from([("treasury_monitor".to_string(), monitor)])
+		};
+
+		let result =
+			MonitorRepository::<NetworkRepository, TriggerRepository>::validate_monitor_references(
+				&create_monitors("Treasury moved ${value}"),
+				&triggers,
+				&networks,
+			);
+		assert!(result.is_ok());
+
+		let result =
+			MonitorRepository::<NetworkRepository, TriggerRepository>::validate_monitor_references(
+				&create_monitors("   "),
+				&triggers,
+				&networks,
+			);
+		let err = result.unwrap_err().to_string();
+		assert!(err.contains("Monitor 'treasury_monitor' has an invalid message override"));
+		assert!(err.contains("'shared'"));
+	}
+
+	#[test]
+	fn test_validate_monitor_references_strict_variables() {
+		let networks = HashMap::new();
+		let create_triggers = |body: &str| {
+			HashMap::from([(
+				"strict".to_string(),
+				TriggerBuilder::new()
+					.name("strict")
+					.message("Alert for ${monitor.name}", body)
+					.strict_variables(true)
+					.build(),
+			)])
+		};
+		let monitors = HashMap::from([(
+			"transfers".to_string(),
+			MonitorBuilder::new()
+				.name("transfers")
+				.networks(vec![])
+				.triggers(vec!["strict".to_string()])
+				.build(),
+		)]);
+
+		let result =
+			MonitorRepository::<NetworkRepository, TriggerRepository>::validate_monitor_references(
+				&monitors,
+				&create_triggers("Transfer of ${events.0.args.value} in ${transaction.hash}"),
+				&networks,
+			);
+		assert!(result.is_ok());
+
+		let result =
+			MonitorRepository::<NetworkRepository, TriggerRepository>::validate_monitor_references(
+				&monitors,
+				&create_triggers("Transfer in ${transacion.hash}"),
+				&networks,
+			);
+		let err = result.unwrap_err().to_string();
+		assert!(err.contains(
+			"Monitor 'transfers' references unknown template variable '${transacion.hash}' in \
+			 trigger 'strict'"
+		));
+	}
+
+	fn write_services() -> (
+		NetworkService<NetworkRepository>,
+		TriggerService<TriggerRepository>,
+	) {
+		let network = NetworkBuilder::new().slug("ethereum_mainnet").build();
+		let trigger = TriggerBuilder::new().name("notify").build();
+		(
+			NetworkService::new_with_repository(NetworkRepository {
+				networks: HashMap::from([(network.slug.clone(), network)]),
+			})
+			.unwrap(),
+			TriggerService::new_with_repository(TriggerRepository {
+				triggers: HashMap::from([(trigger.name.clone(), trigger)]),
+			})
+			.unwrap(),
+		)
+	}
+
+	fn written_monitor(name: &str) -> Monitor {
+		MonitorBuilder::new()
+			.name(name)
+			.networks(vec!["ethereum_mainnet".to_string()])
+			.triggers(vec!["notify".to_string()])
+			.build()
+	}
+
+	#[tokio::test]
+	async fn test_written_monitors_are_loaded_back() {
+		let temp_dir = TempDir::new().unwrap();
+		let monitor_dir = temp_dir.path().join("monitors");
+		fs::create_dir(&monitor_dir).unwrap();
+		let (networks, triggers) = write_services();
+		let mut repository = MonitorRepository::<NetworkRepository, TriggerRepository>::new(
+			Some(&monitor_dir),
+			Some(networks.clone()),
+			Some(triggers.clone()),
+		)
+		.await
+		.unwrap();
+
+		for name in ["kept", "updated", "removed"] {
+			repository
+				.add(
+					written_monitor(name),
+					Some(networks.clone()),
+					Some(triggers.clone()),
+				)
+				.await
+				.unwrap();
+		}
+		let mut updated = written_monitor("updated");
+		updated.paused = true;
+		repository
+			.update(
+				"updated",
+				updated,
+				Some(networks.clone()),
+				Some(triggers.clone()),
+			)
+			.await
+			.unwrap();
+		assert_eq!(repository.remove("removed").unwrap().name, "removed");
+		assert!(repository.remove("removed").is_err());
+
+		let reloaded = MonitorRepository::<NetworkRepository, TriggerRepository>::new(
+			Some(&monitor_dir),
+			Some(networks),
+			Some(triggers),
+		)
+		.await
+		.unwrap();
+		assert_eq!(reloaded.get_all(), repository.get_all());
+		assert!(reloaded.get("updated").unwrap().paused);
+		assert!(reloaded.get("removed").is_none());
+	}
+
+	#[tokio::test]
+	async fn test_load_from_path_validates_against_repository_services() {
+		let temp_dir = TempDir::new().unwrap();
+		let file = temp_dir.path().join("cached.json");
+		let monitor = MonitorBuilder::new()
+			.name("cached")
+			.networks(vec!["cached_network".to_string()])
+			.triggers(vec!["cached_trigger".to_string()])
+			.build();
+		fs::write(&file, serde_json::to_string(&monitor).unwrap()).unwrap();
+
+		// Networks and triggers missing from the default directories
+		let network = NetworkBuilder::new().slug("cached_network").build();
+		let trigger = TriggerBuilder::new().name("cached_trigger").build();
+		let networks = NetworkService::new_with_repository(NetworkRepository {
+			networks: HashMap::from([(network.slug.clone(), network)]),
+		})
+		.unwrap();
+		let triggers = TriggerService::new_with_repository(TriggerRepository {
+			triggers: HashMap::from([(trigger.name.clone(), trigger)]),
+		})
+		.unwrap();
+		let repository =
+			MonitorRepository::<NetworkRepository, TriggerRepository>::new_with_monitors(
+				HashMap::new(),
+			)
+			.with_services(networks.clone(), triggers);
+		for _ in 0..3 {
+			let loaded = repository
+				.load_from_path(Some(&file), None, None)
+				.await
+				.unwrap();
+			assert_eq!(loaded.name, "cached");
+		}
+
+		// Reloads of the services are seen by the repository
+		networks.reload(HashMap::new());
+		let err = repository
+			.load_from_path(Some(&file), None, None)
+			.await
+			.unwrap_err();
+		assert!(err.to_string().contains("cached_network"));
+	}
+
+	#[tokio::test]
+	async fn test_tag_filter_loads_matching_monitors() {
+		let temp_dir = TempDir::new().unwrap();
+		let monitor_dir = temp_dir.path().join("monitors");
+		fs::create_dir(&monitor_dir).unwrap();
+		for (name, tags, trigger) in [
+			("payments", vec!["payments"], "notify"),
+			("shared", vec!["payments", "security"], "notify"),
+			// Excluded monitors are not validated
+			("security", vec!["security"], "missing_trigger"),
+		] {
+			let monitor = MonitorBuilder::new()
+				.name(name)
+				.tags(tags)
+				.networks(vec!["ethereum_mainnet".to_string()])
+				.triggers(vec![trigger.to_string()])
+				.build();
+			fs::write(
+				monitor_dir.join(format!("{}.json", name)),
+				serde_json::to_string(&monitor).unwrap(),
+			)
+			.unwrap();
+		}
+		let (networks, triggers) = write_services();
+
+		let repository =
+			MonitorRepository::<NetworkRepository, TriggerRepository>::new_with_tag_filter(
+				Some(&monitor_dir),
+				Some(networks.clone()),
+				Some(triggers.clone()),
+				Some(vec!["payments".to_string()]),
+				ConsistencyMode::Lenient,
+			)
+			.await
+			.unwrap();
+		let mut names = repository.get_all().into_keys().collect::<Vec<_>>();
+		names.sort();
+		assert_eq!(names, vec!["payments", "shared"]);
+		assert_eq!(repository.get_by_tag("security").len(), 1);
+		assert!(repository.get_by_tag("unknown").is_empty());
+
+		// Without a filter, the invalid monitor fails the load
+		assert!(
+			MonitorRepository::<NetworkRepository, TriggerRepository>::new_with_tag_filter(
+				Some(&monitor_dir),
+				Some(networks),
+				Some(triggers),
+				None,
+				ConsistencyMode::Lenient,
+			)
+			.await
+			.is_err()
+		);
+	}
+
+	#[tokio::test]
+	async fn test_strict_consistency_rejects_unused_triggers() {
+		let temp_dir = TempDir::new().unwrap();
+		let monitor_dir = temp_dir.path().join("monitors");
+		fs::create_dir(&monitor_dir).unwrap();
+		fs::write(
+			monitor_dir.join("watched.json"),
+			serde_json::to_string(&written_monitor("watched")).unwrap(),
+		)
+		.unwrap();
+		let (networks, _) = write_services();
+		let triggers = TriggerService::new_with_repository(TriggerRepository {
+			triggers: HashMap::from([
+				(
+					"notify".to_string(),
+					TriggerBuilder::new().name("notify").build(),
+				),
+				(
+					"unused".to_string(),
+					TriggerBuilder::new().name("unused").build(),
+				),
+			]),
+		})
+		.unwrap();
+
+		let load = |consistency| {
+			MonitorRepository::<NetworkRepository, TriggerRepository>::new_with_tag_filter(
+				Some(&monitor_dir),
+				Some(networks.clone()),
+				Some(triggers.clone()),
+				None,
+				consistency,
+			)
+		};
+		assert!(load(ConsistencyMode::Lenient).await.is_ok());
+		let error = load(ConsistencyMode::Strict).await.err().unwrap();
+		assert!(error
+			.to_string()
+			.contains("Trigger 'unused' is not used by any monitor"));
+	}
+
+	#[tokio::test]
+	async fn test_paused_monitors_are_kept_but_inactive() {
+		let temp_dir = TempDir::new().unwrap();
+		let monitor_dir = temp_dir.path().join("monitors");
+		fs::create_dir(&monitor_dir).unwrap();
+		let (networks, triggers) = write_services();
+		let mut repository = MonitorRepository::<NetworkRepository, TriggerRepository>::new(
+			Some(&monitor_dir),
+			Some(networks.clone()),
+			Some(triggers.clone()),
+		)
+		.await
+		.unwrap();
+		for name in ["noisy", "quiet"] {
+			repository
+				.add(
+					written_monitor(name),
+					Some(networks.clone()),
+					Some(triggers.clone()),
+				)
+				.await
+				.unwrap();
+		}
+
+		repository.set_paused("noisy", true).unwrap();
+		assert!(repository.set_paused("missing", true).is_err());
+		let reloaded = MonitorRepository::<NetworkRepository, TriggerRepository>::new(
+			Some(&monitor_dir),
+			Some(networks),
+			Some(triggers),
+		)
+		.await
+		.unwrap();
+		let service = MonitorService::new_with_repository(reloaded).unwrap();
+		assert_eq!(service.get_all().len(), 2);
+		assert!(service.get("noisy").unwrap().paused);
+		assert_eq!(
+			service.get_active().into_keys().collect::<Vec<_>>(),
+			vec!["quiet".to_string()]
+		);
+
+		repository.set_paused("noisy", false).unwrap();
+		assert_eq!(repository.get_active().len(), 2);
+	}
+
+	#[tokio::test]
+	async fn test_add_rejects_invalid_references() {
+		let (networks, triggers) = write_services();
+		let mut repository =
+			MonitorRepository::<NetworkRepository, TriggerRepository>::new_with_monitors(
+				HashMap::new(),
+			);
+
+		let mut monitor = written_monitor("invalid");
+		monitor.networks = vec!["missing_network".to_string()];
+		monitor.triggers = vec![TriggerReference::Name("missing_trigger".to_string())];
+		let err = repository
+			.add(monitor, Some(networks.clone()), Some(triggers.clone()))
+			.await
+			.unwrap_err();
+		assert!(err
+			.to_string()
+			.contains("references non-existent network 'missing_network'"));
+		assert!(err
+			.to_string()
+			.contains("references non-existent trigger 'missing_trigger'"));
+		assert!(repository.get_all().is_empty());
+
+		repository
+			.add(
+				written_monitor("valid"),
+				Some(networks.clone()),
+				Some(triggers.clone()),
+			)
+			.await
+			.unwrap();
+		assert!(repository
+			.add(written_monitor("valid"), Some(networks), Some(triggers))
+			.await
+			.unwrap_err()
+			.to_string()
+			.contains("already exists"));
+	}
+
+	#[tokio::test]
+	async fn test_failed_write_keeps_monitors() {
+		let temp_dir = TempDir::new().unwrap();
+		let monitor_dir = temp_dir.path().join("monitors");
+		fs::create_dir(&monitor_dir).unwrap();
+		let (networks, triggers) = write_services();
+		let mut repository = MonitorRepository::<NetworkRepository, TriggerRepository>::new(
+			Some(&monitor_dir),
+			Some(networks.clone()),
+			Some(triggers.clone()),
+		)
+		.await
+		.unwrap();
+		repository
+			.add(
+				written_monitor("existing"),
+				Some(networks.clone()),
+				Some(triggers.clone()),
+			)
+			.await
+			.unwrap();
+
+		fs::remove_dir_all(&monitor_dir).unwrap();
+		let mut updated = written_monitor("existing");
+		updated.paused = true;
+		assert!(repository
+			.update(
+				"existing",
+				updated,
+				Some(networks.clone()),
+				Some(triggers.clone())
+			)
+			.await
+			.is_err());
+		assert!(!repository.get("existing").unwrap().paused);
+		assert!(repository
+			.add(written_monitor("new"), Some(networks), Some(triggers))
+			.await
+			.is_err());
+		assert!(repository.get("new").is_none());
+	}
+
+	#[tokio::test]
+	async fn test_names_outside_monitors_directory_are_rejected() {
+		let temp_dir = TempDir::new().unwrap();
+		let monitor_dir = temp_dir.path().join("monitors");
+		fs::create_dir(&monitor_dir).unwrap();
+		let (networks, triggers) = write_services();
+		let mut repository = MonitorRepository::<NetworkRepository, TriggerRepository>::new(
+			Some(&monitor_dir),
+			Some(networks.clone()),
+			Some(triggers.clone()),
+		)
+		.await
+		.unwrap();
+
+		for name in ["../escaped", "nested/monitor", "..", ""] {
+			let err = repository
+				.add(
+					written_monitor(name),
+					Some(networks.clone()),
+					Some(triggers.clone()),
+				)
+				.await
+				.unwrap_err();
+			assert!(err.to_string().contains("cannot be used as a file name"));
+			assert!(repository.get(name).is_none());
+		}
+		assert!(!temp_dir.path().join("escaped.json").exists());
+		assert!(!monitor_dir.join("nested").exists());
+		assert_eq!(fs::read_dir(&monitor_dir).unwrap().count(), 0);
+	}
+
+	#[tokio::test]
+	async fn test_concurrent_add_and_get() {
+		let (networks, triggers) = write_services();
+		let repository = std::sync::Arc::new(tokio::sync::RwLock::new(MonitorRepository::<
+			NetworkRepository,
+			TriggerRepository,
+		>::new_with_monitors(
+			HashMap::new()
+		)));
+
+		let tasks: Vec<_> = (0..8)
+			.map(|i| {
+				let repository = repository.clone();
+				let networks = networks.clone();
+				let triggers = triggers.clone();
+				tokio::spawn(async move {
+					let name = format!("monitor_{}", i);
+					repository
+						.write()
+						.await
+						.add(written_monitor(&name), Some(networks), Some(triggers))
+						.await
+						.unwrap();
+					repository.read().await.get(&name).unwrap().name
+				})
+			})
+			.collect();
+		for (i, task) in tasks.into_iter().enumerate() {
+			assert_eq!(task.await.unwrap(), format!("monitor_{}", i));
+		}
+		assert_eq!(repository.read().await.get_all().len(), 8);
+	}
+
+	#[test]
+	fn test_get_shared_does_not_copy_monitors() {
+		let repository =
+			MonitorRepository::<NetworkRepository, TriggerRepository>::new_with_monitors(
+				HashMap::from([("shared".to_string(), written_monitor("shared"))]),
+			);
+		let service = MonitorService::new_with_repository(repository).unwrap();
+
+		let first = service.get_shared("shared").unwrap();
+		let second = service.get_shared("shared").unwrap();
+		assert!(Arc::ptr_eq(&first, &second));
+		assert!(Arc::ptr_eq(&service.get_all_shared()["shared"], &first));
+		assert_eq!(*first, service.get("shared").unwrap());
+		assert!(service.get_shared("missing").is_none());
+	}
+
 	#[tokio::test]
 	async fn test_load_from_path_error_handling() {
 		// Create a temporary directory for testing
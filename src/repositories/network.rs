@@ -6,9 +6,17 @@
 
 #![allow(clippy::result_large_err)]
 
-use std::{collections::HashMap, path::Path};
+use std::{
+	collections::HashMap,
+	path::Path,
+	sync::{
+		atomic::{AtomicU64, Ordering},
+		Arc, RwLock,
+	},
+};
 
 use async_trait::async_trait;
+use tokio::sync::watch;
 
 use crate::{
 	models::{ConfigLoader, Network},
@@ -59,6 +67,13 @@ pub trait NetworkRepositoryTrait: Clone {
 	///
 	/// Returns a copy of the network map to prevent external mutation.
 	fn get_all(&self) -> HashMap<String, Network>;
+
+	/// Receiver of the version of the networks, for repositories written to after creation
+	///
+	/// Returns None for repositories whose networks never change, the default.
+	fn watch_versions(&self) -> Option<watch::Receiver<u64>> {
+		None
+	}
 }
 
 #[async_trait]
@@ -89,14 +104,27 @@ impl NetworkRepositoryTrait for NetworkRepository {
 	}
 }
 
+/// Shared map of network names to their configurations
+type NetworkMap = Arc<HashMap<String, Arc<Network>>>;
+
 /// Service layer for network repository operations
 ///
 /// This type provides a higher-level interface for working with network configurations,
 /// handling repository initialization and access through a trait-based interface.
-
+///
+/// The networks are loaded from the repository once and handed out as `Arc`s, so lookups do not
+/// clone the configurations. Clones of the service share the same networks, and a reload swaps
+/// them for all clones at once while lookups made before keep the previous configurations.
+///
+/// For repositories publishing versions, such as the in-memory ones, the networks are read
+/// again from the repository by the first lookup after a write, so no manual reload is needed.
 #[derive(Clone)]
 pub struct NetworkService<T: NetworkRepositoryTrait> {
-	repository: T,
+	networks: Arc<RwLock<NetworkMap>>,
+	/// Repository the networks are read from again when its version changes
+	live: Option<(T, watch::Receiver<u64>)>,
+	/// Version of the repository the networks were last read at
+	version: Arc<AtomicU64>,
 }
 
 impl<T: NetworkRepositoryTrait> NetworkService<T> {
@@ -105,12 +133,20 @@ impl<T: NetworkRepositoryTrait> NetworkService<T> {
 		path: Option<&Path>,
 	) -> Result<NetworkService<NetworkRepository>, RepositoryError> {
 		let repository = NetworkRepository::new(path).await?;
-		Ok(NetworkService { repository })
+		NetworkService::new_with_repository(repository)
 	}
 
 	/// Create a new network service with a custom repository implementation
 	pub fn new_with_repository(repository: T) -> Result<Self, RepositoryError> {
-		Ok(NetworkService { repository })
+		let live = repository
+			.watch_versions()
+			.map(|versions| (repository.clone(), versions));
+		let version = live.as_ref().map_or(0, |(_, versions)| *versions.borrow());
+		Ok(NetworkService {
+			networks: Arc::new(RwLock::new(Self::arc_map(repository.get_all()))),
+			live,
+			version: Arc::new(AtomicU64::new(version)),
+		})
 	}
 
 	/// Create a new network service with a specific configuration path
@@ -118,23 +154,75 @@ impl<T: NetworkRepositoryTrait> NetworkService<T> {
 		path: Option<&Path>,
 	) -> Result<NetworkService<NetworkRepository>, RepositoryError> {
 		let repository = NetworkRepository::new(path).await?;
-		Ok(NetworkService { repository })
+		NetworkService::new_with_repository(repository)
 	}
 
 	/// Get a specific network by ID
-	pub fn get(&self, network_id: &str) -> Option<Network> {
-		self.repository.get(network_id)
+	pub fn get(&self, network_id: &str) -> Option<Arc<Network>> {
+		self.refresh();
+		self.networks
+			.read()
+			.unwrap_or_else(|e| e.into_inner())
+			.get(network_id)
+			.cloned()
 	}
 
 	/// Get all networks
 	pub fn get_all(&self) -> HashMap<String, Network> {
-		self.repository.get_all()
+		self.current()
+			.iter()
+			.map(|(name, network)| (name.clone(), Network::clone(network)))
+			.collect()
+	}
+
+	/// Replace the networks of the service and of all its clones
+	///
+	/// `Arc`s obtained before the reload keep pointing to the previous configurations. For
+	/// repositories publishing versions, the next write to the repository replaces them again.
+	pub fn reload(&self, networks: HashMap<String, Network>) {
+		let networks = Self::arc_map(networks);
+		*self.networks.write().unwrap_or_else(|e| e.into_inner()) = networks;
+	}
+
+	/// Reads the networks again if the repository published a new version since the last read
+	fn refresh(&self) {
+		let Some((repository, versions)) = &self.live else {
+			return;
+		};
+		let version = *versions.borrow();
+		if self.version.load(Ordering::Acquire) == version {
+			return;
+		}
+		let mut networks = self.networks.write().unwrap_or_else(|e| e.into_inner());
+		if self.version.load(Ordering::Acquire) != version {
+			*networks = Self::arc_map(repository.get_all());
+			self.version.store(version, Ordering::Release);
+		}
+	}
+
+	/// Snapshot of the current networks
+	fn current(&self) -> NetworkMap {
+		self.refresh();
+		self.networks
+			.read()
+			.unwrap_or_else(|e| e.into_inner())
+			.clone()
+	}
+
+	fn arc_map(networks: HashMap<String, Network>) -> NetworkMap {
+		Arc::new(
+			networks
+				.into_iter()
+				.map(|(name, network)| (name, Arc::new(network)))
+				.collect(),
+		)
 	}
 }
 
 #[cfg(test)]
 mod tests {
 	use super::*;
+	use crate::utils::tests::builders::network::NetworkBuilder;
 
 	#[tokio::test]
 	async fn test_load_error_messages() {
@@ -151,4 +239,30 @@ mod tests {
 			_ => panic!("Expected RepositoryError::LoadError"),
 		}
 	}
+
+	#[test]
+	fn test_reload_keeps_in_flight_networks() {
+		let previous = NetworkBuilder::new()
+			.slug("ethereum_mainnet")
+			.rpc_url("https://eth.example.com")
+			.build();
+		let updated = NetworkBuilder::new()
+			.slug("ethereum_mainnet")
+			.rpc_url("https://eth-fallback.example.com")
+			.build();
+		let service = NetworkService::new_with_repository(NetworkRepository {
+			networks: HashMap::from([("ethereum_mainnet".to_string(), previous.clone())]),
+		})
+		.unwrap();
+		let block_service = service.clone();
+
+		let in_flight = block_service.get("ethereum_mainnet").unwrap();
+		service.reload(HashMap::from([(
+			"ethereum_mainnet".to_string(),
+			updated.clone(),
+		)]));
+
+		assert_eq!(*in_flight, previous);
+		assert_eq!(*block_service.get("ethereum_mainnet").unwrap(), updated);
+	}
 }
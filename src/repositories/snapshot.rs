@@ -0,0 +1,181 @@
+//! Point-in-time exports of the running configuration.
+//!
+//! An export holds the monitors, networks and triggers of the services as they were validated
+//! and are running, serialized with their secrets, header values and URL credentials redacted,
+//! along with the consistency report of the configuration. Exports are meant to be stored for audits, and two exports can
+//! be compared to list the entries added, removed or changed in between. Changes limited to
+//! redacted secrets do not show up in the comparison.
+
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+
+use crate::{
+	models::{redact_secrets, ConfigDiff, ConfigSnapshot, EntryDiff},
+	repositories::{
+		consistency::{validate_configuration_consistency, ConfigValidationReport},
+		monitor::{MonitorRepositoryTrait, MonitorService},
+		network::{NetworkRepositoryTrait, NetworkService},
+		trigger::{TriggerRepositoryTrait, TriggerService},
+	},
+};
+
+/// Configuration of a running instance, with its secrets redacted
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ConfigExport {
+	/// Time the export was taken, in milliseconds since the Unix epoch
+	pub generated_at_ms: i64,
+	/// Monitors keyed by name
+	pub monitors: HashMap<String, Value>,
+	/// Networks keyed by slug
+	pub networks: HashMap<String, Value>,
+	/// Triggers keyed by name
+	pub triggers: HashMap<String, Value>,
+	/// Consistency of the references between the monitors, networks and triggers
+	pub validation: ConfigValidationReport,
+}
+
+impl ConfigExport {
+	/// Exports a configuration, redacting its secrets
+	pub fn from_snapshot(snapshot: &ConfigSnapshot) -> Self {
+		ConfigExport {
+			generated_at_ms: Utc::now().timestamp_millis(),
+			monitors: redact_entries(&snapshot.monitors),
+			networks: redact_entries(&snapshot.networks),
+			triggers: redact_entries(&snapshot.triggers),
+			validation: validate_configuration_consistency(
+				&snapshot.monitors,
+				&snapshot.triggers,
+				&snapshot.networks,
+			),
+		}
+	}
+
+	/// Renders the export as indented JSON, with its entries sorted by name
+	pub fn to_json_pretty(&self) -> serde_json::Result<String> {
+		serde_json::to_string_pretty(&serde_json::to_value(self)?)
+	}
+
+	/// Lists the entries added, removed and changed in another export
+	///
+	/// # Arguments
+	/// * `other` - Later export compared to this one
+	pub fn diff(&self, other: &ConfigExport) -> ConfigDiff {
+		ConfigDiff {
+			monitors: EntryDiff::between(&self.monitors, &other.monitors),
+			networks: EntryDiff::between(&self.networks, &other.networks),
+			triggers: EntryDiff::between(&self.triggers, &other.triggers),
+		}
+	}
+}
+
+/// Serializes each entry with its secrets redacted
+fn redact_entries<C: Serialize>(entries: &HashMap<String, C>) -> HashMap<String, Value> {
+	entries
+		.iter()
+		.map(|(name, entry)| {
+			let value = serde_json::to_value(entry).unwrap_or(Value::Null);
+			(name.clone(), redact_secrets(&value))
+		})
+		.collect()
+}
+
+/// Exports the configuration held by the services
+///
+/// # Arguments
+/// * `monitor_service` - Monitors that are running
+/// * `network_service` - Networks the monitors run on
+/// * `trigger_service` - Triggers the monitors notify
+///
+/// # Returns
+/// * `ConfigExport` - The configuration with its secrets redacted
+pub fn export_snapshot<M, N, T>(
+	monitor_service: &MonitorService<M, N, T>,
+	network_service: &NetworkService<N>,
+	trigger_service: &TriggerService<T>,
+) -> ConfigExport
+where
+	M: MonitorRepositoryTrait<N, T> + Send,
+	N: NetworkRepositoryTrait + Send + Sync + 'static,
+	T: TriggerRepositoryTrait + Send + Sync + 'static,
+{
+	ConfigExport::from_snapshot(&ConfigSnapshot {
+		monitors: monitor_service.get_all(),
+		networks: network_service.get_all(),
+		triggers: trigger_service.get_all(),
+	})
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{
+		repositories::{MonitorRepository, NetworkRepository, TriggerRepository},
+		utils::tests::builders::{
+			evm::monitor::MonitorBuilder, network::NetworkBuilder, trigger::TriggerBuilder,
+		},
+	};
+
+	fn export(monitors: Vec<crate::models::Monitor>) -> ConfigExport {
+		let network = NetworkBuilder::new().slug("ethereum_mainnet").build();
+		let trigger = TriggerBuilder::new()
+			.name("slack")
+			.slack("https://hooks.slack.com/services/SECRET")
+			.build();
+		let monitor_service =
+			MonitorService::new_with_repository(MonitorRepository::new_with_monitors(
+				monitors
+					.into_iter()
+					.map(|monitor| (monitor.name.clone(), monitor))
+					.collect(),
+			))
+			.unwrap();
+		export_snapshot(
+			&monitor_service,
+			&NetworkService::new_with_repository(NetworkRepository {
+				networks: HashMap::from([(network.slug.clone(), network)]),
+			})
+			.unwrap(),
+			&TriggerService::new_with_repository(TriggerRepository {
+				triggers: HashMap::from([(trigger.name.clone(), trigger)]),
+			})
+			.unwrap(),
+		)
+	}
+
+	fn monitor(name: &str) -> crate::models::Monitor {
+		MonitorBuilder::new()
+			.name(name)
+			.networks(vec!["ethereum_mainnet".to_string()])
+			.triggers(vec!["slack".to_string()])
+			.build()
+	}
+
+	#[test]
+	fn test_export_redacts_secrets() {
+		let export = export(vec![monitor("watched")]);
+		let json = export.to_json_pretty().unwrap();
+		assert!(!json.contains("SECRET"));
+		assert!(json.contains("<redacted>"));
+		assert!(export.validation.is_empty());
+
+		let reloaded: ConfigExport = serde_json::from_str(&json).unwrap();
+		assert_eq!(reloaded, export);
+	}
+
+	#[test]
+	fn test_diff_reports_changed_monitor() {
+		let before = export(vec![monitor("watched"), monitor("unchanged")]);
+		let mut paused = monitor("watched");
+		paused.paused = true;
+		let after = export(vec![paused, monitor("unchanged")]);
+
+		let diff = before.diff(&after);
+		assert!(diff.networks.is_empty() && diff.triggers.is_empty());
+		assert!(diff.monitors.added.is_empty() && diff.monitors.removed.is_empty());
+		assert_eq!(diff.monitors.modified.len(), 1);
+		assert_eq!(diff.monitors.modified[0].name, "watched");
+		assert_eq!(diff.monitors.modified[0].changes[0].path, "paused");
+	}
+}
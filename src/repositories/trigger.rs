@@ -6,9 +6,17 @@
 
 #![allow(clippy::result_large_err)]
 
-use std::{collections::HashMap, path::Path};
+use std::{
+	collections::HashMap,
+	path::Path,
+	sync::{
+		atomic::{AtomicU64, Ordering},
+		Arc, RwLock,
+	},
+};
 
 use async_trait::async_trait;
+use tokio::sync::watch;
 
 use crate::{
 	models::{ConfigLoader, Trigger},
@@ -59,6 +67,13 @@ pub trait TriggerRepositoryTrait: Clone {
 	///
 	/// Returns a copy of the trigger map to prevent external mutation.
 	fn get_all(&self) -> HashMap<String, Trigger>;
+
+	/// Receiver of the version of the triggers, for repositories written to after creation
+	///
+	/// Returns None for repositories whose triggers never change, the default.
+	fn watch_versions(&self) -> Option<watch::Receiver<u64>> {
+		None
+	}
 }
 
 #[async_trait]
@@ -89,13 +104,27 @@ impl TriggerRepositoryTrait for TriggerRepository {
 	}
 }
 
+/// Shared map of trigger names to their configurations
+type TriggerMap = Arc<HashMap<String, Arc<Trigger>>>;
+
 /// Service layer for trigger repository operations
 ///
 /// This type provides a higher-level interface for working with trigger configurations,
 /// handling repository initialization and access through a trait-based interface.
+///
+/// The triggers are loaded from the repository once and handed out as `Arc`s, so lookups do not
+/// clone the configurations. Clones of the service share the same triggers, and a reload swaps
+/// them for all clones at once while lookups made before keep the previous configurations.
+///
+/// For repositories publishing versions, such as the in-memory ones, the triggers are read
+/// again from the repository by the first lookup after a write, so no manual reload is needed.
 #[derive(Clone)]
 pub struct TriggerService<T: TriggerRepositoryTrait> {
-	repository: T,
+	triggers: Arc<RwLock<TriggerMap>>,
+	/// Repository the triggers are read from again when its version changes
+	live: Option<(T, watch::Receiver<u64>)>,
+	/// Version of the repository the triggers were last read at
+	version: Arc<AtomicU64>,
 }
 
 impl<T: TriggerRepositoryTrait> TriggerService<T> {
@@ -104,12 +133,20 @@ impl<T: TriggerRepositoryTrait> TriggerService<T> {
 		path: Option<&Path>,
 	) -> Result<TriggerService<TriggerRepository>, RepositoryError> {
 		let repository = TriggerRepository::new(path).await?;
-		Ok(TriggerService { repository })
+		TriggerService::new_with_repository(repository)
 	}
 
 	/// Create a new trigger service with a custom repository implementation
 	pub fn new_with_repository(repository: T) -> Result<Self, RepositoryError> {
-		Ok(TriggerService { repository })
+		let live = repository
+			.watch_versions()
+			.map(|versions| (repository.clone(), versions));
+		let version = live.as_ref().map_or(0, |(_, versions)| *versions.borrow());
+		Ok(TriggerService {
+			triggers: Arc::new(RwLock::new(Self::arc_map(repository.get_all()))),
+			live,
+			version: Arc::new(AtomicU64::new(version)),
+		})
 	}
 
 	/// Create a new trigger service with a specific configuration path
@@ -117,24 +154,77 @@ impl<T: TriggerRepositoryTrait> TriggerService<T> {
 		path: Option<&Path>,
 	) -> Result<TriggerService<TriggerRepository>, RepositoryError> {
 		let repository = TriggerRepository::new(path).await?;
-		Ok(TriggerService { repository })
+		TriggerService::new_with_repository(repository)
 	}
 
 	/// Get a specific trigger by ID
-	pub fn get(&self, trigger_id: &str) -> Option<Trigger> {
-		self.repository.get(trigger_id)
+	pub fn get(&self, trigger_id: &str) -> Option<Arc<Trigger>> {
+		self.refresh();
+		self.triggers
+			.read()
+			.unwrap_or_else(|e| e.into_inner())
+			.get(trigger_id)
+			.cloned()
 	}
 
 	/// Get all triggers
 	pub fn get_all(&self) -> HashMap<String, Trigger> {
-		self.repository.get_all()
+		self.current()
+			.iter()
+			.map(|(name, trigger)| (name.clone(), Trigger::clone(trigger)))
+			.collect()
+	}
+
+	/// Replace the triggers of the service and of all its clones
+	///
+	/// `Arc`s obtained before the reload keep pointing to the previous configurations. For
+	/// repositories publishing versions, the next write to the repository replaces them again.
+	pub fn reload(&self, triggers: HashMap<String, Trigger>) {
+		let triggers = Self::arc_map(triggers);
+		*self.triggers.write().unwrap_or_else(|e| e.into_inner()) = triggers;
+	}
+
+	/// Reads the triggers again if the repository published a new version since the last read
+	fn refresh(&self) {
+		let Some((repository, versions)) = &self.live else {
+			return;
+		};
+		let version = *versions.borrow();
+		if self.version.load(Ordering::Acquire) == version {
+			return;
+		}
+		let mut triggers = self.triggers.write().unwrap_or_else(|e| e.into_inner());
+		if self.version.load(Ordering::Acquire) != version {
+			*triggers = Self::arc_map(repository.get_all());
+			self.version.store(version, Ordering::Release);
+		}
+	}
+
+	/// Snapshot of the current triggers
+	fn current(&self) -> TriggerMap {
+		self.refresh();
+		self.triggers
+			.read()
+			.unwrap_or_else(|e| e.into_inner())
+			.clone()
+	}
+
+	fn arc_map(triggers: HashMap<String, Trigger>) -> TriggerMap {
+		Arc::new(
+			triggers
+				.into_iter()
+				.map(|(name, trigger)| (name, Arc::new(trigger)))
+				.collect(),
+		)
 	}
 }
 
 #[cfg(test)]
 mod tests {
 	use super::*;
-	use crate::repositories::error::RepositoryError;
+	use crate::{
+		repositories::error::RepositoryError, utils::tests::builders::trigger::TriggerBuilder,
+	};
 	use std::path::PathBuf;
 
 	#[tokio::test]
@@ -151,4 +241,85 @@ mod tests {
 			_ => panic!("Expected RepositoryError::LoadError"),
 		}
 	}
+
+	fn slack_trigger(name: &str, webhook_url: &str) -> Trigger {
+		TriggerBuilder::new().name(name).slack(webhook_url).build()
+	}
+
+	fn create_service(triggers: Vec<Trigger>) -> TriggerService<TriggerRepository> {
+		TriggerService::new_with_repository(TriggerRepository {
+			triggers: triggers
+				.into_iter()
+				.map(|trigger| (trigger.name.clone(), trigger))
+				.collect(),
+		})
+		.unwrap()
+	}
+
+	#[test]
+	fn test_reload_keeps_in_flight_triggers() {
+		let previous = slack_trigger("alerts", "https://hooks.slack.com/services/A/B/C");
+		let updated = slack_trigger("alerts", "https://hooks.slack.com/services/D/E/F");
+		let service = create_service(vec![previous.clone()]);
+		let dispatch_service = service.clone();
+
+		// A dispatch resolved the trigger before the reload
+		let in_flight = dispatch_service.get("alerts").unwrap();
+		service.reload(HashMap::from([("alerts".to_string(), updated.clone())]));
+
+		assert_eq!(*in_flight, previous);
+		assert_eq!(*dispatch_service.get("alerts").unwrap(), updated);
+		assert_eq!(
+			service.get_all(),
+			HashMap::from([("alerts".to_string(), updated)])
+		);
+	}
+
+	#[test]
+	fn test_get_shares_the_loaded_trigger() {
+		let service = create_service(vec![slack_trigger(
+			"alerts",
+			"https://hooks.slack.com/services/A/B/C",
+		)]);
+
+		assert!(Arc::ptr_eq(
+			&service.get("alerts").unwrap(),
+			&service.clone().get("alerts").unwrap()
+		));
+		assert!(service.get("missing").is_none());
+	}
+
+	#[test]
+	fn test_reload_swaps_all_triggers_at_once() {
+		let before =
+			["a", "b"].map(|name| slack_trigger(name, "https://hooks.slack.com/services/A/B/C"));
+		let after =
+			["c", "d"].map(|name| slack_trigger(name, "https://hooks.slack.com/services/D/E/F"));
+		let service = create_service(before.to_vec());
+
+		let readers = (0..4)
+			.map(|_| {
+				let service = service.clone();
+				std::thread::spawn(move || {
+					for _ in 0..1000 {
+						let mut names = service.get_all().into_keys().collect::<Vec<_>>();
+						names.sort();
+						assert!(names == ["a", "b"] || names == ["c", "d"], "{:?}", names);
+					}
+				})
+			})
+			.collect::<Vec<_>>();
+		for i in 0..100 {
+			let triggers = if i % 2 == 0 { &after } else { &before };
+			service.reload(
+				triggers
+					.iter()
+					.map(|trigger| (trigger.name.clone(), trigger.clone()))
+					.collect(),
+			);
+		}
+		for reader in readers {
+			reader.join().unwrap();
+		}
+	}
 }
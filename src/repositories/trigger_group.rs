@@ -0,0 +1,94 @@
+//! Trigger group repository implementation.
+//!
+//! This module provides storage and retrieval of named lists of triggers referenced by monitors.
+//! The repository loads trigger group configurations from JSON files, and monitors reference
+//! them by name from their triggers.
+
+#![allow(clippy::result_large_err)]
+
+use std::{collections::HashMap, path::Path};
+
+use async_trait::async_trait;
+
+use crate::{
+	models::{ConfigLoader, TriggerGroup},
+	repositories::error::RepositoryError,
+};
+
+/// Repository for storing and retrieving trigger group configurations
+#[derive(Clone, Default)]
+pub struct TriggerGroupRepository {
+	/// Map of group names to their configurations
+	pub trigger_groups: HashMap<String, TriggerGroup>,
+}
+
+impl TriggerGroupRepository {
+	/// Create a new trigger group repository from the given path
+	///
+	/// Loads all trigger group configurations from JSON files in the specified directory
+	/// (or default config directory if None is provided).
+	pub async fn new(path: Option<&Path>) -> Result<Self, RepositoryError> {
+		let trigger_groups = Self::load_all(path).await?;
+		Ok(TriggerGroupRepository { trigger_groups })
+	}
+}
+
+/// Interface for trigger group repository implementations
+///
+/// This trait defines the standard operations that any trigger group repository must support,
+/// allowing for different storage backends while maintaining a consistent interface.
+#[async_trait]
+pub trait TriggerGroupRepositoryTrait: Clone {
+	/// Create a new repository instance
+	async fn new(path: Option<&Path>) -> Result<Self, RepositoryError>
+	where
+		Self: Sized;
+
+	/// Load all trigger group configurations from the given path
+	///
+	/// If no path is provided, uses the default config directory.
+	/// This is a static method that doesn't require an instance.
+	async fn load_all(
+		path: Option<&Path>,
+	) -> Result<HashMap<String, TriggerGroup>, RepositoryError>;
+
+	/// Get a specific trigger group by name
+	///
+	/// Returns None if the trigger group doesn't exist.
+	fn get(&self, group_name: &str) -> Option<TriggerGroup>;
+
+	/// Get all trigger_groups
+	///
+	/// Returns a copy of the trigger group map to prevent external mutation.
+	fn get_all(&self) -> HashMap<String, TriggerGroup>;
+}
+
+#[async_trait]
+impl TriggerGroupRepositoryTrait for TriggerGroupRepository {
+	async fn new(path: Option<&Path>) -> Result<Self, RepositoryError> {
+		TriggerGroupRepository::new(path).await
+	}
+
+	async fn load_all(
+		path: Option<&Path>,
+	) -> Result<HashMap<String, TriggerGroup>, RepositoryError> {
+		TriggerGroup::load_all(path).await.map_err(|e| {
+			RepositoryError::load_error(
+				"Failed to load trigger groups",
+				Some(Box::new(e)),
+				Some(HashMap::from([(
+					"path".to_string(),
+					path.map_or_else(|| "default".to_string(), |p| p.display().to_string()),
+				)])),
+			)
+		})
+	}
+
+	fn get(&self, group_name: &str) -> Option<TriggerGroup> {
+		self.trigger_groups.get(group_name).cloned()
+	}
+
+	fn get_all(&self) -> HashMap<String, TriggerGroup> {
+		self.trigger_groups.clone()
+	}
+}
@@ -7,7 +7,7 @@ use async_trait::async_trait;
 
 use crate::{
 	models::{BlockType, ContractSpec},
-	services::filter::BlockFilter,
+	services::filter::{BlockFilter, FilterService},
 };
 
 /// Defines the core interface for blockchain clients
@@ -58,5 +58,5 @@ pub trait BlockChainClient: Send + Sync + Clone {
 /// a way to create block filters.
 pub trait BlockFilterFactory<T> {
 	type Filter: BlockFilter<Client = T> + Send;
-	fn filter() -> Self::Filter;
+	fn filter(service: &FilterService) -> Self::Filter;
 }
@@ -2,25 +2,32 @@
 //!
 //! This module provides functionality to interact with Ethereum and other EVM-compatible
 //! blockchains, supporting operations like block retrieval, transaction receipt lookup,
-//! and log filtering.
+//! and log filtering. Log queries rejected by a provider for covering too many blocks are split,
+//! see [`super::log_ranges`].
 
-use std::marker::PhantomData;
+use std::{marker::PhantomData, path::Path, sync::Arc};
 
 use anyhow::Context;
 use async_trait::async_trait;
-use futures;
+use futures::{self, future::BoxFuture};
 use serde_json::json;
-use tracing::instrument;
+use tracing::{debug, instrument, warn};
 
+use super::log_ranges::{
+	dedup_logs, is_log_range_limit_error, is_log_range_limit_message, json_rpc_error_message,
+	LogRangeLimits,
+};
 use crate::{
-	models::{BlockType, EVMBlock, EVMReceiptLog, EVMTransactionReceipt, Network},
+	models::{BlockType, EVMReceiptLog, EVMTransactionReceipt, Network},
 	services::{
 		blockchain::{
 			client::BlockChainClient,
+			decoding::{decode_evm_block, decode_items, DecodeMode},
+			fixtures::{FixtureRecorder, FIXTURE_RECORD_DIR_ENV},
 			transports::{BlockchainTransport, EVMTransportClient},
 			BlockFilterFactory,
 		},
-		filter::{evm_helpers::string_to_h256, EVMBlockFilter},
+		filter::{evm_helpers::string_to_h256, EVMBlockFilter, FilterService},
 	},
 };
 
@@ -31,12 +38,189 @@ use crate::{
 pub struct EvmClient<T: Send + Sync + Clone> {
 	/// The underlying HTTP transport client for RPC communication
 	http_client: T,
+	/// Recorder the fetched data is written to, if fixtures are recorded
+	recorder: Option<Arc<FixtureRecorder>>,
+	/// Slug of the network, reported in decoding diagnostics
+	network_slug: String,
+	/// How blocks and logs that fail to decode are handled
+	decode_mode: DecodeMode,
+	/// Block ranges of log queries the endpoints are known to answer
+	log_ranges: Arc<LogRangeLimits>,
+}
+
+/// Response of the endpoint to a log query
+enum LogsResponse {
+	/// Logs of the range
+	Logs(Vec<EVMReceiptLog>),
+	/// The range is above the limits of the endpoint, with the error it returned
+	RangeLimit(String),
 }
 
 impl<T: Send + Sync + Clone> EvmClient<T> {
 	/// Creates a new EVM client instance with a specific transport client
 	pub fn new_with_transport(http_client: T) -> Self {
-		Self { http_client }
+		Self {
+			http_client,
+			recorder: None,
+			network_slug: String::new(),
+			decode_mode: DecodeMode::default(),
+			log_ranges: Arc::new(LogRangeLimits::default()),
+		}
+	}
+
+	/// Sets the network reported in decoding diagnostics and how items failing to decode are
+	/// handled
+	pub fn with_decoding(mut self, network_slug: &str, decode_mode: DecodeMode) -> Self {
+		self.network_slug = network_slug.to_string();
+		self.decode_mode = decode_mode;
+		self
+	}
+
+	/// Records the blocks, receipts and logs fetched by the client as fixtures
+	pub fn with_recorder(mut self, recorder: Arc<FixtureRecorder>) -> Self {
+		self.recorder = Some(recorder);
+		self
+	}
+
+	/// Passes the recorder, if any, to `record`, logging recording failures
+	///
+	/// Recording never fails the fetch it records.
+	async fn record<F, Fut>(&self, record: F)
+	where
+		F: FnOnce(Arc<FixtureRecorder>) -> Fut,
+		Fut: std::future::Future<Output = Result<(), anyhow::Error>>,
+	{
+		let Some(recorder) = self.recorder.clone() else {
+			return;
+		};
+		let dir = recorder.dir().to_path_buf();
+		if let Err(e) = record(recorder).await {
+			warn!("Failed to record fixture in {}: {:#}", dir.display(), e);
+		}
+	}
+}
+
+impl<T: Send + Sync + Clone + BlockchainTransport> EvmClient<T> {
+	/// Fetches the logs of a block range, bisecting it while the endpoint rejects it as above
+	/// its limits
+	///
+	/// # Arguments
+	/// * `from_block` - Starting block number
+	/// * `to_block` - Ending block number
+	/// * `addresses` - Optional list of addresses to filter logs by
+	///
+	/// # Returns
+	/// * `Result<Vec<EVMReceiptLog>, anyhow::Error>` - Logs of the range, or the first error
+	///   that is not a limit error, or the limit error of a single block
+	fn get_logs_splitting<'a>(
+		&'a self,
+		from_block: u64,
+		to_block: u64,
+		addresses: &'a Option<Vec<String>>,
+	) -> BoxFuture<'a, Result<Vec<EVMReceiptLog>, anyhow::Error>> {
+		Box::pin(async move {
+			let blocks = to_block.saturating_sub(from_block) + 1;
+			match self.request_logs(from_block, to_block, addresses).await? {
+				LogsResponse::Logs(logs) => {
+					if !self.log_ranges.is_empty() {
+						let url = self.http_client.get_current_url().await;
+						self.log_ranges.record_answered(&url, blocks);
+					}
+					Ok(logs)
+				}
+				LogsResponse::RangeLimit(message) if blocks > 1 => {
+					let url = self.http_client.get_current_url().await;
+					self.log_ranges.record_rejected(&url, blocks);
+					debug!(
+						"Splitting logs query for blocks {} - {} rejected by {}: {}",
+						from_block, to_block, url, message
+					);
+					let middle = from_block + (to_block - from_block) / 2;
+					let mut logs = self
+						.get_logs_splitting(from_block, middle, addresses)
+						.await?;
+					logs.extend(
+						self.get_logs_splitting(middle + 1, to_block, addresses)
+							.await?,
+					);
+					Ok(logs)
+				}
+				LogsResponse::RangeLimit(message) => Err(anyhow::anyhow!(
+					"Failed to get logs for block {}: {}",
+					from_block,
+					message
+				)),
+			}
+		})
+	}
+
+	/// Sends a single log query for a block range
+	async fn request_logs(
+		&self,
+		from_block: u64,
+		to_block: u64,
+		addresses: &Option<Vec<String>>,
+	) -> Result<LogsResponse, anyhow::Error> {
+		// Convert parameters to JSON-RPC format
+		let params = json!([{
+			"fromBlock": format!("0x{:x}", from_block),
+			"toBlock": format!("0x{:x}", to_block),
+			"address": addresses
+		}])
+		.as_array()
+		.with_context(|| "Failed to create JSON-RPC params array")?
+		.to_vec();
+
+		let response = match self
+			.http_client
+			.send_raw_request("eth_getLogs", Some(params))
+			.await
+		{
+			Ok(response) => response,
+			Err(e) if is_log_range_limit_error(&e) => {
+				return Ok(LogsResponse::RangeLimit(e.to_string()));
+			}
+			Err(e) => {
+				return Err(anyhow::Error::new(e).context(format!(
+					"Failed to get logs for blocks: {} - {}",
+					from_block, to_block
+				)));
+			}
+		};
+
+		// Providers report the queries above their limits as JSON-RPC errors
+		if let Some(message) = json_rpc_error_message(&response) {
+			if is_log_range_limit_message(&message) {
+				return Ok(LogsResponse::RangeLimit(message));
+			}
+			return Err(anyhow::anyhow!(
+				"Failed to get logs for blocks: {} - {}: {}",
+				from_block,
+				to_block,
+				message
+			));
+		}
+
+		// Extract the "result" field from the JSON-RPC response
+		let logs_data = response
+			.get("result")
+			.with_context(|| "Missing 'result' field")?;
+
+		// Parse the response into the expected type, skipping the logs that fail to decode
+		let raw_logs: Vec<serde_json::Value> =
+			serde_json::from_value(logs_data.clone()).with_context(|| "Failed to parse logs")?;
+		let (logs, diagnostics) = decode_items::<EVMReceiptLog>(
+			raw_logs,
+			&self.network_slug,
+			None,
+			"log",
+			self.decode_mode,
+		)
+		.with_context(|| "Failed to parse logs")?;
+		diagnostics
+			.iter()
+			.for_each(|diagnostic| diagnostic.report());
+		Ok(LogsResponse::Logs(logs))
 	}
 }
 
@@ -48,17 +232,33 @@ impl EvmClient<EVMTransportClient> {
 	///
 	/// # Returns
 	/// * `Result<Self, anyhow::Error>` - New client instance or connection error
+	///
+	/// When `FIXTURE_RECORD_DIR` is set, the data fetched by the client is recorded as fixtures
+	/// under that directory. Chain data is decoded strictly when `STRICT_CHAIN_DATA=true`.
 	pub async fn new(network: &Network) -> Result<Self, anyhow::Error> {
-		let client = EVMTransportClient::new(network).await?;
-		Ok(Self::new_with_transport(client))
+		let client = Self::new_with_transport(EVMTransportClient::new(network).await?)
+			.with_decoding(&network.slug, DecodeMode::from_env());
+		match std::env::var(FIXTURE_RECORD_DIR_ENV) {
+			Ok(dir) if !dir.is_empty() => {
+				let recorder = FixtureRecorder::new(Path::new(&dir), network)?;
+				Ok(client.with_recorder(Arc::new(recorder)))
+			}
+			_ => Ok(client),
+		}
 	}
 }
 
-impl<T: Send + Sync + Clone + BlockchainTransport> BlockFilterFactory<Self> for EvmClient<T> {
+impl<T: Send + Sync + Clone + BlockchainTransport + 'static> BlockFilterFactory<Self>
+	for EvmClient<T>
+{
 	type Filter = EVMBlockFilter<Self>;
-	fn filter() -> Self::Filter {
+	fn filter(service: &FilterService) -> Self::Filter {
 		EVMBlockFilter {
 			_client: PhantomData,
+			price_oracles: Some(service.price_oracles().clone()),
+			prices: None,
+			decode_failures: Default::default(),
+			decode_failure_tracker: Some(service.decode_failures().clone()),
 		}
 	}
 }
@@ -92,6 +292,16 @@ pub trait EvmClientTrait {
 		to_block: u64,
 		addresses: Option<Vec<String>>,
 	) -> Result<Vec<EVMReceiptLog>, anyhow::Error>;
+
+	/// Calls a contract at the latest block without sending a transaction
+	///
+	/// # Arguments
+	/// * `to` - Address of the contract to call
+	/// * `data` - ABI encoded call data
+	///
+	/// # Returns
+	/// * `Result<Vec<u8>, anyhow::Error>` - ABI encoded return data or error
+	async fn call_contract(&self, to: String, data: Vec<u8>) -> Result<Vec<u8>, anyhow::Error>;
 }
 
 #[async_trait]
@@ -129,12 +339,19 @@ impl<T: Send + Sync + Clone + BlockchainTransport> EvmClientTrait for EvmClient<
 			return Err(anyhow::anyhow!("Transaction receipt not found"));
 		}
 
-		Ok(serde_json::from_value(receipt_data.clone())
-			.with_context(|| "Failed to parse transaction receipt")?)
+		let receipt: EVMTransactionReceipt = serde_json::from_value(receipt_data.clone())
+			.with_context(|| "Failed to parse transaction receipt")?;
+		let recorded = &receipt;
+		self.record(|recorder| async move { recorder.record_receipt(recorded).await })
+			.await;
+		Ok(receipt)
 	}
 
 	/// Retrieves logs within the specified block range
 	///
+	/// Ranges above the limits of the endpoint are split, and deduplicated by block hash and
+	/// log index.
+	///
 	/// # Arguments
 	/// * `from_block` - Starting block number
 	/// * `to_block` - Ending block number
@@ -148,34 +365,57 @@ impl<T: Send + Sync + Clone + BlockchainTransport> EvmClientTrait for EvmClient<
 		to_block: u64,
 		addresses: Option<Vec<String>>,
 	) -> Result<Vec<EVMReceiptLog>, anyhow::Error> {
-		// Convert parameters to JSON-RPC format
-		let params = json!([{
-			"fromBlock": format!("0x{:x}", from_block),
-			"toBlock": format!("0x{:x}", to_block),
-			"address": addresses
-		}])
-		.as_array()
-		.with_context(|| "Failed to create JSON-RPC params array")?
-		.to_vec();
+		// Endpoints that rejected a range before are queried in chunks they answered
+		let chunk = if self.log_ranges.is_empty() {
+			None
+		} else {
+			self.log_ranges
+				.limit(&self.http_client.get_current_url().await)
+		};
+
+		let mut logs = Vec::new();
+		let mut start = from_block;
+		loop {
+			let end = chunk.map_or(to_block, |chunk| {
+				to_block.min(start.saturating_add(chunk - 1))
+			});
+			logs.extend(self.get_logs_splitting(start, end, &addresses).await?);
+			if end >= to_block {
+				break;
+			}
+			start = end + 1;
+		}
+		let logs = dedup_logs(logs);
+
+		let recorded = &logs;
+		self.record(|recorder| async move {
+			recorder.record_logs(from_block, to_block, recorded).await
+		})
+		.await;
+		Ok(logs)
+	}
+
+	/// Calls a contract through `eth_call` at the latest block
+	#[instrument(skip(self, data), fields(to))]
+	async fn call_contract(&self, to: String, data: Vec<u8>) -> Result<Vec<u8>, anyhow::Error> {
+		let params = vec![
+			json!({ "to": to, "data": format!("0x{}", hex::encode(&data)) }),
+			json!("latest"),
+		];
 
 		let response = self
 			.http_client
-			.send_raw_request("eth_getLogs", Some(params))
+			.send_raw_request("eth_call", Some(params))
 			.await
-			.with_context(|| {
-				format!(
-					"Failed to get logs for blocks: {} - {}",
-					from_block, to_block
-				)
-			})?;
+			.with_context(|| format!("Failed to call contract: {}", to))?;
 
 		// Extract the "result" field from the JSON-RPC response
-		let logs_data = response
+		let result = response
 			.get("result")
+			.and_then(|result| result.as_str())
 			.with_context(|| "Missing 'result' field")?;
 
-		// Parse the response into the expected type
-		Ok(serde_json::from_value(logs_data.clone()).with_context(|| "Failed to parse logs")?)
+		hex::decode(result.trim_start_matches("0x")).with_context(|| "Failed to parse call result")
 	}
 }
 
@@ -218,6 +458,8 @@ impl<T: Send + Sync + Clone + BlockchainTransport> BlockChainClient for EvmClien
 					true // include full transaction objects
 				]);
 				let client = self.http_client.clone();
+				let network_slug = &self.network_slug;
+				let decode_mode = self.decode_mode;
 
 				async move {
 					let response = client
@@ -233,17 +475,32 @@ impl<T: Send + Sync + Clone + BlockchainTransport> BlockChainClient for EvmClien
 						return Err(anyhow::anyhow!("Block not found"));
 					}
 
-					let block: EVMBlock = serde_json::from_value(block_data.clone())
-						.map_err(|e| anyhow::anyhow!("Failed to parse block: {}", e))?;
+					// Transactions failing to decode are skipped, unless decoding is strict
+					let (block, diagnostics) =
+						decode_evm_block(block_data.clone(), network_slug, decode_mode)?;
+					diagnostics
+						.iter()
+						.for_each(|diagnostic| diagnostic.report());
 
-					Ok(BlockType::EVM(Box::new(block)))
+					Ok(BlockType::EVM(Arc::new(block)))
 				}
 			})
 			.collect();
 
-		futures::future::join_all(block_futures)
+		let blocks = futures::future::join_all(block_futures)
 			.await
 			.into_iter()
-			.collect::<Result<Vec<_>, _>>()
+			.collect::<Result<Vec<_>, _>>()?;
+		let recorded = &blocks;
+		self.record(|recorder| async move {
+			for block in recorded {
+				if let BlockType::EVM(block) = block {
+					recorder.record_block(block).await?;
+				}
+			}
+			Ok(())
+		})
+		.await;
+		Ok(blocks)
 	}
 }
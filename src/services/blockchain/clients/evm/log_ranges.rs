@@ -0,0 +1,237 @@
+//! Adaptive splitting of `eth_getLogs` block ranges.
+//!
+//! Providers cap the block range or the number of results of `eth_getLogs` queries, and report
+//! it with heterogeneous errors, such as "query returned more than 10000 results", "block range
+//! too large" or a 413 status. A query failing with such an error is bisected, and each half
+//! retried recursively down to a single block. The largest range an endpoint answered below
+//! the smallest range it rejected is cached, and later queries to that endpoint are split into
+//! chunks of that size up front.
+//!
+//! Logs are deduplicated by block hash and log index, as providers may return a log at the
+//! boundary of two sub-ranges twice.
+
+use std::{
+	collections::{HashMap, HashSet},
+	sync::Mutex,
+};
+
+use serde_json::Value;
+
+use crate::{models::EVMReceiptLog, services::blockchain::TransportError};
+
+/// Lowercase fragments of the errors providers return for queries above their limits
+const LOG_RANGE_LIMIT_ERRORS: &[&str] = &[
+	"query returned more than",
+	"range too large",
+	"range is too large",
+	"maximum block range",
+	"max block range",
+	"block range limit",
+	"too many results",
+	"too many logs",
+	"response size exceeded",
+	"response size should not",
+	"query timeout exceeded",
+];
+
+/// Returns whether an error message reports a query above the limits of the provider
+pub fn is_log_range_limit_message(message: &str) -> bool {
+	let message = message.to_lowercase();
+	LOG_RANGE_LIMIT_ERRORS
+		.iter()
+		.any(|fragment| message.contains(fragment))
+}
+
+/// Returns whether a transport error reports a query above the limits of the provider
+pub fn is_log_range_limit_error(error: &TransportError) -> bool {
+	match error {
+		TransportError::Http {
+			status_code, body, ..
+		} => {
+			*status_code == reqwest::StatusCode::PAYLOAD_TOO_LARGE
+				|| is_log_range_limit_message(body)
+		}
+		_ => false,
+	}
+}
+
+/// Returns the message of the JSON-RPC error of a response, if it has one
+pub fn json_rpc_error_message(response: &Value) -> Option<String> {
+	let error = response.get("error")?;
+	Some(
+		error
+			.get("message")
+			.and_then(Value::as_str)
+			.map(str::to_string)
+			.unwrap_or_else(|| error.to_string()),
+	)
+}
+
+/// Ranges answered and rejected by an endpoint
+#[derive(Debug, Clone, Copy)]
+struct EndpointRanges {
+	/// Largest range answered below `min_rejected`
+	max_working: u64,
+	/// Smallest range rejected with a limit error
+	min_rejected: u64,
+}
+
+/// Block ranges of `eth_getLogs` queries that work on each endpoint
+#[derive(Debug, Default)]
+pub struct LogRangeLimits {
+	endpoints: Mutex<HashMap<String, EndpointRanges>>,
+}
+
+impl LogRangeLimits {
+	/// Returns whether no endpoint rejected a query yet
+	pub fn is_empty(&self) -> bool {
+		self.lock().is_empty()
+	}
+
+	/// Returns the largest range known to work on an endpoint that rejected a larger one
+	pub fn limit(&self, url: &str) -> Option<u64> {
+		let ranges = *self.lock().get(url)?;
+		// Without an answered range yet, half of the rejected one is the best guess
+		Some(if ranges.max_working > 0 {
+			ranges.max_working
+		} else {
+			(ranges.min_rejected / 2).max(1)
+		})
+	}
+
+	/// Records that an endpoint answered a query over `blocks` blocks
+	pub fn record_answered(&self, url: &str, blocks: u64) {
+		if let Some(ranges) = self.lock().get_mut(url) {
+			if blocks < ranges.min_rejected {
+				ranges.max_working = ranges.max_working.max(blocks);
+			}
+		}
+	}
+
+	/// Records that an endpoint rejected a query over `blocks` blocks with a limit error
+	pub fn record_rejected(&self, url: &str, blocks: u64) {
+		let mut endpoints = self.lock();
+		let ranges = endpoints.entry(url.to_string()).or_insert(EndpointRanges {
+			max_working: 0,
+			min_rejected: blocks,
+		});
+		ranges.min_rejected = ranges.min_rejected.min(blocks);
+		// The limit of the endpoint was lowered, the answered ranges are found again
+		if ranges.max_working >= ranges.min_rejected {
+			ranges.max_working = 0;
+		}
+	}
+
+	fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<String, EndpointRanges>> {
+		self.endpoints.lock().unwrap_or_else(|e| e.into_inner())
+	}
+}
+
+/// Removes the logs already seen with the same block hash and log index, keeping their order
+pub fn dedup_logs(logs: Vec<EVMReceiptLog>) -> Vec<EVMReceiptLog> {
+	let mut seen = HashSet::new();
+	logs.into_iter()
+		.filter(|log| match (log.block_hash, log.log_index) {
+			(Some(block_hash), Some(log_index)) => seen.insert((block_hash, log_index)),
+			_ => true,
+		})
+		.collect()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use alloy::primitives::{Address, Bytes, B256, U256};
+	use serde_json::json;
+
+	fn log(block: u8, index: u64) -> EVMReceiptLog {
+		EVMReceiptLog {
+			address: Address::ZERO,
+			topics: vec![],
+			data: Bytes::new(),
+			block_hash: Some(B256::with_last_byte(block)),
+			block_number: None,
+			transaction_hash: None,
+			transaction_index: None,
+			log_index: Some(U256::from(index)),
+			transaction_log_index: None,
+			log_type: None,
+			removed: None,
+		}
+	}
+
+	#[test]
+	fn test_is_log_range_limit_message() {
+		assert!(is_log_range_limit_message(
+			"query returned more than 10000 results"
+		));
+		assert!(is_log_range_limit_message("Block range too large"));
+		assert!(is_log_range_limit_message(
+			"eth_getLogs is limited to a 10,000 range: exceed maximum block range"
+		));
+		assert!(!is_log_range_limit_message(
+			"invalid argument 0: hex string"
+		));
+		assert!(!is_log_range_limit_message("rate limit exceeded"));
+	}
+
+	#[test]
+	fn test_is_log_range_limit_error() {
+		let error = |status_code, body: &str| {
+			TransportError::http(
+				status_code,
+				"http://localhost".to_string(),
+				body.to_string(),
+				None,
+				None,
+			)
+		};
+		assert!(is_log_range_limit_error(&error(
+			reqwest::StatusCode::PAYLOAD_TOO_LARGE,
+			""
+		)));
+		assert!(is_log_range_limit_error(&error(
+			reqwest::StatusCode::BAD_REQUEST,
+			"block range is too large"
+		)));
+		assert!(!is_log_range_limit_error(&error(
+			reqwest::StatusCode::BAD_GATEWAY,
+			"upstream unavailable"
+		)));
+	}
+
+	#[test]
+	fn test_json_rpc_error_message() {
+		let response = json!({"error": {"code": -32005, "message": "too many logs"}});
+		assert_eq!(
+			json_rpc_error_message(&response).as_deref(),
+			Some("too many logs")
+		);
+		assert!(json_rpc_error_message(&json!({"result": []})).is_none());
+	}
+
+	#[test]
+	fn test_log_range_limits() {
+		let limits = LogRangeLimits::default();
+		assert!(limits.is_empty());
+		limits.record_answered("a", 100);
+		assert!(limits.limit("a").is_none());
+
+		limits.record_rejected("a", 100);
+		assert_eq!(limits.limit("a"), Some(50));
+		limits.record_answered("a", 13);
+		limits.record_answered("a", 7);
+		assert_eq!(limits.limit("a"), Some(13));
+		limits.record_rejected("a", 12);
+		assert_eq!(limits.limit("a"), Some(6));
+		limits.record_answered("a", 7);
+		assert_eq!(limits.limit("a"), Some(7));
+		assert!(limits.limit("b").is_none());
+	}
+
+	#[test]
+	fn test_dedup_logs() {
+		let logs = dedup_logs(vec![log(1, 0), log(1, 1), log(1, 0), log(2, 0)]);
+		assert_eq!(logs, vec![log(1, 0), log(1, 1), log(2, 0)]);
+	}
+}
@@ -19,7 +19,7 @@ use crate::{
 			client::BlockChainClient, transports::BlockchainTransport, BlockFilterFactory,
 			MidnightWsTransportClient,
 		},
-		filter::MidnightBlockFilter,
+		filter::{FilterService, MidnightBlockFilter},
 	},
 };
 
@@ -84,7 +84,7 @@ impl MidnightClient<MidnightWsTransportClient, OnlineClient<subxt::SubstrateConf
 #[async_trait]
 impl<W: Send + Sync + Clone + BlockchainTransport> BlockFilterFactory<Self> for MidnightClient<W> {
 	type Filter = MidnightBlockFilter<Self>;
-	fn filter() -> Self::Filter {
+	fn filter(_service: &FilterService) -> Self::Filter {
 		MidnightBlockFilter {
 			_client: PhantomData,
 		}
@@ -7,6 +7,7 @@
 
 mod evm {
 	pub mod client;
+	pub mod log_ranges;
 }
 mod stellar {
 	pub mod client;
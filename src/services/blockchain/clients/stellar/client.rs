@@ -19,6 +19,7 @@ use crate::{
 	services::{
 		blockchain::{
 			client::{BlockChainClient, BlockFilterFactory},
+			decoding::{decode_items, DecodeMode},
 			transports::StellarTransportClient,
 			BlockchainTransport,
 		},
@@ -27,7 +28,7 @@ use crate::{
 				get_contract_code_ledger_key, get_contract_instance_ledger_key, get_contract_spec,
 				get_wasm_code_from_ledger_entry_data, get_wasm_hash_from_ledger_entry_data,
 			},
-			StellarBlockFilter,
+			FilterService, StellarBlockFilter,
 		},
 	},
 };
@@ -53,12 +54,56 @@ const RETENTION_MESSAGES: [&str; 2] = [
 pub struct StellarClient<T: Send + Sync + Clone> {
 	/// The underlying Stellar transport client for RPC communication
 	http_client: T,
+	/// Slug of the network, reported in decoding diagnostics
+	network_slug: String,
+	/// How transactions and events that fail to decode are handled
+	decode_mode: DecodeMode,
 }
 
 impl<T: Send + Sync + Clone> StellarClient<T> {
 	/// Creates a new Stellar client instance with a specific transport client
 	pub fn new_with_transport(http_client: T) -> Self {
-		Self { http_client }
+		Self {
+			http_client,
+			network_slug: String::new(),
+			decode_mode: DecodeMode::default(),
+		}
+	}
+
+	/// Sets the network reported in decoding diagnostics and how items failing to decode are
+	/// handled
+	pub fn with_decoding(mut self, network_slug: &str, decode_mode: DecodeMode) -> Self {
+		self.network_slug = network_slug.to_string();
+		self.decode_mode = decode_mode;
+		self
+	}
+
+	/// Decodes the items of a response one by one, reporting the ones skipped
+	fn decode_response_items<I: serde::de::DeserializeOwned>(
+		&self,
+		raw_items: &serde_json::Value,
+		item: &'static str,
+		method_name: &'static str,
+	) -> Result<Vec<I>, anyhow::Error> {
+		let parse_error = |e: String| {
+			let message = format!(
+				"Failed to parse {}s from response for method '{}': {}",
+				item, method_name, e
+			);
+			anyhow::anyhow!(StellarClientError::response_parse_error(
+				message, None, None
+			))
+			.context(format!("Failed to parse {} response", item))
+		};
+		let raw_items: Vec<serde_json::Value> =
+			serde_json::from_value(raw_items.clone()).map_err(|e| parse_error(e.to_string()))?;
+		let (items, diagnostics) =
+			decode_items(raw_items, &self.network_slug, None, item, self.decode_mode)
+				.map_err(|e| parse_error(e.to_string()))?;
+		diagnostics
+			.iter()
+			.for_each(|diagnostic| diagnostic.report());
+		Ok(items)
 	}
 
 	/// Checks a JSON-RPC response for error information and converts it into a `StellarClientError` if present.
@@ -135,9 +180,12 @@ impl StellarClient<StellarTransportClient> {
 	///
 	/// # Returns
 	/// * `Result<Self, anyhow::Error>` - New client instance or connection error
+	///
+	/// Chain data is decoded strictly when `STRICT_CHAIN_DATA=true`.
 	pub async fn new(network: &Network) -> Result<Self, anyhow::Error> {
 		let http_client = StellarTransportClient::new(network).await?;
-		Ok(Self::new_with_transport(http_client))
+		Ok(Self::new_with_transport(http_client)
+			.with_decoding(&network.slug, DecodeMode::from_env()))
 	}
 }
 
@@ -262,20 +310,12 @@ impl<T: Send + Sync + Clone + BlockchainTransport> StellarClientTrait for Stella
 								.context("Failed to parse transaction response")
 						})?;
 
-					let ledger_transactions: Vec<StellarTransactionInfo> =
-						serde_json::from_value(raw_transactions.clone()).map_err(|e| {
-							let message = format!(
-								"Failed to parse transactions from response for method '{}': {}",
-								RPC_METHOD_GET_TRANSACTIONS, e
-							);
-							let sce_parse_error = StellarClientError::response_parse_error(
-								message,
-								Some(e.into()),
-								None,
-							);
-							anyhow::anyhow!(sce_parse_error)
-								.context("Failed to parse transaction response")
-						})?;
+					let ledger_transactions: Vec<StellarTransactionInfo> = self
+						.decode_response_items(
+							raw_transactions,
+							"transaction",
+							RPC_METHOD_GET_TRANSACTIONS,
+						)?;
 
 					if ledger_transactions.is_empty() {
 						break;
@@ -408,19 +448,7 @@ impl<T: Send + Sync + Clone + BlockchainTransport> StellarClientTrait for Stella
 						})?;
 
 					let ledger_events: Vec<StellarEvent> =
-						serde_json::from_value(raw_events.clone()).map_err(|e| {
-							let message = format!(
-								"Failed to parse events from response for method '{}': {}",
-								RPC_METHOD_GET_EVENTS, e
-							);
-							let sce_parse_error = StellarClientError::response_parse_error(
-								message,
-								Some(e.into()),
-								None,
-							);
-							anyhow::anyhow!(sce_parse_error)
-								.context("Failed to parse event response")
-						})?;
+						self.decode_response_items(raw_events, "event", RPC_METHOD_GET_EVENTS)?;
 
 					for event in ledger_events {
 						if event.ledger > target_sequence {
@@ -456,10 +484,12 @@ impl<T: Send + Sync + Clone + BlockchainTransport> StellarClientTrait for Stella
 	}
 }
 
-impl<T: Send + Sync + Clone + BlockchainTransport> BlockFilterFactory<Self> for StellarClient<T> {
+impl<T: Send + Sync + Clone + BlockchainTransport + 'static> BlockFilterFactory<Self>
+	for StellarClient<T>
+{
 	type Filter = StellarBlockFilter<Self>;
 
-	fn filter() -> Self::Filter {
+	fn filter(_service: &FilterService) -> Self::Filter {
 		StellarBlockFilter {
 			_client: PhantomData {},
 		}
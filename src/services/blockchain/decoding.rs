@@ -0,0 +1,243 @@
+//! Lenient decoding of chain data.
+//!
+//! Node releases keep adding fields to blocks, transactions and receipts. The chain data models
+//! ignore unknown fields, and an item that still fails to decode, such as a transaction of a new
+//! type, is skipped with a diagnostic instead of failing its whole block. Each diagnostic is
+//! logged with the network, block, index, error and raw item, and counted in the
+//! `chain_data_decode_failures_total` metric.
+//!
+//! Strict decoding, enabled with `STRICT_CHAIN_DATA=true`, fails on the first item that does
+//! not decode, which is the behavior wanted when validating fixtures in CI.
+
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::Value;
+use tracing::warn;
+
+use crate::{models::EVMBlock, utils::metrics::CHAIN_DATA_DECODE_FAILURES};
+
+/// Environment variable enabling strict decoding of chain data
+pub const STRICT_CHAIN_DATA_ENV: &str = "STRICT_CHAIN_DATA";
+
+/// Number of characters of the raw item kept in a diagnostic
+const MAX_RAW_LENGTH: usize = 512;
+
+/// How items that fail to decode are handled
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DecodeMode {
+	/// Items that fail to decode are skipped with a diagnostic
+	#[default]
+	Lenient,
+	/// The first item that fails to decode fails the whole response
+	Strict,
+}
+
+impl DecodeMode {
+	/// Returns the mode set by `STRICT_CHAIN_DATA`, lenient unless it is `true`
+	pub fn from_env() -> Self {
+		match std::env::var(STRICT_CHAIN_DATA_ENV) {
+			Ok(value) if value == "true" => Self::Strict,
+			_ => Self::Lenient,
+		}
+	}
+}
+
+/// Item skipped because it failed to decode
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct DecodeDiagnostic {
+	/// Slug of the network the item was fetched from
+	pub network: String,
+	/// Block or ledger of the item, if known
+	pub block: Option<u64>,
+	/// Position of the item in its block or response
+	pub index: usize,
+	/// Kind of item, e.g. `transaction`
+	pub item: &'static str,
+	/// Deserialization error
+	pub error: String,
+	/// Raw JSON of the item, truncated
+	pub raw: String,
+}
+
+impl DecodeDiagnostic {
+	/// Logs the diagnostic and counts it in the metrics
+	pub fn report(&self) {
+		warn!(
+			network = %self.network,
+			block = ?self.block,
+			index = self.index,
+			item = self.item,
+			error = %self.error,
+			raw = %self.raw,
+			"Skipped {} that failed to decode",
+			self.item
+		);
+		CHAIN_DATA_DECODE_FAILURES
+			.with_label_values(&[self.network.as_str(), self.item])
+			.inc();
+	}
+}
+
+/// Truncates the JSON of a raw item for a diagnostic
+fn truncate_raw(raw: &Value) -> String {
+	let raw = raw.to_string();
+	match raw.char_indices().nth(MAX_RAW_LENGTH) {
+		Some((end, _)) => format!("{}...", &raw[..end]),
+		None => raw,
+	}
+}
+
+/// Returns the block or ledger a raw item refers to, if it has one
+fn item_block(raw: &Value) -> Option<u64> {
+	match raw.get("blockNumber").or_else(|| raw.get("ledger"))? {
+		Value::Number(number) => number.as_u64(),
+		Value::String(number) => u64::from_str_radix(number.trim_start_matches("0x"), 16).ok(),
+		_ => None,
+	}
+}
+
+/// Decodes a list of raw items one by one
+///
+/// # Arguments
+/// * `items` - Raw items
+/// * `network` - Slug of the network the items were fetched from
+/// * `block` - Block of the items, read from each item when `None`
+/// * `item` - Kind of item, for the diagnostics
+/// * `mode` - How items that fail to decode are handled
+///
+/// # Returns
+/// * `Result<(Vec<T>, Vec<DecodeDiagnostic>), anyhow::Error>` - The decoded items with the
+///   diagnostics of the skipped ones, or the error of the first item that failed to decode in
+///   strict mode
+pub fn decode_items<T: DeserializeOwned>(
+	items: Vec<Value>,
+	network: &str,
+	block: Option<u64>,
+	item: &'static str,
+	mode: DecodeMode,
+) -> Result<(Vec<T>, Vec<DecodeDiagnostic>), anyhow::Error> {
+	let mut decoded = Vec::with_capacity(items.len());
+	let mut diagnostics = Vec::new();
+	for (index, raw) in items.into_iter().enumerate() {
+		match T::deserialize(&raw) {
+			Ok(value) => decoded.push(value),
+			Err(e) if mode == DecodeMode::Strict => {
+				return Err(anyhow::anyhow!("Failed to parse {} {}: {}", item, index, e));
+			}
+			Err(e) => diagnostics.push(DecodeDiagnostic {
+				network: network.to_string(),
+				block: block.or_else(|| item_block(&raw)),
+				index,
+				item,
+				error: e.to_string(),
+				raw: truncate_raw(&raw),
+			}),
+		}
+	}
+	Ok((decoded, diagnostics))
+}
+
+/// Decodes an EVM block, decoding its transactions one by one
+///
+/// # Returns
+/// * `Result<(EVMBlock, Vec<DecodeDiagnostic>), anyhow::Error>` - The block without the
+///   transactions that failed to decode, with their diagnostics
+pub fn decode_evm_block(
+	mut raw: Value,
+	network: &str,
+	mode: DecodeMode,
+) -> Result<(EVMBlock, Vec<DecodeDiagnostic>), anyhow::Error> {
+	let transactions = match raw.get_mut("transactions") {
+		Some(Value::Array(transactions)) => std::mem::take(transactions),
+		_ => vec![],
+	};
+	let mut block: EVMBlock =
+		serde_json::from_value(raw).map_err(|e| anyhow::anyhow!("Failed to parse block: {}", e))?;
+	let number = block.number.map(|n| n.to::<u64>());
+	let (transactions, diagnostics) =
+		decode_items(transactions, network, number, "transaction", mode)?;
+	block.0.transactions = transactions;
+	Ok((block, diagnostics))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use serde_json::json;
+
+	fn raw_transaction(index: u64) -> Value {
+		json!({
+			"hash": format!("0x{:064x}", index + 1),
+			"nonce": "0x0",
+			"blockHash": null,
+			"blockNumber": "0x10",
+			"transactionIndex": format!("0x{:x}", index),
+			"from": "0x0000000000000000000000000000000000000001",
+			"to": "0x0000000000000000000000000000000000000002",
+			"value": "0x1",
+			"gasPrice": "0x1",
+			"gas": "0x5208",
+			"input": "0x",
+			"someFutureField": { "nested": true }
+		})
+	}
+
+	fn raw_block(transactions: Vec<Value>) -> Value {
+		json!({
+			"hash": format!("0x{:064x}", 1),
+			"parentHash": format!("0x{:064x}", 0),
+			"stateRoot": format!("0x{:064x}", 0),
+			"transactionsRoot": format!("0x{:064x}", 0),
+			"receiptsRoot": format!("0x{:064x}", 0),
+			"number": "0x10",
+			"gasUsed": "0x0",
+			"extraData": "0x",
+			"logsBloom": null,
+			"timestamp": "0x1",
+			"totalDifficulty": null,
+			"size": null,
+			"mixHash": null,
+			"nonce": null,
+			"withdrawals": [],
+			"blobGasUsed": "0x0",
+			"transactions": transactions
+		})
+	}
+
+	#[test]
+	fn test_malformed_transaction_is_skipped() {
+		let mut malformed = raw_transaction(1);
+		malformed["value"] = json!("not a number");
+		let raw = raw_block(vec![raw_transaction(0), malformed, raw_transaction(2)]);
+
+		let (block, diagnostics) =
+			decode_evm_block(raw, "ethereum_mainnet", DecodeMode::Lenient).unwrap();
+		assert_eq!(block.transactions.len(), 2);
+		assert_eq!(diagnostics.len(), 1);
+		let diagnostic = &diagnostics[0];
+		assert_eq!(diagnostic.network, "ethereum_mainnet");
+		assert_eq!(diagnostic.block, Some(16));
+		assert_eq!(diagnostic.index, 1);
+		assert_eq!(diagnostic.item, "transaction");
+		assert!(diagnostic.raw.contains("not a number"));
+	}
+
+	#[test]
+	fn test_strict_mode_fails_the_block() {
+		let mut malformed = raw_transaction(1);
+		malformed["value"] = json!("not a number");
+		let raw = raw_block(vec![raw_transaction(0), malformed]);
+
+		let error = decode_evm_block(raw, "ethereum_mainnet", DecodeMode::Strict).unwrap_err();
+		assert!(error.to_string().contains("Failed to parse transaction 1"));
+	}
+
+	#[test]
+	fn test_item_block_and_truncated_raw() {
+		assert_eq!(item_block(&json!({ "blockNumber": "0x1f" })), Some(31));
+		assert_eq!(item_block(&json!({ "ledger": 12 })), Some(12));
+		assert_eq!(item_block(&json!({})), None);
+
+		let raw = truncate_raw(&json!("a".repeat(2 * MAX_RAW_LENGTH)));
+		assert_eq!(raw.chars().count(), MAX_RAW_LENGTH + 3);
+	}
+}
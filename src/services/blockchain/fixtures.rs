@@ -0,0 +1,387 @@
+//! Recording and replay of blockchain data as fixture files.
+//!
+//! A [`FixtureRecorder`] attached to an EVM client writes every block, transaction receipt and
+//! log the client fetches to a fixture directory, and a [`FixtureClient`] replays them through
+//! the same client traits. Fixtures are laid out as:
+//!
+//! ```text
+//! <root>/<network_slug>/network.json      network configuration without its RPC URLs
+//! <root>/<network_slug>/<block_number>.json   block, receipts and logs of the block
+//! ```
+//!
+//! Recording is enabled by setting `FIXTURE_RECORD_DIR` to the root directory.
+
+use std::{
+	collections::BTreeMap,
+	path::{Path, PathBuf},
+	sync::Arc,
+};
+
+use anyhow::Context;
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use tokio::sync::Mutex;
+
+use crate::{
+	models::{BlockType, EVMBlock, EVMReceiptLog, EVMTransactionReceipt, Network},
+	services::{
+		blockchain::{
+			BlockChainClient, BlockFilterFactory, ClientPoolTrait, EvmClientTrait, MidnightClient,
+			MidnightWsTransportClient, StellarClient, StellarTransportClient,
+		},
+		filter::{EVMBlockFilter, FilterService},
+	},
+	utils::client_storage::ClientStorage,
+};
+
+/// Environment variable holding the directory fixtures are recorded to
+pub const FIXTURE_RECORD_DIR_ENV: &str = "FIXTURE_RECORD_DIR";
+
+/// Name of the file holding the network configuration of a fixture directory
+const NETWORK_FILE: &str = "network.json";
+
+/// Data fetched for a single EVM block
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct EvmBlockFixture {
+	/// The block, if it was fetched
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub block: Option<EVMBlock>,
+	/// Receipts of the block's transactions that were fetched
+	#[serde(default)]
+	pub receipts: Vec<EVMTransactionReceipt>,
+	/// Logs of the block
+	#[serde(default)]
+	pub logs: Vec<EVMReceiptLog>,
+}
+
+/// Writes the data fetched for a network to a fixture directory
+pub struct FixtureRecorder {
+	/// Directory of the network's fixtures
+	dir: PathBuf,
+	/// Serializes the updates of the block files
+	lock: Mutex<()>,
+}
+
+impl FixtureRecorder {
+	/// Creates a recorder writing to `<root>/<network_slug>`
+	///
+	/// The network configuration is written along the fixtures with its RPC URLs removed, so
+	/// no endpoint or secret ends up in the fixtures.
+	pub fn new(root: &Path, network: &Network) -> Result<Self, anyhow::Error> {
+		let dir = root.join(&network.slug);
+		std::fs::create_dir_all(&dir)
+			.with_context(|| format!("Failed to create fixture directory {}", dir.display()))?;
+
+		let network = Network {
+			rpc_urls: vec![],
+			..network.clone()
+		};
+		let path = dir.join(NETWORK_FILE);
+		std::fs::write(&path, serde_json::to_string_pretty(&network)?)
+			.with_context(|| format!("Failed to write {}", path.display()))?;
+
+		Ok(Self {
+			dir,
+			lock: Mutex::new(()),
+		})
+	}
+
+	/// Directory the network's fixtures are written to
+	pub fn dir(&self) -> &Path {
+		&self.dir
+	}
+
+	/// Records a fetched block
+	pub async fn record_block(&self, block: &EVMBlock) -> Result<(), anyhow::Error> {
+		let number = block.number().context("Block has no number")?;
+		self.update(number, |fixture| fixture.block = Some(block.clone()))
+			.await
+	}
+
+	/// Records a fetched transaction receipt
+	pub async fn record_receipt(
+		&self,
+		receipt: &EVMTransactionReceipt,
+	) -> Result<(), anyhow::Error> {
+		let number = receipt
+			.block_number
+			.context("Receipt has no block number")?
+			.to::<u64>();
+		self.update(number, |fixture| {
+			fixture
+				.receipts
+				.retain(|r| r.transaction_hash != receipt.transaction_hash);
+			fixture.receipts.push(receipt.clone());
+		})
+		.await
+	}
+
+	/// Records the logs fetched for a block range
+	///
+	/// Every block of the range is recorded, including blocks without logs, so replaying the
+	/// range does not miss them.
+	pub async fn record_logs(
+		&self,
+		from_block: u64,
+		to_block: u64,
+		logs: &[EVMReceiptLog],
+	) -> Result<(), anyhow::Error> {
+		for number in from_block..=to_block {
+			let block_logs = logs
+				.iter()
+				.filter(|log| log.block_number.map(|n| n.to::<u64>()) == Some(number))
+				.cloned()
+				.collect::<Vec<_>>();
+			self.update(number, |fixture| {
+				fixture.logs.retain(|log| {
+					!block_logs.iter().any(|l| {
+						l.transaction_hash == log.transaction_hash && l.log_index == log.log_index
+					})
+				});
+				fixture.logs.extend(block_logs);
+			})
+			.await?;
+		}
+		Ok(())
+	}
+
+	/// Applies a change to the fixture file of a block
+	async fn update(
+		&self,
+		number: u64,
+		change: impl FnOnce(&mut EvmBlockFixture),
+	) -> Result<(), anyhow::Error> {
+		let _guard = self.lock.lock().await;
+		let path = block_path(&self.dir, number);
+		let mut fixture = match tokio::fs::read_to_string(&path).await {
+			Ok(content) => serde_json::from_str(&content)
+				.with_context(|| format!("Failed to parse {}", path.display()))?,
+			Err(e) if e.kind() == std::io::ErrorKind::NotFound => EvmBlockFixture::default(),
+			Err(e) => return Err(e).with_context(|| format!("Failed to read {}", path.display())),
+		};
+		change(&mut fixture);
+		tokio::fs::write(&path, serde_json::to_string_pretty(&fixture)?)
+			.await
+			.with_context(|| format!("Failed to write {}", path.display()))
+	}
+}
+
+/// Path of the fixture file of a block
+fn block_path(dir: &Path, number: u64) -> PathBuf {
+	dir.join(format!("{}.json", number))
+}
+
+/// Client replaying the fixtures recorded for a network
+///
+/// Data missing from the fixtures is reported as an error naming the missing block or
+/// transaction, the client never falls back to the network.
+#[derive(Clone)]
+pub struct FixtureClient {
+	/// Directory of the network's fixtures
+	dir: PathBuf,
+	/// Fixtures by block number
+	blocks: Arc<BTreeMap<u64, EvmBlockFixture>>,
+}
+
+impl FixtureClient {
+	/// Loads the fixtures of a network directory
+	pub fn load(dir: &Path) -> Result<Self, anyhow::Error> {
+		let mut blocks = BTreeMap::new();
+		let entries = std::fs::read_dir(dir)
+			.with_context(|| format!("Failed to read fixture directory {}", dir.display()))?;
+		for entry in entries {
+			let path = entry?.path();
+			let Some(number) = path
+				.file_stem()
+				.and_then(|stem| stem.to_str())
+				.and_then(|stem| stem.parse::<u64>().ok())
+			else {
+				continue;
+			};
+			let content = std::fs::read_to_string(&path)
+				.with_context(|| format!("Failed to read {}", path.display()))?;
+			let fixture: EvmBlockFixture = serde_json::from_str(&content)
+				.with_context(|| format!("Failed to parse {}", path.display()))?;
+			blocks.insert(number, fixture);
+		}
+		Ok(Self {
+			dir: dir.to_path_buf(),
+			blocks: Arc::new(blocks),
+		})
+	}
+
+	/// Loads the network configuration recorded along the fixtures
+	pub fn load_network(dir: &Path) -> Result<Network, anyhow::Error> {
+		let path = dir.join(NETWORK_FILE);
+		let content = std::fs::read_to_string(&path)
+			.with_context(|| format!("Failed to read {}", path.display()))?;
+		serde_json::from_str(&content)
+			.with_context(|| format!("Failed to parse {}", path.display()))
+	}
+
+	/// Returns the fixture of a block, or an error naming the missing block
+	fn fixture(&self, number: u64) -> Result<&EvmBlockFixture, anyhow::Error> {
+		self.blocks.get(&number).ok_or_else(|| {
+			anyhow::anyhow!("No fixture for block {} in {}", number, self.dir.display())
+		})
+	}
+}
+
+impl BlockFilterFactory<Self> for FixtureClient {
+	type Filter = EVMBlockFilter<Self>;
+	fn filter(service: &FilterService) -> Self::Filter {
+		EVMBlockFilter {
+			_client: std::marker::PhantomData,
+			price_oracles: Some(service.price_oracles().clone()),
+			prices: None,
+			decode_failures: Default::default(),
+			decode_failure_tracker: Some(service.decode_failures().clone()),
+		}
+	}
+}
+
+#[async_trait]
+impl BlockChainClient for FixtureClient {
+	/// Returns the highest recorded block
+	async fn get_latest_block_number(&self) -> Result<u64, anyhow::Error> {
+		self.blocks
+			.keys()
+			.next_back()
+			.copied()
+			.ok_or_else(|| anyhow::anyhow!("No fixtures in {}", self.dir.display()))
+	}
+
+	async fn get_blocks(
+		&self,
+		start_block: u64,
+		end_block: Option<u64>,
+	) -> Result<Vec<BlockType>, anyhow::Error> {
+		(start_block..=end_block.unwrap_or(start_block))
+			.map(|number| {
+				let block = self.fixture(number)?.block.clone().ok_or_else(|| {
+					anyhow::anyhow!(
+						"Block {} was not recorded in {}",
+						number,
+						self.dir.display()
+					)
+				})?;
+				Ok(BlockType::EVM(Arc::new(block)))
+			})
+			.collect()
+	}
+}
+
+#[async_trait]
+impl EvmClientTrait for FixtureClient {
+	async fn get_transaction_receipt(
+		&self,
+		transaction_hash: String,
+	) -> Result<EVMTransactionReceipt, anyhow::Error> {
+		self.blocks
+			.values()
+			.flat_map(|fixture| &fixture.receipts)
+			.find(|receipt| {
+				format!("{:#x}", receipt.transaction_hash)
+					.eq_ignore_ascii_case(transaction_hash.trim())
+			})
+			.cloned()
+			.ok_or_else(|| {
+				anyhow::anyhow!(
+					"No fixture receipt for transaction {} in {}",
+					transaction_hash,
+					self.dir.display()
+				)
+			})
+	}
+
+	async fn get_logs_for_blocks(
+		&self,
+		from_block: u64,
+		to_block: u64,
+		addresses: Option<Vec<String>>,
+	) -> Result<Vec<EVMReceiptLog>, anyhow::Error> {
+		let mut logs = vec![];
+		for number in from_block..=to_block {
+			logs.extend(
+				self.fixture(number)?
+					.logs
+					.iter()
+					.filter(|log| {
+						addresses.as_ref().is_none_or(|addresses| {
+							let address = format!("{:#x}", log.address);
+							addresses.iter().any(|a| a.eq_ignore_ascii_case(&address))
+						})
+					})
+					.cloned(),
+			);
+		}
+		Ok(logs)
+	}
+
+	async fn call_contract(&self, to: String, _data: Vec<u8>) -> Result<Vec<u8>, anyhow::Error> {
+		Err(anyhow::anyhow!(
+			"Contract calls are not recorded in fixtures: {}",
+			to
+		))
+	}
+}
+
+/// Client pool serving [`FixtureClient`]s from a fixture root directory
+///
+/// Only EVM networks can be replayed, requesting a Stellar or Midnight client is an error.
+pub struct FixtureClientPool {
+	/// Root directory of the fixtures, with one directory per network
+	root: PathBuf,
+	/// Loaded clients by network slug
+	clients: ClientStorage<FixtureClient>,
+}
+
+impl FixtureClientPool {
+	/// Creates a pool replaying the fixtures under `root`
+	pub fn new(root: impl Into<PathBuf>) -> Self {
+		Self {
+			root: root.into(),
+			clients: ClientStorage::new(),
+		}
+	}
+}
+
+#[async_trait]
+impl ClientPoolTrait for FixtureClientPool {
+	type EvmClient = FixtureClient;
+	type StellarClient = StellarClient<StellarTransportClient>;
+	type MidnightClient = MidnightClient<MidnightWsTransportClient>;
+
+	async fn get_evm_client(
+		&self,
+		network: &Network,
+	) -> Result<Arc<Self::EvmClient>, anyhow::Error> {
+		if let Some(client) = self.clients.clients.read().await.get(&network.slug) {
+			return Ok(client.clone());
+		}
+		let mut clients = self.clients.clients.write().await;
+		let client = Arc::new(FixtureClient::load(&self.root.join(&network.slug))?);
+		clients.insert(network.slug.clone(), client.clone());
+		Ok(client)
+	}
+
+	async fn get_stellar_client(
+		&self,
+		network: &Network,
+	) -> Result<Arc<Self::StellarClient>, anyhow::Error> {
+		Err(anyhow::anyhow!(
+			"Fixtures cannot be replayed for Stellar network '{}'",
+			network.slug
+		))
+	}
+
+	async fn get_midnight_client(
+		&self,
+		network: &Network,
+	) -> Result<Arc<Self::MidnightClient>, anyhow::Error> {
+		Err(anyhow::anyhow!(
+			"Fixtures cannot be replayed for Midnight network '{}'",
+			network.slug
+		))
+	}
+}
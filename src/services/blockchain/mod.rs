@@ -8,10 +8,13 @@
 //! - Network transport implementations
 //! - Error handling for blockchain operations
 //! - Client pool for managing multiple clients
+//! - Lenient decoding of chain data
 
 mod client;
 mod clients;
+mod decoding;
 mod error;
+mod fixtures;
 mod pool;
 mod transports;
 
@@ -20,7 +23,13 @@ pub use clients::{
 	EvmClient, EvmClientTrait, MidnightClient, MidnightClientTrait, MidnightSubstrateClientTrait,
 	StellarClient, StellarClientError, StellarClientTrait,
 };
+pub use decoding::{
+	decode_evm_block, decode_items, DecodeDiagnostic, DecodeMode, STRICT_CHAIN_DATA_ENV,
+};
 pub use error::BlockChainError;
+pub use fixtures::{
+	EvmBlockFixture, FixtureClient, FixtureClientPool, FixtureRecorder, FIXTURE_RECORD_DIR_ENV,
+};
 pub use pool::{ClientPool, ClientPoolTrait};
 pub use transports::{
 	BlockchainTransport, EVMTransportClient, HttpEndpointManager, HttpTransportClient,
@@ -22,7 +22,7 @@ use crate::{
 		http::endpoint_manager::EndpointManager, BlockchainTransport, RotatingTransport,
 		TransientErrorRetryStrategy, TransportError,
 	},
-	utils::http::{create_retryable_http_client, RetryConfig},
+	utils::http::{retryable_http_client_builder, RetryConfig},
 };
 
 /// Basic HTTP transport client for blockchain interactions
@@ -86,11 +86,20 @@ impl HttpTransportClient {
 		// Shared across:
 		// - EndpointManager for handling endpoint rotation
 		// - Connection testing for verifying endpoint availability
-		let retryable_client = create_retryable_http_client(
+		#[allow(unused_mut)]
+		let mut client_builder = retryable_http_client_builder(
 			&http_retry_config,
 			(*base_http_client).clone(),
 			Some(TransientErrorRetryStrategy),
 		);
+		// Chaos builds inject the faults configured for the network into every attempt
+		#[cfg(feature = "chaos")]
+		if let Some(rates) = crate::utils::chaos::network_faults(&network.slug) {
+			tracing::warn!("Injecting faults into requests to network {}", network.slug);
+			client_builder =
+				client_builder.with(crate::utils::chaos::FaultInjectionMiddleware::random(rates));
+		}
+		let retryable_client = client_builder.build();
 		for rpc_url in rpc_urls.iter() {
 			let url = match Url::parse(rpc_url.url.as_ref()) {
 				Ok(url) => url,
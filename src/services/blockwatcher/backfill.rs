@@ -0,0 +1,118 @@
+//! Backfill of historical blocks.
+//!
+//! A backfill job processes a range of past blocks through the same block and trigger handlers
+//! as the block watcher. Its matches are marked with the job's origin, so that triggers only
+//! accepting live matches, such as chat channels, are not paged with historical events. A job
+//! can override the origin to intentionally re-alert on the range.
+
+use futures::future::BoxFuture;
+
+use crate::{
+	models::{BlockType, MatchOrigin, Network, ProcessedBlock},
+	services::{blockchain::BlockChainClient, blockwatcher::error::BlockWatcherError},
+};
+
+/// Number of blocks fetched at once by a backfill job
+const BACKFILL_BATCH_SIZE: u64 = 100;
+
+/// Range of historical blocks to process and the origin of their matches
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BackfillJob {
+	/// Identifier of the job, carried by its matches
+	pub job_id: String,
+	/// First block to process
+	pub from_block: u64,
+	/// Last block to process, included
+	pub to_block: u64,
+	/// Origin set on the matches instead of the job's own, e.g. live to re-alert
+	pub origin_override: Option<MatchOrigin>,
+}
+
+impl BackfillJob {
+	/// Returns the origin of the job's matches
+	pub fn origin(&self) -> MatchOrigin {
+		self.origin_override
+			.clone()
+			.unwrap_or_else(|| MatchOrigin::Backfill {
+				job_id: self.job_id.clone(),
+			})
+	}
+}
+
+/// Processes the blocks of a backfill job and dispatches their matches
+///
+/// Blocks are fetched in batches and handled in order. The matches of every block are marked
+/// with the job's origin before being passed to the trigger handler, whose dispatch is awaited
+/// before moving on to the next block.
+///
+/// # Arguments
+/// * `network` - Network configuration
+/// * `rpc_client` - RPC client for the network
+/// * `job` - Blocks to process and origin of their matches
+/// * `block_handler` - Handler function for processed blocks
+/// * `trigger_handler` - Handler function for processed blocks
+///
+/// # Returns
+/// * `Result<usize, BlockWatcherError>` - Number of processed blocks
+pub async fn run_backfill<
+	C: BlockChainClient,
+	H: Fn(BlockType, Network) -> BoxFuture<'static, ProcessedBlock> + Send + Sync + 'static,
+	T: Fn(&ProcessedBlock) -> tokio::task::JoinHandle<()> + Send + Sync + 'static,
+>(
+	network: &Network,
+	rpc_client: &C,
+	job: &BackfillJob,
+	block_handler: &H,
+	trigger_handler: &T,
+) -> Result<usize, BlockWatcherError> {
+	if job.from_block > job.to_block {
+		return Err(BlockWatcherError::processing_error(
+			format!(
+				"Backfill job '{}' starts at block {} after its last block {}",
+				job.job_id, job.from_block, job.to_block
+			),
+			None,
+			None,
+		));
+	}
+
+	let origin = job.origin();
+	let mut processed = 0;
+	let mut start = job.from_block;
+	while start <= job.to_block {
+		let end = job
+			.to_block
+			.min(start.saturating_add(BACKFILL_BATCH_SIZE - 1));
+		let blocks = rpc_client.get_blocks(start, Some(end)).await.map_err(|e| {
+			BlockWatcherError::network_error(
+				format!(
+					"Failed to fetch blocks {} to {} for backfill job '{}'",
+					start, end, job.job_id
+				),
+				Some(e.into()),
+				None,
+			)
+		})?;
+
+		for block in blocks {
+			let mut processed_block = (block_handler)(block, network.clone()).await;
+			processed_block.set_origin(&origin);
+			let _ = (trigger_handler)(&processed_block).await;
+			processed += 1;
+		}
+
+		if end == u64::MAX {
+			break;
+		}
+		start = end + 1;
+	}
+
+	tracing::info!(
+		network = %network.slug,
+		job_id = %job.job_id,
+		processed = processed,
+		"Backfill job completed"
+	);
+
+	Ok(processed)
+}
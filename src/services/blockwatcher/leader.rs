@@ -0,0 +1,453 @@
+//! Leader election between monitor replicas.
+//!
+//! Replicas sharing the same storage compete for a lease holding the id of the current leader
+//! and its expiry. Only the leader processes blocks and dispatches notifications, followers keep
+//! their configuration and clients loaded and take over once the lease expires. Every lease
+//! update is conditional on the lease read just before it, so two replicas cannot both acquire
+//! the same term.
+
+use async_trait::async_trait;
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use std::{
+	path::PathBuf,
+	sync::{Arc, Mutex},
+	time::{Duration, Instant},
+};
+use tokio::{io::AsyncWriteExt, sync::watch};
+
+use crate::utils::metrics::{LEADERSHIP_CHANGES, LEADER_STATUS};
+
+/// Lease held by the leader replica
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Lease {
+	/// Identifier of the replica holding the lease
+	pub holder: String,
+	/// Incremented every time the lease changes holder
+	pub term: u64,
+	/// Unix time in milliseconds after which the lease can be taken over by another replica
+	pub expires_at_ms: i64,
+}
+
+/// Interface for lease storage implementations
+///
+/// Implementations must make `compare_and_swap` atomic with respect to other replicas using
+/// the same storage.
+#[async_trait]
+pub trait LeaseStore: Send + Sync {
+	/// Reads the current lease
+	///
+	/// # Returns
+	/// * `Result<Option<Lease>, anyhow::Error>` - The lease or None if it was never acquired
+	async fn read(&self) -> Result<Option<Lease>, anyhow::Error>;
+
+	/// Replaces the lease if it still equals `expected`
+	///
+	/// # Arguments
+	/// * `expected` - Lease read before the update, None if there was no lease
+	/// * `lease` - New lease to store
+	///
+	/// # Returns
+	/// * `Result<bool, anyhow::Error>` - Whether the lease was replaced
+	async fn compare_and_swap(
+		&self,
+		expected: Option<&Lease>,
+		lease: &Lease,
+	) -> Result<bool, anyhow::Error>;
+}
+
+/// File-based implementation of lease storage
+///
+/// The lease is stored as JSON next to the block storage files, the same storage the replicas
+/// already share for their progress, so leader election needs no other service. Updates are
+/// serialized through an exclusive OS lock on a lock file, and written to a temporary file
+/// renamed over the lease.
+///
+/// The lock is released by the operating system when its holder exits, so a crashed replica
+/// never leaves it behind. The data directory must be on a file system honouring these locks
+/// for all replicas, such as a local disk or a volume mounted by both containers of a host.
+#[derive(Clone)]
+pub struct FileLeaseStore {
+	/// Path of the lease file
+	path: PathBuf,
+}
+
+impl FileLeaseStore {
+	/// Creates a new file-based lease store
+	pub fn new(path: PathBuf) -> Self {
+		FileLeaseStore { path }
+	}
+
+	fn lock_path(&self) -> PathBuf {
+		self.path.with_extension("lock")
+	}
+
+	/// Locks the lock file, returns None if another replica holds it
+	///
+	/// The lock file is never removed, removing it while locked would let a replica lock a new
+	/// file at the same path while another still holds the old one. The lock is released when
+	/// the returned file is dropped.
+	fn try_lock(&self) -> Result<Option<std::fs::File>, anyhow::Error> {
+		let file = std::fs::OpenOptions::new()
+			.create(true)
+			.truncate(false)
+			.write(true)
+			.open(self.lock_path())?;
+		match file.try_lock() {
+			Ok(()) => Ok(Some(file)),
+			Err(std::fs::TryLockError::WouldBlock) => Ok(None),
+			Err(std::fs::TryLockError::Error(e)) => Err(e.into()),
+		}
+	}
+
+	async fn write(&self, lease: &Lease) -> Result<(), anyhow::Error> {
+		let tmp_path = self.path.with_extension("tmp");
+		let mut file = tokio::fs::File::create(&tmp_path).await?;
+		file.write_all(serde_json::to_string(lease)?.as_bytes())
+			.await?;
+		file.sync_all().await?;
+		tokio::fs::rename(&tmp_path, &self.path).await?;
+		Ok(())
+	}
+}
+
+impl Default for FileLeaseStore {
+	/// Default implementation for FileLeaseStore
+	///
+	/// Stores the lease in "data/leader_lease.json"
+	fn default() -> Self {
+		FileLeaseStore::new(PathBuf::from("data/leader_lease.json"))
+	}
+}
+
+#[async_trait]
+impl LeaseStore for FileLeaseStore {
+	async fn read(&self) -> Result<Option<Lease>, anyhow::Error> {
+		match tokio::fs::read_to_string(&self.path).await {
+			Ok(content) => Ok(Some(serde_json::from_str(&content)?)),
+			Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+			Err(e) => Err(e.into()),
+		}
+	}
+
+	async fn compare_and_swap(
+		&self,
+		expected: Option<&Lease>,
+		lease: &Lease,
+	) -> Result<bool, anyhow::Error> {
+		let Some(_lock) = self.try_lock()? else {
+			return Ok(false);
+		};
+
+		let current = self.read().await?;
+		if current.as_ref() != expected {
+			return Ok(false);
+		}
+		self.write(lease).await?;
+		Ok(true)
+	}
+}
+
+/// Returns whether a replica may process and dispatch blocks
+///
+/// Without an elector the replica runs alone and always may. Block processing calls this
+/// again before dispatching each block and before storing its progress, since the lease can
+/// be lost while a range of blocks is being processed.
+pub(crate) fn holds_lease(leader_elector: Option<&LeaderElector>) -> bool {
+	leader_elector.is_none_or(|leader| leader.is_leader())
+}
+
+/// Competes for the lease on behalf of this replica
+///
+/// The leader only considers itself leader for half of the lease TTL after each successful
+/// renewal, while followers wait for the full TTL before taking over, so a leader whose
+/// renewals stop has stopped processing before anyone else starts.
+pub struct LeaderElector {
+	/// Identifier of this replica
+	holder_id: String,
+	/// Storage shared by all replicas
+	store: Arc<dyn LeaseStore>,
+	/// Lease duration
+	ttl: Duration,
+	/// Local deadline until which this replica acts as leader
+	leader_until: Mutex<Option<Instant>>,
+	/// Publishes leadership changes
+	status: watch::Sender<bool>,
+}
+
+impl LeaderElector {
+	/// Creates a new leader elector
+	///
+	/// # Arguments
+	/// * `holder_id` - Unique identifier of this replica
+	/// * `store` - Lease storage shared by all replicas
+	/// * `ttl` - Lease duration, followers take over at most this long after renewals stop
+	pub fn new(holder_id: impl Into<String>, store: Arc<dyn LeaseStore>, ttl: Duration) -> Self {
+		let (status, _) = watch::channel(false);
+		Self {
+			holder_id: holder_id.into(),
+			store,
+			ttl,
+			leader_until: Mutex::new(None),
+			status,
+		}
+	}
+
+	/// Returns the identifier of this replica
+	pub fn holder_id(&self) -> &str {
+		&self.holder_id
+	}
+
+	/// Returns the interval at which the lease should be renewed
+	pub fn renew_interval(&self) -> Duration {
+		self.ttl / 4
+	}
+
+	/// Returns whether this replica currently holds the lease
+	pub fn is_leader(&self) -> bool {
+		self.leader_until
+			.lock()
+			.unwrap_or_else(|e| e.into_inner())
+			.is_some_and(|deadline| Instant::now() < deadline)
+	}
+
+	/// Subscribes to leadership changes of this replica
+	pub fn subscribe(&self) -> watch::Receiver<bool> {
+		self.status.subscribe()
+	}
+
+	/// Acquires or renews the lease if possible
+	///
+	/// # Returns
+	/// * `Result<bool, anyhow::Error>` - Whether this replica is the leader after the attempt
+	pub async fn renew(&self) -> Result<bool, anyhow::Error> {
+		let started = Instant::now();
+		let now = Utc::now().timestamp_millis();
+		let current = self.store.read().await?;
+
+		let term = match &current {
+			None => Some(1),
+			Some(lease) if lease.holder == self.holder_id => Some(lease.term),
+			Some(lease) if lease.expires_at_ms <= now => Some(lease.term + 1),
+			Some(_) => None,
+		};
+
+		let acquired = match term {
+			Some(term) => {
+				let lease = Lease {
+					holder: self.holder_id.clone(),
+					term,
+					expires_at_ms: now + self.ttl.as_millis() as i64,
+				};
+				self.store
+					.compare_and_swap(current.as_ref(), &lease)
+					.await?
+			}
+			None => false,
+		};
+
+		{
+			let mut leader_until = self.leader_until.lock().unwrap_or_else(|e| e.into_inner());
+			if acquired {
+				*leader_until = Some(started + self.ttl / 2);
+			} else if term.is_none() {
+				// Another replica holds the lease. A lost update race leaves the current
+				// deadline to expire on its own.
+				*leader_until = None;
+			}
+		}
+		self.publish();
+
+		Ok(self.is_leader())
+	}
+
+	/// Gives up the lease so that another replica can take over without waiting for the TTL
+	pub async fn release(&self) -> Result<(), anyhow::Error> {
+		*self.leader_until.lock().unwrap_or_else(|e| e.into_inner()) = None;
+		self.publish();
+
+		if let Some(current) = self.store.read().await? {
+			if current.holder == self.holder_id {
+				let released = Lease {
+					expires_at_ms: Utc::now().timestamp_millis(),
+					..current.clone()
+				};
+				self.store
+					.compare_and_swap(Some(&current), &released)
+					.await?;
+			}
+		}
+		Ok(())
+	}
+
+	/// Renews the lease on an interval until shutdown, then releases it
+	///
+	/// # Arguments
+	/// * `shutdown_rx` - Watch channel for shutdown signals
+	pub fn spawn(
+		self: Arc<Self>,
+		mut shutdown_rx: watch::Receiver<bool>,
+	) -> tokio::task::JoinHandle<()> {
+		tokio::spawn(async move {
+			let mut interval = tokio::time::interval(self.renew_interval());
+			loop {
+				tokio::select! {
+					_ = interval.tick() => {
+						if let Err(e) = self.renew().await {
+							tracing::error!(holder = %self.holder_id, "Failed to renew leader lease: {}", e);
+						}
+					}
+					_ = shutdown_rx.changed() => {
+						if let Err(e) = self.release().await {
+							tracing::error!(holder = %self.holder_id, "Failed to release leader lease: {}", e);
+						}
+						break;
+					}
+				}
+			}
+		})
+	}
+
+	/// Publishes the leadership status if it changed
+	fn publish(&self) {
+		let is_leader = self.is_leader();
+		let changed = self.status.send_if_modified(|status| {
+			let changed = *status != is_leader;
+			*status = is_leader;
+			changed
+		});
+		if changed {
+			LEADER_STATUS.set(if is_leader { 1.0 } else { 0.0 });
+			LEADERSHIP_CHANGES.inc();
+			if is_leader {
+				tracing::info!(holder = %self.holder_id, "Acquired leader lease");
+			} else {
+				tracing::info!(holder = %self.holder_id, "Lost leader lease");
+			}
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use tempfile::TempDir;
+
+	fn create_elector(dir: &TempDir, holder: &str, ttl: Duration) -> LeaderElector {
+		let store = FileLeaseStore::new(dir.path().join("leader_lease.json"));
+		LeaderElector::new(holder, Arc::new(store), ttl)
+	}
+
+	#[tokio::test]
+	async fn test_first_replica_acquires_lease() {
+		let dir = TempDir::new().unwrap();
+		let first = create_elector(&dir, "first", Duration::from_secs(10));
+		let second = create_elector(&dir, "second", Duration::from_secs(10));
+
+		assert!(first.renew().await.unwrap());
+		assert!(!second.renew().await.unwrap());
+		assert!(first.is_leader());
+		assert!(!second.is_leader());
+
+		// Renewals keep the same term
+		assert!(first.renew().await.unwrap());
+		let lease = FileLeaseStore::new(dir.path().join("leader_lease.json"))
+			.read()
+			.await
+			.unwrap()
+			.unwrap();
+		assert_eq!(lease.holder, "first");
+		assert_eq!(lease.term, 1);
+	}
+
+	#[tokio::test]
+	async fn test_follower_takes_over_expired_lease() {
+		let dir = TempDir::new().unwrap();
+		let first = create_elector(&dir, "first", Duration::from_millis(100));
+		let second = create_elector(&dir, "second", Duration::from_millis(100));
+
+		assert!(first.renew().await.unwrap());
+		tokio::time::sleep(Duration::from_millis(150)).await;
+
+		assert!(!first.is_leader());
+		assert!(second.renew().await.unwrap());
+		assert!(!first.renew().await.unwrap());
+
+		let lease = FileLeaseStore::new(dir.path().join("leader_lease.json"))
+			.read()
+			.await
+			.unwrap()
+			.unwrap();
+		assert_eq!(lease.holder, "second");
+		assert_eq!(lease.term, 2);
+	}
+
+	#[tokio::test]
+	async fn test_release_allows_immediate_takeover() {
+		let dir = TempDir::new().unwrap();
+		let first = create_elector(&dir, "first", Duration::from_secs(10));
+		let second = create_elector(&dir, "second", Duration::from_secs(10));
+
+		assert!(first.renew().await.unwrap());
+		first.release().await.unwrap();
+
+		assert!(!first.is_leader());
+		assert!(second.renew().await.unwrap());
+	}
+
+	#[tokio::test]
+	async fn test_compare_and_swap_rejects_stale_expectation() {
+		let dir = TempDir::new().unwrap();
+		let store = FileLeaseStore::new(dir.path().join("leader_lease.json"));
+		let lease = Lease {
+			holder: "first".to_string(),
+			term: 1,
+			expires_at_ms: Utc::now().timestamp_millis(),
+		};
+
+		assert!(store.compare_and_swap(None, &lease).await.unwrap());
+		// Another replica still expecting no lease must not overwrite it
+		let other = Lease {
+			holder: "second".to_string(),
+			..lease.clone()
+		};
+		assert!(!store.compare_and_swap(None, &other).await.unwrap());
+		assert_eq!(store.read().await.unwrap(), Some(lease));
+	}
+
+	#[tokio::test]
+	async fn test_compare_and_swap_waits_for_lock_holder() {
+		let dir = TempDir::new().unwrap();
+		let store = FileLeaseStore::new(dir.path().join("leader_lease.json"));
+		let lease = Lease {
+			holder: "first".to_string(),
+			term: 1,
+			expires_at_ms: Utc::now().timestamp_millis(),
+		};
+
+		// A replica in the middle of an update holds the lock, however long it takes
+		let lock = store.try_lock().unwrap().unwrap();
+		assert!(store.try_lock().unwrap().is_none());
+		assert!(!store.compare_and_swap(None, &lease).await.unwrap());
+		assert_eq!(store.read().await.unwrap(), None);
+
+		// The lock is released with its holder, without leaving a stale lock behind
+		drop(lock);
+		assert!(store.compare_and_swap(None, &lease).await.unwrap());
+		assert_eq!(store.read().await.unwrap(), Some(lease));
+	}
+
+	#[tokio::test]
+	async fn test_leadership_changes_are_published() {
+		let dir = TempDir::new().unwrap();
+		let elector = create_elector(&dir, "first", Duration::from_secs(10));
+		let mut status = elector.subscribe();
+
+		elector.renew().await.unwrap();
+		assert!(status.has_changed().unwrap());
+		assert!(*status.borrow_and_update());
+
+		elector.renew().await.unwrap();
+		assert!(!status.has_changed().unwrap());
+	}
+}
@@ -4,16 +4,26 @@
 //! different networks. It includes:
 //! - Block watching service for multiple networks
 //! - Block storage implementations
+//! - Leader election between replicas
+//! - Recovery from blocks that cannot be fetched
+//! - Backfill of historical blocks
 //! - Error handling specific to block watching operations
 
+mod backfill;
 mod error;
+mod leader;
+mod recovery;
 mod service;
 mod storage;
 mod tracker;
 
+pub use backfill::{run_backfill, BackfillJob};
 pub use error::BlockWatcherError;
+pub use leader::{FileLeaseStore, LeaderElector, Lease, LeaseStore};
+pub use recovery::retry_failed_blocks;
 pub use service::{
-	process_new_blocks, BlockWatcherService, JobSchedulerTrait, NetworkBlockWatcher,
+	process_new_blocks, process_new_blocks_at, BlockWatcherService, JobSchedulerTrait,
+	NetworkBlockWatcher,
 };
-pub use storage::{BlockStorage, FileBlockStorage};
+pub use storage::{BlockStorage, FailedBlock, FileBlockStorage};
 pub use tracker::{BlockCheckResult, BlockTracker, BlockTrackerTrait};
@@ -0,0 +1,289 @@
+//! Recovery from blocks that cannot be fetched.
+//!
+//! When a network configures `block_fetch`, a block range that cannot be fetched is fetched one
+//! block at a time, each block with its own timeout and retry budget. Blocks still failing are
+//! recorded in the failed block store and skipped, so a single bad block no longer stalls the
+//! network. Recorded blocks are retried later by [`retry_failed_blocks`].
+
+use anyhow::Context;
+use futures::{future::BoxFuture, stream, StreamExt};
+use std::{collections::HashMap, time::Duration};
+use tokio::time::timeout;
+
+use crate::{
+	models::{BlockFetchConfig, BlockType, Network, ProcessedBlock},
+	services::{
+		blockchain::BlockChainClient,
+		blockwatcher::{
+			error::BlockWatcherError,
+			leader::{holds_lease, LeaderElector},
+			storage::{BlockStorage, FailedBlock},
+		},
+	},
+	utils::metrics::FAILED_BLOCKS,
+};
+
+/// Maximum number of blocks fetched concurrently when falling back to per-block fetches
+const MAX_CONCURRENT_BLOCK_FETCHES: usize = 8;
+
+/// Fetches a single block, retrying within the configured budget
+///
+/// # Returns
+/// * `Result<Vec<BlockType>, String>` - The block or the error of the last attempt
+async fn fetch_block<C: BlockChainClient>(
+	rpc_client: &C,
+	block_number: u64,
+	config: &BlockFetchConfig,
+) -> Result<Vec<BlockType>, String> {
+	let mut last_error = String::new();
+
+	for _ in 0..=config.max_retries {
+		match timeout(
+			Duration::from_millis(config.timeout_ms),
+			rpc_client.get_blocks(block_number, None),
+		)
+		.await
+		{
+			Ok(Ok(blocks)) if !blocks.is_empty() => return Ok(blocks),
+			Ok(Ok(_)) => last_error = "Block not returned by the node".to_string(),
+			Ok(Err(e)) => last_error = format!("{:#}", e),
+			Err(_) => last_error = format!("Timed out after {}ms", config.timeout_ms),
+		}
+	}
+
+	Err(last_error)
+}
+
+/// Fetches the blocks from `start_block` to `end_block`, skipping blocks that cannot be fetched
+///
+/// The range is fetched at once first. If that fails, each block is fetched on its own and the
+/// blocks still failing are recorded in the failed block store before being skipped.
+///
+/// # Arguments
+/// * `network` - Network configuration
+/// * `rpc_client` - RPC client for the network
+/// * `block_storage` - Storage for failed blocks
+/// * `config` - Per-block fetch limits
+/// * `start_block` - First block to fetch
+/// * `end_block` - Last block to fetch
+///
+/// # Returns
+/// * `Result<Vec<BlockType>, BlockWatcherError>` - Fetched blocks, or an error if failed blocks
+///   could not be recorded
+pub(crate) async fn fetch_blocks_with_recovery<S: BlockStorage, C: BlockChainClient>(
+	network: &Network,
+	rpc_client: &C,
+	block_storage: &S,
+	config: &BlockFetchConfig,
+	start_block: u64,
+	end_block: u64,
+) -> Result<Vec<BlockType>, BlockWatcherError> {
+	let block_count = end_block.saturating_sub(start_block) + 1;
+	let range_end = (end_block > start_block).then_some(end_block);
+	let range_timeout = Duration::from_millis(config.timeout_ms.saturating_mul(block_count));
+
+	match timeout(range_timeout, rpc_client.get_blocks(start_block, range_end)).await {
+		Ok(Ok(blocks)) => return Ok(blocks),
+		Ok(Err(e)) => tracing::warn!(
+			network = %network.slug,
+			"Failed to fetch blocks {} to {}, fetching them one at a time: {:#}",
+			start_block,
+			end_block,
+			e
+		),
+		Err(_) => tracing::warn!(
+			network = %network.slug,
+			"Timed out fetching blocks {} to {}, fetching them one at a time",
+			start_block,
+			end_block
+		),
+	}
+
+	let results: Vec<(u64, Result<Vec<BlockType>, String>)> = stream::iter(start_block..=end_block)
+		.map(|block_number| async move {
+			(
+				block_number,
+				fetch_block(rpc_client, block_number, config).await,
+			)
+		})
+		.buffered(MAX_CONCURRENT_BLOCK_FETCHES)
+		.collect()
+		.await;
+
+	let now = chrono::Utc::now().timestamp_millis();
+	let mut blocks = Vec::new();
+	let mut failed = Vec::new();
+	for (block_number, result) in results {
+		match result {
+			Ok(fetched) => blocks.extend(fetched),
+			Err(error) => failed.push(FailedBlock {
+				block_number,
+				error,
+				attempts: config.max_retries + 1,
+				last_attempt_ms: now,
+			}),
+		}
+	}
+
+	if !failed.is_empty() {
+		record_failed_blocks(network, block_storage, config, &failed).await?;
+	}
+
+	Ok(blocks)
+}
+
+/// Adds blocks to the failed block store
+///
+/// Fails without recording anything if the store would exceed `max_failed_blocks`, so that
+/// the caller does not advance past blocks it could not record.
+async fn record_failed_blocks<S: BlockStorage>(
+	network: &Network,
+	block_storage: &S,
+	config: &BlockFetchConfig,
+	failed: &[FailedBlock],
+) -> Result<(), BlockWatcherError> {
+	let mut stored = block_storage
+		.get_failed_blocks(&network.slug)
+		.await
+		.with_context(|| "Failed to get failed blocks")?;
+
+	for block in failed {
+		match stored
+			.iter_mut()
+			.find(|b| b.block_number == block.block_number)
+		{
+			Some(existing) => {
+				existing.attempts += block.attempts;
+				existing.error = block.error.clone();
+				existing.last_attempt_ms = block.last_attempt_ms;
+			}
+			None => stored.push(block.clone()),
+		}
+	}
+
+	let metadata = HashMap::from([("network".to_string(), network.slug.clone())]);
+	if stored.len() > config.max_failed_blocks {
+		return Err(BlockWatcherError::storage_error(
+			format!(
+				"Failed block store is full ({} blocks), not skipping blocks {:?}",
+				config.max_failed_blocks,
+				failed.iter().map(|b| b.block_number).collect::<Vec<_>>()
+			),
+			None,
+			Some(metadata),
+		));
+	}
+
+	stored.sort_by_key(|b| b.block_number);
+	block_storage
+		.save_failed_blocks(&network.slug, &stored)
+		.await
+		.with_context(|| "Failed to save failed blocks")?;
+	FAILED_BLOCKS
+		.with_label_values(&[&network.slug])
+		.set(stored.len() as f64);
+
+	for block in failed {
+		BlockWatcherError::processing_error(
+			format!(
+				"Skipping block {} after {} failed fetch attempts: {}",
+				block.block_number, block.attempts, block.error
+			),
+			None,
+			Some(HashMap::from([
+				("network".to_string(), network.slug.clone()),
+				("block_number".to_string(), block.block_number.to_string()),
+			])),
+		);
+	}
+
+	Ok(())
+}
+
+/// Retries the blocks recorded as failed for a network
+///
+/// Blocks that can now be fetched are processed and their matches dispatched, then removed
+/// from the failed block store. The others stay in the store with their latest error.
+///
+/// With a leader elector, the lease is checked before each block. Once it is lost the retry
+/// stops without updating the store, which the new leader retries from.
+///
+/// # Arguments
+/// * `network` - Network configuration
+/// * `rpc_client` - RPC client for the network
+/// * `block_storage` - Storage for failed blocks
+/// * `block_handler` - Handler function for processed blocks
+/// * `trigger_handler` - Handler function for processed blocks
+/// * `leader_elector` - Elector whose lease is required to dispatch blocks, None when leader
+///   election is disabled
+///
+/// # Returns
+/// * `Result<usize, BlockWatcherError>` - Number of recovered blocks
+pub async fn retry_failed_blocks<
+	S: BlockStorage,
+	C: BlockChainClient,
+	H: Fn(BlockType, Network) -> BoxFuture<'static, ProcessedBlock> + Send + Sync + 'static,
+	T: Fn(&ProcessedBlock) -> tokio::task::JoinHandle<()> + Send + Sync + 'static,
+>(
+	network: &Network,
+	rpc_client: &C,
+	block_storage: &S,
+	block_handler: &H,
+	trigger_handler: &T,
+	leader_elector: Option<&LeaderElector>,
+) -> Result<usize, BlockWatcherError> {
+	let config = network.block_fetch.clone().unwrap_or_default();
+	let failed = block_storage
+		.get_failed_blocks(&network.slug)
+		.await
+		.with_context(|| "Failed to get failed blocks")?;
+
+	if failed.is_empty() {
+		return Ok(0);
+	}
+
+	let mut recovered = 0;
+	let mut remaining = Vec::new();
+	for mut block in failed {
+		if !holds_lease(leader_elector) {
+			tracing::warn!(
+				network = %network.slug,
+				recovered = recovered,
+				"Lost the leader lease, leaving the failed blocks to the new leader"
+			);
+			return Ok(recovered);
+		}
+		match fetch_block(rpc_client, block.block_number, &config).await {
+			Ok(blocks) => {
+				for fetched in blocks {
+					let processed = (block_handler)(fetched, network.clone()).await;
+					let _ = (trigger_handler)(&processed).await;
+				}
+				recovered += 1;
+			}
+			Err(error) => {
+				block.error = error;
+				block.attempts += config.max_retries + 1;
+				block.last_attempt_ms = chrono::Utc::now().timestamp_millis();
+				remaining.push(block);
+			}
+		}
+	}
+
+	block_storage
+		.save_failed_blocks(&network.slug, &remaining)
+		.await
+		.with_context(|| "Failed to save failed blocks")?;
+	FAILED_BLOCKS
+		.with_label_values(&[&network.slug])
+		.set(remaining.len() as f64);
+
+	tracing::info!(
+		network = %network.slug,
+		recovered = recovered,
+		remaining = remaining.len(),
+		"Retried failed blocks"
+	);
+
+	Ok(recovered)
+}
@@ -4,25 +4,29 @@
 //! managing individual watchers for each network and coordinating block processing.
 
 use anyhow::Context;
+use chrono::{DateTime, Utc};
 use futures::{channel::mpsc, future::BoxFuture, stream::StreamExt, SinkExt};
 use std::{
 	collections::{BTreeMap, HashMap},
 	sync::Arc,
 };
-use tokio::sync::RwLock;
+use tokio::sync::{Mutex, RwLock};
 use tokio_cron_scheduler::{Job, JobScheduler};
 use tracing::instrument;
 
 use crate::{
-	models::{BlockType, Network, ProcessedBlock},
+	models::{AlertTiming, BlockType, Network, ProcessedBlock},
 	services::{
 		blockchain::BlockChainClient,
 		blockwatcher::{
 			error::BlockWatcherError,
+			leader::{holds_lease, LeaderElector},
+			recovery::{fetch_blocks_with_recovery, retry_failed_blocks},
 			storage::BlockStorage,
 			tracker::{BlockCheckResult, BlockTracker, BlockTrackerTrait},
 		},
 	},
+	utils::metrics::{NETWORK_MAINTENANCE_ACTIVE, SKIPPED_BLOCKS},
 };
 
 /// Trait for job scheduler
@@ -78,6 +82,7 @@ where
 	pub trigger_handler: Arc<T>,
 	pub scheduler: J,
 	pub block_tracker: Arc<BlockTracker>,
+	pub leader_elector: Option<Arc<LeaderElector>>,
 }
 
 /// Map of active block watchers
@@ -102,6 +107,7 @@ where
 	pub trigger_handler: Arc<T>,
 	pub active_watchers: Arc<RwLock<BlockWatchersMap<S, H, T, J>>>,
 	pub block_tracker: Arc<BlockTracker>,
+	pub leader_elector: Option<Arc<LeaderElector>>,
 }
 
 impl<S, H, T, J> NetworkBlockWatcher<S, H, T, J>
@@ -144,13 +150,21 @@ where
 			trigger_handler,
 			scheduler,
 			block_tracker,
+			leader_elector: None,
 		})
 	}
 
+	/// Only processes blocks while the given elector holds the leader lease
+	pub fn with_leader_elector(mut self, leader_elector: Arc<LeaderElector>) -> Self {
+		self.leader_elector = Some(leader_elector);
+		self
+	}
+
 	/// Starts the network watcher
 	///
 	/// Initializes the scheduler and begins watching for new blocks according
-	/// to the network's cron schedule.
+	/// to the network's cron schedule. With a leader elector, scheduled runs are skipped while
+	/// this replica is not the leader.
 	pub async fn start<C: BlockChainClient + Clone + Send + 'static>(
 		&mut self,
 		rpc_client: C,
@@ -160,34 +174,76 @@ where
 		let block_handler = self.block_handler.clone();
 		let trigger_handler = self.trigger_handler.clone();
 		let block_tracker = self.block_tracker.clone();
+		let leader_elector = self.leader_elector.clone();
+		// Block processing and failed block retries update the same failed block store
+		let processing_lock = Arc::new(Mutex::new(()));
+
+		if let Some(block_fetch) = &self.network.block_fetch {
+			let retry_job = self.create_retry_job(
+				rpc_client.clone(),
+				std::time::Duration::from_millis(block_fetch.retry_interval_ms),
+				processing_lock.clone(),
+			)?;
+			self.scheduler.add(retry_job).await.map_err(|e| {
+				BlockWatcherError::scheduler_error(
+					e.to_string(),
+					Some(e),
+					Some(HashMap::from([(
+						"network".to_string(),
+						self.network.slug.clone(),
+					)])),
+				)
+			})?;
+		}
 
 		let job = Job::new_async(self.network.cron_schedule.as_str(), move |_uuid, _l| {
 			let network = network.clone();
+			let leader_elector = leader_elector.clone();
+			let processing_lock = processing_lock.clone();
 			let block_storage = block_storage.clone();
 			let block_handler = block_handler.clone();
 			let block_tracker = block_tracker.clone();
 			let rpc_client = rpc_client.clone();
 			let trigger_handler = trigger_handler.clone();
 			Box::pin(async move {
-				let _ = process_new_blocks(
+				if !holds_lease(leader_elector.as_deref()) {
+					tracing::debug!(
+						network = %network.slug,
+						"Not the leader, skipping block processing"
+					);
+					return;
+				}
+				let _guard = processing_lock.lock().await;
+				let result = process_new_blocks_at(
 					&network,
 					&rpc_client,
 					block_storage,
 					block_handler,
 					trigger_handler,
 					block_tracker,
+					Utc::now(),
+					leader_elector,
 				)
-				.await
-				.map_err(|e| {
-					BlockWatcherError::processing_error(
-						"Failed to process blocks".to_string(),
-						Some(e.into()),
-						Some(HashMap::from([(
-							"network".to_string(),
-							network.slug.clone(),
-						)])),
-					)
-				});
+				.await;
+				if let Err(e) = result {
+					// RPC errors are expected once a maintenance window has started
+					if network.active_maintenance_window(Utc::now()).is_some() {
+						tracing::warn!(
+							network = %network.slug,
+							error = %e,
+							"Block processing failed during a maintenance window"
+						);
+					} else {
+						let _ = BlockWatcherError::processing_error(
+							"Failed to process blocks".to_string(),
+							Some(e.into()),
+							Some(HashMap::from([(
+								"network".to_string(),
+								network.slug.clone(),
+							)])),
+						);
+					}
+				}
 			})
 		})
 		.with_context(|| "Failed to create job")?;
@@ -218,6 +274,52 @@ where
 		Ok(())
 	}
 
+	/// Creates the job retrying the network's failed blocks on an interval
+	fn create_retry_job<C: BlockChainClient + Clone + Send + 'static>(
+		&self,
+		rpc_client: C,
+		interval: std::time::Duration,
+		processing_lock: Arc<Mutex<()>>,
+	) -> Result<Job, anyhow::Error> {
+		let network = self.network.clone();
+		let block_storage = self.block_storage.clone();
+		let block_handler = self.block_handler.clone();
+		let trigger_handler = self.trigger_handler.clone();
+		let leader_elector = self.leader_elector.clone();
+
+		let job = Job::new_repeated_async(interval, move |_uuid, _l| {
+			let network = network.clone();
+			let block_storage = block_storage.clone();
+			let block_handler = block_handler.clone();
+			let trigger_handler = trigger_handler.clone();
+			let leader_elector = leader_elector.clone();
+			let rpc_client = rpc_client.clone();
+			let processing_lock = processing_lock.clone();
+			Box::pin(async move {
+				if !holds_lease(leader_elector.as_deref()) {
+					return;
+				}
+				// Failed blocks would only fail again while the RPC endpoints are down
+				if network.active_maintenance_window(Utc::now()).is_some() {
+					return;
+				}
+				let _guard = processing_lock.lock().await;
+				let _ = retry_failed_blocks(
+					&network,
+					&rpc_client,
+					&*block_storage,
+					&*block_handler,
+					&*trigger_handler,
+					leader_elector.as_deref(),
+				)
+				.await;
+			})
+		})
+		.with_context(|| "Failed to create failed block retry job")?;
+
+		Ok(job)
+	}
+
 	/// Stops the network watcher
 	///
 	/// Shuts down the scheduler and stops watching for new blocks.
@@ -263,9 +365,19 @@ where
 			trigger_handler,
 			active_watchers: Arc::new(RwLock::new(HashMap::new())),
 			block_tracker,
+			leader_elector: None,
 		})
 	}
 
+	/// Only processes blocks while the given elector holds the leader lease
+	///
+	/// Watchers started afterwards keep running on followers so that they can take over
+	/// without reloading configuration or reconnecting clients.
+	pub fn with_leader_elector(mut self, leader_elector: Arc<LeaderElector>) -> Self {
+		self.leader_elector = Some(leader_elector);
+		self
+	}
+
 	/// Starts a watcher for a specific network
 	///
 	/// # Arguments
@@ -293,6 +405,7 @@ where
 			self.block_tracker.clone(),
 		)
 		.await?;
+		watcher.leader_elector = self.leader_elector.clone();
 
 		watcher.start(rpc_client).await?;
 		watchers.insert(network.slug.clone(), watcher);
@@ -317,6 +430,9 @@ where
 
 /// Processes new blocks for a network
 ///
+/// Equivalent to [`process_new_blocks_at`] evaluated at the current time, without leader
+/// election.
+///
 /// # Arguments
 /// * `network` - Network configuration
 /// * `rpc_client` - RPC client for the network
@@ -327,7 +443,6 @@ where
 ///
 /// # Returns
 /// * `Result<(), BlockWatcherError>` - Success or error
-#[instrument(skip_all, fields(network = network.slug))]
 pub async fn process_new_blocks<
 	S: BlockStorage,
 	C: BlockChainClient + Send + Clone + 'static,
@@ -341,9 +456,82 @@ pub async fn process_new_blocks<
 	block_handler: Arc<H>,
 	trigger_handler: Arc<T>,
 	block_tracker: Arc<TR>,
+) -> Result<(), BlockWatcherError> {
+	process_new_blocks_at(
+		network,
+		rpc_client,
+		block_storage,
+		block_handler,
+		trigger_handler,
+		block_tracker,
+		Utc::now(),
+		None,
+	)
+	.await
+}
+
+/// Processes new blocks for a network as of the given point in time
+///
+/// If the network is inside one of its maintenance windows at `now`, no blocks are fetched
+/// and the last processed block is left untouched, so the skipped range is picked up by the
+/// first run after the window ends. Blocks older than `max_past_blocks` at that point are
+/// skipped, counted by the `skipped_blocks_total` metric and logged as an error.
+///
+/// With `block_fetch` configured, blocks that cannot be fetched are recorded as failed and
+/// skipped instead of failing the whole run.
+///
+/// With a leader elector, the lease is checked again before the matches of each block are
+/// dispatched and before the processed range is stored. Once the lease is lost, the remaining
+/// matches are dropped and the last processed block is left for the new leader to continue
+/// from, so that a replica whose renewals stalled mid-run does not race it.
+///
+/// # Arguments
+/// * `network` - Network configuration
+/// * `rpc_client` - RPC client for the network
+/// * `block_storage` - Storage implementation for blocks
+/// * `block_handler` - Handler function for processed blocks
+/// * `trigger_handler` - Handler function for processed blocks
+/// * `block_tracker` - Tracker implementation for block processing
+/// * `now` - Point in time the maintenance windows are evaluated at
+/// * `leader_elector` - Elector whose lease is required to dispatch and store blocks, None
+///   when leader election is disabled
+///
+/// # Returns
+/// * `Result<(), BlockWatcherError>` - Success or error
+#[instrument(skip_all, fields(network = network.slug))]
+pub async fn process_new_blocks_at<
+	S: BlockStorage,
+	C: BlockChainClient + Send + Clone + 'static,
+	H: Fn(BlockType, Network) -> BoxFuture<'static, ProcessedBlock> + Send + Sync + 'static,
+	T: Fn(&ProcessedBlock) -> tokio::task::JoinHandle<()> + Send + Sync + 'static,
+	TR: BlockTrackerTrait + Send + Sync + 'static,
+>(
+	network: &Network,
+	rpc_client: &C,
+	block_storage: Arc<S>,
+	block_handler: Arc<H>,
+	trigger_handler: Arc<T>,
+	block_tracker: Arc<TR>,
+	now: DateTime<Utc>,
+	leader_elector: Option<Arc<LeaderElector>>,
 ) -> Result<(), BlockWatcherError> {
 	let start_time = std::time::Instant::now();
 
+	if let Some(window) = network.active_maintenance_window(now) {
+		NETWORK_MAINTENANCE_ACTIVE
+			.with_label_values(&[&network.slug])
+			.set(1.0);
+		tracing::info!(
+			network = %network.slug,
+			window = %window.cron_schedule,
+			"Network is in a maintenance window, skipping block processing"
+		);
+		return Ok(());
+	}
+	NETWORK_MAINTENANCE_ACTIVE
+		.with_label_values(&[&network.slug])
+		.set(0.0);
+
 	let last_processed_block = block_storage
 		.get_last_processed_block(&network.slug)
 		.await
@@ -385,8 +573,41 @@ pub async fn process_new_blocks<
 		max_past_blocks
 	);
 
+	if last_processed_block > 0 && start_block > last_processed_block + 1 {
+		let skipped = start_block - (last_processed_block + 1);
+		SKIPPED_BLOCKS
+			.with_label_values(&[&network.slug])
+			.inc_by(skipped);
+		tracing::error!(
+			network = %network.slug,
+			skipped = skipped,
+			"Skipping blocks {} to {}, which are older than max_past_blocks ({}). Raise \
+			 max_past_blocks to cover the longest outage or maintenance window",
+			last_processed_block + 1,
+			start_block - 1,
+			max_past_blocks
+		);
+	}
+
 	let mut blocks = Vec::new();
-	if last_processed_block == 0 {
+	if let Some(block_fetch) = &network.block_fetch {
+		if last_processed_block == 0 || last_processed_block < latest_confirmed_block {
+			let first_block = if last_processed_block == 0 {
+				latest_confirmed_block
+			} else {
+				start_block
+			};
+			blocks = fetch_blocks_with_recovery(
+				network,
+				rpc_client,
+				&*block_storage,
+				block_fetch,
+				first_block,
+				latest_confirmed_block,
+			)
+			.await?;
+		}
+	} else if last_processed_block == 0 {
 		blocks = rpc_client
 			.get_blocks(latest_confirmed_block, None)
 			.await
@@ -403,6 +624,8 @@ pub async fn process_new_blocks<
 			})?;
 	}
 
+	let fetched_at_ms = Utc::now().timestamp_millis();
+
 	// Reset expected_next to start_block to ensure synchronization with this execution
 	// This prevents false out-of-order warnings when reprocessing blocks or restarting
 	block_tracker
@@ -442,12 +665,22 @@ pub async fn process_new_blocks<
 		let mut trigger_tx = trigger_tx.clone();
 
 		async move {
-			// Process blocks concurrently, up to 32 at a time
+			// Process blocks concurrently, up to 32 at a time, stamping the matches with the
+			// times their block went through the pipeline
 			let mut results = process_rx
 				.map(|(block, _)| {
 					let network = network.clone();
 					let block_handler = block_handler.clone();
-					async move { (block_handler)(block, network).await }
+					let block_timestamp_ms = block.timestamp_ms();
+					async move {
+						let mut processed_block = (block_handler)(block, network).await;
+						processed_block.set_timing(AlertTiming {
+							block_timestamp_ms,
+							fetched_at_ms,
+							matched_at_ms: Utc::now().timestamp_millis(),
+						});
+						processed_block
+					}
 				})
 				.buffer_unordered(32);
 
@@ -468,10 +701,12 @@ pub async fn process_new_blocks<
 		let network = network.clone();
 		let trigger_handler = trigger_handler.clone();
 		let block_tracker = block_tracker.clone();
+		let leader_elector = leader_elector.clone();
 
 		async move {
 			let mut trigger_rx = trigger_rx;
 			let mut pending_blocks = BTreeMap::new();
+			let mut trigger_handles = Vec::new();
 			let mut next_block_number = Some(start_block);
 			let block_tracker = block_tracker.clone();
 
@@ -519,7 +754,11 @@ pub async fn process_new_blocks<
 							}
 						}
 
-						(trigger_handler)(&block);
+						if holds_lease(leader_elector.as_deref()) {
+							trigger_handles.push((trigger_handler)(&block));
+						} else {
+							log_lost_lease(&network, expected);
+						}
 						next_block_number = Some(expected + 1);
 					} else {
 						break;
@@ -563,9 +802,17 @@ pub async fn process_new_blocks<
 						}
 					}
 
-					(trigger_handler)(&block);
+					if holds_lease(leader_elector.as_deref()) {
+						trigger_handles.push((trigger_handler)(&block));
+					} else {
+						log_lost_lease(&network, min_block);
+					}
 				}
 			}
+
+			// Wait for the matches to be handed over, so that a slow dispatch holds back block
+			// processing instead of piling up
+			futures::future::join_all(trigger_handles).await;
 			Ok::<(), BlockWatcherError>(())
 		}
 	});
@@ -597,6 +844,16 @@ pub async fn process_new_blocks<
 	// Wait for both pipeline stages to complete
 	let (_process_result, _trigger_result) = tokio::join!(process_handle, trigger_handle);
 
+	if !holds_lease(leader_elector.as_deref()) {
+		tracing::warn!(
+			network = %network.slug,
+			"Lost the leader lease, leaving blocks {} to {} to the new leader",
+			start_block,
+			latest_confirmed_block
+		);
+		return Ok(());
+	}
+
 	if network.store_blocks.unwrap_or(false) {
 		// Delete old blocks before saving new ones
 		block_storage
@@ -623,3 +880,13 @@ pub async fn process_new_blocks<
 
 	Ok(())
 }
+
+/// Logs the matches of a block dropped because the leader lease was lost
+fn log_lost_lease(network: &Network, block_number: u64) {
+	tracing::warn!(
+		network = %network.slug,
+		block_number = block_number,
+		"Lost the leader lease, not dispatching the matches of block {}",
+		block_number
+	);
+}
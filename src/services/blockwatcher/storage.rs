@@ -4,14 +4,29 @@
 //! blockchain blocks and tracking processing state. Currently supports:
 //! - File-based storage with JSON serialization
 //! - Last processed block tracking
+//! - Failed block tracking
 //! - Block deletion for cleanup
 
 use async_trait::async_trait;
 use glob::glob;
+use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 
 use crate::models::BlockType;
 
+/// Block that could not be fetched within its retry budget
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FailedBlock {
+	/// Number of the block
+	pub block_number: u64,
+	/// Error returned by the last fetch attempt
+	pub error: String,
+	/// Number of fetch attempts made so far
+	pub attempts: u32,
+	/// Unix time in milliseconds of the last fetch attempt
+	pub last_attempt_ms: i64,
+}
+
 /// Interface for block storage implementations
 ///
 /// Defines the required functionality for storing and retrieving blocks
@@ -80,6 +95,29 @@ pub trait BlockStorage: Clone + Send + Sync {
 		network_id: &str,
 		blocks: &[u64],
 	) -> Result<(), anyhow::Error>;
+
+	/// Retrieves the blocks that could not be fetched for a network
+	///
+	/// # Arguments
+	/// * `network_id` - Unique identifier for the network
+	///
+	/// # Returns
+	/// * `Result<Vec<FailedBlock>, anyhow::Error>` - Failed blocks ordered by block number
+	async fn get_failed_blocks(&self, network_id: &str) -> Result<Vec<FailedBlock>, anyhow::Error>;
+
+	/// Replaces the blocks that could not be fetched for a network
+	///
+	/// # Arguments
+	/// * `network_id` - Unique identifier for the network
+	/// * `blocks` - Failed blocks to store
+	///
+	/// # Returns
+	/// * `Result<(), anyhow::Error>` - Success or error
+	async fn save_failed_blocks(
+		&self,
+		network_id: &str,
+		blocks: &[FailedBlock],
+	) -> Result<(), anyhow::Error>;
 }
 
 /// File-based implementation of block storage
@@ -241,6 +279,58 @@ impl BlockStorage for FileBlockStorage {
 
 		Ok(())
 	}
+
+	/// Retrieves the failed blocks from a network-specific file
+	///
+	/// The file is named "{network_id}_failed_blocks.json"
+	async fn get_failed_blocks(&self, network_id: &str) -> Result<Vec<FailedBlock>, anyhow::Error> {
+		let file_path = self
+			.storage_path
+			.join(format!("{}_failed_blocks.json", network_id));
+
+		if !file_path.exists() {
+			return Ok(Vec::new());
+		}
+
+		let content = tokio::fs::read_to_string(file_path)
+			.await
+			.map_err(|e| anyhow::anyhow!("Failed to read failed blocks: {}", e))?;
+		let mut blocks: Vec<FailedBlock> = serde_json::from_str(&content)
+			.map_err(|e| anyhow::anyhow!("Failed to parse failed blocks: {}", e))?;
+		blocks.sort_by_key(|b| b.block_number);
+		Ok(blocks)
+	}
+
+	/// Saves the failed blocks to a network-specific file
+	///
+	/// # Note
+	/// Overwrites any existing failed blocks file for the network, and removes it when there
+	/// are no failed blocks left
+	async fn save_failed_blocks(
+		&self,
+		network_id: &str,
+		blocks: &[FailedBlock],
+	) -> Result<(), anyhow::Error> {
+		let file_path = self
+			.storage_path
+			.join(format!("{}_failed_blocks.json", network_id));
+
+		if blocks.is_empty() {
+			if file_path.exists() {
+				tokio::fs::remove_file(file_path)
+					.await
+					.map_err(|e| anyhow::anyhow!("Failed to delete failed blocks: {}", e))?;
+			}
+			return Ok(());
+		}
+
+		let json = serde_json::to_string(blocks)
+			.map_err(|e| anyhow::anyhow!("Failed to serialize failed blocks: {}", e))?;
+		tokio::fs::write(file_path, json)
+			.await
+			.map_err(|e| anyhow::anyhow!("Failed to save failed blocks: {}", e))?;
+		Ok(())
+	}
 }
 
 #[cfg(test)]
@@ -444,4 +534,40 @@ mod tests {
 			assert!(err.to_string().contains("Permission denied"));
 		}
 	}
+
+	#[tokio::test]
+	async fn test_failed_blocks_roundtrip() {
+		let temp_dir = tempfile::tempdir().unwrap();
+		let storage = FileBlockStorage::new(temp_dir.path().to_path_buf());
+
+		// No file yet
+		assert!(storage.get_failed_blocks("test").await.unwrap().is_empty());
+
+		let failed = vec![
+			FailedBlock {
+				block_number: 12,
+				error: "timeout".to_string(),
+				attempts: 4,
+				last_attempt_ms: 1,
+			},
+			FailedBlock {
+				block_number: 10,
+				error: "truncated body".to_string(),
+				attempts: 4,
+				last_attempt_ms: 1,
+			},
+		];
+		storage.save_failed_blocks("test", &failed).await.unwrap();
+
+		let stored = storage.get_failed_blocks("test").await.unwrap();
+		assert_eq!(
+			stored.iter().map(|b| b.block_number).collect::<Vec<_>>(),
+			vec![10, 12]
+		);
+
+		// Saving an empty list removes the file
+		storage.save_failed_blocks("test", &[]).await.unwrap();
+		assert!(!temp_dir.path().join("test_failed_blocks.json").exists());
+		assert!(storage.get_failed_blocks("test").await.unwrap().is_empty());
+	}
 }
@@ -0,0 +1,521 @@
+//! Rolling activity baselines of monitors.
+//!
+//! Matches of a monitor with a `baseline` condition are candidates: they are aggregated per
+//! monitor and network, as a count or a sum of a decoded argument, over a short and a long
+//! window of blocks. Once the warm-up period has passed, the candidate pushing the short window
+//! value above `factor` times the long window average is kept with the aggregates filled in,
+//! and other candidates are dropped. The condition fires again once the value went back below
+//! the threshold.
+//!
+//! The short window is kept per block and older activity in buckets the length of the short
+//! window, so the state of a monitor stays bounded. The state is written to a state store after
+//! each block it changed in and restored at startup.
+
+use serde::{Deserialize, Serialize};
+use std::{
+	collections::{BTreeMap, HashMap, HashSet},
+	sync::{Arc, Mutex},
+};
+
+use crate::{
+	models::{
+		BaselineAggregate, BaselineCondition, EVMBaselineActivity, EVMMonitorMatch, Monitor,
+		MonitorMatch, ProcessedBlock,
+	},
+	services::state::StateStore,
+};
+
+/// Default file the baselines are persisted to when no state store is configured
+pub const DEFAULT_BASELINE_STATE_FILE: &str = "data/baselines.json";
+
+/// Prefix of the state store keys holding the baseline of a monitor on a network
+const STATE_KEY_PREFIX: &str = "baseline:";
+
+/// Aggregated activity of a monitor on a network
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct BaselineState {
+	/// Condition the activity is aggregated for
+	condition: BaselineCondition,
+	/// First block observed
+	first_block: u64,
+	/// Latest block observed
+	last_block: u64,
+	/// Latest block whose candidates were aggregated
+	aggregated_block: Option<u64>,
+	/// Activity of the blocks of the short window, keyed by block number
+	recent: BTreeMap<u64, f64>,
+	/// Activity of older blocks, keyed by block number divided by the short window length
+	buckets: BTreeMap<u64, f64>,
+	/// Whether the short window value is above the threshold
+	firing: bool,
+}
+
+impl BaselineState {
+	fn new(condition: &BaselineCondition, block_number: u64) -> Self {
+		Self {
+			condition: condition.clone(),
+			first_block: block_number,
+			last_block: block_number,
+			aggregated_block: None,
+			recent: BTreeMap::new(),
+			buckets: BTreeMap::new(),
+			firing: false,
+		}
+	}
+
+	/// Returns whether the state was aggregated the same way as the condition requires
+	fn is_recorded_with(&self, condition: &BaselineCondition) -> bool {
+		self.condition.aggregate == condition.aggregate
+			&& self.condition.amount == condition.amount
+			&& self.condition.short_window_blocks == condition.short_window_blocks
+			&& self.condition.long_window_blocks == condition.long_window_blocks
+	}
+
+	/// Moves the activity that left the short window to the buckets and drops the activity
+	/// that left the long window
+	fn advance(&mut self, block_number: u64) {
+		let short = self.condition.short_window_blocks.max(1);
+		self.last_block = self.last_block.max(block_number);
+		while let Some(entry) = self.recent.first_entry() {
+			if *entry.key() + short > self.last_block {
+				break;
+			}
+			let (number, value) = entry.remove_entry();
+			*self.buckets.entry(number / short).or_default() += value;
+		}
+		while let Some(entry) = self.buckets.first_entry() {
+			let bucket_end = (*entry.key() + 1) * short;
+			if bucket_end + self.condition.long_window_blocks > self.last_block {
+				break;
+			}
+			entry.remove();
+		}
+	}
+
+	/// Returns the activity of the short window compared with the long window average
+	fn activity(&self) -> EVMBaselineActivity {
+		let short_window_blocks = self.condition.short_window_blocks;
+		let short_window_value = self.recent.values().sum::<f64>();
+		// Blocks observed before the short window, up to the length of the long window
+		let observed =
+			(self.last_block - self.first_block + 1).min(self.condition.long_window_blocks);
+		let averaged_blocks = observed.saturating_sub(short_window_blocks).max(1);
+		let long_window_average = self.buckets.values().sum::<f64>() * short_window_blocks as f64
+			/ averaged_blocks as f64;
+		EVMBaselineActivity {
+			short_window_value,
+			long_window_average,
+			ratio: short_window_value / long_window_average.max(self.condition.floor),
+		}
+	}
+
+	/// Returns whether the short window value exceeds the threshold once warmed up
+	fn is_above_threshold(&self, activity: &EVMBaselineActivity) -> bool {
+		self.last_block - self.first_block >= self.condition.warmup_blocks()
+			&& activity.ratio > self.condition.factor
+	}
+}
+
+/// Tracks the activity baselines of the monitors with a `baseline` condition
+#[derive(Default)]
+pub struct BaselineTracker {
+	store: Option<Arc<dyn StateStore>>,
+	state: Mutex<HashMap<String, BaselineState>>,
+	/// Keys of the states changed since they were last persisted
+	dirty: Mutex<HashSet<String>>,
+	/// Serializes the writes to the store, so that a state is never overwritten by an older one
+	persisting: tokio::sync::Mutex<()>,
+}
+
+impl BaselineTracker {
+	/// Creates a tracker kept in memory only
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Creates a tracker persisted to the given store
+	///
+	/// # Arguments
+	/// * `store` - Store the baselines are read from by [`Self::restore`] and written to by
+	///   [`Self::persist`]
+	pub fn with_store(store: Arc<dyn StateStore>) -> Self {
+		Self {
+			store: Some(store),
+			..Self::default()
+		}
+	}
+
+	/// Reads the persisted baselines of the given monitors on each of their networks
+	///
+	/// # Returns
+	/// * `usize` - The number of baselines restored
+	pub async fn restore(&self, monitors: &[Monitor]) -> usize {
+		let Some(store) = &self.store else {
+			return 0;
+		};
+		let mut restored = HashMap::new();
+		for monitor in monitors {
+			let Some(condition) = &monitor.match_conditions.baseline else {
+				continue;
+			};
+			for network_slug in &monitor.networks {
+				let key = baseline_key(network_slug, &monitor.name);
+				let value = match store.get(&format!("{}{}", STATE_KEY_PREFIX, key)).await {
+					Ok(Some(value)) => value,
+					Ok(None) => continue,
+					Err(e) => {
+						tracing::error!(
+							"Failed to read the baseline of monitor '{}' on {}, starting over: {}",
+							monitor.name,
+							network_slug,
+							e
+						);
+						continue;
+					}
+				};
+				match serde_json::from_str::<BaselineState>(&value) {
+					Ok(state) if state.is_recorded_with(condition) => {
+						restored.insert(key, state);
+					}
+					Ok(_) => {}
+					Err(e) => {
+						tracing::error!(
+							"Failed to parse the baseline of monitor '{}' on {}, starting over: {}",
+							monitor.name,
+							network_slug,
+							e
+						);
+					}
+				}
+			}
+		}
+		let count = restored.len();
+		self.lock_state().extend(restored);
+		count
+	}
+
+	/// Aggregates the baseline candidates of a block and keeps those crossing the threshold
+	///
+	/// Blocks must be passed in block order. Matches of monitors without a `baseline` condition
+	/// are left untouched.
+	///
+	/// # Arguments
+	/// * `block` - The processed block
+	///
+	/// # Returns
+	/// * `ProcessedBlock` - The block with its baseline candidates resolved
+	pub fn resolve(&self, mut block: ProcessedBlock) -> ProcessedBlock {
+		let has_candidates = block
+			.processing_results
+			.iter()
+			.any(|m| baseline_condition(m).is_some());
+		let mut states = self.lock_state();
+		if !has_candidates
+			&& !states
+				.keys()
+				.any(|key| is_network_key(key, &block.network_slug))
+		{
+			return block;
+		}
+
+		let mut changed = HashSet::new();
+		// Whether the candidates of each monitor are aggregated, as a block processed again
+		// after a restart was already aggregated
+		let mut aggregated = HashMap::new();
+		let mut results = Vec::with_capacity(block.processing_results.len());
+		for monitor_match in std::mem::take(&mut block.processing_results) {
+			let MonitorMatch::EVM(evm_match) = monitor_match else {
+				results.push(monitor_match);
+				continue;
+			};
+			let Some(condition) = evm_match.monitor.match_conditions.baseline.clone() else {
+				results.push(MonitorMatch::EVM(evm_match));
+				continue;
+			};
+			let key = baseline_key(&evm_match.network_slug, &evm_match.monitor.name);
+			let state = states
+				.entry(key.clone())
+				.or_insert_with(|| BaselineState::new(&condition, block.block_number));
+			if !state.is_recorded_with(&condition) {
+				*state = BaselineState::new(&condition, block.block_number);
+			}
+			let is_aggregated = *aggregated.entry(key.clone()).or_insert_with(|| {
+				state
+					.aggregated_block
+					.is_none_or(|number| number < block.block_number)
+			});
+			if !is_aggregated {
+				continue;
+			}
+			state.condition = condition;
+			state.aggregated_block = Some(block.block_number);
+			changed.insert(key);
+			if let Some(activity) = observe(state, &evm_match, block.block_number) {
+				results.push(MonitorMatch::EVM(Box::new(EVMMonitorMatch {
+					baseline: Some(activity),
+					..*evm_match
+				})));
+			}
+		}
+		block.processing_results = results;
+
+		// Re-arm the conditions of the network whose activity went back below the threshold
+		for (key, state) in states.iter_mut() {
+			if !is_network_key(key, &block.network_slug) || changed.contains(key) {
+				continue;
+			}
+			state.advance(block.block_number);
+			if state.firing && !state.is_above_threshold(&state.activity()) {
+				state.firing = false;
+				changed.insert(key.clone());
+			}
+		}
+		drop(states);
+		self.lock_dirty().extend(changed);
+		block
+	}
+
+	/// Writes the baselines changed since the last call to the store, logging failures
+	pub async fn persist(&self) {
+		let Some(store) = &self.store else {
+			return;
+		};
+		let _persisting = self.persisting.lock().await;
+		let keys = std::mem::take(&mut *self.lock_dirty());
+		let states = {
+			let states = self.lock_state();
+			keys.into_iter()
+				.filter_map(|key| states.get(&key).map(|state| (key, state.clone())))
+				.collect::<Vec<_>>()
+		};
+		for (key, state) in states {
+			let result = match serde_json::to_string(&state) {
+				Ok(value) => {
+					store
+						.set(&format!("{}{}", STATE_KEY_PREFIX, key), &value, None)
+						.await
+				}
+				Err(e) => Err(e.into()),
+			};
+			if let Err(e) = result {
+				tracing::error!("Failed to persist the baseline {}: {}", key, e);
+			}
+		}
+	}
+
+	fn lock_state(&self) -> std::sync::MutexGuard<'_, HashMap<String, BaselineState>> {
+		self.state.lock().unwrap_or_else(|e| e.into_inner())
+	}
+
+	fn lock_dirty(&self) -> std::sync::MutexGuard<'_, HashSet<String>> {
+		self.dirty.lock().unwrap_or_else(|e| e.into_inner())
+	}
+}
+
+/// Returns the baseline condition of a candidate
+fn baseline_condition(monitor_match: &MonitorMatch) -> Option<&BaselineCondition> {
+	match monitor_match {
+		MonitorMatch::EVM(evm_match) => evm_match.monitor.match_conditions.baseline.as_ref(),
+		_ => None,
+	}
+}
+
+/// Key of the baseline of a monitor on a network
+fn baseline_key(network_slug: &str, monitor_name: &str) -> String {
+	format!("{}|{}", network_slug, monitor_name)
+}
+
+fn is_network_key(key: &str, network_slug: &str) -> bool {
+	key.split('|').next() == Some(network_slug)
+}
+
+/// Records the activity of a candidate and returns the aggregates if it pushes the short window
+/// value above the threshold
+fn observe(
+	state: &mut BaselineState,
+	evm_match: &EVMMonitorMatch,
+	block_number: u64,
+) -> Option<EVMBaselineActivity> {
+	state.advance(block_number);
+	*state.recent.entry(block_number).or_default() += match_value(&state.condition, evm_match);
+
+	let activity = state.activity();
+	let above = state.is_above_threshold(&activity);
+	let crossed = above && !state.firing;
+	state.firing = above;
+	crossed.then_some(activity)
+}
+
+/// Returns the contribution of a match to its baseline
+///
+/// The `count` aggregate counts each match once. The `sum` aggregate adds the decoded
+/// arguments named by the condition's `amount` of the matched functions and events, ignoring
+/// values that are not numbers.
+fn match_value(condition: &BaselineCondition, evm_match: &EVMMonitorMatch) -> f64 {
+	match condition.aggregate {
+		BaselineAggregate::Count => 1.0,
+		BaselineAggregate::Sum => {
+			let Some(amount) = &condition.amount else {
+				return 0.0;
+			};
+			let Some(args) = &evm_match.matched_on_args else {
+				return 0.0;
+			};
+			args.functions
+				.iter()
+				.chain(args.events.iter())
+				.flatten()
+				.filter_map(|params| params.args.as_ref())
+				.flatten()
+				.filter(|param| &param.name == amount)
+				.filter_map(|param| param.value.parse::<f64>().ok())
+				.sum()
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{
+		models::{EVMMatchArguments, EVMMatchParamEntry, EVMMatchParamsMap},
+		services::state::InMemoryStateStore,
+		utils::tests::builders::evm::{
+			monitor::MonitorBuilder, monitor_match::MonitorMatchBuilder,
+		},
+	};
+
+	fn condition() -> BaselineCondition {
+		BaselineCondition {
+			aggregate: BaselineAggregate::Count,
+			amount: None,
+			short_window_blocks: 10,
+			long_window_blocks: 100,
+			factor: 3.0,
+			warmup_blocks: None,
+			floor: 1.0,
+		}
+	}
+
+	fn monitor(condition: &BaselineCondition) -> Monitor {
+		MonitorBuilder::new()
+			.name("outbound_volume")
+			.networks(vec!["ethereum_mainnet".to_string()])
+			.baseline(condition.clone())
+			.build()
+	}
+
+	fn candidate(condition: &BaselineCondition) -> MonitorMatch {
+		MonitorMatchBuilder::new()
+			.monitor(monitor(condition))
+			.build()
+	}
+
+	fn block(number: u64, processing_results: Vec<MonitorMatch>) -> ProcessedBlock {
+		ProcessedBlock {
+			block_number: number,
+			network_slug: "ethereum_mainnet".to_string(),
+			processing_results,
+		}
+	}
+
+	fn activities(block: &ProcessedBlock) -> Vec<EVMBaselineActivity> {
+		block
+			.processing_results
+			.iter()
+			.filter_map(|m| match m {
+				MonitorMatch::EVM(m) => m.baseline.clone(),
+				_ => None,
+			})
+			.collect()
+	}
+
+	/// Feeds one candidate every 5 blocks from `from` to `to`, returning the matches
+	fn steady(tracker: &BaselineTracker, from: u64, to: u64) -> Vec<EVMBaselineActivity> {
+		(from..=to)
+			.flat_map(|number| {
+				let candidates = if number % 5 == 0 {
+					vec![candidate(&condition())]
+				} else {
+					vec![]
+				};
+				activities(&tracker.resolve(block(number, candidates)))
+			})
+			.collect()
+	}
+
+	fn spike(tracker: &BaselineTracker, number: u64) -> Vec<EVMBaselineActivity> {
+		activities(&tracker.resolve(block(number, vec![candidate(&condition()); 10])))
+	}
+
+	#[test]
+	fn test_no_match_during_warmup() {
+		let tracker = BaselineTracker::new();
+		assert!(steady(&tracker, 1, 50).is_empty());
+		assert!(spike(&tracker, 51).is_empty());
+	}
+
+	#[test]
+	fn test_spike_matches_once_and_rearms() {
+		let tracker = BaselineTracker::new();
+		assert!(steady(&tracker, 1, 150).is_empty());
+
+		let matches = spike(&tracker, 151);
+		assert_eq!(matches.len(), 1);
+		assert!(matches[0].ratio > 3.0);
+		assert_eq!(matches[0].short_window_value, 7.0);
+		assert!(spike(&tracker, 152).is_empty());
+
+		// Once the activity is back to normal, another spike matches again
+		assert!(steady(&tracker, 153, 300).is_empty());
+		assert_eq!(spike(&tracker, 301).len(), 1);
+	}
+
+	#[test]
+	fn test_sum_aggregates_decoded_amount() {
+		let condition = BaselineCondition {
+			aggregate: BaselineAggregate::Sum,
+			amount: Some("value".to_string()),
+			..condition()
+		};
+		let mut evm_match = MonitorMatchBuilder::new()
+			.monitor(monitor(&condition))
+			.matched_on_args(EVMMatchArguments {
+				functions: None,
+				events: Some(vec![EVMMatchParamsMap {
+					signature: "Transfer(address,address,uint256)".to_string(),
+					args: Some(vec![EVMMatchParamEntry {
+						name: "value".to_string(),
+						value: "250".to_string(),
+						kind: "uint256".to_string(),
+						indexed: false,
+					}]),
+					hex_signature: None,
+					log_index: None,
+				}]),
+			})
+			.build_evm();
+		assert_eq!(match_value(&condition, &evm_match), 250.0);
+		evm_match.matched_on_args = None;
+		assert_eq!(match_value(&condition, &evm_match), 0.0);
+	}
+
+	#[tokio::test]
+	async fn test_baseline_recovered_after_restart() {
+		let store: Arc<dyn StateStore> = Arc::new(InMemoryStateStore::new());
+		let tracker = BaselineTracker::with_store(store.clone());
+		assert!(steady(&tracker, 1, 150).is_empty());
+		tracker.persist().await;
+
+		// A tracker starting over is warming up again
+		assert!(spike(&BaselineTracker::new(), 151).is_empty());
+
+		let restarted = BaselineTracker::with_store(store);
+		assert_eq!(restarted.restore(&[monitor(&condition())]).await, 1);
+		// The block processed again after the restart is not counted twice
+		assert!(steady(&restarted, 150, 150).is_empty());
+		let matches = spike(&restarted, 151);
+		assert_eq!(matches, spike(&tracker, 151));
+		assert_eq!(matches.len(), 1);
+	}
+}
@@ -0,0 +1,361 @@
+//! Surfacing of the ABI decoding failures of monitor conditions.
+//!
+//! Function calls and events whose arguments fail to decode with the ABI of a monitor never
+//! match its conditions, which hides a wrong ABI. The EVM filter collects these failures per
+//! condition while evaluating a monitor, and the tracker records them once the block is done:
+//! each failure is counted in the `abi_decode_failures_total` metric, the first failure of a
+//! condition in a block is logged with its selector or topic and the decoding error, and the
+//! totals are served by the `/decode-failures` endpoint of the metrics server.
+//!
+//! Monitors with `alert_on_decode_failure` raise an alert once a condition failed to decode
+//! `threshold` times on a network. The alert is queued until the trigger handler takes it and
+//! delivers it, as a match of the last failing transaction, to the configured triggers. Each
+//! condition alerts once per network; the tracker is kept in memory, so counts start over after
+//! a restart.
+
+use serde::Serialize;
+use std::{collections::HashMap, sync::Mutex};
+
+use crate::{
+	models::{
+		EVMBaseTransaction, EVMBlock, EVMMonitorMatch, EVMTransaction, MatchConditions, Monitor,
+		MonitorMatch,
+	},
+	services::filter::evm_helpers::b256_to_string,
+	utils::metrics::ABI_DECODE_FAILURES,
+};
+
+/// Function call or event a condition could not decode
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DecodeFailure {
+	/// Signature of the condition, or of the event for monitors matching all events
+	pub condition: String,
+	/// Function selector or event topic, as a hex string
+	pub selector: String,
+	/// Decoding error
+	pub error: String,
+	/// Hash of the transaction that made the call or emitted the event
+	pub transaction_hash: String,
+}
+
+/// Decoding failures collected while evaluating a monitor
+#[derive(Debug, Default)]
+pub struct DecodeFailureLog {
+	failures: Mutex<Vec<DecodeFailure>>,
+}
+
+impl DecodeFailureLog {
+	/// Adds a failure to the log
+	pub fn push(&self, failure: DecodeFailure) {
+		self.failures
+			.lock()
+			.unwrap_or_else(|e| e.into_inner())
+			.push(failure);
+	}
+
+	/// Removes and returns the failures of the log
+	pub fn take(&self) -> Vec<DecodeFailure> {
+		std::mem::take(&mut *self.failures.lock().unwrap_or_else(|e| e.into_inner()))
+	}
+}
+
+/// Alert of a condition that reached the decoding failure threshold of its monitor
+#[derive(Debug, Clone)]
+pub struct DecodeFailureReport {
+	/// Signature of the condition
+	pub condition: String,
+	/// Function selector or event topic of the last failure
+	pub selector: String,
+	/// Decoding error of the last failure
+	pub error: String,
+	/// Number of decoding failures of the condition on the network
+	pub count: u64,
+	/// Match of the monitor for the transaction of the last failure
+	pub monitor_match: MonitorMatch,
+}
+
+/// Decoding failures of a condition on a network
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct DecodeFailureSummary {
+	/// Slug of the network
+	pub network: String,
+	/// Name of the monitor
+	pub monitor: String,
+	/// Signature of the condition
+	pub condition: String,
+	/// Function selector or event topic of the last failure
+	pub selector: String,
+	/// Number of decoding failures
+	pub count: u64,
+	/// Block of the last failure
+	pub last_block: u64,
+	/// Decoding error of the last failure
+	pub last_error: String,
+}
+
+/// Failures of a condition on a network
+#[derive(Debug, Default)]
+struct ConditionFailures {
+	selector: String,
+	count: u64,
+	last_block: u64,
+	last_error: String,
+	/// Whether the first failure of `last_block` was logged
+	logged: bool,
+	alerted: bool,
+}
+
+#[derive(Debug, Default)]
+struct DecodeFailuresState {
+	/// Failures by network, monitor and condition
+	conditions: HashMap<(String, String, String), ConditionFailures>,
+	/// Alerts waiting to be delivered, by network
+	alerts: HashMap<String, Vec<DecodeFailureReport>>,
+}
+
+/// Decoding failures of the monitor conditions of all networks
+#[derive(Debug, Default)]
+pub struct DecodeFailures {
+	state: Mutex<DecodeFailuresState>,
+}
+
+impl DecodeFailures {
+	/// Records the decoding failures of a monitor in a block
+	///
+	/// # Arguments
+	/// * `network_slug` - Network of the block
+	/// * `monitor` - Monitor whose conditions failed to decode
+	/// * `block` - Block of the failures
+	/// * `failures` - Failures collected while evaluating the monitor
+	pub fn record(
+		&self,
+		network_slug: &str,
+		monitor: &Monitor,
+		block: &EVMBlock,
+		failures: Vec<DecodeFailure>,
+	) {
+		let block_number = block.number().unwrap_or(0);
+		let mut state = self.lock_state();
+		for failure in failures {
+			ABI_DECODE_FAILURES
+				.with_label_values(&[network_slug, monitor.name.as_str(), &failure.condition])
+				.inc();
+
+			let key = (
+				network_slug.to_string(),
+				monitor.name.clone(),
+				failure.condition.clone(),
+			);
+			let entry = state.conditions.entry(key).or_default();
+			if entry.last_block != block_number {
+				entry.logged = false;
+			}
+			if !entry.logged {
+				tracing::warn!(
+					network = %network_slug,
+					monitor = %monitor.name,
+					condition = %failure.condition,
+					selector = %failure.selector,
+					block = block_number,
+					transaction = %failure.transaction_hash,
+					"Failed to decode the arguments of condition '{}' of monitor '{}': {}",
+					failure.condition,
+					monitor.name,
+					failure.error
+				);
+				entry.logged = true;
+			}
+			entry.count += 1;
+			entry.last_block = block_number;
+			entry.selector = failure.selector.clone();
+			entry.last_error = failure.error.clone();
+
+			let Some(alert) = &monitor.alert_on_decode_failure else {
+				continue;
+			};
+			if entry.alerted || entry.count < alert.threshold {
+				continue;
+			}
+			entry.alerted = true;
+			let count = entry.count;
+			let report = DecodeFailureReport {
+				monitor_match: failure_match(network_slug, monitor, block, &failure),
+				condition: failure.condition,
+				selector: failure.selector,
+				error: failure.error,
+				count,
+			};
+			state
+				.alerts
+				.entry(network_slug.to_string())
+				.or_default()
+				.push(report);
+		}
+	}
+
+	/// Removes and returns the alerts of a network waiting to be delivered
+	pub fn take_alerts(&self, network_slug: &str) -> Vec<DecodeFailureReport> {
+		self.lock_state()
+			.alerts
+			.remove(network_slug)
+			.unwrap_or_default()
+	}
+
+	/// Returns the decoding failures of each condition, by network, monitor and condition
+	pub fn summary(&self) -> Vec<DecodeFailureSummary> {
+		let state = self.lock_state();
+		let mut summary = state
+			.conditions
+			.iter()
+			.map(
+				|((network, monitor, condition), failures)| DecodeFailureSummary {
+					network: network.clone(),
+					monitor: monitor.clone(),
+					condition: condition.clone(),
+					selector: failures.selector.clone(),
+					count: failures.count,
+					last_block: failures.last_block,
+					last_error: failures.last_error.clone(),
+				},
+			)
+			.collect::<Vec<_>>();
+		summary.sort_by(|a, b| {
+			(&a.network, &a.monitor, &a.condition).cmp(&(&b.network, &b.monitor, &b.condition))
+		});
+		summary
+	}
+
+	fn lock_state(&self) -> std::sync::MutexGuard<'_, DecodeFailuresState> {
+		self.state.lock().unwrap_or_else(|e| e.into_inner())
+	}
+}
+
+/// Builds the match of a monitor for the transaction of a decoding failure
+///
+/// The transaction is looked up in the block, and only carries its hash and block otherwise.
+fn failure_match(
+	network_slug: &str,
+	monitor: &Monitor,
+	block: &EVMBlock,
+	failure: &DecodeFailure,
+) -> MonitorMatch {
+	let transaction = block
+		.transactions
+		.iter()
+		.find(|transaction| b256_to_string(transaction.hash) == failure.transaction_hash)
+		.cloned()
+		.unwrap_or_else(|| {
+			EVMTransaction::from(EVMBaseTransaction {
+				hash: failure.transaction_hash.parse().unwrap_or_default(),
+				block_hash: block.hash,
+				block_number: block.number,
+				..Default::default()
+			})
+		});
+	MonitorMatch::EVM(Box::new(EVMMonitorMatch {
+		monitor: monitor.clone(),
+		transaction,
+		receipt: None,
+		logs: None,
+		network_slug: network_slug.to_string(),
+		matched_on: MatchConditions::default(),
+		matched_on_args: None,
+		sender_activity: None,
+		baseline: None,
+		network_metrics: None,
+		timing: None,
+		origin: Default::default(),
+	}))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{
+		models::DecodeFailureAlert,
+		utils::tests::evm::{monitor::MonitorBuilder, transaction::TransactionBuilder},
+	};
+	use alloy::primitives::{B256, U64};
+
+	fn failure(condition: &str, transaction_hash: &str) -> DecodeFailure {
+		DecodeFailure {
+			condition: condition.to_string(),
+			selector: "0xa9059cbb".to_string(),
+			error: "buffer overrun while deserializing".to_string(),
+			transaction_hash: transaction_hash.to_string(),
+		}
+	}
+
+	fn block(number: u64, transactions: Vec<EVMTransaction>) -> EVMBlock {
+		let mut block = EVMBlock::default();
+		block.0.number = Some(U64::from(number));
+		block.0.transactions = transactions;
+		block
+	}
+
+	#[test]
+	fn test_record_counts_failures_per_condition() {
+		let failures = DecodeFailures::default();
+		let monitor = MonitorBuilder::new().name("decode_failures_counts").build();
+		let hash = b256_to_string(B256::with_last_byte(1));
+		failures.record(
+			"ethereum_mainnet",
+			&monitor,
+			&block(1, vec![]),
+			vec![
+				failure("transfer(address,uint256)", &hash),
+				failure("transfer(address,uint256)", &hash),
+				failure("approve(address,uint256)", &hash),
+			],
+		);
+
+		let summary = failures.summary();
+		assert_eq!(summary.len(), 2);
+		assert_eq!(summary[0].condition, "approve(address,uint256)");
+		assert_eq!(summary[0].count, 1);
+		assert_eq!(summary[1].condition, "transfer(address,uint256)");
+		assert_eq!(summary[1].count, 2);
+		assert_eq!(summary[1].last_block, 1);
+		assert!(failures.take_alerts("ethereum_mainnet").is_empty());
+	}
+
+	#[test]
+	fn test_record_alerts_once_at_threshold() {
+		let failures = DecodeFailures::default();
+		let monitor = MonitorBuilder::new()
+			.name("decode_failures_alert")
+			.alert_on_decode_failure(DecodeFailureAlert {
+				threshold: 2,
+				triggers: vec![],
+			})
+			.build();
+		let transaction = TransactionBuilder::new()
+			.hash(B256::with_last_byte(2))
+			.build();
+		let hash = b256_to_string(transaction.hash);
+
+		failures.record(
+			"ethereum_mainnet",
+			&monitor,
+			&block(1, vec![transaction.clone()]),
+			vec![failure("transfer(address,uint256)", &hash)],
+		);
+		assert!(failures.take_alerts("ethereum_mainnet").is_empty());
+
+		failures.record(
+			"ethereum_mainnet",
+			&monitor,
+			&block(2, vec![transaction.clone()]),
+			vec![
+				failure("transfer(address,uint256)", &hash),
+				failure("transfer(address,uint256)", &hash),
+			],
+		);
+		let alerts = failures.take_alerts("ethereum_mainnet");
+		assert_eq!(alerts.len(), 1);
+		assert_eq!(alerts[0].condition, "transfer(address,uint256)");
+		assert_eq!(alerts[0].count, 2);
+		assert_eq!(alerts[0].monitor_match.transaction_hash(), hash);
+		assert!(failures.take_alerts("ethereum_mainnet").is_empty());
+	}
+}
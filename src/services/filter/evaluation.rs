@@ -0,0 +1,351 @@
+//! Evaluation of a monitor against a single transaction.
+//!
+//! Runs the filter path of a monitor for one transaction without dispatching anything, and
+//! explains the outcome of each of its match conditions: whether its expression parses, what
+//! it evaluates to, and the decoded arguments it was evaluated against.
+
+use serde::Serialize;
+use std::marker::PhantomData;
+
+use crate::{
+	models::{
+		ContractSpec, EVMContractSpec, EVMMatchArguments, EVMMatchParamEntry, EVMReceiptLog,
+		EVMTransaction, EVMTransactionReceipt, EventCondition, FunctionCondition, MatchConditions,
+		Monitor, MonitorMatch, TransactionStatus,
+	},
+	services::{
+		blockchain::EVMTransportClient,
+		filter::{evm_helpers, expression, AnyEmitterSpecs, EVMBlockFilter, PriceOracles},
+	},
+};
+
+/// Kind of a match condition
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConditionKind {
+	Transaction,
+	Function,
+	Event,
+}
+
+/// Error raised by the expression of a condition
+#[derive(Debug, Clone, Serialize)]
+pub struct ConditionError {
+	pub message: String,
+	/// Offset in the expression the parser failed at, if it failed to parse
+	pub position: Option<usize>,
+}
+
+/// Outcome of a single match condition
+#[derive(Debug, Clone, Serialize)]
+pub struct ConditionReport {
+	pub kind: ConditionKind,
+	/// Signature of a function or event condition
+	pub signature: Option<String>,
+	/// Status required by a transaction condition
+	pub status: Option<TransactionStatus>,
+	pub expression: Option<String>,
+	/// Whether the expression parses, `true` without an expression
+	pub parsed: bool,
+	/// Whether the condition matched, `None` if its expression failed
+	pub result: Option<bool>,
+	/// Arguments the expression was evaluated against, `None` if the function or event
+	/// was not found in the transaction
+	pub args: Option<Vec<EVMMatchParamEntry>>,
+	pub error: Option<ConditionError>,
+}
+
+/// Outcome of a monitor for a single transaction
+#[derive(Debug, Clone, Serialize)]
+pub struct EvaluationReport {
+	pub monitor: String,
+	pub network: String,
+	pub transaction_hash: String,
+	/// Whether the monitor matches the transaction, as it would when processing its block
+	pub matched: bool,
+	/// Conditions the match was made on
+	pub matched_on: Option<MatchConditions>,
+	pub conditions: Vec<ConditionReport>,
+}
+
+/// Evaluates an EVM monitor against a transaction
+///
+/// # Arguments
+/// * `monitor` - Monitor to evaluate
+/// * `network_slug` - Network the transaction belongs to
+/// * `transaction` - The transaction to evaluate
+/// * `receipt` - Receipt of the transaction, providing its status and `gas_used`
+/// * `logs` - Logs emitted by the transaction
+/// * `price_oracles` - Price oracles the latest prices of the network are read from
+///
+/// # Returns
+/// Report of the monitor outcome and of each of its conditions
+pub fn evaluate_evm_transaction(
+	monitor: &Monitor,
+	network_slug: &str,
+	transaction: &EVMTransaction,
+	receipt: Option<EVMTransactionReceipt>,
+	logs: &[EVMReceiptLog],
+	price_oracles: &PriceOracles,
+) -> EvaluationReport {
+	let filter = EVMBlockFilter::<EVMTransportClient> {
+		_client: PhantomData,
+		price_oracles: None,
+		prices: price_oracles.cached(network_slug),
+		decode_failures: Default::default(),
+		decode_failure_tracker: None,
+	};
+	let contract_specs = monitor
+		.addresses
+		.iter()
+		.filter_map(|address| match &address.contract_spec {
+			Some(ContractSpec::EVM(spec)) => Some((
+				format!("0x{}", evm_helpers::normalize_address(&address.address)),
+				spec.clone(),
+			)),
+			_ => None,
+		})
+		.collect::<Vec<(String, EVMContractSpec)>>();
+
+	let matched_on = filter
+		.filter_transaction(
+			monitor,
+			transaction,
+			logs,
+			receipt.clone(),
+			&contract_specs,
+			&AnyEmitterSpecs::new(monitor),
+			network_slug,
+		)
+		.into_iter()
+		.find_map(|monitor_match| match monitor_match {
+			MonitorMatch::EVM(evm_match) => Some(evm_match.matched_on),
+			_ => None,
+		});
+
+	let tx_status = match &receipt {
+		Some(receipt) if !receipt.status.is_some_and(|s| s.to::<u64>() == 1) => {
+			TransactionStatus::Failure
+		}
+		_ => TransactionStatus::Success,
+	};
+
+	let mut conditions = Vec::new();
+	for condition in &monitor.match_conditions.transactions {
+		let status_matches =
+			condition.status == TransactionStatus::Any || condition.status == tx_status;
+		let mut report = evaluate_condition(
+			&filter,
+			ConditionKind::Transaction,
+			None,
+			condition.expression.as_deref(),
+			vec![Some(filter.transaction_params(transaction, &receipt))],
+		);
+		report.status = Some(condition.status);
+		if !status_matches && report.result.is_some() {
+			report.result = Some(false);
+		}
+		conditions.push(report);
+	}
+
+	for condition in &monitor.match_conditions.functions {
+		let mut matched_on_args = EVMMatchArguments {
+			events: Some(Vec::new()),
+			functions: Some(Vec::new()),
+		};
+		filter.find_matching_functions_for_transaction(
+			&contract_specs,
+			transaction,
+			&Monitor {
+				match_conditions: MatchConditions {
+					functions: vec![FunctionCondition {
+						signature: condition.signature.clone(),
+						expression: None,
+					}],
+					..Default::default()
+				},
+				..monitor.clone()
+			},
+			&mut Vec::new(),
+			&mut matched_on_args,
+		);
+		let calls = matched_on_args
+			.functions
+			.unwrap_or_default()
+			.into_iter()
+			.map(|call| call.args)
+			.collect();
+		conditions.push(evaluate_condition(
+			&filter,
+			ConditionKind::Function,
+			Some(&condition.signature),
+			condition.expression.as_deref(),
+			calls,
+		));
+	}
+
+	for condition in &monitor.match_conditions.events {
+		let mut matched_on_args = EVMMatchArguments {
+			events: Some(Vec::new()),
+			functions: Some(Vec::new()),
+		};
+		let condition_monitor = Monitor {
+			match_conditions: MatchConditions {
+				events: vec![EventCondition {
+					signature: condition.signature.clone(),
+					expression: None,
+					match_any_emitter: condition.match_any_emitter,
+				}],
+				..Default::default()
+			},
+			..monitor.clone()
+		};
+		filter.find_matching_events_for_transaction(
+			logs,
+			&condition_monitor,
+			&AnyEmitterSpecs::new(&condition_monitor),
+			&mut Vec::new(),
+			&mut matched_on_args,
+			&mut Vec::new(),
+		);
+		let events = matched_on_args
+			.events
+			.unwrap_or_default()
+			.into_iter()
+			.map(|event| event.args)
+			.collect();
+		conditions.push(evaluate_condition(
+			&filter,
+			ConditionKind::Event,
+			Some(&condition.signature),
+			condition.expression.as_deref(),
+			events,
+		));
+	}
+
+	EvaluationReport {
+		monitor: monitor.name.clone(),
+		network: network_slug.to_string(),
+		transaction_hash: evm_helpers::b256_to_string(transaction.hash),
+		matched: matched_on.is_some(),
+		matched_on,
+		conditions,
+	}
+}
+
+/// Evaluates the expression of a condition against the arguments of each occurrence of its
+/// function or event, matching if any of them does
+fn evaluate_condition(
+	filter: &EVMBlockFilter<EVMTransportClient>,
+	kind: ConditionKind,
+	signature: Option<&str>,
+	expression: Option<&str>,
+	occurrences: Vec<Option<Vec<EVMMatchParamEntry>>>,
+) -> ConditionReport {
+	let mut report = ConditionReport {
+		kind,
+		signature: signature.map(str::to_string),
+		status: None,
+		expression: expression.map(str::to_string),
+		parsed: true,
+		result: Some(false),
+		args: None,
+		error: None,
+	};
+
+	let Some(expression) = expression else {
+		report.result = Some(!occurrences.is_empty());
+		report.args = occurrences.into_iter().next().flatten();
+		return report;
+	};
+
+	if let Err(e) = expression::parse(expression) {
+		let reason = e.inner().to_string();
+		report.parsed = false;
+		report.result = None;
+		report.error = Some(ConditionError {
+			message: if reason.is_empty() {
+				"Failed to parse expression".to_string()
+			} else {
+				format!("Failed to parse expression: {}", reason)
+			},
+			position: Some(e.offset()),
+		});
+		return report;
+	}
+
+	for args in occurrences {
+		let params = args.clone().unwrap_or_default();
+		match filter.evaluate_expression(expression, &params) {
+			Ok(true) => {
+				report.result = Some(true);
+				report.args = args;
+				report.error = None;
+				break;
+			}
+			Ok(false) => {
+				report.args.get_or_insert_with(|| params.clone());
+			}
+			Err(e) => {
+				report.result = None;
+				report.args.get_or_insert_with(|| params.clone());
+				report.error.get_or_insert(ConditionError {
+					message: e.to_string(),
+					position: None,
+				});
+			}
+		}
+	}
+
+	report
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::utils::tests::evm::{monitor::MonitorBuilder, transaction::TransactionBuilder};
+	use alloy::primitives::{Address, U256};
+
+	#[test]
+	fn test_evaluate_evm_transaction_reports_each_condition() {
+		let to = Address::with_last_byte(2);
+		let transaction = TransactionBuilder::new()
+			.to(to)
+			.value(U256::from(150))
+			.build();
+		let monitor = MonitorBuilder::new()
+			.name("Large Transfers")
+			.address(&evm_helpers::h160_to_string(to))
+			.transaction(TransactionStatus::Any, Some("value > 100".to_string()))
+			.transaction(TransactionStatus::Success, Some("value >".to_string()))
+			.build();
+
+		let report = evaluate_evm_transaction(
+			&monitor,
+			"ethereum_mainnet",
+			&transaction,
+			None,
+			&[],
+			&PriceOracles::default(),
+		);
+
+		assert!(report.matched);
+		assert_eq!(report.conditions.len(), 2);
+
+		let matching = &report.conditions[0];
+		assert_eq!(matching.kind, ConditionKind::Transaction);
+		assert!(matching.parsed);
+		assert_eq!(matching.result, Some(true));
+		assert!(matching
+			.args
+			.as_ref()
+			.unwrap()
+			.iter()
+			.any(|arg| arg.name == "value" && arg.value == "150"));
+
+		let failing = &report.conditions[1];
+		assert!(!failing.parsed);
+		assert_eq!(failing.result, None);
+		assert!(failing.error.as_ref().unwrap().position.is_some());
+	}
+}
@@ -78,6 +78,20 @@ pub enum ConditionLeft<'a> {
 	Simple(&'a str),
 	/// A sequence of accessors that form a path to a variable (e.g., "person.name", "person[0].age", etc.)
 	Path(VariablePath<'a>),
+	/// A function applied to a variable, with optional literal parameters (e.g., "usd(value, native)")
+	/// The function is applied by the chain-specific evaluator.
+	Call(FunctionCall<'a>),
+}
+
+/// Represents a function applied to a variable on the left side of a condition.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FunctionCall<'a> {
+	/// The name of the function (e.g., "usd")
+	pub name: &'a str,
+	/// The variable the function is applied to, a simple variable name or a path
+	pub argument: Box<ConditionLeft<'a>>,
+	/// The literal parameters following the variable
+	pub parameters: Vec<LiteralValue<'a>>,
 }
 
 impl<'a> ConditionLeft<'a> {
@@ -86,17 +100,20 @@ impl<'a> ConditionLeft<'a> {
 		match self {
 			ConditionLeft::Simple(name) => name,
 			ConditionLeft::Path(path) => path.base,
+			ConditionLeft::Call(call) => call.argument.base_name(),
 		}
 	}
 
 	/// Helper method to get the accessors of the variable path.
 	/// If ConditionLeft is a simple variable, it returns an empty slice.
 	/// If it is a path, it returns the accessors of that path.
+	/// If it is a function call, it returns the accessors of its argument.
 	/// Used during evaluation to traverse nested structures.
 	pub fn accessors(&self) -> &[Accessor<'a>] {
 		match self {
 			ConditionLeft::Simple(_) => &[],
 			ConditionLeft::Path(path) => &path.accessors,
+			ConditionLeft::Call(call) => call.argument.accessors(),
 		}
 	}
 }
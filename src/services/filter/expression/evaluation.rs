@@ -23,4 +23,19 @@ pub trait ConditionEvaluator {
 
 	/// Gets the chain-specific kind of a value from a JSON value
 	fn get_kind_from_json_value(&self, value: &serde_json::Value) -> String;
+
+	/// Applies a function of the left side of a condition (e.g., "usd(value, native)") to the
+	/// resolved value of its argument, returning the resulting value and kind
+	/// Returns `None` if the function has no result for the value, in which case the condition
+	/// does not match
+	fn apply_function(
+		&self,
+		name: &str,
+		_value: &str,
+		_kind: &str,
+		_parameters: &[LiteralValue],
+	) -> Result<Option<(String, String)>, EvaluationError> {
+		let msg = format!("Unsupported function '{}'", name);
+		Err(EvaluationError::unsupported_operator(msg, None, None))
+	}
 }
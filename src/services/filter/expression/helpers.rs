@@ -15,41 +15,12 @@ pub fn evaluate(
 ) -> Result<bool, EvaluationError> {
 	match expression {
 		Expression::Condition(condition) => {
-			let base_name = condition.left.base_name();
-			let accessors = condition.left.accessors();
-			let (base_value_str, base_kind_str) = evaluator.get_base_param(base_name)?;
-
-			let final_left_value_str: String;
-			let final_left_kind: String;
-
-			if accessors.is_empty() {
-				// No accessors, use the base value directly
-				final_left_value_str = base_value_str.to_string();
-				final_left_kind = base_kind_str.to_string();
-			} else {
-				let resolved_value = resolve_path_to_json_value(
-					base_value_str,
-					base_kind_str,
-					accessors,
-					base_name,
-					&condition.left,
-				)?;
-
-				// Get the kind from the resolved JSON value from chain-specific evaluator
-				final_left_kind = evaluator.get_kind_from_json_value(&resolved_value);
-
-				// Convert the resolved JSON value to a string representation
-				final_left_value_str = match resolved_value {
-					serde_json::Value::String(s) => s,
-					serde_json::Value::Number(n) => n.to_string(),
-					serde_json::Value::Bool(b) => b.to_string(),
-					serde_json::Value::Null => "null".to_string(),
-					serde_json::Value::Array(_) | serde_json::Value::Object(_) => {
-						// If the resolved value is an array or object, we need to convert it to a string
-						resolved_value.to_string()
-					}
-				};
-			}
+			let Some((final_left_value_str, final_left_kind)) =
+				resolve_condition_left(&condition.left, evaluator)?
+			else {
+				// The function of the left side has no result, the condition doesn't match
+				return Ok(false);
+			};
 
 			evaluator.compare_final_values(
 				&final_left_kind,
@@ -84,6 +55,75 @@ pub fn evaluate(
 	}
 }
 
+/// Resolves the value and kind of the left side of a condition
+/// Returns `None` if it applies a function that has no result for the value
+fn resolve_condition_left(
+	left: &ConditionLeft<'_>,
+	evaluator: &impl ConditionEvaluator,
+) -> Result<Option<(String, String)>, EvaluationError> {
+	if let ConditionLeft::Call(call) = left {
+		let Some((value, kind)) = resolve_condition_left(&call.argument, evaluator)? else {
+			return Ok(None);
+		};
+		return evaluator.apply_function(call.name, &value, &kind, &call.parameters);
+	}
+
+	let base_name = left.base_name();
+	let accessors = left.accessors();
+	let (base_value_str, base_kind_str) = evaluator.get_base_param(base_name)?;
+
+	if accessors.is_empty() {
+		// No accessors, use the base value directly
+		return Ok(Some((
+			base_value_str.to_string(),
+			base_kind_str.to_string(),
+		)));
+	}
+
+	let resolved_value =
+		resolve_path_to_json_value(base_value_str, base_kind_str, accessors, base_name, left)?;
+
+	// Get the kind from the resolved JSON value from chain-specific evaluator
+	let kind = evaluator.get_kind_from_json_value(&resolved_value);
+
+	// Convert the resolved JSON value to a string representation
+	let value = match resolved_value {
+		serde_json::Value::String(s) => s,
+		serde_json::Value::Number(n) => n.to_string(),
+		serde_json::Value::Bool(b) => b.to_string(),
+		serde_json::Value::Null => "null".to_string(),
+		serde_json::Value::Array(_) | serde_json::Value::Object(_) => {
+			// If the resolved value is an array or object, we need to convert it to a string
+			resolved_value.to_string()
+		}
+	};
+
+	Ok(Some((value, kind)))
+}
+
+/// Collects the base variable names referenced by the conditions of an expression
+/// Returns the names in order of appearance, without duplicates
+pub fn variable_names<'a>(expression: &Expression<'a>) -> Vec<&'a str> {
+	fn collect<'a>(expression: &Expression<'a>, names: &mut Vec<&'a str>) {
+		match expression {
+			Expression::Condition(condition) => {
+				let name = condition.left.base_name();
+				if !names.contains(&name) {
+					names.push(name);
+				}
+			}
+			Expression::Logical { left, right, .. } => {
+				collect(left, names);
+				collect(right, names);
+			}
+		}
+	}
+
+	let mut names = Vec::new();
+	collect(expression, &mut names);
+	names
+}
+
 /// Compares two values implementing the Ord trait using the specified comparison operator
 /// Returns true if the comparison is valid, false otherwise
 /// Returns an error if the operator is not supported for the given types
@@ -216,6 +256,15 @@ mod tests {
 	use crate::services::filter::expression::ast::{ComparisonOperator, VariablePath};
 	use serde_json::json;
 
+	// --- Tests for `variable_names` ---
+	#[test]
+	fn test_variable_names() {
+		let expression =
+			super::super::parse("value > 100 AND (to == '0x1' OR value < 5) AND from.len == 1")
+				.unwrap();
+		assert_eq!(variable_names(&expression), vec!["value", "to", "from"]);
+	}
+
 	// --- Tests for `compare_ordered_values` ---
 	#[test]
 	fn test_compare_ordered_values_integers() {
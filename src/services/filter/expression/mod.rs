@@ -9,5 +9,5 @@ mod parsing;
 pub use ast::{ComparisonOperator, LiteralValue};
 pub use error::EvaluationError;
 pub use evaluation::ConditionEvaluator;
-pub use helpers::{compare_ordered_values, evaluate};
+pub use helpers::{compare_ordered_values, evaluate, variable_names};
 pub use parsing::parse;
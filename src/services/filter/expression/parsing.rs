@@ -3,12 +3,12 @@
 //! The parser converts the input string into an abstract syntax tree (AST) representation of the expression.
 
 use super::ast::{
-	Accessor, ComparisonOperator, Condition, ConditionLeft, Expression, LiteralValue,
+	Accessor, ComparisonOperator, Condition, ConditionLeft, Expression, FunctionCall, LiteralValue,
 	LogicalOperator, VariablePath,
 };
 use winnow::{
 	ascii::{digit1, space0, space1, Caseless},
-	combinator::{alt, delimited, eof, opt, peek, repeat, Repeat},
+	combinator::{alt, delimited, eof, opt, peek, preceded, repeat, Repeat},
 	error::{ContextError, ErrMode, ParseError, StrContext, StrContextValue},
 	prelude::*,
 	token::{literal, one_of, take_while},
@@ -177,11 +177,11 @@ fn parse_accessor<'a>(input: &mut Input<'a>) -> ParserResult<Accessor<'a>> {
 		)),
 		// Ensure it's properly delimited
 		peek(alt((
-			space1.value(()),                                 // space
-			eof.value(()),                                    // end of input
-			literal("[").value(()),                           // start of index accessor
-			literal(".").value(()),                           // start of another key accessor
-			one_of(['=', '!', '>', '<', ')', '(']).value(()), // Operators or delimiters
+			space1.value(()),                                      // space
+			eof.value(()),                                         // end of input
+			literal("[").value(()),                                // start of index accessor
+			literal(".").value(()),                                // start of another key accessor
+			one_of(['=', '!', '>', '<', ')', '(', ',']).value(()), // Operators or delimiters
 		))),
 	)
 		.map(|(_, key_slice, _): (_, &str, _)| Accessor::Key(key_slice))
@@ -227,7 +227,7 @@ fn parse_base_variable_name<'a>(input: &mut Input<'a>) -> ParserResult<&'a str>
 	.parse_next(input)
 }
 
-fn parse_condition_lhs<'a>(input: &mut Input<'a>) -> ParserResult<ConditionLeft<'a>> {
+fn parse_variable<'a>(input: &mut Input<'a>) -> ParserResult<ConditionLeft<'a>> {
 	// Parse the base variable name
 	let base = parse_base_variable_name.parse_next(input)?;
 
@@ -241,6 +241,38 @@ fn parse_condition_lhs<'a>(input: &mut Input<'a>) -> ParserResult<ConditionLeft<
 	}
 }
 
+/// Parses a function applied to a variable (e.g., "usd(value, native)") into `ConditionLeft::Call`
+fn parse_function_call<'a>(input: &mut Input<'a>) -> ParserResult<ConditionLeft<'a>> {
+	let (name, _, argument, parameters, _): (&str, _, ConditionLeft, Vec<LiteralValue>, _) = (
+		(
+			one_of(|c: char| c.is_alpha() || c == '_'),
+			take_while(0.., |c: char| c.is_alphanum() || c == '_'),
+		)
+			.take()
+			.verify(|name: &&str| !is_keyword(name)),
+		(literal("("), space0),
+		parse_variable,
+		repeat(0.., preceded((space0, literal(",")), parse_value)),
+		(space0, literal(")")).context(StrContext::Expected(StrContextValue::Description(
+			"closing parenthesis ')' of function call",
+		))),
+	)
+		.context(StrContext::Expected(StrContextValue::Description(
+			"function call (e.g., usd(value, native))",
+		)))
+		.parse_next(input)?;
+
+	Ok(ConditionLeft::Call(FunctionCall {
+		name,
+		argument: Box::new(argument),
+		parameters,
+	}))
+}
+
+fn parse_condition_lhs<'a>(input: &mut Input<'a>) -> ParserResult<ConditionLeft<'a>> {
+	alt((parse_function_call, parse_variable)).parse_next(input)
+}
+
 /// Parses any valid LiteralValue (boolean, number, string, or variable)
 /// Handles optional whitespace around the value
 fn parse_value<'a>(input: &mut Input<'a>) -> ParserResult<LiteralValue<'a>> {
@@ -768,6 +800,57 @@ mod tests {
 		);
 	}
 
+	#[test]
+	fn test_parse_function_call() {
+		assert_parses_ok(
+			parse_condition_lhs,
+			"usd(value, native)",
+			ConditionLeft::Call(FunctionCall {
+				name: "usd",
+				argument: Box::new(ConditionLeft::Simple("value")),
+				parameters: vec![LiteralValue::Str("native")],
+			}),
+			"",
+		);
+		assert_parses_ok(
+			parse_condition_lhs,
+			"usd( transfer.amount ,'0xA0b8' ) >",
+			ConditionLeft::Call(FunctionCall {
+				name: "usd",
+				argument: Box::new(ConditionLeft::Path(VariablePath {
+					base: "transfer",
+					accessors: vec![Accessor::Key("amount")],
+				})),
+				parameters: vec![LiteralValue::Str("0xA0b8")],
+			}),
+			" >",
+		);
+		assert_parses_ok(
+			parse_condition_lhs,
+			"abs(delta)",
+			ConditionLeft::Call(FunctionCall {
+				name: "abs",
+				argument: Box::new(ConditionLeft::Simple("delta")),
+				parameters: vec![],
+			}),
+			"",
+		);
+
+		assert_parse_fails(parse_function_call, "usd()"); // Missing argument
+		assert_parse_fails(parse_function_call, "usd(value, native"); // Missing closing parenthesis
+		assert_parse_fails(parse_function_call, "usd('value', native)"); // Argument must be a variable
+		assert_parse_fails(parse_function_call, "true(value)"); // Keyword
+
+		let expression = parse("usd(value, native) > 1000000 AND to == '0x1'").unwrap();
+		assert!(matches!(
+			expression,
+			Expression::Logical {
+				left,
+				..
+			} if matches!(&*left, Expression::Condition(Condition { left: ConditionLeft::Call(_), .. }))
+		));
+	}
+
 	#[test]
 	fn test_parse_value_alt_order() {
 		// Order: quoted_string, boolean, hex_string, number_or_fixed, unquoted_string
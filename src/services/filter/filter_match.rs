@@ -12,11 +12,11 @@ use alloy::primitives::Address;
 use serde_json::{json, Value as JsonValue};
 
 use crate::{
-	models::{MonitorMatch, ScriptLanguage},
+	models::{EVMMatchParamEntry, MonitorMatch, ScriptLanguage, NATIVE_TOKEN},
 	services::{
 		filter::{
-			evm_helpers::{b256_to_string, h160_to_string},
-			FilterError,
+			evm_helpers::{b256_to_string, h160_to_string, string_to_u256},
+			FilterError, PriceOracles, PriceSnapshot,
 		},
 		trigger::TriggerExecutionServiceTrait,
 	},
@@ -54,7 +54,89 @@ pub async fn handle_match<T: TriggerExecutionServiceTrait>(
 	trigger_service: &T,
 	trigger_scripts: &HashMap<String, (ScriptLanguage, String)>,
 ) -> Result<(), FilterError> {
-	match &matching_monitor {
+	handle_deduped_match(matching_monitor, &[], trigger_service, trigger_scripts).await
+}
+
+/// Process a monitor match that other monitors of the same dedup group also matched.
+///
+/// Behaves like [`handle_match`], additionally exposing the names of the suppressed monitors
+/// to trigger templates as `also_matched.0`, `also_matched.1`, ...
+///
+/// # Arguments
+/// * `matching_monitor` - The matched monitor event containing transaction and trigger information
+/// * `also_matched` - Names of monitors whose delivery was folded into this match
+/// * `trigger_service` - Service responsible for executing triggers
+/// * `trigger_scripts` - Scripts to be executed for each trigger
+///
+/// # Returns
+/// Result indicating success or failure of trigger execution
+pub async fn handle_deduped_match<T: TriggerExecutionServiceTrait>(
+	matching_monitor: MonitorMatch,
+	also_matched: &[String],
+	trigger_service: &T,
+	trigger_scripts: &HashMap<String, (ScriptLanguage, String)>,
+) -> Result<(), FilterError> {
+	let variables = match_variables(
+		&matching_monitor,
+		also_matched,
+		trigger_service.price_oracles(),
+	);
+	handle_match_with_variables(
+		matching_monitor,
+		variables,
+		trigger_service,
+		trigger_scripts,
+	)
+	.await
+}
+
+/// Process a monitor match with template variables that were already computed.
+///
+/// Used when the variables produced by [`match_variables`] are extended before dispatch, e.g.
+/// with the results of the monitor's enrichments.
+///
+/// # Arguments
+/// * `matching_monitor` - The matched monitor event containing transaction and trigger information
+/// * `variables` - Template variables of the match
+/// * `trigger_service` - Service responsible for executing triggers
+/// * `trigger_scripts` - Scripts to be executed for each trigger
+///
+/// # Returns
+/// Result indicating success or failure of trigger execution
+pub async fn handle_match_with_variables<T: TriggerExecutionServiceTrait>(
+	matching_monitor: MonitorMatch,
+	variables: HashMap<String, String>,
+	trigger_service: &T,
+	trigger_scripts: &HashMap<String, (ScriptLanguage, String)>,
+) -> Result<(), FilterError> {
+	// Swallow any errors since it's logged in the trigger service and we want to continue
+	// processing other matches
+	let _ = trigger_service
+		.execute(
+			&matching_monitor.monitor().trigger_names(),
+			variables,
+			&matching_monitor,
+			trigger_scripts,
+		)
+		.await;
+	Ok(())
+}
+
+/// Converts a monitor match into the flattened template variables exposed to triggers
+///
+/// # Arguments
+/// * `matching_monitor` - The matched monitor event
+/// * `also_matched` - Names of monitors whose delivery was folded into this match
+/// * `price_oracles` - Price oracles the USD values are computed with, none are without them
+///
+/// # Returns
+/// Template variables keyed by their dotted path, e.g. `transaction.hash`
+pub fn match_variables(
+	matching_monitor: &MonitorMatch,
+	also_matched: &[String],
+	price_oracles: Option<&PriceOracles>,
+) -> HashMap<String, String> {
+	let mut data_json = match matching_monitor {
 		MonitorMatch::EVM(evm_monitor_match) => {
 			let transaction = evm_monitor_match.transaction.clone();
 			// If sender does not exist, we replace with 0x0000000000000000000000000000000000000000
@@ -79,6 +161,22 @@ pub async fn handle_match<T: TriggerExecutionServiceTrait>(
 				data_json["transaction"]["to"] = json!(h160_to_string(*to));
 			}
 
+			// Add USD values if the network has a price oracle
+			let prices = price_oracles
+				.and_then(|price_oracles| price_oracles.cached(&evm_monitor_match.network_slug));
+			if let Some(value_usd) = prices
+				.as_ref()
+				.and_then(|prices| prices.usd_value(*transaction.value(), NATIVE_TOKEN))
+			{
+				data_json["transaction"]["value_usd"] = json!(value_usd);
+			}
+			let to = transaction.to().map(|to| h160_to_string(*to));
+
+			// Add the index of the transaction in the block if present
+			if let Some(index) = transaction.transaction_index {
+				data_json["transaction"]["index"] = json!(index.0.to_string());
+			}
+
 			// Process matched functions
 			let functions = data_json["functions"].as_array_mut().unwrap();
 			for func in evm_monitor_match.matched_on.functions.iter() {
@@ -97,6 +195,10 @@ pub async fn handle_match<T: TriggerExecutionServiceTrait>(
 									for arg in arg_entries {
 										args_obj.insert(arg.name.clone(), json!(arg.value.clone()));
 									}
+									// Amounts are priced in the called contract's token
+									if let (Some(prices), Some(to)) = (&prices, &to) {
+										insert_usd_args(args_obj, arg_entries, prices, to);
+									}
 								}
 							}
 						}
@@ -108,46 +210,86 @@ pub async fn handle_match<T: TriggerExecutionServiceTrait>(
 
 			// Process matched events
 			let events = data_json["events"].as_array_mut().unwrap();
+			let event_args = evm_monitor_match
+				.matched_on_args
+				.as_ref()
+				.and_then(|args| args.events.as_deref())
+				.unwrap_or_default();
+			let mut occurrences = HashMap::new();
 			for event in evm_monitor_match.matched_on.events.iter() {
 				let mut event_data = json!({
 					"signature": event.signature.clone(),
 					"args": {}
 				});
 
-				// Add event arguments if present
-				if let Some(args) = &evm_monitor_match.matched_on_args {
-					if let Some(event_args) = &args.events {
-						for event_arg in event_args {
-							if event_arg.signature == event.signature {
-								if let Some(arg_entries) = &event_arg.args {
-									let args_obj = event_data["args"].as_object_mut().unwrap();
-									for arg in arg_entries {
-										args_obj.insert(arg.name.clone(), json!(arg.value.clone()));
-									}
-								}
-							}
+				// Name the preset the event condition was expanded from
+				if let Some(preset) = evm_monitor_match.monitor.preset_for_event(&event.signature) {
+					event_data["preset"] = json!(preset);
+				}
+
+				// Add event arguments and log index if present
+				if let Some(event_arg) = matched_args(
+					event_args,
+					&event.signature,
+					&mut occurrences,
+					|event_arg| &event_arg.signature,
+				) {
+					if let Some(arg_entries) = &event_arg.args {
+						let args_obj = event_data["args"].as_object_mut().unwrap();
+						for arg in arg_entries {
+							args_obj.insert(arg.name.clone(), json!(arg.value.clone()));
+						}
+						// Amounts are priced in the emitting contract's token
+						let emitter = evm_monitor_match
+							.logs
+							.as_deref()
+							.unwrap_or_default()
+							.iter()
+							.find(|log| {
+								event_arg.log_index.is_some()
+									&& log.log_index.map(|index| index.to::<u64>())
+										== event_arg.log_index
+							})
+							.map(|log| h160_to_string(log.address));
+						if let (Some(prices), Some(emitter)) = (&prices, &emitter) {
+							insert_usd_args(args_obj, arg_entries, prices, emitter);
 						}
 					}
+					if let Some(log_index) = event_arg.log_index {
+						event_data["log_index"] = json!(log_index.to_string());
+					}
 				}
 
 				events.push(event_data);
 			}
 
-			// Swallow any errors since it's logged in the trigger service and we want to continue
-			// processing other matches
-			let _ = trigger_service
-				.execute(
-					&evm_monitor_match
-						.monitor
-						.triggers
-						.iter()
-						.map(|s| s.to_string())
-						.collect::<Vec<_>>(),
-					json_to_hashmap(&data_json),
-					&matching_monitor,
-					trigger_scripts,
-				)
-				.await;
+			// Add the nonce activity of the sender account if present
+			if let Some(activity) = &evm_monitor_match.sender_activity {
+				let mut activity_data = json!({
+					"kind": activity.kind.map(|kind| kind.to_string()),
+					"nonce": activity.nonce.to_string(),
+				});
+				if let Some(expected_nonce) = activity.expected_nonce {
+					activity_data["expected_nonce"] = json!(expected_nonce.to_string());
+				}
+				if let Some(replaced_hash) = &activity.replaced_hash {
+					activity_data["replaced_hash"] = json!(replaced_hash);
+				}
+				if let Some(stuck_duration) = activity.stuck_duration {
+					activity_data["stuck_duration"] = json!(stuck_duration.to_string());
+				}
+				data_json["sender_activity"] = activity_data;
+			}
+
+			if let Some(baseline) = &evm_monitor_match.baseline {
+				data_json["baseline"] = json!({
+					"short_window_value": baseline.short_window_value.to_string(),
+					"long_window_average": format!("{:.2}", baseline.long_window_average),
+					"ratio": format!("{:.2}", baseline.ratio),
+				});
+			}
+
+			data_json
 		}
 		MonitorMatch::Stellar(stellar_monitor_match) => {
 			let transaction = stellar_monitor_match.transaction.clone();
@@ -166,26 +308,34 @@ pub async fn handle_match<T: TriggerExecutionServiceTrait>(
 
 			// Process matched functions
 			let functions = data_json["functions"].as_array_mut().unwrap();
+			let function_args = stellar_monitor_match
+				.matched_on_args
+				.as_ref()
+				.and_then(|args| args.functions.as_deref())
+				.unwrap_or_default();
+			let mut occurrences = HashMap::new();
 			for func in stellar_monitor_match.matched_on.functions.iter() {
 				let mut function_data = json!({
 					"signature": func.signature.clone(),
 					"args": {}
 				});
 
-				// Add function arguments if present
-				if let Some(args) = &stellar_monitor_match.matched_on_args {
-					if let Some(func_args) = &args.functions {
-						for func_arg in func_args {
-							if func_arg.signature == func.signature {
-								if let Some(arg_entries) = &func_arg.args {
-									let args_obj = function_data["args"].as_object_mut().unwrap();
-									for arg in arg_entries {
-										args_obj.insert(arg.name.clone(), json!(arg.value.clone()));
-									}
-								}
-							}
+				// Add function arguments and operation index if present
+				if let Some(func_arg) = matched_args(
+					function_args,
+					&func.signature,
+					&mut occurrences,
+					|func_arg| &func_arg.signature,
+				) {
+					if let Some(arg_entries) = &func_arg.args {
+						let args_obj = function_data["args"].as_object_mut().unwrap();
+						for arg in arg_entries {
+							args_obj.insert(arg.name.clone(), json!(arg.value.clone()));
 						}
 					}
+					if let Some(operation_index) = func_arg.operation_index {
+						function_data["operation_index"] = json!(operation_index.to_string());
+					}
 				}
 
 				functions.push(function_data);
@@ -193,46 +343,43 @@ pub async fn handle_match<T: TriggerExecutionServiceTrait>(
 
 			// Process matched events
 			let events = data_json["events"].as_array_mut().unwrap();
+			let event_args = stellar_monitor_match
+				.matched_on_args
+				.as_ref()
+				.and_then(|args| args.events.as_deref())
+				.unwrap_or_default();
+			let mut occurrences = HashMap::new();
 			for event in stellar_monitor_match.matched_on.events.iter() {
 				let mut event_data = json!({
 					"signature": event.signature.clone(),
 					"args": {}
 				});
 
-				// Add event arguments if present
-				if let Some(args) = &stellar_monitor_match.matched_on_args {
-					if let Some(event_args) = &args.events {
-						for event_arg in event_args {
-							if event_arg.signature == event.signature {
-								if let Some(arg_entries) = &event_arg.args {
-									let args_obj = event_data["args"].as_object_mut().unwrap();
-									for arg in arg_entries {
-										args_obj.insert(arg.name.clone(), json!(arg.value.clone()));
-									}
-								}
-							}
+				// Add event arguments and location if present
+				if let Some(event_arg) = matched_args(
+					event_args,
+					&event.signature,
+					&mut occurrences,
+					|event_arg| &event_arg.signature,
+				) {
+					if let Some(arg_entries) = &event_arg.args {
+						let args_obj = event_data["args"].as_object_mut().unwrap();
+						for arg in arg_entries {
+							args_obj.insert(arg.name.clone(), json!(arg.value.clone()));
 						}
 					}
+					if let Some(operation_index) = event_arg.operation_index {
+						event_data["operation_index"] = json!(operation_index.to_string());
+					}
+					if let Some(event_index) = event_arg.event_index {
+						event_data["event_index"] = json!(event_index.to_string());
+					}
 				}
 
 				events.push(event_data);
 			}
 
-			// Swallow any errors since it's logged in the trigger service and we want to continue
-			// processing other matches
-			let _ = trigger_service
-				.execute(
-					&stellar_monitor_match
-						.monitor
-						.triggers
-						.iter()
-						.map(|s| s.to_string())
-						.collect::<Vec<_>>(),
-					json_to_hashmap(&data_json),
-					&matching_monitor,
-					trigger_scripts,
-				)
-				.await;
+			data_json
 		}
 		MonitorMatch::Midnight(midnight_monitor_match) => {
 			let transaction = midnight_monitor_match.transaction.clone();
@@ -303,24 +450,86 @@ pub async fn handle_match<T: TriggerExecutionServiceTrait>(
 				events.push(event_data);
 			}
 
-			// Swallow any errors since it's logged in the trigger service and we want to continue
-			// processing other matches
-			let _ = trigger_service
-				.execute(
-					&midnight_monitor_match
-						.monitor
-						.triggers
-						.iter()
-						.map(|s| s.to_string())
-						.collect::<Vec<_>>(),
-					json_to_hashmap(&data_json),
-					&matching_monitor,
-					trigger_scripts,
-				)
-				.await;
+			data_json
+		}
+	};
+
+	if let Some(metrics) = matching_monitor.network_metrics() {
+		let mut metrics_data = json!({
+			"blocks": metrics.series.len().to_string(),
+		});
+		if let Some(first) = metrics.series.first() {
+			metrics_data["first_block"] = json!(first.block_number.to_string());
+		}
+		if let Some(latest) = metrics.latest() {
+			for (name, value) in &latest.values {
+				metrics_data[name.as_str()] = json!(value);
+			}
+		}
+		data_json["network_metrics"] = metrics_data;
+	}
+	if !also_matched.is_empty() {
+		data_json["also_matched"] = json!(also_matched);
+	}
+	if let Some(owner) = &matching_monitor.monitor().owner {
+		data_json["owner"] = json!(owner);
+	}
+	json_to_hashmap(&data_json)
+}
+
+/// Returns the arguments matched for the next condition with the given signature
+///
+/// Matched conditions and their arguments are recorded in the same order, so the n-th condition
+/// with a signature takes the n-th arguments with that signature. Identical events of a
+/// transaction keep their own arguments this way. Falls back to the last arguments with the
+/// signature if there are fewer arguments than conditions.
+fn matched_args<'a, T>(
+	args: &'a [T],
+	signature: &'a str,
+	occurrences: &mut HashMap<&'a str, usize>,
+	signature_of: impl Fn(&T) -> &str,
+) -> Option<&'a T> {
+	let occurrence = occurrences.entry(signature).or_default();
+	let same_signature = args
+		.iter()
+		.filter(|arg| signature_of(arg) == signature)
+		.collect::<Vec<_>>();
+	let matched = same_signature
+		.get(*occurrence)
+		.or(same_signature.last())
+		.copied();
+	*occurrence += 1;
+	matched
+}
+
+/// Adds the USD values of the unsigned integer arguments as `<name>_usd`
+///
+/// Arguments of tokens without a fresh price are skipped, as are values that would replace an
+/// argument of the same name.
+fn insert_usd_args(
+	args_obj: &mut serde_json::Map<String, JsonValue>,
+	args: &[EVMMatchParamEntry],
+	prices: &PriceSnapshot,
+	token: &str,
+) {
+	if !prices.prices_token(token) {
+		return;
+	}
+	for arg in args {
+		if !arg.kind.starts_with("uint") || arg.kind.contains('[') {
+			continue;
+		}
+		let name = format!("{}_usd", arg.name);
+		if args_obj.contains_key(&name) {
+			continue;
+		}
+		if let Some(usd) = string_to_u256(&arg.value)
+			.ok()
+			.and_then(|amount| prices.usd_value(amount, token))
+		{
+			args_obj.insert(name, json!(usd));
 		}
 	}
-	Ok(())
 }
 
 /// Converts a JsonValue to a flattened HashMap with dotted path notation
@@ -369,8 +578,214 @@ fn insert_primitive<T: ToString>(prefix: &str, result: &mut HashMap<String, Stri
 #[cfg(test)]
 mod tests {
 	use super::*;
+	use crate::{
+		models::{EVMBaselineActivity, MonitorOwner, NetworkMetrics, NetworkMetricsSample},
+		services::notification::{
+			GenericWebhookPayloadBuilder, SlackPayloadBuilder, WebhookPayloadBuilder,
+		},
+		utils::tests::builders::evm::{
+			monitor::MonitorBuilder, monitor_match::MonitorMatchBuilder,
+			transaction::TransactionBuilder,
+		},
+	};
 	use serde_json::json;
 
+	fn create_match(owner: Option<MonitorOwner>) -> MonitorMatch {
+		let mut monitor = MonitorBuilder::new().name("Large Withdrawal");
+		if let Some(owner) = owner {
+			monitor = monitor.owner(owner);
+		}
+		MonitorMatchBuilder::new().monitor(monitor.build()).build()
+	}
+
+	#[test]
+	fn test_match_variables_include_owner() {
+		let monitor_match = create_match(Some(MonitorOwner {
+			team: "treasury".to_string(),
+			contact: Some("#treasury-oncall".to_string()),
+			runbook_url: Some("https://runbooks.example.com/withdrawals".to_string()),
+		}));
+
+		let variables = match_variables(&monitor_match, &[], None);
+		assert_eq!(variables["owner.team"], "treasury");
+		assert_eq!(variables["owner.contact"], "#treasury-oncall");
+		assert_eq!(
+			variables["owner.runbook_url"],
+			"https://runbooks.example.com/withdrawals"
+		);
+
+		let payload = SlackPayloadBuilder.build_payload(
+			"${monitor.name}",
+			"Owner: ${owner.team}\nRunbook: ${owner.runbook_url}",
+			&variables,
+		);
+		assert_eq!(
+			payload["blocks"][0]["text"]["text"],
+			"*Large Withdrawal*\n\nOwner: treasury\nRunbook: https://runbooks.example.com/withdrawals"
+		);
+	}
+
+	#[test]
+	fn test_match_variables_include_baseline() {
+		let MonitorMatch::EVM(mut evm_match) = create_match(None) else {
+			unreachable!();
+		};
+		evm_match.baseline = Some(EVMBaselineActivity {
+			short_window_value: 12.0,
+			long_window_average: 2.126,
+			ratio: 5.647,
+		});
+
+		let variables = match_variables(&MonitorMatch::EVM(evm_match), &[], None);
+		assert_eq!(variables["baseline.short_window_value"], "12");
+		assert_eq!(variables["baseline.long_window_average"], "2.13");
+		assert_eq!(variables["baseline.ratio"], "5.65");
+	}
+
+	#[test]
+	fn test_match_variables_include_network_metrics() {
+		let MonitorMatch::EVM(mut evm_match) = create_match(None) else {
+			unreachable!();
+		};
+		let sample = |block_number: u64, base_fee: &str| NetworkMetricsSample {
+			block_number,
+			timestamp: None,
+			values: [("base_fee".to_string(), base_fee.to_string())].into(),
+		};
+		evm_match.network_metrics = Some(NetworkMetrics {
+			series: vec![sample(20, "160000000000"), sample(21, "170000000000")],
+		});
+
+		let variables = match_variables(&MonitorMatch::EVM(evm_match), &[], None);
+		assert_eq!(variables["network_metrics.base_fee"], "170000000000");
+		assert_eq!(variables["network_metrics.blocks"], "2");
+		assert_eq!(variables["network_metrics.first_block"], "20");
+	}
+
+	#[test]
+	fn test_match_variables_include_usd_values() {
+		use crate::{
+			models::{
+				EVMMatchArguments, EVMMatchParamsMap, EVMReceiptLog, EventCondition,
+				PriceOracleConfig, PriceSource, PricedToken,
+			},
+			services::filter::TokenPrice,
+		};
+		use alloy::primitives::U256;
+		use rust_decimal::Decimal;
+
+		let usdc = "0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48";
+		let priced = |decimals: u8, price: &str| PricedToken {
+			decimals,
+			price: Some(price.to_string()),
+			id: None,
+			feed: None,
+		};
+		let config = PriceOracleConfig {
+			source: PriceSource::Static,
+			tokens: HashMap::from([
+				(NATIVE_TOKEN.to_string(), priced(18, "2000")),
+				(usdc.to_string(), priced(6, "0.9998")),
+			]),
+			cache_ttl_secs: 60,
+			max_age_secs: 3600,
+		};
+		let now = chrono::Utc::now().timestamp();
+		let price_oracles = PriceOracles::default();
+		price_oracles.set(
+			"usd_values_mainnet",
+			PriceSnapshot::new(
+				"usd_values_mainnet",
+				&config,
+				HashMap::from([
+					(
+						NATIVE_TOKEN.to_string(),
+						TokenPrice {
+							usd: Decimal::from(2000),
+							updated_at: now,
+						},
+					),
+					(
+						usdc.to_string(),
+						TokenPrice {
+							usd: Decimal::new(9998, 4),
+							updated_at: now,
+						},
+					),
+				]),
+			),
+		);
+
+		let signature = "Transfer(address,address,uint256)";
+		let log: EVMReceiptLog = serde_json::from_value(json!({
+			"address": usdc,
+			"topics": [],
+			"data": "0x",
+			"logIndex": "0x3",
+		}))
+		.unwrap();
+		let MonitorMatch::EVM(mut evm_match) = create_match(None) else {
+			unreachable!();
+		};
+		evm_match.network_slug = "usd_values_mainnet".to_string();
+		evm_match.transaction = TransactionBuilder::new()
+			.value(U256::from(1_500_000_000_000_000_000u128))
+			.build();
+		evm_match.logs = Some(vec![log]);
+		evm_match.matched_on.events = vec![EventCondition {
+			signature: signature.to_string(),
+			expression: None,
+			match_any_emitter: false,
+		}];
+		evm_match.matched_on_args = Some(EVMMatchArguments {
+			functions: None,
+			events: Some(vec![EVMMatchParamsMap {
+				signature: signature.to_string(),
+				args: Some(vec![EVMMatchParamEntry {
+					name: "value".to_string(),
+					value: "2500000000".to_string(),
+					kind: "uint256".to_string(),
+					indexed: false,
+				}]),
+				hex_signature: None,
+				log_index: Some(3),
+			}]),
+		});
+
+		let variables = match_variables(&MonitorMatch::EVM(evm_match), &[], Some(&price_oracles));
+		assert_eq!(variables["transaction.value_usd"], "3000");
+		assert_eq!(variables["events.0.args.value_usd"], "2499.5");
+
+		let payload = GenericWebhookPayloadBuilder.build_payload(
+			"${monitor.name}",
+			"Moved ${events.0.args.value_usd} USD",
+			&variables,
+		);
+		assert_eq!(payload["body"], "Moved 2499.5 USD");
+
+		// Networks without a price oracle have no USD values
+		let variables = match_variables(&create_match(None), &[], Some(&price_oracles));
+		assert!(!variables.contains_key("transaction.value_usd"));
+	}
+
+	#[test]
+	fn test_match_variables_without_owner() {
+		let variables = match_variables(&create_match(None), &[], None);
+		assert!(!variables.keys().any(|key| key.starts_with("owner.")));
+
+		// Only the fields set on the owner are available
+		let variables = match_variables(
+			&create_match(Some(MonitorOwner {
+				team: "treasury".to_string(),
+				..Default::default()
+			})),
+			&[],
+			None,
+		);
+		assert_eq!(variables["owner.team"], "treasury");
+		assert!(!variables.contains_key("owner.runbook_url"));
+	}
+
 	#[test]
 	fn test_json_to_hashmap() {
 		let json = json!({
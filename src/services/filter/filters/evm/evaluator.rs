@@ -4,9 +4,12 @@
 use super::helpers::{are_same_address, string_to_i256, string_to_u256};
 use crate::{
 	models::EVMMatchParamEntry,
-	services::filter::expression::{
-		compare_ordered_values, ComparisonOperator, ConditionEvaluator, EvaluationError,
-		LiteralValue,
+	services::filter::{
+		expression::{
+			compare_ordered_values, ComparisonOperator, ConditionEvaluator, EvaluationError,
+			LiteralValue,
+		},
+		price_oracle::{parse_fixed, PriceSnapshot, USD_DECIMALS},
 	},
 };
 use rust_decimal::Decimal;
@@ -43,13 +46,115 @@ const ARRAY_KINDS: &[&str] = &[
 	"tuple[]",
 ];
 
+/// Kind of the USD values returned by `usd()`, decimal numbers with `USD_DECIMALS` decimals
+const FIXED_KIND: &str = "fixed";
+
 pub struct EVMConditionEvaluator<'a> {
 	args: &'a EVMArgs,
+	/// Prices of the network, used by `usd()`
+	prices: Option<&'a PriceSnapshot>,
 }
 
 impl<'a> EVMConditionEvaluator<'a> {
 	pub fn new(args: &'a EVMArgs) -> Self {
-		Self { args }
+		Self { args, prices: None }
+	}
+
+	/// Sets the prices used by `usd()`
+	pub fn with_prices(mut self, prices: Option<&'a PriceSnapshot>) -> Self {
+		self.prices = prices;
+		self
+	}
+
+	/// Converts an amount of a token to USD, for `usd(amount, token)`
+	///
+	/// Arguments:
+	/// - value: The amount in the smallest unit of the token.
+	/// - kind: The kind of the amount, an unsigned integer.
+	/// - parameters: The token, `native` or its contract address.
+	///
+	/// Returns:
+	/// - The USD value, or None if the price of the token is stale.
+	/// - error if the amount or token is invalid, or the network has no price for the token.
+	pub fn usd(
+		&self,
+		value: &str,
+		kind: &str,
+		parameters: &[LiteralValue<'_>],
+	) -> Result<Option<String>, EvaluationError> {
+		if !UNSIGNED_INTEGER_KINDS.contains(&kind.to_lowercase().as_str()) {
+			let msg = format!("usd() expects an unsigned integer amount, found: {}", kind);
+			return Err(EvaluationError::type_mismatch(msg, None, None));
+		}
+
+		let token = match parameters {
+			[LiteralValue::Str(token)] | [LiteralValue::Number(token)] => *token,
+			_ => {
+				let msg = format!(
+					"usd() expects a token, e.g. usd(value, native), found: {:?}",
+					parameters
+				);
+				return Err(EvaluationError::type_mismatch(msg, None, None));
+			}
+		};
+
+		let prices = self
+			.prices
+			.filter(|prices| prices.prices_token(token))
+			.ok_or_else(|| {
+				let msg = format!("No price oracle price for token '{}'", token);
+				EvaluationError::variable_not_found(msg, None, None)
+			})?;
+
+		let amount = string_to_u256(value).map_err(|error| {
+			let msg = format!("Failed to parse usd() amount '{}' as U256", value);
+			EvaluationError::parse_error(msg, Some(error.into()), None)
+		})?;
+
+		Ok(prices.usd_value(amount, token))
+	}
+
+	/// Compares a USD value with the RHS literal value
+	///
+	/// Arguments:
+	/// - left_str: The left-hand side value as a decimal string.
+	/// - operator: The operator to use for the comparison.
+	/// - right_literal: The right-hand side value.
+	///
+	/// Returns:
+	/// - true if the comparison is true, false otherwise.
+	pub fn compare_fixed(
+		&self,
+		left_str: &str,
+		operator: &ComparisonOperator,
+		right_literal: &LiteralValue<'_>,
+	) -> Result<bool, EvaluationError> {
+		let left = parse_fixed(left_str, USD_DECIMALS).ok_or_else(|| {
+			let msg = format!("Failed to parse LHS value '{}' as a USD value", left_str);
+			EvaluationError::parse_error(msg, None, None)
+		})?;
+
+		let right_str = match right_literal {
+			LiteralValue::Number(s) => s,
+			LiteralValue::Str(s) => s,
+			_ => {
+				let msg = format!(
+					"Expected number or string literal for USD comparison, found: {:?}",
+					right_literal
+				);
+				return Err(EvaluationError::type_mismatch(msg, None, None));
+			}
+		};
+
+		let right = parse_fixed(right_str, USD_DECIMALS).ok_or_else(|| {
+			let msg = format!(
+				"Failed to parse RHS value '{}' as a USD value with at most {} decimals",
+				right_str, USD_DECIMALS
+			);
+			EvaluationError::parse_error(msg, None, None)
+		})?;
+
+		compare_ordered_values(&left, operator, &right)
 	}
 
 	/// Helper to check if a serde_json::Value matches a target string.
@@ -744,6 +849,7 @@ impl ConditionEvaluator for EVMConditionEvaluator<'_> {
 			}
 			"bool" => self.compare_boolean(lhs_value_str, operator, rhs_literal),
 			"tuple" => self.compare_tuple(lhs_value_str, operator, rhs_literal),
+			FIXED_KIND => self.compare_fixed(lhs_value_str, operator, rhs_literal),
 			_ => {
 				let msg = format!(
 					"Unsupported EVM parameter kind for comparison: {}",
@@ -801,6 +907,35 @@ impl ConditionEvaluator for EVMConditionEvaluator<'_> {
 			serde_json::Value::Null => "null".to_string(),
 		}
 	}
+
+	/// This method is used to apply the functions of the EVM condition evaluator.
+	///
+	/// Arguments:
+	/// - name: The name of the function, only `usd` is supported.
+	/// - value: The resolved value of the function argument.
+	/// - kind: The kind of the function argument.
+	/// - parameters: The literal parameters of the function.
+	///
+	/// Returns:
+	/// - The value and kind of the result, or None if the function has no result.
+	/// - error if the function is not supported or its arguments are invalid.
+	fn apply_function(
+		&self,
+		name: &str,
+		value: &str,
+		kind: &str,
+		parameters: &[LiteralValue],
+	) -> Result<Option<(String, String)>, EvaluationError> {
+		match name {
+			"usd" => Ok(self
+				.usd(value, kind, parameters)?
+				.map(|usd| (usd, FIXED_KIND.to_string()))),
+			_ => {
+				let msg = format!("Unsupported EVM function: {}", name);
+				Err(EvaluationError::unsupported_operator(msg, None, None))
+			}
+		}
+	}
 }
 
 #[cfg(test)]
@@ -2259,4 +2394,81 @@ mod tests {
 			"(123,(456,\"test string\",\"hello world 2\"),789)"
 		);
 	}
+
+	#[test]
+	fn test_usd_function() {
+		use crate::{
+			models::{PriceOracleConfig, PriceSource, PricedToken},
+			services::filter::{expression, price_oracle::TokenPrice},
+		};
+		use std::collections::HashMap;
+
+		let token = |price: &str| PricedToken {
+			decimals: 18,
+			price: Some(price.to_string()),
+			id: None,
+			feed: None,
+		};
+		let config = PriceOracleConfig {
+			source: PriceSource::Static,
+			tokens: HashMap::from([
+				("native".to_string(), token("2000")),
+				(
+					"0x0000000000000000000000000000000000000001".to_string(),
+					token("1"),
+				),
+			]),
+			cache_ttl_secs: 60,
+			max_age_secs: 3600,
+		};
+		let now = chrono::Utc::now().timestamp();
+		let prices = PriceSnapshot::new(
+			"ethereum_mainnet",
+			&config,
+			HashMap::from([
+				(
+					"native".to_string(),
+					TokenPrice {
+						usd: Decimal::from(2000),
+						updated_at: now,
+					},
+				),
+				(
+					"0x0000000000000000000000000000000000000001".to_string(),
+					TokenPrice {
+						usd: Decimal::from(1),
+						updated_at: now - 7200,
+					},
+				),
+			]),
+		);
+
+		// 600 ETH is worth $1.2M
+		let args = vec![EVMMatchParamEntry {
+			name: "value".to_string(),
+			value: "600000000000000000000".to_string(),
+			kind: "uint256".to_string(),
+			indexed: false,
+		}];
+		let evaluator = EVMConditionEvaluator::new(&args).with_prices(Some(&prices));
+		let evaluate =
+			|expr: &str| expression::evaluate(&expression::parse(expr).unwrap(), &evaluator);
+
+		assert!(evaluate("usd(value, native) > 1000000").unwrap());
+		assert!(evaluate("usd(value, native) == 1200000.00").unwrap());
+		assert!(!evaluate("usd(value, native) >= 1200000.000001").unwrap());
+
+		// A stale price doesn't match, whatever the comparison
+		assert!(!evaluate("usd(value, 0x0000000000000000000000000000000000000001) >= 0").unwrap());
+
+		// Unpriced tokens, invalid amounts and missing prices are errors
+		assert!(evaluate("usd(value, 0x0000000000000000000000000000000000000002) > 0").is_err());
+		assert!(evaluate("usd(value) > 0").is_err());
+		assert!(evaluate("abs(value) > 0").is_err());
+		assert!(expression::evaluate(
+			&expression::parse("usd(value, native) > 0").unwrap(),
+			&EVMConditionEvaluator::new(&args)
+		)
+		.is_err());
+	}
 }
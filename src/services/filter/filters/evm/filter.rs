@@ -9,17 +9,18 @@
 
 use alloy::core::dyn_abi::{DynSolType, DynSolValue, EventExt};
 use alloy::core::json_abi::{AbiItem, JsonAbi};
-use alloy::primitives::{LogData, U64};
+use alloy::primitives::{keccak256, LogData, B256, U64};
 use async_trait::async_trait;
-use std::marker::PhantomData;
+use futures::{stream, StreamExt, TryStreamExt};
+use std::{collections::HashMap, marker::PhantomData, sync::Arc};
 use tracing::instrument;
 
 use crate::{
 	models::{
-		AddressWithSpec, BlockType, ContractSpec, EVMContractSpec, EVMMatchArguments,
-		EVMMatchParamEntry, EVMMatchParamsMap, EVMMonitorMatch, EVMReceiptLog, EVMTransaction,
-		EVMTransactionReceipt, EventCondition, FunctionCondition, MatchConditions, Monitor,
-		MonitorMatch, Network, TransactionCondition, TransactionStatus,
+		AddressWithSpec, BlockType, ContractSpec, EVMBlock, EVMContractSpec, EVMMatchArguments,
+		EVMMatchParamEntry, EVMMatchParamsMap, EVMMonitorMatch, EVMReceiptLog, EVMSenderActivity,
+		EVMTransaction, EVMTransactionReceipt, EventCondition, FunctionCondition, MatchConditions,
+		Monitor, MonitorMatch, Network, TransactionCondition, TransactionStatus,
 	},
 	services::{
 		blockchain::{BlockChainClient, EvmClientTrait},
@@ -28,9 +29,11 @@ use crate::{
 				are_same_address, are_same_signature, b256_to_string, format_token_value,
 				h160_to_string, normalize_address,
 			},
+			evm_network_metrics_candidate,
 			expression::{self, EvaluationError},
-			filters::evm::evaluator::EVMConditionEvaluator,
-			BlockFilter, FilterError,
+			filters::{evaluate_monitors, evm::evaluator::EVMConditionEvaluator},
+			BlockFilter, DecodeFailure, DecodeFailureLog, DecodeFailures, FilterError,
+			PriceOracles, PriceSnapshot,
 		},
 	},
 };
@@ -38,6 +41,89 @@ use crate::{
 /// Filter implementation for EVM-compatible blockchains
 pub struct EVMBlockFilter<T> {
 	pub _client: PhantomData<T>,
+	/// Price oracles the prices of a block are read from, unset for filters of a single monitor
+	pub price_oracles: Option<Arc<PriceOracles>>,
+	/// Prices of the network, used by `usd()` in expressions
+	pub prices: Option<Arc<PriceSnapshot>>,
+	/// Decoding failures of the conditions of the monitor being evaluated
+	pub decode_failures: DecodeFailureLog,
+	/// Tracker the decoding failures are recorded to once a monitor is evaluated, unset to only
+	/// collect them
+	pub decode_failure_tracker: Option<Arc<DecodeFailures>>,
+}
+
+/// Data of a block shared by the monitors evaluated for it
+struct EVMBlockData {
+	block: Arc<EVMBlock>,
+	/// All the logs of the block
+	logs: Vec<EVMReceiptLog>,
+	/// Logs of the block by transaction hash
+	logs_by_tx: HashMap<String, Vec<EVMReceiptLog>>,
+	/// Receipts by transaction hash, only fetched if a monitor needs them
+	receipts: HashMap<String, EVMTransactionReceipt>,
+	contract_specs: Vec<(String, EVMContractSpec)>,
+	network_slug: String,
+}
+
+/// Contract specs of a monitor declaring the events of its conditions matching any emitter
+///
+/// The specs are looked up once per monitor, so that the logs emitted by contracts that are
+/// not monitored are decoded without parsing the ABIs of the monitor again.
+#[derive(Debug, Default)]
+pub struct AnyEmitterSpecs<'a> {
+	/// First contract spec of the monitored addresses declaring each widened event, by topic
+	specs: HashMap<B256, &'a ContractSpec>,
+}
+
+impl<'a> AnyEmitterSpecs<'a> {
+	/// Finds the contract specs declaring the events of the conditions matching any emitter
+	///
+	/// # Arguments
+	/// * `monitor` - Monitor containing event match conditions
+	pub fn new(monitor: &'a Monitor) -> Self {
+		let topics = monitor
+			.match_conditions
+			.events
+			.iter()
+			.filter(|condition| condition.match_any_emitter)
+			.map(|condition| keccak256(condition.signature.replace(char::is_whitespace, "")))
+			.collect::<Vec<_>>();
+		if topics.is_empty() {
+			return Self::default();
+		}
+
+		let mut specs = HashMap::new();
+		for spec in monitor
+			.addresses
+			.iter()
+			.filter_map(|address| address.contract_spec.as_ref())
+		{
+			let ContractSpec::EVM(evm_spec) = spec else {
+				continue;
+			};
+			let Ok(abi) = serde_json::from_slice::<JsonAbi>(evm_spec.to_string().as_bytes()) else {
+				continue;
+			};
+			for event in abi.events() {
+				let selector = event.selector();
+				if topics.contains(&selector) {
+					specs.entry(selector).or_insert(spec);
+				}
+			}
+		}
+		Self { specs }
+	}
+
+	/// Finds the contract spec to decode a log emitted by a contract that is not monitored.
+	///
+	/// # Arguments
+	/// * `log` - Event log emitted by a contract that is not monitored
+	///
+	/// # Returns
+	/// The contract spec declaring the event of the log, if it should be decoded
+	fn spec(&self, log: &EVMReceiptLog) -> Option<&'a ContractSpec> {
+		self.specs.get(log.topics.first()?).copied()
+	}
 }
 
 impl<T> EVMBlockFilter<T> {
@@ -74,88 +160,7 @@ impl<T> EVMBlockFilter<T> {
 
 				if status_matches {
 					if let Some(expr) = &condition.expression {
-						let tx_params = vec![
-							EVMMatchParamEntry {
-								name: "value".to_string(),
-								value: transaction.value.to_string(),
-								kind: "uint256".to_string(),
-								indexed: false,
-							},
-							EVMMatchParamEntry {
-								name: "from".to_string(),
-								value: transaction.from.map_or("".to_string(), h160_to_string),
-								kind: "address".to_string(),
-								indexed: false,
-							},
-							EVMMatchParamEntry {
-								name: "to".to_string(),
-								value: transaction.to.map_or("".to_string(), h160_to_string),
-								kind: "address".to_string(),
-								indexed: false,
-							},
-							EVMMatchParamEntry {
-								name: "hash".to_string(),
-								value: b256_to_string(transaction.hash),
-								kind: "string".to_string(),
-								indexed: false,
-							},
-							EVMMatchParamEntry {
-								name: "gas_price".to_string(),
-								value: transaction.gas_price.unwrap_or_default().to_string(),
-								kind: "uint256".to_string(),
-								indexed: false,
-							},
-							EVMMatchParamEntry {
-								name: "max_fee_per_gas".to_string(),
-								value: transaction.max_fee_per_gas.unwrap_or_default().to_string(),
-								kind: "uint256".to_string(),
-								indexed: false,
-							},
-							EVMMatchParamEntry {
-								name: "max_priority_fee_per_gas".to_string(),
-								value: transaction
-									.max_priority_fee_per_gas
-									.unwrap_or_default()
-									.to_string(),
-								kind: "uint256".to_string(),
-								indexed: false,
-							},
-							EVMMatchParamEntry {
-								name: "gas_limit".to_string(),
-								value: transaction.gas.to_string(),
-								kind: "uint256".to_string(),
-								indexed: false,
-							},
-							EVMMatchParamEntry {
-								name: "nonce".to_string(),
-								value: transaction.nonce.to_string(),
-								kind: "uint256".to_string(),
-								indexed: false,
-							},
-							EVMMatchParamEntry {
-								name: "input".to_string(),
-								value: format!("0x{}", hex::encode(&transaction.input)),
-								kind: "string".to_string(),
-								indexed: false,
-							},
-							EVMMatchParamEntry {
-								name: "gas_used".to_string(),
-								value: tx_receipt
-									.as_ref()
-									.map(|r| r.gas_used.unwrap_or_default().to_string())
-									.unwrap_or_default(),
-								kind: "uint256".to_string(),
-								indexed: false,
-							},
-							EVMMatchParamEntry {
-								name: "transaction_index".to_string(),
-								value: transaction
-									.transaction_index
-									.map_or("0".to_string(), |idx| idx.0.to_string()),
-								kind: "uint64".to_string(),
-								indexed: false,
-							},
-						];
+						let tx_params = self.transaction_params(transaction, tx_receipt);
 
 						// Evaluate the expression with transaction parameters
 						match self.evaluate_expression(expr, &tx_params) {
@@ -185,6 +190,100 @@ impl<T> EVMBlockFilter<T> {
 		}
 	}
 
+	/// Returns the transaction fields available to the expressions of transaction conditions
+	///
+	/// # Arguments
+	/// * `transaction` - The transaction
+	/// * `tx_receipt` - Transaction receipt, providing `gas_used`
+	pub fn transaction_params(
+		&self,
+		transaction: &EVMTransaction,
+		tx_receipt: &Option<EVMTransactionReceipt>,
+	) -> Vec<EVMMatchParamEntry> {
+		vec![
+			EVMMatchParamEntry {
+				name: "value".to_string(),
+				value: transaction.value.to_string(),
+				kind: "uint256".to_string(),
+				indexed: false,
+			},
+			EVMMatchParamEntry {
+				name: "from".to_string(),
+				value: transaction.from.map_or("".to_string(), h160_to_string),
+				kind: "address".to_string(),
+				indexed: false,
+			},
+			EVMMatchParamEntry {
+				name: "to".to_string(),
+				value: transaction.to.map_or("".to_string(), h160_to_string),
+				kind: "address".to_string(),
+				indexed: false,
+			},
+			EVMMatchParamEntry {
+				name: "hash".to_string(),
+				value: b256_to_string(transaction.hash),
+				kind: "string".to_string(),
+				indexed: false,
+			},
+			EVMMatchParamEntry {
+				name: "gas_price".to_string(),
+				value: transaction.gas_price.unwrap_or_default().to_string(),
+				kind: "uint256".to_string(),
+				indexed: false,
+			},
+			EVMMatchParamEntry {
+				name: "max_fee_per_gas".to_string(),
+				value: transaction.max_fee_per_gas.unwrap_or_default().to_string(),
+				kind: "uint256".to_string(),
+				indexed: false,
+			},
+			EVMMatchParamEntry {
+				name: "max_priority_fee_per_gas".to_string(),
+				value: transaction
+					.max_priority_fee_per_gas
+					.unwrap_or_default()
+					.to_string(),
+				kind: "uint256".to_string(),
+				indexed: false,
+			},
+			EVMMatchParamEntry {
+				name: "gas_limit".to_string(),
+				value: transaction.gas.to_string(),
+				kind: "uint256".to_string(),
+				indexed: false,
+			},
+			EVMMatchParamEntry {
+				name: "nonce".to_string(),
+				value: transaction.nonce.to_string(),
+				kind: "uint256".to_string(),
+				indexed: false,
+			},
+			EVMMatchParamEntry {
+				name: "input".to_string(),
+				value: format!("0x{}", hex::encode(&transaction.input)),
+				kind: "string".to_string(),
+				indexed: false,
+			},
+			EVMMatchParamEntry {
+				name: "gas_used".to_string(),
+				value: tx_receipt
+					.as_ref()
+					.map(|r| r.gas_used.unwrap_or_default().to_string())
+					.unwrap_or_default(),
+				kind: "uint256".to_string(),
+				indexed: false,
+			},
+			EVMMatchParamEntry {
+				name: "transaction_index".to_string(),
+				value: transaction
+					.transaction_index
+					.map_or("0".to_string(), |idx| idx.0.to_string()),
+				kind: "uint64".to_string(),
+				indexed: false,
+			},
+		]
+	}
+
 	/// Finds function calls in a transaction that match the monitor's conditions.
 	///
 	/// Decodes the transaction input data using the contract ABI and matches against
@@ -258,22 +357,22 @@ impl<T> EVMBlockFilter<T> {
 									&function_signature_with_params,
 								) {
 									// Parse selector types into DynSolType
-									let types: Vec<DynSolType> =
-										match selector_types
-											.iter()
-											.map(|s| s.parse::<DynSolType>())
-											.collect::<Result<Vec<_>, _>>()
-										{
-											Ok(types) => types,
-											Err(e) => {
-												FilterError::internal_error(
-												format!("Failed to parse function parameter types: {}", e),
-												Some(e.into()),
-												None,
-											);
-												return;
-											}
-										};
+									let types: Vec<DynSolType> = match selector_types
+										.iter()
+										.map(|s| s.parse::<DynSolType>())
+										.collect::<Result<Vec<_>, _>>()
+									{
+										Ok(types) => types,
+										Err(e) => {
+											self.decode_failures.push(DecodeFailure {
+												condition: condition.signature.clone(),
+												selector: format!("0x{}", hex::encode(selector)),
+												error: format!("Invalid parameter types: {}", e),
+												transaction_hash: b256_to_string(transaction.hash),
+											});
+											return;
+										}
+									};
 
 									// Get bytes, drop selector
 									let mut raw = input_data.0.to_vec();
@@ -287,11 +386,12 @@ impl<T> EVMBlockFilter<T> {
 										Ok(DynSolValue::Tuple(vals)) => vals,
 										Ok(val) => vec![val],
 										Err(e) => {
-											FilterError::internal_error(
-												format!("Failed to decode ABI parameters: {}", e),
-												Some(e.into()),
-												None,
-											);
+											self.decode_failures.push(DecodeFailure {
+												condition: condition.signature.clone(),
+												selector: format!("0x{}", hex::encode(selector)),
+												error: e.to_string(),
+												transaction_hash: b256_to_string(transaction.hash),
+											});
 											continue;
 										}
 									};
@@ -327,6 +427,7 @@ impl<T> EVMBlockFilter<T> {
 															"0x{}",
 															hex::encode(function.selector())
 														)),
+														log_index: None,
 													});
 												}
 												break;
@@ -354,6 +455,7 @@ impl<T> EVMBlockFilter<T> {
 												hex_signature: Some(hex::encode(
 													function.selector(),
 												)),
+												log_index: None,
 											});
 										}
 										break;
@@ -375,6 +477,7 @@ impl<T> EVMBlockFilter<T> {
 	/// # Arguments
 	/// * `logs` - Transaction receipt containing event logs
 	/// * `monitor` - Monitor containing event match conditions
+	/// * `any_emitter_specs` - Contract specs of the monitor for logs of other contracts
 	/// * `matched_events` - Vector to store matching events
 	/// * `matched_on_args` - Arguments from matched events
 	/// * `involved_addresses` - Addresses involved in matched events
@@ -382,6 +485,7 @@ impl<T> EVMBlockFilter<T> {
 		&self,
 		logs: &[EVMReceiptLog],
 		monitor: &Monitor,
+		any_emitter_specs: &AnyEmitterSpecs,
 		matched_events: &mut Vec<EventCondition>,
 		matched_on_args: &mut EVMMatchArguments,
 		involved_addresses: &mut Vec<String>,
@@ -393,17 +497,49 @@ impl<T> EVMBlockFilter<T> {
 				.iter()
 				.find(|addr| are_same_address(&addr.address, &h160_to_string(log.address)));
 
-			// Only process logs from monitored addresses
-			let Some(monitored_addr) = matching_monitored_addr else {
-				continue;
+			// Logs from other contracts only match conditions widened to any emitter
+			let (spec, foreign_emitter) = match matching_monitored_addr {
+				Some(monitored_addr) => {
+					// Add the contract address that emitted the event
+					involved_addresses.push(h160_to_string(log.address));
+					(monitored_addr.contract_spec.as_ref(), false)
+				}
+				None => (any_emitter_specs.spec(log), true),
 			};
 
-			// Add the contract address that emitted the event
-			involved_addresses.push(h160_to_string(log.address));
-
 			// Process the matching address's ABI
-			if let Some(abi) = &monitored_addr.contract_spec {
-				let decoded_log = self.decode_events(abi, log);
+			if let Some(abi) = spec {
+				let decoded_log = match self.decode_event_log(abi, log) {
+					Ok(decoded_log) => decoded_log,
+					Err(mut failure) => {
+						// Failures are reported against the conditions of the event, or against
+						// the event itself for monitors matching all events
+						let condition = monitor.match_conditions.events.iter().find(|condition| {
+							(!foreign_emitter || condition.match_any_emitter)
+								&& are_same_signature(&condition.signature, &failure.condition)
+						});
+						match condition {
+							Some(condition) => {
+								failure.condition = condition.signature.clone();
+								self.decode_failures.push(failure);
+							}
+							None if monitor.match_conditions.events.is_empty() => {
+								self.decode_failures.push(failure);
+							}
+							None => {
+								FilterError::internal_error(
+									format!(
+										"Failed to decode log data of {}: {}",
+										failure.condition, failure.error
+									),
+									None,
+									None,
+								);
+							}
+						}
+						None
+					}
+				};
 
 				if let Some(event_condition) = decoded_log {
 					if monitor.match_conditions.events.is_empty() {
@@ -411,6 +547,7 @@ impl<T> EVMBlockFilter<T> {
 						matched_events.push(EventCondition {
 							signature: event_condition.signature.clone(),
 							expression: None,
+							match_any_emitter: false,
 						});
 						if let Some(events) = &mut matched_on_args.events {
 							events.push(event_condition);
@@ -421,12 +558,16 @@ impl<T> EVMBlockFilter<T> {
 							// Remove any whitespaces to ensure accurate matching
 							// For example: Transfer(address, address, uint256) ==
 							// Transfer(address,address,uint256)
-							if are_same_signature(&condition.signature, &event_condition.signature)
-							{
+							if (!foreign_emitter || condition.match_any_emitter)
+								&& are_same_signature(
+									&condition.signature,
+									&event_condition.signature,
+								) {
 								if condition.expression.is_none() {
 									matched_events.push(EventCondition {
 										signature: event_condition.signature.clone(),
 										expression: None,
+										match_any_emitter: condition.match_any_emitter,
 									});
 									if let Some(events) = &mut matched_on_args.events {
 										events.push(event_condition);
@@ -443,6 +584,7 @@ impl<T> EVMBlockFilter<T> {
 												matched_events.push(EventCondition {
 													signature: event_condition.signature.clone(),
 													expression: Some(expr.to_string()),
+													match_any_emitter: condition.match_any_emitter,
 												});
 												if let Some(events) = &mut matched_on_args.events {
 													events.push(event_condition);
@@ -492,7 +634,7 @@ impl<T> EVMBlockFilter<T> {
 			));
 		}
 
-		let evaluator = EVMConditionEvaluator::new(args);
+		let evaluator = EVMConditionEvaluator::new(args).with_prices(self.prices.as_deref());
 
 		// Parse the expression
 		let parsed_ast = expression::parse(expression).map_err(|e| {
@@ -519,6 +661,37 @@ impl<T> EVMBlockFilter<T> {
 		abi: &ContractSpec,
 		log: &EVMReceiptLog,
 	) -> Option<EVMMatchParamsMap> {
+		match self.decode_event_log(abi, log) {
+			Ok(decoded) => decoded,
+			Err(failure) => {
+				FilterError::internal_error(
+					format!(
+						"Failed to decode log data of {}: {}",
+						failure.condition, failure.error
+					),
+					None,
+					None,
+				);
+				None
+			}
+		}
+	}
+
+	/// Decodes an event log using the provided ABI, keeping the decoding failures of events
+	/// declared by the ABI.
+	///
+	/// # Arguments
+	/// * `abi` - Contract ABI for decoding
+	/// * `log` - Event log to decode
+	///
+	/// # Returns
+	/// The decoded event data, `None` if the ABI does not declare the event, or the failure of
+	/// an event of the ABI whose arguments did not decode, attributed to its signature
+	fn decode_event_log(
+		&self,
+		abi: &ContractSpec,
+		log: &EVMReceiptLog,
+	) -> Result<Option<EVMMatchParamsMap>, DecodeFailure> {
 		// Create contract object from ABI
 		let contract = match abi {
 			ContractSpec::EVM(evm_spec) => {
@@ -530,11 +703,11 @@ impl<T> EVMBlockFilter<T> {
 							Some(e.into()),
 							None,
 						);
-						return None;
+						return Ok(None);
 					}
 				}
 			}
-			_ => return None,
+			_ => return Ok(None),
 		};
 
 		// Find the matching Event
@@ -553,31 +726,37 @@ impl<T> EVMBlockFilter<T> {
 					None,
 					None,
 				);
-				return None;
+				return Ok(None);
 			}
 		};
+		let signature = format!(
+			"{}({})",
+			event.name,
+			event
+				.inputs
+				.iter()
+				.map(|p| p.selector_type())
+				.collect::<Vec<_>>()
+				.join(",")
+		);
+		let failure = |error: String| DecodeFailure {
+			condition: signature.clone(),
+			selector: b256_to_string(log.topics[0]),
+			error,
+			transaction_hash: b256_to_string(log.transaction_hash.unwrap_or_default()),
+		};
 
 		// Decode event in one call (covering non-indexed and indexed params)
 		let log_data = match LogData::new(log.topics.clone(), log.data.clone()) {
 			Some(data) => data,
 			None => {
-				FilterError::internal_error(
-					format!("Failed to create log data: {:?}", log.topics[0]),
-					None,
-					None,
-				);
-				return None;
+				return Err(failure("Invalid number of log topics".to_string()));
 			}
 		};
 		let decoded = match event.decode_log(&log_data) {
 			Ok(decoded) => decoded,
 			Err(e) => {
-				FilterError::internal_error(
-					format!("Failed to decode log data: {:?}", e.to_string()),
-					Some(e.into()),
-					None,
-				);
-				return None;
+				return Err(failure(e.to_string()));
 			}
 		};
 
@@ -607,20 +786,12 @@ impl<T> EVMBlockFilter<T> {
 			})
 			.collect();
 
-		Some(EVMMatchParamsMap {
-			signature: format!(
-				"{}({})",
-				event.name,
-				event
-					.inputs
-					.iter()
-					.map(|p| p.selector_type())
-					.collect::<Vec<_>>()
-					.join(",")
-			),
+		Ok(Some(EVMMatchParamsMap {
+			signature,
 			args: Some(decoded_params),
 			hex_signature: Some(format!("0x{}", hex::encode(event.selector()))),
-		})
+			log_index: log.log_index.map(|index| index.saturating_to()),
+		}))
 	}
 
 	/// Checks if a monitor has any transaction conditions that require a receipt
@@ -651,7 +822,7 @@ impl<T> EVMBlockFilter<T> {
 }
 
 #[async_trait]
-impl<T: BlockChainClient + EvmClientTrait> BlockFilter for EVMBlockFilter<T> {
+impl<T: BlockChainClient + EvmClientTrait + 'static> BlockFilter for EVMBlockFilter<T> {
 	type Client = T;
 	/// Processes a block and finds matches based on monitor conditions.
 	///
@@ -669,7 +840,7 @@ impl<T: BlockChainClient + EvmClientTrait> BlockFilter for EVMBlockFilter<T> {
 		client: &T,
 		network: &Network,
 		block: &BlockType,
-		monitors: &[Monitor],
+		monitors: &[Arc<Monitor>],
 		contract_specs: Option<&[(String, ContractSpec)]>,
 	) -> Result<Vec<MonitorMatch>, FilterError> {
 		let evm_block = match block {
@@ -702,8 +873,6 @@ impl<T: BlockChainClient + EvmClientTrait> BlockFilter for EVMBlockFilter<T> {
 			current_block_number
 		);
 
-		let mut matching_results = Vec::new();
-
 		// Cast contract specs to EVMContractSpec
 		let contract_specs = contract_specs
 			.unwrap_or(&[])
@@ -715,196 +884,363 @@ impl<T: BlockChainClient + EvmClientTrait> BlockFilter for EVMBlockFilter<T> {
 			.collect::<Vec<(String, EVMContractSpec)>>();
 
 		// Group logs by transaction hash
-		let mut logs_by_tx: std::collections::HashMap<String, Vec<EVMReceiptLog>> =
-			std::collections::HashMap::new();
-		for log in all_block_logs.clone() {
+		let mut logs_by_tx: HashMap<String, Vec<EVMReceiptLog>> = HashMap::new();
+		for log in all_block_logs.iter().cloned() {
 			let tx_hash = b256_to_string(log.transaction_hash.unwrap_or_default());
 			logs_by_tx.entry(tx_hash).or_default().push(log);
 		}
 
 		tracing::debug!("Processing {} transactions with logs", logs_by_tx.len());
 
-		for monitor in monitors {
-			tracing::debug!("Processing monitor: {:?}", monitor.name);
-			let monitored_addresses: Vec<String> = monitor
-				.addresses
+		// Receipts are fetched once for the block, whichever monitors need them
+		let concurrency = network.monitor_concurrency();
+		let receipts = if monitors
+			.iter()
+			.any(|monitor| self.needs_receipt(monitor, &all_block_logs))
+		{
+			let tx_hashes = evm_block
+				.transactions
 				.iter()
-				.map(|a| a.address.clone())
+				.map(|transaction| b256_to_string(transaction.hash))
+				.collect::<Vec<_>>();
+			stream::iter(tx_hashes)
+				.map(|tx_hash| async move {
+					let receipt = client.get_transaction_receipt(tx_hash.clone()).await?;
+					Ok::<_, FilterError>((tx_hash, receipt))
+				})
+				.buffered(concurrency)
+				.try_collect::<HashMap<_, _>>()
+				.await?
+		} else {
+			HashMap::new()
+		};
+
+		// Prices are only fetched for networks with a price oracle, failures leave them unset
+		let prices = match &self.price_oracles {
+			Some(price_oracles) => price_oracles.snapshot(network, client).await,
+			None => None,
+		};
+
+		let block = Arc::new(EVMBlockData {
+			block: evm_block.clone(),
+			logs: all_block_logs,
+			logs_by_tx,
+			receipts,
+			contract_specs,
+			network_slug: network.slug.clone(),
+		});
+		let decode_failure_tracker = self.decode_failure_tracker.clone();
+		evaluate_monitors(monitors, concurrency, move |monitor| {
+			EVMBlockFilter::<T> {
+				_client: PhantomData,
+				price_oracles: None,
+				prices: prices.clone(),
+				decode_failures: DecodeFailureLog::default(),
+				decode_failure_tracker: decode_failure_tracker.clone(),
+			}
+			.filter_monitor(monitor, &block)
+		})
+		.await
+	}
+}
+
+impl<T> EVMBlockFilter<T> {
+	/// Finds the matches of a monitor in a block
+	///
+	/// # Arguments
+	/// * `monitor` - Monitor containing match conditions
+	/// * `block` - Block data shared by the monitors
+	///
+	/// # Returns
+	/// Vector of matches of the monitor, in transaction order
+	fn filter_monitor(&self, monitor: &Monitor, block: &EVMBlockData) -> Vec<MonitorMatch> {
+		tracing::debug!("Processing monitor: {:?}", monitor.name);
+
+		// Monitors of the network's metrics watch the block itself, not its transactions
+		if monitor.match_conditions.network_metrics.is_some() {
+			return evm_network_metrics_candidate(monitor, &block.block, &block.network_slug)
+				.into_iter()
 				.collect();
+		}
 
-			// Check if this monitor needs a receipt
-			let should_fetch_receipt = self.needs_receipt(monitor, &all_block_logs);
+		// Check if this monitor needs a receipt
+		let should_fetch_receipt = self.needs_receipt(monitor, &block.logs);
+		let any_emitter_specs = AnyEmitterSpecs::new(monitor);
 
-			// Process all transactions in the block
-			for transaction in &evm_block.transactions {
+		// Process all transactions in the block
+		let empty_logs = Vec::new();
+		let matches: Vec<MonitorMatch> = block
+			.block
+			.transactions
+			.iter()
+			.flat_map(|transaction| {
 				let tx_hash = b256_to_string(transaction.hash);
-				let empty_logs = Vec::new();
-				let logs = logs_by_tx.get(&tx_hash).unwrap_or(&empty_logs);
-				let tx_hash_str = tx_hash.clone();
-
+				let logs = block.logs_by_tx.get(&tx_hash).unwrap_or(&empty_logs);
 				let receipt = if should_fetch_receipt {
-					Some(client.get_transaction_receipt(tx_hash_str).await?)
+					block.receipts.get(&tx_hash).cloned()
 				} else {
 					None
 				};
+				self.filter_transaction(
+					monitor,
+					transaction,
+					logs,
+					receipt,
+					&block.contract_specs,
+					&any_emitter_specs,
+					&block.network_slug,
+				)
+			})
+			.collect();
 
-				// Reset matched_on_args for each transaction
-				let mut matched_on_args = EVMMatchArguments {
-					events: Some(Vec::new()),
-					functions: Some(Vec::new()),
-				};
+		let failures = self.decode_failures.take();
+		if let Some(tracker) = self.decode_failure_tracker.as_ref() {
+			if !failures.is_empty() {
+				tracker.record(&block.network_slug, monitor, &block.block, failures);
+			}
+		}
+		matches
+	}
 
-				// Get transaction status from receipt
-				let tx_status = if let Some(receipt) = receipt.clone() {
-					if receipt.status.map(|s| s.to::<u64>() == 1).unwrap_or(false) {
-						TransactionStatus::Success
-					} else {
-						TransactionStatus::Failure
-					}
-				} else {
-					// Transaction receipt is only fetched when:
-					// 1. The monitor has conditions requiring receipt data (e.g., gas_used)
-					// 2. We need to verify transaction status and have no logs
-					// Otherwise, we can assume success since failed transactions don't emit logs
-					TransactionStatus::Success
-				};
+	/// Finds the matches of a monitor in a transaction
+	///
+	/// # Arguments
+	/// * `monitor` - Monitor containing match conditions
+	/// * `transaction` - The transaction to check
+	/// * `logs` - Logs emitted by the transaction
+	/// * `receipt` - Transaction receipt, if the monitor needs it
+	/// * `contract_specs` - Contract specs the function calls are decoded with
+	/// * `any_emitter_specs` - Contract specs the logs of other contracts are decoded with
+	/// * `network_slug` - Network the transaction belongs to
+	///
+	/// # Returns
+	/// Vector of matches of the monitor for the transaction
+	pub fn filter_transaction(
+		&self,
+		monitor: &Monitor,
+		transaction: &EVMTransaction,
+		logs: &[EVMReceiptLog],
+		receipt: Option<EVMTransactionReceipt>,
+		contract_specs: &[(String, EVMContractSpec)],
+		any_emitter_specs: &AnyEmitterSpecs,
+		network_slug: &str,
+	) -> Vec<MonitorMatch> {
+		let mut matches = Vec::new();
+		let monitored_addresses: Vec<String> = monitor
+			.addresses
+			.iter()
+			.map(|a| a.address.clone())
+			.collect();
 
-				// Collect all involved addresses from receipt logs, transaction.to, and transaction.from
-				let mut involved_addresses = Vec::new();
-				// Add transaction addresses
-				if let Some(from) = transaction.from {
-					involved_addresses.push(h160_to_string(from));
-				}
-				if let Some(to) = transaction.to {
-					involved_addresses.push(h160_to_string(to));
-				}
+		// Reset matched_on_args for each transaction
+		let mut matched_on_args = EVMMatchArguments {
+			events: Some(Vec::new()),
+			functions: Some(Vec::new()),
+		};
 
-				let mut matched_events = Vec::<EventCondition>::new();
-				let mut matched_transactions = Vec::<TransactionCondition>::new();
-				let mut matched_functions = Vec::<FunctionCondition>::new();
+		// Get transaction status from receipt
+		let tx_status = if let Some(receipt) = receipt.clone() {
+			if receipt.status.map(|s| s.to::<u64>() == 1).unwrap_or(false) {
+				TransactionStatus::Success
+			} else {
+				TransactionStatus::Failure
+			}
+		} else {
+			// Transaction receipt is only fetched when:
+			// 1. The monitor has conditions requiring receipt data (e.g., gas_used)
+			// 2. We need to verify transaction status and have no logs
+			// Otherwise, we can assume success since failed transactions don't emit logs
+			TransactionStatus::Success
+		};
 
-				// Check transaction match conditions
-				self.find_matching_transaction(
-					&tx_status,
-					transaction,
-					&receipt.clone(),
-					monitor,
-					&mut matched_transactions,
-				);
+		// Collect all involved addresses from receipt logs, transaction.to, and transaction.from
+		let mut involved_addresses = Vec::new();
+		// Add transaction addresses
+		if let Some(from) = transaction.from {
+			involved_addresses.push(h160_to_string(from));
+		}
+		if let Some(to) = transaction.to {
+			involved_addresses.push(h160_to_string(to));
+		}
 
-				// Check for event match conditions
-				self.find_matching_events_for_transaction(
-					logs,
-					monitor,
-					&mut matched_events,
-					&mut matched_on_args,
-					&mut involved_addresses,
-				);
+		let mut matched_events = Vec::<EventCondition>::new();
+		let mut matched_transactions = Vec::<TransactionCondition>::new();
+		let mut matched_functions = Vec::<FunctionCondition>::new();
+
+		// Check transaction match conditions
+		self.find_matching_transaction(
+			&tx_status,
+			transaction,
+			&receipt.clone(),
+			monitor,
+			&mut matched_transactions,
+		);
 
-				// Check function match conditions
-				self.find_matching_functions_for_transaction(
-					&contract_specs,
-					transaction,
-					monitor,
-					&mut matched_functions,
-					&mut matched_on_args,
-				);
+		// Check for event match conditions
+		self.find_matching_events_for_transaction(
+			logs,
+			monitor,
+			any_emitter_specs,
+			&mut matched_events,
+			&mut matched_on_args,
+			&mut involved_addresses,
+		);
 
-				// Remove duplicates
-				involved_addresses.sort_unstable();
-				involved_addresses.dedup();
-
-				let has_address_match = monitored_addresses.iter().any(|addr| {
-					involved_addresses
-						.iter()
-						.map(|a| normalize_address(a))
-						.collect::<Vec<String>>()
-						.contains(&normalize_address(addr))
-				});
-
-				// Only proceed if we have a matching address
-				if has_address_match {
-					let monitor_conditions = &monitor.match_conditions;
-					let has_event_match =
-						!monitor_conditions.events.is_empty() && !matched_events.is_empty();
-					let has_function_match =
-						!monitor_conditions.functions.is_empty() && !matched_functions.is_empty();
-					let has_transaction_match = !monitor_conditions.transactions.is_empty()
-						&& !matched_transactions.is_empty();
-
-					let should_match: bool = match (
-						monitor_conditions.events.is_empty(),
-						monitor_conditions.functions.is_empty(),
-						monitor_conditions.transactions.is_empty(),
-					) {
-						// Case 1: No conditions defined, match everything
-						(true, true, true) => true,
-
-						// Case 2: Only transaction conditions defined
-						(true, true, false) => has_transaction_match,
-
-						// Case 3: No transaction conditions, match based on events/functions
-						(_, _, true) => has_event_match || has_function_match,
-
-						// Case 4: Transaction conditions exist, they must be satisfied along
-						// with events/functions
-						_ => (has_event_match || has_function_match) && has_transaction_match,
-					};
-
-					if should_match {
-						matching_results.push(MonitorMatch::EVM(Box::new(EVMMonitorMatch {
-							monitor: Monitor {
-								// Omit ABI from monitor since we do not need it here
-								addresses: monitor
-									.addresses
-									.iter()
-									.map(|addr| AddressWithSpec {
-										contract_spec: None,
-										..addr.clone()
-									})
-									.collect(),
-								..monitor.clone()
-							},
-							transaction: transaction.clone(),
-							receipt,
-							logs: Some(logs.clone()),
-							network_slug: network.slug.clone(),
-							matched_on: MatchConditions {
-								events: matched_events
-									.clone()
-									.into_iter()
-									.filter(|_| has_event_match)
-									.collect(),
-								functions: matched_functions
-									.clone()
-									.into_iter()
-									.filter(|_| has_function_match)
-									.collect(),
-								transactions: matched_transactions
-									.clone()
-									.into_iter()
-									.filter(|_| has_transaction_match)
-									.collect(),
-							},
-							matched_on_args: Some(EVMMatchArguments {
-								events: if has_event_match {
-									matched_on_args.events.clone()
-								} else {
-									None
-								},
-								functions: if has_function_match {
-									matched_on_args.functions.clone()
-								} else {
-									None
-								},
-							}),
-						})));
-					}
-				}
+		// Check function match conditions
+		self.find_matching_functions_for_transaction(
+			contract_specs,
+			transaction,
+			monitor,
+			&mut matched_functions,
+			&mut matched_on_args,
+		);
+
+		// Remove duplicates
+		involved_addresses.sort_unstable();
+		involved_addresses.dedup();
+
+		// Events matched from any emitter do not require a monitored address to be involved
+		let has_address_match = matched_events.iter().any(|event| event.match_any_emitter)
+			|| monitored_addresses.iter().any(|addr| {
+				involved_addresses
+					.iter()
+					.map(|a| normalize_address(a))
+					.collect::<Vec<String>>()
+					.contains(&normalize_address(addr))
+			});
+
+		// Only proceed if we have a matching address
+		if has_address_match {
+			let monitor_conditions = &monitor.match_conditions;
+			let has_event_match =
+				!monitor_conditions.events.is_empty() && !matched_events.is_empty();
+			let has_function_match =
+				!monitor_conditions.functions.is_empty() && !matched_functions.is_empty();
+			let has_transaction_match =
+				!monitor_conditions.transactions.is_empty() && !matched_transactions.is_empty();
+
+			let should_match: bool = match (
+				monitor_conditions.events.is_empty(),
+				monitor_conditions.functions.is_empty(),
+				monitor_conditions.transactions.is_empty(),
+			) {
+				// Case 1: No conditions defined, match everything unless the monitor
+				// only tracks sender activity
+				(true, true, true) => monitor_conditions.sender_activity.is_none(),
+
+				// Case 2: Only transaction conditions defined
+				(true, true, false) => has_transaction_match,
+
+				// Case 3: No transaction conditions, match based on events/functions
+				(_, _, true) => has_event_match || has_function_match,
+
+				// Case 4: Transaction conditions exist, they must be satisfied along
+				// with events/functions
+				_ => (has_event_match || has_function_match) && has_transaction_match,
+			};
+
+			if should_match {
+				matches.push(MonitorMatch::EVM(Box::new(EVMMonitorMatch {
+					monitor: Monitor {
+						// Omit ABI from monitor since we do not need it here
+						addresses: monitor
+							.addresses
+							.iter()
+							.map(|addr| AddressWithSpec {
+								contract_spec: None,
+								..addr.clone()
+							})
+							.collect(),
+						..monitor.clone()
+					},
+					transaction: transaction.clone(),
+					receipt,
+					logs: Some(logs.to_vec()),
+					network_slug: network_slug.to_string(),
+					matched_on: MatchConditions {
+						events: matched_events
+							.clone()
+							.into_iter()
+							.filter(|_| has_event_match)
+							.collect(),
+						functions: matched_functions
+							.clone()
+							.into_iter()
+							.filter(|_| has_function_match)
+							.collect(),
+						transactions: matched_transactions
+							.clone()
+							.into_iter()
+							.filter(|_| has_transaction_match)
+							.collect(),
+						sender_activity: None,
+						baseline: None,
+						network_metrics: None,
+					},
+					matched_on_args: Some(EVMMatchArguments {
+						events: if has_event_match {
+							matched_on_args.events.clone()
+						} else {
+							None
+						},
+						functions: if has_function_match {
+							matched_on_args.functions.clone()
+						} else {
+							None
+						},
+					}),
+					sender_activity: None,
+					baseline: None,
+					network_metrics: None,
+					timing: None,
+					origin: Default::default(),
+				})));
+			}
+		}
+
+		// Transactions sent by a monitored address are candidates of the sender
+		// activity condition, checked against the nonce history of the sender once the
+		// block is handled in order
+		if let Some(condition) = &monitor.match_conditions.sender_activity {
+			let is_monitored_sender = transaction.from.is_some_and(|from| {
+				monitored_addresses
+					.iter()
+					.any(|addr| are_same_address(addr, &h160_to_string(from)))
+			});
+			if is_monitored_sender {
+				matches.push(MonitorMatch::EVM(Box::new(EVMMonitorMatch {
+					monitor: Monitor {
+						addresses: monitor
+							.addresses
+							.iter()
+							.map(|addr| AddressWithSpec {
+								contract_spec: None,
+								..addr.clone()
+							})
+							.collect(),
+						..monitor.clone()
+					},
+					transaction: transaction.clone(),
+					receipt: None,
+					logs: Some(logs.to_vec()),
+					network_slug: network_slug.to_string(),
+					matched_on: MatchConditions {
+						sender_activity: Some(condition.clone()),
+						..Default::default()
+					},
+					matched_on_args: None,
+					sender_activity: Some(EVMSenderActivity::observed(
+						transaction.nonce.saturating_to::<u64>(),
+					)),
+					baseline: None,
+					network_metrics: None,
+					timing: None,
+					origin: Default::default(),
+				})));
 			}
 		}
 
-		Ok(matching_results)
+		matches
 	}
 }
 
@@ -918,16 +1254,22 @@ mod tests {
 	};
 
 	use super::*;
+	use crate::{models::DecodeFailureAlert, utils::metrics::ABI_DECODE_FAILURES};
 	use alloy::core::dyn_abi::{DynSolValue, JsonAbiExt};
 	use alloy::core::json_abi::{Function, Param, StateMutability};
 	use alloy::primitives::keccak256;
 	use alloy::primitives::{Address, Bytes, B256, U256};
 	use serde_json::json;
 	use std::str::FromStr;
+	use tracing_test::traced_test;
 
 	fn create_test_filter() -> EVMBlockFilter<()> {
 		EVMBlockFilter::<()> {
 			_client: PhantomData,
+			price_oracles: None,
+			prices: None,
+			decode_failures: Default::default(),
+			decode_failure_tracker: None,
 		}
 	}
 
@@ -945,6 +1287,9 @@ mod tests {
 				events: event_conditions,
 				functions: function_conditions,
 				transactions: transaction_conditions,
+				sender_activity: None,
+				baseline: None,
+				network_metrics: None,
 			})
 			.addresses_with_spec(
 				addresses
@@ -1013,6 +1358,9 @@ mod tests {
 		AddressWithSpec {
 			address: address.to_string(),
 			contract_spec: spec,
+			contract: None,
+			label: None,
+			annotations: HashMap::new(),
 		}
 	}
 
@@ -1954,6 +2302,9 @@ mod tests {
 				}],
 				events: vec![],
 				transactions: vec![],
+				sender_activity: None,
+				baseline: None,
+				network_metrics: None,
 			})
 			.addresses_with_spec(vec![(
 				contract_with_spec.0.clone(),
@@ -2000,6 +2351,7 @@ mod tests {
 			vec![EventCondition {
 				signature: "Transfer(address,address,uint256)".to_string(),
 				expression: None,
+				match_any_emitter: false,
 			}], // events
 			vec![], // functions
 			vec![], // transactions
@@ -2022,6 +2374,7 @@ mod tests {
 		filter.find_matching_events_for_transaction(
 			&receipt.logs,
 			&monitor,
+			&AnyEmitterSpecs::new(&monitor),
 			&mut matched_events,
 			&mut matched_on_args,
 			&mut involved_addresses,
@@ -2055,6 +2408,7 @@ mod tests {
 			vec![EventCondition {
 				signature: "Transfer(address,address,uint256)".to_string(),
 				expression: Some("value > 500".to_string()),
+				match_any_emitter: false,
 			}], // events
 			vec![], // functions
 			vec![], // transactions
@@ -2077,6 +2431,7 @@ mod tests {
 		filter.find_matching_events_for_transaction(
 			&receipt.logs,
 			&monitor,
+			&AnyEmitterSpecs::new(&monitor),
 			&mut matched_events,
 			&mut matched_on_args,
 			&mut involved_addresses,
@@ -2105,6 +2460,7 @@ mod tests {
 		filter.find_matching_events_for_transaction(
 			&receipt_no_match.logs,
 			&monitor,
+			&AnyEmitterSpecs::new(&monitor),
 			&mut matched_events,
 			&mut matched_on_args,
 			&mut involved_addresses,
@@ -2149,6 +2505,7 @@ mod tests {
 		filter.find_matching_events_for_transaction(
 			&receipt.logs,
 			&monitor,
+			&AnyEmitterSpecs::new(&monitor),
 			&mut matched_events,
 			&mut matched_on_args,
 			&mut involved_addresses,
@@ -2158,9 +2515,181 @@ mod tests {
 		assert_eq!(involved_addresses.len(), 0);
 	}
 
+	#[test]
+	fn test_find_matching_events_only_from_watched_emitters_unless_widened() {
+		let filter = create_test_filter();
+		let watched = Address::from_str("0x0000000000000000000000000000000000004321").unwrap();
+		let other = Address::from_str("0x0000000000000000000000000000000000009999").unwrap();
+		let from = Address::from_str("0x0000000000000000000000000000000000001234").unwrap();
+		let to = Address::from_str("0x0000000000000000000000000000000000005678").unwrap();
+		let transfer_topic = "0xddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef";
+		let value = "00000000000000000000000000000000000000000000000000000000000003e8";
+		let logs = vec![
+			create_test_log(watched, transfer_topic, from, to, value),
+			create_test_log(other, transfer_topic, from, to, value),
+		];
+		let monitor_with = |match_any_emitter: bool| {
+			create_test_monitor(
+				vec![EventCondition {
+					signature: "Transfer(address, address, uint256)".to_string(),
+					expression: Some("value > 500".to_string()),
+					match_any_emitter,
+				}],
+				vec![],
+				vec![],
+				vec![create_test_address(
+					"0x0000000000000000000000000000000000004321",
+					Some(create_test_abi("event")),
+				)],
+			)
+		};
+		let find = |monitor: &Monitor, logs: &[EVMReceiptLog]| {
+			let mut matched_events = Vec::new();
+			let mut matched_on_args = EVMMatchArguments {
+				events: Some(Vec::new()),
+				functions: None,
+			};
+			let mut involved_addresses = Vec::new();
+			filter.find_matching_events_for_transaction(
+				logs,
+				monitor,
+				&AnyEmitterSpecs::new(monitor),
+				&mut matched_events,
+				&mut matched_on_args,
+				&mut involved_addresses,
+			);
+			(matched_events, involved_addresses)
+		};
+
+		// By default, only the log of the watched emitter matches
+		let (matched_events, involved_addresses) = find(&monitor_with(false), &logs);
+		assert_eq!(matched_events.len(), 1);
+		assert!(!matched_events[0].match_any_emitter);
+		assert_eq!(
+			involved_addresses,
+			vec!["0x0000000000000000000000000000000000004321"]
+		);
+
+		// Widened, the log of the other emitter matches too
+		let (matched_events, involved_addresses) = find(&monitor_with(true), &logs);
+		assert_eq!(matched_events.len(), 2);
+		assert!(matched_events.iter().all(|event| event.match_any_emitter));
+		assert_eq!(involved_addresses.len(), 1);
+
+		// A widened event matches even if no monitored address is involved
+		let transaction = TransactionBuilder::new().from(from).to(to).build();
+		let monitor = monitor_with(false);
+		let strict = filter.filter_transaction(
+			&monitor,
+			&transaction,
+			&logs[1..],
+			None,
+			&[],
+			&AnyEmitterSpecs::new(&monitor),
+			"evm_mainnet",
+		);
+		assert!(strict.is_empty());
+		let monitor = monitor_with(true);
+		let widened = filter.filter_transaction(
+			&monitor,
+			&transaction,
+			&logs[1..],
+			None,
+			&[],
+			&AnyEmitterSpecs::new(&monitor),
+			"evm_mainnet",
+		);
+		assert_eq!(widened.len(), 1);
+	}
+
 	//////////////////////////////////////////////////////////////////////////////
 	// Test cases for evaluate_expression method:
 	//////////////////////////////////////////////////////////////////////////////
+	#[test]
+	#[traced_test]
+	fn test_filter_monitor_surfaces_event_decode_failures() {
+		// The ABI declares `value` indexed while the contract emits it in the log data
+		let mismatched_abi = ContractSpec::EVM(EVMContractSpec::from(json!([{
+			"type": "event",
+			"name": "Transfer",
+			"inputs": [
+				{"name": "from", "type": "address", "indexed": true},
+				{"name": "to", "type": "address", "indexed": true},
+				{"name": "value", "type": "uint256", "indexed": true}
+			],
+			"anonymous": false,
+		}])));
+		let contract = "0x0000000000000000000000000000000000004321";
+		let mut monitor = create_test_monitor(
+			vec![EventCondition {
+				signature: "Transfer(address, address, uint256)".to_string(),
+				expression: None,
+				match_any_emitter: false,
+			}],
+			vec![],
+			vec![],
+			vec![create_test_address(contract, Some(mismatched_abi))],
+		);
+		monitor.name = "decode_failure_alerts".to_string();
+		monitor.alert_on_decode_failure = Some(DecodeFailureAlert {
+			threshold: 2,
+			triggers: vec![],
+		});
+
+		let transaction = TransactionBuilder::new()
+			.hash(B256::with_last_byte(7))
+			.build();
+		let tx_hash = b256_to_string(transaction.hash);
+		let mut log = create_test_log(
+			Address::from_str(contract).unwrap(),
+			"0xddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef",
+			Address::from_str("0x0000000000000000000000000000000000001234").unwrap(),
+			Address::from_str("0x0000000000000000000000000000000000005678").unwrap(),
+			"0000000000000000000000000000000000000000000000000000000000000064",
+		);
+		log.transaction_hash = Some(transaction.hash);
+		let mut block = EVMBlock::default();
+		block.0.number = Some(U64::from(1));
+		block.0.transactions = vec![transaction];
+		let block = EVMBlockData {
+			block,
+			logs: vec![log.clone()],
+			logs_by_tx: HashMap::from([(tx_hash.clone(), vec![log])]),
+			receipts: HashMap::new(),
+			contract_specs: vec![],
+			network_slug: "decode_failure_network".to_string(),
+		};
+
+		let tracker = Arc::new(DecodeFailures::default());
+		let filter = EVMBlockFilter {
+			decode_failure_tracker: Some(tracker.clone()),
+			..create_test_filter()
+		};
+		assert!(filter.filter_monitor(&monitor, &block).is_empty());
+		assert!(logs_contain(
+			"Failed to decode the arguments of condition 'Transfer(address, address, uint256)'"
+		));
+		assert!(tracker.take_alerts("decode_failure_network").is_empty());
+
+		assert!(filter.filter_monitor(&monitor, &block).is_empty());
+		assert_eq!(
+			ABI_DECODE_FAILURES
+				.with_label_values(&[
+					"decode_failure_network",
+					"decode_failure_alerts",
+					"Transfer(address, address, uint256)",
+				])
+				.get(),
+			2
+		);
+		let alerts = tracker.take_alerts("decode_failure_network");
+		assert_eq!(alerts.len(), 1);
+		assert_eq!(alerts[0].condition, "Transfer(address, address, uint256)");
+		assert_eq!(alerts[0].selector, b256_to_string(block.logs[0].topics[0]));
+		assert_eq!(alerts[0].count, 2);
+		assert_eq!(alerts[0].monitor_match.transaction_hash(), tx_hash);
+	}
+
 	fn create_test_param(name: &str, value: &str, kind: &str) -> EVMMatchParamEntry {
 		EVMMatchParamEntry {
 			name: name.to_string(),
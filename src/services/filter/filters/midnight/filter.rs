@@ -8,7 +8,7 @@
 
 use async_trait::async_trait;
 use midnight_node_ledger_helpers::NetworkId;
-use std::marker::PhantomData;
+use std::{marker::PhantomData, sync::Arc};
 use tracing::instrument;
 
 use crate::{
@@ -261,7 +261,7 @@ impl<T: BlockChainClient + MidnightClientTrait> BlockFilter for MidnightBlockFil
 		client: &T,
 		network: &Network,
 		block: &BlockType,
-		monitors: &[Monitor],
+		monitors: &[Arc<Monitor>],
 		_contract_specs: Option<&[(String, ContractSpec)]>,
 	) -> Result<Vec<MonitorMatch>, FilterError> {
 		let midnight_block = match block {
@@ -306,6 +306,10 @@ impl<T: BlockChainClient + MidnightClientTrait> BlockFilter for MidnightBlockFil
 
 		for monitor in monitors {
 			tracing::debug!("Processing monitor: {:?}", monitor.name);
+			// Network metrics are not available for Midnight blocks
+			if monitor.match_conditions.network_metrics.is_some() {
+				continue;
+			}
 			let monitored_addresses: Vec<String> = monitor
 				.addresses
 				.iter()
@@ -367,7 +371,7 @@ impl<T: BlockChainClient + MidnightClientTrait> BlockFilter for MidnightBlockFil
 
 				if should_match {
 					matching_results.push(MonitorMatch::Midnight(Box::new(MidnightMonitorMatch {
-						monitor: monitor.clone(),
+						monitor: Monitor::clone(monitor),
 						transaction: transaction.clone(),
 						network_slug: network.slug.clone(),
 						matched_on: MatchConditions {
@@ -386,6 +390,9 @@ impl<T: BlockChainClient + MidnightClientTrait> BlockFilter for MidnightBlockFil
 								.into_iter()
 								.filter(|_| has_transaction_match)
 								.collect(),
+							sender_activity: None,
+							baseline: None,
+							network_metrics: None,
 						},
 						matched_on_args: Some(MidnightMatchArguments {
 							events: if has_event_match {
@@ -399,6 +406,8 @@ impl<T: BlockChainClient + MidnightClientTrait> BlockFilter for MidnightBlockFil
 								None
 							},
 						}),
+						timing: None,
+						origin: Default::default(),
 					})));
 				}
 			}
@@ -426,6 +435,7 @@ mod tests {
 		let mut matched_events = vec![EventCondition {
 			signature: "test".to_string(),
 			expression: None,
+			match_any_emitter: false,
 		}];
 		let mut matched_on_args = MidnightMatchArguments {
 			events: Some(Vec::new()),
@@ -24,10 +24,15 @@ pub mod midnight {
 }
 
 use async_trait::async_trait;
+use futures::{stream, StreamExt};
+use std::sync::Arc;
 
 use crate::{
 	models::{BlockType, ContractSpec, Monitor, MonitorMatch, Network},
-	services::{blockchain::BlockFilterFactory, filter::error::FilterError},
+	services::{
+		blockchain::BlockFilterFactory,
+		filter::{error::FilterError, DecodeFailures, PriceOracles},
+	},
 };
 
 /// Trait for filtering blockchain data
@@ -42,7 +47,7 @@ pub trait BlockFilter {
 		client: &Self::Client,
 		network: &Network,
 		block: &BlockType,
-		monitors: &[Monitor],
+		monitors: &[Arc<Monitor>],
 		contract_specs: Option<&[(String, ContractSpec)]>,
 	) -> Result<Vec<MonitorMatch>, FilterError>;
 }
@@ -54,7 +59,7 @@ pub trait FilterServiceTrait: Send + Sync {
 		client: &T,
 		network: &Network,
 		block: &BlockType,
-		monitors: &[Monitor],
+		monitors: &[Arc<Monitor>],
 		contract_specs: Option<&[(String, ContractSpec)]>,
 	) -> Result<Vec<MonitorMatch>, FilterError>;
 }
@@ -62,11 +67,41 @@ pub trait FilterServiceTrait: Send + Sync {
 /// Service for filtering blockchain data
 ///
 /// This service provides a way to filter blockchain data based on a set of monitors.
-pub struct FilterService {}
+pub struct FilterService {
+	/// Price oracles of the networks, refreshed by the filters of their blocks
+	price_oracles: Arc<PriceOracles>,
+	/// Decoding failures of the monitor conditions, recorded by the filters
+	decode_failures: Arc<DecodeFailures>,
+}
 
 impl FilterService {
 	pub fn new() -> Self {
-		FilterService {}
+		FilterService {
+			price_oracles: Arc::new(PriceOracles::default()),
+			decode_failures: Arc::new(DecodeFailures::default()),
+		}
+	}
+
+	/// Shares the given price oracles with the filters, e.g. to read their prices elsewhere
+	pub fn with_price_oracles(mut self, price_oracles: Arc<PriceOracles>) -> Self {
+		self.price_oracles = price_oracles;
+		self
+	}
+
+	/// Returns the price oracles of the networks
+	pub fn price_oracles(&self) -> &Arc<PriceOracles> {
+		&self.price_oracles
+	}
+
+	/// Shares the given decoding failures with the filters, e.g. to alert on them elsewhere
+	pub fn with_decode_failures(mut self, decode_failures: Arc<DecodeFailures>) -> Self {
+		self.decode_failures = decode_failures;
+		self
+	}
+
+	/// Returns the decoding failures of the monitor conditions
+	pub fn decode_failures(&self) -> &Arc<DecodeFailures> {
+		&self.decode_failures
 	}
 }
 
@@ -82,10 +117,10 @@ impl FilterService {
 		client: &T,
 		network: &Network,
 		block: &BlockType,
-		monitors: &[Monitor],
+		monitors: &[Arc<Monitor>],
 		contract_specs: Option<&[(String, ContractSpec)]>,
 	) -> Result<Vec<MonitorMatch>, FilterError> {
-		let filter = T::filter();
+		let filter = T::filter(self);
 		filter
 			.filter_block(client, network, block, monitors, contract_specs)
 			.await
@@ -99,12 +134,55 @@ impl FilterServiceTrait for FilterService {
 		client: &T,
 		network: &Network,
 		block: &BlockType,
-		monitors: &[Monitor],
+		monitors: &[Arc<Monitor>],
 		contract_specs: Option<&[(String, ContractSpec)]>,
 	) -> Result<Vec<MonitorMatch>, FilterError> {
-		let filter = T::filter();
+		let filter = T::filter(self);
 		filter
 			.filter_block(client, network, block, monitors, contract_specs)
 			.await
 	}
 }
+
+/// Evaluates the monitors of a block concurrently
+///
+/// Each monitor is evaluated in its own task, so that the runtime spreads expensive monitors
+/// over its worker threads, with at most `concurrency` monitors in flight. Matches are ordered
+/// by monitor name, keeping the order of the matches of each monitor, so that the result does
+/// not depend on the concurrency nor on the order of `monitors`. Matches are dispatched in this
+/// order, which decides the first match of a dedup group.
+///
+/// # Arguments
+/// * `monitors` - Monitors to evaluate, shared with the tasks instead of copied
+/// * `concurrency` - Maximum number of monitors evaluated at the same time
+/// * `evaluate` - Returns the matches of a monitor, sharing the block data between monitors
+///
+/// # Returns
+/// Matches of all the monitors
+pub(crate) async fn evaluate_monitors<F>(
+	monitors: &[Arc<Monitor>],
+	concurrency: usize,
+	evaluate: F,
+) -> Result<Vec<MonitorMatch>, FilterError>
+where
+	F: Fn(&Monitor) -> Vec<MonitorMatch> + Send + Sync + 'static,
+{
+	let evaluate = Arc::new(evaluate);
+	let results = stream::iter(monitors.iter().cloned())
+		.map(|monitor| {
+			let evaluate = evaluate.clone();
+			tokio::spawn(async move { evaluate(monitor.as_ref()) })
+		})
+		.buffered(concurrency.max(1))
+		.collect::<Vec<_>>()
+		.await;
+
+	let mut matches = Vec::new();
+	for result in results {
+		matches.extend(result.map_err(|e| {
+			FilterError::internal_error("Monitor evaluation failed", Some(e.into()), None)
+		})?);
+	}
+	matches.sort_by(|a, b| a.monitor().name.cmp(&b.monitor().name));
+	Ok(matches)
+}
@@ -6,7 +6,7 @@
 //! - Compare different types of parameter values
 //! - Evaluate complex matching expressions
 
-use std::marker::PhantomData;
+use std::{marker::PhantomData, sync::Arc};
 
 use async_trait::async_trait;
 use base64::Engine;
@@ -17,22 +17,22 @@ use tracing::instrument;
 use crate::{
 	models::{
 		BlockType, ContractSpec, EventCondition, FunctionCondition, MatchConditions, Monitor,
-		MonitorMatch, Network, StellarContractFunction, StellarEvent, StellarEventParamLocation,
-		StellarFormattedContractSpec, StellarMatchArguments, StellarMatchParamEntry,
-		StellarMatchParamsMap, StellarMonitorMatch, StellarTransaction, TransactionCondition,
-		TransactionStatus,
+		MonitorMatch, Network, StellarBlock, StellarContractFunction, StellarEvent,
+		StellarEventParamLocation, StellarFormattedContractSpec, StellarMatchArguments,
+		StellarMatchParamEntry, StellarMatchParamsMap, StellarMonitorMatch, StellarTransaction,
+		TransactionCondition, TransactionStatus,
 	},
 	services::{
 		blockchain::{BlockChainClient, StellarClientTrait},
 		filter::{
 			expression::{self, EvaluationError},
-			filters::stellar::evaluator::StellarConditionEvaluator,
+			filters::{evaluate_monitors, stellar::evaluator::StellarConditionEvaluator},
 			stellar_helpers::{
 				are_same_signature, get_kind_from_value, normalize_address, parse_xdr_value,
 				parse_xdr_value_with_stellar_value, process_invoke_host_function,
 				unpack_stellar_value,
 			},
-			BlockFilter, FilterError,
+			stellar_network_metrics_candidate, BlockFilter, FilterError,
 		},
 	},
 };
@@ -49,6 +49,15 @@ pub struct StellarBlockFilter<T> {
 	pub _client: PhantomData<T>,
 }
 
+/// Data of a block shared by the monitors evaluated for it
+struct StellarBlockData {
+	ledger: StellarBlock,
+	transactions: Vec<StellarTransaction>,
+	events: Vec<StellarEvent>,
+	contract_specs: Vec<(String, StellarFormattedContractSpec)>,
+	network_slug: String,
+}
+
 impl<T> StellarBlockFilter<T> {
 	/// Finds matching transactions based on monitor conditions
 	///
@@ -335,7 +344,7 @@ impl<T> StellarBlockFilter<T> {
 				}
 			};
 
-			for operation in tx_to_process.tx.operations.iter() {
+			for (operation_index, operation) in tx_to_process.tx.operations.iter().enumerate() {
 				if let OperationBody::InvokeHostFunction(invoke_host_function) = &operation.body {
 					let (parsed_operation, contract_spec) =
 						process_invoke_host_function(invoke_host_function, Some(contract_specs));
@@ -379,6 +388,8 @@ impl<T> StellarBlockFilter<T> {
 								functions.push(StellarMatchParamsMap {
 									signature: parsed_operation.function_signature.clone(),
 									args: Some(param_entries),
+									operation_index: Some(operation_index as u32),
+									event_index: None,
 								});
 							}
 						} else {
@@ -407,6 +418,10 @@ impl<T> StellarBlockFilter<T> {
 															.function_signature
 															.clone(),
 														args: Some(param_entries.clone()),
+														operation_index: Some(
+															operation_index as u32,
+														),
+														event_index: None,
 													});
 												}
 												break;
@@ -433,6 +448,8 @@ impl<T> StellarBlockFilter<T> {
 													.function_signature
 													.clone(),
 												args: Some(param_entries.clone()),
+												operation_index: Some(operation_index as u32),
+												event_index: None,
 											});
 										}
 										break;
@@ -485,6 +502,7 @@ impl<T> StellarBlockFilter<T> {
 				matched_events.push(EventCondition {
 					signature: event.signature.clone(),
 					expression: None,
+					match_any_emitter: false,
 				});
 				if let Some(events) = &mut matched_on_args.events {
 					events.push(event.clone());
@@ -505,6 +523,7 @@ impl<T> StellarBlockFilter<T> {
 										matched_events.push(EventCondition {
 											signature: event.signature.clone(),
 											expression: Some(expr.clone()),
+											match_any_emitter: false,
 										});
 										if let Some(events) = &mut matched_on_args.events {
 											events.push(event.clone());
@@ -526,6 +545,7 @@ impl<T> StellarBlockFilter<T> {
 							matched_events.push(EventCondition {
 								signature: event.signature.clone(),
 								expression: None,
+								match_any_emitter: false,
 							});
 						}
 					}
@@ -748,8 +768,11 @@ impl<T> StellarBlockFilter<T> {
 				}
 			);
 
+			let location = event.location();
 			let decoded_event = StellarMatchParamsMap {
 				signature: event_signature,
+				operation_index: location.map(|(operation_index, _)| operation_index),
+				event_index: location.map(|(_, event_index)| event_index),
 				args: Some(
 					[&indexed_args[..], &value_args[..]]
 						.concat()
@@ -820,7 +843,7 @@ impl<T> StellarBlockFilter<T> {
 }
 
 #[async_trait]
-impl<T: BlockChainClient + StellarClientTrait> BlockFilter for StellarBlockFilter<T> {
+impl<T: BlockChainClient + StellarClientTrait + 'static> BlockFilter for StellarBlockFilter<T> {
 	type Client = T;
 	/// Filters a Stellar block against provided monitors
 	///
@@ -839,7 +862,7 @@ impl<T: BlockChainClient + StellarClientTrait> BlockFilter for StellarBlockFilte
 		client: &Self::Client,
 		network: &Network,
 		block: &BlockType,
-		monitors: &[Monitor],
+		monitors: &[Arc<Monitor>],
 		contract_specs: Option<&[(String, ContractSpec)]>,
 	) -> Result<Vec<MonitorMatch>, FilterError> {
 		let stellar_block = match block {
@@ -871,7 +894,18 @@ impl<T: BlockChainClient + StellarClientTrait> BlockFilter for StellarBlockFilte
 
 		if transactions.is_empty() {
 			tracing::debug!("No transactions found for block {}", stellar_block.sequence);
-			return Ok(vec![]);
+			// Ledgers without transactions still count for the network's metrics
+			return Ok(monitors
+				.iter()
+				.filter_map(|monitor| {
+					stellar_network_metrics_candidate(
+						monitor,
+						stellar_block,
+						&transactions,
+						&network.slug,
+					)
+				})
+				.collect());
 		}
 
 		tracing::debug!("Processing {} transaction(s)", transactions.len());
@@ -890,8 +924,6 @@ impl<T: BlockChainClient + StellarClientTrait> BlockFilter for StellarBlockFilte
 		tracing::debug!("Processing {} event(s)", events.len());
 		tracing::debug!("Processing {} monitor(s)", monitors.len());
 
-		let mut matching_results = Vec::new();
-
 		// Cast contract specs to StellarContractSpec
 		let contract_specs = contract_specs
 			.unwrap_or(&[])
@@ -905,120 +937,164 @@ impl<T: BlockChainClient + StellarClientTrait> BlockFilter for StellarBlockFilte
 			})
 			.collect::<Vec<(String, StellarFormattedContractSpec)>>();
 
-		// Process each monitor first
-		for monitor in monitors {
-			tracing::debug!("Processing monitor: {}", monitor.name);
+		let block = Arc::new(StellarBlockData {
+			ledger: (**stellar_block).clone(),
+			transactions,
+			events,
+			contract_specs,
+			network_slug: network.slug.clone(),
+		});
+		evaluate_monitors(monitors, network.monitor_concurrency(), move |monitor| {
+			StellarBlockFilter::<T> {
+				_client: PhantomData,
+			}
+			.filter_monitor(monitor, &block)
+		})
+		.await
+	}
+}
 
-			let monitored_addresses = monitor
-				.addresses
-				.iter()
-				.map(|addr| normalize_address(&addr.address))
-				.collect::<Vec<String>>();
-
-			let decoded_events = self.decode_events(&events, &monitored_addresses, &contract_specs);
-
-			// Then process transactions for this monitor
-			for transaction in &transactions {
-				let mut matched_transactions = Vec::<TransactionCondition>::new();
-				let mut matched_functions = Vec::<FunctionCondition>::new();
-				let mut matched_events = Vec::<EventCondition>::new();
-				let mut matched_on_args = StellarMatchArguments {
-					events: Some(Vec::new()),
-					functions: Some(Vec::new()),
-				};
+impl<T> StellarBlockFilter<T> {
+	/// Finds the matches of a monitor in a block
+	///
+	/// # Arguments
+	/// * `monitor` - Monitor containing match conditions
+	/// * `block` - Block data shared by the monitors
+	///
+	/// # Returns
+	/// Vector of matches of the monitor, in transaction order
+	fn filter_monitor(&self, monitor: &Monitor, block: &StellarBlockData) -> Vec<MonitorMatch> {
+		tracing::debug!("Processing monitor: {}", monitor.name);
+
+		// Monitors of the network's metrics watch the ledger itself, not its transactions
+		if monitor.match_conditions.network_metrics.is_some() {
+			return stellar_network_metrics_candidate(
+				monitor,
+				&block.ledger,
+				&block.transactions,
+				&block.network_slug,
+			)
+			.into_iter()
+			.collect();
+		}
 
-				tracing::debug!("Processing transaction: {:?}", transaction.hash());
-
-				self.find_matching_transaction(transaction, monitor, &mut matched_transactions);
-
-				// Decoded events already account for monitored addresses, so no need to pass in
-				// monitored_addresses
-				self.find_matching_events_for_transaction(
-					&decoded_events,
-					transaction,
-					monitor,
-					&mut matched_events,
-					&mut matched_on_args,
-				);
-
-				self.find_matching_functions_for_transaction(
-					&monitored_addresses,
-					&contract_specs,
-					transaction,
-					monitor,
-					&mut matched_functions,
-					&mut matched_on_args,
-				);
-
-				let monitor_conditions = &monitor.match_conditions;
-				let has_event_match =
-					!monitor_conditions.events.is_empty() && !matched_events.is_empty();
-				let has_function_match =
-					!monitor_conditions.functions.is_empty() && !matched_functions.is_empty();
-				let has_transaction_match =
-					!monitor_conditions.transactions.is_empty() && !matched_transactions.is_empty();
-
-				let should_match = match (
-					monitor_conditions.events.is_empty(),
-					monitor_conditions.functions.is_empty(),
-					monitor_conditions.transactions.is_empty(),
-				) {
-					// Case 1: No conditions defined, match everything
-					(true, true, true) => true,
-
-					// Case 2: Only transaction conditions defined
-					(true, true, false) => has_transaction_match,
-
-					// Case 3: No transaction conditions, match based on events/functions
-					(_, _, true) => has_event_match || has_function_match,
-
-					// Case 4: Transaction conditions exist, they must be satisfied along with
-					// events/functions
-					_ => (has_event_match || has_function_match) && has_transaction_match,
-				};
+		let mut matches = Vec::new();
 
-				if should_match {
-					matching_results.push(MonitorMatch::Stellar(Box::new(StellarMonitorMatch {
-						monitor: monitor.clone(),
-						// The conversion to StellarTransaction triggers decoding of the transaction
-						#[allow(clippy::useless_conversion)]
-						transaction: StellarTransaction::from(transaction.clone()),
-						ledger: *stellar_block.clone(),
-						network_slug: network.slug.clone(),
-						matched_on: MatchConditions {
-							events: matched_events
-								.clone()
-								.into_iter()
-								.filter(|_| has_event_match)
-								.collect(),
-							functions: matched_functions
-								.clone()
-								.into_iter()
-								.filter(|_| has_function_match)
-								.collect(),
-							transactions: matched_transactions
-								.clone()
-								.into_iter()
-								.filter(|_| has_transaction_match)
-								.collect(),
+		let monitored_addresses = monitor
+			.addresses
+			.iter()
+			.map(|addr| normalize_address(&addr.address))
+			.collect::<Vec<String>>();
+
+		let decoded_events =
+			self.decode_events(&block.events, &monitored_addresses, &block.contract_specs);
+
+		// Then process transactions for this monitor
+		for transaction in &block.transactions {
+			let mut matched_transactions = Vec::<TransactionCondition>::new();
+			let mut matched_functions = Vec::<FunctionCondition>::new();
+			let mut matched_events = Vec::<EventCondition>::new();
+			let mut matched_on_args = StellarMatchArguments {
+				events: Some(Vec::new()),
+				functions: Some(Vec::new()),
+			};
+
+			tracing::debug!("Processing transaction: {:?}", transaction.hash());
+
+			self.find_matching_transaction(transaction, monitor, &mut matched_transactions);
+
+			// Decoded events already account for monitored addresses, so no need to pass in
+			// monitored_addresses
+			self.find_matching_events_for_transaction(
+				&decoded_events,
+				transaction,
+				monitor,
+				&mut matched_events,
+				&mut matched_on_args,
+			);
+
+			self.find_matching_functions_for_transaction(
+				&monitored_addresses,
+				&block.contract_specs,
+				transaction,
+				monitor,
+				&mut matched_functions,
+				&mut matched_on_args,
+			);
+
+			let monitor_conditions = &monitor.match_conditions;
+			let has_event_match =
+				!monitor_conditions.events.is_empty() && !matched_events.is_empty();
+			let has_function_match =
+				!monitor_conditions.functions.is_empty() && !matched_functions.is_empty();
+			let has_transaction_match =
+				!monitor_conditions.transactions.is_empty() && !matched_transactions.is_empty();
+
+			let should_match = match (
+				monitor_conditions.events.is_empty(),
+				monitor_conditions.functions.is_empty(),
+				monitor_conditions.transactions.is_empty(),
+			) {
+				// Case 1: No conditions defined, match everything
+				(true, true, true) => true,
+
+				// Case 2: Only transaction conditions defined
+				(true, true, false) => has_transaction_match,
+
+				// Case 3: No transaction conditions, match based on events/functions
+				(_, _, true) => has_event_match || has_function_match,
+
+				// Case 4: Transaction conditions exist, they must be satisfied along with
+				// events/functions
+				_ => (has_event_match || has_function_match) && has_transaction_match,
+			};
+
+			if should_match {
+				matches.push(MonitorMatch::Stellar(Box::new(StellarMonitorMatch {
+					monitor: monitor.clone(),
+					// The conversion to StellarTransaction triggers decoding of the transaction
+					#[allow(clippy::useless_conversion)]
+					transaction: StellarTransaction::from(transaction.clone()),
+					ledger: block.ledger.clone(),
+					network_slug: block.network_slug.clone(),
+					matched_on: MatchConditions {
+						events: matched_events
+							.clone()
+							.into_iter()
+							.filter(|_| has_event_match)
+							.collect(),
+						functions: matched_functions
+							.clone()
+							.into_iter()
+							.filter(|_| has_function_match)
+							.collect(),
+						transactions: matched_transactions
+							.clone()
+							.into_iter()
+							.filter(|_| has_transaction_match)
+							.collect(),
+						sender_activity: None,
+						baseline: None,
+						network_metrics: None,
+					},
+					matched_on_args: Some(StellarMatchArguments {
+						events: if has_event_match {
+							matched_on_args.events.clone()
+						} else {
+							None
 						},
-						matched_on_args: Some(StellarMatchArguments {
-							events: if has_event_match {
-								matched_on_args.events.clone()
-							} else {
-								None
-							},
-							functions: if has_function_match {
-								matched_on_args.functions.clone()
-							} else {
-								None
-							},
-						}),
-					})));
-				}
+						functions: if has_function_match {
+							matched_on_args.functions.clone()
+						} else {
+							None
+						},
+					}),
+					timing: None,
+					origin: Default::default(),
+				})));
 			}
 		}
-		Ok(matching_results)
+		matches
 	}
 }
 
@@ -1071,6 +1147,9 @@ mod tests {
 				events: event_conditions,
 				functions: function_conditions,
 				transactions: transaction_conditions,
+				sender_activity: None,
+				baseline: None,
+				network_metrics: None,
 			})
 			.build()
 	}
@@ -1253,6 +1332,8 @@ mod tests {
 			event: StellarMatchParamsMap {
 				signature: event_signature.to_string(),
 				args,
+				operation_index: None,
+				event_index: None,
 			},
 			tx_hash: tx_hash.to_string(),
 		}
@@ -1516,6 +1597,9 @@ mod tests {
 			vec![AddressWithSpec {
 				address: normalized_contract_address.clone(),
 				contract_spec: None,
+				contract: None,
+				label: None,
+				annotations: Default::default(),
 			}],
 		);
 
@@ -1596,6 +1680,9 @@ mod tests {
 			vec![AddressWithSpec {
 				address: normalized_contract_address.clone(),
 				contract_spec: None,
+				contract: None,
+				label: None,
+				annotations: Default::default(),
 			}],
 		);
 
@@ -1671,6 +1758,9 @@ mod tests {
 			vec![AddressWithSpec {
 				address: normalized_contract_address.clone(),
 				contract_spec: None,
+				contract: None,
+				label: None,
+				annotations: Default::default(),
 			}],
 		);
 
@@ -1746,6 +1836,9 @@ mod tests {
 			vec![AddressWithSpec {
 				address: normalized_different_address.clone(),
 				contract_spec: None,
+				contract: None,
+				label: None,
+				annotations: Default::default(),
 			}],
 		);
 
@@ -1825,6 +1918,9 @@ mod tests {
 			vec![AddressWithSpec {
 				address: normalized_contract_address.clone(),
 				contract_spec: None,
+				contract: None,
+				label: None,
+				annotations: Default::default(),
 			}],
 		);
 
@@ -1901,6 +1997,9 @@ mod tests {
 			vec![AddressWithSpec {
 				address: normalized_contract_address.clone(),
 				contract_spec: None,
+				contract: None,
+				label: None,
+				annotations: Default::default(),
 			}],
 		);
 
@@ -2023,6 +2122,7 @@ mod tests {
 			vec![EventCondition {
 				signature: "Transfer(address,uint256)".to_string(),
 				expression: None,
+				match_any_emitter: false,
 			}],
 			vec![],
 			vec![],
@@ -2070,6 +2170,7 @@ mod tests {
 			vec![EventCondition {
 				signature: "Transfer(address,uint256)".to_string(),
 				expression: Some("0 > 50".to_string()),
+				match_any_emitter: false,
 			}],
 			vec![],
 			vec![],
@@ -2116,6 +2217,7 @@ mod tests {
 			vec![EventCondition {
 				signature: "Transfer(address,uint256)".to_string(),
 				expression: Some("0 > 100".to_string()), // This won't match
+				match_any_emitter: false,
 			}],
 			vec![],
 			vec![],
@@ -2162,6 +2264,7 @@ mod tests {
 			vec![EventCondition {
 				signature: "Transfer(address,uint256)".to_string(),
 				expression: None,
+				match_any_emitter: false,
 			}],
 			vec![],
 			vec![],
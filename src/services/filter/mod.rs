@@ -5,21 +5,48 @@
 //! - Match handling and processing
 //! - Chain-specific helper functions
 
+mod baseline;
+mod decode_failures;
 mod error;
+mod evaluation;
 #[cfg(fuzzing)]
 pub mod expression;
 #[cfg(not(fuzzing))]
 mod expression;
 mod filter_match;
 mod filters;
+mod network_metrics;
+mod price_oracle;
+mod sender_activity;
 
+pub use baseline::{BaselineTracker, DEFAULT_BASELINE_STATE_FILE};
+pub use decode_failures::{
+	DecodeFailure, DecodeFailureLog, DecodeFailureReport, DecodeFailureSummary, DecodeFailures,
+};
 pub use error::FilterError;
-pub use filter_match::handle_match;
+pub use evaluation::{
+	evaluate_evm_transaction, ConditionError, ConditionKind, ConditionReport, EvaluationReport,
+};
+pub use filter_match::{
+	handle_deduped_match, handle_match, handle_match_with_variables, match_variables,
+};
+pub use network_metrics::{
+	evm_network_metrics_candidate, network_metric, stellar_network_metrics_candidate,
+	NetworkMetric, NetworkMetricsTracker, DEFAULT_BLOB_BASE_FEE_UPDATE_FRACTION,
+	EVM_NETWORK_METRICS, STELLAR_NETWORK_METRICS,
+};
+pub use price_oracle::{
+	ChainlinkPriceOracle, HttpPriceOracle, PriceOracle, PriceOracles, PriceSnapshot,
+	StaticPriceOracle, TokenPrice, USD_DECIMALS,
+};
+pub use sender_activity::{
+	SenderActivityClock, SenderActivityTracker, DEFAULT_SENDER_ACTIVITY_STATE_FILE,
+};
 
 pub use filters::{
 	evm::{
 		evaluator::{EVMArgs, EVMConditionEvaluator},
-		filter::EVMBlockFilter,
+		filter::{AnyEmitterSpecs, EVMBlockFilter},
 		helpers as evm_helpers,
 	},
 	midnight::{filter::MidnightBlockFilter, helpers as midnight_helpers},
@@ -32,3 +59,10 @@ pub use filters::{
 };
 
 pub use expression::{ComparisonOperator, ConditionEvaluator, EvaluationError, LiteralValue};
+
+/// Returns the variable names referenced by an expression, or `None` if it cannot be parsed
+pub fn expression_variable_names(expression: &str) -> Option<Vec<&str>> {
+	expression::parse(expression)
+		.ok()
+		.map(|parsed| expression::variable_names(&parsed))
+}
@@ -0,0 +1,670 @@
+//! Threshold conditions on the metrics of a network's blocks.
+//!
+//! Monitors with a `network_metrics` condition watch no address: the filters turn every block
+//! into a candidate carrying the block's metrics, such as its base fee or transaction count.
+//! The tracker evaluates the condition's expression against the candidates in block order and
+//! keeps a candidate once the expression held for `consecutive_blocks` blocks in a row, with
+//! the metrics of these blocks attached. No other match fires for `cooldown_blocks` blocks
+//! after a match, and the next match needs as many blocks in a row again.
+//!
+//! Matches have no transaction: their transaction hash is the hash of the block they fired on.
+//! The tracker is kept in memory, so persistence requirements start over after a restart.
+
+use alloy::primitives::{U256, U512, U64};
+use std::{
+	collections::{BTreeMap, HashMap},
+	sync::Mutex,
+};
+use stellar_xdr::curr::{FeeBumpTransactionInnerTx, TransactionEnvelope};
+
+use crate::{
+	models::{
+		EVMBaseTransaction, EVMBlock, EVMMatchParamEntry, EVMMonitorMatch, EVMTransaction,
+		MatchConditions, Monitor, MonitorMatch, NetworkMetrics, NetworkMetricsCondition,
+		NetworkMetricsSample, ProcessedBlock, StellarBlock, StellarMonitorMatch,
+		StellarTransaction, StellarTransactionInfo,
+	},
+	services::filter::{
+		expression, filters::evm::evaluator::EVMConditionEvaluator, price_oracle::format_fixed,
+		USD_DECIMALS,
+	},
+};
+
+/// Update fraction of the blob base fee since the Prague upgrade
+pub const DEFAULT_BLOB_BASE_FEE_UPDATE_FRACTION: u64 = 5_007_716;
+
+/// Minimum blob base fee, in wei
+const MIN_BLOB_BASE_FEE: u64 = 1;
+
+/// Metric of a network's blocks available to `network_metrics` expressions
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NetworkMetric {
+	/// Name the expressions refer to the metric by
+	pub name: &'static str,
+	/// Kind the metric is compared as
+	pub kind: &'static str,
+	/// Description of the metric
+	pub description: &'static str,
+}
+
+const fn metric(
+	name: &'static str,
+	kind: &'static str,
+	description: &'static str,
+) -> NetworkMetric {
+	NetworkMetric {
+		name,
+		kind,
+		description,
+	}
+}
+
+/// Metrics of EVM blocks
+pub const EVM_NETWORK_METRICS: &[NetworkMetric] = &[
+	metric(
+		"base_fee",
+		"uint256",
+		"Base fee per gas of the block, in wei",
+	),
+	metric("gas_used", "uint256", "Gas used by the block"),
+	metric("gas_limit", "uint256", "Gas limit of the block"),
+	metric(
+		"gas_used_ratio",
+		"fixed",
+		"Gas used divided by the gas limit, between 0 and 1",
+	),
+	metric(
+		"transaction_count",
+		"uint64",
+		"Number of transactions in the block",
+	),
+	metric("blob_gas_used", "uint256", "Blob gas used by the block"),
+	metric(
+		"excess_blob_gas",
+		"uint256",
+		"Excess blob gas carried into the block",
+	),
+	metric(
+		"blob_gas_price",
+		"uint256",
+		"Price of blob gas in the block, in wei",
+	),
+];
+
+/// Metrics of Stellar ledgers
+pub const STELLAR_NETWORK_METRICS: &[NetworkMetric] = &[
+	metric(
+		"close_time",
+		"uint64",
+		"Seconds between the close of the previous ledger and this one",
+	),
+	metric(
+		"transaction_count",
+		"uint64",
+		"Number of transactions in the ledger",
+	),
+	metric(
+		"operation_count",
+		"uint64",
+		"Number of operations of the ledger's transactions",
+	),
+];
+
+/// Returns the metric of the given name on any chain
+pub fn network_metric(name: &str) -> Option<&'static NetworkMetric> {
+	EVM_NETWORK_METRICS
+		.iter()
+		.chain(STELLAR_NETWORK_METRICS)
+		.find(|metric| metric.name == name)
+}
+
+/// Builds the candidate of a monitor with a `network_metrics` condition for an EVM block
+///
+/// # Returns
+/// * `Option<MonitorMatch>` - The candidate, or `None` if the monitor has no such condition
+pub fn evm_network_metrics_candidate(
+	monitor: &Monitor,
+	block: &EVMBlock,
+	network_slug: &str,
+) -> Option<MonitorMatch> {
+	let condition = monitor.match_conditions.network_metrics.as_ref()?;
+	let block_number = block.number()?;
+	let sample = NetworkMetricsSample {
+		block_number,
+		timestamp: i64::try_from(block.timestamp).ok(),
+		values: evm_block_metrics(block, condition),
+	};
+	Some(MonitorMatch::EVM(Box::new(EVMMonitorMatch {
+		monitor: monitor.clone(),
+		transaction: EVMTransaction::from(EVMBaseTransaction {
+			hash: block.hash.unwrap_or_default(),
+			block_hash: block.hash,
+			block_number: Some(U64::from(block_number)),
+			..Default::default()
+		}),
+		receipt: None,
+		logs: None,
+		network_slug: network_slug.to_string(),
+		matched_on: matched_on(condition),
+		matched_on_args: None,
+		sender_activity: None,
+		baseline: None,
+		network_metrics: Some(NetworkMetrics {
+			series: vec![sample],
+		}),
+		timing: None,
+		origin: Default::default(),
+	})))
+}
+
+/// Builds the candidate of a monitor with a `network_metrics` condition for a Stellar ledger
+///
+/// The close time of the ledger is only known once the tracker compares it with the previous
+/// ledger.
+///
+/// # Returns
+/// * `Option<MonitorMatch>` - The candidate, or `None` if the monitor has no such condition
+pub fn stellar_network_metrics_candidate(
+	monitor: &Monitor,
+	ledger: &StellarBlock,
+	transactions: &[StellarTransaction],
+	network_slug: &str,
+) -> Option<MonitorMatch> {
+	let condition = monitor.match_conditions.network_metrics.as_ref()?;
+	let operation_count = transactions
+		.iter()
+		.filter_map(|transaction| transaction.decoded()?.envelope.as_ref())
+		.map(|envelope| match envelope {
+			TransactionEnvelope::TxV0(tx) => tx.tx.operations.len(),
+			TransactionEnvelope::Tx(tx) => tx.tx.operations.len(),
+			TransactionEnvelope::TxFeeBump(tx) => match &tx.tx.inner_tx {
+				FeeBumpTransactionInnerTx::Tx(inner_tx) => inner_tx.tx.operations.len(),
+			},
+		})
+		.sum::<usize>();
+	let sample = NetworkMetricsSample {
+		block_number: ledger.sequence as u64,
+		timestamp: ledger.close_time(),
+		values: BTreeMap::from([
+			(
+				"transaction_count".to_string(),
+				transactions.len().to_string(),
+			),
+			("operation_count".to_string(), operation_count.to_string()),
+		]),
+	};
+	Some(MonitorMatch::Stellar(Box::new(StellarMonitorMatch {
+		monitor: monitor.clone(),
+		transaction: StellarTransaction(StellarTransactionInfo {
+			transaction_hash: ledger.hash.clone(),
+			ledger: ledger.sequence,
+			ledger_close_time: sample.timestamp.unwrap_or_default(),
+			..Default::default()
+		}),
+		ledger: ledger.clone(),
+		network_slug: network_slug.to_string(),
+		matched_on: matched_on(condition),
+		matched_on_args: None,
+		network_metrics: Some(NetworkMetrics {
+			series: vec![sample],
+		}),
+		timing: None,
+		origin: Default::default(),
+	})))
+}
+
+fn matched_on(condition: &NetworkMetricsCondition) -> MatchConditions {
+	MatchConditions {
+		network_metrics: Some(condition.clone()),
+		..Default::default()
+	}
+}
+
+/// Returns the metrics of an EVM block, leaving out those the block does not carry
+fn evm_block_metrics(
+	block: &EVMBlock,
+	condition: &NetworkMetricsCondition,
+) -> BTreeMap<String, String> {
+	let mut values = BTreeMap::from([
+		("gas_used".to_string(), block.gas_used.to_string()),
+		("gas_limit".to_string(), block.gas_limit.to_string()),
+		(
+			"transaction_count".to_string(),
+			block.transactions.len().to_string(),
+		),
+	]);
+	if let Some(base_fee) = block.base_fee_per_gas {
+		values.insert("base_fee".to_string(), base_fee.to_string());
+	}
+	if !block.gas_limit.is_zero() {
+		let scale = U512::from(10u8).pow(U512::from(USD_DECIMALS));
+		let ratio = U512::from(block.gas_used) * scale / U512::from(block.gas_limit);
+		values.insert(
+			"gas_used_ratio".to_string(),
+			format_fixed(ratio, USD_DECIMALS),
+		);
+	}
+	if let Some(blob_gas_used) = block.blob_gas_used {
+		values.insert("blob_gas_used".to_string(), blob_gas_used.to_string());
+	}
+	if let Some(excess_blob_gas) = block.excess_blob_gas {
+		let fraction = condition
+			.blob_base_fee_update_fraction
+			.unwrap_or(DEFAULT_BLOB_BASE_FEE_UPDATE_FRACTION);
+		values.insert("excess_blob_gas".to_string(), excess_blob_gas.to_string());
+		values.insert(
+			"blob_gas_price".to_string(),
+			blob_gas_price(excess_blob_gas, fraction).to_string(),
+		);
+	}
+	values
+}
+
+/// Returns the blob base fee for the given excess blob gas, as defined by EIP-4844
+fn blob_gas_price(excess_blob_gas: U256, update_fraction: u64) -> U256 {
+	let factor = U512::from(MIN_BLOB_BASE_FEE);
+	let numerator = U512::from(excess_blob_gas);
+	let denominator = U512::from(update_fraction.max(1));
+	// e^178 exceeds 2^256, the series would only saturate
+	if numerator / denominator >= U512::from(178u8) {
+		return U256::MAX;
+	}
+	let mut output = U512::ZERO;
+	let mut accumulator = factor * denominator;
+	let mut i = U512::from(1u8);
+	while !accumulator.is_zero() {
+		output = output.saturating_add(accumulator);
+		accumulator = accumulator.saturating_mul(numerator) / (denominator * i);
+		i += U512::from(1u8);
+	}
+	U256::saturating_from(output / denominator)
+}
+
+/// Progress of a monitor's condition on a network
+#[derive(Debug, Default)]
+struct MetricsState {
+	/// Latest block observed
+	last_block: Option<u64>,
+	/// Timestamp of the latest block observed
+	last_timestamp: Option<i64>,
+	/// Metrics of the latest blocks the expression held for in a row, at most
+	/// `consecutive_blocks` of them
+	streak: Vec<NetworkMetricsSample>,
+	/// Latest block a match fired on
+	last_fired: Option<u64>,
+}
+
+/// Tracks the conditions of the monitors with a `network_metrics` condition
+#[derive(Default)]
+pub struct NetworkMetricsTracker {
+	state: Mutex<HashMap<String, MetricsState>>,
+}
+
+impl NetworkMetricsTracker {
+	/// Creates a tracker
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Evaluates the network metrics candidates of a block and keeps those firing a match
+	///
+	/// Blocks must be passed in block order. Matches of monitors without a `network_metrics`
+	/// condition are left untouched.
+	///
+	/// # Arguments
+	/// * `block` - The processed block
+	///
+	/// # Returns
+	/// * `ProcessedBlock` - The block with its network metrics candidates resolved
+	pub fn resolve(&self, mut block: ProcessedBlock) -> ProcessedBlock {
+		if !block
+			.processing_results
+			.iter()
+			.any(|m| m.matched_on().network_metrics.is_some())
+		{
+			return block;
+		}
+
+		let mut states = self.lock_state();
+		let mut results = Vec::with_capacity(block.processing_results.len());
+		for mut monitor_match in std::mem::take(&mut block.processing_results) {
+			let Some(condition) = monitor_match.matched_on().network_metrics.clone() else {
+				results.push(monitor_match);
+				continue;
+			};
+			let measures_close_time = matches!(monitor_match, MonitorMatch::Stellar(_));
+			let key = format!(
+				"{}|{}",
+				monitor_match.network_slug(),
+				monitor_match.monitor().name
+			);
+			let Some(metrics) = candidate_metrics(&mut monitor_match) else {
+				continue;
+			};
+			let Some(sample) = metrics.series.pop() else {
+				continue;
+			};
+			let state = states.entry(key).or_default();
+			if let Some(series) = observe(state, &condition, sample, measures_close_time) {
+				metrics.series = series;
+				results.push(monitor_match);
+			}
+		}
+		block.processing_results = results;
+		block
+	}
+
+	fn lock_state(&self) -> std::sync::MutexGuard<'_, HashMap<String, MetricsState>> {
+		self.state.lock().unwrap_or_else(|e| e.into_inner())
+	}
+}
+
+/// Returns the metrics of a candidate
+fn candidate_metrics(monitor_match: &mut MonitorMatch) -> Option<&mut NetworkMetrics> {
+	match monitor_match {
+		MonitorMatch::EVM(m) => m.network_metrics.as_mut(),
+		MonitorMatch::Stellar(m) => m.network_metrics.as_mut(),
+		MonitorMatch::Midnight(_) => None,
+	}
+}
+
+/// Records the metrics of a block and returns the series of the match if one fires
+///
+/// The close time of a block is measured from the timestamp of the previous block if
+/// `measures_close_time` is set, as for Stellar ledgers.
+fn observe(
+	state: &mut MetricsState,
+	condition: &NetworkMetricsCondition,
+	mut sample: NetworkMetricsSample,
+	measures_close_time: bool,
+) -> Option<Vec<NetworkMetricsSample>> {
+	// A block processed again, e.g. after a restart of its network's watcher
+	if state
+		.last_block
+		.is_some_and(|last_block| sample.block_number <= last_block)
+	{
+		return None;
+	}
+	let follows = sample
+		.block_number
+		.checked_sub(1)
+		.is_some_and(|previous| state.last_block == Some(previous));
+	if !follows {
+		state.streak.clear();
+	}
+	if let (true, true, Some(previous), Some(current)) = (
+		measures_close_time,
+		follows,
+		state.last_timestamp,
+		sample.timestamp,
+	) {
+		if current >= previous {
+			sample
+				.values
+				.insert("close_time".to_string(), (current - previous).to_string());
+		}
+	}
+	state.last_block = Some(sample.block_number);
+	state.last_timestamp = sample.timestamp;
+
+	if !holds(&condition.expression, &sample) {
+		state.streak.clear();
+		return None;
+	}
+	let consecutive_blocks = condition.consecutive_blocks.max(1) as usize;
+	state.streak.push(sample);
+	if state.streak.len() > consecutive_blocks {
+		state.streak.remove(0);
+	}
+	let block_number = state.streak.last()?.block_number;
+	let cooled_down = state
+		.last_fired
+		.is_none_or(|fired| block_number > fired + condition.cooldown_blocks);
+	if state.streak.len() < consecutive_blocks || !cooled_down {
+		return None;
+	}
+	state.last_fired = Some(block_number);
+	Some(std::mem::take(&mut state.streak))
+}
+
+/// Returns whether the expression holds for the metrics of a block
+///
+/// Expressions referring to a metric the block does not carry do not hold.
+fn holds(expression: &str, sample: &NetworkMetricsSample) -> bool {
+	let args = sample
+		.values
+		.iter()
+		.filter_map(|(name, value)| {
+			Some(EVMMatchParamEntry {
+				name: name.clone(),
+				value: value.clone(),
+				kind: network_metric(name)?.kind.to_string(),
+				indexed: false,
+			})
+		})
+		.collect::<Vec<_>>();
+	let result = expression::parse(expression)
+		.map_err(|e| e.to_string())
+		.and_then(|parsed| {
+			expression::evaluate(&parsed, &EVMConditionEvaluator::new(&args))
+				.map_err(|e| e.to_string())
+		});
+	match result {
+		Ok(holds) => holds,
+		Err(e) => {
+			tracing::debug!(
+				"Network metrics expression '{}' not evaluated for block {}: {}",
+				expression,
+				sample.block_number,
+				e
+			);
+			false
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{
+		models::StellarLedgerInfo,
+		utils::tests::builders::{
+			evm::monitor::MonitorBuilder, stellar::monitor::MonitorBuilder as StellarMonitorBuilder,
+		},
+	};
+	use alloy::primitives::B256;
+
+	fn condition(
+		expression: &str,
+		consecutive_blocks: u64,
+		cooldown_blocks: u64,
+	) -> NetworkMetricsCondition {
+		NetworkMetricsCondition {
+			expression: expression.to_string(),
+			consecutive_blocks,
+			cooldown_blocks,
+			blob_base_fee_update_fraction: None,
+		}
+	}
+
+	fn evm_block(number: u64, base_fee: u64) -> EVMBlock {
+		let mut block = EVMBlock::default();
+		block.0.number = Some(U64::from(number));
+		block.0.hash = Some(B256::with_last_byte(number as u8));
+		block.0.base_fee_per_gas = Some(U256::from(base_fee));
+		block.0.gas_used = U256::from(15_000_000u64);
+		block.0.gas_limit = U256::from(30_000_000u64);
+		block
+	}
+
+	/// Drives blocks with the given base fees, in gwei, through the filter and the tracker,
+	/// returning the numbers of the blocks a match fired on
+	fn fire_blocks(
+		tracker: &NetworkMetricsTracker,
+		condition: &NetworkMetricsCondition,
+		base_fees: &[(u64, u64)],
+	) -> Vec<u64> {
+		let monitor = MonitorBuilder::new()
+			.name("base_fee_spike")
+			.network_metrics(condition.clone())
+			.build();
+		base_fees
+			.iter()
+			.flat_map(|&(number, gwei)| {
+				let block = evm_block(number, gwei * 1_000_000_000);
+				let processed = tracker.resolve(ProcessedBlock {
+					block_number: number,
+					network_slug: "ethereum_mainnet".to_string(),
+					processing_results: evm_network_metrics_candidate(
+						&monitor,
+						&block,
+						"ethereum_mainnet",
+					)
+					.into_iter()
+					.collect(),
+				});
+				processed.processing_results
+			})
+			.map(|m| m.network_metrics().unwrap().latest().unwrap().block_number)
+			.collect()
+	}
+
+	#[test]
+	fn test_evm_block_metrics() {
+		let mut block = evm_block(100, 20_000_000_000);
+		block.0.excess_blob_gas = Some(U256::from(0u64));
+		let values = evm_block_metrics(&block, &condition("base_fee > 0", 1, 0));
+		assert_eq!(values["base_fee"], "20000000000");
+		assert_eq!(values["gas_used_ratio"], "0.5");
+		assert_eq!(values["transaction_count"], "0");
+		assert_eq!(values["blob_gas_price"], "1");
+		assert!(!values.contains_key("blob_gas_used"));
+	}
+
+	#[test]
+	fn test_blob_gas_price_grows_with_excess_blob_gas() {
+		let fraction = DEFAULT_BLOB_BASE_FEE_UPDATE_FRACTION;
+		assert_eq!(blob_gas_price(U256::ZERO, fraction), U256::from(1u8));
+		assert!(
+			blob_gas_price(U256::from(100_000_000u64), fraction)
+				> blob_gas_price(U256::from(50_000_000u64), fraction)
+		);
+	}
+
+	#[test]
+	fn test_fires_only_after_consecutive_blocks() {
+		let tracker = NetworkMetricsTracker::new();
+		let condition = condition("base_fee > 150000000000", 3, 0);
+		let blocks = [(1, 200), (2, 200), (3, 100), (4, 200), (5, 200), (6, 200)];
+		assert_eq!(fire_blocks(&tracker, &condition, &blocks), vec![6]);
+	}
+
+	#[test]
+	fn test_match_carries_series() {
+		let tracker = NetworkMetricsTracker::new();
+		let monitor = MonitorBuilder::new()
+			.network_metrics(condition("base_fee > 150000000000", 2, 0))
+			.build();
+		let mut fired = vec![];
+		for number in 10..=11 {
+			let block = evm_block(number, 200_000_000_000);
+			let candidate = evm_network_metrics_candidate(&monitor, &block, "ethereum_mainnet");
+			fired.extend(
+				tracker
+					.resolve(ProcessedBlock {
+						block_number: number,
+						network_slug: "ethereum_mainnet".to_string(),
+						processing_results: candidate.into_iter().collect(),
+					})
+					.processing_results,
+			);
+		}
+		assert_eq!(fired.len(), 1);
+		let metrics = fired[0].network_metrics().unwrap();
+		assert_eq!(
+			metrics
+				.series
+				.iter()
+				.map(|s| s.block_number)
+				.collect::<Vec<_>>(),
+			vec![10, 11]
+		);
+		// The match has no transaction, it is identified by the hash of its block
+		assert_eq!(
+			fired[0].transaction_hash(),
+			B256::with_last_byte(11).to_string()
+		);
+	}
+
+	#[test]
+	fn test_respects_cooldown() {
+		let tracker = NetworkMetricsTracker::new();
+		let condition = condition("base_fee > 150000000000", 2, 5);
+		let blocks = (1..=12).map(|number| (number, 200)).collect::<Vec<_>>();
+		// Fires on block 2, then needs two blocks in a row past block 7
+		assert_eq!(fire_blocks(&tracker, &condition, &blocks), vec![2, 8]);
+	}
+
+	#[test]
+	fn test_gap_resets_streak() {
+		let tracker = NetworkMetricsTracker::new();
+		let condition = condition("base_fee > 150000000000", 2, 0);
+		let blocks = [(1, 200), (3, 200), (4, 200), (4, 200)];
+		assert_eq!(fire_blocks(&tracker, &condition, &blocks), vec![4]);
+	}
+
+	#[test]
+	fn test_unknown_metric_does_not_hold() {
+		let tracker = NetworkMetricsTracker::new();
+		let condition = condition("blob_gas_used > 0", 1, 0);
+		assert!(fire_blocks(&tracker, &condition, &[(1, 200), (2, 200)]).is_empty());
+	}
+
+	#[test]
+	fn test_stellar_close_time() {
+		let tracker = NetworkMetricsTracker::new();
+		let monitor = StellarMonitorBuilder::new()
+			.name("slow_ledgers")
+			.network_metrics(condition("close_time > 7", 1, 0))
+			.build();
+		let close_times = [
+			(100, 1_700_000_000),
+			(101, 1_700_000_005),
+			(102, 1_700_000_015),
+		];
+		let fired = close_times
+			.iter()
+			.flat_map(|&(sequence, close_time)| {
+				let ledger = StellarBlock::from(StellarLedgerInfo {
+					hash: format!("ledger-{}", sequence),
+					sequence,
+					ledger_close_time: close_time.to_string(),
+					..Default::default()
+				});
+				let candidate =
+					stellar_network_metrics_candidate(&monitor, &ledger, &[], "stellar_mainnet");
+				tracker
+					.resolve(ProcessedBlock {
+						block_number: sequence as u64,
+						network_slug: "stellar_mainnet".to_string(),
+						processing_results: candidate.into_iter().collect(),
+					})
+					.processing_results
+			})
+			.collect::<Vec<_>>();
+		assert_eq!(fired.len(), 1);
+		let sample = fired[0].network_metrics().unwrap().latest().unwrap();
+		assert_eq!(sample.block_number, 102);
+		assert_eq!(sample.values["close_time"], "10");
+		assert_eq!(sample.values["operation_count"], "0");
+		assert_eq!(fired[0].transaction_hash(), "ledger-102");
+	}
+
+	#[test]
+	fn test_no_candidate_without_condition() {
+		let monitor = MonitorBuilder::new().build();
+		assert!(
+			evm_network_metrics_candidate(&monitor, &evm_block(1, 1), "ethereum_mainnet").is_none()
+		);
+	}
+}
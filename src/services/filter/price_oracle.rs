@@ -0,0 +1,799 @@
+//! USD prices of network tokens.
+//!
+//! Networks with a `price_oracle` configuration get the prices of their tokens from a static
+//! map, a Coingecko compatible HTTP endpoint or Chainlink feeds. Prices are used by `usd()` in
+//! expressions and by the `*_usd` template variables. They are refreshed at most once per
+//! `cache_ttl_secs`, and a failed refresh keeps the previous prices. A missing or stale price
+//! only makes the conversion unavailable, it never fails the filter.
+
+use std::{
+	collections::HashMap,
+	str::FromStr,
+	sync::{Arc, Mutex, RwLock},
+	time::{Duration, Instant},
+};
+
+use alloy::primitives::{I256, U256, U512};
+use anyhow::Context;
+use async_trait::async_trait;
+use rust_decimal::Decimal;
+use serde_json::Value as JsonValue;
+
+use crate::{
+	models::{Network, PriceOracleConfig, PriceSource, PricedToken, NATIVE_TOKEN},
+	services::blockchain::EvmClientTrait,
+	utils::metrics::{PRICE_ORACLE_FAILURES, PRICE_ORACLE_STALE},
+};
+
+/// Number of decimals of USD values
+pub const USD_DECIMALS: u32 = 6;
+
+/// Selector of Chainlink's `latestRoundData()`
+const LATEST_ROUND_DATA_SELECTOR: [u8; 4] = [0xfe, 0xaf, 0x96, 0x8c];
+
+/// Selector of Chainlink's `decimals()`
+const DECIMALS_SELECTOR: [u8; 4] = [0x31, 0x3c, 0xe5, 0x67];
+
+/// Price of a token
+#[derive(Debug, Clone, PartialEq)]
+pub struct TokenPrice {
+	/// Price of one whole token in USD
+	pub usd: Decimal,
+	/// Unix timestamp in seconds the price was last updated at
+	pub updated_at: i64,
+}
+
+/// Source of token prices
+#[async_trait]
+pub trait PriceOracle: Send + Sync {
+	/// Fetches the prices of tokens
+	///
+	/// # Arguments
+	/// * `tokens` - Tokens to price, by contract address or `native`
+	///
+	/// # Returns
+	/// * `Result<HashMap<String, TokenPrice>, anyhow::Error>` - Prices keyed like `tokens`, or
+	///   an error if any of them could not be fetched
+	async fn prices(
+		&self,
+		tokens: &HashMap<String, PricedToken>,
+	) -> Result<HashMap<String, TokenPrice>, anyhow::Error>;
+}
+
+/// Prices set in the configuration of the tokens
+pub struct StaticPriceOracle;
+
+#[async_trait]
+impl PriceOracle for StaticPriceOracle {
+	async fn prices(
+		&self,
+		tokens: &HashMap<String, PricedToken>,
+	) -> Result<HashMap<String, TokenPrice>, anyhow::Error> {
+		let now = chrono::Utc::now().timestamp();
+		tokens
+			.iter()
+			.map(|(token, priced)| {
+				let price = priced
+					.price
+					.as_deref()
+					.with_context(|| format!("Missing static price of token '{}'", token))?;
+				let usd = Decimal::from_str(price)
+					.with_context(|| format!("Invalid static price of token '{}'", token))?;
+				Ok((
+					token.clone(),
+					TokenPrice {
+						usd,
+						updated_at: now,
+					},
+				))
+			})
+			.collect()
+	}
+}
+
+/// Prices fetched from a Coingecko compatible `simple/price` endpoint
+pub struct HttpPriceOracle {
+	client: reqwest::Client,
+	url: String,
+}
+
+impl HttpPriceOracle {
+	pub fn new(client: reqwest::Client, url: impl Into<String>) -> Self {
+		Self {
+			client,
+			url: url.into(),
+		}
+	}
+}
+
+#[async_trait]
+impl PriceOracle for HttpPriceOracle {
+	async fn prices(
+		&self,
+		tokens: &HashMap<String, PricedToken>,
+	) -> Result<HashMap<String, TokenPrice>, anyhow::Error> {
+		let mut ids = tokens
+			.values()
+			.filter_map(|priced| priced.id.as_deref())
+			.collect::<Vec<_>>();
+		ids.sort_unstable();
+		ids.dedup();
+
+		let response = self
+			.client
+			.get(&self.url)
+			.query(&[
+				("ids", ids.join(",").as_str()),
+				("vs_currencies", "usd"),
+				("include_last_updated_at", "true"),
+			])
+			.send()
+			.await
+			.with_context(|| format!("Failed to fetch prices from {}", self.url))?
+			.error_for_status()
+			.with_context(|| format!("Failed to fetch prices from {}", self.url))?;
+		let body: JsonValue = response
+			.json()
+			.await
+			.with_context(|| "Failed to parse prices")?;
+
+		let now = chrono::Utc::now().timestamp();
+		tokens
+			.iter()
+			.map(|(token, priced)| {
+				let id = priced
+					.id
+					.as_deref()
+					.with_context(|| format!("Missing id of token '{}'", token))?;
+				let quote = body
+					.get(id)
+					.with_context(|| format!("Missing price of '{}'", id))?;
+				let usd = quote
+					.get("usd")
+					.and_then(json_to_decimal)
+					.with_context(|| format!("Invalid USD price of '{}'", id))?;
+				let updated_at = quote
+					.get("last_updated_at")
+					.and_then(JsonValue::as_i64)
+					.unwrap_or(now);
+				Ok((token.clone(), TokenPrice { usd, updated_at }))
+			})
+			.collect()
+	}
+}
+
+/// Prices read from the Chainlink USD feeds of the tokens
+pub struct ChainlinkPriceOracle<'a, C> {
+	client: &'a C,
+}
+
+impl<'a, C> ChainlinkPriceOracle<'a, C> {
+	pub fn new(client: &'a C) -> Self {
+		Self { client }
+	}
+}
+
+impl<C: EvmClientTrait + Send + Sync> ChainlinkPriceOracle<'_, C> {
+	/// Reads the latest answer of a feed
+	async fn latest_price(&self, feed: &str) -> Result<TokenPrice, anyhow::Error> {
+		let decimals = self
+			.client
+			.call_contract(feed.to_string(), DECIMALS_SELECTOR.to_vec())
+			.await?;
+		let decimals = abi_word(&decimals, 0)
+			.map(U256::from_be_bytes::<32>)
+			.and_then(|decimals| u32::try_from(decimals).ok())
+			.with_context(|| format!("Invalid decimals of feed {}", feed))?;
+
+		let round = self
+			.client
+			.call_contract(feed.to_string(), LATEST_ROUND_DATA_SELECTOR.to_vec())
+			.await?;
+		let answer = abi_word(&round, 1)
+			.map(I256::from_be_bytes::<32>)
+			.filter(|answer| answer.is_positive())
+			.and_then(|answer| i128::try_from(answer).ok())
+			.with_context(|| format!("Invalid answer of feed {}", feed))?;
+		let updated_at = abi_word(&round, 3)
+			.map(U256::from_be_bytes::<32>)
+			.and_then(|updated_at| i64::try_from(updated_at).ok())
+			.with_context(|| format!("Invalid update time of feed {}", feed))?;
+
+		let usd = Decimal::try_from_i128_with_scale(answer, decimals)
+			.with_context(|| format!("Answer of feed {} out of range", feed))?;
+		Ok(TokenPrice { usd, updated_at })
+	}
+}
+
+#[async_trait]
+impl<C: EvmClientTrait + Send + Sync> PriceOracle for ChainlinkPriceOracle<'_, C> {
+	async fn prices(
+		&self,
+		tokens: &HashMap<String, PricedToken>,
+	) -> Result<HashMap<String, TokenPrice>, anyhow::Error> {
+		let mut prices = HashMap::new();
+		for (token, priced) in tokens {
+			let feed = priced
+				.feed
+				.as_deref()
+				.with_context(|| format!("Missing feed of token '{}'", token))?;
+			let price = self
+				.latest_price(feed)
+				.await
+				.with_context(|| format!("Failed to read the price of token '{}'", token))?;
+			prices.insert(token.clone(), price);
+		}
+		Ok(prices)
+	}
+}
+
+/// Prices of the tokens of a network at a point in time
+#[derive(Debug, Clone)]
+pub struct PriceSnapshot {
+	network: String,
+	/// Prices and decimals of the tokens, by normalized token
+	prices: HashMap<String, (TokenPrice, u8)>,
+	max_age_secs: u64,
+}
+
+impl PriceSnapshot {
+	/// Creates a snapshot from fetched prices
+	///
+	/// # Arguments
+	/// * `network` - Slug of the network the prices belong to
+	/// * `config` - Price oracle configuration of the network
+	/// * `prices` - Prices keyed like the configured tokens
+	pub fn new(
+		network: &str,
+		config: &PriceOracleConfig,
+		prices: HashMap<String, TokenPrice>,
+	) -> Self {
+		let prices = prices
+			.into_iter()
+			.filter_map(|(token, price)| {
+				let decimals = config.tokens.get(&token)?.decimals;
+				Some((normalize_token(&token), (price, decimals)))
+			})
+			.collect();
+		Self {
+			network: network.to_string(),
+			prices,
+			max_age_secs: config.max_age_secs,
+		}
+	}
+
+	/// Whether the snapshot has a price for a token, fresh or not
+	pub fn prices_token(&self, token: &str) -> bool {
+		self.prices.contains_key(&normalize_token(token))
+	}
+
+	/// Converts an amount of a token to USD
+	///
+	/// # Arguments
+	/// * `amount` - Amount in the smallest unit of the token, e.g. wei
+	/// * `token` - Contract address of the token, or `native`
+	///
+	/// # Returns
+	/// The value in USD with [`USD_DECIMALS`] decimals, or `None` if the token has no price or
+	/// its price is stale. Stale prices are counted in `price_oracle_stale_total`.
+	pub fn usd_value(&self, amount: U256, token: &str) -> Option<String> {
+		let token = normalize_token(token);
+		let (price, decimals) = self.prices.get(&token)?;
+
+		let age = chrono::Utc::now()
+			.timestamp()
+			.saturating_sub(price.updated_at);
+		if age > i64::try_from(self.max_age_secs).unwrap_or(i64::MAX) {
+			tracing::warn!(
+				"Price of token '{}' on network '{}' is {}s old, skipping USD conversion",
+				token,
+				self.network,
+				age
+			);
+			PRICE_ORACLE_STALE
+				.with_label_values(&[self.network.as_str(), token.as_str()])
+				.inc();
+			return None;
+		}
+
+		let mantissa = u128::try_from(price.usd.mantissa()).ok()?;
+		let value = U512::from(amount) * U512::from(mantissa) * pow10(USD_DECIMALS)
+			/ pow10(u32::from(*decimals) + price.usd.scale());
+		Some(format_fixed(value, USD_DECIMALS))
+	}
+}
+
+/// Price snapshots of the networks with a price oracle
+///
+/// Shared by the filters, which refresh the snapshots, and by the template variables, which
+/// only read them.
+#[derive(Default)]
+pub struct PriceOracles {
+	client: reqwest::Client,
+	networks: Mutex<HashMap<String, Arc<NetworkPrices>>>,
+}
+
+/// Latest snapshot of a network, with the time of its last refresh attempt
+#[derive(Default)]
+struct NetworkPrices {
+	refreshed_at: tokio::sync::Mutex<Option<Instant>>,
+	snapshot: RwLock<Option<Arc<PriceSnapshot>>>,
+}
+
+impl PriceOracles {
+	/// Returns the prices of a network, refreshing them if they are older than the cache TTL
+	///
+	/// Refreshes are attempted at most once per `cache_ttl_secs`, failed ones included, so an
+	/// unavailable source is not hammered. A failed refresh keeps the previous prices and is
+	/// counted in `price_oracle_failures_total`.
+	///
+	/// # Arguments
+	/// * `network` - Network to get the prices of
+	/// * `client` - Client of the network, used by Chainlink sources
+	///
+	/// # Returns
+	/// The latest prices, or `None` if the network has no price oracle or no refresh succeeded
+	pub async fn snapshot<C: EvmClientTrait + Send + Sync>(
+		&self,
+		network: &Network,
+		client: &C,
+	) -> Option<Arc<PriceSnapshot>> {
+		let config = network.price_oracle.as_ref()?;
+		let prices = self.network(&network.slug);
+
+		let mut refreshed_at = prices.refreshed_at.lock().await;
+		let ttl = Duration::from_secs(config.cache_ttl_secs);
+		if refreshed_at.is_some_and(|at| at.elapsed() < ttl) {
+			return prices.snapshot.read().ok()?.clone();
+		}
+		*refreshed_at = Some(Instant::now());
+
+		let result = match &config.source {
+			PriceSource::Static => StaticPriceOracle.prices(&config.tokens).await,
+			PriceSource::Http { url } => {
+				HttpPriceOracle::new(self.client.clone(), url)
+					.prices(&config.tokens)
+					.await
+			}
+			PriceSource::Chainlink => {
+				ChainlinkPriceOracle::new(client)
+					.prices(&config.tokens)
+					.await
+			}
+		};
+
+		match result {
+			Ok(fetched) => {
+				let snapshot = Arc::new(PriceSnapshot::new(&network.slug, config, fetched));
+				if let Ok(mut latest) = prices.snapshot.write() {
+					*latest = Some(snapshot.clone());
+				}
+				Some(snapshot)
+			}
+			Err(e) => {
+				tracing::warn!(
+					"Failed to refresh the prices of network '{}', keeping the previous prices: {:#}",
+					network.slug,
+					e
+				);
+				PRICE_ORACLE_FAILURES
+					.with_label_values(&[network.slug.as_str()])
+					.inc();
+				prices.snapshot.read().ok()?.clone()
+			}
+		}
+	}
+
+	/// Returns the latest prices of a network without refreshing them
+	pub fn cached(&self, network_slug: &str) -> Option<Arc<PriceSnapshot>> {
+		let networks = self.networks.lock().ok()?;
+		let prices = networks.get(network_slug)?;
+		let snapshot = prices.snapshot.read().ok()?;
+		snapshot.clone()
+	}
+
+	/// Replaces the latest prices of a network until its next refresh
+	pub fn set(&self, network_slug: &str, snapshot: PriceSnapshot) {
+		if let Ok(mut latest) = self.network(network_slug).snapshot.write() {
+			*latest = Some(Arc::new(snapshot));
+		}
+	}
+
+	/// Returns the prices entry of a network, creating it if needed
+	fn network(&self, network_slug: &str) -> Arc<NetworkPrices> {
+		let mut networks = self
+			.networks
+			.lock()
+			.unwrap_or_else(|poisoned| poisoned.into_inner());
+		networks
+			.entry(network_slug.to_string())
+			.or_default()
+			.clone()
+	}
+}
+
+/// Parses a non-negative decimal number into a fixed-point integer
+///
+/// # Arguments
+/// * `value` - Decimal number, e.g. `1000000` or `1.5`
+/// * `decimals` - Number of decimals of the fixed-point integer
+///
+/// # Returns
+/// The value multiplied by `10^decimals`, or `None` if it is not a non-negative number, has
+/// more than `decimals` decimals or overflows
+pub fn parse_fixed(value: &str, decimals: u32) -> Option<U512> {
+	let (integer, fraction) = value.split_once('.').unwrap_or((value, ""));
+	if integer.is_empty()
+		|| fraction.len() > decimals as usize
+		|| !integer.chars().all(|c| c.is_ascii_digit())
+		|| !fraction.chars().all(|c| c.is_ascii_digit())
+	{
+		return None;
+	}
+	let digits = format!(
+		"{}{:0<width$}",
+		integer,
+		fraction,
+		width = decimals as usize
+	);
+	U512::from_str_radix(&digits, 10).ok()
+}
+
+/// Formats a fixed-point integer as a decimal number, without trailing zeros
+pub(crate) fn format_fixed(value: U512, decimals: u32) -> String {
+	let scale = pow10(decimals);
+	let integer = value / scale;
+	let fraction = format!(
+		"{:0>width$}",
+		(value % scale).to_string(),
+		width = decimals as usize
+	);
+	let fraction = fraction.trim_end_matches('0');
+	if fraction.is_empty() {
+		integer.to_string()
+	} else {
+		format!("{}.{}", integer, fraction)
+	}
+}
+
+fn pow10(exponent: u32) -> U512 {
+	U512::from(10u8).pow(U512::from(exponent))
+}
+
+/// Normalizes a token to `native` or a lowercase contract address
+fn normalize_token(token: &str) -> String {
+	if token.eq_ignore_ascii_case(NATIVE_TOKEN) {
+		NATIVE_TOKEN.to_string()
+	} else {
+		token.to_ascii_lowercase()
+	}
+}
+
+/// Returns the 32-byte word at an index of ABI encoded return data
+fn abi_word(data: &[u8], index: usize) -> Option<[u8; 32]> {
+	data.get(index * 32..(index + 1) * 32)?.try_into().ok()
+}
+
+/// Converts a JSON number or numeric string to a decimal, including scientific notation
+fn json_to_decimal(value: &JsonValue) -> Option<Decimal> {
+	let value = match value {
+		JsonValue::Number(number) => number.to_string(),
+		JsonValue::String(string) => string.clone(),
+		_ => return None,
+	};
+	Decimal::from_str(&value)
+		.or_else(|_| Decimal::from_scientific(&value))
+		.ok()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{
+		models::{EVMReceiptLog, EVMTransactionReceipt},
+		utils::tests::builders::network::NetworkBuilder,
+	};
+
+	const USDC: &str = "0xA0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48";
+	const ETH_USD_FEED: &str = "0x5f4ec3df9cbd43714fe2740f5e3616155c5b8419";
+
+	/// Client answering contract calls like a Chainlink feed with 8 decimals
+	struct FeedClient {
+		answer: i64,
+		updated_at: u64,
+	}
+
+	#[async_trait]
+	impl EvmClientTrait for FeedClient {
+		async fn get_transaction_receipt(
+			&self,
+			_transaction_hash: String,
+		) -> Result<EVMTransactionReceipt, anyhow::Error> {
+			unimplemented!()
+		}
+
+		async fn get_logs_for_blocks(
+			&self,
+			_from_block: u64,
+			_to_block: u64,
+			_addresses: Option<Vec<String>>,
+		) -> Result<Vec<EVMReceiptLog>, anyhow::Error> {
+			unimplemented!()
+		}
+
+		async fn call_contract(&self, to: String, data: Vec<u8>) -> Result<Vec<u8>, anyhow::Error> {
+			assert_eq!(to, ETH_USD_FEED);
+			let words = if data == DECIMALS_SELECTOR {
+				vec![I256::try_from(8).unwrap()]
+			} else {
+				vec![
+					I256::ONE,
+					I256::try_from(self.answer).unwrap(),
+					I256::ZERO,
+					I256::try_from(self.updated_at).unwrap(),
+					I256::ONE,
+				]
+			};
+			Ok(words
+				.into_iter()
+				.flat_map(|word| word.to_be_bytes::<32>())
+				.collect())
+		}
+	}
+
+	fn config(source: PriceSource, tokens: Vec<(&str, PricedToken)>) -> PriceOracleConfig {
+		PriceOracleConfig {
+			source,
+			tokens: tokens
+				.into_iter()
+				.map(|(token, priced)| (token.to_string(), priced))
+				.collect(),
+			cache_ttl_secs: 60,
+			max_age_secs: 3600,
+		}
+	}
+
+	fn priced(decimals: u8, price: Option<&str>, id: Option<&str>) -> PricedToken {
+		PricedToken {
+			decimals,
+			price: price.map(str::to_string),
+			id: id.map(str::to_string),
+			feed: None,
+		}
+	}
+
+	#[tokio::test]
+	async fn test_static_price_oracle() {
+		let config = config(
+			PriceSource::Static,
+			vec![
+				(NATIVE_TOKEN, priced(18, Some("2500.5"), None)),
+				(USDC, priced(6, Some("1"), None)),
+			],
+		);
+		let prices = StaticPriceOracle.prices(&config.tokens).await.unwrap();
+		let snapshot = PriceSnapshot::new("ethereum_mainnet", &config, prices);
+
+		// 2 ETH
+		assert_eq!(
+			snapshot.usd_value(U256::from(2_000_000_000_000_000_000u128), "native"),
+			Some("5001".to_string())
+		);
+		// 1.25 USDC, with the token in any case
+		assert_eq!(
+			snapshot.usd_value(U256::from(1_250_000u64), &USDC.to_lowercase()),
+			Some("1.25".to_string())
+		);
+		assert_eq!(
+			snapshot.usd_value(
+				U256::from(1u8),
+				"0x0000000000000000000000000000000000000001"
+			),
+			None
+		);
+		assert!(!snapshot.prices_token("0x0000000000000000000000000000000000000001"));
+
+		// The largest amounts don't overflow
+		assert!(snapshot.usd_value(U256::MAX, NATIVE_TOKEN).is_some());
+	}
+
+	#[tokio::test]
+	async fn test_http_price_oracle() {
+		let mut server = mockito::Server::new_async().await;
+		let mock = server
+			.mock("GET", "/simple/price")
+			.match_query(mockito::Matcher::AllOf(vec![
+				mockito::Matcher::UrlEncoded("ids".into(), "ethereum,usd-coin".into()),
+				mockito::Matcher::UrlEncoded("vs_currencies".into(), "usd".into()),
+			]))
+			.with_status(200)
+			.with_body(
+				r#"{"ethereum": {"usd": 3000.25, "last_updated_at": 1700000000}, "usd-coin": {"usd": 9.998e-1}}"#,
+			)
+			.create_async()
+			.await;
+
+		let config = config(
+			PriceSource::Http {
+				url: format!("{}/simple/price", server.url()),
+			},
+			vec![
+				(NATIVE_TOKEN, priced(18, None, Some("ethereum"))),
+				(USDC, priced(6, None, Some("usd-coin"))),
+			],
+		);
+		let oracle = HttpPriceOracle::new(
+			reqwest::Client::new(),
+			format!("{}/simple/price", server.url()),
+		);
+		let prices = oracle.prices(&config.tokens).await.unwrap();
+
+		assert_eq!(
+			prices[NATIVE_TOKEN].usd,
+			Decimal::from_str("3000.25").unwrap()
+		);
+		assert_eq!(prices[NATIVE_TOKEN].updated_at, 1_700_000_000);
+		assert_eq!(prices[USDC].usd, Decimal::from_str("0.9998").unwrap());
+		mock.assert_async().await;
+	}
+
+	#[tokio::test]
+	async fn test_http_price_oracle_failures_keep_previous_prices() {
+		let mut server = mockito::Server::new_async().await;
+		let ok = server
+			.mock("GET", mockito::Matcher::Any)
+			.with_status(200)
+			.with_body(r#"{"ethereum": {"usd": 2000}}"#)
+			.expect(1)
+			.create_async()
+			.await;
+
+		let network = NetworkBuilder::new()
+			.slug("price_oracle_failures")
+			.price_oracle(PriceOracleConfig {
+				cache_ttl_secs: 1,
+				..config(
+					PriceSource::Http { url: server.url() },
+					vec![(NATIVE_TOKEN, priced(18, None, Some("ethereum")))],
+				)
+			})
+			.build();
+		let client = FeedClient {
+			answer: 0,
+			updated_at: 0,
+		};
+
+		let oracles = PriceOracles::default();
+		let snapshot = oracles.snapshot(&network, &client).await.unwrap();
+		assert_eq!(
+			snapshot.usd_value(U256::from(10u128.pow(18)), NATIVE_TOKEN),
+			Some("2000".to_string())
+		);
+		ok.assert_async().await;
+
+		// Within the TTL, the source is not requested again
+		server.reset_async().await;
+		let failing = server
+			.mock("GET", mockito::Matcher::Any)
+			.with_status(500)
+			.expect(1)
+			.create_async()
+			.await;
+		assert!(oracles.snapshot(&network, &client).await.is_some());
+
+		// Once expired, a failed refresh keeps the previous prices
+		tokio::time::sleep(Duration::from_millis(1100)).await;
+		let failures = PRICE_ORACLE_FAILURES
+			.with_label_values(&["price_oracle_failures"])
+			.get();
+		let snapshot = oracles.snapshot(&network, &client).await.unwrap();
+		assert_eq!(
+			snapshot.usd_value(U256::from(10u128.pow(18)), NATIVE_TOKEN),
+			Some("2000".to_string())
+		);
+		assert_eq!(
+			PRICE_ORACLE_FAILURES
+				.with_label_values(&["price_oracle_failures"])
+				.get(),
+			failures + 1
+		);
+		assert!(oracles.cached("price_oracle_failures").is_some());
+		failing.assert_async().await;
+	}
+
+	#[tokio::test]
+	async fn test_chainlink_price_oracle() {
+		let now = chrono::Utc::now().timestamp() as u64;
+		let tokens = HashMap::from([(
+			NATIVE_TOKEN.to_string(),
+			PricedToken {
+				feed: Some(ETH_USD_FEED.to_string()),
+				..priced(18, None, None)
+			},
+		)]);
+
+		let client = FeedClient {
+			answer: 312_345_000_000,
+			updated_at: now,
+		};
+		let prices = ChainlinkPriceOracle::new(&client)
+			.prices(&tokens)
+			.await
+			.unwrap();
+		assert_eq!(
+			prices[NATIVE_TOKEN].usd,
+			Decimal::from_str("3123.45").unwrap()
+		);
+		assert_eq!(prices[NATIVE_TOKEN].updated_at, now as i64);
+
+		// Non-positive answers are rejected
+		let client = FeedClient {
+			answer: -1,
+			updated_at: now,
+		};
+		assert!(ChainlinkPriceOracle::new(&client)
+			.prices(&tokens)
+			.await
+			.is_err());
+	}
+
+	#[test]
+	fn test_stale_prices_are_not_used() {
+		let config = config(
+			PriceSource::Static,
+			vec![(NATIVE_TOKEN, priced(18, Some("2000"), None))],
+		);
+		let now = chrono::Utc::now().timestamp();
+		let snapshot = PriceSnapshot::new(
+			"price_oracle_stale",
+			&config,
+			HashMap::from([(
+				NATIVE_TOKEN.to_string(),
+				TokenPrice {
+					usd: Decimal::from(2000),
+					updated_at: now - 7200,
+				},
+			)]),
+		);
+
+		let stale = PRICE_ORACLE_STALE
+			.with_label_values(&["price_oracle_stale", NATIVE_TOKEN])
+			.get();
+		assert!(snapshot.prices_token(NATIVE_TOKEN));
+		assert_eq!(snapshot.usd_value(U256::from(1u8), NATIVE_TOKEN), None);
+		assert_eq!(
+			PRICE_ORACLE_STALE
+				.with_label_values(&["price_oracle_stale", NATIVE_TOKEN])
+				.get(),
+			stale + 1
+		);
+	}
+
+	#[test]
+	fn test_fixed_point_helpers() {
+		assert_eq!(
+			parse_fixed("1000000", 6),
+			Some(U512::from(1_000_000_000_000u64))
+		);
+		assert_eq!(parse_fixed("1.5", 6), Some(U512::from(1_500_000u64)));
+		assert_eq!(parse_fixed("0.0000001", 6), None);
+		assert_eq!(parse_fixed("-1", 6), None);
+		assert_eq!(parse_fixed("1e6", 6), None);
+		assert_eq!(parse_fixed(".5", 6), None);
+
+		assert_eq!(format_fixed(U512::from(1_500_000u64), 6), "1.5");
+		assert_eq!(format_fixed(U512::from(42u64), 6), "0.000042");
+		assert_eq!(format_fixed(U512::ZERO, 6), "0");
+	}
+
+	#[test]
+	fn test_abi_word() {
+		let mut data = vec![0u8; 64];
+		data[63] = 8;
+		assert_eq!(
+			abi_word(&data, 1).map(U256::from_be_bytes::<32>),
+			Some(U256::from(8u8))
+		);
+		assert_eq!(abi_word(&data, 2), None);
+	}
+}
@@ -0,0 +1,548 @@
+//! Nonce tracking of monitored sender accounts.
+//!
+//! The EVM filter emits a candidate match for every transaction sent by an address of a monitor
+//! with a `sender_activity` condition. Blocks are filtered concurrently, so the candidates are
+//! checked here once their block is handled in order: the nonce of each transaction is compared
+//! with the nonce history of its sender to detect nonce gaps and replaced transactions, and
+//! nonces left missing past the condition's threshold are reported as stuck. Candidates that
+//! show none of these are dropped.
+//!
+//! The history kept per sender is bounded, and is optionally persisted to a file so that a
+//! restart does not lose track of the expected nonces.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::{
+	collections::{BTreeMap, HashMap},
+	marker::PhantomData,
+	path::PathBuf,
+	sync::{Arc, Mutex},
+};
+
+use crate::{
+	models::{
+		EVMMatchParamEntry, EVMMonitorMatch, EVMSenderActivity, MonitorMatch, ProcessedBlock,
+		SenderActivityCondition, SenderActivityKind,
+	},
+	services::filter::{
+		evm_helpers::{b256_to_string, h160_to_string, normalize_address},
+		EVMBlockFilter,
+	},
+};
+
+/// Default file the nonce history of the monitored senders is persisted to
+pub const DEFAULT_SENDER_ACTIVITY_STATE_FILE: &str = "data/sender_activity.json";
+
+/// Number of recent nonces remembered per sender for detecting replacements
+const NONCE_HISTORY_LIMIT: usize = 64;
+
+/// Number of missing nonces tracked per sender
+const MISSING_NONCE_LIMIT: usize = 16;
+
+/// Source of the current time
+pub type SenderActivityClock = Arc<dyn Fn() -> DateTime<Utc> + Send + Sync>;
+
+/// Nonce missing from the transactions of a sender
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct MissingNonce {
+	/// Time the nonce went missing in milliseconds since the Unix epoch
+	since: i64,
+	/// Whether the nonce was already reported as stuck
+	reported: bool,
+}
+
+/// Nonce history of a sender, as seen by one monitor on one network
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct SenderState {
+	/// Nonce the sender is expected to use next
+	next_nonce: u64,
+	/// Hashes of the most recent transactions keyed by nonce
+	recent: BTreeMap<u64, String>,
+	/// Nonces skipped by the sender that were not seen since
+	missing: BTreeMap<u64, MissingNonce>,
+	/// Latest transaction of the sender, carrying the monitor reported on when a nonce is stuck
+	last_match: EVMMonitorMatch,
+}
+
+/// Tracks the nonces used by the monitored sender accounts
+pub struct SenderActivityTracker {
+	clock: SenderActivityClock,
+	state_file: Option<PathBuf>,
+	state: Mutex<HashMap<String, SenderState>>,
+}
+
+impl Default for SenderActivityTracker {
+	fn default() -> Self {
+		Self {
+			clock: Arc::new(Utc::now),
+			state_file: None,
+			state: Mutex::new(HashMap::new()),
+		}
+	}
+}
+
+impl SenderActivityTracker {
+	/// Creates a tracker kept in memory only
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Creates a tracker persisted to the given file, restoring the nonce history it holds
+	///
+	/// # Arguments
+	/// * `path` - File the nonce history is read from and written to
+	///
+	/// # Returns
+	/// * `Result<Self, anyhow::Error>` - The tracker, or an error if the file cannot be parsed
+	pub fn with_state_file(path: impl Into<PathBuf>) -> Result<Self, anyhow::Error> {
+		let path = path.into();
+		let state = match std::fs::read_to_string(&path) {
+			Ok(content) => serde_json::from_str(&content)?,
+			Err(e) if e.kind() == std::io::ErrorKind::NotFound => HashMap::new(),
+			Err(e) => return Err(e.into()),
+		};
+		Ok(Self {
+			state_file: Some(path),
+			state: Mutex::new(state),
+			..Self::default()
+		})
+	}
+
+	/// Replaces the source of the current time
+	pub fn with_clock(mut self, clock: SenderActivityClock) -> Self {
+		self.clock = clock;
+		self
+	}
+
+	/// Checks the sender activity candidates of a block against the nonce history of their
+	/// senders
+	///
+	/// Blocks must be passed in block order. Candidates showing a nonce gap or a replacement
+	/// are kept with their activity filled in, other candidates are dropped, and stuck nonces of
+	/// the block's network are added as new matches.
+	///
+	/// # Arguments
+	/// * `block` - The processed block
+	///
+	/// # Returns
+	/// * `ProcessedBlock` - The block with its sender activity matches resolved
+	pub fn resolve(&self, mut block: ProcessedBlock) -> ProcessedBlock {
+		let has_candidates = block
+			.processing_results
+			.iter()
+			.any(|m| observed_nonce(m).is_some());
+		let mut states = self.lock_state();
+		if !has_candidates
+			&& !states
+				.keys()
+				.any(|key| is_network_key(key, &block.network_slug))
+		{
+			return block;
+		}
+
+		let now = (self.clock)().timestamp_millis();
+		let mut results = Vec::with_capacity(block.processing_results.len());
+		for monitor_match in std::mem::take(&mut block.processing_results) {
+			let MonitorMatch::EVM(evm_match) = monitor_match else {
+				results.push(monitor_match);
+				continue;
+			};
+			let Some(nonce) = observed_nonce_of(&evm_match) else {
+				results.push(MonitorMatch::EVM(evm_match));
+				continue;
+			};
+			if let Some(resolved) = observe(&mut states, *evm_match, nonce, now) {
+				results.push(MonitorMatch::EVM(Box::new(resolved)));
+			}
+		}
+		let (stuck, reported) = stuck_nonces(&mut states, &block.network_slug, now);
+		results.extend(stuck.into_iter().map(|m| MonitorMatch::EVM(Box::new(m))));
+		block.processing_results = results;
+
+		if has_candidates || reported {
+			self.persist(&states);
+		}
+		block
+	}
+
+	fn persist(&self, states: &HashMap<String, SenderState>) {
+		let Some(path) = &self.state_file else {
+			return;
+		};
+		let result = serde_json::to_vec(states)
+			.map_err(anyhow::Error::from)
+			.and_then(|json| {
+				if let Some(parent) = path.parent() {
+					std::fs::create_dir_all(parent)?;
+				}
+				let tmp_path = path.with_extension("json.tmp");
+				std::fs::write(&tmp_path, json)?;
+				std::fs::rename(&tmp_path, path)?;
+				Ok(())
+			});
+		if let Err(e) = result {
+			tracing::warn!(
+				"Failed to persist sender activity to {}: {}",
+				path.display(),
+				e
+			);
+		}
+	}
+
+	fn lock_state(&self) -> std::sync::MutexGuard<'_, HashMap<String, SenderState>> {
+		self.state.lock().unwrap_or_else(|e| e.into_inner())
+	}
+}
+
+/// Returns the nonce of a sender activity candidate not yet checked
+fn observed_nonce(monitor_match: &MonitorMatch) -> Option<u64> {
+	match monitor_match {
+		MonitorMatch::EVM(evm_match) => observed_nonce_of(evm_match),
+		_ => None,
+	}
+}
+
+fn observed_nonce_of(evm_match: &EVMMonitorMatch) -> Option<u64> {
+	evm_match
+		.sender_activity
+		.as_ref()
+		.filter(|activity| activity.kind.is_none())
+		.map(|activity| activity.nonce)
+}
+
+/// Key of the nonce history of a sender as seen by a monitor on a network
+fn sender_key(evm_match: &EVMMonitorMatch) -> String {
+	format!(
+		"{}|{}|{}",
+		evm_match.network_slug,
+		evm_match.monitor.name,
+		normalize_address(
+			&evm_match
+				.transaction
+				.from
+				.map_or(String::new(), h160_to_string)
+		)
+	)
+}
+
+fn is_network_key(key: &str, network_slug: &str) -> bool {
+	key.split('|').next() == Some(network_slug)
+}
+
+/// Records a transaction of a sender and returns its match if it shows a nonce gap or a
+/// replacement accepted by the monitor's condition
+fn observe(
+	states: &mut HashMap<String, SenderState>,
+	evm_match: EVMMonitorMatch,
+	nonce: u64,
+	now: i64,
+) -> Option<EVMMonitorMatch> {
+	let hash = b256_to_string(evm_match.transaction.hash);
+	let key = sender_key(&evm_match);
+	let Some(state) = states.get_mut(&key) else {
+		// First transaction seen from the sender, nothing to compare it with
+		states.insert(
+			key,
+			SenderState {
+				next_nonce: nonce + 1,
+				recent: BTreeMap::from([(nonce, hash)]),
+				missing: BTreeMap::new(),
+				last_match: evm_match,
+			},
+		);
+		return None;
+	};
+
+	let expected_nonce = state.next_nonce;
+	let mut activity = EVMSenderActivity::observed(nonce);
+	activity.expected_nonce = Some(expected_nonce);
+	match state.recent.insert(nonce, hash.clone()) {
+		Some(previous) if previous != hash => {
+			activity.kind = Some(SenderActivityKind::Replacement);
+			activity.replaced_hash = Some(previous);
+		}
+		// The same transaction seen again
+		Some(_) => {}
+		None if nonce > expected_nonce => {
+			activity.kind = Some(SenderActivityKind::NonceGap);
+			for missing in (expected_nonce..nonce).take(MISSING_NONCE_LIMIT) {
+				state.missing.insert(
+					missing,
+					MissingNonce {
+						since: now,
+						reported: false,
+					},
+				);
+			}
+		}
+		None => {}
+	}
+	state.missing.remove(&nonce);
+	state.next_nonce = state.next_nonce.max(nonce + 1);
+	while state.recent.len() > NONCE_HISTORY_LIMIT {
+		state.recent.pop_first();
+	}
+	while state.missing.len() > MISSING_NONCE_LIMIT {
+		state.missing.pop_first();
+	}
+	state.last_match = evm_match.clone();
+
+	let kind = activity.kind?;
+	let condition = evm_match.matched_on.sender_activity.as_ref()?;
+	if !condition.matches_kind(kind) || !expression_matches(condition, &activity) {
+		return None;
+	}
+	Some(EVMMonitorMatch {
+		sender_activity: Some(activity),
+		..evm_match
+	})
+}
+
+/// Returns the matches of the nonces of a network that went missing longer ago than the
+/// threshold of their monitor's condition, reporting each nonce once
+///
+/// The returned flag is true if any nonce was newly reported.
+fn stuck_nonces(
+	states: &mut HashMap<String, SenderState>,
+	network_slug: &str,
+	now: i64,
+) -> (Vec<EVMMonitorMatch>, bool) {
+	let mut keys = states
+		.keys()
+		.filter(|key| is_network_key(key, network_slug))
+		.cloned()
+		.collect::<Vec<_>>();
+	keys.sort();
+
+	let mut matches = Vec::new();
+	let mut reported = false;
+	for key in keys {
+		let Some(state) = states.get_mut(&key) else {
+			continue;
+		};
+		let Some(condition) = state.last_match.matched_on.sender_activity.clone() else {
+			continue;
+		};
+		let threshold_ms = condition.stuck_after_secs.saturating_mul(1000) as i64;
+		for (nonce, missing) in state.missing.iter_mut() {
+			let stuck_for = now - missing.since;
+			if missing.reported || stuck_for < threshold_ms {
+				continue;
+			}
+			missing.reported = true;
+			reported = true;
+
+			let activity = EVMSenderActivity {
+				kind: Some(SenderActivityKind::Stuck),
+				nonce: *nonce,
+				expected_nonce: Some(*nonce),
+				replaced_hash: None,
+				stuck_duration: Some((stuck_for / 1000) as u64),
+			};
+			if condition.matches_kind(SenderActivityKind::Stuck)
+				&& expression_matches(&condition, &activity)
+			{
+				matches.push(EVMMonitorMatch {
+					sender_activity: Some(activity),
+					..state.last_match.clone()
+				});
+			}
+		}
+	}
+	(matches, reported)
+}
+
+/// Evaluates the expression of the condition against the activity
+fn expression_matches(condition: &SenderActivityCondition, activity: &EVMSenderActivity) -> bool {
+	let Some(expression) = &condition.expression else {
+		return true;
+	};
+	let params = activity_params(activity);
+	let filter = EVMBlockFilter::<()> {
+		_client: PhantomData,
+		price_oracles: None,
+		prices: None,
+		decode_failures: Default::default(),
+		decode_failure_tracker: None,
+	};
+	match filter.evaluate_expression(expression, &params) {
+		Ok(result) => result,
+		Err(e) => {
+			tracing::error!("Failed to evaluate expression '{}': {}", expression, e);
+			false
+		}
+	}
+}
+
+/// Variables of a sender activity available to expressions
+fn activity_params(activity: &EVMSenderActivity) -> Vec<EVMMatchParamEntry> {
+	let param = |name: &str, value: String, kind: &str| EVMMatchParamEntry {
+		name: name.to_string(),
+		value,
+		kind: kind.to_string(),
+		indexed: false,
+	};
+	vec![
+		param(
+			"kind",
+			activity.kind.map(|k| k.to_string()).unwrap_or_default(),
+			"string",
+		),
+		param("nonce", activity.nonce.to_string(), "uint64"),
+		param(
+			"expected_nonce",
+			activity.expected_nonce.unwrap_or_default().to_string(),
+			"uint64",
+		),
+		param(
+			"replaced_hash",
+			activity.replaced_hash.clone().unwrap_or_default(),
+			"string",
+		),
+		param(
+			"stuck_duration",
+			activity.stuck_duration.unwrap_or_default().to_string(),
+			"uint64",
+		),
+	]
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{
+		models::{MatchConditions, SenderActivityCondition},
+		utils::tests::builders::evm::{
+			monitor::MonitorBuilder, monitor_match::MonitorMatchBuilder,
+			transaction::TransactionBuilder,
+		},
+	};
+	use alloy::primitives::{Address, B256, U256};
+	use std::sync::atomic::{AtomicI64, Ordering};
+
+	const SENDER: &str = "0x00000000000000000000000000000000000000aa";
+
+	fn condition() -> SenderActivityCondition {
+		SenderActivityCondition {
+			kinds: vec![],
+			stuck_after_secs: 60,
+			expression: None,
+		}
+	}
+
+	fn candidate(condition: &SenderActivityCondition, nonce: u64, hash: u8) -> MonitorMatch {
+		MonitorMatchBuilder::new()
+			.monitor(
+				MonitorBuilder::new()
+					.name("relayer")
+					.address(SENDER)
+					.sender_activity(condition.clone())
+					.build(),
+			)
+			.transaction(
+				TransactionBuilder::new()
+					.from(SENDER.parse::<Address>().unwrap())
+					.nonce(U256::from(nonce))
+					.hash(B256::repeat_byte(hash))
+					.build(),
+			)
+			.matched_on(MatchConditions {
+				sender_activity: Some(condition.clone()),
+				..Default::default()
+			})
+			.sender_activity(EVMSenderActivity::observed(nonce))
+			.build()
+	}
+
+	fn block(number: u64, processing_results: Vec<MonitorMatch>) -> ProcessedBlock {
+		ProcessedBlock {
+			block_number: number,
+			network_slug: "ethereum_mainnet".to_string(),
+			processing_results,
+		}
+	}
+
+	fn activities(block: &ProcessedBlock) -> Vec<EVMSenderActivity> {
+		block
+			.processing_results
+			.iter()
+			.filter_map(|m| match m {
+				MonitorMatch::EVM(m) => m.sender_activity.clone(),
+				_ => None,
+			})
+			.collect()
+	}
+
+	#[test]
+	fn test_expression_filters_activity() {
+		let condition = SenderActivityCondition {
+			expression: Some("nonce > 5".to_string()),
+			..condition()
+		};
+		let tracker = SenderActivityTracker::new();
+
+		tracker.resolve(block(1, vec![candidate(&condition, 0, 1)]));
+		let small_gap = tracker.resolve(block(2, vec![candidate(&condition, 2, 2)]));
+		assert!(activities(&small_gap).is_empty());
+		let large_gap = tracker.resolve(block(3, vec![candidate(&condition, 10, 3)]));
+		assert_eq!(activities(&large_gap)[0].expected_nonce, Some(3));
+	}
+
+	#[test]
+	fn test_stuck_nonce_reported_once() {
+		let now = Arc::new(AtomicI64::new(0));
+		let clock_now = now.clone();
+		let tracker = SenderActivityTracker::new().with_clock(Arc::new(move || {
+			DateTime::from_timestamp_millis(clock_now.load(Ordering::SeqCst)).unwrap()
+		}));
+		let condition = SenderActivityCondition {
+			kinds: vec![SenderActivityKind::Stuck],
+			..condition()
+		};
+
+		tracker.resolve(block(1, vec![candidate(&condition, 4, 1)]));
+		// The gap itself is not reported as only stuck nonces are matched
+		let gap = tracker.resolve(block(2, vec![candidate(&condition, 6, 2)]));
+		assert!(activities(&gap).is_empty());
+
+		now.store(59_000, Ordering::SeqCst);
+		assert!(activities(&tracker.resolve(block(3, vec![]))).is_empty());
+
+		now.store(61_000, Ordering::SeqCst);
+		let stuck = activities(&tracker.resolve(block(4, vec![])));
+		assert_eq!(
+			stuck,
+			vec![EVMSenderActivity {
+				kind: Some(SenderActivityKind::Stuck),
+				nonce: 5,
+				expected_nonce: Some(5),
+				replaced_hash: None,
+				stuck_duration: Some(61),
+			}]
+		);
+		assert!(activities(&tracker.resolve(block(5, vec![]))).is_empty());
+	}
+
+	#[test]
+	fn test_history_is_bounded_and_persisted() {
+		let dir = tempfile::tempdir().unwrap();
+		let path = dir.path().join("sender_activity.json");
+		let tracker = SenderActivityTracker::with_state_file(&path).unwrap();
+
+		tracker.resolve(block(1, vec![candidate(&condition(), 0, 1)]));
+		tracker.resolve(block(2, vec![candidate(&condition(), 1000, 2)]));
+		for nonce in 1001..1100 {
+			tracker.resolve(block(nonce, vec![candidate(&condition(), nonce, 3)]));
+		}
+		{
+			let states = tracker.lock_state();
+			let state = states.values().next().unwrap();
+			assert_eq!(state.recent.len(), NONCE_HISTORY_LIMIT);
+			assert_eq!(state.missing.len(), MISSING_NONCE_LIMIT);
+		}
+
+		let restored = SenderActivityTracker::with_state_file(&path).unwrap();
+		let next = restored.resolve(block(1100, vec![candidate(&condition(), 1101, 4)]));
+		assert_eq!(activities(&next)[0].expected_nonce, Some(1100));
+	}
+}
@@ -5,10 +5,12 @@
 //! - `blockwatcher`: Block monitoring and processing
 //! - `filter`: Transaction and event filtering logic
 //! - `notification`: Alert and notification handling
+//! - `state`: State storage shared by monitor instances
 //! - `trigger`: Trigger evaluation and execution
 
 pub mod blockchain;
 pub mod blockwatcher;
 pub mod filter;
 pub mod notification;
+pub mod state;
 pub mod trigger;
@@ -0,0 +1,156 @@
+//! Features supported by the notification channels.
+//!
+//! Each notifier declares the [`ChannelCapabilities`] of its channel. They are used to warn
+//! about trigger options a channel cannot honor when the configuration is linted, and to size
+//! messages before delivery.
+
+use crate::{
+	models::{MessageFormat, TriggerType, TriggerTypeConfig},
+	services::notification::{
+		DiscordPayloadBuilder, EmailNotifier, GenericWebhookPayloadBuilder, ScriptNotifier,
+		SlackPayloadBuilder, TelegramPayloadBuilder,
+	},
+};
+
+/// Features a notification channel supports
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ChannelCapabilities {
+	/// Whether HTML message bodies are rendered
+	pub supports_html: bool,
+	/// Whether files can be attached to notifications
+	pub supports_attachments: bool,
+	/// Whether notifications can be grouped into threads
+	pub supports_threading: bool,
+	/// Maximum size of a message in bytes, if the channel limits it
+	pub max_payload_bytes: Option<usize>,
+	/// Whether the header carrying the idempotency key can be configured
+	pub supports_idempotency_header: bool,
+}
+
+/// A trigger option the trigger's channel does not support
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnsupportedOption {
+	/// Path of the option within the trigger configuration
+	pub field: &'static str,
+	/// Feature the option relies on
+	pub feature: &'static str,
+}
+
+impl ChannelCapabilities {
+	/// Returns the capabilities declared by the notifier of a trigger type
+	pub fn for_trigger_type(trigger_type: &TriggerType) -> Self {
+		match trigger_type {
+			TriggerType::Slack => SlackPayloadBuilder::CAPABILITIES,
+			TriggerType::Discord => DiscordPayloadBuilder::CAPABILITIES,
+			TriggerType::Telegram => TelegramPayloadBuilder::CAPABILITIES,
+			TriggerType::Webhook => GenericWebhookPayloadBuilder::CAPABILITIES,
+			TriggerType::Email => EmailNotifier::CAPABILITIES,
+			TriggerType::Script => ScriptNotifier::CAPABILITIES,
+		}
+	}
+
+	/// Returns the options of a trigger configuration the channel cannot honor
+	pub fn unsupported_options(&self, config: &TriggerTypeConfig) -> Vec<UnsupportedOption> {
+		let mut unsupported = Vec::new();
+		if let Some(message) = config.message() {
+			if message.format == Some(MessageFormat::Html) && !self.supports_html {
+				unsupported.push(UnsupportedOption {
+					field: "message.format",
+					feature: "HTML messages",
+				});
+			}
+			if message.thread_key.is_some() && !self.supports_threading {
+				unsupported.push(UnsupportedOption {
+					field: "message.thread_key",
+					feature: "threading",
+				});
+			}
+			if message
+				.attachments
+				.as_ref()
+				.is_some_and(|attachments| !attachments.is_empty())
+				&& !self.supports_attachments
+			{
+				unsupported.push(UnsupportedOption {
+					field: "message.attachments",
+					feature: "attachments",
+				});
+			}
+		}
+		if let TriggerTypeConfig::Webhook {
+			idempotency_header: Some(_),
+			..
+		} = config
+		{
+			if !self.supports_idempotency_header {
+				unsupported.push(UnsupportedOption {
+					field: "idempotency_header",
+					feature: "custom idempotency headers",
+				});
+			}
+		}
+		unsupported
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::utils::tests::builders::trigger::TriggerBuilder;
+
+	#[test]
+	fn test_email_supports_all_message_options() {
+		let trigger = TriggerBuilder::new()
+			.email(
+				"smtp.example.com",
+				"user",
+				"pass",
+				"alerts@example.com",
+				vec!["ops@example.com"],
+			)
+			.message_format(MessageFormat::Html)
+			.thread_key("${monitor.name}")
+			.attachments(vec!["report.csv".to_string()])
+			.build();
+
+		let capabilities = ChannelCapabilities::for_trigger_type(&trigger.trigger_type);
+		assert!(capabilities.unsupported_options(&trigger.config).is_empty());
+	}
+
+	#[test]
+	fn test_unsupported_message_options() {
+		let trigger = TriggerBuilder::new()
+			.discord("https://discord.com/api/webhooks/1/token")
+			.message_format(MessageFormat::Html)
+			.thread_key("${monitor.name}")
+			.attachments(vec!["report.csv".to_string()])
+			.build();
+
+		let capabilities = ChannelCapabilities::for_trigger_type(&trigger.trigger_type);
+		let fields = capabilities
+			.unsupported_options(&trigger.config)
+			.into_iter()
+			.map(|option| option.field)
+			.collect::<Vec<_>>();
+		assert_eq!(
+			fields,
+			[
+				"message.format",
+				"message.thread_key",
+				"message.attachments"
+			]
+		);
+	}
+
+	#[test]
+	fn test_markdown_format_and_empty_attachments_are_supported_everywhere() {
+		let trigger = TriggerBuilder::new()
+			.slack("https://hooks.slack.com/services/A/B/C")
+			.message_format(MessageFormat::Markdown)
+			.attachments(vec![])
+			.build();
+
+		let capabilities = ChannelCapabilities::for_trigger_type(&trigger.trigger_type);
+		assert!(capabilities.unsupported_options(&trigger.config).is_empty());
+	}
+}
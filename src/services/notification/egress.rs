@@ -0,0 +1,618 @@
+//! Egress controls of HTTP notifiers.
+//!
+//! Hardens webhook-based notifications against requests reaching unintended hosts, such as cloud
+//! metadata services. The addresses a notification endpoint resolves to are checked against
+//! allowed and denied ranges before connecting, redirects to other hosts are refused, and the
+//! resolution of endpoints can be pinned to the addresses first seen. Violations are not retried.
+//!
+//! Controls are configured with environment variables:
+//! - `NOTIFICATION_EGRESS_HARDENING=true` denies link-local and metadata addresses
+//! - `NOTIFICATION_EGRESS_ALLOW` lists the ranges endpoints may resolve to, e.g. `203.0.113.0/24`
+//! - `NOTIFICATION_EGRESS_DENY` lists ranges endpoints may not resolve to, e.g. `10.0.0.0/8`
+//! - `NOTIFICATION_DNS_PINNING=true` keeps using the addresses first resolved for a host
+
+use async_trait::async_trait;
+use futures::future::BoxFuture;
+use reqwest::{
+	dns::{Addrs, Name, Resolve, Resolving},
+	redirect::{Attempt, Policy},
+};
+use reqwest_middleware::{Middleware, Next};
+use reqwest_retry::{Retryable, RetryableStrategy};
+use std::{
+	collections::HashMap,
+	error::Error,
+	fmt,
+	net::{IpAddr, SocketAddr},
+	str::FromStr,
+	sync::{Arc, Mutex},
+};
+use thiserror::Error as ThisError;
+
+use crate::{
+	services::blockchain::TransientErrorRetryStrategy,
+	utils::metrics::{NOTIFICATION_DNS_PIN_CHANGES, NOTIFICATION_EGRESS_VIOLATIONS},
+};
+
+/// Environment variable denying link-local and metadata addresses
+pub const NOTIFICATION_EGRESS_HARDENING_ENV: &str = "NOTIFICATION_EGRESS_HARDENING";
+
+/// Environment variable listing the ranges notification endpoints may resolve to
+pub const NOTIFICATION_EGRESS_ALLOW_ENV: &str = "NOTIFICATION_EGRESS_ALLOW";
+
+/// Environment variable listing the ranges notification endpoints may not resolve to
+pub const NOTIFICATION_EGRESS_DENY_ENV: &str = "NOTIFICATION_EGRESS_DENY";
+
+/// Environment variable pinning the resolution of notification endpoints
+pub const NOTIFICATION_DNS_PINNING_ENV: &str = "NOTIFICATION_DNS_PINNING";
+
+/// Ranges denied in hardening mode: link-local addresses, which include the metadata service
+/// of most clouds, and the metadata addresses outside of them
+const HARDENING_DENIED_RANGES: [&str; 4] = [
+	"169.254.0.0/16",
+	"fe80::/10",
+	"100.100.100.200/32",
+	"fd00:ec2::254/128",
+];
+
+/// Maximum number of redirects followed, as by default
+const MAX_REDIRECTS: usize = 10;
+
+/// Range of IP addresses in CIDR notation, or a single address
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IpRange {
+	/// First address of the range
+	network: IpAddr,
+	/// Number of leading bits shared by the addresses of the range
+	prefix: u8,
+}
+
+impl IpRange {
+	/// Returns whether the address is in the range
+	pub fn contains(&self, ip: &IpAddr) -> bool {
+		match (self.network, ip.to_canonical()) {
+			(IpAddr::V4(network), IpAddr::V4(ip)) => {
+				prefix_matches(&network.octets(), &ip.octets(), self.prefix)
+			}
+			(IpAddr::V6(network), IpAddr::V6(ip)) => {
+				prefix_matches(&network.octets(), &ip.octets(), self.prefix)
+			}
+			_ => false,
+		}
+	}
+}
+
+/// Returns whether the first `prefix` bits of both addresses are equal
+fn prefix_matches(network: &[u8], ip: &[u8], prefix: u8) -> bool {
+	let prefix = prefix as usize;
+	let (bytes, bits) = (prefix / 8, prefix % 8);
+	if network[..bytes] != ip[..bytes] {
+		return false;
+	}
+	bits == 0 || {
+		let mask = 0xffu8 << (8 - bits);
+		network[bytes] & mask == ip[bytes] & mask
+	}
+}
+
+impl FromStr for IpRange {
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		let (address, prefix) = match s.trim().split_once('/') {
+			Some((address, prefix)) => (address, Some(prefix)),
+			None => (s.trim(), None),
+		};
+		let network = IpAddr::from_str(address)
+			.map_err(|e| format!("Invalid address in range '{}': {}", s, e))?
+			.to_canonical();
+		let max_prefix = if network.is_ipv4() { 32 } else { 128 };
+		let prefix = match prefix {
+			Some(prefix) => prefix
+				.parse::<u8>()
+				.ok()
+				.filter(|prefix| *prefix <= max_prefix)
+				.ok_or_else(|| format!("Invalid prefix length in range '{}'", s))?,
+			None => max_prefix,
+		};
+		Ok(Self { network, prefix })
+	}
+}
+
+impl fmt::Display for IpRange {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "{}/{}", self.network, self.prefix)
+	}
+}
+
+/// Request of a notifier refused by the egress controls
+#[derive(ThisError, Debug, Clone, PartialEq, Eq)]
+pub enum EgressViolation {
+	/// The endpoint resolved to an address in a denied range
+	#[error("{host} resolved to {ip}, which is in the denied range {range}")]
+	Denied {
+		host: String,
+		ip: IpAddr,
+		range: IpRange,
+	},
+
+	/// The endpoint resolved to an address outside of the allowed ranges
+	#[error("{host} resolved to {ip}, which is outside of the allowed ranges")]
+	NotAllowed { host: String, ip: IpAddr },
+
+	/// The endpoint redirected to another host
+	#[error("Refused redirect from {from} to {to}, which is not the original host")]
+	Redirect { from: String, to: String },
+}
+
+impl EgressViolation {
+	/// Returns the violation found in the chain of sources of an error, if any
+	pub fn find<'a>(error: &'a (dyn Error + 'static)) -> Option<&'a EgressViolation> {
+		let mut current = Some(error);
+		while let Some(error) = current {
+			if let Some(violation) = error.downcast_ref::<EgressViolation>() {
+				return Some(violation);
+			}
+			// I/O errors skip the error they wrap when asked for their source
+			if let Some(inner) = error
+				.downcast_ref::<std::io::Error>()
+				.and_then(|e| e.get_ref())
+			{
+				if let Some(violation) = Self::find(inner) {
+					return Some(violation);
+				}
+			}
+			current = error.source();
+		}
+		None
+	}
+
+	/// Returns the violation that failed a request, if any
+	pub fn from_request_error(error: &reqwest_middleware::Error) -> Option<&EgressViolation> {
+		match error {
+			reqwest_middleware::Error::Middleware(e) => e.downcast_ref::<EgressViolation>(),
+			reqwest_middleware::Error::Reqwest(e) => Self::find(e),
+		}
+	}
+
+	/// Returns the host the violation is reported for
+	fn host(&self) -> &str {
+		match self {
+			Self::Denied { host, .. } | Self::NotAllowed { host, .. } => host,
+			Self::Redirect { from, .. } => from,
+		}
+	}
+
+	/// Logs the violation and counts it in the metrics
+	fn report(&self) {
+		tracing::error!(host = %self.host(), "Notification egress refused: {}", self);
+		NOTIFICATION_EGRESS_VIOLATIONS
+			.with_label_values(&[self.host()])
+			.inc();
+	}
+}
+
+/// Addresses notification endpoints may and may not resolve to
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EgressPolicy {
+	/// Whether link-local and metadata addresses are denied
+	pub hardening: bool,
+	/// Ranges endpoints may resolve to, any address when empty
+	pub allow: Vec<IpRange>,
+	/// Ranges endpoints may not resolve to
+	pub deny: Vec<IpRange>,
+	/// Whether the addresses first resolved for a host keep being used
+	pub pin_dns: bool,
+}
+
+impl EgressPolicy {
+	/// Returns the policy set by the `NOTIFICATION_EGRESS_*` and `NOTIFICATION_DNS_PINNING`
+	/// variables, or `None` when no control is enabled
+	///
+	/// Invalid ranges are logged and ignored.
+	pub fn from_env() -> Option<Self> {
+		let enabled = |key: &str| std::env::var(key).is_ok_and(|value| value == "true");
+		let ranges = |key: &str| {
+			std::env::var(key)
+				.unwrap_or_default()
+				.split(',')
+				.filter(|range| !range.trim().is_empty())
+				.filter_map(|range| match range.parse::<IpRange>() {
+					Ok(range) => Some(range),
+					Err(e) => {
+						tracing::error!("Ignoring range of {}: {}", key, e);
+						None
+					}
+				})
+				.collect::<Vec<_>>()
+		};
+		let policy = Self {
+			hardening: enabled(NOTIFICATION_EGRESS_HARDENING_ENV),
+			allow: ranges(NOTIFICATION_EGRESS_ALLOW_ENV),
+			deny: ranges(NOTIFICATION_EGRESS_DENY_ENV),
+			pin_dns: enabled(NOTIFICATION_DNS_PINNING_ENV),
+		};
+		(policy != Self::default()).then_some(policy)
+	}
+
+	/// Checks an address a host resolved to against the policy
+	///
+	/// # Arguments
+	/// * `host` - Host of the endpoint
+	/// * `ip` - Address the host resolved to
+	///
+	/// # Returns
+	/// * `Result<(), EgressViolation>` - The violation if the address is refused
+	pub fn check(&self, host: &str, ip: IpAddr) -> Result<(), EgressViolation> {
+		let hardening = self
+			.hardening
+			.then(|| HARDENING_DENIED_RANGES.map(|range| range.parse::<IpRange>().unwrap()));
+		let denied = self
+			.deny
+			.iter()
+			.chain(hardening.iter().flatten())
+			.find(|range| range.contains(&ip));
+		if let Some(range) = denied {
+			return Err(EgressViolation::Denied {
+				host: host.to_string(),
+				ip,
+				range: *range,
+			});
+		}
+		if !self.allow.is_empty() && !self.allow.iter().any(|range| range.contains(&ip)) {
+			return Err(EgressViolation::NotAllowed {
+				host: host.to_string(),
+				ip,
+			});
+		}
+		Ok(())
+	}
+}
+
+/// Resolves host names into addresses
+pub trait HostResolver: Send + Sync {
+	/// Resolves a host name
+	fn resolve(&self, host: &str) -> BoxFuture<'static, std::io::Result<Vec<IpAddr>>>;
+}
+
+/// Resolver of the operating system
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemResolver;
+
+impl HostResolver for SystemResolver {
+	fn resolve(&self, host: &str) -> BoxFuture<'static, std::io::Result<Vec<IpAddr>>> {
+		let host = host.to_string();
+		Box::pin(async move {
+			Ok(tokio::net::lookup_host((host.as_str(), 0))
+				.await?
+				.map(|address| address.ip())
+				.collect())
+		})
+	}
+}
+
+/// Egress controls applied to the HTTP clients of notifiers
+#[derive(Clone)]
+pub struct EgressControls {
+	/// Addresses endpoints may resolve to
+	policy: Arc<EgressPolicy>,
+	/// Resolver of the endpoints
+	resolver: Arc<dyn HostResolver>,
+	/// Addresses pinned per host, with DNS pinning
+	pins: Arc<Mutex<HashMap<String, Vec<IpAddr>>>>,
+}
+
+impl EgressControls {
+	/// Creates the controls of a policy, resolving endpoints with the given resolver
+	pub fn new(policy: EgressPolicy, resolver: Arc<dyn HostResolver>) -> Self {
+		Self {
+			policy: Arc::new(policy),
+			resolver,
+			pins: Arc::new(Mutex::new(HashMap::new())),
+		}
+	}
+
+	/// Returns the controls set by the environment, resolving with the operating system
+	pub fn from_env() -> Option<Self> {
+		EgressPolicy::from_env().map(|policy| Self::new(policy, Arc::new(SystemResolver)))
+	}
+
+	/// Applies the controls to the builder of an HTTP client
+	///
+	/// Host names are resolved and checked by the controls, and redirects to other hosts are
+	/// refused.
+	pub fn apply(&self, builder: reqwest::ClientBuilder) -> reqwest::ClientBuilder {
+		builder
+			.dns_resolver(Arc::new(self.clone()))
+			.redirect(Policy::custom(same_host_redirects))
+	}
+
+	/// Returns the middleware checking the endpoints given as addresses, on every attempt
+	pub fn middleware(&self) -> EgressMiddleware {
+		EgressMiddleware {
+			policy: self.policy.clone(),
+		}
+	}
+
+	/// Resolves a host and checks its addresses, pinning them with DNS pinning
+	async fn resolve_host(&self, host: &str) -> Result<Vec<IpAddr>, Box<dyn Error + Send + Sync>> {
+		let mut addresses = self.resolver.resolve(host).await?;
+		addresses.sort();
+		addresses.dedup();
+
+		if self.policy.pin_dns {
+			let mut pins = self.pins.lock().unwrap();
+			match pins.get(host) {
+				Some(pinned) if *pinned != addresses => {
+					tracing::error!(
+						host = %host,
+						pinned = ?pinned,
+						resolved = ?addresses,
+						"Resolution of notification endpoint changed, keeping the pinned addresses"
+					);
+					NOTIFICATION_DNS_PIN_CHANGES
+						.with_label_values(&[host])
+						.inc();
+					addresses = pinned.clone();
+				}
+				Some(_) => {}
+				None => {
+					pins.insert(host.to_string(), addresses.clone());
+				}
+			}
+		}
+
+		for ip in &addresses {
+			if let Err(violation) = self.policy.check(host, *ip) {
+				violation.report();
+				return Err(Box::new(violation));
+			}
+		}
+		Ok(addresses)
+	}
+}
+
+impl Resolve for EgressControls {
+	fn resolve(&self, name: Name) -> Resolving {
+		let controls = self.clone();
+		Box::pin(async move {
+			controls
+				.resolve_host(name.as_str())
+				.await
+				.map(|addresses| -> Addrs {
+					Box::new(
+						addresses
+							.into_iter()
+							.map(|ip| SocketAddr::new(ip, 0))
+							.collect::<Vec<_>>()
+							.into_iter(),
+					)
+				})
+		})
+	}
+}
+
+/// Follows redirects to the host of the original request only
+fn same_host_redirects(attempt: Attempt) -> reqwest::redirect::Action {
+	let from = attempt
+		.previous()
+		.first()
+		.and_then(|url| url.host_str())
+		.unwrap_or_default()
+		.to_string();
+	let to = attempt.url().host_str().unwrap_or_default().to_string();
+	if from != to {
+		let violation = EgressViolation::Redirect { from, to };
+		violation.report();
+		attempt.error(violation)
+	} else if attempt.previous().len() > MAX_REDIRECTS {
+		attempt.error("too many redirects")
+	} else {
+		attempt.follow()
+	}
+}
+
+/// Middleware checking the endpoints given as addresses, which are not resolved
+///
+/// Added after the retry middleware, so that every attempt is checked.
+#[derive(Clone)]
+pub struct EgressMiddleware {
+	/// Addresses endpoints may resolve to
+	policy: Arc<EgressPolicy>,
+}
+
+#[async_trait]
+impl Middleware for EgressMiddleware {
+	async fn handle(
+		&self,
+		req: reqwest::Request,
+		extensions: &mut http::Extensions,
+		next: Next<'_>,
+	) -> reqwest_middleware::Result<reqwest::Response> {
+		let address = match req.url().host() {
+			Some(url::Host::Ipv4(ip)) => Some(IpAddr::V4(ip)),
+			Some(url::Host::Ipv6(ip)) => Some(IpAddr::V6(ip)),
+			_ => None,
+		};
+		if let Some(ip) = address {
+			if let Err(violation) = self.policy.check(&ip.to_string(), ip) {
+				violation.report();
+				return Err(reqwest_middleware::Error::Middleware(violation.into()));
+			}
+		}
+		next.run(req, extensions).await
+	}
+}
+
+/// Retry strategy of notifiers with egress controls
+///
+/// Requests refused by the controls fail without being retried, other failures are retried as
+/// by [`TransientErrorRetryStrategy`].
+pub struct EgressRetryStrategy;
+
+impl RetryableStrategy for EgressRetryStrategy {
+	fn handle(
+		&self,
+		res: &Result<reqwest::Response, reqwest_middleware::Error>,
+	) -> Option<Retryable> {
+		if let Err(error) = res {
+			if EgressViolation::from_request_error(error).is_some() {
+				return Some(Retryable::Fatal);
+			}
+		}
+		TransientErrorRetryStrategy.handle(res)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn range(range: &str) -> IpRange {
+		range.parse().unwrap()
+	}
+
+	fn ip(ip: &str) -> IpAddr {
+		ip.parse().unwrap()
+	}
+
+	#[test]
+	fn test_ip_range_contains() {
+		assert!(range("10.0.0.0/8").contains(&ip("10.200.1.2")));
+		assert!(!range("10.0.0.0/8").contains(&ip("11.0.0.1")));
+		assert!(range("192.168.4.0/22").contains(&ip("192.168.7.255")));
+		assert!(!range("192.168.4.0/22").contains(&ip("192.168.8.0")));
+		assert!(range("203.0.113.7").contains(&ip("203.0.113.7")));
+		assert!(range("0.0.0.0/0").contains(&ip("8.8.8.8")));
+		assert!(range("fe80::/10").contains(&ip("fe80::1")));
+		assert!(!range("fe80::/10").contains(&ip("10.0.0.1")));
+		// IPv4-mapped IPv6 addresses are checked as IPv4
+		assert!(range("169.254.0.0/16").contains(&ip("::ffff:169.254.169.254")));
+	}
+
+	#[test]
+	fn test_ip_range_parse_errors() {
+		assert!("10.0.0.0/33".parse::<IpRange>().is_err());
+		assert!("10.0.0/8".parse::<IpRange>().is_err());
+		assert!("::/129".parse::<IpRange>().is_err());
+		assert_eq!(range(" 10.0.0.0/8 ").to_string(), "10.0.0.0/8");
+	}
+
+	#[test]
+	fn test_policy_check() {
+		let policy = EgressPolicy {
+			hardening: true,
+			allow: vec![range("10.0.0.0/8"), range("203.0.113.0/24")],
+			deny: vec![range("10.0.0.0/16")],
+			pin_dns: false,
+		};
+
+		assert!(policy.check("hooks.example.com", ip("203.0.113.9")).is_ok());
+		assert!(policy.check("hooks.example.com", ip("10.1.0.1")).is_ok());
+		assert_eq!(
+			policy.check("internal", ip("10.0.3.4")),
+			Err(EgressViolation::Denied {
+				host: "internal".to_string(),
+				ip: ip("10.0.3.4"),
+				range: range("10.0.0.0/16"),
+			})
+		);
+		assert!(matches!(
+			policy.check("other", ip("198.51.100.1")),
+			Err(EgressViolation::NotAllowed { .. })
+		));
+	}
+
+	#[test]
+	fn test_hardening_denies_metadata_addresses() {
+		let policy = EgressPolicy {
+			hardening: true,
+			..Default::default()
+		};
+		for address in [
+			"169.254.169.254",
+			"fe80::1",
+			"100.100.100.200",
+			"fd00:ec2::254",
+		] {
+			let violation = policy.check("metadata", ip(address)).unwrap_err();
+			assert!(violation.to_string().contains(address));
+		}
+		assert!(policy.check("hooks.example.com", ip("10.0.0.1")).is_ok());
+		assert!(EgressPolicy::default()
+			.check("metadata", ip("169.254.169.254"))
+			.is_ok());
+	}
+
+	struct StaticResolver(Mutex<Vec<Vec<IpAddr>>>);
+
+	impl HostResolver for StaticResolver {
+		fn resolve(&self, _host: &str) -> BoxFuture<'static, std::io::Result<Vec<IpAddr>>> {
+			let mut resolutions = self.0.lock().unwrap();
+			let addresses = if resolutions.len() > 1 {
+				resolutions.remove(0)
+			} else {
+				resolutions[0].clone()
+			};
+			Box::pin(async move { Ok(addresses) })
+		}
+	}
+
+	#[tokio::test]
+	async fn test_dns_pinning_keeps_first_resolution() {
+		let resolver = StaticResolver(Mutex::new(vec![
+			vec![ip("203.0.113.5")],
+			vec![ip("169.254.169.254")],
+		]));
+		let controls = EgressControls::new(
+			EgressPolicy {
+				hardening: true,
+				pin_dns: true,
+				..Default::default()
+			},
+			Arc::new(resolver),
+		);
+
+		let first = controls.resolve_host("hooks.example.com").await.unwrap();
+		let second = controls.resolve_host("hooks.example.com").await.unwrap();
+
+		assert_eq!(first, vec![ip("203.0.113.5")]);
+		assert_eq!(second, first);
+	}
+
+	#[tokio::test]
+	async fn test_unpinned_resolution_is_checked_every_time() {
+		let resolver = StaticResolver(Mutex::new(vec![
+			vec![ip("203.0.113.5")],
+			vec![ip("169.254.169.254")],
+		]));
+		let controls = EgressControls::new(
+			EgressPolicy {
+				hardening: true,
+				..Default::default()
+			},
+			Arc::new(resolver),
+		);
+
+		assert!(controls.resolve_host("hooks.example.com").await.is_ok());
+		let error = controls
+			.resolve_host("hooks.example.com")
+			.await
+			.unwrap_err();
+		assert!(matches!(
+			EgressViolation::find(&*error),
+			Some(EgressViolation::Denied { .. })
+		));
+	}
+
+	#[test]
+	fn test_find_violation_through_io_errors() {
+		let violation = EgressViolation::NotAllowed {
+			host: "hooks.example.com".to_string(),
+			ip: ip("198.51.100.1"),
+		};
+		let error = std::io::Error::other(violation.clone());
+		assert_eq!(EgressViolation::find(&error), Some(&violation));
+		assert!(EgressViolation::find(&std::io::Error::other("other")).is_none());
+	}
+}
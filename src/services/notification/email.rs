@@ -8,20 +8,24 @@ use email_address::EmailAddress;
 use lettre::{
 	message::{
 		header::{self, ContentType},
-		Mailbox, Mailboxes,
+		Attachment, Mailbox, Mailboxes, MultiPart, SinglePart,
 	},
 	transport::smtp::Error as SmtpError,
 	AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor,
 };
 use pulldown_cmark::{html, Options, Parser};
-use std::{collections::HashMap, error::Error as StdError, sync::Arc};
+use sha2::{Digest, Sha256};
+use std::{collections::HashMap, error::Error as StdError, path::Path, sync::Arc};
 
 use crate::{
-	models::TriggerTypeConfig,
-	services::notification::{template_formatter, NotificationError},
+	models::{MessageFormat, TriggerTypeConfig},
+	services::notification::{template_formatter, ChannelCapabilities, NotificationError},
 	utils::{JitterSetting, RetryConfig},
 };
 
+/// Domain of the message IDs identifying email threads
+const THREAD_ID_DOMAIN: &str = "openzeppelin-monitor";
+
 /// Implementation of email notifications via SMTP
 #[derive(Debug)]
 pub struct EmailNotifier<T: AsyncTransport + Send + Sync> {
@@ -35,6 +39,12 @@ pub struct EmailNotifier<T: AsyncTransport + Send + Sync> {
 	sender: EmailAddress,
 	/// Email recipients
 	recipients: Vec<EmailAddress>,
+	/// Format of the message template
+	format: Option<MessageFormat>,
+	/// Template of the key grouping emails into threads
+	thread_key: Option<String>,
+	/// Paths of the files attached to emails
+	attachments: Option<Vec<String>>,
 	/// Retry policy for SMTP requests
 	retry_policy: RetryConfig,
 }
@@ -55,6 +65,42 @@ pub struct EmailContent {
 	pub body_template: String,
 	pub sender: EmailAddress,
 	pub recipients: Vec<EmailAddress>,
+	pub format: Option<MessageFormat>,
+	pub thread_key: Option<String>,
+	pub attachments: Option<Vec<String>>,
+}
+
+impl EmailContent {
+	/// Extracts the email content from a trigger configuration
+	///
+	/// # Arguments
+	/// * `config` - Trigger configuration containing email parameters
+	///
+	/// # Returns
+	/// * `Result<Self, NotificationError>` - Email content if config is email type
+	pub fn from_config(config: &TriggerTypeConfig) -> Result<Self, NotificationError> {
+		match config {
+			TriggerTypeConfig::Email {
+				message,
+				sender,
+				recipients,
+				..
+			} => Ok(Self {
+				subject: message.title.clone(),
+				body_template: message.body.clone(),
+				sender: sender.clone(),
+				recipients: recipients.clone(),
+				format: message.format,
+				thread_key: message.thread_key.clone(),
+				attachments: message.attachments.clone(),
+			}),
+			_ => Err(NotificationError::config_error(
+				format!("Invalid email configuration: {:?}", config),
+				None,
+				None,
+			)),
+		}
+	}
 }
 
 // This implementation is only for testing purposes
@@ -82,61 +128,93 @@ where
 			body_template: email_content.body_template,
 			sender: email_content.sender,
 			recipients: email_content.recipients,
+			format: email_content.format,
+			thread_key: email_content.thread_key,
+			attachments: email_content.attachments,
 			client: Arc::new(transport),
 			retry_policy,
 		}
 	}
 
-	/// Sends a formatted message to email
+	/// Formats the message body with the given variables
+	/// HTML templates are sent as is, while Markdown templates are converted to HTML
 	///
 	/// # Arguments
-	/// * `message` - The formatted message to send
+	/// * `variables` - Map of variable names to values
 	///
 	/// # Returns
-	/// * `Result<(), NotificationError>` - Success or error
-	pub async fn notify(&self, message: &str) -> Result<(), NotificationError> {
-		let recipients_str = self
-			.recipients
-			.iter()
-			.map(ToString::to_string)
-			.collect::<Vec<_>>()
-			.join(", ");
+	/// * `String` - HTML body of the email
+	pub fn render_message(&self, variables: &HashMap<String, String>) -> String {
+		match self.format {
+			Some(MessageFormat::Html) => {
+				template_formatter::format_template(&self.body_template, variables)
+			}
+			_ => EmailNotifier::format_message(&self.body_template, variables),
+		}
+	}
 
-		let mailboxes: Mailboxes = recipients_str.parse::<Mailboxes>().map_err(|e| {
-			NotificationError::notify_failed(
-				format!("Failed to parse recipients: {}", e),
-				Some(e.into()),
-				None,
-			)
-		})?;
-		let recipients_header: header::To = mailboxes.into();
+	/// Returns the message ID of the thread the email belongs to, if threading is configured
+	/// Emails whose thread key renders to the same value share the same thread
+	///
+	/// # Arguments
+	/// * `variables` - Map of variable names to values
+	///
+	/// # Returns
+	/// * `Option<String>` - Message ID referenced by the email
+	pub fn thread_id(&self, variables: &HashMap<String, String>) -> Option<String> {
+		let thread_key = self.thread_key.as_ref()?;
+		let key = template_formatter::format_template(thread_key, variables);
+		let digest = hex::encode(Sha256::digest(key.as_bytes()));
+		Some(format!("<{}@{}>", &digest[..32], THREAD_ID_DOMAIN))
+	}
 
-		let email = Message::builder()
-			.mailbox(recipients_header)
-			.from(self.sender.to_string().parse::<Mailbox>().map_err(|e| {
+	/// Reads the attachments of the email
+	async fn read_attachments(&self) -> Result<Vec<SinglePart>, NotificationError> {
+		let mut parts = Vec::new();
+		for path in self.attachments.iter().flatten() {
+			let content = tokio::fs::read(path).await.map_err(|e| {
 				NotificationError::notify_failed(
-					format!("Failed to parse sender: {}", e),
-					Some(e.into()),
-					None,
-				)
-			})?)
-			.reply_to(self.sender.to_string().parse::<Mailbox>().map_err(|e| {
-				NotificationError::notify_failed(
-					format!("Failed to parse reply-to: {}", e),
-					Some(e.into()),
-					None,
-				)
-			})?)
-			.subject(&self.subject)
-			.header(ContentType::TEXT_HTML)
-			.body(message.to_owned())
-			.map_err(|e| {
-				NotificationError::notify_failed(
-					format!("Failed to build email message: {}", e),
+					format!("Failed to read attachment {}: {}", path, e),
 					Some(e.into()),
 					None,
 				)
 			})?;
+			let filename = Path::new(path)
+				.file_name()
+				.map(|name| name.to_string_lossy().into_owned())
+				.unwrap_or_else(|| path.clone());
+			let content_type = ContentType::parse("application/octet-stream")
+				.expect("application/octet-stream is a valid content type");
+			parts.push(Attachment::new(filename).body(content, content_type));
+		}
+		Ok(parts)
+	}
+
+	/// Sends a formatted message to email
+	///
+	/// # Arguments
+	/// * `message` - The formatted message to send
+	///
+	/// # Returns
+	/// * `Result<(), NotificationError>` - Success or error
+	pub async fn notify(&self, message: &str) -> Result<(), NotificationError> {
+		self.notify_in_thread(message, None).await
+	}
+
+	/// Sends a formatted message to email, as a reply to the given thread
+	///
+	/// # Arguments
+	/// * `message` - The formatted message to send
+	/// * `thread_id` - Message ID of the thread, see [`Self::thread_id`]
+	///
+	/// # Returns
+	/// * `Result<(), NotificationError>` - Success or error
+	pub async fn notify_in_thread(
+		&self,
+		message: &str,
+		thread_id: Option<&str>,
+	) -> Result<(), NotificationError> {
+		let email = self.build_email(message, thread_id).await?;
 
 		let operation = || async {
 			self.client.send(email.clone()).await.map_err(|e| {
@@ -180,9 +258,91 @@ where
 			.when(should_retry)
 			.await
 	}
+
+	/// Builds the email sending a formatted message, with its headers and attachments
+	///
+	/// # Arguments
+	/// * `message` - The formatted message to send
+	/// * `thread_id` - Message ID of the thread, see [`Self::thread_id`]
+	///
+	/// # Returns
+	/// * `Result<Message, NotificationError>` - The email or error
+	pub async fn build_email(
+		&self,
+		message: &str,
+		thread_id: Option<&str>,
+	) -> Result<Message, NotificationError> {
+		let recipients_str = self
+			.recipients
+			.iter()
+			.map(ToString::to_string)
+			.collect::<Vec<_>>()
+			.join(", ");
+
+		let mailboxes: Mailboxes = recipients_str.parse::<Mailboxes>().map_err(|e| {
+			NotificationError::notify_failed(
+				format!("Failed to parse recipients: {}", e),
+				Some(e.into()),
+				None,
+			)
+		})?;
+		let recipients_header: header::To = mailboxes.into();
+
+		let mut builder = Message::builder()
+			.mailbox(recipients_header)
+			.from(self.sender.to_string().parse::<Mailbox>().map_err(|e| {
+				NotificationError::notify_failed(
+					format!("Failed to parse sender: {}", e),
+					Some(e.into()),
+					None,
+				)
+			})?)
+			.reply_to(self.sender.to_string().parse::<Mailbox>().map_err(|e| {
+				NotificationError::notify_failed(
+					format!("Failed to parse reply-to: {}", e),
+					Some(e.into()),
+					None,
+				)
+			})?)
+			.subject(&self.subject);
+		if let Some(thread_id) = thread_id {
+			builder = builder
+				.in_reply_to(thread_id.to_string())
+				.references(thread_id.to_string());
+		}
+
+		let attachments = self.read_attachments().await?;
+		if attachments.is_empty() {
+			builder
+				.header(ContentType::TEXT_HTML)
+				.body(message.to_owned())
+		} else {
+			let body = attachments.into_iter().fold(
+				MultiPart::mixed().singlepart(SinglePart::html(message.to_owned())),
+				|body, attachment| body.singlepart(attachment),
+			);
+			builder.multipart(body)
+		}
+		.map_err(|e| {
+			NotificationError::notify_failed(
+				format!("Failed to build email message: {}", e),
+				Some(e.into()),
+				None,
+			)
+		})
+	}
 }
 
 impl EmailNotifier<AsyncSmtpTransport<Tokio1Executor>> {
+	/// Email supports every message option and does not limit the size of messages
+	pub const CAPABILITIES: ChannelCapabilities = ChannelCapabilities {
+		supports_html: true,
+		supports_attachments: true,
+		supports_threading: true,
+		max_payload_bytes: None,
+		supports_idempotency_header: false,
+	};
+
 	/// Creates a new email notifier instance
 	///
 	/// # Arguments
@@ -201,6 +361,9 @@ impl EmailNotifier<AsyncSmtpTransport<Tokio1Executor>> {
 			body_template: email_content.body_template,
 			sender: email_content.sender,
 			recipients: email_content.recipients,
+			format: email_content.format,
+			thread_key: email_content.thread_key,
+			attachments: email_content.attachments,
 			client: smtp_client,
 			retry_policy,
 		})
@@ -247,28 +410,17 @@ impl EmailNotifier<AsyncSmtpTransport<Tokio1Executor>> {
 		config: &TriggerTypeConfig,
 		smtp_client: Arc<AsyncSmtpTransport<Tokio1Executor>>,
 	) -> Result<Self, NotificationError> {
-		if let TriggerTypeConfig::Email {
-			message,
-			sender,
-			recipients,
-			retry_policy,
-			..
-		} = config
-		{
-			let email_content = EmailContent {
-				subject: message.title.clone(),
-				body_template: message.body.clone(),
-				sender: sender.clone(),
-				recipients: recipients.clone(),
-			};
-
-			Self::new(smtp_client, email_content, retry_policy.clone())
-		} else {
-			Err(NotificationError::config_error(
+		match config {
+			TriggerTypeConfig::Email { retry_policy, .. } => Self::new(
+				smtp_client,
+				EmailContent::from_config(config)?,
+				retry_policy.clone(),
+			),
+			_ => Err(NotificationError::config_error(
 				format!("Invalid email configuration: {:?}", config),
 				None,
 				None,
-			))
+			)),
 		}
 	}
 }
@@ -291,6 +443,9 @@ mod tests {
 			body_template: "Hello ${name}, your balance is ${balance}".to_string(),
 			sender: "sender@test.com".parse().unwrap(),
 			recipients: vec!["recipient@test.com".parse().unwrap()],
+			format: None,
+			thread_key: None,
+			attachments: None,
 		}
 	}
 
@@ -322,6 +477,9 @@ mod tests {
 			message: NotificationMessage {
 				title: "Test Subject".to_string(),
 				body: "Hello ${name}".to_string(),
+				format: None,
+				thread_key: None,
+				attachments: None,
 			},
 			sender: "sender@test.com".parse().unwrap(),
 			recipients: vec!["recipient@test.com".parse().unwrap()],
@@ -423,6 +581,9 @@ mod tests {
 			message: NotificationMessage {
 				title: "Test Slack".to_string(),
 				body: "Hello ${name}".to_string(),
+				format: None,
+				thread_key: None,
+				attachments: None,
 			},
 			retry_policy: RetryConfig::default(),
 		};
@@ -510,4 +671,131 @@ mod tests {
 			"Should be called 1 time + default max retries"
 		);
 	}
+
+	////////////////////////////////////////////////////////////
+	// message options tests
+	////////////////////////////////////////////////////////////
+	#[test]
+	fn test_render_message_converts_markdown_to_html() {
+		let notifier = EmailNotifier::with_transport(
+			EmailContent {
+				body_template: "**Hello ${name}**".to_string(),
+				..create_test_email_content()
+			},
+			AsyncStubTransport::new_ok(),
+			RetryConfig::default(),
+		);
+		let variables = HashMap::from([("name".to_string(), "Alice".to_string())]);
+
+		assert_eq!(
+			notifier.render_message(&variables),
+			"<p><strong>Hello Alice</strong></p>\n"
+		);
+	}
+
+	#[test]
+	fn test_render_message_keeps_html_templates() {
+		let notifier = EmailNotifier::with_transport(
+			EmailContent {
+				body_template: "<b>Hello ${name}</b>".to_string(),
+				format: Some(MessageFormat::Html),
+				..create_test_email_content()
+			},
+			AsyncStubTransport::new_ok(),
+			RetryConfig::default(),
+		);
+		let variables = HashMap::from([("name".to_string(), "Alice".to_string())]);
+
+		assert_eq!(notifier.render_message(&variables), "<b>Hello Alice</b>");
+	}
+
+	#[test]
+	fn test_thread_id_is_derived_from_the_rendered_key() {
+		let notifier = EmailNotifier::with_transport(
+			EmailContent {
+				thread_key: Some("${monitor.name}".to_string()),
+				..create_test_email_content()
+			},
+			AsyncStubTransport::new_ok(),
+			RetryConfig::default(),
+		);
+		let variables =
+			|name: &str| HashMap::from([("monitor.name".to_string(), name.to_string())]);
+
+		let thread_id = notifier.thread_id(&variables("Large Transfer")).unwrap();
+		assert!(thread_id.starts_with('<'));
+		assert!(thread_id.ends_with("@openzeppelin-monitor>"));
+		assert_eq!(
+			notifier.thread_id(&variables("Large Transfer")).unwrap(),
+			thread_id
+		);
+		assert_ne!(notifier.thread_id(&variables("Mint")).unwrap(), thread_id);
+
+		let unthreaded = EmailNotifier::with_transport(
+			create_test_email_content(),
+			AsyncStubTransport::new_ok(),
+			RetryConfig::default(),
+		);
+		assert!(unthreaded.thread_id(&variables("Large Transfer")).is_none());
+	}
+
+	#[tokio::test]
+	async fn test_notify_in_thread_sets_reply_headers() {
+		let transport = AsyncStubTransport::new_ok();
+		let notifier = EmailNotifier::with_transport(
+			create_test_email_content(),
+			transport.clone(),
+			RetryConfig::default(),
+		);
+
+		notifier
+			.notify_in_thread("test message", Some("<thread@openzeppelin-monitor>"))
+			.await
+			.unwrap();
+
+		let messages = transport.messages().await;
+		let (_, raw) = &messages[0];
+		assert!(raw.contains("In-Reply-To: <thread@openzeppelin-monitor>"));
+		assert!(raw.contains("References: <thread@openzeppelin-monitor>"));
+	}
+
+	#[tokio::test]
+	async fn test_notify_sends_attachments() {
+		let temp_dir = tempfile::tempdir().unwrap();
+		let path = temp_dir.path().join("report.csv");
+		std::fs::write(&path, "block,amount\n1,100\n").unwrap();
+		let transport = AsyncStubTransport::new_ok();
+		let notifier = EmailNotifier::with_transport(
+			EmailContent {
+				attachments: Some(vec![path.to_string_lossy().into_owned()]),
+				..create_test_email_content()
+			},
+			transport.clone(),
+			RetryConfig::default(),
+		);
+
+		notifier.notify("test message").await.unwrap();
+
+		let messages = transport.messages().await;
+		let (_, raw) = &messages[0];
+		assert!(raw.contains("multipart/mixed"));
+		assert!(raw.contains("filename=\"report.csv\""));
+	}
+
+	#[tokio::test]
+	async fn test_notify_fails_on_missing_attachment() {
+		let transport = AsyncStubTransport::new_ok();
+		let notifier = EmailNotifier::with_transport(
+			EmailContent {
+				attachments: Some(vec!["/nonexistent/report.csv".to_string()]),
+				..create_test_email_content()
+			},
+			transport.clone(),
+			RetryConfig::default(),
+		);
+
+		let result = notifier.notify("test message").await;
+		assert!(result.is_err());
+		assert!(transport.messages().await.is_empty());
+	}
 }
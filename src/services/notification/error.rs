@@ -29,6 +29,10 @@ pub enum NotificationError {
 	/// Error when Notifier `notify`` method fails (e.g., webhook failure, parsing error, invalid signature)
 	#[error("Notification failed: {0}")]
 	NotifyFailed(Box<ErrorContext>),
+
+	/// Errors when a request is refused by the egress controls, which are not retried
+	#[error("Egress denied: {0}")]
+	EgressDenied(Box<ErrorContext>),
 }
 
 impl NotificationError {
@@ -76,6 +80,15 @@ impl NotificationError {
 	) -> Self {
 		Self::NotifyFailed(Box::new(ErrorContext::new_with_log(msg, source, metadata)))
 	}
+
+	// Egress denied error
+	pub fn egress_denied(
+		msg: impl Into<String>,
+		source: Option<Box<dyn std::error::Error + Send + Sync + 'static>>,
+		metadata: Option<HashMap<String, String>>,
+	) -> Self {
+		Self::EgressDenied(Box::new(ErrorContext::new_with_log(msg, source, metadata)))
+	}
 }
 
 impl TraceableError for NotificationError {
@@ -86,6 +99,7 @@ impl TraceableError for NotificationError {
 			Self::InternalError(ctx) => ctx.trace_id.clone(),
 			Self::ExecutionError(ctx) => ctx.trace_id.clone(),
 			Self::NotifyFailed(ctx) => ctx.trace_id.clone(),
+			Self::EgressDenied(ctx) => ctx.trace_id.clone(),
 		}
 	}
 }
@@ -174,6 +188,12 @@ mod tests {
 		);
 	}
 
+	#[test]
+	fn test_egress_denied_error_formatting() {
+		let error = NotificationError::egress_denied("test error", None, None);
+		assert_eq!(error.to_string(), "Egress denied: test error");
+	}
+
 	#[test]
 	fn test_error_source_chain() {
 		let io_error = std::io::Error::other("while reading config");
@@ -232,6 +252,10 @@ mod tests {
 				let (ctx, id) = create_context_with_id();
 				(NotificationError::NotifyFailed(ctx), id)
 			},
+			{
+				let (ctx, id) = create_context_with_id();
+				(NotificationError::EgressDenied(ctx), id)
+			},
 		];
 
 		for (error, original_id) in errors_with_ids {
@@ -0,0 +1,282 @@
+//! Idempotency keys for HTTP notification deliveries.
+//!
+//! Each delivery of a match to a trigger carries a key that receivers can use to drop
+//! duplicates. Keys are derived from the match identity and the trigger name only, so the same
+//! delivery gets the same key across HTTP retries and across monitor restarts, while different
+//! matches found in the same transaction get different keys.
+
+use sha2::{Digest, Sha256};
+use uuid::{Builder, Uuid};
+
+use crate::models::{MatchConditions, MonitorMatch};
+
+/// Header used to send the idempotency key when a trigger does not configure one
+pub const DEFAULT_IDEMPOTENCY_HEADER: &str = "Idempotency-Key";
+
+/// Payload field carrying the idempotency key in generic webhook bodies
+pub const IDEMPOTENCY_KEY_FIELD: &str = "idempotency_key";
+
+/// Idempotency key attached to an outgoing webhook request
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IdempotencyKey {
+	/// Name of the header carrying the key
+	pub header: String,
+	/// The key itself
+	pub key: String,
+}
+
+/// Builds a name-based UUID from the SHA-256 digest of the given parts
+fn uuid_from_parts(parts: &[&str]) -> Uuid {
+	let mut hasher = Sha256::new();
+	for part in parts {
+		// Length-prefix each part so that ("ab", "c") and ("a", "bc") differ
+		hasher.update((part.len() as u64).to_be_bytes());
+		hasher.update(part.as_bytes());
+	}
+	let digest = hasher.finalize();
+
+	let mut bytes = [0u8; 16];
+	bytes.copy_from_slice(&digest[..16]);
+	Builder::from_custom_bytes(bytes).into_uuid()
+}
+
+/// Describes the conditions a match was found on, one part per condition
+///
+/// Each part holds the kind of the condition and its signature (or status) and expression, so
+/// that a function call and an event matched in the same transaction, or two different events,
+/// produce different parts.
+fn matched_condition_parts(conditions: &MatchConditions) -> Vec<String> {
+	let mut parts = Vec::new();
+	for function in &conditions.functions {
+		parts.push(format!(
+			"function:{}:{}",
+			function.signature,
+			function.expression.as_deref().unwrap_or_default()
+		));
+	}
+	for event in &conditions.events {
+		parts.push(format!(
+			"event:{}:{}",
+			event.signature,
+			event.expression.as_deref().unwrap_or_default()
+		));
+	}
+	for transaction in &conditions.transactions {
+		parts.push(format!(
+			"transaction:{:?}:{}",
+			transaction.status,
+			transaction.expression.as_deref().unwrap_or_default()
+		));
+	}
+	if let Some(sender_activity) = &conditions.sender_activity {
+		parts.push(format!(
+			"sender_activity:{:?}:{}",
+			sender_activity.kinds,
+			sender_activity.expression.as_deref().unwrap_or_default()
+		));
+	}
+	if let Some(baseline) = &conditions.baseline {
+		parts.push(format!(
+			"baseline:{}:{}:{}:{}:{}",
+			baseline.aggregate,
+			baseline.amount.as_deref().unwrap_or_default(),
+			baseline.short_window_blocks,
+			baseline.long_window_blocks,
+			baseline.factor
+		));
+	}
+	if conditions.network_metrics.is_some() {
+		parts.push("network_metrics".to_string());
+	}
+	parts
+}
+
+/// Returns a deterministic identifier for a monitor match
+///
+/// The identifier is derived from the network, the monitor name, the match key (the
+/// transaction hash and the locations of the matched events) and the kinds and signatures of
+/// the matched conditions. Together they identify a match independently of when it was
+/// produced, and tell apart different matches of a monitor in the same transaction.
+pub fn match_uuid(monitor_match: &MonitorMatch) -> Uuid {
+	let match_key = monitor_match.match_key();
+	let conditions = matched_condition_parts(monitor_match.matched_on());
+
+	let mut parts = vec![
+		"match",
+		monitor_match.network_slug(),
+		&monitor_match.monitor().name,
+		&match_key,
+	];
+	parts.extend(conditions.iter().map(String::as_str));
+	uuid_from_parts(&parts)
+}
+
+/// Derives the idempotency key for delivering a match to a trigger
+///
+/// # Arguments
+/// * `match_id` - Identifier of the match, see [`match_uuid`]
+/// * `trigger_name` - Name of the trigger the match is delivered to
+///
+/// # Returns
+/// * `Uuid` - Key that is stable for the same match and trigger
+pub fn derive_idempotency_key(match_id: &Uuid, trigger_name: &str) -> Uuid {
+	uuid_from_parts(&["delivery", &match_id.to_string(), trigger_name])
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{
+		models::{BaselineAggregate, BaselineCondition, EventCondition, FunctionCondition},
+		utils::tests::builders::evm::{
+			monitor::MonitorBuilder, monitor_match::MonitorMatchBuilder,
+			transaction::TransactionBuilder,
+		},
+	};
+	use alloy::primitives::B256;
+
+	fn create_match(monitor_name: &str, hash: B256) -> MonitorMatch {
+		create_match_on(monitor_name, hash, MatchConditions::default())
+	}
+
+	fn create_match_on(
+		monitor_name: &str,
+		hash: B256,
+		matched_on: MatchConditions,
+	) -> MonitorMatch {
+		MonitorMatchBuilder::new()
+			.monitor(MonitorBuilder::new().name(monitor_name).build())
+			.transaction(TransactionBuilder::new().hash(hash).build())
+			.matched_on(matched_on)
+			.build()
+	}
+
+	#[test]
+	fn test_same_inputs_produce_same_key() {
+		let first = match_uuid(&create_match("monitor", B256::with_last_byte(1)));
+		let second = match_uuid(&create_match("monitor", B256::with_last_byte(1)));
+		assert_eq!(first, second);
+		assert_eq!(
+			derive_idempotency_key(&first, "webhook"),
+			derive_idempotency_key(&second, "webhook")
+		);
+	}
+
+	#[test]
+	fn test_different_trigger_produces_different_key() {
+		let match_id = match_uuid(&create_match("monitor", B256::with_last_byte(1)));
+		assert_ne!(
+			derive_idempotency_key(&match_id, "webhook"),
+			derive_idempotency_key(&match_id, "other_webhook")
+		);
+	}
+
+	#[test]
+	fn test_different_matches_produce_different_ids() {
+		let base = match_uuid(&create_match("monitor", B256::with_last_byte(1)));
+		assert_ne!(
+			base,
+			match_uuid(&create_match("monitor", B256::with_last_byte(2)))
+		);
+		assert_ne!(
+			base,
+			match_uuid(&create_match("other", B256::with_last_byte(1)))
+		);
+	}
+
+	#[test]
+	fn test_different_matches_in_same_transaction_produce_different_keys() {
+		let hash = B256::with_last_byte(1);
+		let function = create_match_on(
+			"monitor",
+			hash,
+			MatchConditions {
+				functions: vec![FunctionCondition {
+					signature: "transfer(address,uint256)".to_string(),
+					expression: None,
+				}],
+				..Default::default()
+			},
+		);
+		let event = |signature: &str| {
+			create_match_on(
+				"monitor",
+				hash,
+				MatchConditions {
+					events: vec![EventCondition {
+						signature: signature.to_string(),
+						expression: None,
+						match_any_emitter: false,
+					}],
+					..Default::default()
+				},
+			)
+		};
+		let transfer = event("Transfer(address,address,uint256)");
+		let approval = event("Approval(address,address,uint256)");
+
+		let keys = [&function, &transfer, &approval]
+			.map(|monitor_match| derive_idempotency_key(&match_uuid(monitor_match), "webhook"));
+		assert_ne!(keys[0], keys[1]);
+		assert_ne!(keys[1], keys[2]);
+		assert_ne!(keys[0], keys[2]);
+
+		// The same match found again keeps its key
+		assert_eq!(
+			keys[1],
+			derive_idempotency_key(
+				&match_uuid(&event("Transfer(address,address,uint256)")),
+				"webhook"
+			)
+		);
+	}
+
+	#[test]
+	fn test_matches_differing_in_baseline_produce_different_keys() {
+		let hash = B256::with_last_byte(1);
+		let baseline = |factor: f64| {
+			create_match_on(
+				"monitor",
+				hash,
+				MatchConditions {
+					baseline: Some(BaselineCondition {
+						aggregate: BaselineAggregate::Count,
+						amount: None,
+						short_window_blocks: 10,
+						long_window_blocks: 100,
+						factor,
+						warmup_blocks: None,
+						floor: 1.0,
+					}),
+					..Default::default()
+				},
+			)
+		};
+
+		let keys = [
+			&create_match("monitor", hash),
+			&baseline(3.0),
+			&baseline(5.0),
+		]
+		.map(|monitor_match| derive_idempotency_key(&match_uuid(monitor_match), "webhook"));
+		assert_ne!(keys[0], keys[1]);
+		assert_ne!(keys[1], keys[2]);
+		assert_eq!(
+			keys[1],
+			derive_idempotency_key(&match_uuid(&baseline(3.0)), "webhook")
+		);
+	}
+
+	#[test]
+	fn test_match_id_is_stable_across_releases() {
+		let match_id = match_uuid(&create_match("monitor", B256::with_last_byte(1)));
+		assert_eq!(match_id.to_string(), "00d442e6-4c16-8ce6-ab68-c246592d22fa");
+	}
+
+	#[test]
+	fn test_key_is_a_valid_uuid() {
+		let match_id = match_uuid(&create_match("monitor", B256::with_last_byte(1)));
+		let key = derive_idempotency_key(&match_id, "webhook").to_string();
+		assert_eq!(Uuid::parse_str(&key).unwrap().get_version_num(), 8);
+	}
+}
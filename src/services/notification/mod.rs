@@ -5,12 +5,19 @@
 
 use async_trait::async_trait;
 
+use lettre::transport::stub::AsyncStubTransport;
 use std::{collections::HashMap, sync::Arc};
+use uuid::Uuid;
 
+mod capabilities;
+mod egress;
 mod email;
 mod error;
+mod idempotency;
 pub mod payload_builder;
+mod payload_size;
 mod pool;
+mod preview;
 mod script;
 mod template_formatter;
 mod webhook;
@@ -18,20 +25,70 @@ mod webhook;
 use crate::{
 	models::{
 		MonitorMatch, NotificationMessage, ScriptLanguage, Trigger, TriggerType, TriggerTypeConfig,
+		WebhookFormat,
 	},
-	utils::{normalize_string, RetryConfig},
+	utils::{build_info, normalize_string, RetryConfig},
 };
 
+pub use capabilities::{ChannelCapabilities, UnsupportedOption};
+pub use egress::{
+	EgressControls, EgressMiddleware, EgressPolicy, EgressRetryStrategy, EgressViolation,
+	HostResolver, IpRange, SystemResolver, NOTIFICATION_DNS_PINNING_ENV,
+	NOTIFICATION_EGRESS_ALLOW_ENV, NOTIFICATION_EGRESS_DENY_ENV, NOTIFICATION_EGRESS_HARDENING_ENV,
+};
 pub use email::{EmailContent, EmailNotifier, SmtpConfig};
 pub use error::NotificationError;
+pub use idempotency::{
+	derive_idempotency_key, match_uuid, IdempotencyKey, DEFAULT_IDEMPOTENCY_HEADER,
+	IDEMPOTENCY_KEY_FIELD,
+};
 pub use payload_builder::{
 	DiscordPayloadBuilder, GenericWebhookPayloadBuilder, SlackPayloadBuilder,
 	TelegramPayloadBuilder, WebhookPayloadBuilder,
 };
+pub use payload_size::{
+	MessageSize, PayloadSizes, DEFAULT_NEAR_LIMIT_OCCURRENCES, DEFAULT_NEAR_LIMIT_PERCENT,
+	NOTIFICATION_NEAR_LIMIT_OCCURRENCES_ENV, NOTIFICATION_NEAR_LIMIT_PERCENT_ENV,
+};
 pub use pool::NotificationClientPool;
+pub use preview::{NotificationPreview, PREVIEW_SECRET};
 pub use script::ScriptNotifier;
 pub use webhook::{WebhookConfig, WebhookNotifier};
 
+/// Field of generic webhook payloads holding the origin of the match, see [`MatchOrigin`]
+///
+/// [`MatchOrigin`]: crate::models::MatchOrigin
+pub const ORIGIN_FIELD: &str = "origin";
+
+/// Adds the fields of generic webhook payloads
+///
+/// The payload gets the idempotency key and the origin of the match, and the version of the
+/// monitor when given, i.e. when enabled with `INCLUDE_MONITOR_VERSION=true`.
+fn add_generic_webhook_fields(
+	payload: &mut serde_json::Value,
+	idempotency_key: &str,
+	monitor_match: &MonitorMatch,
+	monitor_version: Option<&str>,
+) {
+	let Some(fields) = payload.as_object_mut() else {
+		return;
+	};
+	fields.insert(
+		IDEMPOTENCY_KEY_FIELD.to_string(),
+		serde_json::Value::String(idempotency_key.to_string()),
+	);
+	fields.insert(
+		ORIGIN_FIELD.to_string(),
+		serde_json::to_value(monitor_match.origin()).unwrap_or(serde_json::Value::Null),
+	);
+	if let Some(version) = monitor_version {
+		fields.insert(
+			build_info::MONITOR_VERSION_FIELD.to_string(),
+			serde_json::Value::String(version.to_string()),
+		);
+	}
+}
+
 /// A container for all components needed to configure and send a webhook notification.
 struct WebhookComponents {
 	config: WebhookConfig,
@@ -67,6 +124,7 @@ impl AsWebhookComponents for TriggerTypeConfig {
 				method,
 				secret,
 				headers,
+				format,
 				..
 			} => (
 				url.as_ref().to_string(),
@@ -74,7 +132,10 @@ impl AsWebhookComponents for TriggerTypeConfig {
 				method.clone(),
 				secret.as_ref().map(|s| s.as_ref().to_string()),
 				headers.clone(),
-				Box::new(GenericWebhookPayloadBuilder),
+				match format.unwrap_or_default() {
+					WebhookFormat::Json => Box::new(GenericWebhookPayloadBuilder),
+					WebhookFormat::SlackCompatible => Box::new(SlackPayloadBuilder),
+				},
 			),
 			TriggerTypeConfig::Discord {
 				discord_url,
@@ -178,16 +239,28 @@ pub trait ScriptExecutor {
 pub struct NotificationService {
 	/// Client pool for managing notification clients (HTTP, SMTP)
 	client_pool: Arc<NotificationClientPool>,
+	/// Size accounting of the delivered messages
+	payload_sizes: Arc<PayloadSizes>,
 }
 
 impl NotificationService {
 	/// Creates a new notification service instance
+	///
+	/// Message sizes are accounted with the thresholds of the environment, see
+	/// [`PayloadSizes::from_env`].
 	pub fn new() -> Self {
 		NotificationService {
 			client_pool: Arc::new(NotificationClientPool::new()),
+			payload_sizes: Arc::new(PayloadSizes::from_env()),
 		}
 	}
 
+	/// Replaces the size accounting of the delivered messages
+	pub fn with_payload_sizes(mut self, payload_sizes: Arc<PayloadSizes>) -> Self {
+		self.payload_sizes = payload_sizes;
+		self
+	}
+
 	/// Executes a notification based on the trigger configuration
 	///
 	/// # Arguments
@@ -205,6 +278,39 @@ impl NotificationService {
 		variables: &HashMap<String, String>,
 		monitor_match: &MonitorMatch,
 		trigger_scripts: &HashMap<String, (ScriptLanguage, String)>,
+	) -> Result<(), NotificationError> {
+		self.execute_with_match_id(
+			trigger,
+			variables,
+			&match_uuid(monitor_match),
+			monitor_match,
+			trigger_scripts,
+		)
+		.await
+	}
+
+	/// Executes a notification for a match whose identifier was already derived
+	///
+	/// Used when a match is delivered to several triggers, so that the identifier is derived
+	/// once per match rather than once per trigger.
+	///
+	/// # Arguments
+	/// * `trigger` - Trigger containing the notification type and parameters
+	/// * `variables` - Variables to substitute in message templates
+	/// * `match_id` - Identifier of the match, see [`match_uuid`]
+	/// * `monitor_match` - Monitor match to send (needed for custom script trigger)
+	/// * `trigger_scripts` - Contains the script content to execute (needed for custom script
+	///   trigger)
+	///
+	/// # Returns
+	/// * `Result<(), NotificationError>` - Success or error
+	pub async fn execute_with_match_id(
+		&self,
+		trigger: &Trigger,
+		variables: &HashMap<String, String>,
+		match_id: &Uuid,
+		monitor_match: &MonitorMatch,
+		trigger_scripts: &HashMap<String, (ScriptLanguage, String)>,
 	) -> Result<(), NotificationError> {
 		match &trigger.trigger_type {
 			// Match Webhook-based triggers
@@ -212,32 +318,11 @@ impl NotificationService {
 			| TriggerType::Discord
 			| TriggerType::Webhook
 			| TriggerType::Telegram => {
-				// Use the Webhookable trait to get config, retry policy and payload builder
-				let components = trigger.config.as_webhook_components()?;
-
-				// Get or create the HTTP client from the pool based on the retry policy
-				let http_client = self
-					.client_pool
-					.get_or_create_http_client(&components.retry_policy)
-					.await
-					.map_err(|e| {
-						NotificationError::execution_error(
-							"Failed to get or create HTTP client from pool".to_string(),
-							Some(e.into()),
-							None,
-						)
-					})?;
-
-				// Build the payload
-				let payload = components.builder.build_payload(
-					&components.config.title,
-					&components.config.body_template,
-					variables,
-				);
-
-				// Create the notifier
-				let notifier = WebhookNotifier::new(components.config, http_client)?;
-
+				let (notifier, payload, size) = self
+					.webhook_notification(trigger, variables, match_id, monitor_match)
+					.await?;
+				self.payload_sizes
+					.record(trigger.trigger_type.as_str(), &trigger.name, size);
 				notifier.notify_json(&payload).await?;
 			}
 			TriggerType::Email => {
@@ -278,16 +363,14 @@ impl NotificationService {
 					})?;
 
 				let notifier = EmailNotifier::from_config(&trigger.config, smtp_client)?;
-				let message = EmailNotifier::format_message(notifier.body_template(), variables);
-				notifier.notify(&message).await?;
+				let message = notifier.render_message(variables);
+				notifier
+					.notify_in_thread(&message, notifier.thread_id(variables).as_deref())
+					.await?;
 			}
 			TriggerType::Script => {
 				let notifier = ScriptNotifier::from_config(&trigger.config)?;
-				let monitor_name = match monitor_match {
-					MonitorMatch::EVM(evm_match) => &evm_match.monitor.name,
-					MonitorMatch::Stellar(stellar_match) => &stellar_match.monitor.name,
-					MonitorMatch::Midnight(midnight_match) => &midnight_match.monitor.name,
-				};
+				let monitor_name = &monitor_match.monitor().name;
 				let script_path = match &trigger.config {
 					TriggerTypeConfig::Script { script_path, .. } => script_path,
 					_ => {
@@ -325,6 +408,185 @@ impl NotificationService {
 		}
 		Ok(())
 	}
+
+	/// Builds the notifier and payload delivering a match to a webhook-based trigger
+	///
+	/// # Arguments
+	/// * `trigger` - Slack, Discord, Telegram or webhook trigger
+	/// * `variables` - Variables to substitute in message templates
+	/// * `match_id` - Identifier of the match, see [`match_uuid`]
+	/// * `monitor_match` - Monitor match to send
+	///
+	/// # Returns
+	/// * `Result<(WebhookNotifier, serde_json::Value, MessageSize), NotificationError>` -
+	///   Notifier, payload and size of its message
+	async fn webhook_notification(
+		&self,
+		trigger: &Trigger,
+		variables: &HashMap<String, String>,
+		match_id: &Uuid,
+		monitor_match: &MonitorMatch,
+	) -> Result<(WebhookNotifier, serde_json::Value, MessageSize), NotificationError> {
+		// Use the Webhookable trait to get config, retry policy and payload builder
+		let components = trigger.config.as_webhook_components()?;
+
+		// Get or create the HTTP client from the pool based on the retry policy
+		let http_client = self
+			.client_pool
+			.get_or_create_http_client(&components.retry_policy)
+			.await
+			.map_err(|e| {
+				NotificationError::execution_error(
+					"Failed to get or create HTTP client from pool".to_string(),
+					Some(e.into()),
+					None,
+				)
+			})?;
+
+		// Derive the idempotency key, which stays the same across retries and restarts
+		let idempotency_key = IdempotencyKey {
+			header: match &trigger.config {
+				TriggerTypeConfig::Webhook {
+					idempotency_header: Some(header),
+					..
+				} => header.clone(),
+				_ => DEFAULT_IDEMPOTENCY_HEADER.to_string(),
+			},
+			key: derive_idempotency_key(match_id, &trigger.name).to_string(),
+		};
+
+		// Build the payload
+		let (mut payload, size) = components.builder.build_sized_payload(
+			&components.config.title,
+			&components.config.body_template,
+			variables,
+		);
+
+		// Generic webhook receivers also get the key and the origin of the match in the
+		// body, so that they can tell historical data apart. Receivers compatible with
+		// another channel get that channel's body unchanged.
+		if matches!(
+			trigger.config,
+			TriggerTypeConfig::Webhook {
+				format: None | Some(WebhookFormat::Json),
+				..
+			}
+		) {
+			add_generic_webhook_fields(
+				&mut payload,
+				&idempotency_key.key,
+				monitor_match,
+				build_info::reported_monitor_version(),
+			);
+		}
+
+		// Create the notifier
+		let notifier = WebhookNotifier::new(components.config, http_client)?
+			.with_idempotency_key(idempotency_key);
+		Ok((notifier, payload, size))
+	}
+
+	/// Renders the payload a trigger would deliver for a match, without sending it
+	///
+	/// Payloads go through the same builders, truncation and request construction as
+	/// deliveries. Webhook payloads are signed with [`PREVIEW_SECRET`] instead of the trigger's
+	/// secret, and URLs are left out since Slack, Discord and Telegram URLs embed credentials.
+	/// Script triggers are previewed with the input the script is given.
+	///
+	/// # Arguments
+	/// * `trigger` - Trigger containing the notification type and parameters
+	/// * `variables` - Variables to substitute in message templates
+	/// * `match_id` - Identifier of the match, see [`match_uuid`]
+	/// * `monitor_match` - Monitor match to send
+	/// * `timestamp` - Time the request is signed at, in milliseconds since the epoch
+	///
+	/// # Returns
+	/// * `Result<NotificationPreview, NotificationError>` - Preview or error
+	pub async fn preview(
+		&self,
+		trigger: &Trigger,
+		variables: &HashMap<String, String>,
+		match_id: &Uuid,
+		monitor_match: &MonitorMatch,
+		timestamp: i64,
+	) -> Result<NotificationPreview, NotificationError> {
+		let mut preview = NotificationPreview {
+			trigger: trigger.name.clone(),
+			trigger_type: trigger.trigger_type.clone(),
+			method: None,
+			headers: Vec::new(),
+			body: String::new(),
+		};
+		match &trigger.trigger_type {
+			TriggerType::Slack
+			| TriggerType::Discord
+			| TriggerType::Webhook
+			| TriggerType::Telegram => {
+				let (mut notifier, payload, _) = self
+					.webhook_notification(trigger, variables, match_id, monitor_match)
+					.await?;
+				if notifier.secret.is_some() {
+					notifier.secret = Some(PREVIEW_SECRET.to_string());
+				}
+				let (_, method, headers) = notifier.build_request(&payload, timestamp)?;
+				let mut headers: Vec<_> = headers
+					.iter()
+					.map(|(name, value)| {
+						(
+							name.to_string(),
+							String::from_utf8_lossy(value.as_bytes()).into_owned(),
+						)
+					})
+					.collect();
+				headers.sort();
+				preview.method = Some(method.to_string());
+				preview.headers = headers;
+				preview.body = serde_json::to_string_pretty(&payload).map_err(|e| {
+					NotificationError::internal_error(
+						format!("Failed to serialize payload: {}", e),
+						Some(e.into()),
+						None,
+					)
+				})?;
+			}
+			TriggerType::Email => {
+				let notifier = EmailNotifier::with_transport(
+					EmailContent::from_config(&trigger.config)?,
+					AsyncStubTransport::new_ok(),
+					RetryConfig::default(),
+				);
+				let message = notifier.render_message(variables);
+				let email = notifier
+					.build_email(&message, notifier.thread_id(variables).as_deref())
+					.await?;
+				preview.body = String::from_utf8_lossy(&email.formatted()).into_owned();
+			}
+			TriggerType::Script => {
+				let arguments = match &trigger.config {
+					TriggerTypeConfig::Script { arguments, .. } => arguments,
+					_ => {
+						return Err(NotificationError::config_error(
+							"Invalid script configuration".to_string(),
+							None,
+							None,
+						));
+					}
+				};
+				let input = serde_json::json!({
+					"monitor_match": monitor_match,
+					"args": arguments,
+				});
+				preview.body = serde_json::to_string_pretty(&input).map_err(|e| {
+					NotificationError::internal_error(
+						format!("Failed to serialize script input: {}", e),
+						Some(e.into()),
+						None,
+					)
+				})?;
+			}
+		}
+		Ok(preview)
+	}
 }
 
 impl Default for NotificationService {
@@ -389,11 +651,36 @@ mod tests {
 				functions: vec![],
 				events: vec![],
 				transactions: vec![],
+				sender_activity: None,
+				baseline: None,
+				network_metrics: None,
 			},
 			matched_on_args: None,
+			sender_activity: None,
+			baseline: None,
+			network_metrics: None,
+			timing: None,
+			origin: Default::default(),
 		}))
 	}
 
+	#[test]
+	fn test_generic_webhook_fields() {
+		let monitor_match = create_mock_monitor_match();
+		let build = || serde_json::json!({ "title": "Alert", "body": "Body" });
+
+		let mut payload = build();
+		add_generic_webhook_fields(&mut payload, "key", &monitor_match, None);
+		assert_eq!(payload[IDEMPOTENCY_KEY_FIELD], "key");
+		assert_eq!(payload[ORIGIN_FIELD]["type"], "live");
+		assert!(payload.get(build_info::MONITOR_VERSION_FIELD).is_none());
+
+		let mut payload = build();
+		add_generic_webhook_fields(&mut payload, "key", &monitor_match, Some("1.1.0+abc"));
+		assert_eq!(payload[build_info::MONITOR_VERSION_FIELD], "1.1.0+abc");
+		assert_eq!(payload["title"], "Alert");
+	}
+
 	#[tokio::test]
 	async fn test_slack_notification_invalid_config() {
 		let service = NotificationService::new();
@@ -584,6 +871,9 @@ mod tests {
 			message: NotificationMessage {
 				title: title.to_string(),
 				body: message.to_string(),
+				format: None,
+				thread_key: None,
+				attachments: None,
 			},
 			retry_policy: RetryConfig::default(),
 		};
@@ -622,6 +912,9 @@ mod tests {
 			message: NotificationMessage {
 				title: title.to_string(),
 				body: message.to_string(),
+				format: None,
+				thread_key: None,
+				attachments: None,
 			},
 			retry_policy: RetryConfig::default(),
 		};
@@ -659,6 +952,9 @@ mod tests {
 			message: NotificationMessage {
 				title: title.to_string(),
 				body: message.to_string(),
+				format: None,
+				thread_key: None,
+				attachments: None,
 			},
 			retry_policy: RetryConfig::default(),
 		};
@@ -691,6 +987,9 @@ mod tests {
 			message: NotificationMessage {
 				title: title.to_string(),
 				body: body_template.to_string(),
+				format: None,
+				thread_key: None,
+				attachments: None,
 			},
 			method: Some("PUT".to_string()),
 			secret: Some(SecretValue::Plain(SecretString::new(
@@ -698,6 +997,8 @@ mod tests {
 			))),
 			headers: Some([("X-Custom".to_string(), "Value".to_string())].into()),
 			retry_policy: RetryConfig::default(),
+			idempotency_header: None,
+			format: None,
 		};
 
 		let components = webhook_config.as_webhook_components().unwrap();
@@ -719,4 +1020,197 @@ mod tests {
 		assert!(payload.get("title").is_some());
 		assert!(payload.get("body").is_some());
 	}
+
+	fn preview_variables() -> HashMap<String, String> {
+		HashMap::from([
+			("name".to_string(), "Transfer".to_string()),
+			("value".to_string(), "42".to_string()),
+		])
+	}
+
+	#[tokio::test]
+	async fn test_preview_slack_payload() {
+		let trigger = TriggerBuilder::new()
+			.name("test_slack")
+			.slack("https://hooks.slack.com/services/A/B/C")
+			.message("Alert ${name}", "Value ${value}")
+			.build();
+
+		let preview = NotificationService::new()
+			.preview(
+				&trigger,
+				&preview_variables(),
+				&Uuid::nil(),
+				&create_mock_monitor_match(),
+				0,
+			)
+			.await
+			.unwrap();
+
+		assert_eq!(preview.trigger_type, TriggerType::Slack);
+		assert_eq!(preview.method.as_deref(), Some("POST"));
+		assert_eq!(
+			preview.headers,
+			vec![
+				("content-type".to_string(), "application/json".to_string()),
+				(
+					DEFAULT_IDEMPOTENCY_HEADER.to_lowercase(),
+					derive_idempotency_key(&Uuid::nil(), "test_slack").to_string()
+				),
+			]
+		);
+		assert_eq!(
+			serde_json::from_str::<serde_json::Value>(&preview.body).unwrap(),
+			serde_json::json!({
+				"blocks": [{
+					"type": "section",
+					"text": { "type": "mrkdwn", "text": "*Alert Transfer*\n\nValue 42" }
+				}]
+			})
+		);
+		assert!(!preview.to_string().contains("hooks.slack.com"));
+	}
+
+	#[tokio::test]
+	async fn test_preview_discord_payload() {
+		let trigger = TriggerBuilder::new()
+			.name("test_discord")
+			.discord("https://discord.com/api/webhooks/1/token")
+			.message("Alert ${name}", "Value ${value}")
+			.build();
+
+		let preview = NotificationService::new()
+			.preview(
+				&trigger,
+				&preview_variables(),
+				&Uuid::nil(),
+				&create_mock_monitor_match(),
+				0,
+			)
+			.await
+			.unwrap();
+
+		assert_eq!(
+			serde_json::from_str::<serde_json::Value>(&preview.body).unwrap(),
+			serde_json::json!({ "content": "*Alert Transfer*\n\nValue 42" })
+		);
+	}
+
+	#[tokio::test]
+	async fn test_preview_signs_webhook_payload_with_preview_secret() {
+		use hmac::{Hmac, Mac};
+
+		let trigger = TriggerBuilder::new()
+			.name("test_webhook")
+			.webhook("https://example.com/webhook")
+			.webhook_secret(SecretValue::Plain(SecretString::new(
+				"real-secret".to_string(),
+			)))
+			.message("Alert ${name}", "Value ${value}")
+			.build();
+
+		let preview = NotificationService::new()
+			.preview(
+				&trigger,
+				&preview_variables(),
+				&Uuid::nil(),
+				&create_mock_monitor_match(),
+				1_700_000_000_000,
+			)
+			.await
+			.unwrap();
+
+		let payload: serde_json::Value = serde_json::from_str(&preview.body).unwrap();
+		assert_eq!(payload["title"], "Alert Transfer");
+		assert_eq!(payload["body"], "Value 42");
+		assert_eq!(payload[ORIGIN_FIELD]["type"], "live");
+
+		let mut mac = Hmac::<sha2::Sha256>::new_from_slice(PREVIEW_SECRET.as_bytes()).unwrap();
+		mac.update(format!("{}1700000000000", serde_json::to_string(&payload).unwrap()).as_bytes());
+		let signature = hex::encode(mac.finalize().into_bytes());
+		let header = |name: &str| {
+			preview
+				.headers
+				.iter()
+				.find(|(header, _)| header == name)
+				.map(|(_, value)| value.clone())
+		};
+		assert_eq!(header("x-signature"), Some(signature));
+		assert_eq!(header("x-timestamp"), Some("1700000000000".to_string()));
+	}
+
+	#[tokio::test]
+	async fn test_preview_email_message() {
+		let trigger = TriggerBuilder::new()
+			.name("test_email")
+			.email(
+				"smtp.example.com",
+				"user",
+				"pass",
+				"sender@example.com",
+				vec!["recipient@example.com"],
+			)
+			.message("Alert ${name}", "Value **${value}**")
+			.build();
+
+		let preview = NotificationService::new()
+			.preview(
+				&trigger,
+				&preview_variables(),
+				&Uuid::nil(),
+				&create_mock_monitor_match(),
+				0,
+			)
+			.await
+			.unwrap();
+
+		assert!(preview.method.is_none());
+		assert!(preview.headers.is_empty());
+		assert!(preview.body.contains("Subject: Alert ${name}"));
+		assert!(preview.body.contains("To: recipient@example.com"));
+		assert!(preview.body.contains("Content-Type: text/html"));
+		assert!(preview.body.contains("<strong>42</strong>"));
+	}
+
+	#[tokio::test]
+	async fn test_preview_script_input() {
+		let trigger = TriggerBuilder::new()
+			.name("test_script")
+			.script("scripts/notify.py", ScriptLanguage::Python)
+			.script_arguments(vec!["--verbose".to_string()])
+			.build();
+
+		let preview = NotificationService::new()
+			.preview(
+				&trigger,
+				&HashMap::new(),
+				&Uuid::nil(),
+				&create_mock_monitor_match(),
+				0,
+			)
+			.await
+			.unwrap();
+
+		let input: serde_json::Value = serde_json::from_str(&preview.body).unwrap();
+		assert_eq!(input["args"], serde_json::json!(["--verbose"]));
+		assert_eq!(input["monitor_match"]["EVM"]["monitor"]["name"], "test");
+	}
+
+	#[test]
+	fn test_preview_display() {
+		let preview = NotificationPreview {
+			trigger: "large_transfer_webhook".to_string(),
+			trigger_type: TriggerType::Webhook,
+			method: Some("POST".to_string()),
+			headers: vec![("content-type".to_string(), "application/json".to_string())],
+			body: "{}".to_string(),
+		};
+
+		assert_eq!(
+			preview.to_string(),
+			"==> large_transfer_webhook (webhook) <==\nPOST\ncontent-type: \
+			 application/json\n\n{}\n"
+		);
+		assert_eq!(preview.file_name(), "large_transfer_webhook.txt");
+	}
 }
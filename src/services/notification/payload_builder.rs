@@ -6,7 +6,7 @@ use regex::Regex;
 use serde_json::json;
 use std::collections::HashMap;
 
-use super::template_formatter;
+use super::{template_formatter, ChannelCapabilities, MessageSize};
 
 /// Trait for building webhook payloads.
 pub trait WebhookPayloadBuilder: Send + Sync {
@@ -26,7 +26,30 @@ pub trait WebhookPayloadBuilder: Send + Sync {
 		title: &str,
 		body_template: &str,
 		variables: &HashMap<String, String>,
-	) -> serde_json::Value;
+	) -> serde_json::Value {
+		self.build_sized_payload(title, body_template, variables).0
+	}
+
+	/// Builds a webhook payload, along with the size of its message before truncation.
+	///
+	/// # Arguments
+	///
+	/// * `title` - The raw title of the message.
+	/// * `body_template` - The message body template with variables like `${...}`.
+	/// * `variables` - The map of variables to substitute into the template.
+	///
+	/// # Returns
+	///
+	/// The payload and the size of its message against the limit of the channel.
+	fn build_sized_payload(
+		&self,
+		title: &str,
+		body_template: &str,
+		variables: &HashMap<String, String>,
+	) -> (serde_json::Value, MessageSize);
+
+	/// Returns the features supported by the channel the payloads are sent to.
+	fn capabilities(&self) -> ChannelCapabilities;
 }
 
 /// Formats a message by substituting variables in the template.
@@ -37,17 +60,33 @@ pub fn format_template(template: &str, variables: &HashMap<String, String>) -> S
 /// A payload builder for Slack.
 pub struct SlackPayloadBuilder;
 
+impl SlackPayloadBuilder {
+	/// Slack limits the text of a section block to 3000 characters.
+	pub const CAPABILITIES: ChannelCapabilities = ChannelCapabilities {
+		supports_html: false,
+		supports_attachments: false,
+		supports_threading: false,
+		max_payload_bytes: Some(3000),
+		supports_idempotency_header: false,
+	};
+}
+
 impl WebhookPayloadBuilder for SlackPayloadBuilder {
-	fn build_payload(
+	fn build_sized_payload(
 		&self,
 		title: &str,
 		body_template: &str,
 		variables: &HashMap<String, String>,
-	) -> serde_json::Value {
+	) -> (serde_json::Value, MessageSize) {
 		let formatted_title = format_template(title, variables);
 		let formatted_message = format_template(body_template, variables);
-		let full_message = format!("*{}*\n\n{}", formatted_title, formatted_message);
-		json!({
+		let message = format!("*{}*\n\n{}", formatted_title, formatted_message);
+		let size = MessageSize {
+			rendered_bytes: message.len(),
+			max_bytes: self.capabilities().max_payload_bytes,
+		};
+		let full_message = template_formatter::truncate_message(&message, size.max_bytes);
+		let payload = json!({
 			"blocks": [
 				{
 					"type": "section",
@@ -57,26 +96,52 @@ impl WebhookPayloadBuilder for SlackPayloadBuilder {
 					}
 				}
 			]
-		})
+		});
+		(payload, size)
+	}
+
+	fn capabilities(&self) -> ChannelCapabilities {
+		Self::CAPABILITIES
 	}
 }
 
 /// A payload builder for Discord.
 pub struct DiscordPayloadBuilder;
 
+impl DiscordPayloadBuilder {
+	/// Discord limits the content of a message to 2000 characters.
+	pub const CAPABILITIES: ChannelCapabilities = ChannelCapabilities {
+		supports_html: false,
+		supports_attachments: false,
+		supports_threading: false,
+		max_payload_bytes: Some(2000),
+		supports_idempotency_header: false,
+	};
+}
+
 impl WebhookPayloadBuilder for DiscordPayloadBuilder {
-	fn build_payload(
+	fn build_sized_payload(
 		&self,
 		title: &str,
 		body_template: &str,
 		variables: &HashMap<String, String>,
-	) -> serde_json::Value {
+	) -> (serde_json::Value, MessageSize) {
 		let formatted_title = format_template(title, variables);
 		let formatted_message = format_template(body_template, variables);
-		let full_message = format!("*{}*\n\n{}", formatted_title, formatted_message);
-		json!({
+		let message = format!("*{}*\n\n{}", formatted_title, formatted_message);
+		let size = MessageSize {
+			rendered_bytes: message.len(),
+			max_bytes: self.capabilities().max_payload_bytes,
+		};
+		let full_message = template_formatter::truncate_message(&message, size.max_bytes);
+		let payload = json!({
 			"content": full_message
-		})
+		});
+		(payload, size)
+	}
+
+	fn capabilities(&self) -> ChannelCapabilities {
+		Self::CAPABILITIES
 	}
 }
 
@@ -87,6 +152,15 @@ pub struct TelegramPayloadBuilder {
 }
 
 impl TelegramPayloadBuilder {
+	/// Telegram limits the text of a message to 4096 characters.
+	pub const CAPABILITIES: ChannelCapabilities = ChannelCapabilities {
+		supports_html: false,
+		supports_attachments: false,
+		supports_threading: false,
+		max_payload_bytes: Some(4096),
+		supports_idempotency_header: false,
+	};
+
 	/// Escape a full MarkdownV2 message, preserving entities and
 	/// escaping *all* special chars inside link URLs too.
 	fn escape_markdown_v2(text: &str) -> String {
@@ -152,46 +226,83 @@ impl TelegramPayloadBuilder {
 }
 
 impl WebhookPayloadBuilder for TelegramPayloadBuilder {
-	fn build_payload(
+	fn build_sized_payload(
 		&self,
 		title: &str,
 		body_template: &str,
 		variables: &HashMap<String, String>,
-	) -> serde_json::Value {
+	) -> (serde_json::Value, MessageSize) {
 		// First, substitute variables.
 		let formatted_title = format_template(title, variables);
 		let formatted_message = format_template(body_template, variables);
 
+		// Telegram counts the length of the text once the entities are parsed, so the message is
+		// truncated before escaping to keep the markup intact. The title is kept whole.
+		let size = MessageSize {
+			rendered_bytes: formatted_title.len() + 3 + formatted_message.len(),
+			max_bytes: self.capabilities().max_payload_bytes,
+		};
+		let formatted_message = template_formatter::truncate_message(
+			&formatted_message,
+			size.max_bytes
+				.map(|max_bytes| max_bytes.saturating_sub(formatted_title.len() + 3)),
+		);
+
 		// Then, escape both the title and the formatted message for Telegram MarkdownV2.
 		let escaped_title = Self::escape_markdown_v2(&formatted_title);
 		let escaped_message = Self::escape_markdown_v2(&formatted_message);
 
 		let full_message = format!("*{}* \n\n{}", escaped_title, escaped_message);
-		json!({
+		let payload = json!({
 			"chat_id": self.chat_id,
 			"text": full_message,
 			"parse_mode": "MarkdownV2",
 			"disable_web_page_preview": self.disable_web_preview
-		})
+		});
+		(payload, size)
+	}
+
+	fn capabilities(&self) -> ChannelCapabilities {
+		Self::CAPABILITIES
 	}
 }
 
 /// A payload builder for generic webhooks.
 pub struct GenericWebhookPayloadBuilder;
 
+impl GenericWebhookPayloadBuilder {
+	/// Generic webhooks receive the message as is, with a configurable idempotency header.
+	pub const CAPABILITIES: ChannelCapabilities = ChannelCapabilities {
+		supports_html: false,
+		supports_attachments: false,
+		supports_threading: false,
+		max_payload_bytes: None,
+		supports_idempotency_header: true,
+	};
+}
+
 impl WebhookPayloadBuilder for GenericWebhookPayloadBuilder {
-	fn build_payload(
+	fn build_sized_payload(
 		&self,
 		title: &str,
 		body_template: &str,
 		variables: &HashMap<String, String>,
-	) -> serde_json::Value {
+	) -> (serde_json::Value, MessageSize) {
 		let formatted_title = format_template(title, variables);
 		let formatted_message = format_template(body_template, variables);
-		json!({
+		let size = MessageSize {
+			rendered_bytes: formatted_title.len() + formatted_message.len(),
+			max_bytes: self.capabilities().max_payload_bytes,
+		};
+		let payload = json!({
 			"title": formatted_title,
 			"body": formatted_message
-		})
+		});
+		(payload, size)
+	}
+
+	fn capabilities(&self) -> ChannelCapabilities {
+		Self::CAPABILITIES
 	}
 }
 
@@ -806,4 +917,51 @@ mod tests {
 		assert!(!result_str.contains("abc")); // Should not contain invalid index
 		assert!(!result_str.contains("-1")); // Should not contain negative index
 	}
+
+	#[test]
+	fn test_payloads_are_truncated_to_the_channel_capabilities() {
+		let body = "x".repeat(10_000);
+		let variables = HashMap::new();
+
+		let slack = SlackPayloadBuilder.build_payload("Alert", &body, &variables);
+		let text = slack["blocks"][0]["text"]["text"].as_str().unwrap();
+		assert_eq!(
+			Some(text.len()),
+			SlackPayloadBuilder::CAPABILITIES.max_payload_bytes
+		);
+		assert!(text.ends_with(template_formatter::TRUNCATION_MARKER));
+
+		let discord = DiscordPayloadBuilder.build_payload("Alert", &body, &variables);
+		let content = discord["content"].as_str().unwrap();
+		assert_eq!(
+			Some(content.len()),
+			DiscordPayloadBuilder::CAPABILITIES.max_payload_bytes
+		);
+		assert!(content.ends_with(template_formatter::TRUNCATION_MARKER));
+
+		let telegram = TelegramPayloadBuilder {
+			chat_id: "123".to_string(),
+			disable_web_preview: false,
+		};
+		let text = telegram.build_payload("Alert", &body, &variables)["text"]
+			.as_str()
+			.unwrap()
+			.to_string();
+		// The entity markers of the title are not counted by Telegram
+		assert_eq!(
+			Some(text.len() - 2),
+			TelegramPayloadBuilder::CAPABILITIES.max_payload_bytes
+		);
+
+		// Generic webhooks do not limit the size of messages
+		let webhook = GenericWebhookPayloadBuilder.build_payload("Alert", &body, &variables);
+		assert_eq!(webhook["body"], body);
+	}
+
+	#[test]
+	fn test_payloads_within_the_limit_are_not_truncated() {
+		let payload =
+			DiscordPayloadBuilder.build_payload("Alert", "Short message", &HashMap::new());
+		assert_eq!(payload["content"], "*Alert*\n\nShort message");
+	}
 }
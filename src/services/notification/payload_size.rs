@@ -0,0 +1,217 @@
+//! Size accounting of notification payloads.
+//!
+//! Channels limit the size of the messages they accept, and the payload builders truncate the
+//! messages exceeding the limit declared by the channel's capabilities. Truncation is only
+//! noticed once a mangled alert arrives, so the size of each rendered message is recorded in
+//! the `notification_payload_bytes` histogram, and each truncation is counted by the
+//! `notification_truncations_total` metric.
+//!
+//! A trigger whose messages keep coming close to the limit has a template that needs fixing:
+//! after `NOTIFICATION_NEAR_LIMIT_OCCURRENCES` messages in a row above
+//! `NOTIFICATION_NEAR_LIMIT_PERCENT` of the limit, a warning is logged.
+
+use std::{collections::HashMap, sync::Mutex};
+
+use crate::utils::metrics::{NOTIFICATION_PAYLOAD_BYTES, NOTIFICATION_TRUNCATIONS};
+
+/// Environment variable setting the share of the limit, in percent, above which a message is
+/// near the limit
+pub const NOTIFICATION_NEAR_LIMIT_PERCENT_ENV: &str = "NOTIFICATION_NEAR_LIMIT_PERCENT";
+
+/// Environment variable setting the number of messages in a row near the limit after which a
+/// warning is logged
+pub const NOTIFICATION_NEAR_LIMIT_OCCURRENCES_ENV: &str = "NOTIFICATION_NEAR_LIMIT_OCCURRENCES";
+
+/// Default share of the limit, in percent, above which a message is near the limit
+pub const DEFAULT_NEAR_LIMIT_PERCENT: u8 = 90;
+
+/// Default number of messages in a row near the limit after which a warning is logged
+pub const DEFAULT_NEAR_LIMIT_OCCURRENCES: u32 = 5;
+
+/// Section of the message truncated by the payload builders
+const TRUNCATED_SECTION: &str = "body";
+
+/// Size of a rendered message against the limit of its channel
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MessageSize {
+	/// Size of the message before truncation, in bytes
+	pub rendered_bytes: usize,
+	/// Size the channel limits the message to, in bytes, or None if it is not limited
+	pub max_bytes: Option<usize>,
+}
+
+impl MessageSize {
+	/// Returns whether the message was truncated to fit the limit
+	pub fn is_truncated(&self) -> bool {
+		self.max_bytes
+			.is_some_and(|max_bytes| self.rendered_bytes > max_bytes)
+	}
+}
+
+/// Records the size of notification messages and warns about triggers sustaining sizes near
+/// the limit of their channel
+#[derive(Debug)]
+pub struct PayloadSizes {
+	/// Share of the limit, in percent, above which a message is near the limit
+	near_limit_percent: u8,
+	/// Number of messages in a row near the limit after which a warning is logged
+	near_limit_occurrences: u32,
+	/// Number of messages in a row near the limit, by trigger
+	streaks: Mutex<HashMap<String, u32>>,
+}
+
+impl PayloadSizes {
+	/// Creates a payload size accounting with the given near-limit thresholds
+	///
+	/// # Arguments
+	/// * `near_limit_percent` - Share of the limit, in percent, above which a message is near
+	///   the limit
+	/// * `near_limit_occurrences` - Number of messages in a row near the limit after which a
+	///   warning is logged
+	pub fn new(near_limit_percent: u8, near_limit_occurrences: u32) -> Self {
+		Self {
+			near_limit_percent,
+			near_limit_occurrences: near_limit_occurrences.max(1),
+			streaks: Mutex::new(HashMap::new()),
+		}
+	}
+
+	/// Creates a payload size accounting with the thresholds set by
+	/// `NOTIFICATION_NEAR_LIMIT_PERCENT` and `NOTIFICATION_NEAR_LIMIT_OCCURRENCES`
+	pub fn from_env() -> Self {
+		let near_limit_percent = std::env::var(NOTIFICATION_NEAR_LIMIT_PERCENT_ENV)
+			.ok()
+			.and_then(|v| v.parse::<u8>().ok())
+			.filter(|v| (1..=100).contains(v))
+			.unwrap_or(DEFAULT_NEAR_LIMIT_PERCENT);
+		let near_limit_occurrences = std::env::var(NOTIFICATION_NEAR_LIMIT_OCCURRENCES_ENV)
+			.ok()
+			.and_then(|v| v.parse::<u32>().ok())
+			.filter(|v| *v > 0)
+			.unwrap_or(DEFAULT_NEAR_LIMIT_OCCURRENCES);
+		Self::new(near_limit_percent, near_limit_occurrences)
+	}
+
+	/// Records the size of a message rendered for a trigger
+	///
+	/// # Arguments
+	/// * `channel` - Channel the message is sent to
+	/// * `trigger` - Name of the trigger
+	/// * `size` - Size of the message against the limit of the channel
+	///
+	/// # Returns
+	/// * `bool` - Whether the message completed a run of messages near the limit, and a
+	///   warning was logged
+	pub fn record(&self, channel: &str, trigger: &str, size: MessageSize) -> bool {
+		NOTIFICATION_PAYLOAD_BYTES
+			.with_label_values(&[channel, trigger])
+			.observe(size.rendered_bytes as f64);
+		if size.is_truncated() {
+			NOTIFICATION_TRUNCATIONS
+				.with_label_values(&[channel, trigger, TRUNCATED_SECTION])
+				.inc();
+		}
+		let Some(max_bytes) = size.max_bytes else {
+			return false;
+		};
+
+		let near_limit =
+			size.rendered_bytes * 100 >= max_bytes * usize::from(self.near_limit_percent);
+		let mut streaks = self.streaks.lock().unwrap_or_else(|e| e.into_inner());
+		if !near_limit {
+			streaks.remove(trigger);
+			return false;
+		}
+		let streak = streaks.entry(trigger.to_string()).or_default();
+		*streak += 1;
+		if *streak < self.near_limit_occurrences {
+			return false;
+		}
+		*streak = 0;
+		tracing::warn!(
+			channel = %channel,
+			trigger = %trigger,
+			"The last {} messages of trigger '{}' reached {}% of the {} bytes the channel \
+			 accepts, its template needs to be shortened",
+			self.near_limit_occurrences,
+			trigger,
+			self.near_limit_percent,
+			max_bytes
+		);
+		true
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::services::notification::{
+		DiscordPayloadBuilder, SlackPayloadBuilder, WebhookPayloadBuilder,
+	};
+
+	/// Returns a body making a message of the given size once prefixed with the title
+	fn body_of(bytes: usize) -> String {
+		"x".repeat(bytes - "*Alert*\n\n".len())
+	}
+
+	#[test]
+	fn test_record_accounts_payloads_under_and_over_the_limit() {
+		let sizes = PayloadSizes::new(90, 3);
+		for (channel, builder) in [
+			("slack", &SlackPayloadBuilder as &dyn WebhookPayloadBuilder),
+			(
+				"discord",
+				&DiscordPayloadBuilder as &dyn WebhookPayloadBuilder,
+			),
+		] {
+			let trigger = format!("payload_size_{}", channel);
+			let max_bytes = builder.capabilities().max_payload_bytes.unwrap();
+			let histogram = NOTIFICATION_PAYLOAD_BYTES.with_label_values(&[channel, &trigger]);
+			let truncations =
+				NOTIFICATION_TRUNCATIONS.with_label_values(&[channel, &trigger, "body"]);
+
+			let (_, under) =
+				builder.build_sized_payload("Alert", &body_of(max_bytes), &HashMap::new());
+			assert_eq!(under.rendered_bytes, max_bytes);
+			assert!(!under.is_truncated());
+			sizes.record(channel, &trigger, under);
+			assert_eq!(histogram.get_sample_count(), 1);
+			assert_eq!(histogram.get_sample_sum(), max_bytes as f64);
+			assert_eq!(truncations.get(), 0);
+
+			let (_, over) =
+				builder.build_sized_payload("Alert", &body_of(max_bytes + 1), &HashMap::new());
+			assert!(over.is_truncated());
+			sizes.record(channel, &trigger, over);
+			assert_eq!(histogram.get_sample_count(), 2);
+			assert_eq!(truncations.get(), 1);
+		}
+	}
+
+	#[test]
+	fn test_record_warns_after_sustained_near_limit_payloads() {
+		let sizes = PayloadSizes::new(90, 3);
+		let size = |rendered_bytes| MessageSize {
+			rendered_bytes,
+			max_bytes: Some(2000),
+		};
+
+		assert!(!sizes.record("discord", "near_limit", size(1900)));
+		assert!(!sizes.record("discord", "near_limit", size(1800)));
+		// A message well under the limit starts the count over
+		assert!(!sizes.record("discord", "near_limit", size(100)));
+		assert!(!sizes.record("discord", "near_limit", size(1900)));
+		assert!(!sizes.record("discord", "near_limit", size(2500)));
+		assert!(sizes.record("discord", "near_limit", size(1950)));
+		assert!(!sizes.record("discord", "near_limit", size(1950)));
+
+		// Channels without a limit are never near it
+		let unlimited = MessageSize {
+			rendered_bytes: 1_000_000,
+			max_bytes: None,
+		};
+		for _ in 0..5 {
+			assert!(!sizes.record("webhook", "unlimited", unlimited));
+		}
+	}
+}
@@ -1,7 +1,7 @@
 use crate::services::blockchain::TransientErrorRetryStrategy;
-use crate::services::notification::SmtpConfig;
+use crate::services::notification::{EgressControls, EgressRetryStrategy, SmtpConfig};
 use crate::utils::client_storage::ClientStorage;
-use crate::utils::{create_retryable_http_client, RetryConfig};
+use crate::utils::{create_retryable_http_client, retryable_http_client_builder, RetryConfig};
 use lettre::Tokio1Executor;
 use lettre::{transport::smtp::authentication::Credentials, AsyncSmtpTransport};
 use reqwest::Client as ReqwestClient;
@@ -24,16 +24,27 @@ pub enum NotificationPoolError {
 /// Provides a thread-safe way to access and create HTTP and SMTP clients
 /// for sending notifications. It uses a `ClientStorage` to hold the clients,
 /// allowing for efficient reuse and management of HTTP and SMTP connections.
+/// HTTP clients apply the egress controls set by the environment, if any.
 pub struct NotificationClientPool {
 	http_clients: ClientStorage<ClientWithMiddleware>,
 	smtp_clients: ClientStorage<AsyncSmtpTransport<Tokio1Executor>>,
+	egress: Option<EgressControls>,
 }
 
 impl NotificationClientPool {
 	pub fn new() -> Self {
+		Self::with_egress(EgressControls::from_env())
+	}
+
+	/// Creates a pool whose HTTP clients apply the given egress controls
+	///
+	/// # Arguments
+	/// * `egress` - Egress controls of the HTTP clients, none to leave requests unchecked
+	pub fn with_egress(egress: Option<EgressControls>) -> Self {
 		Self {
 			http_clients: ClientStorage::new(),
 			smtp_clients: ClientStorage::new(),
+			egress,
 		}
 	}
 
@@ -82,18 +93,31 @@ impl NotificationClientPool {
 	) -> Result<Arc<ClientWithMiddleware>, NotificationPoolError> {
 		let key = format!("{:?}", retry_policy);
 		self.get_or_create_client(&key, &self.http_clients, || {
-			let base_client = ReqwestClient::builder()
+			let builder = ReqwestClient::builder()
 				.pool_max_idle_per_host(10)
 				.pool_idle_timeout(Some(Duration::from_secs(90)))
-				.connect_timeout(Duration::from_secs(10))
+				.connect_timeout(Duration::from_secs(10));
+			let Some(egress) = &self.egress else {
+				let base_client = builder
+					.build()
+					.map_err(|e| NotificationPoolError::HttpClientBuildError(e.to_string()))?;
+				return Ok(create_retryable_http_client(
+					retry_policy,
+					base_client,
+					Some(TransientErrorRetryStrategy),
+				));
+			};
+
+			// Addresses are checked on every attempt, and violations are not retried
+			let base_client = egress
+				.apply(builder)
 				.build()
 				.map_err(|e| NotificationPoolError::HttpClientBuildError(e.to_string()))?;
-
-			Ok(create_retryable_http_client(
-				retry_policy,
-				base_client,
-				Some(TransientErrorRetryStrategy),
-			))
+			Ok(
+				retryable_http_client_builder(retry_policy, base_client, Some(EgressRetryStrategy))
+					.with(egress.middleware())
+					.build(),
+			)
 		})
 		.await
 	}
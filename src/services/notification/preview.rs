@@ -0,0 +1,54 @@
+//! Notification payload previews.
+//!
+//! Describes the payload a trigger would deliver for a match, rendered through the same
+//! payload builders and request construction as deliveries, without sending it.
+
+use std::fmt;
+
+use crate::models::TriggerType;
+
+/// Secret the webhook payloads of previews are signed with, in place of the trigger's own
+pub const PREVIEW_SECRET: &str = "preview-secret";
+
+/// Payload a trigger would deliver for a match
+#[derive(Debug, Clone, PartialEq)]
+pub struct NotificationPreview {
+	/// Name of the trigger
+	pub trigger: String,
+	/// Channel of the trigger
+	pub trigger_type: TriggerType,
+	/// Method of the request, for webhook-based channels
+	pub method: Option<String>,
+	/// Headers of the request sorted by name, for webhook-based channels
+	pub headers: Vec<(String, String)>,
+	/// Body of the request, the MIME message of emails or the input of scripts
+	pub body: String,
+}
+
+impl NotificationPreview {
+	/// Returns the name of the file the preview is written to
+	pub fn file_name(&self) -> String {
+		format!("{}.txt", self.trigger)
+	}
+}
+
+impl fmt::Display for NotificationPreview {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		writeln!(
+			f,
+			"==> {} ({}) <==",
+			self.trigger,
+			self.trigger_type.as_str()
+		)?;
+		if let Some(method) = &self.method {
+			writeln!(f, "{}", method)?;
+		}
+		for (name, value) in &self.headers {
+			writeln!(f, "{}: {}", name, value)?;
+		}
+		if self.method.is_some() || !self.headers.is_empty() {
+			writeln!(f)?;
+		}
+		writeln!(f, "{}", self.body)
+	}
+}
@@ -2,7 +2,7 @@ use async_trait::async_trait;
 
 use crate::{
 	models::{MonitorMatch, ScriptLanguage, TriggerTypeConfig},
-	services::notification::{NotificationError, ScriptExecutor},
+	services::notification::{ChannelCapabilities, NotificationError, ScriptExecutor},
 	services::trigger::ScriptExecutorFactory,
 };
 
@@ -17,6 +17,15 @@ pub struct ScriptNotifier {
 }
 
 impl ScriptNotifier {
+	/// Scripts receive the match itself rather than a message
+	pub const CAPABILITIES: ChannelCapabilities = ChannelCapabilities {
+		supports_html: false,
+		supports_attachments: false,
+		supports_threading: false,
+		max_payload_bytes: None,
+		supports_idempotency_header: false,
+	};
+
 	/// Creates a Script notifier from a trigger configuration
 	pub fn from_config(config: &TriggerTypeConfig) -> Result<Self, NotificationError> {
 		if let TriggerTypeConfig::Script { .. } = config {
@@ -129,6 +138,11 @@ mod tests {
 			network_slug: "ethereum_mainnet".to_string(),
 			matched_on: MatchConditions::default(),
 			matched_on_args: None,
+			sender_activity: None,
+			baseline: None,
+			network_metrics: None,
+			timing: None,
+			origin: Default::default(),
 		}))
 	}
 
@@ -147,6 +161,9 @@ mod tests {
 			message: NotificationMessage {
 				title: "Test Slack".to_string(),
 				body: "This is a test message".to_string(),
+				format: None,
+				thread_key: None,
+				attachments: None,
 			},
 			retry_policy: Default::default(),
 		};
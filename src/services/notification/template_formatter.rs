@@ -114,6 +114,30 @@ pub fn build_match_reasons(variables: &HashMap<String, String>, prefix: &str) ->
 	Some(match_reasons)
 }
 
+/// Marker appended to truncated messages
+pub const TRUNCATION_MARKER: &str = "…";
+
+/// Truncates a message so that it fits in the given number of bytes
+/// The message is cut on a character boundary and ends with [`TRUNCATION_MARKER`] when shortened
+///
+/// # Arguments
+/// * `message` - The message to truncate
+/// * `max_bytes` - The maximum size of the message in bytes, or None if it is not limited
+///
+/// # Returns
+/// * `String` - The message, truncated if it exceeds the limit
+pub fn truncate_message(message: &str, max_bytes: Option<usize>) -> String {
+	let max_bytes = match max_bytes {
+		Some(max_bytes) if message.len() > max_bytes => max_bytes,
+		_ => return message.to_string(),
+	};
+	let mut end = max_bytes.saturating_sub(TRUNCATION_MARKER.len());
+	while !message.is_char_boundary(end) {
+		end -= 1;
+	}
+	format!("{}{}", &message[..end], TRUNCATION_MARKER)
+}
+
 #[cfg(test)]
 mod tests {
 	use super::*;
@@ -374,4 +398,24 @@ mod tests {
 		assert!(!result_str.contains("abc")); // Should not contain invalid index
 		assert!(!result_str.contains("-1")); // Should not contain negative index
 	}
+
+	#[test]
+	fn test_truncate_message_within_limit_is_unchanged() {
+		assert_eq!(truncate_message("short message", Some(13)), "short message");
+		assert_eq!(truncate_message("short message", None), "short message");
+	}
+
+	#[test]
+	fn test_truncate_message_fits_the_limit() {
+		let truncated = truncate_message("a long message to shorten", Some(10));
+		assert_eq!(truncated, "a long …");
+		assert!(truncated.len() <= 10);
+	}
+
+	#[test]
+	fn test_truncate_message_cuts_on_char_boundary() {
+		// Each 'é' is 2 bytes long, so 6 bytes of message leave room for 1 of them
+		let truncated = truncate_message("éééé", Some(6));
+		assert_eq!(truncated, "é…");
+	}
 }
@@ -13,7 +13,10 @@ use reqwest_middleware::ClientWithMiddleware;
 use sha2::Sha256;
 use std::{collections::HashMap, sync::Arc};
 
-use crate::{models::TriggerTypeConfig, services::notification::NotificationError};
+use crate::{
+	models::TriggerTypeConfig,
+	services::notification::{EgressViolation, IdempotencyKey, NotificationError},
+};
 
 /// HMAC SHA256 type alias
 type HmacSha256 = Hmac<Sha256>;
@@ -50,6 +53,8 @@ pub struct WebhookNotifier {
 	pub headers: Option<HashMap<String, String>>,
 	/// Payload fields to use for the webhook request
 	pub payload_fields: Option<HashMap<String, serde_json::Value>>,
+	/// Idempotency key sent with every attempt of the webhook request
+	pub idempotency_key: Option<IdempotencyKey>,
 }
 
 impl WebhookNotifier {
@@ -78,9 +83,22 @@ impl WebhookNotifier {
 			secret: config.secret,
 			headers: Some(headers),
 			payload_fields: config.payload_fields,
+			idempotency_key: None,
 		})
 	}
 
+	/// Attaches an idempotency key to the requests sent by this notifier
+	///
+	/// # Arguments
+	/// * `idempotency_key` - Header name and key to send
+	///
+	/// # Returns
+	/// * `Self` - Notifier sending the key with every request
+	pub fn with_idempotency_key(mut self, idempotency_key: IdempotencyKey) -> Self {
+		self.idempotency_key = Some(idempotency_key);
+		self
+	}
+
 	/// Creates a Webhook notifier from a trigger configuration
 	///
 	/// # Arguments
@@ -124,6 +142,24 @@ impl WebhookNotifier {
 		&self,
 		secret: &str,
 		payload: &serde_json::Value,
+	) -> Result<(String, String), NotificationError> {
+		self.sign_payload_at(secret, payload, Utc::now().timestamp_millis())
+	}
+
+	/// Signs a payload as if it was sent at the given time
+	///
+	/// # Arguments
+	/// * `secret` - Secret the signature is computed with
+	/// * `payload` - The JSON payload to sign
+	/// * `timestamp` - Time of the request, in milliseconds since the epoch
+	///
+	/// # Returns
+	/// * `Result<(String, String), NotificationError>` - Signature and timestamp headers
+	pub fn sign_payload_at(
+		&self,
+		secret: &str,
+		payload: &serde_json::Value,
+		timestamp: i64,
 	) -> Result<(String, String), NotificationError> {
 		// Explicitly reject empty secret, because `HmacSha256::new_from_slice` currently allows empty secrets
 		if secret.is_empty() {
@@ -134,8 +170,6 @@ impl WebhookNotifier {
 			));
 		}
 
-		let timestamp = Utc::now().timestamp_millis();
-
 		// Create HMAC instance
 		let mut mac = HmacSha256::new_from_slice(secret.as_bytes()).map_err(|e| {
 			NotificationError::config_error(format!("Invalid secret: {}", e), None, None)
@@ -166,6 +200,58 @@ impl WebhookNotifier {
 	/// # Returns
 	/// * `Result<(), NotificationError>` - Success or error
 	pub async fn notify_json(&self, payload: &serde_json::Value) -> Result<(), NotificationError> {
+		let (url, method, headers) = self.build_request(payload, Utc::now().timestamp_millis())?;
+
+		// Send request with custom payload
+		let response = self
+			.client
+			.request(method, url.as_str())
+			.headers(headers)
+			.json(payload)
+			.send()
+			.await
+			.map_err(|e| match EgressViolation::from_request_error(&e) {
+				Some(violation) => NotificationError::egress_denied(
+					format!("Webhook request refused: {}", violation),
+					Some(e.into()),
+					None,
+				),
+				None => NotificationError::notify_failed(
+					format!("Failed to send webhook request: {}", e),
+					Some(e.into()),
+					None,
+				),
+			})?;
+
+		let status = response.status();
+
+		if !status.is_success() {
+			return Err(NotificationError::notify_failed(
+				format!("Webhook request failed with status: {}", status),
+				None,
+				None,
+			));
+		}
+
+		Ok(())
+	}
+
+	/// Builds the URL, method and headers of the request sending a JSON payload
+	///
+	/// The payload is signed with the secret of the notifier, if any, as if it was sent at the
+	/// given time.
+	///
+	/// # Arguments
+	/// * `payload` - The JSON payload to send
+	/// * `timestamp` - Time of the request, in milliseconds since the epoch
+	///
+	/// # Returns
+	/// * `Result<(String, Method, HeaderMap), NotificationError>` - URL, method and headers
+	pub fn build_request(
+		&self,
+		payload: &serde_json::Value,
+		timestamp: i64,
+	) -> Result<(String, Method, HeaderMap), NotificationError> {
 		let mut url = self.url.clone();
 		// Add URL parameters if present
 		if let Some(params) = &self.url_params {
@@ -192,9 +278,11 @@ impl WebhookNotifier {
 		);
 
 		if let Some(secret) = &self.secret {
-			let (signature, timestamp) = self.sign_payload(secret, payload).map_err(|e| {
-				NotificationError::internal_error(e.to_string(), Some(e.into()), None)
-			})?;
+			let (signature, timestamp) =
+				self.sign_payload_at(secret, payload, timestamp)
+					.map_err(|e| {
+						NotificationError::internal_error(e.to_string(), Some(e.into()), None)
+					})?;
 
 			// Add signature headers
 			headers.insert(
@@ -240,33 +328,30 @@ impl WebhookNotifier {
 			}
 		}
 
-		// Send request with custom payload
-		let response = self
-			.client
-			.request(method, url.as_str())
-			.headers(headers)
-			.json(payload)
-			.send()
-			.await
-			.map_err(|e| {
+		// The idempotency key is applied last, so that no custom header can replace it
+		if let Some(idempotency_key) = &self.idempotency_key {
+			let header_name =
+				HeaderName::from_bytes(idempotency_key.header.as_bytes()).map_err(|e| {
+					NotificationError::notify_failed(
+						format!(
+							"Invalid idempotency header name: {}",
+							idempotency_key.header
+						),
+						Some(e.into()),
+						None,
+					)
+				})?;
+			let header_value = HeaderValue::from_str(&idempotency_key.key).map_err(|e| {
 				NotificationError::notify_failed(
-					format!("Failed to send webhook request: {}", e),
+					"Invalid idempotency key value".to_string(),
 					Some(e.into()),
 					None,
 				)
 			})?;
-
-		let status = response.status();
-
-		if !status.is_success() {
-			return Err(NotificationError::notify_failed(
-				format!("Webhook request failed with status: {}", status),
-				None,
-				None,
-			));
+			headers.insert(header_name, header_value);
 		}
 
-		Ok(())
+		Ok((url, method, headers))
 	}
 }
 
@@ -310,8 +395,13 @@ mod tests {
 			message: NotificationMessage {
 				title: "Test Alert".to_string(),
 				body: "Test message ${value}".to_string(),
+				format: None,
+				thread_key: None,
+				attachments: None,
 			},
 			retry_policy: RetryConfig::default(),
+			idempotency_header: None,
+			format: None,
 		}
 	}
 
@@ -386,6 +476,9 @@ mod tests {
 			message: NotificationMessage {
 				title: "Test Alert".to_string(),
 				body: "Test message ${value}".to_string(),
+				format: None,
+				thread_key: None,
+				attachments: None,
 			},
 			retry_policy: RetryConfig::default(),
 		};
@@ -494,6 +587,22 @@ mod tests {
 		mock.assert();
 	}
 
+	#[test]
+	fn test_idempotency_key_is_not_replaced_by_custom_headers() {
+		let headers = HashMap::from([("idempotency-key".to_string(), "fixed".to_string())]);
+		let notifier = create_test_notifier("https://webhook.example.com", None, Some(headers))
+			.with_idempotency_key(IdempotencyKey {
+				header: "Idempotency-Key".to_string(),
+				key: "match-key".to_string(),
+			});
+
+		let (_, _, headers) = notifier
+			.build_request(&create_test_payload(), Utc::now().timestamp_millis())
+			.unwrap();
+		assert_eq!(headers.get_all("idempotency-key").iter().count(), 1);
+		assert_eq!(headers["idempotency-key"], "match-key");
+	}
+
 	#[tokio::test]
 	async fn test_notify_signature_header_cases() {
 		let mut server = mockito::Server::new_async().await;
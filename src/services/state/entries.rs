@@ -0,0 +1,174 @@
+//! State entries shared by the in-process state stores.
+//!
+//! The memory and file stores apply the operations to the same map of entries, the file store
+//! persisting it between operations.
+
+use serde::{Deserialize, Serialize};
+use std::{collections::HashMap, time::Duration};
+
+/// Value held by a key
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub(super) enum StateValue {
+	/// String value, integer counters are stored in their decimal form
+	Text(String),
+	/// Timestamps of the occurrences in a sliding window, in milliseconds
+	Window(Vec<i64>),
+}
+
+/// Value of a key with its expiry
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub(super) struct StateEntry {
+	pub value: StateValue,
+	/// Expiry in milliseconds since the Unix epoch
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub expires_at: Option<i64>,
+}
+
+/// Interval between removals of expired entries, in milliseconds
+const PURGE_INTERVAL_MS: i64 = 60_000;
+
+/// Entries of a store, keyed by state key
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub(super) struct StateEntries {
+	entries: HashMap<String, StateEntry>,
+	/// Time of the last removal of expired entries
+	#[serde(skip)]
+	purged_at: i64,
+}
+
+fn expiry(now_ms: i64, ttl: Option<Duration>) -> Option<i64> {
+	ttl.map(|ttl| now_ms.saturating_add(ttl.as_millis().min(i64::MAX as u128) as i64))
+}
+
+fn window_ms(window: Duration) -> i64 {
+	window.as_millis().min(i64::MAX as u128) as i64
+}
+
+impl StateEntries {
+	/// Returns the live entry of a key, dropping it if it expired
+	fn live(&mut self, key: &str, now_ms: i64) -> Option<&mut StateEntry> {
+		if self
+			.entries
+			.get(key)
+			.and_then(|entry| entry.expires_at)
+			.is_some_and(|expires_at| expires_at <= now_ms)
+		{
+			self.entries.remove(key);
+		}
+		self.entries.get_mut(key)
+	}
+
+	/// Removes the expired entries, at most once per purge interval
+	///
+	/// Expired entries are otherwise only dropped when their key is accessed.
+	pub fn purge_expired(&mut self, now_ms: i64) {
+		if now_ms - self.purged_at < PURGE_INTERVAL_MS {
+			return;
+		}
+		self.purged_at = now_ms;
+		self.entries.retain(|_, entry| {
+			entry
+				.expires_at
+				.is_none_or(|expires_at| expires_at > now_ms)
+		});
+	}
+
+	pub fn get(&mut self, key: &str, now_ms: i64) -> Result<Option<String>, anyhow::Error> {
+		match self.live(key, now_ms).map(|entry| &entry.value) {
+			None => Ok(None),
+			Some(StateValue::Text(value)) => Ok(Some(value.clone())),
+			Some(StateValue::Window(_)) => Err(anyhow::anyhow!(
+				"key '{}' holds a sliding window, not a value",
+				key
+			)),
+		}
+	}
+
+	pub fn set(&mut self, key: &str, value: &str, ttl: Option<Duration>, now_ms: i64) {
+		self.entries.insert(
+			key.to_string(),
+			StateEntry {
+				value: StateValue::Text(value.to_string()),
+				expires_at: expiry(now_ms, ttl),
+			},
+		);
+	}
+
+	pub fn increment(
+		&mut self,
+		key: &str,
+		delta: i64,
+		ttl: Option<Duration>,
+		now_ms: i64,
+	) -> Result<i64, anyhow::Error> {
+		if self.live(key, now_ms).is_none() {
+			self.set(key, &delta.to_string(), ttl, now_ms);
+			return Ok(delta);
+		}
+		let entry = self.entries.entry(key.to_string()).or_insert(StateEntry {
+			value: StateValue::Text("0".to_string()),
+			expires_at: None,
+		});
+		let StateValue::Text(value) = &mut entry.value else {
+			return Err(anyhow::anyhow!(
+				"key '{}' holds a sliding window, not a counter",
+				key
+			));
+		};
+		let current = value
+			.parse::<i64>()
+			.map_err(|_| anyhow::anyhow!("key '{}' does not hold an integer", key))?;
+		let updated = current
+			.checked_add(delta)
+			.ok_or_else(|| anyhow::anyhow!("increment of key '{}' overflows", key))?;
+		*value = updated.to_string();
+		Ok(updated)
+	}
+
+	pub fn window_add(
+		&mut self,
+		key: &str,
+		timestamp_ms: i64,
+		window: Duration,
+		now_ms: i64,
+	) -> Result<u64, anyhow::Error> {
+		let start = timestamp_ms.saturating_sub(window_ms(window));
+		self.live(key, now_ms);
+		let entry = self.entries.entry(key.to_string()).or_insert(StateEntry {
+			value: StateValue::Window(vec![]),
+			expires_at: None,
+		});
+		let StateValue::Window(timestamps) = &mut entry.value else {
+			return Err(anyhow::anyhow!(
+				"key '{}' holds a value, not a sliding window",
+				key
+			));
+		};
+		timestamps.retain(|t| *t > start);
+		timestamps.push(timestamp_ms);
+		let last = timestamps.iter().copied().max().unwrap_or(timestamp_ms);
+		entry.expires_at = Some(last.saturating_add(window_ms(window)));
+		Ok(timestamps.len() as u64)
+	}
+
+	pub fn window_count(
+		&mut self,
+		key: &str,
+		end_ms: i64,
+		window: Duration,
+		now_ms: i64,
+	) -> Result<u64, anyhow::Error> {
+		let start = end_ms.saturating_sub(window_ms(window));
+		match self.live(key, now_ms).map(|entry| &entry.value) {
+			None => Ok(0),
+			Some(StateValue::Window(timestamps)) => {
+				Ok(timestamps.iter().filter(|t| **t > start).count() as u64)
+			}
+			Some(StateValue::Text(_)) => Err(anyhow::anyhow!(
+				"key '{}' holds a value, not a sliding window",
+				key
+			)),
+		}
+	}
+}
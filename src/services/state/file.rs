@@ -0,0 +1,194 @@
+//! JSON file state store.
+
+use async_trait::async_trait;
+use std::{
+	path::PathBuf,
+	time::{Duration, Instant},
+};
+use tokio::{io::AsyncWriteExt, sync::Mutex};
+
+use super::{entries::StateEntries, StateStore};
+
+/// Default path of the state file
+pub const DEFAULT_STATE_STORE_FILE: &str = "data/state_store.json";
+
+/// State store persisting its entries to a JSON file
+///
+/// Every operation reads the file, applies the change and writes it back, so instances of the
+/// same host pointing at the same file share the state. Operations are serialized through a
+/// lock file created exclusively, and the file is written to a temporary file renamed over it.
+pub struct FileStateStore {
+	/// Path of the state file
+	path: PathBuf,
+	/// Serializes the operations of this process before competing for the lock file
+	local: Mutex<()>,
+	/// Time to wait for the lock file before failing the operation
+	lock_timeout: Duration,
+	/// Age after which a lock file left behind by a crashed instance is removed
+	stale_lock_after: Duration,
+}
+
+impl FileStateStore {
+	/// Creates a store persisting to the given file
+	pub fn new(path: impl Into<PathBuf>) -> Self {
+		Self {
+			path: path.into(),
+			local: Mutex::new(()),
+			lock_timeout: Duration::from_secs(5),
+			stale_lock_after: Duration::from_secs(10),
+		}
+	}
+
+	fn lock_path(&self) -> PathBuf {
+		self.path.with_extension("lock")
+	}
+
+	/// Creates the lock file, waiting for other instances to release it
+	async fn lock(&self) -> Result<(), anyhow::Error> {
+		let lock_path = self.lock_path();
+		if let Some(parent) = lock_path.parent().filter(|p| !p.as_os_str().is_empty()) {
+			tokio::fs::create_dir_all(parent).await?;
+		}
+		let started = Instant::now();
+		loop {
+			match tokio::fs::OpenOptions::new()
+				.write(true)
+				.create_new(true)
+				.open(&lock_path)
+				.await
+			{
+				Ok(_) => return Ok(()),
+				Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => {
+					let is_stale = tokio::fs::metadata(&lock_path)
+						.await
+						.ok()
+						.and_then(|m| m.modified().ok())
+						.and_then(|modified| modified.elapsed().ok())
+						.is_some_and(|age| age > self.stale_lock_after);
+					if is_stale {
+						let _ = tokio::fs::remove_file(&lock_path).await;
+						continue;
+					}
+					if started.elapsed() > self.lock_timeout {
+						return Err(anyhow::anyhow!(
+							"timed out waiting for state store lock {}",
+							lock_path.display()
+						));
+					}
+					tokio::time::sleep(Duration::from_millis(5)).await;
+				}
+				Err(e) => return Err(e.into()),
+			}
+		}
+	}
+
+	async fn read(&self) -> Result<StateEntries, anyhow::Error> {
+		match tokio::fs::read_to_string(&self.path).await {
+			Ok(content) if content.trim().is_empty() => Ok(StateEntries::default()),
+			Ok(content) => Ok(serde_json::from_str(&content)?),
+			Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(StateEntries::default()),
+			Err(e) => Err(e.into()),
+		}
+	}
+
+	async fn write(&self, entries: &StateEntries) -> Result<(), anyhow::Error> {
+		let tmp_path = self.path.with_extension("tmp");
+		let mut file = tokio::fs::File::create(&tmp_path).await?;
+		file.write_all(serde_json::to_string(entries)?.as_bytes())
+			.await?;
+		file.sync_all().await?;
+		tokio::fs::rename(&tmp_path, &self.path).await?;
+		Ok(())
+	}
+
+	/// Applies an operation to the entries under the lock, writing them back if `write` is set
+	async fn with_entries<T>(
+		&self,
+		write: bool,
+		f: impl FnOnce(&mut StateEntries, i64) -> Result<T, anyhow::Error>,
+	) -> Result<T, anyhow::Error> {
+		let _local = self.local.lock().await;
+		self.lock().await?;
+
+		let result = async {
+			let now_ms = chrono::Utc::now().timestamp_millis();
+			let mut entries = self.read().await?;
+			entries.purge_expired(now_ms);
+			let value = f(&mut entries, now_ms)?;
+			if write {
+				self.write(&entries).await?;
+			}
+			Ok(value)
+		}
+		.await;
+
+		let _ = tokio::fs::remove_file(self.lock_path()).await;
+		result
+	}
+}
+
+impl Default for FileStateStore {
+	/// Default implementation for FileStateStore
+	///
+	/// Stores the state in "data/state_store.json"
+	fn default() -> Self {
+		Self::new(DEFAULT_STATE_STORE_FILE)
+	}
+}
+
+#[async_trait]
+impl StateStore for FileStateStore {
+	async fn get(&self, key: &str) -> Result<Option<String>, anyhow::Error> {
+		self.with_entries(false, |entries, now_ms| entries.get(key, now_ms))
+			.await
+	}
+
+	async fn set(
+		&self,
+		key: &str,
+		value: &str,
+		ttl: Option<Duration>,
+	) -> Result<(), anyhow::Error> {
+		self.with_entries(true, |entries, now_ms| {
+			entries.set(key, value, ttl, now_ms);
+			Ok(())
+		})
+		.await
+	}
+
+	async fn increment(
+		&self,
+		key: &str,
+		delta: i64,
+		ttl: Option<Duration>,
+	) -> Result<i64, anyhow::Error> {
+		self.with_entries(true, |entries, now_ms| {
+			entries.increment(key, delta, ttl, now_ms)
+		})
+		.await
+	}
+
+	async fn window_add(
+		&self,
+		key: &str,
+		timestamp_ms: i64,
+		window: Duration,
+	) -> Result<u64, anyhow::Error> {
+		self.with_entries(true, |entries, now_ms| {
+			entries.window_add(key, timestamp_ms, window, now_ms)
+		})
+		.await
+	}
+
+	async fn window_count(
+		&self,
+		key: &str,
+		now_ms: i64,
+		window: Duration,
+	) -> Result<u64, anyhow::Error> {
+		self.with_entries(false, |entries, clock_ms| {
+			entries.window_count(key, now_ms, window, clock_ms)
+		})
+		.await
+	}
+}
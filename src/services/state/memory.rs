@@ -0,0 +1,72 @@
+//! Process-local state store.
+
+use async_trait::async_trait;
+use std::{sync::Mutex, time::Duration};
+
+use super::{entries::StateEntries, StateStore};
+
+/// State store keeping its entries in memory
+///
+/// The state is only shared by the users of the store within the process, and lost on restart.
+#[derive(Debug, Default)]
+pub struct InMemoryStateStore {
+	entries: Mutex<StateEntries>,
+}
+
+impl InMemoryStateStore {
+	/// Creates an empty store
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	fn with_entries<T>(&self, f: impl FnOnce(&mut StateEntries, i64) -> T) -> T {
+		let now_ms = chrono::Utc::now().timestamp_millis();
+		let mut entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+		entries.purge_expired(now_ms);
+		f(&mut entries, now_ms)
+	}
+}
+
+#[async_trait]
+impl StateStore for InMemoryStateStore {
+	async fn get(&self, key: &str) -> Result<Option<String>, anyhow::Error> {
+		self.with_entries(|entries, now_ms| entries.get(key, now_ms))
+	}
+
+	async fn set(
+		&self,
+		key: &str,
+		value: &str,
+		ttl: Option<Duration>,
+	) -> Result<(), anyhow::Error> {
+		self.with_entries(|entries, now_ms| entries.set(key, value, ttl, now_ms));
+		Ok(())
+	}
+
+	async fn increment(
+		&self,
+		key: &str,
+		delta: i64,
+		ttl: Option<Duration>,
+	) -> Result<i64, anyhow::Error> {
+		self.with_entries(|entries, now_ms| entries.increment(key, delta, ttl, now_ms))
+	}
+
+	async fn window_add(
+		&self,
+		key: &str,
+		timestamp_ms: i64,
+		window: Duration,
+	) -> Result<u64, anyhow::Error> {
+		self.with_entries(|entries, now_ms| entries.window_add(key, timestamp_ms, window, now_ms))
+	}
+
+	async fn window_count(
+		&self,
+		key: &str,
+		now_ms: i64,
+		window: Duration,
+	) -> Result<u64, anyhow::Error> {
+		self.with_entries(|entries, clock_ms| entries.window_count(key, now_ms, window, clock_ms))
+	}
+}
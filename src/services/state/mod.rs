@@ -0,0 +1,229 @@
+//! Shared state storage.
+//!
+//! Provides the key-value operations needed by stateful features that must be shared across
+//! monitor instances, behind the [`StateStore`] trait:
+//! - `memory`: Process-local store, the state is lost on restart
+//! - `file`: JSON file store, shared by the instances of a host
+//! - `redis`: Redis store, shared by instances across hosts (requires the `redis` feature)
+//!
+//! The store is configured globally through [`StateStoreConfig`].
+
+use async_trait::async_trait;
+use std::{fmt, path::PathBuf, str::FromStr, sync::Arc, time::Duration};
+
+mod entries;
+mod file;
+mod memory;
+#[cfg(feature = "redis")]
+mod redis;
+
+#[cfg(feature = "redis")]
+pub use self::redis::RedisStateStore;
+pub use file::{FileStateStore, DEFAULT_STATE_STORE_FILE};
+pub use memory::InMemoryStateStore;
+
+/// Key-value storage for state shared by monitor instances
+///
+/// Keys hold either a string value, which may be an integer counter, or a sliding window of
+/// timestamps. Expired keys behave as if they were never set. Implementations must make
+/// `increment` and `window_add` atomic with respect to other instances using the same storage.
+#[async_trait]
+pub trait StateStore: Send + Sync {
+	/// Reads the value of a key
+	///
+	/// # Returns
+	/// * `Result<Option<String>, anyhow::Error>` - The value or None if the key is not set
+	async fn get(&self, key: &str) -> Result<Option<String>, anyhow::Error>;
+
+	/// Sets the value of a key
+	///
+	/// # Arguments
+	/// * `key` - Key to set
+	/// * `value` - Value to store
+	/// * `ttl` - Time after which the key expires, None to keep it until overwritten
+	async fn set(&self, key: &str, value: &str, ttl: Option<Duration>)
+		-> Result<(), anyhow::Error>;
+
+	/// Adds `delta` to the integer value of a key, starting from zero if it is not set
+	///
+	/// # Arguments
+	/// * `key` - Key of the counter
+	/// * `delta` - Amount to add, may be negative
+	/// * `ttl` - Expiry applied when the counter is created, existing counters keep theirs
+	///
+	/// # Returns
+	/// * `Result<i64, anyhow::Error>` - The value after the increment
+	async fn increment(
+		&self,
+		key: &str,
+		delta: i64,
+		ttl: Option<Duration>,
+	) -> Result<i64, anyhow::Error>;
+
+	/// Records an occurrence in the sliding window of a key
+	///
+	/// Occurrences older than `window` relative to `timestamp_ms` are discarded, and the key
+	/// expires once its last occurrence left the window.
+	///
+	/// # Arguments
+	/// * `key` - Key of the window
+	/// * `timestamp_ms` - Time of the occurrence in milliseconds since the Unix epoch
+	/// * `window` - Length of the window
+	///
+	/// # Returns
+	/// * `Result<u64, anyhow::Error>` - The number of occurrences within the window
+	async fn window_add(
+		&self,
+		key: &str,
+		timestamp_ms: i64,
+		window: Duration,
+	) -> Result<u64, anyhow::Error>;
+
+	/// Counts the occurrences in the sliding window of a key
+	///
+	/// # Arguments
+	/// * `key` - Key of the window
+	/// * `now_ms` - End of the window in milliseconds since the Unix epoch
+	/// * `window` - Length of the window
+	///
+	/// # Returns
+	/// * `Result<u64, anyhow::Error>` - The number of occurrences within the window
+	async fn window_count(
+		&self,
+		key: &str,
+		now_ms: i64,
+		window: Duration,
+	) -> Result<u64, anyhow::Error>;
+}
+
+/// Supported state store backends
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StateStoreBackend {
+	/// Process-local store
+	Memory,
+	/// JSON file store
+	File,
+	/// Redis store
+	Redis,
+}
+
+impl fmt::Display for StateStoreBackend {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			StateStoreBackend::Memory => write!(f, "memory"),
+			StateStoreBackend::File => write!(f, "file"),
+			StateStoreBackend::Redis => write!(f, "redis"),
+		}
+	}
+}
+
+impl FromStr for StateStoreBackend {
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		match s.to_lowercase().as_str() {
+			"memory" => Ok(StateStoreBackend::Memory),
+			"file" => Ok(StateStoreBackend::File),
+			"redis" => Ok(StateStoreBackend::Redis),
+			other => Err(format!(
+				"unknown state store '{}', expected 'memory', 'file' or 'redis'",
+				other
+			)),
+		}
+	}
+}
+
+/// Global configuration of the state store
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StateStoreConfig {
+	/// Backend holding the state
+	pub backend: StateStoreBackend,
+	/// Path of the state file, used by the file backend
+	pub path: PathBuf,
+	/// Connection URL, used by the Redis backend
+	pub redis_url: Option<String>,
+	/// Prefix added to all keys, used by the Redis backend
+	pub key_prefix: String,
+}
+
+impl Default for StateStoreConfig {
+	fn default() -> Self {
+		Self {
+			backend: StateStoreBackend::Memory,
+			path: PathBuf::from(DEFAULT_STATE_STORE_FILE),
+			redis_url: None,
+			key_prefix: "openzeppelin-monitor:".to_string(),
+		}
+	}
+}
+
+/// Creates the state store described by the configuration
+///
+/// # Errors
+/// Returns an error if the Redis backend is selected without a URL, cannot be reached, or the
+/// binary was built without the `redis` feature.
+pub async fn create_state_store(
+	config: &StateStoreConfig,
+) -> Result<Arc<dyn StateStore>, anyhow::Error> {
+	match config.backend {
+		StateStoreBackend::Memory => Ok(Arc::new(InMemoryStateStore::new())),
+		StateStoreBackend::File => Ok(Arc::new(FileStateStore::new(config.path.clone()))),
+		StateStoreBackend::Redis => create_redis_store(config).await,
+	}
+}
+
+#[cfg(feature = "redis")]
+async fn create_redis_store(
+	config: &StateStoreConfig,
+) -> Result<Arc<dyn StateStore>, anyhow::Error> {
+	let url = config
+		.redis_url
+		.as_deref()
+		.ok_or_else(|| anyhow::anyhow!("the redis state store requires a connection URL"))?;
+	Ok(Arc::new(
+		RedisStateStore::connect(url, config.key_prefix.clone()).await?,
+	))
+}
+
+#[cfg(not(feature = "redis"))]
+async fn create_redis_store(
+	_config: &StateStoreConfig,
+) -> Result<Arc<dyn StateStore>, anyhow::Error> {
+	Err(anyhow::anyhow!(
+		"the redis state store requires building with the `redis` feature"
+	))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_backend_from_str() {
+		assert_eq!(
+			"Memory".parse::<StateStoreBackend>().unwrap(),
+			StateStoreBackend::Memory
+		);
+		assert_eq!(
+			"file".parse::<StateStoreBackend>().unwrap(),
+			StateStoreBackend::File
+		);
+		assert_eq!(
+			"REDIS".parse::<StateStoreBackend>().unwrap(),
+			StateStoreBackend::Redis
+		);
+		assert!("sqlite".parse::<StateStoreBackend>().is_err());
+	}
+
+	#[cfg(not(feature = "redis"))]
+	#[tokio::test]
+	async fn test_redis_backend_requires_feature() {
+		let config = StateStoreConfig {
+			backend: StateStoreBackend::Redis,
+			redis_url: Some("redis://127.0.0.1:6379".to_string()),
+			..Default::default()
+		};
+		let error = create_state_store(&config).await.err().unwrap();
+		assert!(error.to_string().contains("`redis` feature"));
+	}
+}
@@ -0,0 +1,138 @@
+//! Redis state store.
+
+use async_trait::async_trait;
+use redis::{aio::ConnectionManager, AsyncCommands, Script};
+use std::time::Duration;
+use uuid::Uuid;
+
+use super::StateStore;
+
+/// Adds to a counter, setting the expiry only when the counter is created
+const INCREMENT_SCRIPT: &str = r#"
+local created = redis.call('EXISTS', KEYS[1]) == 0
+local value = redis.call('INCRBY', KEYS[1], ARGV[1])
+if created and tonumber(ARGV[2]) > 0 then
+	redis.call('PEXPIRE', KEYS[1], ARGV[2])
+end
+return value
+"#;
+
+/// Records an occurrence in a sorted set scored by timestamp and counts the window
+const WINDOW_ADD_SCRIPT: &str = r#"
+local timestamp = tonumber(ARGV[1])
+local window = tonumber(ARGV[2])
+redis.call('ZREMRANGEBYSCORE', KEYS[1], '-inf', timestamp - window)
+redis.call('ZADD', KEYS[1], timestamp, ARGV[3])
+local last = redis.call('ZRANGE', KEYS[1], -1, -1, 'WITHSCORES')
+redis.call('PEXPIREAT', KEYS[1], tonumber(last[2]) + window)
+return redis.call('ZCARD', KEYS[1])
+"#;
+
+fn ttl_ms(ttl: Duration) -> u64 {
+	ttl.as_millis().clamp(1, u64::MAX as u128) as u64
+}
+
+/// State store backed by Redis
+///
+/// Counters are stored as Redis strings and sliding windows as sorted sets scored by
+/// timestamp. Multi-step operations run as Lua scripts, so they are atomic across instances.
+#[derive(Clone)]
+pub struct RedisStateStore {
+	connection: ConnectionManager,
+	/// Prefix added to all keys
+	key_prefix: String,
+}
+
+impl RedisStateStore {
+	/// Connects to the Redis server at the given URL
+	///
+	/// # Arguments
+	/// * `url` - Connection URL, e.g. `redis://127.0.0.1:6379`
+	/// * `key_prefix` - Prefix added to all keys, to share a database with other applications
+	pub async fn connect(url: &str, key_prefix: impl Into<String>) -> Result<Self, anyhow::Error> {
+		let client = redis::Client::open(url)?;
+		let connection = ConnectionManager::new(client).await?;
+		Ok(Self {
+			connection,
+			key_prefix: key_prefix.into(),
+		})
+	}
+
+	fn key(&self, key: &str) -> String {
+		format!("{}{}", self.key_prefix, key)
+	}
+}
+
+#[async_trait]
+impl StateStore for RedisStateStore {
+	async fn get(&self, key: &str) -> Result<Option<String>, anyhow::Error> {
+		let mut connection = self.connection.clone();
+		Ok(connection.get(self.key(key)).await?)
+	}
+
+	async fn set(
+		&self,
+		key: &str,
+		value: &str,
+		ttl: Option<Duration>,
+	) -> Result<(), anyhow::Error> {
+		let mut connection = self.connection.clone();
+		match ttl {
+			Some(ttl) => {
+				let _: () = connection
+					.pset_ex(self.key(key), value, ttl_ms(ttl))
+					.await?;
+			}
+			None => {
+				let _: () = connection.set(self.key(key), value).await?;
+			}
+		}
+		Ok(())
+	}
+
+	async fn increment(
+		&self,
+		key: &str,
+		delta: i64,
+		ttl: Option<Duration>,
+	) -> Result<i64, anyhow::Error> {
+		let mut connection = self.connection.clone();
+		Ok(Script::new(INCREMENT_SCRIPT)
+			.key(self.key(key))
+			.arg(delta)
+			.arg(ttl.map_or(0, ttl_ms))
+			.invoke_async(&mut connection)
+			.await?)
+	}
+
+	async fn window_add(
+		&self,
+		key: &str,
+		timestamp_ms: i64,
+		window: Duration,
+	) -> Result<u64, anyhow::Error> {
+		let mut connection = self.connection.clone();
+		// Occurrences sharing a timestamp need distinct members to be counted separately
+		let member = format!("{}-{}", timestamp_ms, Uuid::new_v4());
+		Ok(Script::new(WINDOW_ADD_SCRIPT)
+			.key(self.key(key))
+			.arg(timestamp_ms)
+			.arg(ttl_ms(window))
+			.arg(member)
+			.invoke_async(&mut connection)
+			.await?)
+	}
+
+	async fn window_count(
+		&self,
+		key: &str,
+		now_ms: i64,
+		window: Duration,
+	) -> Result<u64, anyhow::Error> {
+		let mut connection = self.connection.clone();
+		let start = now_ms.saturating_sub(window.as_millis().min(i64::MAX as u128) as i64);
+		Ok(connection
+			.zcount(self.key(key), format!("({}", start), "+inf")
+			.await?)
+	}
+}
@@ -0,0 +1,256 @@
+//! Labels of watched addresses rendered into notifications.
+//!
+//! Monitor addresses can carry a human readable `label` and free-form `annotations`, such as a
+//! criticality. They are indexed per network when the configuration is loaded, so that every
+//! variable of a match can be resolved against the index in constant time. A variable holding a
+//! labeled address gets a sibling `<name>_label` variable, and all labeled addresses involved in
+//! the match are listed in `addresses_involved`, e.g.
+//! `0xabc… (Treasury Timelock, critical)`.
+
+use std::collections::{BTreeMap, HashMap};
+
+use crate::{models::Monitor, utils::normalize_string};
+
+/// Name of the template variable listing the labeled addresses involved in a match
+pub const ADDRESSES_INVOLVED_VARIABLE: &str = "addresses_involved";
+
+/// Suffix of the variables holding the label of an address variable
+pub const LABEL_VARIABLE_SUFFIX: &str = "_label";
+
+/// Label and annotations of a watched address
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AddressLabel {
+	/// Human readable name of the address
+	pub label: Option<String>,
+	/// Free-form annotations, keyed by name
+	pub annotations: BTreeMap<String, String>,
+}
+
+impl AddressLabel {
+	/// Describes an address with its label and annotation values, e.g.
+	/// `0xabc (Treasury Timelock, critical)`
+	pub fn describe(&self, address: &str) -> String {
+		let details = self
+			.label
+			.iter()
+			.chain(self.annotations.values())
+			.map(String::as_str)
+			.collect::<Vec<_>>();
+		if details.is_empty() {
+			address.to_string()
+		} else {
+			format!("{} ({})", address, details.join(", "))
+		}
+	}
+}
+
+/// Labels of the watched addresses, indexed by network slug and normalized address
+#[derive(Debug, Clone, Default)]
+pub struct AddressLabels {
+	networks: HashMap<String, HashMap<String, AddressLabel>>,
+}
+
+impl AddressLabels {
+	/// Indexes the labeled addresses of the monitors on each of their networks
+	///
+	/// When monitors label the same address differently on a network, the first monitor by name
+	/// provides the label and annotations are merged, earlier monitors taking precedence.
+	pub fn from_monitors<'a>(monitors: impl IntoIterator<Item = &'a Monitor>) -> Self {
+		let mut monitors = monitors.into_iter().collect::<Vec<_>>();
+		monitors.sort_by(|a, b| a.name.cmp(&b.name));
+
+		let mut networks: HashMap<String, HashMap<String, AddressLabel>> = HashMap::new();
+		for monitor in monitors {
+			for address in &monitor.addresses {
+				if address.label.is_none() && address.annotations.is_empty() {
+					continue;
+				}
+				for network in &monitor.networks {
+					let entry = networks
+						.entry(network.clone())
+						.or_default()
+						.entry(normalize_string(&address.address))
+						.or_default();
+					if entry.label.is_none() {
+						entry.label = address.label.clone();
+					}
+					for (key, value) in &address.annotations {
+						entry
+							.annotations
+							.entry(key.clone())
+							.or_insert_with(|| value.clone());
+					}
+				}
+			}
+		}
+		Self { networks }
+	}
+
+	/// Returns the label of an address on a network
+	pub fn get(&self, network_slug: &str, address: &str) -> Option<&AddressLabel> {
+		self.networks
+			.get(network_slug)?
+			.get(&normalize_string(address))
+	}
+
+	/// Adds the labels of the addresses found in the variables of a match
+	///
+	/// Every variable holding a labeled address gets a `<name>_label` variable, unless it is
+	/// already set. The labeled addresses are listed in `addresses_involved` in the order of the
+	/// variables naming them, each address once.
+	///
+	/// # Arguments
+	/// * `network_slug` - Network of the match
+	/// * `variables` - Variables of the match
+	pub fn annotate(&self, network_slug: &str, variables: &mut HashMap<String, String>) {
+		let Some(labels) = self.networks.get(network_slug) else {
+			return;
+		};
+
+		let mut names = variables.keys().cloned().collect::<Vec<_>>();
+		names.sort();
+		let mut involved: Vec<(&str, String, &AddressLabel)> = Vec::new();
+		for name in names {
+			let value = variables[&name].clone();
+			let Some((key, label)) = labels.get_key_value(&normalize_string(&value)) else {
+				continue;
+			};
+			if let Some(text) = &label.label {
+				variables
+					.entry(format!("{}{}", name, LABEL_VARIABLE_SUFFIX))
+					.or_insert_with(|| text.clone());
+			}
+			if !involved.iter().any(|(known, _, _)| known == key) {
+				involved.push((key, value, label));
+			}
+		}
+
+		if !involved.is_empty() {
+			variables
+				.entry(ADDRESSES_INVOLVED_VARIABLE.to_string())
+				.or_insert_with(|| {
+					involved
+						.iter()
+						.map(|(_, address, label)| label.describe(address))
+						.collect::<Vec<_>>()
+						.join(", ")
+				});
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::utils::tests::builders::evm::monitor::MonitorBuilder;
+
+	const TIMELOCK: &str = "0xAbC0000000000000000000000000000000000001";
+	const SAFE: &str = "0xdef0000000000000000000000000000000000002";
+	const STRANGER: &str = "0x1230000000000000000000000000000000000003";
+
+	fn create_labels() -> AddressLabels {
+		let monitor = MonitorBuilder::new()
+			.name("treasury")
+			.networks(vec!["ethereum_mainnet".to_string()])
+			.add_labeled_address(
+				TIMELOCK,
+				Some("Treasury Timelock"),
+				vec![("severity", "critical")],
+			)
+			.add_labeled_address(SAFE, Some("Ops Safe"), vec![])
+			.add_labeled_address(STRANGER, None, vec![])
+			.build();
+		AddressLabels::from_monitors([&monitor])
+	}
+
+	#[test]
+	fn test_labels_are_indexed_per_network() {
+		let labels = create_labels();
+
+		let label = labels
+			.get("ethereum_mainnet", &TIMELOCK.to_lowercase())
+			.unwrap();
+		assert_eq!(label.label.as_deref(), Some("Treasury Timelock"));
+		assert_eq!(
+			label.describe(TIMELOCK),
+			format!("{} (Treasury Timelock, critical)", TIMELOCK)
+		);
+		assert!(labels.get("polygon_mainnet", TIMELOCK).is_none());
+		// Addresses without label nor annotations are not indexed
+		assert!(labels.get("ethereum_mainnet", STRANGER).is_none());
+	}
+
+	#[test]
+	fn test_annotate_labels_transfer_addresses() {
+		let labels = create_labels();
+		let mut variables = HashMap::from([
+			("events.0.args.from".to_string(), TIMELOCK.to_string()),
+			("events.0.args.to".to_string(), SAFE.to_string()),
+			("events.0.args.value".to_string(), "1000".to_string()),
+			("transaction.from".to_string(), STRANGER.to_string()),
+			("transaction.to".to_string(), TIMELOCK.to_string()),
+		]);
+
+		labels.annotate("ethereum_mainnet", &mut variables);
+
+		assert_eq!(variables["events.0.args.from_label"], "Treasury Timelock");
+		assert_eq!(variables["events.0.args.to_label"], "Ops Safe");
+		assert_eq!(variables["transaction.to_label"], "Treasury Timelock");
+		// Unlabeled addresses and other values pass through unchanged
+		assert_eq!(variables["transaction.from"], STRANGER);
+		assert!(!variables.contains_key("transaction.from_label"));
+		assert!(!variables.contains_key("events.0.args.value_label"));
+		assert_eq!(
+			variables[ADDRESSES_INVOLVED_VARIABLE],
+			format!(
+				"{} (Treasury Timelock, critical), {} (Ops Safe)",
+				TIMELOCK, SAFE
+			)
+		);
+	}
+
+	#[test]
+	fn test_annotate_keeps_existing_variables() {
+		let labels = create_labels();
+		let mut variables = HashMap::from([
+			("transaction.to".to_string(), SAFE.to_string()),
+			("transaction.to_label".to_string(), "Custom".to_string()),
+		]);
+
+		labels.annotate("ethereum_mainnet", &mut variables);
+		assert_eq!(variables["transaction.to_label"], "Custom");
+
+		let mut variables = HashMap::from([("transaction.to".to_string(), SAFE.to_string())]);
+		labels.annotate("polygon_mainnet", &mut variables);
+		assert_eq!(variables.len(), 1);
+	}
+
+	#[test]
+	fn test_first_monitor_by_name_provides_the_label() {
+		let first = MonitorBuilder::new()
+			.name("a_monitor")
+			.networks(vec!["ethereum_mainnet".to_string()])
+			.add_labeled_address(SAFE, Some("Ops Safe"), vec![("team", "ops")])
+			.build();
+		let second = MonitorBuilder::new()
+			.name("b_monitor")
+			.networks(vec!["ethereum_mainnet".to_string()])
+			.add_labeled_address(
+				SAFE,
+				Some("Multisig"),
+				vec![("team", "treasury"), ("severity", "high")],
+			)
+			.build();
+
+		let labels = AddressLabels::from_monitors([&second, &first]);
+		let label = labels.get("ethereum_mainnet", SAFE).unwrap();
+		assert_eq!(label.label.as_deref(), Some("Ops Safe"));
+		assert_eq!(
+			label.annotations,
+			BTreeMap::from([
+				("severity".to_string(), "high".to_string()),
+				("team".to_string(), "ops".to_string()),
+			])
+		);
+	}
+}
@@ -0,0 +1,406 @@
+//! Per-monitor daily notification budgets.
+//!
+//! Monitors with a `notification_budget` deliver at most `max_per_day` matches to their triggers
+//! per day. The first match over the budget is reported once, and further matches are digested,
+//! dropped or only delivered to recording triggers until the budget resets at the next day
+//! boundary of the budget's UTC offset. Counters are optionally persisted to a file so that a
+//! restart does not reset them.
+
+use chrono::{DateTime, FixedOffset, NaiveTime, Offset, Utc};
+use serde::{Deserialize, Serialize};
+use std::{
+	collections::HashMap,
+	path::PathBuf,
+	sync::{Arc, Mutex},
+};
+
+use crate::{
+	models::{BudgetOverflow, MonitorMatch},
+	utils::metrics::{NOTIFICATION_BUDGET_EXHAUSTED, NOTIFICATION_BUDGET_OVERFLOW},
+};
+
+/// Default file the budget counters are persisted to
+pub const DEFAULT_BUDGET_STATE_FILE: &str = "data/notification_budgets.json";
+
+/// Maximum number of transaction hashes listed in a digest
+const MAX_DIGEST_TRANSACTIONS: usize = 1000;
+
+const DAY_MS: i64 = 24 * 60 * 60 * 1000;
+
+/// Source of the current time
+pub type BudgetClock = Arc<dyn Fn() -> DateTime<Utc> + Send + Sync>;
+
+/// What to do with a match after checking it against its monitor's budget
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BudgetDecision {
+	/// Deliver the match to all of its triggers
+	Deliver,
+	/// The budget is exhausted, handle the match according to the overflow setting
+	Overflow(BudgetOverflow),
+}
+
+/// Summary of the matches held back while a budget was exhausted
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BudgetDigest {
+	/// Number of matches held back
+	pub held_back: u64,
+	/// Hashes of the first held back transactions
+	pub transaction_hashes: Vec<String>,
+	/// Start of the budget period in milliseconds since the Unix epoch
+	pub period_start: i64,
+	/// End of the budget period in milliseconds since the Unix epoch
+	pub period_end: i64,
+	/// Latest held back match, carrying the monitor and its triggers
+	pub last_match: MonitorMatch,
+}
+
+/// Result of checking a match against its monitor's budget
+#[derive(Debug, Clone)]
+pub struct BudgetCheck {
+	/// How to handle the match
+	pub decision: BudgetDecision,
+	/// Set for the first match over the budget of a period
+	pub exhausted: bool,
+	/// Digest of the previous period, due now that it has ended
+	pub digest: Option<BudgetDigest>,
+	/// End of the current budget period in milliseconds since the Unix epoch
+	pub resets_at: i64,
+}
+
+/// Counters of a monitor for the current budget period
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct BudgetState {
+	period_start: i64,
+	resets_at: i64,
+	delivered: u32,
+	held_back: u64,
+	transaction_hashes: Vec<String>,
+	last_match: Option<MonitorMatch>,
+}
+
+impl BudgetState {
+	fn new(now: DateTime<Utc>, offset: FixedOffset) -> Self {
+		let local = now.with_timezone(&offset);
+		let since_midnight = (local.time() - NaiveTime::MIN).num_milliseconds();
+		let period_start = now.timestamp_millis() - since_midnight;
+		Self {
+			period_start,
+			resets_at: period_start + DAY_MS,
+			delivered: 0,
+			held_back: 0,
+			transaction_hashes: Vec::new(),
+			last_match: None,
+		}
+	}
+
+	/// Returns the digest of the period, if matches were held back
+	fn into_digest(self) -> Option<BudgetDigest> {
+		let last_match = self.last_match.filter(|_| self.held_back > 0)?;
+		Some(BudgetDigest {
+			held_back: self.held_back,
+			transaction_hashes: self.transaction_hashes,
+			period_start: self.period_start,
+			period_end: self.resets_at,
+			last_match,
+		})
+	}
+}
+
+/// Tracks the notification budgets of monitors
+pub struct NotificationBudgets {
+	clock: BudgetClock,
+	state_file: Option<PathBuf>,
+	state: Mutex<HashMap<String, BudgetState>>,
+}
+
+impl Default for NotificationBudgets {
+	fn default() -> Self {
+		Self {
+			clock: Arc::new(Utc::now),
+			state_file: None,
+			state: Mutex::new(HashMap::new()),
+		}
+	}
+}
+
+impl NotificationBudgets {
+	/// Creates budgets kept in memory only
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Creates budgets persisted to the given file, restoring the counters it holds
+	///
+	/// # Arguments
+	/// * `path` - File the counters are read from and written to
+	///
+	/// # Returns
+	/// * `Result<Self, anyhow::Error>` - The budgets, or an error if the file cannot be parsed
+	pub fn with_state_file(path: impl Into<PathBuf>) -> Result<Self, anyhow::Error> {
+		let path = path.into();
+		let state = match std::fs::read_to_string(&path) {
+			Ok(content) => serde_json::from_str(&content)?,
+			Err(e) if e.kind() == std::io::ErrorKind::NotFound => HashMap::new(),
+			Err(e) => return Err(e.into()),
+		};
+		Ok(Self {
+			state_file: Some(path),
+			state: Mutex::new(state),
+			..Self::default()
+		})
+	}
+
+	/// Replaces the source of the current time
+	pub fn with_clock(mut self, clock: BudgetClock) -> Self {
+		self.clock = clock;
+		self
+	}
+
+	/// Checks a match against the budget of its monitor and accounts for it
+	///
+	/// Matches of monitors without a budget are always delivered.
+	pub fn check(&self, monitor_match: &MonitorMatch) -> BudgetCheck {
+		let now = (self.clock)();
+		let monitor = monitor_match.monitor();
+		let Some(budget) = &monitor.notification_budget else {
+			return BudgetCheck {
+				decision: BudgetDecision::Deliver,
+				exhausted: false,
+				digest: None,
+				resets_at: i64::MAX,
+			};
+		};
+		let offset = budget.offset().unwrap_or_else(|| Utc.fix());
+
+		let mut states = self.lock_state();
+		let mut digest = None;
+		let state = states
+			.entry(monitor.name.clone())
+			.or_insert_with(|| BudgetState::new(now, offset));
+		if now.timestamp_millis() >= state.resets_at {
+			let expired = std::mem::replace(state, BudgetState::new(now, offset));
+			digest = expired.into_digest();
+		}
+
+		let mut check = BudgetCheck {
+			decision: BudgetDecision::Deliver,
+			exhausted: false,
+			digest,
+			resets_at: state.resets_at,
+		};
+		if state.delivered < budget.max_per_day {
+			state.delivered += 1;
+		} else {
+			check.decision = BudgetDecision::Overflow(budget.overflow);
+			check.exhausted = state.held_back == 0;
+			state.held_back += 1;
+			if check.exhausted {
+				NOTIFICATION_BUDGET_EXHAUSTED
+					.with_label_values(&[monitor.name.as_str(), monitor.owner_team()])
+					.inc();
+			}
+			NOTIFICATION_BUDGET_OVERFLOW
+				.with_label_values(&[
+					monitor.name.as_str(),
+					monitor.owner_team(),
+					overflow_label(budget.overflow),
+				])
+				.inc();
+			if budget.overflow == BudgetOverflow::Digest {
+				if state.transaction_hashes.len() < MAX_DIGEST_TRANSACTIONS {
+					state
+						.transaction_hashes
+						.push(monitor_match.transaction_hash());
+				}
+				state.last_match = Some(monitor_match.clone());
+			}
+		}
+
+		self.persist(&states);
+		check
+	}
+
+	/// Resets the budgets whose period has ended and returns the digests that became due
+	pub fn take_due_digests(&self) -> Vec<BudgetDigest> {
+		let now = (self.clock)().timestamp_millis();
+		let mut states = self.lock_state();
+		let expired = states
+			.iter()
+			.filter(|(_, state)| now >= state.resets_at)
+			.map(|(name, _)| name.clone())
+			.collect::<Vec<_>>();
+		if expired.is_empty() {
+			return Vec::new();
+		}
+
+		let digests = expired
+			.into_iter()
+			.filter_map(|name| states.remove(&name)?.into_digest())
+			.collect();
+		self.persist(&states);
+		digests
+	}
+
+	fn persist(&self, states: &HashMap<String, BudgetState>) {
+		let Some(path) = &self.state_file else {
+			return;
+		};
+		let result = serde_json::to_vec(states)
+			.map_err(anyhow::Error::from)
+			.and_then(|json| {
+				if let Some(parent) = path.parent() {
+					std::fs::create_dir_all(parent)?;
+				}
+				let tmp_path = path.with_extension("json.tmp");
+				std::fs::write(&tmp_path, json)?;
+				std::fs::rename(&tmp_path, path)?;
+				Ok(())
+			});
+		if let Err(e) = result {
+			tracing::warn!(
+				"Failed to persist notification budgets to {}: {}",
+				path.display(),
+				e
+			);
+		}
+	}
+
+	fn lock_state(&self) -> std::sync::MutexGuard<'_, HashMap<String, BudgetState>> {
+		self.state.lock().unwrap_or_else(|e| e.into_inner())
+	}
+}
+
+fn overflow_label(overflow: BudgetOverflow) -> &'static str {
+	match overflow {
+		BudgetOverflow::Digest => "digest",
+		BudgetOverflow::Drop => "drop",
+		BudgetOverflow::DatabaseOnly => "database_only",
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{
+		models::NotificationBudget,
+		utils::tests::builders::evm::{
+			monitor::MonitorBuilder, monitor_match::MonitorMatchBuilder,
+		},
+	};
+	use chrono::TimeDelta;
+	use std::sync::atomic::{AtomicI64, Ordering};
+	use tempfile::TempDir;
+
+	fn create_match(overflow: BudgetOverflow, utc_offset: Option<&str>) -> MonitorMatch {
+		let monitor = MonitorBuilder::new()
+			.name("budgeted")
+			.notification_budget(NotificationBudget {
+				max_per_day: 2,
+				overflow,
+				utc_offset: utc_offset.map(str::to_string),
+				digest_part_delay_ms: None,
+				record_triggers: vec![],
+			})
+			.build();
+		MonitorMatchBuilder::new().monitor(monitor).build()
+	}
+
+	fn fake_clock(start: DateTime<Utc>) -> (Arc<AtomicI64>, BudgetClock) {
+		let now = Arc::new(AtomicI64::new(start.timestamp_millis()));
+		let clock_now = now.clone();
+		let clock: BudgetClock = Arc::new(move || {
+			DateTime::from_timestamp_millis(clock_now.load(Ordering::SeqCst)).unwrap()
+		});
+		(now, clock)
+	}
+
+	fn at(rfc3339: &str) -> DateTime<Utc> {
+		DateTime::parse_from_rfc3339(rfc3339).unwrap().to_utc()
+	}
+
+	#[test]
+	fn test_budget_resets_at_local_midnight() {
+		let (now, clock) = fake_clock(at("2024-03-01T20:00:00Z"));
+		let budgets = NotificationBudgets::new().with_clock(clock);
+		let monitor_match = create_match(BudgetOverflow::Digest, Some("+02:00"));
+
+		assert_eq!(
+			budgets.check(&monitor_match).decision,
+			BudgetDecision::Deliver
+		);
+		let check = budgets.check(&monitor_match);
+		assert_eq!(check.decision, BudgetDecision::Deliver);
+		assert_eq!(
+			check.resets_at,
+			at("2024-03-02T00:00:00+02:00").timestamp_millis()
+		);
+
+		let check = budgets.check(&monitor_match);
+		assert_eq!(
+			check.decision,
+			BudgetDecision::Overflow(BudgetOverflow::Digest)
+		);
+		assert!(check.exhausted);
+		assert!(!budgets.check(&monitor_match).exhausted);
+		assert!(budgets.take_due_digests().is_empty());
+
+		// Local midnight of +02:00 is 22:00 UTC
+		now.store(
+			at("2024-03-01T22:00:00Z").timestamp_millis(),
+			Ordering::SeqCst,
+		);
+		let check = budgets.check(&monitor_match);
+		assert_eq!(check.decision, BudgetDecision::Deliver);
+		let digest = check.digest.expect("digest of the previous day");
+		assert_eq!(digest.held_back, 2);
+		assert_eq!(digest.transaction_hashes.len(), 2);
+	}
+
+	#[test]
+	fn test_take_due_digests() {
+		let (now, clock) = fake_clock(at("2024-03-01T12:00:00Z"));
+		let budgets = NotificationBudgets::new().with_clock(clock);
+
+		let dropped = create_match(BudgetOverflow::Drop, None);
+		for _ in 0..3 {
+			budgets.check(&dropped);
+		}
+		now.fetch_add(TimeDelta::hours(12).num_milliseconds(), Ordering::SeqCst);
+		// Dropped matches are not summarized
+		assert!(budgets.take_due_digests().is_empty());
+
+		let digested = create_match(BudgetOverflow::Digest, None);
+		for _ in 0..5 {
+			budgets.check(&digested);
+		}
+		assert!(budgets.take_due_digests().is_empty());
+		now.fetch_add(TimeDelta::days(1).num_milliseconds(), Ordering::SeqCst);
+		let digests = budgets.take_due_digests();
+		assert_eq!(digests.len(), 1);
+		assert_eq!(digests[0].held_back, 3);
+		assert_eq!(digests[0].period_end - digests[0].period_start, DAY_MS);
+		assert!(budgets.take_due_digests().is_empty());
+	}
+
+	#[test]
+	fn test_counters_persist_across_restarts() {
+		let temp_dir = TempDir::new().unwrap();
+		let path = temp_dir.path().join("budgets.json");
+		let (_, clock) = fake_clock(at("2024-03-01T12:00:00Z"));
+		let monitor_match = create_match(BudgetOverflow::Drop, None);
+
+		let budgets = NotificationBudgets::with_state_file(&path)
+			.unwrap()
+			.with_clock(clock.clone());
+		budgets.check(&monitor_match);
+		budgets.check(&monitor_match);
+
+		let restarted = NotificationBudgets::with_state_file(&path)
+			.unwrap()
+			.with_clock(clock);
+		assert_eq!(
+			restarted.check(&monitor_match).decision,
+			BudgetDecision::Overflow(BudgetOverflow::Drop)
+		);
+	}
+}
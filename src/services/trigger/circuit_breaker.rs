@@ -0,0 +1,465 @@
+//! Per-trigger circuit breakers.
+//!
+//! A trigger whose deliveries keep failing is opened: its deliveries are suppressed until a
+//! backoff deadline, after which a single delivery probes the endpoint. A successful probe
+//! closes the breaker and a failed one opens it again with a doubled backoff, up to a maximum.
+//!
+//! The state of a trigger is written to a state store on every transition and read back the
+//! first time the trigger is used, so that a restart during an outage of an endpoint does not
+//! send it a burst of deliveries. State older than the staleness bound is discarded.
+
+use chrono::{DateTime, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+use std::{collections::HashMap, sync::Arc, time::Duration};
+use tokio::sync::Mutex;
+
+use crate::{
+	services::state::StateStore,
+	utils::metrics::{TRIGGER_CIRCUIT_OPEN, TRIGGER_CIRCUIT_SUPPRESSED},
+};
+
+/// Default file the circuit breakers are persisted to when no state store is configured
+pub const DEFAULT_CIRCUIT_BREAKER_STATE_FILE: &str = "data/trigger_circuit_breakers.json";
+
+/// Prefix of the state store keys holding the state of a trigger
+const STATE_KEY_PREFIX: &str = "trigger_circuit:";
+
+/// Maximum number of times the backoff is doubled
+const MAX_BACKOFF_DOUBLINGS: u32 = 16;
+
+/// Source of the current time
+pub type CircuitBreakerClock = Arc<dyn Fn() -> DateTime<Utc> + Send + Sync>;
+
+/// State of the circuit breaker of a trigger
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CircuitState {
+	/// Deliveries are attempted
+	#[default]
+	Closed,
+	/// Deliveries are suppressed until the next allowed attempt
+	Open,
+	/// A delivery is probing the endpoint, others are suppressed until it completes
+	HalfOpen,
+}
+
+/// Thresholds and durations of the circuit breakers
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CircuitBreakerConfig {
+	/// Consecutive failed deliveries opening the breaker
+	pub failure_threshold: u32,
+	/// Time deliveries are suppressed the first time the breaker opens
+	pub backoff: Duration,
+	/// Maximum time deliveries are suppressed
+	pub max_backoff: Duration,
+	/// Age after which a persisted state is discarded
+	pub stale_after: Duration,
+}
+
+impl Default for CircuitBreakerConfig {
+	fn default() -> Self {
+		Self {
+			failure_threshold: 5,
+			backoff: Duration::from_secs(30),
+			max_backoff: Duration::from_secs(30 * 60),
+			stale_after: Duration::from_secs(24 * 3600),
+		}
+	}
+}
+
+impl CircuitBreakerConfig {
+	/// Returns the time deliveries are suppressed after the given number of consecutive failures
+	fn backoff_ms(&self, failures: u32) -> i64 {
+		let doublings = failures
+			.saturating_sub(self.failure_threshold)
+			.min(MAX_BACKOFF_DOUBLINGS);
+		let backoff = self
+			.backoff
+			.saturating_mul(1 << doublings)
+			.min(self.max_backoff);
+		backoff.as_millis() as i64
+	}
+}
+
+/// Persisted state of the circuit breaker of a trigger
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TriggerCircuit {
+	/// Current state
+	pub state: CircuitState,
+	/// Number of consecutive failed deliveries
+	pub failures: u32,
+	/// Unix time in milliseconds before which deliveries are suppressed
+	pub next_attempt_at_ms: Option<i64>,
+	/// Unix time in milliseconds of the last transition
+	pub updated_at_ms: i64,
+}
+
+/// Outcome of checking the circuit breaker of a trigger before a delivery
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CircuitCheck {
+	/// The delivery can be attempted
+	Allow,
+	/// The delivery is suppressed
+	Suppress {
+		/// Unix time in milliseconds of the next allowed attempt
+		until_ms: i64,
+		/// Number of consecutive failed deliveries
+		failures: u32,
+	},
+}
+
+/// Circuit breakers of the triggers, persisted to a state store
+pub struct TriggerCircuitBreakers {
+	config: CircuitBreakerConfig,
+	store: Arc<dyn StateStore>,
+	clock: CircuitBreakerClock,
+	/// Circuits read from the store, keyed by trigger slug
+	circuits: Mutex<HashMap<String, TriggerCircuit>>,
+}
+
+impl TriggerCircuitBreakers {
+	/// Creates circuit breakers persisted to the given store
+	///
+	/// # Arguments
+	/// * `store` - Store the state of the triggers is read from and written to
+	/// * `config` - Thresholds and durations of the breakers
+	pub fn new(store: Arc<dyn StateStore>, config: CircuitBreakerConfig) -> Self {
+		Self {
+			config,
+			store,
+			clock: Arc::new(Utc::now),
+			circuits: Mutex::new(HashMap::new()),
+		}
+	}
+
+	/// Replaces the source of the current time
+	pub fn with_clock(mut self, clock: CircuitBreakerClock) -> Self {
+		self.clock = clock;
+		self
+	}
+
+	fn now_ms(&self) -> i64 {
+		(self.clock)().timestamp_millis()
+	}
+
+	/// Reads the persisted state of the given triggers
+	///
+	/// Triggers are otherwise read the first time they are used. Restoring them at startup
+	/// reports the suppressed ones early.
+	///
+	/// # Returns
+	/// * `usize` - The number of triggers whose deliveries are still suppressed
+	pub async fn restore<'a>(&self, trigger_slugs: impl IntoIterator<Item = &'a str>) -> usize {
+		let now_ms = self.now_ms();
+		let mut circuits = self.circuits.lock().await;
+		let mut suppressed = 0;
+		for trigger_slug in trigger_slugs {
+			let circuit = self.circuit_mut(&mut circuits, trigger_slug, now_ms).await;
+			if circuit
+				.next_attempt_at_ms
+				.is_some_and(|next_attempt_at_ms| next_attempt_at_ms > now_ms)
+			{
+				tracing::warn!(
+					"Deliveries to trigger '{}' stay suppressed until {} after {} consecutive failures",
+					trigger_slug,
+					format_time_ms(circuit.next_attempt_at_ms.unwrap_or_default()),
+					circuit.failures
+				);
+				suppressed += 1;
+			}
+		}
+		suppressed
+	}
+
+	/// Returns the current state of the breaker of a trigger
+	pub async fn circuit(&self, trigger_slug: &str) -> TriggerCircuit {
+		let now_ms = self.now_ms();
+		let mut circuits = self.circuits.lock().await;
+		self.circuit_mut(&mut circuits, trigger_slug, now_ms)
+			.await
+			.clone()
+	}
+
+	/// Checks whether a delivery to a trigger can be attempted
+	///
+	/// Once the backoff of an open breaker has passed, the delivery is let through as a probe
+	/// and other deliveries are suppressed until it completes, or until another backoff passes
+	/// if its outcome is never recorded.
+	pub async fn check(&self, trigger_slug: &str) -> CircuitCheck {
+		let now_ms = self.now_ms();
+		let mut circuits = self.circuits.lock().await;
+		let circuit = self.circuit_mut(&mut circuits, trigger_slug, now_ms).await;
+		if circuit.state == CircuitState::Closed {
+			return CircuitCheck::Allow;
+		}
+
+		let until_ms = circuit.next_attempt_at_ms.unwrap_or_default();
+		if now_ms < until_ms {
+			TRIGGER_CIRCUIT_SUPPRESSED
+				.with_label_values(&[trigger_slug])
+				.inc();
+			return CircuitCheck::Suppress {
+				until_ms,
+				failures: circuit.failures,
+			};
+		}
+
+		circuit.state = CircuitState::HalfOpen;
+		circuit.next_attempt_at_ms = Some(now_ms + self.config.backoff_ms(circuit.failures));
+		circuit.updated_at_ms = now_ms;
+		let circuit = circuit.clone();
+		self.persist(trigger_slug, &circuit).await;
+		CircuitCheck::Allow
+	}
+
+	/// Records a successful delivery to a trigger, closing its breaker
+	pub async fn record_success(&self, trigger_slug: &str) {
+		let now_ms = self.now_ms();
+		let mut circuits = self.circuits.lock().await;
+		let circuit = self.circuit_mut(&mut circuits, trigger_slug, now_ms).await;
+		if circuit.state == CircuitState::Closed && circuit.failures == 0 {
+			return;
+		}
+		if circuit.state != CircuitState::Closed {
+			tracing::info!(
+				"Deliveries to trigger '{}' succeed again, resuming",
+				trigger_slug
+			);
+		}
+
+		*circuit = TriggerCircuit {
+			updated_at_ms: now_ms,
+			..Default::default()
+		};
+		let circuit = circuit.clone();
+		TRIGGER_CIRCUIT_OPEN
+			.with_label_values(&[trigger_slug])
+			.set(0.0);
+		self.persist(trigger_slug, &circuit).await;
+	}
+
+	/// Records a failed delivery to a trigger, opening its breaker once the threshold is reached
+	pub async fn record_failure(&self, trigger_slug: &str) {
+		let now_ms = self.now_ms();
+		let mut circuits = self.circuits.lock().await;
+		let circuit = self.circuit_mut(&mut circuits, trigger_slug, now_ms).await;
+		circuit.failures = circuit.failures.saturating_add(1);
+		circuit.updated_at_ms = now_ms;
+		if circuit.state == CircuitState::HalfOpen
+			|| circuit.failures >= self.config.failure_threshold
+		{
+			let next_attempt_at_ms = now_ms + self.config.backoff_ms(circuit.failures);
+			circuit.state = CircuitState::Open;
+			circuit.next_attempt_at_ms = Some(next_attempt_at_ms);
+			tracing::warn!(
+				"Suppressing deliveries to trigger '{}' until {} after {} consecutive failures",
+				trigger_slug,
+				format_time_ms(next_attempt_at_ms),
+				circuit.failures
+			);
+			TRIGGER_CIRCUIT_OPEN
+				.with_label_values(&[trigger_slug])
+				.set(1.0);
+		}
+		let circuit = circuit.clone();
+		self.persist(trigger_slug, &circuit).await;
+	}
+
+	/// Returns the circuit of a trigger, reading it from the store the first time
+	async fn circuit_mut<'a>(
+		&self,
+		circuits: &'a mut HashMap<String, TriggerCircuit>,
+		trigger_slug: &str,
+		now_ms: i64,
+	) -> &'a mut TriggerCircuit {
+		if !circuits.contains_key(trigger_slug) {
+			let circuit = self.load(trigger_slug, now_ms).await;
+			if circuit.state != CircuitState::Closed {
+				TRIGGER_CIRCUIT_OPEN
+					.with_label_values(&[trigger_slug])
+					.set(1.0);
+			}
+			circuits.insert(trigger_slug.to_string(), circuit);
+		}
+		circuits
+			.get_mut(trigger_slug)
+			.expect("circuit was just inserted")
+	}
+
+	/// Reads the persisted state of a trigger, discarding it when stale or unreadable
+	async fn load(&self, trigger_slug: &str, now_ms: i64) -> TriggerCircuit {
+		let key = format!("{}{}", STATE_KEY_PREFIX, trigger_slug);
+		let value = match self.store.get(&key).await {
+			Ok(Some(value)) => value,
+			Ok(None) => return TriggerCircuit::default(),
+			Err(e) => {
+				tracing::error!(
+					"Failed to read the circuit breaker of trigger '{}', starting closed: {}",
+					trigger_slug,
+					e
+				);
+				return TriggerCircuit::default();
+			}
+		};
+		match serde_json::from_str::<TriggerCircuit>(&value) {
+			Ok(circuit)
+				if now_ms - circuit.updated_at_ms <= self.config.stale_after.as_millis() as i64 =>
+			{
+				circuit
+			}
+			Ok(_) => TriggerCircuit::default(),
+			Err(e) => {
+				tracing::error!(
+					"Failed to parse the circuit breaker of trigger '{}', starting closed: {}",
+					trigger_slug,
+					e
+				);
+				TriggerCircuit::default()
+			}
+		}
+	}
+
+	/// Writes the state of a trigger to the store, logging failures
+	async fn persist(&self, trigger_slug: &str, circuit: &TriggerCircuit) {
+		let key = format!("{}{}", STATE_KEY_PREFIX, trigger_slug);
+		let result = match serde_json::to_string(circuit) {
+			Ok(value) => {
+				self.store
+					.set(&key, &value, Some(self.config.stale_after))
+					.await
+			}
+			Err(e) => Err(e.into()),
+		};
+		if let Err(e) = result {
+			tracing::error!(
+				"Failed to persist the circuit breaker of trigger '{}': {}",
+				trigger_slug,
+				e
+			);
+		}
+	}
+}
+
+/// Formats a Unix time in milliseconds as an RFC 3339 date
+fn format_time_ms(time_ms: i64) -> String {
+	Utc.timestamp_millis_opt(time_ms)
+		.single()
+		.map_or_else(|| time_ms.to_string(), |time| time.to_rfc3339())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::services::state::InMemoryStateStore;
+	use std::sync::atomic::{AtomicI64, Ordering};
+
+	fn create_breakers(store: Arc<dyn StateStore>, now: &Arc<AtomicI64>) -> TriggerCircuitBreakers {
+		let time = now.clone();
+		TriggerCircuitBreakers::new(
+			store,
+			CircuitBreakerConfig {
+				failure_threshold: 2,
+				backoff: Duration::from_secs(10),
+				max_backoff: Duration::from_secs(30),
+				stale_after: Duration::from_secs(3600),
+			},
+		)
+		.with_clock(Arc::new(move || {
+			Utc.timestamp_millis_opt(time.load(Ordering::SeqCst))
+				.unwrap()
+		}))
+	}
+
+	#[tokio::test]
+	async fn test_opens_after_consecutive_failures() {
+		let now = Arc::new(AtomicI64::new(Utc::now().timestamp_millis()));
+		let start = now.load(Ordering::SeqCst);
+		let breakers = create_breakers(Arc::new(InMemoryStateStore::new()), &now);
+
+		breakers.record_failure("slack").await;
+		assert_eq!(breakers.check("slack").await, CircuitCheck::Allow);
+		breakers.record_failure("slack").await;
+		assert_eq!(
+			breakers.check("slack").await,
+			CircuitCheck::Suppress {
+				until_ms: start + 10_000,
+				failures: 2
+			}
+		);
+		assert_eq!(breakers.check("discord").await, CircuitCheck::Allow);
+
+		// A success below the threshold resets the count
+		breakers.record_success("discord").await;
+		breakers.record_failure("discord").await;
+		breakers.record_success("discord").await;
+		breakers.record_failure("discord").await;
+		assert_eq!(breakers.check("discord").await, CircuitCheck::Allow);
+	}
+
+	#[tokio::test]
+	async fn test_probe_reopens_with_doubled_backoff_and_closes_on_success() {
+		let now = Arc::new(AtomicI64::new(Utc::now().timestamp_millis()));
+		let start = now.load(Ordering::SeqCst);
+		let breakers = create_breakers(Arc::new(InMemoryStateStore::new()), &now);
+		breakers.record_failure("slack").await;
+		breakers.record_failure("slack").await;
+
+		// The first delivery after the backoff probes, the others wait for its outcome
+		now.store(start + 10_000, Ordering::SeqCst);
+		assert_eq!(breakers.check("slack").await, CircuitCheck::Allow);
+		assert!(matches!(
+			breakers.check("slack").await,
+			CircuitCheck::Suppress { .. }
+		));
+
+		breakers.record_failure("slack").await;
+		assert_eq!(
+			breakers.check("slack").await,
+			CircuitCheck::Suppress {
+				until_ms: start + 30_000,
+				failures: 3
+			}
+		);
+
+		// The backoff is capped
+		now.store(start + 30_000, Ordering::SeqCst);
+		assert_eq!(breakers.check("slack").await, CircuitCheck::Allow);
+		breakers.record_failure("slack").await;
+		assert_eq!(
+			breakers.circuit("slack").await.next_attempt_at_ms,
+			Some(start + 60_000)
+		);
+
+		now.store(start + 60_000, Ordering::SeqCst);
+		assert_eq!(breakers.check("slack").await, CircuitCheck::Allow);
+		breakers.record_success("slack").await;
+		assert_eq!(breakers.circuit("slack").await.state, CircuitState::Closed);
+		assert_eq!(breakers.check("slack").await, CircuitCheck::Allow);
+	}
+
+	#[tokio::test]
+	async fn test_state_is_restored_from_the_store() {
+		let store: Arc<dyn StateStore> = Arc::new(InMemoryStateStore::new());
+		let now = Arc::new(AtomicI64::new(Utc::now().timestamp_millis()));
+		let start = now.load(Ordering::SeqCst);
+		let breakers = create_breakers(store.clone(), &now);
+		breakers.record_failure("slack").await;
+		breakers.record_failure("slack").await;
+
+		let restored = create_breakers(store.clone(), &now);
+		assert_eq!(restored.restore(["slack", "discord"]).await, 1);
+		assert_eq!(
+			restored.check("slack").await,
+			CircuitCheck::Suppress {
+				until_ms: start + 10_000,
+				failures: 2
+			}
+		);
+
+		// State older than the staleness bound is discarded
+		now.store(start + 3_600_001, Ordering::SeqCst);
+		let restored = create_breakers(store, &now);
+		assert_eq!(restored.restore(["slack"]).await, 0);
+		assert_eq!(restored.circuit("slack").await, TriggerCircuit::default());
+	}
+}
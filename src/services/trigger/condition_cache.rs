@@ -0,0 +1,262 @@
+//! Per-match cache of trigger condition results.
+//!
+//! A trigger condition script only depends on the match it is given, so its result is computed
+//! once per (match, script) and reused by every later evaluation of the same match, such as a
+//! re-dispatch of the block. Failed executions are not cached and are retried on the next
+//! evaluation.
+
+use std::{
+	collections::HashMap,
+	future::Future,
+	sync::{Arc, Mutex},
+};
+
+use tokio::sync::OnceCell;
+use uuid::Uuid;
+
+/// Cached condition results, keyed by match identifier and condition script
+type ConditionEntries = HashMap<(Uuid, String), Arc<OnceCell<bool>>>;
+
+/// Default maximum number of cached condition results
+pub const DEFAULT_CONDITION_CACHE_CAPACITY: usize = 10_000;
+
+/// Cache of trigger condition results keyed by match identifier and script
+#[derive(Debug)]
+pub struct ConditionCache {
+	/// Cached results, each cell is initialized by the first evaluation of its key
+	entries: Mutex<ConditionEntries>,
+	/// Number of entries after which the cache is cleared
+	capacity: usize,
+}
+
+impl Default for ConditionCache {
+	fn default() -> Self {
+		Self::new(DEFAULT_CONDITION_CACHE_CAPACITY)
+	}
+}
+
+impl ConditionCache {
+	/// Creates a cache holding at most `capacity` results
+	pub fn new(capacity: usize) -> Self {
+		Self {
+			entries: Mutex::new(HashMap::new()),
+			capacity: capacity.max(1),
+		}
+	}
+
+	/// Returns the number of cached entries
+	pub fn len(&self) -> usize {
+		self.entries.lock().unwrap_or_else(|e| e.into_inner()).len()
+	}
+
+	/// Returns true if the cache holds no entries
+	pub fn is_empty(&self) -> bool {
+		self.len() == 0
+	}
+
+	/// Returns the cached result of a condition, evaluating it if needed
+	///
+	/// Concurrent evaluations of the same key wait for the first one instead of executing the
+	/// script again.
+	///
+	/// # Arguments
+	/// * `match_id` - Identifier of the match, see [`crate::services::notification::match_uuid`]
+	/// * `condition_key` - Identifier of the condition script within the monitor
+	/// * `evaluate` - Executes the condition script
+	///
+	/// # Returns
+	/// * `Result<bool, anyhow::Error>` - The condition result or the execution error
+	pub async fn get_or_evaluate<F, Fut>(
+		&self,
+		match_id: Uuid,
+		condition_key: &str,
+		evaluate: F,
+	) -> Result<bool, anyhow::Error>
+	where
+		F: FnOnce() -> Fut,
+		Fut: Future<Output = Result<bool, anyhow::Error>>,
+	{
+		let cell = {
+			let mut entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+			let key = (match_id, condition_key.to_string());
+			if !entries.contains_key(&key) && entries.len() >= self.capacity {
+				entries.clear();
+			}
+			entries.entry(key).or_default().clone()
+		};
+
+		cell.get_or_try_init(evaluate).await.copied()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{
+		models::{EventCondition, MatchConditions, MonitorMatch},
+		services::{notification::match_uuid, trigger::ScriptExecutor},
+		utils::tests::builders::evm::{
+			monitor::MonitorBuilder, monitor_match::MonitorMatchBuilder,
+			transaction::TransactionBuilder,
+		},
+	};
+	use alloy::primitives::B256;
+	use async_trait::async_trait;
+	use futures::future::join_all;
+	use std::{
+		any::Any,
+		sync::atomic::{AtomicUsize, Ordering},
+	};
+
+	/// Script executor counting its executions
+	#[derive(Default)]
+	struct CountingExecutor {
+		executions: AtomicUsize,
+		fail: bool,
+	}
+
+	#[async_trait]
+	impl ScriptExecutor for CountingExecutor {
+		fn as_any(&self) -> &dyn Any {
+			self
+		}
+
+		async fn execute(
+			&self,
+			_input: MonitorMatch,
+			_timeout_ms: &u32,
+			_args: Option<&[String]>,
+			_from_custom_notification: bool,
+		) -> Result<bool, anyhow::Error> {
+			self.executions.fetch_add(1, Ordering::SeqCst);
+			if self.fail {
+				return Err(anyhow::anyhow!("script failed"));
+			}
+			Ok(true)
+		}
+	}
+
+	fn create_match(hash: B256) -> MonitorMatch {
+		create_match_on(hash, MatchConditions::default())
+	}
+
+	fn create_match_on(hash: B256, matched_on: MatchConditions) -> MonitorMatch {
+		MonitorMatchBuilder::new()
+			.monitor(
+				MonitorBuilder::new()
+					.name("monitor")
+					.triggers(vec![
+						"slack".to_string(),
+						"email".to_string(),
+						"webhook".to_string(),
+					])
+					.build(),
+			)
+			.transaction(TransactionBuilder::new().hash(hash).build())
+			.matched_on(matched_on)
+			.build()
+	}
+
+	async fn evaluate(
+		cache: &ConditionCache,
+		executor: &CountingExecutor,
+		monitor_match: &MonitorMatch,
+	) -> Result<bool, anyhow::Error> {
+		cache
+			.get_or_evaluate(match_uuid(monitor_match), "condition.py", || {
+				executor.execute(monitor_match.clone(), &1000, None, false)
+			})
+			.await
+	}
+
+	#[tokio::test]
+	async fn test_condition_runs_once_per_match_across_triggers_and_retries() {
+		let cache = ConditionCache::default();
+		let executor = CountingExecutor::default();
+		let monitor_match = create_match(B256::with_last_byte(1));
+
+		// One evaluation per trigger of the monitor, concurrently
+		let results = join_all((0..3).map(|_| evaluate(&cache, &executor, &monitor_match))).await;
+		assert!(results.iter().all(|r| matches!(r, Ok(true))));
+
+		// Retry of a single delivery
+		assert!(evaluate(&cache, &executor, &monitor_match).await.unwrap());
+
+		assert_eq!(executor.executions.load(Ordering::SeqCst), 1);
+	}
+
+	#[tokio::test]
+	async fn test_different_matches_are_evaluated_separately() {
+		let cache = ConditionCache::default();
+		let executor = CountingExecutor::default();
+
+		evaluate(&cache, &executor, &create_match(B256::with_last_byte(1)))
+			.await
+			.unwrap();
+		evaluate(&cache, &executor, &create_match(B256::with_last_byte(2)))
+			.await
+			.unwrap();
+
+		assert_eq!(executor.executions.load(Ordering::SeqCst), 2);
+		assert_eq!(cache.len(), 2);
+	}
+
+	#[tokio::test]
+	async fn test_different_matches_in_same_transaction_are_evaluated_separately() {
+		let cache = ConditionCache::default();
+		let executor = CountingExecutor::default();
+		let event = |signature: &str| {
+			create_match_on(
+				B256::with_last_byte(1),
+				MatchConditions {
+					events: vec![EventCondition {
+						signature: signature.to_string(),
+						expression: None,
+						match_any_emitter: false,
+					}],
+					..Default::default()
+				},
+			)
+		};
+
+		let transfer = event("Transfer(address,address,uint256)");
+		let approval = event("Approval(address,address,uint256)");
+		evaluate(&cache, &executor, &transfer).await.unwrap();
+		evaluate(&cache, &executor, &approval).await.unwrap();
+		evaluate(&cache, &executor, &transfer).await.unwrap();
+
+		assert_eq!(executor.executions.load(Ordering::SeqCst), 2);
+		assert_eq!(cache.len(), 2);
+	}
+
+	#[tokio::test]
+	async fn test_failed_evaluations_are_not_cached() {
+		let cache = ConditionCache::default();
+		let executor = CountingExecutor {
+			fail: true,
+			..Default::default()
+		};
+		let monitor_match = create_match(B256::with_last_byte(1));
+
+		assert!(evaluate(&cache, &executor, &monitor_match).await.is_err());
+		assert!(evaluate(&cache, &executor, &monitor_match).await.is_err());
+
+		assert_eq!(executor.executions.load(Ordering::SeqCst), 2);
+	}
+
+	#[tokio::test]
+	async fn test_cache_is_cleared_when_full() {
+		let cache = ConditionCache::new(1);
+		let executor = CountingExecutor::default();
+		let first = create_match(B256::with_last_byte(1));
+
+		evaluate(&cache, &executor, &first).await.unwrap();
+		evaluate(&cache, &executor, &create_match(B256::with_last_byte(2)))
+			.await
+			.unwrap();
+		evaluate(&cache, &executor, &first).await.unwrap();
+
+		assert_eq!(executor.executions.load(Ordering::SeqCst), 3);
+		assert_eq!(cache.len(), 1);
+	}
+}
@@ -0,0 +1,540 @@
+//! Match de-duplication across overlapping monitors.
+//!
+//! Monitors that declare the same `dedup_group` may match the same transaction. For triggers
+//! that opt into `group_dedup`, only one match per (group, transaction, trigger) within a block
+//! is delivered, the first or the most severe one depending on the trigger's `dedup_strategy`;
+//! the names of the suppressed monitors are attached to the delivered match.
+//! With a shared state store, deliveries are also claimed across monitor instances.
+
+use serde::{Deserialize, Serialize};
+use std::{
+	collections::{hash_map::Entry, HashMap},
+	sync::Arc,
+	time::Duration,
+};
+
+use crate::{
+	models::{DedupStrategy, MonitorMatch},
+	services::{
+		state::StateStore,
+		trigger::rollup::{match_severity, severity_rank},
+	},
+};
+
+/// Default time a delivery claimed in the shared state store suppresses other instances
+pub const DEFAULT_SHARED_DEDUP_TTL: Duration = Duration::from_secs(3600);
+
+/// A match ready for dispatch after group de-duplication
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DedupedMatch {
+	/// The match, with its monitor's triggers narrowed to the ones still to be executed
+	pub monitor_match: MonitorMatch,
+
+	/// Names of monitors in the same group whose delivery was folded into this match
+	pub also_matched: Vec<String>,
+
+	/// Other matches of the block combined with this one into a rollup notification
+	#[serde(default, skip_serializing_if = "Vec::is_empty")]
+	pub rolled_up: Vec<MonitorMatch>,
+}
+
+/// De-duplicates the matches of a single block across monitors sharing a `dedup_group`
+///
+/// For a given (group, transaction, trigger), one match keeps the trigger and the others drop it,
+/// whichever conditions or events each monitor matched. The winner is the first match in dispatch
+/// order or, for triggers using [`DedupStrategy::HighestSeverity`], the match with the highest
+/// `severity` variable, ties and unknown severities going to the first match. Triggers that have
+/// not opted into group dedup, and monitors without a group, are left untouched. Matches left
+/// without any trigger are removed.
+///
+/// # Arguments
+/// * `matches` - Matches produced for a block, in dispatch order
+/// * `dedup_strategy` - Returns the strategy of the trigger with the given slug, or `None` when
+///   it has not opted into dedup
+///
+/// # Returns
+/// * `Vec<DedupedMatch>` - Matches to dispatch with the list of suppressed monitors
+pub fn dedup_matches<F>(matches: &[MonitorMatch], dedup_strategy: F) -> Vec<DedupedMatch>
+where
+	F: Fn(&str) -> Option<DedupStrategy>,
+{
+	let groups = matches
+		.iter()
+		.map(|monitor_match| {
+			let group = monitor_match.monitor().dedup_group.as_deref()?;
+			Some((group, monitor_match.transaction_hash()))
+		})
+		.collect::<Vec<_>>();
+	let dedup_key = |index: usize, trigger: &str| {
+		let (group, tx_hash) = groups[index].as_ref()?;
+		Some((group.to_string(), tx_hash.clone(), trigger.to_string()))
+	};
+
+	// Pick the winning match of each (group, transaction, trigger)
+	let mut winners: HashMap<(String, String, String), usize> = HashMap::new();
+	for (index, monitor_match) in matches.iter().enumerate() {
+		if groups[index].is_none() {
+			continue;
+		}
+		for trigger in &monitor_match.monitor().triggers {
+			let Some(strategy) = dedup_strategy(trigger.name()) else {
+				continue;
+			};
+			let Some(key) = dedup_key(index, trigger.name()) else {
+				continue;
+			};
+			match winners.entry(key) {
+				Entry::Vacant(entry) => {
+					entry.insert(index);
+				}
+				Entry::Occupied(mut entry) => {
+					if strategy == DedupStrategy::HighestSeverity
+						&& outranks(monitor_match, &matches[*entry.get()])
+					{
+						entry.insert(index);
+					}
+				}
+			}
+		}
+	}
+
+	let is_winner = |index: usize, trigger: &str| {
+		dedup_key(index, trigger)
+			.and_then(|key| winners.get(&key))
+			.is_none_or(|&winner| winner == index)
+	};
+
+	let mut results: Vec<DedupedMatch> = Vec::with_capacity(matches.len());
+	let mut result_indexes: HashMap<usize, usize> = HashMap::new();
+	for (index, monitor_match) in matches.iter().enumerate() {
+		let monitor = monitor_match.monitor();
+		let kept_triggers = monitor
+			.triggers
+			.iter()
+			.filter(|trigger| is_winner(index, trigger.name()))
+			.cloned()
+			.collect::<Vec<_>>();
+
+		if kept_triggers.is_empty() && !monitor.triggers.is_empty() {
+			tracing::debug!(
+				monitor = %monitor.name,
+				group = ?monitor.dedup_group,
+				"Match suppressed by dedup group"
+			);
+			continue;
+		}
+
+		let mut monitor_match = monitor_match.clone();
+		monitor_match.monitor_mut().triggers = kept_triggers;
+		result_indexes.insert(index, results.len());
+		results.push(DedupedMatch {
+			monitor_match,
+			also_matched: vec![],
+			rolled_up: vec![],
+		});
+	}
+
+	// Attach the suppressed monitors to the matches delivered in their place, in dispatch order
+	for (index, monitor_match) in matches.iter().enumerate() {
+		let monitor_name = &monitor_match.monitor().name;
+		for trigger in &monitor_match.monitor().triggers {
+			let Some(&winner) = dedup_key(index, trigger.name()).and_then(|key| winners.get(&key))
+			else {
+				continue;
+			};
+			if winner == index {
+				continue;
+			}
+			let winner = &mut results[result_indexes[&winner]];
+			if &winner.monitor_match.monitor().name != monitor_name
+				&& !winner.also_matched.contains(monitor_name)
+			{
+				winner.also_matched.push(monitor_name.clone());
+			}
+		}
+	}
+
+	results
+}
+
+/// Returns whether a match has a strictly higher severity than the current winner
+fn outranks(candidate: &MonitorMatch, winner: &MonitorMatch) -> bool {
+	let rank = |monitor_match: &MonitorMatch| match_severity(monitor_match).and_then(severity_rank);
+	rank(candidate) > rank(winner)
+}
+
+/// Group de-duplication shared by monitor instances through a state store
+///
+/// Instances processing the same network, e.g. when networks are sharded across instances with
+/// overlap, claim each (network, group, transaction, trigger) delivery in the store. Only the
+/// instance claiming it first delivers, for as long as the claim lives.
+#[derive(Clone)]
+pub struct SharedDedup {
+	/// Store shared by the instances
+	store: Arc<dyn StateStore>,
+	/// Time a claim suppresses the other instances
+	ttl: Duration,
+}
+
+impl SharedDedup {
+	/// Creates a shared de-duplication over the given store
+	pub fn new(store: Arc<dyn StateStore>, ttl: Duration) -> Self {
+		Self { store, ttl }
+	}
+
+	/// Drops the group dedup triggers already claimed by another instance
+	///
+	/// Applied to the output of [`dedup_matches`]. Claims that fail are logged and the trigger is
+	/// kept, so a store outage results in duplicates rather than lost notifications.
+	///
+	/// # Arguments
+	/// * `matches` - De-duplicated matches of a block
+	/// * `is_group_dedup_trigger` - Returns whether the trigger with the given slug opted into dedup
+	///
+	/// # Returns
+	/// * `Vec<DedupedMatch>` - Matches left with at least one trigger to deliver
+	pub async fn claim<F>(
+		&self,
+		matches: Vec<DedupedMatch>,
+		is_group_dedup_trigger: F,
+	) -> Vec<DedupedMatch>
+	where
+		F: Fn(&str) -> bool,
+	{
+		let mut results = Vec::with_capacity(matches.len());
+		for mut deduped in matches {
+			let monitor = deduped.monitor_match.monitor();
+			let Some(group) = monitor.dedup_group.clone() else {
+				results.push(deduped);
+				continue;
+			};
+			if monitor.triggers.is_empty() {
+				results.push(deduped);
+				continue;
+			}
+
+			let prefix = format!(
+				"dedup:{}:{}:{}",
+				deduped.monitor_match.network_slug(),
+				group,
+				deduped.monitor_match.transaction_hash()
+			);
+			let mut kept_triggers = Vec::with_capacity(monitor.triggers.len());
+			for trigger in &monitor.triggers {
+				if !is_group_dedup_trigger(trigger.name()) {
+					kept_triggers.push(trigger.clone());
+					continue;
+				}
+				let key = format!("{}:{}", prefix, trigger.name());
+				match self.store.increment(&key, 1, Some(self.ttl)).await {
+					Ok(1) => kept_triggers.push(trigger.clone()),
+					Ok(_) => {}
+					Err(e) => {
+						tracing::warn!(
+							key = %key,
+							error = %e,
+							"Failed to claim dedup delivery in the state store, delivering"
+						);
+						kept_triggers.push(trigger.clone());
+					}
+				}
+			}
+
+			if kept_triggers.is_empty() {
+				tracing::debug!(
+					monitor = %monitor.name,
+					group = %group,
+					"Match suppressed by dedup group on another instance"
+				);
+				continue;
+			}
+			deduped.monitor_match.monitor_mut().triggers = kept_triggers;
+			results.push(deduped);
+		}
+		results
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{
+		models::{
+			EVMMatchArguments, EVMMatchParamsMap, EVMTransaction, Monitor, TriggerOverride,
+			TriggerReference,
+		},
+		services::{state::InMemoryStateStore, trigger::SEVERITY_VARIABLE},
+		utils::tests::builders::evm::{
+			monitor::MonitorBuilder, monitor_match::MonitorMatchBuilder,
+			transaction::TransactionBuilder,
+		},
+	};
+	use alloy::primitives::B256;
+
+	fn create_match(monitor: Monitor, transaction: EVMTransaction) -> MonitorMatch {
+		MonitorMatchBuilder::new()
+			.monitor(monitor)
+			.transaction(transaction)
+			.build()
+	}
+
+	/// Creates a match of the `Transfer` events at the given log indexes
+	fn create_event_match(
+		monitor: Monitor,
+		transaction: EVMTransaction,
+		log_indexes: &[u64],
+	) -> MonitorMatch {
+		MonitorMatchBuilder::new()
+			.monitor(monitor)
+			.transaction(transaction)
+			.matched_on_args(EVMMatchArguments {
+				functions: None,
+				events: Some(
+					log_indexes
+						.iter()
+						.map(|log_index| EVMMatchParamsMap {
+							signature: "Transfer(address,address,uint256)".to_string(),
+							args: None,
+							hex_signature: None,
+							log_index: Some(*log_index),
+						})
+						.collect(),
+				),
+			})
+			.build()
+	}
+
+	fn create_monitor(name: &str, group: Option<&str>, triggers: Vec<&str>) -> Monitor {
+		let mut builder = MonitorBuilder::new()
+			.name(name)
+			.triggers(triggers.into_iter().map(String::from).collect());
+		if let Some(group) = group {
+			builder = builder.dedup_group(group);
+		}
+		builder.build()
+	}
+
+	fn create_severity_monitor(name: &str, severity: &str) -> Monitor {
+		MonitorBuilder::new()
+			.name(name)
+			.dedup_group("treasury")
+			.trigger_reference(TriggerReference::WithOverrides(TriggerOverride {
+				name: "slack".to_string(),
+				message: None,
+				variables: HashMap::from([(SEVERITY_VARIABLE.to_string(), severity.to_string())]),
+				rollup: false,
+			}))
+			.build()
+	}
+
+	fn create_transaction(hash: B256) -> EVMTransaction {
+		TransactionBuilder::new().hash(hash).build()
+	}
+
+	fn triggers_of(deduped: &DedupedMatch) -> Vec<String> {
+		deduped.monitor_match.monitor().trigger_names()
+	}
+
+	#[test]
+	fn test_overlapping_monitors_deliver_once() {
+		let tx = create_transaction(B256::with_last_byte(1));
+		let matches = vec![
+			create_match(
+				create_monitor("broad", Some("treasury"), vec!["slack"]),
+				tx.clone(),
+			),
+			create_match(
+				create_monitor("narrow", Some("treasury"), vec!["slack"]),
+				tx,
+			),
+		];
+
+		let deduped = dedup_matches(&matches, |_| Some(DedupStrategy::First));
+
+		assert_eq!(deduped.len(), 1);
+		assert_eq!(deduped[0].monitor_match.monitor().name, "broad");
+		assert_eq!(deduped[0].also_matched, vec!["narrow".to_string()]);
+	}
+
+	#[test]
+	fn test_only_opted_in_triggers_are_deduplicated() {
+		let tx = create_transaction(B256::with_last_byte(1));
+		let matches = vec![
+			create_match(
+				create_monitor("broad", Some("treasury"), vec!["slack", "webhook"]),
+				tx.clone(),
+			),
+			create_match(
+				create_monitor("narrow", Some("treasury"), vec!["slack", "webhook"]),
+				tx,
+			),
+		];
+
+		let deduped = dedup_matches(&matches, |slug| {
+			(slug == "slack").then_some(DedupStrategy::First)
+		});
+
+		assert_eq!(deduped.len(), 2);
+		assert_eq!(triggers_of(&deduped[0]), vec!["slack", "webhook"]);
+		assert_eq!(triggers_of(&deduped[1]), vec!["webhook"]);
+		assert_eq!(deduped[0].also_matched, vec!["narrow".to_string()]);
+	}
+
+	#[test]
+	fn test_different_transactions_are_not_deduplicated() {
+		let matches = vec![
+			create_match(
+				create_monitor("broad", Some("treasury"), vec!["slack"]),
+				create_transaction(B256::with_last_byte(1)),
+			),
+			create_match(
+				create_monitor("narrow", Some("treasury"), vec!["slack"]),
+				create_transaction(B256::with_last_byte(2)),
+			),
+		];
+
+		let deduped = dedup_matches(&matches, |_| Some(DedupStrategy::First));
+
+		assert_eq!(deduped.len(), 2);
+		assert!(deduped.iter().all(|m| m.also_matched.is_empty()));
+	}
+
+	#[test]
+	fn test_matches_on_different_events_of_a_transaction_deliver_once() {
+		let tx = create_transaction(B256::with_last_byte(1));
+		let matches = vec![
+			// A broad monitor matching every event of the transaction
+			create_event_match(
+				create_monitor("broad", Some("treasury"), vec!["slack"]),
+				tx.clone(),
+				&[3, 4],
+			),
+			create_event_match(
+				create_monitor("narrow", Some("treasury"), vec!["slack"]),
+				tx.clone(),
+				&[4],
+			),
+			// A monitor matching on the transaction only
+			create_match(
+				create_monitor("function", Some("treasury"), vec!["slack"]),
+				tx,
+			),
+		];
+
+		let deduped = dedup_matches(&matches, |_| Some(DedupStrategy::First));
+
+		assert_eq!(deduped.len(), 1);
+		assert_eq!(deduped[0].monitor_match.monitor().name, "broad");
+		assert_eq!(
+			deduped[0].also_matched,
+			vec!["narrow".to_string(), "function".to_string()]
+		);
+	}
+
+	#[test]
+	fn test_highest_severity_match_is_delivered() {
+		let tx = create_transaction(B256::with_last_byte(1));
+		let matches = vec![
+			create_match(create_severity_monitor("broad", "low"), tx.clone()),
+			create_match(create_severity_monitor("narrow", "critical"), tx.clone()),
+			create_match(create_severity_monitor("other", "high"), tx.clone()),
+			create_match(create_severity_monitor("tied", "critical"), tx),
+		];
+
+		let deduped = dedup_matches(&matches, |_| Some(DedupStrategy::HighestSeverity));
+		assert_eq!(deduped.len(), 1);
+		assert_eq!(deduped[0].monitor_match.monitor().name, "narrow");
+		assert_eq!(
+			deduped[0].also_matched,
+			vec!["broad".to_string(), "other".to_string(), "tied".to_string()]
+		);
+
+		// The first strategy keeps the first match regardless of severity
+		let deduped = dedup_matches(&matches, |_| Some(DedupStrategy::First));
+		assert_eq!(deduped.len(), 1);
+		assert_eq!(deduped[0].monitor_match.monitor().name, "broad");
+	}
+
+	#[test]
+	fn test_monitors_without_group_are_untouched() {
+		let tx = create_transaction(B256::with_last_byte(1));
+		let matches = vec![
+			create_match(create_monitor("broad", None, vec!["slack"]), tx.clone()),
+			create_match(create_monitor("narrow", None, vec!["slack"]), tx),
+		];
+
+		let deduped = dedup_matches(&matches, |_| panic!("should not be called"));
+
+		assert_eq!(deduped.len(), 2);
+		assert_eq!(triggers_of(&deduped[1]), vec!["slack"]);
+	}
+
+	#[test]
+	fn test_different_groups_are_not_deduplicated() {
+		let tx = create_transaction(B256::with_last_byte(1));
+		let matches = vec![
+			create_match(
+				create_monitor("broad", Some("treasury"), vec!["slack"]),
+				tx.clone(),
+			),
+			create_match(create_monitor("narrow", Some("ops"), vec!["slack"]), tx),
+		];
+
+		let deduped = dedup_matches(&matches, |_| Some(DedupStrategy::First));
+
+		assert_eq!(deduped.len(), 2);
+	}
+
+	#[tokio::test]
+	async fn test_shared_dedup_delivers_once_across_instances() {
+		let store: Arc<dyn StateStore> = Arc::new(InMemoryStateStore::new());
+		let first = SharedDedup::new(store.clone(), DEFAULT_SHARED_DEDUP_TTL);
+		let second = SharedDedup::new(store, DEFAULT_SHARED_DEDUP_TTL);
+		let tx = create_transaction(B256::with_last_byte(1));
+		let matches = |name: &str| {
+			dedup_matches(
+				&[create_match(
+					create_monitor(name, Some("treasury"), vec!["slack", "webhook"]),
+					tx.clone(),
+				)],
+				|_| Some(DedupStrategy::First),
+			)
+		};
+
+		let delivered = first.claim(matches("broad"), |slug| slug == "slack").await;
+		assert_eq!(triggers_of(&delivered[0]), vec!["slack", "webhook"]);
+
+		// The other instance keeps only the trigger that did not opt into dedup
+		let delivered = second
+			.claim(matches("narrow"), |slug| slug == "slack")
+			.await;
+		assert_eq!(triggers_of(&delivered[0]), vec!["webhook"]);
+
+		// Once claimed, the remaining trigger is not delivered again
+		let delivered = second.claim(matches("narrow"), |_| true).await;
+		assert_eq!(triggers_of(&delivered[0]), vec!["webhook"]);
+		let delivered = first.claim(matches("broad"), |_| true).await;
+		assert!(delivered.is_empty());
+	}
+
+	#[tokio::test]
+	async fn test_shared_dedup_ignores_monitors_without_group() {
+		let shared = SharedDedup::new(
+			Arc::new(InMemoryStateStore::new()),
+			DEFAULT_SHARED_DEDUP_TTL,
+		);
+		let tx = create_transaction(B256::with_last_byte(1));
+		let matches = || {
+			dedup_matches(
+				&[create_match(
+					create_monitor("broad", None, vec!["slack"]),
+					tx.clone(),
+				)],
+				|_| Some(DedupStrategy::First),
+			)
+		};
+
+		assert_eq!(shared.claim(matches(), |_| true).await.len(), 1);
+		assert_eq!(shared.claim(matches(), |_| true).await.len(), 1);
+	}
+}
@@ -0,0 +1,346 @@
+//! Pagination of digest notifications.
+//!
+//! A digest lists the matches held back over a period in a single message. When that message
+//! exceeds the size a channel accepts (see [`ChannelCapabilities`]), the list is split across
+//! consecutive messages whose titles are marked "part i/N". Entries keep their order, and the
+//! count of unlisted matches is only reported by the last part.
+//!
+//! Parts are delivered one after another with a delay between them to stay under the rate limits
+//! of chat channels. A part that fails is retried on its own, without resending the parts that
+//! were delivered.
+//!
+//! [`ChannelCapabilities`]: crate::services::notification::ChannelCapabilities
+
+use std::{future::Future, time::Duration};
+
+use crate::services::trigger::TriggerError;
+
+/// Default delay between two messages of a digest
+pub const DEFAULT_DIGEST_PART_DELAY: Duration = Duration::from_secs(1);
+
+/// Number of times a part is sent before giving up on it
+const MAX_DIGEST_PART_ATTEMPTS: usize = 3;
+
+/// Room left in every message for the formatting added by the channels
+const MESSAGE_FORMATTING_RESERVE: usize = 16;
+
+/// Separator of the listed entries
+const ENTRY_SEPARATOR: &str = ", ";
+
+/// Content of a digest notification
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DigestMessage {
+	/// Title of the notification
+	pub title: String,
+	/// Summary repeated at the top of every part
+	pub summary: String,
+	/// Label of the listed entries
+	pub entries_label: String,
+	/// Listed entries, in delivery order
+	pub entries: Vec<String>,
+	/// Number of matches summarized but not listed
+	pub unlisted: u64,
+}
+
+/// One message of a digest
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DigestPart {
+	/// Title of the message, marked with the part number when the digest is split
+	pub title: String,
+	/// Body of the message
+	pub body: String,
+}
+
+impl DigestPart {
+	/// Returns the size of the message once formatted by a channel, in bytes
+	fn size(&self) -> usize {
+		self.title.len() + self.body.len() + MESSAGE_FORMATTING_RESERVE
+	}
+}
+
+impl DigestMessage {
+	/// Renders the body listing the given entries
+	fn body(&self, entries: &[String], unlisted: u64) -> String {
+		let mut body = self.summary.clone();
+		if !entries.is_empty() {
+			body.push_str(&format!(
+				"\n{}: {}",
+				self.entries_label,
+				entries.join(ENTRY_SEPARATOR)
+			));
+			if unlisted > 0 {
+				body.push_str(&format!(" and {} more", unlisted));
+			}
+		}
+		body
+	}
+
+	/// Splits the digest into messages of at most `max_bytes` bytes
+	///
+	/// A digest fitting in a single message is returned as is. Otherwise, every part repeats the
+	/// summary and lists as many entries as fit, at least one. Entries too long to fit on their
+	/// own are left to the channel's truncation.
+	///
+	/// # Arguments
+	/// * `max_bytes` - Maximum size of a message, or None if the channel does not limit it
+	///
+	/// # Returns
+	/// * `Vec<DigestPart>` - Messages to send, in order
+	pub fn paginate(&self, max_bytes: Option<usize>) -> Vec<DigestPart> {
+		let whole = DigestPart {
+			title: self.title.clone(),
+			body: self.body(&self.entries, self.unlisted),
+		};
+		let max_bytes = match max_bytes {
+			Some(max_bytes) if whole.size() > max_bytes && self.entries.len() > 1 => max_bytes,
+			_ => return vec![whole],
+		};
+
+		// Room for the entries, with the longest possible part marker and the unlisted count
+		let longest_marker = part_marker(self.entries.len(), self.entries.len()).len();
+		let longest_body = self.body(&[String::new()], self.unlisted).len();
+		let available = max_bytes.saturating_sub(
+			self.title.len() + longest_marker + longest_body + MESSAGE_FORMATTING_RESERVE,
+		);
+
+		let mut pages: Vec<&[String]> = Vec::new();
+		let mut start = 0;
+		let mut length = 0;
+		for (index, entry) in self.entries.iter().enumerate() {
+			let added = if index == start {
+				entry.len()
+			} else {
+				ENTRY_SEPARATOR.len() + entry.len()
+			};
+			if index > start && length + added > available {
+				pages.push(&self.entries[start..index]);
+				start = index;
+				length = entry.len();
+			} else {
+				length += added;
+			}
+		}
+		pages.push(&self.entries[start..]);
+
+		let count = pages.len();
+		pages
+			.into_iter()
+			.enumerate()
+			.map(|(index, entries)| DigestPart {
+				title: format!("{}{}", self.title, part_marker(index + 1, count)),
+				body: self.body(entries, if index + 1 == count { self.unlisted } else { 0 }),
+			})
+			.collect()
+	}
+}
+
+/// Returns the marker appended to the title of a part
+fn part_marker(part: usize, count: usize) -> String {
+	format!(" (part {}/{})", part, count)
+}
+
+/// Sends the parts of a digest one after another
+///
+/// Waits `delay` between two messages, including the retries of a failed part. A part is sent up
+/// to three times before moving on to the next one.
+///
+/// # Arguments
+/// * `parts` - Messages of the digest, in order
+/// * `delay` - Delay between two messages
+/// * `send` - Sends a message
+///
+/// # Returns
+/// * `Vec<usize>` - Indexes of the parts that could not be delivered
+pub async fn deliver_digest_parts<F, Fut>(
+	parts: &[DigestPart],
+	delay: Duration,
+	mut send: F,
+) -> Vec<usize>
+where
+	F: FnMut(&DigestPart) -> Fut,
+	Fut: Future<Output = Result<(), TriggerError>>,
+{
+	let mut failed = Vec::new();
+	let mut first = true;
+	for (index, part) in parts.iter().enumerate() {
+		let mut delivered = false;
+		for attempt in 1..=MAX_DIGEST_PART_ATTEMPTS {
+			if !first && !delay.is_zero() {
+				tokio::time::sleep(delay).await;
+			}
+			first = false;
+			match send(part).await {
+				Ok(()) => {
+					delivered = true;
+					break;
+				}
+				Err(e) => tracing::warn!(
+					"Failed to send part {}/{} of digest '{}' (attempt {}/{}): {}",
+					index + 1,
+					parts.len(),
+					part.title,
+					attempt,
+					MAX_DIGEST_PART_ATTEMPTS,
+					e
+				),
+			}
+		}
+		if !delivered {
+			failed.push(index);
+		}
+	}
+	failed
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::sync::{Arc, Mutex};
+
+	/// Digest of 500 matches with distinct transaction hashes
+	fn synthetic_digest() -> DigestMessage {
+		DigestMessage {
+			title: "Notification digest for busy".to_string(),
+			summary: "600 match(es) of monitor 'busy' were held back.".to_string(),
+			entries_label: "Transactions".to_string(),
+			entries: (0..500).map(|i| format!("0x{:064x}", i)).collect(),
+			unlisted: 100,
+		}
+	}
+
+	/// Returns the entries listed by a part
+	fn listed_entries(part: &DigestPart) -> Vec<String> {
+		let list = part.body.split_once("Transactions: ").unwrap().1;
+		let list = list.split(" and ").next().unwrap();
+		list.split(ENTRY_SEPARATOR).map(str::to_string).collect()
+	}
+
+	#[test]
+	fn test_digest_within_limit_is_not_split() {
+		let digest = DigestMessage {
+			entries: vec!["0x01".to_string(), "0x02".to_string()],
+			..synthetic_digest()
+		};
+
+		let parts = digest.paginate(Some(1000));
+		assert_eq!(parts.len(), 1);
+		assert_eq!(parts[0].title, "Notification digest for busy");
+		assert!(parts[0]
+			.body
+			.ends_with("Transactions: 0x01, 0x02 and 100 more"));
+		assert_eq!(digest.paginate(None), parts);
+	}
+
+	#[test]
+	fn test_digest_is_split_in_order_with_markers() {
+		let digest = synthetic_digest();
+
+		let parts = digest.paginate(Some(2000));
+		// 27 hashes of 66 bytes fit in the room left by the title, markers and summary
+		assert_eq!(parts.len(), 19);
+		for (index, part) in parts.iter().enumerate() {
+			assert_eq!(
+				part.title,
+				format!("Notification digest for busy (part {}/19)", index + 1)
+			);
+			assert!(part.body.starts_with(&digest.summary));
+			assert!(part.size() <= 2000, "part {} is too long", index + 1);
+		}
+
+		let listed = parts.iter().flat_map(listed_entries).collect::<Vec<_>>();
+		assert_eq!(listed, digest.entries);
+
+		// Only the last part reports the unlisted matches
+		let (last, others) = parts.split_last().unwrap();
+		assert!(last.body.ends_with(" and 100 more"));
+		assert!(others.iter().all(|part| !part.body.contains(" more")));
+	}
+
+	#[test]
+	fn test_entries_longer_than_the_limit_get_a_part_each() {
+		let digest = DigestMessage {
+			entries: vec!["a".repeat(200), "b".repeat(200)],
+			unlisted: 0,
+			..synthetic_digest()
+		};
+
+		let parts = digest.paginate(Some(100));
+		assert_eq!(parts.len(), 2);
+		assert_eq!(listed_entries(&parts[0]), vec!["a".repeat(200)]);
+		assert_eq!(listed_entries(&parts[1]), vec!["b".repeat(200)]);
+	}
+
+	#[tokio::test]
+	async fn test_only_failed_parts_are_retried() {
+		let parts = synthetic_digest().paginate(Some(10_000));
+		assert_eq!(parts.len(), 4);
+		let sent = Arc::new(Mutex::new(Vec::new()));
+		let failures = Arc::new(Mutex::new(1));
+
+		let failed = deliver_digest_parts(&parts, Duration::ZERO, |part| {
+			let sent = sent.clone();
+			let failures = failures.clone();
+			let title = part.title.clone();
+			async move {
+				sent.lock().unwrap().push(title.clone());
+				let mut failures = failures.lock().unwrap();
+				if title.ends_with("(part 2/4)") && *failures > 0 {
+					*failures -= 1;
+					return Err(TriggerError::execution_error("webhook down", None, None));
+				}
+				Ok(())
+			}
+		})
+		.await;
+
+		assert!(failed.is_empty());
+		let sent = sent.lock().unwrap();
+		let markers = sent
+			.iter()
+			.map(|title| title.rsplit_once(" (").unwrap().1)
+			.collect::<Vec<_>>();
+		assert_eq!(
+			markers,
+			[
+				"part 1/4)",
+				"part 2/4)",
+				"part 2/4)",
+				"part 3/4)",
+				"part 4/4)"
+			]
+		);
+	}
+
+	#[tokio::test]
+	async fn test_part_failing_every_attempt_is_reported() {
+		let parts = synthetic_digest().paginate(Some(10_000));
+		let attempts = Arc::new(Mutex::new(0));
+
+		let failed = deliver_digest_parts(&parts, Duration::ZERO, |part| {
+			let attempts = attempts.clone();
+			let fails = part.title.ends_with("(part 3/4)");
+			async move {
+				*attempts.lock().unwrap() += 1;
+				if fails {
+					return Err(TriggerError::execution_error("webhook down", None, None));
+				}
+				Ok(())
+			}
+		})
+		.await;
+
+		assert_eq!(failed, vec![2]);
+		assert_eq!(*attempts.lock().unwrap(), 3 + MAX_DIGEST_PART_ATTEMPTS);
+	}
+
+	#[tokio::test]
+	async fn test_parts_are_spaced_by_the_delay() {
+		let parts = synthetic_digest().paginate(Some(10_000));
+		let start = tokio::time::Instant::now();
+
+		deliver_digest_parts(&parts, Duration::from_millis(20), |_| async { Ok(()) }).await;
+
+		// The first part is sent right away, the other three after the delay
+		assert!(start.elapsed() >= Duration::from_millis(60));
+	}
+}
@@ -0,0 +1,397 @@
+//! Bounded queue between match filtering and trigger dispatch.
+//!
+//! Filtered matches wait in a queue of fixed capacity until a dispatch worker delivers them to
+//! their triggers. When the queue is full, producers either wait for space, which slows block
+//! processing down to the delivery rate, or drop the match and account for it in a per-monitor
+//! summary. The queue is drained on shutdown within a deadline.
+
+use futures::{future::BoxFuture, stream, FutureExt, StreamExt};
+use serde::Deserialize;
+use std::{
+	collections::HashMap,
+	panic::AssertUnwindSafe,
+	str::FromStr,
+	sync::{
+		atomic::{AtomicUsize, Ordering},
+		Arc, Mutex,
+	},
+	time::{Duration, Instant},
+};
+use tokio::{sync::mpsc, task::JoinHandle};
+
+use crate::{
+	models::Monitor,
+	services::trigger::DedupedMatch,
+	utils::metrics::{DISPATCH_QUEUE_DEPTH, DISPATCH_QUEUE_WAIT_SECONDS, DISPATCH_SHED_MATCHES},
+};
+
+/// Default number of matches the queue holds
+pub const DEFAULT_DISPATCH_QUEUE_CAPACITY: usize = 1_000;
+
+/// Default number of matches dispatched concurrently
+pub const DEFAULT_DISPATCH_CONCURRENCY: usize = 32;
+
+/// Default time given to the queue to drain on shutdown
+pub const DEFAULT_DISPATCH_DRAIN_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Function delivering a match to its triggers
+pub type DispatchFn = Arc<dyn Fn(DedupedMatch) -> BoxFuture<'static, ()> + Send + Sync>;
+
+/// What to do with a match when the queue is full
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OverflowPolicy {
+	/// Wait for space in the queue, slowing down block processing
+	#[default]
+	Block,
+	/// Drop the match and count it in the per-monitor shed summary
+	Shed,
+}
+
+impl FromStr for OverflowPolicy {
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		match s.to_lowercase().as_str() {
+			"block" => Ok(OverflowPolicy::Block),
+			"shed" => Ok(OverflowPolicy::Shed),
+			other => Err(format!(
+				"Unknown overflow policy '{}', expected 'block' or 'shed'",
+				other
+			)),
+		}
+	}
+}
+
+/// Configuration of the dispatch queue
+#[derive(Debug, Clone)]
+pub struct DispatchQueueConfig {
+	/// Maximum number of matches waiting for dispatch
+	pub capacity: usize,
+	/// Behavior when the queue is full
+	pub overflow: OverflowPolicy,
+	/// Maximum number of matches dispatched concurrently
+	pub concurrency: usize,
+	/// Time given to the queue to drain on shutdown
+	pub drain_timeout: Duration,
+}
+
+impl Default for DispatchQueueConfig {
+	fn default() -> Self {
+		Self {
+			capacity: DEFAULT_DISPATCH_QUEUE_CAPACITY,
+			overflow: OverflowPolicy::default(),
+			concurrency: DEFAULT_DISPATCH_CONCURRENCY,
+			drain_timeout: DEFAULT_DISPATCH_DRAIN_TIMEOUT,
+		}
+	}
+}
+
+/// Match waiting in the queue
+struct QueuedMatch {
+	deduped: DedupedMatch,
+	enqueued_at: Instant,
+}
+
+/// Matches dropped per monitor
+#[derive(Debug, Default)]
+struct ShedCounts {
+	/// Drops not yet reported in a summary
+	unreported: HashMap<String, u64>,
+	/// Drops since the queue was created
+	total: HashMap<String, u64>,
+}
+
+impl ShedCounts {
+	/// Logs and clears the drops not reported yet
+	fn report(&mut self) {
+		for (monitor, count) in self.unreported.drain() {
+			tracing::warn!(
+				monitor = %monitor,
+				shed = count,
+				"Dropped {} matches of monitor {} while the dispatch queue was full",
+				count,
+				monitor
+			);
+		}
+	}
+}
+
+/// Bounded queue of matches waiting to be dispatched to their triggers
+pub struct DispatchQueue {
+	/// Producer side of the queue, removed when the queue is closed
+	sender: Mutex<Option<mpsc::Sender<QueuedMatch>>>,
+	/// Number of matches in the queue
+	depth: Arc<AtomicUsize>,
+	/// Matches dropped because the queue was full
+	shed: Arc<Mutex<ShedCounts>>,
+	/// Task dispatching queued matches
+	worker: tokio::sync::Mutex<Option<JoinHandle<()>>>,
+	config: DispatchQueueConfig,
+}
+
+impl DispatchQueue {
+	/// Creates the queue and starts its dispatch worker
+	///
+	/// # Arguments
+	/// * `config` - Capacity, overflow policy and concurrency of the queue
+	/// * `dispatch` - Delivers a match to its triggers
+	pub fn start(config: DispatchQueueConfig, dispatch: DispatchFn) -> Arc<Self> {
+		let capacity = config.capacity.max(1);
+		let (sender, receiver) = mpsc::channel::<QueuedMatch>(capacity);
+		let depth = Arc::new(AtomicUsize::new(0));
+		let shed = Arc::new(Mutex::new(ShedCounts::default()));
+
+		let worker = tokio::spawn({
+			let depth = depth.clone();
+			let shed = shed.clone();
+			let concurrency = config.concurrency.max(1);
+
+			async move {
+				stream::unfold(receiver, |mut receiver| async move {
+					receiver.recv().await.map(|queued| (queued, receiver))
+				})
+				.for_each_concurrent(concurrency, |queued| {
+					let remaining = depth.fetch_sub(1, Ordering::SeqCst) - 1;
+					DISPATCH_QUEUE_DEPTH.set(remaining as f64);
+					DISPATCH_QUEUE_WAIT_SECONDS.observe(queued.enqueued_at.elapsed().as_secs_f64());
+					let dispatch = dispatch.clone();
+					let depth = depth.clone();
+					let shed = shed.clone();
+
+					async move {
+						// A panicking dispatch must not stop the worker. This only applies to
+						// builds that unwind, release builds abort on panic.
+						if AssertUnwindSafe(dispatch(queued.deduped))
+							.catch_unwind()
+							.await
+							.is_err()
+						{
+							tracing::error!("Dispatch of a match panicked, the match is dropped");
+						}
+						// Report drops once the queue has caught up
+						if depth.load(Ordering::SeqCst) == 0 {
+							shed.lock().unwrap_or_else(|e| e.into_inner()).report();
+						}
+					}
+				})
+				.await;
+			}
+		});
+
+		Arc::new(Self {
+			sender: Mutex::new(Some(sender)),
+			depth,
+			shed,
+			worker: tokio::sync::Mutex::new(Some(worker)),
+			config: DispatchQueueConfig { capacity, ..config },
+		})
+	}
+
+	/// Returns the configuration of the queue
+	pub fn config(&self) -> &DispatchQueueConfig {
+		&self.config
+	}
+
+	/// Returns the number of matches waiting in the queue
+	pub fn len(&self) -> usize {
+		self.depth.load(Ordering::SeqCst)
+	}
+
+	/// Returns true if no match is waiting in the queue
+	pub fn is_empty(&self) -> bool {
+		self.len() == 0
+	}
+
+	/// Returns the number of matches dropped per monitor since the queue was created
+	pub fn shed_summary(&self) -> HashMap<String, u64> {
+		self.shed
+			.lock()
+			.unwrap_or_else(|e| e.into_inner())
+			.total
+			.clone()
+	}
+
+	/// Adds a match to the queue
+	///
+	/// With [`OverflowPolicy::Block`], waits until the queue has space. With
+	/// [`OverflowPolicy::Shed`], drops the match if the queue is full.
+	///
+	/// # Returns
+	/// * `bool` - Whether the match was queued
+	pub async fn enqueue(&self, deduped: DedupedMatch) -> bool {
+		let Some(sender) = self
+			.sender
+			.lock()
+			.unwrap_or_else(|e| e.into_inner())
+			.clone()
+		else {
+			tracing::warn!("Dispatch queue is closed, dropping match");
+			return false;
+		};
+
+		let permit = match self.config.overflow {
+			OverflowPolicy::Block => sender.reserve().await.ok(),
+			OverflowPolicy::Shed => match sender.try_reserve() {
+				Ok(permit) => Some(permit),
+				Err(mpsc::error::TrySendError::Full(())) => {
+					self.record_shed(deduped.monitor_match.monitor());
+					return false;
+				}
+				Err(mpsc::error::TrySendError::Closed(())) => None,
+			},
+		};
+		let Some(permit) = permit else {
+			tracing::warn!("Dispatch queue is closed, dropping match");
+			return false;
+		};
+
+		let depth = self.depth.fetch_add(1, Ordering::SeqCst) + 1;
+		DISPATCH_QUEUE_DEPTH.set(depth as f64);
+		permit.send(QueuedMatch {
+			deduped,
+			enqueued_at: Instant::now(),
+		});
+		true
+	}
+
+	/// Counts a dropped match for its monitor
+	fn record_shed(&self, monitor: &Monitor) {
+		DISPATCH_SHED_MATCHES
+			.with_label_values(&[monitor.name.as_str(), monitor.owner_team()])
+			.inc();
+		let mut shed = self.shed.lock().unwrap_or_else(|e| e.into_inner());
+		*shed.total.entry(monitor.name.clone()).or_default() += 1;
+		*shed.unreported.entry(monitor.name.clone()).or_default() += 1;
+	}
+
+	/// Stops accepting matches and waits for the queued ones to be dispatched
+	///
+	/// Matches still queued after the drain timeout are dropped.
+	pub async fn close(&self) {
+		self.sender.lock().unwrap_or_else(|e| e.into_inner()).take();
+
+		let Some(mut worker) = self.worker.lock().await.take() else {
+			return;
+		};
+		if tokio::time::timeout(self.config.drain_timeout, &mut worker)
+			.await
+			.is_err()
+		{
+			worker.abort();
+			tracing::error!(
+				remaining = self.len(),
+				"Dispatch queue did not drain within {}s, dropping {} matches",
+				self.config.drain_timeout.as_secs(),
+				self.len()
+			);
+		}
+		self.shed.lock().unwrap_or_else(|e| e.into_inner()).report();
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::utils::tests::builders::evm::{
+		monitor::MonitorBuilder, monitor_match::MonitorMatchBuilder,
+	};
+	use tokio::sync::Notify;
+
+	fn create_match(monitor: &str) -> DedupedMatch {
+		MonitorMatchBuilder::new()
+			.monitor(MonitorBuilder::new().name(monitor).build())
+			.build_deduped()
+	}
+
+	#[test]
+	fn test_overflow_policy_from_str() {
+		assert_eq!(
+			"block".parse::<OverflowPolicy>().unwrap(),
+			OverflowPolicy::Block
+		);
+		assert_eq!(
+			"SHED".parse::<OverflowPolicy>().unwrap(),
+			OverflowPolicy::Shed
+		);
+		assert!("drop".parse::<OverflowPolicy>().is_err());
+	}
+
+	#[tokio::test]
+	async fn test_close_drains_queued_matches() {
+		let delivered = Arc::new(AtomicUsize::new(0));
+		let queue = DispatchQueue::start(DispatchQueueConfig::default(), {
+			let delivered = delivered.clone();
+			Arc::new(move |_| {
+				let delivered = delivered.clone();
+				Box::pin(async move {
+					tokio::time::sleep(Duration::from_millis(5)).await;
+					delivered.fetch_add(1, Ordering::SeqCst);
+				})
+			})
+		});
+
+		for _ in 0..10 {
+			assert!(queue.enqueue(create_match("monitor")).await);
+		}
+		queue.close().await;
+
+		assert_eq!(delivered.load(Ordering::SeqCst), 10);
+		assert!(queue.is_empty());
+		assert!(!queue.enqueue(create_match("monitor")).await);
+	}
+
+	#[tokio::test]
+	async fn test_close_gives_up_after_drain_timeout() {
+		let release = Arc::new(Notify::new());
+		let queue = DispatchQueue::start(
+			DispatchQueueConfig {
+				concurrency: 1,
+				drain_timeout: Duration::from_millis(50),
+				..Default::default()
+			},
+			{
+				let release = release.clone();
+				Arc::new(move |_| {
+					let release = release.clone();
+					Box::pin(async move { release.notified().await })
+				})
+			},
+		);
+
+		queue.enqueue(create_match("monitor")).await;
+		queue.enqueue(create_match("monitor")).await;
+		tokio::time::timeout(Duration::from_secs(1), queue.close())
+			.await
+			.expect("close should return after the drain timeout");
+	}
+
+	#[tokio::test]
+	async fn test_worker_survives_panicking_dispatch() {
+		let delivered = Arc::new(AtomicUsize::new(0));
+		let queue = DispatchQueue::start(
+			DispatchQueueConfig {
+				concurrency: 1,
+				..Default::default()
+			},
+			{
+				let delivered = delivered.clone();
+				Arc::new(move |deduped: DedupedMatch| {
+					let delivered = delivered.clone();
+					Box::pin(async move {
+						if deduped.monitor_match.monitor().name == "panicking" {
+							panic!("dispatch panicked");
+						}
+						delivered.fetch_add(1, Ordering::SeqCst);
+					})
+				})
+			},
+		);
+
+		queue.enqueue(create_match("panicking")).await;
+		queue.enqueue(create_match("monitor")).await;
+		queue.close().await;
+
+		assert_eq!(delivered.load(Ordering::SeqCst), 1);
+	}
+}
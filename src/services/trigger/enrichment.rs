@@ -0,0 +1,359 @@
+//! Enrichment of monitor matches with external data.
+//!
+//! Monitors can declare enrichments providing context the chain doesn't have, such as internal
+//! customer ids or token prices, from an HTTP endpoint or a script. Enrichments run between
+//! match production and dispatch, and their variables are exposed to the templates of all the
+//! monitor's triggers. A failed enrichment only leaves its variables unset, unless it is
+//! required.
+
+use std::{
+	collections::HashMap,
+	sync::Mutex,
+	time::{Duration, Instant},
+};
+
+use futures::future::join_all;
+use serde_json::Value as JsonValue;
+
+use crate::{
+	models::{Enrichment, HttpEnrichment, MonitorMatch, ScriptEnrichment, ScriptLanguage},
+	services::{
+		notification::payload_builder::format_template,
+		trigger::{error::TriggerError, script::execute_script_for_output},
+	},
+	utils::{metrics::ENRICHMENT_FAILURES, normalize_string},
+};
+
+/// Default maximum number of cached HTTP enrichment results
+pub const DEFAULT_ENRICHMENT_CACHE_CAPACITY: usize = 10_000;
+
+/// Cached variables with their expiry, keyed by monitor, enrichment and URL
+type CachedVariables = HashMap<String, (Instant, HashMap<String, String>)>;
+
+/// Runs the enrichments of monitor matches
+///
+/// Results of HTTP enrichments with a `cache_ttl_secs` are cached by requested URL.
+pub struct Enricher {
+	/// Client used by HTTP enrichments
+	client: reqwest::Client,
+	/// Cached HTTP enrichment results
+	cache: Mutex<CachedVariables>,
+	/// Number of entries after which expired entries are removed, and the cache cleared if
+	/// none expired
+	capacity: usize,
+}
+
+impl Default for Enricher {
+	fn default() -> Self {
+		Self::new(DEFAULT_ENRICHMENT_CACHE_CAPACITY)
+	}
+}
+
+impl Enricher {
+	/// Creates an enricher caching at most `capacity` HTTP results
+	pub fn new(capacity: usize) -> Self {
+		Self {
+			client: reqwest::Client::new(),
+			cache: Mutex::new(HashMap::new()),
+			capacity: capacity.max(1),
+		}
+	}
+
+	/// Runs the enrichments of a match's monitor
+	///
+	/// Enrichments run concurrently. When several enrichments provide the same variable, the
+	/// one declared last wins. Failures are logged and counted in `enrichment_failures_total`.
+	///
+	/// # Arguments
+	/// * `monitor_match` - The match to enrich
+	/// * `variables` - Template variables of the match, substituted in HTTP URLs
+	/// * `trigger_scripts` - Loaded scripts, including the enrichment scripts
+	///
+	/// # Returns
+	/// * `Result<HashMap<String, String>, TriggerError>` - The variables of the successful
+	///   enrichments, or an error if a required enrichment failed
+	pub async fn enrich(
+		&self,
+		monitor_match: &MonitorMatch,
+		variables: &HashMap<String, String>,
+		trigger_scripts: &HashMap<String, (ScriptLanguage, String)>,
+	) -> Result<HashMap<String, String>, TriggerError> {
+		let monitor = monitor_match.monitor();
+		let results = join_all(monitor.enrichments.iter().map(|enrichment| async move {
+			let result = match enrichment {
+				Enrichment::Http(http) => self.fetch_http(&monitor.name, http, variables).await,
+				Enrichment::Script(script) => {
+					run_script(monitor_match, script, trigger_scripts).await
+				}
+			};
+			(enrichment, result)
+		}))
+		.await;
+
+		let mut enriched = HashMap::new();
+		for (enrichment, result) in results {
+			match result {
+				Ok(values) => enriched.extend(values),
+				Err(e) => {
+					ENRICHMENT_FAILURES
+						.with_label_values(&[
+							monitor.name.as_str(),
+							monitor.owner_team(),
+							enrichment.name(),
+						])
+						.inc();
+					if enrichment.required() {
+						return Err(TriggerError::execution_error_without_log(
+							format!(
+								"Required enrichment '{}' of monitor '{}' failed",
+								enrichment.name(),
+								monitor.name
+							),
+							Some(e.into()),
+							None,
+						));
+					}
+					tracing::warn!(
+						"Enrichment '{}' of monitor '{}' failed, its variables are left unset: {:#}",
+						enrichment.name(),
+						monitor.name,
+						e
+					);
+				}
+			}
+		}
+		Ok(enriched)
+	}
+
+	/// Fetches the variables of an HTTP enrichment, from the cache if possible
+	async fn fetch_http(
+		&self,
+		monitor_name: &str,
+		enrichment: &HttpEnrichment,
+		variables: &HashMap<String, String>,
+	) -> Result<HashMap<String, String>, anyhow::Error> {
+		let url = format_template(&enrichment.url, variables);
+		let cache_key = format!(
+			"{}|{}|{}",
+			normalize_string(monitor_name),
+			enrichment.name,
+			url
+		);
+		let cache_ttl = enrichment.cache_ttl_secs.map(Duration::from_secs);
+		if cache_ttl.is_some() {
+			if let Some(cached) = self.cached(&cache_key) {
+				return Ok(cached);
+			}
+		}
+
+		let response = self
+			.client
+			.get(&url)
+			.timeout(Duration::from_millis(u64::from(enrichment.timeout_ms)))
+			.send()
+			.await?
+			.error_for_status()?;
+		let body: JsonValue = response.json().await?;
+		let values = enrichment
+			.variables
+			.iter()
+			.map(|(name, pointer)| {
+				body.pointer(pointer)
+					.map(|value| (name.clone(), variable_value(value)))
+					.ok_or_else(|| anyhow::anyhow!("response has no value at '{}'", pointer))
+			})
+			.collect::<Result<HashMap<_, _>, _>>()?;
+
+		if let Some(ttl) = cache_ttl {
+			self.store(cache_key, Instant::now() + ttl, values.clone());
+		}
+		Ok(values)
+	}
+
+	fn cached(&self, key: &str) -> Option<HashMap<String, String>> {
+		let cache = self.cache.lock().unwrap_or_else(|e| e.into_inner());
+		cache
+			.get(key)
+			.filter(|(expires_at, _)| *expires_at > Instant::now())
+			.map(|(_, values)| values.clone())
+	}
+
+	fn store(&self, key: String, expires_at: Instant, values: HashMap<String, String>) {
+		let mut cache = self.cache.lock().unwrap_or_else(|e| e.into_inner());
+		if cache.len() >= self.capacity {
+			let now = Instant::now();
+			cache.retain(|_, (expires_at, _)| *expires_at > now);
+			if cache.len() >= self.capacity {
+				cache.clear();
+			}
+		}
+		cache.insert(key, (expires_at, values));
+	}
+}
+
+/// Runs a script enrichment and parses the JSON object it printed
+async fn run_script(
+	monitor_match: &MonitorMatch,
+	enrichment: &ScriptEnrichment,
+	trigger_scripts: &HashMap<String, (ScriptLanguage, String)>,
+) -> Result<HashMap<String, String>, anyhow::Error> {
+	let script_key = format!(
+		"{}|{}",
+		normalize_string(&monitor_match.monitor().name),
+		enrichment.script_path
+	);
+	let (language, content) = trigger_scripts
+		.get(&script_key)
+		.ok_or_else(|| anyhow::anyhow!("script {} is not loaded", enrichment.script_path))?;
+	let output = execute_script_for_output(
+		language,
+		content,
+		monitor_match,
+		enrichment.timeout_ms,
+		enrichment.arguments.as_deref(),
+	)
+	.await?;
+	match serde_json::from_str::<JsonValue>(&output) {
+		Ok(JsonValue::Object(object)) => Ok(object
+			.iter()
+			.map(|(name, value)| (name.clone(), variable_value(value)))
+			.collect()),
+		_ => Err(anyhow::anyhow!(
+			"last line of output is not a JSON object: {}",
+			output
+		)),
+	}
+}
+
+/// Converts a JSON value into a template variable, strings are used without their quotes
+fn variable_value(value: &JsonValue) -> String {
+	match value {
+		JsonValue::String(value) => value.clone(),
+		value => value.to_string(),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{
+		models::Monitor,
+		utils::tests::evm::{monitor::MonitorBuilder, monitor_match::MonitorMatchBuilder},
+	};
+	use serde_json::json;
+
+	fn create_match(monitor: Monitor) -> MonitorMatch {
+		MonitorMatchBuilder::new().monitor(monitor).build()
+	}
+
+	fn script_enrichment(name: &str, required: bool) -> ScriptEnrichment {
+		ScriptEnrichment {
+			name: name.to_string(),
+			script_path: format!("config/filters/{}.sh", name),
+			language: ScriptLanguage::Bash,
+			arguments: None,
+			timeout_ms: 1_000,
+			required,
+		}
+	}
+
+	fn scripts(entries: &[(&str, &str)]) -> HashMap<String, (ScriptLanguage, String)> {
+		entries
+			.iter()
+			.map(|(name, content)| {
+				(
+					format!("test|config/filters/{}.sh", name),
+					(ScriptLanguage::Bash, content.to_string()),
+				)
+			})
+			.collect()
+	}
+
+	#[test]
+	fn test_variable_value() {
+		assert_eq!(variable_value(&json!("alice")), "alice");
+		assert_eq!(variable_value(&json!(42)), "42");
+		assert_eq!(variable_value(&json!(true)), "true");
+		assert_eq!(variable_value(&json!({"a": 1})), "{\"a\":1}");
+	}
+
+	#[tokio::test]
+	async fn test_script_enrichment_provides_variables() {
+		let monitor = MonitorBuilder::new()
+			.name("test")
+			.enrichment(Enrichment::Script(script_enrichment("customer", false)))
+			.build();
+		let trigger_scripts = scripts(&[(
+			"customer",
+			r#"echo "looking up"; echo '{"customer_id": "c-42", "risk": 7}'"#,
+		)]);
+
+		let enriched = Enricher::default()
+			.enrich(&create_match(monitor), &HashMap::new(), &trigger_scripts)
+			.await
+			.unwrap();
+
+		assert_eq!(enriched.get("customer_id").unwrap(), "c-42");
+		assert_eq!(enriched.get("risk").unwrap(), "7");
+	}
+
+	#[tokio::test]
+	async fn test_failed_optional_enrichment_leaves_variables_unset() {
+		let monitor = MonitorBuilder::new()
+			.name("test")
+			.enrichment(Enrichment::Script(script_enrichment("broken", false)))
+			.enrichment(Enrichment::Script(script_enrichment("price", false)))
+			.build();
+		let trigger_scripts = scripts(&[
+			("broken", "echo 'not json'"),
+			("price", r#"echo '{"price": "1.01"}'"#),
+		]);
+
+		let enriched = Enricher::default()
+			.enrich(&create_match(monitor), &HashMap::new(), &trigger_scripts)
+			.await
+			.unwrap();
+
+		assert_eq!(enriched, HashMap::from([("price".into(), "1.01".into())]));
+	}
+
+	#[tokio::test]
+	async fn test_failed_required_enrichment_is_an_error() {
+		let monitor = MonitorBuilder::new()
+			.name("test")
+			.enrichment(Enrichment::Script(script_enrichment("broken", true)))
+			.build();
+		let trigger_scripts = scripts(&[("broken", "exit 1")]);
+
+		let error = Enricher::default()
+			.enrich(&create_match(monitor), &HashMap::new(), &trigger_scripts)
+			.await
+			.unwrap_err();
+
+		assert!(error
+			.to_string()
+			.contains("Required enrichment 'broken' of monitor 'test' failed"));
+	}
+
+	#[test]
+	fn test_cache_expiry_and_capacity() {
+		let enricher = Enricher::new(2);
+		let values = HashMap::from([("price".to_string(), "1".to_string())]);
+		let later = Instant::now() + Duration::from_secs(60);
+
+		enricher.store("expired".into(), Instant::now(), values.clone());
+		enricher.store("a".into(), later, values.clone());
+		assert!(enricher.cached("expired").is_none());
+		assert_eq!(enricher.cached("a"), Some(values.clone()));
+
+		// The expired entry makes room for the new one
+		enricher.store("b".into(), later, values.clone());
+		assert!(enricher.cached("a").is_some());
+		assert!(enricher.cached("b").is_some());
+
+		// Without expired entries, the cache is cleared
+		enricher.store("c".into(), later, values.clone());
+		assert!(enricher.cached("a").is_none());
+		assert!(enricher.cached("c").is_some());
+	}
+}
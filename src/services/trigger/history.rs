@@ -0,0 +1,783 @@
+//! Prior occurrences of monitor matches.
+//!
+//! Monitors with a `history` configuration look up, at dispatch time, how many similar matches
+//! they produced before. Matches are similar when they come from the same monitor and matched the
+//! same function and event signatures, and optionally were sent by the same account. The result
+//! is exposed to templates as `first_occurrence`, `prior_count` and `last_seen_at`, and monitors
+//! with `first_occurrence_only` only deliver the first similar match.
+//!
+//! Triggers with a `context` include the most recent earlier matches of the monitor in their
+//! notifications, optionally only those sent by the same account. The matches of monitors
+//! having such a trigger are recorded with their time, transaction and matched conditions, and
+//! exposed to templates as the `prior` list.
+//!
+//! Occurrences are kept in the state store, so they survive restarts and are shared by the
+//! instances using the same store. Occurrence lookups are cached, and all lookups are bounded by
+//! a time budget so that a slow store cannot delay alerts: when it is exceeded, the variables
+//! are left unset, or the `prior` list empty, and the match is delivered.
+
+use std::{
+	collections::HashMap,
+	future::Future,
+	sync::{Arc, Mutex},
+	time::Duration,
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+	models::{MatchHistoryConfig, MonitorMatch, TriggerContext},
+	services::{state::StateStore, trigger::rollup::matched_conditions},
+	utils::metrics::MATCH_HISTORY_TIMEOUTS,
+};
+
+/// Default time a lookup may take before the match is dispatched without its history
+pub const DEFAULT_MATCH_HISTORY_TIMEOUT: Duration = Duration::from_millis(100);
+
+/// Default maximum number of cached lookups
+pub const DEFAULT_MATCH_HISTORY_CACHE_CAPACITY: usize = 10_000;
+
+/// Default file the occurrences are persisted to when no state store is configured
+pub const DEFAULT_MATCH_HISTORY_STATE_FILE: &str = "data/match_history.json";
+
+/// Prefix of the state store keys holding the occurrences of similar matches
+const STATE_KEY_PREFIX: &str = "history:";
+
+/// Prefix of the state store keys holding the recent matches of monitors
+const RECENT_KEY_PREFIX: &str = "recent:";
+
+/// Number of recent matches kept per monitor, the most a trigger `context` can include
+const RECENT_MATCHES_CAPACITY: usize = u8::MAX as usize;
+
+/// Variable set to `true` for the first similar match, `false` otherwise
+pub const FIRST_OCCURRENCE_VARIABLE: &str = "first_occurrence";
+
+/// Variable holding the number of prior similar matches
+pub const PRIOR_COUNT_VARIABLE: &str = "prior_count";
+
+/// Variable holding the time of the last similar match in RFC 3339 format, unset for the first
+pub const LAST_SEEN_AT_VARIABLE: &str = "last_seen_at";
+
+/// Variable listing the earlier matches included by a trigger's `context`, one per line
+pub const PRIOR_VARIABLE: &str = "prior";
+
+/// Variable holding the number of earlier matches included by a trigger's `context`
+pub const PRIOR_MATCHES_COUNT_VARIABLE: &str = "prior.count";
+
+/// Prior similar matches of a match
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PriorOccurrences {
+	/// Number of prior similar matches
+	pub count: u64,
+	/// Time of the last similar match in milliseconds since the Unix epoch
+	pub last_seen_at_ms: Option<i64>,
+}
+
+impl PriorOccurrences {
+	/// Returns whether no similar match was seen before
+	pub fn is_first(&self) -> bool {
+		self.count == 0
+	}
+
+	/// Returns the template variables describing the prior occurrences
+	pub fn variables(&self) -> HashMap<String, String> {
+		let mut variables = HashMap::from([
+			(
+				FIRST_OCCURRENCE_VARIABLE.to_string(),
+				self.is_first().to_string(),
+			),
+			(PRIOR_COUNT_VARIABLE.to_string(), self.count.to_string()),
+		]);
+		if let Some(last_seen_at) = self
+			.last_seen_at_ms
+			.and_then(chrono::DateTime::from_timestamp_millis)
+		{
+			variables.insert(LAST_SEEN_AT_VARIABLE.to_string(), last_seen_at.to_rfc3339());
+		}
+		variables
+	}
+}
+
+/// Earlier match of a monitor, included in the notifications of triggers with a `context`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PriorMatch {
+	/// Time of the match in milliseconds since the Unix epoch
+	pub timestamp_ms: i64,
+	/// Hash of the matched transaction
+	pub transaction_hash: String,
+	/// Conditions the match was found on
+	pub summary: String,
+}
+
+/// Returns the template variables listing earlier matches, the most recent first
+///
+/// An empty list sets `prior` to an empty string and `prior.count` to 0.
+pub fn prior_match_variables(prior: &[PriorMatch]) -> HashMap<String, String> {
+	let mut variables = HashMap::from([(
+		PRIOR_MATCHES_COUNT_VARIABLE.to_string(),
+		prior.len().to_string(),
+	)]);
+	let mut lines = Vec::with_capacity(prior.len());
+	for (index, prior_match) in prior.iter().enumerate() {
+		let timestamp = chrono::DateTime::from_timestamp_millis(prior_match.timestamp_ms)
+			.map_or_else(
+				|| prior_match.timestamp_ms.to_string(),
+				|time| time.to_rfc3339(),
+			);
+		lines.push(format!(
+			"- {}: {} in {}",
+			timestamp, prior_match.summary, prior_match.transaction_hash
+		));
+		variables.extend([
+			(format!("prior.{}.timestamp", index), timestamp),
+			(
+				format!("prior.{}.transaction_hash", index),
+				prior_match.transaction_hash.clone(),
+			),
+			(
+				format!("prior.{}.summary", index),
+				prior_match.summary.clone(),
+			),
+		]);
+	}
+	variables.insert(PRIOR_VARIABLE.to_string(), lines.join("\n"));
+	variables
+}
+
+/// Cached lookups keyed by similarity key, with the tick of their last use
+type CachedOccurrences = HashMap<String, (u64, PriorOccurrences)>;
+
+/// Looks up and records the occurrences of similar matches
+pub struct MatchHistory {
+	/// Store the occurrences are kept in
+	store: Arc<dyn StateStore>,
+	/// Time a lookup may take
+	timeout: Duration,
+	/// Cached lookups, updated with the occurrences recorded by this instance
+	cache: Mutex<(u64, CachedOccurrences)>,
+	/// Number of cached lookups after which the least recently used one is evicted
+	capacity: usize,
+	/// Serializes the updates of the recent matches made by this instance
+	recent_updates: Arc<tokio::sync::Mutex<()>>,
+}
+
+impl MatchHistory {
+	/// Creates a match history kept in the given store
+	///
+	/// # Arguments
+	/// * `store` - Store the occurrences are kept in
+	/// * `timeout` - Time a lookup may take before the match is dispatched without its history
+	/// * `capacity` - Maximum number of cached lookups
+	pub fn new(store: Arc<dyn StateStore>, timeout: Duration, capacity: usize) -> Self {
+		Self {
+			store,
+			timeout,
+			cache: Mutex::new((0, HashMap::new())),
+			capacity: capacity.max(1),
+			recent_updates: Arc::new(tokio::sync::Mutex::new(())),
+		}
+	}
+
+	/// Applies the history configuration of a match's monitor before dispatch
+	///
+	/// Adds the history variables of the match and records it. Matches of monitors without a
+	/// `history` configuration are left untouched.
+	///
+	/// # Arguments
+	/// * `monitor_match` - Match being dispatched
+	/// * `variables` - Template variables of the match, extended with the history variables
+	///
+	/// # Returns
+	/// * `bool` - Whether the match should be delivered, false for a repeat of a monitor with
+	///   `first_occurrence_only`
+	pub async fn apply(
+		&self,
+		monitor_match: &MonitorMatch,
+		variables: &mut HashMap<String, String>,
+	) -> bool {
+		let Some(config) = monitor_match.monitor().history.clone() else {
+			return true;
+		};
+		let Some(prior) = self.check(monitor_match, variables, &config).await else {
+			return true;
+		};
+		variables.extend(prior.variables());
+		prior.is_first() || !config.first_occurrence_only
+	}
+
+	/// Returns the prior occurrences of similar matches and records this one
+	///
+	/// # Arguments
+	/// * `monitor_match` - Match being dispatched
+	/// * `variables` - Template variables of the match, providing its sender
+	/// * `config` - History configuration of the match's monitor
+	///
+	/// # Returns
+	/// * `Option<PriorOccurrences>` - The prior occurrences, or None if the lookup failed or
+	///   exceeded the time budget
+	pub async fn check(
+		&self,
+		monitor_match: &MonitorMatch,
+		variables: &HashMap<String, String>,
+		config: &MatchHistoryConfig,
+	) -> Option<PriorOccurrences> {
+		let key = similarity_key(monitor_match, variables, config);
+		let monitor_name = &monitor_match.monitor().name;
+		let now_ms = chrono::Utc::now().timestamp_millis();
+
+		let prior = match self.cached(&key) {
+			Some(prior) => Some(prior),
+			None => self.bounded(monitor_name, self.lookup(&key)).await,
+		};
+
+		if let Some(prior) = prior {
+			self.cache(
+				&key,
+				PriorOccurrences {
+					count: prior.count + 1,
+					last_seen_at_ms: Some(now_ms),
+				},
+			);
+		}
+		self.record(key, now_ms);
+		prior
+	}
+
+	/// Returns the most recent earlier matches of a match's monitor, the most recent first
+	///
+	/// Matches of the transaction being dispatched are left out.
+	///
+	/// # Arguments
+	/// * `monitor_match` - Match being dispatched
+	/// * `variables` - Template variables of the match, providing its sender
+	/// * `context` - Context of the trigger the matches are included by
+	///
+	/// # Returns
+	/// * `Vec<PriorMatch>` - Up to `prior_matches` earlier matches, empty if the lookup failed
+	///   or exceeded the time budget
+	pub async fn prior_matches(
+		&self,
+		monitor_match: &MonitorMatch,
+		variables: &HashMap<String, String>,
+		context: &TriggerContext,
+	) -> Vec<PriorMatch> {
+		let key = recent_key(monitor_match, variables, context.by_counterparty);
+		let transaction_hash = monitor_match.transaction_hash();
+		let lookup = read_recent(self.store.as_ref(), &key);
+		self.bounded(&monitor_match.monitor().name, lookup)
+			.await
+			.unwrap_or_default()
+			.into_iter()
+			.filter(|prior_match| prior_match.transaction_hash != transaction_hash)
+			.take(context.prior_matches as usize)
+			.collect()
+	}
+
+	/// Records a match as the most recent of its monitor in the background, logging failures
+	///
+	/// The match is added to the recent matches of the monitor and to those of the sender of
+	/// its transaction, keeping the last `RECENT_MATCHES_CAPACITY` of each.
+	///
+	/// # Arguments
+	/// * `monitor_match` - Match being dispatched
+	/// * `variables` - Template variables of the match, providing its sender
+	pub fn record_recent(&self, monitor_match: &MonitorMatch, variables: &HashMap<String, String>) {
+		let keys = [
+			recent_key(monitor_match, variables, false),
+			recent_key(monitor_match, variables, true),
+		];
+		let prior_match = PriorMatch {
+			timestamp_ms: chrono::Utc::now().timestamp_millis(),
+			transaction_hash: monitor_match.transaction_hash(),
+			summary: matched_conditions(monitor_match),
+		};
+		let store = self.store.clone();
+		let recent_updates = self.recent_updates.clone();
+		tokio::spawn(async move {
+			let _guard = recent_updates.lock().await;
+			for key in keys {
+				let result = push_recent(store.as_ref(), &key, prior_match.clone()).await;
+				if let Err(e) = result {
+					tracing::error!("Failed to record the recent match {}: {}", key, e);
+				}
+			}
+		});
+	}
+
+	/// Runs a lookup of the store within the time budget, logging failures and timeouts
+	async fn bounded<T>(
+		&self,
+		monitor_name: &str,
+		lookup: impl Future<Output = Result<T, anyhow::Error>>,
+	) -> Option<T> {
+		match tokio::time::timeout(self.timeout, lookup).await {
+			Ok(Ok(value)) => Some(value),
+			Ok(Err(e)) => {
+				tracing::warn!(
+					"Failed to look up the history of monitor '{}', dispatching without it: {}",
+					monitor_name,
+					e
+				);
+				None
+			}
+			Err(_) => {
+				tracing::warn!(
+					"History lookup of monitor '{}' exceeded {:?}, dispatching without it",
+					monitor_name,
+					self.timeout
+				);
+				MATCH_HISTORY_TIMEOUTS
+					.with_label_values(&[monitor_name])
+					.inc();
+				None
+			}
+		}
+	}
+
+	/// Reads the occurrences of similar matches from the store
+	async fn lookup(&self, key: &str) -> Result<PriorOccurrences, anyhow::Error> {
+		let count = self.store.get(&count_key(key)).await?;
+		let last_seen_at = self.store.get(&last_seen_key(key)).await?;
+		Ok(PriorOccurrences {
+			count: count.and_then(|v| v.parse().ok()).unwrap_or_default(),
+			last_seen_at_ms: last_seen_at.and_then(|v| v.parse().ok()),
+		})
+	}
+
+	/// Records an occurrence in the store in the background, logging failures
+	fn record(&self, key: String, now_ms: i64) {
+		let store = self.store.clone();
+		tokio::spawn(async move {
+			let result = async {
+				store.increment(&count_key(&key), 1, None).await?;
+				store
+					.set(&last_seen_key(&key), &now_ms.to_string(), None)
+					.await
+			}
+			.await;
+			if let Err(e) = result {
+				tracing::error!("Failed to record the occurrence {}: {}", key, e);
+			}
+		});
+	}
+
+	/// Returns the cached occurrences of a key, marking them as recently used
+	fn cached(&self, key: &str) -> Option<PriorOccurrences> {
+		let mut cache = self.cache.lock().unwrap_or_else(|e| e.into_inner());
+		let (tick, entries) = &mut *cache;
+		*tick += 1;
+		let (used, prior) = entries.get_mut(key)?;
+		*used = *tick;
+		Some(*prior)
+	}
+
+	/// Caches the occurrences of a key, evicting the least recently used key if full
+	fn cache(&self, key: &str, prior: PriorOccurrences) {
+		let mut cache = self.cache.lock().unwrap_or_else(|e| e.into_inner());
+		let (tick, entries) = &mut *cache;
+		*tick += 1;
+		if !entries.contains_key(key) && entries.len() >= self.capacity {
+			if let Some(oldest) = entries
+				.iter()
+				.min_by_key(|(_, (used, _))| *used)
+				.map(|(key, _)| key.clone())
+			{
+				entries.remove(&oldest);
+			}
+		}
+		entries.insert(key.to_string(), (*tick, prior));
+	}
+}
+
+/// Returns the key identifying the matches similar to a match
+///
+/// The key is made of the monitor name, the sorted signatures of the matched functions and
+/// events, and, with `by_counterparty`, the sender of the transaction.
+fn similarity_key(
+	monitor_match: &MonitorMatch,
+	variables: &HashMap<String, String>,
+	config: &MatchHistoryConfig,
+) -> String {
+	let matched_on = monitor_match.matched_on();
+	let mut signatures: Vec<&str> = matched_on
+		.functions
+		.iter()
+		.map(|function| function.signature.as_str())
+		.chain(
+			matched_on
+				.events
+				.iter()
+				.map(|event| event.signature.as_str()),
+		)
+		.collect();
+	signatures.sort_unstable();
+	signatures.dedup();
+
+	let mut key = format!(
+		"{}{}:{}",
+		STATE_KEY_PREFIX,
+		monitor_match.monitor().name,
+		signatures.join(",")
+	);
+	if config.by_counterparty {
+		let sender = variables
+			.get("transaction.from")
+			.map(|from| from.to_lowercase())
+			.unwrap_or_default();
+		key.push(':');
+		key.push_str(&sender);
+	}
+	key
+}
+
+/// Returns the key of the recent matches of a match's monitor, or, with `by_counterparty`, of
+/// those sent by the sender of its transaction
+fn recent_key(
+	monitor_match: &MonitorMatch,
+	variables: &HashMap<String, String>,
+	by_counterparty: bool,
+) -> String {
+	let mut key = format!("{}{}", RECENT_KEY_PREFIX, monitor_match.monitor().name);
+	if by_counterparty {
+		let sender = variables
+			.get("transaction.from")
+			.map(|from| from.to_lowercase())
+			.unwrap_or_default();
+		key.push(':');
+		key.push_str(&sender);
+	}
+	key
+}
+
+/// Reads the recent matches kept under a key, the most recent first
+async fn read_recent(store: &dyn StateStore, key: &str) -> Result<Vec<PriorMatch>, anyhow::Error> {
+	match store.get(key).await? {
+		Some(recent) => Ok(serde_json::from_str(&recent)?),
+		None => Ok(vec![]),
+	}
+}
+
+/// Adds a match to the recent matches kept under a key, dropping the oldest beyond capacity
+async fn push_recent(
+	store: &dyn StateStore,
+	key: &str,
+	prior_match: PriorMatch,
+) -> Result<(), anyhow::Error> {
+	let mut recent = read_recent(store, key).await?;
+	recent.insert(0, prior_match);
+	recent.truncate(RECENT_MATCHES_CAPACITY);
+	store.set(key, &serde_json::to_string(&recent)?, None).await
+}
+
+/// Key of the number of occurrences of similar matches
+fn count_key(key: &str) -> String {
+	format!("{}:count", key)
+}
+
+/// Key of the time of the last of similar matches
+fn last_seen_key(key: &str) -> String {
+	format!("{}:last_seen_at", key)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{
+		models::{EventCondition, MatchConditions},
+		services::state::InMemoryStateStore,
+		utils::tests::builders::evm::{
+			monitor::MonitorBuilder, monitor_match::MonitorMatchBuilder,
+			transaction::TransactionBuilder,
+		},
+	};
+	use async_trait::async_trait;
+
+	/// Store answering reads after a delay
+	struct SlowStateStore {
+		inner: InMemoryStateStore,
+		delay: Duration,
+	}
+
+	#[async_trait]
+	impl StateStore for SlowStateStore {
+		async fn get(&self, key: &str) -> Result<Option<String>, anyhow::Error> {
+			tokio::time::sleep(self.delay).await;
+			self.inner.get(key).await
+		}
+
+		async fn set(
+			&self,
+			key: &str,
+			value: &str,
+			ttl: Option<Duration>,
+		) -> Result<(), anyhow::Error> {
+			self.inner.set(key, value, ttl).await
+		}
+
+		async fn increment(
+			&self,
+			key: &str,
+			delta: i64,
+			ttl: Option<Duration>,
+		) -> Result<i64, anyhow::Error> {
+			self.inner.increment(key, delta, ttl).await
+		}
+
+		async fn window_add(
+			&self,
+			key: &str,
+			timestamp_ms: i64,
+			window: Duration,
+		) -> Result<u64, anyhow::Error> {
+			self.inner.window_add(key, timestamp_ms, window).await
+		}
+
+		async fn window_count(
+			&self,
+			key: &str,
+			now_ms: i64,
+			window: Duration,
+		) -> Result<u64, anyhow::Error> {
+			self.inner.window_count(key, now_ms, window).await
+		}
+	}
+
+	fn history_match(config: MatchHistoryConfig) -> MonitorMatch {
+		let monitor = MonitorBuilder::new()
+			.name("history_monitor")
+			.history(config)
+			.build();
+		MonitorMatchBuilder::new()
+			.monitor(monitor)
+			.matched_on(MatchConditions {
+				events: vec![EventCondition {
+					signature: "Transfer(address,address,uint256)".to_string(),
+					expression: None,
+					match_any_emitter: false,
+				}],
+				..Default::default()
+			})
+			.build()
+	}
+
+	fn sender(from: &str) -> HashMap<String, String> {
+		HashMap::from([("transaction.from".to_string(), from.to_string())])
+	}
+
+	#[tokio::test]
+	async fn test_repeat_match_reads_seeded_history() {
+		let store = Arc::new(InMemoryStateStore::new());
+		let monitor_match = history_match(MatchHistoryConfig::default());
+		let key = similarity_key(
+			&monitor_match,
+			&HashMap::new(),
+			&MatchHistoryConfig::default(),
+		);
+		store.set(&count_key(&key), "2", None).await.unwrap();
+		store
+			.set(&last_seen_key(&key), "1700000000000", None)
+			.await
+			.unwrap();
+		let history = MatchHistory::new(store.clone(), DEFAULT_MATCH_HISTORY_TIMEOUT, 10);
+
+		let mut variables = HashMap::new();
+		assert!(history.apply(&monitor_match, &mut variables).await);
+		assert_eq!(variables[FIRST_OCCURRENCE_VARIABLE], "false");
+		assert_eq!(variables[PRIOR_COUNT_VARIABLE], "2");
+		assert_eq!(
+			variables[LAST_SEEN_AT_VARIABLE],
+			"2023-11-14T22:13:20+00:00"
+		);
+
+		let mut variables = HashMap::new();
+		assert!(history.apply(&monitor_match, &mut variables).await);
+		assert_eq!(variables[PRIOR_COUNT_VARIABLE], "3");
+
+		tokio::time::sleep(Duration::from_millis(20)).await;
+		assert_eq!(
+			store.get(&count_key(&key)).await.unwrap().as_deref(),
+			Some("4")
+		);
+	}
+
+	#[tokio::test]
+	async fn test_first_occurrence_only_drops_repeats() {
+		let history = MatchHistory::new(
+			Arc::new(InMemoryStateStore::new()),
+			DEFAULT_MATCH_HISTORY_TIMEOUT,
+			10,
+		);
+		let monitor_match = history_match(MatchHistoryConfig {
+			by_counterparty: true,
+			first_occurrence_only: true,
+		});
+
+		let mut variables = sender("0xAAAA");
+		assert!(history.apply(&monitor_match, &mut variables).await);
+		assert_eq!(variables[FIRST_OCCURRENCE_VARIABLE], "true");
+		assert_eq!(variables[PRIOR_COUNT_VARIABLE], "0");
+		assert!(!variables.contains_key(LAST_SEEN_AT_VARIABLE));
+
+		assert!(!history.apply(&monitor_match, &mut sender("0xaaaa")).await);
+		assert!(history.apply(&monitor_match, &mut sender("0xbbbb")).await);
+	}
+
+	#[tokio::test]
+	async fn test_slow_store_dispatches_without_history() {
+		let store = Arc::new(SlowStateStore {
+			inner: InMemoryStateStore::new(),
+			delay: Duration::from_millis(200),
+		});
+		let history = MatchHistory::new(store, Duration::from_millis(10), 10);
+		let monitor_match = history_match(MatchHistoryConfig {
+			by_counterparty: false,
+			first_occurrence_only: true,
+		});
+
+		for _ in 0..2 {
+			let mut variables = HashMap::new();
+			assert!(history.apply(&monitor_match, &mut variables).await);
+			assert!(variables.is_empty());
+		}
+	}
+
+	fn seeded_prior_matches() -> Vec<PriorMatch> {
+		(1..=3)
+			.map(|index| PriorMatch {
+				timestamp_ms: 1_700_000_000_000 - index * 60_000,
+				transaction_hash: format!("0x{:064x}", index),
+				summary: "Transfer(address,address,uint256)".to_string(),
+			})
+			.collect()
+	}
+
+	#[tokio::test]
+	async fn test_prior_matches_renders_seeded_matches() {
+		let store = Arc::new(InMemoryStateStore::new());
+		let monitor_match = history_match(MatchHistoryConfig::default());
+		store
+			.set(
+				&recent_key(&monitor_match, &HashMap::new(), false),
+				&serde_json::to_string(&seeded_prior_matches()).unwrap(),
+				None,
+			)
+			.await
+			.unwrap();
+		let history = MatchHistory::new(store, DEFAULT_MATCH_HISTORY_TIMEOUT, 10);
+		let context = TriggerContext {
+			prior_matches: 3,
+			by_counterparty: false,
+		};
+
+		let prior = history
+			.prior_matches(&monitor_match, &HashMap::new(), &context)
+			.await;
+		assert_eq!(prior, seeded_prior_matches());
+		let variables = prior_match_variables(&prior);
+		assert_eq!(variables[PRIOR_MATCHES_COUNT_VARIABLE], "3");
+		assert_eq!(
+			variables[PRIOR_VARIABLE],
+			format!(
+				"- 2023-11-14T22:12:20+00:00: Transfer(address,address,uint256) in 0x{:064x}\n\
+				 - 2023-11-14T22:11:20+00:00: Transfer(address,address,uint256) in 0x{:064x}\n\
+				 - 2023-11-14T22:10:20+00:00: Transfer(address,address,uint256) in 0x{:064x}",
+				1, 2, 3
+			)
+		);
+		assert_eq!(
+			variables["prior.2.transaction_hash"],
+			format!("0x{:064x}", 3)
+		);
+
+		let context = TriggerContext {
+			prior_matches: 2,
+			by_counterparty: true,
+		};
+		let prior = history
+			.prior_matches(&monitor_match, &sender("0xAAAA"), &context)
+			.await;
+		assert!(prior.is_empty());
+	}
+
+	#[tokio::test]
+	async fn test_record_recent_leaves_out_dispatched_transaction() {
+		let history = MatchHistory::new(
+			Arc::new(InMemoryStateStore::new()),
+			DEFAULT_MATCH_HISTORY_TIMEOUT,
+			10,
+		);
+		let monitor_match = history_match(MatchHistoryConfig::default());
+		let context = TriggerContext {
+			prior_matches: 5,
+			by_counterparty: true,
+		};
+
+		history.record_recent(&monitor_match, &sender("0xAAAA"));
+		tokio::time::sleep(Duration::from_millis(20)).await;
+		assert!(history
+			.prior_matches(&monitor_match, &sender("0xaaaa"), &context)
+			.await
+			.is_empty());
+
+		let other_match = history_match(MatchHistoryConfig::default());
+		let MonitorMatch::EVM(mut other) = other_match else {
+			unreachable!()
+		};
+		other.transaction = TransactionBuilder::new()
+			.hash(alloy::primitives::B256::with_last_byte(1))
+			.build();
+		let prior = history
+			.prior_matches(&MonitorMatch::EVM(other), &sender("0xaaaa"), &context)
+			.await;
+		assert_eq!(prior.len(), 1);
+		assert_eq!(prior[0].transaction_hash, monitor_match.transaction_hash());
+		assert_eq!(prior[0].summary, "Transfer(address,address,uint256)");
+	}
+
+	#[tokio::test]
+	async fn test_slow_store_renders_empty_prior_matches() {
+		let store = Arc::new(SlowStateStore {
+			inner: InMemoryStateStore::new(),
+			delay: Duration::from_millis(200),
+		});
+		let monitor_match = history_match(MatchHistoryConfig::default());
+		store
+			.inner
+			.set(
+				&recent_key(&monitor_match, &HashMap::new(), false),
+				&serde_json::to_string(&seeded_prior_matches()).unwrap(),
+				None,
+			)
+			.await
+			.unwrap();
+		let history = MatchHistory::new(store, Duration::from_millis(10), 10);
+		let context = TriggerContext {
+			prior_matches: 3,
+			by_counterparty: false,
+		};
+
+		let prior = history
+			.prior_matches(&monitor_match, &HashMap::new(), &context)
+			.await;
+		assert!(prior.is_empty());
+		let variables = prior_match_variables(&prior);
+		assert_eq!(variables[PRIOR_MATCHES_COUNT_VARIABLE], "0");
+		assert_eq!(variables[PRIOR_VARIABLE], "");
+	}
+
+	#[test]
+	fn test_cache_evicts_least_recently_used() {
+		let history = MatchHistory::new(
+			Arc::new(InMemoryStateStore::new()),
+			DEFAULT_MATCH_HISTORY_TIMEOUT,
+			2,
+		);
+		history.cache("a", PriorOccurrences::default());
+		history.cache("b", PriorOccurrences::default());
+		assert!(history.cached("a").is_some());
+		history.cache("c", PriorOccurrences::default());
+		assert!(history.cached("a").is_some());
+		assert!(history.cached("b").is_none());
+		assert!(history.cached("c").is_some());
+	}
+}
@@ -0,0 +1,186 @@
+//! Time-to-alert tracking of delivered notifications.
+//!
+//! Matches carry the times at which their block was produced, fetched and filtered (see
+//! [`AlertTiming`]). When a notification is delivered, the duration of each stage is recorded in
+//! histograms labeled by network and channel type, and the total latency is exposed to trigger
+//! templates as `alert_latency_ms`.
+//!
+//! The block timestamp is set by the chain while every other time is read from the local clock.
+//! Durations measured from the block timestamp therefore include the clock skew between the two
+//! and are clamped to zero when the block appears to come from the future. The latency since the
+//! block was fetched only depends on the local clock and is recorded separately.
+
+use chrono::{DateTime, Utc};
+use std::sync::Arc;
+
+use crate::{
+	models::AlertTiming,
+	utils::metrics::{
+		ALERT_BLOCK_TO_FETCH_SECONDS, ALERT_FETCH_TO_DELIVERY_SECONDS,
+		ALERT_FETCH_TO_MATCH_SECONDS, ALERT_LATENCY_SECONDS, ALERT_MATCH_TO_DELIVERY_SECONDS,
+	},
+};
+
+/// Name of the template variable holding the latency of an alert
+pub const ALERT_LATENCY_VARIABLE: &str = "alert_latency_ms";
+
+/// Source of the current time
+pub type LatencyClock = Arc<dyn Fn() -> DateTime<Utc> + Send + Sync>;
+
+/// Measures the latency of alerts against a clock
+#[derive(Clone)]
+pub struct AlertLatency {
+	clock: LatencyClock,
+}
+
+impl Default for AlertLatency {
+	fn default() -> Self {
+		Self {
+			clock: Arc::new(Utc::now),
+		}
+	}
+}
+
+impl AlertLatency {
+	/// Creates a tracker reading the system clock
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Replaces the source of the current time
+	pub fn with_clock(mut self, clock: LatencyClock) -> Self {
+		self.clock = clock;
+		self
+	}
+
+	/// Returns the current time in milliseconds since the Unix epoch
+	pub fn now_ms(&self) -> i64 {
+		(self.clock)().timestamp_millis()
+	}
+
+	/// Returns the time elapsed since the match's block was produced, in milliseconds
+	///
+	/// Falls back to the time elapsed since the block was fetched when the chain does not
+	/// provide block timestamps.
+	pub fn alert_latency_ms(&self, timing: &AlertTiming) -> i64 {
+		let origin = timing.block_timestamp_ms.unwrap_or(timing.fetched_at_ms);
+		(self.now_ms() - origin).max(0)
+	}
+
+	/// Records the stage durations of a notification delivered now
+	///
+	/// # Arguments
+	/// * `timing` - Pipeline times of the delivered match
+	/// * `network` - Slug of the network the match was found on
+	/// * `channel` - Type of the trigger the notification was delivered through
+	pub fn record_delivery(&self, timing: &AlertTiming, network: &str, channel: &str) {
+		let delivered_at_ms = self.now_ms();
+		let labels = [network, channel];
+		if let Some(block_timestamp_ms) = timing.block_timestamp_ms {
+			ALERT_BLOCK_TO_FETCH_SECONDS
+				.with_label_values(&labels)
+				.observe(seconds_between(block_timestamp_ms, timing.fetched_at_ms));
+			ALERT_LATENCY_SECONDS
+				.with_label_values(&labels)
+				.observe(seconds_between(block_timestamp_ms, delivered_at_ms));
+		}
+		ALERT_FETCH_TO_MATCH_SECONDS
+			.with_label_values(&labels)
+			.observe(seconds_between(timing.fetched_at_ms, timing.matched_at_ms));
+		ALERT_MATCH_TO_DELIVERY_SECONDS
+			.with_label_values(&labels)
+			.observe(seconds_between(timing.matched_at_ms, delivered_at_ms));
+		ALERT_FETCH_TO_DELIVERY_SECONDS
+			.with_label_values(&labels)
+			.observe(seconds_between(timing.fetched_at_ms, delivered_at_ms));
+	}
+}
+
+/// Returns the seconds elapsed between two times in milliseconds, clamped to zero
+fn seconds_between(from_ms: i64, to_ms: i64) -> f64 {
+	(to_ms - from_ms).max(0) as f64 / 1000.0
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use chrono::TimeZone;
+
+	fn fixed_clock(ms: i64) -> LatencyClock {
+		let now = Utc.timestamp_millis_opt(ms).unwrap();
+		Arc::new(move || now)
+	}
+
+	fn timing(block_timestamp_ms: Option<i64>) -> AlertTiming {
+		AlertTiming {
+			block_timestamp_ms,
+			fetched_at_ms: 10_000,
+			matched_at_ms: 10_500,
+		}
+	}
+
+	#[test]
+	fn test_alert_latency_is_measured_from_the_block_timestamp() {
+		let latency = AlertLatency::new().with_clock(fixed_clock(12_000));
+
+		assert_eq!(latency.alert_latency_ms(&timing(Some(7_000))), 5_000);
+		// Without a block timestamp, the latency is measured from the fetch
+		assert_eq!(latency.alert_latency_ms(&timing(None)), 2_000);
+	}
+
+	#[test]
+	fn test_block_timestamp_ahead_of_the_clock_is_clamped() {
+		let latency = AlertLatency::new().with_clock(fixed_clock(12_000));
+		let timing = timing(Some(15_000));
+
+		assert_eq!(latency.alert_latency_ms(&timing), 0);
+
+		latency.record_delivery(&timing, "latency_unit_skewed", "slack");
+		let labels = ["latency_unit_skewed", "slack"];
+		assert_eq!(
+			ALERT_BLOCK_TO_FETCH_SECONDS
+				.with_label_values(&labels)
+				.get_sample_sum(),
+			0.0
+		);
+		assert_eq!(
+			ALERT_LATENCY_SECONDS
+				.with_label_values(&labels)
+				.get_sample_sum(),
+			0.0
+		);
+		// The fetch-relative latency is not affected by the skew
+		assert_eq!(
+			ALERT_FETCH_TO_DELIVERY_SECONDS
+				.with_label_values(&labels)
+				.get_sample_sum(),
+			2.0
+		);
+	}
+
+	#[test]
+	fn test_block_stages_are_skipped_without_block_timestamp() {
+		let latency = AlertLatency::new().with_clock(fixed_clock(12_000));
+
+		latency.record_delivery(&timing(None), "latency_unit_untimed", "webhook");
+		let labels = ["latency_unit_untimed", "webhook"];
+		assert_eq!(
+			ALERT_BLOCK_TO_FETCH_SECONDS
+				.with_label_values(&labels)
+				.get_sample_count(),
+			0
+		);
+		assert_eq!(
+			ALERT_FETCH_TO_MATCH_SECONDS
+				.with_label_values(&labels)
+				.get_sample_sum(),
+			0.5
+		);
+		assert_eq!(
+			ALERT_MATCH_TO_DELIVERY_SECONDS
+				.with_label_values(&labels)
+				.get_sample_sum(),
+			1.5
+		);
+	}
+}
@@ -4,13 +4,72 @@
 //! which are configurable actions that can be initiated based on
 //! various conditions.
 
+mod address_labels;
+mod budget;
+mod circuit_breaker;
+mod condition_cache;
+mod dedup;
+mod digest;
+mod dispatch_queue;
+mod enrichment;
 mod error;
+mod history;
+mod latency;
+mod mutes;
+mod network_views;
+mod outbox;
+mod resolution;
+mod rollup;
 mod script;
 mod service;
+mod variables;
 
+pub use address_labels::{
+	AddressLabel, AddressLabels, ADDRESSES_INVOLVED_VARIABLE, LABEL_VARIABLE_SUFFIX,
+};
+pub use budget::{
+	BudgetCheck, BudgetClock, BudgetDecision, BudgetDigest, NotificationBudgets,
+	DEFAULT_BUDGET_STATE_FILE,
+};
+pub use circuit_breaker::{
+	CircuitBreakerClock, CircuitBreakerConfig, CircuitCheck, CircuitState, TriggerCircuit,
+	TriggerCircuitBreakers, DEFAULT_CIRCUIT_BREAKER_STATE_FILE,
+};
+pub use condition_cache::{ConditionCache, DEFAULT_CONDITION_CACHE_CAPACITY};
+pub use dedup::{dedup_matches, DedupedMatch, SharedDedup, DEFAULT_SHARED_DEDUP_TTL};
+pub use digest::{deliver_digest_parts, DigestMessage, DigestPart, DEFAULT_DIGEST_PART_DELAY};
+pub use dispatch_queue::{
+	DispatchFn, DispatchQueue, DispatchQueueConfig, OverflowPolicy, DEFAULT_DISPATCH_CONCURRENCY,
+	DEFAULT_DISPATCH_DRAIN_TIMEOUT, DEFAULT_DISPATCH_QUEUE_CAPACITY,
+};
+pub use enrichment::{Enricher, DEFAULT_ENRICHMENT_CACHE_CAPACITY};
 pub use error::TriggerError;
+pub use history::{
+	prior_match_variables, MatchHistory, PriorMatch, PriorOccurrences,
+	DEFAULT_MATCH_HISTORY_CACHE_CAPACITY, DEFAULT_MATCH_HISTORY_STATE_FILE,
+	DEFAULT_MATCH_HISTORY_TIMEOUT, FIRST_OCCURRENCE_VARIABLE, LAST_SEEN_AT_VARIABLE,
+	PRIOR_COUNT_VARIABLE, PRIOR_MATCHES_COUNT_VARIABLE, PRIOR_VARIABLE,
+};
+pub use latency::{AlertLatency, LatencyClock, ALERT_LATENCY_VARIABLE};
+pub use mutes::{
+	MuteClock, MuteRequest, TriggerMute, TriggerMutes, DEFAULT_MUTES_REFRESH_INTERVAL,
+	DEFAULT_TRIGGER_MUTES_STATE_FILE, MAX_MUTE_DURATION,
+};
+pub use network_views::{NetworkDivergence, NetworkViews, DEFAULT_NETWORK_VIEW_WINDOW_BLOCKS};
+pub use outbox::{
+	outbox_entry_id, NotificationOutbox, OutboxDispatcher, OutboxDispatcherConfig, OutboxEntry,
+	DEFAULT_OUTBOX_FILE, DEFAULT_OUTBOX_LEASE, DEFAULT_OUTBOX_POLL_INTERVAL,
+	DEFAULT_OUTBOX_RETENTION,
+};
+pub use resolution::TriggerResolutionCache;
+pub use rollup::{apply_rollup, rollup_matches, SEVERITY_VARIABLE};
 pub use script::{
-	process_script_output, validate_script_config, ScriptError, ScriptExecutor,
-	ScriptExecutorFactory,
+	execute_script_for_output, process_script_output, validate_script_config, ScriptError,
+	ScriptExecutor, ScriptExecutorFactory,
 };
 pub use service::{TriggerExecutionService, TriggerExecutionServiceTrait};
+pub use variables::{
+	is_catalog_variable, message_templates, missing_variable_marker, resolve_template_variables,
+	template_references, template_variables_table, unknown_template_variables, TemplateVariable,
+	TemplateVariablesMode, VariableScope, STRICT_TEMPLATE_VARIABLES_ENV, TEMPLATE_VARIABLES,
+};
@@ -0,0 +1,440 @@
+//! Expiring mutes of triggers.
+//!
+//! During planned maintenance, deliveries to a trigger can be silenced for a bounded window,
+//! optionally only for the matches of one monitor, without editing the configuration. Muted
+//! deliveries are skipped by the trigger execution service and counted by the
+//! `trigger_muted_deliveries_total` metric.
+//!
+//! Mutes are kept in a state store under a single key, so that they survive restarts and are
+//! seen by the instances sharing the store, including the `mute` and `unmute` commands. The
+//! store is read again at most every refresh interval, so a mute written by another process
+//! applies within that interval. Expired mutes are dropped whenever the mutes are read, and
+//! the stored key expires with the last mute.
+
+use chrono::{DateTime, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+use std::{sync::Arc, time::Duration};
+use tokio::sync::Mutex;
+use uuid::Uuid;
+
+use crate::{
+	services::{state::StateStore, trigger::TriggerError},
+	utils::metrics::TRIGGER_MUTED_DELIVERIES,
+};
+
+/// Default file the mutes are persisted to when no state store is configured
+pub const DEFAULT_TRIGGER_MUTES_STATE_FILE: &str = "data/trigger_mutes.json";
+
+/// Default time after which the mutes are read from the store again
+pub const DEFAULT_MUTES_REFRESH_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Longest time a trigger can be muted for
+pub const MAX_MUTE_DURATION: Duration = Duration::from_secs(30 * 24 * 3600);
+
+/// State store key holding the mutes
+const MUTES_KEY: &str = "trigger_mutes";
+
+/// Source of the current time
+pub type MuteClock = Arc<dyn Fn() -> DateTime<Utc> + Send + Sync>;
+
+/// Request to mute a trigger
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct MuteRequest {
+	/// Name of the trigger to mute
+	pub trigger: String,
+	/// Name of the monitor whose matches are muted, all monitors when None
+	#[serde(default)]
+	pub monitor: Option<String>,
+	/// Time the trigger stays muted, in seconds
+	pub duration_secs: u64,
+	/// Reason of the mute, e.g. the planned maintenance
+	#[serde(default)]
+	pub reason: String,
+}
+
+/// Active mute of a trigger
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TriggerMute {
+	/// Identifier of the mute
+	pub id: String,
+	/// Name of the muted trigger
+	pub trigger: String,
+	/// Name of the monitor whose matches are muted, all monitors when None
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub monitor: Option<String>,
+	/// Reason of the mute
+	pub reason: String,
+	/// Unix time in milliseconds the mute was created at
+	pub created_at_ms: i64,
+	/// Unix time in milliseconds the mute expires at
+	pub expires_at_ms: i64,
+}
+
+impl TriggerMute {
+	/// Returns whether the mute silences deliveries to a trigger for a monitor
+	pub fn applies_to(&self, trigger_slug: &str, monitor_name: &str) -> bool {
+		self.trigger == trigger_slug
+			&& self
+				.monitor
+				.as_deref()
+				.is_none_or(|monitor| monitor == monitor_name)
+	}
+
+	/// Returns whether the mute is active at the given time
+	fn is_active(&self, now_ms: i64) -> bool {
+		self.expires_at_ms > now_ms
+	}
+}
+
+/// Mutes read from the store
+#[derive(Debug, Default)]
+struct MutesCache {
+	mutes: Vec<TriggerMute>,
+	/// Unix time in milliseconds the mutes were read at, None before the first read
+	loaded_at_ms: Option<i64>,
+}
+
+/// Registry of the trigger mutes, persisted to a state store
+pub struct TriggerMutes {
+	store: Arc<dyn StateStore>,
+	clock: MuteClock,
+	refresh_interval: Duration,
+	cache: Mutex<MutesCache>,
+}
+
+impl TriggerMutes {
+	/// Creates a registry persisted to the given store
+	pub fn new(store: Arc<dyn StateStore>) -> Self {
+		Self {
+			store,
+			clock: Arc::new(Utc::now),
+			refresh_interval: DEFAULT_MUTES_REFRESH_INTERVAL,
+			cache: Mutex::new(MutesCache::default()),
+		}
+	}
+
+	/// Replaces the source of the current time
+	pub fn with_clock(mut self, clock: MuteClock) -> Self {
+		self.clock = clock;
+		self
+	}
+
+	/// Replaces the time after which the mutes are read from the store again
+	pub fn with_refresh_interval(mut self, refresh_interval: Duration) -> Self {
+		self.refresh_interval = refresh_interval;
+		self
+	}
+
+	fn now_ms(&self) -> i64 {
+		(self.clock)().timestamp_millis()
+	}
+
+	/// Mutes a trigger
+	///
+	/// # Errors
+	/// Returns a configuration error if the trigger is empty or the duration is zero or longer
+	/// than [`MAX_MUTE_DURATION`], and an execution error if the store fails.
+	pub async fn mute(&self, request: MuteRequest) -> Result<TriggerMute, TriggerError> {
+		if request.trigger.is_empty() {
+			return Err(TriggerError::configuration_error(
+				"A trigger is required to mute",
+				None,
+				None,
+			));
+		}
+		if request.duration_secs == 0 || request.duration_secs > MAX_MUTE_DURATION.as_secs() {
+			return Err(TriggerError::configuration_error(
+				format!(
+					"The duration of a mute must be between 1 and {} seconds",
+					MAX_MUTE_DURATION.as_secs()
+				),
+				None,
+				None,
+			));
+		}
+
+		let now_ms = self.now_ms();
+		let mute = TriggerMute {
+			id: Uuid::new_v4().to_string(),
+			trigger: request.trigger,
+			monitor: request.monitor,
+			reason: request.reason,
+			created_at_ms: now_ms,
+			expires_at_ms: now_ms.saturating_add(request.duration_secs as i64 * 1000),
+		};
+		let mut cache = self.cache.lock().await;
+		let mut mutes = self.read(now_ms).await?;
+		mutes.push(mute.clone());
+		self.write(&mut cache, mutes, now_ms).await?;
+		tracing::info!(
+			"Muted trigger '{}'{} until {}: {}",
+			mute.trigger,
+			mute.monitor
+				.as_ref()
+				.map_or_else(String::new, |monitor| format!(" for monitor '{}'", monitor)),
+			format_time_ms(mute.expires_at_ms),
+			mute.reason
+		);
+		Ok(mute)
+	}
+
+	/// Removes a mute before it expires
+	///
+	/// # Returns
+	/// * `Result<Option<TriggerMute>, TriggerError>` - The removed mute, None if no active mute
+	///   has this identifier
+	pub async fn unmute(&self, id: &str) -> Result<Option<TriggerMute>, TriggerError> {
+		let now_ms = self.now_ms();
+		let mut cache = self.cache.lock().await;
+		let mut mutes = self.read(now_ms).await?;
+		let Some(index) = mutes.iter().position(|mute| mute.id == id) else {
+			return Ok(None);
+		};
+		let removed = mutes.remove(index);
+		self.write(&mut cache, mutes, now_ms).await?;
+		tracing::info!("Unmuted trigger '{}'", removed.trigger);
+		Ok(Some(removed))
+	}
+
+	/// Returns the active mutes, read from the store
+	pub async fn list(&self) -> Result<Vec<TriggerMute>, TriggerError> {
+		let now_ms = self.now_ms();
+		let mut cache = self.cache.lock().await;
+		let mutes = self.read(now_ms).await?;
+		*cache = MutesCache {
+			mutes: mutes.clone(),
+			loaded_at_ms: Some(now_ms),
+		};
+		Ok(mutes)
+	}
+
+	/// Checks whether deliveries to a trigger are muted for a monitor, counting muted ones
+	///
+	/// The mutes are read from the store once the refresh interval has passed. A failed read
+	/// is logged and the mutes read last keep applying.
+	///
+	/// # Returns
+	/// * `Option<TriggerMute>` - The mute silencing the delivery, None if it is not muted
+	pub async fn check(&self, trigger_slug: &str, monitor_name: &str) -> Option<TriggerMute> {
+		let now_ms = self.now_ms();
+		let mut cache = self.cache.lock().await;
+		let stale = cache.loaded_at_ms.is_none_or(|loaded_at_ms| {
+			now_ms - loaded_at_ms >= self.refresh_interval.as_millis() as i64
+		});
+		if stale {
+			match self.read(now_ms).await {
+				Ok(mutes) => cache.mutes = mutes,
+				Err(e) => tracing::error!("Failed to refresh the trigger mutes: {}", e),
+			}
+			cache.loaded_at_ms = Some(now_ms);
+		}
+
+		let mute = cache
+			.mutes
+			.iter()
+			.find(|mute| mute.is_active(now_ms) && mute.applies_to(trigger_slug, monitor_name))
+			.cloned()?;
+		TRIGGER_MUTED_DELIVERIES
+			.with_label_values(&[trigger_slug, monitor_name])
+			.inc();
+		Some(mute)
+	}
+
+	/// Reads the active mutes from the store
+	async fn read(&self, now_ms: i64) -> Result<Vec<TriggerMute>, TriggerError> {
+		let value = self.store.get(MUTES_KEY).await.map_err(|e| {
+			TriggerError::execution_error(
+				format!("Failed to read the trigger mutes: {}", e),
+				None,
+				None,
+			)
+		})?;
+		let Some(value) = value else {
+			return Ok(Vec::new());
+		};
+		let mutes = serde_json::from_str::<Vec<TriggerMute>>(&value).map_err(|e| {
+			TriggerError::execution_error(
+				format!("Failed to parse the trigger mutes: {}", e),
+				Some(Box::new(e)),
+				None,
+			)
+		})?;
+		Ok(mutes
+			.into_iter()
+			.filter(|mute| mute.is_active(now_ms))
+			.collect())
+	}
+
+	/// Writes the mutes to the store, expiring the key with the last mute
+	async fn write(
+		&self,
+		cache: &mut MutesCache,
+		mutes: Vec<TriggerMute>,
+		now_ms: i64,
+	) -> Result<(), TriggerError> {
+		let ttl = mutes
+			.iter()
+			.map(|mute| mute.expires_at_ms)
+			.max()
+			.map(|expires_at_ms| Duration::from_millis((expires_at_ms - now_ms).max(1) as u64));
+		let value = serde_json::to_string(&mutes).map_err(|e| {
+			TriggerError::execution_error(
+				format!("Failed to serialize the trigger mutes: {}", e),
+				Some(Box::new(e)),
+				None,
+			)
+		})?;
+		self.store.set(MUTES_KEY, &value, ttl).await.map_err(|e| {
+			TriggerError::execution_error(
+				format!("Failed to persist the trigger mutes: {}", e),
+				None,
+				None,
+			)
+		})?;
+		*cache = MutesCache {
+			mutes,
+			loaded_at_ms: Some(now_ms),
+		};
+		Ok(())
+	}
+}
+
+/// Formats a Unix time in milliseconds as an RFC 3339 date
+fn format_time_ms(time_ms: i64) -> String {
+	Utc.timestamp_millis_opt(time_ms)
+		.single()
+		.map_or_else(|| time_ms.to_string(), |time| time.to_rfc3339())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::services::state::InMemoryStateStore;
+	use std::sync::atomic::{AtomicI64, Ordering};
+
+	fn create_mutes(store: Arc<dyn StateStore>, now: &Arc<AtomicI64>) -> TriggerMutes {
+		let time = now.clone();
+		TriggerMutes::new(store).with_clock(Arc::new(move || {
+			Utc.timestamp_millis_opt(time.load(Ordering::SeqCst))
+				.unwrap()
+		}))
+	}
+
+	fn request(trigger: &str, monitor: Option<&str>, duration_secs: u64) -> MuteRequest {
+		MuteRequest {
+			trigger: trigger.to_string(),
+			monitor: monitor.map(str::to_string),
+			duration_secs,
+			reason: "protocol upgrade".to_string(),
+		}
+	}
+
+	#[tokio::test]
+	async fn test_mute_suppresses_until_expiry() {
+		let now = Arc::new(AtomicI64::new(Utc::now().timestamp_millis()));
+		let start = now.load(Ordering::SeqCst);
+		let mutes = create_mutes(Arc::new(InMemoryStateStore::new()), &now);
+
+		let mute = mutes
+			.mute(request("slack_mutes", Some("upgrade_monitor"), 60))
+			.await
+			.unwrap();
+		assert_eq!(mute.expires_at_ms, start + 60_000);
+		assert_eq!(
+			mutes.check("slack_mutes", "upgrade_monitor").await,
+			Some(mute.clone())
+		);
+		assert!(mutes.check("slack_mutes", "other_monitor").await.is_none());
+		assert!(mutes
+			.check("discord_mutes", "upgrade_monitor")
+			.await
+			.is_none());
+		assert_eq!(
+			TRIGGER_MUTED_DELIVERIES
+				.with_label_values(&["slack_mutes", "upgrade_monitor"])
+				.get(),
+			1
+		);
+
+		now.store(start + 59_999, Ordering::SeqCst);
+		assert!(mutes
+			.check("slack_mutes", "upgrade_monitor")
+			.await
+			.is_some());
+		now.store(start + 60_000, Ordering::SeqCst);
+		assert!(mutes
+			.check("slack_mutes", "upgrade_monitor")
+			.await
+			.is_none());
+		assert!(mutes.list().await.unwrap().is_empty());
+	}
+
+	#[tokio::test]
+	async fn test_mutes_survive_a_restart() {
+		let store: Arc<dyn StateStore> = Arc::new(InMemoryStateStore::new());
+		let now = Arc::new(AtomicI64::new(Utc::now().timestamp_millis()));
+		let mutes = create_mutes(store.clone(), &now);
+		let all_monitors = mutes.mute(request("slack", None, 3600)).await.unwrap();
+		let one_monitor = mutes
+			.mute(request("discord", Some("upgrade_monitor"), 600))
+			.await
+			.unwrap();
+
+		let restarted = create_mutes(store.clone(), &now);
+		assert_eq!(
+			restarted.list().await.unwrap(),
+			vec![all_monitors.clone(), one_monitor.clone()]
+		);
+		assert!(restarted.check("slack", "any_monitor").await.is_some());
+
+		assert_eq!(
+			restarted.unmute(&all_monitors.id).await.unwrap(),
+			Some(all_monitors.clone())
+		);
+		assert!(restarted.unmute(&all_monitors.id).await.unwrap().is_none());
+		assert!(restarted.check("slack", "any_monitor").await.is_none());
+		assert_eq!(
+			create_mutes(store, &now).list().await.unwrap(),
+			vec![one_monitor]
+		);
+	}
+
+	#[tokio::test]
+	async fn test_mutes_written_elsewhere_apply_after_refresh() {
+		let store: Arc<dyn StateStore> = Arc::new(InMemoryStateStore::new());
+		let now = Arc::new(AtomicI64::new(Utc::now().timestamp_millis()));
+		let start = now.load(Ordering::SeqCst);
+		let service = create_mutes(store.clone(), &now);
+		assert!(service.check("slack", "upgrade_monitor").await.is_none());
+
+		// A mute written by another process is read once the refresh interval has passed
+		create_mutes(store, &now)
+			.mute(request("slack", None, 3600))
+			.await
+			.unwrap();
+		assert!(service.check("slack", "upgrade_monitor").await.is_none());
+		now.store(
+			start + DEFAULT_MUTES_REFRESH_INTERVAL.as_millis() as i64,
+			Ordering::SeqCst,
+		);
+		assert!(service.check("slack", "upgrade_monitor").await.is_some());
+	}
+
+	#[tokio::test]
+	async fn test_mute_rejects_invalid_requests() {
+		let now = Arc::new(AtomicI64::new(Utc::now().timestamp_millis()));
+		let mutes = create_mutes(Arc::new(InMemoryStateStore::new()), &now);
+		for invalid in [
+			request("", None, 60),
+			request("slack", None, 0),
+			request("slack", None, MAX_MUTE_DURATION.as_secs() + 1),
+		] {
+			assert!(matches!(
+				mutes.mute(invalid).await,
+				Err(TriggerError::ConfigurationError(_))
+			));
+		}
+		assert!(mutes.list().await.unwrap().is_empty());
+	}
+}
@@ -0,0 +1,430 @@
+//! Cross-view handling of the matches of network groups.
+//!
+//! Networks sharing a `group` observe the same chain through different RPC views, e.g. an
+//! internal node and a public provider, and a monitor referencing the group watches every view.
+//! A transaction is then usually matched once per view. By default, only the first view to match
+//! it delivers. Monitors detecting censorship or missing data opt out of this with
+//! `network_views.dedup`, and with `network_views.divergence_blocks` a match that another view
+//! watched by the monitor did not see within that many blocks is reported as a divergence.
+//!
+//! Views are compared on the blocks they processed, so a view that stops processing blocks is
+//! not reported here.
+
+use std::{collections::HashMap, sync::Mutex};
+
+use crate::{
+	models::{MonitorMatch, Network, ProcessedBlock},
+	utils::metrics::{NETWORK_VIEW_DIVERGENCES, NETWORK_VIEW_DUPLICATES},
+};
+
+/// Number of blocks a view has to match a transaction another view matched, when the monitor
+/// does not report divergences. Later matches are delivered again.
+pub const DEFAULT_NETWORK_VIEW_WINDOW_BLOCKS: u64 = 128;
+
+/// Number of blocks past the first match after which a match is forgotten, even if a view
+/// watched by the monitor has not caught up with it yet
+const MAX_VIEW_LAG_BLOCKS: u64 = 10_000;
+
+/// A match seen by one view of a network group and missing from another
+#[derive(Debug, Clone)]
+pub struct NetworkDivergence {
+	/// Group of the views
+	pub group: String,
+	/// Network that did not see the match
+	pub network_slug: String,
+	/// Block the network reached without seeing the match
+	pub observed_block: u64,
+	/// Block of the first view the match was seen in
+	pub block_number: u64,
+	/// The match, as produced by the first view to see it
+	pub monitor_match: MonitorMatch,
+}
+
+/// A match of a monitor and the views that saw it
+#[derive(Debug)]
+struct Sighting {
+	/// Block of the first view the match was seen in
+	block_number: u64,
+	/// Networks that saw the match, the first one first
+	seen_by: Vec<String>,
+	/// Networks reported as not having seen the match
+	reported: Vec<String>,
+	/// The match, as produced by the first view to see it
+	monitor_match: MonitorMatch,
+}
+
+impl Sighting {
+	/// Returns whether the network still has to be compared with the first view
+	fn is_pending(&self, network_slug: &str) -> bool {
+		!self.seen_by.iter().any(|slug| slug == network_slug)
+			&& !self.reported.iter().any(|slug| slug == network_slug)
+	}
+}
+
+#[derive(Debug, Default)]
+struct ViewsState {
+	/// Highest block processed by each network of a group
+	heads: HashMap<String, u64>,
+	/// Matches by (group, monitor name, match key)
+	sightings: HashMap<(String, String, String), Sighting>,
+}
+
+/// Tracks the matches of the networks of a group across their views
+#[derive(Debug, Default)]
+pub struct NetworkViews {
+	/// Group of each network belonging to one
+	groups: HashMap<String, String>,
+	/// Networks of each group, in slug order
+	members: HashMap<String, Vec<String>>,
+	state: Mutex<ViewsState>,
+}
+
+impl NetworkViews {
+	/// Creates the views of the groups of the given networks
+	pub fn new<'a>(networks: impl IntoIterator<Item = &'a Network>) -> Self {
+		let mut groups = HashMap::new();
+		let mut members: HashMap<String, Vec<String>> = HashMap::new();
+		for network in networks {
+			if let Some(group) = &network.group {
+				groups.insert(network.slug.clone(), group.clone());
+				members
+					.entry(group.clone())
+					.or_default()
+					.push(network.slug.clone());
+			}
+		}
+		for slugs in members.values_mut() {
+			slugs.sort();
+		}
+		Self {
+			groups,
+			members,
+			state: Mutex::new(ViewsState::default()),
+		}
+	}
+
+	/// Returns the group of a network
+	pub fn group_of(&self, network_slug: &str) -> Option<&str> {
+		self.groups.get(network_slug).map(String::as_str)
+	}
+
+	/// Checks the matches of a block against the matches of the other views of its group
+	///
+	/// Matches another view already delivered are dropped, unless their monitor opted out of
+	/// dedup. Matches the block's network did not see within the divergence window of their
+	/// monitor are returned as divergences. Blocks of networks outside a group are returned
+	/// unchanged.
+	///
+	/// # Arguments
+	/// * `block` - The processed block
+	///
+	/// # Returns
+	/// * `(ProcessedBlock, Vec<NetworkDivergence>)` - The block without the duplicated matches,
+	///   and the divergences found
+	pub fn resolve(&self, mut block: ProcessedBlock) -> (ProcessedBlock, Vec<NetworkDivergence>) {
+		let Some(group) = self.groups.get(&block.network_slug) else {
+			return (block, vec![]);
+		};
+		let network_slug = block.network_slug.clone();
+		let mut state = self.lock_state();
+		let ViewsState { heads, sightings } = &mut *state;
+		let head = heads
+			.entry(network_slug.clone())
+			.or_insert(block.block_number);
+		*head = (*head).max(block.block_number);
+		let head = *head;
+
+		let mut results = Vec::with_capacity(block.processing_results.len());
+		for monitor_match in std::mem::take(&mut block.processing_results) {
+			let monitor = monitor_match.monitor();
+			let key = (
+				group.clone(),
+				monitor.name.clone(),
+				monitor_match.match_key(),
+			);
+			let Some(sighting) = sightings.get_mut(&key) else {
+				sightings.insert(
+					key,
+					Sighting {
+						block_number: block.block_number,
+						seen_by: vec![network_slug.clone()],
+						reported: vec![],
+						monitor_match: monitor_match.clone(),
+					},
+				);
+				results.push(monitor_match);
+				continue;
+			};
+			// The view processing a block again delivers its matches again, as without views
+			if !sighting.is_pending(&network_slug) {
+				results.push(monitor_match);
+				continue;
+			}
+			sighting.seen_by.push(network_slug.clone());
+			if monitor
+				.network_views
+				.as_ref()
+				.is_none_or(|views| views.dedup)
+			{
+				NETWORK_VIEW_DUPLICATES
+					.with_label_values(&[group.as_str(), network_slug.as_str()])
+					.inc();
+				tracing::debug!(
+					monitor = %monitor.name,
+					network = %network_slug,
+					group = %group,
+					"Match already delivered through network {}",
+					sighting.seen_by[0]
+				);
+				continue;
+			}
+			results.push(monitor_match);
+		}
+		block.processing_results = results;
+
+		let members = self.members.get(group).map_or(&[][..], Vec::as_slice);
+		let mut divergences = vec![];
+		sightings.retain(|(sighting_group, _, _), sighting| {
+			if sighting_group != group {
+				return true;
+			}
+			let monitor = sighting.monitor_match.monitor();
+			let divergence_blocks = monitor
+				.network_views
+				.as_ref()
+				.and_then(|views| views.divergence_blocks);
+			let window_end = sighting.block_number
+				+ divergence_blocks.unwrap_or(DEFAULT_NETWORK_VIEW_WINDOW_BLOCKS);
+			let watched = members
+				.iter()
+				.filter(|slug| monitor.networks.contains(*slug))
+				.collect::<Vec<_>>();
+
+			if divergence_blocks.is_some() {
+				for slug in &watched {
+					let Some(&view_head) = heads.get(slug.as_str()) else {
+						continue;
+					};
+					if !sighting.is_pending(slug) || view_head < window_end {
+						continue;
+					}
+					NETWORK_VIEW_DIVERGENCES
+						.with_label_values(&[group.as_str(), slug.as_str(), monitor.name.as_str()])
+						.inc();
+					divergences.push(NetworkDivergence {
+						group: group.clone(),
+						network_slug: slug.to_string(),
+						observed_block: view_head,
+						block_number: sighting.block_number,
+						monitor_match: sighting.monitor_match.clone(),
+					});
+					sighting.reported.push(slug.to_string());
+				}
+			}
+
+			let has_pending_view = watched.iter().any(|slug| {
+				sighting.is_pending(slug)
+					&& heads
+						.get(slug.as_str())
+						.is_none_or(|view_head| *view_head < window_end)
+			});
+			has_pending_view && head < sighting.block_number + MAX_VIEW_LAG_BLOCKS
+		});
+
+		(block, divergences)
+	}
+
+	fn lock_state(&self) -> std::sync::MutexGuard<'_, ViewsState> {
+		self.state.lock().unwrap_or_else(|e| e.into_inner())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{
+		models::{Monitor, NetworkViewsConfig},
+		utils::tests::builders::{
+			evm::{
+				monitor::MonitorBuilder, monitor_match::MonitorMatchBuilder,
+				transaction::TransactionBuilder,
+			},
+			network::NetworkBuilder,
+		},
+	};
+	use alloy::primitives::B256;
+
+	const INTERNAL: &str = "ethereum_mainnet_internal";
+	const PUBLIC: &str = "ethereum_mainnet_public";
+
+	fn create_views() -> NetworkViews {
+		let networks = [INTERNAL, PUBLIC]
+			.into_iter()
+			.map(|slug| {
+				NetworkBuilder::new()
+					.slug(slug)
+					.group("ethereum_mainnet")
+					.build()
+			})
+			.chain([NetworkBuilder::new().slug("base_mainnet").build()])
+			.collect::<Vec<_>>();
+		NetworkViews::new(&networks)
+	}
+
+	fn create_monitor(network_views: Option<NetworkViewsConfig>) -> Monitor {
+		let mut builder = MonitorBuilder::new()
+			.name("treasury")
+			.networks(vec![INTERNAL.to_string(), PUBLIC.to_string()]);
+		if let Some(network_views) = network_views {
+			builder = builder.network_views(network_views);
+		}
+		builder.build()
+	}
+
+	fn create_match(monitor: &Monitor, network_slug: &str, hash: u8) -> MonitorMatch {
+		MonitorMatchBuilder::new()
+			.monitor(monitor.clone())
+			.transaction(
+				TransactionBuilder::new()
+					.hash(B256::with_last_byte(hash))
+					.build(),
+			)
+			.network_slug(network_slug)
+			.build()
+	}
+
+	fn create_block(
+		network_slug: &str,
+		block_number: u64,
+		matches: Vec<MonitorMatch>,
+	) -> ProcessedBlock {
+		ProcessedBlock {
+			block_number,
+			network_slug: network_slug.to_string(),
+			processing_results: matches,
+		}
+	}
+
+	#[test]
+	fn test_match_seen_by_both_views_is_delivered_once() {
+		let views = create_views();
+		let monitor = create_monitor(None);
+
+		let (block, divergences) = views.resolve(create_block(
+			INTERNAL,
+			100,
+			vec![create_match(&monitor, INTERNAL, 1)],
+		));
+		assert_eq!(block.processing_results.len(), 1);
+		assert!(divergences.is_empty());
+
+		let (block, divergences) = views.resolve(create_block(
+			PUBLIC,
+			100,
+			vec![
+				create_match(&monitor, PUBLIC, 1),
+				create_match(&monitor, PUBLIC, 2),
+			],
+		));
+		assert_eq!(block.processing_results.len(), 1);
+		assert_eq!(
+			block.processing_results[0].transaction_hash(),
+			create_match(&monitor, PUBLIC, 2).transaction_hash()
+		);
+		assert!(divergences.is_empty());
+	}
+
+	#[test]
+	fn test_each_view_delivers_without_dedup() {
+		let views = create_views();
+		let monitor = create_monitor(Some(NetworkViewsConfig {
+			dedup: false,
+			..Default::default()
+		}));
+
+		for network_slug in [INTERNAL, PUBLIC] {
+			let (block, _) = views.resolve(create_block(
+				network_slug,
+				100,
+				vec![create_match(&monitor, network_slug, 1)],
+			));
+			assert_eq!(block.processing_results.len(), 1);
+			assert_eq!(block.processing_results[0].network_slug(), network_slug);
+		}
+	}
+
+	#[test]
+	fn test_missing_match_is_reported_once_past_the_window() {
+		let views = create_views();
+		let monitor = create_monitor(Some(NetworkViewsConfig {
+			dedup: false,
+			divergence_blocks: Some(2),
+			divergence_triggers: vec![],
+		}));
+
+		views.resolve(create_block(
+			INTERNAL,
+			100,
+			vec![create_match(&monitor, INTERNAL, 1)],
+		));
+		let (_, divergences) = views.resolve(create_block(PUBLIC, 101, vec![]));
+		assert!(divergences.is_empty());
+
+		let (_, divergences) = views.resolve(create_block(PUBLIC, 102, vec![]));
+		assert_eq!(divergences.len(), 1);
+		assert_eq!(divergences[0].group, "ethereum_mainnet");
+		assert_eq!(divergences[0].network_slug, PUBLIC);
+		assert_eq!(divergences[0].block_number, 100);
+		assert_eq!(divergences[0].observed_block, 102);
+		assert_eq!(divergences[0].monitor_match.network_slug(), INTERNAL);
+
+		let (_, divergences) = views.resolve(create_block(PUBLIC, 103, vec![]));
+		assert!(divergences.is_empty());
+	}
+
+	#[test]
+	fn test_match_seen_within_the_window_is_not_reported() {
+		let views = create_views();
+		let monitor = create_monitor(Some(NetworkViewsConfig {
+			dedup: false,
+			divergence_blocks: Some(2),
+			divergence_triggers: vec![],
+		}));
+
+		views.resolve(create_block(
+			INTERNAL,
+			100,
+			vec![create_match(&monitor, INTERNAL, 1)],
+		));
+		let (block, divergences) = views.resolve(create_block(
+			PUBLIC,
+			101,
+			vec![create_match(&monitor, PUBLIC, 1)],
+		));
+		assert_eq!(block.processing_results.len(), 1);
+		assert!(divergences.is_empty());
+
+		let (_, divergences) = views.resolve(create_block(PUBLIC, 110, vec![]));
+		assert!(divergences.is_empty());
+	}
+
+	#[test]
+	fn test_networks_outside_a_group_are_untouched() {
+		let views = create_views();
+		let monitor = MonitorBuilder::new()
+			.networks(vec!["base_mainnet".to_string()])
+			.build();
+
+		for _ in 0..2 {
+			let (block, divergences) = views.resolve(create_block(
+				"base_mainnet",
+				100,
+				vec![create_match(&monitor, "base_mainnet", 1)],
+			));
+			assert_eq!(block.processing_results.len(), 1);
+			assert!(divergences.is_empty());
+		}
+		assert_eq!(views.group_of(INTERNAL), Some("ethereum_mainnet"));
+		assert_eq!(views.group_of("base_mainnet"), None);
+	}
+}
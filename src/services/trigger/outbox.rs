@@ -0,0 +1,651 @@
+//! Persistent outbox of matches waiting for notification dispatch.
+//!
+//! With the outbox enabled, the matches of a block are written to the outbox before the block is
+//! recorded as processed, and a dispatcher claims them, delivers them to their triggers and
+//! marks them done. A claim is a lease held by one dispatcher: a dispatcher stopped between
+//! claiming and completing an entry leaves it to be claimed again once the lease expires, by
+//! the same instance after a restart or by another instance sharing the outbox file.
+//!
+//! Delivery is at least once. Completed entries are kept for a retention period, so that a
+//! block processed again after a crash does not add its matches back, and HTTP deliveries carry
+//! the same idempotency key on every attempt, so receivers can drop the remaining duplicates.
+
+use futures::{stream, FutureExt, StreamExt};
+use serde::{Deserialize, Serialize};
+use std::{
+	panic::AssertUnwindSafe,
+	path::PathBuf,
+	sync::Arc,
+	time::{Duration, Instant},
+};
+use tokio::{
+	io::AsyncWriteExt,
+	sync::{watch, Mutex, Notify},
+	task::JoinHandle,
+};
+
+use crate::services::trigger::{DedupedMatch, DispatchFn};
+
+/// Default path of the outbox file
+pub const DEFAULT_OUTBOX_FILE: &str = "data/notification_outbox.json";
+
+/// Default time a claimed entry is reserved for the dispatcher that claimed it
+pub const DEFAULT_OUTBOX_LEASE: Duration = Duration::from_secs(60);
+
+/// Default time completed entries are kept to recognize matches already delivered
+pub const DEFAULT_OUTBOX_RETENTION: Duration = Duration::from_secs(24 * 3600);
+
+/// Default time between two checks of the outbox when it has nothing to claim
+pub const DEFAULT_OUTBOX_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Match stored in the outbox
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OutboxEntry {
+	/// Identifier of the match, see [`outbox_entry_id`]
+	pub id: String,
+	/// The match to deliver
+	pub deduped: DedupedMatch,
+	/// Unix time in milliseconds at which the entry was added
+	pub created_at_ms: i64,
+	/// Number of times the entry was claimed
+	pub attempts: u32,
+	/// Dispatcher holding the lease on the entry
+	pub lease_owner: Option<String>,
+	/// Unix time in milliseconds at which the lease expires
+	pub lease_expires_at_ms: Option<i64>,
+	/// Unix time in milliseconds at which the entry was delivered
+	pub completed_at_ms: Option<i64>,
+	/// Version of the monitor that added the entry, if enabled with `INCLUDE_MONITOR_VERSION`
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub monitor_version: Option<String>,
+}
+
+impl OutboxEntry {
+	/// Returns true if the entry waits for delivery and is not leased
+	fn is_claimable(&self, now_ms: i64) -> bool {
+		self.completed_at_ms.is_none()
+			&& self
+				.lease_expires_at_ms
+				.is_none_or(|expires_at_ms| expires_at_ms <= now_ms)
+	}
+}
+
+/// Returns the identifier of a match in the outbox
+///
+/// The identifier is derived from the network, the monitor name and the match key, so the same
+/// match produced again when a block is processed twice gets the same identifier.
+pub fn outbox_entry_id(deduped: &DedupedMatch) -> String {
+	let monitor_match = &deduped.monitor_match;
+	format!(
+		"{}/{}/{}",
+		monitor_match.network_slug(),
+		monitor_match.monitor().name,
+		monitor_match.match_key()
+	)
+}
+
+/// Outbox persisting the matches waiting for dispatch to a JSON file
+///
+/// Every operation reads the file, applies the change and writes it back, so instances of the
+/// same host pointing at the same file share the outbox. Operations are serialized through a
+/// lock file created exclusively, and the file is written to a temporary file renamed over it.
+pub struct NotificationOutbox {
+	/// Path of the outbox file
+	path: PathBuf,
+	/// Time completed entries are kept
+	retention: Duration,
+	/// Serializes the operations of this process before competing for the lock file
+	local: Mutex<()>,
+	/// Time to wait for the lock file before failing the operation
+	lock_timeout: Duration,
+	/// Age after which a lock file left behind by a crashed instance is removed
+	stale_lock_after: Duration,
+	/// Wakes up the dispatcher when entries are added
+	added: Notify,
+	/// Version of the monitor recorded in the entries added
+	monitor_version: Option<String>,
+}
+
+impl NotificationOutbox {
+	/// Creates an outbox persisting to the given file
+	///
+	/// # Arguments
+	/// * `path` - Path of the outbox file
+	/// * `retention` - Time completed entries are kept
+	pub fn new(path: impl Into<PathBuf>, retention: Duration) -> Self {
+		Self {
+			path: path.into(),
+			retention,
+			local: Mutex::new(()),
+			lock_timeout: Duration::from_secs(5),
+			stale_lock_after: Duration::from_secs(10),
+			added: Notify::new(),
+			monitor_version: None,
+		}
+	}
+
+	/// Records the given version of the monitor in the entries added to the outbox
+	pub fn with_monitor_version(mut self, monitor_version: Option<String>) -> Self {
+		self.monitor_version = monitor_version;
+		self
+	}
+
+	fn lock_path(&self) -> PathBuf {
+		self.path.with_extension("lock")
+	}
+
+	/// Creates the lock file, waiting for other instances to release it
+	async fn lock(&self) -> Result<(), anyhow::Error> {
+		let lock_path = self.lock_path();
+		if let Some(parent) = lock_path.parent().filter(|p| !p.as_os_str().is_empty()) {
+			tokio::fs::create_dir_all(parent).await?;
+		}
+		let started = Instant::now();
+		loop {
+			match tokio::fs::OpenOptions::new()
+				.write(true)
+				.create_new(true)
+				.open(&lock_path)
+				.await
+			{
+				Ok(_) => return Ok(()),
+				Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => {
+					let is_stale = tokio::fs::metadata(&lock_path)
+						.await
+						.ok()
+						.and_then(|m| m.modified().ok())
+						.and_then(|modified| modified.elapsed().ok())
+						.is_some_and(|age| age > self.stale_lock_after);
+					if is_stale {
+						let _ = tokio::fs::remove_file(&lock_path).await;
+						continue;
+					}
+					if started.elapsed() > self.lock_timeout {
+						return Err(anyhow::anyhow!(
+							"timed out waiting for outbox lock {}",
+							lock_path.display()
+						));
+					}
+					tokio::time::sleep(Duration::from_millis(5)).await;
+				}
+				Err(e) => return Err(e.into()),
+			}
+		}
+	}
+
+	async fn read(&self) -> Result<Vec<OutboxEntry>, anyhow::Error> {
+		match tokio::fs::read_to_string(&self.path).await {
+			Ok(content) if content.trim().is_empty() => Ok(vec![]),
+			Ok(content) => Ok(serde_json::from_str(&content)?),
+			Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(vec![]),
+			Err(e) => Err(e.into()),
+		}
+	}
+
+	async fn write(&self, entries: &[OutboxEntry]) -> Result<(), anyhow::Error> {
+		let tmp_path = self.path.with_extension("tmp");
+		let mut file = tokio::fs::File::create(&tmp_path).await?;
+		file.write_all(serde_json::to_string(entries)?.as_bytes())
+			.await?;
+		file.sync_all().await?;
+		tokio::fs::rename(&tmp_path, &self.path).await?;
+		Ok(())
+	}
+
+	/// Applies an operation to the entries under the lock, writing them back if `write` is set
+	///
+	/// Completed entries older than the retention are removed before the operation.
+	async fn with_entries<T>(
+		&self,
+		write: bool,
+		f: impl FnOnce(&mut Vec<OutboxEntry>, i64) -> T,
+	) -> Result<T, anyhow::Error> {
+		let _local = self.local.lock().await;
+		self.lock().await?;
+
+		let result = async {
+			let now_ms = chrono::Utc::now().timestamp_millis();
+			let retention_ms = self.retention.as_millis() as i64;
+			let mut entries = self.read().await?;
+			entries.retain(|entry| {
+				entry
+					.completed_at_ms
+					.is_none_or(|completed_at_ms| now_ms - completed_at_ms < retention_ms)
+			});
+			let value = f(&mut entries, now_ms);
+			if write {
+				self.write(&entries).await?;
+			}
+			Ok(value)
+		}
+		.await;
+
+		let _ = tokio::fs::remove_file(self.lock_path()).await;
+		result
+	}
+
+	/// Adds matches to the outbox
+	///
+	/// Matches already in the outbox, waiting or delivered within the retention, are skipped.
+	///
+	/// # Returns
+	/// * `Result<usize, anyhow::Error>` - The number of matches added
+	pub async fn add(&self, matches: Vec<DedupedMatch>) -> Result<usize, anyhow::Error> {
+		if matches.is_empty() {
+			return Ok(0);
+		}
+		let added = self
+			.with_entries(true, |entries, now_ms| {
+				let mut added = 0;
+				for deduped in matches {
+					let id = outbox_entry_id(&deduped);
+					if entries.iter().any(|entry| entry.id == id) {
+						continue;
+					}
+					entries.push(OutboxEntry {
+						id,
+						deduped,
+						created_at_ms: now_ms,
+						attempts: 0,
+						lease_owner: None,
+						lease_expires_at_ms: None,
+						completed_at_ms: None,
+						monitor_version: self.monitor_version.clone(),
+					});
+					added += 1;
+				}
+				added
+			})
+			.await?;
+		if added > 0 {
+			self.added.notify_one();
+		}
+		Ok(added)
+	}
+
+	/// Claims the oldest entries waiting for delivery
+	///
+	/// Entries that are not leased, or whose lease expired, are leased to `owner` until `lease`
+	/// from now.
+	///
+	/// # Arguments
+	/// * `owner` - Identifier of the dispatcher claiming the entries
+	/// * `lease` - Time the entries are reserved for the dispatcher
+	/// * `limit` - Maximum number of entries to claim
+	///
+	/// # Returns
+	/// * `Result<Vec<OutboxEntry>, anyhow::Error>` - The claimed entries, oldest first
+	pub async fn claim(
+		&self,
+		owner: &str,
+		lease: Duration,
+		limit: usize,
+	) -> Result<Vec<OutboxEntry>, anyhow::Error> {
+		self.with_entries(true, |entries, now_ms| {
+			entries
+				.iter_mut()
+				.filter(|entry| entry.is_claimable(now_ms))
+				.take(limit)
+				.map(|entry| {
+					entry.lease_owner = Some(owner.to_string());
+					entry.lease_expires_at_ms = Some(now_ms + lease.as_millis() as i64);
+					entry.attempts += 1;
+					entry.clone()
+				})
+				.collect()
+		})
+		.await
+	}
+
+	/// Marks an entry as delivered
+	///
+	/// # Returns
+	/// * `Result<bool, anyhow::Error>` - False if the entry was already delivered or is unknown
+	pub async fn complete(&self, id: &str) -> Result<bool, anyhow::Error> {
+		self.with_entries(true, |entries, now_ms| {
+			match entries
+				.iter_mut()
+				.find(|entry| entry.id == id && entry.completed_at_ms.is_none())
+			{
+				Some(entry) => {
+					entry.completed_at_ms = Some(now_ms);
+					entry.lease_owner = None;
+					entry.lease_expires_at_ms = None;
+					true
+				}
+				None => false,
+			}
+		})
+		.await
+	}
+
+	/// Releases the leases held by `owner` and the expired leases
+	///
+	/// Called at startup, so that entries claimed before a crash are delivered without waiting
+	/// for their lease to expire when the dispatcher keeps its identifier across restarts.
+	///
+	/// # Returns
+	/// * `Result<usize, anyhow::Error>` - The number of entries waiting for delivery
+	pub async fn recover(&self, owner: &str) -> Result<usize, anyhow::Error> {
+		self.with_entries(true, |entries, now_ms| {
+			let mut pending = 0;
+			for entry in entries
+				.iter_mut()
+				.filter(|entry| entry.completed_at_ms.is_none())
+			{
+				if entry.lease_owner.as_deref() == Some(owner) || entry.is_claimable(now_ms) {
+					entry.lease_owner = None;
+					entry.lease_expires_at_ms = None;
+				}
+				pending += 1;
+			}
+			pending
+		})
+		.await
+	}
+
+	/// Returns the entries of the outbox, oldest first
+	pub async fn entries(&self) -> Result<Vec<OutboxEntry>, anyhow::Error> {
+		self.with_entries(false, |entries, _| entries.clone()).await
+	}
+}
+
+impl Default for NotificationOutbox {
+	/// Default implementation for NotificationOutbox
+	///
+	/// Stores the outbox in "data/notification_outbox.json"
+	fn default() -> Self {
+		Self::new(DEFAULT_OUTBOX_FILE, DEFAULT_OUTBOX_RETENTION)
+	}
+}
+
+/// Configuration of the outbox dispatcher
+#[derive(Debug, Clone)]
+pub struct OutboxDispatcherConfig {
+	/// Identifier of the dispatcher, used as the lease owner
+	pub owner: String,
+	/// Time a claimed entry is reserved for the dispatcher
+	pub lease: Duration,
+	/// Time between two checks of the outbox when it has nothing to claim
+	pub poll_interval: Duration,
+	/// Maximum number of entries delivered concurrently
+	pub concurrency: usize,
+}
+
+impl Default for OutboxDispatcherConfig {
+	fn default() -> Self {
+		Self {
+			owner: "monitor".to_string(),
+			lease: DEFAULT_OUTBOX_LEASE,
+			poll_interval: DEFAULT_OUTBOX_POLL_INTERVAL,
+			concurrency: super::DEFAULT_DISPATCH_CONCURRENCY,
+		}
+	}
+}
+
+/// Task claiming the entries of the outbox and delivering them to their triggers
+pub struct OutboxDispatcher {
+	/// Signals the worker to stop claiming entries
+	shutdown: watch::Sender<bool>,
+	/// Task delivering the claimed entries
+	worker: Mutex<Option<JoinHandle<()>>>,
+}
+
+impl OutboxDispatcher {
+	/// Starts the dispatcher
+	///
+	/// # Arguments
+	/// * `outbox` - Outbox the entries are claimed from
+	/// * `config` - Lease owner, lease duration, poll interval and concurrency
+	/// * `dispatch` - Delivers a match to its triggers
+	pub fn start(
+		outbox: Arc<NotificationOutbox>,
+		config: OutboxDispatcherConfig,
+		dispatch: DispatchFn,
+	) -> Arc<Self> {
+		let (shutdown, mut shutdown_rx) = watch::channel(false);
+		let concurrency = config.concurrency.max(1);
+
+		let worker = tokio::spawn(async move {
+			while !*shutdown_rx.borrow() {
+				let entries = match outbox.claim(&config.owner, config.lease, concurrency).await {
+					Ok(entries) => entries,
+					Err(e) => {
+						tracing::error!(
+							"Failed to claim entries of the notification outbox: {}",
+							e
+						);
+						vec![]
+					}
+				};
+				if entries.is_empty() {
+					tokio::select! {
+						_ = outbox.added.notified() => {}
+						_ = tokio::time::sleep(config.poll_interval) => {}
+						_ = shutdown_rx.changed() => {}
+					}
+					continue;
+				}
+
+				stream::iter(entries)
+					.for_each_concurrent(concurrency, |entry| {
+						let outbox = outbox.clone();
+						let dispatch = dispatch.clone();
+						async move {
+							// A panicking dispatch must not stop the worker. The entry is
+							// completed anyway, so that it is not delivered again and again.
+							if AssertUnwindSafe(dispatch(entry.deduped))
+								.catch_unwind()
+								.await
+								.is_err()
+							{
+								tracing::error!(
+									"Dispatch of outbox entry {} panicked, the match is dropped",
+									entry.id
+								);
+							}
+							if let Err(e) = outbox.complete(&entry.id).await {
+								tracing::error!(
+									"Failed to complete outbox entry {}, it will be delivered again: {}",
+									entry.id,
+									e
+								);
+							}
+						}
+					})
+					.await;
+			}
+		});
+
+		Arc::new(Self {
+			shutdown,
+			worker: Mutex::new(Some(worker)),
+		})
+	}
+
+	/// Stops claiming entries and waits for the claimed ones to be delivered
+	///
+	/// Entries still being delivered after `timeout` are left in the outbox, to be claimed
+	/// again once their lease expires.
+	pub async fn close(&self, timeout: Duration) {
+		let _ = self.shutdown.send(true);
+
+		let Some(mut worker) = self.worker.lock().await.take() else {
+			return;
+		};
+		if tokio::time::timeout(timeout, &mut worker).await.is_err() {
+			worker.abort();
+			tracing::error!(
+				"Outbox dispatcher did not finish within {}s, remaining entries will be delivered \
+				 after a restart",
+				timeout.as_secs()
+			);
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::utils::tests::builders::evm::{
+		monitor::MonitorBuilder, monitor_match::MonitorMatchBuilder,
+		transaction::TransactionBuilder,
+	};
+	use alloy::primitives::B256;
+	use tempfile::TempDir;
+
+	fn create_match(monitor: &str, hash: u8) -> DedupedMatch {
+		MonitorMatchBuilder::new()
+			.monitor(MonitorBuilder::new().name(monitor).build())
+			.transaction(
+				TransactionBuilder::new()
+					.hash(B256::with_last_byte(hash))
+					.build(),
+			)
+			.build_deduped()
+	}
+
+	fn create_outbox(dir: &TempDir) -> NotificationOutbox {
+		NotificationOutbox::new(dir.path().join("outbox.json"), DEFAULT_OUTBOX_RETENTION)
+	}
+
+	#[tokio::test]
+	async fn test_add_skips_matches_already_in_outbox() {
+		let dir = TempDir::new().unwrap();
+		let outbox = create_outbox(&dir);
+
+		let added = outbox
+			.add(vec![create_match("a", 1), create_match("a", 2)])
+			.await
+			.unwrap();
+		assert_eq!(added, 2);
+
+		let added = outbox
+			.add(vec![create_match("a", 2), create_match("b", 2)])
+			.await
+			.unwrap();
+		assert_eq!(added, 1);
+		assert_eq!(outbox.entries().await.unwrap().len(), 3);
+	}
+
+	#[tokio::test]
+	async fn test_entries_record_monitor_version_when_enabled() {
+		let dir = TempDir::new().unwrap();
+		let outbox = create_outbox(&dir);
+		outbox.add(vec![create_match("a", 1)]).await.unwrap();
+		let content = tokio::fs::read_to_string(dir.path().join("outbox.json"))
+			.await
+			.unwrap();
+		assert!(!content.contains("monitor_version"));
+
+		let dir = TempDir::new().unwrap();
+		let outbox = create_outbox(&dir).with_monitor_version(Some("1.1.0+abc".to_string()));
+		outbox.add(vec![create_match("a", 1)]).await.unwrap();
+		let entries = outbox.entries().await.unwrap();
+		assert_eq!(entries[0].monitor_version.as_deref(), Some("1.1.0+abc"));
+	}
+
+	#[tokio::test]
+	async fn test_claim_leases_entries_to_one_owner() {
+		let dir = TempDir::new().unwrap();
+		let outbox = create_outbox(&dir);
+		outbox
+			.add(vec![create_match("a", 1), create_match("a", 2)])
+			.await
+			.unwrap();
+
+		let claimed = outbox
+			.claim("first", Duration::from_secs(60), 1)
+			.await
+			.unwrap();
+		assert_eq!(claimed.len(), 1);
+		assert_eq!(claimed[0].attempts, 1);
+		assert_eq!(claimed[0].lease_owner.as_deref(), Some("first"));
+
+		// The leased entry is not claimed again, the other one is
+		let claimed_again = outbox
+			.claim("second", Duration::from_secs(60), 10)
+			.await
+			.unwrap();
+		assert_eq!(claimed_again.len(), 1);
+		assert_ne!(claimed_again[0].id, claimed[0].id);
+
+		assert!(outbox
+			.claim("third", Duration::from_secs(60), 10)
+			.await
+			.unwrap()
+			.is_empty());
+	}
+
+	#[tokio::test]
+	async fn test_expired_lease_is_claimed_again() {
+		let dir = TempDir::new().unwrap();
+		let outbox = create_outbox(&dir);
+		outbox.add(vec![create_match("a", 1)]).await.unwrap();
+
+		outbox
+			.claim("crashed", Duration::from_millis(20), 10)
+			.await
+			.unwrap();
+		tokio::time::sleep(Duration::from_millis(40)).await;
+
+		let claimed = outbox
+			.claim("replacement", Duration::from_secs(60), 10)
+			.await
+			.unwrap();
+		assert_eq!(claimed.len(), 1);
+		assert_eq!(claimed[0].attempts, 2);
+		assert_eq!(claimed[0].lease_owner.as_deref(), Some("replacement"));
+	}
+
+	#[tokio::test]
+	async fn test_completed_entries_are_kept_for_retention() {
+		let dir = TempDir::new().unwrap();
+		let outbox =
+			NotificationOutbox::new(dir.path().join("outbox.json"), Duration::from_millis(50));
+		outbox.add(vec![create_match("a", 1)]).await.unwrap();
+		let claimed = outbox
+			.claim("owner", Duration::from_secs(60), 10)
+			.await
+			.unwrap();
+
+		assert!(outbox.complete(&claimed[0].id).await.unwrap());
+		assert!(!outbox.complete(&claimed[0].id).await.unwrap());
+
+		// A delivered match produced again is not added back within the retention
+		assert_eq!(outbox.add(vec![create_match("a", 1)]).await.unwrap(), 0);
+		assert!(outbox
+			.claim("owner", Duration::from_secs(60), 10)
+			.await
+			.unwrap()
+			.is_empty());
+
+		tokio::time::sleep(Duration::from_millis(80)).await;
+		assert!(outbox.entries().await.unwrap().is_empty());
+	}
+
+	#[tokio::test]
+	async fn test_recover_releases_leases_of_owner() {
+		let dir = TempDir::new().unwrap();
+		let outbox = create_outbox(&dir);
+		outbox
+			.add(vec![create_match("a", 1), create_match("a", 2)])
+			.await
+			.unwrap();
+		outbox
+			.claim("restarted", Duration::from_secs(60), 1)
+			.await
+			.unwrap();
+		outbox
+			.claim("other", Duration::from_secs(60), 1)
+			.await
+			.unwrap();
+
+		assert_eq!(outbox.recover("restarted").await.unwrap(), 2);
+
+		// Only the entry leased by the restarted dispatcher is released
+		let entries = outbox.entries().await.unwrap();
+		assert_eq!(entries[0].lease_owner, None);
+		assert_eq!(entries[1].lease_owner.as_deref(), Some("other"));
+	}
+}
@@ -0,0 +1,69 @@
+//! Per-dispatch cache of trigger lookups.
+//!
+//! Dispatching the matches of a block looks the same triggers up many times, e.g. to find the
+//! triggers opting into group de-duplication for every match. The cache resolves each trigger
+//! name once for the lifetime of a dispatch.
+
+use std::{
+	collections::HashMap,
+	sync::{Arc, Mutex},
+};
+
+use crate::models::Trigger;
+
+/// Cache resolving each trigger name once
+///
+/// Lookups are delegated to the `resolve` function the first time a name is seen, missing
+/// triggers are cached as well.
+pub struct TriggerResolutionCache<F> {
+	resolve: F,
+	resolved: Mutex<HashMap<String, Option<Arc<Trigger>>>>,
+}
+
+impl<F> TriggerResolutionCache<F>
+where
+	F: Fn(&str) -> Option<Arc<Trigger>>,
+{
+	/// Creates an empty cache resolving the trigger names with `resolve`
+	pub fn new(resolve: F) -> Self {
+		Self {
+			resolve,
+			resolved: Mutex::new(HashMap::new()),
+		}
+	}
+
+	/// Returns the trigger with the given name, resolving it on first use
+	pub fn get(&self, trigger_slug: &str) -> Option<Arc<Trigger>> {
+		let mut resolved = self.resolved.lock().unwrap_or_else(|e| e.into_inner());
+		if let Some(trigger) = resolved.get(trigger_slug) {
+			return trigger.clone();
+		}
+		let trigger = (self.resolve)(trigger_slug);
+		resolved.insert(trigger_slug.to_string(), trigger.clone());
+		trigger
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::utils::tests::builders::trigger::TriggerBuilder;
+	use std::sync::atomic::{AtomicUsize, Ordering};
+
+	#[test]
+	fn test_resolves_each_trigger_once() {
+		let lookups = AtomicUsize::new(0);
+		let trigger = Arc::new(TriggerBuilder::new().name("slack").build());
+		let cache = TriggerResolutionCache::new(|slug: &str| {
+			lookups.fetch_add(1, Ordering::SeqCst);
+			(slug == "slack").then(|| trigger.clone())
+		});
+
+		for _ in 0..5 {
+			assert!(Arc::ptr_eq(&cache.get("slack").unwrap(), &trigger));
+			assert!(cache.get("missing").is_none());
+		}
+
+		assert_eq!(lookups.load(Ordering::SeqCst), 2);
+	}
+}
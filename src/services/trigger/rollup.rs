@@ -0,0 +1,397 @@
+//! Rollups of related matches within a block.
+//!
+//! Monitors that declare the same `rollup_group` often match together, e.g. the pause, admin
+//! and oracle monitors of a protocol during an incident. For triggers that roll up matches,
+//! two or more matches of a group within a block are delivered as a single notification
+//! listing each monitor, its matched conditions and transaction. Other triggers of the
+//! monitors still get every match.
+
+use std::collections::HashMap;
+
+use crate::{
+	models::{MessageOverride, MonitorMatch, TriggerOverride, TriggerReference},
+	services::trigger::DedupedMatch,
+};
+
+/// Name of the variable holding the highest severity of the rolled up matches
+pub const SEVERITY_VARIABLE: &str = "severity";
+
+/// Severities of matches from lowest to highest, as set by the `severity` variable of trigger
+/// references
+const SEVERITIES: [&str; 5] = ["info", "low", "medium", "high", "critical"];
+
+/// Combines the matches of a block from monitors sharing a `rollup_group`
+///
+/// For each (group, trigger) the trigger rolls up and that at least two matches of the group
+/// are delivered to, the trigger is removed from the matches and a single match delivering to
+/// it is added, carrying the other matches in `rolled_up`. Triggers rolling up the same matches
+/// share one combined match. Matches left without any trigger are removed.
+///
+/// # Arguments
+/// * `matches` - De-duplicated matches of a block, in dispatch order
+/// * `is_rollup_trigger` - Returns whether the referenced trigger rolls up matches
+///
+/// # Returns
+/// * `Vec<DedupedMatch>` - Matches to dispatch followed by the combined matches
+pub fn rollup_matches<F>(matches: Vec<DedupedMatch>, is_rollup_trigger: F) -> Vec<DedupedMatch>
+where
+	F: Fn(&TriggerReference) -> bool,
+{
+	let mut members: Vec<((String, String), Vec<usize>)> = Vec::new();
+	for (index, deduped) in matches.iter().enumerate() {
+		let monitor = deduped.monitor_match.monitor();
+		let Some(group) = &monitor.rollup_group else {
+			continue;
+		};
+		for reference in monitor.triggers.iter().filter(|r| is_rollup_trigger(r)) {
+			let key = (group.clone(), reference.name().to_string());
+			match members.iter_mut().find(|(k, _)| *k == key) {
+				Some((_, indexes)) => indexes.push(index),
+				None => members.push((key, vec![index])),
+			}
+		}
+	}
+
+	// Triggers rolling up the same matches are delivered together
+	let mut rollups: Vec<(Vec<usize>, Vec<String>)> = Vec::new();
+	for ((_, trigger), indexes) in members {
+		if indexes.len() < 2 {
+			continue;
+		}
+		match rollups.iter_mut().find(|(i, _)| *i == indexes) {
+			Some((_, triggers)) => triggers.push(trigger),
+			None => rollups.push((indexes, vec![trigger])),
+		}
+	}
+	if rollups.is_empty() {
+		return matches;
+	}
+
+	let combined = rollups
+		.iter()
+		.map(|(indexes, triggers)| {
+			let mut first = matches[indexes[0]].clone();
+			first
+				.monitor_match
+				.monitor_mut()
+				.triggers
+				.retain(|reference| triggers.iter().any(|name| name == reference.name()));
+			first.rolled_up = indexes[1..]
+				.iter()
+				.map(|&index| matches[index].monitor_match.clone())
+				.collect();
+			first
+		})
+		.collect::<Vec<_>>();
+
+	let mut results = Vec::with_capacity(matches.len() + combined.len());
+	for (index, mut deduped) in matches.into_iter().enumerate() {
+		let monitor = deduped.monitor_match.monitor_mut();
+		if monitor.triggers.is_empty() {
+			results.push(deduped);
+			continue;
+		}
+		for (indexes, triggers) in &rollups {
+			if indexes.contains(&index) {
+				monitor
+					.triggers
+					.retain(|reference| !triggers.iter().any(|name| name == reference.name()));
+			}
+		}
+		if monitor.triggers.is_empty() {
+			tracing::debug!(
+				monitor = %monitor.name,
+				group = ?monitor.rollup_group,
+				"Match delivered through rollups only"
+			);
+			continue;
+		}
+		results.push(deduped);
+	}
+	results.extend(combined);
+	results
+}
+
+/// Returns the severity of a match, the highest `severity` variable of its trigger references
+pub(super) fn match_severity(monitor_match: &MonitorMatch) -> Option<&str> {
+	monitor_match
+		.monitor()
+		.triggers
+		.iter()
+		.filter_map(|reference| reference.variables()?.get(SEVERITY_VARIABLE))
+		.map(String::as_str)
+		.max_by_key(|severity| severity_rank(severity))
+}
+
+/// Returns the rank of a severity, unknown severities ranking below `info`
+pub(super) fn severity_rank(severity: &str) -> Option<usize> {
+	SEVERITIES
+		.iter()
+		.position(|s| s.eq_ignore_ascii_case(severity))
+}
+
+/// Describes the conditions a match was found on, by their signatures
+pub fn matched_conditions(monitor_match: &MonitorMatch) -> String {
+	let matched_on = monitor_match.matched_on();
+	let conditions = matched_on
+		.events
+		.iter()
+		.map(|event| event.signature.clone())
+		.chain(
+			matched_on
+				.functions
+				.iter()
+				.map(|function| function.signature.clone()),
+		)
+		.collect::<Vec<_>>();
+	if conditions.is_empty() {
+		"transaction".to_string()
+	} else {
+		conditions.join(", ")
+	}
+}
+
+/// Turns a combined match into the rollup notification of the matches it carries
+///
+/// The message of the match's trigger references is replaced by a summary listing each
+/// monitor, its matched conditions and transaction, and the returned variables describe the
+/// rollup: `rollup.group`, `rollup.count`, `rollup.monitors`, `rollup.<n>.monitor`,
+/// `rollup.<n>.condition` and `rollup.<n>.transaction_hash` for each match, and `severity`, the
+/// highest severity among them.
+///
+/// # Arguments
+/// * `monitor_match` - The combined match, i.e. the first match of the rollup
+/// * `rolled_up` - The other matches of the rollup
+///
+/// # Returns
+/// * `HashMap<String, String>` - Variables of the rollup
+pub fn apply_rollup(
+	monitor_match: &mut MonitorMatch,
+	rolled_up: &[MonitorMatch],
+) -> HashMap<String, String> {
+	let group = monitor_match
+		.monitor()
+		.rollup_group
+		.clone()
+		.unwrap_or_default();
+	let all = std::iter::once(&*monitor_match)
+		.chain(rolled_up)
+		.collect::<Vec<_>>();
+
+	let mut variables = HashMap::from([
+		("rollup.group".to_string(), group.clone()),
+		("rollup.count".to_string(), all.len().to_string()),
+		(
+			"rollup.monitors".to_string(),
+			all.iter()
+				.map(|m| m.monitor().name.as_str())
+				.collect::<Vec<_>>()
+				.join(", "),
+		),
+	]);
+	let mut lines = Vec::with_capacity(all.len() + 1);
+	for (index, member) in all.iter().enumerate() {
+		let monitor = &member.monitor().name;
+		let condition = matched_conditions(member);
+		let transaction_hash = member.transaction_hash();
+		lines.push(format!(
+			"- {}: {} in {}",
+			monitor, condition, transaction_hash
+		));
+		variables.extend([
+			(format!("rollup.{}.monitor", index), monitor.clone()),
+			(format!("rollup.{}.condition", index), condition),
+			(
+				format!("rollup.{}.transaction_hash", index),
+				transaction_hash,
+			),
+		]);
+	}
+	let severity = all
+		.iter()
+		.filter_map(|member| match_severity(member))
+		.max_by_key(|severity| severity_rank(severity))
+		.map(str::to_string);
+	if let Some(severity) = &severity {
+		lines.push(format!("Severity: {}", severity));
+		variables.insert(SEVERITY_VARIABLE.to_string(), severity.clone());
+	}
+
+	let message = MessageOverride {
+		title: Some(format!("{} related matches in {}", all.len(), group)),
+		body: Some(lines.join("\n")),
+	};
+	let monitor = monitor_match.monitor_mut();
+	monitor.triggers = monitor
+		.triggers
+		.iter()
+		.map(|reference| {
+			TriggerReference::WithOverrides(TriggerOverride {
+				name: reference.name().to_string(),
+				message: Some(message.clone()),
+				variables: reference.variables().cloned().unwrap_or_default(),
+				rollup: true,
+			})
+		})
+		.collect();
+	variables
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{
+		models::{EventCondition, MatchConditions, Monitor},
+		utils::tests::builders::evm::{
+			monitor::MonitorBuilder, monitor_match::MonitorMatchBuilder,
+			transaction::TransactionBuilder,
+		},
+	};
+	use alloy::primitives::B256;
+
+	fn create_match(monitor: Monitor, event: &str) -> DedupedMatch {
+		MonitorMatchBuilder::new()
+			.monitor(monitor)
+			.transaction(
+				TransactionBuilder::new()
+					.hash(B256::with_last_byte(1))
+					.build(),
+			)
+			.matched_on(MatchConditions {
+				events: vec![EventCondition {
+					signature: event.to_string(),
+					expression: None,
+					match_any_emitter: false,
+				}],
+				..Default::default()
+			})
+			.build_deduped()
+	}
+
+	fn create_monitor(name: &str, group: Option<&str>, severity: &str) -> Monitor {
+		let mut builder = MonitorBuilder::new()
+			.name(name)
+			.trigger_reference(TriggerReference::WithOverrides(TriggerOverride {
+				name: "slack".to_string(),
+				message: None,
+				variables: HashMap::from([(SEVERITY_VARIABLE.to_string(), severity.to_string())]),
+				rollup: false,
+			}))
+			.trigger_reference(TriggerReference::Name("database".to_string()));
+		if let Some(group) = group {
+			builder = builder.rollup_group(group);
+		}
+		builder.build()
+	}
+
+	fn triggers_of(deduped: &DedupedMatch) -> Vec<String> {
+		deduped.monitor_match.monitor().trigger_names()
+	}
+
+	fn is_slack(reference: &TriggerReference) -> bool {
+		reference.name() == "slack"
+	}
+
+	#[test]
+	fn test_related_matches_are_rolled_up() {
+		let matches = vec![
+			create_match(create_monitor("pause", Some("health"), "low"), "Paused()"),
+			create_match(
+				create_monitor("admin", Some("health"), "critical"),
+				"AdminChanged(address,address)",
+			),
+			create_match(create_monitor("other", None, "high"), "Transfer()"),
+			create_match(
+				create_monitor("oracle", Some("health"), "high"),
+				"AnswerUpdated(int256,uint256,uint256)",
+			),
+		];
+
+		let results = rollup_matches(matches, is_slack);
+
+		assert_eq!(results.len(), 5);
+		for deduped in &results[..2] {
+			assert_eq!(triggers_of(deduped), vec!["database".to_string()]);
+		}
+		assert_eq!(results[2].monitor_match.monitor().name, "other");
+		assert_eq!(
+			triggers_of(&results[2]),
+			vec!["slack".to_string(), "database".to_string()]
+		);
+		assert_eq!(triggers_of(&results[3]), vec!["database".to_string()]);
+
+		let rollup = &results[4];
+		assert_eq!(rollup.monitor_match.monitor().name, "pause");
+		assert_eq!(triggers_of(rollup), vec!["slack".to_string()]);
+		let rolled_up = rollup
+			.rolled_up
+			.iter()
+			.map(|m| m.monitor().name.as_str())
+			.collect::<Vec<_>>();
+		assert_eq!(rolled_up, vec!["admin", "oracle"]);
+	}
+
+	#[test]
+	fn test_single_match_of_group_is_not_rolled_up() {
+		let matches = vec![
+			create_match(create_monitor("pause", Some("health"), "low"), "Paused()"),
+			create_match(create_monitor("admin", Some("other"), "low"), "Paused()"),
+		];
+
+		let results = rollup_matches(matches, is_slack);
+
+		assert_eq!(results.len(), 2);
+		assert!(results.iter().all(|m| m.rolled_up.is_empty()));
+		assert!(results
+			.iter()
+			.all(|m| triggers_of(m) == vec!["slack".to_string(), "database".to_string()]));
+	}
+
+	#[test]
+	fn test_apply_rollup_summarizes_matches() {
+		let mut results = rollup_matches(
+			vec![
+				create_match(create_monitor("pause", Some("health"), "low"), "Paused()"),
+				create_match(
+					create_monitor("admin", Some("health"), "critical"),
+					"AdminChanged(address,address)",
+				),
+			],
+			is_slack,
+		);
+		let DedupedMatch {
+			mut monitor_match,
+			rolled_up,
+			..
+		} = results.pop().unwrap();
+
+		let variables = apply_rollup(&mut monitor_match, &rolled_up);
+
+		assert_eq!(variables["rollup.group"], "health");
+		assert_eq!(variables["rollup.count"], "2");
+		assert_eq!(variables["rollup.monitors"], "pause, admin");
+		assert_eq!(
+			variables["rollup.1.condition"],
+			"AdminChanged(address,address)"
+		);
+		assert_eq!(variables[SEVERITY_VARIABLE], "critical");
+
+		let reference = &monitor_match.monitor().triggers[0];
+		assert!(reference.rollup());
+		let message = reference.message().unwrap();
+		assert_eq!(
+			message.title.as_deref(),
+			Some("2 related matches in health")
+		);
+		let body = message.body.as_deref().unwrap();
+		assert!(body.contains("- pause: Paused() in 0x"));
+		assert!(body.contains("- admin: AdminChanged(address,address) in 0x"));
+		assert!(body.ends_with("Severity: critical"));
+	}
+
+	#[test]
+	fn test_severity_ranking() {
+		assert!(severity_rank("critical") > severity_rank("HIGH"));
+		assert!(severity_rank("info") > severity_rank("unknown"));
+	}
+}
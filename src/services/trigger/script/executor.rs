@@ -2,7 +2,7 @@
 //!
 //! This module provides functionality to execute scripts in different languages.
 
-use crate::models::MonitorMatch;
+use crate::models::{MonitorMatch, ScriptLanguage};
 use anyhow::Context;
 use async_trait::async_trait;
 use std::{any::Any, process::Stdio, time::Duration};
@@ -203,12 +203,81 @@ pub fn process_script_output(
 	}
 }
 
+/// Executes a script and returns the last line of its output.
+///
+/// Used by scripts producing data rather than a boolean, such as monitor enrichments. The
+/// script receives the same input as the script executors.
+///
+/// # Arguments
+/// * `language` - The language of the script
+/// * `script_content` - The content of the script
+/// * `input` - The MonitorMatch passed to the script
+/// * `timeout_ms` - The timeout for the script execution in milliseconds
+/// * `args` - Additional arguments passed to the script
+///
+/// # Errors
+/// Returns an error if the script cannot be spawned, times out, exits with a non-zero code or
+/// produces no output.
+pub async fn execute_script_for_output(
+	language: &ScriptLanguage,
+	script_content: &str,
+	input: &MonitorMatch,
+	timeout_ms: u32,
+	args: Option<&[String]>,
+) -> Result<String, anyhow::Error> {
+	let combined_input = serde_json::json!({
+		"monitor_match": input,
+		"args": args
+	});
+	let input_json = serde_json::to_string(&combined_input)
+		.with_context(|| "Failed to serialize monitor match and arguments")?;
+
+	let (program, flag) = match language {
+		ScriptLanguage::Python => ("python3", "-c"),
+		ScriptLanguage::JavaScript => ("node", "-e"),
+		ScriptLanguage::Bash => ("sh", "-c"),
+	};
+	let cmd = tokio::process::Command::new(program)
+		.arg(flag)
+		.arg(script_content)
+		.stdin(Stdio::piped())
+		.stdout(Stdio::piped())
+		.stderr(Stdio::piped())
+		.spawn()
+		.with_context(|| format!("Failed to spawn {} process", program))?;
+
+	let output = wait_for_output(cmd, &input_json, &timeout_ms).await?;
+	if !output.status.success() {
+		let error_message = String::from_utf8_lossy(&output.stderr).to_string();
+		return Err(anyhow::anyhow!(
+			"Script execution failed: {}",
+			error_message
+		));
+	}
+	String::from_utf8_lossy(&output.stdout)
+		.lines()
+		.map(str::trim)
+		.rfind(|line| !line.is_empty())
+		.map(str::to_string)
+		.ok_or_else(|| anyhow::anyhow!("Script produced no output"))
+}
+
 async fn process_command(
-	mut cmd: tokio::process::Child,
+	cmd: tokio::process::Child,
 	input_json: &str,
 	timeout_ms: &u32,
 	from_custom_notification: bool,
 ) -> Result<bool, anyhow::Error> {
+	let output = wait_for_output(cmd, input_json, timeout_ms).await?;
+	process_script_output(output, from_custom_notification)
+}
+
+/// Writes the input to a spawned script and waits for its output
+async fn wait_for_output(
+	mut cmd: tokio::process::Child,
+	input_json: &str,
+	timeout_ms: &u32,
+) -> Result<std::process::Output, anyhow::Error> {
 	if let Some(mut stdin) = cmd.stdin.take() {
 		stdin
 			.write_all(input_json.as_bytes())
@@ -228,9 +297,7 @@ async fn process_command(
 
 	match timeout(timeout_duration, cmd.wait_with_output()).await {
 		Ok(result) => {
-			let output =
-				result.map_err(|e| anyhow::anyhow!("Failed to wait for script output: {}", e))?;
-			process_script_output(output, from_custom_notification)
+			result.map_err(|e| anyhow::anyhow!("Failed to wait for script output: {}", e))
 		}
 		Err(_) => Err(anyhow::anyhow!("Script execution timed out")),
 	}
@@ -304,8 +371,16 @@ mod tests {
 				functions: vec![],
 				events: vec![],
 				transactions: vec![],
+				sender_activity: None,
+				baseline: None,
+				network_metrics: None,
 			},
 			matched_on_args: None,
+			sender_activity: None,
+			baseline: None,
+			network_metrics: None,
+			timing: None,
+			origin: Default::default(),
 		}))
 	}
 
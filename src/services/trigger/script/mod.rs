@@ -9,6 +9,6 @@ mod executor;
 mod factory;
 mod validation;
 pub use error::ScriptError;
-pub use executor::{process_script_output, ScriptExecutor};
+pub use executor::{execute_script_for_output, process_script_output, ScriptExecutor};
 pub use factory::ScriptExecutorFactory;
 pub use validation::validate_script_config;
@@ -3,15 +3,23 @@
 //! Provides functionality to execute triggers with variable substitution
 //! and notification delivery. Manages trigger lookup and execution flow.
 
-use std::{collections::HashMap, path::Path};
+use std::{borrow::Cow, collections::HashMap, path::Path, sync::Arc};
 
 use anyhow::Context;
 use async_trait::async_trait;
 
 use crate::{
-	models::{Monitor, MonitorMatch, ScriptLanguage, TriggerTypeConfig},
+	models::{Enrichment, Monitor, MonitorMatch, ScriptLanguage, Trigger, TriggerTypeConfig},
 	repositories::{TriggerRepositoryTrait, TriggerService},
-	services::{notification::NotificationService, trigger::error::TriggerError},
+	services::{
+		filter::PriceOracles,
+		notification::{match_uuid, NotificationPreview, NotificationService},
+		trigger::{
+			error::TriggerError, prior_match_variables, resolve_template_variables, AddressLabels,
+			AlertLatency, CircuitCheck, MatchHistory, TemplateVariablesMode,
+			TriggerCircuitBreakers, TriggerMutes, ALERT_LATENCY_VARIABLE,
+		},
+	},
 	utils::normalize_string,
 };
 
@@ -32,6 +40,15 @@ pub trait TriggerExecutionServiceTrait {
 		&self,
 		monitors: &[Monitor],
 	) -> Result<HashMap<String, (ScriptLanguage, String)>, TriggerError>;
+	fn get_trigger(&self, trigger_slug: &str) -> Option<Arc<Trigger>>;
+	/// Returns the history of the matches dispatched with this service, if it keeps one
+	fn match_history(&self) -> Option<&MatchHistory> {
+		None
+	}
+	/// Returns the price oracles the USD values of the matches are computed with, if any
+	fn price_oracles(&self) -> Option<&PriceOracles> {
+		None
+	}
 }
 
 /// Service for executing triggers with notifications
@@ -43,6 +60,19 @@ pub struct TriggerExecutionService<T: TriggerRepositoryTrait> {
 	trigger_service: TriggerService<T>,
 	/// Service for sending notifications
 	notification_service: NotificationService,
+	/// Clock the latency of delivered notifications is measured against
+	latency: AlertLatency,
+	/// Labels of the watched addresses, rendered into notifications
+	address_labels: AddressLabels,
+	/// Circuit breakers suppressing deliveries to failing triggers
+	circuit_breakers: Option<Arc<TriggerCircuitBreakers>>,
+	/// Mutes silencing deliveries to triggers during maintenance
+	mutes: Option<Arc<TriggerMutes>>,
+	/// History of the dispatched matches, for monitors with a `history` or triggers with a
+	/// `context`
+	match_history: Option<Arc<MatchHistory>>,
+	/// Price oracles the USD values of the matches are computed with
+	price_oracles: Option<Arc<PriceOracles>>,
 }
 
 impl<T: TriggerRepositoryTrait> TriggerExecutionService<T> {
@@ -61,8 +91,177 @@ impl<T: TriggerRepositoryTrait> TriggerExecutionService<T> {
 		Self {
 			trigger_service,
 			notification_service,
+			latency: AlertLatency::default(),
+			address_labels: AddressLabels::default(),
+			circuit_breakers: None,
+			mutes: None,
+			match_history: None,
+			price_oracles: None,
+		}
+	}
+
+	/// Replaces the clock the latency of delivered notifications is measured against
+	pub fn with_latency(mut self, latency: AlertLatency) -> Self {
+		self.latency = latency;
+		self
+	}
+
+	/// Sets the labels of the watched addresses, rendered into notifications
+	pub fn with_address_labels(mut self, address_labels: AddressLabels) -> Self {
+		self.address_labels = address_labels;
+		self
+	}
+
+	/// Sets the circuit breakers suppressing deliveries to failing triggers
+	pub fn with_circuit_breakers(mut self, circuit_breakers: Arc<TriggerCircuitBreakers>) -> Self {
+		self.circuit_breakers = Some(circuit_breakers);
+		self
+	}
+
+	/// Sets the mutes silencing deliveries to triggers during maintenance
+	pub fn with_mutes(mut self, mutes: Arc<TriggerMutes>) -> Self {
+		self.mutes = Some(mutes);
+		self
+	}
+
+	/// Sets the history of the dispatched matches
+	pub fn with_match_history(mut self, match_history: Arc<MatchHistory>) -> Self {
+		self.match_history = Some(match_history);
+		self
+	}
+
+	/// Sets the price oracles the USD values of the matches are computed with
+	pub fn with_price_oracles(mut self, price_oracles: Arc<PriceOracles>) -> Self {
+		self.price_oracles = Some(price_oracles);
+		self
+	}
+
+	/// Adds the variables shared by all deliveries of a match, i.e. the labels of its
+	/// addresses and the latency of the alert
+	fn annotate_variables(
+		&self,
+		mut variables: HashMap<String, String>,
+		monitor_match: &MonitorMatch,
+	) -> HashMap<String, String> {
+		self.address_labels
+			.annotate(monitor_match.network_slug(), &mut variables);
+		if let Some(timing) = monitor_match.timing() {
+			variables.insert(
+				ALERT_LATENCY_VARIABLE.to_string(),
+				self.latency.alert_latency_ms(timing).to_string(),
+			);
 		}
+		variables
 	}
+
+	/// Renders the payloads the given triggers would deliver for a match, without sending them
+	///
+	/// Variables and monitor overrides are applied as when executing the triggers, and
+	/// triggers not accepting the origin of the match are skipped. Mutes and circuit breakers
+	/// are not consulted, and unset template variables are rendered as missing markers whatever
+	/// the mode of the trigger.
+	///
+	/// # Arguments
+	/// * `trigger_slugs` - List of trigger identifiers to preview
+	/// * `variables` - Variables to substitute in trigger templates
+	/// * `monitor_match` - Match to preview the notifications of
+	///
+	/// # Returns
+	/// * `Result<Vec<NotificationPreview>, TriggerError>` - Previews in the order of the triggers
+	///
+	/// # Errors
+	/// - Returns `TriggerError::NotFound` if a trigger cannot be found
+	/// - Returns `TriggerError::ExecutionError` if a payload cannot be rendered
+	pub async fn preview(
+		&self,
+		trigger_slugs: &[String],
+		variables: HashMap<String, String>,
+		monitor_match: &MonitorMatch,
+	) -> Result<Vec<NotificationPreview>, TriggerError> {
+		let monitor = monitor_match.monitor();
+		let match_id = match_uuid(monitor_match);
+		let variables = &self.annotate_variables(variables, monitor_match);
+		let timestamp = chrono::Utc::now().timestamp_millis();
+
+		let mut previews = Vec::with_capacity(trigger_slugs.len());
+		for trigger_slug in trigger_slugs {
+			let trigger = self
+				.trigger_service
+				.get(trigger_slug)
+				.ok_or_else(|| TriggerError::not_found(trigger_slug.to_string(), None, None))?;
+			if !trigger.accepts_origin(monitor_match.origin()) {
+				continue;
+			}
+			let (trigger, variables) = apply_overrides(monitor, trigger_slug, &trigger, variables);
+			// Previews leave out the match history, the earlier matches list stays empty
+			let variables = with_prior_matches(None, &trigger, monitor_match, variables).await;
+			let variables =
+				resolve_template_variables(&trigger, &variables, TemplateVariablesMode::Lenient)
+					.expect("lenient resolution does not fail");
+			let preview = self
+				.notification_service
+				.preview(&trigger, &variables, &match_id, monitor_match, timestamp)
+				.await
+				.map_err(|e| TriggerError::execution_error(e.to_string(), Some(e.into()), None))?;
+			// Previews are named after the slug the monitor references the trigger by
+			previews.push(NotificationPreview {
+				trigger: trigger_slug.clone(),
+				..preview
+			});
+		}
+		Ok(previews)
+	}
+}
+
+/// Adds the earlier matches of the monitor to the variables of a trigger with a `context`
+///
+/// Without a match history, or when the lookup fails, the list is empty.
+async fn with_prior_matches<'a>(
+	match_history: Option<&MatchHistory>,
+	trigger: &Trigger,
+	monitor_match: &MonitorMatch,
+	variables: Cow<'a, HashMap<String, String>>,
+) -> Cow<'a, HashMap<String, String>> {
+	let Some(context) = &trigger.context else {
+		return variables;
+	};
+	let prior = match match_history {
+		Some(history) => {
+			history
+				.prior_matches(monitor_match, &variables, context)
+				.await
+		}
+		None => vec![],
+	};
+	let mut variables = variables.into_owned();
+	variables.extend(prior_match_variables(&prior));
+	Cow::Owned(variables)
+}
+
+/// Applies the monitor's overrides for a trigger, match variables taking precedence over the
+/// static ones. The trigger is only cloned when its message is overridden.
+fn apply_overrides<'a>(
+	monitor: &Monitor,
+	trigger_slug: &str,
+	trigger: &'a Trigger,
+	variables: &'a HashMap<String, String>,
+) -> (Cow<'a, Trigger>, Cow<'a, HashMap<String, String>>) {
+	let reference = monitor.trigger_reference(trigger_slug);
+	let trigger = match reference.and_then(|r| r.message()) {
+		Some(message_override) => {
+			Cow::Owned(trigger.clone().with_message_override(message_override))
+		}
+		None => Cow::Borrowed(trigger),
+	};
+	let variables = match reference.and_then(|r| r.variables()) {
+		Some(extra) if !extra.is_empty() => {
+			let mut merged = extra.clone();
+			merged.extend(variables.iter().map(|(k, v)| (k.clone(), v.clone())));
+			Cow::Owned(merged)
+		}
+		_ => Cow::Borrowed(variables),
+	};
+	(trigger, variables)
 }
 
 #[async_trait]
@@ -71,6 +270,15 @@ impl<T: TriggerRepositoryTrait + Send + Sync> TriggerExecutionServiceTrait
 {
 	/// Executes multiple triggers with variable substitution
 	///
+	/// Work that only depends on the match, such as its identifier and template variables, is
+	/// done once and shared by all trigger deliveries. Matches carrying their pipeline times
+	/// expose the latency of the alert as `alert_latency_ms` and have the stage durations of
+	/// every successful delivery recorded. Variables holding a labeled address get their label
+	/// as `<name>_label`, and the labeled addresses are listed in `addresses_involved`. Triggers
+	/// not accepting the origin of the match or muted for its monitor are skipped, and
+	/// deliveries to triggers whose circuit breaker is open fail without being attempted. Unset template variables fail the
+	/// delivery of strict triggers and are rendered as missing markers by the others.
+	///
 	/// # Arguments
 	/// * `trigger_slugs` - List of trigger identifiers to execute
 	/// * `variables` - Variables to substitute in trigger templates
@@ -80,7 +288,8 @@ impl<T: TriggerRepositoryTrait + Send + Sync> TriggerExecutionServiceTrait
 	///
 	/// # Errors
 	/// - Returns `TriggerError::NotFound` if a trigger cannot be found
-	/// - Returns `TriggerError::ExecutionError` if notification delivery fails
+	/// - Returns `TriggerError::ExecutionError` if notification delivery fails, or a strict
+	///   trigger references an unset variable
 	async fn execute(
 		&self,
 		trigger_slugs: &[String],
@@ -90,17 +299,110 @@ impl<T: TriggerRepositoryTrait + Send + Sync> TriggerExecutionServiceTrait
 	) -> Result<(), TriggerError> {
 		use futures::future::join_all;
 
+		let monitor = monitor_match.monitor();
+		let match_id = match_uuid(monitor_match);
+		let variables = &self.annotate_variables(variables, monitor_match);
+
 		let futures = trigger_slugs.iter().map(|trigger_slug| async {
 			let trigger = self
 				.trigger_service
 				.get(trigger_slug)
 				.ok_or_else(|| TriggerError::not_found(trigger_slug.to_string(), None, None))?;
 
-			self.notification_service
-				.execute(&trigger, &variables, monitor_match, trigger_scripts)
-				.await
-				// We remove logging capability here since we're logging it further down
-				.map_err(|e| TriggerError::execution_error_without_log(e.to_string(), None, None))
+			if !trigger.accepts_origin(monitor_match.origin()) {
+				tracing::debug!(
+					"Skipping trigger '{}' for a match of origin {:?}",
+					trigger.name,
+					monitor_match.origin()
+				);
+				return Ok(());
+			}
+
+			if let Some(mutes) = &self.mutes {
+				if let Some(mute) = mutes.check(trigger_slug, &monitor.name).await {
+					tracing::debug!(
+						"Skipping muted trigger '{}' for monitor '{}': {}",
+						trigger_slug,
+						monitor.name,
+						mute.reason
+					);
+					return Ok(());
+				}
+			}
+
+			let (trigger, variables) = apply_overrides(monitor, trigger_slug, &trigger, variables);
+			let variables = with_prior_matches(
+				self.match_history.as_deref(),
+				&trigger,
+				monitor_match,
+				variables,
+			)
+			.await;
+			let variables = resolve_template_variables(
+				&trigger,
+				&variables,
+				TemplateVariablesMode::for_trigger(&trigger),
+			)
+			.map_err(|missing| {
+				TriggerError::execution_error_without_log(
+					format!(
+						"Trigger '{}' references unset template variables: {}",
+						trigger_slug,
+						missing.join(", ")
+					),
+					None,
+					None,
+				)
+			})?;
+
+			if let Some(circuit_breakers) = &self.circuit_breakers {
+				if let CircuitCheck::Suppress { until_ms, failures } =
+					circuit_breakers.check(trigger_slug).await
+				{
+					return Err(TriggerError::execution_error_without_log(
+						format!(
+							"Delivery to trigger '{}' suppressed until {} after {} consecutive \
+							 failures",
+							trigger_slug,
+							chrono::DateTime::from_timestamp_millis(until_ms)
+								.map_or_else(|| until_ms.to_string(), |time| time.to_rfc3339()),
+							failures
+						),
+						None,
+						None,
+					));
+				}
+			}
+
+			let result = self
+				.notification_service
+				.execute_with_match_id(
+					&trigger,
+					&variables,
+					&match_id,
+					monitor_match,
+					trigger_scripts,
+				)
+				.await;
+			if let Some(circuit_breakers) = &self.circuit_breakers {
+				match &result {
+					Ok(()) => circuit_breakers.record_success(trigger_slug).await,
+					Err(_) => circuit_breakers.record_failure(trigger_slug).await,
+				}
+			}
+			// We remove logging capability here since we're logging it further down
+			result.map_err(|e| {
+				TriggerError::execution_error_without_log(e.to_string(), None, None)
+			})?;
+
+			if let Some(timing) = monitor_match.timing() {
+				self.latency.record_delivery(
+					timing,
+					monitor_match.network_slug(),
+					trigger.trigger_type.as_str(),
+				);
+			}
+			Ok::<(), TriggerError>(())
 		});
 
 		let results = join_all(futures).await;
@@ -151,7 +453,10 @@ impl<T: TriggerRepositoryTrait + Send + Sync> TriggerExecutionServiceTrait
 
 		for monitor in monitors {
 			// Skip monitors without trigger conditions
-			if monitor.trigger_conditions.is_empty() && monitor.triggers.is_empty() {
+			if monitor.trigger_conditions.is_empty()
+				&& monitor.triggers.is_empty()
+				&& monitor.enrichments.is_empty()
+			{
 				continue;
 			}
 
@@ -176,16 +481,35 @@ impl<T: TriggerRepositoryTrait + Send + Sync> TriggerExecutionServiceTrait
 				);
 			}
 
+			// Load the scripts of the script enrichments
+			for enrichment in &monitor.enrichments {
+				let Enrichment::Script(enrichment) = enrichment else {
+					continue;
+				};
+				let content = tokio::fs::read_to_string(&enrichment.script_path)
+					.await
+					.with_context(|| {
+						format!("Failed to read script file: {}", enrichment.script_path)
+					})?;
+				scripts.insert(
+					format!(
+						"{}|{}",
+						normalize_string(&monitor.name),
+						enrichment.script_path
+					),
+					(enrichment.language.clone(), content),
+				);
+			}
+
 			// For each trigger, we'll load the script
 			for trigger in &monitor.triggers {
-				let trigger_config =
-					self.trigger_service.get(trigger.as_str()).ok_or_else(|| {
-						TriggerError::configuration_error(
-							format!("Failed to get trigger: {}", trigger),
-							None,
-							None,
-						)
-					})?;
+				let trigger_config = self.trigger_service.get(trigger.name()).ok_or_else(|| {
+					TriggerError::configuration_error(
+						format!("Failed to get trigger: {}", trigger.name()),
+						None,
+						None,
+					)
+				})?;
 
 				let TriggerTypeConfig::Script {
 					language,
@@ -223,4 +547,25 @@ impl<T: TriggerRepositoryTrait + Send + Sync> TriggerExecutionServiceTrait
 
 		Ok(scripts)
 	}
+
+	/// Looks up a trigger configuration by its identifier
+	///
+	/// # Arguments
+	/// * `trigger_slug` - Identifier of the trigger
+	///
+	/// # Returns
+	/// * `Option<Arc<Trigger>>` - The trigger configuration, if it exists
+	fn get_trigger(&self, trigger_slug: &str) -> Option<Arc<Trigger>> {
+		self.trigger_service.get(trigger_slug)
+	}
+
+	/// Returns the history of the dispatched matches, if one is set
+	fn match_history(&self) -> Option<&MatchHistory> {
+		self.match_history.as_deref()
+	}
+
+	/// Returns the price oracles the USD values of the matches are computed with, if set
+	fn price_oracles(&self) -> Option<&PriceOracles> {
+		self.price_oracles.as_deref()
+	}
 }
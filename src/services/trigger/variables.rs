@@ -0,0 +1,780 @@
+//! Template variable catalog and resolution.
+//!
+//! The catalog lists the variables a match can set, and the variable tables of the
+//! documentation are generated from it. Templates of triggers in strict mode are checked against
+//! it when the configuration loads, and their deliveries fail when a referenced variable is
+//! unset. Other triggers render unset variables as `⟨missing:<name>⟩` markers, so that a typo
+//! shows up in the first notification instead of passing through silently.
+//!
+//! Strict mode is enabled for all triggers with `STRICT_TEMPLATE_VARIABLES=true`, and set per
+//! trigger with `strict_variables`.
+
+use std::{borrow::Cow, collections::HashMap};
+
+use crate::{
+	models::{Enrichment, Monitor, NotificationMessage, Trigger, TriggerReference},
+	services::trigger::{
+		ADDRESSES_INVOLVED_VARIABLE, ALERT_LATENCY_VARIABLE, FIRST_OCCURRENCE_VARIABLE,
+		LAST_SEEN_AT_VARIABLE, PRIOR_COUNT_VARIABLE, PRIOR_MATCHES_COUNT_VARIABLE, PRIOR_VARIABLE,
+		SEVERITY_VARIABLE,
+	},
+};
+
+/// Environment variable enabling strict template variables for triggers not setting the mode
+pub const STRICT_TEMPLATE_VARIABLES_ENV: &str = "STRICT_TEMPLATE_VARIABLES";
+
+/// Sections rendered from the matched conditions, which are always resolved
+const SECTION_VARIABLES: [&str; 2] = ["events", "functions"];
+
+/// Placeholder of a catalog variable name standing for any other variable
+const ANY_VARIABLE: &str = "<variable>";
+
+/// How references to unset variables are handled
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TemplateVariablesMode {
+	/// Unset variables are rendered as `⟨missing:<name>⟩` markers
+	#[default]
+	Lenient,
+	/// Unknown references fail the configuration, unset variables fail the delivery
+	Strict,
+}
+
+impl TemplateVariablesMode {
+	/// Returns the mode set by `STRICT_TEMPLATE_VARIABLES`, lenient unless it is `true`
+	pub fn from_env() -> Self {
+		match std::env::var(STRICT_TEMPLATE_VARIABLES_ENV) {
+			Ok(value) if value == "true" => Self::Strict,
+			_ => Self::Lenient,
+		}
+	}
+
+	/// Returns the mode of a trigger, its `strict_variables` setting taking precedence over the
+	/// environment
+	pub fn for_trigger(trigger: &Trigger) -> Self {
+		match trigger.strict_variables {
+			Some(true) => Self::Strict,
+			Some(false) => Self::Lenient,
+			None => Self::from_env(),
+		}
+	}
+}
+
+/// Networks a template variable is set for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VariableScope {
+	/// Variables set for matches of every network
+	Common,
+	/// Variables set for matches of EVM networks
+	Evm,
+	/// Variables set for matches of Stellar networks
+	Stellar,
+}
+
+/// Variable of the catalog
+///
+/// Names are dotted paths where `[index]` stands for a position in a list, `[param]` or
+/// `[position]` for a parameter of a condition and `[metric]` for a metric of a network.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TemplateVariable {
+	/// Name of the variable
+	pub name: &'static str,
+	/// Networks the variable is set for
+	pub scope: VariableScope,
+	/// Description of the variable, in Markdown
+	pub description: &'static str,
+}
+
+const fn variable(
+	name: &'static str,
+	scope: VariableScope,
+	description: &'static str,
+) -> TemplateVariable {
+	TemplateVariable {
+		name,
+		scope,
+		description,
+	}
+}
+
+/// Variables a match can set, in the order of the documentation
+pub const TEMPLATE_VARIABLES: &[TemplateVariable] = &[
+	variable(
+		"monitor.name",
+		VariableScope::Common,
+		"Name of the triggered monitor",
+	),
+	variable(
+		"owner.team",
+		VariableScope::Common,
+		"Team owning the monitor, see [Ownership](#ownership)",
+	),
+	variable(
+		"owner.contact",
+		VariableScope::Common,
+		"Contact of the owning team",
+	),
+	variable(
+		"owner.runbook_url",
+		VariableScope::Common,
+		"Runbook to follow when the monitor fires",
+	),
+	variable(
+		ALERT_LATENCY_VARIABLE,
+		VariableScope::Common,
+		"Milliseconds between the block timestamp and the notification being sent, see [Time to \
+		 Alert](#time-to-alert)",
+	),
+	variable(
+		"<variable>_label",
+		VariableScope::Common,
+		"Label of the address held by `<variable>`, see [Address Labels](#address-labels)",
+	),
+	variable(
+		ADDRESSES_INVOLVED_VARIABLE,
+		VariableScope::Common,
+		"Labeled addresses involved in the match, with their annotations",
+	),
+	variable(
+		FIRST_OCCURRENCE_VARIABLE,
+		VariableScope::Common,
+		"Whether the match is the first of its kind, see [Match History](#match-history)",
+	),
+	variable(
+		PRIOR_COUNT_VARIABLE,
+		VariableScope::Common,
+		"Number of prior similar matches",
+	),
+	variable(
+		LAST_SEEN_AT_VARIABLE,
+		VariableScope::Common,
+		"Time of the last similar match",
+	),
+	variable(
+		PRIOR_VARIABLE,
+		VariableScope::Common,
+		"Earlier matches of the monitor, one per line, for triggers with a `context`, see [Match \
+		 Context](#match-context)",
+	),
+	variable(
+		PRIOR_MATCHES_COUNT_VARIABLE,
+		VariableScope::Common,
+		"Number of earlier matches of the monitor",
+	),
+	variable(
+		"prior.[index].timestamp",
+		VariableScope::Common,
+		"Time of an earlier match",
+	),
+	variable(
+		"prior.[index].transaction_hash",
+		VariableScope::Common,
+		"Transaction of an earlier match",
+	),
+	variable(
+		"prior.[index].summary",
+		VariableScope::Common,
+		"Conditions matched by an earlier match",
+	),
+	variable(
+		"also_matched.[index]",
+		VariableScope::Common,
+		"Other monitors of the dedup group matching the transaction, see [Dedup \
+		 Groups](#dedup-groups)",
+	),
+	variable(
+		"rollup.group",
+		VariableScope::Common,
+		"Rollup group of a combined notification, see [Rollups](#rollups)",
+	),
+	variable(
+		"rollup.count",
+		VariableScope::Common,
+		"Number of matches of a combined notification",
+	),
+	variable(
+		"rollup.monitors",
+		VariableScope::Common,
+		"Monitors of the matches of a combined notification",
+	),
+	variable(
+		"rollup.[index].monitor",
+		VariableScope::Common,
+		"Monitor of a match of a combined notification",
+	),
+	variable(
+		"rollup.[index].condition",
+		VariableScope::Common,
+		"Conditions matched by a match of a combined notification",
+	),
+	variable(
+		"rollup.[index].transaction_hash",
+		VariableScope::Common,
+		"Transaction of a match of a combined notification",
+	),
+	variable(
+		SEVERITY_VARIABLE,
+		VariableScope::Common,
+		"Highest severity of the matches of a combined notification",
+	),
+	variable(
+		"divergence.network",
+		VariableScope::Common,
+		"Network view that diverged, see [Network Views](#network-views)",
+	),
+	variable(
+		"divergence.group",
+		VariableScope::Common,
+		"Group of the diverging network views",
+	),
+	variable(
+		"divergence.block_number",
+		VariableScope::Common,
+		"Block the transaction was first matched in",
+	),
+	variable(
+		"divergence.observed_block",
+		VariableScope::Common,
+		"Block the diverging view had reached",
+	),
+	variable(
+		"network_metrics.[metric]",
+		VariableScope::Common,
+		"Metric of the block the match fired on, see [Network Metrics \
+		 Conditions](#network-metrics-conditions)",
+	),
+	variable(
+		"network_metrics.blocks",
+		VariableScope::Common,
+		"Number of blocks in a row the condition held for",
+	),
+	variable(
+		"network_metrics.first_block",
+		VariableScope::Common,
+		"First block of the series the condition held for",
+	),
+	variable(
+		"transaction.hash",
+		VariableScope::Common,
+		"Hash of the transaction",
+	),
+	variable(
+		"functions",
+		VariableScope::Common,
+		"All functions matched and their parameters",
+	),
+	variable(
+		"events",
+		VariableScope::Common,
+		"All events matched and their parameters",
+	),
+	variable("transaction.from", VariableScope::Evm, "Sender address"),
+	variable("transaction.to", VariableScope::Evm, "Recipient address"),
+	variable("transaction.value", VariableScope::Evm, "Transaction value"),
+	variable(
+		"transaction.value_usd",
+		VariableScope::Evm,
+		"Transaction value in USD, if the network has a [price oracle](#price-oracle) pricing \
+		 `native`",
+	),
+	variable(
+		"transaction.index",
+		VariableScope::Evm,
+		"Index of the transaction in the block",
+	),
+	variable(
+		"events.[index].signature",
+		VariableScope::Evm,
+		"Event signature",
+	),
+	variable(
+		"events.[index].args.[param]",
+		VariableScope::Evm,
+		"Event parameters by name",
+	),
+	variable(
+		"events.[index].args.[param]_usd",
+		VariableScope::Evm,
+		"USD value of an unsigned integer event parameter, if the price oracle prices the \
+		 emitting contract",
+	),
+	variable(
+		"events.[index].log_index",
+		VariableScope::Evm,
+		"Index of the event's log in the block",
+	),
+	variable(
+		"events.[index].preset",
+		VariableScope::Evm,
+		"Preset the event condition comes from, see [Condition Presets](#condition-presets)",
+	),
+	variable(
+		"functions.[index].signature",
+		VariableScope::Evm,
+		"Function signature",
+	),
+	variable(
+		"functions.[index].args.[param]",
+		VariableScope::Evm,
+		"Function parameters by name",
+	),
+	variable(
+		"functions.[index].args.[param]_usd",
+		VariableScope::Evm,
+		"USD value of an unsigned integer function parameter, if the price oracle prices the \
+		 called contract",
+	),
+	variable(
+		"sender_activity.kind",
+		VariableScope::Evm,
+		"`nonce_gap`, `replacement` or `stuck`, see [Sender Activity \
+		 Conditions](#sender-activity-conditions-evm)",
+	),
+	variable(
+		"sender_activity.nonce",
+		VariableScope::Evm,
+		"Nonce of the transaction, or the missing nonce when stuck",
+	),
+	variable(
+		"sender_activity.expected_nonce",
+		VariableScope::Evm,
+		"Nonce the sender was expected to use next",
+	),
+	variable(
+		"sender_activity.replaced_hash",
+		VariableScope::Evm,
+		"Hash of the transaction previously seen with the same nonce",
+	),
+	variable(
+		"sender_activity.stuck_duration",
+		VariableScope::Evm,
+		"Seconds the missing nonce has been left unmined",
+	),
+	variable(
+		"baseline.short_window_value",
+		VariableScope::Evm,
+		"Activity of the short window, see [Baseline Conditions](#baseline-conditions-evm)",
+	),
+	variable(
+		"baseline.long_window_average",
+		VariableScope::Evm,
+		"Average activity of the long window, scaled to the length of the short window",
+	),
+	variable(
+		"baseline.ratio",
+		VariableScope::Evm,
+		"Ratio of the short window value to the long window average, floored",
+	),
+	variable(
+		"decode_failure.condition",
+		VariableScope::Evm,
+		"Condition that failed to decode, see [Decoding Failures](#decoding-failures-evm)",
+	),
+	variable(
+		"decode_failure.selector",
+		VariableScope::Evm,
+		"Function selector or event topic of the last failure",
+	),
+	variable(
+		"decode_failure.error",
+		VariableScope::Evm,
+		"Decoding error of the last failure",
+	),
+	variable(
+		"decode_failure.count",
+		VariableScope::Evm,
+		"Number of decoding failures of the condition on the network",
+	),
+	variable(
+		"events.[index].args.[position]",
+		VariableScope::Stellar,
+		"Event parameters by position",
+	),
+	variable(
+		"events.[index].args.[param]",
+		VariableScope::Stellar,
+		"Event parameters by name (only in case the contract supports event parameters name)",
+	),
+	variable(
+		"events.[index].operation_index",
+		VariableScope::Stellar,
+		"Index of the operation emitting the event in the transaction",
+	),
+	variable(
+		"events.[index].event_index",
+		VariableScope::Stellar,
+		"Index of the event in the operation",
+	),
+	variable(
+		"functions.[index].args.[param]",
+		VariableScope::Stellar,
+		"Function parameters by name",
+	),
+	variable(
+		"functions.[index].operation_index",
+		VariableScope::Stellar,
+		"Index of the invoking operation in the transaction",
+	),
+];
+
+/// Returns the marker rendered in place of an unset variable by lenient triggers
+pub fn missing_variable_marker(name: &str) -> String {
+	format!("⟨missing:{}⟩", name)
+}
+
+/// Returns the names of the variables referenced by a template, each name once
+pub fn template_references(template: &str) -> Vec<&str> {
+	let mut references = Vec::new();
+	let mut rest = template;
+	while let Some(start) = rest.find("${") {
+		rest = &rest[start + 2..];
+		let Some(end) = rest.find('}') else {
+			break;
+		};
+		let name = &rest[..end];
+		if !name.is_empty() && !name.contains("${") && !references.contains(&name) {
+			references.push(name);
+		}
+		rest = &rest[end + 1..];
+	}
+	references
+}
+
+/// Returns whether a variable name is described by the catalog
+pub fn is_catalog_variable(name: &str) -> bool {
+	TEMPLATE_VARIABLES
+		.iter()
+		.any(|variable| match variable.name.strip_prefix(ANY_VARIABLE) {
+			Some(suffix) => name
+				.strip_suffix(suffix)
+				.is_some_and(|base| !base.is_empty() && is_catalog_variable(base)),
+			None => matches_pattern(variable.name, name),
+		})
+}
+
+/// Returns whether a name matches a catalog name, segment by segment
+fn matches_pattern(pattern: &str, name: &str) -> bool {
+	let pattern_segments = pattern.split('.').collect::<Vec<_>>();
+	let name_segments = name.split('.').collect::<Vec<_>>();
+	pattern_segments.len() == name_segments.len()
+		&& pattern_segments
+			.iter()
+			.zip(&name_segments)
+			.all(|(pattern, segment)| matches_segment(pattern, segment))
+}
+
+/// Matches a segment of a name against a literal segment or a placeholder with an optional
+/// literal suffix, e.g. `[param]_usd`
+fn matches_segment(pattern: &str, segment: &str) -> bool {
+	let Some((placeholder, suffix)) = pattern
+		.strip_prefix('[')
+		.and_then(|pattern| pattern.split_once(']'))
+	else {
+		return pattern == segment;
+	};
+	let Some(value) = segment
+		.strip_suffix(suffix)
+		.filter(|value| !value.is_empty())
+	else {
+		return false;
+	};
+	match placeholder {
+		"index" => value.bytes().all(|byte| byte.is_ascii_digit()),
+		_ => true,
+	}
+}
+
+/// Returns the templates of a notification message
+pub fn message_templates(message: &NotificationMessage) -> Vec<&str> {
+	let mut templates = vec![message.title.as_str(), message.body.as_str()];
+	templates.extend(message.thread_key.as_deref());
+	templates
+}
+
+/// Returns the variables referenced by the templates of a trigger of a monitor that none of
+/// the monitor's matches can set
+///
+/// Variables of the catalog, of the monitor's HTTP enrichments and the static variables of the
+/// monitor's reference to the trigger are known. Script enrichments can set any variable, so
+/// monitors having one only get references within a namespace of the catalog checked, such as
+/// `transaction.*`.
+///
+/// # Arguments
+/// * `monitor` - The monitor referencing the trigger
+/// * `reference` - The monitor's reference to the trigger, with its overrides
+/// * `trigger` - The referenced trigger
+///
+/// # Returns
+/// * `Vec<String>` - The unknown references, in the order of the templates
+pub fn unknown_template_variables(
+	monitor: &Monitor,
+	reference: &TriggerReference,
+	trigger: &Trigger,
+) -> Vec<String> {
+	let trigger = match reference.message() {
+		Some(message_override) => Cow::Owned(trigger.with_message_override(message_override)),
+		None => Cow::Borrowed(trigger),
+	};
+	let Some(message) = trigger.config.message() else {
+		return Vec::new();
+	};
+	let static_variables = reference.variables();
+	let has_script_enrichment = monitor
+		.enrichments
+		.iter()
+		.any(|enrichment| matches!(enrichment, Enrichment::Script(_)));
+
+	let mut unknown = Vec::new();
+	for name in message_templates(message)
+		.into_iter()
+		.flat_map(template_references)
+	{
+		let known = is_catalog_variable(name)
+			|| static_variables.is_some_and(|variables| variables.contains_key(name))
+			|| monitor
+				.enrichments
+				.iter()
+				.any(|enrichment| match enrichment {
+					Enrichment::Http(http) => http.variables.contains_key(name),
+					Enrichment::Script(_) => false,
+				}) || (has_script_enrichment && !is_catalog_namespace(name));
+		if !known && !unknown.iter().any(|other: &String| other == name) {
+			unknown.push(name.to_string());
+		}
+	}
+	unknown
+}
+
+/// Returns whether the first segment of a name is the first segment of a catalog variable
+fn is_catalog_namespace(name: &str) -> bool {
+	let namespace = name.split('.').next().unwrap_or_default();
+	TEMPLATE_VARIABLES.iter().any(|variable| {
+		variable.name.contains('.') && variable.name.split('.').next() == Some(namespace)
+	})
+}
+
+/// Resolves the variables referenced by the templates of a trigger before rendering
+///
+/// Strict triggers fail when a referenced variable is unset. Lenient triggers get the unset
+/// variables set to their missing marker, the given variables are only cloned in that case.
+///
+/// # Arguments
+/// * `trigger` - The trigger, with the monitor's overrides applied
+/// * `variables` - The variables of the match
+/// * `mode` - How unset variables are handled
+///
+/// # Returns
+/// * `Result<Cow<HashMap<String, String>>, Vec<String>>` - The variables to render the
+///   templates with, or the unset variables of a strict trigger
+pub fn resolve_template_variables<'a>(
+	trigger: &Trigger,
+	variables: &'a HashMap<String, String>,
+	mode: TemplateVariablesMode,
+) -> Result<Cow<'a, HashMap<String, String>>, Vec<String>> {
+	let Some(message) = trigger.config.message() else {
+		return Ok(Cow::Borrowed(variables));
+	};
+	let mut missing = Vec::new();
+	for name in message_templates(message)
+		.into_iter()
+		.flat_map(template_references)
+	{
+		if !variables.contains_key(name)
+			&& !SECTION_VARIABLES.contains(&name)
+			&& !missing.iter().any(|other: &String| other == name)
+		{
+			missing.push(name.to_string());
+		}
+	}
+
+	match (missing.is_empty(), mode) {
+		(true, _) => Ok(Cow::Borrowed(variables)),
+		(false, TemplateVariablesMode::Strict) => Err(missing),
+		(false, TemplateVariablesMode::Lenient) => {
+			let mut resolved = variables.clone();
+			for name in missing {
+				let marker = missing_variable_marker(&name);
+				resolved.insert(name, marker);
+			}
+			Ok(Cow::Owned(resolved))
+		}
+	}
+}
+
+/// Renders the catalog variables of a scope as the Markdown table of the documentation
+pub fn template_variables_table(scope: VariableScope) -> String {
+	let mut table = String::from("| **Variable** | **Description** |\n| --- | --- |\n");
+	for variable in TEMPLATE_VARIABLES
+		.iter()
+		.filter(|variable| variable.scope == scope)
+	{
+		table.push_str(&format!(
+			"| `**{}**` | {} |\n",
+			variable.name, variable.description
+		));
+	}
+	table
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{
+		models::{HttpEnrichment, ScriptEnrichment, ScriptLanguage, TriggerOverride},
+		utils::tests::builders::{evm::monitor::MonitorBuilder, trigger::TriggerBuilder},
+	};
+
+	fn create_trigger(body: &str) -> Trigger {
+		TriggerBuilder::new()
+			.name("test_trigger")
+			.webhook("https://example.com/webhook")
+			.message("Alert for ${monitor.name}", body)
+			.build()
+	}
+
+	#[test]
+	fn test_template_references() {
+		assert_eq!(
+			template_references("${a} and ${b.c} then ${a}, ${} and ${unclosed"),
+			vec!["a", "b.c"]
+		);
+		assert!(template_references("no variables").is_empty());
+	}
+
+	#[test]
+	fn test_catalog_matches_dynamic_names() {
+		assert!(is_catalog_variable("transaction.hash"));
+		assert!(is_catalog_variable("events.0.args.value"));
+		assert!(is_catalog_variable("events.12.args.value_usd"));
+		assert!(is_catalog_variable("functions.1.args.0"));
+		assert!(is_catalog_variable("events.0.args.from_label"));
+		assert!(is_catalog_variable("rollup.2.monitor"));
+		assert!(!is_catalog_variable("transacion.hash"));
+		assert!(!is_catalog_variable("events.first.signature"));
+		assert!(!is_catalog_variable("customer_id_label"));
+		assert!(!is_catalog_variable("_label"));
+	}
+
+	#[test]
+	fn test_unknown_template_variables_reports_typos() {
+		let trigger = create_trigger("Transfer ${transacion.hash} of ${events.0.args.value}");
+		let monitor = MonitorBuilder::new().name("transfers").build();
+
+		assert_eq!(
+			unknown_template_variables(&monitor, &"test_trigger".into(), &trigger),
+			vec!["transacion.hash"]
+		);
+	}
+
+	#[test]
+	fn test_unknown_template_variables_knows_monitor_variables() {
+		let trigger = create_trigger("${customer_id} moved ${events.0.args.value} (${team})");
+		let mut monitor = MonitorBuilder::new().name("transfers").build();
+		let reference = TriggerReference::WithOverrides(TriggerOverride {
+			name: "test_trigger".to_string(),
+			message: None,
+			variables: HashMap::from([("team".to_string(), "security".to_string())]),
+			rollup: false,
+		});
+		assert_eq!(
+			unknown_template_variables(&monitor, &reference, &trigger),
+			vec!["customer_id"]
+		);
+
+		monitor.enrichments = vec![Enrichment::Http(HttpEnrichment {
+			name: "customer".to_string(),
+			url: "https://crm.example.com/${transaction.from}".to_string(),
+			variables: HashMap::from([("customer_id".to_string(), "/id".to_string())]),
+			timeout_ms: 1000,
+			cache_ttl_secs: None,
+			required: false,
+		})];
+		assert!(unknown_template_variables(&monitor, &reference, &trigger).is_empty());
+	}
+
+	#[test]
+	fn test_unknown_template_variables_with_script_enrichment() {
+		let trigger = create_trigger("${risk} for ${transaction.hsh}");
+		let mut monitor = MonitorBuilder::new().name("transfers").build();
+		monitor.enrichments = vec![Enrichment::Script(ScriptEnrichment {
+			name: "risk".to_string(),
+			script_path: "scripts/risk.py".to_string(),
+			language: ScriptLanguage::Python,
+			arguments: None,
+			timeout_ms: 1000,
+			required: false,
+		})];
+
+		// Scripts can set any variable, but not within the namespaces of the catalog
+		assert_eq!(
+			unknown_template_variables(&monitor, &"test_trigger".into(), &trigger),
+			vec!["transaction.hsh"]
+		);
+	}
+
+	#[test]
+	fn test_resolve_template_variables_lenient_renders_markers() {
+		let trigger = create_trigger("Moved ${value} in ${transaction.hash}\n${events}");
+		let variables = HashMap::from([("value".to_string(), "42".to_string())]);
+
+		let resolved =
+			resolve_template_variables(&trigger, &variables, TemplateVariablesMode::Lenient)
+				.unwrap();
+		assert_eq!(resolved["value"], "42");
+		assert_eq!(resolved["monitor.name"], "⟨missing:monitor.name⟩");
+		assert_eq!(resolved["transaction.hash"], "⟨missing:transaction.hash⟩");
+		assert!(!resolved.contains_key("events"));
+	}
+
+	#[test]
+	fn test_resolve_template_variables_strict_fails() {
+		let trigger = create_trigger("Moved ${value}");
+		let variables = HashMap::from([("monitor.name".to_string(), "transfers".to_string())]);
+
+		assert_eq!(
+			resolve_template_variables(&trigger, &variables, TemplateVariablesMode::Strict)
+				.unwrap_err(),
+			vec!["value"]
+		);
+
+		let variables = HashMap::from([
+			("monitor.name".to_string(), "transfers".to_string()),
+			("value".to_string(), "42".to_string()),
+		]);
+		assert!(matches!(
+			resolve_template_variables(&trigger, &variables, TemplateVariablesMode::Strict),
+			Ok(Cow::Borrowed(_))
+		));
+	}
+
+	#[test]
+	fn test_trigger_mode_overrides_environment() {
+		let mut trigger = create_trigger("body");
+		trigger.strict_variables = Some(true);
+		assert_eq!(
+			TemplateVariablesMode::for_trigger(&trigger),
+			TemplateVariablesMode::Strict
+		);
+		trigger.strict_variables = Some(false);
+		assert_eq!(
+			TemplateVariablesMode::for_trigger(&trigger),
+			TemplateVariablesMode::Lenient
+		);
+	}
+
+	#[test]
+	fn test_documentation_lists_the_catalog() {
+		let docs = std::fs::read_to_string("docs/index.mdx").unwrap();
+		for scope in [
+			VariableScope::Common,
+			VariableScope::Evm,
+			VariableScope::Stellar,
+		] {
+			assert!(
+				docs.contains(&template_variables_table(scope)),
+				"the {:?} variables table of docs/index.mdx is out of date, regenerate it with \
+				 template_variables_table",
+				scope
+			);
+		}
+	}
+}
@@ -0,0 +1,103 @@
+//! Build information of the monitor.
+//!
+//! The version comes from the crate manifest, the other fields are set by the build script. They
+//! are reported by the `build_info` metric, the `/version` endpoint of the admin API and the
+//! preflight report, and can be added to notifications with `INCLUDE_MONITOR_VERSION=true`.
+
+use serde::Serialize;
+
+/// Environment variable adding the version of the monitor to webhook payloads and outbox entries
+pub const INCLUDE_MONITOR_VERSION_ENV: &str = "INCLUDE_MONITOR_VERSION";
+
+/// Name of the field carrying the version of the monitor in webhook payloads
+pub const MONITOR_VERSION_FIELD: &str = "monitor_version";
+
+/// Version of the crate
+pub const VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// Short SHA of the commit the monitor was built from, `unknown` if it was not built from a git
+/// checkout
+pub const GIT_SHA: &str = match option_env!("MONITOR_GIT_SHA") {
+	Some(sha) => sha,
+	None => "unknown",
+};
+
+/// Unix time in seconds at which the monitor was built
+pub const BUILD_TIMESTAMP: &str = match option_env!("MONITOR_BUILD_TIMESTAMP") {
+	Some(timestamp) => timestamp,
+	None => "0",
+};
+
+/// Comma-separated list of the cargo features the monitor was built with
+pub const FEATURES: &str = match option_env!("MONITOR_FEATURES") {
+	Some(features) => features,
+	None => "",
+};
+
+/// Build information, as returned by the `/version` endpoint
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct BuildInfo {
+	/// Version of the crate
+	pub version: String,
+	/// Short SHA of the commit
+	pub git_sha: String,
+	/// Build time in RFC 3339 format
+	pub build_timestamp: String,
+	/// Enabled cargo features, sorted
+	pub features: Vec<String>,
+}
+
+impl BuildInfo {
+	/// Returns the build information of the running monitor
+	pub fn current() -> Self {
+		Self {
+			version: VERSION.to_string(),
+			git_sha: GIT_SHA.to_string(),
+			build_timestamp: BUILD_TIMESTAMP
+				.parse::<i64>()
+				.ok()
+				.and_then(|secs| chrono::DateTime::from_timestamp(secs, 0))
+				.map(|time| time.to_rfc3339())
+				.unwrap_or_else(|| BUILD_TIMESTAMP.to_string()),
+			features: FEATURES
+				.split(',')
+				.filter(|feature| !feature.is_empty())
+				.map(str::to_string)
+				.collect(),
+		}
+	}
+}
+
+/// Returns the version and commit of the monitor, e.g. `1.1.0+3f2a9c1d0b7e`
+pub fn monitor_version() -> String {
+	format!("{}+{}", VERSION, GIT_SHA)
+}
+
+/// Returns the version to add to notifications, if enabled by `INCLUDE_MONITOR_VERSION`
+///
+/// The variable is read once, on the first call.
+pub fn reported_monitor_version() -> Option<&'static str> {
+	static REPORTED: std::sync::OnceLock<Option<String>> = std::sync::OnceLock::new();
+	REPORTED
+		.get_or_init(|| {
+			std::env::var(INCLUDE_MONITOR_VERSION_ENV)
+				.is_ok_and(|v| v == "true")
+				.then(monitor_version)
+		})
+		.as_deref()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_current_build_info() {
+		let info = BuildInfo::current();
+		assert_eq!(info.version, env!("CARGO_PKG_VERSION"));
+		assert!(!info.git_sha.is_empty());
+		assert!(chrono::DateTime::parse_from_rfc3339(&info.build_timestamp).is_ok());
+		assert!(info.features.iter().all(|feature| !feature.is_empty()));
+		assert!(monitor_version().starts_with(&format!("{}+", info.version)));
+	}
+}
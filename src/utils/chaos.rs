@@ -0,0 +1,236 @@
+//! Fault injection for resilience testing.
+//!
+//! [`FaultInjectionMiddleware`] makes an HTTP client fail or slow down, following either a
+//! scripted sequence of faults or random draws at configured rates. Added after the retry
+//! middleware, see [`crate::utils::retryable_http_client_builder`], it applies to every attempt,
+//! so retries can be exercised without a misbehaving server.
+//!
+//! Blockchain clients only inject faults when the binary is built with the `chaos` feature and
+//! `CHAOS_NETWORK_FAULTS` configures their network, see [`network_faults`].
+
+use async_trait::async_trait;
+use reqwest::{Request, Response};
+use reqwest_middleware::{Middleware, Next};
+use serde::Deserialize;
+use std::{collections::VecDeque, sync::Mutex, time::Duration};
+
+/// Environment variable holding the fault rates per network slug, as a JSON object
+pub const CHAOS_NETWORK_FAULTS_ENV: &str = "CHAOS_NETWORK_FAULTS";
+
+/// Fault injected into a request
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Fault {
+	/// Forward the request unchanged
+	Pass,
+	/// Answer with the given status code without forwarding the request
+	Status(u16),
+	/// Wait before forwarding the request
+	Delay(Duration),
+}
+
+/// Rates at which random faults are injected
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct FaultRates {
+	/// Probability that a request fails with a `503 Service Unavailable` response
+	#[serde(default)]
+	pub error_rate: f64,
+	/// Probability that a request is delayed
+	#[serde(default)]
+	pub delay_rate: f64,
+	/// Delay of the delayed requests, in milliseconds
+	#[serde(default)]
+	pub delay_ms: u64,
+	/// Seed of the random draws, for reproducible runs
+	#[serde(default)]
+	pub seed: Option<u64>,
+}
+
+/// SplitMix64 generator, good enough to draw faults without a dependency on `rand`
+#[derive(Debug)]
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+	fn next_f64(&mut self) -> f64 {
+		self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+		let mut z = self.0;
+		z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+		z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+		z ^= z >> 31;
+		(z >> 11) as f64 / (1u64 << 53) as f64
+	}
+}
+
+/// Source of the faults injected by the middleware
+#[derive(Debug)]
+enum FaultSource {
+	/// Faults applied in order, requests pass once the script is exhausted
+	Scripted(VecDeque<Fault>),
+	/// Faults drawn at random
+	Random { rates: FaultRates, rng: SplitMix64 },
+}
+
+/// HTTP middleware injecting errors and delays into requests
+#[derive(Debug)]
+pub struct FaultInjectionMiddleware {
+	source: Mutex<FaultSource>,
+}
+
+impl FaultInjectionMiddleware {
+	/// Creates a middleware applying the faults in order, one per request
+	///
+	/// Requests pass unchanged once all the faults were applied.
+	pub fn scripted(faults: impl IntoIterator<Item = Fault>) -> Self {
+		Self {
+			source: Mutex::new(FaultSource::Scripted(faults.into_iter().collect())),
+		}
+	}
+
+	/// Creates a middleware drawing the fault of each request at random
+	///
+	/// An error is drawn first, a delay is only drawn for requests that do not fail.
+	pub fn random(rates: FaultRates) -> Self {
+		let seed = rates
+			.seed
+			.unwrap_or_else(|| uuid::Uuid::new_v4().as_u64_pair().0);
+		Self {
+			source: Mutex::new(FaultSource::Random {
+				rates,
+				rng: SplitMix64(seed),
+			}),
+		}
+	}
+
+	/// Returns the fault of the next request
+	fn next_fault(&self) -> Fault {
+		let mut source = self.source.lock().unwrap_or_else(|e| e.into_inner());
+		match &mut *source {
+			FaultSource::Scripted(faults) => faults.pop_front().unwrap_or(Fault::Pass),
+			FaultSource::Random { rates, rng } => {
+				if rng.next_f64() < rates.error_rate {
+					Fault::Status(503)
+				} else if rng.next_f64() < rates.delay_rate {
+					Fault::Delay(Duration::from_millis(rates.delay_ms))
+				} else {
+					Fault::Pass
+				}
+			}
+		}
+	}
+}
+
+#[async_trait]
+impl Middleware for FaultInjectionMiddleware {
+	async fn handle(
+		&self,
+		req: Request,
+		extensions: &mut http::Extensions,
+		next: Next<'_>,
+	) -> reqwest_middleware::Result<Response> {
+		match self.next_fault() {
+			Fault::Pass => next.run(req, extensions).await,
+			Fault::Delay(delay) => {
+				tokio::time::sleep(delay).await;
+				next.run(req, extensions).await
+			}
+			Fault::Status(status) => {
+				tracing::debug!("Injecting HTTP {} into request to {}", status, req.url());
+				let response = http::Response::builder()
+					.status(status)
+					.body(Vec::<u8>::new())
+					.map_err(|e| reqwest_middleware::Error::Middleware(e.into()))?;
+				Ok(Response::from(response))
+			}
+		}
+	}
+}
+
+/// Returns the fault rates configured for a network in `CHAOS_NETWORK_FAULTS`
+///
+/// The variable holds a JSON object keyed by network slug, e.g.
+/// `{"ethereum_mainnet": {"error_rate": 0.2, "delay_rate": 0.1, "delay_ms": 500}}`.
+/// Invalid values are logged and ignored.
+#[cfg(feature = "chaos")]
+pub fn network_faults(network_slug: &str) -> Option<FaultRates> {
+	let value = std::env::var(CHAOS_NETWORK_FAULTS_ENV).ok()?;
+	match serde_json::from_str::<std::collections::HashMap<String, FaultRates>>(&value) {
+		Ok(mut faults) => faults.remove(network_slug),
+		Err(e) => {
+			tracing::warn!("Ignoring invalid {}: {}", CHAOS_NETWORK_FAULTS_ENV, e);
+			None
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_scripted_faults_pass_once_exhausted() {
+		let middleware = FaultInjectionMiddleware::scripted([
+			Fault::Status(503),
+			Fault::Delay(Duration::from_millis(10)),
+		]);
+		assert_eq!(middleware.next_fault(), Fault::Status(503));
+		assert_eq!(
+			middleware.next_fault(),
+			Fault::Delay(Duration::from_millis(10))
+		);
+		assert_eq!(middleware.next_fault(), Fault::Pass);
+	}
+
+	#[test]
+	fn test_random_faults_follow_rates() {
+		let draws = |rates: FaultRates| {
+			let middleware = FaultInjectionMiddleware::random(rates);
+			(0..10_000)
+				.map(|_| middleware.next_fault())
+				.collect::<Vec<_>>()
+		};
+
+		let faults = draws(FaultRates {
+			error_rate: 0.3,
+			delay_rate: 0.5,
+			delay_ms: 5,
+			seed: Some(42),
+		});
+		let errors = faults.iter().filter(|f| **f == Fault::Status(503)).count();
+		let delays = faults
+			.iter()
+			.filter(|f| matches!(f, Fault::Delay(_)))
+			.count();
+		assert!((2_700..3_300).contains(&errors), "errors: {}", errors);
+		// Delays are drawn among the requests that did not fail
+		assert!((3_200..3_800).contains(&delays), "delays: {}", delays);
+
+		// The same seed draws the same faults
+		let seeded = FaultRates {
+			error_rate: 0.5,
+			seed: Some(7),
+			..Default::default()
+		};
+		assert_eq!(draws(seeded.clone()), draws(seeded));
+
+		assert!(draws(FaultRates::default())
+			.iter()
+			.all(|f| *f == Fault::Pass));
+	}
+
+	#[cfg(feature = "chaos")]
+	#[test]
+	fn test_network_faults_from_env() {
+		std::env::set_var(
+			CHAOS_NETWORK_FAULTS_ENV,
+			r#"{"ethereum_mainnet": {"error_rate": 0.2, "delay_ms": 100}}"#,
+		);
+		let faults = network_faults("ethereum_mainnet").unwrap();
+		assert_eq!(faults.error_rate, 0.2);
+		assert_eq!(faults.delay_ms, 100);
+		assert!(network_faults("stellar_mainnet").is_none());
+
+		std::env::set_var(CHAOS_NETWORK_FAULTS_ENV, "not json");
+		assert!(network_faults("ethereum_mainnet").is_none());
+		std::env::remove_var(CHAOS_NETWORK_FAULTS_ENV);
+	}
+}
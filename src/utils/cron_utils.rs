@@ -40,3 +40,23 @@ pub fn get_cron_interval_ms(cron_schedule: &str) -> Option<i64> {
 		None // Return None if we cannot find two occurrences
 	}
 }
+
+/// Normalizes a cron expression to its canonical form
+///
+/// Surrounding and repeated whitespace is removed, and a trailing `*` year field, which
+/// matches every year, is dropped. Normalizing a normalized expression returns it unchanged.
+///
+/// # Arguments
+///
+/// * `cron_schedule` - A string slice containing a cron expression
+///
+/// # Returns
+///
+/// * `String` - The expression with its fields separated by single spaces
+pub fn normalize_cron_schedule(cron_schedule: &str) -> String {
+	let mut fields: Vec<&str> = cron_schedule.split_whitespace().collect();
+	if fields.len() == 7 && fields[6] == "*" {
+		fields.pop();
+	}
+	fields.join(" ")
+}
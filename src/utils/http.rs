@@ -2,6 +2,7 @@ use reqwest_middleware::{ClientBuilder, ClientWithMiddleware};
 use reqwest_retry::{
 	policies::ExponentialBackoff, Jitter, RetryTransientMiddleware, RetryableStrategy,
 };
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::time::Duration;
 
@@ -23,7 +24,7 @@ fn default_base_for_backoff() -> u32 {
 }
 
 /// Serializable setting for jitter in retry policies
-#[derive(Default, Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[derive(Default, Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq, Eq, Hash)]
 #[serde(rename_all = "lowercase")]
 pub enum JitterSetting {
 	/// No jitter applied to the backoff duration
@@ -34,7 +35,7 @@ pub enum JitterSetting {
 }
 
 /// Configuration for HTTP (RPC and Webhook notifiers) and SMTP (Email notifier) retry policies
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq, Eq, Hash)]
 pub struct RetryConfig {
 	/// Maximum number of retries for transient errors
 	#[serde(default = "default_max_attempts")]
@@ -81,6 +82,27 @@ pub fn create_retryable_http_client<S>(
 	base_client: reqwest::Client,
 	custom_strategy: Option<S>,
 ) -> ClientWithMiddleware
+where
+	S: RetryableStrategy + Send + Sync + 'static,
+{
+	retryable_http_client_builder(config, base_client, custom_strategy).build()
+}
+
+/// Creates the builder of a retryable HTTP client
+///
+/// Like [`create_retryable_http_client`], but leaves the builder open so that middleware can be
+/// added after the retry middleware, e.g. [`crate::utils::chaos::FaultInjectionMiddleware`],
+/// which then applies to every attempt.
+///
+/// # Parameters:
+/// - `config`: Configuration for retry policies
+/// - `base_client`: The base HTTP client to use
+/// - `custom_strategy`: Optional custom retry strategy, complementing the default retry behavior
+pub fn retryable_http_client_builder<S>(
+	config: &RetryConfig,
+	base_client: reqwest::Client,
+	custom_strategy: Option<S>,
+) -> ClientBuilder
 where
 	S: RetryableStrategy + Send + Sync + 'static,
 {
@@ -105,5 +127,4 @@ where
 		ClientBuilder::new(base_client)
 			.with(RetryTransientMiddleware::new_with_policy(retry_policy))
 	}
-	.build()
 }
@@ -5,9 +5,24 @@
 
 pub mod server;
 use lazy_static::lazy_static;
-use prometheus::{Encoder, Gauge, GaugeVec, Opts, Registry, TextEncoder};
+use prometheus::{
+	Encoder, Gauge, GaugeVec, Histogram, HistogramOpts, HistogramVec, IntCounter, IntCounterVec,
+	Opts, Registry, TextEncoder,
+};
 use sysinfo::{Disks, System};
 
+use crate::{models::ContentHash, utils::build_info::BuildInfo};
+
+/// Buckets of the time-to-alert histograms, in seconds
+const ALERT_LATENCY_BUCKETS: [f64; 12] = [
+	0.1, 0.5, 1.0, 2.5, 5.0, 10.0, 15.0, 30.0, 60.0, 120.0, 300.0, 600.0,
+];
+
+/// Buckets of the notification payload size histogram, in bytes
+const PAYLOAD_SIZE_BUCKETS: [f64; 10] = [
+	256.0, 512.0, 1024.0, 2048.0, 3072.0, 4096.0, 8192.0, 16384.0, 65536.0, 262144.0,
+];
+
 lazy_static! {
 	/// Global Prometheus registry.
 	///
@@ -134,10 +149,399 @@ lazy_static! {
 		REGISTRY.register(Box::new(gauge.clone())).unwrap();
 		gauge
 	};
+
+	/// Gauge Vector for the monitor configurations.
+	///
+	/// Always 1, with the monitor name and the content hash of its configuration as labels.
+	pub static ref MONITOR_CONFIG_INFO: GaugeVec = {
+		let gauge = GaugeVec::new(
+			Opts::new("monitor_config_info", "Content hash of each monitor configuration"),
+			&["monitor", "content_hash"]
+		).unwrap();
+		REGISTRY.register(Box::new(gauge.clone())).unwrap();
+		gauge
+	};
+
+	/// Gauge Vector for network maintenance windows.
+	///
+	/// Set to 1 while block processing for a network is paused by a maintenance window, 0 otherwise.
+	pub static ref NETWORK_MAINTENANCE_ACTIVE: GaugeVec = {
+		let gauge = GaugeVec::new(
+			Opts::new("network_maintenance_active", "Whether a network is in a maintenance window"),
+			&["network"]
+		).unwrap();
+		REGISTRY.register(Box::new(gauge.clone())).unwrap();
+		gauge
+	};
+
+	/// Counter Vector for skipped blocks.
+	///
+	/// Blocks per network that were never processed because catching up on them would have
+	/// exceeded `max_past_blocks`, for instance after a long maintenance window.
+	pub static ref SKIPPED_BLOCKS: IntCounterVec = {
+		let counter = IntCounterVec::new(
+			Opts::new("skipped_blocks_total", "Blocks skipped because they were older than max_past_blocks"),
+			&["network"]
+		).unwrap();
+		REGISTRY.register(Box::new(counter.clone())).unwrap();
+		counter
+	};
+
+	/// Gauge Vector for failed blocks.
+	///
+	/// Number of blocks per network that could not be fetched and are waiting to be retried.
+	pub static ref FAILED_BLOCKS: GaugeVec = {
+		let gauge = GaugeVec::new(
+			Opts::new("failed_blocks", "Number of blocks that could not be fetched and are waiting to be retried"),
+			&["network"]
+		).unwrap();
+		REGISTRY.register(Box::new(gauge.clone())).unwrap();
+		gauge
+	};
+
+	/// Gauge for leader election.
+	///
+	/// Set to 1 while this replica holds the leader lease, 0 otherwise.
+	pub static ref LEADER_STATUS: Gauge = {
+		let gauge = Gauge::new("leader_status", "Whether this replica holds the leader lease").unwrap();
+		REGISTRY.register(Box::new(gauge.clone())).unwrap();
+		gauge
+	};
+
+	/// Counter for leader election.
+	///
+	/// Incremented every time this replica acquires or loses the leader lease.
+	pub static ref LEADERSHIP_CHANGES: IntCounter = {
+		let counter = IntCounter::new("leadership_changes_total", "Number of times this replica acquired or lost the leader lease").unwrap();
+		REGISTRY.register(Box::new(counter.clone())).unwrap();
+		counter
+	};
+
+	/// Gauge for the notification dispatch queue.
+	///
+	/// Number of matches waiting between filtering and trigger dispatch.
+	pub static ref DISPATCH_QUEUE_DEPTH: Gauge = {
+		let gauge = Gauge::new("dispatch_queue_depth", "Number of matches waiting to be dispatched to triggers").unwrap();
+		REGISTRY.register(Box::new(gauge.clone())).unwrap();
+		gauge
+	};
+
+	/// Histogram for the notification dispatch queue.
+	///
+	/// Time a match spends in the queue before its dispatch starts.
+	pub static ref DISPATCH_QUEUE_WAIT_SECONDS: Histogram = {
+		let histogram = Histogram::with_opts(HistogramOpts::new("dispatch_queue_wait_seconds", "Time matches spend in the dispatch queue")).unwrap();
+		REGISTRY.register(Box::new(histogram.clone())).unwrap();
+		histogram
+	};
+
+	/// Counter for the notification dispatch queue.
+	///
+	/// Matches dropped per monitor because the queue was full and configured to shed.
+	pub static ref DISPATCH_SHED_MATCHES: IntCounterVec = {
+		let counter = IntCounterVec::new(
+			Opts::new("dispatch_shed_matches_total", "Number of matches dropped because the dispatch queue was full"),
+			&["monitor", "team"]
+		).unwrap();
+		REGISTRY.register(Box::new(counter.clone())).unwrap();
+		counter
+	};
+
+	/// Counter for monitor notification budgets.
+	///
+	/// Number of times a monitor exhausted its daily notification budget.
+	pub static ref NOTIFICATION_BUDGET_EXHAUSTED: IntCounterVec = {
+		let counter = IntCounterVec::new(
+			Opts::new("notification_budget_exhausted_total", "Number of times a monitor exhausted its daily notification budget"),
+			&["monitor", "team"]
+		).unwrap();
+		REGISTRY.register(Box::new(counter.clone())).unwrap();
+		counter
+	};
+
+	/// Counter for monitor notification budgets.
+	///
+	/// Matches over a monitor's daily budget, by overflow handling.
+	pub static ref NOTIFICATION_BUDGET_OVERFLOW: IntCounterVec = {
+		let counter = IntCounterVec::new(
+			Opts::new("notification_budget_overflow_matches_total", "Number of matches over a monitor's daily notification budget"),
+			&["monitor", "team", "overflow"]
+		).unwrap();
+		REGISTRY.register(Box::new(counter.clone())).unwrap();
+		counter
+	};
+
+	/// Counter for monitor enrichments.
+	///
+	/// Failed enrichments per monitor and enrichment name.
+	pub static ref ENRICHMENT_FAILURES: IntCounterVec = {
+		let counter = IntCounterVec::new(
+			Opts::new("enrichment_failures_total", "Number of failed match enrichments"),
+			&["monitor", "team", "enrichment"]
+		).unwrap();
+		REGISTRY.register(Box::new(counter.clone())).unwrap();
+		counter
+	};
+
+	/// Histogram Vector for time-to-alert.
+	///
+	/// Time between the timestamp of a delivered match's block and the block being fetched.
+	pub static ref ALERT_BLOCK_TO_FETCH_SECONDS: HistogramVec = {
+		let histogram = HistogramVec::new(
+			HistogramOpts::new("alert_block_to_fetch_seconds", "Time from the block timestamp to the block being fetched").buckets(ALERT_LATENCY_BUCKETS.to_vec()),
+			&["network", "channel"]
+		).unwrap();
+		REGISTRY.register(Box::new(histogram.clone())).unwrap();
+		histogram
+	};
+
+	/// Histogram Vector for time-to-alert.
+	///
+	/// Time between a delivered match's block being fetched and its matches being produced.
+	pub static ref ALERT_FETCH_TO_MATCH_SECONDS: HistogramVec = {
+		let histogram = HistogramVec::new(
+			HistogramOpts::new("alert_fetch_to_match_seconds", "Time from the block being fetched to its matches being produced").buckets(ALERT_LATENCY_BUCKETS.to_vec()),
+			&["network", "channel"]
+		).unwrap();
+		REGISTRY.register(Box::new(histogram.clone())).unwrap();
+		histogram
+	};
+
+	/// Histogram Vector for time-to-alert.
+	///
+	/// Time between a match being produced and its notification being delivered.
+	pub static ref ALERT_MATCH_TO_DELIVERY_SECONDS: HistogramVec = {
+		let histogram = HistogramVec::new(
+			HistogramOpts::new("alert_match_to_delivery_seconds", "Time from a match being produced to its notification being delivered").buckets(ALERT_LATENCY_BUCKETS.to_vec()),
+			&["network", "channel"]
+		).unwrap();
+		REGISTRY.register(Box::new(histogram.clone())).unwrap();
+		histogram
+	};
+
+	/// Histogram Vector for time-to-alert.
+	///
+	/// Total time between the timestamp of a match's block and its notification being delivered.
+	pub static ref ALERT_LATENCY_SECONDS: HistogramVec = {
+		let histogram = HistogramVec::new(
+			HistogramOpts::new("alert_latency_seconds", "Time from the block timestamp to a notification being delivered").buckets(ALERT_LATENCY_BUCKETS.to_vec()),
+			&["network", "channel"]
+		).unwrap();
+		REGISTRY.register(Box::new(histogram.clone())).unwrap();
+		histogram
+	};
+
+	/// Histogram Vector for time-to-alert.
+	///
+	/// Time between a match's block being fetched and its notification being delivered, measured
+	/// on the local clock only so that it is not affected by clock skew with the chain.
+	pub static ref ALERT_FETCH_TO_DELIVERY_SECONDS: HistogramVec = {
+		let histogram = HistogramVec::new(
+			HistogramOpts::new("alert_fetch_to_delivery_seconds", "Time from the block being fetched to a notification being delivered").buckets(ALERT_LATENCY_BUCKETS.to_vec()),
+			&["network", "channel"]
+		).unwrap();
+		REGISTRY.register(Box::new(histogram.clone())).unwrap();
+		histogram
+	};
+
+	/// Counter for network views.
+	///
+	/// Matches of a network group a view did not see within the configured number of blocks.
+	pub static ref NETWORK_VIEW_DIVERGENCES: IntCounterVec = {
+		let counter = IntCounterVec::new(
+			Opts::new("network_view_divergences_total", "Number of matches a network view did not see within the configured number of blocks"),
+			&["group", "network", "monitor"]
+		).unwrap();
+		REGISTRY.register(Box::new(counter.clone())).unwrap();
+		counter
+	};
+
+	/// Counter for network views.
+	///
+	/// Matches not delivered because another view of the network group already delivered them.
+	pub static ref NETWORK_VIEW_DUPLICATES: IntCounterVec = {
+		let counter = IntCounterVec::new(
+			Opts::new("network_view_duplicates_total", "Number of matches not delivered because another view of the network group delivered them"),
+			&["group", "network"]
+		).unwrap();
+		REGISTRY.register(Box::new(counter.clone())).unwrap();
+		counter
+	};
+
+	/// Gauge Vector for trigger circuit breakers.
+	///
+	/// Set to 1 while deliveries to a trigger are suppressed after consecutive failures, 0 otherwise.
+	pub static ref TRIGGER_CIRCUIT_OPEN: GaugeVec = {
+		let gauge = GaugeVec::new(
+			Opts::new("trigger_circuit_open", "Whether deliveries to a trigger are suppressed by its circuit breaker"),
+			&["trigger"]
+		).unwrap();
+		REGISTRY.register(Box::new(gauge.clone())).unwrap();
+		gauge
+	};
+
+	/// Counter for trigger circuit breakers.
+	///
+	/// Deliveries not attempted because the circuit breaker of their trigger was open.
+	pub static ref TRIGGER_CIRCUIT_SUPPRESSED: IntCounterVec = {
+		let counter = IntCounterVec::new(
+			Opts::new("trigger_circuit_suppressed_total", "Number of deliveries suppressed by the circuit breaker of their trigger"),
+			&["trigger"]
+		).unwrap();
+		REGISTRY.register(Box::new(counter.clone())).unwrap();
+		counter
+	};
+
+	/// Counter for trigger mutes.
+	///
+	/// Deliveries not attempted because their trigger was muted, by trigger and monitor.
+	pub static ref TRIGGER_MUTED_DELIVERIES: IntCounterVec = {
+		let counter = IntCounterVec::new(
+			Opts::new("trigger_muted_deliveries_total", "Number of deliveries suppressed by a mute of their trigger"),
+			&["trigger", "monitor"]
+		).unwrap();
+		REGISTRY.register(Box::new(counter.clone())).unwrap();
+		counter
+	};
+
+	/// Counter for price oracles.
+	///
+	/// Failed refreshes of the prices of a network.
+	pub static ref PRICE_ORACLE_FAILURES: IntCounterVec = {
+		let counter = IntCounterVec::new(
+			Opts::new("price_oracle_failures_total", "Number of failed price oracle refreshes"),
+			&["network"]
+		).unwrap();
+		REGISTRY.register(Box::new(counter.clone())).unwrap();
+		counter
+	};
+
+	/// Counter for price oracles.
+	///
+	/// USD conversions not made because the price of their token was missing or stale.
+	pub static ref PRICE_ORACLE_STALE: IntCounterVec = {
+		let counter = IntCounterVec::new(
+			Opts::new("price_oracle_stale_total", "Number of USD conversions skipped because of a missing or stale price"),
+			&["network", "token"]
+		).unwrap();
+		REGISTRY.register(Box::new(counter.clone())).unwrap();
+		counter
+	};
+
+	/// Counter for chain data decoding.
+	///
+	/// Block items, such as transactions or logs, skipped because they failed to decode.
+	pub static ref CHAIN_DATA_DECODE_FAILURES: IntCounterVec = {
+		let counter = IntCounterVec::new(
+			Opts::new("chain_data_decode_failures_total", "Number of chain data items skipped because they failed to decode"),
+			&["network", "item"]
+		).unwrap();
+		REGISTRY.register(Box::new(counter.clone())).unwrap();
+		counter
+	};
+
+	/// Counter for ABI decoding.
+	///
+	/// Function calls and events a monitor condition could not decode with the monitor's ABIs,
+	/// by condition signature.
+	pub static ref ABI_DECODE_FAILURES: IntCounterVec = {
+		let counter = IntCounterVec::new(
+			Opts::new("abi_decode_failures_total", "Number of function calls and events a monitor condition could not decode"),
+			&["network", "monitor", "condition"]
+		).unwrap();
+		REGISTRY.register(Box::new(counter.clone())).unwrap();
+		counter
+	};
+
+	/// Counter for match history lookups.
+	///
+	/// Lookups of prior occurrences that exceeded their time budget, by monitor.
+	pub static ref MATCH_HISTORY_TIMEOUTS: IntCounterVec = {
+		let counter = IntCounterVec::new(
+			Opts::new("match_history_timeouts_total", "Number of match history lookups that exceeded their time budget"),
+			&["monitor"]
+		).unwrap();
+		REGISTRY.register(Box::new(counter.clone())).unwrap();
+		counter
+	};
+
+	/// Counter for the egress controls of notifiers.
+	///
+	/// Notification requests refused because their endpoint resolved to, or redirected to, a
+	/// refused destination, by host.
+	pub static ref NOTIFICATION_EGRESS_VIOLATIONS: IntCounterVec = {
+		let counter = IntCounterVec::new(
+			Opts::new("notification_egress_violations_total", "Number of notification requests refused by the egress controls"),
+			&["host"]
+		).unwrap();
+		REGISTRY.register(Box::new(counter.clone())).unwrap();
+		counter
+	};
+
+	/// Counter for DNS pinning of notification endpoints.
+	///
+	/// Resolutions of a pinned notification endpoint that differed from its pinned addresses,
+	/// by host.
+	pub static ref NOTIFICATION_DNS_PIN_CHANGES: IntCounterVec = {
+		let counter = IntCounterVec::new(
+			Opts::new("notification_dns_pin_changes_total", "Number of resolutions of notification endpoints that differed from their pinned addresses"),
+			&["host"]
+		).unwrap();
+		REGISTRY.register(Box::new(counter.clone())).unwrap();
+		counter
+	};
+
+	/// Histogram Vector for notification payloads.
+	///
+	/// Size of the messages rendered for notifications before truncation, by channel and
+	/// trigger.
+	pub static ref NOTIFICATION_PAYLOAD_BYTES: HistogramVec = {
+		let histogram = HistogramVec::new(
+			HistogramOpts::new("notification_payload_bytes", "Size of the messages rendered for notifications before truncation").buckets(PAYLOAD_SIZE_BUCKETS.to_vec()),
+			&["channel", "trigger"]
+		).unwrap();
+		REGISTRY.register(Box::new(histogram.clone())).unwrap();
+		histogram
+	};
+
+	/// Counter for notification payloads.
+	///
+	/// Messages truncated to fit the limit of their channel, by channel, trigger and truncated
+	/// section.
+	pub static ref NOTIFICATION_TRUNCATIONS: IntCounterVec = {
+		let counter = IntCounterVec::new(
+			Opts::new("notification_truncations_total", "Number of notification messages truncated to fit the limit of their channel"),
+			&["channel", "trigger", "section"]
+		).unwrap();
+		REGISTRY.register(Box::new(counter.clone())).unwrap();
+		counter
+	};
+
+	/// Gauge Vector for the build information.
+	///
+	/// Always 1, with the version, commit, build time and features of the monitor as labels.
+	pub static ref BUILD_INFO: GaugeVec = {
+		let gauge = GaugeVec::new(
+			Opts::new("build_info", "Build information of the monitor"),
+			&["version", "git_sha", "build_timestamp", "features"]
+		).unwrap();
+		REGISTRY.register(Box::new(gauge.clone())).unwrap();
+		let info = BuildInfo::current();
+		gauge
+			.with_label_values(&[
+				info.version.as_str(),
+				info.git_sha.as_str(),
+				info.build_timestamp.as_str(),
+				info.features.join(",").as_str(),
+			])
+			.set(1.0);
+		gauge
+	};
 }
 
 /// Gather all metrics and encode into the provided format.
 pub fn gather_metrics() -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+	lazy_static::initialize(&BUILD_INFO);
 	let encoder = TextEncoder::new();
 	let metric_families = REGISTRY.gather();
 	let mut buffer = Vec::new();
@@ -257,6 +661,14 @@ pub fn update_monitoring_metrics(
 			.with_label_values(&[&network])
 			.set(count as f64);
 	}
+
+	// Label each monitor with the hash of its current configuration
+	MONITOR_CONFIG_INFO.reset();
+	for monitor in monitors.values() {
+		MONITOR_CONFIG_INFO
+			.with_label_values(&[&monitor.name, &monitor.content_hash()])
+			.set(1.0);
+	}
 }
 
 #[cfg(test)]
@@ -294,6 +706,8 @@ mod tests {
 		CONTRACTS_MONITORED.set(0.0);
 		NETWORKS_MONITORED.set(0.0);
 		NETWORK_MONITORS.reset();
+		NETWORK_MAINTENANCE_ACTIVE.reset();
+		MONITOR_CONFIG_INFO.reset();
 	}
 
 	// Helper function to create a test network
@@ -511,6 +925,13 @@ mod tests {
 			.get_metric_with_label_values(&["arbitrum"])
 			.unwrap();
 		assert_eq!(arbitrum_monitors.get(), 1.0);
+
+		// Each monitor is labeled with the hash of its configuration
+		let monitor = &monitors["monitor1"];
+		let info = MONITOR_CONFIG_INFO
+			.get_metric_with_label_values(&[&monitor.name, &monitor.content_hash()])
+			.unwrap();
+		assert_eq!(info.get(), 1.0);
 	}
 
 	#[test]
@@ -715,4 +1136,24 @@ mod tests {
 			.unwrap();
 		assert_eq!(test_network.get(), 0.0);
 	}
+
+	#[test]
+	fn test_build_info_metric_labels() {
+		let output = String::from_utf8(gather_metrics().unwrap()).unwrap();
+		let info = BuildInfo::current();
+		let line = output
+			.lines()
+			.find(|line| line.starts_with("build_info{"))
+			.expect("build_info metric should be exported");
+
+		assert!(line.ends_with(" 1"));
+		for (label, value) in [
+			("version", info.version),
+			("git_sha", info.git_sha),
+			("build_timestamp", info.build_timestamp),
+			("features", info.features.join(",")),
+		] {
+			assert!(line.contains(&format!("{}=\"{}\"", label, value)));
+		}
+	}
 }
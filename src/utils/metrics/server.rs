@@ -1,21 +1,137 @@
 //! Metrics server module
 //!
-//! This module provides an HTTP server to expose Prometheus metrics for scraping.
+//! This module provides an HTTP server to expose Prometheus metrics for scraping, the
+//! preflight report of the instance under `/preflight`, its build information under `/version`,
+//! the ABI decoding failures of the monitor conditions under `/decode-failures`, and the diff of
+//! the latest reloaded
+//! configuration under `/config/diff`, applied with `POST /config/apply`. Monitors can be
+//! evaluated against a single transaction with `POST /monitors/evaluate`, which never dispatches
+//! any notification. Triggers are muted with `POST /mutes`, and the active mutes are listed
+//! under `/mutes` and removed with `DELETE /mutes/{id}`.
+//!
+//! The preflight, decoding failures, configuration, evaluation and mute endpoints are admin
+//! endpoints. They require an
+//! `Authorization: Bearer <token>` header matching `METRICS_ADMIN_TOKEN`, and are disabled when
+//! no token is configured.
 
 use actix_web::middleware::{Compress, DefaultHeaders, NormalizePath};
-use actix_web::{web, App, HttpResponse, HttpServer, Responder};
-use std::sync::Arc;
+use actix_web::{
+	dev::Payload, error::InternalError, http::header, web, App, FromRequest, HttpRequest,
+	HttpResponse, HttpServer, Responder,
+};
+use serde::Deserialize;
+use std::{future::Ready, sync::Arc};
 use tokio::sync::Mutex;
 use tracing::{error, info};
 
 use crate::{
+	bootstrap::{preflight, ConfigApplier, PreflightOptions},
+	models::{
+		BlockChainType, BlockType, ConfigLoader, EVMReceiptLog, EVMTransaction,
+		EVMTransactionReceipt, Monitor, Network,
+	},
 	repositories::{
 		MonitorRepository, MonitorService, NetworkRepository, NetworkService, TriggerRepository,
 		TriggerService,
 	},
-	utils::metrics::{gather_metrics, update_monitoring_metrics, update_system_metrics},
+	services::{
+		blockchain::{BlockChainClient, ClientPool, ClientPoolTrait, EvmClientTrait},
+		filter::{evaluate_evm_transaction, DecodeFailures, PriceOracles},
+		trigger::{MuteRequest, TriggerError, TriggerMutes},
+	},
+	utils::{
+		build_info::BuildInfo,
+		metrics::{gather_metrics, update_monitoring_metrics, update_system_metrics},
+	},
 };
 
+/// Largest request body accepted by the monitor evaluation endpoint
+const EVALUATE_BODY_LIMIT: usize = 256 * 1024;
+
+/// Environment variable holding the bearer token of the admin endpoints
+pub const METRICS_ADMIN_TOKEN_ENV: &str = "METRICS_ADMIN_TOKEN";
+
+/// Bearer token required by the admin endpoints, which are disabled without one
+#[derive(Clone, Debug, Default)]
+pub struct AdminToken(Option<String>);
+
+impl AdminToken {
+	/// Creates an admin token, treating an empty token as none
+	pub fn new(token: Option<String>) -> Self {
+		Self(token.filter(|token| !token.is_empty()))
+	}
+
+	/// Reads the admin token from `METRICS_ADMIN_TOKEN`
+	pub fn from_env() -> Self {
+		Self::new(std::env::var(METRICS_ADMIN_TOKEN_ENV).ok())
+	}
+
+	/// Whether the admin endpoints are enabled
+	pub fn is_enabled(&self) -> bool {
+		self.0.is_some()
+	}
+}
+
+/// Guard of the admin endpoints
+///
+/// Extracting it fails unless the request carries the configured admin token as a bearer token,
+/// so it must come first in the arguments of a handler.
+struct AdminAuth;
+
+impl FromRequest for AdminAuth {
+	type Error = actix_web::Error;
+	type Future = Ready<Result<Self, Self::Error>>;
+
+	fn from_request(req: &HttpRequest, _: &mut Payload) -> Self::Future {
+		std::future::ready(authorize_admin(req))
+	}
+}
+
+/// Checks the bearer token of a request against the admin token
+fn authorize_admin(req: &HttpRequest) -> Result<AdminAuth, actix_web::Error> {
+	let Some(expected) = req
+		.app_data::<web::Data<AdminToken>>()
+		.and_then(|token| token.0.clone())
+	else {
+		return Err(InternalError::from_response(
+			"Admin endpoints are disabled",
+			HttpResponse::Forbidden().json(serde_json::json!({
+				"error": format!(
+					"Admin endpoints are disabled, set {} to enable them",
+					METRICS_ADMIN_TOKEN_ENV
+				)
+			})),
+		)
+		.into());
+	};
+
+	let provided = req
+		.headers()
+		.get(header::AUTHORIZATION)
+		.and_then(|value| value.to_str().ok())
+		.and_then(|value| value.strip_prefix("Bearer "));
+	match provided {
+		Some(provided) if tokens_match(provided.as_bytes(), expected.as_bytes()) => Ok(AdminAuth),
+		_ => Err(InternalError::from_response(
+			"Invalid admin token",
+			HttpResponse::Unauthorized()
+				.insert_header((header::WWW_AUTHENTICATE, "Bearer"))
+				.json(serde_json::json!({ "error": "Missing or invalid admin token" })),
+		)
+		.into()),
+	}
+}
+
+/// Compares two tokens in time independent of the position of their first difference
+fn tokens_match(provided: &[u8], expected: &[u8]) -> bool {
+	provided.len() == expected.len()
+		&& provided
+			.iter()
+			.zip(expected)
+			.fold(0u8, |diff, (a, b)| diff | (a ^ b))
+			== 0
+}
+
 // Type aliases to simplify complex types in function signatures
 //  MonitorService
 pub type MonitorServiceData = web::Data<
@@ -83,12 +199,297 @@ async fn metrics_handler(
 	}
 }
 
+/// Query parameters of the preflight endpoint
+#[derive(Debug, Deserialize)]
+struct PreflightQuery {
+	/// Whether to probe RPC and notification endpoints
+	#[serde(default)]
+	probe: bool,
+}
+
+/// Preflight endpoint handler
+///
+/// Returns the preflight report as JSON, probing endpoints when called with `?probe=true`.
+async fn preflight_handler(
+	_auth: AdminAuth,
+	query: web::Query<PreflightQuery>,
+	monitor_service: MonitorServiceData,
+	network_service: NetworkServiceData,
+	trigger_service: TriggerServiceData,
+) -> impl Responder {
+	let monitors = monitor_service.lock().await.get_all();
+	let networks = network_service.lock().await.get_all();
+	let triggers = trigger_service.lock().await.get_all();
+
+	let report = preflight(
+		&monitors,
+		&networks,
+		&triggers,
+		&PreflightOptions {
+			probe: query.probe,
+			..Default::default()
+		},
+	)
+	.await;
+
+	HttpResponse::Ok().json(report)
+}
+
+/// Version endpoint handler
+///
+/// Returns the version, commit, build time and features of the monitor.
+async fn version_handler() -> impl Responder {
+	HttpResponse::Ok().json(BuildInfo::current())
+}
+
+/// Decoding failures endpoint handler
+///
+/// Returns the ABI decoding failures of each monitor condition since the monitor started.
+async fn decode_failures_handler(
+	_auth: AdminAuth,
+	decode_failures: web::Data<Arc<DecodeFailures>>,
+) -> impl Responder {
+	HttpResponse::Ok().json(serde_json::json!({
+		"decode_failures": decode_failures.summary()
+	}))
+}
+
+/// Configuration diff endpoint handler
+///
+/// Returns the diff of the latest reloaded configuration and whether it was applied.
+async fn config_diff_handler(
+	_auth: AdminAuth,
+	config_applier: web::Data<Arc<ConfigApplier>>,
+) -> impl Responder {
+	match config_applier.latest_diff() {
+		Some(report) => HttpResponse::Ok().json(report),
+		None => HttpResponse::NotFound()
+			.json(serde_json::json!({ "error": "No configuration has been reloaded" })),
+	}
+}
+
+/// Configuration apply endpoint handler
+///
+/// Applies the staged configuration, returning its diff. The running monitor restarts its block
+/// processing with the applied configuration.
+async fn config_apply_handler(
+	_auth: AdminAuth,
+	config_applier: web::Data<Arc<ConfigApplier>>,
+) -> impl Responder {
+	match config_applier.apply_staged() {
+		Some(report) => {
+			info!("Applied staged configuration {}", report.generation);
+			HttpResponse::Ok().json(report)
+		}
+		None => HttpResponse::Conflict()
+			.json(serde_json::json!({ "error": "No configuration is staged" })),
+	}
+}
+
+/// Mute creation endpoint handler
+///
+/// Mutes a trigger, returning the created mute.
+async fn create_mute_handler(
+	_auth: AdminAuth,
+	request: web::Json<MuteRequest>,
+	mutes: web::Data<Arc<TriggerMutes>>,
+) -> impl Responder {
+	match mutes.mute(request.into_inner()).await {
+		Ok(mute) => HttpResponse::Created().json(mute),
+		Err(TriggerError::ConfigurationError(e)) => {
+			HttpResponse::BadRequest().json(serde_json::json!({ "error": e.message }))
+		}
+		Err(e) => {
+			HttpResponse::InternalServerError().json(serde_json::json!({ "error": e.to_string() }))
+		}
+	}
+}
+
+/// Mutes endpoint handler
+///
+/// Returns the active mutes.
+async fn list_mutes_handler(
+	_auth: AdminAuth,
+	mutes: web::Data<Arc<TriggerMutes>>,
+) -> impl Responder {
+	match mutes.list().await {
+		Ok(mutes) => HttpResponse::Ok().json(serde_json::json!({ "mutes": mutes })),
+		Err(e) => {
+			HttpResponse::InternalServerError().json(serde_json::json!({ "error": e.to_string() }))
+		}
+	}
+}
+
+/// Mute removal endpoint handler
+///
+/// Removes a mute before it expires, returning the removed mute.
+async fn delete_mute_handler(
+	_auth: AdminAuth,
+	id: web::Path<String>,
+	mutes: web::Data<Arc<TriggerMutes>>,
+) -> impl Responder {
+	match mutes.unmute(&id).await {
+		Ok(Some(mute)) => HttpResponse::Ok().json(mute),
+		Ok(None) => HttpResponse::NotFound()
+			.json(serde_json::json!({ "error": format!("Mute '{}' not found", id) })),
+		Err(e) => {
+			HttpResponse::InternalServerError().json(serde_json::json!({ "error": e.to_string() }))
+		}
+	}
+}
+
+/// Transaction provided inline to evaluate a monitor against
+#[derive(Deserialize)]
+struct TransactionFixture {
+	transaction: EVMTransaction,
+	receipt: Option<EVMTransactionReceipt>,
+	#[serde(default)]
+	logs: Vec<EVMReceiptLog>,
+}
+
+/// Body of the monitor evaluation endpoint
+#[derive(Deserialize)]
+struct EvaluateRequest {
+	/// Monitor definition to evaluate
+	monitor: Option<Monitor>,
+	/// Name of a loaded monitor to evaluate, if no definition is provided
+	monitor_name: Option<String>,
+	/// Slug of the network of the transaction
+	network: String,
+	/// Hash of a transaction to fetch from the network
+	transaction_hash: Option<String>,
+	/// Transaction to evaluate, instead of fetching one
+	fixture: Option<TransactionFixture>,
+}
+
+/// Monitor evaluation endpoint handler
+///
+/// Evaluates a monitor against a single transaction and reports the outcome of each of its
+/// conditions, without dispatching any notification.
+async fn evaluate_monitor_handler(
+	_auth: AdminAuth,
+	request: web::Json<EvaluateRequest>,
+	monitor_service: MonitorServiceData,
+	network_service: NetworkServiceData,
+	client_pool: web::Data<Arc<ClientPool>>,
+	price_oracles: web::Data<Arc<PriceOracles>>,
+) -> impl Responder {
+	let request = request.into_inner();
+	let error_body = |message: String| serde_json::json!({ "error": message });
+
+	let monitor = match (request.monitor, request.monitor_name) {
+		(Some(monitor), _) => {
+			if let Err(e) = monitor.validate() {
+				return HttpResponse::BadRequest()
+					.json(error_body(format!("Invalid monitor: {}", e)));
+			}
+			monitor
+		}
+		(None, Some(name)) => match monitor_service.lock().await.get(&name) {
+			Some(monitor) => monitor,
+			None => {
+				return HttpResponse::NotFound()
+					.json(error_body(format!("Monitor '{}' not found", name)))
+			}
+		},
+		(None, None) => {
+			return HttpResponse::BadRequest().json(error_body(
+				"Either monitor or monitor_name is required".to_string(),
+			))
+		}
+	};
+
+	let Some(network) = network_service.lock().await.get(&request.network) else {
+		return HttpResponse::NotFound().json(error_body(format!(
+			"Network '{}' not found",
+			request.network
+		)));
+	};
+	if network.network_type != BlockChainType::EVM {
+		return HttpResponse::BadRequest().json(error_body(format!(
+			"Evaluating monitors is only supported on EVM networks, '{}' is not one",
+			network.slug
+		)));
+	}
+
+	let (transaction, receipt, logs) = match (request.fixture, request.transaction_hash) {
+		(Some(fixture), _) => (fixture.transaction, fixture.receipt, fixture.logs),
+		(None, Some(hash)) => match fetch_evm_transaction(&client_pool, &network, &hash).await {
+			Ok(fetched) => fetched,
+			Err(e) => {
+				error!("Failed to fetch transaction {}: {}", hash, e);
+				return HttpResponse::BadGateway().json(error_body(format!(
+					"Failed to fetch transaction {}: {}",
+					hash, e
+				)));
+			}
+		},
+		(None, None) => {
+			return HttpResponse::BadRequest().json(error_body(
+				"Either transaction_hash or fixture is required".to_string(),
+			))
+		}
+	};
+
+	HttpResponse::Ok().json(evaluate_evm_transaction(
+		&monitor,
+		&network.slug,
+		&transaction,
+		receipt,
+		&logs,
+		&price_oracles,
+	))
+}
+
+/// Fetches a transaction with its receipt and logs from an EVM network
+async fn fetch_evm_transaction(
+	client_pool: &ClientPool,
+	network: &Network,
+	hash: &str,
+) -> Result<
+	(
+		EVMTransaction,
+		Option<EVMTransactionReceipt>,
+		Vec<EVMReceiptLog>,
+	),
+	anyhow::Error,
+> {
+	let client = client_pool.get_evm_client(network).await?;
+	let receipt = client.get_transaction_receipt(hash.to_string()).await?;
+	let block_number = receipt
+		.block_number
+		.ok_or_else(|| anyhow::anyhow!("Transaction is not included in a block yet"))?;
+
+	let transaction = client
+		.get_blocks(block_number.to::<u64>(), None)
+		.await?
+		.into_iter()
+		.find_map(|block| match block {
+			BlockType::EVM(block) => block
+				.transactions
+				.iter()
+				.find(|transaction| transaction.hash == receipt.transaction_hash)
+				.cloned(),
+			_ => None,
+		})
+		.ok_or_else(|| anyhow::anyhow!("Transaction not found in block {}", block_number))?;
+
+	let logs = receipt.logs.clone();
+	Ok((transaction, Some(receipt), logs))
+}
+
 // Create metrics server
 pub fn create_metrics_server(
 	bind_address: String,
 	monitor_service: MonitorServiceArc,
 	network_service: NetworkServiceArc,
 	trigger_service: TriggerServiceArc,
+	config_applier: Arc<ConfigApplier>,
+	client_pool: Arc<ClientPool>,
+	price_oracles: Arc<PriceOracles>,
+	decode_failures: Arc<DecodeFailures>,
+	mutes: Arc<TriggerMutes>,
+	admin_token: AdminToken,
 ) -> std::io::Result<actix_web::dev::Server> {
 	let actual_bind_address = if std::env::var("IN_DOCKER").unwrap_or_default() == "true" {
 		if let Some(port) = bind_address.split(':').nth(1) {
@@ -104,6 +505,12 @@ pub fn create_metrics_server(
 		"Starting metrics server on {} (actual bind: {})",
 		bind_address, actual_bind_address
 	);
+	if !admin_token.is_enabled() {
+		info!(
+			"Admin endpoints of the metrics server are disabled, set {} to enable them",
+			METRICS_ADMIN_TOKEN_ENV
+		);
+	}
 
 	Ok(HttpServer::new(move || {
 		App::new()
@@ -113,7 +520,26 @@ pub fn create_metrics_server(
 			.app_data(web::Data::new(monitor_service.clone()))
 			.app_data(web::Data::new(network_service.clone()))
 			.app_data(web::Data::new(trigger_service.clone()))
+			.app_data(web::Data::new(config_applier.clone()))
+			.app_data(web::Data::new(client_pool.clone()))
+			.app_data(web::Data::new(price_oracles.clone()))
+			.app_data(web::Data::new(decode_failures.clone()))
+			.app_data(web::Data::new(mutes.clone()))
+			.app_data(web::Data::new(admin_token.clone()))
 			.route("/metrics", web::get().to(metrics_handler))
+			.route("/preflight", web::get().to(preflight_handler))
+			.route("/version", web::get().to(version_handler))
+			.route("/decode-failures", web::get().to(decode_failures_handler))
+			.route("/config/diff", web::get().to(config_diff_handler))
+			.route("/config/apply", web::post().to(config_apply_handler))
+			.route("/mutes", web::get().to(list_mutes_handler))
+			.route("/mutes", web::post().to(create_mute_handler))
+			.route("/mutes/{id}", web::delete().to(delete_mute_handler))
+			.service(
+				web::resource("/monitors/evaluate")
+					.app_data(web::JsonConfig::default().limit(EVALUATE_BODY_LIMIT))
+					.route(web::post().to(evaluate_monitor_handler)),
+			)
 	})
 	.workers(2)
 	.bind(actual_bind_address)?
@@ -125,19 +551,34 @@ pub fn create_metrics_server(
 mod tests {
 	use super::*;
 	use crate::{
-		models::{BlockChainType, Monitor, Network, Trigger},
+		bootstrap::ConfigApplyOptions,
+		models::{BlockChainType, ConfigSnapshot, Monitor, Network, TransactionStatus, Trigger},
 		repositories::{
-			MonitorService, NetworkRepository, NetworkService, TriggerRepository, TriggerService,
+			MonitorRepository, MonitorService, NetworkRepository, NetworkService,
+			TriggerRepository, TriggerService,
 		},
+		services::state::InMemoryStateStore,
 		utils::tests::{
-			evm::monitor::MonitorBuilder, network::NetworkBuilder, trigger::TriggerBuilder,
+			evm::{monitor::MonitorBuilder, transaction::TransactionBuilder},
+			network::NetworkBuilder,
+			trigger::TriggerBuilder,
 		},
 	};
-	use actix_web::{test, App};
-	use std::{fs, path::PathBuf};
+	use actix_web::{http::header::ContentType, test, App};
+	use alloy::primitives::{Address, U256};
+	use std::{collections::HashMap, fs, path::PathBuf};
 	use tempfile::TempDir;
 	use tokio::net::TcpListener;
 
+	const TEST_ADMIN_TOKEN: &str = "test-admin-token";
+
+	fn admin_authorization() -> (header::HeaderName, String) {
+		(
+			header::AUTHORIZATION,
+			format!("Bearer {}", TEST_ADMIN_TOKEN),
+		)
+	}
+
 	fn create_test_monitor(
 		name: &str,
 		networks: Vec<&str>,
@@ -297,6 +738,411 @@ mod tests {
 		assert!(body_str.contains("# HELP"));
 	}
 
+	#[actix_web::test]
+	async fn test_preflight_handler() {
+		let network =
+			create_test_network("Ethereum Mainnet", "ethereum_mainnet", BlockChainType::EVM);
+		let trigger = create_test_trigger("test_trigger");
+		let monitor = create_test_monitor(
+			"test_monitor",
+			vec!["ethereum_mainnet"],
+			false,
+			vec!["test_trigger"],
+		);
+
+		let monitor_service: MonitorServiceArc = Arc::new(Mutex::new(
+			MonitorService::new_with_repository(MonitorRepository::new_with_monitors(
+				HashMap::from([(monitor.name.clone(), monitor)]),
+			))
+			.unwrap(),
+		));
+		let network_service: NetworkServiceArc = Arc::new(Mutex::new(
+			NetworkService::new_with_repository(NetworkRepository {
+				networks: HashMap::from([(network.slug.clone(), network)]),
+			})
+			.unwrap(),
+		));
+		let trigger_service: TriggerServiceArc = Arc::new(Mutex::new(
+			TriggerService::new_with_repository(TriggerRepository {
+				triggers: HashMap::from([(trigger.name.clone(), trigger)]),
+			})
+			.unwrap(),
+		));
+
+		let app = test::init_service(
+			App::new()
+				.app_data(web::Data::new(AdminToken::new(Some(
+					TEST_ADMIN_TOKEN.to_string(),
+				))))
+				.app_data(web::Data::new(monitor_service.clone()))
+				.app_data(web::Data::new(network_service.clone()))
+				.app_data(web::Data::new(trigger_service.clone()))
+				.route("/preflight", web::get().to(preflight_handler)),
+		)
+		.await;
+
+		// Probes reach out to every endpoint, so the report requires the admin token
+		let req = test::TestRequest::get()
+			.uri("/preflight?probe=true")
+			.to_request();
+		let resp = test::call_service(&app, req).await;
+		assert_eq!(resp.status(), 401);
+
+		let req = test::TestRequest::get()
+			.uri("/preflight")
+			.insert_header(admin_authorization())
+			.to_request();
+		let resp = test::call_service(&app, req).await;
+		assert!(resp.status().is_success());
+
+		let body: serde_json::Value = test::read_body_json(resp).await;
+		assert_eq!(body["version"], env!("CARGO_PKG_VERSION"));
+		assert_eq!(body["git_sha"], crate::utils::build_info::GIT_SHA);
+		assert_eq!(body["probed"], false);
+		assert_eq!(body["networks"][0]["slug"], "ethereum_mainnet");
+		assert_eq!(body["networks"][0]["active_monitors"], 1);
+		assert_eq!(
+			body["networks"][0]["endpoints"][0]["endpoint"],
+			"http://localhost:8545"
+		);
+		assert_eq!(
+			body["networks"][0]["endpoints"][0]["probe"]["status"],
+			"skipped"
+		);
+		assert_eq!(body["monitors"][0]["triggers"][0], "test_trigger");
+		assert_eq!(body["triggers"][0]["endpoint"], "https://hooks.slack.com");
+	}
+
+	#[actix_web::test]
+	async fn test_version_handler() {
+		let app =
+			test::init_service(App::new().route("/version", web::get().to(version_handler))).await;
+
+		let req = test::TestRequest::get().uri("/version").to_request();
+		let resp = test::call_service(&app, req).await;
+		assert!(resp.status().is_success());
+
+		let body: serde_json::Value = test::read_body_json(resp).await;
+		assert_eq!(body["version"], env!("CARGO_PKG_VERSION"));
+		assert!(body["git_sha"].is_string());
+		assert!(body["build_timestamp"].is_string());
+		assert!(body["features"].is_array());
+		assert_eq!(body.as_object().unwrap().len(), 4);
+	}
+
+	#[actix_web::test]
+	async fn test_decode_failures_handler() {
+		let app = test::init_service(
+			App::new()
+				.app_data(web::Data::new(Arc::new(DecodeFailures::default())))
+				.app_data(web::Data::new(AdminToken::new(Some(
+					TEST_ADMIN_TOKEN.to_string(),
+				))))
+				.route("/decode-failures", web::get().to(decode_failures_handler)),
+		)
+		.await;
+
+		let req = test::TestRequest::get()
+			.uri("/decode-failures")
+			.to_request();
+		let resp = test::call_service(&app, req).await;
+		assert_eq!(resp.status(), 401);
+
+		let req = test::TestRequest::get()
+			.uri("/decode-failures")
+			.insert_header(admin_authorization())
+			.to_request();
+		let resp = test::call_service(&app, req).await;
+		assert!(resp.status().is_success());
+
+		let body: serde_json::Value = test::read_body_json(resp).await;
+		assert!(body["decode_failures"].is_array());
+	}
+
+	#[actix_web::test]
+	async fn test_config_diff_and_apply_handlers() {
+		let config_applier = ConfigApplier::new(
+			ConfigSnapshot::default(),
+			ConfigApplyOptions {
+				require_approval: true,
+				auto_apply_after: None,
+			},
+		);
+		let app = test::init_service(
+			App::new()
+				.app_data(web::Data::new(config_applier.clone()))
+				.app_data(web::Data::new(AdminToken::new(Some(
+					TEST_ADMIN_TOKEN.to_string(),
+				))))
+				.route("/config/diff", web::get().to(config_diff_handler))
+				.route("/config/apply", web::post().to(config_apply_handler)),
+		)
+		.await;
+
+		let req = test::TestRequest::get()
+			.uri("/config/diff")
+			.insert_header(admin_authorization())
+			.to_request();
+		assert_eq!(test::call_service(&app, req).await.status(), 404);
+
+		config_applier.propose(ConfigSnapshot {
+			monitors: HashMap::from([(
+				"Test Monitor".to_string(),
+				create_test_monitor("Test Monitor", vec!["ethereum_mainnet"], false, vec![]),
+			)]),
+			..Default::default()
+		});
+
+		let req = test::TestRequest::get()
+			.uri("/config/diff")
+			.insert_header(admin_authorization())
+			.to_request();
+		let body: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+		assert_eq!(body["status"], "staged");
+		assert_eq!(body["diff"]["monitors"]["added"][0], "Test Monitor");
+		assert!(config_applier.current().monitors.is_empty());
+
+		let req = test::TestRequest::post()
+			.uri("/config/apply")
+			.insert_header(admin_authorization())
+			.to_request();
+		let body: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+		assert_eq!(body["status"], "applied");
+		assert!(config_applier
+			.current()
+			.monitors
+			.contains_key("Test Monitor"));
+
+		let req = test::TestRequest::post()
+			.uri("/config/apply")
+			.insert_header(admin_authorization())
+			.to_request();
+		assert_eq!(test::call_service(&app, req).await.status(), 409);
+	}
+
+	#[actix_web::test]
+	async fn test_mutes_handlers() {
+		let mutes = Arc::new(TriggerMutes::new(Arc::new(InMemoryStateStore::new())));
+		let app = test::init_service(
+			App::new()
+				.app_data(web::Data::new(mutes.clone()))
+				.app_data(web::Data::new(AdminToken::new(Some(
+					TEST_ADMIN_TOKEN.to_string(),
+				))))
+				.route("/mutes", web::get().to(list_mutes_handler))
+				.route("/mutes", web::post().to(create_mute_handler))
+				.route("/mutes/{id}", web::delete().to(delete_mute_handler)),
+		)
+		.await;
+
+		let req = test::TestRequest::post()
+			.uri("/mutes")
+			.set_json(serde_json::json!({
+				"trigger": "test_trigger",
+				"monitor": "test_monitor",
+				"duration_secs": 3600,
+				"reason": "protocol upgrade"
+			}))
+			.insert_header(admin_authorization())
+			.to_request();
+		let resp = test::call_service(&app, req).await;
+		assert_eq!(resp.status(), 201);
+		let created: serde_json::Value = test::read_body_json(resp).await;
+		assert_eq!(created["trigger"], "test_trigger");
+		assert_eq!(created["reason"], "protocol upgrade");
+		let id = created["id"].as_str().unwrap().to_string();
+		assert!(mutes.check("test_trigger", "test_monitor").await.is_some());
+
+		let req = test::TestRequest::get()
+			.uri("/mutes")
+			.insert_header(admin_authorization())
+			.to_request();
+		let body: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+		assert_eq!(body["mutes"], serde_json::json!([created]));
+
+		let req = test::TestRequest::post()
+			.uri("/mutes")
+			.set_json(serde_json::json!({ "trigger": "test_trigger", "duration_secs": 0 }))
+			.insert_header(admin_authorization())
+			.to_request();
+		assert_eq!(test::call_service(&app, req).await.status(), 400);
+
+		let req = test::TestRequest::delete()
+			.uri(&format!("/mutes/{}", id))
+			.insert_header(admin_authorization())
+			.to_request();
+		assert_eq!(test::call_service(&app, req).await.status(), 200);
+		let req = test::TestRequest::delete()
+			.uri(&format!("/mutes/{}", id))
+			.insert_header(admin_authorization())
+			.to_request();
+		assert_eq!(test::call_service(&app, req).await.status(), 404);
+		assert!(mutes.check("test_trigger", "test_monitor").await.is_none());
+	}
+
+	#[actix_web::test]
+	async fn test_evaluate_monitor_handler() {
+		let network =
+			create_test_network("Ethereum Mainnet", "ethereum_mainnet", BlockChainType::EVM);
+		let monitor_service: MonitorServiceArc = Arc::new(Mutex::new(
+			MonitorService::new_with_repository(MonitorRepository::new_with_monitors(
+				HashMap::new(),
+			))
+			.unwrap(),
+		));
+		let network_service: NetworkServiceArc = Arc::new(Mutex::new(
+			NetworkService::new_with_repository(NetworkRepository {
+				networks: HashMap::from([(network.slug.clone(), network)]),
+			})
+			.unwrap(),
+		));
+
+		let app = test::init_service(
+			App::new()
+				.app_data(web::Data::new(monitor_service.clone()))
+				.app_data(web::Data::new(network_service.clone()))
+				.app_data(web::Data::new(Arc::new(ClientPool::new())))
+				.app_data(web::Data::new(Arc::new(PriceOracles::default())))
+				.app_data(web::Data::new(AdminToken::new(Some(
+					TEST_ADMIN_TOKEN.to_string(),
+				))))
+				.service(
+					web::resource("/monitors/evaluate")
+						.app_data(web::JsonConfig::default().limit(EVALUATE_BODY_LIMIT))
+						.route(web::post().to(evaluate_monitor_handler)),
+				),
+		)
+		.await;
+
+		let to = Address::with_last_byte(2);
+		let monitor = MonitorBuilder::new()
+			.name("Large Transfers")
+			.networks(vec!["ethereum_mainnet".to_string()])
+			.address(&format!("{:?}", to))
+			.transaction(TransactionStatus::Any, Some("value > 100".to_string()))
+			.transaction(TransactionStatus::Any, Some("value >".to_string()))
+			.build();
+		let transaction = TransactionBuilder::new()
+			.to(to)
+			.value(U256::from(150))
+			.build();
+
+		let req = test::TestRequest::post()
+			.uri("/monitors/evaluate")
+			.set_json(serde_json::json!({
+				"monitor": monitor,
+				"network": "ethereum_mainnet",
+				"fixture": { "transaction": transaction },
+			}))
+			.insert_header(admin_authorization())
+			.to_request();
+		let resp = test::call_service(&app, req).await;
+		assert!(resp.status().is_success());
+
+		let body: serde_json::Value = test::read_body_json(resp).await;
+		assert_eq!(body["monitor"], "Large Transfers");
+		assert_eq!(body["network"], "ethereum_mainnet");
+		assert_eq!(body["matched"], true);
+		assert_eq!(body["conditions"].as_array().unwrap().len(), 2);
+
+		let matching = &body["conditions"][0];
+		assert_eq!(matching["kind"], "transaction");
+		assert_eq!(matching["expression"], "value > 100");
+		assert_eq!(matching["parsed"], true);
+		assert_eq!(matching["result"], true);
+		assert!(matching["error"].is_null());
+		assert!(matching["args"]
+			.as_array()
+			.unwrap()
+			.iter()
+			.any(|arg| arg["name"] == "value" && arg["value"] == "150"));
+
+		let failing = &body["conditions"][1];
+		assert_eq!(failing["expression"], "value >");
+		assert_eq!(failing["parsed"], false);
+		assert!(failing["result"].is_null());
+		assert!(failing["error"]["message"].is_string());
+		assert!(failing["error"]["position"].is_u64());
+
+		let req = test::TestRequest::post()
+			.uri("/monitors/evaluate")
+			.set_json(serde_json::json!({
+				"monitor": MonitorBuilder::new().name("No Networks").networks(vec![]).build(),
+				"network": "ethereum_mainnet",
+				"fixture": { "transaction": transaction },
+			}))
+			.insert_header(admin_authorization())
+			.to_request();
+		let resp = test::call_service(&app, req).await;
+		assert_eq!(resp.status(), 400);
+		let body: serde_json::Value = test::read_body_json(resp).await;
+		assert!(body["error"]
+			.as_str()
+			.unwrap()
+			.starts_with("Invalid monitor"));
+
+		let req = test::TestRequest::post()
+			.uri("/monitors/evaluate")
+			.insert_header(ContentType::json())
+			.set_payload(format!(
+				"{{\"network\": \"{}\"}}",
+				"a".repeat(EVALUATE_BODY_LIMIT)
+			))
+			.insert_header(admin_authorization())
+			.to_request();
+		assert_eq!(test::call_service(&app, req).await.status(), 413);
+	}
+
+	#[actix_web::test]
+	async fn test_admin_endpoints_require_token() {
+		let mutes = Arc::new(TriggerMutes::new(Arc::new(InMemoryStateStore::new())));
+		let app = test::init_service(
+			App::new()
+				.app_data(web::Data::new(mutes.clone()))
+				.app_data(web::Data::new(AdminToken::new(Some(
+					TEST_ADMIN_TOKEN.to_string(),
+				))))
+				.route("/mutes", web::get().to(list_mutes_handler))
+				.route("/mutes", web::post().to(create_mute_handler)),
+		)
+		.await;
+
+		let req = test::TestRequest::get().uri("/mutes").to_request();
+		let resp = test::call_service(&app, req).await;
+		assert_eq!(resp.status(), 401);
+		assert_eq!(
+			resp.headers().get(header::WWW_AUTHENTICATE).unwrap(),
+			"Bearer"
+		);
+
+		let req = test::TestRequest::post()
+			.uri("/mutes")
+			.set_json(serde_json::json!({ "trigger": "test_trigger", "duration_secs": 3600 }))
+			.insert_header((header::AUTHORIZATION, "Bearer wrong-admin-token"))
+			.to_request();
+		assert_eq!(test::call_service(&app, req).await.status(), 401);
+		assert!(mutes.list().await.unwrap().is_empty());
+
+		let req = test::TestRequest::get()
+			.uri("/mutes")
+			.insert_header(admin_authorization())
+			.to_request();
+		assert_eq!(test::call_service(&app, req).await.status(), 200);
+
+		let app = test::init_service(
+			App::new()
+				.app_data(web::Data::new(mutes.clone()))
+				.app_data(web::Data::new(AdminToken::new(Some(String::new()))))
+				.route("/mutes", web::get().to(list_mutes_handler)),
+		)
+		.await;
+		let req = test::TestRequest::get()
+			.uri("/mutes")
+			.insert_header((header::AUTHORIZATION, "Bearer "))
+			.to_request();
+		assert_eq!(test::call_service(&app, req).await.status(), 403);
+	}
+
 	#[tokio::test]
 	async fn test_create_metrics_server() {
 		// Create test services
@@ -316,6 +1162,12 @@ mod tests {
 			monitor_service,
 			network_service,
 			trigger_service,
+			ConfigApplier::new(ConfigSnapshot::default(), ConfigApplyOptions::default()),
+			Arc::new(ClientPool::new()),
+			Arc::new(PriceOracles::default()),
+			Arc::new(DecodeFailures::default()),
+			Arc::new(TriggerMutes::new(Arc::new(InMemoryStateStore::new()))),
+			AdminToken::default(),
 		);
 
 		// Assert server creation is successful
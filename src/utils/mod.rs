@@ -3,6 +3,8 @@
 //! This module provides various utility functions and types that are used across
 //! the application. Currently includes:
 //!
+//! - build_info: Version and build information of the monitor
+//! - chaos: Fault injection for resilience testing
 //! - constants: Constants for the application
 //! - cron_utils: Utilities for working with cron schedules and time intervals
 //! - logging: Logging utilities
@@ -15,6 +17,8 @@
 
 mod cron_utils;
 
+pub mod build_info;
+pub mod chaos;
 pub mod client_storage;
 pub mod constants;
 pub mod http;
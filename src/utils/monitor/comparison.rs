@@ -0,0 +1,515 @@
+//! Monitor comparison module
+//!
+//! This module evaluates two versions of a monitor over the same range of blocks and reports
+//! the matches only one of them produces. No notification is sent while comparing.
+use crate::{
+	bootstrap::get_contract_specs,
+	models::{BlockChainType, ContractSpec, Monitor, MonitorMatch, Network},
+	services::{
+		blockchain::{BlockChainClient, BlockFilterFactory, ClientPoolTrait},
+		filter::FilterServiceTrait,
+	},
+	utils::monitor::{execution::ExecutionResult, MonitorExecutionError},
+};
+use serde::Serialize;
+use std::{collections::BTreeMap, str::FromStr, sync::Arc};
+use tracing::instrument;
+
+/// Number of blocks fetched at once while comparing
+const BLOCKS_PER_FETCH: u64 = 100;
+
+/// Names the monitors are evaluated under, to tell their matches apart
+const OLD_MONITOR_LABEL: &str = "old";
+const NEW_MONITOR_LABEL: &str = "new";
+
+/// Configuration for comparing two versions of a monitor
+///
+/// # Arguments
+///
+/// * `old_monitor` - The current version of the monitor
+/// * `new_monitor` - The version of the monitor to roll out
+/// * `network` - The network to evaluate the monitors on
+/// * `from_block` - The first block to evaluate
+/// * `to_block` - The last block to evaluate, included
+/// * `filter_service` - The filter service to use
+/// * `client_pool` - The client pool to use
+pub struct MonitorComparisonConfig<
+	CP: ClientPoolTrait + Send + Sync + 'static,
+	FS: FilterServiceTrait + Send + Sync + 'static,
+> {
+	pub old_monitor: Monitor,
+	pub new_monitor: Monitor,
+	pub network: Network,
+	pub from_block: u64,
+	pub to_block: u64,
+	pub filter_service: Arc<FS>,
+	pub client_pool: Arc<CP>,
+}
+
+/// A match in a comparison, identified by its transaction and matched condition
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+pub struct ComparedMatch {
+	/// Number of the block containing the transaction, if known
+	pub block_number: Option<u64>,
+	/// Hash of the matched transaction
+	pub transaction_hash: String,
+	/// Matched condition, e.g. `event Transfer(address,address,uint256)`
+	pub condition: String,
+}
+
+impl ComparedMatch {
+	/// Returns an entry for every condition the match was found on
+	fn from_match(monitor_match: &MonitorMatch) -> Vec<Self> {
+		let matched_on = monitor_match.matched_on();
+		let mut conditions = matched_on
+			.functions
+			.iter()
+			.map(|function| format!("function {}", function.signature))
+			.chain(
+				matched_on
+					.events
+					.iter()
+					.map(|event| format!("event {}", event.signature)),
+			)
+			.chain(
+				matched_on
+					.transactions
+					.iter()
+					.map(|transaction| format!("transaction {:?}", transaction.status)),
+			)
+			.collect::<Vec<_>>();
+		if conditions.is_empty() {
+			conditions.push("transaction".to_string());
+		}
+
+		conditions
+			.into_iter()
+			.map(|condition| Self {
+				block_number: monitor_match.block_reference(),
+				transaction_hash: monitor_match.transaction_hash(),
+				condition,
+			})
+			.collect()
+	}
+}
+
+/// Condition on a comparison report failing a CI check
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ComparisonGate {
+	/// Fails if the old monitor matches something the new one does not
+	OnlyInOld,
+	/// Fails if the new monitor matches something the old one does not
+	OnlyInNew,
+	/// Fails on any difference between the monitors
+	AnyChange,
+}
+
+impl FromStr for ComparisonGate {
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		match s {
+			"only-in-old" => Ok(Self::OnlyInOld),
+			"only-in-new" => Ok(Self::OnlyInNew),
+			"any-change" => Ok(Self::AnyChange),
+			_ => Err(format!(
+				"Invalid comparison gate '{}', expected only-in-old, only-in-new or any-change",
+				s
+			)),
+		}
+	}
+}
+
+/// Result of comparing two versions of a monitor over a range of blocks
+#[derive(Debug, Clone, Serialize)]
+pub struct ComparisonReport {
+	/// Slug of the network the monitors were evaluated on
+	pub network: String,
+	/// First block evaluated
+	pub from_block: u64,
+	/// Last block evaluated, included
+	pub to_block: u64,
+	/// Name of the old monitor
+	pub old_monitor: String,
+	/// Name of the new monitor
+	pub new_monitor: String,
+	/// Matches of the old monitor only
+	pub only_in_old: Vec<ComparedMatch>,
+	/// Matches of the new monitor only
+	pub only_in_new: Vec<ComparedMatch>,
+	/// Matches of both monitors
+	pub common: Vec<ComparedMatch>,
+}
+
+impl ComparisonReport {
+	/// Sorts the matches of both monitors into the report buckets
+	///
+	/// Matches are keyed by transaction hash and condition, each bucket is sorted by block.
+	pub fn new(
+		network: &str,
+		from_block: u64,
+		to_block: u64,
+		(old_monitor, old_matches): (&str, &[MonitorMatch]),
+		(new_monitor, new_matches): (&str, &[MonitorMatch]),
+	) -> Self {
+		let keyed = |matches: &[MonitorMatch]| {
+			matches
+				.iter()
+				.flat_map(ComparedMatch::from_match)
+				.map(|m| ((m.transaction_hash.clone(), m.condition.clone()), m))
+				.collect::<BTreeMap<_, _>>()
+		};
+		let old = keyed(old_matches);
+		let mut new = keyed(new_matches);
+
+		let mut only_in_old = Vec::new();
+		let mut common = Vec::new();
+		for (key, compared) in old {
+			match new.remove(&key) {
+				Some(_) => common.push(compared),
+				None => only_in_old.push(compared),
+			}
+		}
+		let mut only_in_new = new.into_values().collect::<Vec<_>>();
+
+		only_in_old.sort();
+		only_in_new.sort();
+		common.sort();
+		Self {
+			network: network.to_string(),
+			from_block,
+			to_block,
+			old_monitor: old_monitor.to_string(),
+			new_monitor: new_monitor.to_string(),
+			only_in_old,
+			only_in_new,
+			common,
+		}
+	}
+
+	/// Returns whether the report fails the given gate
+	pub fn fails(&self, gate: ComparisonGate) -> bool {
+		match gate {
+			ComparisonGate::OnlyInOld => !self.only_in_old.is_empty(),
+			ComparisonGate::OnlyInNew => !self.only_in_new.is_empty(),
+			ComparisonGate::AnyChange => {
+				!self.only_in_old.is_empty() || !self.only_in_new.is_empty()
+			}
+		}
+	}
+
+	/// Serializes the report as pretty printed JSON
+	pub fn to_json(&self) -> serde_json::Result<String> {
+		serde_json::to_string_pretty(self)
+	}
+
+	/// Serializes the matches of the report as CSV, one row per match and bucket
+	pub fn to_csv(&self) -> String {
+		let mut csv = String::from("bucket,block_number,transaction_hash,condition\n");
+		let buckets = [
+			("only_in_old", &self.only_in_old),
+			("only_in_new", &self.only_in_new),
+			("common", &self.common),
+		];
+		for (bucket, matches) in buckets {
+			for compared in matches {
+				csv.push_str(&format!(
+					"{},{},{},{}\n",
+					bucket,
+					compared
+						.block_number
+						.map(|number| number.to_string())
+						.unwrap_or_default(),
+					csv_field(&compared.transaction_hash),
+					csv_field(&compared.condition)
+				));
+			}
+		}
+		csv
+	}
+
+	/// Returns a one line summary of the bucket sizes
+	pub fn summary(&self) -> String {
+		format!(
+			"{} only in old, {} only in new, {} common",
+			self.only_in_old.len(),
+			self.only_in_new.len(),
+			self.common.len()
+		)
+	}
+}
+
+/// Quotes a CSV field if it contains a separator, a quote or a line break
+fn csv_field(value: &str) -> String {
+	if value.contains([',', '"', '\n']) {
+		format!("\"{}\"", value.replace('"', "\"\""))
+	} else {
+		value.to_string()
+	}
+}
+
+/// Evaluates two versions of a monitor over a range of blocks and compares their matches
+///
+/// Both monitors are evaluated together on every block, so they see identical block data.
+/// Matches are only collected, no notification is sent.
+///
+/// # Arguments
+/// * `config` - The monitors, network and range of blocks to compare
+///
+/// # Returns
+/// * `Result<ComparisonReport, ExecutionError>` - The comparison report or error
+#[instrument(skip_all)]
+pub async fn compare_monitors<
+	CP: ClientPoolTrait + Send + Sync + 'static,
+	FS: FilterServiceTrait + Send + Sync + 'static,
+>(
+	config: MonitorComparisonConfig<CP, FS>,
+) -> ExecutionResult<ComparisonReport> {
+	if config.from_block > config.to_block {
+		return Err(MonitorExecutionError::execution_error(
+			format!(
+				"Invalid block range: {} is after {}",
+				config.from_block, config.to_block
+			),
+			None,
+			None,
+		));
+	}
+
+	let monitors = [
+		Arc::new(Monitor {
+			name: OLD_MONITOR_LABEL.to_string(),
+			..config.old_monitor.clone()
+		}),
+		Arc::new(Monitor {
+			name: NEW_MONITOR_LABEL.to_string(),
+			..config.new_monitor.clone()
+		}),
+	];
+	let network = &config.network;
+	let contract_specs = get_contract_specs(
+		&config.client_pool,
+		&[(
+			network.clone(),
+			monitors
+				.iter()
+				.map(|monitor| monitor.as_ref().clone())
+				.collect(),
+		)],
+	)
+	.await;
+
+	let matches = match network.network_type {
+		BlockChainType::EVM => {
+			let client = config
+				.client_pool
+				.get_evm_client(network)
+				.await
+				.map_err(|e| {
+					MonitorExecutionError::execution_error(
+						format!("Failed to get EVM client: {}", e),
+						None,
+						None,
+					)
+				})?;
+			filter_blocks(&*client, &config, &monitors, &contract_specs).await?
+		}
+		BlockChainType::Stellar => {
+			let client = config
+				.client_pool
+				.get_stellar_client(network)
+				.await
+				.map_err(|e| {
+					MonitorExecutionError::execution_error(
+						format!("Failed to get Stellar client: {}", e),
+						None,
+						None,
+					)
+				})?;
+			filter_blocks(&*client, &config, &monitors, &contract_specs).await?
+		}
+		BlockChainType::Midnight => {
+			let client = config
+				.client_pool
+				.get_midnight_client(network)
+				.await
+				.map_err(|e| {
+					MonitorExecutionError::execution_error(
+						format!("Failed to get Midnight client: {}", e),
+						None,
+						None,
+					)
+				})?;
+			filter_blocks(&*client, &config, &monitors, &contract_specs).await?
+		}
+	};
+
+	let (old_matches, new_matches): (Vec<_>, Vec<_>) = matches
+		.into_iter()
+		.partition(|m| m.monitor().name == OLD_MONITOR_LABEL);
+	Ok(ComparisonReport::new(
+		&network.slug,
+		config.from_block,
+		config.to_block,
+		(&config.old_monitor.name, &old_matches),
+		(&config.new_monitor.name, &new_matches),
+	))
+}
+
+/// Fetches the blocks of the range in batches and filters them with the monitors
+async fn filter_blocks<
+	C: BlockChainClient + BlockFilterFactory<C> + Send + Sync + 'static,
+	CP: ClientPoolTrait + Send + Sync + 'static,
+	FS: FilterServiceTrait + Send + Sync + 'static,
+>(
+	client: &C,
+	config: &MonitorComparisonConfig<CP, FS>,
+	monitors: &[Arc<Monitor>],
+	contract_specs: &[(String, ContractSpec)],
+) -> ExecutionResult<Vec<MonitorMatch>> {
+	let mut matches = Vec::new();
+	let mut start = config.from_block;
+	while start <= config.to_block {
+		let end = config
+			.to_block
+			.min(start.saturating_add(BLOCKS_PER_FETCH - 1));
+		tracing::debug!(from = start, to = end, "Fetching blocks");
+		let blocks = client.get_blocks(start, Some(end)).await.map_err(|e| {
+			MonitorExecutionError::execution_error(
+				format!("Failed to get blocks {} to {}: {}", start, end, e),
+				None,
+				None,
+			)
+		})?;
+
+		for block in &blocks {
+			let block_matches = config
+				.filter_service
+				.filter_block(
+					client,
+					&config.network,
+					block,
+					monitors,
+					Some(contract_specs),
+				)
+				.await
+				.map_err(|e| {
+					MonitorExecutionError::execution_error(
+						format!("Failed to filter block: {}", e),
+						None,
+						None,
+					)
+				})?;
+			matches.extend(block_matches);
+		}
+
+		match end.checked_add(1) {
+			Some(next) => start = next,
+			None => break,
+		}
+	}
+	Ok(matches)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{
+		models::TransactionStatus,
+		models::{EventCondition, MatchConditions, TransactionCondition},
+		utils::tests::builders::evm::{
+			monitor_match::MonitorMatchBuilder, transaction::TransactionBuilder,
+		},
+	};
+	use alloy::primitives::B256;
+
+	fn create_match(hash: u8, matched_on: MatchConditions) -> MonitorMatch {
+		MonitorMatchBuilder::new()
+			.transaction(
+				TransactionBuilder::new()
+					.hash(B256::with_last_byte(hash))
+					.build(),
+			)
+			.matched_on(matched_on)
+			.build()
+	}
+
+	fn transfer(hash: u8) -> MonitorMatch {
+		create_match(
+			hash,
+			MatchConditions {
+				events: vec![EventCondition {
+					signature: "Transfer(address,address,uint256)".to_string(),
+					expression: None,
+					match_any_emitter: false,
+				}],
+				..Default::default()
+			},
+		)
+	}
+
+	#[test]
+	fn test_report_buckets() {
+		let old = vec![transfer(1), transfer(2)];
+		let new = vec![
+			transfer(2),
+			create_match(
+				3,
+				MatchConditions {
+					transactions: vec![TransactionCondition {
+						status: TransactionStatus::Success,
+						expression: None,
+					}],
+					..Default::default()
+				},
+			),
+		];
+
+		let report = ComparisonReport::new("ethereum_mainnet", 1, 2, ("a", &old), ("b", &new));
+
+		assert_eq!(report.only_in_old.len(), 1);
+		assert_eq!(
+			report.only_in_old[0].transaction_hash,
+			format!("0x{}01", "00".repeat(31))
+		);
+		assert_eq!(report.common.len(), 1);
+		assert_eq!(report.only_in_new.len(), 1);
+		assert_eq!(report.only_in_new[0].condition, "transaction Success");
+		assert!(report.fails(ComparisonGate::OnlyInOld));
+		assert!(report.fails(ComparisonGate::OnlyInNew));
+		assert_eq!(report.summary(), "1 only in old, 1 only in new, 1 common");
+
+		let report = ComparisonReport::new("ethereum_mainnet", 1, 2, ("a", &new), ("b", &new));
+		assert!(!report.fails(ComparisonGate::AnyChange));
+	}
+
+	#[test]
+	fn test_report_csv() {
+		let report =
+			ComparisonReport::new("ethereum_mainnet", 1, 2, ("a", &[transfer(1)]), ("b", &[]));
+
+		let csv = report.to_csv();
+		let lines = csv.lines().collect::<Vec<_>>();
+		assert_eq!(lines[0], "bucket,block_number,transaction_hash,condition");
+		assert_eq!(
+			lines[1],
+			format!(
+				"only_in_old,,0x{}01,\"event Transfer(address,address,uint256)\"",
+				"00".repeat(31)
+			)
+		);
+	}
+
+	#[test]
+	fn test_comparison_gate_from_str() {
+		assert_eq!(
+			"only-in-old".parse::<ComparisonGate>(),
+			Ok(ComparisonGate::OnlyInOld)
+		);
+		assert_eq!(
+			"any-change".parse::<ComparisonGate>(),
+			Ok(ComparisonGate::AnyChange)
+		);
+		assert!("sometimes".parse::<ComparisonGate>().is_err());
+	}
+}
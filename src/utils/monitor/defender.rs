@@ -0,0 +1,851 @@
+//! Defender import module
+//!
+//! This module translates an OpenZeppelin Defender Sentinel export into monitor and trigger
+//! configurations. Monitors are imported paused, and triggers are stubs reading their secrets
+//! from environment variables. Anything that cannot be translated is listed in the report.
+use crate::{
+	models::{
+		AddressWithSpec, ConfigError, ConfigLoader, ContractSpec, EVMContractSpec, EventCondition,
+		FunctionCondition, MatchConditions, Monitor, NotificationMessage, SecretValue,
+		TransactionCondition, TransactionStatus, Trigger, TriggerType, TriggerTypeConfig,
+	},
+	services::filter::expression_variable_names,
+	utils::RetryConfig,
+};
+use email_address::EmailAddress;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::{
+	collections::{BTreeMap, HashMap},
+	fmt,
+	path::{Path, PathBuf},
+	str::FromStr,
+};
+
+/// Name of the file the imported triggers are written to, under `triggers/`
+const TRIGGERS_FILE_NAME: &str = "defender_import.json";
+
+/// Name of the file the import report is written to
+const REPORT_FILE_NAME: &str = "defender_import_report.json";
+
+/// Prefix of the slugs of the imported triggers
+const TRIGGER_SLUG_PREFIX: &str = "defender";
+
+/// Fields of the export describing the sentinel itself rather than what it watches
+const METADATA_FIELDS: &[&str] = &[
+	"subscriberId",
+	"tenantId",
+	"createdAt",
+	"createdBy",
+	"lastModifiedAt",
+	"stackResourceId",
+];
+
+/// Placeholders of the email trigger settings that are not part of the export
+const EMAIL_HOST_PLACEHOLDER: &str = "smtp.example.com";
+const EMAIL_SENDER_PLACEHOLDER: &str = "alerts@example.com";
+
+/// Defender Sentinel export
+#[derive(Debug, Clone, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct DefenderExport {
+	/// Exported sentinels
+	#[serde(default)]
+	pub sentinels: Vec<DefenderSentinel>,
+
+	/// Notification channels the sentinels notify
+	#[serde(default)]
+	pub notification_channels: Vec<DefenderChannel>,
+}
+
+/// Sentinel of a Defender export
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DefenderSentinel {
+	/// Name of the sentinel
+	pub name: String,
+
+	/// Kind of the sentinel, `BLOCK` or `FORTA`
+	#[serde(default, rename = "type")]
+	pub sentinel_type: Option<String>,
+
+	/// Defender name of the network watched
+	pub network: String,
+
+	/// Addresses of the watched contracts
+	#[serde(default)]
+	pub addresses: Vec<String>,
+
+	/// ABI of the watched contracts, either inline or as a JSON string
+	#[serde(default)]
+	pub abi: Option<Value>,
+
+	/// Whether the sentinel is paused
+	#[serde(default)]
+	pub paused: bool,
+
+	/// Events matched by the sentinel
+	#[serde(default)]
+	pub event_conditions: Vec<DefenderEventCondition>,
+
+	/// Function calls matched by the sentinel
+	#[serde(default)]
+	pub function_conditions: Vec<DefenderFunctionCondition>,
+
+	/// Expression on the transaction the matches must satisfy
+	#[serde(default)]
+	pub tx_condition: Option<String>,
+
+	/// Number of matches within a window required to notify
+	#[serde(default)]
+	pub alert_threshold: Option<DefenderAlertThreshold>,
+
+	/// Minimum delay between two notifications, in milliseconds
+	#[serde(default)]
+	pub alert_timeout_ms: Option<u64>,
+
+	/// Identifiers of the notification channels notified
+	#[serde(default)]
+	pub notification_channels: Vec<String>,
+
+	/// Remaining fields of the sentinel
+	#[serde(flatten)]
+	pub other: BTreeMap<String, Value>,
+}
+
+/// Event condition of a sentinel
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DefenderEventCondition {
+	/// Signature of the event, with or without parameter names
+	pub event_signature: String,
+
+	/// Expression on the event arguments
+	#[serde(default)]
+	pub expression: Option<String>,
+}
+
+/// Function condition of a sentinel
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DefenderFunctionCondition {
+	/// Signature of the function, with or without parameter names
+	pub function_signature: String,
+
+	/// Expression on the function arguments
+	#[serde(default)]
+	pub expression: Option<String>,
+}
+
+/// Alert threshold of a sentinel
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DefenderAlertThreshold {
+	/// Number of matches required
+	pub amount: u64,
+
+	/// Window the matches are counted over, in seconds
+	pub window_seconds: u64,
+}
+
+/// Notification channel of a Defender export
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DefenderChannel {
+	/// Identifier the sentinels reference the channel by
+	#[serde(alias = "id")]
+	pub notification_id: String,
+
+	/// Kind of the channel, e.g. `slack` or `email`
+	#[serde(rename = "type")]
+	pub channel_type: String,
+
+	/// Name of the channel
+	pub name: String,
+
+	/// Settings of the channel, secrets are usually redacted
+	#[serde(default)]
+	pub config: Value,
+}
+
+/// Part of the export that was not translated
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct ImportReportEntry {
+	/// Name of the sentinel or notification channel
+	pub source: String,
+
+	/// Field of the export the entry is about
+	pub field: String,
+
+	/// What was left out, or what has to be filled in
+	pub message: String,
+}
+
+impl ImportReportEntry {
+	fn new(source: &str, field: &str, message: impl Into<String>) -> Self {
+		Self {
+			source: source.to_string(),
+			field: field.to_string(),
+			message: message.into(),
+		}
+	}
+}
+
+impl fmt::Display for ImportReportEntry {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "{} [{}]: {}", self.source, self.field, self.message)
+	}
+}
+
+/// Configurations translated from a Defender export
+#[derive(Debug, Clone, Default)]
+pub struct DefenderImport {
+	/// Imported monitors, keyed by the name of the file they are written to
+	pub monitors: BTreeMap<String, Monitor>,
+
+	/// Imported trigger stubs, keyed by their slug
+	pub triggers: BTreeMap<String, Trigger>,
+
+	/// Parts of the export that were not translated
+	pub report: Vec<ImportReportEntry>,
+}
+
+impl DefenderImport {
+	/// Writes the monitors, triggers and report into a configuration directory
+	///
+	/// Monitors are written to `monitors/<name>.json`, triggers to
+	/// `triggers/defender_import.json` and the report to `defender_import_report.json`.
+	/// Returns the paths of the written files.
+	pub fn write(&self, dir: &Path) -> Result<Vec<PathBuf>, ConfigError> {
+		let mut paths = Vec::new();
+		for (file_name, monitor) in &self.monitors {
+			let path = dir.join("monitors").join(format!("{}.json", file_name));
+			write_json(&path, monitor)?;
+			paths.push(path);
+		}
+		if !self.triggers.is_empty() {
+			let path = dir.join("triggers").join(TRIGGERS_FILE_NAME);
+			write_json(&path, &self.triggers)?;
+			paths.push(path);
+		}
+		let path = dir.join(REPORT_FILE_NAME);
+		write_json(&path, &self.report)?;
+		paths.push(path);
+		Ok(paths)
+	}
+}
+
+/// Parses a Defender Sentinel export
+pub fn parse_defender_export(content: &str) -> Result<DefenderExport, ConfigError> {
+	serde_json::from_str(content).map_err(|e| {
+		ConfigError::parse_error(
+			format!("Failed to parse Defender export: {}", e),
+			Some(e.into()),
+			None,
+		)
+	})
+}
+
+/// Parses a `<defender network>=<network slug>` network mapping
+pub fn parse_network_mapping(mapping: &str) -> Result<(String, String), String> {
+	match mapping.split_once('=') {
+		Some((defender, slug)) if !defender.trim().is_empty() && !slug.trim().is_empty() => {
+			Ok((defender.trim().to_string(), slug.trim().to_string()))
+		}
+		_ => Err(format!(
+			"invalid network mapping '{}', expected <defender network>=<network slug>",
+			mapping
+		)),
+	}
+}
+
+/// Translates a Defender export into paused monitors and trigger stubs
+///
+/// # Arguments
+/// * `export` - The parsed export
+/// * `networks` - Network slugs of the Defender network names
+///
+/// # Returns
+/// * `DefenderImport` - The translated configurations and the report of what was left out
+pub fn import_defender_export(
+	export: &DefenderExport,
+	networks: &HashMap<String, String>,
+) -> DefenderImport {
+	let mut import = DefenderImport::default();
+
+	let mut channel_slugs = HashMap::new();
+	for channel in &export.notification_channels {
+		let slug = unique_slug(
+			&format!("{}_{}", TRIGGER_SLUG_PREFIX, slugify(&channel.name)),
+			|slug| import.triggers.contains_key(slug),
+		);
+		if let Some(trigger) = import_channel(channel, &slug, &mut import.report) {
+			import.triggers.insert(slug.clone(), trigger);
+			channel_slugs.insert(channel.notification_id.clone(), slug);
+		}
+	}
+
+	for sentinel in &export.sentinels {
+		let Some(monitor) = import_sentinel(sentinel, networks, &channel_slugs, &mut import.report)
+		else {
+			continue;
+		};
+		if let Err(e) = monitor.validate() {
+			import.report.push(ImportReportEntry::new(
+				&sentinel.name,
+				"sentinel",
+				format!("not imported, the translated monitor is invalid: {}", e),
+			));
+			continue;
+		}
+		let file_name = unique_slug(&slugify(&sentinel.name), |name| {
+			import.monitors.contains_key(name)
+		});
+		import.monitors.insert(file_name, monitor);
+	}
+
+	import
+}
+
+/// Translates a sentinel into a paused monitor, or `None` if it cannot be imported at all
+fn import_sentinel(
+	sentinel: &DefenderSentinel,
+	networks: &HashMap<String, String>,
+	channel_slugs: &HashMap<String, String>,
+	report: &mut Vec<ImportReportEntry>,
+) -> Option<Monitor> {
+	let name = sentinel.name.as_str();
+	if let Some(kind) = sentinel
+		.sentinel_type
+		.as_deref()
+		.filter(|kind| !kind.eq_ignore_ascii_case("BLOCK"))
+	{
+		report.push(ImportReportEntry::new(
+			name,
+			"type",
+			format!("not imported, {} sentinels are not supported", kind),
+		));
+		return None;
+	}
+	let Some(network) = networks.get(&sentinel.network) else {
+		report.push(ImportReportEntry::new(
+			name,
+			"network",
+			format!(
+				"not imported, no network slug is mapped to '{}'",
+				sentinel.network
+			),
+		));
+		return None;
+	};
+
+	let contract_spec = sentinel.abi.as_ref().and_then(|abi| match import_abi(abi) {
+		Ok(spec) => Some(spec),
+		Err(e) => {
+			report.push(ImportReportEntry::new(
+				name,
+				"abi",
+				format!("not imported, {}", e),
+			));
+			None
+		}
+	});
+
+	let mut match_conditions = MatchConditions::default();
+	for condition in &sentinel.event_conditions {
+		let Some(expression) = import_expression(
+			name,
+			"eventConditions",
+			condition.expression.as_deref(),
+			report,
+		) else {
+			continue;
+		};
+		match_conditions.events.push(EventCondition {
+			signature: normalize_signature(&condition.event_signature),
+			expression,
+			match_any_emitter: false,
+		});
+	}
+	for condition in &sentinel.function_conditions {
+		let Some(expression) = import_expression(
+			name,
+			"functionConditions",
+			condition.expression.as_deref(),
+			report,
+		) else {
+			continue;
+		};
+		match_conditions.functions.push(FunctionCondition {
+			signature: normalize_signature(&condition.function_signature),
+			expression,
+		});
+	}
+	if let Some(tx_condition) = sentinel
+		.tx_condition
+		.as_deref()
+		.filter(|condition| !condition.trim().is_empty())
+	{
+		match import_transaction_status(tx_condition) {
+			Some(status) => match_conditions.transactions.push(TransactionCondition {
+				status,
+				expression: None,
+			}),
+			None => report.push(ImportReportEntry::new(
+				name,
+				"txCondition",
+				format!(
+					"'{}' not imported, only conditions on the transaction status are supported",
+					tx_condition
+				),
+			)),
+		}
+	}
+
+	if let Some(threshold) = &sentinel.alert_threshold {
+		report.push(ImportReportEntry::new(
+			name,
+			"alertThreshold",
+			format!(
+				"{} matches within {}s not imported, every match is notified",
+				threshold.amount, threshold.window_seconds
+			),
+		));
+	}
+	if let Some(timeout_ms) = sentinel.alert_timeout_ms {
+		report.push(ImportReportEntry::new(
+			name,
+			"alertTimeoutMs",
+			format!(
+				"{}ms between notifications not imported, see notification_budget to cap them",
+				timeout_ms
+			),
+		));
+	}
+	for (field, value) in &sentinel.other {
+		if METADATA_FIELDS.contains(&field.as_str()) || value.is_null() {
+			continue;
+		}
+		let message = match field.as_str() {
+			"autotaskCondition" => "not imported, autotasks can be ported to trigger_conditions",
+			"autotaskTrigger" => "not imported, autotasks can be ported to script triggers",
+			_ => "not imported, the field is not supported",
+		};
+		report.push(ImportReportEntry::new(name, field, message));
+	}
+
+	let mut triggers = Vec::new();
+	for id in &sentinel.notification_channels {
+		match channel_slugs.get(id) {
+			Some(slug) => triggers.push(slug.clone().into()),
+			None => report.push(ImportReportEntry::new(
+				name,
+				"notificationChannels",
+				format!("channel '{}' not imported, it is not referenced", id),
+			)),
+		}
+	}
+
+	Some(Monitor {
+		name: sentinel.name.clone(),
+		networks: vec![network.clone()],
+		paused: true,
+		addresses: sentinel
+			.addresses
+			.iter()
+			.map(|address| AddressWithSpec {
+				address: address.clone(),
+				contract_spec: contract_spec.clone(),
+				contract: None,
+				label: None,
+				annotations: HashMap::new(),
+			})
+			.collect(),
+		match_conditions,
+		triggers,
+		..Default::default()
+	})
+}
+
+/// Translates the expression of a condition, reporting it if it cannot be translated
+///
+/// Returns `None` when the condition has to be left out, since dropping only its expression
+/// would widen what it matches.
+fn import_expression(
+	sentinel: &str,
+	field: &str,
+	expression: Option<&str>,
+	report: &mut Vec<ImportReportEntry>,
+) -> Option<Option<String>> {
+	let Some(expression) = expression.filter(|expression| !expression.trim().is_empty()) else {
+		return Some(None);
+	};
+	match translate_expression(expression) {
+		Some(translated) => Some(Some(translated)),
+		None => {
+			report.push(ImportReportEntry::new(
+				sentinel,
+				field,
+				format!(
+					"condition with expression '{}' not imported, the expression is not supported",
+					expression
+				),
+			));
+			None
+		}
+	}
+}
+
+/// Translates a Defender condition expression into the expression syntax of monitors
+///
+/// The comparison operators, quoted strings and `and` / `or` are shared by both syntaxes.
+/// `&&` and `||` are rewritten, anything else that does not parse is not supported.
+pub fn translate_expression(expression: &str) -> Option<String> {
+	let translated = expression
+		.replace("&&", " AND ")
+		.replace("||", " OR ")
+		.split_whitespace()
+		.collect::<Vec<_>>()
+		.join(" ");
+	expression_variable_names(&translated).map(|_| translated)
+}
+
+/// Maps a transaction condition on the status only to a transaction status
+fn import_transaction_status(condition: &str) -> Option<TransactionStatus> {
+	let normalized: String = condition
+		.chars()
+		.filter(|c| !c.is_whitespace() && *c != '"' && *c != '\'')
+		.collect();
+	match normalized.as_str() {
+		"status==success" => Some(TransactionStatus::Success),
+		"status==failed" | "status==failure" => Some(TransactionStatus::Failure),
+		_ => None,
+	}
+}
+
+/// Parses an inline or stringified ABI into a contract spec
+fn import_abi(abi: &Value) -> Result<ContractSpec, String> {
+	let abi = match abi {
+		Value::String(content) => {
+			serde_json::from_str(content).map_err(|e| format!("the ABI is not JSON: {}", e))?
+		}
+		value => value.clone(),
+	};
+	serde_json::from_value::<alloy::json_abi::JsonAbi>(abi)
+		.map(|abi| ContractSpec::EVM(EVMContractSpec::from(abi)))
+		.map_err(|e| format!("the ABI is invalid: {}", e))
+}
+
+/// Strips the parameter names and `indexed` keywords from a signature
+///
+/// `Transfer(address indexed from, address to, uint256 value)` becomes
+/// `Transfer(address,address,uint256)`. Signatures with tuple parameters are only stripped of
+/// their whitespace.
+fn normalize_signature(signature: &str) -> String {
+	let signature = signature.trim();
+	let Some((name, params)) = signature
+		.split_once('(')
+		.and_then(|(name, rest)| Some((name, rest.strip_suffix(')')?)))
+	else {
+		return signature.to_string();
+	};
+	if params.contains('(') {
+		return signature.split_whitespace().collect();
+	}
+	let params: Vec<&str> = params
+		.split(',')
+		.filter_map(|param| param.split_whitespace().next())
+		.collect();
+	format!("{}({})", name.trim(), params.join(","))
+}
+
+/// Translates a notification channel into a trigger stub, reporting the secrets to fill in
+fn import_channel(
+	channel: &DefenderChannel,
+	slug: &str,
+	report: &mut Vec<ImportReportEntry>,
+) -> Option<Trigger> {
+	let name = channel.name.as_str();
+	let env_var = |suffix: &str| format!("{}_{}", slug.to_uppercase(), suffix);
+	let message = NotificationMessage {
+		title: "${monitor.name} triggered".to_string(),
+		body: "${monitor.name} matched transaction ${transaction.hash}".to_string(),
+		format: None,
+		thread_key: None,
+		attachments: None,
+	};
+	let retry_policy = RetryConfig::default();
+
+	let (trigger_type, config, secrets) = match channel.channel_type.to_lowercase().as_str() {
+		"slack" => (
+			TriggerType::Slack,
+			TriggerTypeConfig::Slack {
+				slack_url: SecretValue::Environment(env_var("SLACK_URL")),
+				message,
+				retry_policy,
+			},
+			vec![env_var("SLACK_URL")],
+		),
+		"discord" => (
+			TriggerType::Discord,
+			TriggerTypeConfig::Discord {
+				discord_url: SecretValue::Environment(env_var("DISCORD_URL")),
+				message,
+				retry_policy,
+			},
+			vec![env_var("DISCORD_URL")],
+		),
+		"webhook" => (
+			TriggerType::Webhook,
+			TriggerTypeConfig::Webhook {
+				url: SecretValue::Environment(env_var("WEBHOOK_URL")),
+				method: Some("POST".to_string()),
+				secret: None,
+				headers: None,
+				message,
+				retry_policy,
+				idempotency_header: None,
+				format: None,
+			},
+			vec![env_var("WEBHOOK_URL")],
+		),
+		"telegram" => {
+			let Some(chat_id) = config_string(&channel.config, "chatId") else {
+				report.push(ImportReportEntry::new(
+					name,
+					"config",
+					"not imported, the Telegram chat is missing",
+				));
+				return None;
+			};
+			(
+				TriggerType::Telegram,
+				TriggerTypeConfig::Telegram {
+					token: SecretValue::Environment(env_var("TELEGRAM_TOKEN")),
+					chat_id,
+					disable_web_preview: None,
+					message,
+					retry_policy,
+				},
+				vec![env_var("TELEGRAM_TOKEN")],
+			)
+		}
+		"email" => {
+			let mut recipients = Vec::new();
+			for email in channel.config["emails"]
+				.as_array()
+				.into_iter()
+				.flatten()
+				.filter_map(Value::as_str)
+			{
+				match EmailAddress::from_str(email) {
+					Ok(recipient) => recipients.push(recipient),
+					Err(_) => report.push(ImportReportEntry::new(
+						name,
+						"config",
+						format!(
+							"recipient '{}' not imported, it is not an email address",
+							email
+						),
+					)),
+				}
+			}
+			if recipients.is_empty() {
+				report.push(ImportReportEntry::new(
+					name,
+					"config",
+					"not imported, the channel has no recipients",
+				));
+				return None;
+			}
+			report.push(ImportReportEntry::new(
+				name,
+				"config",
+				format!(
+					"set the SMTP host and sender of trigger '{}', imported as {} and {}",
+					slug, EMAIL_HOST_PLACEHOLDER, EMAIL_SENDER_PLACEHOLDER
+				),
+			));
+			(
+				TriggerType::Email,
+				TriggerTypeConfig::Email {
+					host: EMAIL_HOST_PLACEHOLDER.to_string(),
+					port: None,
+					username: SecretValue::Environment(env_var("SMTP_USERNAME")),
+					password: SecretValue::Environment(env_var("SMTP_PASSWORD")),
+					message,
+					sender: EmailAddress::new_unchecked(EMAIL_SENDER_PLACEHOLDER),
+					recipients,
+					retry_policy,
+				},
+				vec![env_var("SMTP_USERNAME"), env_var("SMTP_PASSWORD")],
+			)
+		}
+		other => {
+			report.push(ImportReportEntry::new(
+				name,
+				"type",
+				format!("not imported, {} channels are not supported", other),
+			));
+			return None;
+		}
+	};
+
+	report.push(ImportReportEntry::new(
+		name,
+		"secrets",
+		format!(
+			"set {} before loading trigger '{}'",
+			secrets.join(", "),
+			slug
+		),
+	));
+	Some(Trigger {
+		name: channel.name.clone(),
+		trigger_type,
+		config,
+		group_dedup: false,
+		dedup_strategy: Default::default(),
+		rollup: false,
+		accept_origins: None,
+		strict_variables: None,
+		context: None,
+	})
+}
+
+/// Returns a string setting of a channel, accepting numbers for identifiers
+fn config_string(config: &Value, key: &str) -> Option<String> {
+	match &config[key] {
+		Value::String(value) if !value.is_empty() => Some(value.clone()),
+		Value::Number(value) => Some(value.to_string()),
+		_ => None,
+	}
+}
+
+/// Lowercases a name and replaces runs of other characters than letters and digits by `_`
+fn slugify(name: &str) -> String {
+	let slug = name
+		.to_lowercase()
+		.split(|c: char| !c.is_ascii_alphanumeric())
+		.filter(|part| !part.is_empty())
+		.collect::<Vec<_>>()
+		.join("_");
+	if slug.is_empty() {
+		"sentinel".to_string()
+	} else {
+		slug
+	}
+}
+
+/// Suffixes a slug with a counter until it is not taken
+fn unique_slug(slug: &str, taken: impl Fn(&str) -> bool) -> String {
+	let mut candidate = slug.to_string();
+	let mut counter = 1;
+	while taken(&candidate) {
+		counter += 1;
+		candidate = format!("{}_{}", slug, counter);
+	}
+	candidate
+}
+
+/// Serializes a value into a file, creating its directory if needed
+fn write_json<T: Serialize>(path: &Path, value: &T) -> Result<(), ConfigError> {
+	if let Some(dir) = path.parent() {
+		std::fs::create_dir_all(dir).map_err(|e| {
+			ConfigError::file_error(
+				format!("Failed to create directory {}", dir.display()),
+				Some(e.into()),
+				None,
+			)
+		})?;
+	}
+	let content = serde_json::to_string_pretty(value).map_err(|e| {
+		ConfigError::parse_error(
+			format!("Failed to serialize {}", path.display()),
+			Some(e.into()),
+			None,
+		)
+	})?;
+	std::fs::write(path, content + "\n").map_err(|e| {
+		ConfigError::file_error(
+			format!("Failed to write {}", path.display()),
+			Some(e.into()),
+			None,
+		)
+	})
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_translate_expression() {
+		assert_eq!(
+			translate_expression("value > 1000 && to == \"0xabc\"").as_deref(),
+			Some("value > 1000 AND to == \"0xabc\"")
+		);
+		assert_eq!(
+			translate_expression("value > 1000 or from == 0xabc").as_deref(),
+			Some("value > 1000 or from == 0xabc")
+		);
+		assert_eq!(translate_expression("not paused"), None);
+		assert_eq!(translate_expression("value > (1000 * 2)"), None);
+	}
+
+	#[test]
+	fn test_normalize_signature() {
+		assert_eq!(
+			normalize_signature("Transfer(address indexed from, address indexed to, uint256)"),
+			"Transfer(address,address,uint256)"
+		);
+		assert_eq!(normalize_signature("pause()"), "pause()");
+		assert_eq!(
+			normalize_signature("swap((address, uint256) order)"),
+			"swap((address,uint256)order)"
+		);
+	}
+
+	#[test]
+	fn test_import_transaction_status() {
+		assert_eq!(
+			import_transaction_status("status == \"success\""),
+			Some(TransactionStatus::Success)
+		);
+		assert_eq!(
+			import_transaction_status("status=='failed'"),
+			Some(TransactionStatus::Failure)
+		);
+		assert_eq!(import_transaction_status("gasPrice > 100"), None);
+	}
+
+	#[test]
+	fn test_parse_network_mapping() {
+		assert_eq!(
+			parse_network_mapping("mainnet=ethereum_mainnet"),
+			Ok(("mainnet".to_string(), "ethereum_mainnet".to_string()))
+		);
+		assert!(parse_network_mapping("mainnet").is_err());
+		assert!(parse_network_mapping("=ethereum_mainnet").is_err());
+	}
+
+	#[test]
+	fn test_slugs() {
+		assert_eq!(slugify("Large USDC Transfers!"), "large_usdc_transfers");
+		assert_eq!(slugify("***"), "sentinel");
+		let taken = ["ops", "ops_2"];
+		assert_eq!(unique_slug("ops", |slug| taken.contains(&slug)), "ops_3");
+	}
+
+	#[test]
+	fn test_unmapped_network_is_reported() {
+		let export = parse_defender_export(
+			r#"{"sentinels": [{"name": "Watcher", "network": "base", "addresses": []}]}"#,
+		)
+		.unwrap();
+		let import = import_defender_export(&export, &HashMap::new());
+		assert!(import.monitors.is_empty());
+		assert_eq!(import.report.len(), 1);
+		assert_eq!(import.report[0].field, "network");
+	}
+}
@@ -105,7 +105,7 @@ pub async fn execute_monitor<
 					None,
 				)
 			})?;
-		vec![network.clone()]
+		vec![network.as_ref().clone()]
 	} else {
 		tracing::debug!("Finding all active networks for monitor");
 		config
@@ -124,6 +124,7 @@ pub async fn execute_monitor<
 		"Networks found for monitor"
 	);
 
+	let shared_monitor = [Arc::new(monitor.clone())];
 	let mut all_matches = Vec::new();
 	for network in networks_for_monitor {
 		tracing::debug!(
@@ -190,7 +191,7 @@ pub async fn execute_monitor<
 						&*client,
 						&network,
 						block,
-						std::slice::from_ref(&monitor),
+						&shared_monitor,
 						Some(&contract_specs),
 					)
 					.await
@@ -245,7 +246,7 @@ pub async fn execute_monitor<
 						&*client,
 						&network,
 						block,
-						std::slice::from_ref(&monitor),
+						&shared_monitor,
 						Some(&contract_specs),
 					)
 					.await
@@ -300,7 +301,7 @@ pub async fn execute_monitor<
 						&*client,
 						&network,
 						block,
-						std::slice::from_ref(&monitor),
+						&shared_monitor,
 						Some(&contract_specs),
 					)
 					.await
@@ -2,9 +2,15 @@
 //!
 //! This module provides functionality for executing monitors against a specific block
 //!
+//! - comparison: Comparison of two monitor versions over a range of blocks
+//! - defender: Import of monitors and triggers from OpenZeppelin Defender Sentinel exports
 //! - execution: Monitor execution logic against a specific block
+//! - preview: Rendering of the notifications of a match without sending them
 //! - error: Error types for monitor execution
 
+pub mod comparison;
+pub mod defender;
 mod error;
 pub use error::MonitorExecutionError;
 pub mod execution;
+pub mod preview;
@@ -0,0 +1,231 @@
+//! Notification preview module
+//!
+//! This module renders the notifications a monitor's triggers would send for a match, either
+//! recorded or synthetic, without sending them.
+use crate::{
+	models::{
+		EVMBaseTransaction, EVMMonitorMatch, EVMTransaction, MatchOrigin, Monitor, MonitorMatch,
+	},
+	repositories::TriggerRepositoryTrait,
+	services::{
+		filter::match_variables,
+		notification::NotificationPreview,
+		trigger::{TriggerExecutionService, TriggerExecutionServiceTrait},
+	},
+	utils::monitor::MonitorExecutionError,
+};
+use alloy::{
+	primitives::{Address, B256, U256, U64},
+	rpc::types::Index,
+};
+use std::{path::Path, str::FromStr};
+
+/// Name of the monitor triggers are previewed for when no monitor is given
+const SAMPLE_MONITOR: &str = "preview";
+
+/// Network slug of sample matches of monitors without networks
+const SAMPLE_NETWORK: &str = "preview";
+
+/// Value of the sample transaction, 1 ETH
+const SAMPLE_VALUE_WEI: u64 = 1_000_000_000_000_000_000;
+
+/// Builds a monitor referencing the given triggers, to preview triggers on their own
+///
+/// # Arguments
+/// * `trigger_slugs` - Triggers referenced by the monitor
+///
+/// # Returns
+/// * `Monitor` - The sample monitor
+pub fn sample_monitor(trigger_slugs: Vec<String>) -> Monitor {
+	Monitor {
+		name: SAMPLE_MONITOR.to_string(),
+		triggers: trigger_slugs.into_iter().map(Into::into).collect(),
+		..Default::default()
+	}
+}
+
+/// Builds a synthetic EVM match of the monitor, standing in for a recorded match
+///
+/// The match is a transfer of 1 ETH to the first address of the monitor, matched on every
+/// condition of the monitor. Arguments of the conditions are not decoded, so templates
+/// referencing them render missing markers.
+///
+/// # Arguments
+/// * `monitor` - The monitor the match is produced by
+///
+/// # Returns
+/// * `MonitorMatch` - The sample match
+pub fn sample_match(monitor: &Monitor) -> MonitorMatch {
+	let to = monitor
+		.addresses
+		.first()
+		.and_then(|address| Address::from_str(&address.address).ok())
+		.unwrap_or(Address::repeat_byte(0x22));
+	let transaction = EVMBaseTransaction {
+		hash: B256::repeat_byte(0xab),
+		block_number: Some(U64::from(1)),
+		transaction_index: Some(Index(0)),
+		from: Some(Address::repeat_byte(0x11)),
+		to: Some(to),
+		value: U256::from(SAMPLE_VALUE_WEI),
+		..Default::default()
+	};
+	MonitorMatch::EVM(Box::new(EVMMonitorMatch {
+		monitor: monitor.clone(),
+		transaction: EVMTransaction(transaction),
+		receipt: None,
+		logs: None,
+		network_slug: monitor
+			.networks
+			.first()
+			.cloned()
+			.unwrap_or_else(|| SAMPLE_NETWORK.to_string()),
+		matched_on: monitor.match_conditions.clone(),
+		matched_on_args: None,
+		sender_activity: None,
+		baseline: None,
+		network_metrics: None,
+		timing: None,
+		origin: MatchOrigin::Live,
+	}))
+}
+
+/// Reads a recorded match, i.e. a match serialized as JSON or a list of matches such as the
+/// results of `--monitor-path`, of which the first one is used
+///
+/// # Arguments
+/// * `path` - Path to the recorded match
+///
+/// # Returns
+/// * `Result<MonitorMatch, MonitorExecutionError>` - The match or error
+pub fn load_match(path: &Path) -> Result<MonitorMatch, MonitorExecutionError> {
+	let content = std::fs::read_to_string(path).map_err(|e| {
+		MonitorExecutionError::not_found(
+			format!("Failed to read match {}: {}", path.display(), e),
+			Some(e.into()),
+			None,
+		)
+	})?;
+	let value: serde_json::Value = serde_json::from_str(&content).map_err(|e| {
+		MonitorExecutionError::execution_error(
+			format!("Failed to parse match {}: {}", path.display(), e),
+			Some(e.into()),
+			None,
+		)
+	})?;
+	let value = match value {
+		serde_json::Value::Array(matches) => matches.into_iter().next().ok_or_else(|| {
+			MonitorExecutionError::not_found(
+				format!("No match recorded in {}", path.display()),
+				None,
+				None,
+			)
+		})?,
+		value => value,
+	};
+	serde_json::from_value(value).map_err(|e| {
+		MonitorExecutionError::execution_error(
+			format!("Invalid match in {}: {}", path.display(), e),
+			Some(e.into()),
+			None,
+		)
+	})
+}
+
+/// Renders the notifications the triggers of a match's monitor would send for it
+///
+/// The match goes through the same variables and trigger overrides as when dispatched, but
+/// enrichments, notification budgets and match history are not applied.
+///
+/// # Arguments
+/// * `monitor_match` - The match to preview the notifications of
+/// * `trigger_slugs` - Triggers to preview, the monitor's triggers when empty
+/// * `trigger_execution_service` - The trigger execution service to render with
+///
+/// # Returns
+/// * `Result<Vec<NotificationPreview>, MonitorExecutionError>` - The previews or error
+pub async fn preview_notifications<T: TriggerRepositoryTrait + Send + Sync>(
+	monitor_match: &MonitorMatch,
+	trigger_slugs: &[String],
+	trigger_execution_service: &TriggerExecutionService<T>,
+) -> Result<Vec<NotificationPreview>, MonitorExecutionError> {
+	let trigger_slugs = if trigger_slugs.is_empty() {
+		monitor_match.monitor().trigger_names()
+	} else {
+		trigger_slugs.to_vec()
+	};
+	trigger_execution_service
+		.preview(
+			&trigger_slugs,
+			match_variables(
+				monitor_match,
+				&[],
+				trigger_execution_service.price_oracles(),
+			),
+			monitor_match,
+		)
+		.await
+		.map_err(|e| MonitorExecutionError::execution_error(e.to_string(), Some(e.into()), None))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::utils::tests::builders::evm::monitor::MonitorBuilder;
+
+	#[test]
+	fn test_sample_match_uses_monitor() {
+		let monitor = MonitorBuilder::new()
+			.name("large_transfers")
+			.networks(vec!["ethereum_mainnet".to_string()])
+			.address("0x0000000000000000000000000000000000001234")
+			.event("Transfer(address,address,uint256)", None)
+			.build();
+
+		let MonitorMatch::EVM(sample) = sample_match(&monitor) else {
+			panic!("Expected an EVM match");
+		};
+		assert_eq!(sample.monitor.name, "large_transfers");
+		assert_eq!(sample.network_slug, "ethereum_mainnet");
+		assert_eq!(
+			sample.transaction.to(),
+			Some(&Address::from_str("0x0000000000000000000000000000000000001234").unwrap())
+		);
+		assert_eq!(sample.matched_on.events.len(), 1);
+	}
+
+	#[test]
+	fn test_sample_monitor_references_triggers() {
+		let monitor = sample_monitor(vec!["slack_alerts".to_string()]);
+		assert_eq!(monitor.trigger_names(), vec!["slack_alerts".to_string()]);
+		assert!(monitor.networks.is_empty());
+		let MonitorMatch::EVM(sample) = sample_match(&monitor) else {
+			panic!("Expected an EVM match");
+		};
+		assert_eq!(sample.network_slug, SAMPLE_NETWORK);
+	}
+
+	#[test]
+	fn test_load_match_reads_recorded_matches() {
+		let dir = tempfile::tempdir().unwrap();
+		let sample = sample_match(&sample_monitor(vec![]));
+
+		let single = dir.path().join("match.json");
+		std::fs::write(&single, serde_json::to_string(&sample).unwrap()).unwrap();
+		assert_eq!(load_match(&single).unwrap().monitor().name, SAMPLE_MONITOR);
+
+		let recorded = dir.path().join("matches.json");
+		std::fs::write(&recorded, serde_json::to_string(&vec![&sample]).unwrap()).unwrap();
+		assert_eq!(
+			load_match(&recorded).unwrap().monitor().name,
+			SAMPLE_MONITOR
+		);
+
+		let empty = dir.path().join("empty.json");
+		std::fs::write(&empty, "[]").unwrap();
+		assert!(matches!(
+			load_match(&empty),
+			Err(MonitorExecutionError::NotFound(_))
+		));
+	}
+}
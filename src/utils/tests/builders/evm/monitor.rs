@@ -2,10 +2,14 @@
 //!
 //! - `MonitorBuilder`: Builder for creating test Monitor instances
 
+use std::collections::HashMap;
+
 use crate::models::{
-	AddressWithSpec, ChainConfiguration, ContractSpec, EVMMonitorConfig, EventCondition,
-	FunctionCondition, MatchConditions, Monitor, ScriptLanguage, TransactionCondition,
-	TransactionStatus, TriggerConditions,
+	AddressWithSpec, BaselineCondition, ChainConfiguration, ContractSpec, DecodeFailureAlert,
+	EVMMonitorConfig, Enrichment, EventCondition, FunctionCondition, MatchConditions,
+	MatchHistoryConfig, Monitor, MonitorOwner, NetworkMetricsCondition, NetworkViewsConfig,
+	NotificationBudget, PresetReference, ScriptLanguage, SenderActivityCondition,
+	TransactionCondition, TransactionStatus, TriggerConditions, TriggerReference,
 };
 
 /// Builder for creating test Monitor instances
@@ -16,8 +20,18 @@ pub struct MonitorBuilder {
 	addresses: Vec<AddressWithSpec>,
 	match_conditions: MatchConditions,
 	trigger_conditions: Vec<TriggerConditions>,
-	triggers: Vec<String>,
+	triggers: Vec<TriggerReference>,
 	chain_configurations: Vec<ChainConfiguration>,
+	dedup_group: Option<String>,
+	rollup_group: Option<String>,
+	notification_budget: Option<NotificationBudget>,
+	presets: Vec<PresetReference>,
+	enrichments: Vec<Enrichment>,
+	owner: Option<MonitorOwner>,
+	network_views: Option<NetworkViewsConfig>,
+	history: Option<MatchHistoryConfig>,
+	alert_on_decode_failure: Option<DecodeFailureAlert>,
+	tags: Vec<String>,
 }
 
 impl Default for MonitorBuilder {
@@ -29,11 +43,17 @@ impl Default for MonitorBuilder {
 			addresses: vec![AddressWithSpec {
 				address: "0x0000000000000000000000000000000000000000".to_string(),
 				contract_spec: None,
+				contract: None,
+				label: None,
+				annotations: HashMap::new(),
 			}],
 			match_conditions: MatchConditions {
 				functions: vec![],
 				events: vec![],
 				transactions: vec![],
+				sender_activity: None,
+				baseline: None,
+				network_metrics: None,
 			},
 			trigger_conditions: vec![],
 			triggers: vec![],
@@ -41,6 +61,16 @@ impl Default for MonitorBuilder {
 				evm: Some(EVMMonitorConfig::default()),
 				..Default::default()
 			}],
+			dedup_group: None,
+			rollup_group: None,
+			notification_budget: None,
+			presets: vec![],
+			enrichments: vec![],
+			owner: None,
+			network_views: None,
+			history: None,
+			alert_on_decode_failure: None,
+			tags: vec![],
 		}
 	}
 }
@@ -69,6 +99,9 @@ impl MonitorBuilder {
 		self.addresses = vec![AddressWithSpec {
 			address: address.to_string(),
 			contract_spec: None,
+			contract: None,
+			label: None,
+			annotations: HashMap::new(),
 		}];
 		self
 	}
@@ -79,6 +112,9 @@ impl MonitorBuilder {
 			.map(|addr| AddressWithSpec {
 				address: addr,
 				contract_spec: None,
+				contract: None,
+				label: None,
+				annotations: HashMap::new(),
 			})
 			.collect();
 		self
@@ -88,6 +124,28 @@ impl MonitorBuilder {
 		self.addresses.push(AddressWithSpec {
 			address: address.to_string(),
 			contract_spec: None,
+			contract: None,
+			label: None,
+			annotations: HashMap::new(),
+		});
+		self
+	}
+
+	pub fn add_labeled_address(
+		mut self,
+		address: &str,
+		label: Option<&str>,
+		annotations: Vec<(&str, &str)>,
+	) -> Self {
+		self.addresses.push(AddressWithSpec {
+			address: address.to_string(),
+			contract_spec: None,
+			contract: None,
+			label: label.map(str::to_string),
+			annotations: annotations
+				.into_iter()
+				.map(|(key, value)| (key.to_string(), value.to_string()))
+				.collect(),
 		});
 		self
 	}
@@ -96,6 +154,9 @@ impl MonitorBuilder {
 		self.addresses = vec![AddressWithSpec {
 			address: address.to_string(),
 			contract_spec: spec,
+			contract: None,
+			label: None,
+			annotations: HashMap::new(),
 		}];
 		self
 	}
@@ -106,6 +167,9 @@ impl MonitorBuilder {
 			.map(|(addr, spec)| AddressWithSpec {
 				address: addr.to_string(),
 				contract_spec: spec,
+				contract: None,
+				label: None,
+				annotations: HashMap::new(),
 			})
 			.collect();
 		self
@@ -123,6 +187,7 @@ impl MonitorBuilder {
 		self.match_conditions.events.push(EventCondition {
 			signature: signature.to_string(),
 			expression,
+			match_any_emitter: false,
 		});
 		self
 	}
@@ -134,6 +199,31 @@ impl MonitorBuilder {
 		self
 	}
 
+	pub fn sender_activity(mut self, condition: SenderActivityCondition) -> Self {
+		self.match_conditions.sender_activity = Some(condition);
+		self
+	}
+
+	pub fn baseline(mut self, condition: BaselineCondition) -> Self {
+		self.match_conditions.baseline = Some(condition);
+		self
+	}
+
+	pub fn alert_on_decode_failure(mut self, alert: DecodeFailureAlert) -> Self {
+		self.alert_on_decode_failure = Some(alert);
+		self
+	}
+
+	pub fn tags(mut self, tags: Vec<&str>) -> Self {
+		self.tags = tags.into_iter().map(String::from).collect();
+		self
+	}
+
+	pub fn network_metrics(mut self, condition: NetworkMetricsCondition) -> Self {
+		self.match_conditions.network_metrics = Some(condition);
+		self
+	}
+
 	pub fn trigger_condition(
 		mut self,
 		script_path: &str,
@@ -151,7 +241,12 @@ impl MonitorBuilder {
 	}
 
 	pub fn triggers(mut self, triggers: Vec<String>) -> Self {
-		self.triggers = triggers;
+		self.triggers = triggers.into_iter().map(TriggerReference::from).collect();
+		self
+	}
+
+	pub fn trigger_reference(mut self, reference: TriggerReference) -> Self {
+		self.triggers.push(reference);
 		self
 	}
 
@@ -160,6 +255,46 @@ impl MonitorBuilder {
 		self
 	}
 
+	pub fn dedup_group(mut self, group: &str) -> Self {
+		self.dedup_group = Some(group.to_string());
+		self
+	}
+
+	pub fn rollup_group(mut self, group: &str) -> Self {
+		self.rollup_group = Some(group.to_string());
+		self
+	}
+
+	pub fn notification_budget(mut self, budget: NotificationBudget) -> Self {
+		self.notification_budget = Some(budget);
+		self
+	}
+
+	pub fn presets(mut self, presets: Vec<PresetReference>) -> Self {
+		self.presets = presets;
+		self
+	}
+
+	pub fn enrichment(mut self, enrichment: Enrichment) -> Self {
+		self.enrichments.push(enrichment);
+		self
+	}
+
+	pub fn owner(mut self, owner: MonitorOwner) -> Self {
+		self.owner = Some(owner);
+		self
+	}
+
+	pub fn network_views(mut self, network_views: NetworkViewsConfig) -> Self {
+		self.network_views = Some(network_views);
+		self
+	}
+
+	pub fn history(mut self, history: MatchHistoryConfig) -> Self {
+		self.history = Some(history);
+		self
+	}
+
 	pub fn build(self) -> Monitor {
 		Monitor {
 			name: self.name,
@@ -170,6 +305,16 @@ impl MonitorBuilder {
 			trigger_conditions: self.trigger_conditions,
 			triggers: self.triggers,
 			chain_configurations: self.chain_configurations,
+			dedup_group: self.dedup_group,
+			rollup_group: self.rollup_group,
+			notification_budget: self.notification_budget,
+			presets: self.presets,
+			enrichments: self.enrichments,
+			owner: self.owner,
+			network_views: self.network_views,
+			history: self.history,
+			alert_on_decode_failure: self.alert_on_decode_failure,
+			tags: self.tags,
 		}
 	}
 }
@@ -320,6 +465,9 @@ mod tests {
 				}],
 				events: vec![],
 				transactions: vec![],
+				sender_activity: None,
+				baseline: None,
+				network_metrics: None,
 			})
 			.build();
 		assert_eq!(monitor.match_conditions.functions.len(), 1);
@@ -0,0 +1,122 @@
+use crate::{
+	models::{
+		EVMMatchArguments, EVMMonitorMatch, EVMReceiptLog, EVMSenderActivity, EVMTransaction,
+		EVMTransactionReceipt, MatchConditions, MatchOrigin, Monitor, MonitorMatch,
+	},
+	services::trigger::DedupedMatch,
+	utils::tests::builders::evm::{monitor::MonitorBuilder, transaction::TransactionBuilder},
+};
+
+/// A builder for creating test EVM monitor matches with default values.
+#[derive(Debug, Default)]
+pub struct MonitorMatchBuilder {
+	monitor: Option<Monitor>,
+	transaction: Option<EVMTransaction>,
+	receipt: Option<EVMTransactionReceipt>,
+	logs: Option<Vec<EVMReceiptLog>>,
+	network_slug: Option<String>,
+	matched_on: MatchConditions,
+	matched_on_args: Option<EVMMatchArguments>,
+	sender_activity: Option<EVMSenderActivity>,
+	origin: MatchOrigin,
+}
+
+impl MonitorMatchBuilder {
+	/// Creates a new MonitorMatchBuilder instance.
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Sets the monitor that matched. Default is a monitor from `MonitorBuilder`.
+	pub fn monitor(mut self, monitor: Monitor) -> Self {
+		self.monitor = Some(monitor);
+		self
+	}
+
+	/// Sets the transaction that matched. Default is a transaction from `TransactionBuilder`.
+	pub fn transaction(mut self, transaction: EVMTransaction) -> Self {
+		self.transaction = Some(transaction);
+		self
+	}
+
+	/// Sets the receipt of the transaction.
+	pub fn receipt(mut self, receipt: EVMTransactionReceipt) -> Self {
+		self.receipt = Some(receipt);
+		self
+	}
+
+	/// Sets the logs of the transaction.
+	pub fn logs(mut self, logs: Vec<EVMReceiptLog>) -> Self {
+		self.logs = Some(logs);
+		self
+	}
+
+	/// Sets the network the match was found on. Default is `ethereum_mainnet`.
+	pub fn network_slug(mut self, network_slug: &str) -> Self {
+		self.network_slug = Some(network_slug.to_string());
+		self
+	}
+
+	/// Sets the conditions that matched.
+	pub fn matched_on(mut self, matched_on: MatchConditions) -> Self {
+		self.matched_on = matched_on;
+		self
+	}
+
+	/// Sets the decoded arguments of the conditions that matched.
+	pub fn matched_on_args(mut self, matched_on_args: EVMMatchArguments) -> Self {
+		self.matched_on_args = Some(matched_on_args);
+		self
+	}
+
+	/// Sets the nonce activity of the sender of the transaction.
+	pub fn sender_activity(mut self, sender_activity: EVMSenderActivity) -> Self {
+		self.sender_activity = Some(sender_activity);
+		self
+	}
+
+	/// Sets the pipeline the match was produced by. Default is the live pipeline.
+	pub fn origin(mut self, origin: MatchOrigin) -> Self {
+		self.origin = origin;
+		self
+	}
+
+	/// Builds the EVM monitor match.
+	pub fn build_evm(self) -> EVMMonitorMatch {
+		EVMMonitorMatch {
+			monitor: self
+				.monitor
+				.unwrap_or_else(|| MonitorBuilder::new().build()),
+			transaction: self
+				.transaction
+				.unwrap_or_else(|| TransactionBuilder::new().build()),
+			receipt: self.receipt,
+			logs: self.logs,
+			network_slug: self
+				.network_slug
+				.unwrap_or_else(|| "ethereum_mainnet".to_string()),
+			matched_on: self.matched_on,
+			matched_on_args: self.matched_on_args,
+			sender_activity: self.sender_activity,
+			baseline: None,
+			network_metrics: None,
+			timing: None,
+			origin: self.origin,
+		}
+	}
+
+	/// Builds the monitor match.
+	pub fn build(self) -> MonitorMatch {
+		MonitorMatch::EVM(Box::new(self.build_evm()))
+	}
+
+	/// Builds the monitor match as left by deduplication, with no other monitor folded into
+	/// it and no other match rolled up.
+	pub fn build_deduped(self) -> DedupedMatch {
+		DedupedMatch {
+			monitor_match: self.build(),
+			also_matched: vec![],
+			rolled_up: vec![],
+		}
+	}
+}
@@ -2,7 +2,10 @@
 //!
 //! - `NetworkBuilder`: Builder for creating test Network instances
 
-use crate::models::{BlockChainType, Network, RpcUrl, SecretString, SecretValue};
+use crate::models::{
+	BlockChainType, BlockFetchConfig, MaintenanceWindow, Network, PriceOracleConfig, RpcUrl,
+	SecretString, SecretValue,
+};
 
 /// Builder for creating test Network instances
 pub struct NetworkBuilder {
@@ -17,6 +20,11 @@ pub struct NetworkBuilder {
 	confirmation_blocks: u64,
 	cron_schedule: String,
 	max_past_blocks: Option<u64>,
+	maintenance_windows: Option<Vec<MaintenanceWindow>>,
+	block_fetch: Option<BlockFetchConfig>,
+	monitor_concurrency: Option<usize>,
+	group: Option<String>,
+	price_oracle: Option<PriceOracleConfig>,
 }
 
 impl Default for NetworkBuilder {
@@ -33,6 +41,11 @@ impl Default for NetworkBuilder {
 			confirmation_blocks: 1,
 			cron_schedule: "0 */5 * * * *".to_string(),
 			max_past_blocks: Some(10),
+			maintenance_windows: None,
+			block_fetch: None,
+			monitor_concurrency: None,
+			group: None,
+			price_oracle: None,
 		}
 	}
 }
@@ -148,6 +161,60 @@ impl NetworkBuilder {
 		self
 	}
 
+	pub fn maintenance_window(
+		mut self,
+		cron_schedule: &str,
+		duration_ms: u64,
+		utc_offset: Option<&str>,
+	) -> Self {
+		self.maintenance_windows
+			.get_or_insert_with(Vec::new)
+			.push(MaintenanceWindow {
+				cron_schedule: cron_schedule.to_string(),
+				duration_ms,
+				utc_offset: utc_offset.map(|s| s.to_string()),
+				timezone: None,
+			});
+		self
+	}
+
+	pub fn maintenance_window_in_timezone(
+		mut self,
+		cron_schedule: &str,
+		duration_ms: u64,
+		timezone: &str,
+	) -> Self {
+		self.maintenance_windows
+			.get_or_insert_with(Vec::new)
+			.push(MaintenanceWindow {
+				cron_schedule: cron_schedule.to_string(),
+				duration_ms,
+				utc_offset: None,
+				timezone: Some(timezone.to_string()),
+			});
+		self
+	}
+
+	pub fn block_fetch(mut self, block_fetch: BlockFetchConfig) -> Self {
+		self.block_fetch = Some(block_fetch);
+		self
+	}
+
+	pub fn monitor_concurrency(mut self, monitor_concurrency: usize) -> Self {
+		self.monitor_concurrency = Some(monitor_concurrency);
+		self
+	}
+
+	pub fn group(mut self, group: &str) -> Self {
+		self.group = Some(group.to_string());
+		self
+	}
+
+	pub fn price_oracle(mut self, price_oracle: PriceOracleConfig) -> Self {
+		self.price_oracle = Some(price_oracle);
+		self
+	}
+
 	pub fn build(self) -> Network {
 		Network {
 			name: self.name,
@@ -161,6 +228,11 @@ impl NetworkBuilder {
 			confirmation_blocks: self.confirmation_blocks,
 			cron_schedule: self.cron_schedule,
 			max_past_blocks: self.max_past_blocks,
+			maintenance_windows: self.maintenance_windows,
+			block_fetch: self.block_fetch,
+			monitor_concurrency: self.monitor_concurrency,
+			group: self.group,
+			price_oracle: self.price_oracle,
 		}
 	}
 }
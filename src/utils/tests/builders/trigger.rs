@@ -4,8 +4,9 @@
 
 use crate::{
 	models::{
-		NotificationMessage, ScriptLanguage, SecretString, SecretValue, Trigger, TriggerType,
-		TriggerTypeConfig,
+		DedupStrategy, MessageFormat, NotificationMessage, OriginKind, ScriptLanguage,
+		SecretString, SecretValue, Trigger, TriggerContext, TriggerType, TriggerTypeConfig,
+		WebhookFormat,
 	},
 	utils::RetryConfig,
 };
@@ -16,6 +17,12 @@ pub struct TriggerBuilder {
 	name: String,
 	trigger_type: TriggerType,
 	config: TriggerTypeConfig,
+	group_dedup: bool,
+	dedup_strategy: DedupStrategy,
+	rollup: bool,
+	accept_origins: Option<Vec<OriginKind>>,
+	strict_variables: Option<bool>,
+	context: Option<TriggerContext>,
 }
 
 impl Default for TriggerBuilder {
@@ -33,9 +40,20 @@ impl Default for TriggerBuilder {
 				message: NotificationMessage {
 					title: "Alert".to_string(),
 					body: "Test message".to_string(),
+					format: None,
+					thread_key: None,
+					attachments: None,
 				},
 				retry_policy: RetryConfig::default(),
+				idempotency_header: None,
+				format: None,
 			},
+			group_dedup: false,
+			dedup_strategy: DedupStrategy::First,
+			rollup: false,
+			accept_origins: None,
+			strict_variables: None,
+			context: None,
 		}
 	}
 }
@@ -65,8 +83,13 @@ impl TriggerBuilder {
 			message: NotificationMessage {
 				title: "Alert".to_string(),
 				body: "Test message".to_string(),
+				format: None,
+				thread_key: None,
+				attachments: None,
 			},
 			retry_policy: RetryConfig::default(),
+			idempotency_header: None,
+			format: None,
 		};
 		self
 	}
@@ -78,6 +101,9 @@ impl TriggerBuilder {
 			message: NotificationMessage {
 				title: "Alert".to_string(),
 				body: "Test message".to_string(),
+				format: None,
+				thread_key: None,
+				attachments: None,
 			},
 			retry_policy: RetryConfig::default(),
 		};
@@ -91,6 +117,9 @@ impl TriggerBuilder {
 			message: NotificationMessage {
 				title: "Alert".to_string(),
 				body: "Test message".to_string(),
+				format: None,
+				thread_key: None,
+				attachments: None,
 			},
 			retry_policy: RetryConfig::default(),
 		};
@@ -106,6 +135,9 @@ impl TriggerBuilder {
 			message: NotificationMessage {
 				title: "Test title".to_string(),
 				body: "Test message".to_string(),
+				format: None,
+				thread_key: None,
+				attachments: None,
 			},
 			retry_policy: RetryConfig::default(),
 		};
@@ -159,6 +191,27 @@ impl TriggerBuilder {
 		self
 	}
 
+	pub fn message_format(mut self, format: MessageFormat) -> Self {
+		if let Some(message) = self.config.message_mut() {
+			message.format = Some(format);
+		}
+		self
+	}
+
+	pub fn thread_key(mut self, thread_key: &str) -> Self {
+		if let Some(message) = self.config.message_mut() {
+			message.thread_key = Some(thread_key.to_string());
+		}
+		self
+	}
+
+	pub fn attachments(mut self, attachments: Vec<String>) -> Self {
+		if let Some(message) = self.config.message_mut() {
+			message.attachments = Some(attachments);
+		}
+		self
+	}
+
 	pub fn trigger_type(mut self, trigger_type: TriggerType) -> Self {
 		self.trigger_type = trigger_type;
 		self
@@ -181,6 +234,9 @@ impl TriggerBuilder {
 			message: NotificationMessage {
 				title: "Test Subject".to_string(),
 				body: "Test Body".to_string(),
+				format: None,
+				thread_key: None,
+				attachments: None,
 			},
 			sender: EmailAddress::new_unchecked(sender),
 			recipients: recipients
@@ -241,6 +297,24 @@ impl TriggerBuilder {
 		self
 	}
 
+	pub fn webhook_idempotency_header(mut self, header: &str) -> Self {
+		if let TriggerTypeConfig::Webhook {
+			idempotency_header: h,
+			..
+		} = &mut self.config
+		{
+			*h = Some(header.to_string());
+		}
+		self
+	}
+
+	pub fn webhook_format(mut self, format: WebhookFormat) -> Self {
+		if let TriggerTypeConfig::Webhook { format: f, .. } = &mut self.config {
+			*f = Some(format);
+		}
+		self
+	}
+
 	pub fn url(mut self, url: SecretValue) -> Self {
 		self.config = match self.config {
 			TriggerTypeConfig::Webhook {
@@ -250,6 +324,8 @@ impl TriggerBuilder {
 				secret,
 				message,
 				retry_policy,
+				idempotency_header,
+				format,
 			} => TriggerTypeConfig::Webhook {
 				url,
 				method,
@@ -257,6 +333,8 @@ impl TriggerBuilder {
 				secret,
 				message,
 				retry_policy,
+				idempotency_header,
+				format,
 			},
 			TriggerTypeConfig::Discord {
 				discord_url: _,
@@ -281,11 +359,47 @@ impl TriggerBuilder {
 		self
 	}
 
+	pub fn group_dedup(mut self, enabled: bool) -> Self {
+		self.group_dedup = enabled;
+		self
+	}
+
+	pub fn dedup_strategy(mut self, strategy: DedupStrategy) -> Self {
+		self.dedup_strategy = strategy;
+		self
+	}
+
+	pub fn rollup(mut self, enabled: bool) -> Self {
+		self.rollup = enabled;
+		self
+	}
+
+	pub fn accept_origins(mut self, origins: Vec<OriginKind>) -> Self {
+		self.accept_origins = Some(origins);
+		self
+	}
+
+	pub fn strict_variables(mut self, strict: bool) -> Self {
+		self.strict_variables = Some(strict);
+		self
+	}
+
+	pub fn context(mut self, context: TriggerContext) -> Self {
+		self.context = Some(context);
+		self
+	}
+
 	pub fn build(self) -> Trigger {
 		Trigger {
 			name: self.name,
 			trigger_type: self.trigger_type,
 			config: self.config,
+			group_dedup: self.group_dedup,
+			dedup_strategy: self.dedup_strategy,
+			rollup: self.rollup,
+			accept_origins: self.accept_origins,
+			strict_variables: self.strict_variables,
+			context: self.context,
 		}
 	}
 }
@@ -324,8 +438,13 @@ mod tests {
 				message: NotificationMessage {
 					title: "Alert".to_string(),
 					body: "Test message".to_string(),
+					format: None,
+					thread_key: None,
+					attachments: None,
 				},
 				retry_policy: RetryConfig::default(),
+				idempotency_header: None,
+				format: None,
 			})
 			.build();
 
@@ -389,6 +508,8 @@ mod tests {
 				headers: h,
 				message,
 				retry_policy: _,
+				idempotency_header: _,
+				format: _,
 			} => {
 				assert_eq!(url.as_ref().to_string(), "https://webhook.example.com");
 				assert_eq!(method, Some("POST".to_string()));
@@ -697,4 +818,25 @@ mod tests {
 			_ => panic!("Expected slack config"),
 		}
 	}
+
+	#[test]
+	fn test_message_options() {
+		let trigger = TriggerBuilder::new()
+			.email(
+				"smtp.example.com",
+				"user",
+				"pass",
+				"alerts@example.com",
+				vec!["ops@example.com"],
+			)
+			.message_format(MessageFormat::Html)
+			.thread_key("${monitor.name}")
+			.attachments(vec!["report.csv".to_string()])
+			.build();
+
+		let message = trigger.config.message().unwrap();
+		assert_eq!(message.format, Some(MessageFormat::Html));
+		assert_eq!(message.thread_key.as_deref(), Some("${monitor.name}"));
+		assert_eq!(message.attachments, Some(vec!["report.csv".to_string()]));
+	}
 }
@@ -0,0 +1,153 @@
+//! Test helper utilities for pipeline resilience tests
+//!
+//! - `ChaosNotifier`: Trigger execution service failing, stalling or panicking on demand
+
+use async_trait::async_trait;
+use serde::Deserialize;
+use std::{
+	collections::{HashMap, VecDeque},
+	str::FromStr,
+	sync::{Arc, Mutex},
+	time::Duration,
+};
+
+use crate::{
+	models::{Monitor, MonitorMatch, ScriptLanguage, Trigger},
+	services::trigger::{TriggerError, TriggerExecutionServiceTrait},
+	utils::logging::error::ErrorContext,
+};
+
+/// Outcome of a delivery attempt of the [`ChaosNotifier`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(try_from = "String")]
+pub enum ChaosBehavior {
+	/// The delivery succeeds
+	Succeed,
+	/// The delivery fails with an execution error, as a flaky endpoint would
+	FailRetryable,
+	/// The delivery fails with a configuration error, as a rejected payload would
+	FailPermanent,
+	/// The delivery succeeds after the given delay
+	Delay(Duration),
+	/// The delivery panics
+	Panic,
+}
+
+impl FromStr for ChaosBehavior {
+	type Err = String;
+
+	/// Parses `succeed`, `fail_retryable`, `fail_permanent`, `panic` or `delay(<ms>)`
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		match s.trim().to_lowercase().as_str() {
+			"succeed" => Ok(Self::Succeed),
+			"fail_retryable" => Ok(Self::FailRetryable),
+			"fail_permanent" => Ok(Self::FailPermanent),
+			"panic" => Ok(Self::Panic),
+			other => other
+				.strip_prefix("delay(")
+				.and_then(|rest| rest.strip_suffix(')'))
+				.and_then(|ms| ms.trim().parse().ok())
+				.map(|ms| Self::Delay(Duration::from_millis(ms)))
+				.ok_or_else(|| format!("Invalid chaos behavior: {}", s)),
+		}
+	}
+}
+
+impl TryFrom<String> for ChaosBehavior {
+	type Error = String;
+
+	fn try_from(value: String) -> Result<Self, Self::Error> {
+		value.parse()
+	}
+}
+
+/// Trigger execution service following a script of behaviors
+///
+/// Each delivery attempt takes the next behavior of the script, attempts succeed once the script
+/// is exhausted. Attempts and successful deliveries are recorded for assertions.
+#[derive(Debug, Default)]
+pub struct ChaosNotifier {
+	behaviors: Mutex<VecDeque<ChaosBehavior>>,
+	attempts: Mutex<Vec<(String, ChaosBehavior)>>,
+	deliveries: Mutex<Vec<HashMap<String, String>>>,
+}
+
+impl ChaosNotifier {
+	/// Creates a notifier following the given behaviors
+	pub fn new(behaviors: impl IntoIterator<Item = ChaosBehavior>) -> Self {
+		Self {
+			behaviors: Mutex::new(behaviors.into_iter().collect()),
+			..Default::default()
+		}
+	}
+
+	/// Creates a notifier from a JSON list of behaviors, e.g. `["fail_retryable", "delay(250)"]`
+	pub fn from_config(config: &str) -> Result<Self, serde_json::Error> {
+		serde_json::from_str::<Vec<ChaosBehavior>>(config).map(Self::new)
+	}
+
+	/// Returns the monitor name and behavior of every attempt, in order
+	pub fn attempts(&self) -> Vec<(String, ChaosBehavior)> {
+		self.attempts.lock().unwrap().clone()
+	}
+
+	/// Returns the variables of every successful delivery, in order
+	pub fn deliveries(&self) -> Vec<HashMap<String, String>> {
+		self.deliveries.lock().unwrap().clone()
+	}
+}
+
+#[async_trait]
+impl TriggerExecutionServiceTrait for ChaosNotifier {
+	async fn execute(
+		&self,
+		_trigger_slugs: &[String],
+		variables: HashMap<String, String>,
+		monitor_match: &MonitorMatch,
+		_trigger_scripts: &HashMap<String, (ScriptLanguage, String)>,
+	) -> Result<(), TriggerError> {
+		let behavior = self
+			.behaviors
+			.lock()
+			.unwrap()
+			.pop_front()
+			.unwrap_or(ChaosBehavior::Succeed);
+		self.attempts
+			.lock()
+			.unwrap()
+			.push((monitor_match.monitor().name.clone(), behavior));
+
+		match behavior {
+			ChaosBehavior::Succeed => {}
+			ChaosBehavior::Delay(delay) => tokio::time::sleep(delay).await,
+			ChaosBehavior::FailRetryable => {
+				return Err(TriggerError::execution_error_without_log(
+					"Injected retryable failure",
+					None,
+					None,
+				))
+			}
+			ChaosBehavior::FailPermanent => {
+				return Err(TriggerError::ConfigurationError(ErrorContext::new(
+					"Injected permanent failure",
+					None,
+					None,
+				)))
+			}
+			ChaosBehavior::Panic => panic!("Injected panic"),
+		}
+		self.deliveries.lock().unwrap().push(variables);
+		Ok(())
+	}
+
+	async fn load_scripts(
+		&self,
+		_monitors: &[Monitor],
+	) -> Result<HashMap<String, (ScriptLanguage, String)>, TriggerError> {
+		Ok(HashMap::new())
+	}
+
+	fn get_trigger(&self, _trigger_slug: &str) -> Option<Arc<Trigger>> {
+		None
+	}
+}
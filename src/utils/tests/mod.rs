@@ -3,12 +3,14 @@
 //! This module contains test helper utilities for the application.
 //!
 //! - `builders`: Test helper utilities for creating test instances of models
+//! - `chaos`: Test helper utilities for pipeline resilience tests
 //! - `http`: Test helper utilities for creating HTTP clients
 
 pub mod builders {
 	// Chain specific test helpers
 	pub mod evm {
 		pub mod monitor;
+		pub mod monitor_match;
 		pub mod receipt;
 		pub mod transaction;
 	}
@@ -28,6 +30,7 @@ pub mod builders {
 	pub mod trigger;
 }
 
+pub mod chaos;
 pub mod http;
 
 pub use builders::*;
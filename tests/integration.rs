@@ -5,6 +5,7 @@
 
 mod integration {
 	mod blockchain {
+		mod fixtures;
 		mod pool;
 		mod clients {
 			mod evm {
@@ -42,17 +43,33 @@ mod integration {
 		}
 	}
 	mod bootstrap {
+		mod alert_latency;
+		mod chaos;
+		mod dispatch_queue;
+		mod enrichment;
 		mod main;
+		mod network_views;
+		mod notification_budget;
+		mod outbox;
+		mod preflight;
+	}
+	mod config {
+		mod schema;
+		mod trigger_groups;
 	}
 	mod mocks;
 
 	mod blockwatcher {
+		mod backfill;
+		mod leader;
 		mod service;
 	}
 	mod filters {
 		pub mod common;
 		mod evm {
+			mod concurrency;
 			mod filter;
+			mod sender_activity;
 		}
 		mod stellar {
 			mod filter;
@@ -62,15 +79,24 @@ mod integration {
 		}
 	}
 	mod notifications {
+		mod circuit_breaker;
+		mod egress;
 		mod email;
+		mod mutes;
 		mod script;
 		mod webhook;
 	}
 	mod monitor {
+		mod comparison;
+		mod defender;
 		mod execution;
 	}
 
 	mod security {
 		mod secret;
 	}
+
+	mod state {
+		mod store;
+	}
 }
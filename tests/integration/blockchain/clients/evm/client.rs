@@ -13,6 +13,7 @@ use openzeppelin_monitor::{
 	models::{BlockType, EVMBlock, EVMReceiptLog, EVMTransactionReceipt},
 	services::blockchain::{BlockChainClient, EvmClient, EvmClientTrait},
 };
+use std::sync::Arc;
 
 #[tokio::test]
 async fn test_get_transaction_receipt() {
@@ -95,7 +96,7 @@ async fn test_get_latest_block_number() {
 async fn test_get_blocks() {
 	let mut mock = MockEvmClientTrait::<MockEVMTransportClient>::new();
 
-	let block = BlockType::EVM(Box::new(EVMBlock::from(alloy::rpc::types::Block {
+	let block = BlockType::EVM(Arc::new(EVMBlock::from(alloy::rpc::types::Block {
 		header: Header {
 			inner: alloy::consensus::Header {
 				number: 1,
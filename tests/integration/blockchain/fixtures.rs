@@ -0,0 +1,226 @@
+use std::{path::Path, sync::Arc};
+
+use openzeppelin_monitor::{
+	models::{BlockType, EVMReceiptLog, EVMTransactionReceipt, Network},
+	services::blockchain::{
+		BlockChainClient, ClientPoolTrait, EvmBlockFixture, EvmClient, EvmClientTrait,
+		FixtureClient, FixtureClientPool, FixtureRecorder, TransportError,
+	},
+};
+use serde_json::{json, Value};
+use tempfile::TempDir;
+
+use crate::integration::{filters::common::read_and_parse_json, mocks::MockEVMTransportClient};
+
+const RECORDED_FIXTURES: &str = "tests/integration/fixtures/recorded";
+const NETWORK_SLUG: &str = "ethereum_mainnet";
+const BLOCK_NUMBER: u64 = 21306058;
+const TRANSACTION_HASH: &str = "0xd5069b22a3a89a36d592d5a1f72a281bc5d11d6d0bac6f0a878c13abb764b6d8";
+
+fn recorded_dir() -> std::path::PathBuf {
+	Path::new(RECORDED_FIXTURES).join(NETWORK_SLUG)
+}
+
+fn recorded_fixture() -> Value {
+	read_and_parse_json(&format!(
+		"{}/{}/{}.json",
+		RECORDED_FIXTURES, NETWORK_SLUG, BLOCK_NUMBER
+	))
+}
+
+/// Transport answering with the data of the checked-in fixture
+fn create_transport() -> MockEVMTransportClient {
+	let fixture = recorded_fixture();
+	let mut transport = MockEVMTransportClient::new();
+	transport
+		.expect_send_raw_request()
+		.returning(move |method, _params| match method {
+			"eth_getBlockByNumber" => Ok(json!({ "result": fixture["block"] })),
+			"eth_getTransactionReceipt" => Ok(json!({ "result": fixture["receipts"][0] })),
+			"eth_getLogs" => Ok(json!({ "result": fixture["logs"] })),
+			_ => Err(TransportError::http(
+				reqwest::StatusCode::METHOD_NOT_ALLOWED,
+				"random.url".to_string(),
+				"Unexpected method call".to_string(),
+				None,
+				None,
+			)),
+		});
+	transport.expect_clone().returning(create_transport);
+	transport
+}
+
+#[tokio::test]
+async fn test_recorder_writes_one_file_per_block_without_endpoints() {
+	let root = TempDir::new().unwrap();
+	let network: Network =
+		read_and_parse_json("tests/integration/fixtures/evm/networks/network.json");
+	let recorder = FixtureRecorder::new(root.path(), &network).unwrap();
+	let client =
+		EvmClient::new_with_transport(create_transport()).with_recorder(Arc::new(recorder));
+
+	let blocks = client.get_blocks(BLOCK_NUMBER, None).await.unwrap();
+	let receipt = client
+		.get_transaction_receipt(TRANSACTION_HASH.to_string())
+		.await
+		.unwrap();
+	let logs = client
+		.get_logs_for_blocks(BLOCK_NUMBER, BLOCK_NUMBER, None)
+		.await
+		.unwrap();
+
+	let dir = root.path().join(NETWORK_SLUG);
+	let mut files = std::fs::read_dir(&dir)
+		.unwrap()
+		.map(|entry| entry.unwrap().file_name().into_string().unwrap())
+		.collect::<Vec<_>>();
+	files.sort();
+	assert_eq!(
+		files,
+		[format!("{}.json", BLOCK_NUMBER), "network.json".into()]
+	);
+
+	// The network is recorded without its RPC URLs
+	let recorded_network = std::fs::read_to_string(dir.join("network.json")).unwrap();
+	assert!(!recorded_network.contains("drpc.org"));
+	let recorded_network: Network = serde_json::from_str(&recorded_network).unwrap();
+	assert!(recorded_network.rpc_urls.is_empty());
+	assert_eq!(recorded_network.chain_id, network.chain_id);
+
+	let fixture: EvmBlockFixture =
+		read_and_parse_json(dir.join(format!("{}.json", BLOCK_NUMBER)).to_str().unwrap());
+	let BlockType::EVM(block) = &blocks[0] else {
+		panic!("Expected EVM block");
+	};
+	assert_eq!(fixture.block.unwrap().number(), block.number());
+	assert_eq!(fixture.receipts.len(), 1);
+	assert_eq!(
+		fixture.receipts[0].transaction_hash,
+		receipt.transaction_hash
+	);
+	assert_eq!(fixture.logs, logs);
+
+	// The replayed data is the fetched data
+	let replay = FixtureClient::load(&dir).unwrap();
+	assert_eq!(
+		replay
+			.get_logs_for_blocks(BLOCK_NUMBER, BLOCK_NUMBER, None)
+			.await
+			.unwrap(),
+		logs
+	);
+	assert_eq!(
+		replay
+			.get_transaction_receipt(TRANSACTION_HASH.to_string())
+			.await
+			.unwrap()
+			.transaction_hash,
+		receipt.transaction_hash
+	);
+}
+
+#[tokio::test]
+async fn test_replay_of_checked_in_fixtures() {
+	let client = FixtureClient::load(&recorded_dir()).unwrap();
+	let fixture = recorded_fixture();
+
+	assert_eq!(
+		client.get_latest_block_number().await.unwrap(),
+		BLOCK_NUMBER
+	);
+	let blocks = client.get_blocks(BLOCK_NUMBER, None).await.unwrap();
+	let BlockType::EVM(block) = &blocks[0] else {
+		panic!("Expected EVM block");
+	};
+	assert_eq!(block.number(), Some(BLOCK_NUMBER));
+
+	let logs: Vec<EVMReceiptLog> = serde_json::from_value(fixture["logs"].clone()).unwrap();
+	let usdc_logs = client
+		.get_logs_for_blocks(
+			BLOCK_NUMBER,
+			BLOCK_NUMBER,
+			Some(vec![
+				"0xA0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48".to_string()
+			]),
+		)
+		.await
+		.unwrap();
+	assert!(!usdc_logs.is_empty());
+	assert!(usdc_logs.len() < logs.len());
+
+	let receipt: EVMTransactionReceipt =
+		serde_json::from_value(fixture["receipts"][0].clone()).unwrap();
+	assert_eq!(
+		client
+			.get_transaction_receipt(TRANSACTION_HASH.to_uppercase().replace("0X", "0x"))
+			.await
+			.unwrap()
+			.transaction_hash,
+		receipt.transaction_hash
+	);
+
+	let network = FixtureClient::load_network(&recorded_dir()).unwrap();
+	assert_eq!(network.slug, NETWORK_SLUG);
+	assert!(network.rpc_urls.is_empty());
+}
+
+#[tokio::test]
+async fn test_replay_miss_names_the_missing_data() {
+	let client = FixtureClient::load(&recorded_dir()).unwrap();
+
+	let error = client
+		.get_blocks(BLOCK_NUMBER, Some(BLOCK_NUMBER + 1))
+		.await
+		.unwrap_err();
+	assert!(
+		error
+			.to_string()
+			.contains(&format!("No fixture for block {}", BLOCK_NUMBER + 1)),
+		"{}",
+		error
+	);
+
+	let error = client
+		.get_logs_for_blocks(BLOCK_NUMBER - 1, BLOCK_NUMBER, None)
+		.await
+		.unwrap_err();
+	assert!(
+		error
+			.to_string()
+			.contains(&format!("No fixture for block {}", BLOCK_NUMBER - 1)),
+		"{}",
+		error
+	);
+
+	let missing = format!("0x{}", "ab".repeat(32));
+	let error = client
+		.get_transaction_receipt(missing.clone())
+		.await
+		.unwrap_err();
+	assert!(error.to_string().contains(&missing), "{}", error);
+}
+
+#[tokio::test]
+async fn test_fixture_pool_only_replays_evm_networks() {
+	let pool = FixtureClientPool::new(RECORDED_FIXTURES);
+	let network = FixtureClient::load_network(&recorded_dir()).unwrap();
+
+	let client = pool.get_evm_client(&network).await.unwrap();
+	assert_eq!(
+		client.get_latest_block_number().await.unwrap(),
+		BLOCK_NUMBER
+	);
+	assert!(Arc::ptr_eq(
+		&client,
+		&pool.get_evm_client(&network).await.unwrap()
+	));
+
+	let error = pool.get_stellar_client(&network).await.err().unwrap();
+	assert!(error.to_string().contains("Stellar"), "{}", error);
+
+	let unknown = Network {
+		slug: "unknown".to_string(),
+		..network
+	};
+	assert!(pool.get_evm_client(&unknown).await.is_err());
+}
@@ -3,10 +3,15 @@ use alloy::{
 	rpc::types::Index,
 };
 use mockall::predicate;
-use openzeppelin_monitor::services::blockchain::{
-	BlockChainClient, EvmClient, EvmClientTrait, TransportError,
+use openzeppelin_monitor::{
+	models::BlockType,
+	services::blockchain::{
+		BlockChainClient, DecodeMode, EvmClient, EvmClientTrait, TransportError,
+	},
+	utils::metrics::CHAIN_DATA_DECODE_FAILURES,
 };
 use serde_json::{json, Value};
+use std::sync::{Arc, Mutex};
 
 use crate::integration::mocks::MockEVMTransportClient;
 
@@ -37,6 +42,24 @@ fn create_mock_block(number: u64) -> Value {
 	})
 }
 
+fn create_mock_transaction(index: u64) -> Value {
+	json!({
+		"hash": format!("0x{:064x}", index + 1),
+		"nonce": "0x0",
+		"blockHash": format!("0x{:064x}", 1),
+		"blockNumber": "0x1",
+		"transactionIndex": format!("0x{:x}", index),
+		"from": "0x0000000000000000000000000000000000000001",
+		"to": "0x0000000000000000000000000000000000000002",
+		"value": "0x1",
+		"gasPrice": "0x1",
+		"gas": "0x5208",
+		"input": "0x",
+		"blobVersionedHashes": [],
+		"maxFeePerBlobGas": "0x1"
+	})
+}
+
 #[tokio::test]
 async fn test_get_logs_for_blocks_implementation() {
 	let mut mock_evm = MockEVMTransportClient::new();
@@ -132,7 +155,8 @@ async fn test_get_logs_for_blocks_invalid_format() {
 		.expect_send_raw_request()
 		.returning(move |_: &str, _: Option<Vec<Value>>| Ok(mock_response.clone()));
 
-	let client = EvmClient::<MockEVMTransportClient>::new_with_transport(mock_evm);
+	let client = EvmClient::<MockEVMTransportClient>::new_with_transport(mock_evm)
+		.with_decoding("ethereum_mainnet", DecodeMode::Strict);
 	let result = client.get_logs_for_blocks(1, 10, None).await;
 
 	assert!(result.is_err());
@@ -140,6 +164,205 @@ async fn test_get_logs_for_blocks_invalid_format() {
 	assert!(err.to_string().contains("Failed to parse logs"));
 }
 
+#[tokio::test]
+async fn test_get_logs_for_blocks_skips_malformed_log() {
+	let mut mock_evm = MockEVMTransportClient::new();
+
+	let mock_response = json!({
+		"result": [
+			{
+				"invalid_field": "this should fail parsing",
+				"blockNumber": "0x2"
+			},
+			{
+				"address": "0x1234567890123456789012345678901234567890",
+				"topics": [],
+				"data": "0x",
+				"blockNumber": "0x2",
+				"blockHash": null,
+				"transactionHash": null,
+				"transactionIndex": null,
+				"logIndex": "0x1",
+				"transactionLogIndex": null,
+				"removed": false
+			}
+		]
+	});
+
+	mock_evm
+		.expect_send_raw_request()
+		.returning(move |_: &str, _: Option<Vec<Value>>| Ok(mock_response.clone()));
+
+	let client = EvmClient::<MockEVMTransportClient>::new_with_transport(mock_evm)
+		.with_decoding("lenient_logs", DecodeMode::Lenient);
+	let logs = client.get_logs_for_blocks(1, 10, None).await.unwrap();
+
+	assert_eq!(logs.len(), 1);
+	assert_eq!(
+		CHAIN_DATA_DECODE_FAILURES
+			.with_label_values(&["lenient_logs", "log"])
+			.get(),
+		1
+	);
+}
+
+/// Returns the block range of an `eth_getLogs` request
+fn log_request_range(params: &Option<Vec<Value>>) -> (u64, u64) {
+	let filter = &params.as_ref().unwrap()[0];
+	let block = |key: &str| {
+		u64::from_str_radix(filter[key].as_str().unwrap().trim_start_matches("0x"), 16).unwrap()
+	};
+	(block("fromBlock"), block("toBlock"))
+}
+
+/// Returns a log of a block, its log index being the same for all blocks
+fn create_mock_log(block: u64) -> Value {
+	json!({
+		"address": "0x1234567890123456789012345678901234567890",
+		"topics": [],
+		"data": "0x",
+		"blockNumber": format!("0x{:x}", block),
+		"blockHash": format!("0x{:064x}", block),
+		"transactionHash": format!("0x{:064x}", block),
+		"transactionIndex": "0x0",
+		"logIndex": "0x0",
+		"transactionLogIndex": "0x0",
+		"removed": false
+	})
+}
+
+/// Mocks an endpoint answering log queries of at most `max_blocks` blocks, rejecting the others
+/// with `reject`
+///
+/// Answers carry one log per block, and also the log of the block preceding the range, as some
+/// providers return logs at the boundary of adjacent ranges.
+fn create_limited_logs_mock(
+	max_blocks: u64,
+	requests: Arc<Mutex<Vec<(u64, u64)>>>,
+	reject: fn() -> Result<Value, TransportError>,
+) -> MockEVMTransportClient {
+	let mut mock_evm = MockEVMTransportClient::new();
+	mock_evm
+		.expect_get_current_url()
+		.returning(|| "https://limited.example.com".to_string());
+	mock_evm
+		.expect_send_raw_request()
+		.returning(move |_: &str, params: Option<Vec<Value>>| {
+			let (from_block, to_block) = log_request_range(&params);
+			requests.lock().unwrap().push((from_block, to_block));
+			if to_block - from_block + 1 > max_blocks {
+				return reject();
+			}
+			let logs = (from_block.saturating_sub(1).max(1)..=to_block)
+				.map(create_mock_log)
+				.collect::<Vec<_>>();
+			Ok(json!({ "result": logs }))
+		});
+	mock_evm
+}
+
+fn too_many_results() -> Result<Value, TransportError> {
+	Ok(json!({
+		"jsonrpc": "2.0",
+		"id": 1,
+		"error": {"code": -32005, "message": "query returned more than 10000 results"}
+	}))
+}
+
+#[tokio::test]
+async fn test_get_logs_for_blocks_splits_ranges_above_provider_limit() {
+	let requests = Arc::new(Mutex::new(Vec::new()));
+	let mock_evm = create_limited_logs_mock(10, requests.clone(), too_many_results);
+	let client = EvmClient::<MockEVMTransportClient>::new_with_transport(mock_evm);
+
+	// The range is bisected down to ranges the endpoint answers, without duplicates
+	let logs = client.get_logs_for_blocks(1, 100, None).await.unwrap();
+	let blocks = logs
+		.iter()
+		.map(|log| log.block_number.unwrap().to::<u64>())
+		.collect::<Vec<_>>();
+	assert_eq!(blocks, (1..=100).collect::<Vec<_>>());
+	assert!(requests
+		.lock()
+		.unwrap()
+		.iter()
+		.any(|(from_block, to_block)| to_block - from_block + 1 > 10));
+
+	// Later queries are chunked with the cached limit up front
+	requests.lock().unwrap().clear();
+	let logs = client.get_logs_for_blocks(101, 300, None).await.unwrap();
+	let blocks = logs
+		.iter()
+		.map(|log| log.block_number.unwrap().to::<u64>())
+		.collect::<Vec<_>>();
+	// The mock also returns the log of the block preceding the query
+	assert_eq!(blocks, (100..=300).collect::<Vec<_>>());
+	let requests = requests.lock().unwrap();
+	assert!(requests
+		.iter()
+		.all(|(from_block, to_block)| to_block - from_block + 1 <= 10));
+	assert_eq!(
+		requests.first().map(|(from_block, _)| *from_block),
+		Some(101)
+	);
+	assert_eq!(requests.last().map(|(_, to_block)| *to_block), Some(300));
+}
+
+#[tokio::test]
+async fn test_get_logs_for_blocks_splits_ranges_rejected_with_413() {
+	let requests = Arc::new(Mutex::new(Vec::new()));
+	let mock_evm = create_limited_logs_mock(3, requests.clone(), || {
+		Err(TransportError::http(
+			reqwest::StatusCode::PAYLOAD_TOO_LARGE,
+			"https://limited.example.com".to_string(),
+			String::new(),
+			None,
+			None,
+		))
+	});
+	let client = EvmClient::<MockEVMTransportClient>::new_with_transport(mock_evm);
+
+	let logs = client.get_logs_for_blocks(10, 29, None).await.unwrap();
+	let blocks = logs
+		.iter()
+		.map(|log| log.block_number.unwrap().to::<u64>())
+		.collect::<Vec<_>>();
+	assert_eq!(blocks, (9..=29).collect::<Vec<_>>());
+}
+
+#[tokio::test]
+async fn test_get_logs_for_blocks_rejected_single_block() {
+	let requests = Arc::new(Mutex::new(Vec::new()));
+	let mock_evm = create_limited_logs_mock(0, requests.clone(), too_many_results);
+	let client = EvmClient::<MockEVMTransportClient>::new_with_transport(mock_evm);
+
+	let err = client.get_logs_for_blocks(1, 4, None).await.unwrap_err();
+	assert!(err
+		.to_string()
+		.contains("query returned more than 10000 results"));
+	// 1-4, 1-2, 1-1
+	assert_eq!(requests.lock().unwrap().len(), 3);
+}
+
+#[tokio::test]
+async fn test_get_logs_for_blocks_propagates_other_errors() {
+	let mut mock_evm = MockEVMTransportClient::new();
+	mock_evm
+		.expect_send_raw_request()
+		.times(1)
+		.returning(|_: &str, _: Option<Vec<Value>>| {
+			Ok(json!({
+				"jsonrpc": "2.0",
+				"id": 1,
+				"error": {"code": -32602, "message": "invalid argument 0: hex string"}
+			}))
+		});
+	let client = EvmClient::<MockEVMTransportClient>::new_with_transport(mock_evm);
+
+	let err = client.get_logs_for_blocks(1, 100, None).await.unwrap_err();
+	assert!(err.to_string().contains("invalid argument 0: hex string"));
+}
+
 #[tokio::test]
 async fn test_get_logs_for_blocks_alloy_error() {
 	let mut mock_evm = MockEVMTransportClient::new();
@@ -160,6 +383,51 @@ async fn test_get_logs_for_blocks_alloy_error() {
 	assert!(result.is_err());
 }
 
+#[tokio::test]
+async fn test_call_contract() {
+	let mut mock_evm = MockEVMTransportClient::new();
+
+	let expected_params = json!([
+		{ "to": "0x5f4ec3df9cbd43714fe2740f5e3616155c5b8419", "data": "0xfeaf968c" },
+		"latest"
+	]);
+
+	mock_evm
+		.expect_send_raw_request()
+		.with(
+			predicate::eq("eth_call"),
+			predicate::eq(Some(expected_params.as_array().unwrap().to_vec())),
+		)
+		.returning(|_: &str, _: Option<Vec<Value>>| Ok(json!({ "result": "0x0102" })));
+
+	let client = EvmClient::<MockEVMTransportClient>::new_with_transport(mock_evm);
+	let result = client
+		.call_contract(
+			"0x5f4ec3df9cbd43714fe2740f5e3616155c5b8419".to_string(),
+			vec![0xfe, 0xaf, 0x96, 0x8c],
+		)
+		.await;
+
+	assert_eq!(result.unwrap(), vec![0x01, 0x02]);
+}
+
+#[tokio::test]
+async fn test_call_contract_missing_result() {
+	let mut mock_evm = MockEVMTransportClient::new();
+
+	mock_evm
+		.expect_send_raw_request()
+		.returning(|_: &str, _: Option<Vec<Value>>| Ok(json!({ "id": 1, "jsonrpc": "2.0" })));
+
+	let client = EvmClient::<MockEVMTransportClient>::new_with_transport(mock_evm);
+	let result = client.call_contract("0x00".to_string(), vec![]).await;
+
+	assert!(result
+		.unwrap_err()
+		.to_string()
+		.contains("Missing 'result' field"));
+}
+
 #[tokio::test]
 async fn test_get_transaction_receipt_success() {
 	let mut mock_evm = MockEVMTransportClient::new();
@@ -543,3 +811,47 @@ async fn test_get_blocks_parse_failure() {
 	let err = result.unwrap_err();
 	assert!(err.to_string().contains("Failed to parse block"));
 }
+
+#[tokio::test]
+async fn test_get_blocks_skips_malformed_transaction() {
+	let mut mock_evm = MockEVMTransportClient::new();
+
+	mock_evm.expect_clone().returning(|| {
+		let mut new_mock = MockEVMTransportClient::new();
+		let mut malformed = create_mock_transaction(1);
+		malformed["value"] = json!("not a number");
+		let mut block = create_mock_block(1);
+		block["transactions"] = json!([
+			create_mock_transaction(0),
+			malformed,
+			create_mock_transaction(2)
+		]);
+		let mock_response = json!({
+			"jsonrpc": "2.0",
+			"id": 1,
+			"result": block
+		});
+		new_mock
+			.expect_send_raw_request()
+			.returning(move |_, _| Ok(mock_response.clone()));
+		new_mock
+			.expect_clone()
+			.returning(MockEVMTransportClient::new);
+		new_mock
+	});
+
+	let client = EvmClient::<MockEVMTransportClient>::new_with_transport(mock_evm)
+		.with_decoding("lenient_blocks", DecodeMode::Lenient);
+
+	let blocks = client.get_blocks(1, None).await.unwrap();
+	let BlockType::EVM(block) = &blocks[0] else {
+		panic!("Expected EVM block");
+	};
+	assert_eq!(block.transactions.len(), 2);
+	assert_eq!(
+		CHAIN_DATA_DECODE_FAILURES
+			.with_label_values(&["lenient_blocks", "transaction"])
+			.get(),
+		1
+	);
+}
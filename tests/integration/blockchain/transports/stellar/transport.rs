@@ -2,8 +2,10 @@ use mockall::predicate;
 use openzeppelin_monitor::{
 	models::{BlockType, ContractSpec, StellarFormattedContractSpec},
 	services::blockchain::{
-		BlockChainClient, StellarClient, StellarClientError, StellarClientTrait, TransportError,
+		BlockChainClient, DecodeMode, StellarClient, StellarClientError, StellarClientTrait,
+		TransportError,
 	},
+	utils::metrics::CHAIN_DATA_DECODE_FAILURES,
 };
 use serde_json::{json, Value};
 
@@ -113,7 +115,8 @@ async fn test_get_transactions_failed_to_parse_transaction() {
 		.times(1)
 		.returning(move |_, _| Ok(mock_response.clone()));
 
-	let client = StellarClient::new_with_transport(mock_stellar);
+	let client = StellarClient::new_with_transport(mock_stellar)
+		.with_decoding("stellar_testnet", DecodeMode::Strict);
 	let result = client.get_transactions(1, Some(2)).await;
 
 	assert!(result.is_err());
@@ -132,6 +135,57 @@ async fn test_get_transactions_failed_to_parse_transaction() {
 	),);
 }
 
+#[tokio::test]
+async fn test_get_transactions_skips_malformed_transaction() {
+	let mut mock_stellar = MockStellarTransportClient::new();
+
+	let mock_response = json!({
+		"jsonrpc": "2.0",
+		"id": 1,
+		"result": {
+			"transactions": [
+				{
+					"status": "SUCCESS",
+					"ledger": 1
+				},
+				{
+					"status": "SUCCESS",
+					"txHash": "7723ef4c6f11aba528eea5b0cd57676a651333bfd57c2fead949999a3183304d",
+					"applicationOrder": 2,
+					"feeBump": false,
+					"ledger": 1,
+					"createdAt": 1735440610,
+					"someFutureField": "ignored"
+				}
+			],
+			"latestLedger": 1,
+			"cursor": null
+		}
+	});
+
+	mock_stellar
+		.expect_send_raw_request()
+		.with(predicate::eq("getTransactions"), predicate::always())
+		.times(1)
+		.returning(move |_, _| Ok(mock_response.clone()));
+
+	let client = StellarClient::new_with_transport(mock_stellar)
+		.with_decoding("lenient_stellar", DecodeMode::Lenient);
+	let transactions = client.get_transactions(1, Some(2)).await.unwrap();
+
+	assert_eq!(transactions.len(), 1);
+	assert_eq!(
+		transactions[0].hash(),
+		"7723ef4c6f11aba528eea5b0cd57676a651333bfd57c2fead949999a3183304d"
+	);
+	assert_eq!(
+		CHAIN_DATA_DECODE_FAILURES
+			.with_label_values(&["lenient_stellar", "transaction"])
+			.get(),
+		1
+	);
+}
+
 #[tokio::test]
 async fn test_get_transactions_outside_of_rpc_retention_window() {
 	let mut mock_stellar = MockStellarTransportClient::new();
@@ -387,7 +441,8 @@ async fn test_get_events_failed_to_parse_event() {
 		.times(1)
 		.returning(move |_, _| Ok(mock_response.clone()));
 
-	let client = StellarClient::new_with_transport(mock_stellar);
+	let client = StellarClient::new_with_transport(mock_stellar)
+		.with_decoding("stellar_testnet", DecodeMode::Strict);
 	let result = client.get_events(1, Some(2)).await;
 
 	assert!(result.is_err());
@@ -0,0 +1,224 @@
+//! Integration tests for backfill jobs.
+//!
+//! Tests that backfilled matches are only sent to the triggers accepting their origin, and that
+//! the origin reaches webhook payloads.
+
+use futures::future::BoxFuture;
+use mockito::{Matcher, Mock, Server, ServerGuard};
+use openzeppelin_monitor::{
+	models::{BlockChainType, BlockType, MatchOrigin, Monitor, Network, ProcessedBlock},
+	repositories::{TriggerRepository, TriggerService},
+	services::{
+		blockwatcher::{run_backfill, BackfillJob},
+		notification::NotificationService,
+		trigger::{TriggerExecutionService, TriggerExecutionServiceTrait},
+	},
+	utils::tests::{
+		evm::{monitor::MonitorBuilder, monitor_match::MonitorMatchBuilder},
+		trigger::TriggerBuilder,
+	},
+};
+use std::{collections::HashMap, sync::Arc};
+
+use crate::integration::mocks::{
+	create_test_block, create_test_network, MockEVMTransportClient, MockEvmClientTrait,
+};
+
+/// Client serving every block of the requested range
+fn create_range_client() -> MockEvmClientTrait<MockEVMTransportClient> {
+	let mut rpc_client = MockEvmClientTrait::<MockEVMTransportClient>::new();
+	rpc_client.expect_get_blocks().returning(|start, end| {
+		Ok((start..=end.unwrap_or(start))
+			.map(|number| create_test_block(BlockChainType::EVM, number))
+			.collect())
+	});
+	rpc_client
+}
+
+/// Block handler matching every block once with the given monitor
+fn create_matching_block_handler(
+	monitor: Monitor,
+) -> Arc<impl Fn(BlockType, Network) -> BoxFuture<'static, ProcessedBlock> + Send + Sync + 'static>
+{
+	Arc::new(move |block: BlockType, network: Network| {
+		let monitor = monitor.clone();
+		Box::pin(async move {
+			ProcessedBlock {
+				block_number: block.number().unwrap_or(0),
+				network_slug: network.slug.clone(),
+				processing_results: vec![MonitorMatchBuilder::new()
+					.monitor(monitor)
+					.network_slug(&network.slug)
+					.build()],
+			}
+		}) as BoxFuture<'static, ProcessedBlock>
+	})
+}
+
+/// Trigger handler executing the triggers of every match of the block
+fn create_executing_trigger_handler(
+	trigger_execution_service: Arc<TriggerExecutionService<TriggerRepository>>,
+) -> Arc<impl Fn(&ProcessedBlock) -> tokio::task::JoinHandle<()> + Send + Sync + 'static> {
+	Arc::new(move |block: &ProcessedBlock| {
+		let trigger_execution_service = trigger_execution_service.clone();
+		let matches = block.processing_results.clone();
+		tokio::spawn(async move {
+			for monitor_match in matches {
+				trigger_execution_service
+					.execute(
+						&monitor_match.monitor().trigger_names(),
+						HashMap::new(),
+						&monitor_match,
+						&HashMap::new(),
+					)
+					.await
+					.unwrap();
+			}
+		})
+	})
+}
+
+/// Creates a Slack trigger paging people and a webhook trigger archiving every match
+fn create_trigger_execution_service(
+	server: &ServerGuard,
+) -> Arc<TriggerExecutionService<TriggerRepository>> {
+	let pager = TriggerBuilder::new()
+		.name("pager")
+		.slack(&format!("{}/slack", server.url()))
+		.message("Transfer", "Transfer in block")
+		.build();
+	let archive = TriggerBuilder::new()
+		.name("archive")
+		.webhook(&format!("{}/archive", server.url()))
+		.message("Transfer", "Transfer in block")
+		.build();
+	let trigger_service = TriggerService::new_with_repository(TriggerRepository {
+		triggers: HashMap::from([
+			("pager".to_string(), pager),
+			("archive".to_string(), archive),
+		]),
+	})
+	.unwrap();
+	Arc::new(TriggerExecutionService::new(
+		trigger_service,
+		NotificationService::new(),
+	))
+}
+
+async fn mock_endpoint(
+	server: &mut ServerGuard,
+	path: &str,
+	origin: Option<serde_json::Value>,
+	calls: usize,
+) -> Mock {
+	let mut mock = server.mock("POST", path).with_status(200).expect(calls);
+	if let Some(origin) = origin {
+		mock = mock.match_body(Matcher::PartialJson(
+			serde_json::json!({ "origin": origin }),
+		));
+	}
+	mock.create_async().await
+}
+
+#[tokio::test]
+async fn test_backfill_only_pages_when_overridden() {
+	let mut server = Server::new_async().await;
+	let network = create_test_network("Ethereum", "ethereum_mainnet", BlockChainType::EVM);
+	let monitor = MonitorBuilder::new()
+		.name("transfers")
+		.triggers(vec!["pager".to_string(), "archive".to_string()])
+		.build();
+	let block_handler = create_matching_block_handler(monitor);
+	let trigger_handler =
+		create_executing_trigger_handler(create_trigger_execution_service(&server));
+	let rpc_client = create_range_client();
+	let job = BackfillJob {
+		job_id: "backfill-42".to_string(),
+		from_block: 10,
+		to_block: 12,
+		origin_override: None,
+	};
+
+	// Slack only accepts live matches, the archive receives the rows tagged with the job
+	let slack = mock_endpoint(&mut server, "/slack", None, 0).await;
+	let archive = mock_endpoint(
+		&mut server,
+		"/archive",
+		Some(serde_json::json!({ "type": "backfill", "job_id": "backfill-42" })),
+		3,
+	)
+	.await;
+
+	let processed = run_backfill(
+		&network,
+		&rpc_client,
+		&job,
+		&*block_handler,
+		&*trigger_handler,
+	)
+	.await
+	.unwrap();
+
+	assert_eq!(processed, 3);
+	slack.assert_async().await;
+	archive.assert_async().await;
+	slack.remove_async().await;
+	archive.remove_async().await;
+
+	// Overriding the origin re-alerts on the range
+	let slack = mock_endpoint(&mut server, "/slack", None, 3).await;
+	let archive = mock_endpoint(
+		&mut server,
+		"/archive",
+		Some(serde_json::json!({ "type": "live" })),
+		3,
+	)
+	.await;
+
+	let job = BackfillJob {
+		origin_override: Some(MatchOrigin::Live),
+		..job
+	};
+	run_backfill(
+		&network,
+		&rpc_client,
+		&job,
+		&*block_handler,
+		&*trigger_handler,
+	)
+	.await
+	.unwrap();
+
+	slack.assert_async().await;
+	archive.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_backfill_rejects_inverted_range() {
+	let network = create_test_network("Ethereum", "ethereum_mainnet", BlockChainType::EVM);
+	let block_handler = create_matching_block_handler(MonitorBuilder::new().build());
+	let triggered = Arc::new(std::sync::Mutex::new(0));
+	let counter = triggered.clone();
+	let trigger_handler = move |_: &ProcessedBlock| {
+		*counter.lock().unwrap() += 1;
+		tokio::spawn(async {})
+	};
+	let job = BackfillJob {
+		job_id: "inverted".to_string(),
+		from_block: 12,
+		to_block: 10,
+		origin_override: None,
+	};
+
+	let result = run_backfill(
+		&network,
+		&create_range_client(),
+		&job,
+		&*block_handler,
+		&trigger_handler,
+	)
+	.await;
+
+	assert!(result.is_err());
+	assert_eq!(*triggered.lock().unwrap(), 0);
+}
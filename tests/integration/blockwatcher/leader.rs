@@ -0,0 +1,166 @@
+use std::{
+	sync::{Arc, Mutex},
+	time::Duration,
+};
+
+use openzeppelin_monitor::services::blockwatcher::{
+	BlockStorage, FileBlockStorage, FileLeaseStore, LeaderElector, LeaseStore,
+};
+use tempfile::TempDir;
+use tokio::{sync::watch, task::JoinHandle};
+
+const NETWORK: &str = "ethereum_mainnet";
+const BLOCKS_PER_RUN: u64 = 5;
+
+/// Block range processed by a replica
+#[derive(Debug, Clone)]
+struct ProcessedRange {
+	holder: String,
+	from: u64,
+	to: u64,
+}
+
+/// A monitor replica sharing its storage directory with the other replicas
+struct Replica {
+	elector: Arc<LeaderElector>,
+	shutdown_tx: watch::Sender<bool>,
+	renewal: JoinHandle<()>,
+	processing: JoinHandle<()>,
+}
+
+fn start_replica(
+	holder: &str,
+	dir: &TempDir,
+	ttl: Duration,
+	processed: Arc<Mutex<Vec<ProcessedRange>>>,
+) -> Replica {
+	let store = FileLeaseStore::new(dir.path().join("leader_lease.json"));
+	let elector = Arc::new(LeaderElector::new(holder, Arc::new(store), ttl));
+	let (shutdown_tx, shutdown_rx) = watch::channel(false);
+	let renewal = elector.clone().spawn(shutdown_rx);
+
+	// Simulates the block watcher job: process the next range of blocks while leader
+	let block_storage = FileBlockStorage::new(dir.path().to_path_buf());
+	let processing_elector = elector.clone();
+	let holder = holder.to_string();
+	let processing = tokio::spawn(async move {
+		loop {
+			if processing_elector.is_leader() {
+				let last = block_storage
+					.get_last_processed_block(NETWORK)
+					.await
+					.unwrap()
+					.unwrap_or(0);
+				tokio::time::sleep(Duration::from_millis(5)).await;
+				let to = last + BLOCKS_PER_RUN;
+				block_storage
+					.save_last_processed_block(NETWORK, to)
+					.await
+					.unwrap();
+				processed.lock().unwrap().push(ProcessedRange {
+					holder: holder.clone(),
+					from: last + 1,
+					to,
+				});
+			}
+			tokio::time::sleep(Duration::from_millis(10)).await;
+		}
+	});
+
+	Replica {
+		elector,
+		shutdown_tx,
+		renewal,
+		processing,
+	}
+}
+
+async fn wait_for_leader(elector: &LeaderElector, timeout: Duration) -> bool {
+	let deadline = tokio::time::Instant::now() + timeout;
+	while tokio::time::Instant::now() < deadline {
+		if elector.is_leader() {
+			return true;
+		}
+		tokio::time::sleep(Duration::from_millis(5)).await;
+	}
+	false
+}
+
+#[tokio::test]
+async fn test_follower_takes_over_once_without_overlap() {
+	let dir = TempDir::new().unwrap();
+	let ttl = Duration::from_millis(400);
+	let processed = Arc::new(Mutex::new(Vec::new()));
+
+	let leader = start_replica("leader", &dir, ttl, processed.clone());
+	assert!(wait_for_leader(&leader.elector, Duration::from_secs(2)).await);
+
+	let follower = start_replica("follower", &dir, ttl, processed.clone());
+	tokio::time::sleep(Duration::from_millis(300)).await;
+	assert!(!follower.elector.is_leader());
+
+	// Kill the leader's lease renewal without releasing the lease, its block processing keeps
+	// running and must stop on its own
+	leader.renewal.abort();
+	assert!(wait_for_leader(&follower.elector, Duration::from_secs(3)).await);
+	assert!(!leader.elector.is_leader());
+	tokio::time::sleep(Duration::from_millis(300)).await;
+
+	// The follower took over exactly once, the term increases on every change of holder
+	let lease = FileLeaseStore::new(dir.path().join("leader_lease.json"))
+		.read()
+		.await
+		.unwrap()
+		.unwrap();
+	assert_eq!(lease.holder, "follower");
+	assert_eq!(lease.term, 2);
+	assert!(follower.elector.is_leader());
+
+	follower.processing.abort();
+	leader.processing.abort();
+	let _ = follower.shutdown_tx.send(true);
+	let _ = follower.renewal.await;
+	drop(leader.shutdown_tx);
+
+	// Ranges are contiguous, so no block was processed twice, and the leader never processed
+	// after the follower started
+	let processed = processed.lock().unwrap().clone();
+	assert!(processed.iter().any(|r| r.holder == "leader"));
+	assert!(processed.iter().any(|r| r.holder == "follower"));
+	for (previous, next) in processed.iter().zip(processed.iter().skip(1)) {
+		assert_eq!(
+			next.from,
+			previous.to + 1,
+			"overlapping ranges: {processed:?}"
+		);
+	}
+	let first_follower_range = processed
+		.iter()
+		.position(|r| r.holder == "follower")
+		.unwrap();
+	assert!(processed[first_follower_range..]
+		.iter()
+		.all(|r| r.holder == "follower"));
+}
+
+#[tokio::test]
+async fn test_graceful_shutdown_releases_lease() {
+	let dir = TempDir::new().unwrap();
+	let ttl = Duration::from_secs(4);
+	let processed = Arc::new(Mutex::new(Vec::new()));
+
+	let leader = start_replica("leader", &dir, ttl, processed.clone());
+	assert!(wait_for_leader(&leader.elector, Duration::from_secs(2)).await);
+	let follower = start_replica("follower", &dir, ttl, processed.clone());
+
+	let _ = leader.shutdown_tx.send(true);
+	let _ = leader.renewal.await;
+	leader.processing.abort();
+
+	// Takes over within a renewal interval instead of waiting for the lease to expire
+	assert!(wait_for_leader(&follower.elector, Duration::from_secs(2)).await);
+
+	follower.processing.abort();
+	let _ = follower.shutdown_tx.send(true);
+	let _ = follower.renewal.await;
+}
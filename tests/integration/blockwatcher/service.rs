@@ -1,3 +1,4 @@
+use chrono::{DateTime, Utc};
 use futures::future::BoxFuture;
 use mockall::predicate;
 use std::sync::Arc;
@@ -8,12 +9,18 @@ use crate::integration::mocks::{
 	MockEVMTransportClient, MockEvmClientTrait, MockJobScheduler,
 };
 use openzeppelin_monitor::{
-	models::{BlockChainType, BlockType, Network, ProcessedBlock},
+	models::{
+		BlockChainType, BlockFetchConfig, BlockType, MaintenanceWindow, Network, ProcessedBlock,
+	},
 	services::blockwatcher::{
-		process_new_blocks, BlockCheckResult, BlockTracker, BlockTrackerTrait, BlockWatcherError,
-		BlockWatcherService, NetworkBlockWatcher,
+		process_new_blocks, process_new_blocks_at, retry_failed_blocks, BlockCheckResult,
+		BlockStorage, BlockTracker, BlockTrackerTrait, BlockWatcherError, BlockWatcherService,
+		FailedBlock, FileBlockStorage, FileLeaseStore, LeaderElector, NetworkBlockWatcher,
+	},
+	utils::{
+		get_cron_interval_ms, metrics::SKIPPED_BLOCKS,
+		tests::evm::monitor_match::MonitorMatchBuilder,
 	},
-	utils::get_cron_interval_ms,
 };
 
 #[derive(Clone, Default)]
@@ -492,6 +499,67 @@ async fn test_ordered_trigger_handling() {
 	);
 }
 
+#[tokio::test]
+async fn test_matches_are_stamped_with_pipeline_times() {
+	let network = create_test_network("Test Network", "test-network", BlockChainType::EVM);
+	let config = MockConfig {
+		last_processed_block: Some(100),
+		latest_block: 102,
+		blocks_to_return: vec![create_test_block(BlockChainType::EVM, 101)],
+		expected_save_block: Some(101),
+		expected_block_range: Some((101, Some(101))),
+		expected_tracked_blocks: vec![101],
+		store_blocks: false,
+	};
+	let (block_storage, block_tracker, rpc_client) =
+		setup_mocks_with_network(config, Some(&network));
+	let started_at_ms = Utc::now().timestamp_millis();
+
+	let block_handler = Arc::new(|block: BlockType, network: Network| {
+		Box::pin(async move {
+			let monitor_match = MonitorMatchBuilder::new()
+				.network_slug(&network.slug)
+				.build();
+			ProcessedBlock {
+				block_number: block.number().unwrap_or(0),
+				network_slug: network.slug,
+				processing_results: vec![monitor_match],
+			}
+		}) as BoxFuture<'static, ProcessedBlock>
+	});
+	let timings = Arc::new(tokio::sync::Mutex::new(Vec::new()));
+	let trigger_handler = {
+		let timings = timings.clone();
+		Arc::new(move |block: &ProcessedBlock| {
+			let timings = timings.clone();
+			let timing = block.processing_results[0].timing().copied();
+			tokio::spawn(async move {
+				timings.lock().await.push(timing);
+			})
+		})
+	};
+
+	let result = process_new_blocks(
+		&network,
+		&rpc_client,
+		block_storage.clone(),
+		block_handler,
+		trigger_handler,
+		Arc::new(block_tracker),
+	)
+	.await;
+	assert!(result.is_ok(), "Block processing should succeed");
+
+	let timings = timings.lock().await;
+	assert_eq!(timings.len(), 1);
+	let timing = timings[0].expect("Matches should carry their pipeline times");
+	// The test block has a zero timestamp
+	assert_eq!(timing.block_timestamp_ms, Some(0));
+	assert!(timing.fetched_at_ms >= started_at_ms);
+	assert!(timing.matched_at_ms >= timing.fetched_at_ms);
+	assert!(timing.matched_at_ms <= Utc::now().timestamp_millis());
+}
+
 #[tokio::test]
 async fn test_block_storage_enabled() {
 	let mut network = create_test_network("Test Network", "test-network", BlockChainType::EVM);
@@ -725,6 +793,276 @@ async fn test_confirmation_blocks() {
 	assert!(result.is_ok(), "Block processing should succeed");
 }
 
+/// Network paused every Sunday 02:00-03:00 UTC
+fn create_maintenance_network() -> Network {
+	let mut network = create_test_network("Test Network", "test-network", BlockChainType::EVM);
+	network.max_past_blocks = Some(50);
+	network.maintenance_windows = Some(vec![MaintenanceWindow {
+		cron_schedule: "0 0 2 * * Sun".to_string(),
+		duration_ms: 3_600_000,
+		utc_offset: None,
+		timezone: None,
+	}]);
+	network
+}
+
+/// Parses a point in time for the fake clock of the maintenance window tests
+fn at(value: &str) -> DateTime<Utc> {
+	DateTime::parse_from_rfc3339(value)
+		.unwrap()
+		.with_timezone(&Utc)
+}
+
+/// Processes blocks 101 to 110 at the given time, returning the dispatched block numbers
+async fn process_catch_up_at(network: &Network, now: DateTime<Utc>) -> Vec<u64> {
+	// The watermark was left at 100, so the whole missed range is fetched
+	let blocks_to_process: Vec<u64> = (101..=110).collect();
+	let config = MockConfig {
+		last_processed_block: Some(100),
+		latest_block: 111,
+		blocks_to_return: blocks_to_process
+			.iter()
+			.map(|&num| create_test_block(BlockChainType::EVM, num))
+			.collect(),
+		expected_save_block: Some(110),
+		expected_block_range: Some((101, Some(110))),
+		expected_tracked_blocks: blocks_to_process.clone(),
+		store_blocks: false,
+	};
+
+	let (block_storage, block_tracker, rpc_client) =
+		setup_mocks_with_network(config, Some(network));
+
+	let processed = Arc::new(tokio::sync::Mutex::new(Vec::new()));
+	let block_handler = Arc::new(|block: BlockType, network: Network| {
+		Box::pin(async move {
+			ProcessedBlock {
+				block_number: block.number().unwrap_or(0),
+				network_slug: network.slug,
+				processing_results: vec![],
+			}
+		}) as BoxFuture<'static, ProcessedBlock>
+	});
+
+	let trigger_handler = {
+		let processed = processed.clone();
+		Arc::new(move |block: &ProcessedBlock| {
+			let processed = processed.clone();
+			let block_number = block.block_number;
+			tokio::spawn(async move {
+				processed.lock().await.push(block_number);
+			})
+		})
+	};
+
+	let result = process_new_blocks_at(
+		network,
+		&rpc_client,
+		block_storage,
+		block_handler,
+		trigger_handler,
+		Arc::new(block_tracker),
+		now,
+		None,
+	)
+	.await;
+	assert!(
+		result.is_ok(),
+		"Processing outside the window should succeed"
+	);
+
+	// Give the spawned trigger tasks a chance to complete
+	tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+	let mut processed = processed.lock().await.clone();
+	processed.sort();
+	processed
+}
+
+#[tokio::test]
+async fn test_maintenance_window_skips_processing() {
+	let network = create_maintenance_network();
+
+	// 2024-01-07 is a Sunday, the window covers [02:00:00, 03:00:00)
+	for now in [
+		"2024-01-07T02:00:00Z",
+		"2024-01-07T02:30:00Z",
+		"2024-01-07T02:59:59Z",
+	] {
+		// No expectations are set, so any storage, client or tracker call fails the test
+		let block_storage = Arc::new(MockBlockStorage::new());
+		let rpc_client = MockEvmClientTrait::<MockEVMTransportClient>::new();
+		let block_tracker = MockBlockTracker::default();
+
+		let block_handler = Arc::new(|_: BlockType, network: Network| {
+			Box::pin(async move {
+				ProcessedBlock {
+					block_number: 0,
+					network_slug: network.slug,
+					processing_results: vec![],
+				}
+			}) as BoxFuture<'static, ProcessedBlock>
+		});
+
+		let trigger_handler = Arc::new(|_: &ProcessedBlock| tokio::spawn(async {}));
+
+		let result = process_new_blocks_at(
+			&network,
+			&rpc_client,
+			block_storage,
+			block_handler,
+			trigger_handler,
+			Arc::new(block_tracker),
+			at(now),
+			None,
+		)
+		.await;
+
+		assert!(
+			result.is_ok(),
+			"Processing should be skipped during a maintenance window at {}",
+			now
+		);
+	}
+}
+
+#[tokio::test]
+async fn test_maintenance_window_processes_before_window() {
+	let network = create_maintenance_network();
+
+	let processed = process_catch_up_at(&network, at("2024-01-07T01:59:59Z")).await;
+	assert_eq!(processed, (101..=110).collect::<Vec<u64>>());
+}
+
+#[tokio::test]
+async fn test_maintenance_window_catch_up_after_window() {
+	let network = create_maintenance_network();
+
+	// The window ends at 03:00:00, when the missed range is processed in full
+	let processed = process_catch_up_at(&network, at("2024-01-07T03:00:00Z")).await;
+	assert_eq!(processed, (101..=110).collect::<Vec<u64>>());
+}
+
+#[tokio::test]
+async fn test_catch_up_beyond_max_past_blocks_counts_skipped_blocks() {
+	let mut network = create_maintenance_network();
+	network.slug = "skipped-blocks-network".to_string();
+
+	// With max_past_blocks at 50, blocks 101 to 159 are older than the catch-up range
+	let blocks_to_process: Vec<u64> = (160..=210).collect();
+	let config = MockConfig {
+		last_processed_block: Some(100),
+		latest_block: 211,
+		blocks_to_return: blocks_to_process
+			.iter()
+			.map(|&num| create_test_block(BlockChainType::EVM, num))
+			.collect(),
+		expected_save_block: Some(210),
+		expected_block_range: Some((160, Some(210))),
+		expected_tracked_blocks: blocks_to_process.clone(),
+		store_blocks: false,
+	};
+
+	let (block_storage, block_tracker, rpc_client) =
+		setup_mocks_with_network(config, Some(&network));
+
+	let block_handler = Arc::new(|block: BlockType, network: Network| {
+		Box::pin(async move {
+			ProcessedBlock {
+				block_number: block.number().unwrap_or(0),
+				network_slug: network.slug,
+				processing_results: vec![],
+			}
+		}) as BoxFuture<'static, ProcessedBlock>
+	});
+	let trigger_handler = Arc::new(|_: &ProcessedBlock| tokio::spawn(async {}));
+
+	let result = process_new_blocks_at(
+		&network,
+		&rpc_client,
+		block_storage,
+		block_handler,
+		trigger_handler,
+		Arc::new(block_tracker),
+		at("2024-01-07T03:00:00Z"),
+		None,
+	)
+	.await;
+
+	assert!(result.is_ok());
+	assert_eq!(
+		SKIPPED_BLOCKS
+			.with_label_values(&["skipped-blocks-network"])
+			.get(),
+		59
+	);
+}
+
+#[tokio::test]
+async fn test_lost_lease_stops_dispatch_and_storage() {
+	let network = create_test_network("Ethereum", "ethereum_mainnet", BlockChainType::EVM);
+	let blocks_to_process: Vec<u64> = (101..=105).collect();
+	// No save of the last processed block is expected, the new leader continues from 100
+	let config = MockConfig {
+		last_processed_block: Some(100),
+		latest_block: 106,
+		blocks_to_return: blocks_to_process
+			.iter()
+			.map(|&num| create_test_block(BlockChainType::EVM, num))
+			.collect(),
+		expected_save_block: None,
+		expected_block_range: Some((101, Some(105))),
+		expected_tracked_blocks: blocks_to_process.clone(),
+		store_blocks: false,
+	};
+	let (block_storage, block_tracker, rpc_client) =
+		setup_mocks_with_network(config, Some(&network));
+
+	// The replica acts as leader for half of the TTL, which runs out while blocks are processed
+	let lease_dir = tempfile::TempDir::new().unwrap();
+	let store = FileLeaseStore::new(lease_dir.path().join("leader_lease.json"));
+	let elector = Arc::new(LeaderElector::new(
+		"stalled",
+		Arc::new(store),
+		std::time::Duration::from_millis(100),
+	));
+	assert!(elector.renew().await.unwrap());
+
+	let block_handler = Arc::new(|block: BlockType, network: Network| {
+		Box::pin(async move {
+			tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+			ProcessedBlock {
+				block_number: block.number().unwrap_or(0),
+				network_slug: network.slug,
+				processing_results: vec![],
+			}
+		}) as BoxFuture<'static, ProcessedBlock>
+	});
+	let dispatched = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+	let trigger_handler = {
+		let dispatched = dispatched.clone();
+		Arc::new(move |_: &ProcessedBlock| {
+			dispatched.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+			tokio::spawn(async {})
+		})
+	};
+
+	let result = process_new_blocks_at(
+		&network,
+		&rpc_client,
+		block_storage,
+		block_handler,
+		trigger_handler,
+		Arc::new(block_tracker),
+		Utc::now(),
+		Some(elector.clone()),
+	)
+	.await;
+
+	assert!(result.is_ok());
+	assert!(!elector.is_leader());
+	assert_eq!(dispatched.load(std::sync::atomic::Ordering::SeqCst), 0);
+}
+
 #[tokio::test]
 async fn test_process_new_blocks_storage_error() {
 	let network = create_test_network("Ethereum", "ethereum_mainnet", BlockChainType::EVM);
@@ -2723,3 +3061,184 @@ async fn test_duplicate_detection_in_cleanup_phase() {
 		"Process should succeed even with duplicate blocks in cleanup phase"
 	);
 }
+
+/// Mock client whose range fetch fails and whose per-block fetch fails for `failing_block`
+fn create_partially_failing_client(
+	latest_block: u64,
+	failing_block: Option<u64>,
+) -> MockEvmClientTrait<MockEVMTransportClient> {
+	let mut rpc_client = MockEvmClientTrait::<MockEVMTransportClient>::new();
+	rpc_client
+		.expect_get_latest_block_number()
+		.returning(move || Ok(latest_block));
+	rpc_client
+		.expect_get_blocks()
+		.returning(move |start, end| match end {
+			Some(_) => Err(anyhow::anyhow!("response too large")),
+			None if Some(start) == failing_block => Err(anyhow::anyhow!("block not found")),
+			None => Ok(vec![create_test_block(BlockChainType::EVM, start)]),
+		});
+	rpc_client
+}
+
+/// Block handler returning the number of the block it receives
+fn create_numbering_block_handler(
+) -> Arc<impl Fn(BlockType, Network) -> BoxFuture<'static, ProcessedBlock> + Send + Sync + 'static>
+{
+	Arc::new(|block: BlockType, network: Network| {
+		Box::pin(async move {
+			ProcessedBlock {
+				block_number: block.number().unwrap_or(0),
+				network_slug: network.slug,
+				processing_results: vec![],
+			}
+		}) as BoxFuture<'static, ProcessedBlock>
+	})
+}
+
+/// Trigger handler recording the numbers of the blocks it receives
+fn create_recording_trigger_handler(
+	triggered_blocks: Arc<std::sync::Mutex<Vec<u64>>>,
+) -> Arc<impl Fn(&ProcessedBlock) -> tokio::task::JoinHandle<()> + Send + Sync + 'static> {
+	Arc::new(move |block: &ProcessedBlock| {
+		triggered_blocks.lock().unwrap().push(block.block_number);
+		tokio::spawn(async {})
+	})
+}
+
+#[tokio::test]
+async fn test_process_new_blocks_skips_and_retries_failed_blocks() {
+	let temp_dir = tempfile::tempdir().unwrap();
+	let block_storage = Arc::new(FileBlockStorage::new(temp_dir.path().to_path_buf()));
+	let mut network = create_test_network("Test Network", "test-network", BlockChainType::EVM);
+	network.block_fetch = Some(BlockFetchConfig {
+		timeout_ms: 100,
+		max_retries: 1,
+		..Default::default()
+	});
+	block_storage
+		.save_last_processed_block(&network.slug, 100)
+		.await
+		.unwrap();
+
+	let block_handler = create_numbering_block_handler();
+	let triggered_blocks = Arc::new(std::sync::Mutex::new(Vec::new()));
+	let trigger_handler = create_recording_trigger_handler(triggered_blocks.clone());
+
+	// Blocks 101 to 105 are fetched one at a time after the range fetch fails, block 103 fails
+	let rpc_client = create_partially_failing_client(106, Some(103));
+	let result = process_new_blocks(
+		&network,
+		&rpc_client,
+		block_storage.clone(),
+		block_handler.clone(),
+		trigger_handler.clone(),
+		Arc::new(BlockTracker::new(10)),
+	)
+	.await;
+
+	assert!(result.is_ok(), "A failed block should not fail the run");
+	assert_eq!(
+		block_storage
+			.get_last_processed_block(&network.slug)
+			.await
+			.unwrap(),
+		Some(105)
+	);
+	let failed = block_storage
+		.get_failed_blocks(&network.slug)
+		.await
+		.unwrap();
+	assert_eq!(failed.len(), 1);
+	assert_eq!(failed[0].block_number, 103);
+	assert_eq!(failed[0].attempts, 2);
+	assert!(failed[0].error.contains("block not found"));
+	{
+		let mut triggered = triggered_blocks.lock().unwrap();
+		triggered.sort();
+		assert_eq!(*triggered, vec![101, 102, 104, 105]);
+		triggered.clear();
+	}
+
+	// Once the node serves the block again it is processed and removed from the store
+	let rpc_client = create_partially_failing_client(106, None);
+	let recovered = retry_failed_blocks(
+		&network,
+		&rpc_client,
+		&*block_storage,
+		&*block_handler,
+		&*trigger_handler,
+		None,
+	)
+	.await
+	.unwrap();
+
+	assert_eq!(recovered, 1);
+	assert!(block_storage
+		.get_failed_blocks(&network.slug)
+		.await
+		.unwrap()
+		.is_empty());
+	assert_eq!(*triggered_blocks.lock().unwrap(), vec![103]);
+}
+
+#[tokio::test]
+async fn test_process_new_blocks_does_not_advance_when_failed_store_is_full() {
+	let temp_dir = tempfile::tempdir().unwrap();
+	let block_storage = Arc::new(FileBlockStorage::new(temp_dir.path().to_path_buf()));
+	let mut network = create_test_network("Test Network", "test-network", BlockChainType::EVM);
+	network.block_fetch = Some(BlockFetchConfig {
+		timeout_ms: 100,
+		max_retries: 0,
+		max_failed_blocks: 1,
+		..Default::default()
+	});
+	block_storage
+		.save_last_processed_block(&network.slug, 100)
+		.await
+		.unwrap();
+	block_storage
+		.save_failed_blocks(
+			&network.slug,
+			&[FailedBlock {
+				block_number: 42,
+				error: "block not found".to_string(),
+				attempts: 1,
+				last_attempt_ms: 0,
+			}],
+		)
+		.await
+		.unwrap();
+
+	let block_handler = create_numbering_block_handler();
+	let triggered_blocks = Arc::new(std::sync::Mutex::new(Vec::new()));
+	let trigger_handler = create_recording_trigger_handler(triggered_blocks.clone());
+	let rpc_client = create_partially_failing_client(106, Some(103));
+	let result = process_new_blocks(
+		&network,
+		&rpc_client,
+		block_storage.clone(),
+		block_handler,
+		trigger_handler,
+		Arc::new(BlockTracker::new(10)),
+	)
+	.await;
+
+	assert!(result.is_err());
+	assert_eq!(
+		block_storage
+			.get_last_processed_block(&network.slug)
+			.await
+			.unwrap(),
+		Some(100)
+	);
+	assert_eq!(
+		block_storage
+			.get_failed_blocks(&network.slug)
+			.await
+			.unwrap()
+			.len(),
+		1
+	);
+	assert!(triggered_blocks.lock().unwrap().is_empty());
+}
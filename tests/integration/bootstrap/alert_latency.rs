@@ -0,0 +1,166 @@
+use std::{
+	collections::HashMap,
+	path::Path,
+	sync::{
+		atomic::{AtomicI64, Ordering},
+		Arc,
+	},
+};
+
+use chrono::{TimeZone, Utc};
+use mockito::{Matcher, Server};
+use openzeppelin_monitor::{
+	bootstrap::{create_trigger_handler, process_block},
+	models::{AlertTiming, EventCondition, Monitor, Network, ProcessedBlock},
+	repositories::{TriggerRepository, TriggerService},
+	services::{
+		blockchain::{BlockChainClient, FixtureClient},
+		filter::{DecodeFailures, FilterService},
+		notification::NotificationService,
+		trigger::{AlertLatency, LatencyClock, NetworkViews, TriggerExecutionService},
+	},
+	utils::{
+		metrics::{
+			ALERT_BLOCK_TO_FETCH_SECONDS, ALERT_FETCH_TO_DELIVERY_SECONDS,
+			ALERT_FETCH_TO_MATCH_SECONDS, ALERT_LATENCY_SECONDS, ALERT_MATCH_TO_DELIVERY_SECONDS,
+		},
+		tests::trigger::TriggerBuilder,
+	},
+};
+use tokio::sync::watch;
+
+use crate::integration::filters::common::read_and_parse_json;
+
+const RECORDED_FIXTURES_DIR: &str = "tests/integration/fixtures/recorded/ethereum_mainnet";
+const BLOCK_NUMBER: u64 = 21306058;
+// Network slug only used by this test, so that the histogram samples are its own
+const NETWORK_SLUG: &str = "ethereum_mainnet_alert_latency";
+
+/// Clock whose time, in milliseconds since the Unix epoch, is set by the test
+fn fake_clock() -> (Arc<AtomicI64>, LatencyClock) {
+	let now = Arc::new(AtomicI64::new(0));
+	let time = now.clone();
+	let clock: LatencyClock = Arc::new(move || {
+		Utc.timestamp_millis_opt(time.load(Ordering::SeqCst))
+			.unwrap()
+	});
+	(now, clock)
+}
+
+/// Monitor matching a single USDC transfer of the recorded block
+fn create_monitor() -> Monitor {
+	let mut monitor: Monitor =
+		read_and_parse_json("tests/integration/fixtures/evm/monitors/monitor.json");
+	monitor.networks = vec![NETWORK_SLUG.to_string()];
+	monitor.match_conditions.functions = vec![];
+	monitor.match_conditions.transactions = vec![];
+	monitor.match_conditions.events = vec![EventCondition {
+		signature: "Transfer(address,address,uint256)".to_string(),
+		expression: Some(
+			"to == 0xf423d9c1ffeb6386639d024f3b241dab2331b635 AND from == \
+			 0x58b704065b7aff3ed351052f8560019e05925023"
+				.to_string(),
+		),
+		match_any_emitter: false,
+	}];
+	monitor.triggers = vec!["latency_webhook".into()];
+	monitor
+}
+
+#[tokio::test]
+async fn test_alert_latency_of_a_delivered_notification() {
+	let client = FixtureClient::load(Path::new(RECORDED_FIXTURES_DIR)).unwrap();
+	let network = Network {
+		slug: NETWORK_SLUG.to_string(),
+		..FixtureClient::load_network(Path::new(RECORDED_FIXTURES_DIR)).unwrap()
+	};
+	let block = client
+		.get_blocks(BLOCK_NUMBER, None)
+		.await
+		.unwrap()
+		.remove(0);
+	let block_timestamp_ms = block.timestamp_ms().unwrap();
+
+	let (now, clock) = fake_clock();
+	let latency = AlertLatency::new().with_clock(clock);
+
+	// The block is fetched 3s after its timestamp and filtered in 500ms
+	now.store(block_timestamp_ms + 3_000, Ordering::SeqCst);
+	let fetched_at_ms = latency.now_ms();
+	let (_shutdown_tx, mut shutdown_rx) = watch::channel(false);
+	let matches = process_block(
+		&client,
+		&network,
+		&block,
+		&[create_monitor()],
+		None,
+		&FilterService::new(),
+		&mut shutdown_rx,
+	)
+	.await
+	.unwrap();
+	assert_eq!(matches.len(), 1);
+	now.store(block_timestamp_ms + 3_500, Ordering::SeqCst);
+	let mut processed_block = ProcessedBlock {
+		block_number: BLOCK_NUMBER,
+		network_slug: NETWORK_SLUG.to_string(),
+		processing_results: matches,
+	};
+	processed_block.set_timing(AlertTiming {
+		block_timestamp_ms: Some(block_timestamp_ms),
+		fetched_at_ms,
+		matched_at_ms: latency.now_ms(),
+	});
+
+	// The notification is delivered 1.5s after the match
+	let mut server = Server::new_async().await;
+	let mock = server
+		.mock("POST", "/")
+		.match_body(Matcher::PartialJson(serde_json::json!({
+			"body": "Delivered 5000ms after the block"
+		})))
+		.with_status(200)
+		.expect(1)
+		.create_async()
+		.await;
+	let trigger = TriggerBuilder::new()
+		.name("latency_webhook")
+		.webhook(&server.url())
+		.message(
+			"Transfer",
+			"Delivered ${alert_latency_ms}ms after the block",
+		)
+		.build();
+	let trigger_service = TriggerService::new_with_repository(TriggerRepository {
+		triggers: HashMap::from([("latency_webhook".to_string(), trigger)]),
+	})
+	.unwrap();
+	let trigger_execution_service =
+		TriggerExecutionService::new(trigger_service, NotificationService::new())
+			.with_latency(latency);
+	now.store(block_timestamp_ms + 5_000, Ordering::SeqCst);
+
+	let (shutdown_tx, _) = watch::channel(false);
+	let trigger_handler = create_trigger_handler(
+		shutdown_tx,
+		Arc::new(trigger_execution_service),
+		HashMap::new(),
+		Arc::new(NetworkViews::default()),
+		Arc::new(DecodeFailures::default()),
+	);
+	trigger_handler(&processed_block).await.unwrap();
+	mock.assert();
+
+	let labels = [NETWORK_SLUG, "webhook"];
+	for (histogram, seconds) in [
+		(&*ALERT_BLOCK_TO_FETCH_SECONDS, 3.0),
+		(&*ALERT_FETCH_TO_MATCH_SECONDS, 0.5),
+		(&*ALERT_MATCH_TO_DELIVERY_SECONDS, 1.5),
+		(&*ALERT_LATENCY_SECONDS, 5.0),
+		(&*ALERT_FETCH_TO_DELIVERY_SECONDS, 2.0),
+	] {
+		let histogram = histogram.with_label_values(&labels);
+		assert_eq!(histogram.get_sample_count(), 1);
+		assert_eq!(histogram.get_sample_sum(), seconds);
+	}
+}
@@ -0,0 +1,328 @@
+//! Resilience tests of the notification pipeline under injected faults.
+//!
+//! Failed deliveries are retried by the HTTP client and otherwise dropped, the monitor has no
+//! dead-letter queue. Triggers whose deliveries keep failing are suppressed by their circuit
+//! breaker until their endpoint recovers.
+
+use chrono::{TimeZone, Utc};
+use std::{
+	collections::HashMap,
+	sync::{
+		atomic::{AtomicI64, Ordering},
+		Arc,
+	},
+	time::Duration,
+};
+
+use openzeppelin_monitor::{
+	bootstrap::create_dispatch_queue,
+	models::TriggerTypeConfig,
+	repositories::{TriggerRepository, TriggerService},
+	services::{
+		notification::{NotificationService, WebhookConfig, WebhookNotifier},
+		state::InMemoryStateStore,
+		trigger::{
+			CircuitBreakerConfig, CircuitState, DedupedMatch, DispatchQueueConfig,
+			NotificationBudgets, TriggerCircuitBreakers, TriggerExecutionService,
+			TriggerExecutionServiceTrait,
+		},
+	},
+	utils::{
+		chaos::{Fault, FaultInjectionMiddleware, FaultRates},
+		retryable_http_client_builder,
+		tests::{
+			chaos::{ChaosBehavior, ChaosNotifier},
+			evm::{monitor::MonitorBuilder, monitor_match::MonitorMatchBuilder},
+			trigger::TriggerBuilder,
+		},
+		JitterSetting, RetryConfig,
+	},
+};
+use reqwest_retry::DefaultRetryableStrategy;
+use serde_json::json;
+
+fn create_match(monitor: &str) -> DedupedMatch {
+	MonitorMatchBuilder::new()
+		.monitor(
+			MonitorBuilder::new()
+				.name(monitor)
+				.triggers(vec!["webhook".to_string()])
+				.build(),
+		)
+		.build_deduped()
+}
+
+fn retry_config(max_retries: u32) -> RetryConfig {
+	RetryConfig {
+		max_retries,
+		initial_backoff: Duration::from_millis(1),
+		max_backoff: Duration::from_millis(5),
+		jitter: JitterSetting::None,
+		..Default::default()
+	}
+}
+
+fn create_webhook(
+	url: String,
+	middleware: FaultInjectionMiddleware,
+	max_retries: u32,
+) -> WebhookNotifier {
+	let client = retryable_http_client_builder::<DefaultRetryableStrategy>(
+		&retry_config(max_retries),
+		reqwest::Client::new(),
+		None,
+	)
+	.with(middleware)
+	.build();
+	WebhookNotifier::new(
+		WebhookConfig {
+			url,
+			url_params: None,
+			title: "Alert".to_string(),
+			body_template: "Test".to_string(),
+			method: None,
+			secret: None,
+			headers: None,
+			payload_fields: None,
+		},
+		Arc::new(client),
+	)
+	.unwrap()
+}
+
+/// Dispatches the matches through the queue with the notifier and returns once it is closed
+async fn dispatch<S: TriggerExecutionServiceTrait + Send + Sync + 'static>(
+	notifier: Arc<S>,
+	matches: Vec<DedupedMatch>,
+	drain_timeout: Duration,
+) {
+	let queue = create_dispatch_queue(
+		DispatchQueueConfig {
+			capacity: 100,
+			concurrency: 1,
+			drain_timeout,
+			..Default::default()
+		},
+		notifier,
+		HashMap::new(),
+		Arc::new(NotificationBudgets::new()),
+	);
+	for deduped in matches {
+		assert!(queue.enqueue(deduped).await);
+	}
+	queue.close().await;
+}
+
+#[tokio::test]
+async fn test_webhook_retries_injected_errors_within_max_retries() {
+	let mut server = mockito::Server::new_async().await;
+	let mock = server
+		.mock("POST", "/")
+		.with_status(200)
+		.expect(1)
+		.create_async()
+		.await;
+
+	let notifier = create_webhook(
+		server.url(),
+		FaultInjectionMiddleware::scripted([Fault::Status(503), Fault::Status(503)]),
+		2,
+	);
+	assert!(notifier
+		.notify_json(&json!({"text": "alert"}))
+		.await
+		.is_ok());
+	mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_webhook_gives_up_after_max_retries() {
+	let mut server = mockito::Server::new_async().await;
+	let mock = server
+		.mock("POST", "/")
+		.with_status(200)
+		.expect(0)
+		.create_async()
+		.await;
+
+	let notifier = create_webhook(
+		server.url(),
+		FaultInjectionMiddleware::scripted([Fault::Status(503); 3]),
+		2,
+	);
+	assert!(notifier
+		.notify_json(&json!({"text": "alert"}))
+		.await
+		.is_err());
+	mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_seeded_random_faults_are_reproducible() {
+	let mut server = mockito::Server::new_async().await;
+	let _mock = server
+		.mock("POST", "/")
+		.with_status(200)
+		.create_async()
+		.await;
+
+	let outcomes = |notifier: WebhookNotifier| async move {
+		let mut outcomes = Vec::new();
+		for _ in 0..20 {
+			outcomes.push(
+				notifier
+					.notify_json(&json!({"text": "alert"}))
+					.await
+					.is_ok(),
+			);
+		}
+		outcomes
+	};
+	let rates = FaultRates {
+		error_rate: 0.5,
+		seed: Some(11),
+		..Default::default()
+	};
+
+	let first = outcomes(create_webhook(
+		server.url(),
+		FaultInjectionMiddleware::random(rates.clone()),
+		0,
+	))
+	.await;
+	let second = outcomes(create_webhook(
+		server.url(),
+		FaultInjectionMiddleware::random(rates),
+		0,
+	))
+	.await;
+
+	assert_eq!(first, second);
+	assert!(first.contains(&true) && first.contains(&false));
+}
+
+#[tokio::test]
+async fn test_pipeline_survives_failing_and_panicking_notifier() {
+	let notifier = Arc::new(ChaosNotifier::new([
+		ChaosBehavior::FailRetryable,
+		ChaosBehavior::Panic,
+		ChaosBehavior::FailPermanent,
+	]));
+	let matches = (0..5)
+		.map(|i| create_match(&format!("monitor_{}", i)))
+		.collect();
+
+	dispatch(notifier.clone(), matches, Duration::from_secs(5)).await;
+
+	// Every match is attempted once, failed deliveries are not retried nor kept
+	let attempts = notifier.attempts();
+	assert_eq!(attempts.len(), 5);
+	assert_eq!(attempts[1], ("monitor_1".to_string(), ChaosBehavior::Panic));
+	assert_eq!(notifier.deliveries().len(), 2);
+}
+
+#[tokio::test]
+async fn test_shutdown_drains_delayed_deliveries() {
+	let notifier =
+		Arc::new(ChaosNotifier::from_config(r#"["delay(50)", "delay(50)", "delay(50)"]"#).unwrap());
+	let matches = (0..3).map(|_| create_match("monitor")).collect();
+
+	dispatch(notifier.clone(), matches, Duration::from_secs(5)).await;
+
+	assert_eq!(notifier.deliveries().len(), 3);
+}
+
+#[tokio::test]
+async fn test_shutdown_abandons_deliveries_past_drain_timeout() {
+	let notifier = Arc::new(ChaosNotifier::new([
+		ChaosBehavior::Delay(Duration::from_secs(5)),
+		ChaosBehavior::Delay(Duration::from_secs(5)),
+	]));
+	let matches = (0..2).map(|_| create_match("monitor")).collect();
+
+	tokio::time::timeout(
+		Duration::from_secs(2),
+		dispatch(notifier.clone(), matches, Duration::from_millis(100)),
+	)
+	.await
+	.expect("close should return after the drain timeout");
+
+	assert_eq!(notifier.attempts().len(), 1);
+	assert!(notifier.deliveries().is_empty());
+}
+
+#[tokio::test]
+async fn test_circuit_breaker_skips_deliveries_during_an_outage() {
+	const BACKOFF_MS: i64 = 60_000;
+
+	let mut server = mockito::Server::new_async().await;
+	let outage = server
+		.mock("POST", "/")
+		.with_status(503)
+		.expect(2)
+		.create_async()
+		.await;
+
+	let mut trigger = TriggerBuilder::new()
+		.name("webhook")
+		.webhook(&server.url())
+		.build();
+	if let TriggerTypeConfig::Webhook { retry_policy, .. } = &mut trigger.config {
+		retry_policy.max_retries = 0;
+	}
+	let trigger_service = TriggerService::new_with_repository(TriggerRepository {
+		triggers: HashMap::from([("webhook".to_string(), trigger)]),
+	})
+	.unwrap();
+	let now = Arc::new(AtomicI64::new(Utc::now().timestamp_millis()));
+	let opened_at = now.load(Ordering::SeqCst);
+	let time = now.clone();
+	let circuit_breakers = Arc::new(
+		TriggerCircuitBreakers::new(
+			Arc::new(InMemoryStateStore::new()),
+			CircuitBreakerConfig {
+				failure_threshold: 2,
+				backoff: Duration::from_millis(BACKOFF_MS as u64),
+				..Default::default()
+			},
+		)
+		.with_clock(Arc::new(move || {
+			Utc.timestamp_millis_opt(time.load(Ordering::SeqCst))
+				.unwrap()
+		})),
+	);
+	let service = Arc::new(
+		TriggerExecutionService::new(trigger_service, NotificationService::new())
+			.with_circuit_breakers(circuit_breakers.clone()),
+	);
+
+	// Two failed deliveries open the breaker, and the following ones skip the endpoint
+	let matches = (0..5)
+		.map(|i| create_match(&format!("monitor_{}", i)))
+		.collect();
+	dispatch(service.clone(), matches, Duration::from_secs(5)).await;
+	outage.assert_async().await;
+	assert_eq!(
+		circuit_breakers.circuit("webhook").await.state,
+		CircuitState::Open
+	);
+
+	// Once the backoff has passed, deliveries resume against the recovered endpoint
+	outage.remove_async().await;
+	let recovered = server
+		.mock("POST", "/")
+		.with_status(200)
+		.expect(3)
+		.create_async()
+		.await;
+	now.store(opened_at + BACKOFF_MS, Ordering::SeqCst);
+	let matches = (0..3)
+		.map(|i| create_match(&format!("monitor_{}", i)))
+		.collect();
+	dispatch(service, matches, Duration::from_secs(5)).await;
+	recovered.assert_async().await;
+	assert_eq!(
+		circuit_breakers.circuit("webhook").await.state,
+		CircuitState::Closed
+	);
+}
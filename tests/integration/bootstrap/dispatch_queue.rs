@@ -0,0 +1,135 @@
+use std::{
+	sync::{
+		atomic::{AtomicUsize, Ordering},
+		Arc,
+	},
+	time::Duration,
+};
+
+use openzeppelin_monitor::{
+	services::trigger::{
+		DedupedMatch, DispatchFn, DispatchQueue, DispatchQueueConfig, OverflowPolicy,
+	},
+	utils::tests::evm::{monitor::MonitorBuilder, monitor_match::MonitorMatchBuilder},
+};
+use tokio::sync::Notify;
+
+const CAPACITY: usize = 5;
+const MATCHES: usize = 50;
+
+fn create_match(monitor: &str) -> DedupedMatch {
+	MonitorMatchBuilder::new()
+		.monitor(MonitorBuilder::new().name(monitor).build())
+		.build_deduped()
+}
+
+/// Dispatch taking `delay` per match and counting deliveries
+fn create_slow_dispatch(delay: Duration, delivered: Arc<AtomicUsize>) -> DispatchFn {
+	Arc::new(move |_| {
+		let delivered = delivered.clone();
+		Box::pin(async move {
+			tokio::time::sleep(delay).await;
+			delivered.fetch_add(1, Ordering::SeqCst);
+		})
+	})
+}
+
+#[tokio::test]
+async fn test_flood_stays_within_capacity_and_blocks_producer() {
+	let delivered = Arc::new(AtomicUsize::new(0));
+	let enqueued = Arc::new(AtomicUsize::new(0));
+	let queue = DispatchQueue::start(
+		DispatchQueueConfig {
+			capacity: CAPACITY,
+			overflow: OverflowPolicy::Block,
+			concurrency: 1,
+			..Default::default()
+		},
+		create_slow_dispatch(Duration::from_millis(10), delivered.clone()),
+	);
+
+	let producer = tokio::spawn({
+		let queue = queue.clone();
+		let enqueued = enqueued.clone();
+		async move {
+			for _ in 0..MATCHES {
+				assert!(queue.enqueue(create_match("flood")).await);
+				enqueued.fetch_add(1, Ordering::SeqCst);
+			}
+		}
+	});
+
+	// The producer never gets further ahead of the dispatch than the queue capacity plus the
+	// match being dispatched
+	let mut max_depth = 0;
+	while !producer.is_finished() {
+		let depth = queue.len();
+		max_depth = max_depth.max(depth);
+		assert!(depth <= CAPACITY);
+		assert!(enqueued.load(Ordering::SeqCst) <= delivered.load(Ordering::SeqCst) + CAPACITY + 1);
+		tokio::time::sleep(Duration::from_millis(2)).await;
+	}
+	producer.await.unwrap();
+	assert!(max_depth > 0);
+
+	queue.close().await;
+	assert_eq!(delivered.load(Ordering::SeqCst), MATCHES);
+	assert!(queue.is_empty());
+	assert!(queue.shed_summary().is_empty());
+}
+
+#[tokio::test]
+async fn test_shed_policy_drops_overflow_and_summarizes_per_monitor() {
+	let started = Arc::new(Notify::new());
+	let release = Arc::new(Notify::new());
+	let delivered = Arc::new(AtomicUsize::new(0));
+	let queue = DispatchQueue::start(
+		DispatchQueueConfig {
+			capacity: 2,
+			overflow: OverflowPolicy::Shed,
+			concurrency: 1,
+			..Default::default()
+		},
+		{
+			let started = started.clone();
+			let release = release.clone();
+			let delivered = delivered.clone();
+			Arc::new(move |_| {
+				let started = started.clone();
+				let release = release.clone();
+				let delivered = delivered.clone();
+				Box::pin(async move {
+					started.notify_one();
+					release.notified().await;
+					delivered.fetch_add(1, Ordering::SeqCst);
+				})
+			})
+		},
+	);
+
+	// Hold the worker on a first match so that the queue fills up
+	assert!(queue.enqueue(create_match("busy")).await);
+	started.notified().await;
+
+	let mut accepted = 0;
+	for _ in 0..9 {
+		if queue.enqueue(create_match("busy")).await {
+			accepted += 1;
+		}
+	}
+	assert_eq!(accepted, 2);
+	assert!(!queue.enqueue(create_match("other")).await);
+	assert_eq!(queue.len(), 2);
+
+	let summary = queue.shed_summary();
+	assert_eq!(summary.get("busy"), Some(&7));
+	assert_eq!(summary.get("other"), Some(&1));
+
+	// Let the queued matches through
+	for _ in 0..3 {
+		release.notify_one();
+		tokio::time::sleep(Duration::from_millis(10)).await;
+	}
+	queue.close().await;
+	assert_eq!(delivered.load(Ordering::SeqCst), 3);
+}
@@ -0,0 +1,173 @@
+use std::{
+	collections::HashMap,
+	sync::{Arc, Mutex},
+	time::Duration,
+};
+
+use openzeppelin_monitor::{
+	bootstrap::create_dispatch_queue,
+	models::{Enrichment, HttpEnrichment},
+	services::trigger::{DedupedMatch, DispatchQueueConfig, NotificationBudgets},
+	utils::{
+		metrics::ENRICHMENT_FAILURES,
+		tests::evm::{monitor::MonitorBuilder, monitor_match::MonitorMatchBuilder},
+	},
+};
+
+use crate::integration::mocks::{MockTriggerExecutionService, MockTriggerRepository};
+
+type Deliveries = Arc<Mutex<Vec<HashMap<String, String>>>>;
+
+fn http_enrichment(url: String) -> HttpEnrichment {
+	HttpEnrichment {
+		name: "customer".to_string(),
+		url,
+		variables: HashMap::from([
+			("customer_id".to_string(), "/customer/id".to_string()),
+			("risk_score".to_string(), "/risk/score".to_string()),
+		]),
+		timeout_ms: 1_000,
+		cache_ttl_secs: None,
+		required: false,
+	}
+}
+
+fn create_match(monitor_name: &str, enrichment: HttpEnrichment) -> DedupedMatch {
+	MonitorMatchBuilder::new()
+		.monitor(
+			MonitorBuilder::new()
+				.name(monitor_name)
+				.triggers(vec!["slack".to_string()])
+				.enrichment(Enrichment::Http(enrichment))
+				.build(),
+		)
+		.build_deduped()
+}
+
+/// Dispatches the matches and returns the variables delivered to the triggers
+async fn dispatch(matches: Vec<DedupedMatch>) -> Vec<HashMap<String, String>> {
+	let deliveries: Deliveries = Arc::new(Mutex::new(Vec::new()));
+	let mut trigger_service = MockTriggerExecutionService::<MockTriggerRepository>::default();
+	let recorded = deliveries.clone();
+	trigger_service
+		.expect_execute()
+		.returning(move |_, variables, _, _| {
+			recorded.lock().unwrap().push(variables);
+			Ok(())
+		});
+
+	let queue = create_dispatch_queue(
+		DispatchQueueConfig {
+			capacity: 100,
+			concurrency: 1,
+			..Default::default()
+		},
+		Arc::new(trigger_service),
+		Default::default(),
+		Arc::new(NotificationBudgets::new()),
+	);
+	for deduped in matches {
+		assert!(queue.enqueue(deduped).await);
+	}
+	queue.close().await;
+
+	let deliveries = deliveries.lock().unwrap().clone();
+	deliveries
+}
+
+fn failures(monitor_name: &str) -> u64 {
+	ENRICHMENT_FAILURES
+		.with_label_values(&[monitor_name, "", "customer"])
+		.get()
+}
+
+#[tokio::test]
+async fn test_http_enrichment_adds_variables() {
+	let mut server = mockito::Server::new_async().await;
+	let mock = server
+		.mock(
+			"GET",
+			mockito::Matcher::Regex(r"^/addresses/0x[0-9a-f]{40}$".to_string()),
+		)
+		.with_status(200)
+		.with_header("content-type", "application/json")
+		.with_body(r#"{"customer": {"id": "c-42"}, "risk": {"score": 7}}"#)
+		.create_async()
+		.await;
+
+	let enrichment = http_enrichment(format!("{}/addresses/${{transaction.from}}", server.url()));
+	let deliveries = dispatch(vec![create_match("enriched", enrichment)]).await;
+
+	mock.assert_async().await;
+	assert_eq!(deliveries.len(), 1);
+	assert_eq!(deliveries[0].get("customer_id").unwrap(), "c-42");
+	assert_eq!(deliveries[0].get("risk_score").unwrap(), "7");
+	// Variables of the match are still delivered
+	assert!(deliveries[0].contains_key("transaction.hash"));
+}
+
+#[tokio::test]
+async fn test_http_enrichment_timeout_leaves_variables_unset() {
+	let mut server = mockito::Server::new_async().await;
+	let _mock = server
+		.mock("GET", "/slow")
+		.with_status(200)
+		.with_chunked_body(|w| {
+			std::thread::sleep(Duration::from_millis(500));
+			w.write_all(br#"{"customer": {"id": "c-42"}, "risk": {"score": 7}}"#)
+		})
+		.create_async()
+		.await;
+
+	let mut enrichment = http_enrichment(format!("{}/slow", server.url()));
+	enrichment.timeout_ms = 100;
+	let deliveries = dispatch(vec![create_match("slow_enrichment", enrichment)]).await;
+
+	assert_eq!(deliveries.len(), 1);
+	assert!(!deliveries[0].contains_key("customer_id"));
+	assert!(deliveries[0].contains_key("transaction.hash"));
+	assert_eq!(failures("slow_enrichment"), 1);
+}
+
+#[tokio::test]
+async fn test_http_enrichment_results_are_cached() {
+	let mut server = mockito::Server::new_async().await;
+	let mock = server
+		.mock("GET", "/prices")
+		.with_status(200)
+		.with_body(r#"{"customer": {"id": "c-42"}, "risk": {"score": 7}}"#)
+		.expect(1)
+		.create_async()
+		.await;
+
+	let mut enrichment = http_enrichment(format!("{}/prices", server.url()));
+	enrichment.cache_ttl_secs = Some(60);
+	let deliveries = dispatch(vec![
+		create_match("cached_enrichment", enrichment.clone()),
+		create_match("cached_enrichment", enrichment),
+	])
+	.await;
+
+	mock.assert_async().await;
+	assert_eq!(deliveries.len(), 2);
+	for delivery in deliveries {
+		assert_eq!(delivery.get("customer_id").unwrap(), "c-42");
+	}
+}
+
+#[tokio::test]
+async fn test_failed_required_enrichment_blocks_delivery() {
+	let mut server = mockito::Server::new_async().await;
+	let _mock = server
+		.mock("GET", "/down")
+		.with_status(503)
+		.create_async()
+		.await;
+
+	let mut enrichment = http_enrichment(format!("{}/down", server.url()));
+	enrichment.required = true;
+	let deliveries = dispatch(vec![create_match("required_enrichment", enrichment)]).await;
+
+	assert!(deliveries.is_empty());
+	assert_eq!(failures("required_enrichment"), 1);
+}
@@ -20,16 +20,19 @@ use openzeppelin_monitor::{
 		EVMTransactionReceipt, MatchConditions, Monitor, MonitorMatch, ProcessedBlock,
 		ScriptLanguage, SecretString, SecretValue, StellarBlock, StellarContractSpec,
 		StellarFormattedContractSpec, StellarMonitorMatch, TransactionType, Trigger,
-		TriggerConditions,
+		TriggerConditions, TriggerOverride, TriggerReference,
 	},
 	services::{
-		filter::{stellar_helpers::are_same_address, FilterService},
+		filter::{stellar_helpers::are_same_address, DecodeFailures, FilterService},
 		notification::NotificationService,
-		trigger::{TriggerExecutionService, TriggerExecutionServiceTrait},
+		trigger::{NetworkViews, TriggerExecutionService, TriggerExecutionServiceTrait},
 	},
 	utils::{
 		tests::{
-			evm::{monitor::MonitorBuilder, transaction::TransactionBuilder},
+			evm::{
+				monitor::MonitorBuilder, monitor_match::MonitorMatchBuilder,
+				transaction::TransactionBuilder,
+			},
 			trigger::TriggerBuilder,
 		},
 		RetryConfig,
@@ -40,8 +43,12 @@ use stellar_xdr::curr::{
 	ScSpecEntry, ScSpecFunctionInputV0, ScSpecFunctionV0, ScSpecTypeDef, ScSymbol, StringM,
 };
 
+use alloy::primitives::B256;
 use serde_json::json;
-use std::{collections::HashMap, sync::Arc};
+use std::{
+	collections::HashMap,
+	sync::{Arc, Mutex},
+};
 use tokio::sync::watch;
 
 fn create_test_monitor(
@@ -76,6 +83,11 @@ fn create_test_monitor_match(chain: BlockChainType) -> MonitorMatch {
 			logs: Some(vec![]),
 			matched_on: MatchConditions::default(),
 			matched_on_args: None,
+			sender_activity: None,
+			baseline: None,
+			network_metrics: None,
+			timing: None,
+			origin: Default::default(),
 		})),
 		BlockChainType::Stellar => MonitorMatch::Stellar(Box::new(StellarMonitorMatch {
 			monitor: create_test_monitor("test", vec!["stellar_mainnet"], false, vec![]),
@@ -87,6 +99,9 @@ fn create_test_monitor_match(chain: BlockChainType) -> MonitorMatch {
 			ledger: StellarBlock::default(),
 			matched_on: MatchConditions::default(),
 			matched_on_args: None,
+			network_metrics: None,
+			timing: None,
+			origin: Default::default(),
 		})),
 		_ => panic!("Unsupported chain"),
 	}
@@ -138,6 +153,9 @@ async fn test_initialize_services() {
 		Some(mock_monitor_service),
 		Some(mock_network_service),
 		Some(mock_trigger_service),
+		None,
+		None,
+		None,
 	)
 	.await
 	.expect("Failed to initialize services");
@@ -154,8 +172,7 @@ async fn test_initialize_services() {
 	assert!(active_monitors.iter().any(|m| {
 		m.name == "test"
 			&& m.networks.contains(&"ethereum_mainnet".to_string())
-			&& m.triggers
-				.contains(&"evm_large_transfer_usdc_slack".to_string())
+			&& m.triggers.contains(&"evm_large_transfer_usdc_slack".into())
 	}));
 	assert!(networks.contains_key("ethereum_mainnet"));
 
@@ -197,7 +214,7 @@ async fn test_create_block_handler_evm() {
 	let block_handler = create_block_handler::<MockClientPool>(
 		shutdown_tx,
 		filter_service,
-		monitors,
+		monitors.into_iter().map(Arc::new).collect(),
 		client_pool,
 		contract_specs,
 	);
@@ -233,6 +250,8 @@ async fn test_create_trigger_handler() {
 		shutdown_tx,
 		Arc::new(trigger_execution_service),
 		HashMap::new(),
+		Arc::new(NetworkViews::default()),
+		Arc::new(DecodeFailures::default()),
 	);
 
 	assert!(Arc::strong_count(&trigger_handler) == 1);
@@ -249,6 +268,171 @@ async fn test_create_trigger_handler() {
 		.expect("Trigger handler task should complete successfully");
 }
 
+#[tokio::test]
+async fn test_create_trigger_handler_dedup_group() {
+	let mut trigger_execution_service =
+		MockTriggerExecutionService::<MockTriggerRepository>::default();
+	trigger_execution_service
+		.expect_get_trigger()
+		.returning(|slug| {
+			Some(Arc::new(
+				TriggerBuilder::new().name(slug).group_dedup(true).build(),
+			))
+		});
+	trigger_execution_service
+		.expect_execute()
+		.withf(|trigger_slugs, variables, monitor_match, _| {
+			let MonitorMatch::EVM(evm_match) = monitor_match else {
+				return false;
+			};
+			evm_match.monitor.name == "broad"
+				&& trigger_slugs == ["slack"]
+				&& variables.get("also_matched.0") == Some(&"narrow".to_string())
+		})
+		.times(1)
+		.returning(|_, _, _, _| Ok(()));
+
+	let (shutdown_tx, _) = watch::channel(false);
+	let trigger_handler = create_trigger_handler(
+		shutdown_tx,
+		Arc::new(trigger_execution_service),
+		HashMap::new(),
+		Arc::new(NetworkViews::default()),
+		Arc::new(DecodeFailures::default()),
+	);
+
+	// Two overlapping monitors matching the same transaction
+	let create_match = |name: &str| {
+		MonitorMatchBuilder::new()
+			.monitor(
+				MonitorBuilder::new()
+					.name(name)
+					.networks(vec!["ethereum_mainnet".to_string()])
+					.triggers(vec!["slack".to_string()])
+					.dedup_group("treasury")
+					.build(),
+			)
+			.receipt(EVMTransactionReceipt::default())
+			.logs(vec![])
+			.build()
+	};
+
+	let processed_block = ProcessedBlock {
+		block_number: 100,
+		network_slug: "ethereum_mainnet".to_string(),
+		processing_results: vec![create_match("broad"), create_match("narrow")],
+	};
+
+	let handle = trigger_handler(&processed_block);
+	handle
+		.await
+		.expect("Trigger handler task should complete successfully");
+}
+
+#[tokio::test]
+async fn test_create_trigger_handler_rollup_group() {
+	let mut trigger_execution_service =
+		MockTriggerExecutionService::<MockTriggerRepository>::default();
+	trigger_execution_service
+		.expect_get_trigger()
+		.returning(|slug| {
+			Some(Arc::new(
+				TriggerBuilder::new()
+					.name(slug)
+					.rollup(slug == "slack")
+					.build(),
+			))
+		});
+	let deliveries = Arc::new(Mutex::new(Vec::new()));
+	let recorded = deliveries.clone();
+	trigger_execution_service.expect_execute().returning(
+		move |trigger_slugs, variables, monitor_match, _| {
+			recorded.lock().unwrap().push((
+				trigger_slugs.to_vec(),
+				monitor_match.monitor().name.clone(),
+				variables.get("rollup.count").cloned(),
+				variables.get("severity").cloned(),
+			));
+			Ok(())
+		},
+	);
+
+	let (shutdown_tx, _) = watch::channel(false);
+	let trigger_handler = create_trigger_handler(
+		shutdown_tx,
+		Arc::new(trigger_execution_service),
+		HashMap::new(),
+		Arc::new(NetworkViews::default()),
+		Arc::new(DecodeFailures::default()),
+	);
+
+	// Protocol health monitors firing together, and an unrelated monitor
+	let create_match = |name: &str, group: Option<&str>, severity: &str, hash: u8| {
+		let mut monitor = MonitorBuilder::new()
+			.name(name)
+			.networks(vec!["ethereum_mainnet".to_string()])
+			.trigger_reference(TriggerReference::WithOverrides(TriggerOverride {
+				name: "slack".to_string(),
+				message: None,
+				variables: HashMap::from([("severity".to_string(), severity.to_string())]),
+				rollup: false,
+			}))
+			.trigger_reference(TriggerReference::Name("database".to_string()));
+		if let Some(group) = group {
+			monitor = monitor.rollup_group(group);
+		}
+		MonitorMatchBuilder::new()
+			.monitor(monitor.build())
+			.transaction(
+				TransactionBuilder::new()
+					.hash(B256::with_last_byte(hash))
+					.build(),
+			)
+			.receipt(EVMTransactionReceipt::default())
+			.logs(vec![])
+			.build()
+	};
+
+	let processed_block = ProcessedBlock {
+		block_number: 100,
+		network_slug: "ethereum_mainnet".to_string(),
+		processing_results: vec![
+			create_match("pause_events", Some("protocol_health"), "low", 1),
+			create_match("admin_changes", Some("protocol_health"), "critical", 1),
+			create_match("large_transfers", None, "medium", 2),
+			create_match("oracle_updates", Some("protocol_health"), "high", 3),
+		],
+	};
+
+	let handle = trigger_handler(&processed_block);
+	handle
+		.await
+		.expect("Trigger handler task should complete successfully");
+
+	let deliveries = deliveries.lock().unwrap();
+	let rollups = deliveries
+		.iter()
+		.filter(|(_, _, count, _)| count.is_some())
+		.collect::<Vec<_>>();
+	assert_eq!(rollups.len(), 1);
+	assert_eq!(rollups[0].0, vec!["slack".to_string()]);
+	assert_eq!(rollups[0].2.as_deref(), Some("3"));
+	assert_eq!(rollups[0].3.as_deref(), Some("critical"));
+
+	let normal = deliveries
+		.iter()
+		.filter(|(slugs, _, count, _)| count.is_none() && slugs.contains(&"slack".to_string()))
+		.collect::<Vec<_>>();
+	assert_eq!(normal.len(), 1);
+	assert_eq!(normal[0].1, "large_transfers");
+
+	let database = deliveries
+		.iter()
+		.filter(|(slugs, _, _, _)| slugs.contains(&"database".to_string()))
+		.count();
+	assert_eq!(database, 4);
+}
+
 #[tokio::test]
 async fn test_create_trigger_handler_empty_matches() {
 	// Setup test triggers in JSON with known configurations
@@ -261,6 +445,8 @@ async fn test_create_trigger_handler_empty_matches() {
 		shutdown_tx,
 		Arc::new(trigger_execution_service),
 		HashMap::new(),
+		Arc::new(NetworkViews::default()),
+		Arc::new(DecodeFailures::default()),
 	);
 
 	assert!(Arc::strong_count(&trigger_handler) == 1);
@@ -350,7 +536,7 @@ async fn test_create_block_handler_stellar() {
 	let block_handler = create_block_handler::<MockClientPool>(
 		shutdown_tx,
 		filter_service,
-		monitors,
+		monitors.into_iter().map(Arc::new).collect(),
 		Arc::new(handle_block_client_pool),
 		contract_specs,
 	);
@@ -391,7 +577,7 @@ async fn test_create_block_handler_evm_client_error() {
 	let block_handler = create_block_handler::<MockClientPool>(
 		shutdown_tx,
 		filter_service,
-		monitors,
+		monitors.into_iter().map(Arc::new).collect(),
 		client_pool,
 		contract_specs,
 	);
@@ -430,7 +616,7 @@ async fn test_create_block_handler_stellar_client_error() {
 	let block_handler = create_block_handler::<MockClientPool>(
 		shutdown_tx,
 		filter_service,
-		monitors,
+		monitors.into_iter().map(Arc::new).collect(),
 		client_pool,
 		contract_specs,
 	);
@@ -483,6 +669,8 @@ print(True)  # Always return true for test
 		shutdown_tx,
 		Arc::new(trigger_execution_service),
 		trigger_scripts,
+		Arc::new(NetworkViews::default()),
+		Arc::new(DecodeFailures::default()),
 	);
 
 	assert!(Arc::strong_count(&trigger_handler) == 1);
@@ -507,6 +695,11 @@ print(True)  # Always return true for test
 			network_slug: "ethereum_mainnet".to_string(),
 			matched_on: MatchConditions::default(),
 			matched_on_args: None,
+			sender_activity: None,
+			baseline: None,
+			network_metrics: None,
+			timing: None,
+			origin: Default::default(),
 		}))],
 	};
 
@@ -1213,6 +1406,7 @@ async fn test_get_contract_specs() {
 	let mut monitor = create_test_monitor("test", vec!["ethereum_mainnet"], false, vec![]);
 	monitor.addresses.push(AddressWithSpec {
 		address: "0x1234567890123456789012345678901234567890".to_string(),
+		contract: None,
 		contract_spec: Some(ContractSpec::EVM(EVMContractSpec::from(
 			serde_json::json!([{
 				"type": "function",
@@ -1239,11 +1433,16 @@ async fn test_get_contract_specs() {
 				"stateMutability": "nonpayable"
 			}]),
 		))),
+		label: None,
+		annotations: HashMap::new(),
 	});
 
 	monitor.addresses.push(AddressWithSpec {
 		address: "0x1234567890123456789012345678901234567890".to_string(),
 		contract_spec: None,
+		contract: None,
+		label: None,
+		annotations: HashMap::new(),
 	});
 
 	let monitors = vec![monitor];
@@ -1309,6 +1508,7 @@ async fn test_get_contract_specs() {
 
 	stellar_monitor.addresses.push(AddressWithSpec {
 		address: "GABCDEFGHIJKLMNOPQRSTUVWXYZ1234567890".to_string(),
+		contract: None,
 		contract_spec: Some(ContractSpec::Stellar(StellarContractSpec::from(vec![
 			ScSpecEntry::FunctionV0(ScSpecFunctionV0 {
 				doc: StringM::<1024>::from_str("").unwrap(),
@@ -1330,12 +1530,17 @@ async fn test_get_contract_specs() {
 				outputs: vec![ScSpecTypeDef::Bool].try_into().unwrap(),
 			}),
 		]) as StellarContractSpec)),
+		label: None,
+		annotations: HashMap::new(),
 	});
 
 	// Add an address without a contract spec to test fetching from chain
 	stellar_monitor.addresses.push(AddressWithSpec {
 		address: "GZYXWVUTSRQPONMLKJIHGFEDCBA0987654321".to_string(),
 		contract_spec: None,
+		contract: None,
+		label: None,
+		annotations: HashMap::new(),
 	});
 
 	let network_monitors = vec![(network, vec![stellar_monitor])];
@@ -0,0 +1,237 @@
+use std::{collections::HashMap, path::Path, sync::Arc};
+
+use openzeppelin_monitor::{
+	bootstrap::{create_trigger_handler, process_block},
+	models::{
+		BlockType, EventCondition, Monitor, MonitorMatch, Network, NetworkViewsConfig,
+		ProcessedBlock,
+	},
+	services::{
+		blockchain::{BlockChainClient, FixtureClient},
+		filter::{DecodeFailures, FilterService},
+		trigger::NetworkViews,
+	},
+	utils::metrics::{NETWORK_VIEW_DIVERGENCES, NETWORK_VIEW_DUPLICATES},
+};
+use tokio::sync::watch;
+
+use crate::integration::{
+	filters::common::read_and_parse_json,
+	mocks::{MockTriggerExecutionService, MockTriggerRepository},
+};
+
+const RECORDED_FIXTURES_DIR: &str = "tests/integration/fixtures/recorded/ethereum_mainnet";
+const BLOCK_NUMBER: u64 = 21306058;
+
+/// Two views of the recorded chain, in a group only used by the calling test
+struct Views {
+	internal: Network,
+	public: Network,
+	client: FixtureClient,
+}
+
+impl Views {
+	fn new(group: &str) -> Self {
+		let network = FixtureClient::load_network(Path::new(RECORDED_FIXTURES_DIR)).unwrap();
+		let view = |name: &str| Network {
+			slug: format!("{}_{}", group, name),
+			group: Some(group.to_string()),
+			..network.clone()
+		};
+		Self {
+			internal: view("internal"),
+			public: view("public"),
+			client: FixtureClient::load(Path::new(RECORDED_FIXTURES_DIR)).unwrap(),
+		}
+	}
+
+	fn network_views(&self) -> Arc<NetworkViews> {
+		Arc::new(NetworkViews::new([&self.internal, &self.public]))
+	}
+
+	/// Monitor watching both views, matching a single USDC transfer of the recorded block
+	fn create_monitor(&self, network_views: Option<NetworkViewsConfig>) -> Monitor {
+		let mut monitor: Monitor =
+			read_and_parse_json("tests/integration/fixtures/evm/monitors/monitor.json");
+		monitor.networks = vec![self.internal.slug.clone(), self.public.slug.clone()];
+		monitor.match_conditions.functions = vec![];
+		monitor.match_conditions.transactions = vec![];
+		monitor.match_conditions.events = vec![EventCondition {
+			signature: "Transfer(address,address,uint256)".to_string(),
+			expression: Some(
+				"to == 0xf423d9c1ffeb6386639d024f3b241dab2331b635 AND from == \
+				 0x58b704065b7aff3ed351052f8560019e05925023"
+					.to_string(),
+			),
+			match_any_emitter: false,
+		}];
+		monitor.triggers = vec!["views_slack".into()];
+		monitor.network_views = network_views;
+		monitor
+	}
+
+	/// Processes the recorded block as seen by a view
+	async fn process(
+		&self,
+		network: &Network,
+		block: &BlockType,
+		monitor: &Monitor,
+	) -> ProcessedBlock {
+		let (_shutdown_tx, mut shutdown_rx) = watch::channel(false);
+		let matches = process_block(
+			&self.client,
+			network,
+			block,
+			std::slice::from_ref(monitor),
+			None,
+			&FilterService::new(),
+			&mut shutdown_rx,
+		)
+		.await
+		.unwrap();
+		ProcessedBlock {
+			block_number: BLOCK_NUMBER,
+			network_slug: network.slug.clone(),
+			processing_results: matches,
+		}
+	}
+
+	/// Returns the recorded block and the same block with the matched transaction omitted
+	async fn blocks(&self, monitor: &Monitor) -> (BlockType, BlockType) {
+		let block = self
+			.client
+			.get_blocks(BLOCK_NUMBER, None)
+			.await
+			.unwrap()
+			.remove(0);
+		let processed = self.process(&self.internal, &block, monitor).await;
+		assert_eq!(processed.processing_results.len(), 1);
+		let MonitorMatch::EVM(evm_match) = &processed.processing_results[0] else {
+			panic!("Expected an EVM match");
+		};
+		let matched_hash = *evm_match.transaction.hash();
+
+		let mut omitted = block.clone();
+		if let BlockType::EVM(evm_block) = &mut omitted {
+			Arc::make_mut(evm_block)
+				.0
+				.transactions
+				.retain(|transaction| *transaction.hash() != matched_hash);
+		}
+		(block, omitted)
+	}
+}
+
+fn empty_block(network: &Network, block_number: u64) -> ProcessedBlock {
+	ProcessedBlock {
+		block_number,
+		network_slug: network.slug.clone(),
+		processing_results: vec![],
+	}
+}
+
+#[tokio::test]
+async fn test_match_seen_by_both_views_is_delivered_once() {
+	let views = Views::new("views_dedup");
+	let monitor = views.create_monitor(None);
+	let (block, _) = views.blocks(&monitor).await;
+
+	let internal_slug = views.internal.slug.clone();
+	let mut trigger_service = MockTriggerExecutionService::<MockTriggerRepository>::default();
+	trigger_service
+		.expect_execute()
+		.withf(move |trigger_slugs, _, monitor_match, _| {
+			trigger_slugs == ["views_slack"] && monitor_match.network_slug() == internal_slug
+		})
+		.times(1)
+		.returning(|_, _, _, _| Ok(()));
+
+	let (shutdown_tx, _) = watch::channel(false);
+	let trigger_handler = create_trigger_handler(
+		shutdown_tx,
+		Arc::new(trigger_service),
+		HashMap::new(),
+		views.network_views(),
+		Arc::new(DecodeFailures::default()),
+	);
+
+	let internal_block = views.process(&views.internal, &block, &monitor).await;
+	let public_block = views.process(&views.public, &block, &monitor).await;
+	assert_eq!(public_block.processing_results.len(), 1);
+	trigger_handler(&internal_block).await.unwrap();
+	trigger_handler(&public_block).await.unwrap();
+
+	assert_eq!(
+		NETWORK_VIEW_DUPLICATES
+			.with_label_values(&["views_dedup", &views.public.slug])
+			.get(),
+		1
+	);
+}
+
+#[tokio::test]
+async fn test_match_missing_from_a_view_is_reported() {
+	let views = Views::new("views_divergence");
+	let monitor = views.create_monitor(Some(NetworkViewsConfig {
+		dedup: false,
+		divergence_blocks: Some(2),
+		divergence_triggers: vec!["views_errors".to_string()],
+	}));
+	let (block, omitted) = views.blocks(&monitor).await;
+
+	let internal_slug = views.internal.slug.clone();
+	let public_slug = views.public.slug.clone();
+	let mut trigger_service = MockTriggerExecutionService::<MockTriggerRepository>::default();
+	trigger_service
+		.expect_execute()
+		.withf(|trigger_slugs, _, _, _| trigger_slugs == ["views_slack"])
+		.times(1)
+		.returning(|_, _, _, _| Ok(()));
+	trigger_service
+		.expect_execute()
+		.withf(move |trigger_slugs, variables, monitor_match, _| {
+			trigger_slugs == ["views_errors"]
+				&& monitor_match.network_slug() == internal_slug
+				&& variables.get("divergence.network") == Some(&public_slug)
+				&& variables.get("divergence.block_number") == Some(&BLOCK_NUMBER.to_string())
+				&& monitor_match.monitor().triggers[0]
+					.message()
+					.and_then(|message| message.title.as_deref())
+					== Some("Network view divergence for Mint USDC Token")
+		})
+		.times(1)
+		.returning(|_, _, _, _| Ok(()));
+
+	let (shutdown_tx, _) = watch::channel(false);
+	let trigger_handler = create_trigger_handler(
+		shutdown_tx,
+		Arc::new(trigger_service),
+		HashMap::new(),
+		views.network_views(),
+		Arc::new(DecodeFailures::default()),
+	);
+
+	let internal_block = views.process(&views.internal, &block, &monitor).await;
+	let public_block = views.process(&views.public, &omitted, &monitor).await;
+	assert!(public_block.processing_results.is_empty());
+	trigger_handler(&internal_block).await.unwrap();
+	trigger_handler(&public_block).await.unwrap();
+
+	// The public view moves on without the transaction
+	trigger_handler(&empty_block(&views.public, BLOCK_NUMBER + 1))
+		.await
+		.unwrap();
+	trigger_handler(&empty_block(&views.public, BLOCK_NUMBER + 2))
+		.await
+		.unwrap();
+	trigger_handler(&empty_block(&views.public, BLOCK_NUMBER + 3))
+		.await
+		.unwrap();
+
+	assert_eq!(
+		NETWORK_VIEW_DIVERGENCES
+			.with_label_values(&["views_divergence", &views.public.slug, &monitor.name])
+			.get(),
+		1
+	);
+}
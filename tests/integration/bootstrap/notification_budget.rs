@@ -0,0 +1,379 @@
+use std::sync::{
+	atomic::{AtomicI64, Ordering},
+	Arc, Mutex,
+};
+
+use alloy::primitives::B256;
+use chrono::{DateTime, TimeDelta, Utc};
+use openzeppelin_monitor::{
+	bootstrap::{create_dispatch_queue, send_due_budget_digests},
+	models::{BudgetOverflow, NotificationBudget, TriggerReference},
+	services::{
+		notification::SlackPayloadBuilder,
+		trigger::{
+			BudgetClock, DedupedMatch, DispatchQueueConfig, NotificationBudgets, TriggerError,
+		},
+	},
+	utils::tests::{
+		evm::{
+			monitor::MonitorBuilder, monitor_match::MonitorMatchBuilder,
+			transaction::TransactionBuilder,
+		},
+		trigger::TriggerBuilder,
+	},
+};
+
+use crate::integration::mocks::{MockTriggerExecutionService, MockTriggerRepository};
+
+/// Delivery recorded by the mocked trigger service
+#[derive(Debug, Clone)]
+struct Delivery {
+	trigger_slugs: Vec<String>,
+	/// Title override of budget notices, None for regular matches
+	notice_title: Option<String>,
+	notice_body: Option<String>,
+}
+
+fn create_match(budget: NotificationBudget) -> DedupedMatch {
+	create_match_with_hash(budget, B256::ZERO)
+}
+
+fn create_match_with_hash(budget: NotificationBudget, hash: B256) -> DedupedMatch {
+	MonitorMatchBuilder::new()
+		.monitor(
+			MonitorBuilder::new()
+				.name("budgeted")
+				.triggers(vec!["slack".to_string(), "webhook".to_string()])
+				.notification_budget(budget)
+				.build(),
+		)
+		.transaction(TransactionBuilder::new().hash(hash).build())
+		.build_deduped()
+}
+
+/// Trigger service recording what is delivered to the `slack` and `webhook` triggers
+fn create_recording_service(
+	deliveries: Arc<Mutex<Vec<Delivery>>>,
+) -> Arc<MockTriggerExecutionService<MockTriggerRepository>> {
+	create_flaky_recording_service(deliveries, None)
+}
+
+/// Trigger service recording what is delivered to the `slack` and `webhook` triggers, failing
+/// the first delivery of the notice with the given title
+fn create_flaky_recording_service(
+	deliveries: Arc<Mutex<Vec<Delivery>>>,
+	failing_title: Option<&str>,
+) -> Arc<MockTriggerExecutionService<MockTriggerRepository>> {
+	let failing_title = Arc::new(Mutex::new(failing_title.map(str::to_string)));
+	let mut trigger_service = MockTriggerExecutionService::<MockTriggerRepository>::default();
+	trigger_service
+		.expect_get_trigger()
+		.returning(|slug| match slug {
+			"slack" => Some(Arc::new(
+				TriggerBuilder::new()
+					.name(slug)
+					.slack("https://hooks.slack.com/services/A/B/C")
+					.build(),
+			)),
+			"webhook" => Some(Arc::new(
+				TriggerBuilder::new()
+					.name(slug)
+					.webhook("https://webhook.example.com")
+					.build(),
+			)),
+			_ => None,
+		});
+	trigger_service
+		.expect_execute()
+		.returning(move |trigger_slugs, _, monitor_match, _| {
+			let message = monitor_match
+				.monitor()
+				.triggers
+				.first()
+				.and_then(TriggerReference::message)
+				.cloned();
+			let delivery = Delivery {
+				trigger_slugs: trigger_slugs.to_vec(),
+				notice_title: message.as_ref().and_then(|m| m.title.clone()),
+				notice_body: message.and_then(|m| m.body),
+			};
+			let mut failing_title = failing_title.lock().unwrap();
+			let fails = failing_title.is_some() && *failing_title == delivery.notice_title;
+			deliveries.lock().unwrap().push(delivery);
+			if fails {
+				*failing_title = None;
+				return Err(TriggerError::execution_error("slack is down", None, None));
+			}
+			Ok(())
+		});
+	Arc::new(trigger_service)
+}
+
+fn fake_clock(start: DateTime<Utc>) -> (Arc<AtomicI64>, BudgetClock) {
+	let now = Arc::new(AtomicI64::new(start.timestamp_millis()));
+	let clock_now = now.clone();
+	let clock: BudgetClock = Arc::new(move || {
+		DateTime::from_timestamp_millis(clock_now.load(Ordering::SeqCst)).unwrap()
+	});
+	(now, clock)
+}
+
+fn sequential_queue() -> DispatchQueueConfig {
+	DispatchQueueConfig {
+		capacity: 100,
+		concurrency: 1,
+		..Default::default()
+	}
+}
+
+#[tokio::test]
+async fn test_budget_caps_deliveries_and_digests_overflow() {
+	let deliveries = Arc::new(Mutex::new(Vec::new()));
+	let trigger_service = create_recording_service(deliveries.clone());
+	let (now, clock) = fake_clock(
+		DateTime::parse_from_rfc3339("2024-03-01T09:00:00Z")
+			.unwrap()
+			.to_utc(),
+	);
+	let budgets = Arc::new(NotificationBudgets::new().with_clock(clock));
+	let budget = NotificationBudget {
+		max_per_day: 50,
+		overflow: BudgetOverflow::Digest,
+		utc_offset: None,
+		digest_part_delay_ms: None,
+		record_triggers: vec![],
+	};
+
+	let queue = create_dispatch_queue(
+		sequential_queue(),
+		trigger_service.clone(),
+		Default::default(),
+		budgets.clone(),
+	);
+	for _ in 0..60 {
+		assert!(queue.enqueue(create_match(budget.clone())).await);
+	}
+	queue.close().await;
+
+	{
+		let deliveries = deliveries.lock().unwrap();
+		let (notices, matches): (Vec<_>, Vec<_>) =
+			deliveries.iter().partition(|d| d.notice_title.is_some());
+		assert_eq!(matches.len(), 50);
+		assert_eq!(notices.len(), 1);
+		assert_eq!(
+			notices[0].notice_title.as_deref(),
+			Some("Notification budget exhausted for budgeted")
+		);
+		assert_eq!(notices[0].trigger_slugs, vec!["slack", "webhook"]);
+	}
+
+	// Nothing is due before the budget resets at midnight UTC
+	send_due_budget_digests(&budgets, &*trigger_service, &Default::default()).await;
+	assert_eq!(deliveries.lock().unwrap().len(), 51);
+
+	now.fetch_add(TimeDelta::hours(15).num_milliseconds(), Ordering::SeqCst);
+	send_due_budget_digests(&budgets, &*trigger_service, &Default::default()).await;
+	{
+		let deliveries = deliveries.lock().unwrap();
+		assert_eq!(deliveries.len(), 52);
+		let digest = deliveries.last().unwrap();
+		assert_eq!(
+			digest.notice_title.as_deref(),
+			Some("Notification digest for budgeted")
+		);
+		assert!(digest
+			.notice_body
+			.as_deref()
+			.unwrap()
+			.starts_with("10 match(es) of monitor 'budgeted'"));
+	}
+
+	// The budget starts over on the new day
+	let queue = create_dispatch_queue(
+		sequential_queue(),
+		trigger_service.clone(),
+		Default::default(),
+		budgets,
+	);
+	assert!(queue.enqueue(create_match(budget)).await);
+	queue.close().await;
+	let deliveries = deliveries.lock().unwrap();
+	assert_eq!(deliveries.len(), 53);
+	assert!(deliveries.last().unwrap().notice_title.is_none());
+}
+
+#[tokio::test]
+async fn test_database_only_overflow_suppresses_chat_triggers() {
+	let deliveries = Arc::new(Mutex::new(Vec::new()));
+	let trigger_service = create_recording_service(deliveries.clone());
+	let budget = NotificationBudget {
+		max_per_day: 1,
+		overflow: BudgetOverflow::DatabaseOnly,
+		utc_offset: Some("-05:00".to_string()),
+		digest_part_delay_ms: None,
+		record_triggers: vec!["webhook".to_string()],
+	};
+
+	let queue = create_dispatch_queue(
+		sequential_queue(),
+		trigger_service,
+		Default::default(),
+		Arc::new(NotificationBudgets::new()),
+	);
+	for _ in 0..3 {
+		assert!(queue.enqueue(create_match(budget.clone())).await);
+	}
+	queue.close().await;
+
+	let deliveries = deliveries.lock().unwrap();
+	let slugs = deliveries
+		.iter()
+		.map(|d| (d.notice_title.is_some(), d.trigger_slugs.clone()))
+		.collect::<Vec<_>>();
+	assert_eq!(
+		slugs,
+		vec![
+			(false, vec!["slack".to_string(), "webhook".to_string()]),
+			(true, vec!["slack".to_string(), "webhook".to_string()]),
+			(false, vec!["webhook".to_string()]),
+			(false, vec!["webhook".to_string()]),
+		]
+	);
+}
+
+#[tokio::test]
+async fn test_database_only_overflow_drops_triggers_not_recording() {
+	let deliveries = Arc::new(Mutex::new(Vec::new()));
+	let trigger_service = create_recording_service(deliveries.clone());
+	let budget = NotificationBudget {
+		max_per_day: 1,
+		overflow: BudgetOverflow::DatabaseOnly,
+		utc_offset: None,
+		digest_part_delay_ms: None,
+		record_triggers: vec![],
+	};
+
+	let queue = create_dispatch_queue(
+		sequential_queue(),
+		trigger_service,
+		Default::default(),
+		Arc::new(NotificationBudgets::new()),
+	);
+	for _ in 0..3 {
+		assert!(queue.enqueue(create_match(budget.clone())).await);
+	}
+	queue.close().await;
+
+	// The webhook is not listed in record_triggers, so nothing is delivered after the notice
+	let deliveries = deliveries.lock().unwrap();
+	assert_eq!(deliveries.len(), 2);
+	assert!(deliveries[1].notice_title.is_some());
+}
+
+/// Returns the transaction hashes listed by a digest notice
+fn listed_transactions(delivery: &Delivery) -> Vec<String> {
+	let body = delivery.notice_body.as_deref().unwrap();
+	let list = body.split_once("Transactions: ").unwrap().1;
+	list.split(", ").map(str::to_string).collect()
+}
+
+#[tokio::test]
+async fn test_oversized_digest_is_split_for_limited_channels() {
+	let deliveries = Arc::new(Mutex::new(Vec::new()));
+	// The second part sent to Slack fails once
+	let trigger_service = create_flaky_recording_service(
+		deliveries.clone(),
+		Some("Notification digest for budgeted (part 2/13)"),
+	);
+	let (now, clock) = fake_clock(
+		DateTime::parse_from_rfc3339("2024-03-01T09:00:00Z")
+			.unwrap()
+			.to_utc(),
+	);
+	let budgets = Arc::new(NotificationBudgets::new().with_clock(clock));
+	let budget = NotificationBudget {
+		max_per_day: 1,
+		overflow: BudgetOverflow::Digest,
+		utc_offset: None,
+		digest_part_delay_ms: Some(0),
+		record_triggers: vec![],
+	};
+
+	let queue = create_dispatch_queue(
+		sequential_queue(),
+		trigger_service.clone(),
+		Default::default(),
+		budgets.clone(),
+	);
+	let hashes = (0..501u16)
+		.map(|i| B256::left_padding_from(&i.to_be_bytes()))
+		.collect::<Vec<_>>();
+	for hash in &hashes {
+		assert!(
+			queue
+				.enqueue(create_match_with_hash(budget.clone(), *hash))
+				.await
+		);
+	}
+	queue.close().await;
+	// The delivered match and the exhaustion notice
+	assert_eq!(deliveries.lock().unwrap().len(), 2);
+
+	now.fetch_add(TimeDelta::hours(15).num_milliseconds(), Ordering::SeqCst);
+	send_due_budget_digests(&budgets, &*trigger_service, &Default::default()).await;
+
+	let deliveries = deliveries.lock().unwrap();
+	let digests = &deliveries[2..];
+	let held_back = hashes[1..]
+		.iter()
+		.map(|hash| hash.to_string())
+		.collect::<Vec<_>>();
+
+	// Slack receives the digest in 13 parts, the failed part being retried on its own
+	let slack = digests
+		.iter()
+		.filter(|d| d.trigger_slugs == ["slack"])
+		.collect::<Vec<_>>();
+	let markers = slack
+		.iter()
+		.map(|d| {
+			let title = d.notice_title.as_deref().unwrap();
+			title
+				.strip_prefix("Notification digest for budgeted (part ")
+				.and_then(|marker| marker.strip_suffix("/13)"))
+				.unwrap()
+				.parse::<usize>()
+				.unwrap()
+		})
+		.collect::<Vec<_>>();
+	let mut expected_markers = (1..=13).collect::<Vec<_>>();
+	expected_markers.insert(1, 2);
+	assert_eq!(markers, expected_markers);
+
+	let max_payload_bytes = SlackPayloadBuilder::CAPABILITIES.max_payload_bytes.unwrap();
+	let mut listed = Vec::new();
+	for (index, delivery) in slack.iter().enumerate() {
+		let body = delivery.notice_body.as_deref().unwrap();
+		assert!(body.starts_with("500 match(es) of monitor 'budgeted'"));
+		assert!(delivery.notice_title.as_ref().unwrap().len() + body.len() <= max_payload_bytes);
+		// The failed attempt of part 2 is not counted twice
+		if index != 1 {
+			listed.extend(listed_transactions(delivery));
+		}
+	}
+	assert_eq!(listed, held_back);
+
+	// The webhook does not limit the size of messages and receives the whole digest
+	let webhook = digests
+		.iter()
+		.filter(|d| d.trigger_slugs == ["webhook"])
+		.collect::<Vec<_>>();
+	assert_eq!(webhook.len(), 1);
+	assert_eq!(
+		webhook[0].notice_title.as_deref(),
+		Some("Notification digest for budgeted")
+	);
+	assert_eq!(listed_transactions(webhook[0]), held_back);
+	assert_eq!(digests.len(), slack.len() + webhook.len());
+}
@@ -0,0 +1,158 @@
+use std::{
+	sync::{Arc, Mutex},
+	time::Duration,
+};
+
+use alloy::primitives::B256;
+use openzeppelin_monitor::{
+	services::{
+		notification::{derive_idempotency_key, match_uuid},
+		trigger::{
+			DedupedMatch, DispatchFn, NotificationOutbox, OutboxDispatcher, OutboxDispatcherConfig,
+			DEFAULT_OUTBOX_RETENTION,
+		},
+	},
+	utils::tests::evm::{
+		monitor::MonitorBuilder, monitor_match::MonitorMatchBuilder,
+		transaction::TransactionBuilder,
+	},
+};
+use tempfile::TempDir;
+use tokio::sync::Notify;
+
+const TRIGGER: &str = "webhook";
+
+fn create_match() -> DedupedMatch {
+	MonitorMatchBuilder::new()
+		.monitor(
+			MonitorBuilder::new()
+				.name("outbox")
+				.triggers(vec![TRIGGER.to_string()])
+				.build(),
+		)
+		.transaction(
+			TransactionBuilder::new()
+				.hash(B256::with_last_byte(1))
+				.build(),
+		)
+		.build_deduped()
+}
+
+/// Idempotency key a webhook receiver gets for the delivery of a match
+fn idempotency_key(deduped: &DedupedMatch) -> String {
+	derive_idempotency_key(&match_uuid(&deduped.monitor_match), TRIGGER).to_string()
+}
+
+fn dispatcher_config(owner: &str, lease: Duration) -> OutboxDispatcherConfig {
+	OutboxDispatcherConfig {
+		owner: owner.to_string(),
+		lease,
+		poll_interval: Duration::from_millis(10),
+		concurrency: 1,
+	}
+}
+
+/// Dispatch recording the idempotency keys received
+fn create_receiver(received: Arc<Mutex<Vec<String>>>) -> DispatchFn {
+	Arc::new(move |deduped| {
+		let received = received.clone();
+		Box::pin(async move {
+			received.lock().unwrap().push(idempotency_key(&deduped));
+		})
+	})
+}
+
+#[tokio::test]
+async fn test_claimed_entry_is_delivered_once_after_dispatcher_crash() {
+	let dir = TempDir::new().unwrap();
+	let outbox = Arc::new(NotificationOutbox::new(
+		dir.path().join("outbox.json"),
+		DEFAULT_OUTBOX_RETENTION,
+	));
+	assert_eq!(outbox.add(vec![create_match()]).await.unwrap(), 1);
+
+	// The first dispatcher claims the match and is killed before delivering it
+	let claimed = Arc::new(Notify::new());
+	let crashed = OutboxDispatcher::start(
+		outbox.clone(),
+		dispatcher_config("crashed", Duration::from_millis(100)),
+		{
+			let claimed = claimed.clone();
+			Arc::new(move |_| {
+				let claimed = claimed.clone();
+				Box::pin(async move {
+					claimed.notify_one();
+					std::future::pending::<()>().await;
+				})
+			})
+		},
+	);
+	claimed.notified().await;
+	crashed.close(Duration::ZERO).await;
+
+	let entries = outbox.entries().await.unwrap();
+	assert_eq!(entries[0].lease_owner.as_deref(), Some("crashed"));
+	assert_eq!(entries[0].completed_at_ms, None);
+
+	// Another dispatcher claims the match once the lease expired and delivers it
+	let received = Arc::new(Mutex::new(vec![]));
+	let replacement = OutboxDispatcher::start(
+		outbox.clone(),
+		dispatcher_config("replacement", Duration::from_secs(60)),
+		create_receiver(received.clone()),
+	);
+	tokio::time::timeout(Duration::from_secs(5), async {
+		while received.lock().unwrap().is_empty() {
+			tokio::time::sleep(Duration::from_millis(10)).await;
+		}
+	})
+	.await
+	.expect("match was not delivered after the lease expired");
+
+	// The block is processed again after the crash, the match is not added back
+	assert_eq!(outbox.add(vec![create_match()]).await.unwrap(), 0);
+	tokio::time::sleep(Duration::from_millis(100)).await;
+	replacement.close(Duration::from_secs(5)).await;
+
+	assert_eq!(
+		*received.lock().unwrap(),
+		vec![idempotency_key(&create_match())]
+	);
+	let entries = outbox.entries().await.unwrap();
+	assert_eq!(entries.len(), 1);
+	assert_eq!(entries[0].attempts, 2);
+	assert!(entries[0].completed_at_ms.is_some());
+}
+
+#[tokio::test]
+async fn test_restarted_dispatcher_recovers_its_claims() {
+	let dir = TempDir::new().unwrap();
+	let outbox = Arc::new(NotificationOutbox::new(
+		dir.path().join("outbox.json"),
+		DEFAULT_OUTBOX_RETENTION,
+	));
+	outbox.add(vec![create_match()]).await.unwrap();
+	outbox
+		.claim("instance", Duration::from_secs(3600), 10)
+		.await
+		.unwrap();
+
+	// The same instance restarts, its claim is released without waiting for the lease
+	assert_eq!(outbox.recover("instance").await.unwrap(), 1);
+	let received = Arc::new(Mutex::new(vec![]));
+	let dispatcher = OutboxDispatcher::start(
+		outbox.clone(),
+		dispatcher_config("instance", Duration::from_secs(60)),
+		create_receiver(received.clone()),
+	);
+	tokio::time::timeout(Duration::from_secs(5), async {
+		while received.lock().unwrap().is_empty() {
+			tokio::time::sleep(Duration::from_millis(10)).await;
+		}
+	})
+	.await
+	.expect("recovered match was not delivered");
+	dispatcher.close(Duration::from_secs(5)).await;
+
+	assert_eq!(received.lock().unwrap().len(), 1);
+}
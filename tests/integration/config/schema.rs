@@ -0,0 +1,101 @@
+//! Integration tests for the configuration JSON Schemas.
+//!
+//! Every monitor, network and trigger configuration used by the test suite and shipped as an
+//! example must validate against the generated schema of its file kind.
+
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+
+use openzeppelin_monitor::models::{ConfigSchemaKind, Monitor};
+
+const FIXTURE_ROOTS: [&str; 4] = [
+	"tests/integration/fixtures/evm",
+	"tests/integration/fixtures/stellar",
+	"tests/integration/fixtures/midnight",
+	"examples/config",
+];
+
+fn config_files(kind: ConfigSchemaKind) -> Vec<PathBuf> {
+	let mut files = Vec::new();
+	for root in FIXTURE_ROOTS {
+		let dir = Path::new(root).join(format!("{}s", kind.as_str()));
+		let Ok(entries) = std::fs::read_dir(&dir) else {
+			continue;
+		};
+		for entry in entries {
+			let path = entry.unwrap().path();
+			if path.extension().is_some_and(|ext| ext == "json") {
+				files.push(path);
+			}
+		}
+	}
+	files.sort();
+	files
+}
+
+fn read_json(path: &Path) -> Value {
+	serde_json::from_str(&std::fs::read_to_string(path).unwrap()).unwrap()
+}
+
+#[test]
+fn test_config_fixtures_validate_against_schemas() {
+	for kind in ConfigSchemaKind::ALL {
+		let validator = jsonschema::draft202012::new(&kind.schema()).unwrap();
+		let files = config_files(kind);
+		assert!(!files.is_empty(), "No {} fixtures found", kind);
+
+		for path in files {
+			let instance = read_json(&path);
+			let errors = validator
+				.iter_errors(&instance)
+				.map(|e| format!("{} at {}", e, e.instance_path))
+				.collect::<Vec<_>>();
+			assert!(
+				errors.is_empty(),
+				"{} does not match the {} schema: {:?}",
+				path.display(),
+				kind,
+				errors
+			);
+		}
+	}
+}
+
+#[test]
+fn test_invalid_fixture_fails_schema_validation() {
+	let path = Path::new("tests/integration/fixtures/schema/invalid_monitor.json");
+	let instance = read_json(path);
+	let validator = jsonschema::draft202012::new(&ConfigSchemaKind::Monitor.schema()).unwrap();
+
+	let errors = validator
+		.iter_errors(&instance)
+		.map(|e| e.instance_path.to_string())
+		.collect::<Vec<_>>();
+	assert!(errors.contains(&"/paused".to_string()), "{:?}", errors);
+	assert!(
+		errors
+			.iter()
+			.any(|p| p.starts_with("/match_conditions/transactions/0")),
+		"{:?}",
+		errors
+	);
+	assert!(errors.contains(&"".to_string()), "{:?}", errors);
+
+	// The loader rejects the fixture as well
+	assert!(serde_json::from_value::<Monitor>(instance).is_err());
+}
+
+#[test]
+fn test_schema_rejects_unknown_secret_source() {
+	let validator = jsonschema::draft202012::new(&ConfigSchemaKind::Network.schema()).unwrap();
+	let mut network = read_json(Path::new(
+		"tests/integration/fixtures/evm/networks/network.json",
+	));
+	assert!(validator.is_valid(&network));
+
+	network["rpc_urls"][0]["url"]["type"] = Value::String("ENVIRONMENT".to_string());
+	assert!(validator.is_valid(&network));
+
+	network["rpc_urls"][0]["url"]["type"] = Value::String("file".to_string());
+	assert!(!validator.is_valid(&network));
+}
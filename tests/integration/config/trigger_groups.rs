@@ -0,0 +1,142 @@
+//! Integration tests for trigger groups.
+//!
+//! Monitors reference trigger groups by name, the groups are expanded into their member
+//! triggers whenever the monitors are loaded.
+
+use std::{collections::HashMap, path::Path};
+
+use openzeppelin_monitor::{
+	models::{BlockChainType, Monitor, Trigger},
+	repositories::{
+		MonitorRepository, MonitorRepositoryTrait, NetworkRepository, NetworkService,
+		RepositoryError, TriggerRepository, TriggerService,
+	},
+	utils::tests::builders::{
+		evm::monitor::MonitorBuilder, network::NetworkBuilder, trigger::TriggerBuilder,
+	},
+};
+use tempfile::TempDir;
+
+fn create_triggers() -> HashMap<String, Trigger> {
+	["slack_sec", "pagerduty_sec", "webhook_siem"]
+		.into_iter()
+		.map(|name| {
+			(
+				name.to_string(),
+				TriggerBuilder::new()
+					.name(name)
+					.webhook("https://example.com/webhook")
+					.build(),
+			)
+		})
+		.collect()
+}
+
+/// Writes a monitor referencing the `security` group and returns the monitors directory
+fn write_config(config_dir: &Path, security: &[&str]) -> std::path::PathBuf {
+	let monitors_dir = config_dir.join("monitors");
+	std::fs::create_dir_all(&monitors_dir).unwrap();
+	std::fs::create_dir_all(config_dir.join("trigger_groups")).unwrap();
+
+	let monitor = MonitorBuilder::new()
+		.name("Admin Activity")
+		.networks(vec!["ethereum_mainnet".to_string()])
+		.triggers(vec!["security".to_string()])
+		.build();
+	std::fs::write(
+		monitors_dir.join("admin_activity.json"),
+		serde_json::to_string(&monitor).unwrap(),
+	)
+	.unwrap();
+	write_group(config_dir, "security", security);
+	monitors_dir
+}
+
+fn write_group(config_dir: &Path, name: &str, triggers: &[&str]) {
+	std::fs::write(
+		config_dir
+			.join("trigger_groups")
+			.join(format!("{}.json", name)),
+		serde_json::json!({ "name": name, "triggers": triggers }).to_string(),
+	)
+	.unwrap();
+}
+
+async fn load_monitors(monitors_dir: &Path) -> Result<HashMap<String, Monitor>, RepositoryError> {
+	let network = NetworkBuilder::new()
+		.name("Ethereum Mainnet")
+		.slug("ethereum_mainnet")
+		.network_type(BlockChainType::EVM)
+		.build();
+	let network_service = NetworkService::new_with_repository(NetworkRepository {
+		networks: HashMap::from([(network.slug.clone(), network)]),
+	})
+	.unwrap();
+	let trigger_service = TriggerService::new_with_repository(TriggerRepository {
+		triggers: create_triggers(),
+	})
+	.unwrap();
+
+	MonitorRepository::<NetworkRepository, TriggerRepository>::new(
+		Some(monitors_dir),
+		Some(network_service),
+		Some(trigger_service),
+	)
+	.await
+	.map(|repository| repository.get_all())
+}
+
+fn trigger_names(monitors: &HashMap<String, Monitor>) -> Vec<String> {
+	monitors
+		.values()
+		.next()
+		.unwrap()
+		.triggers
+		.iter()
+		.map(|t| t.name().to_string())
+		.collect()
+}
+
+#[tokio::test]
+async fn test_reload_picks_up_group_membership_change() {
+	let temp_dir = TempDir::new().unwrap();
+	let monitors_dir = write_config(temp_dir.path(), &["slack_sec", "pagerduty_sec"]);
+
+	let monitors = load_monitors(&monitors_dir).await.unwrap();
+	assert_eq!(trigger_names(&monitors), vec!["slack_sec", "pagerduty_sec"]);
+
+	// Only the group changes, the monitor file is left untouched
+	write_group(
+		temp_dir.path(),
+		"security",
+		&["pagerduty_sec", "webhook_siem"],
+	);
+
+	let monitors = load_monitors(&monitors_dir).await.unwrap();
+	assert_eq!(
+		trigger_names(&monitors),
+		vec!["pagerduty_sec", "webhook_siem"]
+	);
+}
+
+#[tokio::test]
+async fn test_load_rejects_invalid_groups() {
+	let temp_dir = TempDir::new().unwrap();
+	let monitors_dir = write_config(temp_dir.path(), &["slack_sec", "escalation"]);
+
+	write_group(
+		temp_dir.path(),
+		"escalation",
+		&["pagerduty_sec", "security"],
+	);
+	let err = load_monitors(&monitors_dir).await.unwrap_err();
+	assert!(err
+		.to_string()
+		.contains("Trigger group cycle: security -> escalation -> security"));
+
+	write_group(temp_dir.path(), "escalation", &["pagerduty_sec", "sms"]);
+	let err = load_monitors(&monitors_dir).await.unwrap_err();
+	assert!(err
+		.to_string()
+		.contains("Trigger group 'escalation' references unknown trigger 'sms'"));
+}
@@ -168,8 +168,8 @@ pub async fn setup_trigger_execution_service(
 	let mut mock_trigger_repository = MockTriggerRepository::new(None).await.unwrap();
 
 	mock_trigger_repository
-		.expect_get()
-		.returning(move |id| trigger_map.get(id).cloned());
+		.expect_get_all()
+		.return_once(move || trigger_map);
 
 	let trigger_service = TriggerService::new_with_repository(mock_trigger_repository).unwrap();
 	let notification_service = NotificationService::new();
@@ -192,27 +192,9 @@ pub fn setup_network_service(
 		.expect()
 		.return_once(move |_| Ok(networks_clone.clone()));
 
+	// The service loads the networks once and serves lookups from them
 	let mut mock_repo = MockNetworkRepository::default();
-
-	let networks_clone = networks.clone();
-
-	mock_repo
-		.expect_get_all()
-		.return_once(move || networks_clone.clone());
-
-	mock_repo.expect_clone().return_once({
-		let networks = networks.clone();
-		move || {
-			let mut cloned_repo = MockNetworkRepository::default();
-			let networks_clone = networks.clone();
-			cloned_repo.expect_get_all().return_once(|| networks_clone);
-			cloned_repo
-		}
-	});
-
-	mock_repo
-		.expect_get()
-		.return_once(move |id| networks.get(id).cloned());
+	mock_repo.expect_get_all().return_once(move || networks);
 
 	NetworkService::new_with_repository(mock_repo).unwrap()
 }
@@ -225,26 +207,10 @@ pub fn setup_trigger_service(
 		.expect()
 		.return_once(move |_| Ok(triggers_clone));
 
+	// The service loads the triggers once and serves lookups from them
 	let mut mock_repo = MockTriggerRepository::default();
+	mock_repo.expect_get_all().return_once(move || triggers);
 
-	let triggers_clone = triggers.clone();
-	let triggers_for_get = triggers.clone();
-
-	mock_repo
-		.expect_get_all()
-		.return_once(move || triggers_clone.clone());
-
-	// // Set up get() expectation
-	mock_repo
-		.expect_get()
-		.returning(move |id| triggers_for_get.get(id).cloned());
-
-	mock_repo.expect_clone().return_once(move || {
-		let mut cloned_repo = MockTriggerRepository::default();
-		let triggers_clone = triggers.clone();
-		cloned_repo.expect_get_all().return_once(|| triggers_clone);
-		cloned_repo
-	});
 	TriggerService::new_with_repository(mock_repo).unwrap()
 }
 
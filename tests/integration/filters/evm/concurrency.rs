@@ -0,0 +1,252 @@
+//! Integration tests for the concurrent evaluation of EVM monitors.
+//!
+//! The same block is filtered with monitors evaluated one at a time and concurrently, the
+//! matches and their order must not depend on the concurrency.
+
+use alloy::primitives::{Address, B256, U256, U64};
+use serde_json::{json, Value};
+use std::{
+	sync::{
+		atomic::{AtomicUsize, Ordering},
+		Arc,
+	},
+	time::Instant,
+};
+
+use openzeppelin_monitor::{
+	models::{
+		BlockChainType, BlockType, EVMBlock, EVMTransaction, Monitor, MonitorMatch,
+		TransactionStatus,
+	},
+	services::{
+		blockchain::{EvmClient, TransportError},
+		filter::FilterService,
+	},
+	utils::tests::{
+		evm::{monitor::MonitorBuilder, receipt::ReceiptBuilder, transaction::TransactionBuilder},
+		network::NetworkBuilder,
+	},
+};
+
+use crate::integration::mocks::MockEVMTransportClient;
+
+const ADDRESSES: usize = 5;
+
+fn address(index: usize) -> String {
+	format!("0x{:040x}", index + 1)
+}
+
+/// Client serving empty logs and receipts derived from the transaction hash, counting the
+/// receipts requested
+fn create_client(receipt_requests: Arc<AtomicUsize>) -> EvmClient<MockEVMTransportClient> {
+	let mut mock_transport = MockEVMTransportClient::new();
+	mock_transport
+		.expect_send_raw_request()
+		.returning(move |method, params| match method {
+			"eth_getLogs" => Ok(json!({ "result": [] })),
+			"eth_getTransactionReceipt" => {
+				receipt_requests.fetch_add(1, Ordering::SeqCst);
+				let hash = params
+					.and_then(|params| params.first().and_then(Value::as_str).map(String::from))
+					.unwrap()
+					.parse::<B256>()
+					.unwrap();
+				let receipt = ReceiptBuilder::new()
+					.transaction_hash(hash)
+					.gas_used(U256::from(u64::from(hash[31]) * 1_000))
+					.status(hash[31] % 3 != 0)
+					.build();
+				Ok(json!({ "result": receipt }))
+			}
+			_ => Err(TransportError::http(
+				reqwest::StatusCode::METHOD_NOT_ALLOWED,
+				"random.url".to_string(),
+				"Unexpected method call".to_string(),
+				None,
+				None,
+			)),
+		});
+	EvmClient::new_with_transport(mock_transport)
+}
+
+fn create_block(transactions: usize) -> BlockType {
+	let mut block = EVMBlock::default();
+	block.0.number = Some(U64::from(100));
+	block.0.transactions = (0..transactions)
+		.map(|i| {
+			TransactionBuilder::new()
+				.hash(B256::from(U256::from(i + 1)))
+				.from(address(i % ADDRESSES).parse::<Address>().unwrap())
+				.to(address((i + 1) % ADDRESSES).parse::<Address>().unwrap())
+				.value(U256::from(i * 100))
+				.build()
+		})
+		.collect::<Vec<EVMTransaction>>();
+	BlockType::EVM(Arc::new(block))
+}
+
+/// Creates monitors with a mix of conditions, in an order unrelated to their names
+fn create_monitors(count: usize) -> Vec<Arc<Monitor>> {
+	(0..count)
+		.rev()
+		.map(|i| {
+			let builder = MonitorBuilder::new()
+				.name(&format!("monitor_{:02}", i))
+				.networks(vec!["ethereum_mainnet".to_string()])
+				.address(&address(i % ADDRESSES));
+			match i % 4 {
+				0 => builder,
+				1 => {
+					builder.transaction(TransactionStatus::Any, Some(format!("value > {}", i * 20)))
+				}
+				// Monitors needing the receipts
+				2 => builder.transaction(TransactionStatus::Any, Some("gas_used > 5000".into())),
+				_ => builder.transaction(TransactionStatus::Failure, None),
+			}
+			.build()
+		})
+		.map(Arc::new)
+		.collect()
+}
+
+/// Filters the block with the given monitor concurrency
+async fn filter(
+	block: &BlockType,
+	monitors: &[Arc<Monitor>],
+	concurrency: usize,
+) -> (Vec<MonitorMatch>, usize) {
+	let receipt_requests = Arc::new(AtomicUsize::new(0));
+	let client = create_client(receipt_requests.clone());
+	let network = NetworkBuilder::new()
+		.name("Ethereum Mainnet")
+		.slug("ethereum_mainnet")
+		.network_type(BlockChainType::EVM)
+		.monitor_concurrency(concurrency)
+		.build();
+	let matches = FilterService::new()
+		.filter_block(&client, &network, block, monitors, None)
+		.await
+		.unwrap();
+	(matches, receipt_requests.load(Ordering::SeqCst))
+}
+
+/// Returns the monitor name and transaction hash of the matches
+fn summary(matches: &[MonitorMatch]) -> Vec<(String, String)> {
+	matches
+		.iter()
+		.map(|m| (m.monitor().name.clone(), m.transaction_hash()))
+		.collect()
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn test_concurrent_evaluation_matches_serial_evaluation() {
+	let block = create_block(20);
+	let monitors = create_monitors(50);
+
+	let (serial, serial_receipts) = filter(&block, &monitors, 1).await;
+	let (concurrent, concurrent_receipts) = filter(&block, &monitors, 16).await;
+
+	assert!(!serial.is_empty());
+	assert_eq!(summary(&serial), summary(&concurrent));
+	assert_eq!(
+		serde_json::to_value(&serial).unwrap(),
+		serde_json::to_value(&concurrent).unwrap()
+	);
+
+	// Matches are ordered by monitor name, then by transaction
+	let mut expected = summary(&serial);
+	expected.sort_by_key(|(monitor, hash)| (monitor.clone(), hash.parse::<B256>().unwrap()));
+	assert_eq!(summary(&serial), expected);
+
+	// Receipts are fetched once per transaction, however many monitors need them
+	assert_eq!(serial_receipts, 20);
+	assert_eq!(concurrent_receipts, 20);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn test_matches_are_ordered_by_monitor_name_then_transaction() {
+	let block = create_block(10);
+	// Monitors watching the same address, given in reverse name order
+	let monitors = ["watch_b", "watch_a"]
+		.into_iter()
+		.map(|name| {
+			Arc::new(
+				MonitorBuilder::new()
+					.name(name)
+					.networks(vec!["ethereum_mainnet".to_string()])
+					.address(&address(0))
+					.build(),
+			)
+		})
+		.collect::<Vec<_>>();
+
+	// Transactions 1, 5, 6 and 10 are sent from or to the address
+	let hashes = [1u64, 5, 6, 10].map(|i| B256::from(U256::from(i)));
+	let expected = ["watch_a", "watch_b"]
+		.into_iter()
+		.flat_map(|name| hashes.map(|hash| (name.to_string(), hash)))
+		.collect::<Vec<_>>();
+	for concurrency in [1, 2] {
+		let (matches, _) = filter(&block, &monitors, concurrency).await;
+		let ordered = summary(&matches)
+			.into_iter()
+			.map(|(monitor, hash)| (monitor, hash.parse::<B256>().unwrap()))
+			.collect::<Vec<_>>();
+		assert_eq!(ordered, expected);
+	}
+}
+
+#[tokio::test]
+async fn test_receipts_not_fetched_when_no_monitor_needs_them() {
+	let block = create_block(10);
+	// Keep the monitors without receipt conditions
+	let monitors = create_monitors(8)
+		.into_iter()
+		.filter(|monitor| {
+			monitor
+				.match_conditions
+				.transactions
+				.iter()
+				.all(|condition| {
+					condition.status == TransactionStatus::Any
+						&& !condition
+							.expression
+							.as_deref()
+							.is_some_and(|expression| expression.contains("gas_used"))
+				})
+		})
+		.collect::<Vec<_>>();
+
+	let (matches, receipts) = filter(&block, &monitors, 4).await;
+
+	assert!(!matches.is_empty());
+	assert_eq!(receipts, 0);
+}
+
+/// Compares the time taken to filter a block with 50 monitors one at a time and concurrently
+///
+/// Run with `cargo test --test integration filters::evm::concurrency -- --ignored --nocapture`
+#[tokio::test(flavor = "multi_thread")]
+#[ignore]
+async fn bench_concurrent_evaluation() {
+	let block = create_block(500);
+	let monitors = create_monitors(50);
+
+	let started = Instant::now();
+	let (serial, _) = filter(&block, &monitors, 1).await;
+	let serial_elapsed = started.elapsed();
+
+	let started = Instant::now();
+	let (concurrent, _) = filter(&block, &monitors, 8).await;
+	let concurrent_elapsed = started.elapsed();
+
+	assert_eq!(summary(&serial), summary(&concurrent));
+	println!(
+		"{} monitors, {} matches: serial {:?}, concurrent {:?} ({:.1}x)",
+		monitors.len(),
+		serial.len(),
+		serial_elapsed,
+		concurrent_elapsed,
+		serial_elapsed.as_secs_f64() / concurrent_elapsed.as_secs_f64()
+	);
+}
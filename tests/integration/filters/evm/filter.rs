@@ -3,18 +3,23 @@
 //! Tests the monitoring functionality for EVM-compatible blockchains,
 //! including event and transaction filtering.
 
-use alloy::primitives::Uint;
+use alloy::primitives::{Bytes, Uint, U256};
 use serde_json::json;
-use std::collections::HashMap;
+use std::{collections::HashMap, path::Path, sync::Arc};
 
 use openzeppelin_monitor::{
 	models::{
-		BlockType, ContractSpec, EVMReceiptLog, EVMTransactionReceipt, EventCondition,
-		FunctionCondition, Monitor, MonitorMatch, TransactionCondition, TransactionStatus,
+		AddressWithSpec, BlockType, ContractSpec, EVMReceiptLog, EVMTransactionReceipt,
+		EventCondition, FunctionCondition, Monitor, MonitorMatch, PresetOverride, PresetReference,
+		TransactionCondition, TransactionStatus,
+	},
+	repositories::{
+		MonitorRepository, MonitorRepositoryTrait, NetworkRepository, NetworkService,
+		TriggerRepository, TriggerService,
 	},
 	services::{
-		blockchain::{EvmClient, TransportError},
-		filter::{handle_match, FilterError, FilterService},
+		blockchain::{BlockChainClient, EvmClient, FixtureClient, TransportError},
+		filter::{handle_match, match_variables, FilterError, FilterService},
 	},
 	utils::tests::evm::{receipt::ReceiptBuilder, transaction::TransactionBuilder},
 };
@@ -24,6 +29,9 @@ use crate::integration::{
 	mocks::MockEVMTransportClient,
 };
 
+/// Fixtures recorded from Ethereum mainnet
+const RECORDED_FIXTURES_DIR: &str = "tests/integration/fixtures/recorded/ethereum_mainnet";
+
 fn setup_mock_transport(test_data: TestData) -> MockEVMTransportClient {
 	let mut mock_transport = MockEVMTransportClient::new();
 	let counter = std::sync::atomic::AtomicUsize::new(0);
@@ -69,6 +77,7 @@ fn make_monitor_with_events(mut monitor: Monitor, include_expression: bool) -> M
 		} else {
 			None
 		},
+		match_any_emitter: false,
 	});
 	monitor
 }
@@ -136,7 +145,7 @@ async fn test_monitor_events_with_no_expressions() -> Result<(), Box<FilterError
 			&client,
 			&test_data.network,
 			&test_data.blocks[0],
-			&[monitor],
+			&[Arc::new(monitor)],
 			None,
 		)
 		.await?;
@@ -169,24 +178,18 @@ async fn test_monitor_events_with_no_expressions() -> Result<(), Box<FilterError
 
 #[tokio::test]
 async fn test_monitor_events_with_expressions() -> Result<(), Box<FilterError>> {
-	// Load test data using common utility
+	// Replay the block recorded from the network, with all its logs
 	let test_data = TestDataBuilder::new("evm").build();
 	let filter_service = FilterService::new();
-	// Create mock transport
-	let mock_transport = setup_mock_transport(test_data.clone());
-	let client = EvmClient::new_with_transport(mock_transport);
+	let client = FixtureClient::load(Path::new(RECORDED_FIXTURES_DIR)).unwrap();
+	let network = FixtureClient::load_network(Path::new(RECORDED_FIXTURES_DIR)).unwrap();
+	let block = client.get_blocks(21306058, None).await.unwrap().remove(0);
 
 	let monitor = make_monitor_with_events(test_data.monitor, true);
 
 	// Run filter_block with the test data
 	let matches = filter_service
-		.filter_block(
-			&client,
-			&test_data.network,
-			&test_data.blocks[0],
-			&[monitor],
-			None,
-		)
+		.filter_block(&client, &network, &block, &[Arc::new(monitor)], None)
 		.await?;
 
 	assert!(!matches.is_empty(), "Should have found matching events");
@@ -258,7 +261,7 @@ async fn test_monitor_functions_with_no_expressions() -> Result<(), Box<FilterEr
 			&client,
 			&test_data.network,
 			&test_data.blocks[0],
-			&[monitor],
+			&[Arc::new(monitor)],
 			Some(&[contract_with_spec]),
 		)
 		.await?;
@@ -310,7 +313,7 @@ async fn test_monitor_functions_with_expressions() -> Result<(), Box<FilterError
 			&client,
 			&test_data.network,
 			&test_data.blocks[0],
-			&[monitor],
+			&[Arc::new(monitor)],
 			Some(&[contract_with_spec]),
 		)
 		.await?;
@@ -369,7 +372,7 @@ async fn test_monitor_transactions_with_no_expressions() -> Result<(), Box<Filte
 			&client,
 			&test_data.network,
 			&test_data.blocks[0],
-			&[monitor],
+			&[Arc::new(monitor)],
 			None,
 		)
 		.await?;
@@ -413,7 +416,7 @@ async fn test_monitor_transactions_with_expressions() -> Result<(), Box<FilterEr
 			&client,
 			&test_data.network,
 			&test_data.blocks[0],
-			&[monitor],
+			&[Arc::new(monitor)],
 			None,
 		)
 		.await?;
@@ -462,7 +465,7 @@ async fn test_monitor_with_multiple_conditions() -> Result<(), Box<FilterError>>
 			&client,
 			&test_data.network,
 			&test_data.blocks[0],
-			&[test_data.monitor],
+			&[Arc::new(test_data.monitor)],
 			Some(&[contract_with_spec]),
 		)
 		.await?;
@@ -522,7 +525,7 @@ async fn test_monitor_error_cases() -> Result<(), Box<FilterError>> {
 			&client,
 			&test_data.network,
 			&invalid_block,
-			&[test_data.monitor],
+			&[Arc::new(test_data.monitor)],
 			None,
 		)
 		.await;
@@ -585,7 +588,7 @@ async fn test_handle_match() -> Result<(), Box<FilterError>> {
 			&client,
 			&test_data.network,
 			&test_data.blocks[0],
-			&[test_data.monitor],
+			&[Arc::new(test_data.monitor)],
 			Some(&[contract_with_spec]),
 		)
 		.await?;
@@ -639,7 +642,7 @@ async fn test_handle_match_with_no_args() -> Result<(), Box<FilterError>> {
 			&client,
 			&test_data.network,
 			&test_data.blocks[3], // block at index 3 contains increment() transaction
-			&[monitor],
+			&[Arc::new(monitor)],
 			Some(&[contract_with_spec]),
 		)
 		.await?;
@@ -755,6 +758,9 @@ async fn test_handle_match_with_key_collision() -> Result<(), Box<FilterError>>
 			}],
 			events: vec![],
 			transactions: vec![],
+			sender_activity: None,
+			baseline: None,
+			network_metrics: None,
 		},
 		matched_on_args: Some(EVMMatchArguments {
 			functions: Some(vec![EVMMatchParamsMap {
@@ -774,9 +780,15 @@ async fn test_handle_match_with_key_collision() -> Result<(), Box<FilterError>>
 					},
 				]),
 				hex_signature: Some("0xdeadbeef".to_string()),
+				log_index: None,
 			}]),
 			events: None,
 		}),
+		sender_activity: None,
+		baseline: None,
+		network_metrics: None,
+		timing: None,
+		origin: Default::default(),
 	};
 
 	let match_wrapper = MonitorMatch::EVM(Box::new(evm_match));
@@ -846,6 +858,7 @@ async fn test_filter_block_with_receipt_and_logs() -> Result<(), Box<FilterError
 	monitor.match_conditions.events = vec![EventCondition {
 		signature: "Transfer(address,address,uint256)".to_string(),
 		expression: None,
+		match_any_emitter: false,
 	}];
 	monitor.match_conditions.functions = vec![FunctionCondition {
 		signature: "transfer(address,uint256)".to_string(),
@@ -867,7 +880,7 @@ async fn test_filter_block_with_receipt_and_logs() -> Result<(), Box<FilterError
 			&client,
 			&test_data.network,
 			&test_data.blocks[0],
-			&[monitor],
+			&[Arc::new(monitor)],
 			Some(&[contract_with_spec]),
 		)
 		.await?;
@@ -948,6 +961,7 @@ async fn test_filter_block_with_only_logs() -> Result<(), Box<FilterError>> {
 	monitor.match_conditions.events = vec![EventCondition {
 		signature: "Transfer(address,address,uint256)".to_string(),
 		expression: None,
+		match_any_emitter: false,
 	}];
 	monitor.match_conditions.functions = vec![FunctionCondition {
 		signature: "transfer(address,uint256)".to_string(),
@@ -966,7 +980,7 @@ async fn test_filter_block_with_only_logs() -> Result<(), Box<FilterError>> {
 			&client,
 			&test_data.network,
 			&test_data.blocks[0],
-			&[monitor],
+			&[Arc::new(monitor)],
 			Some(&[contract_with_spec]),
 		)
 		.await?;
@@ -1075,7 +1089,7 @@ async fn test_filter_block_needs_receipt_for_status() -> Result<(), Box<FilterEr
 			&client,
 			&test_data.network,
 			&test_data.blocks[0],
-			&[monitor],
+			&[Arc::new(monitor)],
 			Some(&[contract_with_spec]),
 		)
 		.await?;
@@ -1168,7 +1182,7 @@ async fn test_filter_block_with_tuples_contains_expression() -> Result<(), Box<F
 			&client,
 			&test_data.network,
 			test_data.blocks.last().unwrap(),
-			&[monitor],
+			&[Arc::new(monitor)],
 			Some(&[contract_with_spec]),
 		)
 		.await?;
@@ -1246,7 +1260,7 @@ async fn test_filter_block_with_tuples_expression_equality() -> Result<(), Box<F
 			&client,
 			&test_data.network,
 			test_data.blocks.last().unwrap(),
-			&[monitor],
+			&[Arc::new(monitor)],
 			Some(&[contract_with_spec]),
 		)
 		.await?;
@@ -1288,3 +1302,342 @@ async fn test_filter_block_with_tuples_expression_equality() -> Result<(), Box<F
 
 	Ok(())
 }
+
+/// Writes a contracts registry and monitors referencing it under a temporary config directory
+fn write_registry_config(
+	config_dir: &std::path::Path,
+	contract: serde_json::Value,
+	monitors: &[Monitor],
+) -> std::path::PathBuf {
+	let contracts_dir = config_dir.join("contracts");
+	let monitors_dir = config_dir.join("monitors");
+	std::fs::create_dir_all(&contracts_dir).unwrap();
+	std::fs::create_dir_all(&monitors_dir).unwrap();
+
+	std::fs::write(contracts_dir.join("usdc.json"), contract.to_string()).unwrap();
+	for (index, monitor) in monitors.iter().enumerate() {
+		std::fs::write(
+			monitors_dir.join(format!("monitor_{}.json", index)),
+			serde_json::to_string(monitor).unwrap(),
+		)
+		.unwrap();
+	}
+	monitors_dir
+}
+
+async fn load_registry_monitors(
+	monitors_dir: &std::path::Path,
+	test_data: &TestData,
+) -> Result<HashMap<String, Monitor>, openzeppelin_monitor::repositories::RepositoryError> {
+	let network_service = NetworkService::new_with_repository(NetworkRepository {
+		networks: HashMap::from([(test_data.network.slug.clone(), test_data.network.clone())]),
+	})
+	.unwrap();
+	let trigger_service = TriggerService::new_with_repository(TriggerRepository {
+		triggers: HashMap::new(),
+	})
+	.unwrap();
+
+	MonitorRepository::<NetworkRepository, TriggerRepository>::new(
+		Some(monitors_dir),
+		Some(network_service),
+		Some(trigger_service),
+	)
+	.await
+	.map(|repository| repository.get_all())
+}
+
+/// Returns a copy of the test monitor watching the given addresses without triggers
+fn make_registry_monitor(
+	test_data: &TestData,
+	name: &str,
+	addresses: Vec<AddressWithSpec>,
+) -> Monitor {
+	let mut monitor = make_monitor_with_events(test_data.monitor.clone(), true);
+	monitor.name = name.to_string();
+	monitor.triggers = vec![];
+	monitor.addresses = addresses;
+	monitor
+}
+
+#[tokio::test]
+async fn test_monitors_sharing_registry_contract_decode_events() -> Result<(), Box<FilterError>> {
+	let test_data = TestDataBuilder::new("evm").build();
+	let usdc_address = test_data.monitor.addresses[0].address.clone();
+	let usdc_spec = test_data.monitor.addresses[0]
+		.contract_spec
+		.clone()
+		.unwrap();
+
+	let by_address = make_registry_monitor(
+		&test_data,
+		"USDC by address",
+		vec![AddressWithSpec {
+			address: usdc_address.clone(),
+			contract_spec: None,
+			contract: Some("usdc".to_string()),
+			label: None,
+			annotations: HashMap::new(),
+		}],
+	);
+	let by_default = make_registry_monitor(
+		&test_data,
+		"USDC by default address",
+		vec![AddressWithSpec {
+			address: String::new(),
+			contract_spec: None,
+			contract: Some("usdc".to_string()),
+			label: None,
+			annotations: HashMap::new(),
+		}],
+	);
+
+	let config_dir = tempfile::tempdir().unwrap();
+	let monitors_dir = write_registry_config(
+		config_dir.path(),
+		json!({
+			"name": "usdc",
+			"contract_spec": usdc_spec,
+			"addresses": { (test_data.network.slug.clone()): [usdc_address] },
+		}),
+		&[by_address, by_default],
+	);
+	let monitors = load_registry_monitors(&monitors_dir, &test_data)
+		.await
+		.unwrap();
+	assert_eq!(monitors.len(), 2);
+	for monitor in monitors.values() {
+		assert_eq!(monitor.addresses.len(), 1);
+		assert_eq!(monitor.addresses[0].contract_spec, Some(usdc_spec.clone()));
+	}
+
+	let filter_service = FilterService::new();
+	let client = EvmClient::new_with_transport(setup_mock_transport(test_data.clone()));
+	let mut monitors = monitors.into_values().map(Arc::new).collect::<Vec<_>>();
+	monitors.sort_by(|a, b| a.name.cmp(&b.name));
+	let matches = filter_service
+		.filter_block(
+			&client,
+			&test_data.network,
+			&test_data.blocks[0],
+			&monitors,
+			None,
+		)
+		.await?;
+
+	assert_eq!(matches.len(), 2, "Expected one match per monitor");
+	for monitor_match in &matches {
+		let MonitorMatch::EVM(evm_match) = monitor_match else {
+			panic!("Expected EVM match");
+		};
+		let event_args = &evm_match
+			.matched_on_args
+			.as_ref()
+			.unwrap()
+			.events
+			.as_ref()
+			.unwrap()[0];
+		let args = event_args.args.as_ref().unwrap();
+		assert_eq!(args[0].name, "from");
+		assert_eq!(args[0].value, "0x58b704065b7aff3ed351052f8560019e05925023");
+		assert_eq!(args[2].name, "value");
+		assert_eq!(args[2].value, "8181710000");
+	}
+
+	Ok(())
+}
+
+#[tokio::test]
+async fn test_unknown_registry_contract_fails_load() {
+	let test_data = TestDataBuilder::new("evm").build();
+	let usdc_spec = test_data.monitor.addresses[0]
+		.contract_spec
+		.clone()
+		.unwrap();
+	let monitor = make_registry_monitor(
+		&test_data,
+		"NFT transfers",
+		vec![AddressWithSpec {
+			address: test_data.monitor.addresses[0].address.clone(),
+			contract_spec: None,
+			contract: Some("erc721".to_string()),
+			label: None,
+			annotations: HashMap::new(),
+		}],
+	);
+
+	let config_dir = tempfile::tempdir().unwrap();
+	let monitors_dir = write_registry_config(
+		config_dir.path(),
+		json!({ "name": "usdc", "contract_spec": usdc_spec }),
+		&[monitor],
+	);
+	let err = load_registry_monitors(&monitors_dir, &test_data)
+		.await
+		.unwrap_err();
+
+	assert!(err
+		.to_string()
+		.contains("Monitor 'NFT transfers' references unknown contract 'erc721'"));
+}
+
+#[tokio::test]
+async fn test_monitor_presets_expand_on_load() {
+	let test_data = TestDataBuilder::new("evm").build();
+	let usdc_spec = test_data.monitor.addresses[0]
+		.contract_spec
+		.clone()
+		.unwrap();
+	let admin_role = "0xa49807205ce4d355092ef5a8a18f56e8913cf4a201fbe287825b095693c21775";
+	let mut monitor = make_registry_monitor(
+		&test_data,
+		"Token admin",
+		test_data.monitor.addresses.clone(),
+	);
+	monitor.match_conditions.functions = vec![];
+	monitor.match_conditions.events = vec![];
+	monitor.presets = vec![
+		PresetReference::from("pausable"),
+		PresetReference::WithOverrides(PresetOverride {
+			name: "openzeppelin-access-control".to_string(),
+			expressions: HashMap::from([(
+				"RoleGranted(bytes32,address,address)".to_string(),
+				Some(format!("role == '{}'", admin_role)),
+			)]),
+		}),
+	];
+
+	let config_dir = tempfile::tempdir().unwrap();
+	let monitors_dir = write_registry_config(
+		config_dir.path(),
+		json!({ "name": "usdc", "contract_spec": usdc_spec }),
+		&[monitor],
+	);
+	let monitors = load_registry_monitors(&monitors_dir, &test_data)
+		.await
+		.unwrap();
+	let monitor = monitors.values().next().unwrap();
+	assert_eq!(monitor.name, "Token admin");
+
+	let events = monitor
+		.match_conditions
+		.events
+		.iter()
+		.map(|condition| (condition.signature.clone(), condition.expression.clone()))
+		.collect::<Vec<_>>();
+	assert_eq!(
+		events,
+		vec![
+			("Paused(address)".to_string(), None),
+			("Unpaused(address)".to_string(), None),
+			(
+				"RoleGranted(bytes32,address,address)".to_string(),
+				Some(format!("role == '{}'", admin_role))
+			),
+			("RoleRevoked(bytes32,address,address)".to_string(), None),
+			(
+				"RoleAdminChanged(bytes32,bytes32,bytes32)".to_string(),
+				None
+			),
+		]
+	);
+	// The preset names are kept for display and notifications
+	assert_eq!(monitor.presets.len(), 2);
+	assert_eq!(
+		monitor.preset_for_event("Unpaused(address)"),
+		Some("pausable")
+	);
+
+	// The preset events are added to the contract spec for decoding
+	let Some(ContractSpec::EVM(spec)) = &monitor.addresses[0].contract_spec else {
+		panic!("expected an EVM contract spec");
+	};
+	assert!(spec.events.contains_key("Transfer"));
+	assert!(spec.events.contains_key("RoleAdminChanged"));
+}
+
+#[tokio::test]
+async fn test_unknown_preset_fails_load() {
+	let test_data = TestDataBuilder::new("evm").build();
+	let usdc_spec = test_data.monitor.addresses[0]
+		.contract_spec
+		.clone()
+		.unwrap();
+	let mut monitor =
+		make_registry_monitor(&test_data, "Upgrades", test_data.monitor.addresses.clone());
+	monitor.presets = vec![PresetReference::from("uups-upgrade")];
+
+	let config_dir = tempfile::tempdir().unwrap();
+	let monitors_dir = write_registry_config(
+		config_dir.path(),
+		json!({ "name": "usdc", "contract_spec": usdc_spec }),
+		&[monitor],
+	);
+	let err = load_registry_monitors(&monitors_dir, &test_data)
+		.await
+		.unwrap_err();
+
+	let message = err.to_string();
+	assert!(message.contains(
+		"Monitor 'Upgrades' references unknown preset 'uups-upgrade', did you mean 'uups-upgrades'?"
+	));
+	assert!(message.contains("Available presets: ownable, ownable-2step"));
+}
+
+#[tokio::test]
+async fn test_identical_events_in_a_transaction_are_distinguishable() -> Result<(), Box<FilterError>>
+{
+	let mut test_data = TestDataBuilder::new("evm").build();
+	let monitor = make_monitor_with_events(test_data.monitor.clone(), false);
+
+	// The USDC transfer transaction emits the same Transfer event a second time
+	let first_log = test_data.receipts[0].logs[0].clone();
+	let second_log = EVMReceiptLog {
+		data: Bytes::from(U256::from(1234u64).to_be_bytes::<32>().to_vec()),
+		log_index: first_log.log_index.map(|index| index + U256::from(1)),
+		..first_log.clone()
+	};
+	test_data.receipts[0].0.logs.push(second_log);
+
+	let filter_service = FilterService::new();
+	let client = EvmClient::new_with_transport(setup_mock_transport(test_data.clone()));
+	let matches = filter_service
+		.filter_block(
+			&client,
+			&test_data.network,
+			&test_data.blocks[0],
+			&[Arc::new(monitor)],
+			None,
+		)
+		.await?;
+
+	assert_eq!(matches.len(), 1);
+	let MonitorMatch::EVM(evm_match) = &matches[0] else {
+		panic!("Expected EVM match");
+	};
+	assert_eq!(evm_match.matched_on.events.len(), 2);
+	let log_indexes = evm_match
+		.matched_on_args
+		.as_ref()
+		.unwrap()
+		.events
+		.as_ref()
+		.unwrap()
+		.iter()
+		.map(|event| event.log_index)
+		.collect::<Vec<_>>();
+	assert_eq!(log_indexes, vec![Some(353), Some(354)]);
+
+	let variables = match_variables(&matches[0], &[], None);
+	assert_eq!(variables["events.0.log_index"], "353");
+	assert_eq!(variables["events.0.args.value"], "8181710000");
+	assert_eq!(variables["events.1.log_index"], "354");
+	assert_eq!(variables["events.1.args.value"], "1234");
+	assert!(variables.contains_key("transaction.index"));
+	assert_eq!(
+		matches[0].match_key(),
+		format!("{}:353,354", matches[0].transaction_hash())
+	);
+
+	Ok(())
+}
@@ -0,0 +1,256 @@
+//! Integration tests for the sender activity condition of EVM monitors.
+//!
+//! Scripted block sequences are filtered and then checked against the nonce history of the
+//! monitored sender, in block order as the trigger handler does.
+
+use alloy::primitives::{Address, B256, U256, U64};
+use serde_json::json;
+use std::{
+	collections::HashMap,
+	sync::{Arc, Mutex},
+};
+
+use openzeppelin_monitor::{
+	models::{
+		BlockChainType, BlockType, EVMBlock, EVMSenderActivity, EVMTransaction, Monitor,
+		MonitorMatch, Network, ProcessedBlock, SenderActivityCondition, SenderActivityKind,
+	},
+	services::{
+		blockchain::{EvmClient, TransportError},
+		filter::{handle_match, FilterService, SenderActivityTracker},
+	},
+	utils::tests::{
+		evm::{monitor::MonitorBuilder, transaction::TransactionBuilder},
+		network::NetworkBuilder,
+	},
+};
+
+use crate::integration::mocks::{
+	MockEVMTransportClient, MockTriggerExecutionService, MockTriggerRepository,
+};
+
+const RELAYER: &str = "0x00000000000000000000000000000000000000aa";
+const OTHER: &str = "0x00000000000000000000000000000000000000bb";
+
+fn create_client() -> EvmClient<MockEVMTransportClient> {
+	let mut mock_transport = MockEVMTransportClient::new();
+	mock_transport
+		.expect_send_raw_request()
+		.returning(|method, _params| match method {
+			"eth_getLogs" => Ok(json!({ "result": [] })),
+			_ => Err(TransportError::http(
+				reqwest::StatusCode::METHOD_NOT_ALLOWED,
+				"random.url".to_string(),
+				"Unexpected method call".to_string(),
+				None,
+				None,
+			)),
+		});
+	EvmClient::new_with_transport(mock_transport)
+}
+
+fn create_network() -> Network {
+	NetworkBuilder::new()
+		.name("Ethereum Mainnet")
+		.slug("ethereum_mainnet")
+		.network_type(BlockChainType::EVM)
+		.build()
+}
+
+fn create_monitor(condition: SenderActivityCondition) -> Monitor {
+	MonitorBuilder::new()
+		.name("relayer_nonces")
+		.networks(vec!["ethereum_mainnet".to_string()])
+		.address(RELAYER)
+		.sender_activity(condition)
+		.build()
+}
+
+fn transaction(from: &str, nonce: u64, hash: u8) -> EVMTransaction {
+	TransactionBuilder::new()
+		.from(from.parse::<Address>().unwrap())
+		.to(OTHER.parse::<Address>().unwrap())
+		.nonce(U256::from(nonce))
+		.hash(B256::repeat_byte(hash))
+		.build()
+}
+
+fn create_block(number: u64, transactions: Vec<EVMTransaction>) -> BlockType {
+	let mut block = EVMBlock::default();
+	block.0.number = Some(U64::from(number));
+	block.0.transactions = transactions;
+	BlockType::EVM(Arc::new(block))
+}
+
+/// Filters the blocks in order and resolves their sender activity matches
+async fn run_blocks(
+	monitor: &Monitor,
+	tracker: &SenderActivityTracker,
+	blocks: Vec<(u64, Vec<EVMTransaction>)>,
+) -> Vec<(u64, MonitorMatch)> {
+	let client = create_client();
+	let network = create_network();
+	let filter_service = FilterService::new();
+	let mut resolved = Vec::new();
+
+	for (number, transactions) in blocks {
+		let matches = filter_service
+			.filter_block(
+				&client,
+				&network,
+				&create_block(number, transactions),
+				&[Arc::new(monitor.clone())],
+				None,
+			)
+			.await
+			.unwrap();
+		let block = tracker.resolve(ProcessedBlock {
+			block_number: number,
+			network_slug: network.slug.clone(),
+			processing_results: matches,
+		});
+		resolved.extend(block.processing_results.into_iter().map(|m| (number, m)));
+	}
+	resolved
+}
+
+fn activity(monitor_match: &MonitorMatch) -> EVMSenderActivity {
+	match monitor_match {
+		MonitorMatch::EVM(evm_match) => evm_match.sender_activity.clone().unwrap(),
+		_ => panic!("Expected EVM match"),
+	}
+}
+
+#[tokio::test]
+async fn test_sender_activity_gap_and_replacement() {
+	let monitor = create_monitor(SenderActivityCondition {
+		kinds: vec![],
+		stuck_after_secs: 300,
+		expression: None,
+	});
+	let tracker = SenderActivityTracker::new();
+
+	let matches = run_blocks(
+		&monitor,
+		&tracker,
+		vec![
+			(100, vec![transaction(RELAYER, 7, 0x01)]),
+			// Normal progression, and transactions of other senders are ignored
+			(
+				101,
+				vec![transaction(OTHER, 40, 0x02), transaction(RELAYER, 8, 0x03)],
+			),
+			(102, vec![transaction(RELAYER, 9, 0x04)]),
+			// Nonces 10 and 11 are skipped
+			(103, vec![transaction(RELAYER, 12, 0x05)]),
+			// Nonce 12 is used again by another transaction
+			(104, vec![transaction(RELAYER, 12, 0x06)]),
+			// Nonce 10 shows up and closes part of the gap
+			(105, vec![transaction(RELAYER, 10, 0x07)]),
+			(106, vec![transaction(RELAYER, 13, 0x08)]),
+		],
+	)
+	.await;
+
+	let activities = matches
+		.iter()
+		.map(|(number, m)| (*number, activity(m)))
+		.collect::<Vec<_>>();
+	assert_eq!(
+		activities,
+		vec![
+			(
+				103,
+				EVMSenderActivity {
+					kind: Some(SenderActivityKind::NonceGap),
+					nonce: 12,
+					expected_nonce: Some(10),
+					replaced_hash: None,
+					stuck_duration: None,
+				}
+			),
+			(
+				104,
+				EVMSenderActivity {
+					kind: Some(SenderActivityKind::Replacement),
+					nonce: 12,
+					expected_nonce: Some(13),
+					replaced_hash: Some(format!("0x{}", "05".repeat(32))),
+					stuck_duration: None,
+				}
+			),
+		]
+	);
+}
+
+#[tokio::test]
+async fn test_sender_activity_kinds_and_expression() {
+	let monitor = create_monitor(SenderActivityCondition {
+		kinds: vec![SenderActivityKind::NonceGap],
+		stuck_after_secs: 300,
+		expression: Some("expected_nonce >= 5".to_string()),
+	});
+	let tracker = SenderActivityTracker::new();
+
+	let matches = run_blocks(
+		&monitor,
+		&tracker,
+		vec![
+			(1, vec![transaction(RELAYER, 0, 0x01)]),
+			// Gap rejected by the expression
+			(2, vec![transaction(RELAYER, 2, 0x02)]),
+			// Replacement not matched by the condition's kinds
+			(3, vec![transaction(RELAYER, 2, 0x03)]),
+			(4, vec![transaction(RELAYER, 5, 0x04)]),
+			(5, vec![transaction(RELAYER, 7, 0x05)]),
+		],
+	)
+	.await;
+
+	assert_eq!(matches.len(), 1);
+	assert_eq!(matches[0].0, 5);
+	assert_eq!(activity(&matches[0].1).expected_nonce, Some(6));
+}
+
+#[tokio::test]
+async fn test_sender_activity_template_variables() {
+	let monitor = create_monitor(SenderActivityCondition {
+		kinds: vec![],
+		stuck_after_secs: 300,
+		expression: None,
+	});
+	let tracker = SenderActivityTracker::new();
+	let matches = run_blocks(
+		&monitor,
+		&tracker,
+		vec![
+			(1, vec![transaction(RELAYER, 0, 0x01)]),
+			(2, vec![transaction(RELAYER, 0, 0x02)]),
+		],
+	)
+	.await;
+	assert_eq!(matches.len(), 1);
+
+	let variables = Arc::new(Mutex::new(HashMap::new()));
+	let recorded = variables.clone();
+	let mut trigger_service = MockTriggerExecutionService::<MockTriggerRepository>::default();
+	trigger_service
+		.expect_execute()
+		.returning(move |_, data, _, _| {
+			*recorded.lock().unwrap() = data;
+			Ok(())
+		});
+
+	handle_match(matches[0].1.clone(), &trigger_service, &HashMap::new())
+		.await
+		.unwrap();
+
+	let variables = variables.lock().unwrap();
+	assert_eq!(variables["sender_activity.kind"], "replacement");
+	assert_eq!(variables["sender_activity.nonce"], "0");
+	assert_eq!(variables["sender_activity.expected_nonce"], "1");
+	assert_eq!(
+		variables["sender_activity.replaced_hash"],
+		format!("0x{}", "01".repeat(32))
+	);
+}
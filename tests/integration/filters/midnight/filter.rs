@@ -278,14 +278,14 @@ async fn test_monitor_error_cases() -> Result<(), Box<FilterError>> {
 	let mock_client = MockMidnightClientTrait::<MockMidnightWsTransportClient>::new();
 
 	// Create an invalid block type (e.g., EVM block for Midnight monitor)
-	let invalid_block = BlockType::EVM(Box::default());
+	let invalid_block = BlockType::EVM(Arc::default());
 
 	let result = filter_service
 		.filter_block(
 			&mock_client,
 			&network,
 			&invalid_block,
-			std::slice::from_ref(&monitor),
+			&[Arc::new(monitor.clone())],
 			None,
 		)
 		.await;
@@ -3,7 +3,7 @@
 //! Tests the monitoring functionality for the Stellar blockchain,
 //! including contract invocations and transaction filtering.
 
-use std::collections::HashMap;
+use std::{collections::HashMap, sync::Arc};
 
 use openzeppelin_monitor::{
 	models::{
@@ -40,6 +40,7 @@ fn make_monitor_with_events(mut monitor: Monitor, include_expression: bool) -> M
 		} else {
 			None
 		},
+		match_any_emitter: false,
 	});
 	monitor
 }
@@ -50,6 +51,9 @@ fn make_monitor_with_events_protocol_23(mut monitor: Monitor, include_expression
 	monitor.addresses = vec![AddressWithSpec {
 		address: "CBIELTK6YBZJU5UP2WWQEUCYKLPU6AUNZ2BQ4WWFEIE3USCIHMXQDAMC".to_string(),
 		contract_spec: None,
+		contract: None,
+		label: None,
+		annotations: HashMap::new(),
 	}];
 	monitor.match_conditions.functions = vec![];
 	monitor.match_conditions.transactions = vec![];
@@ -61,6 +65,7 @@ fn make_monitor_with_events_protocol_23(mut monitor: Monitor, include_expression
 		} else {
 			None
 		},
+		match_any_emitter: false,
 	});
 	monitor
 }
@@ -137,7 +142,7 @@ async fn test_monitor_events_with_no_expressions() -> Result<(), Box<FilterError
 			&mock_client,
 			&test_data.network,
 			&test_data.blocks[0],
-			&[monitor],
+			&[Arc::new(monitor)],
 			None,
 		)
 		.await?;
@@ -210,7 +215,7 @@ async fn test_monitor_events_with_expressions() -> Result<(), Box<FilterError>>
 			&mock_client,
 			&test_data.network,
 			&test_data.blocks[0],
-			&[monitor],
+			&[Arc::new(monitor)],
 			None,
 		)
 		.await?;
@@ -320,7 +325,7 @@ async fn test_monitor_functions_with_no_expressions() -> Result<(), Box<FilterEr
 			&mock_client,
 			&test_data.network,
 			&test_data.blocks[0],
-			&[monitor],
+			&[Arc::new(monitor)],
 			Some(&[contract_with_spec]),
 		)
 		.await?;
@@ -400,7 +405,7 @@ async fn test_monitor_functions_with_expressions() -> Result<(), Box<FilterError
 			&mock_client,
 			&test_data.network,
 			&test_data.blocks[0],
-			&[monitor],
+			&[Arc::new(monitor)],
 			Some(&[contract_with_spec]),
 		)
 		.await?;
@@ -494,7 +499,7 @@ async fn test_monitor_transactions_with_expressions() -> Result<(), Box<FilterEr
 			&mock_client,
 			&test_data.network,
 			&test_data.blocks[0],
-			&[monitor],
+			&[Arc::new(monitor)],
 			None,
 		)
 		.await?;
@@ -565,7 +570,7 @@ async fn test_monitor_transactions_with_no_expressions() -> Result<(), Box<Filte
 			&mock_client,
 			&test_data.network,
 			&test_data.blocks[0],
-			&[monitor],
+			&[Arc::new(monitor)],
 			None,
 		)
 		.await?;
@@ -640,7 +645,7 @@ async fn test_monitor_with_multiple_conditions() -> Result<(), Box<FilterError>>
 			&mock_client,
 			&test_data.network,
 			&test_data.blocks[0],
-			&[test_data.monitor],
+			&[Arc::new(test_data.monitor)],
 			Some(&[transfer_contract_with_spec, upsert_contract_with_spec]),
 		)
 		.await?;
@@ -713,14 +718,14 @@ async fn test_monitor_error_cases() -> Result<(), Box<FilterError>> {
 	let mock_client = MockStellarClientTrait::<MockStellarTransportClient>::new();
 
 	// Create an invalid block type
-	let invalid_block = BlockType::EVM(Box::default());
+	let invalid_block = BlockType::EVM(Arc::default());
 
 	let result = filter_service
 		.filter_block(
 			&mock_client,
 			&test_data.network,
 			&invalid_block,
-			&[test_data.monitor],
+			&[Arc::new(test_data.monitor)],
 			None,
 		)
 		.await;
@@ -828,7 +833,7 @@ async fn test_handle_match() -> Result<(), Box<FilterError>> {
 			&mock_client,
 			&test_data.network,
 			&test_data.blocks[0],
-			&[test_data.monitor],
+			&[Arc::new(test_data.monitor)],
 			Some(&[contract_with_spec]),
 		)
 		.await?;
@@ -900,7 +905,7 @@ async fn test_handle_match_with_no_args() -> Result<(), Box<FilterError>> {
 			&mock_client,
 			&test_data.network,
 			&test_data.blocks[0],
-			&[monitor],
+			&[Arc::new(monitor)],
 			Some(&[contract_with_spec]),
 		)
 		.await?;
@@ -1012,6 +1017,9 @@ async fn test_handle_match_with_key_collision() -> Result<(), Box<FilterError>>
 			}],
 			events: vec![],
 			transactions: vec![],
+			sender_activity: None,
+			baseline: None,
+			network_metrics: None,
 		},
 		matched_on_args: Some(StellarMatchArguments {
 			functions: Some(vec![StellarMatchParamsMap {
@@ -1030,9 +1038,13 @@ async fn test_handle_match_with_key_collision() -> Result<(), Box<FilterError>>
 						indexed: false,
 					},
 				]),
+				operation_index: None,
+				event_index: None,
 			}]),
 			events: None,
 		}),
+		timing: None,
+		origin: Default::default(),
 	};
 
 	let match_wrapper = MonitorMatch::Stellar(Box::new(stellar_match));
@@ -1144,7 +1156,7 @@ async fn test_filter_with_contract_spec() -> Result<(), Box<FilterError>> {
 			&mock_client,
 			&test_data.network,
 			&test_data.blocks[0],
-			&[monitor],
+			&[Arc::new(monitor)],
 			Some(&[contract_with_spec]),
 		)
 		.await?;
@@ -1214,7 +1226,7 @@ async fn test_filter_with_invalid_contract_spec() -> Result<(), Box<FilterError>
 			&mock_client,
 			&test_data.network,
 			&test_data.blocks[0],
-			&[monitor],
+			&[Arc::new(monitor)],
 			None,
 		)
 		.await;
@@ -1275,6 +1287,9 @@ async fn test_filter_with_abi_in_config() -> Result<(), Box<FilterError>> {
 	monitor.addresses = vec![AddressWithSpec {
 		address: contract_with_spec.0.clone(),
 		contract_spec: Some(contract_with_spec.1.clone()),
+		contract: None,
+		label: None,
+		annotations: HashMap::new(),
 	}];
 
 	// Run filter_block with the test data
@@ -1283,7 +1298,7 @@ async fn test_filter_with_abi_in_config() -> Result<(), Box<FilterError>> {
 			&mock_client,
 			&test_data.network,
 			&test_data.blocks[0],
-			&[monitor],
+			&[Arc::new(monitor)],
 			Some(&[contract_with_spec]),
 		)
 		.await?;
@@ -1400,6 +1415,9 @@ async fn test_filter_with_udt_expression() -> Result<(), Box<FilterError>> {
 	monitor.addresses = vec![AddressWithSpec {
 		address: contract_with_spec.0.clone(),
 		contract_spec: Some(contract_with_spec.1.clone()),
+		contract: None,
+		label: None,
+		annotations: HashMap::new(),
 	}];
 
 	// Run filter_block with the test data
@@ -1408,7 +1426,7 @@ async fn test_filter_with_udt_expression() -> Result<(), Box<FilterError>> {
 			&mock_client,
 			&test_data.network,
 			&test_data.blocks[0],
-			&[monitor],
+			&[Arc::new(monitor)],
 			Some(&[contract_with_spec]),
 		)
 		.await?;
@@ -1526,7 +1544,7 @@ async fn test_handle_match_with_event_spec() -> Result<(), Box<FilterError>> {
 			&mock_client,
 			&test_data.network,
 			&test_data.blocks[0],
-			&[monitor],
+			&[Arc::new(monitor)],
 			Some(&[contract_with_spec]),
 		)
 		.await?;
@@ -23,7 +23,7 @@ use openzeppelin_monitor::{
 			BlockChainClient, BlockFilterFactory, ClientPoolTrait, EvmClientTrait,
 			MidnightClientTrait, MidnightSubstrateClientTrait, StellarClientTrait,
 		},
-		filter::{EVMBlockFilter, MidnightBlockFilter, StellarBlockFilter},
+		filter::{EVMBlockFilter, FilterService, MidnightBlockFilter, StellarBlockFilter},
 	},
 };
 
@@ -63,6 +63,8 @@ mock! {
 			to_block: u64,
 			addresses: Option<Vec<String>>,
 		) -> Result<Vec<EVMReceiptLog>,  anyhow::Error>;
+
+		async fn call_contract(&self, to: String, data: Vec<u8>) -> Result<Vec<u8>,  anyhow::Error>;
 	}
 
 	impl<T: Send + Sync + Clone + 'static> Clone for EvmClientTrait<T> {
@@ -160,7 +162,7 @@ impl<T: Send + Sync + Clone + 'static> BlockFilterFactory<MockMidnightClientTrai
 	for MockMidnightClientTrait<T>
 {
 	type Filter = MidnightBlockFilter<MockMidnightClientTrait<T>>;
-	fn filter() -> Self::Filter {
+	fn filter(_service: &FilterService) -> Self::Filter {
 		MidnightBlockFilter {
 			_client: PhantomData,
 		}
@@ -171,7 +173,7 @@ impl<T: Send + Sync + Clone + 'static> BlockFilterFactory<MockStellarClientTrait
 	for MockStellarClientTrait<T>
 {
 	type Filter = StellarBlockFilter<MockStellarClientTrait<T>>;
-	fn filter() -> Self::Filter {
+	fn filter(_service: &FilterService) -> Self::Filter {
 		StellarBlockFilter {
 			_client: PhantomData,
 		}
@@ -182,9 +184,13 @@ impl<T: Send + Sync + Clone + 'static> BlockFilterFactory<MockEvmClientTrait<T>>
 	for MockEvmClientTrait<T>
 {
 	type Filter = EVMBlockFilter<MockEvmClientTrait<T>>;
-	fn filter() -> Self::Filter {
+	fn filter(service: &FilterService) -> Self::Filter {
 		EVMBlockFilter {
 			_client: PhantomData,
+			price_oracles: Some(service.price_oracles().clone()),
+			prices: None,
+			decode_failures: Default::default(),
+			decode_failure_tracker: Some(service.decode_failures().clone()),
 		}
 	}
 }
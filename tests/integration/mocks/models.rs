@@ -11,6 +11,7 @@ use openzeppelin_monitor::{
 	},
 };
 use serde_json::json;
+use std::sync::Arc;
 
 pub fn create_test_network(name: &str, slug: &str, network_type: BlockChainType) -> Network {
 	NetworkBuilder::new()
@@ -105,7 +106,7 @@ pub fn create_http_valid_server_mock_network_response(server: &mut Server) -> Mo
 
 pub fn create_test_block(chain: BlockChainType, block_number: u64) -> BlockType {
 	match chain {
-		BlockChainType::EVM => BlockType::EVM(Box::new(EVMBlock::from(alloy::rpc::types::Block {
+		BlockChainType::EVM => BlockType::EVM(Arc::new(EVMBlock::from(alloy::rpc::types::Block {
 			header: alloy::rpc::types::Header {
 				hash: alloy::primitives::B256::ZERO,
 				inner: alloy::consensus::Header {
@@ -1,13 +1,15 @@
 use async_trait::async_trait;
 use mockall::mock;
-use std::collections::HashMap;
+use std::{collections::HashMap, sync::Arc};
 
 use openzeppelin_monitor::{
-	models::{BlockType, ContractSpec, Monitor, MonitorMatch, Network, ScriptLanguage},
+	models::{BlockType, ContractSpec, Monitor, MonitorMatch, Network, ScriptLanguage, Trigger},
 	repositories::{TriggerRepositoryTrait, TriggerService},
 	services::{
 		blockchain::BlockFilterFactory,
-		blockwatcher::{BlockCheckResult, BlockStorage, BlockTrackerTrait, JobSchedulerTrait},
+		blockwatcher::{
+			BlockCheckResult, BlockStorage, BlockTrackerTrait, FailedBlock, JobSchedulerTrait,
+		},
 		filter::{FilterError, FilterServiceTrait},
 		notification::NotificationService,
 		trigger::{TriggerError, TriggerExecutionServiceTrait},
@@ -29,6 +31,7 @@ mock! {
 			trigger_scripts: &HashMap<String, (ScriptLanguage, String)>,
 		) -> Result<(), TriggerError>;
 		async fn load_scripts(&self, monitors: &[Monitor]) -> Result<HashMap<String, (ScriptLanguage, String)>, TriggerError>;
+		fn get_trigger(&self, trigger_slug: &str) -> Option<Arc<Trigger>>;
 	}
 }
 
@@ -39,7 +42,7 @@ mock! {
 			client: &T,
 			network: &Network,
 			block: &BlockType,
-			monitors: &[Monitor],
+			monitors: &[Arc<Monitor>],
 		) -> Result<Vec<MonitorMatch>, FilterError>;
 	}
 }
@@ -51,7 +54,7 @@ impl FilterServiceTrait for MockFilterService {
 		client: &T,
 		network: &Network,
 		block: &BlockType,
-		monitors: &[Monitor],
+		monitors: &[Arc<Monitor>],
 		_contract_specs: Option<&[(String, ContractSpec)]>,
 	) -> Result<Vec<MonitorMatch>, FilterError> {
 		self.filter_block(client, network, block, monitors).await
@@ -67,6 +70,8 @@ mock! {
 		async fn get_last_processed_block(&self, network_slug: &str) -> Result<Option<u64>, anyhow::Error>;
 		async fn save_blocks(&self, network_slug: &str, blocks: &[BlockType]) -> Result<(), anyhow::Error>;
 		async fn delete_blocks(&self, network_slug: &str) -> Result<(), anyhow::Error>;
+		async fn get_failed_blocks(&self, network_slug: &str) -> Result<Vec<FailedBlock>, anyhow::Error>;
+		async fn save_failed_blocks(&self, network_slug: &str, blocks: &[FailedBlock]) -> Result<(), anyhow::Error>;
 	}
 
 	impl Clone for BlockStorage {
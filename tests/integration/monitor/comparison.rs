@@ -0,0 +1,156 @@
+//! Integration tests for comparing two versions of a monitor.
+//!
+//! Both versions are evaluated over the recorded EVM fixtures, the new version narrowing the
+//! expression of its event condition.
+
+use crate::integration::{
+	filters::common::{TestData, TestDataBuilder},
+	mocks::{MockClientPool, MockEvmClientTrait},
+};
+use mockall::predicate;
+use openzeppelin_monitor::{
+	models::{BlockType, EVMTransactionReceipt, Monitor},
+	services::{blockchain::FixtureClientPool, filter::FilterService},
+	utils::monitor::comparison::{
+		compare_monitors, ComparisonGate, ComparisonReport, MonitorComparisonConfig,
+	},
+};
+use std::{collections::HashMap, sync::Arc};
+
+const BLOCK_NUMBER: u64 = 21306058;
+
+/// Creates a client pool serving the recorded block, logs and receipts
+fn create_client_pool(test_data: &TestData) -> MockClientPool {
+	let mut mock_client = MockEvmClientTrait::new();
+
+	let blocks: Vec<BlockType> = test_data.blocks.clone();
+	mock_client
+		.expect_get_blocks()
+		.with(
+			predicate::eq(BLOCK_NUMBER),
+			predicate::eq(Some(BLOCK_NUMBER)),
+		)
+		.times(1)
+		.returning(move |_, _| Ok(blocks.clone()));
+
+	let logs = test_data
+		.receipts
+		.iter()
+		.flat_map(|r| r.logs.clone())
+		.collect::<Vec<_>>();
+	mock_client
+		.expect_get_logs_for_blocks()
+		.returning(move |_, _, _| Ok(logs.clone()));
+
+	let receipts: HashMap<String, EVMTransactionReceipt> = test_data
+		.receipts
+		.iter()
+		.map(|r| (format!("0x{:x}", r.transaction_hash), r.clone()))
+		.collect();
+	mock_client
+		.expect_get_transaction_receipt()
+		.returning(move |hash| Ok(receipts.get(&hash).cloned().unwrap()));
+
+	let mock_client = Arc::new(mock_client);
+	let mut mock_pool = MockClientPool::new();
+	mock_pool
+		.expect_get_evm_client()
+		.returning(move |_| Ok(mock_client.clone()));
+	mock_pool
+}
+
+async fn compare(
+	test_data: &TestData,
+	old_monitor: Monitor,
+	new_monitor: Monitor,
+) -> ComparisonReport {
+	compare_monitors(MonitorComparisonConfig {
+		old_monitor,
+		new_monitor,
+		network: test_data.network.clone(),
+		from_block: BLOCK_NUMBER,
+		to_block: BLOCK_NUMBER,
+		filter_service: Arc::new(FilterService::new()),
+		client_pool: Arc::new(create_client_pool(test_data)),
+	})
+	.await
+	.unwrap()
+}
+
+#[tokio::test]
+async fn test_compare_narrowed_expression() {
+	let test_data = TestDataBuilder::new("evm").build();
+	let old_monitor = test_data.monitor.clone();
+	let mut new_monitor = test_data.monitor.clone();
+	new_monitor.match_conditions.events[0].expression = Some(
+		"to == 0xf423d9c1ffeb6386639d024f3b241dab2331b635 AND from == \
+		 0x58b704065b7aff3ed351052f8560019e05925023 AND value > 9000000000"
+			.to_string(),
+	);
+
+	let report = compare(&test_data, old_monitor, new_monitor).await;
+
+	assert_eq!(report.old_monitor, test_data.monitor.name);
+	assert_eq!(report.only_in_old.len(), 1);
+	assert_eq!(
+		report.only_in_old[0].condition,
+		"event Transfer(address,address,uint256)"
+	);
+	assert_eq!(report.only_in_old[0].block_number, Some(BLOCK_NUMBER));
+	assert!(report.only_in_new.is_empty());
+	assert!(!report.common.is_empty());
+	assert!(!report.common.contains(&report.only_in_old[0]));
+	assert!(report.fails(ComparisonGate::OnlyInOld));
+	assert!(!report.fails(ComparisonGate::OnlyInNew));
+
+	let csv = report.to_csv();
+	assert!(csv
+		.lines()
+		.nth(1)
+		.unwrap()
+		.starts_with("only_in_old,21306058,"));
+	let json: serde_json::Value = serde_json::from_str(&report.to_json().unwrap()).unwrap();
+	assert_eq!(json["only_in_old"].as_array().unwrap().len(), 1);
+}
+
+#[tokio::test]
+async fn test_compare_replays_recorded_fixtures() {
+	let test_data = TestDataBuilder::new("evm").build();
+	let mut new_monitor = test_data.monitor.clone();
+	new_monitor.match_conditions.events[0].expression = Some("value > 9000000000".to_string());
+
+	let report = compare_monitors(MonitorComparisonConfig {
+		old_monitor: test_data.monitor.clone(),
+		new_monitor,
+		network: test_data.network.clone(),
+		from_block: BLOCK_NUMBER,
+		to_block: BLOCK_NUMBER,
+		filter_service: Arc::new(FilterService::new()),
+		client_pool: Arc::new(FixtureClientPool::new(
+			"tests/integration/fixtures/recorded",
+		)),
+	})
+	.await
+	.unwrap();
+
+	assert_eq!(report.only_in_old.len(), 1);
+	assert_eq!(report.only_in_old[0].block_number, Some(BLOCK_NUMBER));
+	assert!(report.only_in_new.is_empty());
+}
+
+#[tokio::test]
+async fn test_compare_identical_monitors() {
+	let test_data = TestDataBuilder::new("evm").build();
+
+	let report = compare(
+		&test_data,
+		test_data.monitor.clone(),
+		test_data.monitor.clone(),
+	)
+	.await;
+
+	assert!(report.only_in_old.is_empty());
+	assert!(report.only_in_new.is_empty());
+	assert!(!report.common.is_empty());
+	assert!(!report.fails(ComparisonGate::AnyChange));
+}
@@ -0,0 +1,150 @@
+//! Integration tests for the import of Defender Sentinel exports.
+
+use std::collections::HashMap;
+
+use openzeppelin_monitor::{
+	models::{
+		ConfigLoader, Monitor, SecretValue, TransactionStatus, Trigger, TriggerReference,
+		TriggerTypeConfig,
+	},
+	utils::monitor::defender::{import_defender_export, parse_defender_export, DefenderImport},
+};
+use tempfile::TempDir;
+
+const EXPORT_PATH: &str = "tests/integration/fixtures/defender/sentinel_export.json";
+
+fn import_fixture() -> DefenderImport {
+	let content = std::fs::read_to_string(EXPORT_PATH).unwrap();
+	let export = parse_defender_export(&content).unwrap();
+	let networks = HashMap::from([("mainnet".to_string(), "ethereum_mainnet".to_string())]);
+	import_defender_export(&export, &networks)
+}
+
+fn reported_fields(import: &DefenderImport, source: &str) -> Vec<String> {
+	import
+		.report
+		.iter()
+		.filter(|entry| entry.source == source)
+		.map(|entry| entry.field.clone())
+		.collect()
+}
+
+fn trigger_slugs(monitor: &Monitor) -> Vec<&str> {
+	monitor
+		.triggers
+		.iter()
+		.map(TriggerReference::name)
+		.collect()
+}
+
+#[test]
+fn test_import_event_sentinel_with_threshold() {
+	let import = import_fixture();
+	let monitor = &import.monitors["large_usdc_transfers"];
+
+	assert!(monitor.paused);
+	assert_eq!(monitor.networks, vec!["ethereum_mainnet"]);
+	assert_eq!(monitor.addresses.len(), 1);
+	assert!(monitor.addresses[0].contract_spec.is_some());
+
+	let event = &monitor.match_conditions.events[0];
+	assert_eq!(event.signature, "Transfer(address,address,uint256)");
+	assert_eq!(
+		event.expression.as_deref(),
+		Some("value > 1000000000000 AND to != \"0x0000000000000000000000000000000000000000\"")
+	);
+
+	// The threshold has no equivalent, and PagerDuty channels are not imported
+	assert_eq!(trigger_slugs(monitor), vec!["defender_security_slack"]);
+	let fields = reported_fields(&import, "Large USDC Transfers");
+	assert!(fields.contains(&"alertThreshold".to_string()));
+	assert!(fields.contains(&"notificationChannels".to_string()));
+	assert!(reported_fields(&import, "PagerDuty").contains(&"type".to_string()));
+}
+
+#[test]
+fn test_import_function_sentinel() {
+	let import = import_fixture();
+	let monitor = &import.monitors["ownership_changes"];
+
+	let function = &monitor.match_conditions.functions[0];
+	assert_eq!(function.signature, "transferOwnership(address)");
+	assert_eq!(function.expression, None);
+	assert_eq!(
+		monitor.match_conditions.transactions[0].status,
+		TransactionStatus::Success
+	);
+	assert_eq!(trigger_slugs(monitor), vec!["defender_ops_email"]);
+	assert!(reported_fields(&import, "Ownership Changes").is_empty());
+
+	match &import.triggers["defender_ops_email"].config {
+		TriggerTypeConfig::Email {
+			username,
+			recipients,
+			..
+		} => {
+			assert_eq!(recipients.len(), 2);
+			assert!(matches!(
+				username,
+				SecretValue::Environment(name) if name == "DEFENDER_OPS_EMAIL_SMTP_USERNAME"
+			));
+		}
+		config => panic!("unexpected trigger config {:?}", config),
+	}
+}
+
+#[test]
+fn test_import_reports_unsupported_features() {
+	let import = import_fixture();
+	let monitor = &import.monitors["bridge_withdrawals"];
+
+	// The condition with an untranslatable expression is left out rather than widened
+	assert_eq!(monitor.match_conditions.events.len(), 1);
+	assert_eq!(
+		monitor.match_conditions.events[0].signature,
+		"Withdrawal(address,uint256)"
+	);
+	assert_eq!(
+		reported_fields(&import, "Bridge Withdrawals"),
+		vec!["eventConditions", "autotaskCondition"]
+	);
+
+	assert!(!import.monitors.contains_key("forta_bot_alerts"));
+	assert_eq!(reported_fields(&import, "Forta Bot Alerts"), vec!["type"]);
+}
+
+#[tokio::test]
+async fn test_import_writes_loadable_configuration() {
+	let import = import_fixture();
+	let temp_dir = TempDir::new().unwrap();
+
+	let paths = import.write(temp_dir.path()).unwrap();
+	assert_eq!(paths.len(), import.monitors.len() + 2);
+
+	for name in import.monitors.keys() {
+		let path = temp_dir
+			.path()
+			.join("monitors")
+			.join(format!("{}.json", name));
+		let monitor = Monitor::load_from_path(&path).await.unwrap();
+		assert_eq!(&monitor, &import.monitors[name]);
+	}
+
+	let content =
+		std::fs::read_to_string(temp_dir.path().join("triggers/defender_import.json")).unwrap();
+	let triggers: HashMap<String, Trigger> = serde_json::from_str(&content).unwrap();
+	assert_eq!(triggers.len(), 2);
+	assert!(matches!(
+		&triggers["defender_security_slack"].config,
+		TriggerTypeConfig::Slack {
+			slack_url: SecretValue::Environment(name),
+			..
+		} if name == "DEFENDER_SECURITY_SLACK_SLACK_URL"
+	));
+
+	let report: serde_json::Value = serde_json::from_str(
+		&std::fs::read_to_string(temp_dir.path().join("defender_import_report.json")).unwrap(),
+	)
+	.unwrap();
+	assert_eq!(report.as_array().unwrap().len(), import.report.len());
+}
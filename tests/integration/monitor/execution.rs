@@ -1553,7 +1553,7 @@ async fn test_load_from_path() {
 	let monitor = result.unwrap();
 	assert_eq!(monitor.name, "monitor");
 	assert!(monitor.networks.contains(&"ethereum_mainnet".to_string()));
-	assert!(monitor.triggers.contains(&"test-trigger".to_string()));
+	assert!(monitor.triggers.contains(&"test-trigger".into()));
 }
 
 #[tokio::test]
@@ -1599,7 +1599,7 @@ async fn test_load_from_path_with_services() {
 	let monitor = result.unwrap();
 	assert_eq!(monitor.name, "monitor");
 	assert!(monitor.networks.contains(&"ethereum_mainnet".to_string()));
-	assert!(monitor.triggers.contains(&"test-trigger".to_string()));
+	assert!(monitor.triggers.contains(&"test-trigger".into()));
 }
 
 #[tokio::test]
@@ -1651,7 +1651,7 @@ async fn test_load_from_path_trait_implementation() {
 	let monitor = result.unwrap();
 	assert_eq!(monitor.name, "monitor");
 	assert!(monitor.networks.contains(&"ethereum_mainnet".to_string()));
-	assert!(monitor.triggers.contains(&"test-trigger".to_string()));
+	assert!(monitor.triggers.contains(&"test-trigger".into()));
 }
 
 #[tokio::test]
@@ -0,0 +1,137 @@
+use chrono::{TimeZone, Utc};
+use mockito::Server;
+use openzeppelin_monitor::{
+	models::{MonitorMatch, TriggerTypeConfig},
+	repositories::{TriggerRepository, TriggerService},
+	services::{
+		notification::NotificationService,
+		state::{InMemoryStateStore, StateStore},
+		trigger::{
+			CircuitBreakerConfig, CircuitState, TriggerCircuitBreakers, TriggerExecutionService,
+			TriggerExecutionServiceTrait,
+		},
+	},
+	utils::tests::{
+		evm::{monitor::MonitorBuilder, monitor_match::MonitorMatchBuilder},
+		trigger::TriggerBuilder,
+	},
+};
+use std::{
+	collections::HashMap,
+	sync::{
+		atomic::{AtomicI64, Ordering},
+		Arc,
+	},
+	time::Duration,
+};
+
+const BACKOFF_MS: i64 = 60_000;
+
+fn create_test_evm_match() -> MonitorMatch {
+	MonitorMatchBuilder::new()
+		.monitor(
+			MonitorBuilder::new()
+				.name("test_monitor")
+				.triggers(vec!["failing_webhook".to_string()])
+				.build(),
+		)
+		.build()
+}
+
+/// Creates the delivery layer of a process, persisting its circuit breakers to `store`
+fn create_trigger_execution_service(
+	url: &str,
+	store: Arc<dyn StateStore>,
+	now: &Arc<AtomicI64>,
+) -> (
+	TriggerExecutionService<TriggerRepository>,
+	Arc<TriggerCircuitBreakers>,
+) {
+	let mut trigger = TriggerBuilder::new()
+		.name("failing_webhook")
+		.webhook(url)
+		.build();
+	if let TriggerTypeConfig::Webhook { retry_policy, .. } = &mut trigger.config {
+		retry_policy.max_retries = 0;
+	}
+	let trigger_service = TriggerService::new_with_repository(TriggerRepository {
+		triggers: HashMap::from([("failing_webhook".to_string(), trigger)]),
+	})
+	.unwrap();
+
+	let time = now.clone();
+	let circuit_breakers = Arc::new(
+		TriggerCircuitBreakers::new(
+			store,
+			CircuitBreakerConfig {
+				failure_threshold: 2,
+				backoff: Duration::from_millis(BACKOFF_MS as u64),
+				..Default::default()
+			},
+		)
+		.with_clock(Arc::new(move || {
+			Utc.timestamp_millis_opt(time.load(Ordering::SeqCst))
+				.unwrap()
+		})),
+	);
+	let service = TriggerExecutionService::new(trigger_service, NotificationService::new())
+		.with_circuit_breakers(circuit_breakers.clone());
+	(service, circuit_breakers)
+}
+
+async fn deliver(service: &TriggerExecutionService<TriggerRepository>) -> bool {
+	service
+		.execute(
+			&["failing_webhook".to_string()],
+			HashMap::new(),
+			&create_test_evm_match(),
+			&HashMap::new(),
+		)
+		.await
+		.is_ok()
+}
+
+#[tokio::test]
+async fn test_open_breaker_survives_a_restart() {
+	let mut server = Server::new_async().await;
+	let failing = server
+		.mock("POST", "/")
+		.with_status(500)
+		.expect(2)
+		.create_async()
+		.await;
+
+	let store: Arc<dyn StateStore> = Arc::new(InMemoryStateStore::new());
+	let now = Arc::new(AtomicI64::new(Utc::now().timestamp_millis()));
+	let opened_at = now.load(Ordering::SeqCst);
+
+	// Two failed deliveries open the breaker
+	let (service, _) = create_trigger_execution_service(&server.url(), store.clone(), &now);
+	assert!(!deliver(&service).await);
+	assert!(!deliver(&service).await);
+	failing.assert_async().await;
+	drop(service);
+
+	// The restarted process restores the breaker and does not call the endpoint
+	let (service, circuit_breakers) = create_trigger_execution_service(&server.url(), store, &now);
+	assert_eq!(circuit_breakers.restore(["failing_webhook"]).await, 1);
+	now.store(opened_at + BACKOFF_MS - 1, Ordering::SeqCst);
+	assert!(!deliver(&service).await);
+	failing.assert_async().await;
+
+	// Once the persisted deadline has passed, a delivery probes the recovered endpoint
+	failing.remove_async().await;
+	let recovered = server
+		.mock("POST", "/")
+		.with_status(200)
+		.expect(1)
+		.create_async()
+		.await;
+	now.store(opened_at + BACKOFF_MS, Ordering::SeqCst);
+	assert!(deliver(&service).await);
+	recovered.assert_async().await;
+	assert_eq!(
+		circuit_breakers.circuit("failing_webhook").await.state,
+		CircuitState::Closed
+	);
+}
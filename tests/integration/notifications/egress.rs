@@ -0,0 +1,195 @@
+use futures::future::BoxFuture;
+use mockito::Server;
+use openzeppelin_monitor::{
+	services::notification::{
+		EgressControls, EgressPolicy, HostResolver, NotificationClientPool, NotificationError,
+		WebhookConfig, WebhookNotifier,
+	},
+	utils::RetryConfig,
+};
+use serde_json::json;
+use std::{
+	collections::HashMap,
+	net::IpAddr,
+	sync::{
+		atomic::{AtomicUsize, Ordering},
+		Arc,
+	},
+};
+
+/// Resolver answering from a fixed table, counting the resolutions
+struct MockResolver {
+	hosts: HashMap<String, IpAddr>,
+	resolutions: AtomicUsize,
+}
+
+impl MockResolver {
+	fn new(hosts: &[(&str, &str)]) -> Arc<Self> {
+		Arc::new(Self {
+			hosts: hosts
+				.iter()
+				.map(|(host, ip)| (host.to_string(), ip.parse().unwrap()))
+				.collect(),
+			resolutions: AtomicUsize::new(0),
+		})
+	}
+}
+
+impl HostResolver for MockResolver {
+	fn resolve(&self, host: &str) -> BoxFuture<'static, std::io::Result<Vec<IpAddr>>> {
+		self.resolutions.fetch_add(1, Ordering::SeqCst);
+		let result = self
+			.hosts
+			.get(host)
+			.map(|ip| vec![*ip])
+			.ok_or_else(|| std::io::Error::new(std::io::ErrorKind::NotFound, "unknown host"));
+		Box::pin(async move { result })
+	}
+}
+
+fn create_policy(allow: &[&str], deny: &[&str]) -> EgressPolicy {
+	EgressPolicy {
+		hardening: true,
+		allow: allow.iter().map(|range| range.parse().unwrap()).collect(),
+		deny: deny.iter().map(|range| range.parse().unwrap()).collect(),
+		pin_dns: false,
+	}
+}
+
+async fn create_notifier(
+	url: String,
+	policy: EgressPolicy,
+	resolver: Arc<MockResolver>,
+) -> WebhookNotifier {
+	let pool = NotificationClientPool::with_egress(Some(EgressControls::new(policy, resolver)));
+	let http_client = pool
+		.get_or_create_http_client(&RetryConfig::default())
+		.await
+		.unwrap();
+	let config = WebhookConfig {
+		url,
+		url_params: None,
+		title: "Test Alert".to_string(),
+		body_template: "Test message".to_string(),
+		method: Some("POST".to_string()),
+		secret: None,
+		headers: None,
+		payload_fields: None,
+	};
+	WebhookNotifier::new(config, http_client).unwrap()
+}
+
+#[tokio::test]
+async fn test_egress_allows_allowlisted_resolution() {
+	let mut server = Server::new_async().await;
+	let mock = server
+		.mock("POST", "/")
+		.with_status(200)
+		.create_async()
+		.await;
+	let port = server.socket_address().port();
+	let resolver = MockResolver::new(&[("hooks.allowed.test", "127.0.0.1")]);
+
+	let notifier = create_notifier(
+		format!("http://hooks.allowed.test:{}/", port),
+		create_policy(&["127.0.0.0/8"], &[]),
+		resolver.clone(),
+	)
+	.await;
+	let result = notifier.notify_json(&json!({"text": "hello"})).await;
+
+	assert!(result.is_ok(), "{:?}", result);
+	assert_eq!(resolver.resolutions.load(Ordering::SeqCst), 1);
+	mock.assert();
+}
+
+#[tokio::test]
+async fn test_egress_denies_private_resolution_without_retrying() {
+	let resolver = MockResolver::new(&[("hooks.internal.test", "10.20.30.40")]);
+
+	let notifier = create_notifier(
+		"http://hooks.internal.test/".to_string(),
+		create_policy(&[], &["10.0.0.0/8"]),
+		resolver.clone(),
+	)
+	.await;
+	let error = notifier
+		.notify_json(&json!({"text": "hello"}))
+		.await
+		.unwrap_err();
+
+	assert!(matches!(error, NotificationError::EgressDenied(_)));
+	assert!(error.to_string().contains("10.20.30.40"));
+	// Violations are not retried
+	assert_eq!(resolver.resolutions.load(Ordering::SeqCst), 1);
+}
+
+#[tokio::test]
+async fn test_egress_denies_addresses_outside_allowlist() {
+	let mut server = Server::new_async().await;
+	let mock = server
+		.mock("POST", "/")
+		.with_status(200)
+		.expect(0)
+		.create_async()
+		.await;
+
+	// Endpoints given as addresses are not resolved, but still checked
+	let notifier = create_notifier(
+		format!("{}/", server.url()),
+		create_policy(&["203.0.113.0/24"], &[]),
+		MockResolver::new(&[]),
+	)
+	.await;
+	let error = notifier
+		.notify_json(&json!({"text": "hello"}))
+		.await
+		.unwrap_err();
+
+	assert!(matches!(error, NotificationError::EgressDenied(_)));
+	assert!(error.to_string().contains("127.0.0.1"));
+	mock.assert();
+}
+
+#[tokio::test]
+async fn test_egress_refuses_redirect_to_other_host() {
+	let mut server = Server::new_async().await;
+	let port = server.socket_address().port();
+	let redirect = server
+		.mock("POST", "/")
+		.with_status(307)
+		.with_header(
+			"location",
+			&format!("http://metadata.escape.test:{}/latest/meta-data", port),
+		)
+		.expect(1)
+		.create_async()
+		.await;
+	let escaped = server
+		.mock("POST", "/latest/meta-data")
+		.with_status(200)
+		.expect(0)
+		.create_async()
+		.await;
+	// Both hosts resolve to allowed addresses, only the redirect is refused
+	let resolver = MockResolver::new(&[
+		("hooks.allowed.test", "127.0.0.1"),
+		("metadata.escape.test", "127.0.0.1"),
+	]);
+
+	let notifier = create_notifier(
+		format!("http://hooks.allowed.test:{}/", port),
+		create_policy(&["127.0.0.0/8"], &[]),
+		resolver,
+	)
+	.await;
+	let error = notifier
+		.notify_json(&json!({"text": "hello"}))
+		.await
+		.unwrap_err();
+
+	assert!(matches!(error, NotificationError::EgressDenied(_)));
+	assert!(error.to_string().contains("metadata.escape.test"));
+	redirect.assert();
+	escaped.assert();
+}
@@ -43,6 +43,11 @@ fn create_test_evm_match(monitor: Monitor) -> MonitorMatch {
 		network_slug: "ethereum_mainnet".to_string(),
 		matched_on: MatchConditions::default(),
 		matched_on_args: None,
+		sender_activity: None,
+		baseline: None,
+		network_metrics: None,
+		timing: None,
+		origin: Default::default(),
 	}))
 }
 
@@ -78,6 +83,9 @@ async fn test_email_notification_success() {
 		body_template: "Test message".to_string(),
 		sender: EmailAddress::new_unchecked("sender@test.com"),
 		recipients: vec![EmailAddress::new_unchecked("recipient@test.com")],
+		format: None,
+		thread_key: None,
+		attachments: None,
 	};
 
 	let stub_transport = AsyncStubTransport::new_ok();
@@ -96,6 +104,9 @@ async fn test_email_notification_failure_after_retries() {
 		body_template: "Test message".to_string(),
 		sender: EmailAddress::new_unchecked("sender@test.com"),
 		recipients: vec![EmailAddress::new_unchecked("recipient@test.com")],
+		format: None,
+		thread_key: None,
+		attachments: None,
 	};
 
 	let stub_transport = AsyncStubTransport::new_error();
@@ -135,6 +146,9 @@ async fn test_notification_service_email_execution_failure() {
 		message: NotificationMessage {
 			title: "Email Test Alert".to_string(),
 			body: "Test email message with value ${value}".to_string(),
+			format: None,
+			thread_key: None,
+			attachments: None,
 		},
 		sender: "sender@example.com".parse().unwrap(),
 		recipients: vec!["recipient@example.com".parse().unwrap()],
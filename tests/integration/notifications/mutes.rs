@@ -0,0 +1,132 @@
+use chrono::{TimeZone, Utc};
+use mockito::Server;
+use openzeppelin_monitor::{
+	models::MonitorMatch,
+	repositories::{TriggerRepository, TriggerService},
+	services::{
+		notification::NotificationService,
+		state::{InMemoryStateStore, StateStore},
+		trigger::{
+			MuteRequest, TriggerExecutionService, TriggerExecutionServiceTrait, TriggerMutes,
+		},
+	},
+	utils::tests::{
+		evm::{monitor::MonitorBuilder, monitor_match::MonitorMatchBuilder},
+		trigger::TriggerBuilder,
+	},
+};
+use std::{
+	collections::HashMap,
+	sync::{
+		atomic::{AtomicI64, Ordering},
+		Arc,
+	},
+};
+
+const MUTE_SECS: u64 = 600;
+
+fn create_test_evm_match(monitor_name: &str) -> MonitorMatch {
+	MonitorMatchBuilder::new()
+		.monitor(
+			MonitorBuilder::new()
+				.name(monitor_name)
+				.triggers(vec!["maintenance_webhook".to_string()])
+				.build(),
+		)
+		.build()
+}
+
+/// Creates the delivery layer of a process, reading its mutes from `store`
+fn create_trigger_execution_service(
+	url: &str,
+	store: Arc<dyn StateStore>,
+	now: &Arc<AtomicI64>,
+) -> (
+	TriggerExecutionService<TriggerRepository>,
+	Arc<TriggerMutes>,
+) {
+	let trigger = TriggerBuilder::new()
+		.name("maintenance_webhook")
+		.webhook(url)
+		.build();
+	let trigger_service = TriggerService::new_with_repository(TriggerRepository {
+		triggers: HashMap::from([("maintenance_webhook".to_string(), trigger)]),
+	})
+	.unwrap();
+
+	let time = now.clone();
+	let mutes = Arc::new(TriggerMutes::new(store).with_clock(Arc::new(move || {
+		Utc.timestamp_millis_opt(time.load(Ordering::SeqCst))
+			.unwrap()
+	})));
+	let service = TriggerExecutionService::new(trigger_service, NotificationService::new())
+		.with_mutes(mutes.clone());
+	(service, mutes)
+}
+
+async fn deliver(service: &TriggerExecutionService<TriggerRepository>, monitor_name: &str) {
+	service
+		.execute(
+			&["maintenance_webhook".to_string()],
+			HashMap::new(),
+			&create_test_evm_match(monitor_name),
+			&HashMap::new(),
+		)
+		.await
+		.unwrap();
+}
+
+#[tokio::test]
+async fn test_muted_deliveries_are_skipped_until_the_mute_expires() {
+	let mut server = Server::new_async().await;
+	let store: Arc<dyn StateStore> = Arc::new(InMemoryStateStore::new());
+	let now = Arc::new(AtomicI64::new(Utc::now().timestamp_millis()));
+	let muted_at = now.load(Ordering::SeqCst);
+
+	let (service, mutes) = create_trigger_execution_service(&server.url(), store.clone(), &now);
+	mutes
+		.mute(MuteRequest {
+			trigger: "maintenance_webhook".to_string(),
+			monitor: Some("upgraded_monitor".to_string()),
+			duration_secs: MUTE_SECS,
+			reason: "protocol upgrade".to_string(),
+		})
+		.await
+		.unwrap();
+
+	// Only the matches of the muted monitor are silenced
+	let other_monitor = server
+		.mock("POST", "/")
+		.with_status(200)
+		.expect(1)
+		.create_async()
+		.await;
+	deliver(&service, "upgraded_monitor").await;
+	deliver(&service, "other_monitor").await;
+	other_monitor.assert_async().await;
+	other_monitor.remove_async().await;
+	drop(service);
+
+	// The restarted process still honors the mute
+	let silenced = server
+		.mock("POST", "/")
+		.with_status(200)
+		.expect(0)
+		.create_async()
+		.await;
+	let (service, _) = create_trigger_execution_service(&server.url(), store, &now);
+	deliver(&service, "upgraded_monitor").await;
+	silenced.assert_async().await;
+	silenced.remove_async().await;
+
+	// Once the mute has expired, deliveries resume
+	let resumed = server
+		.mock("POST", "/")
+		.with_status(200)
+		.expect(1)
+		.create_async()
+		.await;
+	now.store(muted_at + MUTE_SECS as i64 * 1000, Ordering::SeqCst);
+	deliver(&service, "upgraded_monitor").await;
+	resumed.assert_async().await;
+}
@@ -30,6 +30,11 @@ fn create_test_evm_match(monitor: Monitor) -> MonitorMatch {
 		network_slug: "ethereum_mainnet".to_string(),
 		matched_on: MatchConditions::default(),
 		matched_on_args: None,
+		sender_activity: None,
+		baseline: None,
+		network_metrics: None,
+		timing: None,
+		origin: Default::default(),
 	}))
 }
 
@@ -1,9 +1,17 @@
 use mockito::{Matcher, Server};
 use openzeppelin_monitor::{
-	models::{EVMMonitorMatch, MatchConditions, Monitor, MonitorMatch, TriggerType},
-	services::notification::{
-		GenericWebhookPayloadBuilder, NotificationError, NotificationService, WebhookConfig,
-		WebhookNotifier, WebhookPayloadBuilder,
+	models::{
+		EVMMonitorMatch, MatchConditions, MessageOverride, Monitor, MonitorMatch, SecretString,
+		SecretValue, TriggerOverride, TriggerReference, TriggerType, WebhookFormat,
+	},
+	repositories::{TriggerRepository, TriggerService},
+	services::{
+		notification::{
+			derive_idempotency_key, match_uuid, GenericWebhookPayloadBuilder, NotificationError,
+			NotificationService, SlackPayloadBuilder, WebhookConfig, WebhookNotifier,
+			WebhookPayloadBuilder,
+		},
+		trigger::{TriggerExecutionService, TriggerExecutionServiceTrait},
 	},
 	utils::{
 		tests::{
@@ -38,6 +46,11 @@ fn create_test_evm_match(monitor: Monitor) -> MonitorMatch {
 		network_slug: "ethereum_mainnet".to_string(),
 		matched_on: MatchConditions::default(),
 		matched_on_args: None,
+		sender_activity: None,
+		baseline: None,
+		network_metrics: None,
+		timing: None,
+		origin: Default::default(),
 	}))
 }
 fn create_test_payload() -> serde_json::Value {
@@ -177,6 +190,240 @@ async fn test_notification_service_webhook_execution() {
 	mock.assert();
 }
 
+#[tokio::test]
+async fn test_notification_service_webhook_idempotency_key_stable_across_retries() {
+	let notification_service = NotificationService::new();
+	let mut server = Server::new_async().await;
+
+	let monitor_match = create_test_evm_match(create_test_monitor("test_monitor"));
+	let expected_key =
+		derive_idempotency_key(&match_uuid(&monitor_match), "test_trigger").to_string();
+
+	// The first attempt fails and is retried, both attempts must carry the same key
+	let failed_attempt = server
+		.mock("POST", "/")
+		.match_header("idempotency-key", expected_key.as_str())
+		.match_body(Matcher::PartialJson(
+			serde_json::json!({ "idempotency_key": expected_key }),
+		))
+		.with_status(500)
+		.expect(1)
+		.create_async()
+		.await;
+	let retried_attempt = server
+		.mock("POST", "/")
+		.match_header("idempotency-key", expected_key.as_str())
+		.match_body(Matcher::PartialJson(
+			serde_json::json!({ "idempotency_key": expected_key }),
+		))
+		.with_status(200)
+		.expect(1)
+		.create_async()
+		.await;
+
+	let trigger = TriggerBuilder::new()
+		.name("test_trigger")
+		.webhook(&server.url())
+		.message("Test Alert", "Test message ${value}")
+		.build();
+
+	let variables = HashMap::from([("value".to_string(), "42".to_string())]);
+
+	let result = notification_service
+		.execute(&trigger, &variables, &monitor_match, &HashMap::new())
+		.await;
+
+	assert!(result.is_ok());
+	failed_attempt.assert();
+	retried_attempt.assert();
+}
+
+#[tokio::test]
+async fn test_notification_service_webhook_custom_idempotency_header() {
+	let notification_service = NotificationService::new();
+	let mut server = Server::new_async().await;
+
+	let monitor_match = create_test_evm_match(create_test_monitor("test_monitor"));
+	let expected_key =
+		derive_idempotency_key(&match_uuid(&monitor_match), "test_trigger").to_string();
+
+	let mock = server
+		.mock("POST", "/")
+		.match_header("x-delivery-id", expected_key.as_str())
+		.match_header("idempotency-key", Matcher::Missing)
+		.with_status(200)
+		.create_async()
+		.await;
+
+	let trigger = TriggerBuilder::new()
+		.name("test_trigger")
+		.webhook(&server.url())
+		.webhook_idempotency_header("X-Delivery-Id")
+		.message("Test Alert", "Test message")
+		.build();
+
+	let result = notification_service
+		.execute(&trigger, &HashMap::new(), &monitor_match, &HashMap::new())
+		.await;
+
+	assert!(result.is_ok());
+	mock.assert();
+}
+
+#[tokio::test]
+async fn test_notification_service_webhook_slack_compatible_format() {
+	let notification_service = NotificationService::new();
+	let mut server = Server::new_async().await;
+
+	let monitor_match = create_test_evm_match(create_test_monitor("test_monitor"));
+	let expected_key =
+		derive_idempotency_key(&match_uuid(&monitor_match), "test_trigger").to_string();
+	let variables = HashMap::from([("value".to_string(), "42".to_string())]);
+
+	// The body is the Slack renderer's output, without the fields of generic webhook bodies
+	let golden = serde_json::to_string(&SlackPayloadBuilder.build_payload(
+		"Test Alert",
+		"Test message ${value}",
+		&variables,
+	))
+	.unwrap();
+	assert_eq!(
+		serde_json::from_str::<serde_json::Value>(&golden).unwrap(),
+		serde_json::json!({
+			"blocks": [{
+				"type": "section",
+				"text": { "type": "mrkdwn", "text": "*Test Alert*\n\nTest message 42" }
+			}]
+		})
+	);
+
+	// Long messages are truncated by the Slack rules
+	let long_value = "x".repeat(4000);
+	let long_variables = HashMap::from([("value".to_string(), long_value)]);
+	let long_golden = serde_json::to_string(&SlackPayloadBuilder.build_payload(
+		"Test Alert",
+		"Test message ${value}",
+		&long_variables,
+	))
+	.unwrap();
+
+	let mock = server
+		.mock("POST", "/")
+		.match_header("x-team", "treasury")
+		.match_header("x-signature", Matcher::Any)
+		.match_header("x-timestamp", Matcher::Any)
+		.match_header("idempotency-key", expected_key.as_str())
+		.match_body(Matcher::Exact(golden))
+		.with_status(200)
+		.expect(1)
+		.create_async()
+		.await;
+	let long_mock = server
+		.mock("POST", "/")
+		.match_body(Matcher::Exact(long_golden))
+		.with_status(200)
+		.expect(1)
+		.create_async()
+		.await;
+
+	let trigger = TriggerBuilder::new()
+		.name("test_trigger")
+		.webhook(&server.url())
+		.webhook_format(WebhookFormat::SlackCompatible)
+		.webhook_secret(SecretValue::Plain(SecretString::new(
+			"webhook_secret".to_string(),
+		)))
+		.webhook_headers(HashMap::from([(
+			"X-Team".to_string(),
+			"treasury".to_string(),
+		)]))
+		.message("Test Alert", "Test message ${value}")
+		.build();
+
+	let result = notification_service
+		.execute(&trigger, &variables, &monitor_match, &HashMap::new())
+		.await;
+	assert!(result.is_ok());
+	mock.assert();
+
+	let result = notification_service
+		.execute(&trigger, &long_variables, &monitor_match, &HashMap::new())
+		.await;
+	assert!(result.is_ok());
+	long_mock.assert();
+}
+
+#[tokio::test]
+async fn test_trigger_message_overrides_render_per_monitor() {
+	let mut server = Server::new_async().await;
+
+	let default_body = server
+		.mock("POST", "/")
+		.match_body(Matcher::PartialJson(serde_json::json!({
+			"title": "Shared Alert",
+			"body": "Default message 42"
+		})))
+		.with_status(200)
+		.expect(1)
+		.create_async()
+		.await;
+	let overridden_body = server
+		.mock("POST", "/")
+		.match_body(Matcher::PartialJson(serde_json::json!({
+			"title": "Treasury Alert",
+			"body": "Treasury moved 42 (security team)"
+		})))
+		.with_status(200)
+		.expect(1)
+		.create_async()
+		.await;
+
+	let trigger = TriggerBuilder::new()
+		.name("shared_webhook")
+		.webhook(&server.url())
+		.message("Shared Alert", "Default message ${value}")
+		.build();
+	let trigger_service = TriggerService::new_with_repository(TriggerRepository {
+		triggers: HashMap::from([("shared_webhook".to_string(), trigger)]),
+	})
+	.unwrap();
+	let trigger_execution_service =
+		TriggerExecutionService::new(trigger_service, NotificationService::new());
+
+	let plain_monitor = MonitorBuilder::new()
+		.name("plain")
+		.triggers(vec!["shared_webhook".to_string()])
+		.build();
+	let treasury_monitor = MonitorBuilder::new()
+		.name("treasury")
+		.trigger_reference(TriggerReference::WithOverrides(TriggerOverride {
+			name: "shared_webhook".to_string(),
+			message: Some(MessageOverride {
+				title: Some("Treasury Alert".to_string()),
+				body: Some("Treasury moved ${value} (${team} team)".to_string()),
+			}),
+			variables: HashMap::from([("team".to_string(), "security".to_string())]),
+			rollup: false,
+		}))
+		.build();
+
+	let variables = HashMap::from([("value".to_string(), "42".to_string())]);
+	for monitor in [plain_monitor, treasury_monitor] {
+		let result = trigger_execution_service
+			.execute(
+				&monitor.trigger_names(),
+				variables.clone(),
+				&create_test_evm_match(monitor),
+				&HashMap::new(),
+			)
+			.await;
+		assert!(result.is_ok());
+	}
+
+	default_body.assert();
+	overridden_body.assert();
+}
+
 #[tokio::test]
 async fn test_notification_service_webhook_execution_failure() {
 	let notification_service = NotificationService::new();
@@ -0,0 +1,206 @@
+//! Conformance tests for the state store implementations.
+//!
+//! The same scenarios run against every store. The Redis store is only exercised when built with
+//! the `redis` feature and `STATE_STORE_REDIS_URL` points at a server.
+
+use std::{collections::HashSet, sync::Arc, time::Duration};
+
+use openzeppelin_monitor::services::state::{FileStateStore, InMemoryStateStore, StateStore};
+use tempfile::TempDir;
+
+fn now_ms() -> i64 {
+	chrono::Utc::now().timestamp_millis()
+}
+
+async fn assert_values(store: &dyn StateStore) {
+	assert_eq!(store.get("value").await.unwrap(), None);
+
+	store.set("value", "first", None).await.unwrap();
+	assert_eq!(store.get("value").await.unwrap().as_deref(), Some("first"));
+	store.set("value", "second", None).await.unwrap();
+	assert_eq!(store.get("value").await.unwrap().as_deref(), Some("second"));
+
+	store
+		.set("expiring", "value", Some(Duration::from_millis(100)))
+		.await
+		.unwrap();
+	assert!(store.get("expiring").await.unwrap().is_some());
+	tokio::time::sleep(Duration::from_millis(250)).await;
+	assert_eq!(store.get("expiring").await.unwrap(), None);
+}
+
+async fn assert_counters(store: &dyn StateStore) {
+	assert_eq!(store.increment("counter", 1, None).await.unwrap(), 1);
+	assert_eq!(store.increment("counter", 5, None).await.unwrap(), 6);
+	assert_eq!(store.increment("counter", -2, None).await.unwrap(), 4);
+	assert_eq!(store.get("counter").await.unwrap().as_deref(), Some("4"));
+
+	// The expiry is set when the counter is created and not extended by later increments
+	let ttl = Some(Duration::from_millis(300));
+	assert_eq!(
+		store.increment("expiring_counter", 1, ttl).await.unwrap(),
+		1
+	);
+	tokio::time::sleep(Duration::from_millis(200)).await;
+	assert_eq!(
+		store.increment("expiring_counter", 1, ttl).await.unwrap(),
+		2
+	);
+	tokio::time::sleep(Duration::from_millis(250)).await;
+	assert_eq!(store.get("expiring_counter").await.unwrap(), None);
+	assert_eq!(
+		store.increment("expiring_counter", 1, ttl).await.unwrap(),
+		1
+	);
+
+	store.set("text", "not a number", None).await.unwrap();
+	assert!(store.increment("text", 1, None).await.is_err());
+}
+
+async fn assert_windows(store: &dyn StateStore) {
+	let now = now_ms();
+	let window = Duration::from_millis(2_500);
+	assert_eq!(store.window_count("window", now, window).await.unwrap(), 0);
+
+	assert_eq!(
+		store
+			.window_add("window", now - 2_000, window)
+			.await
+			.unwrap(),
+		1
+	);
+	// Occurrences may be recorded out of order
+	assert_eq!(
+		store
+			.window_add("window", now - 3_000, window)
+			.await
+			.unwrap(),
+		2
+	);
+	// The first occurrence left the window
+	assert_eq!(store.window_add("window", now, window).await.unwrap(), 2);
+	// Occurrences sharing a timestamp are counted separately
+	assert_eq!(store.window_add("window", now, window).await.unwrap(), 3);
+
+	assert_eq!(store.window_count("window", now, window).await.unwrap(), 3);
+	assert_eq!(
+		store
+			.window_count("window", now, Duration::from_millis(1_000))
+			.await
+			.unwrap(),
+		2
+	);
+
+	// Values and windows cannot be mixed up
+	store.set("plain", "value", None).await.unwrap();
+	assert!(store.window_add("plain", now, window).await.is_err());
+	assert!(store.get("window").await.is_err());
+}
+
+async fn assert_conformance(store: &dyn StateStore) {
+	assert_values(store).await;
+	assert_counters(store).await;
+	assert_windows(store).await;
+}
+
+/// Increments one counter concurrently through several handles on the same storage
+async fn assert_atomic_increments(stores: Vec<Arc<dyn StateStore>>, per_store: usize) {
+	let mut handles = Vec::new();
+	for store in &stores {
+		for _ in 0..per_store {
+			let store = store.clone();
+			handles.push(tokio::spawn(async move {
+				store
+					.increment("concurrent", 1, Some(Duration::from_secs(60)))
+					.await
+					.unwrap()
+			}));
+		}
+	}
+
+	let mut values = HashSet::new();
+	for handle in handles {
+		assert!(values.insert(handle.await.unwrap()));
+	}
+	let total = (stores.len() * per_store) as i64;
+	assert_eq!(values, (1..=total).collect::<HashSet<_>>());
+	assert_eq!(
+		stores[0].get("concurrent").await.unwrap(),
+		Some(total.to_string())
+	);
+}
+
+#[tokio::test]
+async fn test_in_memory_store_conformance() {
+	assert_conformance(&InMemoryStateStore::new()).await;
+}
+
+#[tokio::test]
+async fn test_file_store_conformance() {
+	let dir = TempDir::new().unwrap();
+	let store = FileStateStore::new(dir.path().join("state.json"));
+	assert_conformance(&store).await;
+
+	// The state survives the store
+	let reopened = FileStateStore::new(dir.path().join("state.json"));
+	assert_eq!(reopened.get("counter").await.unwrap().as_deref(), Some("4"));
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn test_in_memory_store_atomic_increments() {
+	let store: Arc<dyn StateStore> = Arc::new(InMemoryStateStore::new());
+	assert_atomic_increments(vec![store], 100).await;
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn test_file_store_atomic_increments() {
+	let dir = TempDir::new().unwrap();
+	// Separate handles on one file behave like instances sharing a host
+	let stores = (0..3)
+		.map(|_| {
+			Arc::new(FileStateStore::new(dir.path().join("state.json"))) as Arc<dyn StateStore>
+		})
+		.collect();
+	assert_atomic_increments(stores, 20).await;
+}
+
+#[cfg(feature = "redis")]
+mod redis {
+	use super::*;
+	use openzeppelin_monitor::services::state::RedisStateStore;
+
+	/// Connects to the test server, keys are prefixed per test so tests do not share state
+	async fn connect(prefix: &str) -> Option<RedisStateStore> {
+		let Ok(url) = std::env::var("STATE_STORE_REDIS_URL") else {
+			eprintln!("STATE_STORE_REDIS_URL is not set, skipping Redis state store test");
+			return None;
+		};
+		Some(RedisStateStore::connect(&url, prefix).await.unwrap())
+	}
+
+	fn test_prefix() -> String {
+		format!("openzeppelin-monitor-test:{}:", uuid::Uuid::new_v4())
+	}
+
+	#[tokio::test]
+	async fn test_redis_store_conformance() {
+		let Some(store) = connect(&test_prefix()).await else {
+			return;
+		};
+		assert_conformance(&store).await;
+	}
+
+	#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+	async fn test_redis_store_atomic_increments() {
+		let prefix = test_prefix();
+		let mut stores: Vec<Arc<dyn StateStore>> = Vec::new();
+		// Separate connections behave like instances sharing the server
+		for _ in 0..3 {
+			let Some(store) = connect(&prefix).await else {
+				return;
+			};
+			stores.push(Arc::new(store));
+		}
+		assert_atomic_increments(stores, 50).await;
+	}
+}
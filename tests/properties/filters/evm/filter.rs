@@ -16,7 +16,7 @@ use openzeppelin_monitor::{
 			evm_helpers::{
 				are_same_address, are_same_signature, normalize_address, normalize_signature,
 			},
-			EVMBlockFilter,
+			AnyEmitterSpecs, EVMBlockFilter,
 		},
 	},
 	utils::tests::evm::{monitor::MonitorBuilder, receipt::ReceiptBuilder},
@@ -445,6 +445,10 @@ proptest! {
 
 		let filter = EVMBlockFilter::<EvmClient<EVMTransportClient>> {
 			_client: PhantomData,
+			price_oracles: None,
+			prices: None,
+			decode_failures: Default::default(),
+			decode_failure_tracker: None,
 		};
 		let result = filter.evaluate_expression(&expr, &params).unwrap();
 
@@ -480,6 +484,10 @@ proptest! {
 
 		let filter = EVMBlockFilter::<EvmClient<EVMTransportClient>> {
 			_client: PhantomData,
+			price_oracles: None,
+			prices: None,
+			decode_failures: Default::default(),
+			decode_failure_tracker: None,
 		};
 		let result = filter.evaluate_expression(&expr, &params).unwrap();
 
@@ -522,6 +530,10 @@ proptest! {
 
 		let filter = EVMBlockFilter::<EvmClient<EVMTransportClient>> {
 			_client: PhantomData,
+			price_oracles: None,
+			prices: None,
+			decode_failures: Default::default(),
+			decode_failure_tracker: None,
 		};
 
 		let result = filter.evaluate_expression(&expr, &params).unwrap();
@@ -563,6 +575,10 @@ proptest! {
 
 		let filter = EVMBlockFilter::<EvmClient<EVMTransportClient>> {
 			_client: PhantomData,
+			price_oracles: None,
+			prices: None,
+			decode_failures: Default::default(),
+			decode_failure_tracker: None,
 		};
 		let result = filter.evaluate_expression(&expr, &params).unwrap();
 
@@ -602,6 +618,10 @@ proptest! {
 
 		let filter = EVMBlockFilter::<EvmClient<EVMTransportClient>> {
 			_client: PhantomData,
+			price_oracles: None,
+			prices: None,
+			decode_failures: Default::default(),
+			decode_failure_tracker: None,
 		};
 		let result = filter.evaluate_expression(&expr, &params).unwrap();
 
@@ -660,6 +680,10 @@ proptest! {
 
 		let filter = EVMBlockFilter::<EvmClient<EVMTransportClient>> {
 			_client: PhantomData,
+			price_oracles: None,
+			prices: None,
+			decode_failures: Default::default(),
+			decode_failure_tracker: None,
 		};
 		let result = filter.evaluate_expression(&expr, &params).unwrap();
 		let lhs_as_u128 = lhs_value_str.parse::<u128>().unwrap_or_default();
@@ -701,6 +725,10 @@ proptest! {
 
 			let filter = EVMBlockFilter::<EvmClient<EVMTransportClient>> {
 					_client: PhantomData,
+					price_oracles: None,
+					prices: None,
+					decode_failures: Default::default(),
+					decode_failure_tracker: None,
 			};
 			let result = filter.evaluate_expression(&expr, &params).unwrap();
 
@@ -732,6 +760,10 @@ proptest! {
 
 			let filter = EVMBlockFilter::<EvmClient<EVMTransportClient>> {
 					_client: PhantomData,
+					price_oracles: None,
+					prices: None,
+					decode_failures: Default::default(),
+					decode_failure_tracker: None,
 			};
 			let result = filter.evaluate_expression(&expr, &params).unwrap();
 			// Normalize the target for comparison
@@ -780,6 +812,10 @@ proptest! {
 
 			let filter = EVMBlockFilter::<EvmClient<EVMTransportClient>> {
 					_client: PhantomData,
+					price_oracles: None,
+					prices: None,
+					decode_failures: Default::default(),
+					decode_failure_tracker: None,
 			};
 			let result = filter.evaluate_expression(&expr, &params).unwrap();
 
@@ -817,6 +853,10 @@ proptest! {
 
 		let filter = EVMBlockFilter::<EvmClient<EVMTransportClient>> {
 			_client: PhantomData,
+			price_oracles: None,
+			prices: None,
+			decode_failures: Default::default(),
+			decode_failure_tracker: None,
 		};
 		let result = filter.evaluate_expression(&expr, &params).unwrap();
 
@@ -857,6 +897,10 @@ proptest! {
 
 		let filter = EVMBlockFilter::<EvmClient<EVMTransportClient>> {
 			_client: PhantomData,
+			price_oracles: None,
+			prices: None,
+			decode_failures: Default::default(),
+			decode_failure_tracker: None,
 		};
 		let result = filter.evaluate_expression(&expr, &params).unwrap();
 
@@ -884,6 +928,10 @@ proptest! {
 
 		let filter = EVMBlockFilter::<EvmClient<EVMTransportClient>> {
 			_client: PhantomData,
+			price_oracles: None,
+			prices: None,
+			decode_failures: Default::default(),
+			decode_failure_tracker: None,
 		};
 		let result = filter.evaluate_expression(&expr, &params).unwrap();
 
@@ -936,6 +984,10 @@ proptest! {
 
 		let filter = EVMBlockFilter::<EvmClient<EVMTransportClient>> {
 			_client: PhantomData,
+			price_oracles: None,
+			prices: None,
+			decode_failures: Default::default(),
+			decode_failure_tracker: None,
 		};
 		let result = filter.evaluate_expression(&expr, &params).unwrap();
 
@@ -973,6 +1025,10 @@ proptest! {
 
 		let filter = EVMBlockFilter::<EvmClient<EVMTransportClient>> {
 			_client: PhantomData,
+			price_oracles: None,
+			prices: None,
+			decode_failures: Default::default(),
+			decode_failure_tracker: None,
 		};
 
 		// Test various invalid expression scenarios
@@ -1001,6 +1057,10 @@ proptest! {
 	) {
 		let filter = EVMBlockFilter::<EvmClient<EVMTransportClient>> {
 			_client: PhantomData,
+			price_oracles: None,
+			prices: None,
+			decode_failures: Default::default(),
+			decode_failure_tracker: None,
 		};
 
 		// Test transaction matching across different status types
@@ -1047,6 +1107,10 @@ proptest! {
 	) {
 		let filter = EVMBlockFilter::<EvmClient<EVMTransportClient>> {
 			_client: PhantomData,
+			price_oracles: None,
+			prices: None,
+			decode_failures: Default::default(),
+			decode_failure_tracker: None,
 		};
 		let mut matched_transactions = Vec::new();
 
@@ -1077,6 +1141,10 @@ proptest! {
 	) {
 		let filter = EVMBlockFilter::<EvmClient<EVMTransportClient>> {
 			_client: PhantomData,
+			price_oracles: None,
+			prices: None,
+			decode_failures: Default::default(),
+			decode_failure_tracker: None,
 		};
 		let mut matched_functions = Vec::new();
 		let mut matched_args = EVMMatchArguments {
@@ -1144,6 +1212,10 @@ proptest! {
 	) {
 		let filter = EVMBlockFilter::<EvmClient<EVMTransportClient>> {
 			_client: PhantomData,
+			price_oracles: None,
+			prices: None,
+			decode_failures: Default::default(),
+			decode_failure_tracker: None,
 		};
 		let mut matched_events = Vec::new();
 		let mut matched_args = EVMMatchArguments {
@@ -1164,6 +1236,7 @@ proptest! {
 		filter.find_matching_events_for_transaction(
 			&tx_receipt.logs,
 			&monitor,
+			&AnyEmitterSpecs::new(&monitor),
 			&mut matched_events,
 			&mut matched_args,
 			&mut monitor.addresses.iter().map(|a| a.address.clone()).collect()
@@ -1185,6 +1258,10 @@ proptest! {
 	) {
 		let filter = EVMBlockFilter::<EvmClient<EVMTransportClient>> {
 			_client: PhantomData,
+			price_oracles: None,
+			prices: None,
+			decode_failures: Default::default(),
+			decode_failure_tracker: None,
 		};
 
 		// Decode the event
@@ -1630,6 +1630,8 @@ proptest! {
 						indexed: false,
 					}
 				]),
+				operation_index: None,
+				event_index: None,
 			},
 			tx_hash: tx_hash.clone(),
 		};
@@ -73,7 +73,7 @@ proptest! {
 		// Test invalid references
 		let mut invalid_monitors = monitors.clone();
 		for monitor in invalid_monitors.values_mut() {
-			monitor.triggers.push("non_existent_trigger".to_string());
+			monitor.triggers.push("non_existent_trigger".into());
 			monitor.networks.push("non_existent_network".to_string());
 		}
 
@@ -107,6 +107,9 @@ proptest! {
 							*m = NotificationMessage {
 								title: "".to_string(),
 								body: "test".to_string(),
+								format: None,
+								thread_key: None,
+								attachments: None,
 							};
 						}
 						prop_assert!(invalid_trigger.validate().is_err());
@@ -117,6 +120,9 @@ proptest! {
 							*m = NotificationMessage {
 								title: "Alert".to_string(),
 								body: "".to_string(),
+								format: None,
+								thread_key: None,
+								attachments: None,
 							};
 						}
 						prop_assert!(invalid_trigger.validate().is_err());
@@ -144,13 +150,16 @@ proptest! {
 							*m = NotificationMessage {
 								title: "   ".to_string(),
 								body: "".to_string(),
+								format: None,
+								thread_key: None,
+								attachments: None,
 							};
 						}
 						prop_assert!(invalid_trigger.validate().is_err());
 					}
 				}
 				TriggerType::Webhook => {
-					if let TriggerTypeConfig::Webhook { url: _, method: _, headers: _, secret: _, message: _, retry_policy: _ } = &trigger.config {
+					if let TriggerTypeConfig::Webhook { url: _, method: _, headers: _, secret: _, message: _, retry_policy: _, idempotency_header: _, format: _ } = &trigger.config {
 						// Test invalid method
 						invalid_trigger = trigger.clone();
 						if let TriggerTypeConfig::Webhook { method: m, .. } = &mut invalid_trigger.config {
@@ -171,6 +180,9 @@ proptest! {
 							*m = NotificationMessage {
 								title: "".to_string(),
 								body: "test".to_string(),
+								format: None,
+								thread_key: None,
+								attachments: None,
 							};
 						}
 						prop_assert!(invalid_trigger.validate().is_err());
@@ -181,6 +193,9 @@ proptest! {
 							*m = NotificationMessage {
 								title: "Alert".to_string(),
 								body: "".to_string(),
+								format: None,
+								thread_key: None,
+								attachments: None,
 							};
 						}
 						prop_assert!(invalid_trigger.validate().is_err());
@@ -201,6 +216,9 @@ proptest! {
 							*m = NotificationMessage {
 								title: "".to_string(),
 								body: "test".to_string(),
+								format: None,
+								thread_key: None,
+								attachments: None,
 							};
 						}
 						prop_assert!(invalid_trigger.validate().is_err());
@@ -211,6 +229,9 @@ proptest! {
 							*m = NotificationMessage {
 								title: "Alert".to_string(),
 								body: "".to_string(),
+								format: None,
+								thread_key: None,
+								attachments: None,
 							};
 						}
 						prop_assert!(invalid_trigger.validate().is_err());
@@ -238,6 +259,9 @@ proptest! {
 							*m = NotificationMessage {
 								title: "".to_string(),
 								body: "test".to_string(),
+								format: None,
+								thread_key: None,
+								attachments: None,
 							};
 						}
 						prop_assert!(invalid_trigger.validate().is_err());
@@ -248,6 +272,9 @@ proptest! {
 							*m = NotificationMessage {
 								title: "Alert".to_string(),
 								body: "".to_string(),
+								format: None,
+								thread_key: None,
+								attachments: None,
 							};
 						}
 						prop_assert!(invalid_trigger.validate().is_err());
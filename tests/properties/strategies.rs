@@ -38,6 +38,9 @@ pub fn monitor_strategy(
 				AddressWithSpec {
 					address,
 					contract_spec: None,
+					contract: None,
+					label: None,
+					annotations: Default::default(),
 				}
 			}),
 			MIN_COLLECTION_SIZE..MAX_ADDRESSES,
@@ -81,7 +84,11 @@ pub fn notification_message_strategy() -> impl Strategy<Value = NotificationMess
 		"[a-zA-Z0-9_]{1,50}".prop_map(|s| s.to_string()),
 		"[a-zA-Z0-9_]{1,100}".prop_map(|s| s.to_string()),
 	)
-		.prop_map(|(title, body)| NotificationMessage { title, body })
+		.prop_map(|(title, body)| NotificationMessage {
+			title,
+			body,
+			..Default::default()
+		})
 }
 
 pub fn trigger_strategy() -> impl Strategy<Value = Trigger> {
@@ -164,6 +171,8 @@ pub fn trigger_strategy() -> impl Strategy<Value = Trigger> {
 						secret: secret.map(|s| SecretValue::Plain(SecretString::new(s))),
 						message,
 						retry_policy: RetryConfig::default(),
+						idempotency_header: None,
+						format: None,
 					}
 				})
 		)
@@ -252,6 +261,7 @@ pub fn match_conditions_strategy() -> impl Strategy<Value = MatchConditions> {
 		.prop_map(|(signature, expression)| EventCondition {
 			signature,
 			expression,
+			match_any_emitter: false,
 		});
 
 	let transaction_condition_strategy = (
@@ -282,6 +292,9 @@ pub fn match_conditions_strategy() -> impl Strategy<Value = MatchConditions> {
 			functions,
 			events,
 			transactions,
+			sender_activity: None,
+			baseline: None,
+			network_metrics: None,
 		})
 }
 